@@ -0,0 +1,403 @@
+//! # avila-bench
+//!
+//! **Baselines de desempenho para os kernels de criptografia e geometria**
+//!
+//! Os benchmarks ficam no `benches/` de cada crate e rodam com criterion
+//! (`avila-tls`: AEADs; `avila-vec3d`: transformações, volumes envolventes,
+//! frustum; `avila-optimizer`: merge, dedup, LOD). Este crate lê o que o
+//! criterion grava em `target/criterion`, calcula o throughput, salva
+//! baselines em JSON e compara uma rodada nova com a baseline:
+//!
+//! ```text
+//! cargo bench -p avila-tls
+//! avila-bench save target/criterion baselines/tls.json
+//! # ... aplica o patch ...
+//! cargo bench -p avila-tls
+//! avila-bench compare baselines/tls.json target/criterion --threshold 5
+//! ```
+//!
+//! `compare` termina com código 1 se algum benchmark ficou mais lento que
+//! o limite, para ser usado como gate na revisão de patches de desempenho.
+//!
+//! O criterion só distingue bytes e elementos; grupos com
+//! `Throughput::Elements` cujo nome termina em `_tris` são reportados em
+//! tris/s, os demais em elem/s.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+pub type Result<T> = std::result::Result<T, BenchError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BenchError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid criterion result: {0}")]
+    InvalidResult(String),
+}
+
+// ============================================================================
+// THROUGHPUT
+// ============================================================================
+
+/// Quantidade processada por iteração
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Throughput {
+    Bytes(u64),
+    Triangles(u64),
+    Elements(u64),
+}
+
+impl Throughput {
+    fn amount(&self) -> u64 {
+        match *self {
+            Self::Bytes(n) | Self::Triangles(n) | Self::Elements(n) => n,
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Self::Bytes(_) => "B/s",
+            Self::Triangles(_) => "tris/s",
+            Self::Elements(_) => "elem/s",
+        }
+    }
+
+    /// Unidades por segundo para uma iteração de `mean_ns`
+    pub fn per_second(&self, mean_ns: f64) -> f64 {
+        self.amount() as f64 * 1e9 / mean_ns
+    }
+
+    /// Taxa legível, com prefixo decimal (ex.: `812.4 MB/s`, `35.1 Mtris/s`)
+    pub fn format(&self, mean_ns: f64) -> String {
+        format_rate(self.per_second(mean_ns), self.unit())
+    }
+}
+
+fn format_rate(rate: f64, unit: &str) -> String {
+    let (value, prefix) = match rate {
+        r if r >= 1e9 => (r / 1e9, "G"),
+        r if r >= 1e6 => (r / 1e6, "M"),
+        r if r >= 1e3 => (r / 1e3, "k"),
+        r => (r, ""),
+    };
+    format!("{:.1} {}{}", value, prefix, unit)
+}
+
+fn format_time(ns: f64) -> String {
+    match ns {
+        t if t >= 1e9 => format!("{:.2} s", t / 1e9),
+        t if t >= 1e6 => format!("{:.2} ms", t / 1e6),
+        t if t >= 1e3 => format!("{:.2} µs", t / 1e3),
+        t => format!("{:.1} ns", t),
+    }
+}
+
+// ============================================================================
+// MEDIÇÕES E BASELINES
+// ============================================================================
+
+/// Resultado de um benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// `grupo/função` como no criterion
+    pub id: String,
+    /// Tempo médio por iteração
+    pub mean_ns: f64,
+    pub std_dev_ns: f64,
+    pub throughput: Option<Throughput>,
+}
+
+impl Measurement {
+    /// Taxa legível ou `-` sem throughput
+    pub fn rate(&self) -> String {
+        self.throughput.map_or_else(|| "-".into(), |t| t.format(self.mean_ns))
+    }
+}
+
+/// Conjunto de medições indexado por id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub measurements: BTreeMap<String, Measurement>,
+}
+
+/// Partes do `benchmark.json` do criterion que interessam
+#[derive(Deserialize)]
+struct CriterionBenchmark {
+    group_id: String,
+    full_id: String,
+    throughput: Option<CriterionThroughput>,
+}
+
+#[derive(Deserialize)]
+enum CriterionThroughput {
+    Bytes(u64),
+    BytesDecimal(u64),
+    Elements(u64),
+}
+
+#[derive(Deserialize)]
+struct CriterionEstimates {
+    mean: CriterionEstimate,
+    std_dev: CriterionEstimate,
+}
+
+#[derive(Deserialize)]
+struct CriterionEstimate {
+    point_estimate: f64,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, measurement: Measurement) {
+        self.measurements.insert(measurement.id.clone(), measurement);
+    }
+
+    pub fn len(&self) -> usize {
+        self.measurements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.measurements.is_empty()
+    }
+
+    /// Lê a última rodada (`*/new/`) de um diretório `target/criterion`
+    pub fn from_criterion_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut baseline = Self::new();
+        let mut pending = vec![dir.as_ref().to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if !path.is_dir() {
+                    continue;
+                }
+                // `base/` e `change/` são cópias e comparações do próprio criterion
+                if path.file_name().is_some_and(|name| name == "new") {
+                    if path.join("estimates.json").is_file() {
+                        baseline.insert(read_criterion_run(&path)?);
+                    }
+                } else if path.file_name().is_none_or(|name| name != "report") {
+                    pending.push(path);
+                }
+            }
+        }
+        Ok(baseline)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn read_criterion_run(dir: &Path) -> Result<Measurement> {
+    let invalid = |error: serde_json::Error| BenchError::InvalidResult(format!("{}: {}", dir.display(), error));
+    let benchmark: CriterionBenchmark =
+        serde_json::from_slice(&std::fs::read(dir.join("benchmark.json"))?).map_err(invalid)?;
+    let estimates: CriterionEstimates =
+        serde_json::from_slice(&std::fs::read(dir.join("estimates.json"))?).map_err(invalid)?;
+
+    let throughput = benchmark.throughput.map(|t| match t {
+        CriterionThroughput::Bytes(n) | CriterionThroughput::BytesDecimal(n) => Throughput::Bytes(n),
+        CriterionThroughput::Elements(n) if benchmark.group_id.ends_with("_tris") => Throughput::Triangles(n),
+        CriterionThroughput::Elements(n) => Throughput::Elements(n),
+    });
+    let mean = estimates.mean.point_estimate;
+    if !mean.is_finite() || mean <= 0.0 {
+        return Err(BenchError::InvalidResult(format!("{}: non-positive mean", benchmark.full_id)));
+    }
+    Ok(Measurement {
+        id: benchmark.full_id,
+        mean_ns: mean,
+        std_dev_ns: estimates.std_dev.point_estimate,
+        throughput,
+    })
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in self.measurements.values() {
+            writeln!(f, "{:<48} {:>12} {:>18}", m.id, format_time(m.mean_ns), m.rate())?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// COMPARAÇÃO
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Improved,
+    Unchanged,
+    Regressed,
+    /// Só na rodada nova
+    New,
+    /// Só na baseline
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonEntry {
+    pub id: String,
+    pub baseline_ns: Option<f64>,
+    pub current_ns: Option<f64>,
+    /// Variação do tempo médio em % (positivo = mais lento)
+    pub change_percent: Option<f64>,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub threshold_percent: f64,
+    pub entries: Vec<ComparisonEntry>,
+}
+
+/// Compara `current` com `baseline`
+///
+/// Variações de tempo dentro de ±`threshold_percent` contam como ruído.
+pub fn compare(baseline: &Baseline, current: &Baseline, threshold_percent: f64) -> Comparison {
+    let ids: std::collections::BTreeSet<&String> =
+        baseline.measurements.keys().chain(current.measurements.keys()).collect();
+    let entries = ids
+        .into_iter()
+        .map(|id| {
+            let before = baseline.measurements.get(id).map(|m| m.mean_ns);
+            let after = current.measurements.get(id).map(|m| m.mean_ns);
+            let (change_percent, verdict) = match (before, after) {
+                (Some(before), Some(after)) => {
+                    let change = (after - before) / before * 100.0;
+                    let verdict = if change > threshold_percent {
+                        Verdict::Regressed
+                    } else if change < -threshold_percent {
+                        Verdict::Improved
+                    } else {
+                        Verdict::Unchanged
+                    };
+                    (Some(change), verdict)
+                }
+                (None, _) => (None, Verdict::New),
+                (_, None) => (None, Verdict::Missing),
+            };
+            ComparisonEntry { id: id.clone(), baseline_ns: before, current_ns: after, change_percent, verdict }
+        })
+        .collect();
+    Comparison { threshold_percent, entries }
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries.iter().filter(|e| e.verdict == Verdict::Regressed)
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = |ns: Option<f64>| ns.map_or_else(|| "-".into(), format_time);
+        for e in &self.entries {
+            let change = e.change_percent.map_or_else(|| "-".into(), |c| format!("{:+.1}%", c));
+            writeln!(
+                f,
+                "{:<48} {:>12} {:>12} {:>9}  {:?}",
+                e.id,
+                time(e.baseline_ns),
+                time(e.current_ns),
+                change,
+                e.verdict
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(id: &str, mean_ns: f64) -> Measurement {
+        Measurement { id: id.into(), mean_ns, std_dev_ns: 0.0, throughput: None }
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut baseline = Baseline::new();
+        baseline.insert(measurement("aes_gcm_1mb/aes128_seal", 1000.0));
+        baseline.insert(measurement("aes_gcm_1mb/aes128_open", 1000.0));
+        baseline.insert(measurement("merge_tris/spheres", 1000.0));
+
+        let mut current = Baseline::new();
+        current.insert(measurement("aes_gcm_1mb/aes128_seal", 1200.0));
+        current.insert(measurement("aes_gcm_1mb/aes128_open", 1030.0));
+        current.insert(measurement("lod_tris/spheres", 500.0));
+
+        let comparison = compare(&baseline, &current, 5.0);
+        let verdicts: Vec<_> = comparison.entries.iter().map(|e| (e.id.as_str(), e.verdict)).collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                ("aes_gcm_1mb/aes128_open", Verdict::Unchanged),
+                ("aes_gcm_1mb/aes128_seal", Verdict::Regressed),
+                ("lod_tris/spheres", Verdict::New),
+                ("merge_tris/spheres", Verdict::Missing),
+            ]
+        );
+        assert_eq!(comparison.regressions().count(), 1);
+        assert!((comparison.entries[1].change_percent.unwrap() - 20.0).abs() < 1e-9);
+
+        assert_eq!(Throughput::Bytes(1 << 20).format(1e6), "1.0 GB/s");
+        assert_eq!(Throughput::Triangles(5000).format(1e5), "50.0 Mtris/s");
+    }
+
+    #[test]
+    fn test_from_criterion_dir() {
+        let dir = std::env::temp_dir().join(format!("avila-bench-{}", std::process::id()));
+        let run = dir.join("merge_tris").join("spheres").join("new");
+        std::fs::create_dir_all(&run).unwrap();
+        std::fs::create_dir_all(dir.join("report")).unwrap();
+        std::fs::write(
+            run.join("benchmark.json"),
+            r#"{"group_id":"merge_tris","function_id":"spheres","value_str":null,
+                "throughput":{"Elements":20000},"full_id":"merge_tris/spheres",
+                "directory_name":"merge_tris/spheres","title":"merge_tris/spheres"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            run.join("estimates.json"),
+            r#"{"mean":{"confidence_interval":{"confidence_level":0.95,"lower_bound":1.9e6,"upper_bound":2.1e6},
+                "point_estimate":2.0e6,"standard_error":1.0},
+                "std_dev":{"confidence_interval":{"confidence_level":0.95,"lower_bound":1.0,"upper_bound":3.0},
+                "point_estimate":2.0,"standard_error":0.1}}"#,
+        )
+        .unwrap();
+
+        let baseline = Baseline::from_criterion_dir(&dir).unwrap();
+        let path = dir.join("baseline.json");
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, baseline);
+        let m = &baseline.measurements["merge_tris/spheres"];
+        assert_eq!(m.throughput, Some(Throughput::Triangles(20000)));
+        assert_eq!(m.rate(), "10.0 Mtris/s");
+    }
+}
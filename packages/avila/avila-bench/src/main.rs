@@ -0,0 +1,67 @@
+//! `avila-bench`: relatório, baselines e comparação de resultados do criterion
+//!
+//! ```text
+//! avila-bench report  <criterion-dir>
+//! avila-bench save    <criterion-dir> <baseline.json>
+//! avila-bench compare <baseline.json> <criterion-dir> [--threshold <percent>]
+//! ```
+
+use avila_bench::{compare, Baseline};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage:
+  avila-bench report  <criterion-dir>
+  avila-bench save    <criterion-dir> <baseline.json>
+  avila-bench compare <baseline.json> <criterion-dir> [--threshold <percent>]";
+
+/// Variação padrão tolerada em `compare` (%)
+const DEFAULT_THRESHOLD: f64 = 5.0;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["report", dir] => Baseline::from_criterion_dir(dir).map(|current| {
+            print!("{}", current);
+            ExitCode::SUCCESS
+        }),
+        ["save", dir, out] => Baseline::from_criterion_dir(dir).and_then(|current| {
+            current.save(out)?;
+            println!("{} benchmarks saved to {}", current.len(), out);
+            Ok(ExitCode::SUCCESS)
+        }),
+        ["compare", baseline, dir, rest @ ..] => {
+            let threshold = match rest {
+                [] => DEFAULT_THRESHOLD,
+                ["--threshold", value] => match value.parse::<f64>() {
+                    Ok(value) if value >= 0.0 => value,
+                    _ => return usage(),
+                },
+                _ => return usage(),
+            };
+            Baseline::load(baseline).and_then(|baseline| {
+                let current = Baseline::from_criterion_dir(dir)?;
+                let comparison = compare(&baseline, &current, threshold);
+                print!("{}", comparison);
+                if comparison.has_regressions() {
+                    eprintln!("{} regression(s) above {}%", comparison.regressions().count(), threshold);
+                    Ok(ExitCode::FAILURE)
+                } else {
+                    Ok(ExitCode::SUCCESS)
+                }
+            })
+        }
+        _ => return usage(),
+    };
+
+    result.unwrap_or_else(|error| {
+        eprintln!("error: {}", error);
+        ExitCode::from(2)
+    })
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::from(2)
+}
@@ -0,0 +1,48 @@
+//! Pontos de entrada para fuzzing (feature `fuzzing`)
+//!
+//! Cada função recebe bytes arbitrários e não pode entrar em pânico; os alvos
+//! em `packages/avila/fuzz` só repassam a entrada. As asserções conferem
+//! invariantes que valem para qualquer entrada aceita.
+
+use crate::file_parsers::{FileParser, ParseError};
+use crate::gltf_parser::GltfParser;
+use crate::step_index::{index_data_section, index_data_section_lenient, DiagnosticKind};
+use crate::step_tokenizer::StepTokenizer;
+use std::collections::HashSet;
+
+/// Tokenizador STEP: `tokenize` e `next_token` produzem a mesma sequência
+pub fn step_tokenizer(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+
+    let Ok(tokens) = StepTokenizer::new(input).tokenize() else { return };
+    let mut tokenizer = StepTokenizer::new(input);
+    let streamed: Vec<_> = std::iter::from_fn(|| tokenizer.next_token()).collect();
+    assert_eq!(tokens, streamed);
+}
+
+/// Índice da seção DATA: os intervalos caem dentro do texto, e quando o modo
+/// estrito aceita a entrada o tolerante só difere nos ids repetidos
+pub fn step_index(data: &[u8]) {
+    let Ok(content) = std::str::from_utf8(data) else { return };
+
+    if let Ok((records, _)) = index_data_section_lenient(content) {
+        for record in &records {
+            assert!(content.get(record.range.clone()).is_some(), "range {:?} out of bounds", record.range);
+        }
+    }
+    if let Ok(mut records) = index_data_section(content) {
+        let (lenient, diagnostics) = index_data_section_lenient(content).expect("lenient rejected a strict-valid file");
+        let mut seen = HashSet::new();
+        records.retain(|record| seen.insert(record.id));
+        assert_eq!(records, lenient);
+        assert!(diagnostics.iter().all(|d| d.kind == DiagnosticKind::DuplicateId));
+    }
+}
+
+/// Cabeçalho GLB: entrada curta ou sem magic é sempre `InvalidFormat`
+pub fn glb(data: &[u8]) {
+    let result = GltfParser.parse(data, "fuzz.glb");
+    if data.len() < 20 || &data[0..4] != b"glTF" {
+        assert!(matches!(result, Err(ParseError::InvalidFormat(_))));
+    }
+}
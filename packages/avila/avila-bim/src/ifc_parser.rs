@@ -1,99 +1,260 @@
 //! Parser IFC completo (Industry Foundation Classes) (Rust puro)
 
 use crate::file_parsers::*;
+use crate::geo::Georeference;
+use crate::step_index::{
+    index_data_section, index_data_section_lenient, DiagnosticKind, EntityRecord, ParseDiagnostic,
+};
 use crate::step_tokenizer::{StepTokenizer, Token};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Parser IFC
 pub struct IfcParser;
 
+/// Opções de decodificação paralela da seção DATA
+#[derive(Debug, Clone)]
+pub struct DecodeOptions {
+    /// Número de threads de decodificação (0 = número de CPUs disponíveis)
+    pub workers: usize,
+    /// Entidades por chunk de trabalho
+    pub chunk_size: usize,
+    /// Modo tolerante: registros malformados são pulados e reportados como
+    /// [`ParseDiagnostic`] em vez de abortar o carregamento
+    pub lenient: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            workers: 0,
+            chunk_size: 4096,
+            lenient: false,
+        }
+    }
+}
+
+/// Modelo carregado e os problemas contornados no modo tolerante
+#[derive(Debug, Clone)]
+pub struct ParseReport {
+    pub model: LoadedModel,
+    /// Sempre vazio no modo estrito
+    pub diagnostics: Vec<ParseDiagnostic>,
+    /// `IfcMapConversion`, localização do site e norte verdadeiro, se houver
+    pub georeference: Option<Georeference>,
+}
+
+impl DecodeOptions {
+    fn worker_count(&self) -> usize {
+        if self.workers > 0 {
+            self.workers
+        } else {
+            std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        }
+    }
+}
+
 impl FileParser for IfcParser {
     fn can_parse(&self, format: FileFormat) -> bool {
         matches!(format, FileFormat::IFC)
     }
 
     fn parse(&self, data: &[u8], filename: &str) -> ParseResult<LoadedModel> {
-        let content = std::str::from_utf8(data)
-            .map_err(|_| ParseError::InvalidFormat("Invalid UTF-8 encoding".to_string()))?;
+        self.parse_with_options(data, filename, &DecodeOptions::default())
+    }
+}
 
-        let mut tokenizer = StepTokenizer::new(content);
-        let mut entities = HashMap::new();
+impl IfcParser {
+    /// Parseia o arquivo em duas passadas: índice dos registros e
+    /// decodificação paralela dos payloads
+    pub fn parse_with_options(
+        &self,
+        data: &[u8],
+        filename: &str,
+        options: &DecodeOptions,
+    ) -> ParseResult<LoadedModel> {
+        self.parse_with_diagnostics(data, filename, options).map(|report| report.model)
+    }
 
-        // Parse header
-        self.parse_header(&mut tokenizer)?;
+    /// Como [`parse_with_options`](Self::parse_with_options), devolvendo também
+    /// os diagnósticos do modo tolerante (`options.lenient`)
+    ///
+    /// No modo tolerante o modelo parcial contém todas as entidades válidas;
+    /// referências a entidades descartadas ou inexistentes são reportadas e
+    /// os elementos que dependem delas ficam sem a geometria correspondente.
+    pub fn parse_with_diagnostics(
+        &self,
+        data: &[u8],
+        filename: &str,
+        options: &DecodeOptions,
+    ) -> ParseResult<ParseReport> {
+        let mut diagnostics = Vec::new();
+
+        let content = match std::str::from_utf8(data) {
+            Ok(content) => Cow::Borrowed(content),
+            Err(err) if options.lenient => {
+                let line = 1 + data[..err.valid_up_to()].iter().filter(|&&b| b == b'\n').count();
+                diagnostics.push(ParseDiagnostic {
+                    kind: DiagnosticKind::Syntax,
+                    line,
+                    entity_id: None,
+                    reason: "Invalid UTF-8 encoding, replaced with U+FFFD".to_string(),
+                });
+                String::from_utf8_lossy(data)
+            }
+            Err(_) => return Err(ParseError::InvalidFormat("Invalid UTF-8 encoding".to_string())),
+        };
+
+        // 1. Índice (sequencial, barato)
+        let records = if options.lenient {
+            let (records, index_diagnostics) = index_data_section_lenient(&content)?;
+            diagnostics.extend(index_diagnostics);
+            records
+        } else {
+            index_data_section(&content)?
+        };
 
-        // Parse data section
-        self.parse_data_section(&mut tokenizer, &mut entities)?;
+        // 2. Decodificação dos payloads
+        let entities = self.decode_records(&content, &records, options, &mut diagnostics)?;
+        if options.lenient {
+            diagnostics.extend(dangling_references(&records, &entities));
+        }
 
-        // Convert to LoadedModel
-        self.convert_to_model(entities, filename)
+        // 3. Conversão para LoadedModel
+        let georeference = Georeference::from_entities(&entities);
+        let model = self.convert_to_model(entities, filename)?;
+        Ok(ParseReport { model, diagnostics, georeference })
     }
-}
 
-impl IfcParser {
-    fn parse_header(&self, tokenizer: &mut StepTokenizer) -> ParseResult<()> {
-        // HEADER section
-        let token = tokenizer.next_token()
-            .ok_or_else(|| ParseError::InvalidFormat("Missing HEADER".to_string()))?;
+    /// Decodifica os registros em chunks paralelos
+    ///
+    /// Chunks com entidades de geometria entram primeiro na fila. O resultado
+    /// é montado na ordem do arquivo, independente da ordem de conclusão dos
+    /// workers, e o erro reportado é sempre o do primeiro registro inválido.
+    /// No modo tolerante os registros inválidos viram diagnósticos.
+    fn decode_records(
+        &self,
+        content: &str,
+        records: &[EntityRecord],
+        options: &DecodeOptions,
+        diagnostics: &mut Vec<ParseDiagnostic>,
+    ) -> ParseResult<HashMap<u32, StepEntity>> {
+        let chunk_size = options.chunk_size.max(1);
+
+        // Chunks de índices de registros: geometria primeiro
+        let (geometry, other): (Vec<usize>, Vec<usize>) =
+            (0..records.len()).partition(|&i| records[i].is_geometry());
+        let chunks: Vec<&[usize]> = geometry
+            .chunks(chunk_size)
+            .chain(other.chunks(chunk_size))
+            .collect();
+
+        let workers = options.worker_count().min(chunks.len()).max(1);
+        let mut decoded: Vec<Option<ParseResult<StepEntity>>> = vec![None; records.len()];
+
+        if workers == 1 {
+            for chunk in &chunks {
+                for &index in *chunk {
+                    decoded[index] = Some(self.decode_record(content, &records[index]));
+                }
+            }
+        } else {
+            let next_chunk = AtomicUsize::new(0);
+            let results = std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..workers)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut local = Vec::new();
+                            loop {
+                                let chunk_index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                                let Some(chunk) = chunks.get(chunk_index) else {
+                                    break;
+                                };
+                                for &index in *chunk {
+                                    local.push((index, self.decode_record(content, &records[index])));
+                                }
+                            }
+                            local
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("IFC decode worker panicked"))
+                    .collect::<Vec<_>>()
+            });
 
-        if !matches!(token, Token::Keyword(ref k) if k == "HEADER") {
-            return Err(ParseError::InvalidFormat("Expected HEADER".to_string()));
+            for (index, result) in results {
+                decoded[index] = Some(result);
+            }
         }
 
-        // Skip header entities for now
-        while let Some(token) = tokenizer.next_token() {
-            if matches!(token, Token::Keyword(ref k) if k == "DATA") {
-                break;
+        // Montagem determinística, na ordem do arquivo
+        let mut entities = HashMap::with_capacity(records.len());
+        for (result, record) in decoded.into_iter().zip(records) {
+            match result {
+                Some(Ok(entity)) => {
+                    entities.insert(entity.id, entity);
+                }
+                Some(Err(err)) if options.lenient => diagnostics.push(ParseDiagnostic {
+                    kind: DiagnosticKind::Syntax,
+                    line: record.line,
+                    entity_id: Some(record.id),
+                    reason: match err {
+                        ParseError::InvalidFormat(msg) => msg,
+                        other => other.to_string(),
+                    },
+                }),
+                Some(Err(ParseError::InvalidFormat(msg))) => {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "{} (entity #{} at line {})",
+                        msg, record.id, record.line
+                    )))
+                }
+                Some(Err(other)) => return Err(other),
+                None => {}
             }
         }
 
-        Ok(())
+        Ok(entities)
     }
 
-    fn parse_data_section(
-        &self,
-        tokenizer: &mut StepTokenizer,
-        entities: &mut HashMap<u32, StepEntity>,
-    ) -> ParseResult<()> {
-        while let Some(token) = tokenizer.next_token() {
-            match token {
-                Token::EntityRef(id) => {
-                    self.parse_entity(tokenizer, id, entities)?;
-                }
-                Token::Keyword(ref k) if k == "ENDSEC" => break,
-                _ => {} // Skip other tokens
-            }
+    /// Decodifica um único registro `#id=TIPO(...)`
+    fn decode_record(&self, content: &str, record: &EntityRecord) -> ParseResult<StepEntity> {
+        let mut tokenizer = StepTokenizer::new(&content[record.range.clone()]);
+
+        match tokenizer.next_token() {
+            Some(Token::EntityRef(id)) if id == record.id => {}
+            _ => return Err(ParseError::InvalidFormat("Malformed entity header".to_string())),
         }
 
-        Ok(())
+        self.parse_entity(&mut tokenizer, record.id)
     }
 
-    fn parse_entity(
-        &self,
-        tokenizer: &mut StepTokenizer,
-        id: u32,
-        entities: &mut HashMap<u32, StepEntity>,
-    ) -> ParseResult<()> {
-        // Entity name
-        let name_token = tokenizer.next_token()
+    fn parse_entity(&self, tokenizer: &mut StepTokenizer, id: u32) -> ParseResult<StepEntity> {
+        // Entity name (após o '=')
+        let mut name_token = tokenizer.next_token()
             .ok_or_else(|| ParseError::InvalidFormat("Missing entity name".to_string()))?;
+        if matches!(name_token, Token::Equal) {
+            name_token = tokenizer.next_token()
+                .ok_or_else(|| ParseError::InvalidFormat("Missing entity name".to_string()))?;
+        }
 
         let entity_name = match name_token {
-            Token::Keyword(name) => name,
+            Token::Keyword(name) => name.to_ascii_uppercase(),
             _ => return Err(ParseError::InvalidFormat("Expected entity name".to_string())),
         };
 
         // Parameters
-        let params = self.parse_parameters(tokenizer)?;
+        let parameters = self.parse_parameters(tokenizer)?;
 
-        let entity = StepEntity {
+        Ok(StepEntity {
             id,
             name: entity_name,
-            parameters: params,
-        };
-
-        entities.insert(id, entity);
-        Ok(())
+            parameters,
+        })
     }
 
     fn parse_parameters(&self, tokenizer: &mut StepTokenizer) -> ParseResult<Vec<StepValue>> {
@@ -159,9 +320,11 @@ impl IfcParser {
             metadata.insert("project_id".to_string(), project.id.to_string());
         }
 
-        // Convert IFC entities to ModelElements
-        for (id, entity) in entities {
-            if let Some(element) = self.convert_entity_to_element(&entity, &entities) {
+        // Convert IFC entities to ModelElements (ordem estável por id)
+        let mut ids: Vec<u32> = entities.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            if let Some(element) = self.convert_entity_to_element(&entities[&id], &entities) {
                 elements.push(element);
             }
         }
@@ -807,6 +970,36 @@ impl IfcParser {
     }
 }
 
+/// Referências `#id` para entidades ausentes, uma por par (entidade, alvo)
+fn dangling_references(records: &[EntityRecord], entities: &HashMap<u32, StepEntity>) -> Vec<ParseDiagnostic> {
+    fn collect(value: &StepValue, refs: &mut BTreeSet<u32>) {
+        match value {
+            StepValue::EntityRef(id) => {
+                refs.insert(*id);
+            }
+            StepValue::List(values) => values.iter().for_each(|v| collect(v, refs)),
+            _ => {}
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for record in records {
+        let Some(entity) = entities.get(&record.id) else { continue };
+        let mut refs = BTreeSet::new();
+        entity.parameters.iter().for_each(|v| collect(v, &mut refs));
+
+        for target in refs.into_iter().filter(|id| !entities.contains_key(id)) {
+            diagnostics.push(ParseDiagnostic {
+                kind: DiagnosticKind::DanglingReference,
+                line: record.line,
+                entity_id: Some(record.id),
+                reason: format!("Reference to missing entity #{}", target),
+            });
+        }
+    }
+    diagnostics
+}
+
 /// Entidade STEP parseada
 #[derive(Debug, Clone)]
 pub struct StepEntity {
@@ -857,4 +1050,83 @@ mod tests {
         let token = tokenizer.next_token().unwrap();
         assert!(matches!(token, Token::Keyword(ref k) if k == "IFCWALL"));
     }
+
+    const SAMPLE: &str = "ISO-10303-21;
+HEADER;
+FILE_SCHEMA(('IFC4'));
+ENDSEC;
+DATA;
+#1=IFCPROJECT('2O_RrAJHv7xv2dl5cNZYOF',$,'Projeto',$,$,$,$,$,$);
+#2=IFCCARTESIANPOINT((0.,0.,0.));
+#3=IFCCARTESIANPOINT((1.,0.,0.));
+#4=IFCPOLYLOOP((#2,#3));
+#5=IFCWALL('3kd9F8QlX9wvJRPqN0Z6Yc',$,'Parede',$,$,$,$,$);
+ENDSEC;
+END-ISO-10303-21;";
+
+    #[test]
+    fn test_parallel_decode_matches_sequential() {
+        let parser = IfcParser;
+        let records = index_data_section(SAMPLE).unwrap();
+
+        let sequential = DecodeOptions { workers: 1, chunk_size: 64, ..Default::default() };
+        let parallel = DecodeOptions { workers: 4, chunk_size: 1, ..Default::default() };
+
+        let a = parser.decode_records(SAMPLE, &records, &sequential, &mut Vec::new()).unwrap();
+        let b = parser.decode_records(SAMPLE, &records, &parallel, &mut Vec::new()).unwrap();
+
+        assert_eq!(a.len(), 5);
+        assert_eq!(a.len(), b.len());
+        for (id, entity) in &a {
+            assert_eq!(entity.name, b[id].name);
+            assert_eq!(entity.parameters.len(), b[id].parameters.len());
+        }
+        assert_eq!(b[&4].name, "IFCPOLYLOOP");
+    }
+
+    #[test]
+    fn test_parallel_decode_reports_first_error_in_file_order() {
+        let content = SAMPLE
+            .replace("#3=IFCCARTESIANPOINT((1.,0.,0.))", "#3=IFCCARTESIANPOINT 1.")
+            .replace("#5=IFCWALL(", "#5=IFCWALL ");
+        let parser = IfcParser;
+        let records = index_data_section(&content).unwrap();
+
+        let options = DecodeOptions { workers: 4, chunk_size: 1, ..Default::default() };
+        match parser.decode_records(&content, &records, &options, &mut Vec::new()) {
+            Err(ParseError::InvalidFormat(msg)) => assert!(msg.contains("#3"), "{}", msg),
+            other => panic!("expected decode error, got {:?}", other.map(|e| e.len())),
+        }
+    }
+
+    #[test]
+    fn test_lenient_mode_reports_and_recovers() {
+        let content = SAMPLE
+            .replace("#3=IFCCARTESIANPOINT((1.,0.,0.))", "#3=IFCCARTESIANPOINT 1.")
+            .replace("#4=IFCPOLYLOOP((#2,#3))", "#4=IFCPOLYLOOP((#2,#3,#9))")
+            .replace("ENDSEC;\nEND-ISO", "END-ISO");
+        let parser = IfcParser;
+        let strict = DecodeOptions::default();
+        assert!(parser.parse_with_options(content.as_bytes(), "broken.ifc", &strict).is_err());
+
+        let lenient = DecodeOptions { workers: 2, chunk_size: 1, lenient: true };
+        let report = parser.parse_with_diagnostics(content.as_bytes(), "broken.ifc", &lenient).unwrap();
+        assert_eq!(report.model.metadata["project_id"], "1");
+
+        let summary: Vec<_> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.kind, d.line, d.entity_id))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiagnosticKind::Truncated, 11, None),
+                (DiagnosticKind::Syntax, 8, Some(3)),
+                (DiagnosticKind::DanglingReference, 9, Some(4)),
+                (DiagnosticKind::DanglingReference, 9, Some(4)),
+            ]
+        );
+        assert_eq!(report.diagnostics[3].reason, "Reference to missing entity #9");
+    }
 }
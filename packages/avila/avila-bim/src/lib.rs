@@ -36,17 +36,23 @@ pub mod mesh_gen;
 pub mod mesh_optimizer;
 pub mod hash;
 pub mod step_tokenizer;
+pub mod step_index;
 pub mod triangulation;
 pub mod intersection;
 pub mod convex_hull;
 pub mod kdtree;
 pub mod curve;
 pub mod transform;
+pub mod geo;
 pub mod bvh;
 pub mod octree;
 pub mod polygon_ops;
 pub mod clipper;
 
+// Fuzzing entry points
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+
 // Re-export core types
 pub use bim_core::*;
 pub use bim_converter::*;
@@ -62,3 +68,5 @@ pub use validation::{IfcValidator, GeometryValidator};
 // Re-export file parser types
 pub use file_parsers::{ParserManager, LoadedModel, ModelElement, ElementGeometry, FileFormat, FileParser, ParseError};
 pub use dwg_parser::DwgFileParser;
+pub use ifc_parser::DecodeOptions;
+pub use geo::{GeoPoint, Georeference};
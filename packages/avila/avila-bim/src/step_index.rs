@@ -0,0 +1,394 @@
+//! Índice da seção DATA de arquivos STEP (Rust puro)
+//!
+//! Primeira passada do parser IFC: localiza cada registro `#id=TIPO(...);`
+//! sem decodificar seus atributos. O payload é decodificado depois, em
+//! paralelo, a partir dos intervalos de bytes registrados aqui.
+
+use crate::file_parsers::{ParseError, ParseResult};
+use std::ops::Range;
+
+/// Registro de entidade localizado na seção DATA
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityRecord {
+    /// Id da entidade (`#123`)
+    pub id: u32,
+    /// Nome do tipo em maiúsculas (`IFCWALL`)
+    pub type_name: String,
+    /// Intervalo de bytes do registro completo, sem o `;` final
+    pub range: Range<usize>,
+    /// Linha (1-based) onde o registro começa
+    pub line: usize,
+}
+
+impl EntityRecord {
+    /// Entidades que carregam geometria são decodificadas primeiro
+    pub fn is_geometry(&self) -> bool {
+        is_geometry_entity(&self.type_name)
+    }
+}
+
+/// Tipos STEP que fazem parte da cadeia de geometria
+pub fn is_geometry_entity(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "IFCCARTESIANPOINT"
+            | "IFCCARTESIANPOINTLIST3D"
+            | "IFCDIRECTION"
+            | "IFCPOLYLINE"
+            | "IFCPOLYLOOP"
+            | "IFCFACE"
+            | "IFCFACEBOUND"
+            | "IFCFACEOUTERBOUND"
+            | "IFCCLOSEDSHELL"
+            | "IFCOPENSHELL"
+            | "IFCFACETEDBREP"
+            | "IFCEXTRUDEDAREASOLID"
+            | "IFCTRIANGULATEDFACESET"
+            | "IFCPOLYGONALFACESET"
+            | "IFCSHAPEREPRESENTATION"
+            | "IFCPRODUCTDEFINITIONSHAPE"
+            | "IFCAXIS2PLACEMENT2D"
+            | "IFCAXIS2PLACEMENT3D"
+            | "IFCLOCALPLACEMENT"
+            | "IFCMAPPEDITEM"
+            | "IFCREPRESENTATIONMAP"
+            | "IFCBOOLEANCLIPPINGRESULT"
+            | "IFCBOOLEANRESULT"
+            | "IFCHALFSPACESOLID"
+            | "IFCPOLYGONALBOUNDEDHALFSPACE"
+    ) || type_name.ends_with("PROFILEDEF")
+}
+
+/// Tipo de problema recuperável encontrado no modo tolerante
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// Codificação, cabeçalho ou sintaxe de um registro
+    Syntax,
+    /// Registro sem `;` ou seção DATA sem `ENDSEC`
+    Truncated,
+    /// Id repetido (a primeira definição é mantida)
+    DuplicateId,
+    /// Referência a uma entidade que não existe no arquivo
+    DanglingReference,
+}
+
+/// Diagnóstico estruturado de parse (modo tolerante)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDiagnostic {
+    pub kind: DiagnosticKind,
+    /// Linha (1-based) do registro afetado
+    pub line: usize,
+    /// Entidade afetada, quando o id pôde ser lido
+    pub entity_id: Option<u32>,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.entity_id {
+            Some(id) => write!(f, "line {}: #{}: {}", self.line, id, self.reason),
+            None => write!(f, "line {}: {}", self.line, self.reason),
+        }
+    }
+}
+
+/// Indexa todos os registros da seção DATA
+///
+/// Respeita strings STEP (com `''` como escape) e comentários `/* */`, de
+/// modo que `;` dentro de textos não encerra o registro.
+pub fn index_data_section(content: &str) -> ParseResult<Vec<EntityRecord>> {
+    index_records(content, None)
+}
+
+/// Indexa a seção DATA pulando registros malformados
+///
+/// Ids inválidos e repetidos são descartados; um registro sem `;` ou a falta
+/// de `ENDSEC` encerram o índice com o que foi lido até ali. Apenas a ausência
+/// da seção DATA continua sendo erro.
+pub fn index_data_section_lenient(content: &str) -> ParseResult<(Vec<EntityRecord>, Vec<ParseDiagnostic>)> {
+    let mut diagnostics = Vec::new();
+    let records = index_records(content, Some(&mut diagnostics))?;
+    Ok((records, diagnostics))
+}
+
+/// No modo estrito (`diagnostics == None`) devolve o erro; no tolerante, registra
+fn recover(
+    diagnostics: &mut Option<&mut Vec<ParseDiagnostic>>,
+    error: ParseError,
+    diagnostic: ParseDiagnostic,
+) -> ParseResult<()> {
+    match diagnostics {
+        Some(diagnostics) => {
+            diagnostics.push(diagnostic);
+            Ok(())
+        }
+        None => Err(error),
+    }
+}
+
+fn index_records(content: &str, mut diagnostics: Option<&mut Vec<ParseDiagnostic>>) -> ParseResult<Vec<EntityRecord>> {
+    let bytes = content.as_bytes();
+
+    // DATA começa depois do ENDSEC do cabeçalho; `'DATA;'` numa string do
+    // cabeçalho (FILE_DESCRIPTION, FILE_NAME) não conta
+    let header = find_keyword(bytes, 0, b"HEADER;");
+    if header.is_none() {
+        recover(
+            &mut diagnostics,
+            ParseError::InvalidFormat("Missing HEADER".to_string()),
+            ParseDiagnostic { kind: DiagnosticKind::Syntax, line: 1, entity_id: None, reason: "Missing HEADER".to_string() },
+        )?;
+    }
+    let header_end = header.map_or(0, |header| {
+        let body = header + b"HEADER;".len();
+        find_keyword(bytes, body, b"ENDSEC;").map_or(body, |end| end + b"ENDSEC;".len())
+    });
+    let data_start = find_keyword(bytes, header_end, b"DATA;")
+        .ok_or_else(|| ParseError::InvalidFormat("Missing DATA section".to_string()))?
+        + b"DATA;".len();
+
+    let mut records = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut pos = data_start;
+    let mut line = 1 + bytes[..data_start].iter().filter(|&&b| b == b'\n').count();
+
+    loop {
+        // Avançar até o próximo registro
+        while pos < bytes.len() && bytes[pos] != b'#' {
+            if bytes[pos] == b'\n' {
+                line += 1;
+            }
+            if bytes[pos..].starts_with(b"ENDSEC;") {
+                return Ok(records);
+            }
+            if bytes[pos..].starts_with(b"/*") {
+                let (end, lines) = skip_comment(bytes, pos);
+                pos = end;
+                line += lines;
+                continue;
+            }
+            pos += 1;
+        }
+
+        if pos >= bytes.len() {
+            let reason = "DATA section is not terminated by ENDSEC".to_string();
+            recover(
+                &mut diagnostics,
+                ParseError::CorruptedFile(reason.clone()),
+                ParseDiagnostic { kind: DiagnosticKind::Truncated, line, entity_id: None, reason },
+            )?;
+            return Ok(records);
+        }
+
+        let start = pos;
+        let start_line = line;
+
+        // #id
+        pos += 1;
+        let id_start = pos;
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        let id: Option<u32> = content[id_start..pos].parse().ok();
+        if id.is_none() {
+            let reason = format!("Invalid entity id at line {}", start_line);
+            recover(
+                &mut diagnostics,
+                ParseError::InvalidFormat(reason),
+                ParseDiagnostic {
+                    kind: DiagnosticKind::Syntax,
+                    line: start_line,
+                    entity_id: None,
+                    reason: "Invalid entity id".to_string(),
+                },
+            )?;
+        }
+
+        // = TIPO
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'=') {
+            if bytes[pos] == b'\n' {
+                line += 1;
+            }
+            pos += 1;
+        }
+        let name_start = pos;
+        while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+            pos += 1;
+        }
+        let type_name = content[name_start..pos].to_ascii_uppercase();
+
+        // Atributos até o ';' de fechamento
+        let mut in_string = false;
+        let mut terminated = false;
+        while pos < bytes.len() {
+            let b = bytes[pos];
+            if b == b'\n' {
+                line += 1;
+            }
+            if in_string {
+                if b == b'\'' {
+                    if bytes.get(pos + 1) == Some(&b'\'') {
+                        pos += 1;
+                    } else {
+                        in_string = false;
+                    }
+                }
+            } else if b == b'\'' {
+                in_string = true;
+            } else if bytes[pos..].starts_with(b"/*") {
+                let (end, lines) = skip_comment(bytes, pos);
+                pos = end;
+                line += lines;
+                continue;
+            } else if b == b';' {
+                terminated = true;
+                break;
+            }
+            pos += 1;
+        }
+
+        if !terminated {
+            let reason = format!("Entity #{} at line {} is not terminated", id.unwrap_or_default(), start_line);
+            recover(
+                &mut diagnostics,
+                ParseError::CorruptedFile(reason),
+                ParseDiagnostic {
+                    kind: DiagnosticKind::Truncated,
+                    line: start_line,
+                    entity_id: id,
+                    reason: "Entity is not terminated".to_string(),
+                },
+            )?;
+            return Ok(records);
+        }
+
+        if let Some(id) = id {
+            match diagnostics.as_mut() {
+                // Duplicados só são detectados no modo tolerante
+                Some(diagnostics) if !seen.insert(id) => diagnostics.push(ParseDiagnostic {
+                    kind: DiagnosticKind::DuplicateId,
+                    line: start_line,
+                    entity_id: Some(id),
+                    reason: "Duplicate entity id, keeping the first definition".to_string(),
+                }),
+                _ => records.push(EntityRecord {
+                    id,
+                    type_name,
+                    range: start..pos,
+                    line: start_line,
+                }),
+            }
+        }
+        pos += 1; // ';'
+    }
+}
+
+/// Primeira ocorrência de `keyword` a partir de `from`, fora de strings e comentários
+fn find_keyword(bytes: &[u8], from: usize, keyword: &[u8]) -> Option<usize> {
+    let mut pos = from;
+    let mut in_string = false;
+    while pos < bytes.len() {
+        let b = bytes[pos];
+        if in_string {
+            // `''` fecha e reabre a string: o efeito é o do escape
+            if b == b'\'' {
+                in_string = false;
+            }
+        } else if b == b'\'' {
+            in_string = true;
+        } else if bytes[pos..].starts_with(b"/*") {
+            pos = skip_comment(bytes, pos).0;
+            continue;
+        } else if bytes[pos..].starts_with(keyword) {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Pula um comentário `/* ... */`, retornando (nova posição, linhas consumidas)
+fn skip_comment(bytes: &[u8], start: usize) -> (usize, usize) {
+    let mut pos = start + 2;
+    let mut lines = 0;
+    while pos + 1 < bytes.len() && !(bytes[pos] == b'*' && bytes[pos + 1] == b'/') {
+        if bytes[pos] == b'\n' {
+            lines += 1;
+        }
+        pos += 1;
+    }
+    ((pos + 2).min(bytes.len()), lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "ISO-10303-21;
+HEADER;
+FILE_SCHEMA(('IFC4'));
+ENDSEC;
+DATA;
+#1=IFCPROJECT('2O_RrAJHv7xv2dl5cNZYOF',$,'Proj; with semicolon',$,$,$,$,$,$);
+/* comentário; com ponto e vírgula */
+#2= IFCCARTESIANPOINT((0.,0.,0.));
+#3=IFCWALL('3kd9F8QlX9wvJRPqN0Z6Yc',$,'It''s a wall',$,$,$,$,$);
+ENDSEC;
+END-ISO-10303-21;";
+
+    #[test]
+    fn test_index_records() {
+        let records = index_data_section(SAMPLE).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].id, 1);
+        assert_eq!(records[0].type_name, "IFCPROJECT");
+        assert_eq!(records[0].line, 6);
+        assert!(SAMPLE[records[0].range.clone()].ends_with("$)"));
+        assert_eq!(records[1].type_name, "IFCCARTESIANPOINT");
+        assert!(records[1].is_geometry());
+        assert_eq!(records[2].line, 9);
+    }
+
+    #[test]
+    fn test_data_keyword_inside_header_strings() {
+        let content = SAMPLE.replace(
+            "FILE_SCHEMA(('IFC4'));",
+            "FILE_DESCRIPTION(('ViewDefinition [DATA;ENDSEC;]'),'2;1');\n/* DATA; */\nFILE_SCHEMA(('IFC4'));",
+        );
+        let records = index_data_section(&content).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].type_name, "IFCPROJECT");
+        assert_eq!(records[0].line, 8);
+
+        // Sem DATA fora das strings, a seção não existe
+        let header_only = "ISO-10303-21;\nHEADER;\nFILE_NAME('DATA;','x');\nENDSEC;\nEND-ISO-10303-21;";
+        assert!(index_data_section(header_only).is_err());
+    }
+
+    #[test]
+    fn test_lenient_index_skips_malformed_records() {
+        let content = SAMPLE
+            .replace("#2= IFCCARTESIANPOINT", "#x= IFCCARTESIANPOINT")
+            .replace("#3=IFCWALL", "#1=IFCWALL");
+        assert!(index_data_section(&content.replace("#x", "#")).is_err());
+
+        let (records, diagnostics) = index_data_section_lenient(&content).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].type_name, "IFCPROJECT");
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!((diagnostics[0].kind, diagnostics[0].line), (DiagnosticKind::Syntax, 8));
+        assert_eq!((diagnostics[1].kind, diagnostics[1].entity_id), (DiagnosticKind::DuplicateId, Some(1)));
+        assert_eq!(diagnostics[1].to_string(), "line 9: #1: Duplicate entity id, keeping the first definition");
+
+        let truncated = "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n#1=IFCWALL('a');\n#2=IFCWALL('x'";
+        let (records, diagnostics) = index_data_section_lenient(truncated).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((diagnostics[0].kind, diagnostics[0].entity_id), (DiagnosticKind::Truncated, Some(2)));
+    }
+
+    #[test]
+    fn test_unterminated_data_section() {
+        let content = "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n#1=IFCWALL('x'";
+        assert!(index_data_section(content).is_err());
+    }
+}
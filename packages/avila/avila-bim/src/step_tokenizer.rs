@@ -52,14 +52,25 @@ impl StepTokenizer {
                 break;
             }
 
-            let token = self.next_token()?;
+            let token = self.read_token()?;
             tokens.push(token);
         }
 
         Ok(tokens)
     }
 
-    fn next_token(&mut self) -> Result<Token, String> {
+    /// Próximo token, ou `None` no fim da entrada (ou em caractere inválido)
+    pub fn next_token(&mut self) -> Option<Token> {
+        self.skip_whitespace();
+
+        if self.position >= self.input.len() {
+            return None;
+        }
+
+        self.read_token().ok()
+    }
+
+    fn read_token(&mut self) -> Result<Token, String> {
         let ch = self.current_char();
 
         match ch {
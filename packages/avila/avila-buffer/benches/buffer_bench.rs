@@ -60,17 +60,5 @@ fn bench_compact(c: &mut Criterion) {
     });
 }
 
-// Note: This won't compile until Clone is implemented for ByteBuffer
-// Keeping it here as a template for future implementation
-impl Clone for ByteBuffer {
-    fn clone(&self) -> Self {
-        Self {
-            data: self.data.clone(),
-            read_pos: self.read_pos,
-            write_pos: self.write_pos,
-        }
-    }
-}
-
 criterion_group!(benches, bench_write, bench_read, bench_write_read, bench_compact);
 criterion_main!(benches);
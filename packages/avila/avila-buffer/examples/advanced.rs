@@ -100,7 +100,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut buffer = ByteBuffer::new();
     let data = vec![65, 66, 67, 68, 69]; // ASCII A-E
 
-    buffer.extend(data.into_iter())?;
+    buffer.extend(data)?;
     println!("Extended buffer: {:?}", std::str::from_utf8(buffer.as_slice())?);
 
     // Position tracking
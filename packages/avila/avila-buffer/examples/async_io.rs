@@ -0,0 +1,26 @@
+//! Async I/O example (requires the `async` feature)
+//!
+//! Run with: cargo run --example async_io --features async
+
+use avila_buffer::io::Framed;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Length-Delimited Frames over Tokio ===\n");
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    runtime.block_on(async {
+        let (client, server) = tokio::io::duplex(1024);
+        let mut client = Framed::new(client);
+        let mut server = Framed::new(server);
+
+        for message in ["header", "body", "trailer"] {
+            client.send(message.as_bytes()).await?;
+        }
+        drop(client);
+
+        while let Some(frame) = server.next_frame().await? {
+            println!("Received frame: {:?}", String::from_utf8_lossy(frame.as_slice()));
+        }
+        Ok(())
+    })
+}
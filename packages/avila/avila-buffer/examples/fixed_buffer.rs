@@ -54,6 +54,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let original: FixedBuffer<128> = FixedBuffer::new();
     let cloned = original; // Copy, not expensive clone!
+    assert_eq!(cloned.len(), original.len());
 
     println!("FixedBuffer implements Copy trait - very efficient!");
     println!("Original and cloned are independent stack values");
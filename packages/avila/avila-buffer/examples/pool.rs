@@ -33,8 +33,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     {
         let mut buf = PooledBuffer::new(&mut pool)?;
+        // O guard empresta o pool até o fim do escopo
         println!("Acquired with RAII guard");
-        println!("  Available: {}", pool.available());
 
         buf.write(b"Auto-release on drop")?;
         println!("  Buffer contains: {:?}", std::str::from_utf8(buf.as_slice())?);
@@ -81,7 +81,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Memory Efficiency ===\n");
 
     let pool = BufferPool::new(100, 4096);
-    let pool_memory = 100 * 4096;
+    let pool_memory = pool.available() * 4096;
     println!("Pool of 100 x 4KB buffers = {} KB total", pool_memory / 1024);
     println!("Reusable for unlimited operations!");
 
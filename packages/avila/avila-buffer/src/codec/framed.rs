@@ -0,0 +1,151 @@
+//! Length-delimited framing
+//!
+//! Each frame is a LEB128 varint length followed by the payload. The codec
+//! works on plain [`ByteBuffer`]s; `io::Framed` (feature `async`) drives it
+//! over async streams.
+
+use crate::codec::VarintEncoder;
+use crate::ByteBuffer;
+use avila_error::{Error, ErrorKind, Result};
+
+/// Default upper bound for a single frame (8 MiB)
+pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// Varint length-prefixed frame codec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthDelimitedCodec {
+    max_frame_len: usize,
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a codec with [`DEFAULT_MAX_FRAME_LEN`]
+    pub fn new() -> Self {
+        Self {
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// Sets the largest accepted payload; bigger frames are rejected on both sides
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Largest accepted payload
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Writes only the length prefix for `payload_len`
+    pub fn encode_header(&self, payload_len: usize, dst: &mut ByteBuffer) -> Result<usize> {
+        self.check_len(payload_len)?;
+        dst.write_varint_u64(payload_len as u64)
+    }
+
+    /// Writes the length prefix followed by the payload
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use avila_buffer::ByteBuffer;
+    /// use avila_buffer::codec::LengthDelimitedCodec;
+    ///
+    /// let codec = LengthDelimitedCodec::new();
+    /// let mut wire = ByteBuffer::new();
+    /// codec.encode(b"ping", &mut wire).unwrap();
+    ///
+    /// let frame = codec.decode(&mut wire).unwrap().unwrap();
+    /// assert_eq!(frame.as_slice(), b"ping");
+    /// ```
+    pub fn encode(&self, payload: &[u8], dst: &mut ByteBuffer) -> Result<usize> {
+        let header = self.encode_header(payload.len(), dst)?;
+        Ok(header + dst.write(payload)?)
+    }
+
+    /// Removes one complete frame from `src`
+    ///
+    /// Returns `Ok(None)` without consuming anything while the frame is incomplete.
+    pub fn decode(&self, src: &mut ByteBuffer) -> Result<Option<ByteBuffer>> {
+        let Some((len, header)) = self.decode_header(src.as_slice())? else {
+            return Ok(None);
+        };
+        if src.len() < header + len {
+            return Ok(None);
+        }
+
+        let frame = ByteBuffer::from(&src.as_slice()[header..header + len]);
+        src.skip(header + len)?;
+        Ok(Some(frame))
+    }
+
+    /// Parses the length prefix: `(payload_len, header_len)` once it is complete
+    pub fn decode_header(&self, data: &[u8]) -> Result<Option<(usize, usize)>> {
+        let mut value = 0u64;
+        for (i, &byte) in data.iter().enumerate() {
+            if i >= 10 {
+                return Err(Error::new(ErrorKind::InvalidInput, "Varint overflow: too many bytes"));
+            }
+            value |= ((byte & 0x7F) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                let len = usize::try_from(value)
+                    .map_err(|_| Error::new(ErrorKind::InvalidInput, "Frame length overflow"))?;
+                self.check_len(len)?;
+                return Ok(Some((len, i + 1)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len > self.max_frame_len {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                alloc::format!("Frame of {} bytes exceeds limit of {}", len, self.max_frame_len),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_frames() {
+        let codec = LengthDelimitedCodec::new();
+        let mut wire = ByteBuffer::new();
+        codec.encode(&[7u8; 200], &mut wire).unwrap();
+        codec.encode(b"", &mut wire).unwrap();
+
+        // 200 needs a two-byte prefix; feed it one byte at a time
+        let bytes = wire.as_slice().to_vec();
+        let mut src = ByteBuffer::new();
+        src.write(&bytes[..1]).unwrap();
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        src.write(&bytes[1..150]).unwrap();
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        assert_eq!(src.len(), 150);
+
+        src.write(&bytes[150..]).unwrap();
+        assert_eq!(codec.decode(&mut src).unwrap().unwrap().len(), 200);
+        assert!(codec.decode(&mut src).unwrap().unwrap().is_empty());
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_frame_limit() {
+        let codec = LengthDelimitedCodec::new().with_max_frame_len(4);
+        let mut wire = ByteBuffer::new();
+        assert!(codec.encode(b"too long", &mut wire).is_err());
+
+        LengthDelimitedCodec::new().encode(b"too long", &mut wire).unwrap();
+        assert!(codec.decode(&mut wire).is_err());
+    }
+}
@@ -0,0 +1,517 @@
+//! Tagged binary messages with schema evolution
+//!
+//! Every field is written as a varint key `(number << 3) | wire_type` followed
+//! by its value, so readers can skip fields they do not know:
+//!
+//! | Wire type | Id | Used for |
+//! |-----------|----|----------|
+//! | Varint | 0 | `uint`, `sint` (zigzag), `bool` |
+//! | Fixed64 | 1 | `double` |
+//! | LengthDelimited | 2 | `bytes`, `string`, nested messages |
+//! | Fixed32 | 5 | `float` |
+//!
+//! Evolving a schema: add fields under new numbers and never reuse the number
+//! of a removed field. Old readers skip the new fields; new readers leave
+//! fields missing from old payloads at their `Default`. Optional fields are
+//! simply omitted, repeated fields are written once per element.
+
+use crate::codec::VarintEncoder;
+use crate::ByteBuffer;
+use alloc::string::String;
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+/// Highest field number that fits the key encoding
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// How a field value is laid out on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireType {
+    /// LEB128 varint
+    Varint = 0,
+    /// 8 bytes, little-endian
+    Fixed64 = 1,
+    /// Varint length followed by the bytes
+    LengthDelimited = 2,
+    /// 4 bytes, little-endian
+    Fixed32 = 5,
+}
+
+impl WireType {
+    /// Parses the low three bits of a field key
+    pub fn from_u8(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(WireType::Varint),
+            1 => Ok(WireType::Fixed64),
+            2 => Ok(WireType::LengthDelimited),
+            5 => Ok(WireType::Fixed32),
+            _ => Err(Error::new(ErrorKind::InvalidInput, "Invalid wire type")),
+        }
+    }
+}
+
+/// A type with a stable tagged wire format
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::codec::{Field, Message, MessageWriter};
+/// use avila_error::Result;
+///
+/// #[derive(Debug, Default, PartialEq)]
+/// struct Element {
+///     id: u64,
+///     name: String,
+///     storey: Option<String>,
+///     children: Vec<Element>,
+/// }
+///
+/// impl Message for Element {
+///     fn encode_fields(&self, w: &mut MessageWriter<'_>) -> Result<()> {
+///         w.uint(1, self.id)?;
+///         w.string(2, &self.name)?;
+///         w.optional(3, self.storey.as_deref(), MessageWriter::string)?;
+///         w.repeated(4, &self.children, MessageWriter::message)
+///     }
+///
+///     fn merge_field(&mut self, field: Field<'_>) -> Result<()> {
+///         match field.number() {
+///             1 => self.id = field.as_u64()?,
+///             2 => self.name = field.as_string()?,
+///             3 => self.storey = Some(field.as_string()?),
+///             4 => self.children.push(field.as_message()?),
+///             _ => {}
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let wall = Element { id: 7, name: "Wall".into(), storey: None, children: vec![Element::default()] };
+/// let bytes = wall.to_vec().unwrap();
+/// assert_eq!(Element::decode(&bytes).unwrap(), wall);
+/// ```
+pub trait Message {
+    /// Writes every present field
+    fn encode_fields(&self, writer: &mut MessageWriter<'_>) -> Result<()>;
+
+    /// Applies one decoded field; unknown numbers should be ignored
+    fn merge_field(&mut self, field: Field<'_>) -> Result<()>;
+
+    /// Appends the encoded message to `dst`
+    fn encode(&self, dst: &mut ByteBuffer) -> Result<()> {
+        self.encode_fields(&mut MessageWriter::new(dst))
+    }
+
+    /// Encodes into a fresh vector
+    fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut buffer = ByteBuffer::new();
+        self.encode(&mut buffer)?;
+        Ok(buffer.as_slice().to_vec())
+    }
+
+    /// Merges every field of `data` into `self`
+    fn merge(&mut self, data: &[u8]) -> Result<()> {
+        let mut reader = MessageReader::new(data);
+        while let Some(field) = reader.next_field()? {
+            self.merge_field(field)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes a message starting from its `Default`
+    fn decode(data: &[u8]) -> Result<Self>
+    where
+        Self: Default + Sized,
+    {
+        let mut message = Self::default();
+        message.merge(data)?;
+        Ok(message)
+    }
+}
+
+// ============================================================================
+// Writer
+// ============================================================================
+
+/// Appends tagged fields to a [`ByteBuffer`]
+#[derive(Debug)]
+pub struct MessageWriter<'a> {
+    dst: &'a mut ByteBuffer,
+}
+
+impl<'a> MessageWriter<'a> {
+    /// Writes fields after any bytes already in `dst`
+    pub fn new(dst: &'a mut ByteBuffer) -> Self {
+        Self { dst }
+    }
+
+    /// Unsigned integer as a varint
+    pub fn uint(&mut self, field: u32, value: u64) -> Result<()> {
+        self.key(field, WireType::Varint)?;
+        self.dst.write_varint_u64(value).map(|_| ())
+    }
+
+    /// Signed integer, zigzag-encoded so small negatives stay short
+    pub fn sint(&mut self, field: u32, value: i64) -> Result<()> {
+        self.key(field, WireType::Varint)?;
+        self.dst.write_varint_i64(value).map(|_| ())
+    }
+
+    /// Boolean as a one-byte varint
+    pub fn bool(&mut self, field: u32, value: bool) -> Result<()> {
+        self.uint(field, value as u64)
+    }
+
+    /// `f32` as fixed32
+    pub fn float(&mut self, field: u32, value: f32) -> Result<()> {
+        self.key(field, WireType::Fixed32)?;
+        self.dst.write(&value.to_le_bytes()).map(|_| ())
+    }
+
+    /// `f64` as fixed64
+    pub fn double(&mut self, field: u32, value: f64) -> Result<()> {
+        self.key(field, WireType::Fixed64)?;
+        self.dst.write(&value.to_le_bytes()).map(|_| ())
+    }
+
+    /// Length-prefixed raw bytes
+    pub fn bytes(&mut self, field: u32, value: &[u8]) -> Result<()> {
+        self.key(field, WireType::LengthDelimited)?;
+        self.dst.write_varint_u64(value.len() as u64)?;
+        self.dst.write(value).map(|_| ())
+    }
+
+    /// Length-prefixed UTF-8
+    pub fn string(&mut self, field: u32, value: &str) -> Result<()> {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Nested message, length-prefixed
+    pub fn message<M: Message + ?Sized>(&mut self, field: u32, value: &M) -> Result<()> {
+        let mut nested = ByteBuffer::new();
+        value.encode(&mut nested)?;
+        self.bytes(field, nested.as_slice())
+    }
+
+    /// Writes `value` with `write` only when present
+    pub fn optional<T>(
+        &mut self,
+        field: u32,
+        value: Option<T>,
+        write: impl FnOnce(&mut Self, u32, T) -> Result<()>,
+    ) -> Result<()> {
+        match value {
+            Some(value) => write(self, field, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes one field per element, in order
+    pub fn repeated<T>(
+        &mut self,
+        field: u32,
+        values: impl IntoIterator<Item = T>,
+        mut write: impl FnMut(&mut Self, u32, T) -> Result<()>,
+    ) -> Result<()> {
+        for value in values {
+            write(self, field, value)?;
+        }
+        Ok(())
+    }
+
+    fn key(&mut self, field: u32, wire_type: WireType) -> Result<()> {
+        if field == 0 || field > MAX_FIELD_NUMBER {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                alloc::format!("Field number {} out of range", field),
+            ));
+        }
+        self.dst.write_varint_u32((field << 3) | wire_type as u32).map(|_| ())
+    }
+}
+
+// ============================================================================
+// Reader
+// ============================================================================
+
+/// A decoded field value borrowing from the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldValue<'a> {
+    /// Varint value, still zigzag-encoded for signed fields
+    Varint(u64),
+    /// Raw fixed64 bits
+    Fixed64(u64),
+    /// Length-delimited payload
+    Bytes(&'a [u8]),
+    /// Raw fixed32 bits
+    Fixed32(u32),
+}
+
+/// One field as read off the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    number: u32,
+    value: FieldValue<'a>,
+}
+
+impl<'a> Field<'a> {
+    /// Field number from the schema
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// Raw value as read off the wire
+    pub fn value(&self) -> FieldValue<'a> {
+        self.value
+    }
+
+    /// Varint as `u64`
+    pub fn as_u64(&self) -> Result<u64> {
+        match self.value {
+            FieldValue::Varint(v) => Ok(v),
+            _ => Err(self.mismatch("varint")),
+        }
+    }
+
+    /// Varint as `u32`, rejecting larger values
+    pub fn as_u32(&self) -> Result<u32> {
+        u32::try_from(self.as_u64()?).map_err(|_| self.overflow())
+    }
+
+    /// Reverses the zigzag encoding of [`MessageWriter::sint`]
+    pub fn as_i64(&self) -> Result<i64> {
+        let v = self.as_u64()?;
+        Ok(((v >> 1) as i64) ^ -((v & 1) as i64))
+    }
+
+    /// Zigzag varint as `i32`, rejecting larger values
+    pub fn as_i32(&self) -> Result<i32> {
+        i32::try_from(self.as_i64()?).map_err(|_| self.overflow())
+    }
+
+    /// Varint as a boolean; any non-zero value is `true`
+    pub fn as_bool(&self) -> Result<bool> {
+        Ok(self.as_u64()? != 0)
+    }
+
+    /// Fixed32 as `f32`
+    pub fn as_f32(&self) -> Result<f32> {
+        match self.value {
+            FieldValue::Fixed32(v) => Ok(f32::from_bits(v)),
+            _ => Err(self.mismatch("fixed32")),
+        }
+    }
+
+    /// Fixed64 as `f64`
+    pub fn as_f64(&self) -> Result<f64> {
+        match self.value {
+            FieldValue::Fixed64(v) => Ok(f64::from_bits(v)),
+            _ => Err(self.mismatch("fixed64")),
+        }
+    }
+
+    /// Length-delimited payload, borrowed from the input
+    pub fn as_bytes(&self) -> Result<&'a [u8]> {
+        match self.value {
+            FieldValue::Bytes(v) => Ok(v),
+            _ => Err(self.mismatch("length-delimited")),
+        }
+    }
+
+    /// Length-delimited payload as UTF-8, borrowed from the input
+    pub fn as_str(&self) -> Result<&'a str> {
+        core::str::from_utf8(self.as_bytes()?).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                alloc::format!("Field {} is not valid UTF-8", self.number),
+            )
+        })
+    }
+
+    /// Length-delimited payload as an owned string
+    pub fn as_string(&self) -> Result<String> {
+        self.as_str().map(String::from)
+    }
+
+    /// Decodes a nested message
+    pub fn as_message<M: Message + Default>(&self) -> Result<M> {
+        M::decode(self.as_bytes()?)
+    }
+
+    fn mismatch(&self, expected: &str) -> Error {
+        Error::new(
+            ErrorKind::InvalidInput,
+            alloc::format!("Field {} is not {}: found {:?}", self.number, expected, self.value),
+        )
+    }
+
+    fn overflow(&self) -> Error {
+        Error::new(
+            ErrorKind::InvalidInput,
+            alloc::format!("Field {} value out of range", self.number),
+        )
+    }
+}
+
+/// Iterates over the fields of an encoded message
+#[derive(Debug, Clone)]
+pub struct MessageReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MessageReader<'a> {
+    /// Reads fields from an encoded message
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Returns the next field, or `None` at the end of the message
+    pub fn next_field(&mut self) -> Result<Option<Field<'a>>> {
+        if self.pos == self.data.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|n| (1..=MAX_FIELD_NUMBER).contains(n))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid field number"))?;
+        let value = match WireType::from_u8((key & 0x7) as u8)? {
+            WireType::Varint => FieldValue::Varint(self.varint()?),
+            WireType::Fixed64 => FieldValue::Fixed64(u64::from_le_bytes(self.array()?)),
+            WireType::Fixed32 => FieldValue::Fixed32(u32::from_le_bytes(self.array()?)),
+            WireType::LengthDelimited => {
+                let len = usize::try_from(self.varint()?)
+                    .map_err(|_| Error::new(ErrorKind::InvalidInput, "Field length overflow"))?;
+                FieldValue::Bytes(self.take(len)?)
+            }
+        };
+        Ok(Some(Field { number, value }))
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for i in 0..10 {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::new(ErrorKind::InvalidInput, "Varint overflow: too many bytes"))
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() - self.pos < n {
+            return Err(Error::new(ErrorKind::InvalidInput, "Truncated message"));
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+}
+
+impl<'a> Iterator for MessageReader<'a> {
+    type Item = Result<Field<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_field().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct PointV1 {
+        x: i64,
+        label: String,
+    }
+
+    impl Message for PointV1 {
+        fn encode_fields(&self, w: &mut MessageWriter<'_>) -> Result<()> {
+            w.sint(1, self.x)?;
+            w.string(2, &self.label)
+        }
+
+        fn merge_field(&mut self, field: Field<'_>) -> Result<()> {
+            match field.number() {
+                1 => self.x = field.as_i64()?,
+                2 => self.label = field.as_string()?,
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    /// Same message after adding fields 3 and 4
+    #[derive(Debug, Default, PartialEq)]
+    struct PointV2 {
+        x: i64,
+        label: String,
+        weight: Option<f64>,
+        tags: Vec<String>,
+    }
+
+    impl Message for PointV2 {
+        fn encode_fields(&self, w: &mut MessageWriter<'_>) -> Result<()> {
+            w.sint(1, self.x)?;
+            w.string(2, &self.label)?;
+            w.optional(3, self.weight, MessageWriter::double)?;
+            w.repeated(4, &self.tags, |w, n, tag| w.string(n, tag))
+        }
+
+        fn merge_field(&mut self, field: Field<'_>) -> Result<()> {
+            match field.number() {
+                1 => self.x = field.as_i64()?,
+                2 => self.label = field.as_string()?,
+                3 => self.weight = Some(field.as_f64()?),
+                4 => self.tags.push(field.as_string()?),
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_schema_evolution() {
+        let new = PointV2 {
+            x: -3,
+            label: "P1".into(),
+            weight: Some(0.5),
+            tags: vec!["a".into(), "b".into()],
+        };
+        let old_reader = PointV1::decode(&new.to_vec().unwrap()).unwrap();
+        assert_eq!(old_reader, PointV1 { x: -3, label: "P1".into() });
+
+        let old = PointV1 { x: 9, label: "P2".into() };
+        let new_reader = PointV2::decode(&old.to_vec().unwrap()).unwrap();
+        assert_eq!(new_reader, PointV2 { x: 9, label: "P2".into(), ..Default::default() });
+
+        assert_eq!(PointV2::decode(&new.to_vec().unwrap()).unwrap(), new);
+    }
+
+    #[test]
+    fn test_wire_layout_and_errors() {
+        let bytes = PointV1 { x: -1, label: "ok".into() }.to_vec().unwrap();
+        // key(1, varint), zigzag(-1) = 1, key(2, bytes), len 2, "ok"
+        assert_eq!(bytes, [0x08, 0x01, 0x12, 0x02, b'o', b'k']);
+
+        assert!(PointV1::decode(&bytes[..5]).is_err());
+        // Field 1 sent as a string: wire type mismatch on a known field
+        assert!(PointV1::decode(&[0x0A, 0x00]).is_err());
+        assert!(PointV1::decode(&[0x13]).is_err());
+
+        let mut buffer = ByteBuffer::new();
+        assert!(MessageWriter::new(&mut buffer).uint(0, 1).is_err());
+    }
+}
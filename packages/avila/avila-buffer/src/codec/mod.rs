@@ -1,7 +1,11 @@
 //! Encoding and decoding of data types
 
+pub mod framed;
+pub mod message;
 pub mod primitive;
 pub mod varint;
 
+pub use framed::*;
+pub use message::*;
 pub use primitive::*;
 pub use varint::*;
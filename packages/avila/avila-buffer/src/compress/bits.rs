@@ -0,0 +1,152 @@
+//! LSB-first bit streams used by DEFLATE
+
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+use super::huffman::DecodeTable;
+
+/// Source of compressed bytes for the decoder
+pub(crate) trait ByteSource {
+    /// Next byte, or `None` at end of input
+    fn next_byte(&mut self) -> Result<Option<u8>>;
+}
+
+impl ByteSource for &[u8] {
+    #[inline]
+    fn next_byte(&mut self) -> Result<Option<u8>> {
+        let Some((&byte, rest)) = self.split_first() else {
+            return Ok(None);
+        };
+        *self = rest;
+        Ok(Some(byte))
+    }
+}
+
+fn truncated() -> Error {
+    Error::new(ErrorKind::InvalidInput, "Compressed stream is truncated")
+}
+
+/// Reads bits least-significant first, pulling bytes only when needed
+pub(crate) struct BitReader<S> {
+    source: S,
+    bits: u64,
+    count: u32,
+}
+
+impl<S: ByteSource> BitReader<S> {
+    pub(crate) fn new(source: S) -> Self {
+        Self { source, bits: 0, count: 0 }
+    }
+
+    fn refill(&mut self) -> Result<bool> {
+        match self.source.next_byte()? {
+            Some(byte) => {
+                self.bits |= (byte as u64) << self.count;
+                self.count += 8;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Reads `n` (≤ 32) bits
+    #[inline]
+    pub(crate) fn bits(&mut self, n: u32) -> Result<u32> {
+        while self.count < n {
+            if !self.refill()? {
+                return Err(truncated());
+            }
+        }
+        let value = (self.bits & ((1u64 << n) - 1)) as u32;
+        self.bits >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Decodes one Huffman symbol
+    ///
+    /// Looks up with the bits already buffered and only pulls another byte
+    /// when the matching code is longer than that, so the reader never
+    /// consumes input past the end of the stream.
+    #[inline]
+    pub(crate) fn decode(&mut self, table: &DecodeTable) -> Result<u16> {
+        loop {
+            let (symbol, len) = table.lookup(self.bits);
+            if len != 0 && len <= self.count {
+                self.bits >>= len;
+                self.count -= len;
+                return Ok(symbol);
+            }
+            if self.count >= table.bits {
+                return Err(Error::new(ErrorKind::InvalidInput, "Invalid Huffman code"));
+            }
+            if !self.refill()? {
+                return Err(truncated());
+            }
+        }
+    }
+
+    /// Drops bits up to the next byte boundary
+    pub(crate) fn align(&mut self) {
+        let skip = self.count % 8;
+        self.bits >>= skip;
+        self.count -= skip;
+    }
+}
+
+/// Writes bits least-significant first
+#[derive(Default)]
+pub(crate) struct BitWriter {
+    pub(crate) out: Vec<u8>,
+    bits: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Appends the low `n` (≤ 32) bits of `value`
+    #[inline]
+    pub(crate) fn put(&mut self, value: u32, n: u32) {
+        self.bits |= (value as u64) << self.count;
+        self.count += n;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Pads with zero bits to the next byte boundary
+    pub(crate) fn align(&mut self) {
+        if self.count > 0 {
+            self.put(0, 8 - self.count);
+        }
+    }
+
+    /// Bits needed to reach the next byte boundary
+    pub(crate) fn padding(&self) -> u32 {
+        (8 - self.count % 8) % 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_round_trip() {
+        let mut writer = BitWriter::default();
+        writer.put(0b101, 3);
+        writer.put(0x1234, 16);
+        writer.put(1, 1);
+        writer.align();
+        writer.put(0xAB, 8);
+
+        let mut reader = BitReader::new(writer.out.as_slice());
+        assert_eq!(reader.bits(3).unwrap(), 0b101);
+        assert_eq!(reader.bits(16).unwrap(), 0x1234);
+        assert_eq!(reader.bits(1).unwrap(), 1);
+        reader.align();
+        assert_eq!(reader.bits(8).unwrap(), 0xAB);
+        assert!(reader.bits(1).is_err());
+    }
+}
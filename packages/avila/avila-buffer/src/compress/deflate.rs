@@ -0,0 +1,660 @@
+//! DEFLATE (RFC 1951) encoder and raw-stream helpers
+//!
+//! The encoder is a hash-chain LZ77 matcher (zlib's per-level tuning, lazy
+//! matching from level 4 up)
+//! followed by a per-block choice between stored, fixed and dynamic Huffman
+//! coding, whichever is smallest.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use avila_error::Result;
+
+use super::bits::BitWriter;
+use super::huffman::{
+    build_codes, build_lengths, distance_symbol, fixed_dist_lengths, fixed_lit_lengths, length_symbol, CL_ORDER,
+    DIST_EXTRA, LEN_BASE, LEN_EXTRA, MAX_BITS, MAX_CL_BITS,
+};
+use super::inflate::{inflate, WINDOW};
+use super::zlib::Adler32;
+use super::Level;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+const NIL: usize = usize::MAX;
+/// Input accumulated before the matcher runs
+const CHUNK: usize = 64 * 1024;
+/// Symbols per Huffman block
+const BLOCK_SYMBOLS: usize = 16 * 1024;
+const MAX_STORED: usize = 65_535;
+
+/// Compresses `input` into a raw DEFLATE stream
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{deflate, Level};
+///
+/// let json = br#"{"elements":[{"type":"IfcWall"},{"type":"IfcWall"},{"type":"IfcWall"}]}"#;
+/// let packed = deflate::compress(json, Level::DEFAULT);
+/// assert!(packed.len() < json.len());
+/// assert_eq!(deflate::decompress(&packed).unwrap(), json);
+/// ```
+pub fn compress(input: &[u8], level: Level) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 64);
+    let mut deflater = Deflater::new(level);
+    deflater.write(input, &mut out);
+    deflater.finish(&mut out);
+    out
+}
+
+/// Decompresses a raw DEFLATE stream
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>> {
+    inflate(input, false, usize::MAX)
+}
+
+/// Decompresses a raw DEFLATE stream, failing past `limit` output bytes
+///
+/// Use this for untrusted input, where a tiny stream can expand to gigabytes.
+pub fn decompress_limited(input: &[u8], limit: usize) -> Result<Vec<u8>> {
+    inflate(input, false, limit)
+}
+
+// ============================================================================
+// Encoder
+// ============================================================================
+
+#[derive(Clone, Copy)]
+enum Symbol {
+    Literal(u8),
+    Match { len: u16, dist: u16 },
+}
+
+struct MatchParams {
+    /// Chain is cut to a quarter once a match this long is in hand
+    good_len: usize,
+    /// Lazy evaluation is skipped for matches at least this long (0 = greedy)
+    lazy_len: usize,
+    nice_len: usize,
+    max_chain: usize,
+}
+
+impl MatchParams {
+    /// zlib's configuration table
+    fn for_level(level: Level) -> Self {
+        let (good_len, lazy_len, nice_len, max_chain) = match level.get() {
+            1 => (4, 0, 8, 4),
+            2 => (4, 0, 16, 8),
+            3 => (4, 0, 32, 32),
+            4 => (4, 4, 16, 16),
+            5 => (8, 16, 32, 32),
+            6 => (8, 16, 128, 128),
+            7 => (8, 32, 128, 256),
+            8 => (32, 128, 258, 1024),
+            _ => (32, 258, 258, 4096),
+        };
+        Self { good_len, lazy_len, nice_len, max_chain }
+    }
+}
+
+/// Incremental DEFLATE encoder
+///
+/// Input can be fed in pieces of any size; compressed bytes are appended to
+/// the caller's vector as whole blocks complete. Back-references reach into
+/// earlier pieces, so chunking does not hurt the ratio.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{zlib, Deflater, Level};
+///
+/// let mut deflater = Deflater::zlib(Level::FASTEST);
+/// let mut out = Vec::new();
+/// for _ in 0..100 {
+///     deflater.write(b"IfcBuildingStorey;", &mut out);
+/// }
+/// deflater.finish(&mut out);
+///
+/// assert_eq!(zlib::decompress(&out).unwrap(), b"IfcBuildingStorey;".repeat(100));
+/// ```
+pub struct Deflater {
+    level: Level,
+    params: MatchParams,
+    zlib: bool,
+    header_written: bool,
+    finished: bool,
+    writer: BitWriter,
+    adler: Adler32,
+    /// Window history followed by input not yet matched
+    buf: Vec<u8>,
+    /// Stream offset of `buf[0]`
+    base: usize,
+    /// First byte of `buf` not yet matched
+    pending: usize,
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl Deflater {
+    /// Creates an encoder producing a raw DEFLATE stream
+    pub fn new(level: Level) -> Self {
+        Self::with_framing(level, false)
+    }
+
+    /// Creates an encoder producing a zlib stream (header and Adler-32 trailer)
+    pub fn zlib(level: Level) -> Self {
+        Self::with_framing(level, true)
+    }
+
+    fn with_framing(level: Level, zlib: bool) -> Self {
+        Self {
+            level,
+            params: MatchParams::for_level(level),
+            zlib,
+            header_written: false,
+            finished: false,
+            writer: BitWriter::default(),
+            adler: Adler32::new(),
+            buf: Vec::new(),
+            base: 0,
+            pending: 0,
+            head: Vec::new(),
+            prev: Vec::new(),
+        }
+    }
+
+    /// Compression level in use
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Feeds `input`, appending any completed output to `out`
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after [`finish`](Self::finish).
+    pub fn write(&mut self, input: &[u8], out: &mut Vec<u8>) {
+        assert!(!self.finished, "Deflater::write called after finish");
+        self.write_header();
+        if self.zlib {
+            self.adler.update(input);
+        }
+        self.buf.extend_from_slice(input);
+        if self.buf.len() - self.pending >= CHUNK {
+            self.compress_pending(false);
+        }
+        out.append(&mut self.writer.out);
+    }
+
+    /// Emits everything fed so far and byte-aligns the stream (sync flush)
+    ///
+    /// The receiver can decode all data written before the flush; useful for
+    /// interactive streams such as chunked HTTP responses.
+    pub fn flush(&mut self, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        self.write_header();
+        self.compress_pending(false);
+        // Empty stored block: 3 header bits, padding, LEN = 0, NLEN = 0xFFFF
+        self.writer.put(0, 3);
+        self.writer.align();
+        self.writer.put(0xFFFF_0000, 32);
+        out.append(&mut self.writer.out);
+    }
+
+    /// Writes the final block (and the zlib trailer); later calls do nothing
+    pub fn finish(&mut self, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        self.write_header();
+        self.compress_pending(true);
+        self.writer.align();
+        if self.zlib {
+            self.writer.out.extend_from_slice(&self.adler.finish().to_be_bytes());
+        }
+        self.finished = true;
+        out.append(&mut self.writer.out);
+    }
+
+    fn write_header(&mut self) {
+        if self.zlib && !self.header_written {
+            // CM = 8 (deflate), CINFO = 7 (32K window), FLEVEL from the level
+            let flevel: u16 = match self.level.get() {
+                0 | 1 => 0,
+                2..=5 => 1,
+                6 => 2,
+                _ => 3,
+            };
+            let mut header = 0x7800 | flevel << 6;
+            header += (31 - header % 31) % 31;
+            self.writer.out.extend_from_slice(&header.to_be_bytes());
+        }
+        self.header_written = true;
+    }
+
+    /// Matches `buf[pending..]` and writes it as one or more blocks
+    fn compress_pending(&mut self, last: bool) {
+        let end = self.buf.len();
+        if self.level.get() == 0 {
+            let data = core::mem::take(&mut self.buf);
+            self.write_stored(&data[self.pending..], last);
+            self.buf = data;
+        } else {
+            self.run_matcher(end, last);
+        }
+        self.pending = end;
+        self.slide();
+    }
+
+    fn run_matcher(&mut self, end: usize, last: bool) {
+        if self.head.is_empty() {
+            self.head = vec![NIL; 1 << HASH_BITS];
+            self.prev = vec![NIL; WINDOW];
+        }
+
+        let mut symbols = Vec::with_capacity(BLOCK_SYMBOLS.min(end - self.pending + 1));
+        let mut block_start = self.pending;
+        let mut pos = self.pending;
+        while pos < end {
+            let (len, dist) = self.longest_match(pos, end, 0);
+            self.insert(pos);
+
+            if len >= MIN_MATCH && len < self.params.lazy_len {
+                // Lazy evaluation: prefer a literal if the next position matches longer
+                let (next_len, _) = self.longest_match(pos + 1, end, len);
+                if next_len > len {
+                    symbols.push(Symbol::Literal(self.buf[pos]));
+                    pos += 1;
+                    self.maybe_flush_block(&mut symbols, &mut block_start, pos);
+                    continue;
+                }
+            }
+
+            if len >= MIN_MATCH {
+                symbols.push(Symbol::Match { len: len as u16, dist: dist as u16 });
+                for p in pos + 1..pos + len {
+                    self.insert(p);
+                }
+                pos += len;
+            } else {
+                symbols.push(Symbol::Literal(self.buf[pos]));
+                pos += 1;
+            }
+            self.maybe_flush_block(&mut symbols, &mut block_start, pos);
+        }
+
+        if !symbols.is_empty() || last {
+            let data = core::mem::take(&mut self.buf);
+            self.write_block(&symbols, &data[block_start..end], last);
+            self.buf = data;
+        }
+    }
+
+    fn maybe_flush_block(&mut self, symbols: &mut Vec<Symbol>, block_start: &mut usize, pos: usize) {
+        if symbols.len() >= BLOCK_SYMBOLS {
+            let data = core::mem::take(&mut self.buf);
+            self.write_block(symbols, &data[*block_start..pos], false);
+            self.buf = data;
+            symbols.clear();
+            *block_start = pos;
+        }
+    }
+
+    #[inline]
+    fn hash(&self, pos: usize) -> usize {
+        let b = &self.buf[pos..pos + 3];
+        let key = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        (key.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, pos: usize) {
+        if pos + MIN_MATCH > self.buf.len() {
+            return;
+        }
+        let h = self.hash(pos);
+        let abs = self.base + pos;
+        self.prev[abs % WINDOW] = self.head[h];
+        self.head[h] = abs;
+    }
+
+    /// Longest earlier match for `pos` as `(len, dist)`; len < 3 means none
+    ///
+    /// `prev_len` is the match already found at the previous position.
+    fn longest_match(&self, pos: usize, end: usize, prev_len: usize) -> (usize, usize) {
+        if pos + MIN_MATCH > end {
+            return (0, 0);
+        }
+        let max_len = (end - pos).min(MAX_MATCH);
+        let abs = self.base + pos;
+        let (mut best_len, mut best_dist) = (0, 0);
+        let mut candidate = self.head[self.hash(pos)];
+        let mut chain = self.params.max_chain;
+        if prev_len >= self.params.good_len {
+            chain = (chain / 4).max(1);
+        }
+
+        while candidate != NIL && chain > 0 {
+            // Chains are strictly decreasing; anything else is a stale slot
+            if candidate >= abs || abs - candidate > WINDOW || candidate < self.base {
+                break;
+            }
+            let start = candidate - self.base;
+            if self.buf[start + best_len.min(max_len - 1)] == self.buf[pos + best_len.min(max_len - 1)] {
+                let len = common_prefix(&self.buf[start..start + max_len], &self.buf[pos..pos + max_len]);
+                if len > best_len {
+                    best_len = len;
+                    best_dist = abs - candidate;
+                    if len >= self.params.nice_len || len == max_len {
+                        break;
+                    }
+                }
+            }
+            let next = self.prev[candidate % WINDOW];
+            if next != NIL && next >= candidate {
+                break;
+            }
+            candidate = next;
+            chain -= 1;
+        }
+        (best_len, best_dist)
+    }
+
+    /// Keeps only the last window of history once it grows past a chunk
+    fn slide(&mut self) {
+        if self.buf.len() >= WINDOW + CHUNK {
+            let drop = self.buf.len() - WINDOW;
+            self.buf.drain(..drop);
+            self.base += drop;
+            self.pending -= drop;
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Block output
+    // ------------------------------------------------------------------------
+
+    fn write_block(&mut self, symbols: &[Symbol], raw: &[u8], last: bool) {
+        let mut lit_freq = [0u32; 286];
+        let mut dist_freq = [0u32; 30];
+        for symbol in symbols {
+            match *symbol {
+                Symbol::Literal(byte) => lit_freq[byte as usize] += 1,
+                Symbol::Match { len, dist } => {
+                    lit_freq[257 + length_symbol(len)] += 1;
+                    dist_freq[distance_symbol(dist)] += 1;
+                }
+            }
+        }
+        lit_freq[256] = 1;
+
+        let lit_lengths = build_lengths(&lit_freq, MAX_BITS);
+        let dist_lengths = build_lengths(&dist_freq, MAX_BITS);
+        let header = DynamicHeader::new(&lit_lengths, &dist_lengths);
+
+        let fixed_lit = fixed_lit_lengths();
+        let fixed_dist = fixed_dist_lengths();
+        let dynamic_cost = 3 + header.cost() + data_cost(&lit_freq, &dist_freq, &lit_lengths, &dist_lengths);
+        let fixed_cost = 3 + data_cost(&lit_freq, &dist_freq, &fixed_lit, &fixed_dist);
+        let stored_cost = stored_cost(raw.len(), self.writer.padding());
+
+        if stored_cost <= dynamic_cost.min(fixed_cost) {
+            self.write_stored(raw, last);
+        } else if fixed_cost <= dynamic_cost {
+            self.writer.put(last as u32 | 1 << 1, 3);
+            self.write_symbols(symbols, &fixed_lit, &fixed_dist);
+        } else {
+            self.writer.put(last as u32 | 2 << 1, 3);
+            header.write(&mut self.writer);
+            self.write_symbols(symbols, &lit_lengths, &dist_lengths);
+        }
+    }
+
+    fn write_stored(&mut self, raw: &[u8], last: bool) {
+        let mut chunks = raw.chunks(MAX_STORED).peekable();
+        if chunks.peek().is_none() {
+            if last {
+                self.writer.put(1, 3);
+                self.writer.align();
+                self.writer.put(0xFFFF_0000, 32);
+            }
+            return;
+        }
+        while let Some(chunk) = chunks.next() {
+            let final_chunk = last && chunks.peek().is_none();
+            self.writer.put(final_chunk as u32, 3);
+            self.writer.align();
+            let len = chunk.len() as u32;
+            self.writer.put(len | (!len & 0xFFFF) << 16, 32);
+            self.writer.out.extend_from_slice(chunk);
+        }
+    }
+
+    fn write_symbols(&mut self, symbols: &[Symbol], lit_lengths: &[u8], dist_lengths: &[u8]) {
+        let lit_codes = build_codes(lit_lengths);
+        let dist_codes = build_codes(dist_lengths);
+        let writer = &mut self.writer;
+        for symbol in symbols {
+            match *symbol {
+                Symbol::Literal(byte) => {
+                    let sym = byte as usize;
+                    writer.put(lit_codes[sym] as u32, lit_lengths[sym] as u32);
+                }
+                Symbol::Match { len, dist } => {
+                    let ls = length_symbol(len);
+                    writer.put(lit_codes[257 + ls] as u32, lit_lengths[257 + ls] as u32);
+                    writer.put((len - LEN_BASE[ls]) as u32, LEN_EXTRA[ls] as u32);
+                    let ds = distance_symbol(dist);
+                    writer.put(dist_codes[ds] as u32, dist_lengths[ds] as u32);
+                    writer.put((dist - super::huffman::DIST_BASE[ds]) as u32, DIST_EXTRA[ds] as u32);
+                }
+            }
+        }
+        writer.put(lit_codes[256] as u32, lit_lengths[256] as u32);
+    }
+}
+
+impl Default for Deflater {
+    fn default() -> Self {
+        Self::new(Level::DEFAULT)
+    }
+}
+
+/// Length of the common prefix of two equally long slices, 8 bytes at a time
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    let mut len = 0;
+    for (x, y) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
+        let diff = u64::from_le_bytes(x.try_into().unwrap()) ^ u64::from_le_bytes(y.try_into().unwrap());
+        if diff != 0 {
+            return len + (diff.trailing_zeros() / 8) as usize;
+        }
+        len += 8;
+    }
+    len + a[len..].iter().zip(&b[len..]).take_while(|(x, y)| x == y).count()
+}
+
+fn data_cost(lit_freq: &[u32], dist_freq: &[u32], lit_lengths: &[u8], dist_lengths: &[u8]) -> usize {
+    let mut bits = 0usize;
+    for (sym, &freq) in lit_freq.iter().enumerate() {
+        let extra = if sym > 256 { LEN_EXTRA[sym - 257] as usize } else { 0 };
+        bits += freq as usize * (lit_lengths[sym] as usize + extra);
+    }
+    for (sym, &freq) in dist_freq.iter().enumerate() {
+        bits += freq as usize * (dist_lengths[sym] as usize + DIST_EXTRA[sym] as usize);
+    }
+    bits
+}
+
+fn stored_cost(len: usize, padding: u32) -> usize {
+    let blocks = len.div_ceil(MAX_STORED).max(1);
+    // The first header may share the current byte; later ones start aligned
+    3 + ((padding + 5) % 8) as usize + (blocks - 1) * 8 + blocks * 32 + len * 8
+}
+
+/// Code-length encoding of a dynamic block's trees (RFC 1951 §3.2.7)
+struct DynamicHeader {
+    hlit: usize,
+    hdist: usize,
+    hclen: usize,
+    /// Code-length symbols with their extra-bit values
+    tokens: Vec<(u8, u8)>,
+    cl_lengths: Vec<u8>,
+}
+
+impl DynamicHeader {
+    fn new(lit_lengths: &[u8], dist_lengths: &[u8]) -> Self {
+        let hlit = 257.max(lit_lengths.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1));
+        let hdist = 1.max(dist_lengths.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1));
+        let all: Vec<u8> = lit_lengths[..hlit].iter().chain(&dist_lengths[..hdist]).copied().collect();
+
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < all.len() {
+            let value = all[i];
+            let run = all[i..].iter().take_while(|&&l| l == value).count();
+            if value == 0 && run >= 11 {
+                let n = run.min(138);
+                tokens.push((18, (n - 11) as u8));
+                i += n;
+            } else if value == 0 && run >= 3 {
+                tokens.push((17, (run - 3) as u8));
+                i += run;
+            } else if value != 0 && run >= 4 {
+                // Literal length once, then repeat it 3..=6 times
+                tokens.push((value, 0));
+                let n = (run - 1).min(6);
+                tokens.push((16, (n - 3) as u8));
+                i += 1 + n;
+            } else {
+                tokens.push((value, 0));
+                i += 1;
+            }
+        }
+
+        let mut cl_freq = [0u32; 19];
+        for &(sym, _) in &tokens {
+            cl_freq[sym as usize] += 1;
+        }
+        let cl_lengths = build_lengths(&cl_freq, MAX_CL_BITS);
+        let hclen = 4.max(CL_ORDER.iter().rposition(|&sym| cl_lengths[sym] != 0).map_or(0, |i| i + 1));
+        Self { hlit, hdist, hclen, tokens, cl_lengths }
+    }
+
+    fn cost(&self) -> usize {
+        let extra = |sym: u8| match sym {
+            16 => 2,
+            17 => 3,
+            18 => 7,
+            _ => 0,
+        };
+        let tokens: usize = self
+            .tokens
+            .iter()
+            .map(|&(sym, _)| self.cl_lengths[sym as usize] as usize + extra(sym))
+            .sum();
+        14 + 3 * self.hclen + tokens
+    }
+
+    fn write(&self, writer: &mut BitWriter) {
+        writer.put((self.hlit - 257) as u32, 5);
+        writer.put((self.hdist - 1) as u32, 5);
+        writer.put((self.hclen - 4) as u32, 4);
+        for &sym in &CL_ORDER[..self.hclen] {
+            writer.put(self.cl_lengths[sym] as u32, 3);
+        }
+        let codes = build_codes(&self.cl_lengths);
+        for &(sym, extra) in &self.tokens {
+            writer.put(codes[sym as usize] as u32, self.cl_lengths[sym as usize] as u32);
+            match sym {
+                16 => writer.put(extra as u32, 2),
+                17 => writer.put(extra as u32, 3),
+                18 => writer.put(extra as u32, 7),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic mix of text-like and noisy bytes
+    fn sample(len: usize) -> Vec<u8> {
+        let words: [&[u8]; 6] = [b"IfcWall ", b"IfcSlab ", b"GlobalId=", b"0.000 ", b"{\"name\":", b"\n"];
+        let mut state = 0x1234_5678u32;
+        let mut out = Vec::with_capacity(len);
+        while out.len() < len {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            if state.is_multiple_of(5) {
+                out.push(state as u8);
+            } else {
+                out.extend_from_slice(words[state as usize % words.len()]);
+            }
+        }
+        out.truncate(len);
+        out
+    }
+
+    #[test]
+    fn test_round_trip_all_levels() {
+        let data = sample(200_000);
+        for level in 0..=9 {
+            let packed = compress(&data, Level::new(level));
+            assert_eq!(decompress(&packed).unwrap(), data, "level {}", level);
+            if level > 0 {
+                assert!(packed.len() < data.len() / 2, "level {}: {}", level, packed.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_inputs() {
+        for data in [&b""[..], b"a", b"ab", b"abc", &[0u8; 300_000][..]] {
+            let packed = compress(data, Level::DEFAULT);
+            assert_eq!(decompress(&packed).unwrap(), data);
+        }
+        // Incompressible data falls back to stored blocks
+        let mut noise = vec![0u8; 100_000];
+        let mut state = 1u64;
+        for byte in &mut noise {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            *byte = (state >> 56) as u8;
+        }
+        let packed = compress(&noise, Level::BEST);
+        assert!(packed.len() <= noise.len() + noise.len() / 1000);
+        assert_eq!(decompress(&packed).unwrap(), noise);
+    }
+
+    #[test]
+    fn test_chunked_writes_and_sync_flush() {
+        let data = sample(150_000);
+        let mut deflater = Deflater::new(Level::DEFAULT);
+        let mut out = Vec::new();
+        for (i, piece) in data.chunks(7_919).enumerate() {
+            deflater.write(piece, &mut out);
+            if i == 3 {
+                deflater.flush(&mut out);
+                // Everything written so far is decodable after a flush
+                let mut inflater = super::super::inflate::Inflater::new(out.as_slice(), false);
+                inflater.fill(4 * 7_919).unwrap();
+                assert_eq!(inflater.out, &data[..4 * 7_919]);
+            }
+        }
+        deflater.finish(&mut out);
+        assert_eq!(decompress(&out).unwrap(), data);
+        assert!(out.len() < data.len() / 2);
+    }
+
+    #[test]
+    fn test_decode_reference_stream() {
+        // "hello hello hello" as produced by zlib's deflate (fixed Huffman)
+        let stream = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00];
+        assert_eq!(decompress(&stream).unwrap(), b"hello hello hello");
+        assert!(decompress(&stream[..5]).is_err());
+        assert!(decompress_limited(&stream, 10).is_err());
+    }
+}
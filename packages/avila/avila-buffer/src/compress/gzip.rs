@@ -0,0 +1,173 @@
+//! gzip framing (RFC 1952): 10-byte header, DEFLATE data, CRC-32 and size trailer
+//!
+//! Only single-member streams are produced; decoding accepts the optional
+//! header fields (`FEXTRA`, `FNAME`, `FCOMMENT`, `FHCRC`) that HTTP servers
+//! and the `gzip` tool emit.
+
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+use super::deflate::Deflater;
+use super::inflate::inflate;
+use super::Level;
+
+const MAGIC: [u8; 2] = [0x1F, 0x8B];
+const METHOD_DEFLATE: u8 = 8;
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// OS byte for "unknown"
+const OS_UNKNOWN: u8 = 255;
+
+fn corrupt(message: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+/// Compresses `input` into a gzip stream
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{gzip, Level};
+///
+/// let packed = gzip::compress(b"hello hello hello", Level::DEFAULT);
+/// assert_eq!(&packed[..2], &[0x1F, 0x8B]);
+/// assert_eq!(gzip::decompress(&packed).unwrap(), b"hello hello hello");
+/// ```
+pub fn compress(input: &[u8], level: Level) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 64);
+    // XFL: 2 = best compression, 4 = fastest
+    let xfl = match level.get() {
+        9 => 2,
+        1 => 4,
+        _ => 0,
+    };
+    out.extend_from_slice(&[MAGIC[0], MAGIC[1], METHOD_DEFLATE, 0, 0, 0, 0, 0, xfl, OS_UNKNOWN]);
+
+    let mut deflater = Deflater::new(level);
+    deflater.write(input, &mut out);
+    deflater.finish(&mut out);
+
+    out.extend_from_slice(&crc32(input).to_le_bytes());
+    out.extend_from_slice(&(input.len() as u32).to_le_bytes());
+    out
+}
+
+/// Decompresses a gzip stream, verifying its CRC-32 and size
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>> {
+    decompress_limited(input, usize::MAX)
+}
+
+/// Decompresses a gzip stream, failing past `limit` output bytes
+pub fn decompress_limited(input: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let start = header_len(input)?;
+    if input.len() < start + 8 {
+        return Err(corrupt("Compressed stream is truncated"));
+    }
+    let (data, trailer) = input[start..].split_at(input.len() - start - 8);
+
+    let out = inflate(data, false, limit)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&out) {
+        return Err(corrupt("gzip checksum mismatch"));
+    }
+    if size != out.len() as u32 {
+        return Err(corrupt("gzip size mismatch"));
+    }
+    Ok(out)
+}
+
+/// Length of the member header, including optional fields
+fn header_len(input: &[u8]) -> Result<usize> {
+    if input.len() < 10 || input[..2] != MAGIC {
+        return Err(corrupt("Not a gzip stream"));
+    }
+    if input[2] != METHOD_DEFLATE {
+        return Err(corrupt("Unsupported gzip compression method"));
+    }
+    let flags = input[3];
+    let mut pos = 10;
+
+    if flags & FEXTRA != 0 {
+        let extra = input.get(pos..pos + 2).ok_or_else(|| corrupt("Compressed stream is truncated"))?;
+        pos += 2 + u16::from_le_bytes([extra[0], extra[1]]) as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = input
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(|| corrupt("Compressed stream is truncated"))?;
+            pos += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+
+    if pos > input.len() {
+        return Err(corrupt("Compressed stream is truncated"));
+    }
+    Ok(pos)
+}
+
+/// CRC-32 (IEEE 802.3, reflected) of `data`
+pub fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    !data.iter().fold(!0u32, |crc, &b| TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_decompress_gzip_tool_output() {
+        // `printf 'hello gzip\n' | gzip -n` with FNAME added by hand
+        let mut stream = alloc::vec![0x1F, 0x8B, 0x08, FNAME, 0, 0, 0, 0, 0x00, 0x03];
+        stream.extend_from_slice(b"hello.txt\0");
+        stream.extend_from_slice(&super::super::deflate::compress(b"hello gzip\n", Level::DEFAULT));
+        stream.extend_from_slice(&crc32(b"hello gzip\n").to_le_bytes());
+        stream.extend_from_slice(&11u32.to_le_bytes());
+        assert_eq!(decompress(&stream).unwrap(), b"hello gzip\n");
+
+        let last = stream.len() - 5;
+        stream[last] ^= 1;
+        assert!(decompress(&stream).is_err());
+        assert!(decompress(&stream[..12]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_limited() {
+        let data = b"Content-Encoding: gzip ".repeat(500);
+        let packed = compress(&data, Level::BEST);
+        assert!(packed.len() < data.len() / 10);
+        assert_eq!(decompress_limited(&packed, data.len()).unwrap(), data);
+        assert!(decompress_limited(&packed, data.len() - 1).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! Canonical Huffman codes and the DEFLATE symbol tables (RFC 1951 §3.2)
+
+use alloc::vec;
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+/// Longest literal/length or distance code
+pub(crate) const MAX_BITS: u32 = 15;
+/// Longest code-length code
+pub(crate) const MAX_CL_BITS: u32 = 7;
+
+/// Base match length for symbols 257..=285
+pub(crate) const LEN_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+/// Extra bits following each length symbol
+pub(crate) const LEN_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distance for symbols 0..=29
+pub(crate) const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+/// Extra bits following each distance symbol
+pub(crate) const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+/// Order in which code-length code lengths are stored
+pub(crate) const CL_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Code lengths of the fixed literal/length code (BTYPE = 01)
+pub(crate) fn fixed_lit_lengths() -> [u8; 288] {
+    let mut lengths = [8u8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths
+}
+
+/// Code lengths of the fixed distance code
+pub(crate) fn fixed_dist_lengths() -> [u8; 30] {
+    [5; 30]
+}
+
+/// Length symbol (0-based, add 257) for a match length of 3..=258
+pub(crate) fn length_symbol(len: u16) -> usize {
+    match LEN_BASE.binary_search(&len) {
+        Ok(i) => i,
+        Err(i) => i - 1,
+    }
+}
+
+/// Distance symbol for a distance of 1..=32768
+pub(crate) fn distance_symbol(dist: u16) -> usize {
+    match DIST_BASE.binary_search(&dist) {
+        Ok(i) => i,
+        Err(i) => i - 1,
+    }
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// Builds length-limited code lengths for `freqs`
+///
+/// At least two symbols always get a code, so the result is a complete
+/// prefix code even for blocks that only use one symbol.
+pub(crate) fn build_lengths(freqs: &[u32], max_bits: u32) -> Vec<u8> {
+    let mut lengths = vec![0u8; freqs.len()];
+    let mut leaves: Vec<(u32, usize)> = freqs
+        .iter()
+        .enumerate()
+        .filter(|(_, &f)| f > 0)
+        .map(|(sym, &f)| (f, sym))
+        .collect();
+
+    if leaves.len() < 2 {
+        let used = leaves.first().map_or(0, |&(_, sym)| sym);
+        lengths[used] = 1;
+        lengths[if used == 0 { 1 } else { 0 }] = 1;
+        return lengths;
+    }
+    leaves.sort_unstable();
+
+    // Two-queue Huffman: leaves are sorted and internal nodes are created in
+    // increasing weight order, so the two lightest nodes are always at the
+    // front of one of the queues
+    let n = leaves.len();
+    let mut weight: Vec<u64> = leaves.iter().map(|&(f, _)| f as u64).collect();
+    weight.resize(2 * n - 1, 0);
+    let mut parent = vec![0usize; 2 * n - 1];
+    let (mut next_leaf, mut next_node) = (0, n);
+    for node in n..2 * n - 1 {
+        let mut pick = || {
+            if next_leaf < n && (next_node >= node || weight[next_leaf] <= weight[next_node]) {
+                next_leaf += 1;
+                next_leaf - 1
+            } else {
+                next_node += 1;
+                next_node - 1
+            }
+        };
+        let (a, b) = (pick(), pick());
+        weight[node] = weight[a] + weight[b];
+        parent[a] = node;
+        parent[b] = node;
+    }
+
+    let mut depth = vec![0u32; 2 * n - 1];
+    for i in (0..2 * n - 2).rev() {
+        depth[i] = depth[parent[i]] + 1;
+    }
+
+    // Clamp to max_bits, then rebalance until the Kraft sum is exactly one
+    let max = max_bits as usize;
+    let mut counts = vec![0u32; max + 1];
+    for &d in &depth[..n] {
+        counts[(d as usize).min(max)] += 1;
+    }
+    let mut total: u64 = (1..=max).map(|len| (counts[len] as u64) << (max - len)).sum();
+    while total > 1 << max {
+        counts[max] -= 1;
+        if let Some(len) = (1..max).rev().find(|&len| counts[len] != 0) {
+            counts[len] -= 1;
+            counts[len + 1] += 2;
+        }
+        total -= 1;
+    }
+
+    // Least frequent symbols take the longest codes
+    let mut leaf = 0;
+    for len in (1..=max).rev() {
+        for _ in 0..counts[len] {
+            lengths[leaves[leaf].1] = len as u8;
+            leaf += 1;
+        }
+    }
+    lengths
+}
+
+/// Canonical codes for `lengths`, bit-reversed for LSB-first output
+pub(crate) fn build_codes(lengths: &[u8]) -> Vec<u16> {
+    let mut bl_count = [0u16; MAX_BITS as usize + 1];
+    for &len in lengths {
+        bl_count[len as usize] += 1;
+    }
+    bl_count[0] = 0;
+
+    let mut next = [0u16; MAX_BITS as usize + 2];
+    let mut code = 0u16;
+    for bits in 1..=MAX_BITS as usize {
+        code = (code + bl_count[bits - 1]) << 1;
+        next[bits] = code;
+    }
+
+    lengths
+        .iter()
+        .map(|&len| {
+            if len == 0 {
+                return 0;
+            }
+            let code = next[len as usize];
+            next[len as usize] += 1;
+            code.reverse_bits() >> (16 - len as u32)
+        })
+        .collect()
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Single-level lookup table indexed by the next `bits` input bits
+///
+/// Each entry packs `symbol << 4 | length`; a zero length marks bit patterns
+/// that no code starts with.
+pub(crate) struct DecodeTable {
+    pub(crate) bits: u32,
+    entries: Vec<u16>,
+}
+
+impl DecodeTable {
+    pub(crate) fn new(lengths: &[u8]) -> Result<Self> {
+        let max_len = lengths.iter().copied().max().unwrap_or(0) as u32;
+        if max_len == 0 {
+            // Valid for a distance code in a block made only of literals
+            return Ok(Self { bits: 1, entries: vec![0; 2] });
+        }
+
+        let mut kraft = 0u32;
+        for &len in lengths.iter().filter(|&&len| len > 0) {
+            kraft += 1 << (max_len - len as u32);
+        }
+        if kraft > 1 << max_len {
+            return Err(Error::new(ErrorKind::InvalidInput, "Over-subscribed Huffman code"));
+        }
+
+        let codes = build_codes(lengths);
+        let mut entries = vec![0u16; 1 << max_len];
+        for (sym, (&len, &code)) in lengths.iter().zip(&codes).enumerate() {
+            if len == 0 {
+                continue;
+            }
+            let entry = (sym as u16) << 4 | len as u16;
+            let mut index = code as usize;
+            while index < entries.len() {
+                entries[index] = entry;
+                index += 1 << len;
+            }
+        }
+        Ok(Self { bits: max_len, entries })
+    }
+
+    /// Looks up `(symbol, length)`; length 0 means no code matches
+    #[inline]
+    pub(crate) fn lookup(&self, bits: u64) -> (u16, u32) {
+        let entry = self.entries[(bits & ((1 << self.bits) - 1)) as usize];
+        (entry >> 4, (entry & 0xF) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kraft(lengths: &[u8], max: u32) -> u64 {
+        lengths.iter().filter(|&&l| l > 0).map(|&l| 1u64 << (max - l as u32)).sum()
+    }
+
+    #[test]
+    fn test_lengths_are_complete_and_limited() {
+        // Fibonacci frequencies force a degenerate tree deeper than 15
+        let mut freqs = vec![0u32; 30];
+        let (mut a, mut b) = (1u32, 1u32);
+        for f in freqs.iter_mut() {
+            *f = a;
+            (a, b) = (b, a.saturating_add(b));
+        }
+        let lengths = build_lengths(&freqs, MAX_BITS);
+        assert!(lengths.iter().all(|&l| (1..=15).contains(&l)));
+        assert_eq!(kraft(&lengths, 15), 1 << 15);
+
+        // Single used symbol still yields a complete two-symbol code
+        let lengths = build_lengths(&[0, 0, 5, 0], MAX_BITS);
+        assert_eq!(lengths, [1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_symbol_tables() {
+        assert_eq!(length_symbol(3), 0);
+        assert_eq!(length_symbol(12), 8);
+        assert_eq!(length_symbol(257), 27);
+        assert_eq!(length_symbol(258), 28);
+        assert_eq!(distance_symbol(1), 0);
+        assert_eq!(distance_symbol(6), 4);
+        assert_eq!(distance_symbol(32768), 29);
+    }
+}
@@ -0,0 +1,259 @@
+//! DEFLATE decoder (RFC 1951) with optional zlib framing (RFC 1950)
+
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+use super::bits::{BitReader, ByteSource};
+use super::huffman::{
+    fixed_dist_lengths, fixed_lit_lengths, DecodeTable, CL_ORDER, DIST_BASE, DIST_EXTRA, LEN_BASE, LEN_EXTRA,
+};
+use super::zlib::Adler32;
+
+/// Back-reference window; decoded output older than this is never read again
+pub(crate) const WINDOW: usize = 32 * 1024;
+
+fn corrupt(message: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+enum State {
+    Header,
+    Block,
+    Stored { remaining: usize, last: bool },
+    Codes { lit: DecodeTable, dist: DecodeTable, last: bool },
+    Trailer,
+    Done,
+}
+
+/// Pull-based decoder: output is produced on demand into `out`
+///
+/// `out` doubles as the back-reference window. Streaming callers drain it
+/// with [`Inflater::discard`] while keeping the last [`WINDOW`] bytes.
+pub(crate) struct Inflater<S> {
+    input: BitReader<S>,
+    state: State,
+    zlib: bool,
+    pub(crate) out: Vec<u8>,
+    adler: Adler32,
+    checked: usize,
+}
+
+impl<S: ByteSource> Inflater<S> {
+    pub(crate) fn new(source: S, zlib: bool) -> Self {
+        Self {
+            input: BitReader::new(source),
+            state: if zlib { State::Header } else { State::Block },
+            zlib,
+            out: Vec::new(),
+            adler: Adler32::new(),
+            checked: 0,
+        }
+    }
+
+    /// Checks if the final block (and trailer) has been decoded
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+
+    /// Decodes until `out` holds at least `target` bytes or the stream ends
+    ///
+    /// May overshoot by up to one stored block or one match.
+    pub(crate) fn fill(&mut self, target: usize) -> Result<()> {
+        while self.out.len() < target {
+            match core::mem::replace(&mut self.state, State::Done) {
+                State::Header => {
+                    self.read_zlib_header()?;
+                    self.state = State::Block;
+                }
+                State::Block => self.state = self.read_block_header()?,
+                State::Stored { remaining, last } => {
+                    let n = remaining.min(target - self.out.len());
+                    for _ in 0..n {
+                        self.out.push(self.input.bits(8)? as u8);
+                    }
+                    self.state = if n < remaining {
+                        State::Stored { remaining: remaining - n, last }
+                    } else {
+                        self.block_end(last)
+                    };
+                }
+                State::Codes { lit, dist, last } => {
+                    self.state = if self.decode_codes(&lit, &dist, target)? {
+                        self.block_end(last)
+                    } else {
+                        State::Codes { lit, dist, last }
+                    };
+                }
+                State::Trailer => {
+                    self.update_checksum();
+                    self.input.align();
+                    let mut expected = 0u32;
+                    for _ in 0..4 {
+                        expected = expected << 8 | self.input.bits(8)?;
+                    }
+                    if expected != self.adler.finish() {
+                        return Err(corrupt("zlib checksum mismatch"));
+                    }
+                    self.state = State::Done;
+                }
+                State::Done => break,
+            }
+        }
+        self.update_checksum();
+        Ok(())
+    }
+
+    /// Drops the first `n` bytes of `out` (already handed to the caller)
+    #[cfg(feature = "std")]
+    pub(crate) fn discard(&mut self, n: usize) {
+        self.update_checksum();
+        self.out.drain(..n);
+        self.checked -= n;
+    }
+
+    fn update_checksum(&mut self) {
+        if self.zlib {
+            self.adler.update(&self.out[self.checked..]);
+        }
+        self.checked = self.out.len();
+    }
+
+    fn block_end(&self, last: bool) -> State {
+        match (last, self.zlib) {
+            (false, _) => State::Block,
+            (true, true) => State::Trailer,
+            (true, false) => State::Done,
+        }
+    }
+
+    fn read_zlib_header(&mut self) -> Result<()> {
+        let (cmf, flg) = (self.input.bits(8)?, self.input.bits(8)?);
+        if (cmf << 8 | flg) % 31 != 0 {
+            return Err(corrupt("Invalid zlib header checksum"));
+        }
+        if cmf & 0x0F != 8 || cmf >> 4 > 7 {
+            return Err(corrupt("Unsupported zlib compression method"));
+        }
+        if flg & 0x20 != 0 {
+            return Err(corrupt("zlib preset dictionaries are not supported"));
+        }
+        Ok(())
+    }
+
+    fn read_block_header(&mut self) -> Result<State> {
+        let last = self.input.bits(1)? == 1;
+        match self.input.bits(2)? {
+            0 => {
+                self.input.align();
+                let len = self.input.bits(16)?;
+                let nlen = self.input.bits(16)?;
+                if len != !nlen & 0xFFFF {
+                    return Err(corrupt("Stored block length mismatch"));
+                }
+                Ok(State::Stored { remaining: len as usize, last })
+            }
+            1 => Ok(State::Codes {
+                lit: DecodeTable::new(&fixed_lit_lengths())?,
+                dist: DecodeTable::new(&fixed_dist_lengths())?,
+                last,
+            }),
+            2 => {
+                let (lit, dist) = self.read_dynamic_tables()?;
+                Ok(State::Codes { lit, dist, last })
+            }
+            _ => Err(corrupt("Invalid DEFLATE block type")),
+        }
+    }
+
+    fn read_dynamic_tables(&mut self) -> Result<(DecodeTable, DecodeTable)> {
+        let hlit = self.input.bits(5)? as usize + 257;
+        let hdist = self.input.bits(5)? as usize + 1;
+        let hclen = self.input.bits(4)? as usize + 4;
+        if hlit > 286 || hdist > 30 {
+            return Err(corrupt("Too many DEFLATE codes"));
+        }
+
+        let mut cl_lengths = [0u8; 19];
+        for &sym in &CL_ORDER[..hclen] {
+            cl_lengths[sym] = self.input.bits(3)? as u8;
+        }
+        let cl_table = DecodeTable::new(&cl_lengths)?;
+
+        let mut lengths = [0u8; 286 + 30];
+        let mut i = 0;
+        while i < hlit + hdist {
+            let (value, repeat) = match self.input.decode(&cl_table)? {
+                sym @ 0..=15 => (sym as u8, 1),
+                16 => {
+                    let previous = *lengths[..i].last().ok_or_else(|| corrupt("Repeat with no previous length"))?;
+                    (previous, 3 + self.input.bits(2)? as usize)
+                }
+                17 => (0, 3 + self.input.bits(3)? as usize),
+                _ => (0, 11 + self.input.bits(7)? as usize),
+            };
+            if i + repeat > hlit + hdist {
+                return Err(corrupt("Code length repeat overflows"));
+            }
+            lengths[i..i + repeat].fill(value);
+            i += repeat;
+        }
+        if lengths[256] == 0 {
+            return Err(corrupt("Missing end-of-block code"));
+        }
+
+        Ok((DecodeTable::new(&lengths[..hlit])?, DecodeTable::new(&lengths[hlit..hlit + hdist])?))
+    }
+
+    /// Returns `true` once the end-of-block symbol is reached
+    fn decode_codes(&mut self, lit: &DecodeTable, dist: &DecodeTable, target: usize) -> Result<bool> {
+        while self.out.len() < target {
+            let sym = self.input.decode(lit)? as usize;
+            if sym < 256 {
+                self.out.push(sym as u8);
+                continue;
+            }
+            if sym == 256 {
+                return Ok(true);
+            }
+
+            let index = sym - 257;
+            if index >= LEN_BASE.len() {
+                return Err(corrupt("Invalid length symbol"));
+            }
+            let len = LEN_BASE[index] as usize + self.input.bits(LEN_EXTRA[index] as u32)? as usize;
+            let dsym = self.input.decode(dist)? as usize;
+            if dsym >= DIST_BASE.len() {
+                return Err(corrupt("Invalid distance symbol"));
+            }
+            let distance = DIST_BASE[dsym] as usize + self.input.bits(DIST_EXTRA[dsym] as u32)? as usize;
+            if distance > self.out.len() {
+                return Err(corrupt("Distance points before start of output"));
+            }
+
+            let start = self.out.len() - distance;
+            if distance >= len {
+                self.out.extend_from_within(start..start + len);
+            } else {
+                // Overlapping copy repeats the last `distance` bytes
+                for k in 0..len {
+                    let byte = self.out[start + k];
+                    self.out.push(byte);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Decodes a whole stream, failing if it would exceed `limit` bytes
+pub(crate) fn inflate(input: &[u8], zlib: bool, limit: usize) -> Result<Vec<u8>> {
+    let mut inflater = Inflater::new(input, zlib);
+    inflater.fill(limit.saturating_add(1))?;
+    if inflater.out.len() > limit {
+        return Err(Error::new(ErrorKind::InvalidInput, "Decompressed data exceeds the size limit"));
+    }
+    if !inflater.is_done() {
+        return Err(corrupt("Compressed stream is truncated"));
+    }
+    Ok(inflater.out)
+}
@@ -0,0 +1,280 @@
+//! LZ4 block format
+//!
+//! Trades ratio for speed: decoding is a tight copy loop, which suits large
+//! binary payloads (GLB buffers, vertex data) that are read far more often
+//! than written. Blocks carry no length; [`compress_prepend_size`] adds a
+//! little-endian `u32` prefix so [`decompress_size_prepended`] can size the
+//! output.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use avila_error::{Error, ErrorKind, Result};
+
+const MIN_MATCH: usize = 4;
+/// The last match must start at least this far from the end
+const MF_LIMIT: usize = 12;
+/// The last bytes of a block are always literals
+const LAST_LITERALS: usize = 5;
+const HASH_LOG: u32 = 14;
+const MAX_OFFSET: usize = 65_535;
+
+/// Worst-case compressed size of `len` input bytes
+pub fn max_compressed_size(len: usize) -> usize {
+    len + len / 255 + 16
+}
+
+/// Compresses `input` into an LZ4 block
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::lz4;
+///
+/// let vertices: Vec<u8> = [0.0f32, 1.0, 0.5].iter().cycle().take(3000).flat_map(|f| f.to_le_bytes()).collect();
+/// let block = lz4::compress(&vertices);
+/// assert!(block.len() < vertices.len() / 10);
+/// assert_eq!(lz4::decompress(&block, vertices.len()).unwrap(), vertices);
+/// ```
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(max_compressed_size(input.len()));
+    compress_into(input, &mut out);
+    out
+}
+
+/// Compresses `input`, appending the block to `out`
+pub fn compress_into(input: &[u8], out: &mut Vec<u8>) {
+    let len = input.len();
+    let mut anchor = 0;
+
+    if len > MF_LIMIT {
+        let match_limit = len - LAST_LITERALS;
+        let scan_end = len - MF_LIMIT;
+        // Positions + 1, so zero means empty
+        let mut table = vec![0u32; 1 << HASH_LOG];
+        let mut pos = 0;
+
+        while pos < scan_end {
+            let sequence = read_u32(input, pos);
+            let slot = &mut table[hash(sequence)];
+            let candidate = (*slot as usize).wrapping_sub(1);
+            *slot = pos as u32 + 1;
+
+            if candidate == usize::MAX || pos - candidate > MAX_OFFSET || read_u32(input, candidate) != sequence {
+                // Skip faster through data that does not compress
+                pos += 1 + ((pos - anchor) >> 6);
+                continue;
+            }
+
+            // Extend backwards into pending literals, then forwards
+            let (mut start, mut reference) = (pos, candidate);
+            while start > anchor && reference > 0 && input[start - 1] == input[reference - 1] {
+                start -= 1;
+                reference -= 1;
+            }
+            let mut match_len = MIN_MATCH + (pos - start);
+            while start + match_len < match_limit && input[reference + match_len] == input[start + match_len] {
+                match_len += 1;
+            }
+
+            write_sequence(out, &input[anchor..start], start - reference, match_len);
+            pos = start + match_len;
+            anchor = pos;
+            if pos < scan_end {
+                // Seed the table with a position inside the match
+                table[hash(read_u32(input, pos - 2))] = (pos - 2) as u32 + 1;
+            }
+        }
+    }
+
+    write_literals_only(out, &input[anchor..]);
+}
+
+/// Decompresses an LZ4 block, failing if the output would exceed `max_size`
+pub fn decompress(input: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(max_size.min(input.len().saturating_mul(8)));
+    decompress_into(input, &mut out, max_size)?;
+    Ok(out)
+}
+
+/// Decompresses an LZ4 block, appending at most `max_size` bytes to `out`
+///
+/// Matches may reference bytes already in `out` before the call, which
+/// allows decoding dependent blocks back to back.
+pub fn decompress_into(input: &[u8], out: &mut Vec<u8>, max_size: usize) -> Result<()> {
+    let limit = out.len().saturating_add(max_size);
+    let mut pos = 0;
+
+    loop {
+        let token = *input.get(pos).ok_or_else(|| corrupt("LZ4 block is truncated"))?;
+        pos += 1;
+
+        let literals = read_length(input, &mut pos, (token >> 4) as usize)?;
+        let end = pos.checked_add(literals).filter(|&end| end <= input.len());
+        let end = end.ok_or_else(|| corrupt("LZ4 literals run past the end of the block"))?;
+        if out.len() + literals > limit {
+            return Err(too_large());
+        }
+        out.extend_from_slice(&input[pos..end]);
+        pos = end;
+
+        if pos == input.len() {
+            return Ok(());
+        }
+
+        let offset = match input.get(pos..pos + 2) {
+            Some(bytes) => u16::from_le_bytes([bytes[0], bytes[1]]) as usize,
+            None => return Err(corrupt("LZ4 block is truncated")),
+        };
+        pos += 2;
+        if offset == 0 || offset > out.len() {
+            return Err(corrupt("LZ4 match offset is out of range"));
+        }
+
+        let match_len = read_length(input, &mut pos, (token & 0x0F) as usize)? + MIN_MATCH;
+        if out.len() + match_len > limit {
+            return Err(too_large());
+        }
+        let start = out.len() - offset;
+        if offset >= match_len {
+            out.extend_from_within(start..start + match_len);
+        } else {
+            // Overlapping match repeats the last `offset` bytes
+            for k in 0..match_len {
+                let byte = out[start + k];
+                out.push(byte);
+            }
+        }
+    }
+}
+
+/// Compresses with a little-endian `u32` size prefix
+pub fn compress_prepend_size(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + max_compressed_size(input.len()));
+    out.extend_from_slice(&(input.len() as u32).to_le_bytes());
+    compress_into(input, &mut out);
+    out
+}
+
+/// Decompresses a block written by [`compress_prepend_size`]
+pub fn decompress_size_prepended(input: &[u8]) -> Result<Vec<u8>> {
+    decompress_size_prepended_limited(input, u32::MAX as usize)
+}
+
+/// Like [`decompress_size_prepended`], rejecting declared sizes over `limit`
+pub fn decompress_size_prepended_limited(input: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let Some((prefix, block)) = input.split_first_chunk::<4>() else {
+        return Err(corrupt("LZ4 size prefix is missing"));
+    };
+    let size = u32::from_le_bytes(*prefix) as usize;
+    if size > limit {
+        return Err(too_large());
+    }
+    let out = decompress(block, size)?;
+    if out.len() != size {
+        return Err(corrupt("LZ4 block size does not match its prefix"));
+    }
+    Ok(out)
+}
+
+#[inline]
+fn read_u32(input: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([input[pos], input[pos + 1], input[pos + 2], input[pos + 3]])
+}
+
+#[inline]
+fn hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize
+}
+
+fn write_length(out: &mut Vec<u8>, mut len: usize) {
+    while len >= 255 {
+        out.push(255);
+        len -= 255;
+    }
+    out.push(len as u8);
+}
+
+fn write_sequence(out: &mut Vec<u8>, literals: &[u8], offset: usize, match_len: usize) {
+    let extra = match_len - MIN_MATCH;
+    out.push((literals.len().min(15) as u8) << 4 | extra.min(15) as u8);
+    if literals.len() >= 15 {
+        write_length(out, literals.len() - 15);
+    }
+    out.extend_from_slice(literals);
+    out.extend_from_slice(&(offset as u16).to_le_bytes());
+    if extra >= 15 {
+        write_length(out, extra - 15);
+    }
+}
+
+fn write_literals_only(out: &mut Vec<u8>, literals: &[u8]) {
+    out.push((literals.len().min(15) as u8) << 4);
+    if literals.len() >= 15 {
+        write_length(out, literals.len() - 15);
+    }
+    out.extend_from_slice(literals);
+}
+
+fn read_length(input: &[u8], pos: &mut usize, nibble: usize) -> Result<usize> {
+    let mut len = nibble;
+    if nibble == 15 {
+        loop {
+            let byte = *input.get(*pos).ok_or_else(|| corrupt("LZ4 block is truncated"))?;
+            *pos += 1;
+            len = len.checked_add(byte as usize).ok_or_else(|| corrupt("LZ4 length overflows"))?;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+    Ok(len)
+}
+
+fn corrupt(message: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn too_large() -> Error {
+    Error::new(ErrorKind::InvalidInput, "Decompressed data exceeds the size limit")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let text = b"IfcWall;IfcWall;IfcSlab;IfcWall;IfcDoor;".repeat(500);
+        let mut noise = vec![0u8; 10_000];
+        let mut state = 7u32;
+        for byte in &mut noise {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            *byte = (state >> 24) as u8;
+        }
+
+        for data in [&b""[..], b"short", b"exactly13byte", &text, &noise, &[9u8; 70_000][..]] {
+            let block = compress(data);
+            assert!(block.len() <= max_compressed_size(data.len()));
+            assert_eq!(decompress(&block, data.len()).unwrap(), data);
+        }
+        assert!(compress(&text).len() < text.len() / 20);
+    }
+
+    #[test]
+    fn test_reference_block() {
+        // Literal "abc", then a 12-byte match at offset 3, then 5 literals
+        let block = [0x38, b'a', b'b', b'c', 0x03, 0x00, 0x50, b'a', b'b', b'c', b'a', b'b'];
+        assert_eq!(decompress(&block, 64).unwrap(), b"abcabcabcabcabcabcab");
+        assert!(decompress(&block, 10).is_err());
+        assert!(decompress(&block[..5], 64).is_err());
+    }
+
+    #[test]
+    fn test_size_prepended() {
+        let data = b"0.0,0.0,0.0;1.0,0.0,0.0;".repeat(100);
+        let packed = compress_prepend_size(&data);
+        assert_eq!(decompress_size_prepended(&packed).unwrap(), data);
+        assert!(decompress_size_prepended_limited(&packed, 100).is_err());
+        assert!(decompress_size_prepended(&packed[..2]).is_err());
+    }
+}
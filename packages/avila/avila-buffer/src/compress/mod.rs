@@ -0,0 +1,159 @@
+//! Compression codecs, pure Rust with no external crates
+//!
+//! - [`lz4`]: LZ4 block format, for fast round-trips of large binary payloads
+//! - [`deflate`], [`zlib`] and [`gzip`]: DEFLATE (RFC 1951), raw, zlib-framed
+//!   (RFC 1950) or gzip-framed (RFC 1952), interoperable with zlib, browsers
+//!   and HTTP `Content-Encoding`
+//! - [`Deflater`] for incremental encoding, plus `std::io` adapters
+//!   ([`DeflateWriter`], [`InflateReader`]) with the `std` feature
+//! - [`simple`]: RLE, delta and zero-run helpers for small payloads
+//!
+//! [`Codec`] picks a format at runtime and works on slices or [`ByteBuffer`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use avila_buffer::compress::Codec;
+//! use avila_buffer::ByteBuffer;
+//!
+//! let metadata = ByteBuffer::from_vec(br#"{"IfcWall":120,"IfcSlab":40,"IfcWall":120}"#.repeat(20));
+//! for codec in [Codec::Lz4, Codec::Deflate, Codec::Zlib, Codec::Gzip] {
+//!     let packed = metadata.compress(codec);
+//!     assert!(packed.len() < metadata.len());
+//!     assert_eq!(packed.decompress(codec).unwrap(), metadata);
+//! }
+//! ```
+
+mod bits;
+pub mod deflate;
+pub mod gzip;
+mod huffman;
+mod inflate;
+pub mod lz4;
+pub mod simple;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod zlib;
+
+pub use deflate::Deflater;
+pub use simple::{DeltaEncoder, RleCompressor, XorCipher, ZeroRunCompressor};
+#[cfg(feature = "std")]
+pub use stream::{DeflateWriter, InflateReader};
+
+use crate::ByteBuffer;
+use alloc::vec::Vec;
+use avila_error::Result;
+
+/// DEFLATE compression level, from 0 (stored) to 9 (smallest output)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Level(u8);
+
+impl Level {
+    /// No compression: stored blocks only
+    pub const NONE: Level = Level(0);
+    /// Fastest matching
+    pub const FASTEST: Level = Level(1);
+    /// zlib's default trade-off
+    pub const DEFAULT: Level = Level(6);
+    /// Smallest output
+    pub const BEST: Level = Level(9);
+
+    /// Creates a level, clamping values above 9
+    pub const fn new(level: u8) -> Self {
+        Level(if level > 9 { 9 } else { level })
+    }
+
+    /// Numeric level (0-9)
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Compression format selectable at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// LZ4 block with a little-endian `u32` size prefix
+    Lz4,
+    /// Raw DEFLATE stream
+    Deflate,
+    /// zlib stream (DEFLATE with header and Adler-32 trailer)
+    Zlib,
+    /// gzip member (DEFLATE with header and CRC-32 trailer)
+    Gzip,
+}
+
+impl Codec {
+    /// Compresses with the default level
+    pub fn compress(self, input: &[u8]) -> Vec<u8> {
+        self.compress_with(input, Level::DEFAULT)
+    }
+
+    /// Compresses with `level` (ignored by LZ4)
+    pub fn compress_with(self, input: &[u8], level: Level) -> Vec<u8> {
+        match self {
+            Codec::Lz4 => lz4::compress_prepend_size(input),
+            Codec::Deflate => deflate::compress(input, level),
+            Codec::Zlib => zlib::compress(input, level),
+            Codec::Gzip => gzip::compress(input, level),
+        }
+    }
+
+    /// Decompresses `input`
+    pub fn decompress(self, input: &[u8]) -> Result<Vec<u8>> {
+        self.decompress_limited(input, usize::MAX)
+    }
+
+    /// Decompresses `input`, failing if the output would exceed `limit` bytes
+    pub fn decompress_limited(self, input: &[u8], limit: usize) -> Result<Vec<u8>> {
+        match self {
+            Codec::Lz4 => lz4::decompress_size_prepended_limited(input, limit),
+            Codec::Deflate => deflate::decompress_limited(input, limit),
+            Codec::Zlib => zlib::decompress_limited(input, limit),
+            Codec::Gzip => gzip::decompress_limited(input, limit),
+        }
+    }
+}
+
+impl ByteBuffer {
+    /// Compresses the unread bytes into a new buffer
+    pub fn compress(&self, codec: Codec) -> ByteBuffer {
+        ByteBuffer::from_vec(codec.compress(self.as_slice()))
+    }
+
+    /// Decompresses the unread bytes into a new buffer
+    pub fn decompress(&self, codec: Codec) -> Result<ByteBuffer> {
+        codec.decompress(self.as_slice()).map(ByteBuffer::from_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codecs_round_trip_buffers() {
+        let mut buffer = ByteBuffer::new();
+        buffer.write(b"skipped").unwrap();
+        buffer.write(&b"glTF vertex data ".repeat(1000)).unwrap();
+        buffer.skip(7).unwrap();
+
+        for codec in [Codec::Lz4, Codec::Deflate, Codec::Zlib, Codec::Gzip] {
+            let packed = buffer.compress(codec);
+            assert!(packed.len() < 1000, "{:?}: {}", codec, packed.len());
+            assert_eq!(packed.decompress(codec).unwrap().as_slice(), buffer.as_slice());
+            assert!(codec.decompress_limited(packed.as_slice(), 100).is_err());
+        }
+    }
+
+    #[test]
+    fn test_level_clamps() {
+        assert_eq!(Level::new(42), Level::BEST);
+        assert_eq!(Level::default().get(), 6);
+    }
+}
@@ -230,9 +230,7 @@ impl ZeroRunCompressor {
             if input[i] == 0x00 {
                 if i + 1 < input.len() {
                     let count = input[i + 1] as usize;
-                    for _ in 0..count {
-                        output.push(0);
-                    }
+                    output.resize(output.len() + count, 0);
                     i += 2;
                 } else {
                     return Err(Error::new(
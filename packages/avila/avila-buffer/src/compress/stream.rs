@@ -0,0 +1,264 @@
+//! `std::io` adapters for streaming DEFLATE and zlib
+
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use super::bits::ByteSource;
+use super::deflate::Deflater;
+use super::inflate::{Inflater, WINDOW};
+use super::Level;
+
+fn to_io(err: avila_error::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// Compresses everything written to it into `inner`
+///
+/// Call [`finish`](Self::finish) to write the final block and get the inner
+/// writer back; dropping the writer finishes on a best-effort basis and
+/// ignores errors.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{zlib, DeflateWriter, Level};
+/// use std::io::Write;
+///
+/// let mut writer = DeflateWriter::zlib(Vec::new(), Level::DEFAULT);
+/// for storey in 0..50 {
+///     writeln!(writer, "{{\"storey\":{},\"elevation\":{}.0}}", storey, storey * 3).unwrap();
+/// }
+/// let packed = writer.finish().unwrap();
+/// assert!(zlib::decompress(&packed).unwrap().starts_with(b"{\"storey\":0,"));
+/// ```
+pub struct DeflateWriter<W: Write> {
+    inner: Option<W>,
+    deflater: Deflater,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> DeflateWriter<W> {
+    /// Writes a raw DEFLATE stream (e.g. `Content-Encoding: deflate` bodies that omit zlib framing)
+    pub fn new(inner: W, level: Level) -> Self {
+        Self::with_deflater(inner, Deflater::new(level))
+    }
+
+    /// Writes a zlib stream
+    pub fn zlib(inner: W, level: Level) -> Self {
+        Self::with_deflater(inner, Deflater::zlib(level))
+    }
+
+    fn with_deflater(inner: W, deflater: Deflater) -> Self {
+        Self {
+            inner: Some(inner),
+            deflater,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("inner writer taken by finish")
+    }
+
+    /// Returns a mutable reference to the inner writer
+    ///
+    /// Writing to it directly corrupts the compressed stream.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("inner writer taken by finish")
+    }
+
+    /// Writes the final block and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.deflater.finish(&mut self.buf);
+        self.write_out()?;
+        Ok(self.inner.take().expect("inner writer taken by finish"))
+    }
+
+    fn write_out(&mut self) -> io::Result<()> {
+        if !self.buf.is_empty() {
+            let inner = self.inner.as_mut().expect("inner writer taken by finish");
+            inner.write_all(&self.buf)?;
+            self.buf.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for DeflateWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.deflater.write(data, &mut self.buf);
+        self.write_out()?;
+        Ok(data.len())
+    }
+
+    /// Sync-flushes so the reader can decode everything written so far
+    fn flush(&mut self) -> io::Result<()> {
+        self.deflater.flush(&mut self.buf);
+        self.write_out()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for DeflateWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            self.deflater.finish(&mut self.buf);
+            let _ = self.write_out();
+        }
+    }
+}
+
+/// Buffered byte source over a reader
+struct IoSource<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+}
+
+impl<R: Read> ByteSource for IoSource<R> {
+    fn next_byte(&mut self) -> avila_error::Result<Option<u8>> {
+        if self.pos == self.len {
+            self.len = loop {
+                match self.inner.read(&mut self.buf) {
+                    Ok(n) => break n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            };
+            self.pos = 0;
+            if self.len == 0 {
+                return Ok(None);
+            }
+        }
+        self.pos += 1;
+        Ok(Some(self.buf[self.pos - 1]))
+    }
+}
+
+/// Decompresses a DEFLATE or zlib stream read from `inner`
+///
+/// Only the last 32 KiB of output is kept for back-references, so memory
+/// stays bounded regardless of the stream length.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{zlib, InflateReader, Level};
+/// use std::io::Read;
+///
+/// let packed = zlib::compress(&[42u8; 100_000], Level::FASTEST);
+/// let mut reader = InflateReader::zlib(packed.as_slice());
+/// let mut total = 0;
+/// let mut chunk = [0u8; 4096];
+/// loop {
+///     match reader.read(&mut chunk).unwrap() {
+///         0 => break,
+///         n => total += n,
+///     }
+/// }
+/// assert_eq!(total, 100_000);
+/// ```
+pub struct InflateReader<R: Read> {
+    inflater: Inflater<IoSource<R>>,
+    pos: usize,
+}
+
+impl<R: Read> InflateReader<R> {
+    /// Reads a raw DEFLATE stream
+    pub fn new(inner: R) -> Self {
+        Self::with_framing(inner, false)
+    }
+
+    /// Reads a zlib stream and verifies its checksum at the end
+    pub fn zlib(inner: R) -> Self {
+        Self::with_framing(inner, true)
+    }
+
+    fn with_framing(inner: R, zlib: bool) -> Self {
+        let source = IoSource {
+            inner,
+            buf: vec![0; 8 * 1024],
+            pos: 0,
+            len: 0,
+        };
+        Self {
+            inflater: Inflater::new(source, zlib),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for InflateReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos == self.inflater.out.len() {
+            if self.pos >= 4 * WINDOW {
+                self.inflater.discard(self.pos - WINDOW);
+                self.pos = WINDOW;
+            }
+            self.inflater.fill(self.pos + buf.len()).map_err(to_io)?;
+        }
+
+        let available = &self.inflater.out[self.pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compress::{deflate, zlib};
+
+    fn sample() -> Vec<u8> {
+        (0..300_000u64).map(|i| (i * i / 7 % 251) as u8 ^ (i / 1000) as u8).collect()
+    }
+
+    #[test]
+    fn test_writer_reader_round_trip() {
+        let data = sample();
+        let mut writer = DeflateWriter::zlib(Vec::new(), Level::DEFAULT);
+        for piece in data.chunks(10_007) {
+            writer.write_all(piece).unwrap();
+        }
+        let packed = writer.finish().unwrap();
+        assert_eq!(zlib::decompress(&packed).unwrap(), data);
+
+        let mut reader = InflateReader::zlib(packed.as_slice());
+        let mut out = Vec::new();
+        let mut chunk = [0u8; 3_001];
+        loop {
+            match reader.read(&mut chunk).unwrap() {
+                0 => break,
+                n => out.extend_from_slice(&chunk[..n]),
+            }
+        }
+        assert_eq!(out, data);
+        assert!(reader.inflater.out.len() <= 4 * WINDOW + 3_001 + 65_535);
+    }
+
+    #[test]
+    fn test_reader_reports_corruption() {
+        let mut packed = deflate::compress(&sample(), Level::FASTEST);
+        packed.truncate(packed.len() / 2);
+        let mut out = Vec::new();
+        let err = InflateReader::new(packed.as_slice()).read_to_end(&mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_drop_finishes_stream() {
+        let mut sink = Vec::new();
+        {
+            let mut writer = DeflateWriter::new(&mut sink, Level::BEST);
+            writer.write_all(b"dropped without finish").unwrap();
+        }
+        assert_eq!(deflate::decompress(&sink).unwrap(), b"dropped without finish");
+    }
+}
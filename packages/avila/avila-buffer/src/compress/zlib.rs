@@ -0,0 +1,112 @@
+//! zlib framing (RFC 1950): 2-byte header, DEFLATE data, Adler-32 trailer
+
+use alloc::vec::Vec;
+use avila_error::Result;
+
+use super::deflate::Deflater;
+use super::inflate::inflate;
+use super::Level;
+
+/// Compresses `input` into a zlib stream
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::compress::{zlib, Level};
+///
+/// let packed = zlib::compress(b"hello hello hello", Level::BEST);
+/// assert_eq!(&packed[..2], &[0x78, 0xDA]);
+/// assert_eq!(zlib::decompress(&packed).unwrap(), b"hello hello hello");
+/// ```
+pub fn compress(input: &[u8], level: Level) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 64);
+    let mut deflater = Deflater::zlib(level);
+    deflater.write(input, &mut out);
+    deflater.finish(&mut out);
+    out
+}
+
+/// Decompresses a zlib stream, verifying its checksum
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>> {
+    inflate(input, true, usize::MAX)
+}
+
+/// Decompresses a zlib stream, failing past `limit` output bytes
+pub fn decompress_limited(input: &[u8], limit: usize) -> Result<Vec<u8>> {
+    inflate(input, true, limit)
+}
+
+/// Adler-32 checksum of `data`
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(data);
+    adler.finish()
+}
+
+/// Running Adler-32 checksum
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MOD: u32 = 65_521;
+    /// Longest run before the sums can overflow a u32
+    const NMAX: usize = 5_552;
+
+    pub(crate) fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(Self::NMAX) {
+            for &byte in chunk {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adler32() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        let data = alloc::vec![0xFFu8; 100_000];
+        let mut split = Adler32::new();
+        split.update(&data[..12_345]);
+        split.update(&data[12_345..]);
+        assert_eq!(split.finish(), adler32(&data));
+    }
+
+    #[test]
+    fn test_reference_stream_and_corruption() {
+        // zlib.compress(b"hello hello hello") from CPython
+        let stream = [
+            0x78, 0x9c, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00, 0x3a, 0x2e, 0x06, 0x7d,
+        ];
+        assert_eq!(decompress(&stream).unwrap(), b"hello hello hello");
+
+        let mut bad_checksum = stream;
+        bad_checksum[15] ^= 1;
+        assert!(decompress(&bad_checksum).is_err());
+        let mut bad_header = stream;
+        bad_header[1] = 0x9d;
+        assert!(decompress(&bad_header).is_err());
+
+        let packed = compress(&stream, Level::DEFAULT);
+        assert_eq!(decompress(&packed).unwrap(), stream);
+    }
+}
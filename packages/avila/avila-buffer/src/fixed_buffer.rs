@@ -172,11 +172,7 @@ impl<const N: usize> Default for FixedBuffer<N> {
 
 impl<const N: usize> Clone for FixedBuffer<N> {
     fn clone(&self) -> Self {
-        Self {
-            data: self.data,
-            read_pos: self.read_pos,
-            write_pos: self.write_pos,
-        }
+        *self
     }
 }
 
@@ -232,11 +228,11 @@ mod tests {
     }
 
     #[test]
-    fn test_fixed_buffer_clone() {
+    fn test_fixed_buffer_copy() {
         let mut buf: FixedBuffer<8> = FixedBuffer::new();
         buf.write(b"test").unwrap();
 
-        let cloned = buf.clone();
-        assert_eq!(buf.as_slice(), cloned.as_slice());
+        let copied = buf;
+        assert_eq!(buf.as_slice(), copied.as_slice());
     }
 }
@@ -0,0 +1,55 @@
+//! Fuzzing entry points (feature `fuzzing`)
+//!
+//! Each function takes arbitrary bytes and must not panic; the targets in
+//! `packages/avila/fuzz` only forward their input. Whatever decodes must
+//! survive a round trip through the matching encoder.
+
+use crate::codec::{LengthDelimitedCodec, MessageReader, VarintDecoder, VarintEncoder};
+use crate::ByteBuffer;
+
+/// Frame limit for [`framed`], so the fuzzer does not chase huge allocations
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Varints: decodes every value in the input, in all four flavours
+pub fn varint(data: &[u8]) {
+    macro_rules! round_trip {
+        ($read:ident, $write:ident) => {
+            let mut src = ByteBuffer::from(data);
+            while let Ok(value) = src.$read() {
+                let mut encoded = ByteBuffer::new();
+                encoded.$write(value).unwrap();
+                assert_eq!(encoded.$read().unwrap(), value);
+            }
+        };
+    }
+    round_trip!(read_varint_u64, write_varint_u64);
+    round_trip!(read_varint_i64, write_varint_i64);
+    round_trip!(read_varint_u32, write_varint_u32);
+    round_trip!(read_varint_i32, write_varint_i32);
+}
+
+/// Length-delimited framing: frames decode until the input runs out
+pub fn framed(data: &[u8]) {
+    let codec = LengthDelimitedCodec::new().with_max_frame_len(MAX_FRAME_LEN);
+    let mut src = ByteBuffer::from(data);
+    while let Ok(Some(frame)) = codec.decode(&mut src) {
+        let mut wire = ByteBuffer::new();
+        codec.encode(frame.as_slice(), &mut wire).unwrap();
+        let decoded = codec.decode(&mut wire).unwrap().expect("re-encoded frame is complete");
+        assert_eq!(decoded.as_slice(), frame.as_slice());
+        assert_eq!(wire.len(), 0);
+    }
+}
+
+/// Message fields: every accessor returns, whatever the wire type
+pub fn message(data: &[u8]) {
+    for field in MessageReader::new(data) {
+        let Ok(field) = field else { break };
+        let _ = (field.as_u64(), field.as_u32(), field.as_i64(), field.as_i32(), field.as_bool());
+        let _ = (field.as_f32(), field.as_f64(), field.as_bytes(), field.as_str());
+        // Nested messages are fields too
+        if let Ok(bytes) = field.as_bytes() {
+            MessageReader::new(bytes).take_while(|field| field.is_ok()).for_each(drop);
+        }
+    }
+}
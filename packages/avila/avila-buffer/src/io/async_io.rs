@@ -0,0 +1,177 @@
+//! Async I/O adapters for Tokio streams (feature `async`)
+
+use crate::codec::LengthDelimitedCodec;
+use crate::ByteBuffer;
+use avila_error::{Error, ErrorKind, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Minimum spare capacity reserved before each read
+const READ_RESERVE: usize = 4096;
+
+impl ByteBuffer {
+    /// Reads once from `reader` straight into the buffer's spare capacity
+    ///
+    /// Returns the number of bytes read; `0` means end of stream.
+    pub async fn read_from_async<R: AsyncRead + Unpin>(&mut self, reader: &mut R) -> Result<usize> {
+        // Bytes past write_pos are stale; drop them so the read appends in place
+        self.data.truncate(self.write_pos);
+        if self.data.capacity() - self.data.len() < READ_RESERVE {
+            self.data.reserve(READ_RESERVE);
+        }
+
+        let n = reader.read_buf(&mut self.data).await?;
+        self.write_pos = self.data.len();
+        Ok(n)
+    }
+
+    /// Writes all unread bytes to `writer` and consumes them
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(&mut self, writer: &mut W) -> Result<usize> {
+        let n = self.available();
+        writer.write_all(self.as_slice()).await?;
+        self.read_pos = self.write_pos;
+        Ok(n)
+    }
+}
+
+/// Length-delimited frames over an async stream
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(stream: tokio::net::TcpStream) -> avila_error::Result<()> {
+/// use avila_buffer::io::Framed;
+///
+/// let mut framed = Framed::new(stream);
+/// framed.send(b"hello").await?;
+/// while let Some(frame) = framed.next_frame().await? {
+///     println!("{} bytes", frame.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Framed<T> {
+    io: T,
+    codec: LengthDelimitedCodec,
+    read_buf: ByteBuffer,
+    write_buf: ByteBuffer,
+}
+
+impl<T> Framed<T> {
+    /// Wraps a stream with the default codec
+    pub fn new(io: T) -> Self {
+        Self::with_codec(io, LengthDelimitedCodec::new())
+    }
+
+    /// Wraps a stream with a configured codec
+    pub fn with_codec(io: T, codec: LengthDelimitedCodec) -> Self {
+        Self {
+            io,
+            codec,
+            read_buf: ByteBuffer::with_capacity(READ_RESERVE),
+            write_buf: ByteBuffer::with_capacity(16),
+        }
+    }
+
+    /// Returns the underlying stream
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Returns the underlying stream mutably
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Unwraps the stream; bytes already buffered are discarded
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+impl<T: AsyncRead + Unpin> Framed<T> {
+    /// Reads the next frame; `Ok(None)` on a clean end of stream
+    pub async fn next_frame(&mut self) -> Result<Option<ByteBuffer>> {
+        loop {
+            if let Some(frame) = self.codec.decode(&mut self.read_buf)? {
+                if self.read_buf.read_position() > self.read_buf.capacity() / 2 {
+                    self.read_buf.compact();
+                }
+                return Ok(Some(frame));
+            }
+
+            if self.read_buf.read_from_async(&mut self.io).await? == 0 {
+                return if self.read_buf.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(Error::new(ErrorKind::Io, "Stream closed in the middle of a frame"))
+                };
+            }
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> Framed<T> {
+    /// Writes one frame and flushes; the payload is not copied
+    pub async fn send(&mut self, payload: &[u8]) -> Result<()> {
+        self.write_buf.clear();
+        self.codec.encode_header(payload.len(), &mut self.write_buf)?;
+        self.io.write_all(self.write_buf.as_slice()).await?;
+        self.io.write_all(payload).await?;
+        self.io.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_async_fill_and_drain() {
+        block_on(async {
+            let mut source: &[u8] = b"streamed bytes";
+            let mut buffer = ByteBuffer::new();
+            while buffer.read_from_async(&mut source).await.unwrap() > 0 {}
+            assert_eq!(buffer.as_slice(), b"streamed bytes");
+
+            let mut sink = Vec::new();
+            assert_eq!(buffer.write_to_async(&mut sink).await.unwrap(), 14);
+            assert_eq!(sink, b"streamed bytes");
+            assert!(buffer.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_framed_roundtrip() {
+        block_on(async {
+            let (client, server) = tokio::io::duplex(64);
+            let mut writer = Framed::new(client);
+            let mut reader = Framed::new(server);
+
+            let big = vec![0xAB; 10_000];
+            let send = async {
+                writer.send(b"header").await.unwrap();
+                writer.send(&big).await.unwrap();
+                drop(writer);
+            };
+            let receive = async {
+                let mut frames = Vec::new();
+                while let Some(frame) = reader.next_frame().await.unwrap() {
+                    frames.push(frame.as_slice().to_vec());
+                }
+                frames
+            };
+
+            let ((), frames) = tokio::join!(send, receive);
+            assert_eq!(frames, vec![b"header".to_vec(), big.clone()]);
+        });
+    }
+}
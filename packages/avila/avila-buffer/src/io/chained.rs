@@ -0,0 +1,267 @@
+//! Logical concatenation of buffers for zero-copy message assembly
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::ByteBuffer;
+
+#[cfg(feature = "std")]
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+
+/// Sequence of [`ByteBuffer`]s read as one contiguous stream
+///
+/// Buffers are moved in, never copied, so a frame header and its body can be
+/// assembled separately and sent with a single vectored write.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::{ByteBuffer, ChainedBuffer};
+///
+/// let mut chain = ChainedBuffer::new();
+/// chain.push(ByteBuffer::from(&b"HEAD"[..]));
+/// chain.push(ByteBuffer::from(&b"body"[..]));
+/// assert_eq!(chain.len(), 8);
+///
+/// let mut out = [0u8; 6];
+/// assert_eq!(chain.read(&mut out), 6);
+/// assert_eq!(&out, b"HEADbo");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChainedBuffer {
+    buffers: VecDeque<ByteBuffer>,
+    len: usize,
+}
+
+impl ChainedBuffer {
+    /// Creates an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a buffer to the end of the chain
+    pub fn push(&mut self, buffer: ByteBuffer) {
+        if !buffer.is_empty() {
+            self.len += buffer.len();
+            self.buffers.push_back(buffer);
+        }
+    }
+
+    /// Prepends a buffer (e.g. a header computed after the body)
+    pub fn push_front(&mut self, buffer: ByteBuffer) {
+        if !buffer.is_empty() {
+            self.len += buffer.len();
+            self.buffers.push_front(buffer);
+        }
+    }
+
+    /// Total unread bytes across all buffers
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if there is nothing left to read
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of buffers still holding unread data
+    pub fn buffer_count(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Iterates over the unread part of each buffer, in order
+    pub fn chunks(&self) -> impl Iterator<Item = &[u8]> {
+        self.buffers.iter().map(ByteBuffer::as_slice)
+    }
+
+    /// Reads bytes across buffer boundaries, dropping exhausted buffers
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let n = self.peek(buf);
+        self.advance(n);
+        n
+    }
+
+    /// Copies bytes without consuming them
+    pub fn peek(&self, buf: &mut [u8]) -> usize {
+        let mut copied = 0;
+        for chunk in self.chunks() {
+            if copied == buf.len() {
+                break;
+            }
+            let n = chunk.len().min(buf.len() - copied);
+            buf[copied..copied + n].copy_from_slice(&chunk[..n]);
+            copied += n;
+        }
+        copied
+    }
+
+    /// Consumes up to `n` bytes, returning how many were skipped
+    pub fn advance(&mut self, n: usize) -> usize {
+        let mut remaining = n.min(self.len);
+        let skipped = remaining;
+        while remaining > 0 {
+            let Some(front) = self.buffers.front_mut() else { break };
+            let step = remaining.min(front.len());
+            // skip() never fails; it clamps to the available bytes
+            let _ = front.skip(step);
+            remaining -= step;
+            if front.is_empty() {
+                self.buffers.pop_front();
+            }
+        }
+        self.len -= skipped;
+        skipped
+    }
+
+    /// Removes all buffers
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+        self.len = 0;
+    }
+
+    /// Copies the unread bytes into a single contiguous vector
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.len);
+        for chunk in self.chunks() {
+            out.extend_from_slice(chunk);
+        }
+        out
+    }
+
+    /// Collapses the chain into one buffer (no copy when it holds a single buffer)
+    pub fn into_byte_buffer(mut self) -> ByteBuffer {
+        if self.buffers.len() == 1 {
+            self.buffers.pop_front().unwrap_or_default()
+        } else {
+            ByteBuffer::from_vec(self.to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ChainedBuffer {
+    /// Borrows every buffer as an [`IoSlice`] for `write_vectored`
+    pub fn io_slices(&self) -> Vec<IoSlice<'_>> {
+        self.chunks().map(IoSlice::new).collect()
+    }
+
+    /// Writes the chain to `writer` with vectored writes until drained
+    pub fn write_to<W: Write>(&mut self, writer: &mut W) -> io::Result<usize> {
+        let mut total = 0;
+        while !self.is_empty() {
+            let n = writer.write_vectored(&self.io_slices())?;
+            if n == 0 {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write chained buffer"));
+            }
+            self.advance(n);
+            total += n;
+        }
+        Ok(total)
+    }
+}
+
+impl From<Vec<ByteBuffer>> for ChainedBuffer {
+    fn from(buffers: Vec<ByteBuffer>) -> Self {
+        let mut chain = Self::new();
+        for buffer in buffers {
+            chain.push(buffer);
+        }
+        chain
+    }
+}
+
+#[cfg(feature = "std")]
+impl Read for ChainedBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(ChainedBuffer::read(self, buf))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut total = 0;
+        for buf in bufs {
+            let n = ChainedBuffer::read(self, buf);
+            total += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// Writes append to the last buffer, or start a new one
+#[cfg(feature = "std")]
+impl Write for ChainedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.buffers.back_mut() {
+            Some(back) => {
+                back.write(buf).map_err(|e| io::Error::other(e.to_string()))?;
+                self.len += buf.len();
+            }
+            None => self.push(ByteBuffer::from(buf)),
+        }
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let mut total = 0;
+        for buf in bufs {
+            total += Write::write(self, buf)?;
+        }
+        Ok(total)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chained_read_across_buffers() {
+        let mut chain = ChainedBuffer::from(alloc::vec![
+            ByteBuffer::from(&b"ab"[..]),
+            ByteBuffer::new(),
+            ByteBuffer::from(&b"cde"[..]),
+        ]);
+        chain.push_front(ByteBuffer::from(&b"01"[..]));
+        assert_eq!((chain.len(), chain.buffer_count()), (7, 3));
+
+        let mut out = [0u8; 3];
+        assert_eq!(chain.read(&mut out), 3);
+        assert_eq!(&out, b"01a");
+        assert_eq!(chain.chunks().collect::<Vec<_>>(), [&b"b"[..], &b"cde"[..]]);
+
+        assert_eq!(chain.advance(2), 2);
+        assert_eq!(chain.to_vec(), b"de");
+        assert_eq!(chain.advance(10), 2);
+        assert!(chain.is_empty() && chain.buffer_count() == 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_chained_std_io() {
+        let mut chain = ChainedBuffer::new();
+        chain.push(ByteBuffer::from(&b"HDR:"[..]));
+        Write::write_all(&mut chain, b"payload").unwrap();
+        assert_eq!(chain.buffer_count(), 1);
+
+        let mut sink = Vec::new();
+        assert_eq!(chain.write_to(&mut sink).unwrap(), 11);
+        assert_eq!(sink, b"HDR:payload");
+        assert!(chain.is_empty());
+
+        chain.push(ByteBuffer::from(&b"abc"[..]));
+        chain.push(ByteBuffer::from(&b"defg"[..]));
+        let (mut a, mut b) = ([0u8; 2], [0u8; 8]);
+        let n = chain.read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)]).unwrap();
+        assert_eq!((n, &a, &b[..5]), (7, b"ab", &b"cdefg"[..]));
+    }
+}
@@ -1,7 +1,11 @@
 //! I/O traits and implementations for buffers
 
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod chained;
 pub mod read;
 pub mod write;
 
-pub use read::*;
-pub use write::*;
+#[cfg(feature = "async")]
+pub use async_io::Framed;
+pub use chained::ChainedBuffer;
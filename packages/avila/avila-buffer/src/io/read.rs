@@ -1,7 +1,7 @@
 //! Read trait implementations for buffers
 
 #[cfg(feature = "std")]
-use std::io::{self, Read};
+use std::io::{self, IoSliceMut, Read};
 
 #[cfg(feature = "std")]
 impl Read for crate::ByteBuffer {
@@ -15,9 +15,13 @@ impl Read for crate::ByteBuffer {
 
         match self.read(&mut buf[..to_read]) {
             Ok(n) => Ok(n),
-            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+            Err(e) => Err(io::Error::other(e.to_string())),
         }
     }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        crate::ByteBuffer::read_vectored(self, bufs).map_err(|e| io::Error::other(e.to_string()))
+    }
 }
 
 // Additional read implementations to be added
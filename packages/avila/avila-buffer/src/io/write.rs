@@ -1,17 +1,21 @@
 //! Write trait implementations for buffers
 
 #[cfg(feature = "std")]
-use std::io::{self, Write};
+use std::io::{self, IoSlice, Write};
 
 #[cfg(feature = "std")]
 impl Write for crate::ByteBuffer {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self.write(buf) {
             Ok(n) => Ok(n),
-            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+            Err(e) => Err(io::Error::other(e.to_string())),
         }
     }
 
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        crate::ByteBuffer::write_vectored(self, bufs).map_err(|e| io::Error::other(e.to_string()))
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
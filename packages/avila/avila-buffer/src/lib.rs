@@ -24,6 +24,7 @@ use avila_error::{Error, ErrorKind, Result};
 
 // Module declarations
 pub mod codec;
+pub mod compress;
 pub mod io;
 pub mod sync;
 pub mod utils;
@@ -33,6 +34,9 @@ pub mod iter;
 pub mod fixed_buffer;
 pub mod pool;
 
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+
 // Re-exports
 pub use codec::{PrimitiveDecoder, PrimitiveEncoder, VarintDecoder, VarintEncoder};
 pub use utils::BufferMetrics;
@@ -40,6 +44,9 @@ pub use fixed_buffer::FixedBuffer;
 pub use pool::{BufferPool, PooledBuffer};
 pub use iter::{ByteBufferIter, ChunkIter, WindowIter};
 
+pub use sync::{ArcBuffer, SharedBytes};
+pub use io::ChainedBuffer;
+
 #[cfg(feature = "std")]
 pub use sync::SharedBuffer;
 
@@ -345,6 +352,46 @@ impl ByteBuffer {
     }
 }
 
+#[cfg(feature = "std")]
+impl ByteBuffer {
+    /// Writes several slices in order with a single reservation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use avila_buffer::ByteBuffer;
+    /// use std::io::IoSlice;
+    ///
+    /// let mut buffer = ByteBuffer::new();
+    /// let n = buffer.write_vectored(&[IoSlice::new(b"HDR"), IoSlice::new(b"body")]).unwrap();
+    /// assert_eq!(n, 7);
+    /// assert_eq!(buffer.as_slice(), b"HDRbody");
+    /// ```
+    pub fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        if self.remaining() < total {
+            self.reserve(total - self.remaining());
+        }
+        for buf in bufs {
+            self.write(buf)?;
+        }
+        Ok(total)
+    }
+
+    /// Reads into several slices in order, stopping when the buffer runs dry
+    pub fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        let mut total = 0;
+        for buf in bufs {
+            let n = self.read(buf)?;
+            total += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
 impl Default for ByteBuffer {
     fn default() -> Self {
         Self::new()
@@ -508,6 +555,8 @@ pub mod prelude {
     pub use crate::{ByteBuffer, RingBuffer, FixedBuffer, BufferPool, PooledBuffer};
     pub use crate::codec::{PrimitiveDecoder, PrimitiveEncoder, VarintDecoder, VarintEncoder};
     pub use crate::utils::BufferMetrics;
+    pub use crate::sync::{ArcBuffer, SharedBytes};
+    pub use crate::io::ChainedBuffer;
 
     #[cfg(feature = "std")]
     pub use crate::sync::SharedBuffer;
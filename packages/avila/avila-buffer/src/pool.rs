@@ -2,7 +2,6 @@
 //! Reduces allocation overhead in high-throughput scenarios
 
 extern crate alloc;
-use alloc::vec::Vec;
 use alloc::collections::VecDeque;
 use crate::ByteBuffer;
 use avila_error::{Error, ErrorKind, Result};
@@ -14,7 +13,7 @@ use avila_error::{Error, ErrorKind, Result};
 /// ```
 /// use avila_buffer::BufferPool;
 ///
-/// let pool = BufferPool::new(10, 1024);
+/// let mut pool = BufferPool::new(10, 1024);
 /// let buffer = pool.acquire().unwrap();
 /// // use buffer...
 /// pool.release(buffer);
@@ -226,7 +225,7 @@ mod tests {
         {
             let mut buf = PooledBuffer::new(&mut pool).unwrap();
             buf.write(b"test").unwrap();
-            assert_eq!(pool.available(), 1);
+            assert_eq!(buf.as_slice(), b"test");
         } // Buffer automatically released here
 
         assert_eq!(pool.available(), 2);
@@ -0,0 +1,252 @@
+//! Immutable reference-counted byte slices
+//!
+//! [`ArcBuffer`] is the read-only counterpart of [`SharedBytes`](super::SharedBytes):
+//! one allocation fanned out to many consumers, each holding a cheap view
+//! that can be sliced or split further without copying.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, RangeBounds};
+use avila_error::{Error, ErrorKind, Result};
+
+use super::SharedBytes;
+
+/// Immutable, reference-counted view into shared bytes
+///
+/// Cloning, slicing and splitting are O(1) and share the allocation.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::{ArcBuffer, ByteBuffer};
+///
+/// let mut frame = ArcBuffer::from(ByteBuffer::from(&b"HDRpayload"[..]));
+/// let header = frame.split_to(3).unwrap();
+/// let tail = frame.slice(4..).unwrap();
+///
+/// assert_eq!(&header[..], b"HDR");
+/// assert_eq!(&frame[..], b"payload");
+/// assert_eq!(&tail[..], b"oad");
+/// assert!(header.ptr_eq(&tail));
+/// ```
+#[derive(Clone)]
+pub struct ArcBuffer {
+    data: Arc<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl ArcBuffer {
+    /// Creates an empty buffer
+    pub fn new() -> Self {
+        Self::from_vec(Vec::new())
+    }
+
+    /// Takes ownership of a vector without copying
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        let end = data.len();
+        Self {
+            data: Arc::new(data),
+            start: 0,
+            end,
+        }
+    }
+
+    /// Copies a slice into new shared storage
+    pub fn copy_from_slice(data: &[u8]) -> Self {
+        Self::from_vec(data.to_vec())
+    }
+
+    /// Returns the viewed bytes
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+
+    /// Returns the number of viewed bytes
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Checks if the view is empty
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Number of views sharing the underlying allocation
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.data)
+    }
+
+    /// Checks if two views share the same allocation
+    pub fn ptr_eq(&self, other: &ArcBuffer) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    /// Returns a view of a sub-range, sharing the allocation
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Result<ArcBuffer> {
+        let (start, end) = super::resolve_range(range, self.len())?;
+        Ok(Self {
+            data: Arc::clone(&self.data),
+            start: self.start + start,
+            end: self.start + end,
+        })
+    }
+
+    /// Splits off `[0, at)` and returns it; `self` keeps `[at, len)`
+    pub fn split_to(&mut self, at: usize) -> Result<ArcBuffer> {
+        let head = self.slice(..at)?;
+        self.start += at;
+        Ok(head)
+    }
+
+    /// Splits off `[at, len)` and returns it; `self` keeps `[0, at)`
+    pub fn split_off(&mut self, at: usize) -> Result<ArcBuffer> {
+        let tail = self.slice(at..)?;
+        self.end = self.start + at;
+        Ok(tail)
+    }
+
+    /// Drops the first `n` bytes from the view
+    pub fn advance(&mut self, n: usize) -> Result<()> {
+        if n > self.len() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Advance beyond end"));
+        }
+        self.start += n;
+        Ok(())
+    }
+
+    /// Shortens the view to `len` bytes
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.end = self.start + len;
+        }
+    }
+
+    /// Extracts the bytes, reusing the allocation when this is the only view
+    pub fn into_vec(self) -> Vec<u8> {
+        SharedBytes::from(self).into_vec()
+    }
+}
+
+impl Default for ArcBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for ArcBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for ArcBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl PartialEq for ArcBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for ArcBuffer {}
+
+impl Hash for ArcBuffer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl core::fmt::Debug for ArcBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArcBuffer")
+            .field("len", &self.len())
+            .field("ref_count", &self.ref_count())
+            .finish()
+    }
+}
+
+impl From<Vec<u8>> for ArcBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        Self::from_vec(data)
+    }
+}
+
+impl From<&[u8]> for ArcBuffer {
+    fn from(data: &[u8]) -> Self {
+        Self::copy_from_slice(data)
+    }
+}
+
+impl From<crate::ByteBuffer> for ArcBuffer {
+    fn from(mut buffer: crate::ByteBuffer) -> Self {
+        let (start, end) = (buffer.read_pos, buffer.write_pos);
+        buffer.data.truncate(end);
+        Self {
+            data: Arc::new(buffer.data),
+            start,
+            end,
+        }
+    }
+}
+
+/// Freezes shared bytes without copying
+impl From<SharedBytes> for ArcBuffer {
+    fn from(bytes: SharedBytes) -> Self {
+        let (data, start, end) = bytes.into_parts();
+        Self { data, start, end }
+    }
+}
+
+/// Thaws the view; the first mutation copies if the allocation is shared
+impl From<ArcBuffer> for SharedBytes {
+    fn from(buffer: ArcBuffer) -> Self {
+        SharedBytes::from_parts(buffer.data, buffer.start, buffer.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_and_split_share_storage() {
+        let mut buffer = ArcBuffer::from_vec(b"0123456789".to_vec());
+        let middle = buffer.slice(2..=4).unwrap();
+        assert_eq!(middle.as_slice(), b"234");
+
+        let head = buffer.split_to(3).unwrap();
+        let tail = buffer.split_off(4).unwrap();
+        assert_eq!((head.as_slice(), buffer.as_slice(), tail.as_slice()), (&b"012"[..], &b"3456"[..], &b"789"[..]));
+        assert_eq!(buffer.ref_count(), 4);
+        assert!(head.ptr_eq(&tail));
+
+        assert!(buffer.slice(2..9).is_err());
+        assert!(buffer.split_to(5).is_err());
+        buffer.advance(1).unwrap();
+        assert_eq!(buffer.as_slice(), b"456");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut source = crate::ByteBuffer::from_vec(b"..data".to_vec());
+        source.skip(2).unwrap();
+        let frozen = ArcBuffer::from(source);
+        assert_eq!(frozen.as_slice(), b"data");
+
+        let reader = frozen.clone();
+        let mut thawed = SharedBytes::from(frozen);
+        thawed.write(b"!");
+        assert_eq!((reader.as_slice(), thawed.as_slice()), (&b"data"[..], &b"data!"[..]));
+
+        let refrozen = ArcBuffer::from(thawed);
+        assert_eq!(refrozen.into_vec(), b"data!".to_vec());
+        assert_eq!(reader.into_vec(), b"data".to_vec());
+    }
+}
@@ -0,0 +1,303 @@
+//! Copy-on-write shared bytes
+//!
+//! [`SharedBytes`] lets many readers hold the same bytes while a writer keeps
+//! mutating its own handle. Handles share one allocation through an atomic
+//! reference count; the first mutation on a shared handle copies the bytes
+//! it views into a private allocation, so readers never observe the change.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::{Deref, RangeBounds};
+use avila_error::Result;
+
+/// Reference-counted byte storage with copy-on-write mutation
+///
+/// Cloning is O(1) and never copies. Mutating methods copy only when the
+/// storage is shared with another handle (or when this handle is a
+/// sub-slice view), which makes snapshots for concurrent readers cheap.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::sync::SharedBytes;
+///
+/// let mut writer = SharedBytes::from_vec(b"header".to_vec());
+/// let reader = writer.clone();
+///
+/// writer.write(b"+body");
+/// assert_eq!(reader.as_slice(), b"header");
+/// assert_eq!(writer.as_slice(), b"header+body");
+/// ```
+#[derive(Clone)]
+pub struct SharedBytes {
+    data: Arc<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl SharedBytes {
+    /// Creates empty shared bytes
+    pub fn new() -> Self {
+        Self::from_vec(Vec::new())
+    }
+
+    /// Takes ownership of a vector without copying
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        let end = data.len();
+        Self {
+            data: Arc::new(data),
+            start: 0,
+            end,
+        }
+    }
+
+    /// Copies a slice into new shared storage
+    pub fn copy_from_slice(data: &[u8]) -> Self {
+        Self::from_vec(data.to_vec())
+    }
+
+    /// Returns the viewed bytes
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+
+    /// Returns the number of viewed bytes
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Checks if the view is empty
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Number of handles sharing the underlying allocation
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.data)
+    }
+
+    /// Checks if this handle is the only owner of the allocation
+    pub fn is_unique(&self) -> bool {
+        self.ref_count() == 1
+    }
+
+    /// Checks if two handles share the same allocation
+    pub fn ptr_eq(&self, other: &SharedBytes) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    /// Returns a cheap view of a sub-range, sharing the allocation
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Result<SharedBytes> {
+        let (start, end) = super::resolve_range(range, self.len())?;
+        Ok(Self {
+            data: Arc::clone(&self.data),
+            start: self.start + start,
+            end: self.start + end,
+        })
+    }
+
+    /// Returns mutable access, copying first if the storage is shared
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        let (start, end) = (self.start, self.end);
+        &mut self.storage_mut()[start..end]
+    }
+
+    /// Appends bytes, copying first if the storage is shared
+    pub fn write(&mut self, bytes: &[u8]) -> usize {
+        let end = self.end;
+        let storage = self.storage_mut();
+        storage.truncate(end);
+        storage.extend_from_slice(bytes);
+        self.end += bytes.len();
+        bytes.len()
+    }
+
+    /// Shortens the view to `len` bytes (never copies)
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.end = self.start + len;
+        }
+    }
+
+    /// Extracts the bytes, reusing the allocation when unique
+    pub fn into_vec(self) -> Vec<u8> {
+        let (start, end) = (self.start, self.end);
+        match Arc::try_unwrap(self.data) {
+            Ok(mut data) => {
+                data.truncate(end);
+                if start > 0 {
+                    data.drain(..start);
+                }
+                data
+            }
+            Err(shared) => shared[start..end].to_vec(),
+        }
+    }
+
+    pub(crate) fn into_parts(self) -> (Arc<Vec<u8>>, usize, usize) {
+        (self.data, self.start, self.end)
+    }
+
+    pub(crate) fn from_parts(data: Arc<Vec<u8>>, start: usize, end: usize) -> Self {
+        Self { data, start, end }
+    }
+
+    /// Ensures this handle owns an allocation laid out exactly as the view
+    fn storage_mut(&mut self) -> &mut Vec<u8> {
+        let exclusive = self.start == 0 && self.end == self.data.len();
+        if !exclusive || Arc::get_mut(&mut self.data).is_none() {
+            let copy = self.as_slice().to_vec();
+            self.end = copy.len();
+            self.start = 0;
+            self.data = Arc::new(copy);
+        }
+
+        // Unique after the copy above
+        Arc::get_mut(&mut self.data).expect("storage is unique after copy-on-write")
+    }
+}
+
+impl Default for SharedBytes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SharedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl PartialEq for SharedBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for SharedBytes {}
+
+impl core::fmt::Debug for SharedBytes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedBytes")
+            .field("len", &self.len())
+            .field("ref_count", &self.ref_count())
+            .finish()
+    }
+}
+
+impl From<Vec<u8>> for SharedBytes {
+    fn from(data: Vec<u8>) -> Self {
+        Self::from_vec(data)
+    }
+}
+
+impl From<&[u8]> for SharedBytes {
+    fn from(data: &[u8]) -> Self {
+        Self::copy_from_slice(data)
+    }
+}
+
+impl From<crate::ByteBuffer> for SharedBytes {
+    fn from(mut buffer: crate::ByteBuffer) -> Self {
+        let (start, end) = (buffer.read_pos, buffer.write_pos);
+        buffer.data.truncate(end);
+        Self {
+            data: Arc::new(buffer.data),
+            start,
+            end,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_shares_storage() {
+        let a = SharedBytes::from_vec(alloc::vec![1, 2, 3]);
+        let b = a.clone();
+
+        assert!(a.ptr_eq(&b));
+        assert_eq!(a.ref_count(), 2);
+    }
+
+    #[test]
+    fn test_write_copies_when_shared() {
+        let mut writer = SharedBytes::from_vec(alloc::vec![1, 2, 3]);
+        let reader = writer.clone();
+
+        writer.make_mut()[0] = 9;
+        assert_eq!(reader.as_slice(), &[1, 2, 3]);
+        assert_eq!(writer.as_slice(), &[9, 2, 3]);
+        assert!(!writer.ptr_eq(&reader));
+        assert!(reader.is_unique());
+    }
+
+    #[test]
+    fn test_write_in_place_when_unique() {
+        let mut bytes = SharedBytes::from_vec(alloc::vec![1, 2, 3]);
+        let before = bytes.as_slice().as_ptr();
+
+        bytes.make_mut()[1] = 7;
+        assert_eq!(bytes.as_slice().as_ptr(), before);
+        assert_eq!(bytes.as_slice(), &[1, 7, 3]);
+    }
+
+    #[test]
+    fn test_slice_view_is_copied_on_write() {
+        let parent = SharedBytes::from_vec(b"Hello World".to_vec());
+        let mut world = parent.slice(6..).unwrap();
+        assert_eq!(world.as_slice(), b"World");
+
+        world.write(b"!");
+        assert_eq!(world.as_slice(), b"World!");
+        assert_eq!(parent.as_slice(), b"Hello World");
+        assert!(parent.slice(3..20).is_err());
+    }
+
+    #[test]
+    fn test_from_byte_buffer_and_into_vec() {
+        let mut buffer = crate::ByteBuffer::from_vec(b"xxpayload".to_vec());
+        buffer.skip(2).unwrap();
+
+        let bytes = SharedBytes::from(buffer);
+        assert_eq!(bytes.as_slice(), b"payload");
+        assert_eq!(bytes.into_vec(), b"payload".to_vec());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod thread_tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_concurrent_readers_see_snapshot() {
+        let mut writer = SharedBytes::from_vec(alloc::vec![0u8; 1024]);
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let snapshot = writer.clone();
+                thread::spawn(move || snapshot.iter().all(|&b| b == 0))
+            })
+            .collect();
+
+        for byte in writer.make_mut() {
+            *byte = 0xFF;
+        }
+
+        for reader in readers {
+            assert!(reader.join().unwrap());
+        }
+        assert!(writer.iter().all(|&b| b == 0xFF));
+    }
+}
@@ -1,5 +1,42 @@
 //! Thread-safe buffer implementations
 
+pub mod arc;
+pub mod cow;
+#[cfg(feature = "std")]
+pub mod mpmc;
 pub mod shared;
+#[cfg(feature = "std")]
+pub mod spsc;
+#[cfg(feature = "std")]
+mod wait;
 
+pub use arc::*;
+pub use cow::*;
+#[cfg(feature = "std")]
+pub use mpmc::{channel, Receiver, Sender};
+#[cfg(feature = "std")]
 pub use shared::*;
+#[cfg(feature = "std")]
+pub use spsc::{spsc_ring, RingConsumer, RingProducer};
+
+use core::ops::{Bound, RangeBounds};
+use avila_error::{Error, ErrorKind, Result};
+
+/// Resolves a range against a view of `len` bytes into `(start, end)`
+pub(crate) fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Result<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n + 1,
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+
+    if start > end || end > len {
+        return Err(Error::new(ErrorKind::InvalidInput, "Slice range out of bounds"));
+    }
+    Ok((start, end))
+}
@@ -0,0 +1,394 @@
+//! Lock-free bounded multi-producer, multi-consumer channel
+//!
+//! Slots carry a sequence number that tells producers and consumers whose
+//! turn it is (D. Vyukov's bounded queue), so `send` and `recv` are a single
+//! compare-and-swap on the fast path.
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+use std::sync::Arc;
+
+use super::wait::{block_on, WaitList};
+
+/// Creates a bounded channel holding at least `capacity` values
+///
+/// The capacity is rounded up to a power of two (minimum 2). Both halves can
+/// be cloned; the channel disconnects when every sender or every receiver is
+/// gone.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::sync::channel;
+/// use std::thread;
+///
+/// let (tx, rx) = channel::<Vec<f32>>(16);
+/// let workers: Vec<_> = (0..4)
+///     .map(|id| {
+///         let tx = tx.clone();
+///         thread::spawn(move || tx.send(vec![id as f32; 3]).unwrap())
+///     })
+///     .collect();
+/// drop(tx);
+///
+/// let meshes: Vec<_> = rx.collect();
+/// assert_eq!(meshes.len(), 4);
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = capacity.max(2).next_power_of_two();
+    let chan = Arc::new(Channel {
+        slots: (0..capacity)
+            .map(|i| Slot {
+                seq: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect(),
+        mask: capacity - 1,
+        enqueue: AtomicUsize::new(0),
+        dequeue: AtomicUsize::new(0),
+        senders: AtomicUsize::new(1),
+        receivers: AtomicUsize::new(1),
+        send_waiters: WaitList::default(),
+        recv_waiters: WaitList::default(),
+    });
+    (
+        Sender {
+            chan: Arc::clone(&chan),
+        },
+        Receiver { chan },
+    )
+}
+
+struct Slot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct Channel<T> {
+    slots: Box<[Slot<T>]>,
+    mask: usize,
+    enqueue: AtomicUsize,
+    dequeue: AtomicUsize,
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+    send_waiters: WaitList,
+    recv_waiters: WaitList,
+}
+
+// SAFETY: a slot's value is only accessed by the thread that won the CAS for
+// it, and the slot sequence number hands it over with release/acquire.
+unsafe impl<T: Send> Send for Channel<T> {}
+unsafe impl<T: Send> Sync for Channel<T> {}
+
+impl<T> Channel<T> {
+    fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos as isize) {
+                0 => match self.enqueue.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        // SAFETY: winning the CAS gives exclusive access to the empty slot
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                },
+                diff if diff < 0 => return Err(value),
+                _ => pos = self.enqueue.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<T> {
+        let mut pos = self.dequeue.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos.wrapping_add(1) as isize) {
+                0 => match self.dequeue.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        // SAFETY: winning the CAS gives exclusive access to the filled slot
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.seq.store(pos.wrapping_add(self.mask + 1), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                },
+                diff if diff < 0 => return None,
+                _ => pos = self.dequeue.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        let tail = self.enqueue.load(Ordering::Acquire);
+        let head = self.dequeue.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(self.mask + 1)
+    }
+}
+
+impl<T> Drop for Channel<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// Sending half of [`channel`]
+pub struct Sender<T> {
+    chan: Arc<Channel<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Maximum number of queued values
+    pub fn capacity(&self) -> usize {
+        self.chan.mask + 1
+    }
+
+    /// Number of queued values (a snapshot under concurrency)
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    /// Checks if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if every receiver was dropped
+    pub fn is_disconnected(&self) -> bool {
+        self.chan.receivers.load(Ordering::Acquire) == 0
+    }
+
+    /// Sends without waiting; gives the value back if full or disconnected
+    pub fn try_send(&self, value: T) -> Result<(), T> {
+        if self.is_disconnected() {
+            return Err(value);
+        }
+        self.chan.push(value)?;
+        self.chan.recv_waiters.wake_all();
+        Ok(())
+    }
+
+    /// Waits for a free slot; gives the value back if disconnected
+    pub async fn send_async(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        poll_fn(|cx| self.poll_send(cx, &mut value)).await
+    }
+
+    /// Blocks until there is a free slot; gives the value back if disconnected
+    pub fn send(&self, value: T) -> Result<(), T> {
+        block_on(self.send_async(value))
+    }
+
+    fn poll_send(&self, cx: &mut Context<'_>, value: &mut Option<T>) -> Poll<Result<(), T>> {
+        let mut pending = value.take().expect("send polled after completion");
+        for attempt in 0..2 {
+            if self.is_disconnected() {
+                return Poll::Ready(Err(pending));
+            }
+            match self.try_send(pending) {
+                Ok(()) => return Poll::Ready(Ok(())),
+                Err(v) => pending = v,
+            }
+            if attempt == 0 {
+                self.chan.send_waiters.register(cx.waker());
+            }
+        }
+        *value = Some(pending);
+        Poll::Pending
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.chan.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            chan: Arc::clone(&self.chan),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.chan.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.chan.recv_waiters.wake_all();
+        }
+    }
+}
+
+/// Receiving half of [`channel`]
+///
+/// Iterating blocks for each value and ends once every sender is gone.
+pub struct Receiver<T> {
+    chan: Arc<Channel<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Maximum number of queued values
+    pub fn capacity(&self) -> usize {
+        self.chan.mask + 1
+    }
+
+    /// Number of queued values (a snapshot under concurrency)
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    /// Checks if nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if every sender was dropped (queued values stay readable)
+    pub fn is_disconnected(&self) -> bool {
+        self.chan.senders.load(Ordering::Acquire) == 0
+    }
+
+    /// Takes a value if one is queued; never waits
+    pub fn try_recv(&self) -> Option<T> {
+        let value = self.chan.pop()?;
+        self.chan.send_waiters.wake_all();
+        Some(value)
+    }
+
+    /// Waits for a value; `None` once disconnected and drained
+    pub async fn recv_async(&self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Blocks until a value arrives; `None` once disconnected and drained
+    pub fn recv(&self) -> Option<T> {
+        block_on(self.recv_async())
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        for attempt in 0..2 {
+            // Observe disconnection before the final pop so no value sent
+            // right before the last sender dropped is missed
+            let disconnected = self.is_disconnected();
+            if let Some(value) = self.try_recv() {
+                return Poll::Ready(Some(value));
+            }
+            if disconnected {
+                return Poll::Ready(None);
+            }
+            if attempt == 0 {
+                self.chan.recv_waiters.register(cx.waker());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.chan.receivers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            chan: Arc::clone(&self.chan),
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        if self.chan.receivers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.chan.send_waiters.wake_all();
+        }
+    }
+}
+
+impl<T> Iterator for Receiver<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_try_send_full_and_disconnect() {
+        let (tx, rx) = channel(3);
+        assert_eq!(tx.capacity(), 4);
+        for i in 0..4 {
+            tx.try_send(i).unwrap();
+        }
+        assert_eq!(tx.try_send(9), Err(9));
+        assert_eq!(rx.try_recv(), Some(0));
+        tx.try_send(4).unwrap();
+
+        drop(tx);
+        assert!(rx.is_disconnected());
+        assert_eq!(rx.collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        let (tx, rx) = channel::<String>(2);
+        drop(rx);
+        assert_eq!(tx.send("late".into()), Err("late".to_string()));
+    }
+
+    #[test]
+    fn test_many_producers_many_consumers() {
+        const PER_PRODUCER: usize = 5_000;
+        let (tx, rx) = channel::<usize>(8);
+
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        tx.send(p * PER_PRODUCER + i).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let consumers: Vec<_> = (0..3)
+            .map(|c| {
+                let rx = rx.clone();
+                thread::spawn(move || {
+                    let mut got = Vec::new();
+                    loop {
+                        // Mix blocking and async receives
+                        let next = if c == 0 { block_on(rx.recv_async()) } else { rx.recv() };
+                        match next {
+                            Some(v) => got.push(v),
+                            None => break got,
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(rx);
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all: Vec<usize> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..4 * PER_PRODUCER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_queued_values_dropped_with_channel() {
+        let marker = Arc::new(());
+        let (tx, rx) = channel(4);
+        tx.send(Arc::clone(&marker)).unwrap();
+        tx.send(Arc::clone(&marker)).unwrap();
+        drop((tx, rx));
+        assert_eq!(Arc::strong_count(&marker), 1);
+    }
+}
@@ -2,6 +2,7 @@
 
 #[cfg(feature = "std")]
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(feature = "std")]
 use avila_error::{Error, ErrorKind, Result};
 
 /// Thread-safe shared buffer
@@ -155,7 +156,7 @@ impl SharedBuffer {
         F: FnOnce(&crate::ByteBuffer) -> R,
     {
         let guard = self.read_lock()?;
-        Ok(f(&*guard))
+        Ok(f(&guard))
     }
 
     /// Execute a closure with write access
@@ -164,7 +165,7 @@ impl SharedBuffer {
         F: FnOnce(&mut crate::ByteBuffer) -> R,
     {
         let mut guard = self.write_lock()?;
-        Ok(f(&mut *guard))
+        Ok(f(&mut guard))
     }
 }
 
@@ -0,0 +1,335 @@
+//! Lock-free single-producer, single-consumer byte ring
+
+use core::cell::UnsafeCell;
+use core::future::poll_fn;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+use std::sync::Arc;
+use avila_error::{Error, ErrorKind, Result};
+
+use super::wait::{block_on, WaitList};
+
+/// Creates a byte ring holding at least `capacity` bytes
+///
+/// The capacity is rounded up to a power of two. Each side is owned by one
+/// thread; reads and writes never take a lock, and waiting (blocking or
+/// async) only happens when the ring is empty or full.
+///
+/// # Examples
+///
+/// ```
+/// use avila_buffer::sync::spsc_ring;
+/// use std::thread;
+///
+/// let (mut producer, mut consumer) = spsc_ring(64);
+/// let writer = thread::spawn(move || producer.write_all(&[7u8; 1000]).unwrap());
+///
+/// let mut received = Vec::new();
+/// let mut chunk = [0u8; 128];
+/// loop {
+///     match consumer.read(&mut chunk) {
+///         0 => break, // producer dropped and ring drained
+///         n => received.extend_from_slice(&chunk[..n]),
+///     }
+/// }
+/// writer.join().unwrap();
+/// assert_eq!(received, vec![7u8; 1000]);
+/// ```
+pub fn spsc_ring(capacity: usize) -> (RingProducer, RingConsumer) {
+    let capacity = capacity.max(1).next_power_of_two();
+    let shared = Arc::new(Shared {
+        buf: (0..capacity).map(|_| UnsafeCell::new(0)).collect(),
+        mask: capacity - 1,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        producer_alive: AtomicBool::new(true),
+        consumer_alive: AtomicBool::new(true),
+        readers: WaitList::default(),
+        writers: WaitList::default(),
+    });
+    (
+        RingProducer {
+            shared: Arc::clone(&shared),
+        },
+        RingConsumer { shared },
+    )
+}
+
+struct Shared {
+    buf: Box<[UnsafeCell<u8>]>,
+    mask: usize,
+    /// Next byte to read; only the consumer stores it
+    head: AtomicUsize,
+    /// Next byte to write; only the producer stores it
+    tail: AtomicUsize,
+    producer_alive: AtomicBool,
+    consumer_alive: AtomicBool,
+    readers: WaitList,
+    writers: WaitList,
+}
+
+// SAFETY: the producer only touches bytes in [tail, head + capacity) and the
+// consumer only bytes in [head, tail); the release/acquire pairs on head and
+// tail hand each byte over before the other side can reach it.
+unsafe impl Sync for Shared {}
+
+impl Shared {
+    fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    fn base(&self) -> *mut u8 {
+        UnsafeCell::raw_get(self.buf.as_ptr())
+    }
+
+    /// Splits `len` bytes starting at logical `pos` into two contiguous runs
+    fn runs(&self, pos: usize, len: usize) -> (usize, usize) {
+        let start = pos & self.mask;
+        let first = len.min(self.capacity() - start);
+        (start, first)
+    }
+}
+
+/// Writing half of [`spsc_ring`]
+pub struct RingProducer {
+    shared: Arc<Shared>,
+}
+
+impl RingProducer {
+    /// Ring size in bytes
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Bytes that can be written without waiting
+    pub fn free_space(&self) -> usize {
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let head = self.shared.head.load(Ordering::Acquire);
+        self.capacity() - tail.wrapping_sub(head)
+    }
+
+    /// Checks if the consumer was dropped
+    pub fn is_closed(&self) -> bool {
+        !self.shared.consumer_alive.load(Ordering::Acquire)
+    }
+
+    /// Writes as many bytes as fit right now; never waits
+    pub fn try_write(&mut self, data: &[u8]) -> usize {
+        let n = data.len().min(self.free_space());
+        if n == 0 {
+            return 0;
+        }
+
+        let shared = &*self.shared;
+        let tail = shared.tail.load(Ordering::Relaxed);
+        let (start, first) = shared.runs(tail, n);
+        // SAFETY: [tail, tail + n) is free space owned by the producer
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), shared.base().add(start), first);
+            core::ptr::copy_nonoverlapping(data.as_ptr().add(first), shared.base(), n - first);
+        }
+        shared.tail.store(tail.wrapping_add(n), Ordering::Release);
+        shared.readers.wake_all();
+        n
+    }
+
+    /// Waits for free space, then writes what fits
+    ///
+    /// Fails once the consumer is dropped.
+    pub async fn write_async(&mut self, data: &[u8]) -> Result<usize> {
+        poll_fn(|cx| self.poll_write(cx, data)).await
+    }
+
+    /// Writes every byte, waiting asynchronously whenever the ring is full
+    pub async fn write_all_async(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            let n = self.write_async(data).await?;
+            data = &data[n..];
+        }
+        Ok(())
+    }
+
+    /// Blocks until there is free space, then writes what fits
+    pub fn write(&mut self, data: &[u8]) -> Result<usize> {
+        block_on(self.write_async(data))
+    }
+
+    /// Writes every byte, blocking whenever the ring is full
+    pub fn write_all(&mut self, data: &[u8]) -> Result<()> {
+        block_on(self.write_all_async(data))
+    }
+
+    fn poll_write(&mut self, cx: &mut Context<'_>, data: &[u8]) -> Poll<Result<usize>> {
+        if data.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        for attempt in 0..2 {
+            if self.is_closed() {
+                return Poll::Ready(Err(Error::new(ErrorKind::Io, "Ring consumer dropped")));
+            }
+            let n = self.try_write(data);
+            if n > 0 {
+                return Poll::Ready(Ok(n));
+            }
+            if attempt == 0 {
+                self.shared.writers.register(cx.waker());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for RingProducer {
+    fn drop(&mut self) {
+        self.shared.producer_alive.store(false, Ordering::Release);
+        self.shared.readers.wake_all();
+    }
+}
+
+impl std::io::Write for RingProducer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        RingProducer::write(self, buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e.to_string()))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reading half of [`spsc_ring`]
+pub struct RingConsumer {
+    shared: Arc<Shared>,
+}
+
+impl RingConsumer {
+    /// Ring size in bytes
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// Bytes that can be read without waiting
+    pub fn available(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        self.shared.tail.load(Ordering::Acquire).wrapping_sub(head)
+    }
+
+    /// Checks if the producer was dropped (buffered bytes stay readable)
+    pub fn is_closed(&self) -> bool {
+        !self.shared.producer_alive.load(Ordering::Acquire)
+    }
+
+    /// Reads whatever is buffered right now; never waits
+    pub fn try_read(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.available());
+        if n == 0 {
+            return 0;
+        }
+
+        let shared = &*self.shared;
+        let head = shared.head.load(Ordering::Relaxed);
+        let (start, first) = shared.runs(head, n);
+        // SAFETY: [head, head + n) was published by the producer's release store
+        unsafe {
+            core::ptr::copy_nonoverlapping(shared.base().add(start), buf.as_mut_ptr(), first);
+            core::ptr::copy_nonoverlapping(shared.base(), buf.as_mut_ptr().add(first), n - first);
+        }
+        shared.head.store(head.wrapping_add(n), Ordering::Release);
+        shared.writers.wake_all();
+        n
+    }
+
+    /// Waits for data; `0` means the producer is gone and the ring is drained
+    pub async fn read_async(&mut self, buf: &mut [u8]) -> usize {
+        poll_fn(|cx| self.poll_read(cx, buf)).await
+    }
+
+    /// Blocks until data arrives; `0` means the producer is gone and the ring is drained
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        block_on(self.read_async(buf))
+    }
+
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize> {
+        if buf.is_empty() {
+            return Poll::Ready(0);
+        }
+        for attempt in 0..2 {
+            // Check for closure first: bytes written before the drop are
+            // visible once it is observed
+            let closed = self.is_closed();
+            let n = self.try_read(buf);
+            if n > 0 || closed {
+                return Poll::Ready(n);
+            }
+            if attempt == 0 {
+                self.shared.readers.register(cx.waker());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for RingConsumer {
+    fn drop(&mut self) {
+        self.shared.consumer_alive.store(false, Ordering::Release);
+        self.shared.writers.wake_all();
+    }
+}
+
+impl std::io::Read for RingConsumer {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(RingConsumer::read(self, buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_try_ops_wrap_around() {
+        let (mut producer, mut consumer) = spsc_ring(6);
+        assert_eq!(producer.capacity(), 8);
+        assert_eq!(producer.try_write(b"abcdef"), 6);
+        assert_eq!(producer.try_write(b"ghijk"), 2);
+
+        let mut out = [0u8; 5];
+        assert_eq!(consumer.try_read(&mut out), 5);
+        assert_eq!(&out, b"abcde");
+        // Wraps around the end of the storage
+        assert_eq!(producer.try_write(b"12345"), 5);
+        let mut rest = [0u8; 16];
+        assert_eq!(consumer.try_read(&mut rest), 8);
+        assert_eq!(&rest[..8], b"fgh12345");
+        assert_eq!(consumer.try_read(&mut rest), 0);
+
+        drop(consumer);
+        assert!(producer.is_closed());
+        assert!(producer.write(b"x").is_err());
+    }
+
+    #[test]
+    fn test_streaming_between_threads() {
+        let (mut producer, mut consumer) = spsc_ring(64);
+        let payload: Vec<u8> = (0..100_000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let expected = payload.clone();
+
+        let writer = thread::spawn(move || {
+            for chunk in payload.chunks(97) {
+                producer.write_all(chunk).unwrap();
+            }
+        });
+
+        let mut received = Vec::with_capacity(expected.len());
+        let mut buf = [0u8; 50];
+        loop {
+            let n = block_on(consumer.read_async(&mut buf));
+            if n == 0 {
+                break;
+            }
+            received.extend_from_slice(&buf[..n]);
+        }
+        writer.join().unwrap();
+        assert_eq!(received, expected);
+    }
+}
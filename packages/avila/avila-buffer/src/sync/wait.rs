@@ -0,0 +1,79 @@
+//! Parking support shared by the lock-free rings
+//!
+//! Blocking and async waits go through the same path: an async operation is
+//! polled with a waker, and the blocking variant drives it with a waker that
+//! unparks the calling thread.
+
+use core::future::Future;
+use core::pin::pin;
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+use core::task::{Context, Poll, Waker};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::Wake;
+use std::thread::{self, Thread};
+
+/// Wakers parked on one side of a queue
+///
+/// The fast path only reads an atomic counter; the mutex is touched when
+/// someone is actually waiting.
+#[derive(Debug, Default)]
+pub(crate) struct WaitList {
+    waiting: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl WaitList {
+    /// Registers `waker`; the caller must re-check its condition afterwards
+    pub(crate) fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        self.waiting.store(wakers.len(), Ordering::SeqCst);
+        drop(wakers);
+        fence(Ordering::SeqCst);
+    }
+
+    /// Wakes every registered waiter
+    pub(crate) fn wake_all(&self) {
+        // Pairs with the fence in register(): either the waiter sees our
+        // state change on its re-check, or we see its registration here
+        fence(Ordering::SeqCst);
+        if self.waiting.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let wakers = {
+            let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+            self.waiting.store(0, Ordering::SeqCst);
+            core::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread, parking between polls
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
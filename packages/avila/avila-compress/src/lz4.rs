@@ -693,11 +693,8 @@ pub fn decompress(input: &[u8]) -> Result<Vec<u8>> {
             break;
         }
 
-        // If match_len from token is 0, there's no match to process
-        if match_len == 0 {
-            continue;
-        }
-
+        // Every sequence but the last carries a match; a low nibble of 0
+        // means a match of MIN_MATCH bytes, not "no match"
         // Read match offset
         if pos + 2 > input.len() {
             return Err(Error::CorruptedData(
@@ -877,4 +874,18 @@ mod tests {
             assert_eq!(data, decompressed);
         }
     }
+
+    #[test]
+    fn test_min_length_match() {
+        // 4-byte matches are encoded with a zero match nibble
+        let data = b"abcdXXXXXXXXabcdYYYYYYYYYYYYYYYY";
+        let compressed = compress(data).unwrap();
+        assert_eq!(decompress(&compressed).unwrap(), data);
+
+        let data: Vec<u8> = (0..5000u32).flat_map(|i| (i % 97).to_le_bytes()).collect();
+        for level in [Level::Fast, Level::Balanced, Level::Best] {
+            let compressed = compress_with_level(&data, level).unwrap();
+            assert_eq!(decompress(&compressed).unwrap(), data);
+        }
+    }
 }
@@ -53,6 +53,26 @@ impl Coordinator {
         }
     }
 
+    /// Cancels a pending or running task; finished tasks yield `InvalidTransition`
+    pub fn cancel(&mut self, id: u64) -> Result<(), TaskError> {
+        let task_id = TaskId::new(id);
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id) {
+            task.cancel()
+        } else {
+            Err(TaskError::NotFound)
+        }
+    }
+
+    /// Moves a failed task back to pending; other states yield `InvalidTransition`
+    pub fn retry(&mut self, id: u64) -> Result<(), TaskError> {
+        let task_id = TaskId::new(id);
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id) {
+            task.retry()
+        } else {
+            Err(TaskError::NotFound)
+        }
+    }
+
     pub fn get_task(&self, id: u64) -> Option<&Task> {
         let task_id = TaskId::new(id);
         self.tasks.iter().find(|t| t.id == task_id)
@@ -0,0 +1,223 @@
+//! # Cron - Recurring schedules
+//!
+//! `CronSchedule` parses the classic five cron fields
+//! (`minute hour day-of-month month day-of-week`) with `*`, lists, ranges
+//! and steps, plus the `@hourly`/`@daily`/`@weekly`/`@monthly`/`@yearly`
+//! shorthands. Times are `Timestamp`s (milliseconds since the Unix epoch)
+//! and are evaluated in UTC.
+//!
+//! # Example
+//! ```ignore
+//! let nightly = CronSchedule::parse("30 2 * * *")?; // 02:30 every day
+//! let next = nightly.next_after(now);
+//! ```
+use core::str::FromStr;
+use crate::metrics::{Duration, Timestamp};
+
+const MS_PER_MINUTE: u64 = 60_000;
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+/// How far ahead `next_after` looks before giving up (e.g. `0 0 30 2 *`)
+const SEARCH_YEARS: i64 = 5;
+
+/// Error types for cron expressions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CronError {
+    /// Not exactly five fields
+    FieldCount,
+    /// A field has a bad value, range or step
+    InvalidField { field: &'static str },
+}
+
+impl CronError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            CronError::FieldCount => "Cron expression needs 5 fields",
+            CronError::InvalidField { .. } => "Invalid cron field",
+        }
+    }
+}
+
+/// Parsed cron expression, one bit per allowed value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    /// `*` in day-of-month / day-of-week (cron ORs the two only when both are restricted)
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: [&str; 5] = {
+            let mut parts = expression.split_whitespace();
+            let fields = [parts.next(), parts.next(), parts.next(), parts.next(), parts.next()];
+            if parts.next().is_some() || fields.iter().any(Option::is_none) {
+                return Err(CronError::FieldCount);
+            }
+            fields.map(Option::unwrap)
+        };
+
+        let minutes = parse_field(fields[0], 0, 59, "minute")?;
+        let hours = parse_field(fields[1], 0, 23, "hour")?;
+        let days = parse_field(fields[2], 1, 31, "day of month")?;
+        let months = parse_field(fields[3], 1, 12, "month")?;
+        // 7 is Sunday too
+        let weekdays = parse_field(fields[4], 0, 7, "day of week")?;
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days: days as u32,
+            months: months as u16,
+            weekdays: weekdays as u8,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: Timestamp) -> Option<Timestamp> {
+        let mut minute = after.0 / MS_PER_MINUTE + 1;
+        let limit = days_from_civil(civil_from_days((minute / MINUTES_PER_DAY) as i64).0 + SEARCH_YEARS, 1, 1);
+
+        loop {
+            let day = (minute / MINUTES_PER_DAY) as i64;
+            if day >= limit {
+                return None;
+            }
+            let (year, month, dom) = civil_from_days(day);
+            if !bit(self.months as u64, month) {
+                let (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+                minute = days_from_civil(year, month, 1) as u64 * MINUTES_PER_DAY;
+                continue;
+            }
+            if !self.matches_day(dom, weekday(day)) {
+                minute = (day as u64 + 1) * MINUTES_PER_DAY;
+                continue;
+            }
+            let of_day = minute % MINUTES_PER_DAY;
+            if !bit(self.hours as u64, (of_day / 60) as u32) {
+                minute = minute - of_day % 60 + 60;
+                continue;
+            }
+            if !bit(self.minutes, (of_day % 60) as u32) {
+                minute += 1;
+                continue;
+            }
+            return Some(Timestamp(minute * MS_PER_MINUTE));
+        }
+    }
+
+    fn matches_day(&self, dom: u32, weekday: u32) -> bool {
+        let by_day = bit(self.days as u64, dom);
+        let by_weekday = bit(self.weekdays as u64, weekday);
+        match (self.any_day, self.any_weekday) {
+            (false, false) => by_day || by_weekday,
+            _ => by_day && by_weekday,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// When a recurring task fires again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    /// Fixed interval from the previous firing
+    Every(Duration),
+    Cron(CronSchedule),
+}
+
+impl Recurrence {
+    pub fn next_after(&self, after: Timestamp) -> Option<Timestamp> {
+        match self {
+            Recurrence::Every(interval) if interval.0 > 0 => after.0.checked_add(interval.0).map(Timestamp),
+            Recurrence::Every(_) => None,
+            Recurrence::Cron(schedule) => schedule.next_after(after),
+        }
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// `*`, `5`, `1-5`, `*/15`, `10-40/10` and comma-separated lists of those
+fn parse_field(field: &str, min: u32, max: u32, name: &'static str) -> Result<u64, CronError> {
+    let invalid = CronError::InvalidField { field: name };
+    let number = |text: &str| text.parse::<u32>().ok().filter(|n| (min..=max).contains(n)).ok_or(invalid);
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|&s| s > 0).ok_or(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (number(start)?, number(end)?),
+                // `5/10` means from 5 to the maximum
+                None if step > 1 => (number(range)?, max),
+                None => (number(range)?, number(range)?),
+            },
+        };
+        if start > end {
+            return Err(invalid);
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+/// Day of week for days since 1970-01-01 (a Thursday), Sunday = 0
+fn weekday(days: i64) -> u32 {
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// (year, month, day) for days since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 for a civil date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -0,0 +1,471 @@
+//! # Executor - Runs task futures on a worker pool
+//!
+//! Turns the coordinator into a job runner: `spawn` hands over a future,
+//! the pool runs it once its dependencies completed, picking among ready
+//! tasks with a `Scheduler` (priority order by default), retrying failures
+//! with the task's `RetryPolicy` and publishing `TaskEvent`s.
+//!
+//! Each worker drives one future at a time to completion, so a task that
+//! blocks holds its worker; size the pool for the expected concurrency.
+//!
+//! ```ignore
+//! let executor = Executor::new(4);
+//! let parse = executor.spawn(async move { parse_ifc(&path) });
+//! let export = executor.spawn_with(
+//!     SpawnOptions::new().after(parse).with_retry(RetryPolicy::new(2)),
+//!     move || export_glb(out.clone()),
+//! )?;
+//! executor.wait();
+//! ```
+extern crate alloc;
+extern crate std;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::future::Future;
+use core::pin::{pin, Pin};
+use core::task::{Context, Poll, Waker};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::Wake;
+use std::thread::{self, JoinHandle, Thread};
+use std::time::{Duration, Instant};
+
+use crate::coordinator::Coordinator;
+use crate::dependencies::DependencyGraph;
+use crate::events::{EventHandler, TaskEvent};
+use crate::metrics::{MetricsCollector, TaskMetrics, Timestamp};
+use crate::priority::Priority;
+use crate::retry::RetryPolicy;
+use crate::scheduler::{PriorityScheduler, Scheduler};
+use crate::task::{Task, TaskState};
+use crate::types::{TaskError, TaskId};
+
+/// Boxed future produced for each attempt of a task
+pub type TaskFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+type Factory = Box<dyn FnMut() -> TaskFuture + Send>;
+
+/// Scheduling options for a spawned task
+#[derive(Clone, Debug, Default)]
+pub struct SpawnOptions {
+    pub priority: Priority,
+    pub depends_on: Vec<TaskId>,
+    pub retry: Option<RetryPolicy>,
+}
+
+impl SpawnOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Runs only after `task` completed; fails if `task` fails
+    pub fn after(mut self, task: TaskId) -> Self {
+        self.depends_on.push(task);
+        self
+    }
+
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+}
+
+/// Worker pool executing spawned tasks
+pub struct Executor {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when a task may have become runnable
+    work: Condvar,
+    /// Signalled when a task reached a terminal state
+    done: Condvar,
+}
+
+struct State {
+    coordinator: Coordinator,
+    graph: DependencyGraph,
+    scheduler: Box<dyn Scheduler + Send>,
+    metrics: MetricsCollector,
+    /// Origin of the timestamps recorded in `metrics`
+    epoch: Instant,
+    handlers: Vec<Box<dyn EventHandler + Send>>,
+    jobs: BTreeMap<TaskId, Job>,
+    completed: Vec<TaskId>,
+    next_id: u64,
+    shutdown: bool,
+}
+
+struct Job {
+    /// Taken by the worker while an attempt runs
+    factory: Option<Factory>,
+    depends_on: Vec<TaskId>,
+    retry: Option<RetryPolicy>,
+    not_before: Option<Instant>,
+    error: Option<String>,
+    resolved: bool,
+}
+
+impl Executor {
+    /// Pool with `workers` threads and priority scheduling
+    pub fn new(workers: usize) -> Self {
+        Self::with_scheduler(workers, Box::new(PriorityScheduler))
+    }
+
+    /// Pool choosing among ready tasks with `scheduler`
+    pub fn with_scheduler(workers: usize, scheduler: Box<dyn Scheduler + Send>) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                coordinator: Coordinator::new(),
+                graph: DependencyGraph::new(),
+                scheduler,
+                metrics: MetricsCollector::new(),
+                epoch: Instant::now(),
+                handlers: Vec::new(),
+                jobs: BTreeMap::new(),
+                completed: Vec::new(),
+                next_id: 1,
+                shutdown: false,
+            }),
+            work: Condvar::new(),
+            done: Condvar::new(),
+        });
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || worker_loop(&shared))
+            })
+            .collect();
+        Self { shared, workers }
+    }
+
+    pub fn subscribe(&self, handler: Box<dyn EventHandler + Send>) {
+        self.shared.lock().handlers.push(handler);
+    }
+
+    /// Runs `future` once, with default options
+    pub fn spawn<F, E>(&self, future: F) -> TaskId
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let mut future = Some(future);
+        let factory = move || future.take().expect("task future polled twice");
+        self.spawn_with(SpawnOptions::new(), factory)
+            .expect("default options have no dependencies")
+    }
+
+    /// Runs a future made by `factory`, calling it again for every retry
+    ///
+    /// Dependencies must be tasks spawned on this executor, which also rules
+    /// out cycles.
+    pub fn spawn_with<M, F, E>(&self, options: SpawnOptions, mut factory: M) -> Result<TaskId, TaskError>
+    where
+        M: FnMut() -> F + Send + 'static,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let mut state = self.shared.lock();
+        if options.depends_on.iter().any(|dep| !state.jobs.contains_key(dep)) {
+            return Err(TaskError::NotFound);
+        }
+
+        let id = TaskId::new(state.next_id);
+        state.next_id += 1;
+        state.coordinator.submit_with_priority(id.as_u64(), options.priority);
+        for dep in &options.depends_on {
+            state.graph.add_dependency(id, *dep);
+        }
+        let factory: Factory = Box::new(move || {
+            let future = factory();
+            Box::pin(async move { future.await.map_err(|e| e.to_string()) })
+        });
+        state.jobs.insert(id, Job {
+            factory: Some(factory),
+            resolved: options.depends_on.is_empty(),
+            depends_on: options.depends_on,
+            retry: options.retry,
+            not_before: None,
+            error: None,
+        });
+        state.publish(&TaskEvent::Submitted(id));
+        drop(state);
+
+        self.shared.work.notify_all();
+        Ok(id)
+    }
+
+    /// Cancels a task that has not started; its dependents fail
+    pub fn cancel(&self, id: TaskId) -> Result<(), TaskError> {
+        let mut state = self.shared.lock();
+        match state.coordinator.get_task(id.as_u64()).map(|t| t.state) {
+            None => return Err(TaskError::NotFound),
+            Some(TaskState::Running) => return Err(TaskError::InvalidState),
+            Some(_) => state.coordinator.cancel(id.as_u64())?,
+        }
+        drop(state);
+
+        self.shared.work.notify_all();
+        self.shared.done.notify_all();
+        Ok(())
+    }
+
+    pub fn state(&self, id: TaskId) -> Option<TaskState> {
+        self.shared.lock().coordinator.get_task(id.as_u64()).map(|t| t.state)
+    }
+
+    /// Error of the last failed attempt
+    pub fn error(&self, id: TaskId) -> Option<String> {
+        self.shared.lock().jobs.get(&id).and_then(|job| job.error.clone())
+    }
+
+    /// Execution metrics of `id`; timestamps are milliseconds since the pool started
+    pub fn metrics(&self, id: TaskId) -> Option<TaskMetrics> {
+        self.shared.lock().metrics.get(id).cloned()
+    }
+
+    /// Pushes the pool's scheduler metrics into `monitor` at time `now`
+    #[cfg(feature = "avila-metrics")]
+    pub fn export_metrics(
+        &self,
+        exporter: &mut crate::monitor::MonitorExporter,
+        monitor: &mut avila_monitor::Monitor,
+        now: crate::metrics::Timestamp,
+    ) {
+        let state = self.shared.lock();
+        exporter.export(monitor, &state.coordinator, &state.metrics, now);
+    }
+
+    /// Blocks until `id` reaches a terminal state
+    pub fn wait_for(&self, id: TaskId) -> Result<TaskState, TaskError> {
+        let mut state = self.shared.lock();
+        loop {
+            match state.coordinator.get_task(id.as_u64()).map(|t| t.state) {
+                None => return Err(TaskError::NotFound),
+                Some(task_state) if task_state.is_terminal() => return Ok(task_state),
+                Some(_) => state = self.shared.wait(&self.shared.done, state),
+            }
+        }
+    }
+
+    /// Blocks until every spawned task reached a terminal state
+    pub fn wait(&self) {
+        let mut state = self.shared.lock();
+        while state.coordinator.iter().any(|t| !t.state.is_terminal()) {
+            state = self.shared.wait(&self.shared.done, state);
+        }
+    }
+
+    /// Waits for all tasks, then stops the workers
+    pub fn shutdown(self) {
+        self.wait();
+    }
+}
+
+impl Drop for Executor {
+    /// Stops the workers after their current attempt; queued tasks stay pending
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.work.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait<'a>(&self, condvar: &Condvar, guard: MutexGuard<'a, State>) -> MutexGuard<'a, State> {
+        condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn publish(&mut self, event: &TaskEvent) {
+        for handler in &mut self.handlers {
+            handler.on_event(event);
+        }
+    }
+
+    fn task_state(&self, id: TaskId) -> Option<TaskState> {
+        self.coordinator.get_task(id.as_u64()).map(|t| t.state)
+    }
+
+    /// Fails pending tasks whose dependencies failed or were cancelled
+    fn fail_blocked(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            let blocked: Vec<(TaskId, TaskId)> = self.jobs.iter()
+                .filter(|(id, _)| self.task_state(**id) == Some(TaskState::Pending))
+                .filter_map(|(id, job)| {
+                    job.depends_on.iter()
+                        .find(|dep| matches!(self.task_state(**dep), Some(TaskState::Failed | TaskState::Cancelled)))
+                        .map(|dep| (*id, *dep))
+                })
+                .collect();
+            if blocked.is_empty() {
+                return changed;
+            }
+            for (id, dep) in blocked {
+                // Pending -> Failed skips Running on purpose: the task never ran
+                if let Some(task) = self.coordinator.iter_mut().find(|t| t.id == id) {
+                    task.fail();
+                }
+                if let Some(job) = self.jobs.get_mut(&id) {
+                    job.error = Some(format!("dependency {} did not complete", dep.as_u64()));
+                }
+                self.publish(&TaskEvent::Failed(id));
+            }
+            changed = true;
+        }
+    }
+
+    fn clock(&self) -> Timestamp {
+        Timestamp(self.epoch.elapsed().as_millis() as u64)
+    }
+
+    /// Next task to run, or the earliest instant a delayed retry becomes due
+    fn next_ready(&mut self, now: Instant) -> Result<TaskId, Option<Instant>> {
+        let mut ready: Vec<Task> = Vec::new();
+        let mut resolved = Vec::new();
+        let mut due: Option<Instant> = None;
+        for task in self.coordinator.iter().filter(|t| t.state == TaskState::Pending) {
+            let Some(job) = self.jobs.get(&task.id) else { continue };
+            if job.factory.is_none() || !self.graph.can_execute(task.id, &self.completed) {
+                continue;
+            }
+            if !job.resolved {
+                resolved.push(task.id);
+            }
+            match job.not_before {
+                Some(at) if at > now => due = Some(due.map_or(at, |d| d.min(at))),
+                _ => ready.push(task.clone()),
+            }
+        }
+
+        for id in resolved {
+            if let Some(job) = self.jobs.get_mut(&id) {
+                job.resolved = true;
+            }
+            self.publish(&TaskEvent::DependencyResolved(id));
+        }
+        self.scheduler.next_task(&ready).ok_or(due)
+    }
+
+    /// Records the outcome of an attempt; returns true once the task is terminal
+    fn finish_attempt(&mut self, id: TaskId, result: Result<(), String>, factory: Factory) -> bool {
+        let now = self.clock();
+        let job = self.jobs.get_mut(&id).expect("running task has a job");
+        job.factory = Some(factory);
+
+        let error = match result {
+            Ok(()) => {
+                job.error = None;
+                self.metrics.get_or_create(id).record_success_at(now);
+                let _ = self.coordinator.complete(id.as_u64());
+                self.completed.push(id);
+                self.publish(&TaskEvent::Completed(id));
+                return true;
+            }
+            Err(error) => error,
+        };
+
+        self.metrics.get_or_create(id).record_failure_at(now);
+        let _ = self.coordinator.fail(id.as_u64());
+        job.error = Some(error);
+        match job.retry.as_mut().filter(|policy| policy.can_retry()) {
+            Some(policy) => {
+                let delay = policy.calculate_delay();
+                policy.increment();
+                let attempt = policy.current_attempt;
+                job.not_before = Some(Instant::now() + Duration::from_millis(delay));
+                let _ = self.coordinator.retry(id.as_u64());
+                self.publish(&TaskEvent::Retrying(id, attempt));
+                false
+            }
+            None => {
+                self.publish(&TaskEvent::Failed(id));
+                true
+            }
+        }
+    }
+}
+
+fn worker_loop(shared: &Shared) {
+    loop {
+        let (id, mut factory) = {
+            let mut state = shared.lock();
+            loop {
+                if state.shutdown {
+                    return;
+                }
+                if state.fail_blocked() {
+                    shared.done.notify_all();
+                }
+                match state.next_ready(Instant::now()) {
+                    Ok(id) => {
+                        let factory = state.jobs.get_mut(&id).and_then(|job| job.factory.take());
+                        let factory = factory.expect("ready task has a factory");
+                        let _ = state.coordinator.start(id.as_u64());
+                        let now = state.clock();
+                        state.metrics.get_or_create(id).record_attempt_at(now);
+                        state.publish(&TaskEvent::Started(id));
+                        break (id, factory);
+                    }
+                    Err(Some(due)) => {
+                        let timeout = due.saturating_duration_since(Instant::now());
+                        state = shared.work.wait_timeout(state, timeout)
+                            .unwrap_or_else(PoisonError::into_inner).0;
+                    }
+                    Err(None) => state = shared.wait(&shared.work, state),
+                }
+            }
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| block_on(factory())))
+            .unwrap_or_else(|_| Err("task panicked".to_string()));
+
+        let terminal = shared.lock().finish_attempt(id, result, factory);
+        if terminal {
+            shared.done.notify_all();
+        }
+        shared.work.notify_all();
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on the current thread, parking between wake-ups
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
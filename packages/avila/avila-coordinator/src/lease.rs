@@ -0,0 +1,400 @@
+//! # Lease - Work queue for remote workers
+//!
+//! `LeaseQueue` hands pending tasks to workers that pull them (e.g. over
+//! HTTP) instead of running them in-process. Every delivery is a `Lease`
+//! with a visibility timeout: the worker must heartbeat before it expires,
+//! otherwise the task goes back to `Pending` and another worker picks it up.
+//!
+//! Each lease has its own id, which doubles as a fencing token. Only the
+//! current holder can complete a task, so a worker whose lease expired
+//! (a stalled process, a network partition) cannot complete it a second
+//! time after it was handed to someone else. Acknowledging the same lease
+//! again with the same outcome is accepted as a duplicate, which makes
+//! client retries safe. Jobs must still be idempotent: an expired lease
+//! means the work may run twice, but the task completes only once.
+//!
+//! Like `TimedScheduler`, the queue does not read a clock; every call takes
+//! the current time.
+//!
+//! # Example
+//! ```ignore
+//! let mut queue = LeaseQueue::new(Duration(30_000));
+//! let leases = queue.lease(&mut coordinator, "worker-1", 4, Timestamp(now_ms));
+//! // while working
+//! queue.heartbeat(leases[0].id, Timestamp(now_ms))?;
+//! // when done
+//! queue.ack(&mut coordinator, leases[0].id, LeaseOutcome::Completed, Timestamp(now_ms))?;
+//! ```
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::coordinator::Coordinator;
+use crate::dependencies::DependencyGraph;
+use crate::metrics::{Duration, Timestamp};
+use crate::scheduler::{PriorityScheduler, Scheduler};
+use crate::task::{Task, TaskState};
+use crate::types::{TaskError, TaskId};
+use crate::validation::StateValidator;
+
+/// Acknowledged leases remembered for duplicate detection
+const ACK_HISTORY: usize = 4096;
+
+/// Identifier of a single delivery; never reused
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct LeaseId(pub u64);
+
+/// A task handed to a worker until `expires_at`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lease {
+    pub id: LeaseId,
+    pub task: TaskId,
+    pub worker: String,
+    /// 1 on the first delivery of the task
+    pub attempt: u32,
+    pub expires_at: Timestamp,
+}
+
+/// How a worker finished a leased task
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaseOutcome {
+    Completed,
+    Failed,
+    /// Hand the task back untouched (e.g. the worker is shutting down)
+    Released,
+}
+
+/// Result of a successful `ack`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AckStatus {
+    Applied,
+    /// The lease was already acknowledged with the same outcome
+    Duplicate,
+}
+
+/// Error types for lease operations
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LeaseError {
+    /// No lease with this id was ever granted
+    Unknown,
+    /// The visibility timeout passed; the task may belong to another worker
+    Expired,
+    /// The lease was already acknowledged with a different outcome
+    Conflict,
+    /// The task changed under the lease (e.g. it was cancelled)
+    Task(TaskError),
+}
+
+impl LeaseError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            LeaseError::Unknown => "Unknown lease",
+            LeaseError::Expired => "Lease expired",
+            LeaseError::Conflict => "Lease already acknowledged with another outcome",
+            LeaseError::Task(err) => err.message(),
+        }
+    }
+}
+
+impl From<TaskError> for LeaseError {
+    fn from(err: TaskError) -> Self {
+        LeaseError::Task(err)
+    }
+}
+
+/// Pull-based work queue with visibility timeouts
+///
+/// Workers call `lease` to take up to `max` ready tasks: pending tasks whose
+/// dependencies completed, in the order chosen by the wrapped scheduler.
+/// Expired leases are reaped at the start of every `lease` and `ack`, or
+/// explicitly with `expire`.
+pub struct LeaseQueue<S: Scheduler = PriorityScheduler> {
+    inner: S,
+    visibility: Duration,
+    max_deliveries: Option<u32>,
+    next_lease: u64,
+    active: BTreeMap<LeaseId, Lease>,
+    by_task: BTreeMap<TaskId, LeaseId>,
+    deliveries: BTreeMap<TaskId, u32>,
+    acked: BTreeMap<LeaseId, (TaskId, LeaseOutcome)>,
+    dependencies: DependencyGraph,
+}
+
+impl LeaseQueue {
+    /// Create a queue over `PriorityScheduler` whose leases last `visibility`
+    pub fn new(visibility: Duration) -> Self {
+        Self::with_scheduler(PriorityScheduler, visibility)
+    }
+}
+
+impl<S: Scheduler> LeaseQueue<S> {
+    /// Create a queue choosing among ready tasks with `inner`
+    pub fn with_scheduler(inner: S, visibility: Duration) -> Self {
+        Self {
+            inner,
+            visibility,
+            max_deliveries: None,
+            next_lease: 1,
+            active: BTreeMap::new(),
+            by_task: BTreeMap::new(),
+            deliveries: BTreeMap::new(),
+            acked: BTreeMap::new(),
+            dependencies: DependencyGraph::new(),
+        }
+    }
+
+    /// Fail a task instead of requeueing it once `max` leases on it expired
+    ///
+    /// Guards the queue against a job that crashes every worker it lands on.
+    pub fn with_max_deliveries(mut self, max: u32) -> Self {
+        self.max_deliveries = Some(max);
+        self
+    }
+
+    /// Hand out lease ids from `first` on, past the ones granted before a
+    /// restart (see `PersistentCoordinator::lease_queue`)
+    pub fn starting_at(mut self, first: LeaseId) -> Self {
+        self.next_lease = self.next_lease.max(first.0);
+        self
+    }
+
+    pub fn visibility(&self) -> Duration {
+        self.visibility
+    }
+
+    /// Only lease `task` after `depends_on` completed
+    pub fn add_dependency(&mut self, task: TaskId, depends_on: TaskId) -> Result<(), TaskError> {
+        if task == depends_on || self.depends_on(depends_on, task) {
+            return Err(TaskError::CircularDependency);
+        }
+        self.dependencies.add_dependency(task, depends_on);
+        Ok(())
+    }
+
+    /// Whether `task` transitively depends on `target`
+    fn depends_on(&self, task: TaskId, target: TaskId) -> bool {
+        let mut stack = alloc::vec![task];
+        let mut seen = Vec::new();
+        while let Some(current) = stack.pop() {
+            if current == target {
+                return true;
+            }
+            if seen.contains(&current) {
+                continue;
+            }
+            seen.push(current);
+            if let Some(deps) = self.dependencies.get_dependencies(current) {
+                stack.extend(deps.iter().copied());
+            }
+        }
+        false
+    }
+
+    /// Lease up to `max` ready tasks to `worker`
+    pub fn lease(&mut self, coordinator: &mut Coordinator, worker: &str, max: usize, now: Timestamp) -> Vec<Lease> {
+        self.expire(coordinator, now);
+
+        let mut granted = Vec::new();
+        while granted.len() < max {
+            let Some(task) = self.next_task(&coordinator.tasks) else {
+                break;
+            };
+            if coordinator.start(task.0).is_err() {
+                break;
+            }
+            let attempt = {
+                let count = self.deliveries.entry(task).or_insert(0);
+                *count += 1;
+                *count
+            };
+            let lease = Lease {
+                id: LeaseId(self.next_lease),
+                task,
+                worker: String::from(worker),
+                attempt,
+                expires_at: Timestamp(now.0.saturating_add(self.visibility.0)),
+            };
+            self.next_lease += 1;
+            self.by_task.insert(task, lease.id);
+            self.active.insert(lease.id, lease.clone());
+            granted.push(lease);
+        }
+        granted
+    }
+
+    /// Extend a live lease by the visibility timeout; returns the new expiry
+    pub fn heartbeat(&mut self, lease: LeaseId, now: Timestamp) -> Result<Timestamp, LeaseError> {
+        let visibility = self.visibility;
+        match self.active.get_mut(&lease) {
+            Some(active) if active.expires_at > now => {
+                active.expires_at = Timestamp(now.0.saturating_add(visibility.0));
+                Ok(active.expires_at)
+            }
+            // Reaped on the next `lease`, `ack` or `expire`
+            Some(_) => Err(LeaseError::Expired),
+            None => Err(self.gone(lease)),
+        }
+    }
+
+    /// Finish a leased task
+    ///
+    /// Only the current holder can move the task; acknowledging a finished
+    /// lease again with the same outcome returns `AckStatus::Duplicate`.
+    pub fn ack(
+        &mut self,
+        coordinator: &mut Coordinator,
+        lease: LeaseId,
+        outcome: LeaseOutcome,
+        now: Timestamp,
+    ) -> Result<AckStatus, LeaseError> {
+        if let Some(&(_, previous)) = self.acked.get(&lease) {
+            return if previous == outcome {
+                Ok(AckStatus::Duplicate)
+            } else {
+                Err(LeaseError::Conflict)
+            };
+        }
+        self.expire(coordinator, now);
+        let Some(active) = self.active.remove(&lease) else {
+            return Err(self.gone(lease));
+        };
+        self.by_task.remove(&active.task);
+
+        let task = coordinator
+            .iter_mut()
+            .find(|t| t.id == active.task)
+            .ok_or(TaskError::NotFound)?;
+        let target = target_state(outcome);
+        if task.state != TaskState::Running {
+            // Cancelled (or otherwise moved) while the worker held it
+            StateValidator::can_transition(task.state, target)?;
+            return Err(TaskError::InvalidState.into());
+        }
+        task.state = target;
+        if outcome == LeaseOutcome::Released {
+            // Handing a task back does not count as a delivery
+            if let Some(count) = self.deliveries.get_mut(&active.task) {
+                *count = count.saturating_sub(1);
+            }
+        }
+
+        self.acked.insert(lease, (active.task, outcome));
+        while self.acked.len() > ACK_HISTORY {
+            self.acked.pop_first();
+        }
+        Ok(AckStatus::Applied)
+    }
+
+    /// Hand back every task leased to `worker`; returns the released leases
+    pub fn release_worker(&mut self, coordinator: &mut Coordinator, worker: &str, now: Timestamp) -> Vec<Lease> {
+        let held: Vec<Lease> = self.active.values().filter(|l| l.worker == worker).cloned().collect();
+        held.into_iter()
+            .filter(|lease| self.ack(coordinator, lease.id, LeaseOutcome::Released, now).is_ok())
+            .collect()
+    }
+
+    /// Reap leases whose visibility timeout passed; returns them
+    ///
+    /// Their tasks go back to `Pending`, or to `Failed` once they reached
+    /// the delivery limit.
+    pub fn expire(&mut self, coordinator: &mut Coordinator, now: Timestamp) -> Vec<Lease> {
+        let expired: Vec<LeaseId> = self
+            .active
+            .values()
+            .filter(|lease| lease.expires_at <= now)
+            .map(|lease| lease.id)
+            .collect();
+
+        let mut reaped = Vec::with_capacity(expired.len());
+        for id in expired {
+            let Some(lease) = self.active.remove(&id) else {
+                continue;
+            };
+            self.by_task.remove(&lease.task);
+            let exhausted = self.max_deliveries.is_some_and(|max| lease.attempt >= max);
+            if let Some(task) = coordinator.iter_mut().find(|t| t.id == lease.task) {
+                if task.state == TaskState::Running {
+                    task.state = if exhausted { TaskState::Failed } else { TaskState::Pending };
+                }
+            }
+            reaped.push(lease);
+        }
+        reaped
+    }
+
+    /// Leases granted and neither acknowledged nor reaped yet
+    pub fn leases(&self) -> impl Iterator<Item = &Lease> {
+        self.active.values()
+    }
+
+    pub fn get(&self, lease: LeaseId) -> Option<&Lease> {
+        self.active.get(&lease)
+    }
+
+    /// Task and outcome of a recently acknowledged lease
+    pub fn acknowledged(&self, lease: LeaseId) -> Option<(TaskId, LeaseOutcome)> {
+        self.acked.get(&lease).copied()
+    }
+
+    /// Current lease on `task`, if a worker holds it
+    pub fn lease_of(&self, task: TaskId) -> Option<&Lease> {
+        self.by_task.get(&task).and_then(|id| self.active.get(id))
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// How many times `task` was leased (released leases not counted)
+    pub fn deliveries(&self, task: TaskId) -> u32 {
+        self.deliveries.get(&task).copied().unwrap_or(0)
+    }
+
+    /// Earliest expiry among the live leases
+    pub fn next_expiry(&self) -> Option<Timestamp> {
+        self.active.values().map(|lease| lease.expires_at).min()
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Error for a lease id that is not live: granted ids are below `next_lease`
+    fn gone(&self, lease: LeaseId) -> LeaseError {
+        if lease.0 > 0 && lease.0 < self.next_lease {
+            LeaseError::Expired
+        } else {
+            LeaseError::Unknown
+        }
+    }
+}
+
+impl<S: Scheduler> Scheduler for LeaseQueue<S> {
+    /// Picks among pending tasks whose dependencies all completed
+    fn next_task(&mut self, tasks: &[Task]) -> Option<TaskId> {
+        let completed: Vec<TaskId> = tasks
+            .iter()
+            .filter(|t| t.state == TaskState::Completed)
+            .map(|t| t.id)
+            .collect();
+        let ready: Vec<Task> = tasks
+            .iter()
+            .filter(|t| t.state == TaskState::Pending)
+            .filter(|t| self.dependencies.can_execute(t.id, &completed))
+            .cloned()
+            .collect();
+        self.inner.next_task(&ready)
+    }
+}
+
+pub(crate) fn target_state(outcome: LeaseOutcome) -> TaskState {
+    match outcome {
+        LeaseOutcome::Completed => TaskState::Completed,
+        LeaseOutcome::Failed => TaskState::Failed,
+        LeaseOutcome::Released => TaskState::Pending,
+    }
+}
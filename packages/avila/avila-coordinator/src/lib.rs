@@ -14,6 +14,7 @@ pub mod coordinator;
 pub mod priority;
 pub mod dependencies;
 pub mod scheduler;
+pub mod cron;
 pub mod metrics;
 pub mod events;
 pub mod retry;
@@ -22,7 +23,14 @@ pub mod concurrent;
 pub mod builder;
 pub mod workflow;
 pub mod resources;
+pub mod timeline;
 pub mod serde_support;
+pub mod store;
+pub mod lease;
+#[cfg(feature = "std")]
+pub mod executor;
+#[cfg(feature = "avila-metrics")]
+pub mod monitor;
 
 // Re-exports for convenience
 pub use types::{TaskId, TaskResult, TaskError};
@@ -30,7 +38,8 @@ pub use task::{Task, TaskState};
 pub use coordinator::Coordinator;
 pub use priority::Priority;
 pub use dependencies::{TaskDependency, DependencyGraph};
-pub use scheduler::{Scheduler, FifoScheduler, PriorityScheduler, FairScheduler, DeadlineScheduler, WeightedScheduler};
+pub use scheduler::{Scheduler, FifoScheduler, PriorityScheduler, FairScheduler, DeadlineScheduler, WeightedScheduler, TimedScheduler, ScheduleId};
+pub use cron::{CronSchedule, CronError, Recurrence};
 pub use metrics::{TaskMetrics, MetricsCollector};
 pub use events::{TaskEvent, EventHandler, EventBus};
 pub use retry::{RetryPolicy, TaskRetryInfo, BackoffStrategy, RetryManager};
@@ -38,7 +47,16 @@ pub use validation::{StateValidator, IdValidator, PreCondition, AlwaysValid};
 pub use builder::{CoordinatorBuilder, AdvancedCoordinator};
 pub use metrics::{Timestamp, Duration, ExecutionRecord};
 pub use workflow::{WorkflowNode, Workflow, WorkflowExecution};
+pub use timeline::{ExecutionEvent, ExecutionSnapshot};
 pub use resources::{ResourceId, Resource, ResourceState, ResourcePool, RateLimiter, QuotaManager};
+pub use store::{TaskStore, StoreRecord, StoreError, MemoryStore, PersistentCoordinator, RecoveryReport};
+pub use lease::{LeaseQueue, Lease, LeaseId, LeaseOutcome, AckStatus, LeaseError};
+#[cfg(feature = "std")]
+pub use store::wal::FileStore;
+#[cfg(feature = "std")]
+pub use executor::{Executor, SpawnOptions, TaskFuture};
+#[cfg(feature = "avila-metrics")]
+pub use monitor::{MonitorExporter, CoordinatorMetric};
 
 #[cfg(test)]
 mod tests {
@@ -52,6 +70,24 @@ mod tests {
         assert_eq!(coord.tasks[0].state, TaskState::Completed);
     }
 
+    #[test]
+    fn test_cancel() {
+        let mut coord = Coordinator::new();
+        coord.submit(1);
+        coord.submit(2);
+        coord.start(2).unwrap();
+        coord.complete(2).unwrap();
+
+        assert_eq!(coord.cancel(1), Ok(()));
+        assert_eq!(coord.get_task(1).unwrap().state, TaskState::Cancelled);
+        assert!(coord.get_task(1).unwrap().state.is_terminal());
+        assert_eq!(
+            coord.cancel(2),
+            Err(TaskError::InvalidTransition { from: "Completed", to: "Cancelled" })
+        );
+        assert_eq!(coord.cancel(3), Err(TaskError::NotFound));
+    }
+
     #[test]
     fn test_task_state_transitions() {
         let mut task = Task::new(TaskId::new(1));
@@ -130,7 +166,7 @@ mod tests {
         coord.submit_with_priority(2, Critical);
         coord.submit_with_priority(3, Normal);
 
-        let mut scheduler = PriorityScheduler::default();
+        let mut scheduler = PriorityScheduler;
         let next = scheduler.next_task(&coord.tasks);
         assert_eq!(next, Some(TaskId::new(2))); // Critical priority
     }
@@ -239,6 +275,52 @@ mod tests {
         assert_eq!(order[2], TaskId::new(3));
     }
 
+    #[test]
+    fn test_workflow_time_travel() {
+        use alloc::string::ToString;
+        use core::sync::atomic::{AtomicU64, Ordering};
+
+        static CLOCK: AtomicU64 = AtomicU64::new(0);
+        fn tick() -> Timestamp {
+            Timestamp(CLOCK.fetch_add(10, Ordering::SeqCst))
+        }
+
+        let mut workflow = Workflow::new("pipeline".to_string());
+        for id in 1..=3 {
+            workflow.add_node(WorkflowNode::new(TaskId::new(id))).unwrap();
+        }
+        workflow.add_edge(TaskId::new(1), TaskId::new(3)).unwrap();
+        workflow.add_edge(TaskId::new(2), TaskId::new(3)).unwrap();
+
+        let mut execution = WorkflowExecution::with_clock(workflow, tick);
+        assert_eq!(execution.next_batch().len(), 2);
+        execution.mark_completed(TaskId::new(1));
+        execution.mark_completed(TaskId::new(2));
+        assert_eq!(execution.next_batch(), alloc::vec![TaskId::new(3)]);
+        execution.mark_failed(TaskId::new(3));
+
+        let events = execution.events();
+        assert_eq!(events.len(), 6);
+        assert!(events.windows(2).all(|w| w[0].sequence + 1 == w[1].sequence));
+
+        let before_failure = execution.replay(5);
+        assert_eq!(before_failure.running, alloc::vec![TaskId::new(3)]);
+        assert_eq!(before_failure.completed.len(), 2);
+        assert!(before_failure.failed.is_empty());
+
+        let at = execution.replay_at(events[2].timestamp);
+        assert_eq!(at.applied_events, 3);
+        assert_eq!(at.completed, alloc::vec![TaskId::new(1)]);
+
+        let full = execution.replay(events.len());
+        assert!(full.is_complete());
+        assert_eq!(full.failed, alloc::vec![TaskId::new(3)]);
+
+        let json = execution.timeline_json();
+        assert!(json.starts_with("{\"workflow\":\"pipeline\",\"totalTasks\":3"));
+        assert!(json.contains("\"event\":\"failed\",\"task\":3"));
+    }
+
     #[test]
     fn test_resource_pool() {
         let mut pool = ResourcePool::new(5);
@@ -87,37 +87,51 @@ impl TaskMetrics {
     }
 
     pub fn record_attempt(&mut self) {
+        self.record_attempt_at(Timestamp::now());
+    }
+
+    pub fn record_success(&mut self) {
+        self.record_success_at(Timestamp::now());
+    }
+
+    pub fn record_failure(&mut self) {
+        self.record_failure_at(Timestamp::now());
+    }
+
+    /// Starts an execution at a caller-supplied time (`Timestamp::now` is a placeholder in no_std)
+    pub fn record_attempt_at(&mut self, now: Timestamp) {
         self.attempts += 1;
         if self.first_started_at.is_none() {
-            self.first_started_at = Some(Timestamp::now());
+            self.first_started_at = Some(now);
         }
-        self.executions.push(ExecutionRecord::new(Timestamp::now()));
+        self.executions.push(ExecutionRecord::new(now));
     }
 
-    pub fn record_success(&mut self) {
+    pub fn record_success_at(&mut self, now: Timestamp) {
         self.success_count += 1;
-        let now = Timestamp::now();
-        self.last_completed_at = Some(now);
-        if let Some(exec) = self.executions.last_mut() {
-            exec.complete(now, true);
-            if let Some(duration) = exec.duration {
-                self.total_duration = Duration(self.total_duration.0 + duration.0);
-            }
-        }
+        self.finish_execution(now, true);
     }
 
-    pub fn record_failure(&mut self) {
+    pub fn record_failure_at(&mut self, now: Timestamp) {
         self.failure_count += 1;
-        let now = Timestamp::now();
+        self.finish_execution(now, false);
+    }
+
+    fn finish_execution(&mut self, now: Timestamp, success: bool) {
         self.last_completed_at = Some(now);
         if let Some(exec) = self.executions.last_mut() {
-            exec.complete(now, false);
+            exec.complete(now, success);
             if let Some(duration) = exec.duration {
                 self.total_duration = Duration(self.total_duration.0 + duration.0);
             }
         }
     }
 
+    /// Attempts beyond the first one
+    pub fn retries(&self) -> u32 {
+        self.attempts.saturating_sub(1)
+    }
+
     pub fn average_duration(&self) -> Option<Duration> {
         let completed = self.success_count + self.failure_count;
         if completed > 0 {
@@ -165,6 +179,10 @@ impl MetricsCollector {
         self.metrics.iter().find(|m| m.task_id == task_id)
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &TaskMetrics> {
+        self.metrics.iter()
+    }
+
     pub fn total_tasks(&self) -> usize {
         self.metrics.len()
     }
@@ -173,6 +191,10 @@ impl MetricsCollector {
         self.metrics.iter().map(|m| m.attempts).sum()
     }
 
+    pub fn total_retries(&self) -> u32 {
+        self.metrics.iter().map(|m| m.retries()).sum()
+    }
+
     pub fn total_successes(&self) -> u32 {
         self.metrics.iter().map(|m| m.success_count).sum()
     }
@@ -0,0 +1,245 @@
+//! # Monitor - Scheduler health export into avila-monitor
+//!
+//! `MonitorExporter` turns coordinator state into `avila_monitor::Monitor`
+//! metrics: throughput, queue depth, per-priority latency percentiles,
+//! retries and resource-pool utilization. Every metric gets metadata
+//! (name with labels, unit, description) and is recorded with a timestamp,
+//! so history, alerts and percentiles of the monitor work on it as usual.
+//!
+//! Metric ids are `base + offset` (see [`CoordinatorMetric`]), which lets
+//! several coordinators share one monitor.
+//!
+//! ```ignore
+//! let mut exporter = MonitorExporter::new(1_000).with_pool("coordinator.pool{name=\"gpu\"}");
+//! exporter.export(&mut monitor, &coordinator, &collector, Timestamp(now_ms));
+//! exporter.export_pool(&mut monitor, "coordinator.pool{name=\"gpu\"}", &gpus, Timestamp(now_ms));
+//! let p95 = monitor.get(exporter.id(CoordinatorMetric::LatencyP95(Priority::High)));
+//! ```
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use avila_monitor::Monitor;
+
+use crate::coordinator::Coordinator;
+use crate::metrics::{MetricsCollector, Timestamp};
+use crate::priority::Priority;
+use crate::resources::ResourcePool;
+use crate::task::TaskState;
+use crate::types::TaskId;
+
+const PRIORITIES: [Priority; 4] = [Priority::Low, Priority::Normal, Priority::High, Priority::Critical];
+
+/// First offset used by resource pools, one id per registered pool
+const POOL_OFFSET: u64 = 32;
+
+/// Metrics exported for a coordinator, addressed relative to the exporter's base id
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoordinatorMetric {
+    /// Tasks finished (completed or failed) per second since the previous export
+    Throughput,
+    /// Pending tasks
+    QueueDepth,
+    /// Running tasks
+    Running,
+    /// Successful executions so far
+    Completed,
+    /// Failed executions so far
+    Failed,
+    /// Attempts beyond the first one, over all tasks
+    Retries,
+    /// Raw execution latencies (one history entry per finished execution)
+    Latency(Priority),
+    LatencyP50(Priority),
+    LatencyP95(Priority),
+    LatencyP99(Priority),
+}
+
+impl CoordinatorMetric {
+    pub fn offset(&self) -> u64 {
+        match self {
+            CoordinatorMetric::Throughput => 0,
+            CoordinatorMetric::QueueDepth => 1,
+            CoordinatorMetric::Running => 2,
+            CoordinatorMetric::Completed => 3,
+            CoordinatorMetric::Failed => 4,
+            CoordinatorMetric::Retries => 5,
+            CoordinatorMetric::Latency(p) => 8 + *p as u64 * 4,
+            CoordinatorMetric::LatencyP50(p) => 9 + *p as u64 * 4,
+            CoordinatorMetric::LatencyP95(p) => 10 + *p as u64 * 4,
+            CoordinatorMetric::LatencyP99(p) => 11 + *p as u64 * 4,
+        }
+    }
+
+    fn metadata(&self) -> (&'static str, &'static str, &'static str) {
+        const LATENCY: [[&str; 4]; 4] = [
+            [
+                "coordinator.latency{priority=\"low\"}",
+                "coordinator.latency.p50{priority=\"low\"}",
+                "coordinator.latency.p95{priority=\"low\"}",
+                "coordinator.latency.p99{priority=\"low\"}",
+            ],
+            [
+                "coordinator.latency{priority=\"normal\"}",
+                "coordinator.latency.p50{priority=\"normal\"}",
+                "coordinator.latency.p95{priority=\"normal\"}",
+                "coordinator.latency.p99{priority=\"normal\"}",
+            ],
+            [
+                "coordinator.latency{priority=\"high\"}",
+                "coordinator.latency.p50{priority=\"high\"}",
+                "coordinator.latency.p95{priority=\"high\"}",
+                "coordinator.latency.p99{priority=\"high\"}",
+            ],
+            [
+                "coordinator.latency{priority=\"critical\"}",
+                "coordinator.latency.p50{priority=\"critical\"}",
+                "coordinator.latency.p95{priority=\"critical\"}",
+                "coordinator.latency.p99{priority=\"critical\"}",
+            ],
+        ];
+        match self {
+            CoordinatorMetric::Throughput => ("coordinator.throughput", "tasks/s", "Tasks finished per second"),
+            CoordinatorMetric::QueueDepth => ("coordinator.queue_depth", "tasks", "Pending tasks"),
+            CoordinatorMetric::Running => ("coordinator.running", "tasks", "Running tasks"),
+            CoordinatorMetric::Completed => ("coordinator.completed_total", "executions", "Successful executions"),
+            CoordinatorMetric::Failed => ("coordinator.failed_total", "executions", "Failed executions"),
+            CoordinatorMetric::Retries => ("coordinator.retries_total", "attempts", "Retried attempts"),
+            CoordinatorMetric::Latency(p) => (LATENCY[*p as usize][0], "ms", "Execution latency"),
+            CoordinatorMetric::LatencyP50(p) => (LATENCY[*p as usize][1], "ms", "Execution latency, 50th percentile"),
+            CoordinatorMetric::LatencyP95(p) => (LATENCY[*p as usize][2], "ms", "Execution latency, 95th percentile"),
+            CoordinatorMetric::LatencyP99(p) => (LATENCY[*p as usize][3], "ms", "Execution latency, 99th percentile"),
+        }
+    }
+}
+
+/// Pushes coordinator metrics into an avila-monitor `Monitor`
+pub struct MonitorExporter {
+    base: u64,
+    pools: Vec<&'static str>,
+    /// Finished executions already exported, per task
+    exported: BTreeMap<TaskId, usize>,
+    /// Finished executions and time of the previous export, for throughput
+    last: Option<(u32, Timestamp)>,
+}
+
+impl MonitorExporter {
+    /// Exporter writing metric ids starting at `base`
+    pub fn new(base: u64) -> Self {
+        Self {
+            base,
+            pools: Vec::new(),
+            exported: BTreeMap::new(),
+            last: None,
+        }
+    }
+
+    /// Registers a resource pool; `label` becomes the metric name, e.g. `coordinator.pool{name="gpu"}`
+    pub fn with_pool(mut self, label: &'static str) -> Self {
+        if !self.pools.contains(&label) {
+            self.pools.push(label);
+        }
+        self
+    }
+
+    pub fn id(&self, metric: CoordinatorMetric) -> u64 {
+        self.base + metric.offset()
+    }
+
+    /// Metric id of a registered pool's utilization
+    pub fn pool_id(&self, label: &str) -> Option<u64> {
+        self.pools.iter()
+            .position(|&l| l == label)
+            .map(|i| self.base + POOL_OFFSET + i as u64)
+    }
+
+    /// Records metadata for every metric, including registered pools
+    pub fn describe(&self, monitor: &mut Monitor) {
+        let mut metrics = Vec::from([
+            CoordinatorMetric::Throughput,
+            CoordinatorMetric::QueueDepth,
+            CoordinatorMetric::Running,
+            CoordinatorMetric::Completed,
+            CoordinatorMetric::Failed,
+            CoordinatorMetric::Retries,
+        ]);
+        for p in PRIORITIES {
+            metrics.extend([
+                CoordinatorMetric::Latency(p),
+                CoordinatorMetric::LatencyP50(p),
+                CoordinatorMetric::LatencyP95(p),
+                CoordinatorMetric::LatencyP99(p),
+            ]);
+        }
+        for metric in metrics {
+            let (name, unit, description) = metric.metadata();
+            monitor.set_metadata(self.id(metric), name, unit, description);
+        }
+        for (i, label) in self.pools.iter().enumerate() {
+            monitor.set_metadata(self.base + POOL_OFFSET + i as u64, label, "ratio", "Resource pool utilization");
+        }
+    }
+
+    /// Exports queue, throughput, retry and latency metrics at time `now`
+    ///
+    /// Latency samples are the executions finished since the previous export;
+    /// a task's priority is looked up in `coordinator` (Normal if it's gone).
+    pub fn export(&mut self, monitor: &mut Monitor, coordinator: &Coordinator, metrics: &MetricsCollector, now: Timestamp) {
+        self.describe(monitor);
+        let ts = now.0;
+
+        let successes = metrics.total_successes();
+        let failures = metrics.total_failures();
+        let finished = successes + failures;
+        if let Some((previous, at)) = self.last {
+            let elapsed = now.elapsed_since(at).as_millis();
+            if elapsed > 0 {
+                let rate = finished.saturating_sub(previous) as f64 * 1000.0 / elapsed as f64;
+                monitor.record_with_timestamp(self.id(CoordinatorMetric::Throughput), rate, ts);
+            }
+        }
+        self.last = Some((finished, now));
+
+        let pending = coordinator.task_count_by_state(TaskState::Pending);
+        let running = coordinator.task_count_by_state(TaskState::Running);
+        monitor.record_with_timestamp(self.id(CoordinatorMetric::QueueDepth), pending as f64, ts);
+        monitor.record_with_timestamp(self.id(CoordinatorMetric::Running), running as f64, ts);
+        monitor.record_with_timestamp(self.id(CoordinatorMetric::Completed), successes as f64, ts);
+        monitor.record_with_timestamp(self.id(CoordinatorMetric::Failed), failures as f64, ts);
+        monitor.record_with_timestamp(self.id(CoordinatorMetric::Retries), metrics.total_retries() as f64, ts);
+
+        let mut sampled = [false; 4];
+        for task in metrics.iter() {
+            let seen = self.exported.entry(task.task_id).or_insert(0);
+            if *seen > task.executions.len() {
+                // Collector was cleared and the task recreated
+                *seen = 0;
+            }
+            let priority = coordinator.iter()
+                .find(|t| t.id == task.task_id)
+                .map_or(Priority::Normal, |t| t.priority);
+            let latency = self.base + CoordinatorMetric::Latency(priority).offset();
+            for execution in &task.executions[*seen..] {
+                let Some(duration) = execution.duration else { break };
+                monitor.record_with_timestamp(latency, duration.as_millis() as f64, ts);
+                sampled[priority as usize] = true;
+                *seen += 1;
+            }
+        }
+        self.exported.retain(|id, _| metrics.get(*id).is_some());
+
+        for p in PRIORITIES.into_iter().filter(|&p| sampled[p as usize]) {
+            if let Some(percentiles) = monitor.calculate_percentiles(self.id(CoordinatorMetric::Latency(p))) {
+                monitor.record_with_timestamp(self.id(CoordinatorMetric::LatencyP50(p)), percentiles.p50, ts);
+                monitor.record_with_timestamp(self.id(CoordinatorMetric::LatencyP95(p)), percentiles.p95, ts);
+                monitor.record_with_timestamp(self.id(CoordinatorMetric::LatencyP99(p)), percentiles.p99, ts);
+            }
+        }
+    }
+
+    /// Exports the utilization of a pool registered with `with_pool`; unknown labels are ignored
+    pub fn export_pool(&self, monitor: &mut Monitor, label: &str, pool: &ResourcePool, now: Timestamp) {
+        if let Some(id) = self.pool_id(label) {
+            monitor.record_with_timestamp(id, pool.utilization() as f64, now.0);
+        }
+    }
+}
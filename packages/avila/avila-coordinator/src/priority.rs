@@ -1,15 +1,10 @@
 //! # Priority - Task priority management
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Priority {
     Low = 0,
+    #[default]
     Normal = 1,
     High = 2,
     Critical = 3,
 }
-
-impl Default for Priority {
-    fn default() -> Self {
-        Priority::Normal
-    }
-}
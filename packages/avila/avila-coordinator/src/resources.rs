@@ -78,10 +78,8 @@ impl ResourcePool {
 
     pub fn acquire(&mut self, task_id: TaskId) -> Option<ResourceId> {
         for resource in &mut self.resources {
-            if resource.state == ResourceState::Available {
-                if resource.acquire(task_id).is_ok() {
-                    return Some(resource.id);
-                }
+            if resource.state == ResourceState::Available && resource.acquire(task_id).is_ok() {
+                return Some(resource.id);
             }
         }
         None
@@ -111,6 +109,15 @@ impl ResourcePool {
     pub fn total_count(&self) -> usize {
         self.resources.len()
     }
+
+    /// Fraction of the pool's resources currently in use (0.0 for an empty pool)
+    pub fn utilization(&self) -> f32 {
+        if self.resources.is_empty() {
+            0.0
+        } else {
+            self.in_use_count() as f32 / self.resources.len() as f32
+        }
+    }
 }
 
 /// Rate limiter for controlling task execution rate
@@ -10,9 +10,14 @@
 //! - `FairScheduler`: Fair scheduling (round-robin)
 //! - `DeadlineScheduler`: Deadline-based (earliest deadline first)
 //! - `WeightedScheduler`: Weight-based (highest weight first)
+//! - `TimedScheduler`: Delayed and recurring tasks on top of another scheduler
 extern crate alloc;
 use alloc::vec::Vec;
 use alloc::collections::BTreeMap;
+use crate::coordinator::Coordinator;
+use crate::cron::Recurrence;
+use crate::metrics::{Duration, Timestamp};
+use crate::priority::Priority;
 use crate::task::{Task, TaskState};
 use crate::types::TaskId;
 
@@ -38,12 +43,14 @@ impl Default for FifoScheduler {
     }
 }
 
-/// Priority-based scheduler (highest priority first)
+/// Priority-based scheduler (highest priority first, FIFO among equals)
 pub struct PriorityScheduler;
 
 impl Scheduler for PriorityScheduler {
     fn next_task(&mut self, tasks: &[Task]) -> Option<TaskId> {
+        // max_by_key keeps the last maximum; reversing makes ties go to the earliest task
         tasks.iter()
+            .rev()
             .filter(|t| t.state == TaskState::Pending)
             .max_by_key(|t| t.priority)
             .map(|t| t.id)
@@ -284,3 +291,197 @@ impl Scheduler for WeightedScheduler {
             .map(|t| t.id)
     }
 }
+
+/// Identifier of a recurring schedule in a `TimedScheduler`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct ScheduleId(pub u64);
+
+#[derive(Clone, Debug)]
+struct RecurringEntry {
+    id: ScheduleId,
+    recurrence: Recurrence,
+    priority: Priority,
+    next_fire: Option<Timestamp>,
+}
+
+/// Time-based scheduler (delayed and recurring tasks)
+///
+/// Tasks can be held back until a point in time, and recurring schedules
+/// submit a new task each time they fire. Time only moves when
+/// `poll_due_tasks` is called, so the caller drives it from a timer tick
+/// (or a test clock). Among the tasks that are due, the wrapped scheduler
+/// picks as usual.
+///
+/// A recurring schedule that missed several firings (e.g. the process was
+/// down) fires once and then resumes from the current time.
+///
+/// # Example
+/// ```ignore
+/// let mut scheduler = TimedScheduler::new(Timestamp(now_ms));
+/// scheduler.add_recurring(Recurrence::Cron(CronSchedule::parse("0 3 * * *")?), Priority::Low);
+/// scheduler.submit_after(&mut coordinator, 7, Duration(30_000));
+/// // on every tick
+/// scheduler.poll_due_tasks(&mut coordinator, Timestamp(now_ms));
+/// let next = scheduler.next_task(&coordinator.tasks);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimedScheduler<S: Scheduler = PriorityScheduler> {
+    inner: S,
+    now: Timestamp,
+    delayed: BTreeMap<TaskId, Timestamp>,
+    recurring: Vec<RecurringEntry>,
+    instances: BTreeMap<TaskId, ScheduleId>,
+    next_schedule: u64,
+}
+
+impl TimedScheduler {
+    /// Create a timed scheduler over `PriorityScheduler`, starting at `now`
+    pub fn new(now: Timestamp) -> Self {
+        Self::with_scheduler(PriorityScheduler, now)
+    }
+}
+
+impl<S: Scheduler> TimedScheduler<S> {
+    /// Create a timed scheduler choosing among due tasks with `inner`
+    pub fn with_scheduler(inner: S, now: Timestamp) -> Self {
+        Self {
+            inner,
+            now,
+            delayed: BTreeMap::new(),
+            recurring: Vec::new(),
+            instances: BTreeMap::new(),
+            next_schedule: 1,
+        }
+    }
+
+    /// Submit a task that becomes runnable at `at`
+    pub fn submit_at(&mut self, coordinator: &mut Coordinator, id: u64, at: Timestamp) -> TaskId {
+        coordinator.submit(id);
+        self.defer_until(TaskId::new(id), at);
+        TaskId::new(id)
+    }
+
+    /// Submit a task that becomes runnable `delay` after the current time
+    pub fn submit_after(&mut self, coordinator: &mut Coordinator, id: u64, delay: Duration) -> TaskId {
+        let at = Timestamp(self.now.0.saturating_add(delay.0));
+        self.submit_at(coordinator, id, at)
+    }
+
+    /// Hold back an existing task (e.g. a retry after backoff) until `at`
+    pub fn defer_until(&mut self, task_id: TaskId, at: Timestamp) {
+        if at > self.now {
+            self.delayed.insert(task_id, at);
+        } else {
+            self.delayed.remove(&task_id);
+        }
+    }
+
+    /// Hold back an existing task for `delay` from the current time
+    pub fn defer(&mut self, task_id: TaskId, delay: Duration) {
+        self.defer_until(task_id, Timestamp(self.now.0.saturating_add(delay.0)));
+    }
+
+    /// When a deferred task becomes runnable (`None` if it is not deferred)
+    pub fn due_at(&self, task_id: TaskId) -> Option<Timestamp> {
+        self.delayed.get(&task_id).copied()
+    }
+
+    /// Register a recurring schedule; its first firing is the next one after the current time
+    pub fn add_recurring(&mut self, recurrence: Recurrence, priority: Priority) -> ScheduleId {
+        let id = ScheduleId(self.next_schedule);
+        self.next_schedule += 1;
+        self.recurring.push(RecurringEntry {
+            id,
+            recurrence,
+            priority,
+            next_fire: recurrence.next_after(self.now),
+        });
+        id
+    }
+
+    /// Stop a recurring schedule; tasks it already submitted are kept
+    pub fn remove_recurring(&mut self, id: ScheduleId) -> bool {
+        let before = self.recurring.len();
+        self.recurring.retain(|entry| entry.id != id);
+        self.recurring.len() != before
+    }
+
+    pub fn next_fire(&self, id: ScheduleId) -> Option<Timestamp> {
+        self.recurring.iter().find(|entry| entry.id == id).and_then(|entry| entry.next_fire)
+    }
+
+    /// Recurring schedule that submitted `task_id`
+    pub fn schedule_of(&self, task_id: TaskId) -> Option<ScheduleId> {
+        self.instances.get(&task_id).copied()
+    }
+
+    /// Advance the clock to `now`
+    ///
+    /// Releases deferred tasks that are due and submits one task for every
+    /// recurring schedule that fired. Returns the tasks that became runnable,
+    /// earliest due first.
+    pub fn poll_due_tasks(&mut self, coordinator: &mut Coordinator, now: Timestamp) -> Vec<TaskId> {
+        self.now = self.now.max(now);
+        let now = self.now;
+
+        let mut due: Vec<(Timestamp, TaskId)> = Vec::new();
+        self.delayed.retain(|&task_id, &mut at| {
+            if at > now {
+                return true;
+            }
+            if coordinator.get_task(task_id.as_u64()).is_some_and(|t| t.state == TaskState::Pending) {
+                due.push((at, task_id));
+            }
+            false
+        });
+
+        let mut next_id = coordinator.iter().map(|t| t.id.as_u64() + 1).max().unwrap_or(1);
+        for entry in &mut self.recurring {
+            let Some(fire_at) = entry.next_fire.filter(|&at| at <= now) else {
+                continue;
+            };
+            coordinator.submit_with_priority(next_id, entry.priority);
+            self.instances.insert(TaskId::new(next_id), entry.id);
+            due.push((fire_at, TaskId::new(next_id)));
+            next_id += 1;
+            entry.next_fire = entry.recurrence.next_after(now);
+        }
+
+        due.sort();
+        due.into_iter().map(|(_, task_id)| task_id).collect()
+    }
+
+    /// Earliest time at which `poll_due_tasks` has something to do
+    pub fn next_wakeup(&self) -> Option<Timestamp> {
+        let delayed = self.delayed.values().copied().min();
+        let recurring = self.recurring.iter().filter_map(|entry| entry.next_fire).min();
+        match (delayed, recurring) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Get the current time
+    pub fn current_time(&self) -> Timestamp {
+        self.now
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+}
+
+impl<S: Scheduler> Scheduler for TimedScheduler<S> {
+    fn next_task(&mut self, tasks: &[Task]) -> Option<TaskId> {
+        let now = self.now;
+        let due: Vec<Task> = tasks.iter()
+            .filter(|t| self.delayed.get(&t.id).is_none_or(|&at| at <= now))
+            .cloned()
+            .collect();
+        self.inner.next_task(&due)
+    }
+}
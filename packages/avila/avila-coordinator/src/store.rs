@@ -0,0 +1,908 @@
+//! # Store - Persistent task state and crash recovery
+//!
+//! A `TaskStore` is an append-only journal of `StoreRecord`s. The
+//! `PersistentCoordinator` writes a record before applying each change, so
+//! replaying the journal with `Coordinator::recover` rebuilds the tasks,
+//! their dependencies, retry counters and workflow DAGs after a restart.
+//! Leases handed to remote workers (`LeaseQueue`) are journaled too; the
+//! ones still outstanding when the process died are expired on recovery.
+//! Tasks that were running when the process died go back to pending, and
+//! the `RecoveryReport` says what the replay kept, dropped and reset.
+//!
+//! `MemoryStore` keeps the journal in memory; with the `std` feature,
+//! `wal::FileStore` keeps it in a file, one checksummed line per record.
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::coordinator::Coordinator;
+use crate::dependencies::DependencyGraph;
+use crate::lease::{target_state, AckStatus, Lease, LeaseError, LeaseId, LeaseOutcome, LeaseQueue};
+use crate::metrics::{Duration, Timestamp};
+use crate::priority::Priority;
+use crate::scheduler::Scheduler;
+use crate::task::{Task, TaskState};
+use crate::types::{TaskError, TaskId};
+use crate::validation::StateValidator;
+use crate::workflow::{Workflow, WorkflowNode};
+
+/// One journaled change
+#[derive(Clone, Debug, PartialEq)]
+pub enum StoreRecord {
+    Submitted { task: TaskId, priority: Priority },
+    State { task: TaskId, state: TaskState },
+    Retry { task: TaskId, attempt: u32 },
+    Dependency { task: TaskId, depends_on: TaskId },
+    Workflow { name: String, nodes: Vec<WorkflowNode> },
+    Removed { task: TaskId },
+    /// Written before the lease reaches the worker
+    Leased { lease: Lease },
+    LeaseAcked { lease: LeaseId, outcome: LeaseOutcome },
+    /// Timed out, or outstanding when the process holding it died
+    LeaseExpired { lease: LeaseId },
+    /// First lease id not handed out yet, kept by `compact`
+    NextLease { lease: LeaseId },
+}
+
+/// Error types for store operations
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoreError {
+    /// Underlying storage failed
+    Io(String),
+    /// Unreadable record before the end of the journal (1-based line)
+    Corrupted { line: usize },
+    /// The change was rejected by the coordinator
+    Task(TaskError),
+    /// The lease queue rejected the acknowledgement
+    Lease(LeaseError),
+}
+
+impl StoreError {
+    pub fn message(&self) -> &str {
+        match self {
+            StoreError::Io(message) => message,
+            StoreError::Corrupted { .. } => "Corrupted task store",
+            StoreError::Task(error) => error.message(),
+            StoreError::Lease(error) => error.message(),
+        }
+    }
+}
+
+impl From<TaskError> for StoreError {
+    fn from(error: TaskError) -> Self {
+        StoreError::Task(error)
+    }
+}
+
+/// Append-only storage for task records
+pub trait TaskStore {
+    fn append(&mut self, record: &StoreRecord) -> Result<(), StoreError>;
+
+    /// All records, in append order
+    fn load(&mut self) -> Result<Vec<StoreRecord>, StoreError>;
+
+    /// Replaces the journal with an equivalent, shorter one
+    fn compact(&mut self, records: &[StoreRecord]) -> Result<(), StoreError>;
+
+    /// Records the last `load` discarded as torn writes
+    fn torn(&self) -> usize {
+        0
+    }
+}
+
+/// In-memory store, for tests and `no_std` users that persist elsewhere
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore {
+    records: Vec<StoreRecord>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> &[StoreRecord] {
+        &self.records
+    }
+}
+
+impl TaskStore for MemoryStore {
+    fn append(&mut self, record: &StoreRecord) -> Result<(), StoreError> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+
+    fn load(&mut self) -> Result<Vec<StoreRecord>, StoreError> {
+        Ok(self.records.clone())
+    }
+
+    fn compact(&mut self, records: &[StoreRecord]) -> Result<(), StoreError> {
+        self.records = records.to_vec();
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TEXT ENCODING
+// ============================================================================
+
+impl StoreRecord {
+    /// Single-line text form, e.g. `state 7 running`
+    pub fn encode(&self) -> String {
+        match self {
+            StoreRecord::Submitted { task, priority } => {
+                format!("submit {} {}", task.as_u64(), priority_name(*priority))
+            }
+            StoreRecord::State { task, state } => format!("state {} {}", task.as_u64(), state_name(*state)),
+            StoreRecord::Retry { task, attempt } => format!("retry {} {}", task.as_u64(), attempt),
+            StoreRecord::Dependency { task, depends_on } => {
+                format!("dep {} {}", task.as_u64(), depends_on.as_u64())
+            }
+            StoreRecord::Workflow { name, nodes } => {
+                // workflow <name> <id>[:<dep>,<dep>][=<node name>];...
+                let mut line = format!("workflow {} ", escape(name));
+                for (i, node) in nodes.iter().enumerate() {
+                    if i > 0 {
+                        line.push(';');
+                    }
+                    let _ = write!(line, "{}", node.task_id.as_u64());
+                    for (j, dep) in node.dependencies.iter().enumerate() {
+                        line.push(if j == 0 { ':' } else { ',' });
+                        let _ = write!(line, "{}", dep.as_u64());
+                    }
+                    if let Some(node_name) = &node.name {
+                        line.push('=');
+                        line.push_str(&escape(node_name));
+                    }
+                }
+                line
+            }
+            StoreRecord::Removed { task } => format!("remove {}", task.as_u64()),
+            StoreRecord::Leased { lease } => format!(
+                "lease {} {} {} {} {}",
+                lease.id.0,
+                lease.task.as_u64(),
+                lease.attempt,
+                lease.expires_at.0,
+                escape(&lease.worker)
+            ),
+            StoreRecord::LeaseAcked { lease, outcome } => format!("ack {} {}", lease.0, outcome_name(*outcome)),
+            StoreRecord::LeaseExpired { lease } => format!("expire {}", lease.0),
+            StoreRecord::NextLease { lease } => format!("next-lease {}", lease.0),
+        }
+    }
+
+    /// Parses the output of `encode`
+    pub fn decode(line: &str) -> Option<Self> {
+        let mut parts = line.split(' ');
+        let kind = parts.next()?;
+        let record = match kind {
+            "submit" => StoreRecord::Submitted { task: parse_id(parts.next()?)?, priority: parse_priority(parts.next()?)? },
+            "state" => StoreRecord::State { task: parse_id(parts.next()?)?, state: parse_state(parts.next()?)? },
+            "retry" => StoreRecord::Retry { task: parse_id(parts.next()?)?, attempt: parts.next()?.parse().ok()? },
+            "dep" => StoreRecord::Dependency { task: parse_id(parts.next()?)?, depends_on: parse_id(parts.next()?)? },
+            "workflow" => {
+                let name = unescape(parts.next()?)?;
+                let spec = parts.next()?;
+                let mut nodes = Vec::new();
+                for node_spec in spec.split(';').filter(|s| !s.is_empty()) {
+                    let (ids, node_name) = match node_spec.split_once('=') {
+                        Some((ids, node_name)) => (ids, Some(unescape(node_name)?)),
+                        None => (node_spec, None),
+                    };
+                    let (id, deps) = ids.split_once(':').unwrap_or((ids, ""));
+                    let mut node = WorkflowNode::new(parse_id(id)?);
+                    for dep in deps.split(',').filter(|s| !s.is_empty()) {
+                        node.add_dependency(parse_id(dep)?);
+                    }
+                    node.name = node_name;
+                    nodes.push(node);
+                }
+                StoreRecord::Workflow { name, nodes }
+            }
+            "remove" => StoreRecord::Removed { task: parse_id(parts.next()?)? },
+            "lease" => StoreRecord::Leased {
+                lease: Lease {
+                    id: LeaseId(parts.next()?.parse().ok()?),
+                    task: parse_id(parts.next()?)?,
+                    attempt: parts.next()?.parse().ok()?,
+                    expires_at: Timestamp(parts.next()?.parse().ok()?),
+                    worker: unescape(parts.next()?)?,
+                },
+            },
+            "ack" => StoreRecord::LeaseAcked {
+                lease: LeaseId(parts.next()?.parse().ok()?),
+                outcome: parse_outcome(parts.next()?)?,
+            },
+            "expire" => StoreRecord::LeaseExpired { lease: LeaseId(parts.next()?.parse().ok()?) },
+            "next-lease" => StoreRecord::NextLease { lease: LeaseId(parts.next()?.parse().ok()?) },
+            _ => return None,
+        };
+        match parts.next() {
+            None => Some(record),
+            Some(_) => None,
+        }
+    }
+}
+
+fn parse_id(text: &str) -> Option<TaskId> {
+    text.parse().ok().map(TaskId::new)
+}
+
+fn priority_name(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Low => "low",
+        Priority::Normal => "normal",
+        Priority::High => "high",
+        Priority::Critical => "critical",
+    }
+}
+
+fn parse_priority(text: &str) -> Option<Priority> {
+    match text {
+        "low" => Some(Priority::Low),
+        "normal" => Some(Priority::Normal),
+        "high" => Some(Priority::High),
+        "critical" => Some(Priority::Critical),
+        _ => None,
+    }
+}
+
+fn state_name(state: TaskState) -> &'static str {
+    match state {
+        TaskState::Pending => "pending",
+        TaskState::Running => "running",
+        TaskState::Completed => "completed",
+        TaskState::Failed => "failed",
+        TaskState::Cancelled => "cancelled",
+    }
+}
+
+fn parse_state(text: &str) -> Option<TaskState> {
+    match text {
+        "pending" => Some(TaskState::Pending),
+        "running" => Some(TaskState::Running),
+        "completed" => Some(TaskState::Completed),
+        "failed" => Some(TaskState::Failed),
+        "cancelled" => Some(TaskState::Cancelled),
+        _ => None,
+    }
+}
+
+fn outcome_name(outcome: LeaseOutcome) -> &'static str {
+    match outcome {
+        LeaseOutcome::Completed => "completed",
+        LeaseOutcome::Failed => "failed",
+        LeaseOutcome::Released => "released",
+    }
+}
+
+fn parse_outcome(text: &str) -> Option<LeaseOutcome> {
+    match text {
+        "completed" => Some(LeaseOutcome::Completed),
+        "failed" => Some(LeaseOutcome::Failed),
+        "released" => Some(LeaseOutcome::Released),
+        _ => None,
+    }
+}
+
+/// Percent-escapes the separators of the text form (and `%` itself)
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            ' ' | '%' | ';' | ',' | ':' | '=' | '\n' | '\r' => {
+                let _ = write!(out, "%{:02X}", c as u32);
+            }
+            _ => out.push(c),
+        }
+    }
+    if out.is_empty() {
+        // Keeps the field present when the name is empty
+        out.push_str("%00");
+    }
+    out
+}
+
+fn unescape(text: &str) -> Option<String> {
+    if text == "%00" {
+        return Some(String::new());
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let hex: String = chars.by_ref().take(2).collect();
+            out.push(u8::from_str_radix(&hex, 16).ok().filter(u8::is_ascii)? as char);
+        } else {
+            out.push(c);
+        }
+    }
+    Some(out)
+}
+
+// ============================================================================
+// PERSISTENT COORDINATOR
+// ============================================================================
+
+/// What `Coordinator::recover` found in the journal and repaired
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Records replayed
+    pub records_applied: usize,
+    /// Records skipped: torn writes, or changes to tasks the journal does
+    /// not know (a state for a missing task, a second submit of an id, ...)
+    pub records_dropped: usize,
+    /// Tasks that were running outside a lease, back to pending
+    pub tasks_reset: Vec<TaskId>,
+    /// Leases the journal still had outstanding: their workers were talking
+    /// to the process that died, and their tasks go back to pending
+    pub leases_expired: Vec<Lease>,
+}
+
+impl RecoveryReport {
+    /// Whether the journal replayed cleanly with nothing to repair
+    pub fn is_clean(&self) -> bool {
+        self.records_dropped == 0 && self.tasks_reset.is_empty() && self.leases_expired.is_empty()
+    }
+}
+
+/// Coordinator whose every change is journaled to a `TaskStore` first
+///
+/// Unlike `Coordinator`, transitions are checked with `StateValidator`, so
+/// the journal only ever holds valid histories.
+pub struct PersistentCoordinator<S: TaskStore> {
+    coordinator: Coordinator,
+    dependencies: DependencyGraph,
+    retries: BTreeMap<TaskId, u32>,
+    workflows: Vec<Workflow>,
+    leases: BTreeMap<LeaseId, Lease>,
+    next_lease: u64,
+    report: RecoveryReport,
+    store: S,
+}
+
+impl Coordinator {
+    /// Rebuilds the coordinator journaled in `store`
+    ///
+    /// Records that do not fit the journal replayed so far are dropped.
+    /// Outstanding leases expire, and tasks that were running are
+    /// interrupted work: both go back to pending (journaled as such).
+    /// `report()` lists all three.
+    pub fn recover<S: TaskStore>(mut store: S) -> Result<PersistentCoordinator<S>, StoreError> {
+        let records = store.load()?;
+        let mut recovered = PersistentCoordinator::new(store);
+        recovered.report.records_dropped = recovered.store.torn();
+        for record in &records {
+            if recovered.apply(record) {
+                recovered.report.records_applied += 1;
+            } else {
+                recovered.report.records_dropped += 1;
+            }
+        }
+
+        let outstanding: Vec<Lease> = recovered.leases.values().cloned().collect();
+        for lease in outstanding {
+            let record = StoreRecord::LeaseExpired { lease: lease.id };
+            recovered.store.append(&record)?;
+            recovered.apply(&record);
+            recovered.report.leases_expired.push(lease);
+        }
+
+        let interrupted: Vec<TaskId> = recovered.coordinator.iter()
+            .filter(|t| t.state == TaskState::Running)
+            .map(|t| t.id)
+            .collect();
+        for task in interrupted {
+            let record = StoreRecord::State { task, state: TaskState::Pending };
+            recovered.store.append(&record)?;
+            recovered.apply(&record);
+            recovered.report.tasks_reset.push(task);
+        }
+        Ok(recovered)
+    }
+}
+
+impl<S: TaskStore> PersistentCoordinator<S> {
+    /// Empty coordinator journaling to `store` (use `Coordinator::recover`
+    /// for a store that already has records)
+    pub fn new(store: S) -> Self {
+        Self {
+            coordinator: Coordinator::new(),
+            dependencies: DependencyGraph::new(),
+            retries: BTreeMap::new(),
+            workflows: Vec::new(),
+            leases: BTreeMap::new(),
+            next_lease: 1,
+            report: RecoveryReport::default(),
+            store,
+        }
+    }
+
+    pub fn submit(&mut self, id: u64, priority: Priority) -> Result<(), StoreError> {
+        if self.coordinator.get_task(id).is_some() {
+            return Err(TaskError::DuplicateId.into());
+        }
+        self.journal(StoreRecord::Submitted { task: TaskId::new(id), priority })
+    }
+
+    /// `task` runs only after `depends_on`; both must exist and the edge may not close a cycle
+    pub fn add_dependency(&mut self, task: u64, depends_on: u64) -> Result<(), StoreError> {
+        self.task(task)?;
+        self.task(depends_on)?;
+        let (task, depends_on) = (TaskId::new(task), TaskId::new(depends_on));
+        if task == depends_on || self.depends_on(depends_on, task) {
+            return Err(TaskError::CircularDependency.into());
+        }
+        self.journal(StoreRecord::Dependency { task, depends_on })
+    }
+
+    pub fn start(&mut self, id: u64) -> Result<(), StoreError> {
+        self.transition(id, TaskState::Running)
+    }
+
+    pub fn complete(&mut self, id: u64) -> Result<(), StoreError> {
+        self.transition(id, TaskState::Completed)
+    }
+
+    pub fn fail(&mut self, id: u64) -> Result<(), StoreError> {
+        self.transition(id, TaskState::Failed)
+    }
+
+    pub fn cancel(&mut self, id: u64) -> Result<(), StoreError> {
+        self.transition(id, TaskState::Cancelled)
+    }
+
+    /// Moves a failed task back to pending; returns the attempt number
+    pub fn retry(&mut self, id: u64) -> Result<u32, StoreError> {
+        let task = self.task(id)?;
+        StateValidator::can_transition(task.state, TaskState::Pending)?;
+        let task = task.id;
+        let attempt = self.retry_count(task) + 1;
+        self.journal(StoreRecord::Retry { task, attempt })?;
+        self.journal(StoreRecord::State { task, state: TaskState::Pending })?;
+        Ok(attempt)
+    }
+
+    pub fn remove_task(&mut self, id: u64) -> Result<Task, StoreError> {
+        let task = self.task(id)?.clone();
+        self.journal(StoreRecord::Removed { task: task.id })?;
+        Ok(task)
+    }
+
+    /// Stores a workflow DAG, replacing one with the same name
+    pub fn add_workflow(&mut self, workflow: &Workflow) -> Result<(), StoreError> {
+        self.journal(StoreRecord::Workflow {
+            name: workflow.name().to_string(),
+            nodes: workflow.nodes().to_vec(),
+        })
+    }
+
+    /// Lease queue over `inner` that continues the journaled lease ids
+    ///
+    /// Leases granted before a restart are never handed out again, so late
+    /// acks on them fail with `LeaseError::Expired`. Drive the queue through
+    /// `lease`, `ack` and `expire_leases` so that every grant is journaled.
+    pub fn lease_queue<Q: Scheduler>(&self, inner: Q, visibility: Duration) -> LeaseQueue<Q> {
+        LeaseQueue::with_scheduler(inner, visibility).starting_at(LeaseId(self.next_lease))
+    }
+
+    /// `LeaseQueue::lease`, journaling each lease before returning it
+    ///
+    /// If the journal fails, the leases not written yet go back to the queue.
+    pub fn lease<Q: Scheduler>(
+        &mut self,
+        queue: &mut LeaseQueue<Q>,
+        worker: &str,
+        max: usize,
+        now: Timestamp,
+    ) -> Result<Vec<Lease>, StoreError> {
+        self.expire_leases(queue, now)?;
+        let granted = queue.lease(&mut self.coordinator, worker, max, now);
+        for (i, lease) in granted.iter().enumerate() {
+            if let Err(error) = self.journal(StoreRecord::Leased { lease: lease.clone() }) {
+                for unwritten in &granted[i..] {
+                    let _ = queue.ack(&mut self.coordinator, unwritten.id, LeaseOutcome::Released, now);
+                }
+                return Err(error);
+            }
+        }
+        Ok(granted)
+    }
+
+    /// `LeaseQueue::ack`, journaling the outcome once applied
+    pub fn ack<Q: Scheduler>(
+        &mut self,
+        queue: &mut LeaseQueue<Q>,
+        lease: LeaseId,
+        outcome: LeaseOutcome,
+        now: Timestamp,
+    ) -> Result<AckStatus, StoreError> {
+        self.expire_leases(queue, now)?;
+        match queue.ack(&mut self.coordinator, lease, outcome, now) {
+            Ok(AckStatus::Applied) => {
+                self.journal(StoreRecord::LeaseAcked { lease, outcome })?;
+                Ok(AckStatus::Applied)
+            }
+            Ok(AckStatus::Duplicate) => Ok(AckStatus::Duplicate),
+            Err(error) => {
+                // The queue drops a lease whose task moved under it (e.g. cancelled)
+                if self.leases.contains_key(&lease) && queue.get(lease).is_none() {
+                    self.journal(StoreRecord::LeaseExpired { lease })?;
+                }
+                Err(StoreError::Lease(error))
+            }
+        }
+    }
+
+    /// `LeaseQueue::expire`, journaling the reaped leases
+    pub fn expire_leases<Q: Scheduler>(
+        &mut self,
+        queue: &mut LeaseQueue<Q>,
+        now: Timestamp,
+    ) -> Result<Vec<Lease>, StoreError> {
+        let reaped = queue.expire(&mut self.coordinator, now);
+        for lease in &reaped {
+            // Failed instead of pending: the lease reached the delivery limit
+            let exhausted = self.coordinator.get_task(lease.task.as_u64())
+                .is_some_and(|t| t.state == TaskState::Failed);
+            self.journal(StoreRecord::LeaseExpired { lease: lease.id })?;
+            if exhausted {
+                self.journal(StoreRecord::State { task: lease.task, state: TaskState::Failed })?;
+            }
+        }
+        Ok(reaped)
+    }
+
+    /// Leases journaled and neither acknowledged nor expired yet
+    pub fn leases(&self) -> impl Iterator<Item = &Lease> {
+        self.leases.values()
+    }
+
+    pub fn coordinator(&self) -> &Coordinator {
+        &self.coordinator
+    }
+
+    pub fn dependencies(&self) -> &DependencyGraph {
+        &self.dependencies
+    }
+
+    pub fn workflow(&self, name: &str) -> Option<&Workflow> {
+        self.workflows.iter().find(|w| w.name() == name)
+    }
+
+    pub fn workflows(&self) -> &[Workflow] {
+        &self.workflows
+    }
+
+    /// Retries made so far, to restore `RetryPolicy::current_attempt`
+    pub fn retry_count(&self, task: TaskId) -> u32 {
+        self.retries.get(&task).copied().unwrap_or(0)
+    }
+
+    /// Tasks that were running when the journal was last written
+    pub fn resumed(&self) -> &[TaskId] {
+        &self.report.tasks_reset
+    }
+
+    /// What recovery validated, dropped, reset and expired
+    pub fn report(&self) -> &RecoveryReport {
+        &self.report
+    }
+
+    /// Pending tasks whose dependencies all completed
+    pub fn ready_tasks(&self) -> Vec<TaskId> {
+        let completed: Vec<TaskId> = self.coordinator.iter()
+            .filter(|t| t.state == TaskState::Completed)
+            .map(|t| t.id)
+            .collect();
+        self.coordinator.iter()
+            .filter(|t| t.state == TaskState::Pending)
+            .filter(|t| self.dependencies.can_execute(t.id, &completed))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Rewrites the journal as a snapshot of the current state
+    pub fn compact(&mut self) -> Result<(), StoreError> {
+        let mut records = Vec::new();
+        for task in self.coordinator.iter() {
+            records.push(StoreRecord::Submitted { task: task.id, priority: task.priority });
+            if let Some(&attempt) = self.retries.get(&task.id) {
+                records.push(StoreRecord::Retry { task: task.id, attempt });
+            }
+            if task.state != TaskState::Pending {
+                records.push(StoreRecord::State { task: task.id, state: task.state });
+            }
+        }
+        for task in self.coordinator.iter() {
+            for &depends_on in self.dependencies.get_dependencies(task.id).into_iter().flatten() {
+                records.push(StoreRecord::Dependency { task: task.id, depends_on });
+            }
+        }
+        for workflow in &self.workflows {
+            records.push(StoreRecord::Workflow {
+                name: workflow.name().to_string(),
+                nodes: workflow.nodes().to_vec(),
+            });
+        }
+        for lease in self.leases.values() {
+            records.push(StoreRecord::Leased { lease: lease.clone() });
+        }
+        if self.next_lease > 1 {
+            records.push(StoreRecord::NextLease { lease: LeaseId(self.next_lease) });
+        }
+        self.store.compact(&records)
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    fn task(&self, id: u64) -> Result<&Task, StoreError> {
+        self.coordinator.get_task(id).ok_or(StoreError::Task(TaskError::NotFound))
+    }
+
+    fn transition(&mut self, id: u64, state: TaskState) -> Result<(), StoreError> {
+        let task = self.task(id)?;
+        StateValidator::can_transition(task.state, state)?;
+        let task = task.id;
+        self.journal(StoreRecord::State { task, state })
+    }
+
+    /// Write-ahead: the record reaches the store before the change is applied
+    fn journal(&mut self, record: StoreRecord) -> Result<(), StoreError> {
+        self.store.append(&record)?;
+        self.apply(&record);
+        Ok(())
+    }
+
+    fn depends_on(&self, task: TaskId, target: TaskId) -> bool {
+        let mut stack = alloc::vec![task];
+        let mut seen = Vec::new();
+        while let Some(current) = stack.pop() {
+            if current == target {
+                return true;
+            }
+            if !seen.contains(&current) {
+                seen.push(current);
+                stack.extend(self.dependencies.get_dependencies(current).into_iter().flatten().copied());
+            }
+        }
+        false
+    }
+
+    /// Applies a record; false (and no change) if it names a task the
+    /// journal does not have
+    ///
+    /// Transitions are not checked: a compacted journal jumps straight from
+    /// submit to the final state. A dependency may point at a removed task,
+    /// which keeps the dependent blocked as it was before the restart.
+    fn apply(&mut self, record: &StoreRecord) -> bool {
+        let known = |task: &TaskId| self.coordinator.get_task(task.as_u64()).is_some();
+        match record {
+            StoreRecord::Submitted { task, priority } => {
+                if known(task) {
+                    return false;
+                }
+                self.coordinator.submit_with_priority(task.as_u64(), *priority);
+            }
+            StoreRecord::State { task, state } => {
+                let Some(t) = self.coordinator.iter_mut().find(|t| t.id == *task) else {
+                    return false;
+                };
+                t.state = *state;
+            }
+            StoreRecord::Retry { task, attempt } => {
+                if !known(task) {
+                    return false;
+                }
+                self.retries.insert(*task, *attempt);
+            }
+            StoreRecord::Dependency { task, depends_on } => {
+                if !known(task) || task == depends_on {
+                    return false;
+                }
+                self.dependencies.add_dependency(*task, *depends_on);
+            }
+            StoreRecord::Workflow { name, nodes } => {
+                let mut workflow = Workflow::new(name.clone());
+                for node in nodes {
+                    let _ = workflow.add_node(node.clone());
+                }
+                self.workflows.retain(|w| w.name() != name);
+                self.workflows.push(workflow);
+            }
+            StoreRecord::Removed { task } => {
+                if self.coordinator.remove_task(task.as_u64()).is_err() {
+                    return false;
+                }
+                self.retries.remove(task);
+            }
+            StoreRecord::Leased { lease } => {
+                if !known(&lease.task) || self.leases.contains_key(&lease.id) {
+                    return false;
+                }
+                self.set_running_state(lease.task, TaskState::Running);
+                self.next_lease = self.next_lease.max(lease.id.0.saturating_add(1));
+                self.leases.insert(lease.id, lease.clone());
+            }
+            StoreRecord::LeaseAcked { lease, outcome } => {
+                let Some(lease) = self.leases.remove(lease) else {
+                    return false;
+                };
+                self.set_running_state(lease.task, target_state(*outcome));
+            }
+            StoreRecord::LeaseExpired { lease } => {
+                let Some(lease) = self.leases.remove(lease) else {
+                    return false;
+                };
+                self.set_running_state(lease.task, TaskState::Pending);
+            }
+            StoreRecord::NextLease { lease } => {
+                self.next_lease = self.next_lease.max(lease.0);
+            }
+        }
+        true
+    }
+
+    /// Moves `task` to `state` if it is running (`Leased` also from pending)
+    ///
+    /// A lease outlives neither a cancel nor a removal of its task.
+    fn set_running_state(&mut self, task: TaskId, state: TaskState) {
+        if let Some(t) = self.coordinator.iter_mut().find(|t| t.id == task) {
+            let from_pending = state == TaskState::Running && t.state == TaskState::Pending;
+            if t.state == TaskState::Running || from_pending {
+                t.state = state;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// FILE STORE
+// ============================================================================
+
+/// File-backed write-ahead log
+#[cfg(feature = "std")]
+pub mod wal {
+    extern crate std;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{Read, Write};
+    use std::path::{Path, PathBuf};
+    use super::{StoreError, StoreRecord, TaskStore};
+
+    /// Journal file with one `<crc32> <record>` line per record
+    ///
+    /// A crash in the middle of an append leaves a torn tail: the last line,
+    /// or several lines when unsynced appends were lost. `load` drops every
+    /// unreadable line at the end and truncates the file so the next append
+    /// starts clean. A bad line followed by a good one is reported as
+    /// `StoreError::Corrupted`.
+    pub struct FileStore {
+        path: PathBuf,
+        file: File,
+        sync: bool,
+        torn: usize,
+    }
+
+    impl FileStore {
+        /// Opens (or creates) the journal at `path`
+        pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+            let path = path.as_ref().to_path_buf();
+            let file = open_append(&path)?;
+            Ok(Self { path, file, sync: true, torn: 0 })
+        }
+
+        /// Skips the fsync after each append (faster, loses the tail on power failure)
+        pub fn without_sync(mut self) -> Self {
+            self.sync = false;
+            self
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl TaskStore for FileStore {
+        fn append(&mut self, record: &StoreRecord) -> Result<(), StoreError> {
+            self.file.write_all(line(record).as_bytes()).map_err(io_error)?;
+            if self.sync {
+                self.file.sync_data().map_err(io_error)?;
+            }
+            Ok(())
+        }
+
+        fn load(&mut self) -> Result<Vec<StoreRecord>, StoreError> {
+            let mut bytes = Vec::new();
+            File::open(&self.path).and_then(|mut f| f.read_to_end(&mut bytes)).map_err(io_error)?;
+
+            // Unreadable lines count as a torn tail only if nothing
+            // readable follows them
+            let mut records = Vec::new();
+            let mut torn_at = None;
+            let mut offset = 0;
+            for (number, raw) in bytes.split_inclusive(|&b| b == b'\n').enumerate() {
+                match (raw.strip_suffix(b"\n").and_then(parse_line), torn_at) {
+                    (Some(record), None) => records.push(record),
+                    (Some(_), Some((line, _))) => return Err(StoreError::Corrupted { line }),
+                    (None, None) => torn_at = Some((number + 1, offset)),
+                    (None, Some(_)) => {}
+                }
+                offset += raw.len();
+            }
+
+            self.torn = 0;
+            if let Some((_, valid_len)) = torn_at {
+                self.torn = bytes[valid_len..].split_inclusive(|&b| b == b'\n').count();
+                self.file.set_len(valid_len as u64).map_err(io_error)?;
+                self.file.sync_all().map_err(io_error)?;
+            }
+            Ok(records)
+        }
+
+        fn compact(&mut self, records: &[StoreRecord]) -> Result<(), StoreError> {
+            let mut tmp_name = self.path.as_os_str().to_owned();
+            tmp_name.push(".tmp");
+            let tmp = PathBuf::from(tmp_name);
+
+            let mut out = File::create(&tmp).map_err(io_error)?;
+            for record in records {
+                out.write_all(line(record).as_bytes()).map_err(io_error)?;
+            }
+            out.sync_all().map_err(io_error)?;
+            fs::rename(&tmp, &self.path).map_err(io_error)?;
+            self.file = open_append(&self.path)?;
+            Ok(())
+        }
+
+        fn torn(&self) -> usize {
+            self.torn
+        }
+    }
+
+    fn open_append(path: &Path) -> Result<File, StoreError> {
+        OpenOptions::new().read(true).append(true).create(true).open(path).map_err(io_error)
+    }
+
+    fn line(record: &StoreRecord) -> alloc::string::String {
+        let text = record.encode();
+        format!("{:08x} {}\n", crc32(text.as_bytes()), text)
+    }
+
+    fn parse_line(line: &[u8]) -> Option<StoreRecord> {
+        let line = core::str::from_utf8(line).ok()?;
+        let (crc, text) = line.split_once(' ')?;
+        let crc = u32::from_str_radix(crc, 16).ok()?;
+        if crc != crc32(text.as_bytes()) {
+            return None;
+        }
+        StoreRecord::decode(text)
+    }
+
+    fn io_error(error: std::io::Error) -> StoreError {
+        StoreError::Io(error.to_string())
+    }
+
+    /// CRC-32 (IEEE), bitwise
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+            }
+        }
+        !crc
+    }
+}
@@ -1,6 +1,7 @@
 //! # Task - Task definition and state management
 extern crate alloc;
-use crate::types::TaskId;
+use crate::types::{TaskId, TaskError};
+use crate::validation::StateValidator;
 use crate::priority::Priority;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -9,6 +10,14 @@ pub enum TaskState {
     Running,
     Completed,
     Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    /// Terminal states never transition again (except Failed -> Pending on retry)
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TaskState::Completed | TaskState::Failed | TaskState::Cancelled)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -47,6 +56,20 @@ impl Task {
         self.state = TaskState::Failed;
     }
 
+    /// Cancels a pending or running task
+    pub fn cancel(&mut self) -> Result<(), TaskError> {
+        StateValidator::can_transition(self.state, TaskState::Cancelled)?;
+        self.state = TaskState::Cancelled;
+        Ok(())
+    }
+
+    /// Puts a failed task back to pending for another attempt
+    pub fn retry(&mut self) -> Result<(), TaskError> {
+        StateValidator::can_transition(self.state, TaskState::Pending)?;
+        self.state = TaskState::Pending;
+        Ok(())
+    }
+
     pub fn set_priority(&mut self, priority: Priority) {
         self.priority = priority;
     }
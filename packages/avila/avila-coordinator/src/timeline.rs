@@ -0,0 +1,175 @@
+//! # Timeline - Execution history for time-travel debugging
+//!
+//! Every state change of a `WorkflowExecution` is stored as an ordered
+//! `ExecutionEvent`. Replaying a prefix of the stream reconstructs the
+//! execution state at that point, and the whole timeline can be exported
+//! as JSON for a debugging UI.
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::events::TaskEvent;
+use crate::metrics::Timestamp;
+use crate::types::TaskId;
+
+/// A recorded event with its position in the execution stream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionEvent {
+    pub sequence: u64,
+    pub timestamp: Timestamp,
+    pub event: TaskEvent,
+}
+
+/// Execution state reconstructed from an event prefix
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionSnapshot {
+    /// Number of events applied
+    pub applied_events: usize,
+    /// Timestamp of the last applied event
+    pub timestamp: Option<Timestamp>,
+    pub running: Vec<TaskId>,
+    pub completed: Vec<TaskId>,
+    pub failed: Vec<TaskId>,
+    pub total_tasks: usize,
+}
+
+impl ExecutionSnapshot {
+    pub fn new(total_tasks: usize) -> Self {
+        Self {
+            total_tasks,
+            ..Self::default()
+        }
+    }
+
+    /// Applies one event on top of this snapshot
+    pub fn apply(&mut self, event: &ExecutionEvent) {
+        match event.event {
+            TaskEvent::Started(id) => {
+                self.failed.retain(|&t| t != id);
+                push_unique(&mut self.running, id);
+            }
+            TaskEvent::Completed(id) => {
+                self.running.retain(|&t| t != id);
+                push_unique(&mut self.completed, id);
+            }
+            TaskEvent::Failed(id) => {
+                self.running.retain(|&t| t != id);
+                push_unique(&mut self.failed, id);
+            }
+            TaskEvent::Retrying(id, _) => {
+                self.failed.retain(|&t| t != id);
+            }
+            TaskEvent::Submitted(_) | TaskEvent::DependencyResolved(_) => {}
+        }
+        self.applied_events += 1;
+        self.timestamp = Some(event.timestamp);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed.len() + self.failed.len() == self.total_tasks
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.total_tasks > 0 {
+            self.completed.len() as f32 / self.total_tasks as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+fn push_unique(list: &mut Vec<TaskId>, id: TaskId) {
+    if !list.contains(&id) {
+        list.push(id);
+    }
+}
+
+/// Replays `events` up to (excluding) index `upto`
+pub fn replay(events: &[ExecutionEvent], total_tasks: usize, upto: usize) -> ExecutionSnapshot {
+    let mut snapshot = ExecutionSnapshot::new(total_tasks);
+    for event in events.iter().take(upto) {
+        snapshot.apply(event);
+    }
+    snapshot
+}
+
+/// Event name and task id, as used in the JSON timeline
+fn event_parts(event: &TaskEvent) -> (&'static str, TaskId) {
+    match *event {
+        TaskEvent::Submitted(id) => ("submitted", id),
+        TaskEvent::Started(id) => ("started", id),
+        TaskEvent::Completed(id) => ("completed", id),
+        TaskEvent::Failed(id) => ("failed", id),
+        TaskEvent::Retrying(id, _) => ("retrying", id),
+        TaskEvent::DependencyResolved(id) => ("dependencyResolved", id),
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_id_list(out: &mut String, ids: &[TaskId]) {
+    out.push('[');
+    for (i, id) in ids.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{}", id.as_u64());
+    }
+    out.push(']');
+}
+
+/// Serializes the timeline as JSON
+///
+/// Each event carries the snapshot right after it, so a UI can scrub
+/// through the execution without re-implementing the replay logic.
+pub fn timeline_json(workflow: &str, events: &[ExecutionEvent], total_tasks: usize) -> String {
+    let mut out = String::new();
+    out.push_str("{\"workflow\":");
+    write_json_string(&mut out, workflow);
+    let _ = write!(out, ",\"totalTasks\":{},\"events\":[", total_tasks);
+
+    let mut snapshot = ExecutionSnapshot::new(total_tasks);
+    for (i, event) in events.iter().enumerate() {
+        snapshot.apply(event);
+        if i > 0 {
+            out.push(',');
+        }
+        let (kind, task) = event_parts(&event.event);
+        let _ = write!(
+            out,
+            "{{\"sequence\":{},\"timestamp\":{},\"event\":\"{}\",\"task\":{}",
+            event.sequence,
+            event.timestamp.0,
+            kind,
+            task.as_u64()
+        );
+        if let TaskEvent::Retrying(_, attempt) = event.event {
+            let _ = write!(out, ",\"attempt\":{}", attempt);
+        }
+        out.push_str(",\"state\":{\"running\":");
+        write_id_list(&mut out, &snapshot.running);
+        out.push_str(",\"completed\":");
+        write_id_list(&mut out, &snapshot.completed);
+        out.push_str(",\"failed\":");
+        write_id_list(&mut out, &snapshot.failed);
+        out.push_str("}}");
+    }
+    out.push_str("]}");
+    out
+}
@@ -13,6 +13,8 @@ impl StateValidator {
             (TaskState::Running, TaskState::Completed) => Ok(()),
             (TaskState::Running, TaskState::Failed) => Ok(()),
             (TaskState::Failed, TaskState::Pending) => Ok(()), // retry
+            (TaskState::Pending, TaskState::Cancelled) => Ok(()),
+            (TaskState::Running, TaskState::Cancelled) => Ok(()),
             _ => Err(TaskError::InvalidTransition {
                 from: Self::state_name(from),
                 to: Self::state_name(to),
@@ -26,6 +28,7 @@ impl StateValidator {
             TaskState::Running => "Running",
             TaskState::Completed => "Completed",
             TaskState::Failed => "Failed",
+            TaskState::Cancelled => "Cancelled",
         }
     }
 
@@ -3,6 +3,9 @@ extern crate alloc;
 use alloc::vec::Vec;
 use alloc::string::String;
 use crate::types::{TaskId, TaskError};
+use crate::events::TaskEvent;
+use crate::metrics::Timestamp;
+use crate::timeline::{self, ExecutionEvent, ExecutionSnapshot};
 
 /// Workflow node representing a task in the DAG
 #[derive(Clone, Debug, PartialEq)]
@@ -47,6 +50,10 @@ impl Workflow {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn add_node(&mut self, node: WorkflowNode) -> Result<(), TaskError> {
         // Check for duplicate IDs
         if self.nodes.iter().any(|n| n.task_id == node.task_id) {
@@ -119,7 +126,7 @@ impl Workflow {
             }
         }
 
-        result.reverse();
+        // Post-order over dependencies already yields dependencies first
         Ok(result)
     }
 
@@ -152,28 +159,57 @@ impl Workflow {
     pub fn get_node(&self, task_id: TaskId) -> Option<&WorkflowNode> {
         self.nodes.iter().find(|n| n.task_id == task_id)
     }
+
+    pub fn nodes(&self) -> &[WorkflowNode] {
+        &self.nodes
+    }
 }
 
 /// Workflow execution context
+///
+/// Every state change is recorded as an `ExecutionEvent`, so a failed run
+/// can be inspected step by step with `replay()` / `replay_at()`.
 pub struct WorkflowExecution {
     workflow: Workflow,
     completed_tasks: Vec<TaskId>,
     failed_tasks: Vec<TaskId>,
     current_tasks: Vec<TaskId>,
+    events: Vec<ExecutionEvent>,
+    clock: fn() -> Timestamp,
 }
 
 impl WorkflowExecution {
     pub fn new(workflow: Workflow) -> Self {
+        Self::with_clock(workflow, Timestamp::now)
+    }
+
+    /// Uses `clock` to timestamp recorded events
+    pub fn with_clock(workflow: Workflow, clock: fn() -> Timestamp) -> Self {
         Self {
             workflow,
             completed_tasks: Vec::new(),
             failed_tasks: Vec::new(),
             current_tasks: Vec::new(),
+            events: Vec::new(),
+            clock,
         }
     }
 
+    fn record(&mut self, event: TaskEvent) {
+        self.events.push(ExecutionEvent {
+            sequence: self.events.len() as u64,
+            timestamp: (self.clock)(),
+            event,
+        });
+    }
+
     pub fn next_batch(&mut self) -> Vec<TaskId> {
         let ready = self.workflow.get_ready_tasks(&self.completed_tasks);
+        for &task_id in &ready {
+            if !self.current_tasks.contains(&task_id) {
+                self.record(TaskEvent::Started(task_id));
+            }
+        }
         self.current_tasks = ready.clone();
         ready
     }
@@ -181,6 +217,7 @@ impl WorkflowExecution {
     pub fn mark_completed(&mut self, task_id: TaskId) {
         if !self.completed_tasks.contains(&task_id) {
             self.completed_tasks.push(task_id);
+            self.record(TaskEvent::Completed(task_id));
         }
         self.current_tasks.retain(|&id| id != task_id);
     }
@@ -188,6 +225,7 @@ impl WorkflowExecution {
     pub fn mark_failed(&mut self, task_id: TaskId) {
         if !self.failed_tasks.contains(&task_id) {
             self.failed_tasks.push(task_id);
+            self.record(TaskEvent::Failed(task_id));
         }
         self.current_tasks.retain(|&id| id != task_id);
     }
@@ -208,4 +246,29 @@ impl WorkflowExecution {
             0.0
         }
     }
+
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    /// Full ordered event stream of this execution
+    pub fn events(&self) -> &[ExecutionEvent] {
+        &self.events
+    }
+
+    /// Reconstructs the state after the first `upto` events
+    pub fn replay(&self, upto: usize) -> ExecutionSnapshot {
+        timeline::replay(&self.events, self.workflow.node_count(), upto)
+    }
+
+    /// Reconstructs the state at `at` (events with timestamp <= `at`)
+    pub fn replay_at(&self, at: Timestamp) -> ExecutionSnapshot {
+        let upto = self.events.iter().take_while(|e| e.timestamp <= at).count();
+        self.replay(upto)
+    }
+
+    /// Exports the timeline as JSON for a debugging UI
+    pub fn timeline_json(&self) -> String {
+        timeline::timeline_json(&self.workflow.name, &self.events, self.workflow.node_count())
+    }
 }
@@ -0,0 +1,184 @@
+#![cfg(feature = "std")]
+//! Integration tests for the worker pool executor
+
+use avila_coordinator::{
+    EventHandler, Executor, Priority, RetryPolicy, SpawnOptions, TaskError, TaskEvent, TaskId, TaskState,
+};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+struct Recorder(Arc<Mutex<Vec<TaskEvent>>>);
+
+impl EventHandler for Recorder {
+    fn on_event(&mut self, event: &TaskEvent) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+fn recorder(executor: &Executor) -> Arc<Mutex<Vec<TaskEvent>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    executor.subscribe(Box::new(Recorder(Arc::clone(&events))));
+    events
+}
+
+fn push(log: &Arc<Mutex<Vec<u64>>>, value: u64) -> impl std::future::Future<Output = Result<(), String>> {
+    let log = Arc::clone(log);
+    async move {
+        log.lock().unwrap().push(value);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_executor_runs_dependencies_in_order() {
+    let executor = Executor::new(4);
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let parse = executor.spawn(push(&log, 1));
+    let (l, m) = (Arc::clone(&log), Arc::clone(&log));
+    let geometry = executor.spawn_with(SpawnOptions::new().after(parse), move || push(&l, 2)).unwrap();
+    let metadata = executor.spawn_with(SpawnOptions::new().after(parse), move || push(&m, 2)).unwrap();
+    let l = Arc::clone(&log);
+    let export = executor
+        .spawn_with(SpawnOptions::new().after(geometry).after(metadata), move || push(&l, 3))
+        .unwrap();
+
+    assert_eq!(executor.wait_for(export), Ok(TaskState::Completed));
+    assert_eq!(*log.lock().unwrap(), vec![1, 2, 2, 3]);
+    assert_eq!(executor.metrics(export).unwrap().success_count, 1);
+}
+
+#[test]
+fn test_executor_priority_order() {
+    let executor = Executor::new(1);
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    // Occupies the only worker until the queue is filled
+    let (started_tx, started_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    executor.spawn(async move {
+        started_tx.send(()).unwrap();
+        release_rx.recv().map_err(|e| e.to_string())
+    });
+    started_rx.recv().unwrap();
+
+    for (value, priority) in [(1, Priority::Low), (2, Priority::Critical), (3, Priority::Normal), (4, Priority::Critical)] {
+        let l = Arc::clone(&log);
+        executor
+            .spawn_with(SpawnOptions::new().with_priority(priority), move || push(&l, value))
+            .unwrap();
+    }
+    release_tx.send(()).unwrap();
+
+    executor.wait();
+    assert_eq!(*log.lock().unwrap(), vec![2, 4, 3, 1]);
+}
+
+#[test]
+fn test_executor_retries_failed_attempts() {
+    let executor = Executor::new(2);
+    let events = recorder(&executor);
+    let attempts = Arc::new(AtomicU32::new(0));
+
+    let counter = Arc::clone(&attempts);
+    let flaky = executor
+        .spawn_with(SpawnOptions::new().with_retry(RetryPolicy::new(3).with_base_delay(1)), move || {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                match attempt {
+                    1 | 2 => Err(format!("attempt {} failed", attempt)),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .unwrap();
+
+    assert_eq!(executor.wait_for(flaky), Ok(TaskState::Completed));
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(executor.error(flaky), None);
+    let metrics = executor.metrics(flaky).unwrap();
+    assert_eq!((metrics.attempts, metrics.failure_count), (3, 2));
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            TaskEvent::Submitted(flaky),
+            TaskEvent::Started(flaky),
+            TaskEvent::Retrying(flaky, 1),
+            TaskEvent::Started(flaky),
+            TaskEvent::Retrying(flaky, 2),
+            TaskEvent::Started(flaky),
+            TaskEvent::Completed(flaky),
+        ]
+    );
+}
+
+#[test]
+fn test_executor_failure_propagates_to_dependents() {
+    let executor = Executor::new(2);
+    let events = recorder(&executor);
+
+    let broken = executor
+        .spawn_with(SpawnOptions::new().with_retry(RetryPolicy::new(1).with_base_delay(0)), || async {
+            Err::<(), _>("invalid IFC header")
+        })
+        .unwrap();
+    let export = executor.spawn_with(SpawnOptions::new().after(broken), || async { Ok::<(), String>(()) }).unwrap();
+    let upload = executor.spawn_with(SpawnOptions::new().after(export), || async { Ok::<(), String>(()) }).unwrap();
+
+    assert_eq!(executor.wait_for(upload), Ok(TaskState::Failed));
+    assert_eq!(executor.state(broken), Some(TaskState::Failed));
+    assert_eq!(executor.error(broken).as_deref(), Some("invalid IFC header"));
+    assert_eq!(executor.error(export).as_deref(), Some("dependency 1 did not complete"));
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.iter().filter(|e| **e == TaskEvent::Started(broken)).count(), 2);
+    assert!(!events.contains(&TaskEvent::Started(export)));
+    assert!(events.contains(&TaskEvent::Failed(upload)));
+}
+
+#[test]
+fn test_executor_cancel_and_errors() {
+    let executor = Executor::new(1);
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let (started_tx, started_rx) = mpsc::channel();
+    let gate = executor.spawn(async move {
+        started_tx.send(()).unwrap();
+        release_rx.recv().map_err(|e| e.to_string())
+    });
+    started_rx.recv().unwrap();
+
+    let queued = executor.spawn(async { Ok::<(), String>(()) });
+    let dependent = executor.spawn_with(SpawnOptions::new().after(queued), || async { Ok::<(), String>(()) }).unwrap();
+    assert_eq!(executor.cancel(gate), Err(TaskError::InvalidState));
+    assert_eq!(executor.cancel(queued), Ok(()));
+    assert_eq!(executor.cancel(TaskId::new(99)), Err(TaskError::NotFound));
+    assert_eq!(
+        executor.spawn_with(SpawnOptions::new().after(TaskId::new(99)), || async { Ok::<(), String>(()) }),
+        Err(TaskError::NotFound)
+    );
+
+    release_tx.send(()).unwrap();
+    executor.wait();
+    assert_eq!(executor.state(gate), Some(TaskState::Completed));
+    assert_eq!(executor.state(queued), Some(TaskState::Cancelled));
+    assert_eq!(executor.state(dependent), Some(TaskState::Failed));
+}
+
+#[test]
+fn test_executor_catches_panics() {
+    let executor = Executor::new(1);
+    let task = executor.spawn(async {
+        if true {
+            panic!("converter crashed");
+        }
+        Ok::<(), String>(())
+    });
+
+    assert_eq!(executor.wait_for(task), Ok(TaskState::Failed));
+    assert_eq!(executor.error(task).as_deref(), Some("task panicked"));
+
+    // The worker survives the panic
+    let next = executor.spawn(async { Ok::<(), String>(()) });
+    assert_eq!(executor.wait_for(next), Ok(TaskState::Completed));
+    executor.shutdown();
+}
@@ -0,0 +1,129 @@
+//! Integration tests for the lease-based work queue
+use avila_coordinator::{
+    AckStatus, Coordinator, Duration, LeaseError, LeaseId, LeaseOutcome, LeaseQueue, Priority, TaskError, TaskId,
+    TaskState, Timestamp,
+};
+
+const VISIBILITY: Duration = Duration(30_000);
+
+fn coordinator(tasks: &[(u64, Priority)]) -> Coordinator {
+    let mut coord = Coordinator::new();
+    for &(id, priority) in tasks {
+        coord.submit_with_priority(id, priority);
+    }
+    coord
+}
+
+fn state(coord: &Coordinator, id: u64) -> TaskState {
+    coord.get_task(id).unwrap().state
+}
+
+#[test]
+fn test_lease_order_and_dependencies() {
+    let mut coord = coordinator(&[(1, Priority::Low), (2, Priority::Critical), (3, Priority::High), (4, Priority::Critical)]);
+    let mut queue = LeaseQueue::new(VISIBILITY);
+    queue.add_dependency(TaskId::new(4), TaskId::new(1)).unwrap();
+    assert_eq!(queue.add_dependency(TaskId::new(1), TaskId::new(4)), Err(TaskError::CircularDependency));
+
+    let leases = queue.lease(&mut coord, "worker-a", 2, Timestamp(1_000));
+    assert_eq!(leases.iter().map(|l| l.task).collect::<Vec<_>>(), vec![TaskId::new(2), TaskId::new(3)]);
+    assert_eq!(leases[0].expires_at, Timestamp(31_000));
+    assert_eq!((leases[0].attempt, leases[0].worker.as_str()), (1, "worker-a"));
+    assert_eq!(state(&coord, 2), TaskState::Running);
+
+    // Task 4 waits for task 1
+    let leases = queue.lease(&mut coord, "worker-b", 5, Timestamp(2_000));
+    assert_eq!(leases.iter().map(|l| l.task).collect::<Vec<_>>(), vec![TaskId::new(1)]);
+    assert!(queue.lease(&mut coord, "worker-b", 5, Timestamp(2_000)).is_empty());
+
+    queue.ack(&mut coord, leases[0].id, LeaseOutcome::Completed, Timestamp(3_000)).unwrap();
+    let leases = queue.lease(&mut coord, "worker-b", 5, Timestamp(3_000));
+    assert_eq!(leases.iter().map(|l| l.task).collect::<Vec<_>>(), vec![TaskId::new(4)]);
+    assert_eq!(queue.active_count(), 3);
+    assert_eq!(queue.lease_of(TaskId::new(4)).map(|l| l.id), Some(leases[0].id));
+}
+
+#[test]
+fn test_visibility_timeout_and_heartbeat() {
+    let mut coord = coordinator(&[(1, Priority::Normal), (2, Priority::Normal)]);
+    let mut queue = LeaseQueue::new(VISIBILITY);
+    let leases = queue.lease(&mut coord, "worker-a", 2, Timestamp(0));
+    let (kept, lost) = (leases[0].id, leases[1].id);
+
+    assert_eq!(queue.heartbeat(kept, Timestamp(20_000)), Ok(Timestamp(50_000)));
+    assert_eq!(queue.next_expiry(), Some(Timestamp(30_000)));
+    assert_eq!(queue.heartbeat(lost, Timestamp(30_000)), Err(LeaseError::Expired));
+
+    let expired = queue.expire(&mut coord, Timestamp(30_000));
+    assert_eq!(expired.iter().map(|l| l.id).collect::<Vec<_>>(), vec![lost]);
+    assert_eq!(state(&coord, 2), TaskState::Pending);
+    assert_eq!(state(&coord, 1), TaskState::Running);
+
+    // Another worker picks the task up; the stalled one can no longer finish it
+    let stolen = queue.lease(&mut coord, "worker-b", 1, Timestamp(31_000));
+    assert_eq!((stolen[0].task, stolen[0].attempt), (TaskId::new(2), 2));
+    assert_eq!(queue.ack(&mut coord, lost, LeaseOutcome::Completed, Timestamp(32_000)), Err(LeaseError::Expired));
+    assert_eq!(queue.heartbeat(lost, Timestamp(32_000)), Err(LeaseError::Expired));
+    assert_eq!(queue.heartbeat(LeaseId(99), Timestamp(32_000)), Err(LeaseError::Unknown));
+    assert_eq!(state(&coord, 2), TaskState::Running);
+
+    assert_eq!(queue.ack(&mut coord, stolen[0].id, LeaseOutcome::Completed, Timestamp(33_000)), Ok(AckStatus::Applied));
+    assert_eq!(state(&coord, 2), TaskState::Completed);
+    assert_eq!(queue.deliveries(TaskId::new(2)), 2);
+}
+
+#[test]
+fn test_ack_is_idempotent() {
+    let mut coord = coordinator(&[(1, Priority::Normal), (2, Priority::Normal)]);
+    let mut queue = LeaseQueue::new(VISIBILITY);
+    let leases = queue.lease(&mut coord, "worker-a", 2, Timestamp(0));
+    let (done, failed) = (leases[0].id, leases[1].id);
+
+    assert_eq!(queue.ack(&mut coord, done, LeaseOutcome::Completed, Timestamp(1)), Ok(AckStatus::Applied));
+    // The response was lost and the worker retries
+    assert_eq!(queue.ack(&mut coord, done, LeaseOutcome::Completed, Timestamp(2)), Ok(AckStatus::Duplicate));
+    assert_eq!(queue.ack(&mut coord, done, LeaseOutcome::Failed, Timestamp(3)), Err(LeaseError::Conflict));
+    assert_eq!(state(&coord, 1), TaskState::Completed);
+
+    assert_eq!(queue.ack(&mut coord, failed, LeaseOutcome::Failed, Timestamp(4)), Ok(AckStatus::Applied));
+    assert_eq!(state(&coord, 2), TaskState::Failed);
+    assert_eq!(queue.ack(&mut coord, LeaseId(7), LeaseOutcome::Completed, Timestamp(5)), Err(LeaseError::Unknown));
+    assert_eq!(queue.active_count(), 0);
+}
+
+#[test]
+fn test_release_and_cancel() {
+    let mut coord = coordinator(&[(1, Priority::High), (2, Priority::Normal), (3, Priority::Low)]);
+    let mut queue = LeaseQueue::new(VISIBILITY);
+    let leases = queue.lease(&mut coord, "worker-a", 3, Timestamp(0));
+
+    // Cancelled through the API while the worker held it
+    coord.cancel(3).unwrap();
+    assert_eq!(
+        queue.ack(&mut coord, leases[2].id, LeaseOutcome::Completed, Timestamp(1)),
+        Err(LeaseError::Task(TaskError::InvalidTransition { from: "Cancelled", to: "Completed" }))
+    );
+    assert_eq!(state(&coord, 3), TaskState::Cancelled);
+
+    // A worker shutting down hands its remaining tasks back
+    let released = queue.release_worker(&mut coord, "worker-a", Timestamp(2));
+    assert_eq!(released.len(), 2);
+    assert_eq!((state(&coord, 1), state(&coord, 2)), (TaskState::Pending, TaskState::Pending));
+    assert_eq!(queue.deliveries(TaskId::new(1)), 0);
+
+    let again = queue.lease(&mut coord, "worker-b", 1, Timestamp(3));
+    assert_eq!((again[0].task, again[0].attempt), (TaskId::new(1), 1));
+}
+
+#[test]
+fn test_max_deliveries() {
+    let mut coord = coordinator(&[(1, Priority::Normal)]);
+    let mut queue = LeaseQueue::new(Duration(10)).with_max_deliveries(2);
+
+    assert_eq!(queue.lease(&mut coord, "worker-a", 1, Timestamp(0)).len(), 1);
+    assert_eq!(queue.lease(&mut coord, "worker-b", 1, Timestamp(10)).len(), 1);
+    // The second expiry exhausts the task instead of requeueing it
+    assert!(queue.lease(&mut coord, "worker-c", 1, Timestamp(20)).is_empty());
+    assert_eq!(state(&coord, 1), TaskState::Failed);
+    assert_eq!(queue.next_expiry(), None);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "avila-metrics")]
+//! Integration tests for the avila-monitor exporter
+
+use avila_coordinator::{
+    Coordinator, CoordinatorMetric, MetricsCollector, MonitorExporter, Priority, Resource, ResourceId, ResourcePool,
+    TaskId, Timestamp,
+};
+use avila_monitor::Monitor;
+
+fn run(collector: &mut MetricsCollector, id: u64, start: u64, end: u64, success: bool) {
+    let metrics = collector.get_or_create(TaskId::new(id));
+    metrics.record_attempt_at(Timestamp(start));
+    if success {
+        metrics.record_success_at(Timestamp(end));
+    } else {
+        metrics.record_failure_at(Timestamp(end));
+    }
+}
+
+#[test]
+fn exports_queue_depth_throughput_and_retries() {
+    let mut coordinator = Coordinator::new();
+    let mut collector = MetricsCollector::new();
+    let mut monitor = Monitor::new();
+    let mut exporter = MonitorExporter::new(100);
+
+    for id in 1..=4 {
+        coordinator.submit(id);
+    }
+    coordinator.start(1).unwrap();
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::QueueDepth)), Some(3.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Running)), Some(1.0));
+    // Throughput needs two exports
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Throughput)), None);
+
+    run(&mut collector, 1, 0, 10, false);
+    run(&mut collector, 1, 20, 30, true);
+    run(&mut collector, 2, 0, 40, true);
+    run(&mut collector, 3, 0, 50, true);
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(2_000));
+
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Throughput)), Some(2.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Completed)), Some(3.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Failed)), Some(1.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::Retries)), Some(1.0));
+
+    let meta = monitor.get_metadata(exporter.id(CoordinatorMetric::QueueDepth)).unwrap();
+    assert_eq!(meta.name, "coordinator.queue_depth");
+    assert_eq!(meta.unit, "tasks");
+}
+
+#[test]
+fn latency_percentiles_are_split_by_priority() {
+    let mut coordinator = Coordinator::new();
+    let mut collector = MetricsCollector::new();
+    let mut monitor = Monitor::new();
+    let mut exporter = MonitorExporter::new(0);
+
+    for id in 1..=100 {
+        coordinator.submit_with_priority(id, Priority::High);
+        run(&mut collector, id, 0, id, true);
+    }
+    coordinator.submit_with_priority(101, Priority::Low);
+    run(&mut collector, 101, 0, 5_000, true);
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(1));
+
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::LatencyP50(Priority::High))), Some(51.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::LatencyP99(Priority::High))), Some(100.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::LatencyP95(Priority::Low))), Some(5_000.0));
+    assert_eq!(monitor.get(exporter.id(CoordinatorMetric::LatencyP50(Priority::Critical))), None);
+
+    let meta = monitor.get_metadata(exporter.id(CoordinatorMetric::LatencyP95(Priority::High))).unwrap();
+    assert_eq!(meta.name, "coordinator.latency.p95{priority=\"high\"}");
+
+    // Executions are sampled once
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(2));
+    let samples = monitor.get_history(exporter.id(CoordinatorMetric::Latency(Priority::High))).unwrap();
+    assert_eq!(samples.len(), 100);
+
+    // Unfinished attempts are picked up when they finish
+    collector.get_or_create(TaskId::new(101)).record_attempt_at(Timestamp(10));
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(3));
+    collector.get_or_create(TaskId::new(101)).record_success_at(Timestamp(17));
+    exporter.export(&mut monitor, &coordinator, &collector, Timestamp(4));
+    let samples = monitor.get_history(exporter.id(CoordinatorMetric::Latency(Priority::Low))).unwrap();
+    assert_eq!(samples.iter().map(|e| e.value).collect::<Vec<_>>(), vec![5_000.0, 7.0]);
+}
+
+#[test]
+fn exports_registered_pools_only() {
+    let mut monitor = Monitor::new();
+    let exporter = MonitorExporter::new(1_000).with_pool("coordinator.pool{name=\"gpu\"}");
+    let mut pool = ResourcePool::new(4);
+    for id in 1..=4 {
+        pool.add_resource(Resource::new(ResourceId::new(id))).unwrap();
+    }
+    pool.acquire(TaskId::new(1));
+
+    exporter.describe(&mut monitor);
+    exporter.export_pool(&mut monitor, "coordinator.pool{name=\"gpu\"}", &pool, Timestamp(0));
+    exporter.export_pool(&mut monitor, "coordinator.pool{name=\"cpu\"}", &pool, Timestamp(0));
+
+    let id = exporter.pool_id("coordinator.pool{name=\"gpu\"}").unwrap();
+    assert_eq!(monitor.get(id), Some(0.25));
+    assert_eq!(monitor.get_metadata(id).unwrap().unit, "ratio");
+    assert_eq!(exporter.pool_id("coordinator.pool{name=\"cpu\"}"), None);
+}
@@ -2,7 +2,8 @@
 use avila_coordinator::{
     Task, TaskId, Scheduler,
     DeadlineScheduler, WeightedScheduler, FifoScheduler,
-    PriorityScheduler, FairScheduler
+    PriorityScheduler, FairScheduler, TimedScheduler,
+    Coordinator, CronSchedule, CronError, Recurrence, Priority, Timestamp, Duration
 };
 
 #[test]
@@ -29,7 +30,8 @@ fn test_deadline_scheduler_with_time() {
     let mut scheduler = DeadlineScheduler::with_time(0);
 
     let task1 = Task::new(TaskId::new(1));
-    let _tasks = vec![task1];    scheduler.set_deadline(TaskId::new(1), 100);
+    let _tasks = [task1];
+    scheduler.set_deadline(TaskId::new(1), 100);
 
     // Check time to deadline
     assert_eq!(scheduler.time_to_deadline(TaskId::new(1)), 100);
@@ -246,8 +248,8 @@ fn test_multiple_pending_tasks_weighted() {
 fn test_all_schedulers_empty_task_list() {
     let mut deadline_scheduler = DeadlineScheduler::new();
     let mut weighted_scheduler = WeightedScheduler::new();
-    let mut fifo_scheduler = FifoScheduler::default();
-    let mut priority_scheduler = PriorityScheduler::default();
+    let mut fifo_scheduler = FifoScheduler;
+    let mut priority_scheduler = PriorityScheduler;
     let mut fair_scheduler = FairScheduler::new();
 
     let tasks: Vec<Task> = vec![];
@@ -294,3 +296,95 @@ fn test_weighted_scheduler_clone() {
 
     assert_eq!(scheduler1.get_weight(TaskId::new(1)), scheduler2.get_weight(TaskId::new(1)));
 }
+
+/// 2026-10-17 12:00:00 UTC, a Saturday
+const SATURDAY_NOON: u64 = 1_792_238_400_000;
+
+#[test]
+fn test_cron_next_after() {
+    let at = |expr: &str, after: u64| CronSchedule::parse(expr).unwrap().next_after(Timestamp(after)).map(|t| t.0);
+
+    assert_eq!(at("30 2 * * *", SATURDAY_NOON), Some(1_792_290_600_000)); // Sunday 02:30
+    assert_eq!(at("*/15 * * * *", SATURDAY_NOON), Some(1_792_239_300_000)); // strictly after: 12:15
+    assert_eq!(at("*/15 9-17 * * 1-5", SATURDAY_NOON + 450_000), Some(1_792_400_400_000)); // Monday 09:00
+    assert_eq!(at("0 0 29 2 *", SATURDAY_NOON), Some(1_835_395_200_000)); // 2028-02-29
+    assert_eq!(at("@yearly", 1_798_761_540_000), Some(1_798_761_600_000)); // 2027-01-01
+    // Day of month and day of week are ORed when both are restricted
+    assert_eq!(at("0 0 13 * *", SATURDAY_NOON), Some(1_794_528_000_000));
+    assert_eq!(at("0 0 13 * 5", SATURDAY_NOON), Some(1_792_713_600_000)); // Friday 10-23
+    assert_eq!(at("0 0 * * 7", SATURDAY_NOON), at("0 0 * * 0", SATURDAY_NOON));
+    assert_eq!(at("0 0 30 2 *", SATURDAY_NOON), None);
+}
+
+#[test]
+fn test_cron_parse_errors() {
+    assert_eq!(CronSchedule::parse("* * * *"), Err(CronError::FieldCount));
+    assert_eq!(CronSchedule::parse("* * * * * *"), Err(CronError::FieldCount));
+    assert_eq!(CronSchedule::parse("60 * * * *"), Err(CronError::InvalidField { field: "minute" }));
+    assert_eq!(CronSchedule::parse("* 5-1 * * *"), Err(CronError::InvalidField { field: "hour" }));
+    assert_eq!(CronSchedule::parse("* * 0 * *"), Err(CronError::InvalidField { field: "day of month" }));
+    assert_eq!(CronSchedule::parse("*/0 * * * *"), Err(CronError::InvalidField { field: "minute" }));
+    assert!("0,30 8-18/2 1,15 */3 mon".parse::<CronSchedule>().is_err());
+    assert!("0,30 8-18/2 1,15 */3 1".parse::<CronSchedule>().is_ok());
+}
+
+#[test]
+fn test_timed_scheduler_delays() {
+    let mut coord = Coordinator::new();
+    let mut scheduler = TimedScheduler::new(Timestamp(1_000));
+
+    coord.submit(1);
+    scheduler.submit_after(&mut coord, 2, Duration(500));
+    scheduler.submit_at(&mut coord, 3, Timestamp(1_200));
+    assert_eq!(scheduler.next_wakeup(), Some(Timestamp(1_200)));
+
+    // Only the undelayed task is runnable
+    assert_eq!(scheduler.next_task(&coord.tasks), Some(TaskId::new(1)));
+    coord.start(1).unwrap();
+    assert_eq!(scheduler.next_task(&coord.tasks), None);
+
+    assert_eq!(scheduler.poll_due_tasks(&mut coord, Timestamp(1_300)), vec![TaskId::new(3)]);
+    assert_eq!(scheduler.next_task(&coord.tasks), Some(TaskId::new(3)));
+
+    // Delayed retry of a failed task
+    coord.complete(1).unwrap();
+    coord.start(3).unwrap();
+    coord.fail(3).unwrap();
+    coord.retry(3).unwrap();
+    scheduler.defer(TaskId::new(3), Duration(1_000));
+    assert_eq!(scheduler.due_at(TaskId::new(3)), Some(Timestamp(2_300)));
+
+    assert_eq!(scheduler.poll_due_tasks(&mut coord, Timestamp(2_400)), vec![TaskId::new(2), TaskId::new(3)]);
+    assert_eq!(scheduler.next_wakeup(), None);
+    assert_eq!(scheduler.next_task(&coord.tasks), Some(TaskId::new(2)));
+}
+
+#[test]
+fn test_timed_scheduler_recurring() {
+    let mut coord = Coordinator::new();
+    let mut scheduler = TimedScheduler::new(Timestamp(SATURDAY_NOON));
+    coord.submit(10);
+
+    let nightly = scheduler.add_recurring(Recurrence::Cron(CronSchedule::parse("30 2 * * *").unwrap()), Priority::Low);
+    let heartbeat = scheduler.add_recurring(Recurrence::Every(Duration(60_000)), Priority::High);
+    assert_eq!(scheduler.next_fire(nightly), Some(Timestamp(1_792_290_600_000)));
+    assert_eq!(scheduler.next_wakeup(), Some(Timestamp(SATURDAY_NOON + 60_000)));
+
+    assert!(scheduler.poll_due_tasks(&mut coord, Timestamp(SATURDAY_NOON + 59_999)).is_empty());
+    let fired = scheduler.poll_due_tasks(&mut coord, Timestamp(SATURDAY_NOON + 60_000));
+    assert_eq!(fired, vec![TaskId::new(11)]);
+    assert_eq!(scheduler.schedule_of(TaskId::new(11)), Some(heartbeat));
+    assert_eq!(coord.get_task(11).unwrap().priority, Priority::High);
+
+    // A long pause fires each schedule once, then continues from now
+    let fired = scheduler.poll_due_tasks(&mut coord, Timestamp(1_792_290_600_000 + 3 * 86_400_000));
+    assert_eq!(fired.len(), 2);
+    assert_eq!(scheduler.schedule_of(fired[0]), Some(heartbeat));
+    assert_eq!(scheduler.schedule_of(fired[1]), Some(nightly));
+    assert_eq!(scheduler.next_fire(nightly), Some(Timestamp(1_792_290_600_000 + 4 * 86_400_000)));
+
+    assert!(scheduler.remove_recurring(heartbeat));
+    assert!(!scheduler.remove_recurring(heartbeat));
+    assert_eq!(scheduler.next_wakeup(), scheduler.next_fire(nightly));
+    assert_eq!(coord.task_count(), 4);
+}
@@ -0,0 +1,330 @@
+//! Integration tests for task persistence and crash recovery
+use avila_coordinator::{
+    AckStatus, Coordinator, Duration, Lease, LeaseError, LeaseId, LeaseOutcome, MemoryStore, PersistentCoordinator,
+    Priority, PriorityScheduler, RecoveryReport, StoreError, StoreRecord, TaskError, TaskId, TaskState, TaskStore,
+    Timestamp, Workflow, WorkflowNode,
+};
+
+const VISIBILITY: Duration = Duration(30_000);
+
+fn pipeline(store: MemoryStore) -> PersistentCoordinator<MemoryStore> {
+    let mut coord = PersistentCoordinator::new(store);
+    coord.submit(1, Priority::High).unwrap();
+    coord.submit(2, Priority::Normal).unwrap();
+    coord.submit(3, Priority::Low).unwrap();
+    coord.add_dependency(2, 1).unwrap();
+    coord.add_dependency(3, 2).unwrap();
+    coord
+}
+
+#[test]
+fn test_recover_resumes_interrupted_tasks() {
+    let mut coord = pipeline(MemoryStore::new());
+    coord.start(1).unwrap();
+    coord.complete(1).unwrap();
+    coord.start(2).unwrap();
+    // Process dies here, with task 2 running
+
+    let recovered = Coordinator::recover(coord.into_store()).unwrap();
+    assert_eq!(recovered.resumed(), &[TaskId::new(2)]);
+    let tasks = recovered.coordinator();
+    assert_eq!(tasks.get_task(1).unwrap().state, TaskState::Completed);
+    assert_eq!(tasks.get_task(2).unwrap().state, TaskState::Pending);
+    assert_eq!(tasks.get_task(1).unwrap().priority, Priority::High);
+    assert_eq!(recovered.ready_tasks(), vec![TaskId::new(2)]);
+    assert_eq!(recovered.dependencies().get_dependencies(TaskId::new(3)), Some(&vec![TaskId::new(2)]));
+
+    // The reset was journaled: a second recovery has nothing to resume
+    let again = Coordinator::recover(recovered.into_store()).unwrap();
+    assert!(again.resumed().is_empty());
+}
+
+#[test]
+fn test_recovery_report() {
+    let mut coord = pipeline(MemoryStore::new());
+    coord.submit(4, Priority::Normal).unwrap();
+    coord.start(4).unwrap();
+    // A lease handed out by the process that is about to die
+    let mut queue = coord.lease_queue(PriorityScheduler, VISIBILITY);
+    let leases = coord.lease(&mut queue, "worker-a", 1, Timestamp(0)).unwrap();
+    assert_eq!(leases[0].task, TaskId::new(1));
+
+    let mut store = coord.into_store();
+    // Records that do not fit the journal: unknown tasks, a second submit
+    store.append(&StoreRecord::State { task: TaskId::new(9), state: TaskState::Completed }).unwrap();
+    store.append(&StoreRecord::Submitted { task: TaskId::new(2), priority: Priority::Critical }).unwrap();
+    store.append(&StoreRecord::Retry { task: TaskId::new(9), attempt: 1 }).unwrap();
+    store.append(&StoreRecord::Dependency { task: TaskId::new(3), depends_on: TaskId::new(3) }).unwrap();
+    store.append(&StoreRecord::Removed { task: TaskId::new(8) }).unwrap();
+
+    let mut recovered = Coordinator::recover(store).unwrap();
+    let report = recovered.report();
+    assert_eq!(report.records_applied, 8);
+    assert_eq!(report.records_dropped, 5);
+    assert_eq!(report.tasks_reset, vec![TaskId::new(4)]);
+    assert_eq!(report.leases_expired, leases);
+    assert!(!report.is_clean());
+    assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Pending);
+    assert_eq!(recovered.coordinator().get_task(2).unwrap().priority, Priority::Normal);
+    assert_eq!(recovered.coordinator().task_count(), 4);
+
+    // The worker acks late; the new queue never reuses its lease id
+    let mut queue = recovered.lease_queue(PriorityScheduler, VISIBILITY);
+    assert_eq!(
+        recovered.ack(&mut queue, leases[0].id, LeaseOutcome::Completed, Timestamp(1)),
+        Err(StoreError::Lease(LeaseError::Expired))
+    );
+    let retaken = recovered.lease(&mut queue, "worker-b", 1, Timestamp(2)).unwrap();
+    assert_eq!(retaken[0].task, TaskId::new(1));
+    assert!(retaken[0].id > leases[0].id);
+
+    // The dropped records stay in the journal; only the new lease is left to expire
+    let again = Coordinator::recover(recovered.into_store()).unwrap();
+    assert_eq!(again.report().tasks_reset, Vec::new());
+    assert_eq!(again.report().records_dropped, 5);
+    assert_eq!(again.report().leases_expired, retaken);
+}
+
+#[test]
+fn test_leases_are_journaled() {
+    let mut coord = pipeline(MemoryStore::new());
+    let mut queue = coord.lease_queue(PriorityScheduler, VISIBILITY);
+    let first = coord.lease(&mut queue, "worker-a", 1, Timestamp(0)).unwrap();
+    assert_eq!(coord.ack(&mut queue, first[0].id, LeaseOutcome::Completed, Timestamp(1_000)), Ok(AckStatus::Applied));
+    assert_eq!(coord.ack(&mut queue, first[0].id, LeaseOutcome::Completed, Timestamp(1_000)), Ok(AckStatus::Duplicate));
+
+    // Task 2 times out once and goes to another worker
+    let second = coord.lease(&mut queue, "worker-a", 1, Timestamp(2_000)).unwrap();
+    assert_eq!(coord.expire_leases(&mut queue, Timestamp(40_000)).unwrap(), second);
+    let third = coord.lease(&mut queue, "worker-b", 1, Timestamp(41_000)).unwrap();
+    assert_eq!(third[0].task, TaskId::new(2));
+    assert_eq!(coord.leases().cloned().collect::<Vec<_>>(), third);
+
+    coord.compact().unwrap();
+    let mut recovered = Coordinator::recover(coord.into_store()).unwrap();
+    assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Completed);
+    assert_eq!(recovered.coordinator().get_task(2).unwrap().state, TaskState::Pending);
+    assert_eq!(recovered.report().leases_expired, third);
+    assert!(recovered.report().tasks_reset.is_empty());
+    assert_eq!(recovered.leases().count(), 0);
+
+    let mut queue = recovered.lease_queue(PriorityScheduler, VISIBILITY);
+    let fourth = recovered.lease(&mut queue, "worker-c", 1, Timestamp(50_000)).unwrap();
+    assert_eq!(fourth[0].task, TaskId::new(2));
+    assert!(fourth[0].id > third[0].id);
+}
+
+#[test]
+fn test_clean_recovery() {
+    let coord = pipeline(MemoryStore::new());
+    let recovered = Coordinator::recover(coord.into_store()).unwrap();
+    assert_eq!(recovered.report(), &RecoveryReport { records_applied: 5, ..RecoveryReport::default() });
+    assert!(recovered.report().is_clean());
+}
+
+#[test]
+fn test_retry_counters_and_validation() {
+    let mut coord = pipeline(MemoryStore::new());
+    assert_eq!(coord.complete(1), Err(StoreError::Task(TaskError::InvalidTransition { from: "Pending", to: "Completed" })));
+    assert_eq!(coord.submit(1, Priority::Low), Err(StoreError::Task(TaskError::DuplicateId)));
+    assert_eq!(coord.add_dependency(1, 3), Err(StoreError::Task(TaskError::CircularDependency)));
+    assert_eq!(coord.start(9), Err(StoreError::Task(TaskError::NotFound)));
+    let journaled = coord.store().records().len();
+
+    coord.start(1).unwrap();
+    coord.fail(1).unwrap();
+    assert_eq!(coord.retry(1), Ok(1));
+    coord.start(1).unwrap();
+    coord.fail(1).unwrap();
+    assert_eq!(coord.retry(1), Ok(2));
+    assert_eq!(coord.store().records().len(), journaled + 8);
+
+    let recovered = Coordinator::recover(coord.into_store()).unwrap();
+    assert_eq!(recovered.retry_count(TaskId::new(1)), 2);
+    assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Pending);
+}
+
+#[test]
+fn test_workflows_and_compaction() {
+    let mut coord = pipeline(MemoryStore::new());
+    let mut workflow = Workflow::new("ifc import; model A".to_string());
+    workflow.add_node(WorkflowNode::new(TaskId::new(1)).with_name("parse=IFC".to_string())).unwrap();
+    workflow.add_node(WorkflowNode::new(TaskId::new(2))).unwrap();
+    workflow.add_node(WorkflowNode::new(TaskId::new(3)).with_name(String::new())).unwrap();
+    workflow.add_edge(TaskId::new(1), TaskId::new(2)).unwrap();
+    workflow.add_edge(TaskId::new(1), TaskId::new(3)).unwrap();
+    workflow.add_edge(TaskId::new(2), TaskId::new(3)).unwrap();
+    coord.add_workflow(&workflow).unwrap();
+
+    coord.start(1).unwrap();
+    coord.fail(1).unwrap();
+    coord.retry(1).unwrap();
+    coord.start(1).unwrap();
+    coord.complete(1).unwrap();
+    coord.submit(4, Priority::Normal).unwrap();
+    coord.remove_task(4).unwrap();
+
+    let before = coord.store().records().len();
+    coord.compact().unwrap();
+    assert!(coord.store().records().len() < before);
+
+    let recovered = Coordinator::recover(coord.into_store()).unwrap();
+    assert_eq!(recovered.coordinator().task_count(), 3);
+    assert_eq!(recovered.retry_count(TaskId::new(1)), 1);
+    assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Completed);
+    assert_eq!(recovered.ready_tasks(), vec![TaskId::new(2)]);
+
+    let restored = recovered.workflow("ifc import; model A").unwrap();
+    assert_eq!(restored.nodes(), workflow.nodes());
+    assert_eq!(restored.execution_order().unwrap(), workflow.execution_order().unwrap());
+}
+
+#[test]
+fn test_record_text_round_trip() {
+    let mut node = WorkflowNode::new(TaskId::new(7)).with_name("a%b,c:d\ne".to_string());
+    node.add_dependency(TaskId::new(5));
+    node.add_dependency(TaskId::new(6));
+    let records = [
+        StoreRecord::Submitted { task: TaskId::new(1), priority: Priority::Critical },
+        StoreRecord::State { task: TaskId::new(1), state: TaskState::Cancelled },
+        StoreRecord::Retry { task: TaskId::new(1), attempt: 3 },
+        StoreRecord::Dependency { task: TaskId::new(2), depends_on: TaskId::new(1) },
+        StoreRecord::Workflow { name: "conversão".to_string(), nodes: vec![node] },
+        StoreRecord::Workflow { name: String::new(), nodes: Vec::new() },
+        StoreRecord::Removed { task: TaskId::new(2) },
+        StoreRecord::Leased {
+            lease: Lease {
+                id: LeaseId(4),
+                task: TaskId::new(1),
+                worker: "host a:9%".to_string(),
+                attempt: 2,
+                expires_at: Timestamp(31_000),
+            },
+        },
+        StoreRecord::LeaseAcked { lease: LeaseId(4), outcome: LeaseOutcome::Released },
+        StoreRecord::LeaseExpired { lease: LeaseId(4) },
+        StoreRecord::NextLease { lease: LeaseId(5) },
+    ];
+    for record in records {
+        let line = record.encode();
+        assert!(!line.contains('\n'));
+        assert_eq!(StoreRecord::decode(&line), Some(record));
+    }
+    assert_eq!(StoreRecord::decode("state 1 sleeping"), None);
+    assert_eq!(StoreRecord::decode("remove 1 2"), None);
+}
+
+#[cfg(feature = "std")]
+mod file_store {
+    use super::*;
+    use avila_coordinator::FileStore;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn journal(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("avila-coordinator-{}-{}.wal", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_store_survives_restart() {
+        let path = journal("restart");
+        let mut coord = PersistentCoordinator::new(FileStore::open(&path).unwrap());
+        coord.submit(1, Priority::Normal).unwrap();
+        coord.submit(2, Priority::Normal).unwrap();
+        coord.add_dependency(2, 1).unwrap();
+        coord.start(1).unwrap();
+        drop(coord);
+
+        let mut recovered = Coordinator::recover(FileStore::open(&path).unwrap()).unwrap();
+        assert_eq!(recovered.resumed(), &[TaskId::new(1)]);
+        recovered.start(1).unwrap();
+        recovered.complete(1).unwrap();
+        recovered.compact().unwrap();
+        recovered.start(2).unwrap();
+        drop(recovered);
+
+        let recovered = Coordinator::recover(FileStore::open(&path).unwrap()).unwrap();
+        assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Completed);
+        assert_eq!(recovered.resumed(), &[TaskId::new(2)]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_drops_torn_tail() {
+        let path = journal("torn");
+        let mut coord = PersistentCoordinator::new(FileStore::open(&path).unwrap().without_sync());
+        coord.submit(1, Priority::Normal).unwrap();
+        coord.start(1).unwrap();
+        drop(coord);
+
+        // Crash halfway through writing the next record
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"1c0ffee0 state 1 comp").unwrap();
+
+        let mut recovered = Coordinator::recover(FileStore::open(&path).unwrap()).unwrap();
+        assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Pending);
+        assert_eq!(recovered.report().records_dropped, 1);
+        recovered.start(1).unwrap();
+        recovered.complete(1).unwrap();
+        drop(recovered);
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("comp\n") && !text.contains("1c0ffee0"));
+        let mut store = FileStore::open(&path).unwrap();
+        assert_eq!(store.load().unwrap().last(), Some(&StoreRecord::State { task: TaskId::new(1), state: TaskState::Completed }));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_drops_torn_tail_of_several_records() {
+        let path = journal("torn-several");
+        let mut coord = PersistentCoordinator::new(FileStore::open(&path).unwrap().without_sync());
+        coord.submit(1, Priority::Normal).unwrap();
+        coord.submit(2, Priority::Normal).unwrap();
+        coord.start(1).unwrap();
+        coord.complete(1).unwrap();
+        drop(coord);
+
+        // Unsynced appends lost: the last two records are zeroed, and the
+        // one after them is cut short
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let mut torn = format!("{}\n{}\n", lines[0], lines[1]);
+        for line in &lines[2..] {
+            torn.push_str(&"\0".repeat(line.len()));
+            torn.push('\n');
+        }
+        torn.push_str("1c0ffee0 remo");
+        fs::write(&path, torn).unwrap();
+
+        let recovered = Coordinator::recover(FileStore::open(&path).unwrap()).unwrap();
+        assert_eq!(recovered.report().records_applied, 2);
+        assert_eq!(recovered.report().records_dropped, 3);
+        assert_eq!(recovered.coordinator().get_task(1).unwrap().state, TaskState::Pending);
+        drop(recovered);
+
+        let mut store = FileStore::open(&path).unwrap();
+        assert_eq!(store.load().unwrap().len(), 2);
+        assert_eq!(store.torn(), 0);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_reports_corruption() {
+        let path = journal("corrupt");
+        let mut coord = PersistentCoordinator::new(FileStore::open(&path).unwrap().without_sync());
+        coord.submit(1, Priority::Normal).unwrap();
+        coord.submit(2, Priority::Normal).unwrap();
+        drop(coord);
+
+        let text = fs::read_to_string(&path).unwrap().replacen("submit 1", "submit 7", 1);
+        fs::write(&path, text).unwrap();
+        assert_eq!(
+            Coordinator::recover(FileStore::open(&path).unwrap()).err(),
+            Some(StoreError::Corrupted { line: 1 })
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
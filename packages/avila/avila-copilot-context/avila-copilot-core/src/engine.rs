@@ -1,6 +1,6 @@
 // Main Copilot engine - orchestrates all layers
 
-use crate::{CopilotConfig, CopilotError, Result, MAX_LATENCY_MS};
+use crate::{CancellationToken, CopilotConfig, CopilotError, Result, MAX_LATENCY_MS};
 use avila_copilot_context::ContextManager;
 use avila_copilot_inference::InferenceEngine;
 use avila_copilot_intelligence::CodeIntelligence;
@@ -10,7 +10,10 @@ use std::sync::Arc;
 use std::time::Instant;
 
 // Re-export types from intelligence
-pub use avila_copilot_intelligence::{Bug, BugSeverity, Refactoring, RefactoringKind};
+pub use avila_copilot_intelligence::{
+    Bug, BugSeverity, CodeEdit, CodeLocation, InlayHint, InlayHintKind, Refactoring, RefactoringKind, SemanticToken,
+    TokenKind, TokenModifier,
+};
 
 /// Main Copilot engine coordinating all 7 layers
 pub struct CopilotEngine {
@@ -76,18 +79,32 @@ impl CopilotEngine {
 
     /// Generate code completion with latency guarantee
     pub async fn complete(&self, input: &str, cursor_position: usize) -> Result<Completion> {
+        self.complete_cancellable(input, cursor_position, &CancellationToken::new()).await
+    }
+
+    /// Generate code completion, stopping between layers once `cancel` fires
+    pub async fn complete_cancellable(
+        &self,
+        input: &str,
+        cursor_position: usize,
+        cancel: &CancellationToken,
+    ) -> Result<Completion> {
         let start = Instant::now();
 
         // Get context
+        cancel.check()?;
         let context = self.context_manager.get_context(input, cursor_position).await;
 
         // Tokenize
+        cancel.check()?;
         let tokens = self.tokenizer.encode(&context)?;
 
         // Run inference
+        cancel.check()?;
         let output = self.inference_engine.infer(&tokens).await?;
 
         // Decode result
+        cancel.check()?;
         let completion_text = self.tokenizer.decode(&output)?;
 
         let latency_ms = start.elapsed().as_millis() as u64;
@@ -112,6 +129,24 @@ impl CopilotEngine {
         Ok(self.code_intelligence.detect_bugs(code).await?)
     }
 
+    /// Detect bugs in code unless `cancel` already fired
+    pub async fn detect_bugs_cancellable(&self, code: &str, cancel: &CancellationToken) -> Result<Vec<Bug>> {
+        cancel.check()?;
+        let bugs = self.detect_bugs(code).await?;
+        cancel.check()?;
+        Ok(bugs)
+    }
+
+    /// Classify tokens for semantic highlighting
+    pub async fn semantic_tokens(&self, code: &str) -> Result<Vec<SemanticToken>> {
+        Ok(self.code_intelligence.semantic_tokens(code).await?)
+    }
+
+    /// Inferred types and parameter names to show inline
+    pub async fn inlay_hints(&self, code: &str) -> Result<Vec<InlayHint>> {
+        Ok(self.code_intelligence.inlay_hints(code).await?)
+    }
+
     /// Generate documentation
     pub async fn generate_docs(&self, code: &str) -> Result<String> {
         Ok(self.code_intelligence.generate_documentation(code).await?)
@@ -126,6 +161,23 @@ impl CopilotEngine {
     pub async fn suggest_refactorings(&self, code: &str) -> Result<Vec<Refactoring>> {
         Ok(self.code_intelligence.suggest_refactorings(code).await?)
     }
+
+    /// Text edits that perform a suggested refactoring
+    pub fn refactoring_edits(&self, code: &str, refactoring: &Refactoring) -> Vec<CodeEdit> {
+        self.code_intelligence.refactoring_edits(code, refactoring)
+    }
+
+    /// Suggest refactorings unless `cancel` already fired
+    pub async fn suggest_refactorings_cancellable(
+        &self,
+        code: &str,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Refactoring>> {
+        cancel.check()?;
+        let refactorings = self.suggest_refactorings(code).await?;
+        cancel.check()?;
+        Ok(refactorings)
+    }
 }
 
 /// Code completion result
@@ -20,6 +20,8 @@ pub enum CopilotError {
     ConfigError(String),
     /// Latency exceeded
     LatencyExceeded { actual_ms: u64, max_ms: u64 },
+    /// Call cancelled through its `CancellationToken`
+    Cancelled,
 }
 
 impl fmt::Display for CopilotError {
@@ -34,6 +36,7 @@ impl fmt::Display for CopilotError {
             Self::LatencyExceeded { actual_ms, max_ms } => {
                 write!(f, "Latency exceeded: {}ms > {}ms", actual_ms, max_ms)
             }
+            Self::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
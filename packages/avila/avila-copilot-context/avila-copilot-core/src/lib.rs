@@ -7,16 +7,21 @@ use avila_copilot_intelligence::CodeIntelligence;
 use avila_copilot_model_storage::ModelStorage;
 use avila_copilot_tokenizer::CopilotTokenizer;
 
+pub mod cancel;
 pub mod config;
 pub mod engine;
 pub mod error;
 pub mod metrics;
 
+pub use cancel::CancellationToken;
 pub use engine::CopilotEngine;
 pub use error::{CopilotError, Result};
 
 // Re-export types from engine (which re-exports from intelligence)
-pub use engine::{Bug, BugSeverity, Completion, Refactoring, RefactoringKind};
+pub use engine::{
+    Bug, BugSeverity, CodeEdit, CodeLocation, Completion, InlayHint, InlayHintKind, Refactoring, RefactoringKind,
+    SemanticToken, TokenKind, TokenModifier,
+};
 
 /// Performance targets
 pub const MAX_LATENCY_MS: u64 = 50;
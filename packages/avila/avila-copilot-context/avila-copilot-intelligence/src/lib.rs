@@ -8,12 +8,14 @@ pub mod bug_detector;
 pub mod doc_generator;
 pub mod error;
 pub mod refactoring;
+pub mod semantics;
 pub mod test_generator;
 
 pub use bug_detector::BugDetector;
 pub use doc_generator::DocGenerator;
 pub use error::{IntelligenceError, Result};
 pub use refactoring::RefactoringEngine;
+pub use semantics::{InlayHint, InlayHintKind, SemanticAnalyzer, SemanticToken, TokenKind, TokenModifier};
 pub use test_generator::TestGenerator;
 
 /// Code intelligence system
@@ -23,6 +25,7 @@ pub struct CodeIntelligence {
     doc_generator: Arc<DocGenerator>,
     test_generator: Arc<TestGenerator>,
     refactoring_engine: Arc<RefactoringEngine>,
+    semantic_analyzer: Arc<SemanticAnalyzer>,
 }
 
 impl CodeIntelligence {
@@ -32,6 +35,7 @@ impl CodeIntelligence {
         let doc_generator = Arc::new(DocGenerator::new());
         let test_generator = Arc::new(TestGenerator::new());
         let refactoring_engine = Arc::new(RefactoringEngine::new());
+        let semantic_analyzer = Arc::new(SemanticAnalyzer::new());
 
         Ok(Self {
             context_manager,
@@ -39,6 +43,7 @@ impl CodeIntelligence {
             doc_generator,
             test_generator,
             refactoring_engine,
+            semantic_analyzer,
         })
     }
 
@@ -67,6 +72,21 @@ impl CodeIntelligence {
         self.refactoring_engine.apply(code, refactoring).await
     }
 
+    /// Text edits that perform a refactoring (empty if it cannot be automated)
+    pub fn refactoring_edits(&self, code: &str, refactoring: &Refactoring) -> Vec<CodeEdit> {
+        self.refactoring_engine.edits(code, refactoring)
+    }
+
+    /// Classify tokens for semantic highlighting
+    pub async fn semantic_tokens(&self, code: &str) -> Result<Vec<SemanticToken>> {
+        self.semantic_analyzer.tokens(code).await
+    }
+
+    /// Inferred types and parameter names to show inline
+    pub async fn inlay_hints(&self, code: &str) -> Result<Vec<InlayHint>> {
+        self.semantic_analyzer.inlay_hints(code).await
+    }
+
     /// Get code complexity metrics
     pub fn analyze_complexity(&self, code: &str) -> Result<ComplexityMetrics> {
         Ok(ComplexityMetrics {
@@ -141,6 +161,13 @@ pub struct CodeLocation {
     pub end_column: usize,
 }
 
+/// Replacement of the text at `location`, in coordinates of the original code
+#[derive(Debug, Clone)]
+pub struct CodeEdit {
+    pub location: CodeLocation,
+    pub new_text: String,
+}
+
 /// Code complexity metrics
 #[derive(Debug, Clone)]
 pub struct ComplexityMetrics {
@@ -1,6 +1,6 @@
 // Refactoring engine
 
-use crate::{CodeLocation, Refactoring, RefactoringKind, Result};
+use crate::{CodeEdit, CodeLocation, Refactoring, RefactoringKind, Result};
 use std::sync::Arc;
 
 /// Refactoring suggestion engine
@@ -33,10 +33,52 @@ impl RefactoringEngine {
         match refactoring.kind {
             RefactoringKind::ExtractFunction => self.apply_extract_function(code, refactoring),
             RefactoringKind::SimplifyExpression => self.apply_simplify_expression(code, refactoring),
+            RefactoringKind::ExtractVariable => Ok(apply_edits(code, &self.edits(code, refactoring))),
             _ => Ok(code.to_string()),
         }
     }
 
+    /// Concrete edits for a refactoring, all relative to `code` as given
+    ///
+    /// Only refactorings with a mechanical rewrite produce edits; the rest
+    /// stay suggestions.
+    pub fn edits(&self, code: &str, refactoring: &Refactoring) -> Vec<CodeEdit> {
+        match refactoring.kind {
+            RefactoringKind::ExtractVariable => self.extract_condition_edits(code, refactoring),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Moves an `if` condition into a `let condition = ...;` line above it
+    fn extract_condition_edits(&self, code: &str, refactoring: &Refactoring) -> Vec<CodeEdit> {
+        let line_num = refactoring.location.start_line;
+        let Some(line) = code.lines().nth(line_num) else { return Vec::new() };
+        let Some(if_start) = line.find("if ") else { return Vec::new() };
+        let cond_start = if_start + "if ".len();
+        let Some(brace) = line.rfind('{').filter(|brace| *brace > cond_start) else { return Vec::new() };
+        let condition = line[cond_start..brace].trim_end();
+        if condition.is_empty() || condition.starts_with("let ") {
+            return Vec::new();
+        }
+        let indent = &line[..line.len() - line.trim_start().len()];
+
+        vec![
+            CodeEdit {
+                location: CodeLocation { start_line: line_num, start_column: 0, end_line: line_num, end_column: 0 },
+                new_text: format!("{}let condition = {};\n", indent, condition),
+            },
+            CodeEdit {
+                location: CodeLocation {
+                    start_line: line_num,
+                    start_column: cond_start,
+                    end_line: line_num,
+                    end_column: cond_start + condition.len(),
+                },
+                new_text: "condition".to_string(),
+            },
+        ]
+    }
+
     fn detect_long_functions(&self, code: &str) -> Vec<Refactoring> {
         let mut refactorings = Vec::new();
         let mut in_function = false;
@@ -112,6 +154,24 @@ impl RefactoringEngine {
     }
 }
 
+/// Applies non-overlapping edits (byte columns) to `code`
+fn apply_edits(code: &str, edits: &[CodeEdit]) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let offset = |line: usize, column: usize| line_starts.get(line).map_or(code.len(), |start| start + column);
+
+    let mut sorted: Vec<&CodeEdit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| std::cmp::Reverse(offset(edit.location.start_line, edit.location.start_column)));
+    let mut result = code.to_string();
+    for edit in sorted {
+        let start = offset(edit.location.start_line, edit.location.start_column);
+        let end = offset(edit.location.end_line, edit.location.end_column);
+        result.replace_range(start..end, &edit.new_text);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +191,21 @@ mod tests {
         let refactorings = engine.suggest(&code).await.unwrap();
         assert!(!refactorings.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_extract_condition() {
+        let engine = RefactoringEngine::new();
+        let condition = "user.is_active() && user.has_permission(Permission::Write) && !document.is_locked() && quota.remaining() > 0";
+        let code = format!("fn save() {{\n    if {} {{\n        write();\n    }}\n}}\n", condition);
+
+        let refactorings = engine.suggest(&code).await.unwrap();
+        let extract = refactorings.iter().find(|r| r.kind == RefactoringKind::ExtractVariable).unwrap();
+        assert_eq!(engine.edits(&code, extract).len(), 2);
+
+        let applied = engine.apply(&code, extract).await.unwrap();
+        assert_eq!(
+            applied,
+            format!("fn save() {{\n    let condition = {};\n    if condition {{\n        write();\n    }}\n}}\n", condition)
+        );
+    }
 }
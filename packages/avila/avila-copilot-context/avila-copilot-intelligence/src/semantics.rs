@@ -0,0 +1,539 @@
+// Semantic token classification and inlay hints
+
+use crate::Result;
+use std::collections::HashMap;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "class", "const", "continue", "crate", "def", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "from", "function", "if", "impl", "import", "in", "let", "loop", "match",
+    "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "var", "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64",
+];
+
+/// Lexical/semantic analyzer behind semantic highlighting and inlay hints
+///
+/// Heuristic and line based like the rest of this layer: it tracks
+/// declarations (functions, parameters, locals) within the current function
+/// so later uses are classified consistently, without a full parse.
+pub struct SemanticAnalyzer {
+    // Analyzer state
+}
+
+impl SemanticAnalyzer {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Classifies every token of `code`, in document order
+    pub async fn tokens(&self, code: &str) -> Result<Vec<SemanticToken>> {
+        Ok(Lexer::default().run(code))
+    }
+
+    /// Type hints for unannotated `let` bindings and parameter-name hints
+    /// for calls to functions declared in `code`
+    pub async fn inlay_hints(&self, code: &str) -> Result<Vec<InlayHint>> {
+        let signatures = function_signatures(code);
+        let mut hints = Vec::new();
+
+        for (line_num, line) in code.lines().enumerate() {
+            if let Some(hint) = let_type_hint(line_num, line) {
+                hints.push(hint);
+            }
+            hints.extend(parameter_hints(line_num, line, &signatures));
+        }
+
+        Ok(hints)
+    }
+}
+
+impl Default for SemanticAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One classified token; `line` is 0-based, `start`/`length` are bytes within the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub line: usize,
+    pub start: usize,
+    pub length: usize,
+    pub kind: TokenKind,
+    pub modifiers: Vec<TokenModifier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Namespace,
+    Type,
+    Function,
+    Method,
+    Macro,
+    Parameter,
+    Variable,
+    Property,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenModifier {
+    Declaration,
+    Mutable,
+    Documentation,
+}
+
+/// Inline annotation; `line` is 0-based, `column` is a byte offset within the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    pub line: usize,
+    pub column: usize,
+    pub label: String,
+    pub kind: InlayHintKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+    Type,
+    Parameter,
+}
+
+#[derive(Default)]
+struct Lexer {
+    tokens: Vec<SemanticToken>,
+    in_block_comment: bool,
+    /// Declared names of the current function and what they were declared as
+    locals: HashMap<String, (TokenKind, bool)>,
+    /// Parenthesis depth inside a function signature's parameter list
+    signature_depth: Option<usize>,
+    awaiting_signature: bool,
+}
+
+impl Lexer {
+    fn run(mut self, code: &str) -> Vec<SemanticToken> {
+        for (line_num, line) in code.lines().enumerate() {
+            self.line(line_num, line);
+        }
+        self.tokens
+    }
+
+    fn push(&mut self, line: usize, start: usize, end: usize, kind: TokenKind, modifiers: Vec<TokenModifier>) {
+        if end > start {
+            self.tokens.push(SemanticToken { line, start, length: end - start, kind, modifiers });
+        }
+    }
+
+    fn line(&mut self, line_num: usize, line: &str) {
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        let mut prev_word: Option<&str> = None;
+        let mut let_mut = false;
+
+        while i < bytes.len() {
+            if self.in_block_comment {
+                let end = line[i..].find("*/").map(|p| i + p + 2);
+                self.in_block_comment = end.is_none();
+                let end = end.unwrap_or(bytes.len());
+                self.push(line_num, i, end, TokenKind::Comment, Vec::new());
+                i = end;
+                continue;
+            }
+
+            let c = bytes[i];
+            if c.is_ascii_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            if line[i..].starts_with("//") {
+                let doc = line[i..].starts_with("///") || line[i..].starts_with("//!");
+                let modifiers = if doc { vec![TokenModifier::Documentation] } else { Vec::new() };
+                self.push(line_num, i, bytes.len(), TokenKind::Comment, modifiers);
+                return;
+            }
+            if line[i..].starts_with("/*") {
+                self.in_block_comment = true;
+                continue;
+            }
+
+            if c == b'"' {
+                let end = string_end(bytes, i);
+                self.push(line_num, i, end, TokenKind::String, Vec::new());
+                prev_word = None;
+                i = end;
+                continue;
+            }
+
+            if c == b'\'' {
+                // Char literal ('a', '\n'); otherwise a lifetime, left unclassified
+                match char_literal_end(line, i) {
+                    Some(end) => {
+                        self.push(line_num, i, end, TokenKind::String, Vec::new());
+                        i = end;
+                    }
+                    None => i = ident_end(bytes, i + 1),
+                }
+                prev_word = None;
+                continue;
+            }
+
+            if c.is_ascii_digit() {
+                let mut end = i;
+                while end < bytes.len()
+                    && (bytes[end].is_ascii_alphanumeric()
+                        || bytes[end] == b'_'
+                        || (bytes[end] == b'.' && bytes.get(end + 1).is_some_and(u8::is_ascii_digit)))
+                {
+                    end += 1;
+                }
+                self.push(line_num, i, end, TokenKind::Number, Vec::new());
+                prev_word = None;
+                i = end;
+                continue;
+            }
+
+            if c.is_ascii_alphabetic() || c == b'_' {
+                let end = ident_end(bytes, i);
+                let word = &line[i..end];
+                let (kind, modifiers) = self.classify(line, i, end, prev_word, let_mut);
+                self.push(line_num, i, end, kind, modifiers);
+                let_mut = word == "mut" && prev_word == Some("let");
+                if word != "mut" || !let_mut {
+                    prev_word = Some(word);
+                }
+                i = end;
+                continue;
+            }
+
+            // Punctuation (and any non-ASCII character)
+            match c {
+                b'(' => {
+                    if self.awaiting_signature {
+                        self.awaiting_signature = false;
+                        self.signature_depth = Some(1);
+                    } else if let Some(depth) = self.signature_depth.as_mut() {
+                        *depth += 1;
+                    }
+                }
+                b')' => {
+                    if let Some(depth) = self.signature_depth {
+                        self.signature_depth = depth.checked_sub(1).filter(|d| *d > 0);
+                    }
+                }
+                b'{' | b';' => self.awaiting_signature = false,
+                _ => {}
+            }
+            prev_word = None;
+            i += line[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    fn classify(
+        &mut self,
+        line: &str,
+        start: usize,
+        end: usize,
+        prev_word: Option<&str>,
+        let_mut: bool,
+    ) -> (TokenKind, Vec<TokenModifier>) {
+        let word = &line[start..end];
+        let rest = line[end..].trim_start();
+        let before = line[..start].trim_end();
+
+        if KEYWORDS.contains(&word) {
+            if matches!(word, "fn" | "function" | "def") {
+                self.locals.clear();
+                self.signature_depth = None;
+            }
+            return (TokenKind::Keyword, Vec::new());
+        }
+
+        match prev_word {
+            Some("fn" | "function" | "def") => {
+                self.awaiting_signature = true;
+                return (TokenKind::Function, vec![TokenModifier::Declaration]);
+            }
+            Some("struct" | "enum" | "trait" | "type" | "class" | "union") => {
+                return (TokenKind::Type, vec![TokenModifier::Declaration]);
+            }
+            Some("mod") => return (TokenKind::Namespace, vec![TokenModifier::Declaration]),
+            Some("let" | "var" | "const" | "static") => {
+                self.locals.insert(word.to_string(), (TokenKind::Variable, let_mut));
+                let mut modifiers = vec![TokenModifier::Declaration];
+                if let_mut {
+                    modifiers.push(TokenModifier::Mutable);
+                }
+                return (TokenKind::Variable, modifiers);
+            }
+            _ => {}
+        }
+
+        if self.signature_depth == Some(1) && rest.starts_with(':') && !rest.starts_with("::") {
+            let mutable = prev_word == Some("mut");
+            self.locals.insert(word.to_string(), (TokenKind::Parameter, mutable));
+            let mut modifiers = vec![TokenModifier::Declaration];
+            if mutable {
+                modifiers.push(TokenModifier::Mutable);
+            }
+            return (TokenKind::Parameter, modifiers);
+        }
+
+        if rest.starts_with('!') && !rest.starts_with("!=") {
+            return (TokenKind::Macro, Vec::new());
+        }
+        if before.ends_with('.') && !before.ends_with("..") {
+            let kind = if rest.starts_with('(') { TokenKind::Method } else { TokenKind::Property };
+            return (kind, Vec::new());
+        }
+        if rest.starts_with('(') {
+            return (TokenKind::Function, Vec::new());
+        }
+        if let Some(&(kind, mutable)) = self.locals.get(word) {
+            let modifiers = if mutable { vec![TokenModifier::Mutable] } else { Vec::new() };
+            return (kind, modifiers);
+        }
+        let capitalized = word.starts_with(|c: char| c.is_ascii_uppercase());
+        if PRIMITIVES.contains(&word) {
+            return (TokenKind::Type, Vec::new());
+        }
+        if rest.starts_with("::") && !capitalized {
+            return (TokenKind::Namespace, Vec::new());
+        }
+        if capitalized {
+            return (TokenKind::Type, Vec::new());
+        }
+        (TokenKind::Variable, Vec::new())
+    }
+}
+
+fn ident_end(bytes: &[u8], start: usize) -> usize {
+    let mut end = start;
+    while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+        end += 1;
+    }
+    end
+}
+
+/// End of a string literal starting at `start`, or the end of the line if unterminated
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn char_literal_end(line: &str, start: usize) -> Option<usize> {
+    let rest = &line[start + 1..];
+    let mut chars = rest.char_indices();
+    let (_, first) = chars.next()?;
+    let (_, next) = if first == '\\' { chars.nth(1)? } else { chars.next()? };
+    if next != '\'' {
+        return None;
+    }
+    let skip = first.len_utf8();
+    let close = rest[skip..].find('\'')? + skip;
+    Some(start + 1 + close + 1)
+}
+
+/// Parameter names of each function declared in `code`
+fn function_signatures(code: &str) -> HashMap<String, Vec<String>> {
+    let mut signatures = HashMap::new();
+    for line in code.lines() {
+        let Some(pos) = line.find("fn ") else { continue };
+        if pos > 0 && !line[..pos].ends_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+        let after = &line[pos + 3..];
+        let Some(open) = after.find('(') else { continue };
+        let name = after[..open].split('<').next().unwrap_or("").trim();
+        let Some(close) = after[open..].find(')') else { continue };
+        let params = after[open + 1..open + close]
+            .split(',')
+            .filter_map(|param| {
+                let (name, _) = param.split_once(':')?;
+                let name = name.trim().trim_start_matches("mut ").trim();
+                (!name.is_empty()).then(|| name.to_string())
+            })
+            .collect();
+        if !name.is_empty() {
+            signatures.insert(name.to_string(), params);
+        }
+    }
+    signatures
+}
+
+/// `: T` after the name of an unannotated `let` whose initializer has an obvious type
+fn let_type_hint(line_num: usize, line: &str) -> Option<InlayHint> {
+    let let_pos = line.find("let ")?;
+    if let_pos > 0 && !line[..let_pos].ends_with(|c: char| c.is_whitespace()) {
+        return None;
+    }
+    let after_let = let_pos + 4;
+    let name_start = if line[after_let..].starts_with("mut ") { after_let + 4 } else { after_let };
+    let name_end = ident_end(line.as_bytes(), name_start);
+    if name_end == name_start {
+        return None;
+    }
+    let rest = line[name_end..].trim_start();
+    let init = rest.strip_prefix('=')?.trim().trim_end_matches(';').trim();
+    let ty = literal_type(init)?;
+
+    Some(InlayHint {
+        line: line_num,
+        column: name_end,
+        label: format!(": {}", ty),
+        kind: InlayHintKind::Type,
+    })
+}
+
+fn literal_type(init: &str) -> Option<String> {
+    const SUFFIXES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64",
+    ];
+
+    if init == "true" || init == "false" {
+        return Some("bool".to_string());
+    }
+    if init.starts_with('"') && init.ends_with('"') && init.len() >= 2 {
+        return Some("&str".to_string());
+    }
+    if init.starts_with('\'') && init.ends_with('\'') && init.len() >= 3 {
+        return Some("char".to_string());
+    }
+    if init.starts_with(|c: char| c.is_ascii_digit()) && init.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        if let Some(suffix) = SUFFIXES.iter().find(|s| init.ends_with(*s)) {
+            return Some(suffix.to_string());
+        }
+        return Some(if init.contains('.') { "f64" } else { "i32" }.to_string());
+    }
+    if init.starts_with("String::") || init.starts_with("format!(") || init.ends_with(".to_string()") {
+        return Some("String".to_string());
+    }
+    if init.starts_with("vec![") || init.starts_with("Vec::") {
+        return Some("Vec<_>".to_string());
+    }
+    None
+}
+
+/// `name:` before each argument of calls to known functions, unless the argument already is that name
+fn parameter_hints(line_num: usize, line: &str, signatures: &HashMap<String, Vec<String>>) -> Vec<InlayHint> {
+    let bytes = line.as_bytes();
+    let mut hints = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !(bytes[i].is_ascii_alphabetic() || bytes[i] == b'_') {
+            i += 1;
+            continue;
+        }
+        let end = ident_end(bytes, i);
+        let word = &line[i..end];
+        let declaration = line[..i].trim_end().ends_with("fn");
+        let params = signatures.get(word).filter(|_| !declaration && bytes.get(end) == Some(&b'('));
+        i = end;
+        let Some(params) = params else { continue };
+
+        let mut depth = 0usize;
+        let mut arg_start = end + 1;
+        let mut index = 0;
+        for (offset, c) in line[end + 1..].char_indices() {
+            let pos = end + 1 + offset;
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth > 0 => depth -= 1,
+                ',' | ')' if depth == 0 => {
+                    let arg = &line[arg_start..pos];
+                    let trimmed = arg.trim_start();
+                    if let Some(param) = params.get(index).filter(|p| !trimmed.is_empty() && trimmed.trim_end() != p.as_str()) {
+                        hints.push(InlayHint {
+                            line: line_num,
+                            column: arg_start + (arg.len() - trimmed.len()),
+                            label: format!("{}:", param),
+                            kind: InlayHintKind::Parameter,
+                        });
+                    }
+                    index += 1;
+                    arg_start = pos + 1;
+                    if c == ')' {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(code: &str) -> Vec<(String, TokenKind)> {
+        let tokens = Lexer::default().run(code);
+        let lines: Vec<&str> = code.lines().collect();
+        tokens
+            .iter()
+            .map(|t| (lines[t.line][t.start..t.start + t.length].to_string(), t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_classifies_declarations_and_uses() {
+        let code = "fn add(mut a: i32, b: i32) -> i32 {\n    let mut total = a + b; // sum\n    println!(\"{}\", total.max(1));\n    total\n}";
+        let tokens = kinds(code);
+
+        assert!(tokens.contains(&("add".to_string(), TokenKind::Function)));
+        assert!(tokens.contains(&("a".to_string(), TokenKind::Parameter)));
+        assert!(tokens.contains(&("println".to_string(), TokenKind::Macro)));
+        assert!(tokens.contains(&("max".to_string(), TokenKind::Method)));
+        assert!(tokens.contains(&("// sum".to_string(), TokenKind::Comment)));
+        assert!(tokens.contains(&("\"{}\"".to_string(), TokenKind::String)));
+
+        let all = Lexer::default().run(code);
+        let total_uses: Vec<_> = all.iter().filter(|t| t.line == 3).collect();
+        assert_eq!(total_uses[0].kind, TokenKind::Variable);
+        assert_eq!(total_uses[0].modifiers, vec![TokenModifier::Mutable]);
+    }
+
+    #[test]
+    fn test_block_comments_span_lines() {
+        let tokens = Lexer::default().run("/* one\ntwo */ let x = 1;");
+        assert_eq!(tokens[0].kind, TokenKind::Comment);
+        assert_eq!((tokens[1].line, tokens[1].length, tokens[1].kind), (1, 6, TokenKind::Comment));
+        assert_eq!(tokens[2].kind, TokenKind::Keyword);
+    }
+
+    #[test]
+    fn test_inlay_hints() {
+        let analyzer = SemanticAnalyzer::new();
+        let code = "fn area(width: f64, height: f64) -> f64 { width * height }\nlet w = 2.5;\nlet name = \"x\";\nlet n: u8 = 1;\nlet a = area(w, height);";
+        let hints = block_on(analyzer.inlay_hints(code)).unwrap();
+
+        let labels: Vec<_> = hints.iter().map(|h| (h.line, h.label.as_str())).collect();
+        assert_eq!(labels, vec![(1, ": f64"), (2, ": &str"), (4, "width:")]);
+        assert_eq!(hints[2].column, 13);
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => unreachable!("analysis never awaits"),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// Open text documents, kept in sync through didOpen/didChange/didClose
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// LSP position: 0-based line and UTF-16 code unit offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    /// True if the line/character overlaps this range (end exclusive)
+    pub fn contains(&self, position: Position) -> bool {
+        self.start <= position && position < self.end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem {
+    pub uri: String,
+    #[serde(default)]
+    pub language_id: String,
+    pub version: i64,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionedTextDocumentIdentifier {
+    pub uri: String,
+    pub version: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidOpenParams {
+    pub text_document: TextDocumentItem,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidChangeParams {
+    pub text_document: VersionedTextDocumentIdentifier,
+    pub content_changes: Vec<ContentChange>,
+}
+
+/// Whole-document replacement, or a range edit when `range` is set
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentChange {
+    #[serde(default)]
+    pub range: Option<Range>,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidCloseParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub text: String,
+    pub version: i64,
+}
+
+/// Documents the client has open, by URI
+#[derive(Debug, Default)]
+pub struct DocumentStore {
+    documents: HashMap<String, Document>,
+}
+
+impl DocumentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, item: TextDocumentItem) {
+        self.documents.insert(item.uri, Document { text: item.text, version: item.version });
+    }
+
+    /// Applies changes in order; changes to unknown documents are ignored
+    pub fn change(&mut self, params: DidChangeParams) {
+        let Some(document) = self.documents.get_mut(&params.text_document.uri) else { return };
+        for change in params.content_changes {
+            match change.range {
+                Some(range) => {
+                    let start = offset_of(&document.text, range.start);
+                    let end = offset_of(&document.text, range.end).max(start);
+                    document.text.replace_range(start..end, &change.text);
+                }
+                None => document.text = change.text,
+            }
+        }
+        document.version = params.text_document.version;
+    }
+
+    pub fn close(&mut self, uri: &str) {
+        self.documents.remove(uri);
+    }
+
+    pub fn get(&self, uri: &str) -> Option<&Document> {
+        self.documents.get(uri)
+    }
+}
+
+/// Length of `text` in UTF-16 code units
+pub fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Byte offset of a UTF-16 column within `line`, clamped to the line end
+pub fn utf16_to_byte(line: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (offset, c) in line.char_indices() {
+        if units >= character {
+            return offset;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+/// Byte offset of `position` in `text`, clamped to the end of its line or of the text
+pub fn offset_of(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return text.len(),
+        }
+    }
+    let line_end = text[line_start..].find('\n').map_or(text.len(), |n| line_start + n);
+    line_start + utf16_to_byte(&text[line_start..line_end], position.character)
+}
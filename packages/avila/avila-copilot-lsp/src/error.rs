@@ -1,5 +1,7 @@
 // Error types for LSP server
 
+use crate::protocol::{error_codes, ResponseError};
+use crate::workspace_edit::EditError;
 use std::fmt;
 
 pub type Result<T> = std::result::Result<T, LspError>;
@@ -10,6 +12,16 @@ pub enum LspError {
     ParseError(String),
     SerdeError(serde_json::Error),
     EngineError(String),
+    /// Request params did not match the method
+    InvalidParams(serde_json::Error),
+    /// Request for a method the server does not implement
+    MethodNotFound(String),
+    /// Request cancelled by `$/cancelRequest` or server shutdown
+    Cancelled,
+    /// Workspace edit does not fit the documents as they are now
+    InvalidEdit(EditError),
+    /// Client refused or failed a server-initiated request
+    ClientRejected(String),
 }
 
 impl fmt::Display for LspError {
@@ -19,18 +31,54 @@ impl fmt::Display for LspError {
             Self::ParseError(msg) => write!(f, "Parse error: {}", msg),
             Self::SerdeError(e) => write!(f, "Serde error: {}", e),
             Self::EngineError(msg) => write!(f, "Engine error: {}", msg),
+            Self::InvalidParams(e) => write!(f, "Invalid params: {}", e),
+            Self::MethodNotFound(method) => write!(f, "Method not found: {}", method),
+            Self::Cancelled => write!(f, "Request cancelled"),
+            Self::InvalidEdit(e) => write!(f, "Invalid edit: {}", e),
+            Self::ClientRejected(reason) => write!(f, "Client rejected request: {}", reason),
         }
     }
 }
 
 impl std::error::Error for LspError {}
 
+impl LspError {
+    /// JSON-RPC error object sent back for a failed request
+    pub fn to_response_error(&self) -> ResponseError {
+        let code = match self {
+            Self::InvalidParams(_) => error_codes::INVALID_PARAMS,
+            Self::MethodNotFound(_) => error_codes::METHOD_NOT_FOUND,
+            Self::Cancelled => error_codes::REQUEST_CANCELLED,
+            Self::ParseError(_) => error_codes::PARSE_ERROR,
+            Self::InvalidEdit(EditError::StaleVersion { .. }) => error_codes::CONTENT_MODIFIED,
+            Self::InvalidEdit(_) | Self::ClientRejected(_) => error_codes::REQUEST_FAILED,
+            Self::IoError(_) | Self::SerdeError(_) | Self::EngineError(_) => error_codes::INTERNAL_ERROR,
+        };
+        ResponseError::new(code, self.to_string())
+    }
+}
+
 impl From<std::io::Error> for LspError {
     fn from(e: std::io::Error) -> Self {
         Self::IoError(e)
     }
 }
 
+impl From<avila_copilot_core::CopilotError> for LspError {
+    fn from(e: avila_copilot_core::CopilotError) -> Self {
+        match e {
+            avila_copilot_core::CopilotError::Cancelled => Self::Cancelled,
+            e => Self::EngineError(e.to_string()),
+        }
+    }
+}
+
+impl From<EditError> for LspError {
+    fn from(e: EditError) -> Self {
+        Self::InvalidEdit(e)
+    }
+}
+
 impl From<serde_json::Error> for LspError {
     fn from(e: serde_json::Error) -> Self {
         Self::SerdeError(e)
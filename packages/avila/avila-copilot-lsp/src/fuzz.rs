@@ -0,0 +1,29 @@
+// Fuzzing entry points (feature `fuzzing`)
+// Each function takes arbitrary bytes and must not panic; the targets in
+// packages/avila/fuzz only forward their input.
+
+use crate::{read_message, write_message};
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+/// Message framing: reads messages until the input runs out, and every
+/// message read survives a round trip through `write_message`
+pub fn framing(data: &[u8]) {
+    let mut reader = data;
+    while let Ok(message) = block_on(read_message(&mut reader)) {
+        let mut wire = Vec::new();
+        block_on(write_message(&mut wire, &message)).expect("writing to a Vec cannot fail");
+        let reread = block_on(read_message(&mut wire.as_slice())).expect("re-encoded message is valid");
+        assert_eq!(serde_json::to_value(&reread).unwrap(), serde_json::to_value(&message).unwrap());
+    }
+}
+
+/// In-memory readers and writers never return `Pending`
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("in-memory I/O returned Pending"),
+    }
+}
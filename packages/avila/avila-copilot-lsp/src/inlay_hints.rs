@@ -0,0 +1,43 @@
+// Inlay hints: engine hints converted to LSP positions
+
+use crate::documents::{utf16_len, Position, Range, TextDocumentIdentifier};
+use avila_copilot_core::InlayHintKind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    pub position: Position,
+    pub label: String,
+    /// 1 = type, 2 = parameter
+    pub kind: u8,
+    pub padding_left: bool,
+    pub padding_right: bool,
+}
+
+/// Hints of `text` whose position falls inside `range`
+pub fn to_lsp(text: &str, hints: &[avila_copilot_core::InlayHint], range: Range) -> Vec<InlayHint> {
+    let lines: Vec<&str> = text.lines().collect();
+    hints
+        .iter()
+        .filter_map(|hint| {
+            let line = lines.get(hint.line)?;
+            let position = Position { line: hint.line, character: utf16_len(line.get(..hint.column)?) };
+            if position < range.start || position > range.end {
+                return None;
+            }
+            let (kind, padding_left, padding_right) = match hint.kind {
+                InlayHintKind::Type => (1, false, false),
+                InlayHintKind::Parameter => (2, false, true),
+            };
+            Some(InlayHint { position, label: hint.label.clone(), kind, padding_left, padding_right })
+        })
+        .collect()
+}
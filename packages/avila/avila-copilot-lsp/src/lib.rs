@@ -1,25 +1,54 @@
 // Layer 6: LSP Server
 // Language Server Protocol implementation for Avila Copilot
 
-use avila_copilot_core::CopilotEngine;
+use avila_copilot_core::{CancellationToken, CopilotEngine};
+use documents::{DidChangeParams, DidCloseParams, DidOpenParams, DocumentStore, TextDocumentIdentifier};
+use inlay_hints::InlayHintParams;
+use protocol::{error_codes, CancelParams, InitializeRequest, RequestId, ResponseError};
+use semantic_tokens::{
+    SemanticTokens, SemanticTokensCache, SemanticTokensDelta, SemanticTokensDeltaParams, SemanticTokensLegend,
+    SemanticTokensParams, SemanticTokensRangeParams, TokenEncoder,
+};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot, RwLock};
+use workspace_edit::{
+    ApplyWorkspaceEditParams, ApplyWorkspaceEditResult, EditError, EditSupport, FileOptions, ResourceOperation, TextEdit,
+    WorkspaceEdit,
+};
 
 pub mod completion;
 pub mod diagnostics;
+pub mod documents;
 pub mod error;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
 pub mod hover;
+pub mod inlay_hints;
 pub mod protocol;
 pub mod refactor;
+pub mod semantic_tokens;
+pub mod workspace_edit;
 
 pub use error::{LspError, Result};
 
 /// LSP server for Avila Copilot
+///
+/// Requests run concurrently, each with its own `CancellationToken` that
+/// `$/cancelRequest` fires; responses are written by a single writer task
+/// in completion order. Notifications never get a reply. The server's
+/// own requests (`workspace/applyEdit`) go through the same writer and
+/// their responses are routed back by id.
+#[derive(Clone)]
 pub struct LspServer {
     engine: Arc<CopilotEngine>,
     state: Arc<RwLock<ServerState>>,
+    /// Tokens of in-flight requests
+    pending: Arc<Mutex<HashMap<RequestId, CancellationToken>>>,
+    client: Arc<ClientRequests>,
 }
 
 impl LspServer {
@@ -27,115 +56,288 @@ impl LspServer {
         Self {
             engine: Arc::new(engine),
             state: Arc::new(RwLock::new(ServerState::default())),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            client: Arc::new(ClientRequests::default()),
         }
     }
 
-    /// Run LSP server
+    /// Run LSP server on stdin/stdout
     pub async fn run(&self) -> Result<()> {
-        let stdin = tokio::io::stdin();
-        let stdout = tokio::io::stdout();
-
-        let mut reader = BufReader::new(stdin);
-        let mut writer = stdout;
-
-        loop {
-            // Read LSP message
-            let message = self.read_message(&mut reader).await?;
+        self.serve(tokio::io::stdin(), tokio::io::stdout()).await
+    }
 
-            // Process message
-            let response = self.handle_message(&message).await?;
+    /// Serves one client until `exit` or end of input
+    pub async fn serve<R, W>(&self, input: R, output: W) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut reader = BufReader::new(input);
+        let (outgoing, mut queue) = mpsc::unbounded_channel::<LspMessage>();
+        let writer = tokio::spawn(async move {
+            let mut output = output;
+            while let Some(message) = queue.recv().await {
+                write_message(&mut output, &message).await?;
+            }
+            Ok::<(), LspError>(())
+        });
+        self.client.connect(outgoing.clone());
+
+        let result = loop {
+            let message = match read_message(&mut reader).await {
+                Ok(message) => message,
+                // The body was consumed, so the stream is still in sync
+                Err(LspError::SerdeError(e)) => {
+                    let error = ResponseError::new(error_codes::PARSE_ERROR, e.to_string());
+                    let _ = outgoing.send(LspMessage::error_response(None, error));
+                    continue;
+                }
+                Err(LspError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break Ok(()),
+                Err(e) => break Err(e),
+            };
+
+            match (message.id.clone(), message.method.clone()) {
+                (Some(id), Some(method)) => self.dispatch_request(id, method, message, &outgoing).await,
+                (None, Some(method)) => {
+                    if method == "exit" {
+                        break Ok(());
+                    }
+                    self.handle_notification(&method, message).await;
+                }
+                (_, None) => self.client.resolve(message),
+            }
+        };
 
-            // Send response
-            self.write_message(&mut writer, &response).await?;
+        // Stop in-flight requests so their replies (and senders) go away
+        for token in self.pending.lock().unwrap_or_else(|e| e.into_inner()).values() {
+            token.cancel();
+        }
+        self.client.disconnect();
+        drop(outgoing);
+        match writer.await {
+            Ok(written) => result.and(written),
+            Err(e) => result.and(Err(LspError::IoError(std::io::Error::other(e)))),
         }
     }
 
-    async fn read_message<R: AsyncBufReadExt + Unpin>(&self, reader: &mut R) -> Result<LspMessage> {
-        let mut headers = Vec::new();
-        let mut line = String::new();
-
-        // Read headers
-        loop {
-            line.clear();
-            reader.read_line(&mut line).await?;
-
-            if line == "\r\n" || line == "\n" {
-                break;
+    async fn dispatch_request(
+        &self,
+        id: RequestId,
+        method: String,
+        message: LspMessage,
+        outgoing: &mpsc::UnboundedSender<LspMessage>,
+    ) {
+        // Lifecycle requests run inline so ordering with later messages holds
+        let lifecycle = match &method[..] {
+            "initialize" => Some(self.handle_initialize(&message).await),
+            "shutdown" => {
+                self.state.write().await.shutdown_requested = true;
+                Some(Ok(serde_json::Value::Null))
             }
-
-            headers.push(line.clone());
+            _ => None,
+        };
+        if let Some(result) = lifecycle {
+            let _ = outgoing.send(LspMessage::from_result(id, result));
+            return;
         }
 
-        // Parse Content-Length
-        let content_length = self.parse_content_length(&headers)?;
-
-        // Read content
-        let mut content = vec![0u8; content_length];
-        reader.read_exact(&mut content).await?;
+        let refusal = {
+            let state = self.state.read().await;
+            if !state.initialized {
+                Some(ResponseError::new(error_codes::SERVER_NOT_INITIALIZED, "Server not initialized"))
+            } else if state.shutdown_requested {
+                Some(ResponseError::new(error_codes::INVALID_REQUEST, "Server is shutting down"))
+            } else {
+                None
+            }
+        };
+        if let Some(error) = refusal {
+            let _ = outgoing.send(LspMessage::error_response(Some(id), error));
+            return;
+        }
 
-        let message: LspMessage = serde_json::from_slice(&content)?;
-        Ok(message)
+        let token = CancellationToken::new();
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).insert(id.clone(), token.clone());
+
+        let server = self.clone();
+        let outgoing = outgoing.clone();
+        tokio::spawn(async move {
+            let result = tokio::select! {
+                result = server.handle_request(&method, &message, &token) => result,
+                _ = token.cancelled() => Err(LspError::Cancelled),
+            };
+            server.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+            let _ = outgoing.send(LspMessage::from_result(id, result));
+        });
     }
 
-    async fn write_message<W: AsyncWriteExt + Unpin>(&self, writer: &mut W, message: &LspMessage) -> Result<()> {
-        let content = serde_json::to_string(message)?;
-        let header = format!("Content-Length: {}\r\n\r\n", content.len());
-
-        writer.write_all(header.as_bytes()).await?;
-        writer.write_all(content.as_bytes()).await?;
-        writer.flush().await?;
-
-        Ok(())
+    async fn handle_request(
+        &self,
+        method: &str,
+        message: &LspMessage,
+        cancel: &CancellationToken,
+    ) -> Result<serde_json::Value> {
+        match method {
+            "textDocument/completion" => self.handle_completion(message, cancel).await,
+            "textDocument/hover" => self.handle_hover(message).await,
+            "textDocument/diagnostic" => self.handle_diagnostic(message, cancel).await,
+            "textDocument/codeAction" => self.handle_code_action(message, cancel).await,
+            "textDocument/semanticTokens/full" => self.handle_semantic_tokens_full(message).await,
+            "textDocument/semanticTokens/full/delta" => self.handle_semantic_tokens_delta(message).await,
+            "textDocument/semanticTokens/range" => self.handle_semantic_tokens_range(message).await,
+            "textDocument/inlayHint" => self.handle_inlay_hint(message).await,
+            "workspace/executeCommand" => self.handle_execute_command(message).await,
+            _ => Err(LspError::MethodNotFound(method.to_string())),
+        }
     }
 
-    fn parse_content_length(&self, headers: &[String]) -> Result<usize> {
-        for header in headers {
-            if header.starts_with("Content-Length:") {
-                let length_str = header.trim_start_matches("Content-Length:").trim();
-                return length_str.parse().map_err(|_| LspError::ParseError("Invalid Content-Length".to_string()));
+    async fn handle_notification(&self, method: &str, message: LspMessage) {
+        match method {
+            "initialized" => {}
+            "textDocument/didOpen" => {
+                if let Ok(params) = serde_json::from_value::<DidOpenParams>(message.params) {
+                    let mut state = self.state.write().await;
+                    state.semantic_tokens.forget(&params.text_document.uri);
+                    state.documents.open(params.text_document);
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = serde_json::from_value::<DidChangeParams>(message.params) {
+                    self.state.write().await.documents.change(params);
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = serde_json::from_value::<DidCloseParams>(message.params) {
+                    let mut state = self.state.write().await;
+                    state.documents.close(&params.text_document.uri);
+                    state.semantic_tokens.forget(&params.text_document.uri);
+                }
             }
+            "$/cancelRequest" => {
+                if let Ok(params) = serde_json::from_value::<CancelParams>(message.params) {
+                    let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Some(token) = pending.get(&params.id) {
+                        token.cancel();
+                    }
+                }
+            }
+            // Unknown notifications are dropped; `$/` ones may be ignored by spec
+            _ => {}
         }
-
-        Err(LspError::ParseError("Missing Content-Length header".to_string()))
     }
 
-    async fn handle_message(&self, message: &LspMessage) -> Result<LspMessage> {
-        match &message.method[..] {
-            "initialize" => self.handle_initialize(message).await,
-            "textDocument/completion" => self.handle_completion(message).await,
-            "textDocument/hover" => self.handle_hover(message).await,
-            "textDocument/diagnostic" => self.handle_diagnostic(message).await,
-            "textDocument/codeAction" => self.handle_code_action(message).await,
-            _ => Ok(self.create_error_response(message, "Unknown method")),
-        }
-    }
+    async fn handle_initialize(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        // Clients sending no (or unknown) capabilities still get the base feature set
+        let request: InitializeRequest = serde_json::from_value(message.params.clone()).unwrap_or_default();
+        let text_document = request.capabilities.text_document.unwrap_or_default();
+        let edit_support = EditSupport::negotiate(&request.capabilities.workspace.unwrap_or_default());
+
+        let semantic_tokens_provider = text_document.semantic_tokens.as_ref()
+            .filter(|client| client.requests.full() || client.requests.range())
+            .map(|client| SemanticTokensOptions {
+                legend: SemanticTokensLegend::server(),
+                range: client.requests.range(),
+                full: SemanticTokensFullOptions { delta: client.requests.delta() },
+            });
 
-    async fn handle_initialize(&self, message: &LspMessage) -> Result<LspMessage> {
         let capabilities = ServerCapabilities {
+            text_document_sync: Some(TEXT_DOCUMENT_SYNC_FULL),
             completion_provider: Some(CompletionOptions {
                 trigger_characters: vec![".".to_string(), ":".to_string()],
             }),
             hover_provider: Some(true),
             diagnostic_provider: Some(true),
             code_action_provider: Some(true),
+            execute_command_provider: edit_support.apply_edit.then(|| ExecuteCommandOptions {
+                commands: vec![GENERATE_TESTS_COMMAND.to_string()],
+            }),
+            semantic_tokens_provider,
+            inlay_hint_provider: text_document.inlay_hint.is_some().then_some(true),
         };
 
-        Ok(LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::to_value(capabilities)?,
-        })
+        let mut state = self.state.write().await;
+        state.initialized = true;
+        state.edit_support = edit_support;
+        state.token_encoder = match &text_document.semantic_tokens {
+            Some(client) => TokenEncoder::negotiate(client),
+            None => TokenEncoder::all(),
+        };
+        Ok(serde_json::json!({ "capabilities": capabilities }))
+    }
+
+    /// Text of an open document
+    async fn document_text(&self, uri: &str) -> Result<String> {
+        Ok(self.document(uri).await?.text)
+    }
+
+    /// Snapshot of an open document
+    async fn document(&self, uri: &str) -> Result<documents::Document> {
+        let state = self.state.read().await;
+        state.documents.get(uri)
+            .cloned()
+            .ok_or_else(|| LspError::InvalidParams(serde::de::Error::custom(format!("document not open: {}", uri))))
+    }
+
+    /// Encoded tokens of a document, optionally limited to a range
+    async fn encode_semantic_tokens(&self, uri: &str, range: Option<documents::Range>) -> Result<Vec<u32>> {
+        let text = self.document_text(uri).await?;
+        let tokens = self.engine.semantic_tokens(&text).await?;
+        let encoder = self.state.read().await.token_encoder.clone();
+        Ok(encoder.encode(&text, &tokens, range))
+    }
+
+    async fn handle_semantic_tokens_full(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        let params: SemanticTokensParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+        let uri = params.text_document.uri;
+
+        let data = self.encode_semantic_tokens(&uri, None).await?;
+        let result_id = self.state.write().await.semantic_tokens.store(&uri, data.clone());
+        Ok(serde_json::to_value(SemanticTokens { result_id: Some(result_id), data })?)
+    }
+
+    /// Edits against `previousResultId`, or a full result if that id is no longer known
+    async fn handle_semantic_tokens_delta(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        let params: SemanticTokensDeltaParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+        let uri = params.text_document.uri;
+
+        let data = self.encode_semantic_tokens(&uri, None).await?;
+        let mut state = self.state.write().await;
+        let edits = state.semantic_tokens.previous(&uri, &params.previous_result_id)
+            .map(|previous| semantic_tokens::diff(previous, &data));
+        let result_id = state.semantic_tokens.store(&uri, data.clone());
+        match edits {
+            Some(edits) => Ok(serde_json::to_value(SemanticTokensDelta { result_id, edits })?),
+            None => Ok(serde_json::to_value(SemanticTokens { result_id: Some(result_id), data })?),
+        }
+    }
+
+    async fn handle_semantic_tokens_range(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        let params: SemanticTokensRangeParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+
+        let data = self.encode_semantic_tokens(&params.text_document.uri, Some(params.range)).await?;
+        Ok(serde_json::to_value(SemanticTokens { result_id: None, data })?)
     }
 
-    async fn handle_completion(&self, message: &LspMessage) -> Result<LspMessage> {
-        let params: CompletionParams = serde_json::from_value(message.params.clone())?;
+    async fn handle_inlay_hint(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        let params: InlayHintParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+
+        let text = self.document_text(&params.text_document.uri).await?;
+        let hints = self.engine.inlay_hints(&text).await?;
+        Ok(serde_json::to_value(inlay_hints::to_lsp(&text, &hints, params.range))?)
+    }
+
+    async fn handle_completion(&self, message: &LspMessage, cancel: &CancellationToken) -> Result<serde_json::Value> {
+        let params: CompletionParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
 
         // Get completion from engine
         let completion = self.engine
-            .complete(&params.text, params.position)
-            .await
-            .map_err(|e| LspError::EngineError(e.to_string()))?;
+            .complete_cancellable(&params.text, params.position, cancel)
+            .await?;
 
         let items = vec![CompletionItem {
             label: completion.text.clone(),
@@ -144,27 +346,22 @@ impl LspServer {
             documentation: None,
         }];
 
-        Ok(LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::to_value(items)?,
-        })
+        Ok(serde_json::to_value(items)?)
     }
 
-    async fn handle_hover(&self, message: &LspMessage) -> Result<LspMessage> {
+    async fn handle_hover(&self, _message: &LspMessage) -> Result<serde_json::Value> {
         // TODO: Implement hover
-        Ok(self.create_empty_response(message))
+        Ok(serde_json::Value::Null)
     }
 
-    async fn handle_diagnostic(&self, message: &LspMessage) -> Result<LspMessage> {
-        let params: DiagnosticParams = serde_json::from_value(message.params.clone())?;
+    async fn handle_diagnostic(&self, message: &LspMessage, cancel: &CancellationToken) -> Result<serde_json::Value> {
+        let params: DiagnosticParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
 
         // Get bugs from engine
         let bugs = self.engine
-            .detect_bugs(&params.text)
-            .await
-            .map_err(|e| LspError::EngineError(e.to_string()))?;
+            .detect_bugs_cancellable(&params.text, cancel)
+            .await?;
 
         let diagnostics: Vec<Diagnostic> = bugs
             .into_iter()
@@ -182,83 +379,383 @@ impl LspServer {
             })
             .collect();
 
-        Ok(LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::to_value(diagnostics)?,
-        })
+        Ok(serde_json::to_value(diagnostics)?)
     }
 
-    async fn handle_code_action(&self, message: &LspMessage) -> Result<LspMessage> {
-        let params: CodeActionParams = serde_json::from_value(message.params.clone())?;
+    /// Refactorings touching the requested range, each carrying the edit
+    /// that performs it when one can be computed, plus a source action that
+    /// writes generated tests to a new file through `workspace/applyEdit`
+    async fn handle_code_action(&self, message: &LspMessage, cancel: &CancellationToken) -> Result<serde_json::Value> {
+        let params: CodeActionParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+        let uri = params.text_document.uri;
+        let document = self.document(&uri).await?;
 
         // Get refactorings from engine
         let refactorings = self.engine
-            .suggest_refactorings(&params.text)
-            .await
-            .map_err(|e| LspError::EngineError(e.to_string()))?;
+            .suggest_refactorings_cancellable(&document.text, cancel)
+            .await?;
+
+        let state = self.state.read().await;
+        let support = &state.edit_support;
+        let mut actions: Vec<CodeAction> = Vec::new();
+        for refactoring in refactorings {
+            let location = &refactoring.location;
+            if location.end_line < params.range.start.line || location.start_line > params.range.end.line {
+                continue;
+            }
+            let edits: Vec<TextEdit> = self.engine
+                .refactoring_edits(&document.text, &refactoring)
+                .iter()
+                .map(|edit| workspace_edit::text_edit(&document.text, edit))
+                .collect();
+            let edit = Some(WorkspaceEdit::new().edit(uri.clone(), Some(document.version), edits))
+                .filter(|edit| !edit.document_changes.is_empty() && edit.validate(&state.documents).is_ok());
+
+            actions.push(CodeAction {
+                title: refactoring.description,
+                kind: if edit.is_some() { CodeActionKind::RefactorExtract } else { CodeActionKind::Refactor },
+                edit: edit.map(|edit| edit.to_value(support)),
+                command: None,
+            });
+        }
 
-        let actions: Vec<CodeAction> = refactorings
-            .into_iter()
-            .map(|r| CodeAction {
-                title: r.description,
-                kind: CodeActionKind::Refactor,
-            })
-            .collect();
+        let creates_files = WorkspaceEdit::new()
+            .operation(ResourceOperation::Create { uri: tests_uri(&uri), options: FileOptions::default() })
+            .supported_by(support);
+        if support.apply_edit && creates_files {
+            actions.push(CodeAction {
+                title: "Generate tests".to_string(),
+                kind: CodeActionKind::Source,
+                edit: None,
+                command: Some(Command {
+                    title: "Generate tests".to_string(),
+                    command: GENERATE_TESTS_COMMAND.to_string(),
+                    arguments: vec![serde_json::json!(uri), serde_json::json!(document.version)],
+                }),
+            });
+        }
 
-        Ok(LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::to_value(actions)?,
-        })
+        Ok(serde_json::to_value(actions)?)
     }
 
-    fn create_error_response(&self, message: &LspMessage, error: &str) -> LspMessage {
-        LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::json!({ "error": error }),
+    async fn handle_execute_command(&self, message: &LspMessage) -> Result<serde_json::Value> {
+        let params: ExecuteCommandParams = serde_json::from_value(message.params.clone())
+            .map_err(LspError::InvalidParams)?;
+
+        match &params.command[..] {
+            GENERATE_TESTS_COMMAND => {
+                let (uri, version): (String, i64) = serde_json::from_value(serde_json::Value::Array(params.arguments))
+                    .map_err(LspError::InvalidParams)?;
+                self.generate_tests_file(&uri, version).await?;
+                Ok(serde_json::Value::Null)
+            }
+            command => Err(LspError::InvalidParams(serde::de::Error::custom(format!("unknown command: {}", command)))),
         }
     }
 
-    fn create_empty_response(&self, message: &LspMessage) -> LspMessage {
-        LspMessage {
-            jsonrpc: "2.0".to_string(),
-            id: message.id.clone(),
-            method: String::new(),
-            params: serde_json::json!(null),
+    /// Creates `<name>_tests.rs` next to `uri` holding tests for the document
+    /// as it was at `version`
+    async fn generate_tests_file(&self, uri: &str, version: i64) -> Result<()> {
+        let document = self.document(uri).await?;
+        if document.version != version {
+            return Err(EditError::StaleVersion { uri: uri.to_string(), expected: version, actual: document.version }.into());
+        }
+        let tests = self.engine.generate_tests(&document.text).await?;
+
+        let target = tests_uri(uri);
+        let start = documents::Position { line: 0, character: 0 };
+        let edit = WorkspaceEdit::new()
+            .operation(ResourceOperation::Create { uri: target.clone(), options: FileOptions::default() })
+            .edit(target, None, vec![TextEdit { range: documents::Range { start, end: start }, new_text: tests }]);
+        self.apply_edit("Generate tests", &edit).await
+    }
+
+    /// Validates `edit` against the open documents and asks the client to apply it
+    async fn apply_edit(&self, label: &str, edit: &WorkspaceEdit) -> Result<()> {
+        let value = {
+            let state = self.state.read().await;
+            if !edit.supported_by(&state.edit_support) {
+                return Err(LspError::ClientRejected("client cannot apply this kind of edit".to_string()));
+            }
+            edit.validate(&state.documents)?;
+            edit.to_value(&state.edit_support)
+        };
+
+        let params = ApplyWorkspaceEditParams { label: Some(label.to_string()), edit: value };
+        let response = self.client.request("workspace/applyEdit", serde_json::to_value(params)?).await?;
+        let result: ApplyWorkspaceEditResult = serde_json::from_value(response)?;
+        if result.applied {
+            Ok(())
+        } else {
+            Err(LspError::ClientRejected(result.failure_reason.unwrap_or_else(|| "edit not applied".to_string())))
         }
     }
 }
 
-/// Server state
+/// Command behind the "Generate tests" source action; arguments are `[uri, version]`
+pub const GENERATE_TESTS_COMMAND: &str = "avila.generateTests";
+
+/// `src/foo.rs` -> `src/foo_tests.rs`
+fn tests_uri(uri: &str) -> String {
+    match uri.strip_suffix(".rs") {
+        Some(stem) => format!("{}_tests.rs", stem),
+        None => format!("{}_tests.rs", uri),
+    }
+}
+
+/// Requests the server sends to the client, awaiting responses by id
 #[derive(Default)]
+struct ClientRequests {
+    next_id: AtomicI64,
+    outgoing: Mutex<Option<mpsc::UnboundedSender<LspMessage>>>,
+    waiting: Mutex<HashMap<RequestId, oneshot::Sender<LspMessage>>>,
+}
+
+impl ClientRequests {
+    fn connect(&self, outgoing: mpsc::UnboundedSender<LspMessage>) {
+        *self.outgoing.lock().unwrap_or_else(|e| e.into_inner()) = Some(outgoing);
+    }
+
+    /// Drops the writer and fails every request still waiting for the client
+    fn disconnect(&self) {
+        self.outgoing.lock().unwrap_or_else(|e| e.into_inner()).take();
+        self.waiting.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    async fn request(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let id = RequestId::Number(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let (reply, response) = oneshot::channel();
+        self.waiting.lock().unwrap_or_else(|e| e.into_inner()).insert(id.clone(), reply);
+
+        let sent = self.outgoing.lock().unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|outgoing| outgoing.send(LspMessage::request(id.clone(), method, params)).is_ok());
+        if !sent {
+            self.waiting.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+            return Err(LspError::Cancelled);
+        }
+
+        let message = response.await.map_err(|_| LspError::Cancelled)?;
+        match (message.result, message.error) {
+            (_, Some(error)) => Err(LspError::ClientRejected(error.message)),
+            (result, None) => Ok(result.unwrap_or(serde_json::Value::Null)),
+        }
+    }
+
+    /// Hands a client response to the request waiting for it; unknown ids are dropped
+    fn resolve(&self, message: LspMessage) {
+        let Some(id) = message.id.clone() else { return };
+        if let Some(reply) = self.waiting.lock().unwrap_or_else(|e| e.into_inner()).remove(&id) {
+            let _ = reply.send(message);
+        }
+    }
+}
+
+/// Largest message body accepted, so a bad header cannot force a huge allocation
+pub const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Reads one `Content-Length` framed message
+pub async fn read_message<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Result<LspMessage> {
+    let mut headers = Vec::new();
+    let mut line = String::new();
+
+    // Read headers
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(LspError::IoError(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+
+        headers.push(line.clone());
+    }
+
+    // Parse Content-Length
+    let content_length = parse_content_length(&headers)?;
+    if content_length > MAX_CONTENT_LENGTH {
+        return Err(LspError::ParseError(format!("Content-Length {} exceeds limit", content_length)));
+    }
+
+    // Read content
+    let mut content = vec![0u8; content_length];
+    reader.read_exact(&mut content).await?;
+
+    let message: LspMessage = serde_json::from_slice(&content)?;
+    Ok(message)
+}
+
+/// Writes one message with its `Content-Length` header
+pub async fn write_message<W: AsyncWriteExt + Unpin>(writer: &mut W, message: &LspMessage) -> Result<()> {
+    let content = serde_json::to_string(message)?;
+    let header = format!("Content-Length: {}\r\n\r\n", content.len());
+
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(content.as_bytes()).await?;
+    writer.flush().await?;
+
+    Ok(())
+}
+
+fn parse_content_length(headers: &[String]) -> Result<usize> {
+    for header in headers {
+        if header.starts_with("Content-Length:") {
+            let length_str = header.trim_start_matches("Content-Length:").trim();
+            return length_str.parse().map_err(|_| LspError::ParseError("Invalid Content-Length".to_string()));
+        }
+    }
+
+    Err(LspError::ParseError("Missing Content-Length header".to_string()))
+}
+
+/// Server state
 struct ServerState {
     initialized: bool,
+    shutdown_requested: bool,
+    documents: DocumentStore,
+    semantic_tokens: SemanticTokensCache,
+    token_encoder: TokenEncoder,
+    edit_support: EditSupport,
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self {
+            initialized: false,
+            shutdown_requested: false,
+            documents: DocumentStore::new(),
+            semantic_tokens: SemanticTokensCache::default(),
+            token_encoder: TokenEncoder::all(),
+            edit_support: EditSupport::default(),
+        }
+    }
 }
 
-/// LSP message
+/// JSON-RPC message: a request (`id` and `method`), a notification
+/// (`method` only) or a response (`id` with `result` or `error`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LspMessage {
     pub jsonrpc: String,
-    pub id: Option<i64>,
-    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<RequestId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
     pub params: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ResponseError>,
+}
+
+impl LspMessage {
+    pub fn request(id: RequestId, method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(id),
+            method: Some(method.into()),
+            params,
+            result: None,
+            error: None,
+        }
+    }
+
+    pub fn notification(method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: Some(method.into()),
+            params,
+            result: None,
+            error: None,
+        }
+    }
+
+    pub fn response(id: RequestId, result: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(id),
+            method: None,
+            params: serde_json::Value::Null,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Error response; `id` is `None` only when the request could not be parsed
+    pub fn error_response(id: Option<RequestId>, error: ResponseError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            method: None,
+            params: serde_json::Value::Null,
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    fn from_result(id: RequestId, result: Result<serde_json::Value>) -> Self {
+        match result {
+            Ok(value) => Self::response(id, value),
+            Err(e) => Self::error_response(Some(id), e.to_response_error()),
+        }
+    }
+
+    pub fn is_request(&self) -> bool {
+        self.id.is_some() && self.method.is_some()
+    }
+
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none() && self.method.is_some()
+    }
+
+    pub fn is_response(&self) -> bool {
+        self.method.is_none()
+    }
 }
 
 /// Server capabilities
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct ServerCapabilities {
+    text_document_sync: Option<u8>,
     completion_provider: Option<CompletionOptions>,
     hover_provider: Option<bool>,
     diagnostic_provider: Option<bool>,
     code_action_provider: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    execute_command_provider: Option<ExecuteCommandOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    semantic_tokens_provider: Option<SemanticTokensOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inlay_hint_provider: Option<bool>,
+}
+
+/// `TextDocumentSyncKind.Full`: clients send the whole text on every change
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SemanticTokensOptions {
+    legend: SemanticTokensLegend,
+    range: bool,
+    full: SemanticTokensFullOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SemanticTokensFullOptions {
+    delta: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExecuteCommandOptions {
+    commands: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct CompletionOptions {
     trigger_characters: Vec<String>,
 }
@@ -320,18 +817,45 @@ struct Position {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct CodeActionParams {
-    text: String,
+    text_document: TextDocumentIdentifier,
+    range: documents::Range,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct CodeAction {
     title: String,
     kind: CodeActionKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edit: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<Command>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 enum CodeActionKind {
+    #[serde(rename = "quickfix")]
     QuickFix,
+    #[serde(rename = "refactor")]
     Refactor,
+    #[serde(rename = "refactor.extract")]
+    RefactorExtract,
+    #[serde(rename = "source")]
+    Source,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Command {
+    title: String,
+    command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    arguments: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExecuteCommandParams {
+    command: String,
+    #[serde(default)]
+    arguments: Vec<serde_json::Value>,
 }
@@ -1,15 +1,21 @@
 // LSP Protocol types and utilities
 
+use crate::semantic_tokens::SemanticTokensClientCapabilities;
+use crate::workspace_edit::WorkspaceClientCapabilities;
 use serde::{Deserialize, Serialize};
 
 /// LSP protocol version
 pub const LSP_VERSION: &str = "3.17";
 
 /// Initialize request
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct InitializeRequest {
+    #[serde(default)]
     pub process_id: Option<i64>,
+    #[serde(default)]
     pub client_info: Option<ClientInfo>,
+    #[serde(default)]
     pub capabilities: ClientCapabilities,
 }
 
@@ -19,23 +25,85 @@ pub struct ClientInfo {
     pub version: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ClientCapabilities {
+    #[serde(default)]
     pub text_document: Option<TextDocumentClientCapabilities>,
+    #[serde(default)]
+    pub workspace: Option<WorkspaceClientCapabilities>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TextDocumentClientCapabilities {
+    #[serde(default)]
     pub completion: Option<CompletionClientCapabilities>,
+    #[serde(default)]
     pub hover: Option<HoverClientCapabilities>,
+    #[serde(default)]
+    pub semantic_tokens: Option<SemanticTokensClientCapabilities>,
+    #[serde(default)]
+    pub inlay_hint: Option<InlayHintClientCapabilities>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CompletionClientCapabilities {
     pub dynamic_registration: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct HoverClientCapabilities {
     pub dynamic_registration: Option<bool>,
 }
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintClientCapabilities {
+    #[serde(default)]
+    pub dynamic_registration: Option<bool>,
+}
+
+/// JSON-RPC request id; clients may send numbers or strings
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequestId {
+    Number(i64),
+    String(String),
+}
+
+/// Error object of a failed response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl ResponseError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+}
+
+/// JSON-RPC and LSP error codes
+pub mod error_codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+    pub const REQUEST_CANCELLED: i64 = -32800;
+    pub const CONTENT_MODIFIED: i64 = -32801;
+    pub const REQUEST_FAILED: i64 = -32803;
+}
+
+/// `$/cancelRequest` params
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelParams {
+    pub id: RequestId,
+}
@@ -0,0 +1,301 @@
+// Semantic tokens: legend, relative encoding and delta edits
+
+use crate::documents::{utf16_len, Position, Range, TextDocumentIdentifier};
+use avila_copilot_core::{SemanticToken, TokenKind, TokenModifier};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Token types in legend order; a token's type is its index here
+pub const TOKEN_TYPES: &[&str] = &[
+    "namespace", "type", "function", "method", "macro", "parameter", "variable", "property", "keyword", "string",
+    "number", "comment",
+];
+
+/// Token modifiers in legend order; bit `i` of a token's modifier set is entry `i`
+pub const TOKEN_MODIFIERS: &[&str] = &["declaration", "mutable", "documentation"];
+
+fn type_index(kind: TokenKind) -> u32 {
+    match kind {
+        TokenKind::Namespace => 0,
+        TokenKind::Type => 1,
+        TokenKind::Function => 2,
+        TokenKind::Method => 3,
+        TokenKind::Macro => 4,
+        TokenKind::Parameter => 5,
+        TokenKind::Variable => 6,
+        TokenKind::Property => 7,
+        TokenKind::Keyword => 8,
+        TokenKind::String => 9,
+        TokenKind::Number => 10,
+        TokenKind::Comment => 11,
+    }
+}
+
+fn modifier_bit(modifier: TokenModifier) -> u32 {
+    match modifier {
+        TokenModifier::Declaration => 1 << 0,
+        TokenModifier::Mutable => 1 << 1,
+        TokenModifier::Documentation => 1 << 2,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+}
+
+impl SemanticTokensLegend {
+    pub fn server() -> Self {
+        Self {
+            token_types: TOKEN_TYPES.iter().map(|t| t.to_string()).collect(),
+            token_modifiers: TOKEN_MODIFIERS.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+}
+
+/// What the client declared under `textDocument.semanticTokens`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensClientCapabilities {
+    #[serde(default)]
+    pub requests: SemanticTokensRequests,
+    #[serde(default)]
+    pub token_types: Vec<String>,
+    #[serde(default)]
+    pub token_modifiers: Vec<String>,
+    #[serde(default)]
+    pub multiline_token_support: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SemanticTokensRequests {
+    /// `true` or `{}`; absent means not supported
+    #[serde(default)]
+    pub range: Option<serde_json::Value>,
+    /// `true` or `{ "delta": bool }`
+    #[serde(default)]
+    pub full: Option<serde_json::Value>,
+}
+
+impl SemanticTokensRequests {
+    pub fn range(&self) -> bool {
+        is_enabled(self.range.as_ref())
+    }
+
+    pub fn full(&self) -> bool {
+        is_enabled(self.full.as_ref())
+    }
+
+    pub fn delta(&self) -> bool {
+        self.full.as_ref().and_then(|f| f.get("delta")).and_then(|d| d.as_bool()).unwrap_or(false)
+    }
+}
+
+fn is_enabled(value: Option<&serde_json::Value>) -> bool {
+    match value {
+        Some(serde_json::Value::Bool(enabled)) => *enabled,
+        Some(serde_json::Value::Object(_)) => true,
+        _ => false,
+    }
+}
+
+/// Negotiated encoding: which legend entries the client understands
+#[derive(Debug, Clone)]
+pub struct TokenEncoder {
+    /// Legend type index -> allowed
+    types: Vec<bool>,
+    /// Modifier bits the client knows
+    modifier_mask: u32,
+}
+
+impl TokenEncoder {
+    /// Encoder for a client that did not restrict the legend
+    pub fn all() -> Self {
+        Self { types: vec![true; TOKEN_TYPES.len()], modifier_mask: u32::MAX }
+    }
+
+    /// Drops token types and modifiers missing from the client's lists (empty lists allow everything)
+    pub fn negotiate(client: &SemanticTokensClientCapabilities) -> Self {
+        let types = TOKEN_TYPES
+            .iter()
+            .map(|t| client.token_types.is_empty() || client.token_types.iter().any(|c| c == t))
+            .collect();
+        let modifier_mask = TOKEN_MODIFIERS
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| client.token_modifiers.is_empty() || client.token_modifiers.iter().any(|c| c == *m))
+            .fold(0, |mask, (i, _)| mask | 1 << i);
+        Self { types, modifier_mask }
+    }
+
+    /// Encodes tokens as LSP relative quintuples, keeping those that start inside `range`
+    ///
+    /// Byte columns from the engine become UTF-16 columns of `text`; tokens
+    /// must be in document order, as the engine produces them.
+    pub fn encode(&self, text: &str, tokens: &[SemanticToken], range: Option<Range>) -> Vec<u32> {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut data = Vec::with_capacity(tokens.len() * 5);
+        let (mut prev_line, mut prev_start) = (0usize, 0usize);
+
+        for token in tokens {
+            let type_index = type_index(token.kind);
+            if !self.types[type_index as usize] {
+                continue;
+            }
+            let Some(line) = lines.get(token.line) else { continue };
+            let Some(before) = line.get(..token.start) else { continue };
+            let Some(body) = line.get(token.start..token.start + token.length) else { continue };
+
+            let start = utf16_len(before);
+            if let Some(range) = range {
+                if !range.contains(Position { line: token.line, character: start }) {
+                    continue;
+                }
+            }
+            let modifiers = token.modifiers.iter().fold(0, |bits, m| bits | modifier_bit(*m)) & self.modifier_mask;
+
+            let delta_line = token.line - prev_line;
+            let delta_start = if delta_line == 0 { start - prev_start } else { start };
+            data.extend([delta_line as u32, delta_start as u32, utf16_len(body) as u32, type_index, modifiers]);
+            prev_line = token.line;
+            prev_start = start;
+        }
+
+        data
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SemanticTokensEdit {
+    pub start: u32,
+    #[serde(rename = "deleteCount")]
+    pub delete_count: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub data: Vec<u32>,
+}
+
+/// Smallest single edit turning `old` into `new` (empty if equal)
+///
+/// Edits keep whole quintuples, so clients never see a half-replaced token.
+pub fn diff(old: &[u32], new: &[u32]) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count() / 5 * 5;
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old.iter().rev().zip(new.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count() / 5 * 5;
+
+    if prefix == old.len() && prefix == new.len() {
+        return Vec::new();
+    }
+    vec![SemanticTokensEdit {
+        start: prefix as u32,
+        delete_count: (old.len() - prefix - suffix) as u32,
+        data: new[prefix..new.len() - suffix].to_vec(),
+    }]
+}
+
+/// Last full result sent per document, so `full/delta` can answer with edits
+#[derive(Debug, Default)]
+pub struct SemanticTokensCache {
+    next_id: u64,
+    results: HashMap<String, (String, Vec<u32>)>,
+}
+
+impl SemanticTokensCache {
+    /// Stores `data` as the document's latest result and returns its id
+    pub fn store(&mut self, uri: &str, data: Vec<u32>) -> String {
+        self.next_id += 1;
+        let id = self.next_id.to_string();
+        self.results.insert(uri.to_string(), (id.clone(), data));
+        id
+    }
+
+    /// Data of `result_id` if it is still the document's latest result
+    pub fn previous(&self, uri: &str, result_id: &str) -> Option<&[u32]> {
+        self.results.get(uri).filter(|(id, _)| id == result_id).map(|(_, data)| data.as_slice())
+    }
+
+    pub fn forget(&mut self, uri: &str) {
+        self.results.remove(uri);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensDeltaParams {
+    pub text_document: TextDocumentIdentifier,
+    pub previous_result_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensRangeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokens {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensDelta {
+    pub result_id: String,
+    pub edits: Vec<SemanticTokensEdit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(line: usize, start: usize, length: usize, kind: TokenKind) -> SemanticToken {
+        SemanticToken { line, start, length, kind, modifiers: Vec::new() }
+    }
+
+    #[test]
+    fn test_encode_is_relative_and_utf16() {
+        let text = "let é = 1;\nfn f() {}";
+        let tokens = [
+            token(0, 0, 3, TokenKind::Keyword),
+            SemanticToken { modifiers: vec![TokenModifier::Declaration], ..token(0, 4, 2, TokenKind::Variable) },
+            token(0, 9, 1, TokenKind::Number),
+            token(1, 3, 1, TokenKind::Function),
+        ];
+        let data = TokenEncoder::all().encode(text, &tokens, None);
+        assert_eq!(data, vec![0, 0, 3, 8, 0, 0, 4, 1, 6, 1, 0, 4, 1, 10, 0, 1, 3, 1, 2, 0]);
+
+        let range = Range { start: Position { line: 1, character: 0 }, end: Position { line: 2, character: 0 } };
+        assert_eq!(TokenEncoder::all().encode(text, &tokens, Some(range)), vec![1, 3, 1, 2, 0]);
+
+        let client = SemanticTokensClientCapabilities {
+            token_types: vec!["keyword".into(), "variable".into()],
+            token_modifiers: vec!["mutable".into()],
+            ..Default::default()
+        };
+        let data = TokenEncoder::negotiate(&client).encode(text, &tokens, None);
+        assert_eq!(data, vec![0, 0, 3, 8, 0, 0, 4, 1, 6, 0]);
+    }
+
+    #[test]
+    fn test_diff_replaces_whole_tokens() {
+        let old = vec![0, 0, 3, 8, 0, 0, 4, 1, 6, 1, 1, 0, 2, 2, 0];
+        let new = vec![0, 0, 3, 8, 0, 0, 4, 2, 6, 1, 1, 0, 2, 2, 0];
+        let edits = diff(&old, &new);
+        assert_eq!(edits, vec![SemanticTokensEdit { start: 5, delete_count: 5, data: new[5..10].to_vec() }]);
+        assert!(diff(&old, &old).is_empty());
+
+        let appended = [old.clone(), vec![1, 0, 1, 9, 0]].concat();
+        assert_eq!(diff(&old, &appended), vec![SemanticTokensEdit { start: 15, delete_count: 0, data: vec![1, 0, 1, 9, 0] }]);
+    }
+}
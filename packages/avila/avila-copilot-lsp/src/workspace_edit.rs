@@ -0,0 +1,411 @@
+// WorkspaceEdit model, client edit capabilities and validation against open documents
+
+use crate::documents::{offset_of, utf16_len, DocumentStore, Position, Range};
+use avila_copilot_core::CodeEdit;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// `version: None` applies regardless of the client's document version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionalVersionedTextDocumentIdentifier {
+    pub uri: String,
+    pub version: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentEdit {
+    pub text_document: OptionalVersionedTextDocumentIdentifier,
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOptions {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overwrite: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_if_exists: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_if_not_exists: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ResourceOperation {
+    Create {
+        uri: String,
+        #[serde(default)]
+        options: FileOptions,
+    },
+    Rename {
+        #[serde(rename = "oldUri")]
+        old_uri: String,
+        #[serde(rename = "newUri")]
+        new_uri: String,
+        #[serde(default)]
+        options: FileOptions,
+    },
+    Delete {
+        uri: String,
+        #[serde(default)]
+        options: FileOptions,
+    },
+}
+
+impl ResourceOperation {
+    /// Name used in the client's `resourceOperations` capability
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Create { .. } => "create",
+            Self::Rename { .. } => "rename",
+            Self::Delete { .. } => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DocumentChange {
+    Edit(TextDocumentEdit),
+    Operation(ResourceOperation),
+}
+
+/// Ordered document changes; serialized as `documentChanges` or, for
+/// clients without that capability, as the `changes` map
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceEdit {
+    pub document_changes: Vec<DocumentChange>,
+}
+
+impl WorkspaceEdit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds edits to `uri`, pinned to `version` when given
+    pub fn edit(mut self, uri: impl Into<String>, version: Option<i64>, edits: Vec<TextEdit>) -> Self {
+        self.document_changes.push(DocumentChange::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri: uri.into(), version },
+            edits,
+        }));
+        self
+    }
+
+    pub fn operation(mut self, operation: ResourceOperation) -> Self {
+        self.document_changes.push(DocumentChange::Operation(operation));
+        self
+    }
+
+    pub fn operations(&self) -> impl Iterator<Item = &ResourceOperation> {
+        self.document_changes.iter().filter_map(|change| match change {
+            DocumentChange::Operation(operation) => Some(operation),
+            DocumentChange::Edit(_) => None,
+        })
+    }
+
+    /// Whether a client with `support` can apply this edit
+    pub fn supported_by(&self, support: &EditSupport) -> bool {
+        if support.document_changes {
+            self.operations().all(|op| support.resource_operations.iter().any(|kind| kind == op.kind()))
+        } else {
+            self.operations().next().is_none()
+        }
+    }
+
+    /// Wire form for a client with `support`
+    pub fn to_value(&self, support: &EditSupport) -> serde_json::Value {
+        if support.document_changes {
+            return serde_json::json!({ "documentChanges": self.document_changes });
+        }
+        let mut changes: HashMap<&str, Vec<&TextEdit>> = HashMap::new();
+        for change in &self.document_changes {
+            if let DocumentChange::Edit(edit) = change {
+                changes.entry(&edit.text_document.uri).or_default().extend(&edit.edits);
+            }
+        }
+        serde_json::json!({ "changes": changes })
+    }
+
+    /// Checks pinned versions against `documents` and that each document's
+    /// edits stay inside it without overlapping
+    pub fn validate(&self, documents: &DocumentStore) -> Result<(), EditError> {
+        let mut created: Vec<&str> = Vec::new();
+        for change in &self.document_changes {
+            let edit = match change {
+                DocumentChange::Operation(ResourceOperation::Create { uri, .. }) => {
+                    created.push(uri);
+                    continue;
+                }
+                DocumentChange::Operation(ResourceOperation::Rename { new_uri, .. }) => {
+                    created.push(new_uri);
+                    continue;
+                }
+                DocumentChange::Operation(ResourceOperation::Delete { .. }) => continue,
+                DocumentChange::Edit(edit) => edit,
+            };
+
+            let uri = &edit.text_document.uri;
+            let document = documents.get(uri);
+            if let Some(version) = edit.text_document.version {
+                match document {
+                    Some(document) if document.version == version => {}
+                    Some(document) => {
+                        return Err(EditError::StaleVersion { uri: uri.clone(), expected: version, actual: document.version })
+                    }
+                    None => return Err(EditError::NotOpen(uri.clone())),
+                }
+            }
+
+            let text = match document {
+                Some(document) => document.text.as_str(),
+                // Files created earlier in this edit start empty; others are outside our view
+                None if created.contains(&uri.as_str()) => "",
+                None => continue,
+            };
+            let mut ranges: Vec<(usize, usize)> = Vec::with_capacity(edit.edits.len());
+            for text_edit in &edit.edits {
+                let Range { start, end } = text_edit.range;
+                if end < start || !in_bounds(text, start) || !in_bounds(text, end) {
+                    return Err(EditError::InvalidRange { uri: uri.clone(), range: text_edit.range });
+                }
+                ranges.push((offset_of(text, start), offset_of(text, end)));
+            }
+            ranges.sort_unstable();
+            if ranges.windows(2).any(|pair| pair[0].1 > pair[1].0) {
+                return Err(EditError::Overlapping(uri.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for WorkspaceEdit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Wire<'a> {
+            document_changes: &'a [DocumentChange],
+        }
+        Wire { document_changes: &self.document_changes }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WorkspaceEdit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Wire {
+            #[serde(default)]
+            document_changes: Vec<DocumentChange>,
+            #[serde(default)]
+            changes: HashMap<String, Vec<TextEdit>>,
+        }
+        let wire = Wire::deserialize(deserializer)?;
+        let mut edit = WorkspaceEdit { document_changes: wire.document_changes };
+        let mut changes: Vec<_> = wire.changes.into_iter().collect();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        for (uri, edits) in changes {
+            edit = edit.edit(uri, None, edits);
+        }
+        Ok(edit)
+    }
+}
+
+/// Position is on an existing line, at most at its end
+fn in_bounds(text: &str, position: Position) -> bool {
+    match text.split('\n').nth(position.line) {
+        Some(line) => position.character <= utf16_len(line),
+        // One past the last line is the end of the document
+        None => position.line == text.split('\n').count() && position.character == 0,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The edit was computed for another version of the document
+    StaleVersion { uri: String, expected: i64, actual: i64 },
+    /// A versioned edit targets a document the client has not opened
+    NotOpen(String),
+    InvalidRange { uri: String, range: Range },
+    Overlapping(String),
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StaleVersion { uri, expected, actual } => {
+                write!(f, "{} changed: edit is for version {}, document is at {}", uri, expected, actual)
+            }
+            Self::NotOpen(uri) => write!(f, "{} is not open", uri),
+            Self::InvalidRange { uri, range } => write!(
+                f,
+                "{}: range {}:{}-{}:{} is outside the document",
+                uri, range.start.line, range.start.character, range.end.line, range.end.character
+            ),
+            Self::Overlapping(uri) => write!(f, "{}: edits overlap", uri),
+        }
+    }
+}
+
+/// What the client declared under `workspace`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceClientCapabilities {
+    #[serde(default)]
+    pub apply_edit: bool,
+    #[serde(default)]
+    pub workspace_edit: Option<WorkspaceEditClientCapabilities>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEditClientCapabilities {
+    #[serde(default)]
+    pub document_changes: bool,
+    #[serde(default)]
+    pub resource_operations: Vec<String>,
+}
+
+/// Negotiated edit features
+#[derive(Debug, Clone, Default)]
+pub struct EditSupport {
+    /// Client answers `workspace/applyEdit`
+    pub apply_edit: bool,
+    pub document_changes: bool,
+    pub resource_operations: Vec<String>,
+}
+
+impl EditSupport {
+    pub fn negotiate(client: &WorkspaceClientCapabilities) -> Self {
+        let workspace_edit = client.workspace_edit.clone().unwrap_or_default();
+        Self {
+            apply_edit: client.apply_edit,
+            document_changes: workspace_edit.document_changes,
+            resource_operations: workspace_edit.resource_operations,
+        }
+    }
+}
+
+/// `workspace/applyEdit` params
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyWorkspaceEditParams {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    pub edit: serde_json::Value,
+}
+
+/// Client's answer to `workspace/applyEdit`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyWorkspaceEditResult {
+    pub applied: bool,
+    #[serde(default)]
+    pub failure_reason: Option<String>,
+}
+
+/// Engine edit (byte columns) as an LSP edit of `text` (UTF-16 columns)
+pub fn text_edit(text: &str, edit: &CodeEdit) -> TextEdit {
+    let position = |line: usize, column: usize| {
+        let line_text = text.split('\n').nth(line).unwrap_or("");
+        let column = column.min(line_text.len());
+        let before = line_text.get(..column).unwrap_or(line_text);
+        Position { line, character: utf16_len(before) }
+    };
+    let location = &edit.location;
+    TextEdit {
+        range: Range {
+            start: position(location.start_line, location.start_column),
+            end: position(location.end_line, location.end_column),
+        },
+        new_text: edit.new_text.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::TextDocumentItem;
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+        Range {
+            start: Position { line: start.0, character: start.1 },
+            end: Position { line: end.0, character: end.1 },
+        }
+    }
+
+    fn store() -> DocumentStore {
+        let mut documents = DocumentStore::new();
+        documents.open(TextDocumentItem {
+            uri: "file:///a.rs".into(),
+            language_id: "rust".into(),
+            version: 3,
+            text: "fn a() {}\nfn b() {}".into(),
+        });
+        documents
+    }
+
+    #[test]
+    fn test_validate_versions_and_ranges() {
+        let documents = store();
+        let insert = |r| vec![TextEdit { range: r, new_text: "x".into() }];
+
+        assert!(WorkspaceEdit::new().edit("file:///a.rs", Some(3), insert(range((1, 0), (1, 9)))).validate(&documents).is_ok());
+        assert!(matches!(
+            WorkspaceEdit::new().edit("file:///a.rs", Some(2), insert(range((0, 0), (0, 1)))).validate(&documents),
+            Err(EditError::StaleVersion { expected: 2, actual: 3, .. })
+        ));
+        assert!(matches!(
+            WorkspaceEdit::new().edit("file:///a.rs", None, insert(range((0, 0), (0, 10)))).validate(&documents),
+            Err(EditError::InvalidRange { .. })
+        ));
+
+        let overlapping = vec![
+            TextEdit { range: range((0, 0), (0, 5)), new_text: String::new() },
+            TextEdit { range: range((0, 3), (0, 4)), new_text: String::new() },
+        ];
+        assert_eq!(
+            WorkspaceEdit::new().edit("file:///a.rs", None, overlapping).validate(&documents),
+            Err(EditError::Overlapping("file:///a.rs".into()))
+        );
+
+        let created = WorkspaceEdit::new()
+            .operation(ResourceOperation::Create { uri: "file:///t.rs".into(), options: FileOptions::default() })
+            .edit("file:///t.rs", None, insert(range((0, 0), (0, 0))));
+        assert!(created.validate(&documents).is_ok());
+    }
+
+    #[test]
+    fn test_wire_forms() {
+        let edit = WorkspaceEdit::new()
+            .operation(ResourceOperation::Rename {
+                old_uri: "file:///a.rs".into(),
+                new_uri: "file:///b.rs".into(),
+                options: FileOptions { overwrite: true, ..Default::default() },
+            })
+            .edit("file:///b.rs", Some(1), vec![TextEdit { range: range((0, 0), (0, 0)), new_text: "//".into() }]);
+
+        let full = EditSupport { apply_edit: true, document_changes: true, resource_operations: vec!["rename".into()] };
+        let value = edit.to_value(&full);
+        assert_eq!(value["documentChanges"][0]["kind"], "rename");
+        assert_eq!(value["documentChanges"][0]["newUri"], "file:///b.rs");
+        assert_eq!(value["documentChanges"][0]["options"]["overwrite"], true);
+        assert_eq!(value["documentChanges"][1]["textDocument"]["version"], 1);
+        assert_eq!(serde_json::from_value::<WorkspaceEdit>(value).unwrap(), edit);
+
+        assert!(edit.supported_by(&full));
+        assert!(!edit.supported_by(&EditSupport::default()));
+
+        let text_only = WorkspaceEdit::new().edit("file:///a.rs", Some(1), vec![]);
+        assert_eq!(text_only.to_value(&EditSupport::default()), serde_json::json!({ "changes": { "file:///a.rs": [] } }));
+    }
+}
@@ -1,10 +1,23 @@
-//! AES-256-GCM - Implementação pura em Rust
+//! AES-GCM - Implementação pura em Rust
 //!
-//! Implementação completa de AES-256 em modo GCM (Galois/Counter Mode)
+//! Implementação completa de AES-128/256 em modo GCM (Galois/Counter Mode)
 //! Suporta tanto software puro quanto aceleração por hardware quando disponível
+//!
+//! Além de [`AesGcm::encrypt`]/[`AesGcm::decrypt`] (chave a cada chamada),
+//! [`AesGcm::encrypt_detached`] e [`AesGcm::decrypt_detached`] reaproveitam
+//! a chave expandida, trabalham no lugar com a tag separada e aceitam a AAD
+//! em pedaços, sem concatenar (ex.: cabeçalho de registro TLS + metadados).
+//!
+//! O GHASH usa o método de Shoup com nibbles: uma tabela de 16 múltiplos de
+//! `H` calculada por chave e 32 consultas por bloco, em vez de 128 passos
+//! bit a bit. A tabela (256 bytes, quatro linhas de cache) fica numa `Box`
+//! para o `AesGcm` continuar pequeno dentro de enums; é indexada por dados
+//! secretos, com a mesma ressalva de cache da S-box.
+
+use alloc::boxed::Box;
 
 // AES S-box
-const SBOX: [u8; 256] = [
+pub(super) const SBOX: [u8; 256] = [
     0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
     0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
     0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
@@ -24,58 +37,118 @@ const SBOX: [u8; 256] = [
 ];
 
 // Rcon para key expansion
-const RCON: [u8; 11] = [0x8d, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+pub(super) const RCON: [u8; 11] = [0x8d, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Redução módulo o polinômio do GCM para os 4 bits que saem em `z >> 4`
+const LAST4: [u16; 16] = [
+    0x0000, 0x1c20, 0x3840, 0x2460, 0x7080, 0x6ca0, 0x48c0, 0x54e0,
+    0xe100, 0xfd20, 0xd940, 0xc560, 0x9180, 0x8da0, 0xa9c0, 0xb5e0,
+];
+
+/// Múltiplos `n·H` da subchave do GHASH, um por nibble `n`
+struct GhashTable([u128; 16]);
+
+impl GhashTable {
+    fn new(h: u128) -> Self {
+        const R: u128 = 0xE1 << 120;
+        // Convenção refletida: o bit mais alto do nibble é o coeficiente de x^0
+        let mut table = [0u128; 16];
+        table[8] = h;
+        for i in [4, 2, 1] {
+            let v = table[i * 2];
+            table[i] = (v >> 1) ^ (R & (v & 1).wrapping_neg());
+        }
+        for i in [2, 4, 8] {
+            for j in 1..i {
+                table[i + j] = table[i] ^ table[j];
+            }
+        }
+        Self(table)
+    }
+
+    /// `x·H` em GF(2^128), do nibble menos significativo ao mais significativo
+    fn mul(&self, x: u128) -> u128 {
+        let mut z = 0u128;
+        for k in 0..32 {
+            let rem = (z & 0xf) as usize;
+            z = (z >> 4) ^ (u128::from(LAST4[rem]) << 112);
+            z ^= self.0[((x >> (4 * k)) & 0xf) as usize];
+        }
+        z
+    }
+}
 
-/// AES-256-GCM cipher
+/// AES-GCM cipher (chave de 16 ou 32 bytes)
 pub struct AesGcm {
     round_keys: [[u8; 16]; 15], // AES-256 tem 14 rounds + 1 inicial
+    /// 10 (AES-128) ou 14 (AES-256)
+    rounds: usize,
+    /// Tabela da subchave do GHASH, `H = E(K, 0^128)`
+    ghash: Box<GhashTable>,
 }
 
 impl AesGcm {
-    /// Cria novo cipher com a chave
+    /// Cria novo cipher AES-256 com a chave
     pub fn new(key: &[u8; 32]) -> Self {
+        Self::with_key(key)
+    }
+
+    /// Cria novo cipher AES-128 com a chave
+    pub fn new_128(key: &[u8; 16]) -> Self {
+        Self::with_key(key)
+    }
+
+    fn with_key(key: &[u8]) -> Self {
         let mut cipher = Self {
             round_keys: [[0u8; 16]; 15],
+            rounds: 0,
+            ghash: Box::new(GhashTable([0; 16])),
         };
         cipher.key_expansion(key);
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+        *cipher.ghash = GhashTable::new(u128::from_be_bytes(h));
         cipher
     }
 
-    /// Key expansion para AES-256
-    fn key_expansion(&mut self, key: &[u8; 32]) {
-        let mut w = [[0u8; 4]; 60]; // 4 * (14 + 1) = 60 words
+    /// Key expansion para AES-128 (Nk = 4) e AES-256 (Nk = 8)
+    fn key_expansion(&mut self, key: &[u8]) {
+        let nk = key.len() / 4;
+        self.rounds = nk + 6;
+        let total = 4 * (self.rounds + 1);
+        let mut w = [[0u8; 4]; 60]; // até 4 * (14 + 1) = 60 words
 
-        // Primeiras 8 words vêm da chave
-        for i in 0..8 {
+        // Primeiras Nk words vêm da chave
+        for i in 0..nk {
             w[i].copy_from_slice(&key[i * 4..(i + 1) * 4]);
         }
 
         // Expande o resto
-        for i in 8..60 {
+        for i in nk..total {
             let mut temp = w[i - 1];
 
-            if i % 8 == 0 {
+            if i % nk == 0 {
                 // RotWord + SubWord + Rcon
                 temp.rotate_left(1);
                 for byte in &mut temp {
                     *byte = SBOX[*byte as usize];
                 }
-                temp[0] ^= RCON[i / 8];
-            } else if i % 8 == 4 {
-                // SubWord apenas
+                temp[0] ^= RCON[i / nk];
+            } else if nk > 6 && i % nk == 4 {
+                // SubWord apenas (só AES-256)
                 for byte in &mut temp {
                     *byte = SBOX[*byte as usize];
                 }
             }
 
             for j in 0..4 {
-                temp[j] ^= w[i - 8][j];
+                temp[j] ^= w[i - nk][j];
             }
             w[i] = temp;
         }
 
         // Converte words para round keys
-        for i in 0..15 {
+        for i in 0..=self.rounds {
             for j in 0..4 {
                 self.round_keys[i][j * 4..(j + 1) * 4].copy_from_slice(&w[i * 4 + j]);
             }
@@ -83,14 +156,14 @@ impl AesGcm {
     }
 
     /// SubBytes transformation
-    fn sub_bytes(state: &mut [u8; 16]) {
+    pub(super) fn sub_bytes(state: &mut [u8; 16]) {
         for byte in state.iter_mut() {
             *byte = SBOX[*byte as usize];
         }
     }
 
     /// ShiftRows transformation
-    fn shift_rows(state: &mut [u8; 16]) {
+    pub(super) fn shift_rows(state: &mut [u8; 16]) {
         let temp = *state;
         // Row 0: no shift
         // Row 1: shift left by 1
@@ -111,7 +184,7 @@ impl AesGcm {
     }
 
     /// MixColumns transformation
-    fn mix_columns(state: &mut [u8; 16]) {
+    pub(super) fn mix_columns(state: &mut [u8; 16]) {
         fn xtime(x: u8) -> u8 {
             let msb = x & 0x80;
             let result = x << 1;
@@ -136,19 +209,19 @@ impl AesGcm {
     }
 
     /// AddRoundKey transformation
-    fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    pub(super) fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
         for i in 0..16 {
             state[i] ^= round_key[i];
         }
     }
 
-    /// Encripta um bloco AES-256
+    /// Encripta um bloco AES
     fn encrypt_block(&self, block: &mut [u8; 16]) {
         // Initial round
         Self::add_round_key(block, &self.round_keys[0]);
 
         // Main rounds
-        for round in 1..14 {
+        for round in 1..self.rounds {
             Self::sub_bytes(block);
             Self::shift_rows(block);
             Self::mix_columns(block);
@@ -158,11 +231,11 @@ impl AesGcm {
         // Final round (sem MixColumns)
         Self::sub_bytes(block);
         Self::shift_rows(block);
-        Self::add_round_key(block, &self.round_keys[14]);
+        Self::add_round_key(block, &self.round_keys[self.rounds]);
     }
 
     /// Incrementa counter para CTR mode
-    fn increment_counter(counter: &mut [u8; 16]) {
+    pub(super) fn increment_counter(counter: &mut [u8; 16]) {
         for i in (0..16).rev() {
             counter[i] = counter[i].wrapping_add(1);
             if counter[i] != 0 {
@@ -171,71 +244,21 @@ impl AesGcm {
         }
     }
 
-    /// GHASH para autenticação
-    fn ghash(h: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
-        let mut y = [0u8; 16];
-
-        // Process AAD
-        for chunk in aad.chunks(16) {
-            let mut block = [0u8; 16];
-            block[..chunk.len()].copy_from_slice(chunk);
-            for i in 0..16 {
-                y[i] ^= block[i];
-            }
-            Self::gmul(&mut y, h);
-        }
-
-        // Process ciphertext
-        for chunk in ciphertext.chunks(16) {
-            let mut block = [0u8; 16];
-            block[..chunk.len()].copy_from_slice(chunk);
-            for i in 0..16 {
-                y[i] ^= block[i];
-            }
-            Self::gmul(&mut y, h);
-        }
-
-        // Process lengths
-        let aad_bits = (aad.len() as u64) * 8;
-        let ct_bits = (ciphertext.len() as u64) * 8;
-        let mut len_block = [0u8; 16];
-        len_block[0..8].copy_from_slice(&aad_bits.to_be_bytes());
-        len_block[8..16].copy_from_slice(&ct_bits.to_be_bytes());
-
-        for i in 0..16 {
-            y[i] ^= len_block[i];
-        }
-        Self::gmul(&mut y, h);
-
-        y
+    /// Cifra `data` no lugar e devolve a tag; a AAD é a concatenação de `aad`
+    pub fn encrypt_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16] {
+        self.apply_ctr(nonce, data);
+        self.tag(nonce, aad, data)
     }
 
-    /// Multiplicação em GF(2^128) para GHASH
-    fn gmul(x: &mut [u8; 16], h: &[u8; 16]) {
-        let mut z = [0u8; 16];
-        let mut v = *h;
-
-        for byte in x.iter() {
-            for bit in (0..8).rev() {
-                if (byte >> bit) & 1 == 1 {
-                    for i in 0..16 {
-                        z[i] ^= v[i];
-                    }
-                }
-
-                let lsb = v[15] & 1;
-                for i in (1..16).rev() {
-                    v[i] = (v[i] >> 1) | (v[i - 1] << 7);
-                }
-                v[0] >>= 1;
-
-                if lsb == 1 {
-                    v[0] ^= 0xe1;
-                }
-            }
+    /// Confere `tag` e só então decifra `data` no lugar
+    ///
+    /// Retorna `false` (com `data` intacto) se a tag não confere.
+    pub fn decrypt_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8], tag: &[u8; 16]) -> bool {
+        if !ct_eq(&self.tag(nonce, aad, data), tag) {
+            return false;
         }
-
-        *x = z;
+        self.apply_ctr(nonce, data);
+        true
     }
 
     /// Criptografa com AES-256-GCM
@@ -249,46 +272,14 @@ impl AesGcm {
     ) {
         assert!(ciphertext.len() >= plaintext.len());
 
-        let cipher = Self::new(key);
-
-        // Gera H = E(K, 0^128)
-        let mut h = [0u8; 16];
-        cipher.encrypt_block(&mut h);
-
-        // Prepara counter inicial: nonce || 0x00000001
-        let mut counter = [0u8; 16];
-        counter[..12].copy_from_slice(nonce);
-        counter[15] = 1;
-
-        // Encripta usando CTR mode
-        for (i, chunk) in plaintext.chunks(16).enumerate() {
-            let mut keystream = counter;
-            cipher.encrypt_block(&mut keystream);
-
-            let offset = i * 16;
-            let len = chunk.len().min(16);
-            for j in 0..len {
-                ciphertext[offset + j] = chunk[j] ^ keystream[j];
-            }
-
-            Self::increment_counter(&mut counter);
-        }
-
-        // Calcula tag usando GHASH
-        let ghash_result = Self::ghash(&h, aad, &ciphertext[..plaintext.len()]);
-
-        // Tag = GHASH XOR E(K, nonce || 0x00000001)
-        let mut tag_mask = [0u8; 16];
-        tag_mask[..12].copy_from_slice(nonce);
-        tag_mask[15] = 1;
-        cipher.encrypt_block(&mut tag_mask);
-
-        for i in 0..16 {
-            tag[i] = ghash_result[i] ^ tag_mask[i];
-        }
+        let ciphertext = &mut ciphertext[..plaintext.len()];
+        ciphertext.copy_from_slice(plaintext);
+        *tag = Self::new(key).encrypt_detached(nonce, &[aad], ciphertext);
     }
 
     /// Decriptografa com AES-256-GCM
+    ///
+    /// Retorna true se a tag for válida; só então o plaintext é escrito
     pub fn decrypt(
         key: &[u8; 32],
         nonce: &[u8; 12],
@@ -299,51 +290,195 @@ impl AesGcm {
     ) -> bool {
         assert!(plaintext.len() >= ciphertext.len());
 
-        let cipher = Self::new(key);
-
         // Verifica tag primeiro
-        let mut h = [0u8; 16];
-        cipher.encrypt_block(&mut h);
+        let cipher = Self::new(key);
+        if !ct_eq(&cipher.tag(nonce, &[aad], ciphertext), tag) {
+            return false;
+        }
 
-        let ghash_result = Self::ghash(&h, aad, ciphertext);
+        let plaintext = &mut plaintext[..ciphertext.len()];
+        plaintext.copy_from_slice(ciphertext);
+        cipher.apply_ctr(nonce, plaintext);
+        true
+    }
 
-        let mut expected_tag = [0u8; 16];
-        expected_tag[..12].copy_from_slice(nonce);
-        expected_tag[15] = 1;
-        cipher.encrypt_block(&mut expected_tag);
+    /// CTR a partir de inc32(J0) = nonce || 0x00000002; J0 fica para a tag
+    fn apply_ctr(&self, nonce: &[u8; 12], data: &mut [u8]) {
+        let mut counter = [0u8; 16];
+        counter[..12].copy_from_slice(nonce);
+        counter[15] = 2;
 
-        for i in 0..16 {
-            expected_tag[i] ^= ghash_result[i];
+        for chunk in data.chunks_mut(16) {
+            let mut keystream = counter;
+            self.encrypt_block(&mut keystream);
+            for (byte, k) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= k;
+            }
+            Self::increment_counter(&mut counter);
         }
+    }
 
-        // Constant-time comparison
-        let mut diff = 0u8;
-        for i in 0..16 {
-            diff |= tag[i] ^ expected_tag[i];
-        }
+    /// Tag = GHASH(aad, ciphertext) XOR E(K, J0)
+    fn tag(&self, nonce: &[u8; 12], aad: &[&[u8]], ciphertext: &[u8]) -> [u8; 16] {
+        let mut y = 0u128;
+        let mut absorb = |block: &[u8; 16]| y = self.ghash.mul(y ^ u128::from_be_bytes(*block));
+        padded_blocks(aad, &mut absorb);
+        padded_blocks(&[ciphertext], &mut absorb);
+
+        let aad_bits = aad.iter().map(|part| part.len() as u128).sum::<u128>() * 8;
+        let ct_bits = (ciphertext.len() as u128) * 8;
+        let y = self.ghash.mul(y ^ (aad_bits << 64) ^ ct_bits);
+
+        let mut mask = [0u8; 16];
+        mask[..12].copy_from_slice(nonce);
+        mask[15] = 1;
+        self.encrypt_block(&mut mask);
+        (y ^ u128::from_be_bytes(mask)).to_be_bytes()
+    }
+}
 
-        if diff != 0 {
-            return false;
+/// Blocos de 16 bytes da concatenação de `parts`, o último completado com zeros
+fn padded_blocks(parts: &[&[u8]], mut f: impl FnMut(&[u8; 16])) {
+    let mut block = [0u8; 16];
+    let mut filled = 0;
+    for part in parts {
+        let mut part: &[u8] = part;
+        while !part.is_empty() {
+            let take = (16 - filled).min(part.len());
+            block[filled..filled + take].copy_from_slice(&part[..take]);
+            filled += take;
+            part = &part[take..];
+            if filled == 16 {
+                f(&block);
+                filled = 0;
+            }
         }
+    }
+    if filled > 0 {
+        block[filled..].fill(0);
+        f(&block);
+    }
+}
 
-        // Decripta usando CTR mode (idêntico à encriptação)
-        let mut counter = [0u8; 16];
-        counter[..12].copy_from_slice(nonce);
-        counter[15] = 1;
+/// Comparação sem desvio dependente do conteúdo
+pub(super) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
 
-        for (i, chunk) in ciphertext.chunks(16).enumerate() {
-            let mut keystream = counter;
-            cipher.encrypt_block(&mut keystream);
+/// Multiplicação bit a bit em GF(2^128), referência para a tabela
+#[cfg(test)]
+fn gf_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    let mut z = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & bit.wrapping_neg();
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & lsb.wrapping_neg());
+    }
+    z
+}
 
-            let offset = i * 16;
-            let len = chunk.len().min(16);
-            for j in 0..len {
-                plaintext[offset + j] = chunk[j] ^ keystream[j];
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
 
-            Self::increment_counter(&mut counter);
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ghash_table() {
+        // Compara com a multiplicação bit a bit em valores pseudoaleatórios
+        let mut state = 0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C834u128;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..64 {
+            let (h, x) = (next(), next());
+            assert_eq!(GhashTable::new(h).mul(x), gf_mul(x, h));
         }
+        assert_eq!(GhashTable::new(1 << 127).mul(0xdead_beef), 0xdead_beef);
+    }
 
-        true
+    #[test]
+    fn test_aes_block() {
+        // FIPS 197, apêndice C
+        let plaintext: [u8; 16] = hex("00112233445566778899aabbccddeeff").try_into().unwrap();
+        let mut block = plaintext;
+        AesGcm::new_128(&hex("000102030405060708090a0b0c0d0e0f").try_into().unwrap()).encrypt_block(&mut block);
+        assert_eq!(block.to_vec(), hex("69c4e0d86a7b0430d8cdb78070b4c55a"));
+        let mut block = plaintext;
+        AesGcm::new(&hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").try_into().unwrap())
+            .encrypt_block(&mut block);
+        assert_eq!(block.to_vec(), hex("8ea2b7ca516745bfeafc49904b496089"));
+    }
+
+    #[test]
+    fn test_nist_gcm_vector() {
+        // NIST GCM, caso de teste 16 (AES-256, com AAD)
+        let key: [u8; 32] = hex("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308")
+            .try_into()
+            .unwrap();
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        );
+
+        let mut ciphertext = alloc::vec![0u8; plaintext.len()];
+        let mut tag = [0u8; 16];
+        AesGcm::encrypt(&key, &nonce, &aad, &plaintext, &mut ciphertext, &mut tag);
+        assert_eq!(
+            ciphertext,
+            hex(
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+                 8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662"
+            )
+        );
+        assert_eq!(tag.to_vec(), hex("76fc6ece0f4e1768cddf8853bb2d551b"));
+
+        let mut decrypted = alloc::vec![0u8; ciphertext.len()];
+        assert!(AesGcm::decrypt(&key, &nonce, &aad, &ciphertext, &tag, &mut decrypted));
+        assert_eq!(decrypted, plaintext);
+
+        tag[0] ^= 1;
+        assert!(!AesGcm::decrypt(&key, &nonce, &aad, &ciphertext, &tag, &mut decrypted));
+    }
+
+    #[test]
+    fn test_detached() {
+        // NIST GCM, caso de teste 4 (AES-128), com a AAD em pedaços que não
+        // caem na fronteira de bloco
+        let cipher = AesGcm::new_128(&hex("feffe9928665731c6d6a8f9467308308").try_into().unwrap());
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        );
+        let parts: [&[u8]; 3] = [&aad[..3], &[], &aad[3..]];
+
+        let mut data = plaintext.clone();
+        let tag = cipher.encrypt_detached(&nonce, &parts, &mut data);
+        assert_eq!(&data[..16], &hex("42831ec2217774244b7221b784d0d49c")[..]);
+        assert_eq!(tag.to_vec(), hex("5bc94fbc3221a5db94fae95ae7121a47"));
+
+        let mut wrong = tag;
+        wrong[15] ^= 1;
+        let ciphertext = data.clone();
+        assert!(!cipher.decrypt_detached(&nonce, &parts, &mut data, &wrong));
+        assert_eq!(data, ciphertext);
+        assert!(cipher.decrypt_detached(&nonce, &[&aad], &mut data, &tag));
+        assert_eq!(data, plaintext);
     }
 }
@@ -0,0 +1,390 @@
+//! AES-SIV - AEAD determinística (RFC 5297)
+//!
+//! Pensada para criptografar chaves e valores de um key-value store onde o
+//! mesmo registro precisa sempre gerar o mesmo ciphertext (busca por
+//! igualdade, deduplicação, índices). Não recebe nonce: o IV sintético
+//! (SIV) é derivado do próprio conteúdo via S2V/CMAC.
+//!
+//! ## Modelo de vazamento
+//!
+//! - Mesmo plaintext + mesmos dados associados ⇒ **mesmo ciphertext**. Um
+//!   observador aprende quando dois registros são iguais (e quantas vezes um
+//!   valor se repete), mas nada além disso.
+//! - O tamanho do plaintext é revelado (ciphertext = 16 bytes de SIV + plaintext).
+//! - Reusar a chave não quebra confidencialidade nem autenticidade: o pior
+//!   caso é justamente o vazamento de igualdade acima (*misuse resistance*).
+//!
+//! Se a igualdade entre registros não puder vazar, inclua um nonce aleatório
+//! como último componente de `associated_data` (modo nonce-based da RFC 5297)
+//! ou use uma das cifras com nonce ([`super::aes_gcm`], ChaCha20-Poly1305).
+
+use alloc::vec::Vec;
+use super::aes_gcm::{AesGcm, RCON, SBOX};
+
+/// Tamanho do IV sintético prefixado ao ciphertext
+pub const SIV_LEN: usize = 16;
+
+/// Máximo de componentes de dados associados (RFC 5297, seção 7)
+pub const MAX_ASSOCIATED_DATA: usize = 126;
+
+/// Bloco AES com 10 (AES-128) ou 14 (AES-256) rounds
+struct AesBlock {
+    round_keys: [[u8; 16]; 15],
+    rounds: usize,
+}
+
+impl AesBlock {
+    /// Key expansion para chaves de 16 ou 32 bytes
+    fn new(key: &[u8]) -> Self {
+        let nk = key.len() / 4;
+        let rounds = nk + 6;
+        let total = 4 * (rounds + 1);
+        let mut w = [[0u8; 4]; 60];
+
+        for i in 0..nk {
+            w[i].copy_from_slice(&key[i * 4..(i + 1) * 4]);
+        }
+
+        for i in nk..total {
+            let mut temp = w[i - 1];
+
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                for byte in &mut temp {
+                    *byte = SBOX[*byte as usize];
+                }
+                temp[0] ^= RCON[i / nk];
+            } else if nk > 6 && i % nk == 4 {
+                for byte in &mut temp {
+                    *byte = SBOX[*byte as usize];
+                }
+            }
+
+            for j in 0..4 {
+                temp[j] ^= w[i - nk][j];
+            }
+            w[i] = temp;
+        }
+
+        let mut round_keys = [[0u8; 16]; 15];
+        for i in 0..=rounds {
+            for j in 0..4 {
+                round_keys[i][j * 4..(j + 1) * 4].copy_from_slice(&w[i * 4 + j]);
+            }
+        }
+
+        Self { round_keys, rounds }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        AesGcm::add_round_key(block, &self.round_keys[0]);
+
+        for round in 1..self.rounds {
+            AesGcm::sub_bytes(block);
+            AesGcm::shift_rows(block);
+            AesGcm::mix_columns(block);
+            AesGcm::add_round_key(block, &self.round_keys[round]);
+        }
+
+        AesGcm::sub_bytes(block);
+        AesGcm::shift_rows(block);
+        AesGcm::add_round_key(block, &self.round_keys[self.rounds]);
+    }
+
+    /// AES-CMAC (RFC 4493)
+    fn cmac(&self, message: &[u8]) -> [u8; 16] {
+        let mut l = [0u8; 16];
+        self.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+
+        let blocks = message.len().div_ceil(16).max(1);
+
+        let mut x = [0u8; 16];
+        for chunk in message.chunks(16).take(blocks - 1) {
+            xor_in(&mut x, chunk);
+            self.encrypt_block(&mut x);
+        }
+
+        let tail = &message[(blocks - 1) * 16..];
+        let mut last = [0u8; 16];
+        last[..tail.len()].copy_from_slice(tail);
+        if tail.len() == 16 {
+            xor_in(&mut last, &k1);
+        } else {
+            last[tail.len()] = 0x80;
+            xor_in(&mut last, &k2);
+        }
+
+        xor_in(&mut x, &last);
+        self.encrypt_block(&mut x);
+        x
+    }
+}
+
+/// Multiplicação por x em GF(2^128) (polinômio x^128 + x^7 + x^2 + x + 1)
+fn dbl(block: &[u8; 16]) -> [u8; 16] {
+    let mut out = [0u8; 16];
+    for i in 0..15 {
+        out[i] = (block[i] << 1) | (block[i + 1] >> 7);
+    }
+    out[15] = block[15] << 1;
+    if block[0] & 0x80 != 0 {
+        out[15] ^= 0x87;
+    }
+    out
+}
+
+fn xor_in(target: &mut [u8; 16], data: &[u8]) {
+    for (t, d) in target.iter_mut().zip(data) {
+        *t ^= d;
+    }
+}
+
+/// AES-SIV: AEAD determinística, separada das cifras com nonce
+pub struct AesSiv {
+    mac: AesBlock,
+    ctr: AesBlock,
+}
+
+impl AesSiv {
+    /// AES-SIV-512: chave de 64 bytes (K1 para S2V, K2 para CTR, ambas AES-256)
+    pub fn new(key: &[u8; 64]) -> Self {
+        Self {
+            mac: AesBlock::new(&key[..32]),
+            ctr: AesBlock::new(&key[32..]),
+        }
+    }
+
+    /// AES-SIV-256: chave de 32 bytes (duas chaves AES-128), para interoperar
+    pub fn new_256(key: &[u8; 32]) -> Self {
+        Self {
+            mac: AesBlock::new(&key[..16]),
+            ctr: AesBlock::new(&key[16..]),
+        }
+    }
+
+    /// Criptografa de forma determinística
+    ///
+    /// Retorna `SIV || ciphertext` (16 bytes a mais que o plaintext).
+    ///
+    /// # Panics
+    /// Se houver mais de [`MAX_ASSOCIATED_DATA`] componentes de dados associados.
+    pub fn encrypt(&self, associated_data: &[&[u8]], plaintext: &[u8]) -> Vec<u8> {
+        let siv = self.s2v(associated_data, plaintext);
+
+        let mut output = Vec::with_capacity(SIV_LEN + plaintext.len());
+        output.extend_from_slice(&siv);
+        output.extend_from_slice(plaintext);
+        self.apply_ctr(&siv, &mut output[SIV_LEN..]);
+        output
+    }
+
+    /// Decriptografa e verifica `SIV || ciphertext`
+    ///
+    /// Retorna `None` se o ciphertext ou os dados associados foram alterados.
+    ///
+    /// # Panics
+    /// Se houver mais de [`MAX_ASSOCIATED_DATA`] componentes de dados associados.
+    pub fn decrypt(&self, associated_data: &[&[u8]], ciphertext: &[u8]) -> Option<Vec<u8>> {
+        if ciphertext.len() < SIV_LEN {
+            return None;
+        }
+
+        let mut siv = [0u8; 16];
+        siv.copy_from_slice(&ciphertext[..SIV_LEN]);
+
+        let mut plaintext = ciphertext[SIV_LEN..].to_vec();
+        self.apply_ctr(&siv, &mut plaintext);
+
+        let expected = self.s2v(associated_data, &plaintext);
+
+        // Constant-time comparison
+        let mut diff = 0u8;
+        for i in 0..16 {
+            diff |= siv[i] ^ expected[i];
+        }
+
+        if diff != 0 {
+            // Não expõe o plaintext não autenticado
+            plaintext.iter_mut().for_each(|b| *b = 0);
+            return None;
+        }
+
+        Some(plaintext)
+    }
+
+    /// Criptografa `data` no lugar e devolve o SIV, que faz o papel de tag
+    ///
+    /// Diferente do GCM, cada fatia de `associated_data` é um componente
+    /// separado do S2V, não um pedaço de uma AAD concatenada.
+    ///
+    /// # Panics
+    /// Se houver mais de [`MAX_ASSOCIATED_DATA`] componentes de dados associados.
+    pub fn encrypt_detached(&self, associated_data: &[&[u8]], data: &mut [u8]) -> [u8; SIV_LEN] {
+        let siv = self.s2v(associated_data, data);
+        self.apply_ctr(&siv, data);
+        siv
+    }
+
+    /// Decriptografa `data` no lugar e confere o SIV
+    ///
+    /// Retorna `false` (com `data` de volta ao ciphertext) se o ciphertext,
+    /// o SIV ou os dados associados foram alterados.
+    ///
+    /// # Panics
+    /// Se houver mais de [`MAX_ASSOCIATED_DATA`] componentes de dados associados.
+    pub fn decrypt_detached(&self, associated_data: &[&[u8]], data: &mut [u8], siv: &[u8; SIV_LEN]) -> bool {
+        self.apply_ctr(siv, data);
+        let expected = self.s2v(associated_data, data);
+
+        // Constant-time comparison
+        let mut diff = 0u8;
+        for i in 0..16 {
+            diff |= siv[i] ^ expected[i];
+        }
+
+        if diff != 0 {
+            // Não expõe o plaintext não autenticado
+            self.apply_ctr(siv, data);
+            return false;
+        }
+        true
+    }
+
+    /// S2V: PRF sobre um vetor de strings (RFC 5297, seção 2.4)
+    fn s2v(&self, associated_data: &[&[u8]], plaintext: &[u8]) -> [u8; 16] {
+        assert!(
+            associated_data.len() <= MAX_ASSOCIATED_DATA,
+            "AES-SIV supports at most {} associated data components",
+            MAX_ASSOCIATED_DATA
+        );
+
+        let mut d = self.mac.cmac(&[0u8; 16]);
+
+        for ad in associated_data {
+            d = dbl(&d);
+            xor_in(&mut d, &self.mac.cmac(ad));
+        }
+
+        if plaintext.len() >= 16 {
+            // xorend: D é combinado com os últimos 16 bytes
+            let mut t = plaintext.to_vec();
+            let offset = t.len() - 16;
+            for (i, byte) in d.iter().enumerate() {
+                t[offset + i] ^= byte;
+            }
+            self.mac.cmac(&t)
+        } else {
+            let mut t = dbl(&d);
+            let mut padded = [0u8; 16];
+            padded[..plaintext.len()].copy_from_slice(plaintext);
+            padded[plaintext.len()] = 0x80;
+            xor_in(&mut t, &padded);
+            self.mac.cmac(&t)
+        }
+    }
+
+    /// CTR com os bits 31 e 63 do SIV zerados
+    fn apply_ctr(&self, siv: &[u8; 16], data: &mut [u8]) {
+        let mut counter = *siv;
+        counter[8] &= 0x7f;
+        counter[12] &= 0x7f;
+
+        for chunk in data.chunks_mut(16) {
+            let mut keystream = counter;
+            self.ctr.encrypt_block(&mut keystream);
+            for (byte, k) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= k;
+            }
+            AesGcm::increment_counter(&mut counter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rfc5297_deterministic_vector() {
+        let key: [u8; 32] = hex("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")
+            .try_into()
+            .unwrap();
+        let ad = hex("101112131415161718191a1b1c1d1e1f2021222324252627");
+        let plaintext = hex("112233445566778899aabbccddee");
+
+        let siv = AesSiv::new_256(&key);
+        let ciphertext = siv.encrypt(&[&ad], &plaintext);
+        assert_eq!(
+            ciphertext,
+            hex("85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c")
+        );
+        assert_eq!(siv.decrypt(&[&ad], &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_rfc5297_nonce_based_vector() {
+        let key: [u8; 32] = hex("7f7e7d7c7b7a79787776757473727170404142434445464748494a4b4c4d4e4f")
+            .try_into()
+            .unwrap();
+        let ad1 = hex("00112233445566778899aabbccddeeffdeaddadadeaddadaffeeddccbbaa99887766554433221100");
+        let ad2 = hex("102030405060708090a0");
+        let nonce = hex("09f911029d74e35bd84156c5635688c0");
+        let plaintext = hex("7468697320697320736f6d6520706c61696e7465787420746f20656e6372797074207573696e67205349562d414553");
+
+        let siv = AesSiv::new_256(&key);
+        let ciphertext = siv.encrypt(&[&ad1, &ad2, &nonce], &plaintext);
+        assert_eq!(
+            ciphertext,
+            hex("7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe404326601965c889bf17dba77ceb094fa663b7a3f748ba8af829ea64ad544a272e9c485b62a3fd5c0d")
+        );
+    }
+
+    #[test]
+    fn test_siv_512_is_deterministic_and_authenticated() {
+        let key: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let siv = AesSiv::new(&key);
+
+        let a = siv.encrypt(&[b"header"], b"metadata key");
+        let b = siv.encrypt(&[b"header"], b"metadata key");
+        assert_eq!(a, b);
+        assert_eq!(a, hex("a4170287c322b96089ad0855a14a41a0df4e5c5b62c68b6fae5acd97"));
+        assert_ne!(a, siv.encrypt(&[b"other"], b"metadata key"));
+
+        assert_eq!(siv.decrypt(&[b"header"], &a).unwrap(), b"metadata key");
+        assert!(siv.decrypt(&[b"other"], &a).is_none());
+
+        let mut tampered = a.clone();
+        tampered[20] ^= 1;
+        assert!(siv.decrypt(&[b"header"], &tampered).is_none());
+        assert!(siv.decrypt(&[], &a[..8]).is_none());
+    }
+
+    #[test]
+    fn test_detached_matches_attached() {
+        let key: [u8; 32] = hex("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")
+            .try_into()
+            .unwrap();
+        let ad = hex("101112131415161718191a1b1c1d1e1f2021222324252627");
+        let plaintext = hex("112233445566778899aabbccddee");
+
+        let siv = AesSiv::new_256(&key);
+        let mut data = plaintext.clone();
+        let tag = siv.encrypt_detached(&[&ad], &mut data);
+        assert_eq!([&tag[..], &data[..]].concat(), siv.encrypt(&[&ad], &plaintext));
+
+        let ciphertext = data.clone();
+        assert!(!siv.decrypt_detached(&[&ad, b""], &mut data, &tag));
+        assert_eq!(data, ciphertext);
+        assert!(siv.decrypt_detached(&[&ad], &mut data, &tag));
+        assert_eq!(data, plaintext);
+    }
+}
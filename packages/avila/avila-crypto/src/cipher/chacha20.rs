@@ -6,6 +6,74 @@
 //! - Rápido em software
 //! - Não requer AES-NI
 //! - NSA não consegue quebrar
+//!
+//! ## Geração vetorizada
+//!
+//! Com dados suficientes, [`ChaCha20::apply_keystream`] gera 4 ou 8 blocos
+//! consecutivos de uma vez, com cada palavra do estado guardada como um vetor
+//! de lanes (um bloco por lane). O laço sobre as lanes é compilado para
+//! SSE2/NEON (4 lanes) ou AVX2 (8 lanes) pelo próprio LLVM.
+//!
+//! [`Backend::detect`] consulta a CPU em tempo de execução (`cpuid` em x86,
+//! ver `crate::cpu`), e as 8 lanes rodam numa função com AVX2 habilitado,
+//! chamada só quando a CPU o tem; um build para `x86_64` genérico também gera
+//! código de 256 bits. Fora de x86 vale [`Backend::compiled`]: as features
+//! do alvo em tempo de compilação (NEON é base em aarch64). O caminho
+//! escalar continua cuidando da cauda.
+
+use crate::cpu::{self, Avx2};
+
+/// Bytes por bloco ChaCha20
+const BLOCK_LEN: usize = 64;
+
+/// Caminho de geração do keystream
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Um bloco por vez
+    Scalar,
+    /// 4 blocos por vez (SSE2/NEON)
+    Lanes4,
+    /// 8 blocos por vez (AVX2)
+    Lanes8,
+}
+
+impl Backend {
+    /// Caminho mais largo disponível na CPU em execução
+    ///
+    /// Fora de x86 o resultado é o de [`Self::compiled`].
+    #[must_use]
+    pub fn detect() -> Self {
+        if cpu::has_avx2() {
+            Backend::Lanes8
+        } else if cpu::has_sse2() {
+            Backend::Lanes4
+        } else {
+            Self::compiled()
+        }
+    }
+
+    /// Caminho mais largo para as features do alvo compilado
+    #[must_use]
+    pub const fn compiled() -> Self {
+        if cfg!(any(target_feature = "avx2", target_feature = "avx512f")) {
+            Backend::Lanes8
+        } else if cfg!(any(target_feature = "sse2", target_feature = "neon", target_feature = "simd128")) {
+            Backend::Lanes4
+        } else {
+            Backend::Scalar
+        }
+    }
+
+    /// Blocos gerados por iteração
+    #[must_use]
+    pub const fn lanes(self) -> usize {
+        match self {
+            Backend::Scalar => 1,
+            Backend::Lanes4 => 4,
+            Backend::Lanes8 => 8,
+        }
+    }
+}
 
 /// ChaCha20 state: 16 × u32
 #[derive(Clone, Copy)]
@@ -101,9 +169,115 @@ impl ChaCha20 {
         working_state
     }
 
+    /// `N` blocos consecutivos (contadores `state[12]..state[12] + N`) em paralelo
+    ///
+    /// Estado em layout por palavra: `x[palavra][lane]`, para o laço interno
+    /// sobre as lanes virar uma instrução vetorial.
+    #[inline(always)]
+    fn blocks<const N: usize>(&self) -> [[u32; N]; 16] {
+        let mut initial = [[0u32; N]; 16];
+        for (word, lanes) in initial.iter_mut().enumerate() {
+            *lanes = [self.state[word]; N];
+        }
+        for (lane, counter) in initial[12].iter_mut().enumerate() {
+            *counter = counter.wrapping_add(lane as u32);
+        }
+
+        let mut x = initial;
+        for _ in 0..10 {
+            // Column rounds
+            Self::quarter_round_lanes(&mut x, 0, 4, 8, 12);
+            Self::quarter_round_lanes(&mut x, 1, 5, 9, 13);
+            Self::quarter_round_lanes(&mut x, 2, 6, 10, 14);
+            Self::quarter_round_lanes(&mut x, 3, 7, 11, 15);
+
+            // Diagonal rounds
+            Self::quarter_round_lanes(&mut x, 0, 5, 10, 15);
+            Self::quarter_round_lanes(&mut x, 1, 6, 11, 12);
+            Self::quarter_round_lanes(&mut x, 2, 7, 8, 13);
+            Self::quarter_round_lanes(&mut x, 3, 4, 9, 14);
+        }
+
+        for (word, lanes) in x.iter_mut().enumerate() {
+            for (value, original) in lanes.iter_mut().zip(initial[word]) {
+                *value = value.wrapping_add(original);
+            }
+        }
+        x
+    }
+
+    /// Quarter round aplicado a todas as lanes
+    #[inline(always)]
+    #[allow(clippy::needless_range_loop)] // cada lane lê e escreve várias linhas de `x`
+    fn quarter_round_lanes<const N: usize>(x: &mut [[u32; N]; 16], a: usize, b: usize, c: usize, d: usize) {
+        for lane in 0..N {
+            x[a][lane] = x[a][lane].wrapping_add(x[b][lane]);
+            x[d][lane] = (x[d][lane] ^ x[a][lane]).rotate_left(16);
+        }
+        for lane in 0..N {
+            x[c][lane] = x[c][lane].wrapping_add(x[d][lane]);
+            x[b][lane] = (x[b][lane] ^ x[c][lane]).rotate_left(12);
+        }
+        for lane in 0..N {
+            x[a][lane] = x[a][lane].wrapping_add(x[b][lane]);
+            x[d][lane] = (x[d][lane] ^ x[a][lane]).rotate_left(8);
+        }
+        for lane in 0..N {
+            x[c][lane] = x[c][lane].wrapping_add(x[d][lane]);
+            x[b][lane] = (x[b][lane] ^ x[c][lane]).rotate_left(7);
+        }
+    }
+
+    /// XOR de todos os grupos de `N` blocos completos; devolve a sobra
+    #[inline(always)]
+    #[allow(clippy::inline_always)] // inlinada sob `Avx2::run`, herda o AVX2
+    fn xor_wide<'a, const N: usize>(&mut self, data: &'a mut [u8]) -> &'a mut [u8] {
+        let mut chunks = data.chunks_exact_mut(N * BLOCK_LEN);
+        for chunk in &mut chunks {
+            self.xor_blocks::<N>(chunk);
+        }
+        chunks.into_remainder()
+    }
+
+    /// `xor_wide::<8>`, compilado com AVX2 se a CPU o tiver
+    fn xor_wide_8<'a>(&mut self, data: &'a mut [u8]) -> &'a mut [u8] {
+        match Avx2::detect() {
+            Some(avx2) => avx2.run(|| self.xor_wide::<8>(data)),
+            None => self.xor_wide::<8>(data),
+        }
+    }
+
+    /// XOR de `N` blocos completos de `data` com o keystream, avançando o contador
+    #[inline(always)]
+    fn xor_blocks<const N: usize>(&mut self, data: &mut [u8]) {
+        let keystream = self.blocks::<N>();
+        for (lane, block) in data.chunks_exact_mut(BLOCK_LEN).take(N).enumerate() {
+            for (word, bytes) in block.chunks_exact_mut(4).enumerate() {
+                for (byte, key) in bytes.iter_mut().zip(keystream[word][lane].to_le_bytes()) {
+                    *byte ^= key;
+                }
+            }
+        }
+        self.state[12] = self.state[12].wrapping_add(N as u32);
+    }
+
     /// Criptografa/decriptografa dados (XOR stream)
     pub fn apply_keystream(&mut self, data: &mut [u8]) {
-        for chunk in data.chunks_mut(64) {
+        self.apply_keystream_with(Backend::detect(), data);
+    }
+
+    /// Como [`Self::apply_keystream`], forçando o caminho de geração
+    ///
+    /// Todos os caminhos produzem o mesmo keystream; serve para testes e
+    /// benchmarks comparando os backends.
+    pub fn apply_keystream_with(&mut self, backend: Backend, data: &mut [u8]) {
+        let rest = match backend {
+            Backend::Scalar => data,
+            Backend::Lanes4 => self.xor_wide::<4>(data),
+            Backend::Lanes8 => self.xor_wide_8(data),
+        };
+
+        for chunk in rest.chunks_mut(BLOCK_LEN) {
             let keystream = self.block();
 
             // XOR dados com keystream
@@ -120,69 +294,246 @@ impl ChaCha20 {
     }
 }
 
-/// Poly1305 MAC
-pub struct Poly1305 {
-    // TODO: Implementar Poly1305 authenticator
+pub use crate::mac::poly1305::Poly1305;
+
+/// Bytes da tag de autenticação
+pub const TAG_LEN: usize = 16;
+
+/// ChaCha20-Poly1305 AEAD (RFC 8439, 2.8) com a chave guardada
+///
+/// Trabalha no lugar, com a tag separada e a AAD em pedaços (a AAD
+/// autenticada é a concatenação deles), sem alocar.
+#[derive(Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; 32],
 }
 
-impl Poly1305 {
-    /// Computa MAC de dados
-    pub fn mac(key: &[u8; 32], data: &[u8]) -> [u8; 16] {
-        // TODO: Implementar
-        [0u8; 16]
+impl ChaCha20Poly1305 {
+    /// Cria o AEAD com a chave de 32 bytes
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Cifra `data` no lugar e devolve a tag; a AAD é a concatenação de `aad`
+    pub fn encrypt_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; TAG_LEN] {
+        // Keystream a partir do bloco 1; o bloco 0 vira a chave do Poly1305
+        ChaCha20::new(&self.key, nonce, 1).apply_keystream(data);
+        self.tag(nonce, aad, data)
+    }
+
+    /// Confere `tag` em tempo constante e só então decifra `data` no lugar
+    ///
+    /// Retorna `false` (com `data` intacto) se a tag não confere.
+    pub fn decrypt_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8], tag: &[u8; TAG_LEN]) -> bool {
+        let expected = self.tag(nonce, aad, data);
+        if expected.iter().zip(tag).fold(0, |acc, (a, b)| acc | (a ^ b)) != 0 {
+            return false;
+        }
+        ChaCha20::new(&self.key, nonce, 1).apply_keystream(data);
+        true
+    }
+
+    /// Poly1305 sobre aad || pad || ciphertext || pad || len(aad) || len(ciphertext),
+    /// com a chave de uso único tirada do bloco 0
+    fn tag(&self, nonce: &[u8; 12], aad: &[&[u8]], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+        let mut poly_key = [0u8; 32];
+        ChaCha20::new(&self.key, nonce, 0).apply_keystream(&mut poly_key);
+
+        let mut poly = Poly1305::new(&poly_key);
+        let aad_len: usize = aad.iter().map(|part| part.len()).sum();
+        for part in aad {
+            poly.update(part);
+        }
+        poly.update(&[0u8; 16][..(16 - aad_len % 16) % 16]);
+        poly.update(ciphertext);
+        poly.update(&[0u8; 16][..(16 - ciphertext.len() % 16) % 16]);
+
+        let mut lengths = [0u8; 16];
+        lengths[..8].copy_from_slice(&(aad_len as u64).to_le_bytes());
+        lengths[8..].copy_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+        poly.update(&lengths);
+        poly.finalize()
     }
 }
 
-/// ChaCha20-Poly1305 AEAD encrypt
+/// ChaCha20-Poly1305 AEAD encrypt (RFC 8439, 2.8)
 ///
-/// Retorna o tamanho do ciphertext (igual ao plaintext)
-/// Caller deve alocar buffer com tamanho adequado
+/// Escreve o ciphertext (mesmo tamanho do plaintext) e a tag, que cobre
+/// `aad` e o ciphertext. Caller deve alocar buffer com tamanho adequado
 pub fn chacha20_poly1305_encrypt(
     key: &[u8; 32],
     nonce: &[u8; 12],
-    _aad: &[u8],
+    aad: &[u8],
     plaintext: &[u8],
     ciphertext: &mut [u8],
-    tag: &mut [u8; 16],
+    tag: &mut [u8; TAG_LEN],
 ) {
     assert!(ciphertext.len() >= plaintext.len());
+    let ciphertext = &mut ciphertext[..plaintext.len()];
 
-    // Copia plaintext para ciphertext
-    ciphertext[..plaintext.len()].copy_from_slice(plaintext);
-
-    // Aplica keystream
-    let mut cipher = ChaCha20::new(key, nonce, 1);
-    cipher.apply_keystream(&mut ciphertext[..plaintext.len()]);
-
-    // Calcula MAC
-    *tag = Poly1305::mac(key, &ciphertext[..plaintext.len()]);
+    ciphertext.copy_from_slice(plaintext);
+    *tag = ChaCha20Poly1305::new(key).encrypt_detached(nonce, &[aad], ciphertext);
 }
 
 /// ChaCha20-Poly1305 AEAD decrypt
 ///
-/// Retorna true se MAC válido, false caso contrário
+/// Retorna true se a tag for válida; só então o plaintext é escrito
 pub fn chacha20_poly1305_decrypt(
     key: &[u8; 32],
     nonce: &[u8; 12],
-    _aad: &[u8],
+    aad: &[u8],
     ciphertext: &[u8],
-    tag: &[u8; 16],
+    tag: &[u8; TAG_LEN],
     plaintext: &mut [u8],
 ) -> bool {
     assert!(plaintext.len() >= ciphertext.len());
 
-    // Verifica MAC primeiro
-    let computed_tag = Poly1305::mac(key, ciphertext);
-    if computed_tag != *tag {
+    // Verifica a tag primeiro, em tempo constante
+    let cipher = ChaCha20Poly1305::new(key);
+    let expected = cipher.tag(nonce, &[aad], ciphertext);
+    if expected.iter().zip(tag).fold(0, |acc, (a, b)| acc | (a ^ b)) != 0 {
         return false;
     }
 
-    // Copia ciphertext para plaintext
-    plaintext[..ciphertext.len()].copy_from_slice(ciphertext);
-
-    // Aplica keystream
-    let mut cipher = ChaCha20::new(key, nonce, 1);
-    cipher.apply_keystream(&mut plaintext[..ciphertext.len()]);
+    let plaintext = &mut plaintext[..ciphertext.len()];
+    plaintext.copy_from_slice(ciphertext);
+    ChaCha20::new(key, nonce, 1).apply_keystream(plaintext);
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const BACKENDS: [Backend; 3] = [Backend::Scalar, Backend::Lanes4, Backend::Lanes8];
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn key() -> [u8; 32] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn test_rfc8439_block_vector() {
+        // RFC 8439, seção 2.3.2: keystream do bloco com contador 1, gerado
+        // como primeira lane de cada backend
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let expected = hex(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        );
+        for backend in BACKENDS {
+            let mut keystream = alloc::vec![0u8; backend.lanes() * BLOCK_LEN];
+            ChaCha20::new(&key(), &nonce, 1).apply_keystream_with(backend, &mut keystream);
+            assert_eq!(&keystream[..BLOCK_LEN], &expected[..], "{:?}", backend);
+        }
+    }
+
+    #[test]
+    fn test_rfc8439_encryption_vector() {
+        // RFC 8439, seção 2.4.2
+        let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let expected = hex(
+            "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+             f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+             07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+             5af90bbf74a35be6b40b8eedf2785e42874d",
+        );
+        for backend in BACKENDS {
+            let mut data = plaintext.to_vec();
+            ChaCha20::new(&key(), &nonce, 1).apply_keystream_with(backend, &mut data);
+            assert_eq!(data, expected, "{:?}", backend);
+        }
+    }
+
+    #[test]
+    fn test_rfc8439_aead_vector() {
+        // RFC 8439, seção 2.8.2
+        let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+        let nonce = [0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+        let aad = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let expected = hex(
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+             3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+             92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+             3ff4def08e4b7a9de576d26586cec64b6116",
+        );
+
+        let mut ciphertext = alloc::vec![0u8; plaintext.len()];
+        let mut tag = [0u8; TAG_LEN];
+        chacha20_poly1305_encrypt(&key, &nonce, &aad, plaintext, &mut ciphertext, &mut tag);
+        assert_eq!(ciphertext, expected);
+        assert_eq!(tag.to_vec(), hex("1ae10b594f09e26a7e902ecbd0600691"));
+
+        let mut decrypted = alloc::vec![0u8; ciphertext.len()];
+        assert!(chacha20_poly1305_decrypt(&key, &nonce, &aad, &ciphertext, &tag, &mut decrypted));
+        assert_eq!(&decrypted[..], &plaintext[..]);
+
+        // Qualquer bit trocado no ciphertext, na tag ou no aad invalida
+        ciphertext[0] ^= 1;
+        assert!(!chacha20_poly1305_decrypt(&key, &nonce, &aad, &ciphertext, &tag, &mut decrypted));
+        ciphertext[0] ^= 1;
+        tag[15] ^= 0x80;
+        assert!(!chacha20_poly1305_decrypt(&key, &nonce, &aad, &ciphertext, &tag, &mut decrypted));
+        tag[15] ^= 0x80;
+        assert!(!chacha20_poly1305_decrypt(&key, &nonce, &aad[1..], &ciphertext, &tag, &mut decrypted));
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        // Tamanhos que passam pelo caminho largo e pela cauda escalar, com o
+        // contador dando a volta no meio
+        let nonce = [7u8; 12];
+        for len in [0, 63, 64, 255, 256, 511, 512, 1000, 4096 + 17] {
+            let plaintext: Vec<u8> = (0..len).map(|i| (i * 31 % 251) as u8).collect();
+            let mut reference = plaintext.clone();
+            ChaCha20::new(&key(), &nonce, u32::MAX - 3).apply_keystream_with(Backend::Scalar, &mut reference);
+            for backend in [Backend::Lanes4, Backend::Lanes8, Backend::detect()] {
+                let mut cipher = ChaCha20::new(&key(), &nonce, u32::MAX - 3);
+                let mut data = plaintext.clone();
+                // Em duas chamadas (numa fronteira de bloco), para o contador seguir entre elas
+                let split = len / 3 / BLOCK_LEN * BLOCK_LEN;
+                cipher.apply_keystream_with(backend, &mut data[..split]);
+                cipher.apply_keystream_with(backend, &mut data[split..]);
+                assert_eq!(data, reference, "{:?} len {}", backend, len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_at_least_compiled() {
+        // A CPU em execução tem ao menos as features com que o alvo foi compilado
+        assert!(Backend::detect().lanes() >= Backend::compiled().lanes());
+    }
+
+    #[test]
+    fn test_detached_multi_part_aad() {
+        // RFC 8439, seção 2.8.2, com a AAD em pedaços
+        let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+        let nonce = [0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+        let aad = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let parts: [&[u8]; 3] = [&aad[..5], &[], &aad[5..]];
+
+        let aead = ChaCha20Poly1305::new(&key);
+        let mut data = plaintext.to_vec();
+        let tag = aead.encrypt_detached(&nonce, &parts, &mut data);
+        assert_eq!(&data[..16], &hex("d31a8d34648e60db7b86afbc53ef7ec2")[..]);
+        assert_eq!(tag.to_vec(), hex("1ae10b594f09e26a7e902ecbd0600691"));
+
+        let ciphertext = data.clone();
+        assert!(!aead.decrypt_detached(&nonce, &parts[..1], &mut data, &tag));
+        assert_eq!(data, ciphertext);
+        assert!(aead.decrypt_detached(&nonce, &[&aad], &mut data, &tag));
+        assert_eq!(&data[..], &plaintext[..]);
+    }
+}
@@ -1,5 +1,8 @@
 //! Cifras simétricas aprovadas pela Ávila
 
+#![forbid(unsafe_code)]
+
 pub mod chacha20;
 pub mod xchacha20;
 pub mod aes_gcm;
+pub mod aes_siv;
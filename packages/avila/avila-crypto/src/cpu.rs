@@ -0,0 +1,124 @@
+//! Detecção de CPU e despacho para código compilado com AVX2
+//!
+//! Único módulo do crate com `unsafe`: os demais têm `#![forbid(unsafe_code)]`.
+//! Sem `std` não há `is_x86_feature_detected!`, então a consulta vai direto
+//! ao `cpuid`/`xgetbv`, uma vez só, e o resultado fica num atômico. Em
+//! aarch64 o NEON faz parte da arquitetura base e a checagem em tempo de
+//! compilação basta.
+
+/// Prova de que a CPU em execução tem AVX2
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Avx2(());
+
+impl Avx2 {
+    /// `Some` só se a CPU (e o SO, que salva os registradores YMM) suporta AVX2
+    pub(crate) fn detect() -> Option<Self> {
+        has_avx2().then_some(Avx2(()))
+    }
+
+    /// Roda `f` numa função compilada com AVX2
+    ///
+    /// O que `f` chamar com `#[inline(always)]` é gerado com instruções de
+    /// 256 bits, mesmo num build para `x86_64` genérico.
+    #[inline]
+    #[allow(clippy::unused_self)] // o token só prova que o AVX2 existe
+    pub(crate) fn run<R>(self, f: impl FnOnce() -> R) -> R {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            // SAFETY: `Avx2` só é criado por `detect`, depois de confirmar o AVX2
+            unsafe { x86::with_avx2(f) }
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            // `detect` sempre devolve `None` fora de x86
+            f()
+        }
+    }
+}
+
+/// AVX2 utilizável na CPU em execução
+pub(crate) fn has_avx2() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        cfg!(target_feature = "avx2") || x86::features() & x86::AVX2 != 0
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        false
+    }
+}
+
+/// SSE2 utilizável na CPU em execução (sempre, em `x86_64`)
+pub(crate) fn has_sse2() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        cfg!(target_feature = "sse2") || x86::features() & x86::SSE2 != 0
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        false
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{__cpuid, __cpuid_count, _xgetbv};
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    pub(super) const SSE2: u8 = 1 << 0;
+    pub(super) const AVX2: u8 = 1 << 1;
+    const DETECTED: u8 = 1 << 7;
+
+    static FEATURES: AtomicU8 = AtomicU8::new(0);
+
+    /// Bits `SSE2`/`AVX2`, consultando a CPU na primeira chamada
+    pub(super) fn features() -> u8 {
+        let cached = FEATURES.load(Ordering::Relaxed);
+        if cached & DETECTED != 0 {
+            return cached;
+        }
+        // Corridas só repetem a consulta, que dá sempre o mesmo resultado
+        let features = detect() | DETECTED;
+        FEATURES.store(features, Ordering::Relaxed);
+        features
+    }
+
+    fn detect() -> u8 {
+        let (max_leaf, leaf1) = (__cpuid(0).eax, __cpuid(1));
+        let mut features = 0;
+        if leaf1.edx & (1 << 26) != 0 {
+            features |= SSE2;
+        }
+
+        let osxsave = leaf1.ecx & (1 << 27) != 0;
+        let avx = leaf1.ecx & (1 << 28) != 0;
+        if max_leaf >= 7 && osxsave && avx {
+            let leaf7 = __cpuid_count(7, 0);
+            // SAFETY: com OSXSAVE ligado, a CPU tem XSAVE e o SO habilitou `xgetbv`
+            let xcr0 = unsafe { xcr0() };
+            // O SO precisa salvar XMM (bit 1) e YMM (bit 2) na troca de contexto
+            if xcr0 & 0b110 == 0b110 && leaf7.ebx & (1 << 5) != 0 {
+                features |= AVX2;
+            }
+        }
+        features
+    }
+
+    /// # Safety
+    /// A CPU tem XSAVE e o SO o habilitou (CPUID.1:ECX.OSXSAVE)
+    #[target_feature(enable = "xsave")]
+    unsafe fn xcr0() -> u64 {
+        // SAFETY: garantido por quem chama
+        unsafe { _xgetbv(0) }
+    }
+
+    /// # Safety
+    /// A CPU em execução tem AVX2
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn with_avx2<R>(f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
@@ -1,5 +1,7 @@
 //! Curvas elípticas aprovadas pela Ávila
 
+#![forbid(unsafe_code)]
+
 pub mod secp256k1;
 pub mod curve25519;
 pub mod bls12_381;
@@ -1,5 +1,7 @@
 //! Funções de hash criptográficas
 
+#![forbid(unsafe_code)]
+
 pub mod blake3;
 pub mod keccak;
 pub mod sha3;
@@ -17,9 +17,9 @@
 //! - SHA-2: aprovado demais pelos governos
 
 #![no_std]
-extern crate alloc;
 #![deny(unsafe_op_in_unsafe_fn)]
-#![forbid(unsafe_code)]
+// `forbid` em cada módulo; só `cpu` (detecção e despacho AVX2) tem `unsafe`
+#![deny(unsafe_code)]
 #![deny(unreachable_pub)]
 #![deny(rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
@@ -27,12 +27,17 @@ extern crate alloc;
 #![cfg_attr(clippy, deny(clippy::pedantic))]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
 pub mod curves;
 pub mod signatures;
 pub mod hash;
 pub mod cipher;
 pub mod mac;
 
+#[allow(unsafe_code)]
+mod cpu;
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -1,4 +1,6 @@
 ﻿//! Message Authentication Codes
 
+#![forbid(unsafe_code)]
+
 pub mod poly1305;
 pub mod hmac;
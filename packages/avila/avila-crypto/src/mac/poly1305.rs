@@ -3,7 +3,7 @@
 //! Designed by Daniel J. Bernstein
 //! Used with ChaCha20 for AEAD
 
-/// Poly1305 state: accumulator and key
+/// Poly1305 state: accumulator and key, in 26-bit limbs
 pub struct Poly1305 {
     r: [u32; 5],  // Clamped r (130 bits)
     h: [u32; 5],  // Accumulator (130 bits)
@@ -12,27 +12,26 @@ pub struct Poly1305 {
     buffer_len: usize,
 }
 
+const MASK26: u32 = 0x03ff_ffff;
+
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
 impl Poly1305 {
     /// Initialize with 32-byte key (r || s)
     pub fn new(key: &[u8; 32]) -> Self {
-        let mut r = [0u32; 5];
-        let mut s = [0u32; 4];
-
-        // Load r and clamp
-        r[0] = u32::from_le_bytes([key[0], key[1], key[2], key[3]]) & 0x0fffffff;
-        r[1] = u32::from_le_bytes([key[4], key[5], key[6], key[7]]) & 0x0ffffffc;
-        r[2] = u32::from_le_bytes([key[8], key[9], key[10], key[11]]) & 0x0ffffffc;
-        r[3] = u32::from_le_bytes([key[12], key[13], key[14], key[15]]) & 0x0ffffffc;
+        // Load r in 26-bit limbs and clamp
+        let r = [
+            le32(&key[0..]) & 0x03ff_ffff,
+            (le32(&key[3..]) >> 2) & 0x03ff_ff03,
+            (le32(&key[6..]) >> 4) & 0x03ff_c0ff,
+            (le32(&key[9..]) >> 6) & 0x03f0_3fff,
+            (le32(&key[12..]) >> 8) & 0x000f_ffff,
+        ];
 
         // Load s
-        for i in 0..4 {
-            s[i] = u32::from_le_bytes([
-                key[16 + i * 4],
-                key[16 + i * 4 + 1],
-                key[16 + i * 4 + 2],
-                key[16 + i * 4 + 3],
-            ]);
-        }
+        let s = core::array::from_fn(|i| le32(&key[16 + i * 4..]));
 
         Self {
             r,
@@ -57,17 +56,16 @@ impl Poly1305 {
 
             if self.buffer_len == 16 {
                 let buf = self.buffer;
-                self.process_block(&buf, false);
+                self.process_block(&buf, 1 << 24);
                 self.buffer_len = 0;
             }
         }
 
         // Process complete blocks
         while offset + 16 <= data.len() {
-            let block = &data[offset..offset + 16];
-            let mut block_arr = [0u8; 16];
-            block_arr.copy_from_slice(block);
-            self.process_block(&block_arr, false);
+            let mut block = [0u8; 16];
+            block.copy_from_slice(&data[offset..offset + 16]);
+            self.process_block(&block, 1 << 24);
             offset += 16;
         }
 
@@ -81,129 +79,90 @@ impl Poly1305 {
 
     /// Finalize and return 16-byte tag
     pub fn finalize(&mut self) -> [u8; 16] {
-        // Process final block if any
+        // Final partial block: 0x01 after the data instead of the 2^128 bit
         if self.buffer_len > 0 {
-            let buffer_copy = self.buffer;
-            let buffer_len = self.buffer_len;
-            self.process_block(&buffer_copy[..buffer_len], true);
+            let mut block = [0u8; 16];
+            block[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            block[self.buffer_len] = 1;
+            self.process_block(&block, 0);
+            self.buffer_len = 0;
         }
 
         // Fully reduce h mod 2^130-5
         self.reduce();
 
-        // h += s
+        // Pack into 4 words and add s mod 2^128
+        let [h0, h1, h2, h3, h4] = self.h;
+        let words = [h0 | (h1 << 26), (h1 >> 6) | (h2 << 20), (h2 >> 12) | (h3 << 14), (h3 >> 18) | (h4 << 8)];
+        let mut tag = [0u8; 16];
         let mut carry = 0u64;
         for i in 0..4 {
-            carry += self.h[i] as u64 + self.s[i] as u64;
-            self.h[i] = carry as u32;
+            carry += words[i] as u64 + self.s[i] as u64;
+            tag[i * 4..(i + 1) * 4].copy_from_slice(&(carry as u32).to_le_bytes());
             carry >>= 32;
         }
-
-        // Convert to bytes
-        let mut tag = [0u8; 16];
-        for i in 0..4 {
-            tag[i * 4..(i + 1) * 4].copy_from_slice(&self.h[i].to_le_bytes());
-        }
         tag
     }
 
-    fn process_block(&mut self, block: &[u8], is_final: bool) {
-        // Convert block to 130-bit number with padding bit
-        let mut c = [0u32; 5];
+    /// h = (h + block + hibit * 2^128) * r mod 2^130-5
+    fn process_block(&mut self, block: &[u8; 16], hibit: u32) {
+        let h = &mut self.h;
+        h[0] += le32(&block[0..]) & MASK26;
+        h[1] += (le32(&block[3..]) >> 2) & MASK26;
+        h[2] += (le32(&block[6..]) >> 4) & MASK26;
+        h[3] += (le32(&block[9..]) >> 6) & MASK26;
+        h[4] += (le32(&block[12..]) >> 8) | hibit;
+
+        // Schoolbook product; 2^130 = 5 (mod p) folds the high limbs back
+        let r = self.r.map(u64::from);
+        let s = [0, r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+        let [h0, h1, h2, h3, h4] = h.map(u64::from);
+        let mut d = [
+            h0 * r[0] + h1 * s[4] + h2 * s[3] + h3 * s[2] + h4 * s[1],
+            h0 * r[1] + h1 * r[0] + h2 * s[4] + h3 * s[3] + h4 * s[2],
+            h0 * r[2] + h1 * r[1] + h2 * r[0] + h3 * s[4] + h4 * s[3],
+            h0 * r[3] + h1 * r[2] + h2 * r[1] + h3 * r[0] + h4 * s[4],
+            h0 * r[4] + h1 * r[3] + h2 * r[2] + h3 * r[1] + h4 * r[0],
+        ];
 
-        let len = if is_final { block.len() } else { 16 };
-
-        for i in 0..len.min(16) {
-            let byte_idx = i / 4;
-            let shift = (i % 4) * 8;
-            c[byte_idx] |= (block[i] as u32) << shift;
+        // Carry propagation
+        for i in 0..4 {
+            d[i + 1] += d[i] >> 26;
+            h[i] = d[i] as u32 & MASK26;
         }
+        h[4] = d[4] as u32 & MASK26;
+        h[0] += (d[4] >> 26) as u32 * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK26;
+    }
 
-        // Add padding bit (2^128 for full block, 2^(8*len) for final)
-        if !is_final {
-            c[4] = 1;
-        } else if len < 16 {
-            let byte_idx = len / 4;
-            let shift = (len % 4) * 8;
-            if byte_idx < 5 {
-                c[byte_idx] |= 1u32 << shift;
-            }
-        } else {
-            c[4] = 1;
+    /// Carries h fully and subtracts p if h >= p, without branching
+    fn reduce(&mut self) {
+        let h = &mut self.h;
+        for i in 1..5 {
+            h[i] += h[i - 1] >> 26;
+            h[i - 1] &= MASK26;
         }
-
-        // h += c
-        let mut carry = 0u64;
+        h[0] += (h[4] >> 26) * 5;
+        h[4] &= MASK26;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK26;
+
+        // g = h + 5 - 2^130
+        let mut g = [0u32; 5];
+        let mut carry = 5;
         for i in 0..5 {
-            carry += self.h[i] as u64 + c[i] as u64;
-            self.h[i] = carry as u32;
-            carry >>= 32;
+            let sum = h[i] + carry;
+            g[i] = sum & MASK26;
+            carry = sum >> 26;
         }
+        g[4] = g[4].wrapping_add(carry << 26).wrapping_sub(1 << 26);
 
-        // h *= r (mod 2^130-5)
-        self.multiply_r();
-    }
-
-    fn multiply_r(&mut self) {
-        // Multiply h by r using schoolbook multiplication
-        let h0 = self.h[0] as u64;
-        let h1 = self.h[1] as u64;
-        let h2 = self.h[2] as u64;
-        let h3 = self.h[3] as u64;
-        let h4 = self.h[4] as u64;
-
-        let r0 = self.r[0] as u64;
-        let r1 = self.r[1] as u64;
-        let r2 = self.r[2] as u64;
-        let r3 = self.r[3] as u64;
-
-        // Precompute r * 5 for reduction (use u128 to prevent overflow)
-        let r1_5 = (r1 as u128) * 5;
-        let r2_5 = (r2 as u128) * 5;
-        let r3_5 = (r3 as u128) * 5;
-        let r0_5 = (r0 as u128) * 5;
-
-        // Multiply (with modular reduction via r*5) - use u128 arithmetic
-        let d0 = (h0 as u128) * (r0 as u128) + (h1 as u128) * r3_5 + (h2 as u128) * r2_5 + (h3 as u128) * r1_5 + (h4 as u128) * r0_5;
-        let mut d1 = (h0 as u128) * (r1 as u128) + (h1 as u128) * (r0 as u128) + (h2 as u128) * r3_5 + (h3 as u128) * r2_5 + (h4 as u128) * r1_5;
-        let mut d2 = (h0 as u128) * (r2 as u128) + (h1 as u128) * (r1 as u128) + (h2 as u128) * (r0 as u128) + (h3 as u128) * r3_5 + (h4 as u128) * r2_5;
-        let mut d3 = (h0 as u128) * (r3 as u128) + (h1 as u128) * (r2 as u128) + (h2 as u128) * (r1 as u128) + (h3 as u128) * (r0 as u128) + (h4 as u128) * r3_5;
-        let mut d4 = (h4 as u128) * ((r0 as u128) & 3);
-
-        // Carry propagation
-        let mut c = d0 >> 32;
-        self.h[0] = d0 as u32;
-        d1 += c;
-
-        c = d1 >> 32;
-        self.h[1] = d1 as u32;
-        d2 += c;
-
-        c = d2 >> 32;
-        self.h[2] = d2 as u32;
-        d3 += c;
-
-        c = d3 >> 32;
-        self.h[3] = d3 as u32;
-        d4 += c;
-
-        self.h[4] = d4 as u32;
-    }
-
-    fn reduce(&mut self) {
-        // Reduce h mod 2^130-5
-        let mut c = self.h[4] >> 2;
-        self.h[4] &= 3;
-
-        c = c.wrapping_mul(5);
-
-        for i in 0..4 {
-            let sum = (self.h[i] as u64).wrapping_add(c as u64);
-            self.h[i] = sum as u32;
-            c = (sum >> 32) as u32;
+        // g >= 0 means h >= p: keep g
+        let mask = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !mask) | (g[i] & mask);
         }
-
-        self.h[4] = self.h[4].wrapping_add(c);
     }
 
     /// One-shot MAC computation
@@ -225,6 +184,36 @@ mod tests {
         assert_eq!(tag.len(), 16);
     }
 
+    #[test]
+    fn test_rfc8439_vector() {
+        // RFC 8439, seção 2.5.2
+        let key = [
+            0x85, 0xd6, 0xbe, 0x78, 0x57, 0x55, 0x6d, 0x33, 0x7f, 0x44, 0x52, 0xfe, 0x42, 0xd5, 0x06, 0xa8,
+            0x01, 0x03, 0x80, 0x8a, 0xfb, 0x0d, 0xb2, 0xfd, 0x4a, 0xbf, 0xf6, 0xaf, 0x41, 0x49, 0xf5, 0x1b,
+        ];
+        let expected = [
+            0xa8, 0x06, 0x1d, 0xc1, 0x30, 0x51, 0x36, 0xc6, 0xc2, 0x2b, 0x8b, 0xaf, 0x0c, 0x01, 0x27, 0xa9,
+        ];
+        assert_eq!(Poly1305::mac(&key, b"Cryptographic Forum Research Group"), expected);
+
+        // Em pedaços que não caem em fronteira de bloco
+        let mut poly = Poly1305::new(&key);
+        for part in [&b"Cryptographic"[..], b" Forum Research", b" Group"] {
+            poly.update(part);
+        }
+        assert_eq!(poly.finalize(), expected);
+    }
+
+    #[test]
+    fn test_final_reduction() {
+        // r = 1, s = 0 e dois blocos 0xff..ff: h = 2^130 - 2 >= p, reduz para 3
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        let mut expected = [0u8; 16];
+        expected[0] = 3;
+        assert_eq!(Poly1305::mac(&key, &[0xff; 32]), expected);
+    }
+
     #[test]
     fn test_poly1305_consistency() {
         let key = [0x42; 32];
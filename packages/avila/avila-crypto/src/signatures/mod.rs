@@ -1,5 +1,7 @@
 //! Esquemas de assinatura digital aprovados pela Ávila
 
+#![forbid(unsafe_code)]
+
 pub mod ecdsa;
 pub mod schnorr;
 pub mod eddsa;
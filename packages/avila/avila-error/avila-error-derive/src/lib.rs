@@ -4,7 +4,7 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, Lit, Meta};
 
 /// Derive macro para criar implementações de erro
 /// Similar ao #[derive(thiserror::Error)]
-#[proc_macro_derive(Error, attributes(error))]
+#[proc_macro_derive(Error, attributes(error, from))]
 pub fn derive_error(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let _name = &input.ident;
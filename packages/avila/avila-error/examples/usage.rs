@@ -2,7 +2,7 @@
 //!
 //! Demonstra como substituir thiserror e anyhow
 
-use avila_error::{Error as AvilaError, ErrorKind, Result};
+use avila_error::Result;
 
 #[cfg(feature = "derive")]
 use avila_error::ErrorDerive as Error;
@@ -58,6 +58,7 @@ fn parse_json(_data: &str) -> std::result::Result<Config, std::io::Error> {
     Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid json"))
 }
 
+#[cfg(feature = "context")]
 #[derive(Debug)]
 struct Config {
     name: String,
@@ -71,7 +72,7 @@ fn basic_error_handling() -> Result<()> {
     let value = Some(42);
 
     // Usando bail!
-    if value.unwrap() < 0 {
+    if value.is_some_and(|v| v < 0) {
         avila_error::bail!("Value must be positive");
     }
 
@@ -105,6 +106,10 @@ fn main() {
             Ok(_) => println!("\n✅ Config loaded"),
             Err(e) => println!("\n❌ Context example: {}", e),
         }
+        match parse_config("{}") {
+            Ok(config) => println!("✅ Config: {}", config.name),
+            Err(e) => println!("❌ Context example: {}", e),
+        }
     }
 
     println!("\n=== All examples completed ===");
@@ -0,0 +1,186 @@
+//! Registro de códigos de erro estáveis
+//!
+//! Cada código tem um número que nunca muda nem é reaproveitado, um nome em
+//! `SCREAMING_SNAKE_CASE`, uma [`Category`] (que define o status HTTP) e se
+//! vale a pena repetir a operação. O registro fica todo aqui para que os
+//! números sejam únicos entre os crates; as faixas são:
+//!
+//! | Faixa | Origem |
+//! |-------|--------|
+//! | 1000-1999 | genéricos, um por [`ErrorKind`](crate::ErrorKind) |
+//! | 2000-2099 | HTTP (`avila-webframework`) |
+//! | 2100-2199 | jobs, workflows e fila de trabalho |
+//! | 3000-3099 | conversão IFC → GLB (`avila-vizzio-convert`) |
+//!
+//! Um código removido continua listado como comentário, para o número não
+//! ser reutilizado.
+
+use std::fmt;
+
+/// Classe do erro, da qual sai o status HTTP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Requisição ou dado malformado
+    InvalidInput,
+    Unauthorized,
+    NotFound,
+    /// Estado atual não permite a operação
+    Conflict,
+    /// Existiu, mas não vale mais (ex.: arrendamento expirado)
+    Gone,
+    TooLarge,
+    /// Bem formado, mas impossível de processar (ex.: IFC com geometria inválida)
+    Unprocessable,
+    /// Dependência fora do ar ou sobrecarga
+    Unavailable,
+    Timeout,
+    Internal,
+}
+
+impl Category {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InvalidInput => "invalid_input",
+            Self::Unauthorized => "unauthorized",
+            Self::NotFound => "not_found",
+            Self::Conflict => "conflict",
+            Self::Gone => "gone",
+            Self::TooLarge => "too_large",
+            Self::Unprocessable => "unprocessable",
+            Self::Unavailable => "unavailable",
+            Self::Timeout => "timeout",
+            Self::Internal => "internal",
+        }
+    }
+
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::InvalidInput => 400,
+            Self::Unauthorized => 401,
+            Self::NotFound => 404,
+            Self::Conflict => 409,
+            Self::Gone => 410,
+            Self::TooLarge => 413,
+            Self::Unprocessable => 422,
+            Self::Unavailable => 503,
+            Self::Timeout => 504,
+            Self::Internal => 500,
+        }
+    }
+}
+
+/// Código registrado; só existe pelas constantes abaixo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    code: u16,
+    name: &'static str,
+    category: Category,
+    retryable: bool,
+}
+
+macro_rules! registry {
+    ($($(#[$doc:meta])* $name:ident = $code:literal, $category:ident, $retryable:literal;)*) => {
+        impl ErrorCode {
+            $(
+                $(#[$doc])*
+                pub const $name: ErrorCode = ErrorCode {
+                    code: $code,
+                    name: stringify!($name),
+                    category: Category::$category,
+                    retryable: $retryable,
+                };
+            )*
+
+            /// Todos os códigos, em ordem numérica
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$name),*];
+        }
+    };
+}
+
+registry! {
+    // Genéricos
+    INTERNAL = 1000, Internal, false;
+    IO = 1001, Internal, true;
+    PARSE = 1002, InvalidInput, false;
+    NETWORK = 1003, Unavailable, true;
+    DATABASE = 1004, Unavailable, true;
+    AUTH = 1005, Unauthorized, false;
+    NOT_FOUND = 1006, NotFound, false;
+    INVALID_INPUT = 1007, InvalidInput, false;
+    INVALID_STATE = 1008, Conflict, false;
+    TLS = 1009, Unavailable, false;
+    SERIALIZATION = 1010, Internal, false;
+    OTHER = 1011, Internal, false;
+
+    // HTTP
+    /// Linha de requisição, cabeçalho ou framing do corpo inválido
+    HTTP_MALFORMED_REQUEST = 2000, InvalidInput, false;
+    HTTP_BODY_TOO_LARGE = 2001, TooLarge, false;
+    HTTP_ROUTE_NOT_FOUND = 2002, NotFound, false;
+    /// Prazo do chamador ou da rota esgotado
+    HTTP_DEADLINE_EXCEEDED = 2003, Timeout, true;
+    /// Bulkhead da rota cheio
+    HTTP_OVERLOADED = 2004, Unavailable, true;
+    HTTP_INVALID_JSON = 2005, InvalidInput, false;
+    HTTP_INVALID_MULTIPART = 2006, InvalidInput, false;
+    /// Parâmetro de caminho ou campo obrigatório inválido
+    HTTP_INVALID_PARAMETER = 2007, InvalidInput, false;
+
+    // Jobs, workflows e fila
+    JOB_NOT_FOUND = 2100, NotFound, false;
+    /// Id duplicado ou transição de estado não permitida
+    JOB_CONFLICT = 2101, Conflict, false;
+    /// Job recusado (validação, dependência circular, ...)
+    JOB_REJECTED = 2102, InvalidInput, false;
+    WORKFLOW_NOT_FOUND = 2103, NotFound, false;
+    WORKFLOW_RUN_NOT_FOUND = 2104, NotFound, false;
+    LEASE_NOT_FOUND = 2110, NotFound, false;
+    /// O job pode estar com outro worker: descarte o resultado
+    LEASE_EXPIRED = 2111, Gone, false;
+    LEASE_CONFLICT = 2112, Conflict, false;
+
+    // Conversão IFC → GLB
+    /// IFC malformado
+    CONVERT_PARSE = 3000, Unprocessable, false;
+    CONVERT_TESSELATION = 3001, Unprocessable, false;
+    /// Conversão cancelada pelo chamador ou por prazo
+    CONVERT_CANCELLED = 3002, Unavailable, true;
+    CONVERT_OPTIMIZATION = 3003, Internal, false;
+    CONVERT_EXPORT = 3004, Internal, false;
+    CONVERT_METADATA = 3005, Internal, false;
+    CONVERT_THUMBNAIL = 3006, Internal, false;
+}
+
+impl ErrorCode {
+    /// Código registrado com o número dado
+    pub fn from_code(code: u16) -> Option<ErrorCode> {
+        Self::ALL.iter().copied().find(|c| c.code == code)
+    }
+
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// Repetir a mesma operação mais tarde pode dar certo
+    pub fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+
+    pub fn http_status(&self) -> u16 {
+        self.category.http_status()
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{} {}", self.code, self.name)
+    }
+}
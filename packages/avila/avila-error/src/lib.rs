@@ -5,6 +5,12 @@
 //! - `derive`: Enable #[derive(Error)] macro
 //! - `context`: Enable Context trait for anyhow-style error handling
 //! - `full`: Enable all features
+//!
+//! # Códigos
+//! Todo [`Error`] tem um [`ErrorCode`] estável (número, categoria e se pode
+//! ser repetido): o registrado com [`Error::coded`]/[`Error::with_code`] ou o
+//! padrão do seu [`ErrorKind`]. [`Error::context`] empilha o que o chamador
+//! estava fazendo sem trocar o código.
 
 #[cfg(feature = "derive")]
 pub use avila_error_derive::Error as ErrorDerive;
@@ -12,11 +18,18 @@ pub use avila_error_derive::Error as ErrorDerive;
 use std::fmt;
 use std::error::Error as StdError;
 
+mod code;
+
+pub use code::{Category, ErrorCode};
+
 /// Generic error type for AVL Platform
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
     message: String,
+    code: Option<ErrorCode>,
+    /// Contextos, do mais interno ao mais externo
+    context: Vec<String>,
     source: Option<Box<dyn StdError + Send + Sync>>,
 }
 
@@ -36,15 +49,69 @@ pub enum ErrorKind {
     Other,
 }
 
+impl ErrorKind {
+    /// Código usado quando o erro não registra um próprio
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Io => ErrorCode::IO,
+            Self::Parse => ErrorCode::PARSE,
+            Self::Network => ErrorCode::NETWORK,
+            Self::Database => ErrorCode::DATABASE,
+            Self::Auth => ErrorCode::AUTH,
+            Self::NotFound => ErrorCode::NOT_FOUND,
+            Self::InvalidInput => ErrorCode::INVALID_INPUT,
+            Self::InvalidState => ErrorCode::INVALID_STATE,
+            Self::Internal => ErrorCode::INTERNAL,
+            Self::Tls => ErrorCode::TLS,
+            Self::Serialization => ErrorCode::SERIALIZATION,
+            Self::Other => ErrorCode::OTHER,
+        }
+    }
+}
+
+impl Category {
+    /// `ErrorKind` de um erro criado só com o código
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::InvalidInput | Self::TooLarge | Self::Unprocessable => ErrorKind::InvalidInput,
+            Self::Unauthorized => ErrorKind::Auth,
+            Self::NotFound => ErrorKind::NotFound,
+            Self::Conflict | Self::Gone => ErrorKind::InvalidState,
+            Self::Unavailable | Self::Timeout => ErrorKind::Network,
+            Self::Internal => ErrorKind::Internal,
+        }
+    }
+}
+
 impl Error {
     pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
             kind,
             message: message.into(),
+            code: None,
+            context: Vec::new(),
             source: None,
         }
     }
 
+    /// Erro com código registrado; o `ErrorKind` sai da categoria
+    pub fn coded(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(code.category().kind(), message).with_code(code)
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Acrescenta o que se estava fazendo quando o erro aconteceu
+    ///
+    /// Aparece antes da mensagem no `Display`, o mais externo primeiro.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context.push(context.into());
+        self
+    }
+
     pub fn with_source<E>(mut self, source: E) -> Self
     where
         E: StdError + Send + Sync + 'static,
@@ -57,6 +124,25 @@ impl Error {
         self.kind
     }
 
+    /// Código registrado ou, na falta dele, o padrão do `ErrorKind`
+    pub fn code(&self) -> ErrorCode {
+        self.code.unwrap_or_else(|| self.kind.code())
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.code().is_retryable()
+    }
+
+    /// Mensagem sem contexto nem causa
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Contextos, do mais externo ao mais interno
+    pub fn contexts(&self) -> impl Iterator<Item = &str> {
+        self.context.iter().rev().map(String::as_str)
+    }
+
     // Convenience constructors
     pub fn io(message: impl Into<String>) -> Self {
         Self::new(ErrorKind::Io, message)
@@ -97,6 +183,9 @@ impl Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.contexts() {
+            write!(f, "{}: ", context)?;
+        }
         write!(f, "{}", self.message)?;
         if let Some(ref source) = self.source {
             write!(f, ": {}", source)?;
@@ -184,11 +273,7 @@ where
     where
         C: fmt::Display + Send + Sync + 'static,
     {
-        self.map_err(|error| {
-            let mut err = Error::new(ErrorKind::Other, context.to_string());
-            err.source = Some(Box::new(error));
-            err
-        })
+        self.map_err(|error| wrap(error, context.to_string()))
     }
 
     fn with_context<C, F>(self, f: F) -> Result<T>
@@ -196,12 +281,24 @@ where
         C: fmt::Display + Send + Sync + 'static,
         F: FnOnce() -> C,
     {
-        self.map_err(|error| {
-            let context = f();
-            let mut err = Error::new(ErrorKind::Other, context.to_string());
-            err.source = Some(Box::new(error));
+        self.map_err(|error| wrap(error, f().to_string()))
+    }
+}
+
+/// Um `Error` só ganha o contexto (mantém código e cadeia); outros viram a causa
+#[cfg(feature = "context")]
+fn wrap<E>(error: E, context: String) -> Error
+where
+    E: StdError + Send + Sync + 'static,
+{
+    let boxed: Box<dyn StdError + Send + Sync> = Box::new(error);
+    match boxed.downcast::<Error>() {
+        Ok(error) => error.context(context),
+        Err(source) => {
+            let mut err = Error::new(ErrorKind::Other, context);
+            err.source = Some(source);
             err
-        })
+        }
     }
 }
 
@@ -243,4 +340,52 @@ mod tests {
         let err = Error::io("Failed to read file").with_source(io_err);
         assert!(err.source().is_some());
     }
+
+    #[test]
+    fn test_error_codes() {
+        let err = Error::not_found("Item not found");
+        assert_eq!(err.code(), ErrorCode::NOT_FOUND);
+        assert_eq!(err.code().http_status(), 404);
+
+        let err = Error::coded(ErrorCode::HTTP_OVERLOADED, "Route is full");
+        assert_eq!(err.kind(), ErrorKind::Network);
+        assert!(err.is_retryable());
+        let err = Error::io("disk").with_code(ErrorCode::CONVERT_EXPORT);
+        assert_eq!((err.kind(), err.code().name()), (ErrorKind::Io, "CONVERT_EXPORT"));
+
+        // Números únicos, em ordem, e cada código achável pelo número
+        assert!(ErrorCode::ALL.windows(2).all(|pair| pair[0].code() < pair[1].code()));
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_code(code.code()), Some(*code));
+        }
+        assert_eq!(ErrorCode::from_code(9999), None);
+        assert_eq!(ErrorCode::LEASE_EXPIRED.to_string(), "E2111 LEASE_EXPIRED");
+    }
+
+    #[test]
+    fn test_context_chain() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = Error::coded(ErrorCode::CONVERT_PARSE, "invalid header")
+            .with_source(io_err)
+            .context("parsing model.ifc")
+            .context("converting job 7");
+        assert_eq!(err.to_string(), "converting job 7: parsing model.ifc: invalid header: no such file");
+        assert_eq!(err.contexts().collect::<Vec<_>>(), ["converting job 7", "parsing model.ifc"]);
+        assert_eq!(err.message(), "invalid header");
+        assert_eq!(err.code(), ErrorCode::CONVERT_PARSE);
+    }
+
+    #[cfg(feature = "context")]
+    #[test]
+    fn test_context_trait_keeps_code() {
+        let result: Result<()> = Err(Error::coded(ErrorCode::JOB_NOT_FOUND, "Job 3 not found"));
+        let err = result.context("cancelling job").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::JOB_NOT_FOUND);
+        assert_eq!(err.to_string(), "cancelling job: Job 3 not found");
+
+        let parsed: std::result::Result<u8, _> = "x".parse::<u8>();
+        let err = parsed.context("reading port").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::OTHER);
+        assert!(err.source().is_some());
+    }
 }
@@ -0,0 +1,213 @@
+//! Animação de visibilidade por elemento
+//!
+//! Sequências 4D (elementos surgindo e sumindo ao longo da obra) viram uma
+//! animação glTF padrão: cada node cuja mesh tem o `element_guid` de uma
+//! trilha recebe um canal `scale` com interpolação `STEP`, alternando entre
+//! 1 (visível) e 0 (oculto). Qualquer player glTF reproduz a sequência sem
+//! extensões.
+//!
+//! Meshes que juntam vários elementos (merge por material) não têm GUID e
+//! ficam fora da animação.
+
+use crate::{GltfAccessor, GltfBufferView, GltfError, GltfRoot, Result};
+use avila_mesh::Scene;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Trilhas de visibilidade por GUID de elemento
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityAnimation {
+    pub name: Option<String>,
+    /// GUID → keyframes em ordem crescente de tempo
+    pub tracks: BTreeMap<String, Vec<VisibilityKey>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityKey {
+    /// Segundos desde o início da animação
+    pub time: f32,
+    pub visible: bool,
+}
+
+impl VisibilityAnimation {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: Some(name.into()), tracks: BTreeMap::new() }
+    }
+
+    pub fn add_track(&mut self, guid: impl Into<String>, keys: Vec<VisibilityKey>) {
+        self.tracks.insert(guid.into(), keys);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GltfAnimation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    channels: Vec<GltfChannel>,
+    samplers: Vec<GltfAnimationSampler>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfChannel {
+    sampler: u32,
+    target: GltfChannelTarget,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfChannelTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node: Option<u32>,
+    path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfAnimationSampler {
+    input: u32,
+    #[serde(default = "linear")]
+    interpolation: String,
+    output: u32,
+}
+
+fn linear() -> String {
+    "LINEAR".into()
+}
+
+/// Acrescenta a animação ao documento; o node `i` desenha `scene.meshes[i]`
+pub(crate) fn add_visibility(
+    animation: &VisibilityAnimation,
+    scene: &Scene,
+    gltf: &mut GltfRoot,
+    bin_data: &mut Vec<u8>,
+) -> Result<()> {
+    let mut samplers: HashMap<&str, u32> = HashMap::new();
+    let mut result = GltfAnimation { name: animation.name.clone(), channels: Vec::new(), samplers: Vec::new() };
+
+    for (node, mesh) in scene.meshes.iter().enumerate() {
+        let Some((guid, keys)) = mesh.element_guid.as_deref().and_then(|guid| animation.tracks.get_key_value(guid)) else {
+            continue;
+        };
+        let sampler = match samplers.get(guid.as_str()) {
+            Some(&sampler) => sampler,
+            None => {
+                validate(guid, keys)?;
+                let times: Vec<f32> = keys.iter().map(|k| k.time).collect();
+                let scales: Vec<f32> = keys.iter().flat_map(|k| [if k.visible { 1.0 } else { 0.0 }; 3]).collect();
+                let first = times[0];
+                let last = times[times.len() - 1];
+                let input = add_accessor(gltf, bin_data, &times, "SCALAR", Some((vec![first], vec![last])));
+                let output = add_accessor(gltf, bin_data, &scales, "VEC3", None);
+
+                let sampler = result.samplers.len() as u32;
+                result.samplers.push(GltfAnimationSampler { input, interpolation: "STEP".into(), output });
+                samplers.insert(guid, sampler);
+                sampler
+            }
+        };
+        result.channels.push(GltfChannel {
+            sampler,
+            target: GltfChannelTarget { node: Some(node as u32), path: "scale".into() },
+        });
+    }
+
+    if !result.channels.is_empty() {
+        gltf.animations.push(result);
+    }
+    Ok(())
+}
+
+fn validate(guid: &str, keys: &[VisibilityKey]) -> Result<()> {
+    if keys.is_empty() {
+        return Err(GltfError::ExportError(format!("Visibility track '{}' has no keys", guid)));
+    }
+    if keys.iter().any(|k| !k.time.is_finite() || k.time < 0.0) || keys.windows(2).any(|w| w[1].time <= w[0].time) {
+        return Err(GltfError::ExportError(format!(
+            "Visibility track '{}' needs non-negative, strictly increasing times",
+            guid
+        )));
+    }
+    Ok(())
+}
+
+/// Dados de animação no BIN (bufferView sem `target`, como pede a especificação)
+fn add_accessor(
+    gltf: &mut GltfRoot,
+    bin_data: &mut Vec<u8>,
+    data: &[f32],
+    accessor_type: &str,
+    bounds: Option<(Vec<f32>, Vec<f32>)>,
+) -> u32 {
+    let byte_offset = bin_data.len() as u32;
+    bin_data.extend(data.iter().flat_map(|v| v.to_le_bytes()));
+
+    let buffer_view = gltf.buffer_views.len() as u32;
+    gltf.buffer_views.push(GltfBufferView {
+        buffer: 0,
+        byte_offset,
+        byte_length: (data.len() * 4) as u32,
+        byte_stride: None,
+        target: None,
+    });
+
+    let components = if accessor_type == "VEC3" { 3 } else { 1 };
+    let (min, max) = bounds.unzip();
+    gltf.accessors.push(GltfAccessor {
+        buffer_view: Some(buffer_view),
+        byte_offset: 0,
+        component_type: 5126, // FLOAT
+        count: data.len() / components,
+        accessor_type: accessor_type.into(),
+        min,
+        max,
+    });
+    (gltf.accessors.len() - 1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExportOptions, GltfExporter};
+    use avila_mesh::primitives;
+
+    #[test]
+    fn test_visibility_animation() {
+        let mut scene = Scene::new();
+        for guid in ["wall", "wall", "slab", "other"] {
+            let mut cube = primitives::cube(1.0);
+            cube.element_guid = Some(guid.into());
+            scene.add_mesh(cube);
+        }
+
+        let key = |time, visible| VisibilityKey { time, visible };
+        let mut animation = VisibilityAnimation::new("Sequência 4D");
+        animation.add_track("wall", vec![key(0.0, false), key(2.5, true)]);
+        animation.add_track("slab", vec![key(0.0, false), key(5.0, true)]);
+        animation.add_track("missing", vec![key(0.0, true)]);
+
+        let opts = ExportOptions { animation: Some(animation.clone()), ..Default::default() };
+        let glb = GltfExporter::new().export_glb(&scene, &opts).unwrap();
+        let len = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        let json: serde_json::Value = serde_json::from_slice(&glb[20..20 + len]).unwrap();
+
+        let exported = &json["animations"][0];
+        assert_eq!(exported["name"], "Sequência 4D");
+        // Duas meshes da parede compartilham o sampler
+        let channels = exported["channels"].as_array().unwrap();
+        let targets: Vec<_> = channels.iter().map(|c| (c["target"]["node"].clone(), c["sampler"].clone())).collect();
+        assert_eq!(targets, vec![(0.into(), 0.into()), (1.into(), 0.into()), (2.into(), 1.into())]);
+        assert_eq!(channels[0]["target"]["path"], "scale");
+
+        let sampler = &exported["samplers"][1];
+        assert_eq!(sampler["interpolation"], "STEP");
+        let input = &json["accessors"][sampler["input"].as_u64().unwrap() as usize];
+        assert_eq!((input["count"].clone(), input["max"][0].clone()), (2.into(), 5.0.into()));
+        let output = &json["accessors"][sampler["output"].as_u64().unwrap() as usize];
+        assert_eq!((output["type"].clone(), output["count"].clone()), ("VEC3".into(), 2.into()));
+
+        // O GLB animado continua importável
+        assert_eq!(crate::GltfImporter::new().import_glb(&glb).unwrap().meshes.len(), 4);
+
+        animation.add_track("wall", vec![key(1.0, true), key(1.0, false)]);
+        let opts = ExportOptions { animation: Some(animation), ..Default::default() };
+        assert!(GltfExporter::new().export_glb(&scene, &opts).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! Exportação `.gltf` com arquivos separados
+//!
+//! Gera o documento JSON, o `.bin` com a geometria e um PNG por textura da
+//! cena, todos referenciados por URIs relativas ao `.gltf`. Nada é gravado
+//! em disco: [`GltfFiles::files`] mapeia cada caminho relativo ao conteúdo,
+//! e quem chama decide onde gravar ([`GltfFiles::write_to`] grava num
+//! diretório).
+//!
+//! Buffers e texturas pequenos podem ir como data URI base64 dentro do
+//! próprio `.gltf` ([`GltfFileOptions::inline_limit`]).
+
+use crate::{
+    finish, ExportOptions, ExportReport, GltfBuffer, GltfError, GltfExporter, NameAllocator, NameCollision, Result,
+};
+use avila_mesh::Scene;
+use avila_url::percent::{self, EncodeSet};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Layout dos arquivos gerados por [`GltfExporter::export_gltf`]
+#[derive(Debug, Clone)]
+pub struct GltfFileOptions {
+    /// Nome base: `{name}.gltf` e `{name}.bin`
+    pub name: String,
+    /// Subdiretório das texturas, relativo ao `.gltf` (vazio = mesmo diretório)
+    pub texture_dir: String,
+    /// Buffers e texturas com até este tamanho viram data URI (0 = nunca)
+    pub inline_limit: usize,
+}
+
+impl Default for GltfFileOptions {
+    fn default() -> Self {
+        Self {
+            name: "model".into(),
+            texture_dir: "textures".into(),
+            inline_limit: 0,
+        }
+    }
+}
+
+/// Resultado de [`GltfExporter::export_gltf`]
+#[derive(Debug, Clone)]
+pub struct GltfFiles {
+    /// Caminho do documento `.gltf` em `files`
+    pub document: String,
+    /// Caminho relativo (separado por `/`) → conteúdo, incluindo o documento
+    pub files: BTreeMap<String, Vec<u8>>,
+    pub report: ExportReport,
+}
+
+impl GltfFiles {
+    /// Grava todos os arquivos sob `dir`, criando os subdiretórios
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        for (path, data) in &self.files {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, data)?;
+        }
+        Ok(())
+    }
+}
+
+impl GltfExporter {
+    /// Exporta para `.gltf` + `.bin` + texturas PNG, sem gravar em disco
+    pub fn export_gltf(&self, scene: &Scene, opts: &ExportOptions, layout: &GltfFileOptions) -> Result<GltfFiles> {
+        let name = file_name(&layout.name, "model");
+        let mut textures = TextureFiles::new(layout)?;
+        let (mut gltf, bin, report) = self.build(scene, opts, Some(&mut textures))?;
+        let mut files = textures.files;
+
+        if !bin.is_empty() {
+            let uri = if bin.len() <= layout.inline_limit {
+                data_uri("application/octet-stream", &bin)
+            } else {
+                let path = format!("{}.bin", name);
+                let uri = relative_uri(&path);
+                files.insert(path, bin.clone());
+                uri
+            };
+            gltf.buffers.push(GltfBuffer {
+                byte_length: bin.len() as u32,
+                uri: Some(uri),
+            });
+        }
+
+        let document = format!("{}.gltf", name);
+        files.insert(document.clone(), finish(gltf, &report, opts)?.into_bytes());
+        Ok(GltfFiles { document, files, report })
+    }
+}
+
+/// Texturas da cena gravadas como arquivos (ou data URIs)
+pub(crate) struct TextureFiles {
+    dir: String,
+    inline_limit: usize,
+    names: NameAllocator,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl TextureFiles {
+    fn new(layout: &GltfFileOptions) -> Result<Self> {
+        let mut segments = Vec::new();
+        for segment in layout.texture_dir.split(['/', '\\']) {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    return Err(GltfError::ExportError(format!(
+                        "Texture directory must stay inside the output: '{}'",
+                        layout.texture_dir
+                    )))
+                }
+                segment => segments.push(file_name(segment, "textures")),
+            }
+        }
+        Ok(Self {
+            dir: segments.join("/"),
+            inline_limit: layout.inline_limit,
+            names: NameAllocator::new(NameCollision::Suffix),
+            files: BTreeMap::new(),
+        })
+    }
+
+    /// Guarda o PNG da textura `id` e devolve a URI relativa ao `.gltf`
+    pub(crate) fn store(&mut self, id: &str, png: Vec<u8>) -> String {
+        if png.len() <= self.inline_limit {
+            return data_uri("image/png", &png);
+        }
+        let stem = self.names.allocate(Some(file_name(id, "texture"))).unwrap_or_default();
+        let path = if self.dir.is_empty() {
+            format!("{}.png", stem)
+        } else {
+            format!("{}/{}.png", self.dir, stem)
+        };
+        let uri = relative_uri(&path);
+        self.files.insert(path, png);
+        uri
+    }
+}
+
+/// Nome de arquivo seguro: ASCII alfanumérico, `-`, `_` e `.` (sem `.` inicial)
+fn file_name(name: &str, fallback: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '_' })
+        .collect();
+    let safe = safe.trim_start_matches('.');
+    if safe.is_empty() {
+        fallback.to_string()
+    } else {
+        safe.to_string()
+    }
+}
+
+/// Caminho relativo como URI, segmento a segmento
+fn relative_uri(path: &str) -> String {
+    path.split('/')
+        .map(|segment| percent::encode(segment, EncodeSet::PathSegment))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn data_uri(mime_type: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", mime_type, base64(data))
+}
+
+/// Base64 padrão (RFC 4648) com `=`
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let group = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::{bake_maps, primitives, BakeOptions, PbrMaterial};
+
+    fn textured_scene() -> Scene {
+        let mut scene = Scene::new();
+        let mut low = primitives::plane(2.0, 2.0);
+        low.material_id = Some("floor slab".into());
+        scene.add_material(PbrMaterial::default_material("floor slab"));
+        let options = BakeOptions { width: 8, height: 8, ..Default::default() };
+        bake_maps(&low, &low, &options).unwrap().attach_to(&mut scene, "floor slab").unwrap();
+        scene.add_mesh(low);
+        scene
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(file_name("../a b/ç.png", "x"), "_a_b__.png");
+        assert_eq!(relative_uri("tex maps/a.png"), "tex%20maps/a.png");
+    }
+
+    #[test]
+    fn test_export_separate_files() {
+        let scene = textured_scene();
+        let layout = GltfFileOptions {
+            name: "torre A".into(),
+            texture_dir: "./maps/baked".into(),
+            inline_limit: 0,
+        };
+        let exported = GltfExporter::new().export_gltf(&scene, &ExportOptions::default(), &layout).unwrap();
+        assert_eq!(exported.document, "torre_A.gltf");
+        let paths: Vec<&str> = exported.files.keys().map(String::as_str).collect();
+        assert!(paths.contains(&"torre_A.bin"));
+        assert!(paths.contains(&"maps/baked/floor_slab_normal.png"));
+
+        let root: serde_json::Value = serde_json::from_slice(&exported.files["torre_A.gltf"]).unwrap();
+        assert_eq!(root["buffers"][0]["uri"], "torre_A.bin");
+        assert_eq!(root["buffers"][0]["byteLength"], exported.files["torre_A.bin"].len());
+        let image = &root["images"][0];
+        assert_eq!(image["uri"], "maps/baked/floor_slab_normal.png");
+        assert!(image.get("bufferView").is_none());
+        let png = &exported.files["maps/baked/floor_slab_normal.png"];
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert!(exported.report.embedded_textures.contains(&"floor slab_normal".to_string()));
+
+        // A geometria é a mesma do GLB, que leva os PNGs antes dela
+        let (_, bin, _) = GltfExporter::new().export_parts(&scene, &ExportOptions::default()).unwrap();
+        assert!(bin.len() > exported.files["torre_A.bin"].len());
+        assert!(bin.ends_with(&exported.files["torre_A.bin"]));
+
+        let escape = GltfFileOptions { texture_dir: "../outside".into(), ..Default::default() };
+        assert!(GltfExporter::new().export_gltf(&scene, &ExportOptions::default(), &escape).is_err());
+    }
+
+    #[test]
+    fn test_inline_data_uris() {
+        let scene = textured_scene();
+        let layout = GltfFileOptions { inline_limit: 1 << 20, ..Default::default() };
+        let exported = GltfExporter::new().export_gltf(&scene, &ExportOptions::default(), &layout).unwrap();
+        assert_eq!(exported.files.keys().collect::<Vec<_>>(), ["model.gltf"]);
+
+        let root: serde_json::Value = serde_json::from_slice(&exported.files["model.gltf"]).unwrap();
+        let uri = root["buffers"][0]["uri"].as_str().unwrap();
+        assert!(uri.starts_with("data:application/octet-stream;base64,"));
+        assert!(root["images"][0]["uri"].as_str().unwrap().starts_with("data:image/png;base64,iVBORw0KGgo"));
+    }
+}
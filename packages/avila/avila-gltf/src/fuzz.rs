@@ -0,0 +1,23 @@
+//! Pontos de entrada para fuzzing (feature `fuzzing`)
+//!
+//! Dois caminhos leem GLB de fora: [`ExportReport::from_glb`], que percorre
+//! o cabeçalho e o chunk JSON, e o [`GltfImporter`], que lê também o BIN.
+
+use crate::{ExportReport, GltfImporter};
+
+/// Leitura do relatório num GLB arbitrário: sem pânico, e o que for lido
+/// sobrevive a uma volta pelo JSON
+pub fn glb_report(data: &[u8]) {
+    let Some(report) = ExportReport::from_glb(data) else { return };
+    let json = serde_json::to_value(&report).expect("report serializes");
+    assert_eq!(serde_json::from_value::<ExportReport>(json).ok(), Some(report));
+}
+
+/// Importação de um GLB arbitrário: erro ou cena, nunca pânico, e toda
+/// malha importada tem índices válidos
+pub fn glb_import(data: &[u8]) {
+    let Ok(scene) = GltfImporter::new().import_glb(data) else { return };
+    for mesh in &scene.meshes {
+        assert!(mesh.validate().is_ok());
+    }
+}
@@ -0,0 +1,474 @@
+//! Importação de GLB
+//!
+//! Lê um GLB glTF 2.0 (deste exportador ou de outro) de volta para uma
+//! [`Scene`]: cada primitiva triangular vira um [`Mesh`] com a transformação
+//! acumulada dos nodes já aplicada, e cada material vira um [`PbrMaterial`].
+//! Nomes no formato `"nome [guid]"` ([`NamingPolicy::NameAndGuid`]) voltam a
+//! ser separados em nome e GUID (ou ID, nos materiais).
+//!
+//! Limitações: só o buffer do chunk BIN (sem URIs nem accessors esparsos),
+//! atributos em `FLOAT`, primitivas de pontos/linhas ignoradas e texturas
+//! embutidas não decodificadas: os materiais guardam apenas URIs externas.
+//!
+//! [`NamingPolicy::NameAndGuid`]: crate::NamingPolicy::NameAndGuid
+
+use crate::{GltfAccessor, GltfError, GltfNode, GltfPrimitive, GltfRoot, Result};
+use avila_mesh::*;
+use avila_vec3d::{Mat4, Quat, Vec2, Vec3};
+use std::collections::HashSet;
+
+const GLB_MAGIC: u32 = 0x46546C67; // "glTF"
+const CHUNK_JSON: u32 = 0x4E4F534A;
+const CHUNK_BIN: u32 = 0x004E4942;
+
+const MODE_TRIANGLES: u32 = 4;
+const UNSIGNED_BYTE: u32 = 5121;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+pub struct GltfImporter;
+
+impl GltfImporter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Importa um GLB (binário glTF 2.0) para uma cena
+    pub fn import_glb(&self, glb: &[u8]) -> Result<Scene> {
+        let (json, bin) = split_glb(glb)?;
+        let document = Document {
+            root: serde_json::from_slice(json)?,
+            bin,
+        };
+        document.scene()
+    }
+}
+
+impl Default for GltfImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn invalid(message: impl Into<String>) -> GltfError {
+    GltfError::ImportError(message.into())
+}
+
+/// Separa os chunks JSON e BIN (o BIN é opcional)
+fn split_glb(glb: &[u8]) -> Result<(&[u8], &[u8])> {
+    let word = |offset: usize| {
+        glb.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+    };
+    if word(0) != Some(GLB_MAGIC) {
+        return Err(invalid("Invalid GLB magic number"));
+    }
+    match word(4) {
+        Some(2) => {}
+        Some(version) => return Err(invalid(format!("Unsupported GLB version {}", version))),
+        None => return Err(invalid("Truncated GLB header")),
+    }
+    let total = word(8).ok_or_else(|| invalid("Truncated GLB header"))? as usize;
+    let glb = glb.get(..total).ok_or_else(|| invalid("GLB shorter than declared length"))?;
+
+    let (mut json, mut bin) = (None, None);
+    let mut offset = 12;
+    while offset < glb.len() {
+        let (Some(length), Some(kind)) = (word(offset), word(offset + 4)) else {
+            return Err(invalid("Truncated chunk header"));
+        };
+        let start = offset + 8;
+        let end = start
+            .checked_add(length as usize)
+            .filter(|&end| end <= glb.len())
+            .ok_or_else(|| invalid("Truncated chunk"))?;
+        match kind {
+            CHUNK_JSON if json.is_none() => json = Some(&glb[start..end]),
+            CHUNK_BIN if bin.is_none() => bin = Some(&glb[start..end]),
+            // Chunks desconhecidos são ignorados (especificação)
+            _ => {}
+        }
+        offset = end;
+    }
+
+    let json = json.ok_or_else(|| invalid("Missing JSON chunk"))?;
+    Ok((json, bin.unwrap_or_default()))
+}
+
+/// Separa `"nome [guid]"` em nome e GUID
+fn split_name(name: Option<&str>) -> (Option<String>, Option<String>) {
+    let Some(name) = name.filter(|s| !s.is_empty()) else { return (None, None) };
+    match name.strip_suffix(']').and_then(|s| s.rsplit_once(" [")) {
+        Some((name, guid)) if !name.is_empty() && !guid.is_empty() => (Some(name.into()), Some(guid.into())),
+        _ => (Some(name.into()), None),
+    }
+}
+
+/// Matriz local do node: `matrix` ou T * R * S
+fn local_matrix(node: &GltfNode) -> Mat4 {
+    if let Some(matrix) = node.matrix {
+        let mut m = Mat4::IDENTITY;
+        for (col, column) in m.m.iter_mut().enumerate() {
+            column.copy_from_slice(&matrix[col * 4..col * 4 + 4]);
+        }
+        return m;
+    }
+    let [tx, ty, tz] = node.translation.unwrap_or([0.0; 3]);
+    let [x, y, z, w] = node.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+    let [sx, sy, sz] = node.scale.unwrap_or([1.0; 3]);
+    Mat4::translation(Vec3::new(tx, ty, tz))
+        .mul_mat4(&Quat::new(x, y, z, w).to_mat4())
+        .mul_mat4(&Mat4::scale(Vec3::new(sx, sy, sz)))
+}
+
+struct Document<'a> {
+    root: GltfRoot,
+    bin: &'a [u8],
+}
+
+impl Document<'_> {
+    fn scene(&self) -> Result<Scene> {
+        let mut scene = Scene::new();
+
+        let mut material_ids = Vec::with_capacity(self.root.materials.len());
+        for index in 0..self.root.materials.len() {
+            let material = self.material(index, &scene);
+            material_ids.push(material.id.clone());
+            scene.add_material(material);
+        }
+
+        // Raízes: a cena ativa ou, sem cenas, os nodes que não são filhos
+        let roots: Vec<u32> = match self.root.scenes.get(self.root.scene.unwrap_or(0) as usize) {
+            Some(gltf_scene) => gltf_scene.nodes.clone(),
+            None => {
+                let children: HashSet<u32> = self.root.nodes.iter().flat_map(|n| n.children.iter().copied()).collect();
+                (0..self.root.nodes.len() as u32).filter(|i| !children.contains(i)).collect()
+            }
+        };
+
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(u32, Mat4)> = roots.into_iter().rev().map(|i| (i, Mat4::IDENTITY)).collect();
+        while let Some((index, parent)) = stack.pop() {
+            // Ciclos ou nodes compartilhados: cada node é visitado uma vez
+            if !visited.insert(index) {
+                continue;
+            }
+            let node = self
+                .root
+                .nodes
+                .get(index as usize)
+                .ok_or_else(|| invalid(format!("Node {} out of range", index)))?;
+            let world = parent.mul_mat4(&local_matrix(node));
+            stack.extend(node.children.iter().rev().map(|&child| (child, world)));
+
+            let Some(mesh_index) = node.mesh else { continue };
+            let gltf_mesh = self
+                .root
+                .meshes
+                .get(mesh_index as usize)
+                .ok_or_else(|| invalid(format!("Mesh {} out of range", mesh_index)))?;
+            let (name, guid) = split_name(node.name.as_deref().or(gltf_mesh.name.as_deref()));
+
+            for primitive in &gltf_mesh.primitives {
+                let Some(mut mesh) = self.primitive(primitive)? else { continue };
+                if world != Mat4::IDENTITY {
+                    mesh.transform(&world);
+                }
+                mesh.name = name.clone();
+                mesh.element_guid = guid.clone();
+                mesh.material_id = match primitive.material {
+                    Some(m) => Some(
+                        material_ids
+                            .get(m as usize)
+                            .cloned()
+                            .ok_or_else(|| invalid(format!("Material {} out of range", m)))?,
+                    ),
+                    None => None,
+                };
+                scene.add_mesh(mesh);
+            }
+        }
+
+        Ok(scene)
+    }
+
+    fn material(&self, index: usize, scene: &Scene) -> PbrMaterial {
+        let gltf = &self.root.materials[index];
+        let (name, id) = split_name(gltf.name.as_deref());
+        let mut id = id.or_else(|| name.clone()).unwrap_or_else(|| format!("material_{}", index));
+        // IDs repetidos (nomes repetidos no glTF) ganham o índice
+        if scene.materials.contains_key(&id) {
+            id = format!("{}_{}", id, index);
+        }
+
+        let pbr = &gltf.pbr_metallic_roughness;
+        let mut material = PbrMaterial::default_material(id);
+        material.name = name.unwrap_or_else(|| format!("Material {}", index));
+        material.base_color_factor = pbr.base_color_factor;
+        material.metallic_factor = pbr.metallic_factor;
+        material.roughness_factor = pbr.roughness_factor;
+        material.double_sided = gltf.double_sided.unwrap_or(false);
+        if let Some(normal) = &gltf.normal_texture {
+            material.normal_texture = self.texture_uri(normal.index);
+            material.normal_scale = normal.scale;
+        }
+        if let Some(occlusion) = &gltf.occlusion_texture {
+            material.occlusion_texture = self.texture_uri(occlusion.index);
+            material.occlusion_strength = occlusion.strength;
+        }
+        material
+    }
+
+    /// URI externa da textura; embutidas (bufferView ou data URI) não são lidas
+    fn texture_uri(&self, index: u32) -> Option<String> {
+        let texture = self.root.textures.get(index as usize)?;
+        let uri = self.root.images.get(texture.source as usize)?.uri.as_ref()?;
+        (!uri.starts_with("data:")).then(|| uri.clone())
+    }
+
+    /// Mesh de uma primitiva, em espaço local; `None` para pontos e linhas
+    fn primitive(&self, primitive: &GltfPrimitive) -> Result<Option<Mesh>> {
+        if primitive.mode != MODE_TRIANGLES {
+            return Ok(None);
+        }
+        let attribute = |name: &str, accessor_type: &str| {
+            primitive
+                .attributes
+                .get(name)
+                .map(|&index| self.read_floats(index, accessor_type))
+                .transpose()
+        };
+        let positions = attribute("POSITION", "VEC3")?.ok_or_else(|| invalid("Primitive without POSITION"))?;
+        let normals = attribute("NORMAL", "VEC3")?;
+        let uvs = attribute("TEXCOORD_0", "VEC2")?;
+
+        let vertex_count = positions.len() / 3;
+        if normals.as_ref().is_some_and(|n| n.len() != positions.len())
+            || uvs.as_ref().is_some_and(|uv| uv.len() / 2 != vertex_count)
+        {
+            return Err(invalid("Attribute counts differ within a primitive"));
+        }
+        let indices = match primitive.indices {
+            Some(index) => self.read_indices(index)?,
+            None => (0..vertex_count as u32).collect(),
+        };
+
+        let mut mesh = Mesh::with_capacity(vertex_count, indices.len());
+        for i in 0..vertex_count {
+            let vec3 = |data: &[f32]| Vec3::new(data[i * 3], data[i * 3 + 1], data[i * 3 + 2]);
+            let mut vertex = Vertex::new(vec3(&positions));
+            if let Some(normals) = &normals {
+                vertex = vertex.with_normal(vec3(normals));
+            }
+            if let Some(uvs) = &uvs {
+                vertex = vertex.with_uv(Vec2::new(uvs[i * 2], uvs[i * 2 + 1]));
+            }
+            mesh.add_vertex(vertex);
+        }
+        for triangle in indices.chunks_exact(3) {
+            mesh.add_triangle(triangle[0], triangle[1], triangle[2])
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+        if normals.is_none() {
+            mesh.recalculate_normals_smooth();
+        }
+        Ok(Some(mesh))
+    }
+
+    fn accessor(&self, index: u32, accessor_type: &str) -> Result<&GltfAccessor> {
+        let accessor = self
+            .root
+            .accessors
+            .get(index as usize)
+            .ok_or_else(|| invalid(format!("Accessor {} out of range", index)))?;
+        if accessor.accessor_type != accessor_type {
+            return Err(invalid(format!(
+                "Accessor {} is {}, expected {}",
+                index, accessor.accessor_type, accessor_type
+            )));
+        }
+        Ok(accessor)
+    }
+
+    /// Bytes de cada elemento do accessor, respeitando `byteStride`
+    fn elements(&self, accessor: &GltfAccessor, element_size: usize) -> Result<Vec<&[u8]>> {
+        let view_index = accessor.buffer_view.ok_or_else(|| invalid("Sparse accessors are not supported"))?;
+        let view = self
+            .root
+            .buffer_views
+            .get(view_index as usize)
+            .ok_or_else(|| invalid(format!("Buffer view {} out of range", view_index)))?;
+        let external = self.root.buffers.get(view.buffer as usize).is_some_and(|b| b.uri.is_some());
+        if view.buffer != 0 || external {
+            return Err(invalid("Only the GLB binary chunk is supported as buffer"));
+        }
+
+        // Somas verificadas: em wasm32 `usize` tem 32 bits e offsets u32 transbordam
+        let start = view.byte_offset as usize;
+        let data = start
+            .checked_add(view.byte_length as usize)
+            .and_then(|end| self.bin.get(start..end))
+            .ok_or_else(|| invalid(format!("Buffer view {} exceeds the binary chunk", view_index)))?;
+        let stride = view.byte_stride.map_or(element_size, |stride| stride as usize);
+        if stride < element_size {
+            return Err(invalid(format!("Buffer view {} stride is smaller than its elements", view_index)));
+        }
+        (0..accessor.count)
+            .map(|i| {
+                i.checked_mul(stride)
+                    .and_then(|offset| offset.checked_add(accessor.byte_offset as usize))
+                    .and_then(|offset| data.get(offset..offset.checked_add(element_size)?))
+                    .ok_or_else(|| invalid(format!("Accessor exceeds buffer view {}", view_index)))
+            })
+            .collect()
+    }
+
+    fn read_floats(&self, index: u32, accessor_type: &str) -> Result<Vec<f32>> {
+        let accessor = self.accessor(index, accessor_type)?;
+        if accessor.component_type != FLOAT {
+            return Err(invalid(format!("Accessor {} must be FLOAT", index)));
+        }
+        let components = if accessor_type == "VEC2" { 2 } else { 3 };
+        Ok(self
+            .elements(accessor, components * 4)?
+            .into_iter()
+            .flat_map(|element| element.chunks_exact(4))
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+            .collect())
+    }
+
+    fn read_indices(&self, index: u32) -> Result<Vec<u32>> {
+        let accessor = self.accessor(index, "SCALAR")?;
+        let size = match accessor.component_type {
+            UNSIGNED_BYTE => 1,
+            UNSIGNED_SHORT => 2,
+            UNSIGNED_INT => 4,
+            other => return Err(invalid(format!("Invalid index component type {}", other))),
+        };
+        Ok(self
+            .elements(accessor, size)?
+            .into_iter()
+            .map(|bytes| match bytes {
+                [a] => *a as u32,
+                [a, b] => u16::from_le_bytes([*a, *b]) as u32,
+                _ => u32::from_le_bytes(bytes.try_into().expect("4 bytes")),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExportOptions, GltfExporter, NamingPolicy};
+    use avila_mesh::primitives;
+
+    /// Monta um GLB a partir do JSON e do BIN
+    fn glb(json: &serde_json::Value, bin: &[u8]) -> Vec<u8> {
+        let mut json = serde_json::to_vec(json).unwrap();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut glb = Vec::new();
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(bin);
+        glb
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut scene = Scene::new();
+        for (x, guid) in [(0.0, "2O2Fr$t4X7Zf8NOew3FLOH"), (5.0, "1hOSvn6df7F8_7GcBWlRGQ")] {
+            let mut wall = primitives::cube(1.0);
+            wall.transform(&Mat4::translation(Vec3::new(x, 0.0, 0.0)));
+            wall.name = Some("Basic Wall".into());
+            wall.element_guid = Some(guid.into());
+            wall.material_id = Some("concrete".into());
+            scene.add_mesh(wall);
+        }
+        let mut concrete = PbrMaterial::from_ifc_material("concrete", "Concreto");
+        concrete.normal_texture = Some("maps/concrete_normal.png".into());
+        scene.add_material(concrete);
+
+        let opts = ExportOptions { naming: NamingPolicy::NameAndGuid, ..Default::default() };
+        let glb = GltfExporter::new().export_glb(&scene, &opts).unwrap();
+        let imported = GltfImporter::new().import_glb(&glb).unwrap();
+
+        assert_eq!(imported.mesh_count(), 2);
+        assert_eq!(imported.triangle_count(), scene.triangle_count());
+        assert_eq!(imported.meshes[1].name.as_deref(), Some("Basic Wall"));
+        assert_eq!(imported.meshes[1].element_guid.as_deref(), Some("1hOSvn6df7F8_7GcBWlRGQ"));
+        assert_eq!(imported.meshes[1].material_id.as_deref(), Some("concrete"));
+        assert_eq!(imported.meshes[1].vertices, scene.meshes[1].vertices);
+        assert_eq!(imported.bounds, scene.bounds);
+
+        let material = &imported.materials["concrete"];
+        assert_eq!(material.name, "Concreto");
+        assert_eq!(material.roughness_factor, 0.9);
+        assert_eq!(material.normal_texture.as_deref(), Some("maps/concrete_normal.png"));
+    }
+
+    #[test]
+    fn test_node_hierarchy_and_defaults() {
+        // Triângulo não indexado, com atributos intercalados e sem normais
+        let mut bin = Vec::new();
+        for [x, y] in [[0.0f32, 0.0], [1.0, 0.0], [0.0, 1.0]] {
+            for value in [x, y, 0.0, 9.0] {
+                bin.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        let json = serde_json::json!({
+            "asset": { "version": "2.0" },
+            "nodes": [
+                { "name": "Piso", "children": [1], "translation": [10.0, 0.0, 0.0] },
+                { "mesh": 0, "scale": [2.0, 2.0, 2.0] },
+            ],
+            "meshes": [{ "name": "slab", "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "buffers": [{ "byteLength": bin.len() }],
+            "bufferViews": [{ "buffer": 0, "byteLength": bin.len(), "byteStride": 16 }],
+            "accessors": [{ "bufferView": 0, "componentType": FLOAT, "count": 3, "type": "VEC3" }],
+        });
+
+        let scene = GltfImporter::new().import_glb(&glb(&json, &bin)).unwrap();
+        assert_eq!(scene.mesh_count(), 1);
+        let mesh = &scene.meshes[0];
+        // O node filho não tem nome: usa o da mesh
+        assert_eq!(mesh.name.as_deref(), Some("slab"));
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert_eq!(mesh.vertices[1].position, Vec3::new(12.0, 0.0, 0.0));
+        assert_eq!(mesh.vertices[2].position, Vec3::new(10.0, 2.0, 0.0));
+        assert_eq!(mesh.vertices[0].normal, Vec3::Z);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let importer = GltfImporter::new();
+        assert!(importer.import_glb(b"not a glb").is_err());
+
+        let json = serde_json::json!({
+            "asset": { "version": "2.0" },
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "buffers": [{ "byteLength": 4 }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 4 }],
+            "accessors": [{ "bufferView": 0, "componentType": FLOAT, "count": 3, "type": "VEC3" }],
+        });
+        let glb = glb(&json, &[0; 4]);
+        assert!(matches!(importer.import_glb(&glb), Err(GltfError::ImportError(_))));
+        assert!(importer.import_glb(&glb[..glb.len() - 2]).is_err());
+
+        // Offsets no limite do u32 (transbordavam em wasm32)
+        let mut json = json;
+        json["bufferViews"][0] = serde_json::json!({ "buffer": 0, "byteOffset": u32::MAX, "byteLength": u32::MAX });
+        assert!(importer.import_glb(&self::glb(&json, &[0; 4])).is_err());
+        json["bufferViews"][0] = serde_json::json!({ "buffer": 0, "byteLength": 4 });
+        json["accessors"][0]["byteOffset"] = serde_json::json!(u32::MAX);
+        assert!(importer.import_glb(&self::glb(&json, &[0; 4])).is_err());
+    }
+}
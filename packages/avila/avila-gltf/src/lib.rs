@@ -1,8 +1,30 @@
 ﻿//! # avila-gltf
 //!
 //! Exporter glTF 2.0 / GLB **100% Rust nativo - DO ZERO**.
+//!
+//! Duas saídas: GLB num único arquivo ([`GltfExporter::export_glb`]) ou
+//! `.gltf` + `.bin` + texturas PNG com URIs relativas
+//! ([`GltfExporter::export_gltf`]). GLBs voltam para uma [`Scene`] com
+//! [`GltfImporter::import_glb`].
+//!
+//! Sequências de visibilidade por elemento (4D) são exportadas como
+//! animação glTF via [`ExportOptions::animation`].
+//!
+//! Nuvens de pontos da cena ([`Scene::point_clouds`]) viram primitivas
+//! `POINTS` com `COLOR_0` e o atributo `_INTENSITY`.
+
+mod animation;
+mod files;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+mod import;
+
+pub use animation::{VisibilityAnimation, VisibilityKey};
+pub use files::{GltfFileOptions, GltfFiles};
+pub use import::GltfImporter;
 
 use avila_mesh::*;
+use files::TextureFiles;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Write;
@@ -13,6 +35,8 @@ pub type Result<T> = std::result::Result<T, GltfError>;
 pub enum GltfError {
     #[error("Export error: {0}")]
     ExportError(String),
+    #[error("Import error: {0}")]
+    ImportError(String),
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
     #[error("IO error: {0}")]
@@ -26,6 +50,16 @@ pub struct ExportOptions {
     pub asset_name: String,
     pub include_normals: bool,
     pub include_uvs: bool,
+    /// Como nomear nodes, meshes e materiais
+    pub naming: NamingPolicy,
+    /// O que fazer com nomes repetidos
+    pub name_collisions: NameCollision,
+    /// Grava o [`ExportReport`] em `asset.extras.avilaExport`
+    pub embed_report: bool,
+    /// Etapas anteriores (merge, weld, compressão...) a registrar no relatório
+    pub pipeline: Vec<PipelineStep>,
+    /// Animação de visibilidade por `element_guid` (ex.: sequência 4D)
+    pub animation: Option<VisibilityAnimation>,
 }
 
 impl Default for ExportOptions {
@@ -34,10 +68,278 @@ impl Default for ExportOptions {
             asset_name: "Avila BIM".into(),
             include_normals: true,
             include_uvs: true,
+            naming: NamingPolicy::ElementName,
+            name_collisions: NameCollision::Suffix,
+            embed_report: true,
+            pipeline: Vec::new(),
+            animation: None,
+        }
+    }
+}
+
+/// Política de nomes no glTF exportado
+///
+/// | Política      | Node / mesh          | Material           |
+/// |---------------|----------------------|--------------------|
+/// | `None`        | sem nome             | sem nome           |
+/// | `ElementName` | `Mesh::name`         | `PbrMaterial::name`|
+/// | `Guid`        | `Mesh::element_guid` | `PbrMaterial::id`  |
+/// | `NameAndGuid` | `"nome [guid]"`      | `"nome [id]"`      |
+///
+/// Quando só um dos dois existe em `NameAndGuid`, ele é usado sozinho.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NamingPolicy {
+    None,
+    #[default]
+    ElementName,
+    Guid,
+    NameAndGuid,
+}
+
+impl NamingPolicy {
+    fn compose(&self, name: Option<&str>, id: Option<&str>) -> Option<String> {
+        let name = name.filter(|s| !s.is_empty());
+        let id = id.filter(|s| !s.is_empty());
+        match self {
+            Self::None => None,
+            Self::ElementName => name.map(str::to_string),
+            Self::Guid => id.map(str::to_string),
+            Self::NameAndGuid => match (name, id) {
+                (Some(name), Some(id)) => Some(format!("{} [{}]", name, id)),
+                (name, id) => name.or(id).map(str::to_string),
+            },
+        }
+    }
+}
+
+/// Estratégia para nomes repetidos (por categoria: nodes, meshes, materiais)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameCollision {
+    /// Mantém nomes repetidos (glTF permite)
+    Allow,
+    /// Acrescenta `_2`, `_3`, ... às repetições
+    #[default]
+    Suffix,
+}
+
+/// Distribui nomes únicos conforme a [`NameCollision`]
+struct NameAllocator {
+    strategy: NameCollision,
+    used: HashMap<String, usize>,
+}
+
+impl NameAllocator {
+    fn new(strategy: NameCollision) -> Self {
+        Self { strategy, used: HashMap::new() }
+    }
+
+    fn allocate(&mut self, name: Option<String>) -> Option<String> {
+        let name = name?;
+        if self.strategy == NameCollision::Allow {
+            return Some(name);
+        }
+
+        let mut count = self.used.get(&name).copied().unwrap_or(0);
+        let unique = loop {
+            count += 1;
+            let candidate = if count == 1 { name.clone() } else { format!("{}_{}", name, count) };
+            if !self.used.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        self.used.insert(name, count);
+        self.used.entry(unique.clone()).or_insert(1);
+        Some(unique)
+    }
+}
+
+// ============================================================================
+// RELATÓRIO DE EXPORTAÇÃO
+// ============================================================================
+
+/// O que foi decidido na exportação, para suporte e auditoria
+///
+/// Devolvido por [`GltfExporter::export_glb_with_report`] e, com
+/// [`ExportOptions::embed_report`], gravado em `asset.extras.avilaExport`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub generator: String,
+    pub meshes: usize,
+    pub materials: usize,
+    pub vertices: usize,
+    pub triangles: usize,
+    /// Pontos das nuvens exportadas
+    #[serde(default)]
+    pub points: usize,
+    pub naming: NamingPolicy,
+    /// Meshes com índices `u16` e `u32`
+    pub index_formats: IndexFormats,
+    /// Meshes exportadas com cada atributo opcional
+    pub attributes: AttributeCounts,
+    /// Texturas da cena, exportadas como PNG (no BIN ou em arquivo)
+    pub embedded_textures: Vec<String>,
+    /// Texturas fora da cena, referenciadas por URI
+    pub external_textures: Vec<String>,
+    /// Nomes alterados para evitar colisões
+    pub renamed: Vec<Rename>,
+    /// Meshes cujo material não existe na cena (exportadas sem material)
+    pub missing_materials: Vec<MissingMaterial>,
+    /// Correções aplicadas a dados inválidos para o glTF
+    pub sanitized: Vec<Sanitation>,
+    /// Etapas anteriores informadas em [`ExportOptions::pipeline`]
+    pub pipeline: Vec<PipelineStep>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexFormats {
+    pub u16: usize,
+    pub u32: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeCounts {
+    pub normals: usize,
+    pub uvs: usize,
+}
+
+/// Categoria de nome no glTF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NameKind {
+    Node,
+    Mesh,
+    Material,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rename {
+    pub kind: NameKind,
+    pub original: String,
+    pub exported: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingMaterial {
+    pub mesh: u32,
+    pub material_id: String,
+}
+
+/// Tipo de correção aplicada pela sanitização
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SanitationKind {
+    /// Coordenada NaN/infinita substituída por 0
+    NonFinitePosition,
+    /// Normal fora do comprimento unitário, renormalizada
+    NormalRenormalized,
+    /// Normal nula ou NaN, substituída por +Z
+    NormalReplaced,
+    /// UV NaN/infinita substituída por 0
+    NonFiniteUv,
+    /// Triângulo com índice fora do intervalo, removido
+    TriangleDropped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sanitation {
+    /// Índice da mesh no glTF
+    pub mesh: u32,
+    pub kind: SanitationKind,
+    /// Valores (vértices ou triângulos) afetados
+    pub count: usize,
+}
+
+/// Etapa anterior à exportação (ex.: merge por material, solda de vértices)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStep {
+    pub stage: String,
+    /// Parâmetros e contagens da etapa
+    pub details: serde_json::Value,
+}
+
+impl PipelineStep {
+    pub fn new(stage: impl Into<String>, details: serde_json::Value) -> Self {
+        Self { stage: stage.into(), details }
+    }
+}
+
+impl ExportReport {
+    /// Lê o relatório gravado em `asset.extras` de um GLB
+    pub fn from_glb(glb: &[u8]) -> Option<Self> {
+        if glb.len() < 20 || &glb[0..4] != b"glTF" || &glb[16..20] != b"JSON" {
+            return None;
+        }
+        let len = u32::from_le_bytes(glb[12..16].try_into().ok()?) as usize;
+        let json: serde_json::Value = serde_json::from_slice(glb.get(20..20usize.checked_add(len)?)?).ok()?;
+        serde_json::from_value(json["asset"]["extras"]["avilaExport"].clone()).ok()
+    }
+
+    fn record_primitive(&mut self, primitive: &GltfPrimitive, accessors: &[GltfAccessor]) {
+        self.attributes.normals += primitive.attributes.contains_key("NORMAL") as usize;
+        self.attributes.uvs += primitive.attributes.contains_key("TEXCOORD_0") as usize;
+        match primitive.indices.map(|index| accessors[index as usize].component_type) {
+            Some(5123) => self.index_formats.u16 += 1,
+            Some(_) => self.index_formats.u32 += 1,
+            None => {}
+        }
+    }
+
+    fn count_fix(&mut self, mesh: u32, kind: SanitationKind, count: usize) {
+        if count > 0 {
+            self.sanitized.push(Sanitation { mesh, kind, count });
         }
     }
 }
 
+/// Corrige valores que o glTF não aceita, registrando cada tipo de correção
+fn sanitize_buffers(buffers: &mut MeshBuffers, mesh: u32, report: &mut ExportReport) {
+    let mut non_finite = 0;
+    for position in buffers.positions.chunks_mut(3) {
+        if position.iter().any(|v| !v.is_finite()) {
+            non_finite += 1;
+            position.iter_mut().filter(|v| !v.is_finite()).for_each(|v| *v = 0.0);
+        }
+    }
+    report.count_fix(mesh, SanitationKind::NonFinitePosition, non_finite);
+
+    let (mut renormalized, mut replaced) = (0, 0);
+    for normal in buffers.normals.chunks_mut(3) {
+        let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        if !len.is_finite() || len < 1e-12 {
+            normal.copy_from_slice(&[0.0, 0.0, 1.0]);
+            replaced += 1;
+        } else if (len - 1.0).abs() > 1e-3 {
+            normal.iter_mut().for_each(|v| *v /= len);
+            renormalized += 1;
+        }
+    }
+    report.count_fix(mesh, SanitationKind::NormalRenormalized, renormalized);
+    report.count_fix(mesh, SanitationKind::NormalReplaced, replaced);
+
+    let mut non_finite = 0;
+    for uv in buffers.uvs.chunks_mut(2) {
+        if uv.iter().any(|v| !v.is_finite()) {
+            non_finite += 1;
+            uv.iter_mut().filter(|v| !v.is_finite()).for_each(|v| *v = 0.0);
+        }
+    }
+    report.count_fix(mesh, SanitationKind::NonFiniteUv, non_finite);
+
+    let vertex_count = (buffers.positions.len() / 3) as u32;
+    let triangles = buffers.indices.len() / 3;
+    let mut kept = Vec::with_capacity(buffers.indices.len());
+    for triangle in buffers.indices.chunks_exact(3) {
+        if triangle.iter().all(|&i| i < vertex_count) {
+            kept.extend_from_slice(triangle);
+        }
+    }
+    report.count_fix(mesh, SanitationKind::TriangleDropped, triangles - kept.len() / 3);
+    buffers.indices = kept;
+}
+
 impl GltfExporter {
     pub fn new() -> Self {
         Self
@@ -45,7 +347,12 @@ impl GltfExporter {
 
     /// Exporta cena completa para GLB (binário glTF 2.0)
     pub fn export_glb(&self, scene: &Scene, opts: &ExportOptions) -> Result<Vec<u8>> {
-        let (json, bin) = self.export_parts(scene, opts)?;
+        Ok(self.export_glb_with_report(scene, opts)?.0)
+    }
+
+    /// Exporta para GLB e devolve o relatório das decisões tomadas
+    pub fn export_glb_with_report(&self, scene: &Scene, opts: &ExportOptions) -> Result<(Vec<u8>, ExportReport)> {
+        let (json, bin, report) = self.export_parts(scene, opts)?;
 
         let mut glb = Vec::new();
 
@@ -74,14 +381,42 @@ impl GltfExporter {
             glb.write_all(&vec![0u8; bin_padding])?;
         }
 
-        Ok(glb)
+        Ok((glb, report))
     }
 
-    fn export_parts(&self, scene: &Scene, opts: &ExportOptions) -> Result<(String, Vec<u8>)> {
+    fn export_parts(&self, scene: &Scene, opts: &ExportOptions) -> Result<(String, Vec<u8>, ExportReport)> {
+        let (mut gltf, bin_data, report) = self.build(scene, opts, None)?;
+        if !bin_data.is_empty() {
+            gltf.buffers.push(GltfBuffer {
+                byte_length: bin_data.len() as u32,
+                uri: None,
+            });
+        }
+        let json = finish(gltf, &report, opts)?;
+        Ok((json, bin_data, report))
+    }
+
+    /// Monta o documento e o BIN, ainda sem o buffer declarado
+    ///
+    /// Com `texture_files`, as texturas da cena vão para arquivos em vez do BIN.
+    fn build(
+        &self,
+        scene: &Scene,
+        opts: &ExportOptions,
+        mut texture_files: Option<&mut TextureFiles>,
+    ) -> Result<(GltfRoot, Vec<u8>, ExportReport)> {
+        let generator = format!("avila-gltf {}", env!("CARGO_PKG_VERSION"));
+        let mut report = ExportReport {
+            generator: generator.clone(),
+            naming: opts.naming,
+            pipeline: opts.pipeline.clone(),
+            ..Default::default()
+        };
         let mut gltf = GltfRoot {
             asset: GltfAsset {
                 version: "2.0".into(),
-                generator: Some("avila-gltf".into()),
+                generator: Some(generator),
+                extras: None,
             },
             scene: Some(0),
             scenes: vec![GltfScene {
@@ -90,58 +425,123 @@ impl GltfExporter {
             nodes: Vec::new(),
             meshes: Vec::new(),
             materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
             buffers: Vec::new(),
             buffer_views: Vec::new(),
             accessors: Vec::new(),
+            animations: Vec::new(),
         };
 
         let mut bin_data = Vec::new();
         let mut material_map = HashMap::new();
+        let mut texture_map = HashMap::new();
+        let mut material_names = NameAllocator::new(opts.name_collisions);
+        let mut node_names = NameAllocator::new(opts.name_collisions);
+        let mut mesh_names = NameAllocator::new(opts.name_collisions);
+
+        // Materiais em ordem de ID (saída determinística)
+        let mut materials: Vec<(&String, &PbrMaterial)> = scene.materials.iter().collect();
+        materials.sort_by(|a, b| a.0.cmp(b.0));
+
+        // Texturas da cena são embutidas no BIN; as demais viram URI
+        for (mat_id, material) in materials {
+            let mut texture = |id: &Option<String>| -> Result<Option<u32>> {
+                id.as_ref()
+                    .map(|id| {
+                        let files = texture_files.as_deref_mut();
+                        self.add_texture(id, scene, &mut bin_data, &mut gltf, &mut texture_map, files)
+                    })
+                    .transpose()
+            };
+            let normal = texture(&material.normal_texture)?;
+            let occlusion = texture(&material.occlusion_texture)?;
 
-        // Materiais
-        for (mat_id, material) in &scene.materials {
             let idx = gltf.materials.len() as u32;
             material_map.insert(mat_id.clone(), idx);
-            gltf.materials.push(material_to_gltf(material));
+            let mut gltf_material = material_to_gltf(material, normal, occlusion);
+            let name = opts.naming.compose(Some(&material.name), Some(&material.id));
+            gltf_material.name = allocate_logged(&mut material_names, name, NameKind::Material, &mut report);
+            gltf.materials.push(gltf_material);
         }
+        report.materials = gltf.materials.len();
 
         // Meshes
         for mesh in &scene.meshes {
-            let material_idx = mesh.material_id.as_ref()
-                .and_then(|id| material_map.get(id).copied());
+            let mesh_idx = gltf.meshes.len() as u32;
+            let material_idx = mesh.material_id.as_ref().and_then(|id| {
+                let index = material_map.get(id).copied();
+                if index.is_none() {
+                    report.missing_materials.push(MissingMaterial { mesh: mesh_idx, material_id: id.clone() });
+                }
+                index
+            });
+
+            let mut buffers = mesh.to_buffers();
+            sanitize_buffers(&mut buffers, mesh_idx, &mut report);
+            report.vertices += buffers.positions.len() / 3;
+            report.triangles += buffers.indices.len() / 3;
 
-            let gltf_mesh = self.mesh_to_gltf(
-                mesh,
+            let mut gltf_mesh = self.mesh_to_gltf(
+                &buffers,
                 &mut bin_data,
                 &mut gltf.buffer_views,
                 &mut gltf.accessors,
                 material_idx,
                 opts
             )?;
+            report.record_primitive(&gltf_mesh.primitives[0], &gltf.accessors);
 
-            let mesh_idx = gltf.meshes.len() as u32;
+            let name = opts.naming.compose(mesh.name.as_deref(), mesh.element_guid.as_deref());
+            gltf_mesh.name = allocate_logged(&mut mesh_names, name.clone(), NameKind::Mesh, &mut report);
             gltf.meshes.push(gltf_mesh);
 
             gltf.nodes.push(GltfNode {
+                name: allocate_logged(&mut node_names, name, NameKind::Node, &mut report),
                 mesh: Some(mesh_idx),
-                matrix: None,
+                ..Default::default()
             });
         }
+        report.meshes = gltf.meshes.len();
 
-        if !bin_data.is_empty() {
-            gltf.buffers.push(GltfBuffer {
-                byte_length: bin_data.len() as u32,
-                uri: None,
+        // Nuvens de pontos: nodes depois dos das meshes
+        for cloud in scene.point_clouds.iter().filter(|c| !c.is_empty()) {
+            cloud.validate().map_err(|e| GltfError::ExportError(e.to_string()))?;
+            let mesh_idx = gltf.meshes.len() as u32;
+            let mut gltf_mesh =
+                self.point_cloud_to_gltf(cloud, &mut bin_data, &mut gltf.buffer_views, &mut gltf.accessors)?;
+            report.points += cloud.len();
+
+            gltf_mesh.name = allocate_logged(&mut mesh_names, cloud.name.clone(), NameKind::Mesh, &mut report);
+            gltf.meshes.push(gltf_mesh);
+
+            gltf.scenes[0].nodes.push(gltf.nodes.len() as u32);
+            gltf.nodes.push(GltfNode {
+                name: allocate_logged(&mut node_names, cloud.name.clone(), NameKind::Node, &mut report),
+                mesh: Some(mesh_idx),
+                ..Default::default()
             });
         }
 
-        let json = serde_json::to_string_pretty(&gltf)?;
-        Ok((json, bin_data))
+        if let Some(animation) = &opts.animation {
+            animation::add_visibility(animation, scene, &mut gltf, &mut bin_data)?;
+        }
+
+        // Só texturas da cena têm nome; as demais são apenas a URI
+        for image in &gltf.images {
+            match (&image.name, &image.uri) {
+                (Some(name), _) => report.embedded_textures.push(name.clone()),
+                (None, Some(uri)) => report.external_textures.push(uri.clone()),
+                (None, None) => {}
+            }
+        }
+
+        Ok((gltf, bin_data, report))
     }
 
     fn mesh_to_gltf(
         &self,
-        mesh: &Mesh,
+        buffers: &MeshBuffers,
         bin_data: &mut Vec<u8>,
         buffer_views: &mut Vec<GltfBufferView>,
         accessors: &mut Vec<GltfAccessor>,
@@ -150,9 +550,6 @@ impl GltfExporter {
     ) -> Result<GltfMesh> {
         let mut attributes = HashMap::new();
 
-        // Converter para buffers
-        let buffers = mesh.to_buffers();
-
         // POSITION
         let pos_accessor = self.add_buffer(
             bin_data,
@@ -205,6 +602,7 @@ impl GltfExporter {
         )?;
 
         Ok(GltfMesh {
+            name: None,
             primitives: vec![GltfPrimitive {
                 attributes,
                 indices: Some(indices_accessor),
@@ -214,6 +612,47 @@ impl GltfExporter {
         })
     }
 
+    /// Primitiva `POINTS`: cores sRGB convertidas para `COLOR_0` linear,
+    /// intensidade bruta em `_INTENSITY` (atributo da aplicação)
+    fn point_cloud_to_gltf(
+        &self,
+        cloud: &PointCloud,
+        bin_data: &mut Vec<u8>,
+        buffer_views: &mut Vec<GltfBufferView>,
+        accessors: &mut Vec<GltfAccessor>,
+    ) -> Result<GltfMesh> {
+        let mut attributes = HashMap::new();
+        let count = cloud.len();
+
+        let positions: Vec<f32> = cloud.positions.iter().flat_map(|p| [p.x, p.y, p.z]).collect();
+        let pos_accessor = self.add_buffer(bin_data, buffer_views, accessors, &positions, 5126, "VEC3", count, true)?;
+        attributes.insert("POSITION".into(), pos_accessor);
+
+        if cloud.has_colors() {
+            let colors: Vec<f32> = cloud.colors.iter().flat_map(|c| c.map(srgb_to_linear)).collect();
+            let color_accessor = self.add_buffer(bin_data, buffer_views, accessors, &colors, 5126, "VEC3", count, false)?;
+            attributes.insert("COLOR_0".into(), color_accessor);
+        }
+
+        if cloud.has_intensities() {
+            let intensities: Vec<f32> = cloud.intensities.iter().map(|&i| i as f32).collect();
+            let intensity_accessor =
+                self.add_buffer(bin_data, buffer_views, accessors, &intensities, 5126, "SCALAR", count, false)?;
+            attributes.insert("_INTENSITY".into(), intensity_accessor);
+        }
+
+        Ok(GltfMesh {
+            name: None,
+            primitives: vec![GltfPrimitive {
+                attributes,
+                indices: None,
+                material: None,
+                mode: 0, // POINTS
+            }],
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)] // uma accessor glTF inteira: buffer, view e metadados
     fn add_buffer(
         &self,
         bin_data: &mut Vec<u8>,
@@ -242,6 +681,7 @@ impl GltfExporter {
             buffer: 0,
             byte_offset,
             byte_length,
+            byte_stride: None,
             target: Some(34962), // ARRAY_BUFFER
         });
 
@@ -265,6 +705,66 @@ impl GltfExporter {
         Ok(accessor_idx)
     }
 
+    /// Registra uma textura (imagem + texture) uma única vez por ID
+    fn add_texture(
+        &self,
+        id: &str,
+        scene: &Scene,
+        bin_data: &mut Vec<u8>,
+        gltf: &mut GltfRoot,
+        texture_map: &mut HashMap<String, u32>,
+        files: Option<&mut TextureFiles>,
+    ) -> Result<u32> {
+        if let Some(&index) = texture_map.get(id) {
+            return Ok(index);
+        }
+
+        let image = match (scene.textures.get(id), files) {
+            (Some(texture), Some(files)) => GltfImage {
+                name: Some(id.to_string()),
+                uri: Some(files.store(id, texture.encode_png())),
+                buffer_view: None,
+                mime_type: Some("image/png".into()),
+            },
+            (Some(texture), None) => {
+                let png = texture.encode_png();
+                let byte_offset = bin_data.len() as u32;
+                bin_data.write_all(&png)?;
+
+                let padding = (4 - (bin_data.len() % 4)) % 4;
+                bin_data.extend_from_slice(&vec![0u8; padding]);
+
+                gltf.buffer_views.push(GltfBufferView {
+                    buffer: 0,
+                    byte_offset,
+                    byte_length: png.len() as u32,
+                    byte_stride: None,
+                    target: None,
+                });
+                GltfImage {
+                    name: Some(id.to_string()),
+                    uri: None,
+                    buffer_view: Some(gltf.buffer_views.len() as u32 - 1),
+                    mime_type: Some("image/png".into()),
+                }
+            }
+            (None, _) => GltfImage {
+                name: None,
+                uri: Some(id.to_string()),
+                buffer_view: None,
+                mime_type: None,
+            },
+        };
+
+        gltf.images.push(image);
+        let index = gltf.textures.len() as u32;
+        gltf.textures.push(GltfTexture {
+            source: gltf.images.len() as u32 - 1,
+        });
+        texture_map.insert(id.to_string(), index);
+        Ok(index)
+    }
+
     fn add_indices_buffer(
         &self,
         indices: &[u32],
@@ -297,6 +797,7 @@ impl GltfExporter {
             buffer: 0,
             byte_offset,
             byte_length,
+            byte_stride: None,
             target: Some(34963), // ELEMENT_ARRAY_BUFFER
         });
 
@@ -321,6 +822,37 @@ impl Default for GltfExporter {
     }
 }
 
+/// Grava o relatório (se pedido) e serializa o documento
+fn finish(mut gltf: GltfRoot, report: &ExportReport, opts: &ExportOptions) -> Result<String> {
+    if opts.embed_report {
+        gltf.asset.extras = Some(serde_json::json!({ "avilaExport": report }));
+    }
+    Ok(serde_json::to_string_pretty(&gltf)?)
+}
+
+/// Aloca o nome e registra no relatório quando ele precisou mudar
+fn allocate_logged(
+    names: &mut NameAllocator,
+    name: Option<String>,
+    kind: NameKind,
+    report: &mut ExportReport,
+) -> Option<String> {
+    let allocated = names.allocate(name.clone())?;
+    if let Some(original) = name.filter(|original| *original != allocated) {
+        report.renamed.push(Rename { kind, original, exported: allocated.clone() });
+    }
+    Some(allocated)
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
 fn calc_bounds_vec3(vertices: &[f32]) -> (Option<Vec<f32>>, Option<Vec<f32>>) {
     if vertices.is_empty() {
         return (None, None);
@@ -339,14 +871,22 @@ fn calc_bounds_vec3(vertices: &[f32]) -> (Option<Vec<f32>>, Option<Vec<f32>>) {
     (Some(min.to_vec()), Some(max.to_vec()))
 }
 
-fn material_to_gltf(mat: &PbrMaterial) -> GltfMaterial {
+fn material_to_gltf(mat: &PbrMaterial, normal: Option<u32>, occlusion: Option<u32>) -> GltfMaterial {
     GltfMaterial {
-        name: Some(mat.name.clone()),
+        name: None,
         pbr_metallic_roughness: PbrMetallicRoughness {
             base_color_factor: mat.base_color_factor,
             metallic_factor: mat.metallic_factor,
             roughness_factor: mat.roughness_factor,
         },
+        normal_texture: normal.map(|index| GltfNormalTextureInfo {
+            index,
+            scale: mat.normal_scale,
+        }),
+        occlusion_texture: occlusion.map(|index| GltfOcclusionTextureInfo {
+            index,
+            strength: mat.occlusion_strength,
+        }),
         double_sided: Some(mat.double_sided),
     }
 }
@@ -360,15 +900,26 @@ struct GltfRoot {
     asset: GltfAsset,
     #[serde(skip_serializing_if = "Option::is_none")]
     scene: Option<u32>,
+    #[serde(default)]
     scenes: Vec<GltfScene>,
+    #[serde(default)]
     nodes: Vec<GltfNode>,
+    #[serde(default)]
     meshes: Vec<GltfMesh>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     materials: Vec<GltfMaterial>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    textures: Vec<GltfTexture>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    images: Vec<GltfImage>,
+    #[serde(default)]
     buffers: Vec<GltfBuffer>,
-    #[serde(rename = "bufferViews")]
+    #[serde(default, rename = "bufferViews")]
     buffer_views: Vec<GltfBufferView>,
+    #[serde(default)]
     accessors: Vec<GltfAccessor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    animations: Vec<animation::GltfAnimation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -376,23 +927,38 @@ struct GltfAsset {
     version: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     generator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extras: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GltfScene {
+    #[serde(default)]
     nodes: Vec<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct GltfNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     mesh: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     matrix: Option<[f32; 16]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translation: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rotation: Option<[f32; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scale: Option<[f32; 3]>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GltfMesh {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     primitives: Vec<GltfPrimitive>,
 }
 
@@ -403,20 +969,71 @@ struct GltfPrimitive {
     indices: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     material: Option<u32>,
+    #[serde(default = "triangles")]
     mode: u32,
 }
 
+/// Modo padrão das primitivas glTF (TRIANGLES)
+fn triangles() -> u32 {
+    4
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GltfMaterial {
     #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
-    #[serde(rename = "pbrMetallicRoughness")]
+    #[serde(default, rename = "pbrMetallicRoughness")]
     pbr_metallic_roughness: PbrMetallicRoughness,
+    #[serde(rename = "normalTexture")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    normal_texture: Option<GltfNormalTextureInfo>,
+    #[serde(rename = "occlusionTexture")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    occlusion_texture: Option<GltfOcclusionTextureInfo>,
+    #[serde(rename = "doubleSided")]
     #[serde(skip_serializing_if = "Option::is_none")]
     double_sided: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+struct GltfNormalTextureInfo {
+    index: u32,
+    #[serde(default = "one")]
+    scale: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfOcclusionTextureInfo {
+    index: u32,
+    #[serde(default = "one")]
+    strength: f32,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfTexture {
+    source: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GltfImage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri: Option<String>,
+    #[serde(rename = "bufferView")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buffer_view: Option<u32>,
+    #[serde(rename = "mimeType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 struct PbrMetallicRoughness {
     #[serde(rename = "baseColorFactor")]
     base_color_factor: [f32; 4],
@@ -426,6 +1043,17 @@ struct PbrMetallicRoughness {
     roughness_factor: f32,
 }
 
+/// Valores padrão da especificação glTF
+impl Default for PbrMetallicRoughness {
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.0; 4],
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GltfBuffer {
     #[serde(rename = "byteLength")]
@@ -437,10 +1065,13 @@ struct GltfBuffer {
 #[derive(Debug, Serialize, Deserialize)]
 struct GltfBufferView {
     buffer: u32,
-    #[serde(rename = "byteOffset")]
+    #[serde(default, rename = "byteOffset")]
     byte_offset: u32,
     #[serde(rename = "byteLength")]
     byte_length: u32,
+    #[serde(rename = "byteStride")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    byte_stride: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     target: Option<u32>,
 }
@@ -450,7 +1081,7 @@ struct GltfAccessor {
     #[serde(rename = "bufferView")]
     #[serde(skip_serializing_if = "Option::is_none")]
     buffer_view: Option<u32>,
-    #[serde(rename = "byteOffset")]
+    #[serde(default, rename = "byteOffset")]
     byte_offset: u32,
     #[serde(rename = "componentType")]
     component_type: u32,
@@ -500,4 +1131,162 @@ mod tests {
 
         assert!(glb.len() > 100);
     }
+
+    #[test]
+    fn test_export_baked_normal_map() {
+        let mut scene = Scene::new();
+        let mut low = primitives::plane(2.0, 2.0);
+        low.material_id = Some("floor".into());
+        scene.add_material(PbrMaterial::default_material("floor"));
+
+        let options = BakeOptions { width: 8, height: 8, ..Default::default() };
+        bake_maps(&low, &low, &options).unwrap().attach_to(&mut scene, "floor").unwrap();
+        scene.add_mesh(low);
+
+        let (json, bin, _) = GltfExporter::new().export_parts(&scene, &ExportOptions::default()).unwrap();
+        let root: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(root["materials"][0]["normalTexture"]["index"], 0);
+        assert_eq!(root["images"][0]["mimeType"], "image/png");
+
+        let view = &root["bufferViews"][root["images"][0]["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        assert_eq!(&bin[offset..offset + 8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    fn named_scene() -> Scene {
+        let mut scene = Scene::new();
+        for guid in ["2O2Fr$t4X7Zf8NOew3FLOH", "1hOSvn6df7F8_7GcBWlRGQ"] {
+            let mut wall = primitives::cube(1.0);
+            wall.name = Some("Basic Wall".into());
+            wall.element_guid = Some(guid.into());
+            wall.material_id = Some("concrete".into());
+            scene.add_mesh(wall);
+        }
+        scene.add_material(PbrMaterial::from_ifc_material("concrete", "Concreto"));
+        scene
+    }
+
+    fn export_json(scene: &Scene, opts: &ExportOptions) -> serde_json::Value {
+        let (json, _, _) = GltfExporter::new().export_parts(scene, opts).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_naming_policies() {
+        let scene = named_scene();
+
+        let root = export_json(&scene, &ExportOptions::default());
+        assert_eq!(root["nodes"][0]["name"], "Basic Wall");
+        assert_eq!(root["nodes"][1]["name"], "Basic Wall_2");
+        assert_eq!(root["meshes"][1]["name"], "Basic Wall_2");
+        assert_eq!(root["materials"][0]["name"], "Concreto");
+
+        let opts = ExportOptions { naming: NamingPolicy::NameAndGuid, ..Default::default() };
+        let root = export_json(&scene, &opts);
+        assert_eq!(root["nodes"][1]["name"], "Basic Wall [1hOSvn6df7F8_7GcBWlRGQ]");
+        assert_eq!(root["materials"][0]["name"], "Concreto [concrete]");
+
+        let opts = ExportOptions { naming: NamingPolicy::None, ..Default::default() };
+        let root = export_json(&scene, &opts);
+        assert!(root["nodes"][0].get("name").is_none());
+        assert!(root["materials"][0].get("name").is_none());
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let mut names = NameAllocator::new(NameCollision::Suffix);
+        assert_eq!(names.allocate(Some("Door_2".into())).unwrap(), "Door_2");
+        assert_eq!(names.allocate(Some("Door".into())).unwrap(), "Door");
+        // "Door_2" já existe: pula para "Door_3"
+        assert_eq!(names.allocate(Some("Door".into())).unwrap(), "Door_3");
+        assert_eq!(names.allocate(None), None);
+
+        let mut names = NameAllocator::new(NameCollision::Allow);
+        assert_eq!(names.allocate(Some("Door".into())).unwrap(), "Door");
+        assert_eq!(names.allocate(Some("Door".into())).unwrap(), "Door");
+    }
+
+    #[test]
+    fn test_export_report() {
+        let mut scene = named_scene();
+        scene.meshes[1].material_id = Some("steel".into());
+        scene.meshes[1].vertices[0].position.x = f32::NAN;
+        let normal = &mut scene.meshes[1].vertices[1].normal;
+        (normal.x, normal.y, normal.z) = (0.0, 0.0, 0.0);
+        scene.meshes[1].indices.extend_from_slice(&[0, 1, 999]);
+
+        let opts = ExportOptions {
+            pipeline: vec![PipelineStep::new("merge", serde_json::json!({ "meshesBefore": 40, "meshesAfter": 2 }))],
+            ..Default::default()
+        };
+        let (glb, report) = GltfExporter::new().export_glb_with_report(&scene, &opts).unwrap();
+
+        assert_eq!(report.meshes, 2);
+        assert_eq!(report.triangles, 24);
+        assert_eq!(report.index_formats, IndexFormats { u16: 2, u32: 0 });
+        assert_eq!(report.attributes.normals, 2);
+        assert_eq!(report.missing_materials, vec![MissingMaterial { mesh: 1, material_id: "steel".into() }]);
+        assert!(report.renamed.contains(&Rename {
+            kind: NameKind::Node,
+            original: "Basic Wall".into(),
+            exported: "Basic Wall_2".into(),
+        }));
+        let fixes: Vec<_> = report.sanitized.iter().map(|fix| (fix.mesh, fix.kind, fix.count)).collect();
+        assert_eq!(
+            fixes,
+            vec![
+                (1, SanitationKind::NonFinitePosition, 1),
+                (1, SanitationKind::NormalReplaced, 1),
+                (1, SanitationKind::TriangleDropped, 1),
+            ]
+        );
+        assert_eq!(report.pipeline[0].details["meshesAfter"], 2);
+
+        // O mesmo relatório fica em asset.extras
+        assert_eq!(ExportReport::from_glb(&glb), Some(report));
+
+        let opts = ExportOptions { embed_report: false, ..Default::default() };
+        let glb = GltfExporter::new().export_glb(&scene, &opts).unwrap();
+        assert_eq!(ExportReport::from_glb(&glb), None);
+    }
+
+    #[test]
+    fn test_export_point_cloud() {
+        let mut scene = named_scene();
+        let mut cloud = PointCloud::new();
+        cloud.name = Some("Scan Térreo".into());
+        for (i, color) in [[255, 0, 0], [0, 128, 255], [10, 10, 10]].into_iter().enumerate() {
+            let mut point = Point::new(avila_vec3d::Vec3::new(i as f32, 0.0, 2.0));
+            point.color = Some(color);
+            point.intensity = Some(1000 * i as u16);
+            cloud.push(point);
+        }
+        scene.add_point_cloud(cloud.clone());
+        scene.add_point_cloud(PointCloud::new());
+
+        let (glb, report) = GltfExporter::new().export_glb_with_report(&scene, &ExportOptions::default()).unwrap();
+        assert_eq!((report.meshes, report.points), (2, 3));
+
+        let root = export_json(&scene, &ExportOptions::default());
+        // Nuvens vazias não são exportadas
+        assert_eq!(root["scenes"][0]["nodes"], serde_json::json!([0, 1, 2]));
+        assert_eq!(root["nodes"][2]["name"], "Scan Térreo");
+        let primitive = &root["meshes"][2]["primitives"][0];
+        assert_eq!(primitive["mode"], 0);
+        assert!(primitive.get("indices").is_none());
+        let accessor = |attribute: &str| &root["accessors"][primitive["attributes"][attribute].as_u64().unwrap() as usize];
+        assert_eq!(accessor("POSITION")["count"], 3);
+        assert_eq!(accessor("POSITION")["max"], serde_json::json!([2.0, 0.0, 2.0]));
+        assert_eq!(accessor("COLOR_0")["type"], "VEC3");
+        assert_eq!(accessor("_INTENSITY")["type"], "SCALAR");
+        assert!((srgb_to_linear(128) - 0.2158).abs() < 1e-3);
+
+        // O importador ignora primitivas de pontos
+        assert_eq!(GltfImporter::new().import_glb(&glb).unwrap().meshes.len(), 2);
+
+        cloud.intensities.pop();
+        scene.point_clouds = vec![cloud];
+        assert!(GltfExporter::new().export_glb(&scene, &ExportOptions::default()).is_err());
+    }
 }
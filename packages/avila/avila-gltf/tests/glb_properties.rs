@@ -0,0 +1,142 @@
+//! Propriedades de exportação → importação de GLB e robustez a GLB corrompido
+
+use avila_gltf::{ExportOptions, ExportReport, GltfExporter, GltfImporter, NameCollision, NamingPolicy};
+use avila_mesh::{Mesh, Scene, Vertex};
+use avila_vec3d::{Vec2, Vec3};
+use proptest::prelude::*;
+
+type VertexParts = (f32, f32, f32, f32, f32);
+
+/// Malha com 3..40 vértices, 1..60 triângulos, nome e GUID opcional
+fn mesh() -> impl Strategy<Value = Mesh> {
+    let coord = -1000.0f32..1000.0;
+    let vertex = (coord.clone(), coord.clone(), coord, 0.0f32..1.0, 0.0f32..1.0);
+    prop::collection::vec(vertex, 3..40)
+        .prop_flat_map(|vertices: Vec<VertexParts>| {
+            let count = vertices.len() as u32;
+            let triangle = (0..count, 0..count, 0..count);
+            (
+                Just(vertices),
+                prop::collection::vec(triangle, 1..60),
+                "[A-Za-z0-9_-][A-Za-z0-9 _-]{0,15}",
+                prop::option::of("[0-9A-Za-z_$]{22}"),
+            )
+        })
+        .prop_map(|(vertices, triangles, name, guid)| {
+            let mut mesh = Mesh::new();
+            for (x, y, z, u, v) in vertices {
+                mesh.add_vertex(Vertex::new(Vec3::new(x, y, z)).with_uv(Vec2::new(u, v)));
+            }
+            for (a, b, c) in triangles {
+                mesh.add_triangle(a, b, c).expect("índices dentro da malha");
+            }
+            mesh.name = Some(name);
+            mesh.element_guid = guid;
+            mesh
+        })
+}
+
+fn scene() -> impl Strategy<Value = Scene> {
+    prop::collection::vec(mesh(), 1..4).prop_map(|meshes| {
+        let mut scene = Scene::new();
+        for mesh in meshes {
+            scene.add_mesh(mesh);
+        }
+        scene
+    })
+}
+
+/// Nome e GUID voltam separados de `"nome [guid]"`
+fn export(scene: &Scene) -> Vec<u8> {
+    let options = ExportOptions {
+        naming: NamingPolicy::NameAndGuid,
+        name_collisions: NameCollision::Allow,
+        ..Default::default()
+    };
+    GltfExporter::new().export_glb(scene, &options).expect("cena válida")
+}
+
+#[derive(Debug, Clone)]
+enum Corruption {
+    FlipBit(prop::sample::Index, u8),
+    Truncate(prop::sample::Index),
+    /// Sobrescreve uma palavra alinhada (comprimentos, offsets, contagens)
+    OverwriteWord(prop::sample::Index, u32),
+    /// Reescreve o comprimento do chunk JSON ou do BIN
+    ChunkLength(bool, u32),
+}
+
+impl Corruption {
+    fn apply(&self, glb: &mut Vec<u8>) {
+        match *self {
+            Self::FlipBit(at, bit) => {
+                let i = at.index(glb.len());
+                glb[i] ^= 1 << (bit % 8);
+            }
+            Self::Truncate(at) => glb.truncate(at.index(glb.len())),
+            Self::OverwriteWord(at, value) => {
+                let at = at.index(glb.len() / 4) * 4;
+                glb[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            }
+            Self::ChunkLength(bin, value) => {
+                let json_len = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+                let at = if bin { 20 + json_len } else { 12 };
+                if let Some(word) = glb.get_mut(at..at + 4) {
+                    word.copy_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+fn corruption() -> impl Strategy<Value = Corruption> {
+    prop_oneof![
+        (any::<prop::sample::Index>(), any::<u8>()).prop_map(|(at, bit)| Corruption::FlipBit(at, bit)),
+        any::<prop::sample::Index>().prop_map(Corruption::Truncate),
+        (any::<prop::sample::Index>(), any::<u32>()).prop_map(|(at, value)| Corruption::OverwriteWord(at, value)),
+        (any::<bool>(), any::<u32>()).prop_map(|(bin, value)| Corruption::ChunkLength(bin, value)),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn export_import_round_trip(scene in scene()) {
+        let glb = export(&scene);
+        let imported = GltfImporter::new().import_glb(&glb).expect("GLB exportado importa");
+        prop_assert_eq!(imported.mesh_count(), scene.mesh_count());
+
+        for (got, expected) in imported.meshes.iter().zip(&scene.meshes) {
+            prop_assert_eq!(&got.indices, &expected.indices);
+            let positions = |mesh: &Mesh| mesh.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+            let uvs = |mesh: &Mesh| mesh.vertices.iter().map(|v| v.uv).collect::<Vec<_>>();
+            prop_assert_eq!(positions(got), positions(expected));
+            prop_assert_eq!(uvs(got), uvs(expected));
+            prop_assert_eq!(&got.name, &expected.name);
+            prop_assert_eq!(&got.element_guid, &expected.element_guid);
+            prop_assert_eq!(got.bounds, expected.bounds);
+        }
+
+        let report = ExportReport::from_glb(&glb).expect("relatório embutido");
+        prop_assert_eq!(report.meshes, scene.mesh_count());
+        prop_assert_eq!(report.triangles, scene.meshes.iter().map(|mesh| mesh.indices.len() / 3).sum::<usize>());
+    }
+
+    #[test]
+    fn corrupted_glb_never_panics(scene in scene(), corruptions in prop::collection::vec(corruption(), 1..4)) {
+        let mut glb = export(&scene);
+        for corruption in &corruptions {
+            if glb.len() < 20 {
+                break;
+            }
+            corruption.apply(&mut glb);
+        }
+
+        // Erro ou malhas consistentes; nunca pânico
+        if let Ok(imported) = GltfImporter::new().import_glb(&glb) {
+            for mesh in &imported.meshes {
+                prop_assert!(mesh.validate().is_ok());
+            }
+        }
+        let _ = ExportReport::from_glb(&glb);
+    }
+}
@@ -0,0 +1,972 @@
+//! Estado de uma conexão HTTP/2, sem I/O
+
+use crate::frame::{setting, Frame, DEFAULT_MAX_FRAME_SIZE, MAX_FRAME_SIZE_LIMIT, MAX_WINDOW_SIZE};
+use crate::hpack::{Decoder, Encoder};
+use crate::{ErrorCode, H2Error, Result, PREFACE};
+use std::collections::{HashMap, VecDeque};
+
+/// Janela inicial de qualquer stream e da conexão antes de SETTINGS
+const DEFAULT_WINDOW: u32 = 65_535;
+
+/// Janela de recepção da conexão, compartilhada por todos os streams
+const CONNECTION_WINDOW: u32 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Client,
+    Server,
+}
+
+/// Parâmetros anunciados ao peer no SETTINGS inicial
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub header_table_size: u32,
+    /// Streams que o peer pode manter abertos ao mesmo tempo
+    pub max_concurrent_streams: u32,
+    /// Janela de recepção de cada stream
+    pub initial_window_size: u32,
+    pub max_frame_size: u32,
+    pub max_header_list_size: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            header_table_size: 4096,
+            max_concurrent_streams: 100,
+            initial_window_size: 1024 * 1024,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_header_list_size: 64 * 1024,
+        }
+    }
+}
+
+/// Parâmetros do peer (valores padrão da RFC até o SETTINGS dele chegar)
+#[derive(Debug)]
+struct RemoteSettings {
+    max_concurrent_streams: u32,
+    initial_window_size: u32,
+    max_frame_size: u32,
+}
+
+/// O que a conexão entrega a quem a usa
+#[derive(Debug, PartialEq, Eq)]
+pub enum Event {
+    /// Cabeçalhos de requisição ou resposta, ou trailers
+    Headers {
+        stream: u32,
+        headers: Vec<(String, String)>,
+        end_stream: bool,
+    },
+    Data {
+        stream: u32,
+        data: Vec<u8>,
+        end_stream: bool,
+    },
+    /// O peer cancelou o stream, ou o recusou no GOAWAY
+    Reset { stream: u32, code: ErrorCode },
+    /// O peer não aceita mais streams novos
+    GoAway { last_stream: u32, code: ErrorCode },
+}
+
+#[derive(Debug)]
+struct Stream {
+    send_window: i64,
+    recv_window: i64,
+    /// Bytes recebidos ainda não devolvidos com WINDOW_UPDATE
+    recv_unacked: u32,
+    /// Dados aguardando janela de envio
+    pending: VecDeque<u8>,
+    /// END_STREAM depois de `pending`
+    pending_end: bool,
+    /// Trailers a enviar depois de `pending`
+    trailers: Option<Vec<(String, String)>>,
+    local_closed: bool,
+    remote_closed: bool,
+}
+
+impl Stream {
+    fn new(send_window: u32, recv_window: u32) -> Self {
+        Self {
+            send_window: send_window as i64,
+            recv_window: recv_window as i64,
+            recv_unacked: 0,
+            pending: VecDeque::new(),
+            pending_end: false,
+            trailers: None,
+            local_closed: false,
+            remote_closed: false,
+        }
+    }
+
+    fn accepts_send(&self) -> bool {
+        !self.local_closed && !self.pending_end && self.trailers.is_none()
+    }
+}
+
+enum Progress {
+    /// Stream sem mais nada a enviar
+    Done,
+    More,
+    /// Sem janela de envio
+    Blocked,
+}
+
+/// Uma conexão HTTP/2, de qualquer um dos lados
+///
+/// Dados recebidos são devolvidos ao peer (WINDOW_UPDATE) assim que viram
+/// [`Event::Data`]; quem consome os eventos é responsável por armazená-los.
+#[derive(Debug)]
+pub struct Connection {
+    role: Role,
+    local: Settings,
+    local_acked: bool,
+    remote: RemoteSettings,
+    encoder: Encoder,
+    decoder: Decoder,
+    streams: HashMap<u32, Stream>,
+    /// Streams com dados pendentes, atendidos em rodízio
+    send_queue: VecDeque<u32>,
+    next_stream_id: u32,
+    last_peer_stream: u32,
+    send_window: i64,
+    recv_window: i64,
+    recv_unacked: u32,
+    /// Bloco de cabeçalhos aguardando CONTINUATION: (stream, bloco, END_STREAM)
+    continuation: Option<(u32, Vec<u8>, bool)>,
+    awaiting_preface: bool,
+    goaway_received: Option<ErrorCode>,
+    goaway_sent: bool,
+    closed: bool,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    events: VecDeque<Event>,
+}
+
+impl Connection {
+    /// Lado cliente; o prefácio e o SETTINGS já ficam na saída
+    pub fn client(settings: Settings) -> Self {
+        let mut connection = Self::new(Role::Client, settings);
+        connection.output.extend_from_slice(PREFACE);
+        connection.send_settings();
+        connection
+    }
+
+    /// Lado servidor; espera o prefácio do cliente antes do primeiro frame
+    pub fn server(settings: Settings) -> Self {
+        let mut connection = Self::new(Role::Server, settings);
+        connection.awaiting_preface = true;
+        connection.send_settings();
+        connection
+    }
+
+    fn new(role: Role, local: Settings) -> Self {
+        Self {
+            role,
+            encoder: Encoder::new(),
+            decoder: Decoder::new(local.header_table_size as usize, local.max_header_list_size as usize),
+            local,
+            local_acked: false,
+            remote: RemoteSettings {
+                max_concurrent_streams: u32::MAX,
+                initial_window_size: DEFAULT_WINDOW,
+                max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            },
+            streams: HashMap::new(),
+            send_queue: VecDeque::new(),
+            next_stream_id: if role == Role::Client { 1 } else { 2 },
+            last_peer_stream: 0,
+            send_window: DEFAULT_WINDOW as i64,
+            recv_window: CONNECTION_WINDOW as i64,
+            recv_unacked: 0,
+            continuation: None,
+            awaiting_preface: false,
+            goaway_received: None,
+            goaway_sent: false,
+            closed: false,
+            input: Vec::new(),
+            output: Vec::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    fn send_settings(&mut self) {
+        let mut params = vec![(setting::HEADER_TABLE_SIZE, self.local.header_table_size)];
+        if self.role == Role::Client {
+            params.push((setting::ENABLE_PUSH, 0));
+        }
+        params.extend([
+            (setting::MAX_CONCURRENT_STREAMS, self.local.max_concurrent_streams),
+            (setting::INITIAL_WINDOW_SIZE, self.local.initial_window_size),
+            (setting::MAX_FRAME_SIZE, self.local.max_frame_size),
+            (setting::MAX_HEADER_LIST_SIZE, self.local.max_header_list_size),
+        ]);
+        Frame::Settings { ack: false, params }.encode(&mut self.output);
+        Frame::WindowUpdate {
+            stream: 0,
+            increment: CONNECTION_WINDOW - DEFAULT_WINDOW,
+        }
+        .encode(&mut self.output);
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    // ========================================================================
+    // ENTRADA
+    // ========================================================================
+
+    /// Processa bytes lidos do socket
+    ///
+    /// Um erro encerra a conexão: o GOAWAY correspondente já está na saída e
+    /// deve ser escrito antes de fechar o socket.
+    pub fn receive(&mut self, data: &[u8]) -> Result<()> {
+        if self.closed {
+            return Err(H2Error::GoAway(ErrorCode::NoError));
+        }
+        self.input.extend_from_slice(data);
+        let result = self.process_input();
+        if let Err(error) = &result {
+            Frame::GoAway {
+                last_stream: self.last_peer_stream,
+                code: error.code(),
+                debug: error.to_string().into_bytes(),
+            }
+            .encode(&mut self.output);
+            self.closed = true;
+        }
+        result
+    }
+
+    fn process_input(&mut self) -> Result<()> {
+        if self.awaiting_preface {
+            let n = self.input.len().min(PREFACE.len());
+            if self.input[..n] != PREFACE[..n] {
+                return Err(H2Error::protocol(ErrorCode::ProtocolError, "invalid connection preface"));
+            }
+            if n < PREFACE.len() {
+                return Ok(());
+            }
+            self.input.drain(..PREFACE.len());
+            self.awaiting_preface = false;
+        }
+        let mut offset = 0;
+        let result = loop {
+            match Frame::parse(&self.input[offset..], self.local.max_frame_size) {
+                Ok(Some((frame, used))) => {
+                    offset += used;
+                    if let Err(error) = self.handle_frame(frame) {
+                        break Err(error);
+                    }
+                }
+                Ok(None) => break Ok(()),
+                Err(error) => break Err(error),
+            }
+        };
+        self.input.drain(..offset);
+        result
+    }
+
+    fn handle_frame(&mut self, frame: Frame) -> Result<()> {
+        if let Some((stream, ..)) = &self.continuation {
+            if !matches!(frame, Frame::Continuation { stream: s, .. } if s == *stream) {
+                return Err(H2Error::protocol(ErrorCode::ProtocolError, "expected CONTINUATION frame"));
+            }
+        }
+        match frame {
+            Frame::Data {
+                stream,
+                data,
+                end_stream,
+                flow_len,
+            } => self.on_data(stream, data, end_stream, flow_len),
+            Frame::Headers {
+                stream,
+                block,
+                end_stream,
+                end_headers,
+            } => {
+                if end_headers {
+                    self.on_headers(stream, &block, end_stream)
+                } else {
+                    self.continuation = Some((stream, block, end_stream));
+                    Ok(())
+                }
+            }
+            Frame::Continuation { block, end_headers, .. } => {
+                let Some((stream, mut pending, end_stream)) = self.continuation.take() else {
+                    return Err(H2Error::protocol(ErrorCode::ProtocolError, "unexpected CONTINUATION frame"));
+                };
+                pending.extend_from_slice(&block);
+                if pending.len() > self.local.max_header_list_size as usize {
+                    return Err(H2Error::protocol(ErrorCode::EnhanceYourCalm, "header block too large"));
+                }
+                if end_headers {
+                    self.on_headers(stream, &pending, end_stream)
+                } else {
+                    self.continuation = Some((stream, pending, end_stream));
+                    Ok(())
+                }
+            }
+            Frame::RstStream { stream, code } => {
+                if self.is_idle(stream) {
+                    return Err(H2Error::protocol(
+                        ErrorCode::ProtocolError,
+                        format!("RST_STREAM on idle stream {stream}"),
+                    ));
+                }
+                if self.streams.remove(&stream).is_some() {
+                    self.send_queue.retain(|&id| id != stream);
+                    self.events.push_back(Event::Reset { stream, code });
+                }
+                Ok(())
+            }
+            Frame::Settings { ack: true, .. } => {
+                self.local_acked = true;
+                Ok(())
+            }
+            Frame::Settings { ack: false, params } => {
+                self.apply_settings(&params)?;
+                Frame::Settings {
+                    ack: true,
+                    params: Vec::new(),
+                }
+                .encode(&mut self.output);
+                self.flush();
+                Ok(())
+            }
+            Frame::PushPromise { .. } => Err(H2Error::protocol(
+                ErrorCode::ProtocolError,
+                "PUSH_PROMISE received with push disabled",
+            )),
+            Frame::Ping { ack: false, data } => {
+                Frame::Ping { ack: true, data }.encode(&mut self.output);
+                Ok(())
+            }
+            Frame::GoAway { last_stream, code, .. } => {
+                self.on_goaway(last_stream, code);
+                Ok(())
+            }
+            Frame::WindowUpdate { stream, increment } => self.on_window_update(stream, increment),
+            Frame::Ping { ack: true, .. } | Frame::Priority { .. } | Frame::Unknown { .. } => Ok(()),
+        }
+    }
+
+    fn on_headers(&mut self, stream: u32, block: &[u8], end_stream: bool) -> Result<()> {
+        // Decodifica sempre, mesmo se o stream for descartado, para manter a tabela HPACK em sincronia
+        let headers = self.decoder.decode(block)?;
+
+        if let Some(state) = self.streams.get_mut(&stream) {
+            if state.remote_closed {
+                self.reset_stream(stream, ErrorCode::StreamClosed);
+                return Ok(());
+            }
+            state.remote_closed = end_stream;
+            self.events.push_back(Event::Headers {
+                stream,
+                headers,
+                end_stream,
+            });
+            self.maybe_close(stream);
+            return Ok(());
+        }
+
+        if self.is_idle(stream) && !self.is_peer_initiated(stream) {
+            return Err(H2Error::protocol(
+                ErrorCode::ProtocolError,
+                format!("HEADERS on idle stream {stream}"),
+            ));
+        }
+        if self.role == Role::Client && self.is_peer_initiated(stream) {
+            return Err(H2Error::protocol(ErrorCode::ProtocolError, "server opened a stream"));
+        }
+        // Stream já encerrado (ou resetado por nós): ignorado
+        if !self.is_idle(stream) {
+            return Ok(());
+        }
+
+        self.last_peer_stream = stream;
+        if self.goaway_sent {
+            return Ok(());
+        }
+        let open = self.streams.keys().filter(|&&id| self.is_peer_initiated(id)).count();
+        if open >= self.local.max_concurrent_streams as usize {
+            Frame::RstStream {
+                stream,
+                code: ErrorCode::RefusedStream,
+            }
+            .encode(&mut self.output);
+            return Ok(());
+        }
+        let mut state = Stream::new(self.remote.initial_window_size, self.recv_initial_window());
+        state.remote_closed = end_stream;
+        self.streams.insert(stream, state);
+        self.events.push_back(Event::Headers {
+            stream,
+            headers,
+            end_stream,
+        });
+        Ok(())
+    }
+
+    fn on_data(&mut self, stream: u32, data: Vec<u8>, end_stream: bool, flow_len: u32) -> Result<()> {
+        // A janela da conexão conta até frames de streams descartados
+        if flow_len as i64 > self.recv_window {
+            return Err(H2Error::protocol(
+                ErrorCode::FlowControlError,
+                "connection flow-control window exceeded",
+            ));
+        }
+        self.recv_window -= flow_len as i64;
+        self.recv_unacked += flow_len;
+        if self.recv_unacked >= CONNECTION_WINDOW / 2 {
+            self.window_update(0, self.recv_unacked);
+            self.recv_window += self.recv_unacked as i64;
+            self.recv_unacked = 0;
+        }
+
+        let threshold = self.local.initial_window_size / 2;
+        let Some(state) = self.streams.get_mut(&stream) else {
+            if self.is_idle(stream) {
+                return Err(H2Error::protocol(
+                    ErrorCode::ProtocolError,
+                    format!("DATA on idle stream {stream}"),
+                ));
+            }
+            return Ok(());
+        };
+        if state.remote_closed {
+            self.reset_stream(stream, ErrorCode::StreamClosed);
+            return Ok(());
+        }
+        if flow_len as i64 > state.recv_window {
+            self.reset_stream(stream, ErrorCode::FlowControlError);
+            return Ok(());
+        }
+        state.recv_window -= flow_len as i64;
+        if end_stream {
+            state.remote_closed = true;
+        } else {
+            state.recv_unacked += flow_len;
+            if state.recv_unacked >= threshold.max(1) {
+                let increment = state.recv_unacked;
+                state.recv_window += increment as i64;
+                state.recv_unacked = 0;
+                self.window_update(stream, increment);
+            }
+        }
+        self.events.push_back(Event::Data {
+            stream,
+            data,
+            end_stream,
+        });
+        self.maybe_close(stream);
+        Ok(())
+    }
+
+    fn apply_settings(&mut self, params: &[(u16, u32)]) -> Result<()> {
+        for &(id, value) in params {
+            match id {
+                setting::HEADER_TABLE_SIZE => self.encoder.set_max_table_size(value as usize),
+                setting::ENABLE_PUSH if value > 1 => {
+                    return Err(H2Error::protocol(ErrorCode::ProtocolError, "invalid SETTINGS_ENABLE_PUSH"));
+                }
+                setting::MAX_CONCURRENT_STREAMS => self.remote.max_concurrent_streams = value,
+                setting::INITIAL_WINDOW_SIZE => {
+                    if value > MAX_WINDOW_SIZE {
+                        return Err(H2Error::protocol(
+                            ErrorCode::FlowControlError,
+                            "SETTINGS_INITIAL_WINDOW_SIZE above 2^31-1",
+                        ));
+                    }
+                    let delta = value as i64 - self.remote.initial_window_size as i64;
+                    for state in self.streams.values_mut() {
+                        state.send_window += delta;
+                        if state.send_window > MAX_WINDOW_SIZE as i64 {
+                            return Err(H2Error::protocol(ErrorCode::FlowControlError, "stream window overflow"));
+                        }
+                    }
+                    self.remote.initial_window_size = value;
+                }
+                setting::MAX_FRAME_SIZE => {
+                    if !(DEFAULT_MAX_FRAME_SIZE..=MAX_FRAME_SIZE_LIMIT).contains(&value) {
+                        return Err(H2Error::protocol(ErrorCode::ProtocolError, "invalid SETTINGS_MAX_FRAME_SIZE"));
+                    }
+                    self.remote.max_frame_size = value;
+                }
+                // MAX_HEADER_LIST_SIZE é só um aviso; identificadores desconhecidos são ignorados
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn on_goaway(&mut self, last_stream: u32, code: ErrorCode) {
+        self.goaway_received = Some(code);
+        let mut refused: Vec<u32> = self
+            .streams
+            .keys()
+            .filter(|&&id| !self.is_peer_initiated(id) && id > last_stream)
+            .copied()
+            .collect();
+        refused.sort_unstable();
+        for stream in refused {
+            self.streams.remove(&stream);
+            self.send_queue.retain(|&id| id != stream);
+            self.events.push_back(Event::Reset {
+                stream,
+                code: ErrorCode::RefusedStream,
+            });
+        }
+        self.events.push_back(Event::GoAway { last_stream, code });
+    }
+
+    fn on_window_update(&mut self, stream: u32, increment: u32) -> Result<()> {
+        if stream == 0 {
+            if increment == 0 {
+                return Err(H2Error::protocol(ErrorCode::ProtocolError, "WINDOW_UPDATE with zero increment"));
+            }
+            self.send_window += increment as i64;
+            if self.send_window > MAX_WINDOW_SIZE as i64 {
+                return Err(H2Error::protocol(
+                    ErrorCode::FlowControlError,
+                    "connection window overflow",
+                ));
+            }
+        } else if let Some(state) = self.streams.get_mut(&stream) {
+            state.send_window += increment as i64;
+            if increment == 0 {
+                self.reset_stream(stream, ErrorCode::ProtocolError);
+            } else if state.send_window > MAX_WINDOW_SIZE as i64 {
+                self.reset_stream(stream, ErrorCode::FlowControlError);
+            }
+        } else if self.is_idle(stream) {
+            return Err(H2Error::protocol(
+                ErrorCode::ProtocolError,
+                format!("WINDOW_UPDATE on idle stream {stream}"),
+            ));
+        }
+        self.flush();
+        Ok(())
+    }
+
+    // ========================================================================
+    // SAÍDA
+    // ========================================================================
+
+    /// Abre um stream com os cabeçalhos da requisição (só no cliente)
+    pub fn send_request(&mut self, headers: &[(String, String)], end_stream: bool) -> Result<u32> {
+        if self.role != Role::Client {
+            return Err(H2Error::Usage("only clients open streams"));
+        }
+        if let Some(code) = self.goaway_received {
+            return Err(H2Error::GoAway(code));
+        }
+        if self.closed || self.goaway_sent {
+            return Err(H2Error::GoAway(ErrorCode::NoError));
+        }
+        if !self.can_open_stream() {
+            return Err(H2Error::StreamLimit);
+        }
+        let stream = self.next_stream_id;
+        self.next_stream_id += 2;
+        let mut state = Stream::new(self.remote.initial_window_size, self.recv_initial_window());
+        state.local_closed = end_stream;
+        self.streams.insert(stream, state);
+        self.write_headers(stream, headers, end_stream);
+        Ok(stream)
+    }
+
+    /// Envia cabeçalhos de resposta, ou trailers se `end_stream`
+    ///
+    /// Trailers enviados com dados ainda pendentes saem depois deles.
+    pub fn send_headers(&mut self, stream: u32, headers: &[(String, String)], end_stream: bool) -> Result<()> {
+        let state = self
+            .streams
+            .get_mut(&stream)
+            .filter(|state| state.accepts_send())
+            .ok_or(H2Error::StreamClosed(stream))?;
+        if !state.pending.is_empty() {
+            if !end_stream {
+                return Err(H2Error::Usage("headers after pending data must end the stream"));
+            }
+            state.trailers = Some(headers.to_vec());
+            return Ok(());
+        }
+        state.local_closed = end_stream;
+        self.write_headers(stream, headers, end_stream);
+        self.maybe_close(stream);
+        Ok(())
+    }
+
+    /// Enfileira dados; saem conforme as janelas de envio permitem
+    pub fn send_data(&mut self, stream: u32, data: &[u8], end_stream: bool) -> Result<()> {
+        let state = self
+            .streams
+            .get_mut(&stream)
+            .filter(|state| state.accepts_send())
+            .ok_or(H2Error::StreamClosed(stream))?;
+        if data.is_empty() && !end_stream {
+            return Ok(());
+        }
+        state.pending.extend(data);
+        state.pending_end = end_stream;
+        if !self.send_queue.contains(&stream) {
+            self.send_queue.push_back(stream);
+        }
+        self.flush();
+        Ok(())
+    }
+
+    /// Cancela um stream
+    pub fn reset_stream(&mut self, stream: u32, code: ErrorCode) {
+        self.streams.remove(&stream);
+        self.send_queue.retain(|&id| id != stream);
+        Frame::RstStream { stream, code }.encode(&mut self.output);
+    }
+
+    /// Encerra a conexão de forma ordenada: streams abertos terminam, novos são ignorados
+    pub fn go_away(&mut self, code: ErrorCode) {
+        if self.goaway_sent {
+            return;
+        }
+        self.goaway_sent = true;
+        Frame::GoAway {
+            last_stream: self.last_peer_stream,
+            code,
+            debug: Vec::new(),
+        }
+        .encode(&mut self.output);
+    }
+
+    /// Próximo evento recebido, na ordem de chegada
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// Bytes a escrever no socket
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+
+    pub fn has_output(&self) -> bool {
+        !self.output.is_empty()
+    }
+
+    /// Se um novo stream pode ser aberto agora sem exceder o limite do peer
+    pub fn can_open_stream(&self) -> bool {
+        let open = self.streams.keys().filter(|&&id| !self.is_peer_initiated(id)).count();
+        !self.closed
+            && !self.goaway_sent
+            && self.goaway_received.is_none()
+            && self.next_stream_id <= MAX_WINDOW_SIZE
+            && open < self.remote.max_concurrent_streams as usize
+    }
+
+    /// Streams ainda abertos em alguma direção
+    pub fn open_streams(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Bytes de `stream` ainda aguardando janela de envio
+    pub fn buffered(&self, stream: u32) -> usize {
+        self.streams.get(&stream).map(|state| state.pending.len()).unwrap_or(0)
+    }
+
+    /// A conexão não aceita mais streams novos (GOAWAY enviado ou recebido, ou erro)
+    pub fn is_going_away(&self) -> bool {
+        self.closed || self.goaway_sent || self.goaway_received.is_some()
+    }
+
+    // ========================================================================
+    // INTERNOS
+    // ========================================================================
+
+    fn write_headers(&mut self, stream: u32, headers: &[(String, String)], end_stream: bool) {
+        let mut block = Vec::new();
+        self.encoder
+            .encode(headers.iter().map(|(name, value)| (name.as_str(), value.as_str())), &mut block);
+        let max = self.remote.max_frame_size as usize;
+        let mut chunks = block.chunks(max).peekable();
+        let first = chunks.next().unwrap_or_default().to_vec();
+        Frame::Headers {
+            stream,
+            block: first,
+            end_stream,
+            end_headers: chunks.peek().is_none(),
+        }
+        .encode(&mut self.output);
+        while let Some(chunk) = chunks.next() {
+            Frame::Continuation {
+                stream,
+                block: chunk.to_vec(),
+                end_headers: chunks.peek().is_none(),
+            }
+            .encode(&mut self.output);
+        }
+    }
+
+    fn window_update(&mut self, stream: u32, increment: u32) {
+        Frame::WindowUpdate { stream, increment }.encode(&mut self.output);
+    }
+
+    /// Envia o que as janelas permitem, um frame por stream a cada rodada
+    fn flush(&mut self) {
+        let mut blocked = 0;
+        while blocked < self.send_queue.len() {
+            let Some(stream) = self.send_queue.pop_front() else {
+                break;
+            };
+            match self.send_chunk(stream) {
+                Progress::Done => blocked = 0,
+                Progress::More => {
+                    self.send_queue.push_back(stream);
+                    blocked = 0;
+                }
+                Progress::Blocked => {
+                    self.send_queue.push_back(stream);
+                    blocked += 1;
+                }
+            }
+        }
+    }
+
+    fn send_chunk(&mut self, stream: u32) -> Progress {
+        let max_frame = self.remote.max_frame_size as i64;
+        let Some(state) = self.streams.get_mut(&stream) else {
+            return Progress::Done;
+        };
+        let window = state.send_window.min(self.send_window).min(max_frame).max(0) as usize;
+        let len = state.pending.len().min(window);
+        if len == 0 && !state.pending.is_empty() {
+            return Progress::Blocked;
+        }
+        let data: Vec<u8> = state.pending.drain(..len).collect();
+        let finished = state.pending.is_empty();
+        let end_stream = finished && state.pending_end && state.trailers.is_none();
+        state.send_window -= len as i64;
+        self.send_window -= len as i64;
+        if !data.is_empty() || end_stream {
+            Frame::Data {
+                stream,
+                data,
+                end_stream,
+                flow_len: len as u32,
+            }
+            .encode(&mut self.output);
+        }
+        if !finished {
+            return Progress::More;
+        }
+        let trailers = state.trailers.take();
+        if trailers.is_some() || state.pending_end {
+            state.local_closed = true;
+        }
+        if let Some(trailers) = trailers {
+            self.write_headers(stream, &trailers, true);
+        }
+        self.maybe_close(stream);
+        Progress::Done
+    }
+
+    fn maybe_close(&mut self, stream: u32) {
+        if self
+            .streams
+            .get(&stream)
+            .is_some_and(|state| state.local_closed && state.remote_closed)
+        {
+            self.streams.remove(&stream);
+        }
+    }
+
+    /// Streams pares são do servidor, ímpares do cliente
+    fn is_peer_initiated(&self, stream: u32) -> bool {
+        (stream % 2 == 1) == (self.role == Role::Server)
+    }
+
+    /// Stream que nunca foi aberto
+    fn is_idle(&self, stream: u32) -> bool {
+        if self.streams.contains_key(&stream) {
+            return false;
+        }
+        if self.is_peer_initiated(stream) {
+            stream > self.last_peer_stream
+        } else {
+            stream >= self.next_stream_id
+        }
+    }
+
+    /// Até o peer confirmar nosso SETTINGS ele pode usar a janela padrão
+    fn recv_initial_window(&self) -> u32 {
+        if self.local_acked {
+            self.local.initial_window_size
+        } else {
+            self.local.initial_window_size.max(DEFAULT_WINDOW)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect()
+    }
+
+    fn request() -> Vec<(String, String)> {
+        headers(&[(":method", "POST"), (":scheme", "http"), (":authority", "localhost"), (":path", "/upload")])
+    }
+
+    /// Troca bytes até nenhum dos lados ter o que enviar
+    fn pump(client: &mut Connection, server: &mut Connection) {
+        while client.has_output() || server.has_output() {
+            server.receive(&client.take_output()).unwrap();
+            client.receive(&server.take_output()).unwrap();
+        }
+    }
+
+    fn events(connection: &mut Connection) -> Vec<Event> {
+        std::iter::from_fn(|| connection.poll_event()).collect()
+    }
+
+    #[test]
+    fn test_request_response() {
+        let mut client = Connection::client(Settings::default());
+        let mut server = Connection::server(Settings::default());
+        let stream = client.send_request(&request(), false).unwrap();
+        client.send_data(stream, b"hello", true).unwrap();
+        pump(&mut client, &mut server);
+
+        assert_eq!(
+            events(&mut server),
+            [
+                Event::Headers {
+                    stream,
+                    headers: request(),
+                    end_stream: false
+                },
+                Event::Data {
+                    stream,
+                    data: b"hello".to_vec(),
+                    end_stream: true
+                },
+            ]
+        );
+
+        server.send_headers(stream, &headers(&[(":status", "200")]), false).unwrap();
+        server.send_data(stream, b"world", false).unwrap();
+        server.send_headers(stream, &headers(&[("grpc-status", "0")]), true).unwrap();
+        assert!(server.send_data(stream, b"late", false).is_err());
+        pump(&mut client, &mut server);
+
+        let received = events(&mut client);
+        assert_eq!(received.len(), 3);
+        assert_eq!(
+            received[2],
+            Event::Headers {
+                stream,
+                headers: headers(&[("grpc-status", "0")]),
+                end_stream: true
+            }
+        );
+        assert_eq!(client.open_streams(), 0);
+        assert_eq!(server.open_streams(), 0);
+    }
+
+    #[test]
+    fn test_flow_control() {
+        let mut client = Connection::client(Settings::default());
+        let mut server = Connection::server(Settings {
+            initial_window_size: 10,
+            ..Settings::default()
+        });
+        pump(&mut client, &mut server);
+
+        // 25 bytes numa janela de 10: o resto espera os WINDOW_UPDATE do servidor
+        let stream = client.send_request(&request(), false).unwrap();
+        client.send_data(stream, &[7; 25], true).unwrap();
+        assert_eq!(client.buffered(stream), 15);
+        pump(&mut client, &mut server);
+
+        let mut body = Vec::new();
+        let mut ended = false;
+        for event in events(&mut server) {
+            if let Event::Data { data, end_stream, .. } = event {
+                assert!(data.len() <= 10);
+                body.extend(data);
+                ended = end_stream;
+            }
+        }
+        assert_eq!(body, [7; 25]);
+        assert!(ended);
+    }
+
+    #[test]
+    fn test_concurrency_limit_and_goaway() {
+        let mut client = Connection::client(Settings::default());
+        let mut server = Connection::server(Settings {
+            max_concurrent_streams: 1,
+            ..Settings::default()
+        });
+
+        // Antes do SETTINGS do servidor o cliente não conhece o limite: o excedente é recusado
+        let first = client.send_request(&request(), false).unwrap();
+        let second = client.send_request(&request(), false).unwrap();
+        pump(&mut client, &mut server);
+        assert_eq!(
+            events(&mut client),
+            [Event::Reset {
+                stream: second,
+                code: ErrorCode::RefusedStream
+            }]
+        );
+        assert!(matches!(client.send_request(&request(), false), Err(H2Error::StreamLimit)));
+
+        server.go_away(ErrorCode::NoError);
+        pump(&mut client, &mut server);
+        assert!(matches!(
+            events(&mut client)[..],
+            [Event::GoAway {
+                code: ErrorCode::NoError,
+                ..
+            }]
+        ));
+        assert!(matches!(client.send_request(&request(), true), Err(H2Error::GoAway(_))));
+
+        // O stream aceito ainda termina normalmente
+        client.send_data(first, b"done", true).unwrap();
+        server.send_headers(first, &headers(&[(":status", "204")]), true).unwrap();
+        pump(&mut client, &mut server);
+        assert_eq!(client.open_streams(), 0);
+    }
+
+    #[test]
+    fn test_connection_errors() {
+        let mut server = Connection::server(Settings::default());
+        server.take_output();
+        let err = server.receive(b"GET / HTTP/1.1\r\n\r\n").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ProtocolError);
+        let (frame, _) = Frame::parse(&server.take_output(), DEFAULT_MAX_FRAME_SIZE).unwrap().unwrap();
+        assert!(matches!(frame, Frame::GoAway { code: ErrorCode::ProtocolError, .. }));
+
+        // PING é respondido; DATA num stream nunca aberto derruba a conexão
+        let mut server = Connection::server(Settings::default());
+        let mut input = PREFACE.to_vec();
+        Frame::Ping { ack: false, data: *b"abcdefgh" }.encode(&mut input);
+        server.receive(&input).unwrap();
+        let output = server.take_output();
+        assert!(output.ends_with(&[0, 0, 8, 6, 1, 0, 0, 0, 0, b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h']));
+
+        let mut data = Vec::new();
+        Frame::Data {
+            stream: 9,
+            data: b"x".to_vec(),
+            end_stream: false,
+            flow_len: 1,
+        }
+        .encode(&mut data);
+        assert_eq!(server.receive(&data).unwrap_err().code(), ErrorCode::ProtocolError);
+    }
+}
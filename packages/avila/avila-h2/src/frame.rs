@@ -0,0 +1,440 @@
+//! Frames HTTP/2 (RFC 9113, seção 6)
+
+use crate::{ErrorCode, H2Error, Result};
+
+pub const HEADER_LEN: usize = 9;
+/// Menor valor permitido de SETTINGS_MAX_FRAME_SIZE (e o padrão)
+pub const DEFAULT_MAX_FRAME_SIZE: u32 = 16_384;
+pub const MAX_FRAME_SIZE_LIMIT: u32 = (1 << 24) - 1;
+pub const MAX_WINDOW_SIZE: u32 = (1 << 31) - 1;
+
+pub mod kind {
+    pub const DATA: u8 = 0x0;
+    pub const HEADERS: u8 = 0x1;
+    pub const PRIORITY: u8 = 0x2;
+    pub const RST_STREAM: u8 = 0x3;
+    pub const SETTINGS: u8 = 0x4;
+    pub const PUSH_PROMISE: u8 = 0x5;
+    pub const PING: u8 = 0x6;
+    pub const GOAWAY: u8 = 0x7;
+    pub const WINDOW_UPDATE: u8 = 0x8;
+    pub const CONTINUATION: u8 = 0x9;
+}
+
+pub mod flags {
+    pub const END_STREAM: u8 = 0x1;
+    pub const ACK: u8 = 0x1;
+    pub const END_HEADERS: u8 = 0x4;
+    pub const PADDED: u8 = 0x8;
+    pub const PRIORITY: u8 = 0x20;
+}
+
+/// Identificadores de SETTINGS
+pub mod setting {
+    pub const HEADER_TABLE_SIZE: u16 = 0x1;
+    pub const ENABLE_PUSH: u16 = 0x2;
+    pub const MAX_CONCURRENT_STREAMS: u16 = 0x3;
+    pub const INITIAL_WINDOW_SIZE: u16 = 0x4;
+    pub const MAX_FRAME_SIZE: u16 = 0x5;
+    pub const MAX_HEADER_LIST_SIZE: u16 = 0x6;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    Data {
+        stream: u32,
+        data: Vec<u8>,
+        end_stream: bool,
+        /// Tamanho do payload com padding, que é o que conta no controle de fluxo
+        flow_len: u32,
+    },
+    Headers {
+        stream: u32,
+        block: Vec<u8>,
+        end_stream: bool,
+        end_headers: bool,
+    },
+    Priority {
+        stream: u32,
+    },
+    RstStream {
+        stream: u32,
+        code: ErrorCode,
+    },
+    Settings {
+        ack: bool,
+        params: Vec<(u16, u32)>,
+    },
+    PushPromise {
+        stream: u32,
+        promised: u32,
+    },
+    Ping {
+        ack: bool,
+        data: [u8; 8],
+    },
+    GoAway {
+        last_stream: u32,
+        code: ErrorCode,
+        debug: Vec<u8>,
+    },
+    WindowUpdate {
+        stream: u32,
+        increment: u32,
+    },
+    Continuation {
+        stream: u32,
+        block: Vec<u8>,
+        end_headers: bool,
+    },
+    /// Tipo desconhecido; deve ser ignorado
+    Unknown {
+        kind: u8,
+        stream: u32,
+    },
+}
+
+impl Frame {
+    pub fn stream(&self) -> u32 {
+        match self {
+            Frame::Data { stream, .. }
+            | Frame::Headers { stream, .. }
+            | Frame::Priority { stream }
+            | Frame::RstStream { stream, .. }
+            | Frame::PushPromise { stream, .. }
+            | Frame::WindowUpdate { stream, .. }
+            | Frame::Continuation { stream, .. }
+            | Frame::Unknown { stream, .. } => *stream,
+            Frame::Settings { .. } | Frame::Ping { .. } | Frame::GoAway { .. } => 0,
+        }
+    }
+
+    /// Lê um frame do início de `buf`; `None` se ainda não chegou inteiro
+    ///
+    /// Retorna o frame e quantos bytes ele ocupa.
+    pub fn parse(buf: &[u8], max_frame_size: u32) -> Result<Option<(Frame, usize)>> {
+        if buf.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let len = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+        if len > max_frame_size {
+            return Err(H2Error::protocol(
+                ErrorCode::FrameSizeError,
+                format!("frame of {len} bytes exceeds the maximum of {max_frame_size}"),
+            ));
+        }
+        let total = HEADER_LEN + len as usize;
+        if buf.len() < total {
+            return Ok(None);
+        }
+        let kind = buf[3];
+        let flag = buf[4];
+        let stream = u32::from_be_bytes([buf[5], buf[6], buf[7], buf[8]]) & MAX_WINDOW_SIZE;
+        let payload = &buf[HEADER_LEN..total];
+
+        let frame = match kind {
+            kind::DATA => {
+                require_stream(stream, "DATA")?;
+                let data = strip_padding(payload, flag)?;
+                Frame::Data {
+                    stream,
+                    data: data.to_vec(),
+                    end_stream: flag & flags::END_STREAM != 0,
+                    flow_len: len,
+                }
+            }
+            kind::HEADERS => {
+                require_stream(stream, "HEADERS")?;
+                let mut block = strip_padding(payload, flag)?;
+                if flag & flags::PRIORITY != 0 {
+                    if block.len() < 5 {
+                        return Err(frame_size("HEADERS priority"));
+                    }
+                    check_dependency(stream, &block[..4])?;
+                    block = &block[5..];
+                }
+                Frame::Headers {
+                    stream,
+                    block: block.to_vec(),
+                    end_stream: flag & flags::END_STREAM != 0,
+                    end_headers: flag & flags::END_HEADERS != 0,
+                }
+            }
+            kind::PRIORITY => {
+                require_stream(stream, "PRIORITY")?;
+                if payload.len() != 5 {
+                    return Err(frame_size("PRIORITY"));
+                }
+                check_dependency(stream, &payload[..4])?;
+                Frame::Priority { stream }
+            }
+            kind::RST_STREAM => {
+                require_stream(stream, "RST_STREAM")?;
+                if payload.len() != 4 {
+                    return Err(frame_size("RST_STREAM"));
+                }
+                Frame::RstStream {
+                    stream,
+                    code: read_u32(payload).into(),
+                }
+            }
+            kind::SETTINGS => {
+                require_connection(stream, "SETTINGS")?;
+                let ack = flag & flags::ACK != 0;
+                if !payload.len().is_multiple_of(6) || (ack && !payload.is_empty()) {
+                    return Err(frame_size("SETTINGS"));
+                }
+                let params = payload
+                    .chunks_exact(6)
+                    .map(|p| (u16::from_be_bytes([p[0], p[1]]), read_u32(&p[2..])))
+                    .collect();
+                Frame::Settings { ack, params }
+            }
+            kind::PUSH_PROMISE => {
+                require_stream(stream, "PUSH_PROMISE")?;
+                let block = strip_padding(payload, flag)?;
+                if block.len() < 4 {
+                    return Err(frame_size("PUSH_PROMISE"));
+                }
+                Frame::PushPromise {
+                    stream,
+                    promised: read_u32(block) & MAX_WINDOW_SIZE,
+                }
+            }
+            kind::PING => {
+                require_connection(stream, "PING")?;
+                let data: [u8; 8] = payload.try_into().map_err(|_| frame_size("PING"))?;
+                Frame::Ping {
+                    ack: flag & flags::ACK != 0,
+                    data,
+                }
+            }
+            kind::GOAWAY => {
+                require_connection(stream, "GOAWAY")?;
+                if payload.len() < 8 {
+                    return Err(frame_size("GOAWAY"));
+                }
+                Frame::GoAway {
+                    last_stream: read_u32(payload) & MAX_WINDOW_SIZE,
+                    code: read_u32(&payload[4..]).into(),
+                    debug: payload[8..].to_vec(),
+                }
+            }
+            kind::WINDOW_UPDATE => {
+                if payload.len() != 4 {
+                    return Err(frame_size("WINDOW_UPDATE"));
+                }
+                Frame::WindowUpdate {
+                    stream,
+                    increment: read_u32(payload) & MAX_WINDOW_SIZE,
+                }
+            }
+            kind::CONTINUATION => {
+                require_stream(stream, "CONTINUATION")?;
+                Frame::Continuation {
+                    stream,
+                    block: payload.to_vec(),
+                    end_headers: flag & flags::END_HEADERS != 0,
+                }
+            }
+            other => Frame::Unknown { kind: other, stream },
+        };
+        Ok(Some((frame, total)))
+    }
+
+    /// Serializa o frame no fim de `out` (sem padding)
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Frame::Data { stream, data, end_stream, .. } => {
+                let flag = if *end_stream { flags::END_STREAM } else { 0 };
+                write_header(out, data.len(), kind::DATA, flag, *stream);
+                out.extend_from_slice(data);
+            }
+            Frame::Headers {
+                stream,
+                block,
+                end_stream,
+                end_headers,
+            } => {
+                let mut flag = 0;
+                if *end_stream {
+                    flag |= flags::END_STREAM;
+                }
+                if *end_headers {
+                    flag |= flags::END_HEADERS;
+                }
+                write_header(out, block.len(), kind::HEADERS, flag, *stream);
+                out.extend_from_slice(block);
+            }
+            Frame::Priority { stream } => {
+                write_header(out, 5, kind::PRIORITY, 0, *stream);
+                out.extend_from_slice(&[0, 0, 0, 0, 15]);
+            }
+            Frame::RstStream { stream, code } => {
+                write_header(out, 4, kind::RST_STREAM, 0, *stream);
+                out.extend_from_slice(&u32::from(*code).to_be_bytes());
+            }
+            Frame::Settings { ack, params } => {
+                let flag = if *ack { flags::ACK } else { 0 };
+                write_header(out, params.len() * 6, kind::SETTINGS, flag, 0);
+                for (id, value) in params {
+                    out.extend_from_slice(&id.to_be_bytes());
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            Frame::PushPromise { stream, promised } => {
+                write_header(out, 4, kind::PUSH_PROMISE, flags::END_HEADERS, *stream);
+                out.extend_from_slice(&promised.to_be_bytes());
+            }
+            Frame::Ping { ack, data } => {
+                let flag = if *ack { flags::ACK } else { 0 };
+                write_header(out, 8, kind::PING, flag, 0);
+                out.extend_from_slice(data);
+            }
+            Frame::GoAway { last_stream, code, debug } => {
+                write_header(out, 8 + debug.len(), kind::GOAWAY, 0, 0);
+                out.extend_from_slice(&last_stream.to_be_bytes());
+                out.extend_from_slice(&u32::from(*code).to_be_bytes());
+                out.extend_from_slice(debug);
+            }
+            Frame::WindowUpdate { stream, increment } => {
+                write_header(out, 4, kind::WINDOW_UPDATE, 0, *stream);
+                out.extend_from_slice(&increment.to_be_bytes());
+            }
+            Frame::Continuation {
+                stream,
+                block,
+                end_headers,
+            } => {
+                let flag = if *end_headers { flags::END_HEADERS } else { 0 };
+                write_header(out, block.len(), kind::CONTINUATION, flag, *stream);
+                out.extend_from_slice(block);
+            }
+            Frame::Unknown { kind, stream } => write_header(out, 0, *kind, 0, *stream),
+        }
+    }
+}
+
+fn write_header(out: &mut Vec<u8>, len: usize, kind: u8, flag: u8, stream: u32) {
+    out.extend_from_slice(&(len as u32).to_be_bytes()[1..]);
+    out.push(kind);
+    out.push(flag);
+    out.extend_from_slice(&stream.to_be_bytes());
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn strip_padding(payload: &[u8], flag: u8) -> Result<&[u8]> {
+    if flag & flags::PADDED == 0 {
+        return Ok(payload);
+    }
+    let Some((&pad, rest)) = payload.split_first() else {
+        return Err(frame_size("padded frame"));
+    };
+    if pad as usize > rest.len() {
+        return Err(H2Error::protocol(ErrorCode::ProtocolError, "padding exceeds frame payload"));
+    }
+    Ok(&rest[..rest.len() - pad as usize])
+}
+
+/// Um stream não pode depender de si mesmo
+fn check_dependency(stream: u32, dependency: &[u8]) -> Result<()> {
+    if read_u32(dependency) & MAX_WINDOW_SIZE == stream {
+        return Err(H2Error::protocol(ErrorCode::ProtocolError, format!("stream {stream} depends on itself")));
+    }
+    Ok(())
+}
+
+fn require_stream(stream: u32, name: &str) -> Result<()> {
+    if stream == 0 {
+        return Err(H2Error::protocol(ErrorCode::ProtocolError, format!("{name} frame on stream 0")));
+    }
+    Ok(())
+}
+
+fn require_connection(stream: u32, name: &str) -> Result<()> {
+    if stream != 0 {
+        return Err(H2Error::protocol(ErrorCode::ProtocolError, format!("{name} frame on stream {stream}")));
+    }
+    Ok(())
+}
+
+fn frame_size(name: &str) -> H2Error {
+    H2Error::protocol(ErrorCode::FrameSizeError, format!("invalid {name} frame length"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(frame: Frame) {
+        let mut out = Vec::new();
+        frame.encode(&mut out);
+        let (parsed, used) = Frame::parse(&out, DEFAULT_MAX_FRAME_SIZE).unwrap().unwrap();
+        assert_eq!(used, out.len());
+        assert_eq!(parsed, frame);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip(Frame::Data {
+            stream: 1,
+            data: b"hello".to_vec(),
+            end_stream: true,
+            flow_len: 5,
+        });
+        roundtrip(Frame::Headers {
+            stream: 3,
+            block: vec![0x82, 0x86],
+            end_stream: false,
+            end_headers: true,
+        });
+        roundtrip(Frame::Settings {
+            ack: false,
+            params: vec![(setting::ENABLE_PUSH, 0), (setting::INITIAL_WINDOW_SIZE, 1 << 20)],
+        });
+        roundtrip(Frame::Ping { ack: true, data: *b"12345678" });
+        roundtrip(Frame::GoAway {
+            last_stream: 7,
+            code: ErrorCode::EnhanceYourCalm,
+            debug: b"slow down".to_vec(),
+        });
+        roundtrip(Frame::WindowUpdate { stream: 0, increment: 1000 });
+        roundtrip(Frame::RstStream { stream: 5, code: ErrorCode::Cancel });
+    }
+
+    #[test]
+    fn test_padding_and_errors() {
+        // DATA com 2 bytes de padding: conta 6 bytes no controle de fluxo
+        let mut buf = vec![0, 0, 6, kind::DATA, flags::PADDED, 0, 0, 0, 1, 2, b'h', b'i', b'!', 0, 0];
+        let (frame, used) = Frame::parse(&buf, DEFAULT_MAX_FRAME_SIZE).unwrap().unwrap();
+        assert_eq!(used, 15);
+        assert_eq!(
+            frame,
+            Frame::Data {
+                stream: 1,
+                data: b"hi!".to_vec(),
+                end_stream: false,
+                flow_len: 6,
+            }
+        );
+
+        // Incompleto
+        assert!(Frame::parse(&buf[..10], DEFAULT_MAX_FRAME_SIZE).unwrap().is_none());
+
+        // Padding maior que o payload
+        buf[9] = 10;
+        assert!(Frame::parse(&buf, DEFAULT_MAX_FRAME_SIZE).is_err());
+
+        // Frame grande demais
+        let big = [0, 0x40, 0x01, kind::DATA, 0, 0, 0, 0, 1];
+        let err = Frame::parse(&big, DEFAULT_MAX_FRAME_SIZE).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::FrameSizeError);
+
+        // SETTINGS fora do stream 0
+        let settings = [0, 0, 0, kind::SETTINGS, 0, 0, 0, 0, 1];
+        assert_eq!(Frame::parse(&settings, DEFAULT_MAX_FRAME_SIZE).unwrap_err().code(), ErrorCode::ProtocolError);
+    }
+}
@@ -0,0 +1,461 @@
+//! Compressão de cabeçalhos HPACK (RFC 7541)
+
+use crate::{huffman, H2Error, Result};
+use std::collections::VecDeque;
+
+/// Tamanho padrão da tabela dinâmica (SETTINGS_HEADER_TABLE_SIZE)
+pub const DEFAULT_TABLE_SIZE: usize = 4096;
+
+/// Custo fixo de cada entrada da tabela dinâmica
+const ENTRY_OVERHEAD: usize = 32;
+
+const STATIC_TABLE: [(&str, &str); 61] = [
+    (":authority", ""),
+    (":method", "GET"),
+    (":method", "POST"),
+    (":path", "/"),
+    (":path", "/index.html"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "200"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "304"),
+    (":status", "400"),
+    (":status", "404"),
+    (":status", "500"),
+    ("accept-charset", ""),
+    ("accept-encoding", "gzip, deflate"),
+    ("accept-language", ""),
+    ("accept-ranges", ""),
+    ("accept", ""),
+    ("access-control-allow-origin", ""),
+    ("age", ""),
+    ("allow", ""),
+    ("authorization", ""),
+    ("cache-control", ""),
+    ("content-disposition", ""),
+    ("content-encoding", ""),
+    ("content-language", ""),
+    ("content-length", ""),
+    ("content-location", ""),
+    ("content-range", ""),
+    ("content-type", ""),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("expect", ""),
+    ("expires", ""),
+    ("from", ""),
+    ("host", ""),
+    ("if-match", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("if-range", ""),
+    ("if-unmodified-since", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("max-forwards", ""),
+    ("proxy-authenticate", ""),
+    ("proxy-authorization", ""),
+    ("range", ""),
+    ("referer", ""),
+    ("refresh", ""),
+    ("retry-after", ""),
+    ("server", ""),
+    ("set-cookie", ""),
+    ("strict-transport-security", ""),
+    ("transfer-encoding", ""),
+    ("user-agent", ""),
+    ("vary", ""),
+    ("via", ""),
+    ("www-authenticate", ""),
+];
+
+/// Cabeçalhos que nunca entram na tabela dinâmica (nem em intermediários)
+const SENSITIVE: [&str; 3] = ["authorization", "proxy-authorization", "set-cookie"];
+
+// ============================================================================
+// TABELA DINÂMICA
+// ============================================================================
+
+#[derive(Debug)]
+struct DynamicTable {
+    /// Entrada mais recente primeiro
+    entries: VecDeque<(String, String)>,
+    size: usize,
+    max_size: usize,
+}
+
+impl DynamicTable {
+    fn new(max_size: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            size: 0,
+            max_size,
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<(&str, &str)> {
+        if index == 0 {
+            return None;
+        }
+        if index <= STATIC_TABLE.len() {
+            return Some(STATIC_TABLE[index - 1]);
+        }
+        self.entries
+            .get(index - STATIC_TABLE.len() - 1)
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    fn insert(&mut self, name: String, value: String) {
+        let size = name.len() + value.len() + ENTRY_OVERHEAD;
+        self.evict(self.max_size.saturating_sub(size));
+        // Uma entrada maior que a tabela só a esvazia
+        if size <= self.max_size {
+            self.size += size;
+            self.entries.push_front((name, value));
+        }
+    }
+
+    fn resize(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        self.evict(max_size);
+    }
+
+    fn evict(&mut self, target: usize) {
+        while self.size > target {
+            let Some((name, value)) = self.entries.pop_back() else {
+                break;
+            };
+            self.size -= name.len() + value.len() + ENTRY_OVERHEAD;
+        }
+    }
+
+    /// Procura `(nome, valor)`; retorna o índice e se o valor também bate
+    fn find(&self, name: &str, value: &str) -> Option<(usize, bool)> {
+        let mut by_name = None;
+        for (i, (n, v)) in STATIC_TABLE.iter().enumerate() {
+            if *n == name {
+                if *v == value {
+                    return Some((i + 1, true));
+                }
+                by_name.get_or_insert(i + 1);
+            }
+        }
+        for (i, (n, v)) in self.entries.iter().enumerate() {
+            if n == name {
+                let index = STATIC_TABLE.len() + i + 1;
+                if v == value {
+                    return Some((index, true));
+                }
+                by_name.get_or_insert(index);
+            }
+        }
+        by_name.map(|index| (index, false))
+    }
+}
+
+// ============================================================================
+// DECODIFICADOR
+// ============================================================================
+
+#[derive(Debug)]
+pub struct Decoder {
+    table: DynamicTable,
+    /// Limite anunciado ao peer em SETTINGS_HEADER_TABLE_SIZE
+    max_table_size: usize,
+    /// Limite para a soma dos cabeçalhos de um bloco (como SETTINGS_MAX_HEADER_LIST_SIZE)
+    max_header_list_size: usize,
+}
+
+impl Decoder {
+    pub fn new(max_table_size: usize, max_header_list_size: usize) -> Self {
+        Self {
+            table: DynamicTable::new(max_table_size),
+            max_table_size,
+            max_header_list_size,
+        }
+    }
+
+    /// Decodifica um bloco de cabeçalhos completo
+    pub fn decode(&mut self, mut block: &[u8]) -> Result<Vec<(String, String)>> {
+        let mut headers = Vec::new();
+        let mut list_size = 0;
+        let mut first = true;
+        while let Some(&byte) = block.first() {
+            if byte & 0x80 != 0 {
+                // Campo indexado
+                let index = decode_int(&mut block, 7)?;
+                let (name, value) = self
+                    .table
+                    .get(index)
+                    .ok_or_else(|| H2Error::Compression(format!("invalid table index {index}")))?;
+                headers.push((name.to_string(), value.to_string()));
+            } else if byte & 0xe0 == 0x20 {
+                // Atualização do tamanho da tabela: só no início do bloco
+                if !first {
+                    return Err(H2Error::Compression("table size update after header field".into()));
+                }
+                let size = decode_int(&mut block, 5)?;
+                if size > self.max_table_size {
+                    return Err(H2Error::Compression(format!("table size update to {size} exceeds the limit")));
+                }
+                self.table.resize(size);
+                continue;
+            } else {
+                // Literal: com indexação (01), sem indexação (0000) ou nunca indexado (0001)
+                let indexing = byte & 0x40 != 0;
+                let index = decode_int(&mut block, if indexing { 6 } else { 4 })?;
+                let name = if index == 0 {
+                    decode_string(&mut block)?
+                } else {
+                    self.table
+                        .get(index)
+                        .ok_or_else(|| H2Error::Compression(format!("invalid table index {index}")))?
+                        .0
+                        .to_string()
+                };
+                let value = decode_string(&mut block)?;
+                if indexing {
+                    self.table.insert(name.clone(), value.clone());
+                }
+                headers.push((name, value));
+            }
+            first = false;
+            let (name, value) = headers.last().expect("header just pushed");
+            list_size += name.len() + value.len() + ENTRY_OVERHEAD;
+            if list_size > self.max_header_list_size {
+                return Err(H2Error::Compression("header list too large".into()));
+            }
+        }
+        Ok(headers)
+    }
+}
+
+// ============================================================================
+// CODIFICADOR
+// ============================================================================
+
+#[derive(Debug)]
+pub struct Encoder {
+    table: DynamicTable,
+    /// Redução de tamanho a sinalizar no próximo bloco
+    pending_resize: Option<usize>,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self {
+            table: DynamicTable::new(DEFAULT_TABLE_SIZE),
+            pending_resize: None,
+        }
+    }
+
+    /// Aplica o SETTINGS_HEADER_TABLE_SIZE do peer (sem crescer além do padrão)
+    pub fn set_max_table_size(&mut self, size: usize) {
+        let size = size.min(DEFAULT_TABLE_SIZE);
+        if size != self.table.max_size {
+            self.table.resize(size);
+            self.pending_resize = Some(size);
+        }
+    }
+
+    /// Codifica um bloco; nomes devem estar em minúsculas
+    pub fn encode<'a, I>(&mut self, headers: I, out: &mut Vec<u8>)
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        if let Some(size) = self.pending_resize.take() {
+            encode_int(size, 5, 0x20, out);
+        }
+        for (name, value) in headers {
+            let sensitive = SENSITIVE.contains(&name);
+            match self.table.find(name, value) {
+                Some((index, true)) if !sensitive => encode_int(index, 7, 0x80, out),
+                found => {
+                    let name_index = found.map(|(index, _)| index).unwrap_or(0);
+                    // Valores grandes expulsariam a tabela inteira
+                    let indexed = !sensitive && name.len() + value.len() + ENTRY_OVERHEAD <= self.table.max_size / 2;
+                    if sensitive {
+                        encode_int(name_index, 4, 0x10, out);
+                    } else if indexed {
+                        encode_int(name_index, 6, 0x40, out);
+                    } else {
+                        encode_int(name_index, 4, 0x00, out);
+                    }
+                    if name_index == 0 {
+                        encode_string(name.as_bytes(), out);
+                    }
+                    encode_string(value.as_bytes(), out);
+                    if indexed {
+                        self.table.insert(name.to_string(), value.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// PRIMITIVAS
+// ============================================================================
+
+fn encode_int(value: usize, prefix: u8, first: u8, out: &mut Vec<u8>) {
+    let max = (1usize << prefix) - 1;
+    if value < max {
+        out.push(first | value as u8);
+        return;
+    }
+    out.push(first | max as u8);
+    let mut rest = value - max;
+    while rest >= 0x80 {
+        out.push((rest & 0x7f) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.push(rest as u8);
+}
+
+fn decode_int(block: &mut &[u8], prefix: u8) -> Result<usize> {
+    let max = (1usize << prefix) - 1;
+    let (&first, mut rest) = block.split_first().ok_or_else(truncated)?;
+    let mut value = first as usize & max;
+    if value == max {
+        let mut shift = 0;
+        loop {
+            let (&byte, tail) = rest.split_first().ok_or_else(truncated)?;
+            rest = tail;
+            if shift > 28 {
+                return Err(H2Error::Compression("integer overflow".into()));
+            }
+            value += ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+    *block = rest;
+    Ok(value)
+}
+
+fn encode_string(data: &[u8], out: &mut Vec<u8>) {
+    let huffman_len = huffman::encoded_len(data);
+    if huffman_len < data.len() {
+        encode_int(huffman_len, 7, 0x80, out);
+        huffman::encode(data, out);
+    } else {
+        encode_int(data.len(), 7, 0x00, out);
+        out.extend_from_slice(data);
+    }
+}
+
+fn decode_string(block: &mut &[u8]) -> Result<String> {
+    let huffman = block.first().ok_or_else(truncated)? & 0x80 != 0;
+    let len = decode_int(block, 7)?;
+    if block.len() < len {
+        return Err(truncated());
+    }
+    let (raw, rest) = block.split_at(len);
+    *block = rest;
+    let bytes = if huffman { huffman::decode(raw)? } else { raw.to_vec() };
+    // Cabeçalhos com bytes fora de UTF-8 (obs-text) são raros; substituímos
+    Ok(String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
+fn truncated() -> H2Error {
+    H2Error::Compression("truncated header block".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn pairs(headers: &[(String, String)]) -> Vec<(&str, &str)> {
+        headers.iter().map(|(n, v)| (n.as_str(), v.as_str())).collect()
+    }
+
+    #[test]
+    fn test_integer() {
+        // RFC 7541, C.1
+        let mut out = Vec::new();
+        encode_int(1337, 5, 0, &mut out);
+        assert_eq!(out, [31, 154, 10]);
+        assert_eq!(decode_int(&mut out.as_slice(), 5).unwrap(), 1337);
+        assert!(decode_int(&mut [0x1f, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f].as_slice(), 5).is_err());
+    }
+
+    #[test]
+    fn test_rfc_requests_with_huffman() {
+        // RFC 7541, C.4: três requisições na mesma conexão
+        let mut decoder = Decoder::new(DEFAULT_TABLE_SIZE, 64 * 1024);
+        let first = decoder.decode(&hex("8286 8441 8cf1 e3c2 e5f2 3a6b a0ab 90f4 ff")).unwrap();
+        assert_eq!(
+            pairs(&first),
+            [(":method", "GET"), (":scheme", "http"), (":path", "/"), (":authority", "www.example.com")]
+        );
+        let second = decoder.decode(&hex("8286 84be 5886 a8eb 1064 9cbf")).unwrap();
+        assert_eq!(second[3], (":authority".into(), "www.example.com".into()));
+        assert_eq!(second[4], ("cache-control".into(), "no-cache".into()));
+        let third = decoder
+            .decode(&hex("8287 85bf 4088 25a8 49e9 5ba9 7d7f 8925 a849 e95b b8e8 b4bf"))
+            .unwrap();
+        assert_eq!(third[4], ("custom-key".into(), "custom-value".into()));
+        assert_eq!(decoder.table.size, 164);
+    }
+
+    #[test]
+    fn test_encoder_roundtrip() {
+        let mut encoder = Encoder::new();
+        let mut decoder = Decoder::new(DEFAULT_TABLE_SIZE, 64 * 1024);
+        let headers = [
+            (":status", "200"),
+            ("content-type", "text/event-stream"),
+            ("authorization", "Bearer secret"),
+            ("x-request-id", "abc"),
+        ];
+        let mut first = Vec::new();
+        encoder.encode(headers, &mut first);
+        assert_eq!(pairs(&decoder.decode(&first).unwrap()), headers);
+
+        // Repetidos vêm da tabela dinâmica; o segredo continua literal
+        let mut second = Vec::new();
+        encoder.encode(headers, &mut second);
+        assert!(second.len() < first.len());
+        assert_eq!(pairs(&decoder.decode(&second).unwrap()), headers);
+        assert!(!decoder.table.entries.iter().any(|(name, _)| name == "authorization"));
+
+        // Redução pedida pelo peer é sinalizada no próximo bloco
+        encoder.set_max_table_size(0);
+        let mut third = Vec::new();
+        encoder.encode(headers, &mut third);
+        assert_eq!(third[0], 0x20);
+        assert_eq!(pairs(&decoder.decode(&third).unwrap()), headers);
+        assert!(decoder.table.entries.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_blocks() {
+        let mut decoder = Decoder::new(DEFAULT_TABLE_SIZE, 100);
+        assert!(decoder.decode(&[0x80]).is_err());
+        assert!(decoder.decode(&[0xff, 0x00]).is_err());
+        assert!(decoder.decode(&[0x82, 0x3f, 0xe1, 0x1f]).is_err());
+        assert!(decoder.decode(&[0x40, 0x03, b'a']).is_err());
+        let mut big = vec![0x40, 0x7f, 0x00];
+        big.extend_from_slice(&[b'a'; 127]);
+        big.push(0x00);
+        assert!(decoder.decode(&big).is_err());
+    }
+}
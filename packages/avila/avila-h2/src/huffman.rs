@@ -0,0 +1,154 @@
+//! Código de Huffman do HPACK (RFC 7541, Apêndice B)
+//!
+//! O código é canônico: basta o comprimento de cada símbolo para reconstruir
+//! os códigos, atribuídos em ordem de (comprimento, símbolo).
+
+use crate::{H2Error, Result};
+
+const EOS: usize = 256;
+const MAX_LEN: usize = 30;
+
+/// Comprimento em bits do código de cada símbolo (0..=255 e EOS)
+const LENGTHS: [u8; 257] = [
+    13, 23, 28, 28, 28, 28, 28, 28, 28, 24, 30, 28, 28, 30, 28, 28,
+    28, 28, 28, 28, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    6, 10, 10, 12, 13, 6, 8, 11, 10, 10, 8, 11, 8, 6, 6, 6,
+    5, 5, 5, 6, 6, 6, 6, 6, 6, 6, 7, 8, 15, 6, 12, 10,
+    13, 6, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+    7, 7, 7, 7, 7, 7, 7, 7, 8, 7, 8, 13, 19, 13, 14, 6,
+    15, 5, 6, 5, 6, 5, 6, 6, 6, 5, 7, 7, 6, 6, 6, 5,
+    6, 7, 6, 5, 5, 6, 7, 7, 7, 7, 7, 15, 11, 14, 13, 28,
+    20, 22, 20, 20, 22, 22, 22, 23, 22, 23, 23, 23, 23, 23, 24, 23,
+    24, 24, 22, 23, 24, 23, 23, 23, 23, 21, 22, 23, 22, 23, 23, 24,
+    22, 21, 20, 22, 22, 23, 23, 21, 23, 22, 22, 24, 21, 22, 23, 23,
+    21, 21, 22, 21, 23, 22, 23, 23, 20, 22, 22, 22, 23, 22, 22, 23,
+    26, 26, 20, 19, 22, 23, 22, 25, 26, 26, 26, 27, 27, 26, 24, 25,
+    19, 21, 26, 27, 27, 26, 27, 24, 21, 21, 26, 26, 28, 27, 27, 27,
+    20, 24, 20, 21, 22, 21, 21, 23, 22, 22, 25, 25, 24, 24, 26, 23,
+    26, 27, 26, 26, 27, 27, 27, 27, 27, 28, 27, 27, 27, 27, 27, 26,
+    30,
+];
+
+struct Table {
+    codes: [u32; 257],
+    /// Primeiro código de cada comprimento
+    first_code: [u32; MAX_LEN + 1],
+    /// Quantos símbolos têm cada comprimento
+    count: [u16; MAX_LEN + 1],
+    /// Posição em `symbols` do primeiro símbolo de cada comprimento
+    first_index: [u16; MAX_LEN + 1],
+    /// Símbolos ordenados por (comprimento, símbolo)
+    symbols: [u16; 257],
+}
+
+const TABLE: Table = build();
+
+const fn build() -> Table {
+    let mut table = Table {
+        codes: [0; 257],
+        first_code: [0; MAX_LEN + 1],
+        count: [0; MAX_LEN + 1],
+        first_index: [0; MAX_LEN + 1],
+        symbols: [0; 257],
+    };
+    let mut code = 0u32;
+    let mut index = 0u16;
+    let mut len = 1;
+    while len <= MAX_LEN {
+        table.first_code[len] = code;
+        table.first_index[len] = index;
+        let mut symbol = 0;
+        while symbol < 257 {
+            if LENGTHS[symbol] as usize == len {
+                table.codes[symbol] = code;
+                table.symbols[index as usize] = symbol as u16;
+                table.count[len] += 1;
+                code += 1;
+                index += 1;
+            }
+            symbol += 1;
+        }
+        code <<= 1;
+        len += 1;
+    }
+    table
+}
+
+/// Tamanho em bytes de `data` codificado
+pub fn encoded_len(data: &[u8]) -> usize {
+    let bits: usize = data.iter().map(|&b| LENGTHS[b as usize] as usize).sum();
+    bits.div_ceil(8)
+}
+
+pub fn encode(data: &[u8], out: &mut Vec<u8>) {
+    let mut acc = 0u64;
+    let mut bits = 0u32;
+    for &byte in data {
+        let len = LENGTHS[byte as usize] as u32;
+        acc = (acc << len) | TABLE.codes[byte as usize] as u64;
+        bits += len;
+        while bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if bits > 0 {
+        // Completa com o prefixo do EOS (só bits 1)
+        let pad = 8 - bits;
+        out.push(((acc << pad) as u8) | ((1u8 << pad) - 1));
+    }
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 8 / 5);
+    let mut code = 0u32;
+    let mut len = 0usize;
+    for &byte in data {
+        for shift in (0..8).rev() {
+            code = (code << 1) | ((byte >> shift) & 1) as u32;
+            len += 1;
+            if len > MAX_LEN {
+                return Err(H2Error::Compression("invalid Huffman code".into()));
+            }
+            let offset = code.wrapping_sub(TABLE.first_code[len]);
+            if code >= TABLE.first_code[len] && offset < TABLE.count[len] as u32 {
+                let symbol = TABLE.symbols[TABLE.first_index[len] as usize + offset as usize] as usize;
+                if symbol == EOS {
+                    return Err(H2Error::Compression("EOS symbol in Huffman string".into()));
+                }
+                out.push(symbol as u8);
+                code = 0;
+                len = 0;
+            }
+        }
+    }
+    // O padding tem no máximo 7 bits e é o prefixo do EOS
+    if len > 7 || code != (1 << len) - 1 {
+        return Err(H2Error::Compression("invalid Huffman padding".into()));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc_examples() {
+        // RFC 7541, C.4.1
+        let mut out = Vec::new();
+        encode(b"www.example.com", &mut out);
+        assert_eq!(out, [0xf1, 0xe3, 0xc2, 0xe5, 0xf2, 0x3a, 0x6b, 0xa0, 0xab, 0x90, 0xf4, 0xff]);
+        assert_eq!(encoded_len(b"www.example.com"), out.len());
+        assert_eq!(decode(&out).unwrap(), b"www.example.com");
+
+        let all: Vec<u8> = (0..=255).collect();
+        let mut out = Vec::new();
+        encode(&all, &mut out);
+        assert_eq!(decode(&out).unwrap(), all);
+
+        // Padding longo demais ou com bits 0
+        assert!(decode(&[0xf1, 0xff]).is_err());
+        assert!(decode(&[0x00]).is_err());
+    }
+}
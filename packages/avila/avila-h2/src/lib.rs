@@ -0,0 +1,170 @@
+//! # avila-h2 - HTTP/2
+//!
+//! Camada HTTP/2 (RFC 9113) sem I/O, compartilhada pelo cliente `avila-http`
+//! e pelo servidor do `avila-webframework`:
+//! - Frames com padding, prioridade (ignorada) e CONTINUATION
+//! - HPACK (RFC 7541) com tabela dinâmica e Huffman
+//! - Multiplexação de streams com limite de concorrência
+//! - Controle de fluxo por stream e por conexão, com WINDOW_UPDATE automático
+//! - SETTINGS, PING e GOAWAY tratados internamente
+//!
+//! A [`Connection`] é alimentada com os bytes lidos do socket
+//! ([`Connection::receive`]), entrega [`Event`]s e acumula os bytes a escrever
+//! ([`Connection::take_output`]); quem a usa decide como fazer o I/O.
+//!
+//! Fora do escopo: server push (sempre desabilitado) e priorização.
+//!
+//! ```
+//! use avila_h2::{Connection, Event, Settings};
+//!
+//! let mut client = Connection::client(Settings::default());
+//! let mut server = Connection::server(Settings::default());
+//!
+//! let headers = vec![
+//!     (":method".to_string(), "GET".to_string()),
+//!     (":scheme".to_string(), "https".to_string()),
+//!     (":authority".to_string(), "example.com".to_string()),
+//!     (":path".to_string(), "/".to_string()),
+//! ];
+//! let stream = client.send_request(&headers, true)?;
+//! server.receive(&client.take_output())?;
+//! match server.poll_event() {
+//!     Some(Event::Headers { stream: id, end_stream, .. }) => {
+//!         assert_eq!(id, stream);
+//!         assert!(end_stream);
+//!     }
+//!     other => panic!("{other:?}"),
+//! }
+//! # Ok::<(), avila_h2::H2Error>(())
+//! ```
+
+mod connection;
+pub mod frame;
+pub mod hpack;
+mod huffman;
+
+pub use connection::{Connection, Event, Role, Settings};
+
+pub type Result<T> = std::result::Result<T, H2Error>;
+
+/// Prefácio que o cliente envia antes do primeiro frame
+pub const PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Identificador de protocolo negociado via ALPN
+pub const ALPN: &str = "h2";
+
+// ============================================================================
+// CÓDIGOS DE ERRO
+// ============================================================================
+
+/// Códigos de erro de RST_STREAM e GOAWAY
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    NoError,
+    ProtocolError,
+    InternalError,
+    FlowControlError,
+    SettingsTimeout,
+    StreamClosed,
+    FrameSizeError,
+    RefusedStream,
+    Cancel,
+    CompressionError,
+    ConnectError,
+    EnhanceYourCalm,
+    InadequateSecurity,
+    Http11Required,
+    /// Código desconhecido; não tem semântica especial
+    Unknown(u32),
+}
+
+impl From<u32> for ErrorCode {
+    fn from(code: u32) -> Self {
+        match code {
+            0x0 => ErrorCode::NoError,
+            0x1 => ErrorCode::ProtocolError,
+            0x2 => ErrorCode::InternalError,
+            0x3 => ErrorCode::FlowControlError,
+            0x4 => ErrorCode::SettingsTimeout,
+            0x5 => ErrorCode::StreamClosed,
+            0x6 => ErrorCode::FrameSizeError,
+            0x7 => ErrorCode::RefusedStream,
+            0x8 => ErrorCode::Cancel,
+            0x9 => ErrorCode::CompressionError,
+            0xa => ErrorCode::ConnectError,
+            0xb => ErrorCode::EnhanceYourCalm,
+            0xc => ErrorCode::InadequateSecurity,
+            0xd => ErrorCode::Http11Required,
+            other => ErrorCode::Unknown(other),
+        }
+    }
+}
+
+impl From<ErrorCode> for u32 {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::NoError => 0x0,
+            ErrorCode::ProtocolError => 0x1,
+            ErrorCode::InternalError => 0x2,
+            ErrorCode::FlowControlError => 0x3,
+            ErrorCode::SettingsTimeout => 0x4,
+            ErrorCode::StreamClosed => 0x5,
+            ErrorCode::FrameSizeError => 0x6,
+            ErrorCode::RefusedStream => 0x7,
+            ErrorCode::Cancel => 0x8,
+            ErrorCode::CompressionError => 0x9,
+            ErrorCode::ConnectError => 0xa,
+            ErrorCode::EnhanceYourCalm => 0xb,
+            ErrorCode::InadequateSecurity => 0xc,
+            ErrorCode::Http11Required => 0xd,
+            ErrorCode::Unknown(other) => other,
+        }
+    }
+}
+
+// ============================================================================
+// ERROS
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum H2Error {
+    /// Erro de conexão; um GOAWAY com `code` já foi enfileirado
+    #[error("HTTP/2 protocol error ({code:?}): {message}")]
+    Protocol { code: ErrorCode, message: String },
+
+    #[error("HPACK decoding failed: {0}")]
+    Compression(String),
+
+    #[error("Connection is going away ({0:?})")]
+    GoAway(ErrorCode),
+
+    #[error("Peer stream limit reached")]
+    StreamLimit,
+
+    #[error("Stream {0} is not open for sending")]
+    StreamClosed(u32),
+
+    #[error("Operation not valid for this side of the connection: {0}")]
+    Usage(&'static str),
+}
+
+impl H2Error {
+    pub(crate) fn protocol(code: ErrorCode, message: impl Into<String>) -> Self {
+        H2Error::Protocol {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Código a enviar no GOAWAY quando o erro encerra a conexão
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            H2Error::Protocol { code, .. } => *code,
+            H2Error::Compression(_) => ErrorCode::CompressionError,
+            H2Error::GoAway(code) => *code,
+            H2Error::StreamLimit => ErrorCode::RefusedStream,
+            H2Error::StreamClosed(_) => ErrorCode::StreamClosed,
+            H2Error::Usage(_) => ErrorCode::InternalError,
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! BLAKE3
+//!
+//! Portable implementation of the reference algorithm: 1 KiB chunks hashed
+//! into a binary tree, with a stack of chaining values so input can arrive in
+//! pieces of any size. Supports the plain, keyed and key-derivation modes and
+//! extendable output.
+
+use crate::Hasher;
+use avila_primitives::Bytes32;
+
+const OUT_LEN: usize = 32;
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// Enough chaining values for 2^54 chunks, the most a 64-bit length allows
+const MAX_DEPTH: usize = 54;
+
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn round(state: &mut [u32; 16], m: &[u32; 16]) {
+    // Columns, then diagonals
+    g(state, 0, 4, 8, 12, m[0], m[1]);
+    g(state, 1, 5, 9, 13, m[2], m[3]);
+    g(state, 2, 6, 10, 14, m[4], m[5]);
+    g(state, 3, 7, 11, 15, m[6], m[7]);
+    g(state, 0, 5, 10, 15, m[8], m[9]);
+    g(state, 1, 6, 11, 12, m[10], m[11]);
+    g(state, 2, 7, 8, 13, m[12], m[13]);
+    g(state, 3, 4, 9, 14, m[14], m[15]);
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        cv[0], cv[1], cv[2], cv[3], cv[4], cv[5], cv[6], cv[7],
+        IV[0], IV[1], IV[2], IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+    let mut m = *block;
+    for i in 0..7 {
+        round(&mut state, &m);
+        if i < 6 {
+            m = core::array::from_fn(|j| m[MSG_PERMUTATION[j]]);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    state
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    core::array::from_fn(|i| words[i])
+}
+
+fn words_from_le_bytes<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    core::array::from_fn(|i| u32::from_le_bytes([bytes[4 * i], bytes[4 * i + 1], bytes[4 * i + 2], bytes[4 * i + 3]]))
+}
+
+/// A compression still to be run: a chaining value, or root output bytes
+#[derive(Clone, Copy)]
+struct Output {
+    input_cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(&self.input_cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (counter, block) in out.chunks_mut(2 * OUT_LEN).enumerate() {
+            let words = compress(&self.input_cv, &self.block, counter as u64, self.block_len, self.flags | ROOT);
+            for (bytes, word) in block.chunks_mut(4).zip(words) {
+                bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+            }
+        }
+    }
+}
+
+fn parent_output(left: [u32; 8], right: [u32; 8], key: [u32; 8], flags: u32) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Output {
+        input_cv: key,
+        block,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT | flags,
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: [u32; 8], chunk_counter: u64, flags: u32) -> Self {
+        Self {
+            cv: key,
+            chunk_counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // The last block of a chunk is compressed by `output`, with CHUNK_END
+            if self.block_len == BLOCK_LEN {
+                let block = words_from_le_bytes(&self.block);
+                let flags = self.flags | self.start_flag();
+                self.cv = first_8(compress(&self.cv, &block, self.chunk_counter, BLOCK_LEN as u32, flags));
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            input_cv: self.cv,
+            block: words_from_le_bytes(&self.block),
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// BLAKE3 hasher
+#[derive(Clone)]
+pub struct Blake3 {
+    chunk: ChunkState,
+    key: [u32; 8],
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_stack_len: usize,
+    flags: u32,
+}
+
+impl Blake3 {
+    fn with_key(key: [u32; 8], flags: u32) -> Self {
+        Self {
+            chunk: ChunkState::new(key, 0, flags),
+            key,
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    /// Creates a new BLAKE3 hasher
+    pub fn new() -> Self {
+        Self::with_key(IV, 0)
+    }
+
+    /// Keyed hasher, a MAC with a 32-byte key
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self::with_key(words_from_le_bytes(key), KEYED_HASH)
+    }
+
+    /// Key derivation hasher: feed the key material, the output is the key
+    ///
+    /// `context` should be a hardcoded, globally unique string such as
+    /// `"vizzio 2025-01-01 sync session keys"`.
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hasher = Self::with_key(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+        let mut context_key = [0u8; 32];
+        context_hasher.finalize_xof(&mut context_key);
+        Self::with_key(words_from_le_bytes(&context_key), DERIVE_KEY_MATERIAL)
+    }
+
+    /// Updates hash with data
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.chunk.len() == CHUNK_LEN {
+                let chunk_cv = self.chunk.output().chaining_value();
+                let total_chunks = self.chunk.chunk_counter + 1;
+                self.push_chunk_cv(chunk_cv, total_chunks);
+                self.chunk = ChunkState::new(self.key, total_chunks, self.flags);
+            }
+            let take = (CHUNK_LEN - self.chunk.len()).min(data.len());
+            self.chunk.update(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    /// Merges completed subtrees: one merge per trailing zero bit of the chunk count
+    fn push_chunk_cv(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            cv = parent_output(self.cv_stack[self.cv_stack_len], cv, self.key, self.flags).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = cv;
+        self.cv_stack_len += 1;
+    }
+
+    /// Finalizes and returns hash
+    pub fn finalize(self) -> Bytes32 {
+        let mut result = [0u8; 32];
+        self.finalize_xof(&mut result);
+        Bytes32::from(result)
+    }
+
+    /// Fills `out` with output of any length; the first 32 bytes are the hash
+    ///
+    /// Does not consume the hasher, so more input can still be added.
+    pub fn finalize_xof(&self, out: &mut [u8]) {
+        let mut output = self.chunk.output();
+        for &cv in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            output = parent_output(cv, output.chaining_value(), self.key, self.flags);
+        }
+        output.root_bytes(out);
+    }
+
+    /// One-shot hash
+    pub fn hash(data: &[u8]) -> Bytes32 {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// One-shot keyed hash
+    pub fn keyed_hash(key: &[u8; 32], data: &[u8]) -> Bytes32 {
+        let mut hasher = Self::new_keyed(key);
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// One-shot key derivation
+    pub fn derive_key(context: &str, key_material: &[u8]) -> Bytes32 {
+        let mut hasher = Self::new_derive_key(context);
+        hasher.update(key_material);
+        hasher.finalize()
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake3 {
+    type Output = Bytes32;
+    const BLOCK_SIZE: usize = BLOCK_LEN;
+
+    fn update(&mut self, data: &[u8]) {
+        Blake3::update(self, data);
+    }
+
+    fn finalize(self) -> Bytes32 {
+        Blake3::finalize(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{hex, pattern};
+
+    #[test]
+    fn test_blake3_vectors() {
+        assert_eq!(hex(&Blake3::hash(b"")), "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+        assert_eq!(hex(&Blake3::hash(b"abc")), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+
+        // Official test vectors: input byte i is i % 251
+        let key = b"whats the Elvish word for friend";
+        let context = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+        let mut xof = [0u8; 40];
+        Blake3::new().finalize_xof(&mut xof);
+        assert_eq!(hex(&xof), "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262e00f03e7b69af26b");
+        assert_eq!(hex(&Blake3::keyed_hash(key, b"")), "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26");
+        assert_eq!(hex(&Blake3::derive_key(context, b"")), "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d");
+        assert_eq!(hex(&Blake3::hash(&pattern(1))), "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213");
+    }
+
+    #[test]
+    fn test_incremental_matches_one_shot() {
+        // Crosses block, chunk and multi-level tree boundaries
+        let data = pattern(5 * CHUNK_LEN + 17);
+        let expected = Blake3::hash(&data);
+        for split in [0, 1, 63, 64, 65, 1023, 1024, 1025, 2048, 3072, 4097, data.len()] {
+            let mut hasher = Blake3::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hasher.finalize(), expected, "split {}", split);
+        }
+
+        let mut byte_at_a_time = Blake3::new();
+        for byte in data.chunks(1) {
+            byte_at_a_time.update(byte);
+        }
+        assert_eq!(byte_at_a_time.finalize(), expected);
+
+        // XOF output extends the hash
+        let mut long = [0u8; 200];
+        Blake3::new_keyed(&[7; 32]).finalize_xof(&mut long);
+        assert_eq!(&long[..32], Blake3::keyed_hash(&[7; 32], b"").as_slice());
+    }
+}
@@ -0,0 +1,139 @@
+//! HMAC (RFC 2104) over any [`Hasher`]
+
+use crate::Hasher;
+
+/// Largest block size among the hashers here (SHA3-256's rate)
+const MAX_BLOCK_SIZE: usize = 136;
+
+/// HMAC, incremental like the hashers
+///
+/// ```
+/// use avila_hash::{Hmac, Sha256};
+///
+/// let mut mac = Hmac::<Sha256>::new(b"key");
+/// mac.update(b"The quick brown fox ");
+/// mac.update(b"jumps over the lazy dog");
+/// let tag = mac.finalize();
+/// assert!(Hmac::<Sha256>::new(b"key").chain(b"The quick brown fox jumps over the lazy dog").verify(tag.as_ref()));
+/// ```
+#[derive(Clone)]
+pub struct Hmac<H: Hasher> {
+    inner: H,
+    outer: H,
+}
+
+impl<H: Hasher> Hmac<H> {
+    /// Creates a MAC; keys longer than the block size are hashed first
+    pub fn new(key: &[u8]) -> Self {
+        assert!(H::BLOCK_SIZE <= MAX_BLOCK_SIZE, "hash block size exceeds HMAC buffer");
+        let mut block = [0u8; MAX_BLOCK_SIZE];
+        if key.len() > H::BLOCK_SIZE {
+            let digest = H::hash(key);
+            block[..digest.as_ref().len()].copy_from_slice(digest.as_ref());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let block = &mut block[..H::BLOCK_SIZE];
+
+        let mut inner = H::default();
+        block.iter_mut().for_each(|byte| *byte ^= 0x36);
+        inner.update(block);
+        let mut outer = H::default();
+        block.iter_mut().for_each(|byte| *byte ^= 0x36 ^ 0x5c);
+        outer.update(block);
+        block.fill(0);
+
+        Self { inner, outer }
+    }
+
+    /// Updates the MAC with data
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// [`update`](Self::update) by value, for one-liners
+    pub fn chain(mut self, data: &[u8]) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Finalizes and returns the tag
+    pub fn finalize(self) -> H::Output {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize().as_ref());
+        outer.finalize()
+    }
+
+    /// Compares against `tag` in constant time
+    ///
+    /// Truncated tags are accepted if at least half the output long (RFC 2104, 5).
+    pub fn verify(self, tag: &[u8]) -> bool {
+        let expected = self.finalize();
+        let expected = expected.as_ref();
+        if tag.len() > expected.len() || tag.len() < expected.len() / 2 {
+            return false;
+        }
+        expected.iter().zip(tag).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+
+    /// One-shot MAC
+    pub fn mac(key: &[u8], data: &[u8]) -> H::Output {
+        Self::new(key).chain(data).finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::hex;
+    use crate::{Blake3, Sha256, Sha384, Sha3_256, Sha512};
+
+    #[test]
+    fn test_rfc4231_vectors() {
+        // Case 1
+        let key = [0x0b; 20];
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(&key, b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&Hmac::<Sha384>::mac(&key, b"Hi There")),
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+             faea9ea9076ede7f4af152e8b2fa9cb6"
+        );
+        assert_eq!(
+            hex(&Hmac::<Sha512>::mac(&key, b"Hi There")),
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+             daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+        );
+        // Case 2: key shorter than the output
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Case 6: key longer than the block, hashed first
+        let long_key = [0xaa; 131];
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(&long_key, b"Test Using Larger Than Block-Size Key - Hash Key First")),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_verify_and_other_hashers() {
+        let tag = Hmac::<Sha256>::mac(b"key", b"message");
+        assert!(Hmac::<Sha256>::new(b"key").chain(b"message").verify(tag.as_ref()));
+        assert!(Hmac::<Sha256>::new(b"key").chain(b"message").verify(&tag.as_ref()[..16]));
+        assert!(!Hmac::<Sha256>::new(b"key").chain(b"message").verify(&tag.as_ref()[..15]));
+        assert!(!Hmac::<Sha256>::new(b"key").chain(b"massage").verify(tag.as_ref()));
+        assert!(!Hmac::<Sha256>::new(b"kez").chain(b"message").verify(tag.as_ref()));
+
+        // NIST example: HMAC-SHA3-256, 32-byte key 00..1f
+        let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+        assert_eq!(
+            hex(&Hmac::<Sha3_256>::mac(&key, b"Sample message for keylen<blocklen")),
+            "4fe8e202c4f058e8dddc23d8c34e467343e23555e24fc2f025d598f558f67205"
+        );
+        assert_ne!(Hmac::<Blake3>::mac(b"key", b"message"), Blake3::keyed_hash(&[0; 32], b"message"));
+    }
+}
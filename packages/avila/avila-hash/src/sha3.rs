@@ -0,0 +1,148 @@
+//! SHA3-256 (FIPS 202)
+//!
+//! Keccak-f\[1600\] sponge with a 136-byte rate and the SHA-3 domain padding
+//! (`0x06`), which is what sets it apart from Ethereum's Keccak-256.
+
+use crate::{BlockBuffer, Hasher};
+use avila_primitives::Bytes32;
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// Rotation offsets, in the order the pi step visits the lanes
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+/// SHA3-256 hasher
+#[derive(Clone)]
+pub struct Sha3_256 {
+    state: [u64; 25],
+    buffer: BlockBuffer<RATE>,
+}
+
+impl Sha3_256 {
+    /// Creates a new SHA3-256 hasher
+    pub const fn new() -> Self {
+        Self {
+            state: [0; 25],
+            buffer: BlockBuffer::new(),
+        }
+    }
+
+    /// Updates hash with data
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| absorb(state, block));
+    }
+
+    /// Finalizes and returns hash
+    pub fn finalize(mut self) -> Bytes32 {
+        let (pending, len) = (self.buffer.bytes, self.buffer.len);
+        let mut block = [0u8; RATE];
+        block[..len].copy_from_slice(&pending[..len]);
+        block[len] ^= 0x06;
+        block[RATE - 1] ^= 0x80;
+        absorb(&mut self.state, &block);
+
+        let mut result = [0u8; 32];
+        for (out, lane) in result.chunks_exact_mut(8).zip(self.state) {
+            out.copy_from_slice(&lane.to_le_bytes());
+        }
+        Bytes32::from(result)
+    }
+
+    /// One-shot hash
+    pub fn hash(data: &[u8]) -> Bytes32 {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha3_256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Sha3_256 {
+    type Output = Bytes32;
+    const BLOCK_SIZE: usize = RATE;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_256::update(self, data);
+    }
+
+    fn finalize(self) -> Bytes32 {
+        Sha3_256::finalize(self)
+    }
+}
+
+fn absorb(state: &mut [u64; 25], block: &[u8; RATE]) {
+    for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(bytes);
+        *lane ^= u64::from_le_bytes(word);
+    }
+    keccak_f(state);
+}
+
+/// Keccak-f\[1600\]: 24 rounds of theta, rho, pi, chi and iota
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        let mut c = [0u64; 5];
+        for (x, column) in c.iter_mut().enumerate() {
+            *column = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        let mut last = a[1];
+        for (&lane, &rotation) in PI.iter().zip(&RHO) {
+            let next = a[lane];
+            a[lane] = last.rotate_left(rotation);
+            last = next;
+        }
+
+        for y in 0..5 {
+            let row = [a[5 * y], a[5 * y + 1], a[5 * y + 2], a[5 * y + 3], a[5 * y + 4]];
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        a[0] ^= rc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{hex, pattern};
+
+    #[test]
+    fn test_sha3_256_vectors() {
+        assert_eq!(hex(&Sha3_256::hash(b"")), "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a");
+        assert_eq!(hex(&Sha3_256::hash(b"abc")), "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532");
+
+        let data = pattern(1000);
+        for split in [0, 1, 135, 136, 137, 272, 999] {
+            let mut hasher = Sha3_256::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hasher.finalize(), Sha3_256::hash(&data), "split {}", split);
+        }
+    }
+}
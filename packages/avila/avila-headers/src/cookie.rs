@@ -0,0 +1,400 @@
+//! Cookies (RFC 6265)
+//!
+//! [`Cookie`] é um par nome/valor do header `Cookie`; [`SetCookie`] adiciona
+//! os atributos do header `Set-Cookie`. Nome e valor são validados na
+//! criação, os atributos ao serializar com [`SetCookie::to_header`], que
+//! também aplica as regras de `SameSite=None` e dos prefixos `__Secure-` e
+//! `__Host-`. A leitura de `Set-Cookie` é tolerante com atributos, como pede
+//! a seção 5.2: atributos desconhecidos ou inválidos são ignorados.
+
+use crate::{http_date, is_token, parse_http_date, HeaderError, Result};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Par nome/valor validado
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cookie {
+    name: String,
+    value: String,
+}
+
+impl Cookie {
+    /// Nome como token; valor só com `cookie-octet`, opcionalmente entre aspas
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        let (name, value) = (name.into(), value.into());
+        if !is_token(&name) {
+            return Err(HeaderError::InvalidCookieName(name));
+        }
+        if !is_cookie_value(&value) {
+            return Err(HeaderError::InvalidCookieValue(name));
+        }
+        Ok(Self { name, value })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Cookies de um header `Cookie` (`a=1; b=2`); pares inválidos são ignorados
+    pub fn parse_list(header: &str) -> Vec<Cookie> {
+        header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                Cookie::new(name.trim(), value.trim()).ok()
+            })
+            .collect()
+    }
+
+    /// Valor do header `Cookie` com os cookies dados
+    pub fn join(cookies: &[Cookie]) -> String {
+        cookies.iter().map(Cookie::to_string).collect::<Vec<_>>().join("; ")
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+/// `cookie-value` da RFC 6265, seção 4.1.1
+fn is_cookie_value(value: &str) -> bool {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value);
+    inner
+        .bytes()
+        .all(|b| matches!(b, 0x21 | 0x23..=0x2b | 0x2d..=0x3a | 0x3c..=0x5b | 0x5d..=0x7e))
+}
+
+// ============================================================================
+// SameSite
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    /// Exige `Secure`
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [SameSite::Strict, SameSite::Lax, SameSite::None]
+            .into_iter()
+            .find(|same_site| same_site.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+// ============================================================================
+// Set-Cookie
+// ============================================================================
+
+/// Cookie com os atributos de `Set-Cookie`
+///
+/// ```
+/// use avila_headers::{SameSite, SetCookie};
+/// use std::time::Duration;
+///
+/// let header = SetCookie::new("__Host-sessao", "abc123")?
+///     .path("/")
+///     .max_age(Duration::from_secs(3600))
+///     .secure(true)
+///     .http_only(true)
+///     .same_site(SameSite::Lax)
+///     .to_header()?;
+/// assert_eq!(header, "__Host-sessao=abc123; Path=/; Max-Age=3600; Secure; HttpOnly; SameSite=Lax");
+/// # Ok::<(), avila_headers::HeaderError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCookie {
+    cookie: Cookie,
+    pub path: Option<String>,
+    pub domain: Option<String>,
+    /// Zero remove o cookie
+    pub max_age: Option<Duration>,
+    /// Truncado em segundos ao serializar
+    pub expires: Option<SystemTime>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+}
+
+impl SetCookie {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        Ok(Self::from(Cookie::new(name, value)?))
+    }
+
+    /// `Set-Cookie` que apaga o cookie no cliente
+    ///
+    /// `Path` e `Domain` precisam ser os mesmos usados ao criá-lo.
+    pub fn expired(name: impl Into<String>) -> Result<Self> {
+        Ok(Self::new(name, "")?.max_age(Duration::ZERO).expires(UNIX_EPOCH))
+    }
+
+    /// Lê um header `Set-Cookie`
+    ///
+    /// Só o par nome/valor precisa ser válido; atributos desconhecidos ou
+    /// malformados são ignorados.
+    pub fn parse(header: &str) -> Result<Self> {
+        let mut parts = header.split(';');
+        let (name, value) = parts
+            .next()
+            .and_then(|pair| pair.split_once('='))
+            .ok_or(HeaderError::Malformed("Set-Cookie"))?;
+        let mut set_cookie = Self::new(name.trim(), value.trim())?;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let (key, value) = (key.trim(), value.trim());
+            match key.to_ascii_lowercase().as_str() {
+                "path" if value.starts_with('/') => set_cookie.path = Some(value.to_string()),
+                "domain" if !value.is_empty() => {
+                    set_cookie.domain = Some(value.trim_start_matches('.').to_ascii_lowercase())
+                }
+                "max-age" => {
+                    if let Some(max_age) = parse_max_age(value) {
+                        set_cookie.max_age = Some(max_age);
+                    }
+                }
+                "expires" => {
+                    if let Some(expires) = parse_http_date(value) {
+                        set_cookie.expires = Some(expires);
+                    }
+                }
+                "secure" => set_cookie.secure = true,
+                "httponly" => set_cookie.http_only = true,
+                "samesite" => set_cookie.same_site = SameSite::parse(value),
+                _ => {}
+            }
+        }
+        Ok(set_cookie)
+    }
+
+    pub fn cookie(&self) -> &Cookie {
+        &self.cookie
+    }
+
+    pub fn name(&self) -> &str {
+        self.cookie.name()
+    }
+
+    pub fn value(&self) -> &str {
+        self.cookie.value()
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn expires(mut self, expires: SystemTime) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Confere os atributos e as regras que o navegador aplicaria
+    ///
+    /// Um cookie que o navegador descartaria em silêncio vira erro aqui.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason| {
+            Err(HeaderError::InvalidCookieAttribute {
+                cookie: self.name().to_string(),
+                reason,
+            })
+        };
+        if [&self.path, &self.domain].into_iter().flatten().any(|value| !is_attribute_value(value)) {
+            return invalid("attribute contains ';' or control characters");
+        }
+        if self.same_site == Some(SameSite::None) && !self.secure {
+            return invalid("SameSite=None requires Secure");
+        }
+        let name = self.name();
+        if (name.starts_with("__Secure-") || name.starts_with("__Host-")) && !self.secure {
+            return invalid("prefixed cookies require Secure");
+        }
+        if name.starts_with("__Host-") && (self.domain.is_some() || self.path.as_deref() != Some("/")) {
+            return invalid("__Host- cookies require Path=/ and no Domain");
+        }
+        Ok(())
+    }
+
+    /// Valor do header `Set-Cookie`
+    pub fn to_header(&self) -> Result<String> {
+        self.validate()?;
+        let mut header = self.cookie.to_string();
+        if let Some(path) = &self.path {
+            header.push_str(&format!("; Path={}", path));
+        }
+        if let Some(domain) = &self.domain {
+            header.push_str(&format!("; Domain={}", domain));
+        }
+        if let Some(max_age) = self.max_age {
+            header.push_str(&format!("; Max-Age={}", max_age.as_secs()));
+        }
+        if let Some(expires) = self.expires {
+            header.push_str(&format!("; Expires={}", http_date(expires)));
+        }
+        if self.secure {
+            header.push_str("; Secure");
+        }
+        if self.http_only {
+            header.push_str("; HttpOnly");
+        }
+        if let Some(same_site) = self.same_site {
+            header.push_str(&format!("; SameSite={}", same_site.as_str()));
+        }
+        Ok(header)
+    }
+}
+
+impl From<Cookie> for SetCookie {
+    fn from(cookie: Cookie) -> Self {
+        Self {
+            cookie,
+            path: None,
+            domain: None,
+            max_age: None,
+            expires: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+}
+
+/// Segundos; zero ou negativo expira o cookie (RFC 6265, 5.2.2)
+fn parse_max_age(value: &str) -> Option<Duration> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if value.starts_with('-') {
+        return Some(Duration::ZERO);
+    }
+    // Valores enormes saturam em vez de descartar o atributo
+    Some(Duration::from_secs(digits.parse().unwrap_or(u64::MAX)))
+}
+
+fn is_attribute_value(value: &str) -> bool {
+    !value.bytes().any(|b| b == b';' || b.is_ascii_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_validation() {
+        assert!(Cookie::new("sessao", "abc-123_%3D").is_ok());
+        assert!(Cookie::new("vazio", "").is_ok());
+        assert!(Cookie::new("aspas", "\"abc\"").is_ok());
+        assert_eq!(Cookie::new("bad name", "x"), Err(HeaderError::InvalidCookieName("bad name".to_string())));
+        assert_eq!(Cookie::new("", "x"), Err(HeaderError::InvalidCookieName(String::new())));
+        for value in ["a b", "a;b", "a,b", "a\\b", "\"a", "a\r\nSet-Cookie: x=1", "café"] {
+            assert_eq!(Cookie::new("c", value), Err(HeaderError::InvalidCookieValue("c".to_string())), "{:?}", value);
+        }
+
+        let cookies = Cookie::parse_list("a=1; b=two;invalid; c d=3 ; e=\"q\"");
+        assert_eq!(cookies.iter().map(Cookie::name).collect::<Vec<_>>(), ["a", "b", "e"]);
+        assert_eq!(cookies[1].value(), "two");
+        assert_eq!(Cookie::join(&cookies), "a=1; b=two; e=\"q\"");
+    }
+
+    #[test]
+    fn test_set_cookie_roundtrip() {
+        let expires = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        let set_cookie = SetCookie::new("id", "42")
+            .unwrap()
+            .path("/app")
+            .domain("example.com")
+            .expires(expires)
+            .secure(true)
+            .same_site(SameSite::None);
+        let header = set_cookie.to_header().unwrap();
+        assert_eq!(
+            header,
+            "id=42; Path=/app; Domain=example.com; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Secure; SameSite=None"
+        );
+        assert_eq!(SetCookie::parse(&header), Ok(set_cookie));
+
+        let expired = SetCookie::expired("id").unwrap().to_header().unwrap();
+        assert_eq!(expired, "id=; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_set_cookie_parse_lenient() {
+        let parsed = SetCookie::parse("a=1; HTTPONLY; max-age=-1; Domain=.Example.COM; Path=relative; SameSite=weird; Foo=bar")
+            .unwrap();
+        assert!(parsed.http_only);
+        assert!(!parsed.secure);
+        assert_eq!(parsed.max_age, Some(Duration::ZERO));
+        assert_eq!(parsed.domain.as_deref(), Some("example.com"));
+        assert_eq!(parsed.path, None);
+        assert_eq!(parsed.same_site, None);
+
+        assert_eq!(SetCookie::parse("a=1; Max-Age=soon").unwrap().max_age, None);
+        assert_eq!(SetCookie::parse("noequals; Secure"), Err(HeaderError::Malformed("Set-Cookie")));
+        assert_eq!(SetCookie::parse("a=b c"), Err(HeaderError::InvalidCookieValue("a".to_string())));
+    }
+
+    #[test]
+    fn test_set_cookie_rules() {
+        let reason = |set_cookie: SetCookie| match set_cookie.to_header() {
+            Err(HeaderError::InvalidCookieAttribute { reason, .. }) => reason,
+            other => panic!("expected attribute error, got {:?}", other),
+        };
+        let cookie = |name| SetCookie::new(name, "1").unwrap();
+
+        assert_eq!(reason(cookie("a").same_site(SameSite::None)), "SameSite=None requires Secure");
+        assert_eq!(reason(cookie("__Secure-a")), "prefixed cookies require Secure");
+        assert_eq!(
+            reason(cookie("__Host-a").secure(true).path("/").domain("example.com")),
+            "__Host- cookies require Path=/ and no Domain"
+        );
+        assert_eq!(reason(cookie("__Host-a").secure(true)), "__Host- cookies require Path=/ and no Domain");
+        assert_eq!(reason(cookie("a").path("/; Domain=evil.com")), "attribute contains ';' or control characters");
+        assert!(cookie("__Host-a").secure(true).path("/").to_header().is_ok());
+        assert!(cookie("__Secure-a").secure(true).domain("example.com").to_header().is_ok());
+    }
+}
@@ -0,0 +1,321 @@
+//! # avila-headers - Headers e cookies HTTP
+//!
+//! Tipos de headers compartilhados por `avila-http` e `avila-webframework`:
+//! - [`HeaderMap`]: nomes sem distinção de maiúsculas, vários valores por
+//!   nome (`Set-Cookie`, `Vary`, ...) e ordem de inserção preservada
+//! - Headers tipados ([`Header`]): [`ContentType`], [`ContentLength`],
+//!   [`Authorization`]
+//! - Cookies validados (RFC 6265): [`Cookie`] para o header `Cookie` e
+//!   [`SetCookie`] para ler e montar `Set-Cookie`
+//! - Datas HTTP (IMF-fixdate): [`http_date`], [`parse_http_date`]
+//!
+//! Nomes e valores não são validados ao inserir; quem recebe headers de fora
+//! (builders, parsers) confere com [`validate_header`] ou
+//! [`HeaderMap::validate`] antes de escrever na conexão.
+//!
+//! ```
+//! use avila_headers::{ContentType, HeaderMap, SameSite, SetCookie};
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.set_typed(ContentType::json());
+//! headers.append("Set-Cookie", SetCookie::new("sessao", "abc123")?.http_only(true).to_header()?);
+//! headers.append("set-cookie", "tema=escuro; Path=/; SameSite=Lax");
+//!
+//! assert_eq!(headers.get("content-type"), Some("application/json"));
+//! assert_eq!(headers.content_type().unwrap().essence(), "application/json");
+//! let cookies = headers.set_cookies();
+//! assert_eq!(cookies.len(), 2);
+//! assert_eq!(cookies[1].same_site, Some(SameSite::Lax));
+//! # Ok::<(), avila_headers::HeaderError>(())
+//! ```
+
+pub mod cookie;
+mod date;
+mod typed;
+
+pub use cookie::{Cookie, SameSite, SetCookie};
+pub use date::{http_date, parse_http_date};
+pub use typed::{Authorization, ContentLength, ContentType, Header};
+
+use std::fmt;
+use std::ops::Index;
+
+pub type Result<T> = std::result::Result<T, HeaderError>;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderError {
+    #[error("Invalid header name '{0}'")]
+    InvalidName(String),
+
+    #[error("Invalid value for header '{0}'")]
+    InvalidValue(String),
+
+    #[error("Malformed {0} header")]
+    Malformed(&'static str),
+
+    #[error("Invalid cookie name '{0}'")]
+    InvalidCookieName(String),
+
+    #[error("Invalid value for cookie '{0}'")]
+    InvalidCookieValue(String),
+
+    #[error("Invalid attribute for cookie '{cookie}': {reason}")]
+    InvalidCookieAttribute { cookie: String, reason: &'static str },
+}
+
+// ============================================================================
+// HeaderMap
+// ============================================================================
+
+/// Headers de uma requisição ou resposta
+///
+/// Os nomes são comparados sem distinção de maiúsculas e guardados como
+/// foram inseridos, para serializar igual. `insert` substitui todos os
+/// valores do nome; `append` acrescenta mais um.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Número de valores (um nome repetido conta mais de uma vez)
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Primeiro valor do header
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Todos os valores do header, na ordem em que chegaram
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Define o header, descartando os valores anteriores; devolve o primeiro deles
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        let name = name.into();
+        let previous = self.remove(&name);
+        self.entries.push((name, value.into()));
+        previous
+    }
+
+    /// Acrescenta um valor, mantendo os existentes
+    pub fn append(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.entries.push((name.into(), value.into()));
+    }
+
+    /// Remove todos os valores do header; devolve o primeiro
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let mut first = None;
+        self.entries.retain_mut(|(key, value)| {
+            if !key.eq_ignore_ascii_case(name) {
+                return true;
+            }
+            if first.is_none() {
+                first = Some(std::mem::take(value));
+            }
+            false
+        });
+        first
+    }
+
+    /// Pares (nome, valor) na ordem de inserção
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Confere nomes e valores (ver [`validate_header`])
+    pub fn validate(&self) -> Result<()> {
+        self.iter().try_for_each(|(name, value)| validate_header(name, value))
+    }
+
+    /// Header tipado; `None` se ausente ou inválido
+    pub fn typed<H: Header>(&self) -> Option<H> {
+        H::parse(self.get(H::NAME)?)
+    }
+
+    /// Define um header tipado, substituindo o anterior
+    pub fn set_typed<H: Header>(&mut self, header: H) {
+        self.insert(H::NAME, header.to_value());
+    }
+
+    pub fn content_type(&self) -> Option<ContentType> {
+        self.typed()
+    }
+
+    /// `Content-Length`; `None` se ausente, inválido ou repetido com valores diferentes
+    pub fn content_length(&self) -> Option<u64> {
+        let mut values = self.get_all(ContentLength::NAME).map(ContentLength::parse);
+        let first = values.next()??;
+        values.all(|value| value == Some(first)).then_some(first.0)
+    }
+
+    pub fn authorization(&self) -> Option<Authorization> {
+        self.typed()
+    }
+
+    /// Cookies do header `Cookie` (HTTP/2 pode dividi-lo em vários campos)
+    ///
+    /// Pares inválidos são ignorados.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.get_all("cookie").flat_map(Cookie::parse_list).collect()
+    }
+
+    /// Cada `Set-Cookie` válido da resposta
+    pub fn set_cookies(&self) -> Vec<SetCookie> {
+        self.get_all("set-cookie").filter_map(|value| SetCookie::parse(value).ok()).collect()
+    }
+}
+
+impl fmt::Debug for HeaderMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// `headers["Content-Type"]`: primeiro valor
+///
+/// # Panics
+///
+/// Se o header não existir.
+impl Index<&str> for HeaderMap {
+    type Output = String;
+
+    fn index(&self, name: &str) -> &String {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("no header named '{}'", name))
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for HeaderMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut headers = Self::new();
+        headers.extend(iter);
+        headers
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for HeaderMap {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.append(name, value);
+        }
+    }
+}
+
+impl IntoIterator for HeaderMap {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+// ============================================================================
+// Validação
+// ============================================================================
+
+/// Nome como token (RFC 9110) e valor sem CR, LF, NUL nem outros controles
+///
+/// Barra a injeção de headers (`\r\n` num valor vindo do usuário).
+pub fn validate_header(name: &str, value: &str) -> Result<()> {
+    if !is_token(name) {
+        return Err(HeaderError::InvalidName(name.to_string()));
+    }
+    if !is_field_value(value) {
+        return Err(HeaderError::InvalidValue(name.to_string()));
+    }
+    Ok(())
+}
+
+/// `tchar` da RFC 9110, ao menos um
+pub(crate) fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Tab e caracteres visíveis (incluindo UTF-8 opaco); sem espaços nas pontas
+fn is_field_value(value: &str) -> bool {
+    value.bytes().all(|b| b == b'\t' || (b >= 0x20 && b != 0x7f))
+        && !value.starts_with([' ', '\t'])
+        && !value.ends_with([' ', '\t'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_value_and_case() {
+        let mut headers: HeaderMap = [("Vary", "Accept"), ("Content-Type", "text/plain")].into_iter().collect();
+        headers.append("vary", "Accept-Encoding");
+        assert_eq!(headers.get_all("VARY").collect::<Vec<_>>(), ["Accept", "Accept-Encoding"]);
+        assert_eq!(headers["content-type"], "text/plain");
+        assert_eq!(headers.len(), 3);
+
+        assert_eq!(headers.insert("VARY", "*"), Some("Accept".to_string()));
+        assert_eq!(headers.get_all("vary").collect::<Vec<_>>(), ["*"]);
+        assert_eq!(headers.iter().last(), Some(("VARY", "*")));
+        assert_eq!(headers.remove("content-TYPE"), Some("text/plain".to_string()));
+        assert!(!headers.contains_key("content-type"));
+        assert_eq!(headers.remove("missing"), None);
+    }
+
+    #[test]
+    fn test_content_length() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(headers.content_length(), None);
+        headers.set_typed(ContentLength(42));
+        assert_eq!(headers.get("Content-Length"), Some("42"));
+        headers.append("content-length", "42");
+        assert_eq!(headers.content_length(), Some(42));
+        // Valores divergentes tornam a mensagem ambígua (request smuggling)
+        headers.append("content-length", "43");
+        assert_eq!(headers.content_length(), None);
+        headers.insert("content-length", "+5");
+        assert_eq!(headers.content_length(), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate_header("X-Request-Id", "abc 123").is_ok());
+        assert!(validate_header("X-Nome", "café").is_ok());
+        assert_eq!(validate_header("Bad Name", "x"), Err(HeaderError::InvalidName("Bad Name".to_string())));
+        assert_eq!(validate_header("", "x"), Err(HeaderError::InvalidName(String::new())));
+        assert!(validate_header("Location", "/ok\r\nSet-Cookie: x=1").is_err());
+        assert!(validate_header("X", "a\0b").is_err());
+        assert!(validate_header("X", " padded").is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.append("X-Ok", "1");
+        assert!(headers.validate().is_ok());
+        headers.append("X-Bad", "1\n2");
+        assert_eq!(headers.validate(), Err(HeaderError::InvalidValue("X-Bad".to_string())));
+    }
+}
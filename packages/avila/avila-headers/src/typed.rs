@@ -0,0 +1,308 @@
+//! Headers tipados
+
+use crate::is_token;
+use avila_codec::base64;
+use std::fmt;
+
+/// Header com representação tipada
+///
+/// `parse` devolve `None` para valores malformados; quem lê headers de fora
+/// trata isso como header ausente.
+pub trait Header: Sized {
+    /// Nome canônico, usado ao inserir
+    const NAME: &'static str;
+
+    fn parse(value: &str) -> Option<Self>;
+
+    fn to_value(&self) -> String;
+}
+
+// ============================================================================
+// Content-Type
+// ============================================================================
+
+/// `Content-Type`: tipo de mídia e parâmetros (RFC 9110, 8.3)
+///
+/// Tipo e nomes de parâmetros ficam em minúsculas; valores são mantidos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentType {
+    essence: String,
+    params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// `tipo/subtipo` sem parâmetros; `None` se não for um tipo de mídia
+    pub fn new(essence: &str) -> Option<Self> {
+        let essence = essence.trim().to_ascii_lowercase();
+        let (kind, subtype) = essence.split_once('/')?;
+        (is_token(kind) && is_token(subtype)).then_some(Self {
+            essence,
+            params: Vec::new(),
+        })
+    }
+
+    pub fn json() -> Self {
+        Self::known("application/json")
+    }
+
+    pub fn form() -> Self {
+        Self::known("application/x-www-form-urlencoded")
+    }
+
+    pub fn text() -> Self {
+        Self::known("text/plain").with_param("charset", "utf-8")
+    }
+
+    pub fn html() -> Self {
+        Self::known("text/html").with_param("charset", "utf-8")
+    }
+
+    fn known(essence: &str) -> Self {
+        Self {
+            essence: essence.to_string(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Adiciona ou substitui um parâmetro
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        self.params.retain(|(key, _)| *key != name);
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// `tipo/subtipo`, em minúsculas
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn charset(&self) -> Option<&str> {
+        self.param("charset")
+    }
+
+    /// Compara só `tipo/subtipo`, ignorando parâmetros
+    pub fn is(&self, essence: &str) -> bool {
+        self.essence.eq_ignore_ascii_case(essence)
+    }
+}
+
+impl Header for ContentType {
+    const NAME: &'static str = "Content-Type";
+
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = split_params(value);
+        let mut content_type = Self::new(parts.next()?)?;
+        for param in parts {
+            let (name, value) = param.split_once('=')?;
+            let name = name.trim();
+            if !is_token(name) {
+                return None;
+            }
+            content_type = content_type.with_param(name, &unquote(value.trim())?);
+        }
+        Some(content_type)
+    }
+
+    fn to_value(&self) -> String {
+        let mut value = self.essence.clone();
+        for (name, param) in &self.params {
+            if is_token(param) {
+                value.push_str(&format!("; {}={}", name, param));
+            } else {
+                value.push_str(&format!("; {}=\"{}\"", name, param.replace('\\', "\\\\").replace('"', "\\\"")));
+            }
+        }
+        value
+    }
+}
+
+impl fmt::Display for ContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_value())
+    }
+}
+
+/// Divide em `;` fora de aspas
+fn split_params(value: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    let mut escaped = false;
+    value
+        .split(move |c| {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                ';' if !quoted => return true,
+                _ => {}
+            }
+            false
+        })
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+}
+
+/// Token ou quoted-string; `None` se as aspas não fecharem
+fn unquote(value: &str) -> Option<String> {
+    let Some(inner) = value.strip_prefix('"') else {
+        return is_token(value).then(|| value.to_string());
+    };
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push(chars.next()?),
+            '"' => return chars.as_str().is_empty().then_some(result),
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+// ============================================================================
+// Content-Length
+// ============================================================================
+
+/// `Content-Length`: só dígitos, sem sinal nem espaços internos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ContentLength(pub u64);
+
+impl Header for ContentLength {
+    const NAME: &'static str = "Content-Length";
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        value.parse().ok().map(Self)
+    }
+
+    fn to_value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+// ============================================================================
+// Authorization
+// ============================================================================
+
+/// `Authorization` (RFC 9110, 11.6.2)
+#[derive(Clone, PartialEq, Eq)]
+pub enum Authorization {
+    /// RFC 7617; a senha pode conter `:`
+    Basic { username: String, password: String },
+    /// RFC 6750
+    Bearer(String),
+    Other { scheme: String, credentials: String },
+}
+
+impl Authorization {
+    pub fn basic(username: &str, password: &str) -> Self {
+        Self::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    pub fn bearer(token: &str) -> Self {
+        Self::Bearer(token.to_string())
+    }
+
+    /// Token Bearer, se for esse o esquema
+    pub fn bearer_token(&self) -> Option<&str> {
+        match self {
+            Self::Bearer(token) => Some(token),
+            _ => None,
+        }
+    }
+}
+
+impl Header for Authorization {
+    const NAME: &'static str = "Authorization";
+
+    fn parse(value: &str) -> Option<Self> {
+        let (scheme, credentials) = value.trim().split_once(' ')?;
+        let credentials = credentials.trim();
+        if !is_token(scheme) || credentials.is_empty() {
+            return None;
+        }
+        // Esquemas não distinguem maiúsculas (RFC 9110, 11.1)
+        if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = String::from_utf8(base64::decode(credentials).ok()?).ok()?;
+            let (username, password) = decoded.split_once(':')?;
+            Some(Self::basic(username, password))
+        } else if scheme.eq_ignore_ascii_case("bearer") {
+            Some(Self::bearer(credentials))
+        } else {
+            Some(Self::Other {
+                scheme: scheme.to_string(),
+                credentials: credentials.to_string(),
+            })
+        }
+    }
+
+    fn to_value(&self) -> String {
+        match self {
+            Self::Basic { username, password } => {
+                format!("Basic {}", base64::encode(format!("{}:{}", username, password).as_bytes()))
+            }
+            Self::Bearer(token) => format!("Bearer {}", token),
+            Self::Other { scheme, credentials } => format!("{} {}", scheme, credentials),
+        }
+    }
+}
+
+/// Não mostra credenciais em logs
+impl fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => write!(f, "Basic({:?}, <redacted>)", username),
+            Self::Bearer(_) => f.write_str("Bearer(<redacted>)"),
+            Self::Other { scheme, .. } => write!(f, "{}(<redacted>)", scheme),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type() {
+        let content_type = ContentType::parse("Text/HTML; Charset=\"UTF-8\"; boundary=\"a;b \\\"c\\\"\"").unwrap();
+        assert_eq!(content_type.essence(), "text/html");
+        assert!(content_type.is("TEXT/html"));
+        assert_eq!(content_type.charset(), Some("UTF-8"));
+        assert_eq!(content_type.param("BOUNDARY"), Some("a;b \"c\""));
+        assert_eq!(content_type.to_value(), "text/html; charset=UTF-8; boundary=\"a;b \\\"c\\\"\"");
+        assert_eq!(ContentType::parse(&content_type.to_value()), Some(content_type));
+
+        assert_eq!(ContentType::text().to_string(), "text/plain; charset=utf-8");
+        for invalid in ["", "json", "text/", "text/plain; charset", "a/b; x=\"open", "a b/c"] {
+            assert_eq!(ContentType::parse(invalid), None, "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_authorization() {
+        let basic = Authorization::basic("Aladdin", "open:sesame");
+        assert_eq!(basic.to_value(), "Basic QWxhZGRpbjpvcGVuOnNlc2FtZQ==");
+        assert_eq!(Authorization::parse("basic QWxhZGRpbjpvcGVuOnNlc2FtZQ=="), Some(basic.clone()));
+        assert_eq!(format!("{:?}", basic), "Basic(\"Aladdin\", <redacted>)");
+
+        let bearer = Authorization::parse("Bearer  abc.def").unwrap();
+        assert_eq!(bearer.bearer_token(), Some("abc.def"));
+        assert!(matches!(Authorization::parse("Digest x=1"), Some(Authorization::Other { .. })));
+        for invalid in ["Bearer", "Basic !!!!", "Basic bm9jb2xvbg==", ""] {
+            assert_eq!(Authorization::parse(invalid), None, "{:?}", invalid);
+        }
+    }
+}
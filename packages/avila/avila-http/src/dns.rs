@@ -0,0 +1,612 @@
+//! Resolução de nomes e conexão dual-stack
+//!
+//! [`Resolver`] consulta registros A e AAAA em paralelo, direto nos
+//! servidores configurados (UDP, repetindo em TCP quando a resposta vem
+//! truncada) ou, sem servidores, pelo resolvedor do sistema. As respostas
+//! ficam em cache pelo TTL de cada registro, limitado por
+//! [`ResolverConfig::min_ttl`] e [`ResolverConfig::max_ttl`]; respostas vazias
+//! também, por [`ResolverConfig::negative_ttl`].
+//!
+//! [`happy_eyeballs`] conecta ao primeiro endereço que responder (RFC 8305):
+//! as tentativas começam escalonadas, alternando IPv6 e IPv4, e uma falha
+//! dispara a próxima sem esperar o intervalo.
+
+use avila_error::{Error, ErrorKind, Result};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::task::JoinSet;
+
+/// Intervalo entre o início de duas tentativas de conexão (RFC 8305, 5)
+pub const DEFAULT_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Maior resposta UDP aceita (sem EDNS o servidor trunca em 512)
+const MAX_UDP_RESPONSE: usize = 4096;
+
+// ============================================================================
+// CONFIGURAÇÃO
+// ============================================================================
+
+/// Tipo de registro de endereço
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecordType {
+    A,
+    Aaaa,
+}
+
+impl RecordType {
+    fn code(self) -> u16 {
+        match self {
+            RecordType::A => 1,
+            RecordType::Aaaa => 28,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResolverConfig {
+    /// Servidores consultados em ordem; vazio usa o resolvedor do sistema
+    pub nameservers: Vec<SocketAddr>,
+    /// Espera por uma resposta de cada servidor
+    pub query_timeout: Duration,
+    /// Consultas por servidor antes de passar ao próximo
+    pub attempts: usize,
+    pub min_ttl: Duration,
+    pub max_ttl: Duration,
+    /// Cache de respostas sem endereços (NXDOMAIN ou sem registros do tipo)
+    pub negative_ttl: Duration,
+    /// O resolvedor do sistema não informa TTL; este vale para as respostas dele
+    pub system_ttl: Duration,
+    /// Nomes × tipos em cache; cheio, os que expiram primeiro saem
+    pub cache_capacity: usize,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            nameservers: Vec::new(),
+            query_timeout: Duration::from_secs(2),
+            attempts: 2,
+            min_ttl: Duration::ZERO,
+            max_ttl: Duration::from_secs(3600),
+            negative_ttl: Duration::from_secs(30),
+            system_ttl: Duration::from_secs(30),
+            cache_capacity: 1024,
+        }
+    }
+}
+
+impl ResolverConfig {
+    /// Servidores e opções (`timeout:`, `attempts:`) de um `resolv.conf`
+    pub fn from_resolv_conf(contents: &str) -> Self {
+        let mut config = Self::default();
+        for line in contents.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("nameserver") => {
+                    // Endereços com escopo (`fe80::1%eth0`) ficam de fora
+                    if let Some(ip) = words.next().and_then(|ip| ip.parse::<IpAddr>().ok()) {
+                        config.nameservers.push(SocketAddr::new(ip, 53));
+                    }
+                }
+                Some("options") => {
+                    for option in words {
+                        match option.split_once(':') {
+                            Some(("timeout", secs)) => {
+                                if let Ok(secs) = secs.parse() {
+                                    config.query_timeout = Duration::from_secs(secs);
+                                }
+                            }
+                            Some(("attempts", n)) => {
+                                if let Ok(n) = n.parse() {
+                                    config.attempts = n;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        config
+    }
+
+    /// Lê `/etc/resolv.conf`
+    pub fn from_system() -> io::Result<Self> {
+        Ok(Self::from_resolv_conf(&std::fs::read_to_string("/etc/resolv.conf")?))
+    }
+}
+
+// ============================================================================
+// RESOLVER
+// ============================================================================
+
+struct CacheEntry {
+    addrs: Vec<IpAddr>,
+    expires: Instant,
+}
+
+/// Resolvedor A/AAAA com cache, compartilhável entre clientes
+pub struct Resolver {
+    config: ResolverConfig,
+    cache: Mutex<HashMap<(String, RecordType), CacheEntry>>,
+    queries: AtomicU64,
+    ids: RandomState,
+}
+
+impl Resolver {
+    pub fn new(config: ResolverConfig) -> Self {
+        Self {
+            config,
+            cache: Mutex::new(HashMap::new()),
+            queries: AtomicU64::new(0),
+            ids: RandomState::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ResolverConfig {
+        &self.config
+    }
+
+    /// Endereços IPv6 e IPv4 de `host` (IPs literais voltam como estão)
+    ///
+    /// Falha só se nenhum dos dois tipos trouxer endereço.
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>> {
+        if let Some(ip) = ip_literal(host) {
+            return Ok(vec![ip]);
+        }
+        let host = normalize(host);
+        let (v6, v4) = if self.config.nameservers.is_empty() {
+            self.lookup_system(&host).await?
+        } else if host == "localhost" || host.ends_with(".localhost") {
+            // RFC 6761: nunca vai aos servidores
+            (vec![IpAddr::V6(Ipv6Addr::LOCALHOST)], vec![IpAddr::V4(Ipv4Addr::LOCALHOST)])
+        } else {
+            match tokio::join!(self.query(&host, RecordType::Aaaa), self.query(&host, RecordType::A)) {
+                (Err(e), Err(_)) => return Err(e),
+                (v6, v4) => (v6.unwrap_or_default(), v4.unwrap_or_default()),
+            }
+        };
+
+        let addrs: Vec<IpAddr> = v6.into_iter().chain(v4).collect();
+        if addrs.is_empty() {
+            return Err(Error::not_found(format!("No addresses found for host '{}'", host)));
+        }
+        Ok(addrs)
+    }
+
+    /// Registros de um tipo, do cache ou dos servidores
+    pub async fn query(&self, host: &str, record: RecordType) -> Result<Vec<IpAddr>> {
+        let host = normalize(host);
+        if let Some(addrs) = self.cached(&host, record) {
+            return Ok(addrs);
+        }
+        if self.config.nameservers.is_empty() {
+            let (v6, v4) = self.lookup_system(&host).await?;
+            return Ok(if record == RecordType::Aaaa { v6 } else { v4 });
+        }
+
+        let (addrs, ttl) = self.query_servers(&host, record).await?;
+        self.store(&host, record, addrs.clone(), ttl);
+        Ok(addrs)
+    }
+
+    /// Consultas enviadas aos servidores (ou ao sistema) desde a criação
+    pub fn queries_sent(&self) -> u64 {
+        self.queries.load(Ordering::Relaxed)
+    }
+
+    pub fn clear_cache(&self) {
+        self.lock_cache().clear();
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, HashMap<(String, RecordType), CacheEntry>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn cached(&self, host: &str, record: RecordType) -> Option<Vec<IpAddr>> {
+        let cache = self.lock_cache();
+        let entry = cache.get(&(host.to_string(), record))?;
+        (entry.expires > Instant::now()).then(|| entry.addrs.clone())
+    }
+
+    fn store(&self, host: &str, record: RecordType, addrs: Vec<IpAddr>, ttl: Duration) {
+        if ttl.is_zero() || self.config.cache_capacity == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let mut cache = self.lock_cache();
+        if cache.len() >= self.config.cache_capacity {
+            cache.retain(|_, entry| entry.expires > now);
+        }
+        while cache.len() >= self.config.cache_capacity {
+            let Some(oldest) = cache.iter().min_by_key(|(_, entry)| entry.expires).map(|(key, _)| key.clone()) else {
+                break;
+            };
+            cache.remove(&oldest);
+        }
+        cache.insert((host.to_string(), record), CacheEntry { addrs, expires: now + ttl });
+    }
+
+    /// `getaddrinfo` de uma vez para os dois tipos, em cache por `system_ttl`
+    async fn lookup_system(&self, host: &str) -> Result<(Vec<IpAddr>, Vec<IpAddr>)> {
+        if let (Some(v6), Some(v4)) = (self.cached(host, RecordType::Aaaa), self.cached(host, RecordType::A)) {
+            return Ok((v6, v4));
+        }
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        let resolved = tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|e| Error::network(format!("Failed to resolve '{}': {}", host, e)))?;
+
+        let (mut v6, mut v4) = (Vec::new(), Vec::new());
+        for addr in resolved {
+            let list = if addr.is_ipv6() { &mut v6 } else { &mut v4 };
+            if !list.contains(&addr.ip()) {
+                list.push(addr.ip());
+            }
+        }
+        self.store(host, RecordType::Aaaa, v6.clone(), self.config.system_ttl);
+        self.store(host, RecordType::A, v4.clone(), self.config.system_ttl);
+        Ok((v6, v4))
+    }
+
+    /// Tenta cada servidor `attempts` vezes; NXDOMAIN encerra a busca
+    async fn query_servers(&self, host: &str, record: RecordType) -> Result<(Vec<IpAddr>, Duration)> {
+        let mut last_error = None;
+        for &server in &self.config.nameservers {
+            for _ in 0..self.config.attempts.max(1) {
+                let id = self.ids.hash_one(self.queries.fetch_add(1, Ordering::Relaxed)) as u16;
+                let query = encode_query(id, host, record)?;
+                let answer = match exchange(server, &query, self.config.query_timeout).await {
+                    Ok(packet) => parse_response(&packet, id, record),
+                    Err(e) => {
+                        last_error = Some(Error::network(format!("DNS query to {} for '{}' failed: {}", server, host, e)));
+                        continue;
+                    }
+                };
+                match answer {
+                    Some(answer) if answer.rcode == RCODE_NO_ERROR => {
+                        let ttl = match answer.records.iter().map(|&(_, ttl)| ttl).min() {
+                            Some(ttl) => Duration::from_secs(ttl.into()).clamp(self.config.min_ttl, self.config.max_ttl),
+                            None => self.config.negative_ttl,
+                        };
+                        return Ok((answer.records.into_iter().map(|(ip, _)| ip).collect(), ttl));
+                    }
+                    Some(answer) if answer.rcode == RCODE_NX_DOMAIN => return Ok((Vec::new(), self.config.negative_ttl)),
+                    // SERVFAIL, REFUSED...: o próximo servidor pode responder
+                    Some(answer) => {
+                        last_error = Some(Error::network(format!(
+                            "DNS server {} answered rcode {} for '{}'",
+                            server, answer.rcode, host
+                        )));
+                        break;
+                    }
+                    None => last_error = Some(Error::parse(format!("Malformed DNS response from {}", server))),
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::new(ErrorKind::InvalidState, "No DNS servers configured")))
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new(ResolverConfig::default())
+    }
+}
+
+fn ip_literal(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+// ============================================================================
+// PROTOCOLO (RFC 1035)
+// ============================================================================
+
+const RCODE_NO_ERROR: u8 = 0;
+const RCODE_NX_DOMAIN: u8 = 3;
+
+struct Answer {
+    rcode: u8,
+    /// Endereço e TTL (segundos)
+    records: Vec<(IpAddr, u32)>,
+}
+
+/// Consulta recursiva (RD) com uma pergunta
+fn encode_query(id: u16, host: &str, record: RecordType) -> Result<Vec<u8>> {
+    let invalid = || Error::new(ErrorKind::InvalidInput, format!("Invalid host name '{}'", host));
+    let mut packet = Vec::with_capacity(18 + host.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(invalid());
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    if packet.len() - 12 > 255 {
+        return Err(invalid());
+    }
+    packet.extend_from_slice(&record.code().to_be_bytes());
+    packet.extend_from_slice(&[0, 1]); // IN
+    Ok(packet)
+}
+
+/// Endereços do tipo pedido na seção de respostas
+///
+/// Registros CNAME da cadeia são pulados: o servidor recursivo já inclui os
+/// endereços do nome final. `None` para pacotes malformados ou de outra consulta.
+fn parse_response(packet: &[u8], id: u16, record: RecordType) -> Option<Answer> {
+    let u16_at = |pos: usize| Some(u16::from_be_bytes(packet.get(pos..pos + 2)?.try_into().ok()?));
+    if u16_at(0)? != id || packet.get(2)? & 0x80 == 0 {
+        return None;
+    }
+    let mut answer = Answer {
+        rcode: packet[3] & 0x0f,
+        records: Vec::new(),
+    };
+    let (questions, answers) = (u16_at(4)?, u16_at(6)?);
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(packet, pos)? + 4;
+    }
+    for _ in 0..answers {
+        pos = skip_name(packet, pos)?;
+        let (kind, class) = (u16_at(pos)?, u16_at(pos + 2)?);
+        let ttl = u32::from_be_bytes(packet.get(pos + 4..pos + 8)?.try_into().ok()?);
+        let len = u16_at(pos + 8)? as usize;
+        let data = packet.get(pos + 10..pos + 10 + len)?;
+        pos += 10 + len;
+        if kind != record.code() || class != 1 {
+            continue;
+        }
+        let ip = match (record, data.len()) {
+            (RecordType::A, 4) => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(data).ok()?)),
+            (RecordType::Aaaa, 16) => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data).ok()?)),
+            _ => return None,
+        };
+        // TTL com o bit mais alto ligado conta como 0 (RFC 2181, 8)
+        answer.records.push((ip, if ttl > i32::MAX as u32 { 0 } else { ttl }));
+    }
+    Some(answer)
+}
+
+/// Posição logo após um nome (rótulos ou ponteiro de compressão)
+fn skip_name(packet: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *packet.get(pos)?;
+        match len {
+            0 => return Some(pos + 1),
+            len if len & 0xc0 == 0xc0 => return Some(pos + 2),
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+/// Envia por UDP; resposta truncada repete a consulta em TCP
+async fn exchange(server: SocketAddr, query: &[u8], timeout: Duration) -> io::Result<Vec<u8>> {
+    let timed_out = || io::Error::new(io::ErrorKind::TimedOut, format!("no answer within {:?}", timeout));
+    let packet = tokio::time::timeout(timeout, exchange_udp(server, query))
+        .await
+        .map_err(|_| timed_out())??;
+    if packet.get(2).is_some_and(|flags| flags & 0x02 != 0) {
+        return tokio::time::timeout(timeout, exchange_tcp(server, query))
+            .await
+            .map_err(|_| timed_out())?;
+    }
+    Ok(packet)
+}
+
+async fn exchange_udp(server: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    let local: SocketAddr = if server.is_ipv6() {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(query).await?;
+    let mut buf = vec![0u8; MAX_UDP_RESPONSE];
+    loop {
+        let len = socket.recv(&mut buf).await?;
+        // Respostas atrasadas de outra consulta são descartadas
+        if buf[..len.min(2)] == query[..2] {
+            buf.truncate(len);
+            return Ok(buf);
+        }
+    }
+}
+
+/// Mensagens em TCP levam o tamanho em 2 bytes na frente (RFC 1035, 4.2.2)
+async fn exchange_tcp(server: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(server).await?;
+    let mut framed = (query.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(query);
+    stream.write_all(&framed).await?;
+    let len = stream.read_u16().await? as usize;
+    let mut packet = vec![0u8; len];
+    stream.read_exact(&mut packet).await?;
+    Ok(packet)
+}
+
+// ============================================================================
+// HAPPY EYEBALLS (RFC 8305)
+// ============================================================================
+
+/// Alterna as famílias, começando pela do primeiro endereço (IPv6 quando há)
+pub fn interleave(addrs: Vec<IpAddr>) -> Vec<IpAddr> {
+    let (v6, v4): (Vec<IpAddr>, Vec<IpAddr>) = addrs.into_iter().partition(IpAddr::is_ipv6);
+    let (first, second) = if v6.is_empty() { (v4, v6) } else { (v6, v4) };
+    let mut out = Vec::with_capacity(first.len() + second.len());
+    let (mut first, mut second) = (first.into_iter(), second.into_iter());
+    loop {
+        match (first.next(), second.next()) {
+            (None, None) => return out,
+            (a, b) => out.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
+/// Conecta ao primeiro de `addrs` que aceitar, nessa ordem de preferência
+///
+/// Uma nova tentativa começa a cada `attempt_delay` ou assim que a anterior
+/// falha; as que ainda estiverem em andamento são canceladas na primeira
+/// conexão estabelecida.
+pub async fn happy_eyeballs(addrs: Vec<SocketAddr>, attempt_delay: Duration) -> io::Result<TcpStream> {
+    let mut pending = addrs.into_iter();
+    let mut attempts = JoinSet::new();
+    let mut last_error = io::Error::new(io::ErrorKind::InvalidInput, "no addresses to connect to");
+
+    match pending.next() {
+        Some(addr) => attempts.spawn(TcpStream::connect(addr)),
+        None => return Err(last_error),
+    };
+    loop {
+        let more = pending.len() > 0;
+        tokio::select! {
+            finished = attempts.join_next() => match finished {
+                Some(Ok(Ok(stream))) => return Ok(stream),
+                Some(Ok(Err(e))) => last_error = e,
+                Some(Err(e)) => last_error = io::Error::other(e),
+                None => return Err(last_error),
+            },
+            _ = tokio::time::sleep(attempt_delay), if more => {}
+        }
+        // Intervalo esgotado ou tentativa falhou: começa a próxima
+        if let Some(addr) = pending.next() {
+            attempts.spawn(TcpStream::connect(addr));
+        } else if attempts.is_empty() {
+            return Err(last_error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Resposta a `query` com os endereços dados e um CNAME na frente
+    fn respond(query: &[u8], addrs: &[IpAddr], ttl: u32) -> Vec<u8> {
+        let mut packet = query.to_vec();
+        packet[2] = 0x81;
+        packet[3] = 0x80;
+        packet[7] = addrs.len() as u8 + 1;
+        // CNAME apontando para a própria pergunta (ponteiro 0xc00c)
+        packet.extend_from_slice(&[0xc0, 0x0c, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xc0, 0x0c]);
+        for ip in addrs {
+            let (kind, data) = match ip {
+                IpAddr::V4(ip) => (1u16, ip.octets().to_vec()),
+                IpAddr::V6(ip) => (28u16, ip.octets().to_vec()),
+            };
+            packet.extend_from_slice(&[0xc0, 0x0c]);
+            packet.extend_from_slice(&kind.to_be_bytes());
+            packet.extend_from_slice(&[0, 1]);
+            packet.extend_from_slice(&ttl.to_be_bytes());
+            packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            packet.extend_from_slice(&data);
+        }
+        packet
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let query = encode_query(0xbeef, "api.example.com", RecordType::A).unwrap();
+        assert_eq!(&query[..4], &[0xbe, 0xef, 0x01, 0x00]);
+        assert_eq!(&query[12..29], b"\x03api\x07example\x03com\x00");
+        assert_eq!(&query[29..], &[0, 1, 0, 1]);
+        assert!(encode_query(1, "a..b", RecordType::A).is_err());
+        assert!(encode_query(1, &"x".repeat(64), RecordType::A).is_err());
+
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+        let packet = respond(&query, &[ip], 300);
+        let answer = parse_response(&packet, 0xbeef, RecordType::A).unwrap();
+        assert_eq!(answer.rcode, 0);
+        assert_eq!(answer.records, [(ip, 300)]);
+        assert!(parse_response(&packet, 0xbeee, RecordType::A).is_none());
+        assert!(parse_response(&packet[..packet.len() - 1], 0xbeef, RecordType::A).is_none());
+        assert!(parse_response(&packet, 0xbeef, RecordType::Aaaa).unwrap().records.is_empty());
+
+        let config = ResolverConfig::from_resolv_conf("# local\nnameserver 10.0.0.2\nnameserver fe80::1%eth0\noptions timeout:1 attempts:3\n");
+        assert_eq!(config.nameservers, ["10.0.0.2:53".parse().unwrap()]);
+        assert_eq!((config.query_timeout, config.attempts), (Duration::from_secs(1), 3));
+
+        let order = interleave(["10.0.0.1", "10.0.0.2", "::1", "10.0.0.3", "::2"].map(|ip| ip.parse().unwrap()).to_vec());
+        let order: Vec<String> = order.iter().map(IpAddr::to_string).collect();
+        assert_eq!(order, ["::1", "10.0.0.1", "::2", "10.0.0.2", "10.0.0.3"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolver_caches_by_ttl() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            loop {
+                let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+                let query = &buf[..len];
+                // Só IPv4: AAAA volta sem registros
+                let addrs: Vec<IpAddr> = match query[len - 3] {
+                    1 => vec!["10.1.2.3".parse().unwrap()],
+                    _ => Vec::new(),
+                };
+                server.send_to(&respond(query, &addrs, 60), peer).await.unwrap();
+            }
+        });
+
+        let resolver = Resolver::new(ResolverConfig { nameservers: vec![addr], ..Default::default() });
+        let expected: Vec<IpAddr> = vec!["10.1.2.3".parse().unwrap()];
+        assert_eq!(resolver.lookup("Models.Example.com.").await.unwrap(), expected);
+        assert_eq!(resolver.queries_sent(), 2);
+        assert_eq!(resolver.lookup("models.example.com").await.unwrap(), expected);
+        assert_eq!(resolver.queries_sent(), 2);
+
+        assert_eq!(resolver.lookup("[::1]").await.unwrap(), ["::1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(resolver.lookup("localhost").await.unwrap().len(), 2);
+        assert_eq!(resolver.queries_sent(), 2);
+
+        resolver.clear_cache();
+        resolver.lookup("models.example.com").await.unwrap();
+        assert_eq!(resolver.queries_sent(), 4);
+
+        // Servidor mudo: esgota as tentativas dentro do timeout
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let resolver = Resolver::new(ResolverConfig {
+            nameservers: vec![silent.local_addr().unwrap()],
+            query_timeout: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let error = resolver.lookup("models.example.com").await.unwrap_err();
+        assert!(error.to_string().contains("no answer"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_happy_eyeballs_falls_back() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        let closed = {
+            let socket = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            socket.local_addr().unwrap()
+        };
+
+        let stream = happy_eyeballs(vec![closed, open], Duration::from_secs(10)).await.unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), open);
+        assert!(happy_eyeballs(vec![closed], DEFAULT_ATTEMPT_DELAY).await.is_err());
+        assert!(happy_eyeballs(Vec::new(), DEFAULT_ATTEMPT_DELAY).await.is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! HTTP/2 sobre uma conexão do pool (ALPN `h2` ou prior knowledge)
+//!
+//! Cada conexão tem uma task que faz todo o I/O; as requisições chegam por um
+//! canal, viram streams multiplexados e a resposta volta por um oneshot.
+
+use crate::pool::{Connection, H2Lease};
+use crate::{decode_content, Exchange, HeaderMap, Response, MAX_DECODED_BODY};
+use avila_error::{Error, Result};
+use avila_h2::{ErrorCode, Event, H2Error, Settings};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+
+/// Cabeçalhos proibidos em HTTP/2 (RFC 9113, seção 8.2.2)
+const CONNECTION_HEADERS: [&str; 6] = ["connection", "keep-alive", "proxy-connection", "transfer-encoding", "upgrade", "host"];
+
+struct H2Request {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    respond: oneshot::Sender<Result<Exchange>>,
+}
+
+/// Requisição enviada, aguardando a resposta completa
+struct InFlight {
+    respond: oneshot::Sender<Result<Exchange>>,
+    /// `None` até chegar a resposta final (respostas 1xx são ignoradas)
+    status: Option<u16>,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+/// Handle compartilhado de uma conexão HTTP/2
+#[derive(Clone)]
+pub(crate) struct H2Handle {
+    requests: mpsc::UnboundedSender<H2Request>,
+    going_away: Arc<AtomicBool>,
+}
+
+impl H2Handle {
+    /// Inicia a task da conexão; `lease` é liberado quando ela termina
+    ///
+    /// Sem `idle_timeout`, a conexão termina quando todos os handles forem descartados.
+    pub(crate) fn spawn(io: BufReader<Connection>, lease: H2Lease, idle_timeout: Option<Duration>) -> Self {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        let going_away = Arc::new(AtomicBool::new(false));
+        let driver = Driver {
+            io,
+            connection: avila_h2::Connection::client(Settings::default()),
+            requests: receiver,
+            waiting: VecDeque::new(),
+            in_flight: HashMap::new(),
+            going_away: Arc::clone(&going_away),
+        };
+        tokio::spawn(async move {
+            driver.run(idle_timeout).await;
+            drop(lease);
+        });
+        Self { requests, going_away }
+    }
+
+    /// Se ainda aceita requisições novas
+    pub(crate) fn is_usable(&self) -> bool {
+        !self.requests.is_closed() && !self.going_away.load(Ordering::Acquire)
+    }
+
+    /// Envia a requisição num stream novo
+    ///
+    /// `Exchange::Closed` indica que o servidor não a processou (conexão
+    /// encerrada ou stream recusado) e que ela pode ser repetida.
+    pub(crate) async fn send(&self, headers: Vec<(String, String)>, body: Vec<u8>) -> Result<Exchange> {
+        let (respond, response) = oneshot::channel();
+        let request = H2Request { headers, body, respond };
+        if self.requests.send(request).is_err() {
+            return Ok(Exchange::Closed(Error::network("HTTP/2 connection closed")));
+        }
+        response
+            .await
+            .unwrap_or_else(|_| Ok(Exchange::Closed(Error::network("HTTP/2 connection closed"))))
+    }
+}
+
+struct Driver {
+    io: BufReader<Connection>,
+    connection: avila_h2::Connection,
+    requests: mpsc::UnboundedReceiver<H2Request>,
+    /// Requisições aguardando o limite de streams do servidor
+    waiting: VecDeque<H2Request>,
+    in_flight: HashMap<u32, InFlight>,
+    going_away: Arc<AtomicBool>,
+}
+
+impl Driver {
+    async fn run(mut self, idle_timeout: Option<Duration>) {
+        let mut buf = vec![0u8; 32 * 1024];
+        let mut accepting = true;
+        let result = loop {
+            self.start_waiting();
+            if self.connection.has_output() {
+                let output = self.connection.take_output();
+                if let Err(e) = self.io.get_mut().write_all(&output).await {
+                    break Err(Error::network(format!("Failed to write HTTP/2 frames: {}", e)));
+                }
+            }
+            let idle = self.in_flight.is_empty() && self.waiting.is_empty();
+            if idle && (!accepting || self.connection.is_going_away()) {
+                break Ok(());
+            }
+
+            tokio::select! {
+                read = self.io.read(&mut buf) => match read {
+                    Ok(0) => break Err(Error::network("HTTP/2 connection closed by server")),
+                    Ok(n) => {
+                        if let Err(e) = self.connection.receive(&buf[..n]) {
+                            let _ = self.io.get_mut().write_all(&self.connection.take_output()).await;
+                            break Err(h2_error(e));
+                        }
+                        self.handle_events();
+                    }
+                    Err(e) => break Err(Error::network(format!("Failed to read HTTP/2 frames: {}", e))),
+                },
+                request = self.requests.recv(), if accepting => match request {
+                    Some(request) => self.waiting.push_back(request),
+                    None => accepting = false,
+                },
+                _ = sleep_or_pending(idle_timeout), if idle => {
+                    self.connection.go_away(ErrorCode::NoError);
+                    let _ = self.io.get_mut().write_all(&self.connection.take_output()).await;
+                    break Ok(());
+                }
+            }
+        };
+
+        self.going_away.store(true, Ordering::Release);
+        self.requests.close();
+        let err = result.err().unwrap_or_else(|| Error::network("HTTP/2 connection closed"));
+        for (_, request) in self.in_flight.drain() {
+            let _ = request.respond.send(Err(Error::network(err.to_string())));
+        }
+        // Nunca enviadas: podem ir por outra conexão
+        while let Some(request) = self.waiting.pop_front().or_else(|| self.requests.try_recv().ok()) {
+            let _ = request.respond.send(Ok(Exchange::Closed(Error::network(err.to_string()))));
+        }
+        let _ = self.io.get_mut().shutdown().await;
+    }
+
+    /// Abre streams para as requisições em espera, dentro do limite do servidor
+    fn start_waiting(&mut self) {
+        while self.connection.can_open_stream() {
+            let Some(request) = self.waiting.pop_front() else {
+                return;
+            };
+            let end_stream = request.body.is_empty();
+            let stream = match self.connection.send_request(&request.headers, end_stream) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = request.respond.send(Err(h2_error(e)));
+                    continue;
+                }
+            };
+            if !end_stream {
+                // O stream acabou de ser aberto; enviar não falha
+                let _ = self.connection.send_data(stream, &request.body, true);
+            }
+            self.in_flight.insert(
+                stream,
+                InFlight {
+                    respond: request.respond,
+                    status: None,
+                    headers: HeaderMap::new(),
+                    body: Vec::new(),
+                },
+            );
+        }
+    }
+
+    fn handle_events(&mut self) {
+        while let Some(event) = self.connection.poll_event() {
+            match event {
+                Event::Headers { stream, headers, end_stream } => {
+                    let Some(request) = self.in_flight.get_mut(&stream) else {
+                        continue;
+                    };
+                    if request.status.is_none() {
+                        let status = headers
+                            .iter()
+                            .find(|(name, _)| name == ":status")
+                            .and_then(|(_, value)| value.parse::<u16>().ok());
+                        match status {
+                            Some(status) if (100..200).contains(&status) => continue,
+                            Some(status) => request.status = Some(status),
+                            None => {
+                                self.fail(stream, Error::parse("HTTP/2 response without :status"));
+                                continue;
+                            }
+                        }
+                    }
+                    // Na resposta final ou nos trailers
+                    for (name, value) in headers {
+                        if !name.starts_with(':') {
+                            request.headers.append(name, value);
+                        }
+                    }
+                    if end_stream {
+                        self.complete(stream);
+                    }
+                }
+                Event::Data { stream, data, end_stream } => {
+                    let Some(request) = self.in_flight.get_mut(&stream) else {
+                        continue;
+                    };
+                    if request.body.len() + data.len() > MAX_DECODED_BODY {
+                        self.fail(stream, Error::parse("Response body exceeds the size limit"));
+                        continue;
+                    }
+                    request.body.extend_from_slice(&data);
+                    if end_stream {
+                        self.complete(stream);
+                    }
+                }
+                Event::Reset { stream, code } => {
+                    let Some(request) = self.in_flight.remove(&stream) else {
+                        continue;
+                    };
+                    let err = Error::network(format!("HTTP/2 stream reset by server ({:?})", code));
+                    let result = match code {
+                        ErrorCode::RefusedStream => Ok(Exchange::Closed(err)),
+                        _ => Err(err),
+                    };
+                    let _ = request.respond.send(result);
+                }
+                Event::GoAway { .. } => self.going_away.store(true, Ordering::Release),
+            }
+        }
+    }
+
+    fn complete(&mut self, stream: u32) {
+        let Some(request) = self.in_flight.remove(&stream) else {
+            return;
+        };
+        let mut headers = request.headers;
+        let result = decode_content(&mut headers, request.body).map(|body| {
+            let response = Response {
+                status: request.status.unwrap_or_default(),
+                headers,
+                body,
+                url: String::new(),
+            };
+            Exchange::Done(response, true)
+        });
+        let _ = request.respond.send(result);
+    }
+
+    fn fail(&mut self, stream: u32, err: Error) {
+        self.connection.reset_stream(stream, ErrorCode::Cancel);
+        if let Some(request) = self.in_flight.remove(&stream) {
+            let _ = request.respond.send(Err(err));
+        }
+    }
+}
+
+async fn sleep_or_pending(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending().await,
+    }
+}
+
+fn h2_error(err: H2Error) -> Error {
+    Error::network(format!("HTTP/2 error: {}", err))
+}
+
+/// Cabeçalhos HTTP/2: pseudo-cabeçalhos primeiro, nomes em minúsculas
+pub(crate) fn request_headers<'a>(
+    method: &str,
+    scheme: &str,
+    authority: &str,
+    path: &str,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(String, String)> {
+    let mut out = vec![
+        (":method".to_string(), method.to_string()),
+        (":scheme".to_string(), scheme.to_string()),
+        (":authority".to_string(), authority.to_string()),
+        (":path".to_string(), path.to_string()),
+    ];
+    for (name, value) in headers {
+        let name = name.to_ascii_lowercase();
+        // `te` só pode levar "trailers"
+        if CONNECTION_HEADERS.contains(&name.as_str()) || (name == "te" && !value.eq_ignore_ascii_case("trailers")) {
+            continue;
+        }
+        out.push((name, value.to_string()));
+    }
+    out
+}
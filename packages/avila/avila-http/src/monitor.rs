@@ -0,0 +1,76 @@
+//! Métricas do pool de conexões no avila-monitor (feature `monitor`)
+
+use crate::PoolMetrics;
+use avila_monitor::Monitor;
+use std::sync::{Arc, Mutex};
+
+const METRICS: [(&str, &str, &str); 7] = [
+    ("http.pool.opened", "connections", "Conexões TCP abertas"),
+    ("http.pool.reused", "requests", "Requisições em conexões reaproveitadas"),
+    ("http.pool.expired", "connections", "Conexões ociosas fechadas pelo idle timeout"),
+    ("http.pool.discarded", "connections", "Conexões fechadas após o uso"),
+    ("http.pool.waits", "requests", "Requisições que esperaram o limite por host"),
+    ("http.pool.idle", "connections", "Conexões ociosas"),
+    ("http.pool.in_use", "connections", "Conexões em uso"),
+];
+
+/// Publica [`PoolMetrics`] no [`Monitor`] compartilhado
+///
+/// Usa sete métricas consecutivas a partir de `base_metric_id`, na ordem de
+/// `PoolMetrics`: opened, reused, expired, discarded, waits, idle, in_use.
+pub(crate) struct PoolMonitor {
+    monitor: Arc<Mutex<Monitor>>,
+    base_metric_id: u64,
+}
+
+impl PoolMonitor {
+    pub(crate) fn new(monitor: Arc<Mutex<Monitor>>, base_metric_id: u64) -> Self {
+        if let Ok(mut guard) = monitor.lock() {
+            for (offset, (name, unit, description)) in METRICS.into_iter().enumerate() {
+                guard.set_metadata(base_metric_id + offset as u64, name, unit, description);
+            }
+        }
+        Self { monitor, base_metric_id }
+    }
+
+    pub(crate) fn publish(&self, metrics: &PoolMetrics) {
+        let values = [
+            metrics.opened,
+            metrics.reused,
+            metrics.expired,
+            metrics.discarded,
+            metrics.waits,
+            metrics.idle,
+            metrics.in_use,
+        ];
+        if let Ok(mut monitor) = self.monitor.lock() {
+            for (offset, value) in values.into_iter().enumerate() {
+                monitor.record(self.base_metric_id + offset as u64, value as f64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_pool_metrics() {
+        let monitor = Arc::new(Mutex::new(Monitor::new()));
+        let pool_monitor = PoolMonitor::new(Arc::clone(&monitor), 100);
+        pool_monitor.publish(&PoolMetrics {
+            opened: 2,
+            reused: 5,
+            idle: 1,
+            ..PoolMetrics::default()
+        });
+
+        let monitor = monitor.lock().unwrap();
+        assert_eq!(monitor.get_metadata(101).unwrap().name, "http.pool.reused");
+        assert_eq!(monitor.get(100), Some(2.0));
+        assert_eq!(monitor.get(101), Some(5.0));
+        assert_eq!(monitor.get(105), Some(1.0));
+        assert_eq!(monitor.get(106), Some(0.0));
+    }
+}
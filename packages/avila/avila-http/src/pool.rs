@@ -0,0 +1,421 @@
+//! Pool de conexões keep-alive por `esquema://host:porta`
+
+use crate::http2::H2Handle;
+use crate::tls_stream::TlsStream;
+use crate::Scheme;
+use std::collections::HashMap;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
+use tokio::net::TcpStream;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+/// Tempo padrão que uma conexão ociosa fica disponível para reuso
+pub(crate) const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Conexões simultâneas padrão por host (ociosas + em uso)
+pub(crate) const DEFAULT_MAX_PER_HOST: usize = 32;
+
+// ============================================================================
+// Transporte
+// ============================================================================
+
+/// Socket de uma conexão: TCP puro ou TLS
+pub(crate) enum Connection {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl Connection {
+    /// O servidor escolheu `h2` no ALPN
+    pub(crate) fn negotiated_h2(&self) -> bool {
+        match self {
+            Connection::Plain(_) => false,
+            Connection::Tls(stream) => stream.alpn_protocol() == Some(avila_h2::ALPN.as_bytes()),
+        }
+    }
+}
+
+impl AsyncRead for Connection {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Connection::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Connection {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Connection::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Connection::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Connection::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PoolKey {
+    pub(crate) scheme: Scheme,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+}
+
+// ============================================================================
+// Métricas
+// ============================================================================
+
+/// Contadores e ocupação do pool de conexões
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// Conexões TCP abertas
+    pub opened: u64,
+    /// Requisições atendidas por uma conexão já aberta
+    pub reused: u64,
+    /// Conexões ociosas fechadas pelo idle timeout
+    pub expired: u64,
+    /// Conexões fechadas após o uso (`Connection: close`, erro, corpo sem tamanho)
+    pub discarded: u64,
+    /// Requisições que esperaram pelo limite de conexões do host
+    pub waits: u64,
+    /// Conexões ociosas no momento
+    pub idle: u64,
+    /// Conexões em uso no momento
+    pub in_use: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    opened: AtomicU64,
+    reused: AtomicU64,
+    expired: AtomicU64,
+    discarded: AtomicU64,
+    waits: AtomicU64,
+    idle: AtomicU64,
+    in_use: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> PoolMetrics {
+        PoolMetrics {
+            opened: self.opened.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            discarded: self.discarded.load(Ordering::Relaxed),
+            waits: self.waits.load(Ordering::Relaxed),
+            idle: self.idle.load(Ordering::Relaxed),
+            in_use: self.in_use.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn drop_one(counter: &AtomicU64) {
+    counter.fetch_sub(1, Ordering::Relaxed);
+}
+
+// ============================================================================
+// Pool
+// ============================================================================
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PoolConfig {
+    /// `Duration::ZERO` desativa o reuso (toda requisição usa `Connection: close`)
+    pub(crate) idle_timeout: Duration,
+    pub(crate) max_per_host: usize,
+}
+
+struct IdleConnection {
+    io: BufReader<Connection>,
+    permit: OwnedSemaphorePermit,
+    since: Instant,
+}
+
+struct Host {
+    /// Mais recente no fim (reuso LIFO mantém as conexões quentes)
+    idle: Vec<IdleConnection>,
+    /// Conexões abertas (ociosas + em uso); as ociosas guardam sua permissão
+    limit: Arc<Semaphore>,
+    /// Acorda quem espera o limite quando uma conexão volta ou é fechada
+    available: Arc<Notify>,
+    /// Conexão HTTP/2 compartilhada por todas as requisições ao host
+    h2: Option<H2Handle>,
+}
+
+pub(crate) struct Pool {
+    config: PoolConfig,
+    hosts: Mutex<HashMap<PoolKey, Host>>,
+    counters: Counters,
+    #[cfg(feature = "monitor")]
+    monitor: Option<crate::monitor::PoolMonitor>,
+}
+
+/// Resultado do checkout: conexão reaproveitada ou permissão para abrir outra
+pub(crate) enum Checkout {
+    Reuse(PooledConnection),
+    /// Conexão HTTP/2 já aberta; a requisição vira mais um stream
+    Shared(H2Handle),
+    Open(OwnedSemaphorePermit),
+}
+
+impl Pool {
+    pub(crate) fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+            counters: Counters::default(),
+            #[cfg(feature = "monitor")]
+            monitor: None,
+        }
+    }
+
+    #[cfg(feature = "monitor")]
+    pub(crate) fn with_monitor(mut self, monitor: Option<crate::monitor::PoolMonitor>) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    fn max_permits(&self) -> usize {
+        self.config.max_per_host.clamp(1, Semaphore::MAX_PERMITS)
+    }
+
+    pub(crate) fn keep_alive(&self) -> bool {
+        !self.config.idle_timeout.is_zero()
+    }
+
+    pub(crate) fn metrics(&self) -> PoolMetrics {
+        self.counters.snapshot()
+    }
+
+    fn hosts(&self) -> MutexGuard<'_, HashMap<PoolKey, Host>> {
+        self.hosts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn publish(&self) {
+        #[cfg(feature = "monitor")]
+        if let Some(monitor) = &self.monitor {
+            monitor.publish(&self.counters.snapshot());
+        }
+    }
+
+    /// Conexão ociosa válida, ou permissão para abrir uma nova dentro do limite do host
+    pub(crate) async fn checkout(self: &Arc<Self>, key: &PoolKey) -> Checkout {
+        let mut waited = false;
+        loop {
+            let (limit, available) = {
+                let mut hosts = self.hosts();
+                self.evict_expired(&mut hosts);
+                let host = hosts.entry(key.clone()).or_insert_with(|| Host {
+                    idle: Vec::new(),
+                    limit: Arc::new(Semaphore::new(self.max_permits())),
+                    available: Arc::new(Notify::new()),
+                    h2: None,
+                });
+                match &host.h2 {
+                    Some(h2) if h2.is_usable() => {
+                        bump(&self.counters.reused);
+                        self.publish();
+                        return Checkout::Shared(h2.clone());
+                    }
+                    Some(_) => host.h2 = None,
+                    None => {}
+                }
+                if let Some(idle) = host.idle.pop() {
+                    return Checkout::Reuse(self.reuse(key, idle, &host.available));
+                }
+                (Arc::clone(&host.limit), Arc::clone(&host.available))
+            };
+
+            if let Ok(permit) = limit.try_acquire_owned() {
+                return Checkout::Open(permit);
+            }
+            if !waited {
+                waited = true;
+                bump(&self.counters.waits);
+                self.publish();
+            }
+            // `notify_one` guarda o aviso se ninguém estiver esperando ainda
+            available.notified().await;
+        }
+    }
+
+    fn reuse(self: &Arc<Self>, key: &PoolKey, idle: IdleConnection, available: &Arc<Notify>) -> PooledConnection {
+        drop_one(&self.counters.idle);
+        bump(&self.counters.reused);
+        bump(&self.counters.in_use);
+        self.publish();
+        PooledConnection {
+            io: Some(idle.io),
+            permit: Some(idle.permit),
+            key: key.clone(),
+            available: Arc::clone(available),
+            pool: Arc::clone(self),
+        }
+    }
+
+    /// Registra uma conexão recém-aberta
+    pub(crate) fn connected(
+        self: &Arc<Self>,
+        key: &PoolKey,
+        connection: Connection,
+        permit: OwnedSemaphorePermit,
+    ) -> PooledConnection {
+        let available = self.hosts().get(key).map(|host| Arc::clone(&host.available)).unwrap_or_default();
+        bump(&self.counters.opened);
+        bump(&self.counters.in_use);
+        self.publish();
+        PooledConnection {
+            io: Some(BufReader::new(connection)),
+            permit: Some(permit),
+            key: key.clone(),
+            available,
+            pool: Arc::clone(self),
+        }
+    }
+
+    /// Registra uma conexão recém-aberta que negociou HTTP/2
+    ///
+    /// A conexão ocupa a permissão do host enquanto estiver aberta e, com
+    /// keep-alive, passa a atender as próximas requisições ao host.
+    pub(crate) fn connected_h2(
+        self: &Arc<Self>,
+        key: &PoolKey,
+        connection: Connection,
+        permit: OwnedSemaphorePermit,
+    ) -> H2Handle {
+        let available = self.hosts().get(key).map(|host| Arc::clone(&host.available)).unwrap_or_default();
+        bump(&self.counters.opened);
+        bump(&self.counters.in_use);
+        self.publish();
+        let lease = H2Lease {
+            permit: Some(permit),
+            available: Arc::clone(&available),
+            pool: Arc::clone(self),
+        };
+        let idle_timeout = self.keep_alive().then_some(self.config.idle_timeout);
+        let handle = H2Handle::spawn(BufReader::new(connection), lease, idle_timeout);
+        if self.keep_alive() {
+            if let Some(host) = self.hosts().get_mut(key) {
+                host.h2 = Some(handle.clone());
+            }
+            // Quem esperava o limite do host pode usar esta conexão
+            available.notify_waiters();
+            available.notify_one();
+        }
+        handle
+    }
+
+    /// Fecha as conexões ociosas além do idle timeout (de todos os hosts)
+    fn evict_expired(&self, hosts: &mut HashMap<PoolKey, Host>) {
+        let timeout = self.config.idle_timeout;
+        let mut expired = 0;
+        for host in hosts.values_mut() {
+            let before = host.idle.len();
+            host.idle.retain(|idle| idle.since.elapsed() < timeout);
+            expired += before - host.idle.len();
+        }
+        // Hosts sem conexão alguma não precisam ocupar o mapa
+        hosts.retain(|_, host| !host.idle.is_empty() || host.limit.available_permits() < self.max_permits());
+        if expired > 0 {
+            self.counters.expired.fetch_add(expired as u64, Ordering::Relaxed);
+            self.counters.idle.fetch_sub(expired as u64, Ordering::Relaxed);
+            self.publish();
+        }
+    }
+}
+
+/// Conexão emprestada do pool; volta com [`PooledConnection::release`] ou é fechada no drop
+pub(crate) struct PooledConnection {
+    io: Option<BufReader<Connection>>,
+    permit: Option<OwnedSemaphorePermit>,
+    key: PoolKey,
+    available: Arc<Notify>,
+    pool: Arc<Pool>,
+}
+
+impl PooledConnection {
+    pub(crate) fn io(&mut self) -> &mut BufReader<Connection> {
+        self.io.as_mut().expect("connection already released")
+    }
+
+    /// Devolve a conexão para reuso, ou a fecha se `reusable` for falso
+    pub(crate) fn release(mut self, reusable: bool) {
+        // Bytes sobrando indicam uma resposta mal delimitada
+        let clean = self.io.as_ref().is_some_and(|io| io.buffer().is_empty());
+        if !(reusable && clean && self.pool.keep_alive()) {
+            return;
+        }
+
+        let (Some(io), Some(permit)) = (self.io.take(), self.permit.take()) else {
+            return;
+        };
+        let pool = Arc::clone(&self.pool);
+        drop_one(&pool.counters.in_use);
+        match pool.hosts().get_mut(&self.key) {
+            Some(host) => {
+                host.idle.push(IdleConnection {
+                    io,
+                    permit,
+                    since: Instant::now(),
+                });
+                bump(&pool.counters.idle);
+            }
+            None => {
+                drop(permit);
+                bump(&pool.counters.discarded);
+            }
+        }
+        pool.publish();
+        self.available.notify_one();
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if self.io.take().is_some() {
+            drop_one(&self.pool.counters.in_use);
+            bump(&self.pool.counters.discarded);
+            self.pool.publish();
+            drop(self.permit.take());
+            self.available.notify_one();
+        }
+    }
+}
+
+/// Permissão de uma conexão HTTP/2, liberada quando a task da conexão termina
+pub(crate) struct H2Lease {
+    permit: Option<OwnedSemaphorePermit>,
+    available: Arc<Notify>,
+    pool: Arc<Pool>,
+}
+
+impl Drop for H2Lease {
+    fn drop(&mut self) {
+        drop_one(&self.pool.counters.in_use);
+        bump(&self.pool.counters.discarded);
+        self.pool.publish();
+        drop(self.permit.take());
+        self.available.notify_one();
+    }
+}
@@ -0,0 +1,122 @@
+//! Stream TLS assíncrono para `https://` (avila-tls sobre tokio)
+
+use avila_tls::{ClientConfig, ClientConnection, TlsError};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+pub(crate) struct TlsStream<S> {
+    conn: ClientConnection,
+    sock: S,
+    /// Registros cifrados ainda não aceitos pelo socket
+    pending: Vec<u8>,
+    shutdown_sent: bool,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> TlsStream<S> {
+    /// Executa o handshake completo sobre `sock`
+    pub(crate) async fn connect(config: Arc<ClientConfig>, server_name: &str, mut sock: S) -> Result<Self, TlsError> {
+        let mut conn = ClientConnection::new(config, server_name)?;
+        let mut buf = vec![0u8; 16 * 1024];
+        while conn.is_handshaking() {
+            sock.write_all(&conn.take_tls_output()).await?;
+            let n = sock.read(&mut buf).await?;
+            if n == 0 {
+                return Err(TlsError::Handshake("connection closed during handshake".into()));
+            }
+            let result = conn.read_tls(&buf[..n]);
+            if conn.wants_write() {
+                // Alerta de falha é best-effort
+                let _ = sock.write_all(&conn.take_tls_output()).await;
+            }
+            result?;
+        }
+        Ok(Self {
+            conn,
+            sock,
+            pending: Vec::new(),
+            shutdown_sent: false,
+        })
+    }
+
+    /// Protocolo ALPN aceito pelo servidor
+    pub(crate) fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.conn.alpn_protocol()
+    }
+
+    /// Envia o que estiver pendente; `Pending` se o socket não aceitou tudo
+    fn poll_flush_tls(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.pending.extend_from_slice(&self.conn.take_tls_output());
+        while !self.pending.is_empty() {
+            let n = ready!(Pin::new(&mut self.sock).poll_write(cx, &self.pending))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pending.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsStream<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let mut chunk = [0u8; 16 * 1024];
+        loop {
+            if this.conn.has_plaintext() {
+                let n = this.conn.read_plaintext(buf.initialize_unfilled());
+                buf.advance(n);
+                return Poll::Ready(Ok(()));
+            }
+            if this.conn.peer_closed() {
+                return Poll::Ready(Ok(()));
+            }
+            // Respostas a KeyUpdate saem junto com a leitura
+            if let Poll::Ready(Err(err)) = this.poll_flush_tls(cx) {
+                return Poll::Ready(Err(err));
+            }
+
+            let mut read_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.sock).poll_read(cx, &mut read_buf))?;
+            if read_buf.filled().is_empty() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "peer closed connection without close_notify",
+                )));
+            }
+            if let Err(err) = this.conn.read_tls(read_buf.filled()) {
+                let _ = this.poll_flush_tls(cx);
+                return Poll::Ready(Err(err.into()));
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsStream<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_flush_tls(cx))?;
+        this.conn.write_plaintext(buf)?;
+        // Os registros ficam em `pending` se o socket não aceitar agora
+        let _ = this.poll_flush_tls(cx)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_flush_tls(cx))?;
+        Pin::new(&mut this.sock).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.shutdown_sent {
+            this.conn.send_close_notify();
+            this.shutdown_sent = true;
+        }
+        ready!(this.poll_flush_tls(cx))?;
+        Pin::new(&mut this.sock).poll_shutdown(cx)
+    }
+}
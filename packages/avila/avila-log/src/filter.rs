@@ -0,0 +1,158 @@
+//! Filtro de níveis por módulo
+
+use crate::Level;
+use std::fmt;
+
+/// Nível mínimo padrão mais exceções por prefixo de módulo
+///
+/// O prefixo mais longo que casa com o `target` do registro vence; `None`
+/// silencia o módulo por completo.
+///
+/// ```
+/// use avila_log::{Filter, Level};
+///
+/// let filter = Filter::parse("info,avila_http=debug,avila_http::pool=off").unwrap();
+/// assert!(filter.enabled(Level::Debug, "avila_http::client"));
+/// assert!(!filter.enabled(Level::Error, "avila_http::pool"));
+/// assert!(!filter.enabled(Level::Debug, "avila_webframework"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    directives: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// Aceita tudo a partir de `level`
+    pub fn new(level: Level) -> Self {
+        Self {
+            default: Some(level),
+            directives: Vec::new(),
+        }
+    }
+
+    /// Rejeita tudo que não tiver uma diretiva própria
+    pub fn off() -> Self {
+        Self {
+            default: None,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Define o nível mínimo de um módulo e seus submódulos
+    pub fn with_module(self, prefix: &str, level: Level) -> Self {
+        self.directive(prefix, Some(level))
+    }
+
+    /// Silencia um módulo e seus submódulos
+    pub fn silence(self, prefix: &str) -> Self {
+        self.directive(prefix, None)
+    }
+
+    /// Interpreta `"info,avila_http=debug,avila_async=off"`
+    ///
+    /// Um item sem `=` define o nível padrão.
+    pub fn parse(spec: &str) -> Result<Self, ParseLevelError> {
+        let mut filter = Filter::new(Level::Info);
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match item.split_once('=') {
+                Some((module, level)) => {
+                    filter = filter.directive(module.trim(), parse_level_filter(level.trim())?);
+                }
+                None => filter.default = parse_level_filter(item)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Lê a especificação da variável `AVILA_LOG`, com `fallback` se ausente ou inválida
+    pub fn from_env(fallback: Level) -> Self {
+        std::env::var("AVILA_LOG")
+            .ok()
+            .and_then(|spec| Filter::parse(&spec).ok())
+            .unwrap_or_else(|| Filter::new(fallback))
+    }
+
+    /// Verifica se um registro de `level` vindo de `target` passa
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let threshold = self
+            .directives
+            .iter()
+            .find(|(prefix, _)| matches_module(target, prefix))
+            .map_or(self.default, |(_, level)| *level);
+        threshold.is_some_and(|min| level >= min)
+    }
+
+    fn directive(mut self, prefix: &str, level: Option<Level>) -> Self {
+        self.directives.retain(|(existing, _)| existing != prefix);
+        self.directives.push((prefix.to_string(), level));
+        // Longest prefix first, so the first match is the most specific one
+        self.directives.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new(Level::Info)
+    }
+}
+
+fn matches_module(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+fn parse_level_filter(s: &str) -> Result<Option<Level>, ParseLevelError> {
+    if s.eq_ignore_ascii_case("off") {
+        Ok(None)
+    } else {
+        s.parse().map(Some)
+    }
+}
+
+/// Nível de log desconhecido
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(pub(crate) String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown log level: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let filter = Filter::new(Level::Warn)
+            .with_module("avila_http", Level::Debug)
+            .with_module("avila_http::pool", Level::Error);
+
+        assert!(filter.enabled(Level::Debug, "avila_http"));
+        assert!(filter.enabled(Level::Debug, "avila_http::client"));
+        assert!(!filter.enabled(Level::Warn, "avila_http::pool::idle"));
+        // A shared prefix that is not a module boundary does not match
+        assert!(!filter.enabled(Level::Debug, "avila_https"));
+        assert!(filter.enabled(Level::Warn, ""));
+    }
+
+    #[test]
+    fn test_parse() {
+        let filter = Filter::parse(" debug , avila_async=OFF,avila_bim=trace ").unwrap();
+        assert!(filter.enabled(Level::Debug, "vizzio_convert"));
+        assert!(!filter.enabled(Level::Error, "avila_async::runtime"));
+        assert!(filter.enabled(Level::Trace, "avila_bim::ifc_parser"));
+
+        assert!(Filter::parse("off").unwrap() == Filter::off());
+        assert_eq!(
+            Filter::parse("info,avila_http=loud").unwrap_err().to_string(),
+            "Unknown log level: \"loud\""
+        );
+    }
+}
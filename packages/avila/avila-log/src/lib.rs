@@ -1,9 +1,39 @@
 //! Avila Tracing - Sistema de logging nativo
 //! Substitui tracing/tracing-subscriber
-
-use std::sync::Mutex;
-
-static LOGGER: Mutex<Option<Box<dyn Logger + Send>>> = Mutex::new(None);
+//!
+//! Registros têm nível, módulo de origem (`target`), mensagem e campos
+//! chave-valor. Um [`Dispatch`] global aplica o [`Filter`] por módulo e
+//! repassa o que passar para os [`Sink`]s: [`StderrSink`], [`JsonLinesSink`]
+//! e, com a feature `monitor`, `MonitorSink` (contadores no avila-monitor).
+//!
+//! ```no_run
+//! use avila_log::{Dispatch, Filter, JsonLinesSink, Level, StderrSink};
+//!
+//! Dispatch::new(Filter::from_env(Level::Info))
+//!     .with_sink(StderrSink::new())
+//!     .with_sink(JsonLinesSink::open("vizzio.log.jsonl").unwrap())
+//!     .install();
+//!
+//! let addr = "0.0.0.0:8080";
+//! avila_log::info!(addr = %addr, workers = 4, "Server running");
+//! avila_log::warn!("Retrying in {} ms", 250);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+mod filter;
+#[cfg(feature = "monitor")]
+mod monitor;
+mod sink;
+
+pub use filter::{Filter, ParseLevelError};
+#[cfg(feature = "monitor")]
+pub use monitor::MonitorSink;
+pub use sink::{JsonLinesSink, Sink, StderrSink};
+
+static DISPATCH: RwLock<Option<Arc<Dispatch>>> = RwLock::new(None);
 
 pub trait Logger {
     fn log(&self, level: Level, message: &str);
@@ -40,6 +70,158 @@ impl Level {
     }
 }
 
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Level::Trace, Level::Debug, Level::Info, Level::Warn, Level::Error]
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseLevelError(s.to_string()))
+    }
+}
+
+// ============================================================================
+// Registros e despacho
+// ============================================================================
+
+/// Um evento de log: mensagem formatada sob demanda mais campos estruturados
+#[derive(Clone, Copy)]
+pub struct Record<'a> {
+    level: Level,
+    target: &'a str,
+    message: fmt::Arguments<'a>,
+    fields: &'a [(&'a str, &'a dyn fmt::Display)],
+}
+
+impl<'a> Record<'a> {
+    pub fn new(
+        level: Level,
+        target: &'a str,
+        message: fmt::Arguments<'a>,
+        fields: &'a [(&'a str, &'a dyn fmt::Display)],
+    ) -> Self {
+        Self {
+            level,
+            target,
+            message,
+            fields,
+        }
+    }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Caminho do módulo que emitiu o registro (`module_path!()`)
+    pub fn target(&self) -> &'a str {
+        self.target
+    }
+
+    pub fn message(&self) -> fmt::Arguments<'a> {
+        self.message
+    }
+
+    pub fn fields(&self) -> &'a [(&'a str, &'a dyn fmt::Display)] {
+        self.fields
+    }
+}
+
+/// Mensagem seguida de `chave=valor` para cada campo
+impl fmt::Display for Record<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        for (key, value) in self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Filtro mais lista de sinks
+///
+/// Pode ser usado localmente ou instalado como destino global dos macros.
+pub struct Dispatch {
+    filter: Filter,
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Dispatch {
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            sinks: Vec::new(),
+        }
+    }
+
+    pub fn with_sink(mut self, sink: impl Sink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        self.filter.enabled(level, target)
+    }
+
+    /// Entrega o registro a todos os sinks, se o filtro permitir
+    pub fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.level, record.target) {
+            for sink in &self.sinks {
+                sink.write(record);
+            }
+        }
+    }
+
+    pub fn flush(&self) {
+        for sink in &self.sinks {
+            sink.flush();
+        }
+    }
+
+    /// Substitui o despacho global usado pelos macros
+    pub fn install(self) {
+        *DISPATCH.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(self));
+    }
+}
+
+fn current() -> Option<Arc<Dispatch>> {
+    DISPATCH.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Verifica se o despacho global aceitaria o registro
+///
+/// Sem despacho instalado tudo passa (e vai para stderr).
+pub fn enabled(level: Level, target: &str) -> bool {
+    current().is_none_or(|dispatch| dispatch.enabled(level, target))
+}
+
+/// Entrega um registro ao despacho global
+pub fn log_record(record: &Record<'_>) {
+    // The lock is released before the sinks run, so a sink may log itself
+    match current() {
+        Some(dispatch) => dispatch.log(record),
+        // Fallback: print to stderr if no logger initialized
+        None => eprintln!("[{}] {}", record.level.as_str(), record),
+    }
+}
+
+/// Descarrega os sinks do despacho global
+pub fn flush() {
+    if let Some(dispatch) = current() {
+        dispatch.flush();
+    }
+}
+
 pub struct ConsoleLogger {
     min_level: Level,
     colored: bool,
@@ -86,9 +268,11 @@ impl Logger for ConsoleLogger {
     }
 }
 
+/// Instala um [`Logger`] como único sink, sem filtro próprio
 pub fn init(logger: impl Logger + Send + 'static) {
-    let mut guard = LOGGER.lock().unwrap();
-    *guard = Some(Box::new(logger));
+    Dispatch::new(Filter::new(Level::Trace))
+        .with_sink(sink::LoggerSink(Mutex::new(Box::new(logger))))
+        .install();
 }
 
 pub fn trace(message: &str) {
@@ -112,47 +296,89 @@ pub fn error(message: &str) {
 }
 
 fn log(level: Level, message: &str) {
-    let guard = LOGGER.lock().unwrap();
-    if let Some(logger) = guard.as_ref() {
-        logger.log(level, message);
-    } else {
-        // Fallback: print to stderr if no logger initialized
-        eprintln!("[{}] {}", level.as_str(), message);
+    if enabled(level, "") {
+        log_record(&Record::new(level, "", format_args!("{}", message), &[]));
     }
 }
 
+#[doc(hidden)]
+pub mod __private {
+    use std::fmt;
+
+    /// Campo `?valor`: formatado com `Debug`
+    pub struct DebugValue<'a, T: ?Sized>(pub &'a T);
+
+    impl<T: fmt::Debug + ?Sized> fmt::Display for DebugValue<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(self.0, f)
+        }
+    }
+}
+
+/// Emite um registro com nível dinâmico
+///
+/// Campos opcionais vêm antes da mensagem: `chave = valor` (Display),
+/// `chave = %valor` (Display) ou `chave = ?valor` (Debug). Nada é avaliado
+/// nem formatado se o filtro rejeitar o registro.
+#[macro_export]
+macro_rules! log {
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = % $value:expr, $($rest:tt)+) => {
+        $crate::log!(@fields $level, [$($fields)* ($key, $value)] $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = ? $value:expr, $($rest:tt)+) => {
+        $crate::log!(@fields $level, [$($fields)* ($key, $crate::__private::DebugValue(&$value))] $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = $value:expr, $($rest:tt)+) => {
+        $crate::log!(@fields $level, [$($fields)* ($key, $value)] $($rest)+)
+    };
+    (@fields $level:expr, [$(($key:ident, $value:expr))*] $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::enabled(level, module_path!()) {
+            $crate::log_record(&$crate::Record::new(
+                level,
+                module_path!(),
+                format_args!($($arg)+),
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*],
+            ));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!(@fields $level, [] $($arg)+)
+    };
+}
+
 #[macro_export]
 macro_rules! trace {
-    ($($arg:tt)*) => {
-        $crate::trace(&format!($($arg)*))
+    ($($arg:tt)+) => {
+        $crate::log!($crate::Level::Trace, $($arg)+)
     };
 }
 
 #[macro_export]
 macro_rules! debug {
-    ($($arg:tt)*) => {
-        $crate::debug(&format!($($arg)*))
+    ($($arg:tt)+) => {
+        $crate::log!($crate::Level::Debug, $($arg)+)
     };
 }
 
 #[macro_export]
 macro_rules! info {
-    ($($arg:tt)*) => {
-        $crate::info(&format!($($arg)*))
+    ($($arg:tt)+) => {
+        $crate::log!($crate::Level::Info, $($arg)+)
     };
 }
 
 #[macro_export]
 macro_rules! warn {
-    ($($arg:tt)*) => {
-        $crate::warn(&format!($($arg)*))
+    ($($arg:tt)+) => {
+        $crate::log!($crate::Level::Warn, $($arg)+)
     };
 }
 
 #[macro_export]
 macro_rules! error {
-    ($($arg:tt)*) => {
-        $crate::error(&format!($($arg)*))
+    ($($arg:tt)+) => {
+        $crate::log!($crate::Level::Error, $($arg)+)
     };
 }
 
@@ -231,6 +457,37 @@ mod tests {
         assert!(Level::Error > Level::Warn);
         assert!(Level::Info > Level::Debug);
         assert_eq!(Level::Info.as_str(), "INFO");
+        assert_eq!("warn".parse::<Level>(), Ok(Level::Warn));
+    }
+
+    #[derive(Default)]
+    struct Capture(Mutex<Vec<(Level, String, String)>>);
+
+    impl Sink for Capture {
+        fn write(&self, record: &Record<'_>) {
+            let entry = (record.level(), record.target().to_string(), record.to_string());
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[test]
+    fn test_macros_with_fields() {
+        let capture = Arc::new(Capture::default());
+        Dispatch::new(Filter::new(Level::Info)).with_sink(Arc::clone(&capture)).install();
+
+        let peer = "10.0.0.7:5000";
+        info!(status = 503u16, peer = %peer, reason = ?Some("timeout"), "upstream {} failed", "coordinator");
+        warn!("legacy {} call", "format");
+        debug!(skipped = 1, "below the filter");
+
+        let logs = capture.0.lock().unwrap();
+        let ours: Vec<_> = logs.iter().filter(|(_, target, _)| target == module_path!()).collect();
+        assert_eq!(ours.len(), 2);
+        assert_eq!(
+            ours[0].2,
+            "upstream coordinator failed status=503 peer=10.0.0.7:5000 reason=Some(\"timeout\")"
+        );
+        assert_eq!((ours[1].0, ours[1].2.as_str()), (Level::Warn, "legacy format call"));
     }
 
     #[test]
@@ -0,0 +1,68 @@
+//! Contadores de registros no avila-monitor (feature `monitor`)
+
+use crate::{Level, Record, Sink};
+use avila_monitor::Monitor;
+use std::sync::{Arc, Mutex};
+
+const LEVELS: [(Level, &str, &str); 5] = [
+    (Level::Trace, "log.trace", "Registros TRACE emitidos"),
+    (Level::Debug, "log.debug", "Registros DEBUG emitidos"),
+    (Level::Info, "log.info", "Registros INFO emitidos"),
+    (Level::Warn, "log.warn", "Registros WARN emitidos"),
+    (Level::Error, "log.error", "Registros ERROR emitidos"),
+];
+
+/// Conta registros por nível no [`Monitor`] compartilhado
+///
+/// Usa cinco métricas consecutivas a partir de `base_metric_id`, na ordem
+/// TRACE, DEBUG, INFO, WARN, ERROR.
+pub struct MonitorSink {
+    monitor: Arc<Mutex<Monitor>>,
+    base_metric_id: u64,
+}
+
+impl MonitorSink {
+    pub fn new(monitor: Arc<Mutex<Monitor>>, base_metric_id: u64) -> Self {
+        if let Ok(mut guard) = monitor.lock() {
+            for (level, name, description) in LEVELS {
+                guard.set_metadata(base_metric_id + level as u64, name, "events", description);
+            }
+        }
+        Self { monitor, base_metric_id }
+    }
+
+    /// Métrica que conta os registros de `level`
+    pub fn metric_id(&self, level: Level) -> u64 {
+        self.base_metric_id + level as u64
+    }
+}
+
+impl Sink for MonitorSink {
+    fn write(&self, record: &Record<'_>) {
+        if let Ok(mut monitor) = self.monitor.lock() {
+            monitor.increment(self.metric_id(record.level()), 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dispatch, Filter};
+
+    #[test]
+    fn test_counts_per_level() {
+        let monitor = Arc::new(Mutex::new(Monitor::new()));
+        let dispatch = Dispatch::new(Filter::new(Level::Info)).with_sink(MonitorSink::new(Arc::clone(&monitor), 900));
+
+        for level in [Level::Debug, Level::Info, Level::Error, Level::Error] {
+            dispatch.log(&Record::new(level, "avila_webframework", format_args!("request"), &[]));
+        }
+
+        let monitor = monitor.lock().unwrap();
+        assert_eq!(monitor.get(901), None);
+        assert_eq!(monitor.get(902), Some(1.0));
+        assert_eq!(monitor.get(904), Some(2.0));
+        assert_eq!(monitor.get_metadata(904).unwrap().name, "log.error");
+    }
+}
@@ -0,0 +1,171 @@
+//! Destinos de registros: stderr e arquivo JSON lines
+
+use crate::{Logger, Record};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Destino de registros já aprovados pelo filtro
+pub trait Sink: Send + Sync {
+    fn write(&self, record: &Record<'_>);
+
+    fn flush(&self) {}
+}
+
+impl<S: Sink + ?Sized> Sink for Arc<S> {
+    fn write(&self, record: &Record<'_>) {
+        (**self).write(record);
+    }
+
+    fn flush(&self) {
+        (**self).flush();
+    }
+}
+
+/// Uma linha legível por registro em stderr
+pub struct StderrSink {
+    colored: bool,
+}
+
+impl StderrSink {
+    pub fn new() -> Self {
+        Self { colored: true }
+    }
+
+    pub fn with_colors(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+}
+
+impl Default for StderrSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sink for StderrSink {
+    fn write(&self, record: &Record<'_>) {
+        let timestamp = avila_time::DateTime::now().format("%Y-%m-%d %H:%M:%S");
+        let level = record.level();
+        let mut stderr = io::stderr().lock();
+        // Nothing sensible to do if stderr itself is gone
+        let _ = if self.colored {
+            writeln!(stderr, "{}[{}]\x1b[0m {} {}: {}", level.color(), level.as_str(), timestamp, record.target(), record)
+        } else {
+            writeln!(stderr, "[{}] {} {}: {}", level.as_str(), timestamp, record.target(), record)
+        };
+    }
+}
+
+/// Um objeto JSON por linha, acrescentado ao arquivo
+///
+/// ```text
+/// {"ts":"2026-03-01T12:00:00Z","level":"INFO","target":"avila_webframework","message":"Server running","fields":{"addr":"0.0.0.0:8080"}}
+/// ```
+///
+/// Os valores dos campos são gravados como strings.
+pub struct JsonLinesSink {
+    file: Mutex<File>,
+}
+
+impl JsonLinesSink {
+    /// Abre (ou cria) o arquivo em modo append
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+}
+
+impl Sink for JsonLinesSink {
+    fn write(&self, record: &Record<'_>) {
+        let line = to_json_line(record, &avila_time::DateTime::now().to_rfc3339());
+        if let Ok(mut file) = self.file.lock() {
+            // One write per record keeps lines intact across processes appending to the same file
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+pub(crate) fn to_json_line(record: &Record<'_>, timestamp: &str) -> String {
+    let mut line = String::with_capacity(128);
+    line.push_str("{\"ts\":");
+    push_json_str(&mut line, timestamp);
+    line.push_str(",\"level\":");
+    push_json_str(&mut line, record.level().as_str());
+    line.push_str(",\"target\":");
+    push_json_str(&mut line, record.target());
+    line.push_str(",\"message\":");
+    push_json_str(&mut line, &record.message().to_string());
+    line.push_str(",\"fields\":{");
+    for (i, (key, value)) in record.fields().iter().enumerate() {
+        if i > 0 {
+            line.push(',');
+        }
+        push_json_str(&mut line, key);
+        line.push(':');
+        push_json_str(&mut line, &value.to_string());
+    }
+    line.push_str("}}\n");
+    line
+}
+
+fn push_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Adapta um [`Logger`] instalado via [`init`](crate::init)
+pub(crate) struct LoggerSink(pub(crate) Mutex<Box<dyn Logger + Send>>);
+
+impl Sink for LoggerSink {
+    fn write(&self, record: &Record<'_>) {
+        if let Ok(logger) = self.0.lock() {
+            logger.log(record.level(), &record.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn test_json_line_escaping() {
+        let path = "C:\\models\\tower.ifc";
+        let fields: [(&str, &dyn std::fmt::Display); 2] = [("path", &path), ("entities", &42)];
+        let line = to_json_line(
+            &Record::new(Level::Warn, "vizzio_convert", format_args!("skipped \"{}\"\n", "IfcSpace"), &fields),
+            "2026-03-01T12:00:00Z",
+        );
+
+        assert_eq!(
+            line,
+            "{\"ts\":\"2026-03-01T12:00:00Z\",\"level\":\"WARN\",\"target\":\"vizzio_convert\",\
+             \"message\":\"skipped \\\"IfcSpace\\\"\\n\",\
+             \"fields\":{\"path\":\"C:\\\\models\\\\tower.ifc\",\"entities\":\"42\"}}\n"
+        );
+    }
+}
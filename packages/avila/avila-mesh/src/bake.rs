@@ -0,0 +1,806 @@
+//! Baking de mapas (high-poly → low-poly)
+//!
+//! Depois de uma simplificação agressiva (LODs), o detalhe da malha original
+//! pode ser preservado em texturas:
+//! - **Normal map** em espaço tangente (convenção glTF: +X direita, +Y para cima)
+//! - **Occlusion map** opcional (canal R, como o glTF espera)
+//!
+//! Para cada texel coberto pelas UVs do low-poly, um raio parte da "gaiola"
+//! (superfície deslocada `max_distance` ao longo da normal) em direção à
+//! superfície e encontra o high-poly. As texturas resultantes são
+//! [`TextureBuffer`]s que podem ser registrados na [`Scene`] e referenciados
+//! pelo material, sendo embutidos no GLB pelo exporter.
+
+use crate::{Mesh, MeshError, PbrMaterial, Result, Scene};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// TEXTURA
+// ============================================================================
+
+/// Formato de pixel (8 bits por canal)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextureFormat {
+    Gray8,
+    Rgb8,
+}
+
+impl TextureFormat {
+    pub fn channels(&self) -> usize {
+        match self {
+            Self::Gray8 => 1,
+            Self::Rgb8 => 3,
+        }
+    }
+}
+
+/// Textura em memória (linhas de cima para baixo, mesma orientação das UVs glTF)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextureBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    pub data: Vec<u8>,
+}
+
+impl TextureBuffer {
+    pub fn new(width: u32, height: u32, format: TextureFormat) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            data: vec![0; width as usize * height as usize * format.channels()],
+        }
+    }
+
+    /// Textura preenchida com um valor constante
+    pub fn filled(width: u32, height: u32, format: TextureFormat, value: &[u8]) -> Self {
+        let mut texture = Self::new(width, height, format);
+        for pixel in texture.data.chunks_exact_mut(format.channels()) {
+            pixel.copy_from_slice(value);
+        }
+        texture
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> &[u8] {
+        let channels = self.format.channels();
+        let offset = (y as usize * self.width as usize + x as usize) * channels;
+        &self.data[offset..offset + channels]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: &[u8]) {
+        let channels = self.format.channels();
+        let offset = (y as usize * self.width as usize + x as usize) * channels;
+        self.data[offset..offset + channels].copy_from_slice(value);
+    }
+
+    /// Codifica como PNG (deflate sem compressão, sem dependências externas)
+    pub fn encode_png(&self) -> Vec<u8> {
+        let channels = self.format.channels();
+        let row_len = self.width as usize * channels;
+
+        // Filtro 0 (None) no início de cada linha
+        let mut raw = Vec::with_capacity((row_len + 1) * self.height as usize);
+        for row in self.data.chunks_exact(row_len.max(1)).take(self.height as usize) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&self.width.to_be_bytes());
+        ihdr.extend_from_slice(&self.height.to_be_bytes());
+        let color_type = match self.format {
+            TextureFormat::Gray8 => 0,
+            TextureFormat::Rgb8 => 2,
+        };
+        ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        write_png_chunk(&mut png, b"IHDR", &ihdr);
+        write_png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Stream zlib com blocos deflate "stored"
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(u8::from(blocks.peek().is_none()));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// ============================================================================
+// OPÇÕES E RESULTADO
+// ============================================================================
+
+/// Configuração do baking
+#[derive(Debug, Clone)]
+pub struct BakeOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Distância máxima de busca ao longo da normal (em cada sentido)
+    pub max_distance: f32,
+    /// Gera occlusion map
+    pub occlusion: bool,
+    /// Raios por texel para a oclusão
+    pub occlusion_samples: u32,
+    /// Alcance dos raios de oclusão
+    pub occlusion_distance: f32,
+    /// Texels de dilatação além das ilhas UV (evita costuras com mipmaps)
+    pub padding: u32,
+}
+
+impl Default for BakeOptions {
+    fn default() -> Self {
+        Self {
+            width: 512,
+            height: 512,
+            max_distance: 0.1,
+            occlusion: false,
+            occlusion_samples: 16,
+            occlusion_distance: 1.0,
+            padding: 4,
+        }
+    }
+}
+
+/// Mapas gerados pelo baking
+#[derive(Debug, Clone)]
+pub struct BakeResult {
+    pub normal_map: TextureBuffer,
+    pub occlusion_map: Option<TextureBuffer>,
+    /// Texels cobertos pelas UVs do low-poly
+    pub covered_texels: usize,
+    /// Texels cujo raio encontrou o high-poly
+    pub hit_texels: usize,
+}
+
+impl BakeResult {
+    /// Registra as texturas na cena e as associa ao material
+    ///
+    /// As texturas recebem os IDs `{material_id}_normal` e
+    /// `{material_id}_occlusion`.
+    pub fn attach_to(self, scene: &mut Scene, material_id: &str) -> Result<()> {
+        let material: &mut PbrMaterial = scene
+            .materials
+            .get_mut(material_id)
+            .ok_or_else(|| MeshError::MaterialNotFound(material_id.to_string()))?;
+
+        let normal_id = format!("{}_normal", material_id);
+        material.normal_texture = Some(normal_id.clone());
+
+        let occlusion_id = self.occlusion_map.as_ref().map(|_| format!("{}_occlusion", material_id));
+        if let Some(id) = &occlusion_id {
+            material.occlusion_texture = Some(id.clone());
+        }
+
+        scene.add_texture(normal_id, self.normal_map);
+        if let (Some(id), Some(map)) = (occlusion_id, self.occlusion_map) {
+            scene.add_texture(id, map);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// BAKING
+// ============================================================================
+
+/// Normal "plana" em espaço tangente
+const FLAT_NORMAL: [u8; 3] = [128, 128, 255];
+
+/// Faz o baking de `high` nas UVs de `low`
+pub fn bake_maps(low: &Mesh, high: &Mesh, options: &BakeOptions) -> Result<BakeResult> {
+    if options.width == 0 || options.height == 0 {
+        return Err(MeshError::InvalidMesh("Bake texture size must be non-zero".into()));
+    }
+    if options.max_distance <= 0.0 {
+        return Err(MeshError::InvalidMesh("Bake max_distance must be positive".into()));
+    }
+    low.validate()?;
+    high.validate()?;
+
+    let (width, height) = (options.width, options.height);
+    let bvh = TriangleBvh::build(high);
+
+    let mut normal_map = TextureBuffer::filled(width, height, TextureFormat::Rgb8, &FLAT_NORMAL);
+    let mut occlusion_map = options
+        .occlusion
+        .then(|| TextureBuffer::filled(width, height, TextureFormat::Gray8, &[255]));
+    let mut covered = vec![false; width as usize * height as usize];
+    let mut hit_texels = 0;
+
+    for tri in low.indices.chunks_exact(3) {
+        let v = [
+            &low.vertices[tri[0] as usize],
+            &low.vertices[tri[1] as usize],
+            &low.vertices[tri[2] as usize],
+        ];
+
+        // Triângulo em espaço de pixels
+        let px = v.map(|v| Vec2::new(v.uv.x * width as f32, v.uv.y * height as f32));
+        let area = edge(px[0], px[1], px[2]);
+        if area.abs() < 1e-12 {
+            continue; // sem área em UV
+        }
+
+        let Some(frame) = TangentFrame::from_triangle(v) else {
+            continue;
+        };
+
+        let min_x = px.iter().map(|p| p.x).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let min_y = px.iter().map(|p| p.y).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let max_x = (px.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max).ceil() as u32).min(width);
+        let max_y = (px.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max).ceil() as u32).min(height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w = [
+                    edge(px[1], px[2], center) / area,
+                    edge(px[2], px[0], center) / area,
+                    edge(px[0], px[1], center) / area,
+                ];
+                if w.iter().any(|&w| w < -1e-4) {
+                    continue;
+                }
+
+                let texel = y as usize * width as usize + x as usize;
+                if covered[texel] {
+                    continue; // UVs sobrepostas: mantém o primeiro
+                }
+                covered[texel] = true;
+
+                let position = v[0].position * w[0] + v[1].position * w[1] + v[2].position * w[2];
+                let Some((normal, tangent, bitangent)) = frame.at(v, w) else {
+                    continue;
+                };
+
+                let cage = position + normal * options.max_distance;
+                let Ok(ray) = Ray::new(cage, -normal) else {
+                    continue;
+                };
+                let Some(hit) = bvh.intersect(&ray, 2.0 * options.max_distance) else {
+                    continue;
+                };
+                hit_texels += 1;
+
+                let high_normal = bvh.normal_at(&hit);
+                let encoded = [
+                    encode_unit(high_normal.dot(&tangent)),
+                    encode_unit(high_normal.dot(&bitangent)),
+                    encode_unit(high_normal.dot(&normal)),
+                ];
+                normal_map.set_pixel(x, y, &encoded);
+
+                if let Some(map) = occlusion_map.as_mut() {
+                    let point = ray.at(hit.t);
+                    let visibility = bvh.visibility(point, high_normal, options);
+                    map.set_pixel(x, y, &[(visibility * 255.0).round() as u8]);
+                }
+            }
+        }
+    }
+
+    let covered_texels = covered.iter().filter(|&&c| c).count();
+    if options.padding > 0 {
+        let mut mask = covered.clone();
+        dilate(&mut normal_map, &mut mask, options.padding);
+        if let Some(map) = occlusion_map.as_mut() {
+            let mut mask = covered;
+            dilate(map, &mut mask, options.padding);
+        }
+    }
+
+    Ok(BakeResult {
+        normal_map,
+        occlusion_map,
+        covered_texels,
+        hit_texels,
+    })
+}
+
+#[inline]
+fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+#[inline]
+fn encode_unit(value: f32) -> u8 {
+    ((value.clamp(-1.0, 1.0) * 0.5 + 0.5) * 255.0).round() as u8
+}
+
+/// Base tangente de um triângulo do low-poly
+///
+/// A tangente segue +U; a bitangente aponta para -V (para cima na imagem),
+/// como o glTF espera para o canal verde.
+struct TangentFrame {
+    tangent: Vec3,
+    up: Vec3,
+}
+
+impl TangentFrame {
+    fn from_triangle(v: [&crate::Vertex; 3]) -> Option<Self> {
+        let (dp1, dp2) = (v[1].position - v[0].position, v[2].position - v[0].position);
+        let (duv1, duv2) = (v[1].uv - v[0].uv, v[2].uv - v[0].uv);
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let r = 1.0 / det;
+        let tangent = (dp1 * duv2.y - dp2 * duv1.y) * r;
+        let down = (dp2 * duv1.x - dp1 * duv2.x) * r;
+        Some(Self { tangent, up: -down })
+    }
+
+    /// Base ortonormal (normal, tangente, bitangente) no ponto baricêntrico `w`
+    fn at(&self, v: [&crate::Vertex; 3], w: [f32; 3]) -> Option<(Vec3, Vec3, Vec3)> {
+        let normal = (v[0].normal * w[0] + v[1].normal * w[1] + v[2].normal * w[2]).normalize().ok()?;
+
+        let tangent = match (v[0].tangent, v[1].tangent, v[2].tangent) {
+            (Some(t0), Some(t1), Some(t2)) => t0 * w[0] + t1 * w[1] + t2 * w[2],
+            _ => self.tangent,
+        };
+        let tangent = (tangent - normal * normal.dot(&tangent)).normalize().ok()?;
+
+        let mut bitangent = normal.cross(&tangent);
+        if bitangent.dot(&self.up) < 0.0 {
+            bitangent = -bitangent; // UVs espelhadas
+        }
+        Some((normal, tangent, bitangent))
+    }
+}
+
+/// Estende os texels cobertos para os vizinhos vazios (média dos vizinhos)
+fn dilate(texture: &mut TextureBuffer, mask: &mut [bool], iterations: u32) {
+    let (width, height) = (texture.width as i64, texture.height as i64);
+    let channels = texture.format.channels();
+
+    for _ in 0..iterations {
+        let mut updates = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if mask[(y * width + x) as usize] {
+                    continue;
+                }
+                let neighbors: Vec<(i64, i64)> = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)]
+                    .into_iter()
+                    .map(|(dx, dy)| (x + dx, y + dy))
+                    .filter(|&(nx, ny)| {
+                        nx >= 0 && ny >= 0 && nx < width && ny < height && mask[(ny * width + nx) as usize]
+                    })
+                    .collect();
+                if neighbors.is_empty() {
+                    continue;
+                }
+
+                let mut pixel = [0u8; 3];
+                for (c, value) in pixel.iter_mut().enumerate().take(channels) {
+                    let sum: u32 = neighbors.iter().map(|&(nx, ny)| texture.pixel(nx as u32, ny as u32)[c] as u32).sum();
+                    *value = (sum / neighbors.len() as u32) as u8;
+                }
+                updates.push((x, y, pixel));
+            }
+        }
+
+        if updates.is_empty() {
+            break;
+        }
+        for (x, y, pixel) in updates {
+            texture.set_pixel(x as u32, y as u32, &pixel[..channels]);
+            mask[(y * width + x) as usize] = true;
+        }
+    }
+}
+
+// ============================================================================
+// BVH (interseção raio-triângulo e ponto mais próximo)
+// ============================================================================
+
+const BVH_LEAF_SIZE: usize = 4;
+
+struct BvhNode {
+    bounds: Aabb,
+    /// Folha: intervalo em `triangles`; nó interno: filhos em `left`/`left + 1`
+    start: usize,
+    count: usize,
+    left: usize,
+}
+
+/// Ponto na superfície: `t` é a distância ao longo do raio (ou até a consulta)
+/// e `(u, v)` são os pesos baricêntricos dos vértices 1 e 2
+pub(crate) struct Hit {
+    pub(crate) t: f32,
+    pub(crate) triangle: usize,
+    pub(crate) u: f32,
+    pub(crate) v: f32,
+}
+
+/// BVH de triângulos, também usado nas métricas de [`crate::compare`]
+pub(crate) struct TriangleBvh<'a> {
+    pub(crate) mesh: &'a Mesh,
+    nodes: Vec<BvhNode>,
+    triangles: Vec<usize>,
+}
+
+impl<'a> TriangleBvh<'a> {
+    pub(crate) fn build(mesh: &'a Mesh) -> Self {
+        let mut bvh = Self {
+            mesh,
+            nodes: Vec::new(),
+            triangles: (0..mesh.triangle_count()).collect(),
+        };
+        let centroids: Vec<Vec3> = bvh
+            .triangles
+            .iter()
+            .map(|&t| {
+                let [a, b, c] = bvh.corners(t);
+                (a + b + c) / 3.0
+            })
+            .collect();
+
+        if !bvh.triangles.is_empty() {
+            bvh.nodes.push(BvhNode { bounds: Aabb::EMPTY, start: 0, count: bvh.triangles.len(), left: 0 });
+            bvh.subdivide(0, &centroids);
+        }
+        bvh
+    }
+
+    pub(crate) fn corners(&self, triangle: usize) -> [Vec3; 3] {
+        let i = &self.mesh.indices[triangle * 3..triangle * 3 + 3];
+        [
+            self.mesh.vertices[i[0] as usize].position,
+            self.mesh.vertices[i[1] as usize].position,
+            self.mesh.vertices[i[2] as usize].position,
+        ]
+    }
+
+    fn subdivide(&mut self, node: usize, centroids: &[Vec3]) {
+        let (start, count) = (self.nodes[node].start, self.nodes[node].count);
+        let mut bounds = Aabb::EMPTY;
+        for &t in &self.triangles[start..start + count] {
+            for corner in self.corners(t) {
+                bounds.expand_point(corner);
+            }
+        }
+        self.nodes[node].bounds = bounds;
+        if count <= BVH_LEAF_SIZE {
+            return;
+        }
+
+        // Divide na mediana do maior eixo
+        let size = bounds.size();
+        let axis = |v: Vec3| {
+            if size.x >= size.y && size.x >= size.z {
+                v.x
+            } else if size.y >= size.z {
+                v.y
+            } else {
+                v.z
+            }
+        };
+        self.triangles[start..start + count]
+            .sort_unstable_by(|&a, &b| axis(centroids[a]).total_cmp(&axis(centroids[b])));
+
+        let half = count / 2;
+        let left = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: Aabb::EMPTY, start, count: half, left: 0 });
+        self.nodes.push(BvhNode { bounds: Aabb::EMPTY, start: start + half, count: count - half, left: 0 });
+        self.nodes[node].count = 0;
+        self.nodes[node].left = left;
+
+        self.subdivide(left, centroids);
+        self.subdivide(left + 1, centroids);
+    }
+
+    /// Interseção mais próxima em `(0, max_t]`
+    pub(crate) fn intersect(&self, ray: &Ray, max_t: f32) -> Option<Hit> {
+        let mut best: Option<Hit> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let limit = best.as_ref().map_or(max_t, |hit| hit.t);
+            match ray.intersect_aabb(&node.bounds) {
+                Some((near, far)) if far >= 0.0 && near <= limit => {}
+                _ => continue,
+            }
+
+            if node.count == 0 {
+                stack.push(node.left);
+                stack.push(node.left + 1);
+                continue;
+            }
+
+            for &triangle in &self.triangles[node.start..node.start + node.count] {
+                let limit = best.as_ref().map_or(max_t, |hit| hit.t);
+                if let Some((t, u, v)) = intersect_triangle(ray, self.corners(triangle)) {
+                    if t <= limit {
+                        best = Some(Hit { t, triangle, u, v });
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Ponto da superfície mais próximo de `point` (`t` = distância)
+    pub(crate) fn closest(&self, point: Vec3) -> Option<Hit> {
+        let mut best: Option<Hit> = None;
+        let mut best_sq = f32::INFINITY;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if distance_squared_to_aabb(point, &node.bounds) >= best_sq {
+                continue;
+            }
+
+            if node.count == 0 {
+                // Visita primeiro o filho mais próximo para podar mais cedo
+                let (a, b) = (node.left, node.left + 1);
+                let da = distance_squared_to_aabb(point, &self.nodes[a].bounds);
+                let db = distance_squared_to_aabb(point, &self.nodes[b].bounds);
+                if da <= db {
+                    stack.push(b);
+                    stack.push(a);
+                } else {
+                    stack.push(a);
+                    stack.push(b);
+                }
+                continue;
+            }
+
+            for &triangle in &self.triangles[node.start..node.start + node.count] {
+                let (closest, u, v) = closest_point_on_triangle(point, self.corners(triangle));
+                let d = closest.distance_squared(&point);
+                if d < best_sq {
+                    best_sq = d;
+                    best = Some(Hit { t: d.sqrt(), triangle, u, v });
+                }
+            }
+        }
+        best
+    }
+
+    /// Normal suavizada do high-poly no ponto de impacto
+    pub(crate) fn normal_at(&self, hit: &Hit) -> Vec3 {
+        let i = &self.mesh.indices[hit.triangle * 3..hit.triangle * 3 + 3];
+        let n = |k: usize| self.mesh.vertices[i[k] as usize].normal;
+        let w = 1.0 - hit.u - hit.v;
+        (n(0) * w + n(1) * hit.u + n(2) * hit.v).normalize().unwrap_or_else(|_| {
+            let [a, b, c] = self.corners(hit.triangle);
+            (b - a).cross(&(c - a)).normalize().unwrap_or(Vec3::Z)
+        })
+    }
+
+    /// Fração do hemisfério visível a partir de `point` (1 = sem oclusão)
+    fn visibility(&self, point: Vec3, normal: Vec3, options: &BakeOptions) -> f32 {
+        let samples = options.occlusion_samples.max(1);
+        let helper = if normal.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+        let Ok(u) = normal.cross(&helper).normalize() else {
+            return 1.0;
+        };
+        let v = normal.cross(&u);
+        let origin = point + normal * (options.occlusion_distance * 1e-3).max(1e-5);
+
+        let mut occluded = 0;
+        for i in 0..samples {
+            // Sequência de Hammersley com distribuição cosseno
+            let (s, t) = ((i as f32 + 0.5) / samples as f32, radical_inverse(i));
+            let r = s.sqrt();
+            let phi = core::f32::consts::TAU * t;
+            let direction = u * (r * phi.cos()) + v * (r * phi.sin()) + normal * (1.0 - s).max(0.0).sqrt();
+
+            if let Ok(ray) = Ray::new(origin, direction) {
+                if self.intersect(&ray, options.occlusion_distance).is_some() {
+                    occluded += 1;
+                }
+            }
+        }
+        1.0 - occluded as f32 / samples as f32
+    }
+}
+
+pub(crate) fn radical_inverse(i: u32) -> f32 {
+    i.reverse_bits() as f32 * (1.0 / 4_294_967_296.0)
+}
+
+fn distance_squared_to_aabb(point: Vec3, bounds: &Aabb) -> f32 {
+    let axis = |p: f32, min: f32, max: f32| (min - p).max(0.0).max(p - max);
+    let d = Vec3::new(
+        axis(point.x, bounds.min.x, bounds.max.x),
+        axis(point.y, bounds.min.y, bounds.max.y),
+        axis(point.z, bounds.min.z, bounds.max.z),
+    );
+    d.length_squared()
+}
+
+/// Ponto mais próximo no triângulo (Ericson, *Real-Time Collision Detection* 5.1.5):
+/// retorna o ponto e os pesos baricêntricos (u, v) de `b` e `c`
+fn closest_point_on_triangle(p: Vec3, [a, b, c]: [Vec3; 3]) -> (Vec3, f32, f32) {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (a, 0.0, 0.0);
+    }
+
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return (b, 1.0, 0.0);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let u = d1 / (d1 - d3);
+        return (a + ab * u, u, 0.0);
+    }
+
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return (c, 0.0, 1.0);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let v = d2 / (d2 - d6);
+        return (a + ac * v, 0.0, v);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, 1.0 - w, w);
+    }
+
+    let denom = va + vb + vc;
+    if denom.abs() < f32::MIN_POSITIVE {
+        // Triângulo degenerado: fica com o vértice `a`
+        return (a, 0.0, 0.0);
+    }
+    let (u, v) = (vb / denom, vc / denom);
+    (a + ab * u + ac * v, u, v)
+}
+
+/// Möller–Trumbore: retorna (t, u, v) para t > 0
+fn intersect_triangle(ray: &Ray, [a, b, c]: [Vec3; 3]) -> Option<(f32, f32, f32)> {
+    let (e1, e2) = (b - a, c - a);
+    let p = ray.direction.cross(&e2);
+    let det = e1.dot(&p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = ray.origin - a;
+    let u = s.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(&e1);
+    let v = ray.direction.dot(&q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(&q) * inv_det;
+    (t > 1e-6).then_some((t, u, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives;
+
+    fn options(size: u32) -> BakeOptions {
+        BakeOptions { width: size, height: size, max_distance: 0.5, ..Default::default() }
+    }
+
+    #[test]
+    fn test_bake_identical_mesh_is_flat() {
+        let plane = primitives::plane(2.0, 2.0);
+        let result = bake_maps(&plane, &plane, &options(16)).unwrap();
+
+        assert_eq!(result.covered_texels, 256);
+        assert_eq!(result.hit_texels, 256);
+        assert!(result.normal_map.data.chunks_exact(3).all(|p| p == FLAT_NORMAL));
+    }
+
+    #[test]
+    fn test_bake_tilted_surface() {
+        // High-poly inclinado em direção a +X (= +U): canal vermelho acima de 128
+        let low = primitives::plane(2.0, 2.0);
+        let mut high = primitives::plane(2.0, 2.0);
+        high.transform(&Mat4::rotation_z(-0.2));
+
+        let result = bake_maps(&low, &high, &options(16)).unwrap();
+        assert_eq!(result.hit_texels, 256);
+
+        let pixel = result.normal_map.pixel(8, 8);
+        assert!((pixel[0] as i32 - encode_unit(0.2f32.sin()) as i32).abs() <= 1);
+        assert!((pixel[1] as i32 - 128).abs() <= 1);
+        assert!((pixel[2] as i32 - encode_unit(0.2f32.cos()) as i32).abs() <= 1);
+    }
+
+    #[test]
+    fn test_bake_occlusion_near_geometry() {
+        // Cubo apoiado no centro de um piso 4x4
+        let low = primitives::plane(4.0, 4.0);
+        let mut high = primitives::plane(4.0, 4.0);
+        let mut cube = primitives::cube(1.0);
+        cube.transform(&Mat4::translation(Vec3::new(0.0, 0.5, 0.0)));
+        high.merge(&cube);
+
+        let options = BakeOptions { occlusion: true, occlusion_samples: 64, ..options(40) };
+        let result = bake_maps(&low, &high, &options).unwrap();
+        let occlusion = result.occlusion_map.unwrap();
+
+        // x = 0.7 (ao lado do cubo) vs. canto do piso
+        let near = occlusion.pixel(27, 20)[0];
+        let far = occlusion.pixel(1, 1)[0];
+        assert_eq!(far, 255);
+        assert!(near < 230, "near = {}", near);
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let texture = TextureBuffer::filled(3, 2, TextureFormat::Rgb8, &FLAT_NORMAL);
+        let png = texture.encode_png();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 3);
+        // IEND com CRC fixo
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+    }
+}
@@ -0,0 +1,235 @@
+//! Ordem canônica de vértices, triângulos e meshes
+//!
+//! Tesselação paralela e merges via `HashMap` produzem a mesma geometria
+//! com ordens diferentes a cada execução, o que invalida caches por hash e
+//! polui diffs. Os passes daqui levam a mesma geometria sempre à mesma
+//! ordem, independente da ordem de entrada:
+//!
+//! - **Vértices**: curva de Morton (Z-order) das posições, com os bits de
+//!   todos os atributos como desempate; de quebra, melhora a localidade
+//!   do cache de vértices da GPU
+//! - **Triângulos**: cada triângulo começa no menor índice (mantendo o
+//!   sentido de rotação) e a lista é ordenada lexicograficamente
+//! - **Meshes**: ordenação estável por material, GUID e nome
+//!
+//! Vértices idênticos em todos os atributos só ficam canônicos se forem
+//! deduplicados antes.
+
+use crate::{Mesh, Scene, Vertex};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+use core::cmp::Ordering;
+
+/// Bits por eixo no código de Morton (3 × 21 = 63 bits)
+const MORTON_BITS: u32 = 21;
+
+/// Código de Morton de `position`, quantizada dentro de `bounds`
+///
+/// Eixos degenerados (extensão zero) e valores não finitos contam como 0.
+pub fn morton_code(position: Vec3, bounds: &Aabb) -> u64 {
+    let scale = ((1u32 << MORTON_BITS) - 1) as f32;
+    let quantize = |value: f32, min: f32, max: f32| -> u64 {
+        let extent = max - min;
+        if extent.is_nan() || extent <= 0.0 {
+            return 0;
+        }
+        // `as` satura e leva NaN a 0
+        (((value - min) / extent).clamp(0.0, 1.0) * scale).round() as u64
+    };
+    spread_bits(quantize(position.x, bounds.min.x, bounds.max.x))
+        | spread_bits(quantize(position.y, bounds.min.y, bounds.max.y)) << 1
+        | spread_bits(quantize(position.z, bounds.min.z, bounds.max.z)) << 2
+}
+
+/// Intercala dois zeros entre cada um dos 21 bits menos significativos
+fn spread_bits(value: u64) -> u64 {
+    let mut x = value & 0x1f_ffff;
+    x = (x | x << 32) & 0x001f_0000_0000_ffff;
+    x = (x | x << 16) & 0x001f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+    x
+}
+
+/// Todos os atributos do vértice como bits, para um desempate total
+fn vertex_bits(vertex: &Vertex) -> [u32; 17] {
+    let tangent = vertex.tangent.unwrap_or(Vec3::ZERO);
+    let color = vertex.color.unwrap_or([0.0; 4]);
+    [
+        vertex.position.x.to_bits(),
+        vertex.position.y.to_bits(),
+        vertex.position.z.to_bits(),
+        vertex.normal.x.to_bits(),
+        vertex.normal.y.to_bits(),
+        vertex.normal.z.to_bits(),
+        vertex.uv.x.to_bits(),
+        vertex.uv.y.to_bits(),
+        vertex.tangent.is_some() as u32,
+        tangent.x.to_bits(),
+        tangent.y.to_bits(),
+        tangent.z.to_bits(),
+        vertex.color.is_some() as u32,
+        color[0].to_bits(),
+        color[1].to_bits(),
+        color[2].to_bits(),
+        color[3].to_bits(),
+    ]
+}
+
+impl Mesh {
+    /// Reordena os vértices pela curva de Morton e remapeia os índices
+    pub fn sort_vertices_morton(&mut self) {
+        if self.vertices.len() < 2 {
+            return;
+        }
+        let positions: Vec<Vec3> = self.vertices.iter().map(|v| v.position).collect();
+        let bounds = Aabb::from_points(&positions);
+
+        let mut order: Vec<(u64, [u32; 17], u32)> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, vertex)| (morton_code(vertex.position, &bounds), vertex_bits(vertex), i as u32))
+            .collect();
+        order.sort_unstable();
+
+        let mut remap = vec![0u32; order.len()];
+        for (new_index, &(_, _, old_index)) in order.iter().enumerate() {
+            remap[old_index as usize] = new_index as u32;
+        }
+        self.vertices = order.iter().map(|&(_, _, old)| self.vertices[old as usize]).collect();
+        for index in &mut self.indices {
+            // Índices fora do intervalo ficam como estão (`validate` os acusa)
+            if let Some(&new_index) = remap.get(*index as usize) {
+                *index = new_index;
+            }
+        }
+    }
+
+    /// Rotaciona cada triângulo para começar no menor índice e ordena a lista
+    ///
+    /// A rotação preserva o sentido (e portanto a normal) de cada face.
+    pub fn sort_triangles(&mut self) {
+        let mut triangles: Vec<[u32; 3]> = self
+            .indices
+            .chunks_exact(3)
+            .map(|t| match (t[0] <= t[1] && t[0] <= t[2], t[1] <= t[2]) {
+                (true, _) => [t[0], t[1], t[2]],
+                (false, true) => [t[1], t[2], t[0]],
+                (false, false) => [t[2], t[0], t[1]],
+            })
+            .collect();
+        triangles.sort_unstable();
+
+        // Índices que não formam um triângulo completo ficam no fim
+        let tail = self.indices.len() - self.indices.len() % 3;
+        let rest = self.indices.split_off(tail);
+        self.indices = triangles.into_iter().flatten().chain(rest).collect();
+    }
+
+    /// Ordem canônica completa: vértices por Morton, depois triângulos
+    pub fn canonicalize(&mut self) {
+        self.sort_vertices_morton();
+        self.sort_triangles();
+    }
+}
+
+impl Scene {
+    /// Ordenação estável das meshes por material, GUID e nome
+    ///
+    /// Meshes sem material vêm primeiro.
+    pub fn sort_meshes(&mut self) {
+        self.meshes.sort_by(compare_meshes);
+    }
+
+    /// Canonicaliza cada mesh e depois a ordem das meshes
+    pub fn canonicalize(&mut self) {
+        for mesh in &mut self.meshes {
+            mesh.canonicalize();
+        }
+        self.sort_meshes();
+    }
+}
+
+fn compare_meshes(a: &Mesh, b: &Mesh) -> Ordering {
+    a.material_id
+        .cmp(&b.material_id)
+        .then_with(|| a.element_guid.cmp(&b.element_guid))
+        .then_with(|| a.name.cmp(&b.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad(offset: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            mesh.add_vertex(Vertex::new(Vec3::new(x + offset, y, 0.0)).with_uv(Vec2::new(x, y)));
+        }
+        mesh.add_triangle(0, 1, 2).unwrap();
+        mesh.add_triangle(0, 2, 3).unwrap();
+        mesh
+    }
+
+    /// Mesma geometria com vértices e triângulos embaralhados
+    fn shuffled(mesh: &Mesh, vertex_order: &[usize]) -> Mesh {
+        let mut remap = vec![0u32; vertex_order.len()];
+        let mut out = Mesh::new();
+        for (new_index, &old) in vertex_order.iter().enumerate() {
+            remap[old] = new_index as u32;
+            out.add_vertex(mesh.vertices[old]);
+        }
+        let triangles: Vec<&[u32]> = mesh.indices.chunks(3).rev().collect();
+        for t in triangles {
+            out.add_triangle(remap[t[1] as usize], remap[t[2] as usize], remap[t[0] as usize])
+                .unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_morton_code() {
+        let bounds = Aabb::new(Vec3::ZERO, Vec3::ONE);
+        assert_eq!(morton_code(Vec3::ZERO, &bounds), 0);
+        assert_eq!(morton_code(Vec3::ONE, &bounds), (1u64 << 63) - 1);
+        assert_eq!(spread_bits(0b101), 0b1_000_001);
+        assert!(morton_code(Vec3::new(1.0, 0.0, 0.0), &bounds) < morton_code(Vec3::new(0.0, 1.0, 0.0), &bounds));
+        let flat = Aabb::new(Vec3::ZERO, Vec3::new(1.0, 0.0, 1.0));
+        assert_eq!(morton_code(Vec3::new(f32::NAN, 5.0, 0.0), &flat), 0);
+    }
+
+    #[test]
+    fn test_canonical_mesh_is_order_independent() {
+        let mut a = quad(0.0);
+        let mut b = shuffled(&a, &[2, 0, 3, 1]);
+        a.canonicalize();
+        b.canonicalize();
+        assert_eq!(a.vertices, b.vertices);
+        assert_eq!(a.indices, b.indices);
+        assert!(a.validate().is_ok());
+
+        // As faces continuam voltadas para +Z
+        b.recalculate_normals_flat();
+        assert!(b.vertices.iter().all(|v| v.normal.z > 0.99));
+    }
+
+    #[test]
+    fn test_scene_sort_is_stable_by_material() {
+        let mut scene = Scene::new();
+        for (material, offset) in [(Some("b"), 0.0), (None, 1.0), (Some("a"), 2.0), (Some("b"), 3.0)] {
+            let mut mesh = quad(offset);
+            mesh.material_id = material.map(String::from);
+            scene.add_mesh(mesh);
+        }
+        scene.canonicalize();
+        let order: Vec<_> = scene
+            .meshes
+            .iter()
+            .map(|m| (m.material_id.as_deref(), m.bounds.min.x))
+            .collect();
+        assert_eq!(order, [(None, 1.0), (Some("a"), 2.0), (Some("b"), 0.0), (Some("b"), 3.0)]);
+    }
+}
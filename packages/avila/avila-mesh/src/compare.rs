@@ -0,0 +1,257 @@
+//! Métricas de comparação entre meshes
+//!
+//! Mede o quanto uma mesh (ex.: um LOD) se afasta de outra (a original):
+//! - **Distância de Hausdorff** amostrada (pior caso), média e RMS
+//! - **Desvio de normais** (graus) entre pontos correspondentes
+//!
+//! As amostras são determinísticas: pontos distribuídos por área em cada
+//! triângulo (sequência de Hammersley) mais os próprios vértices, nos dois
+//! sentidos (A → B e B → A). O ponto mais próximo na outra superfície é
+//! encontrado com o mesmo BVH usado pelo [`bake`](crate::bake).
+
+use crate::bake::{radical_inverse, Hit, TriangleBvh};
+use crate::{Mesh, MeshError, Result};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// OPÇÕES E RESULTADO
+// ============================================================================
+
+/// Configuração da comparação
+#[derive(Debug, Clone)]
+pub struct CompareOptions {
+    /// Amostras de superfície por sentido (distribuídas por área)
+    pub samples: usize,
+    /// Inclui os vértices de cada mesh como amostras de distância
+    pub include_vertices: bool,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            samples: 10_000,
+            include_vertices: true,
+        }
+    }
+}
+
+/// Estatísticas de distância em um sentido (da mesh de origem até a outra)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DistanceStats {
+    pub max: f32,
+    pub mean: f32,
+    pub rms: f32,
+    pub samples: usize,
+}
+
+impl DistanceStats {
+    fn from_distances(distances: &[f32]) -> Self {
+        if distances.is_empty() {
+            return Self::default();
+        }
+        let n = distances.len() as f64;
+        let sum: f64 = distances.iter().map(|&d| d as f64).sum();
+        let sum_sq: f64 = distances.iter().map(|&d| (d as f64) * (d as f64)).sum();
+        Self {
+            max: distances.iter().copied().fold(0.0, f32::max),
+            mean: (sum / n) as f32,
+            rms: (sum_sq / n).sqrt() as f32,
+            samples: distances.len(),
+        }
+    }
+}
+
+/// Resultado de [`compare_meshes`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MeshComparison {
+    /// Distâncias de A até a superfície de B
+    pub a_to_b: DistanceStats,
+    /// Distâncias de B até a superfície de A
+    pub b_to_a: DistanceStats,
+    /// Desvio médio das normais, em graus (amostras de superfície, ambos os sentidos)
+    pub normal_mean_deg: f32,
+    /// Maior desvio de normal, em graus (180 = normal invertida)
+    pub normal_max_deg: f32,
+    /// Diagonal da AABB de A, usada como escala em [`Self::relative_hausdorff`]
+    pub diagonal: f32,
+}
+
+impl MeshComparison {
+    /// Distância de Hausdorff simétrica (maior das duas direções)
+    pub fn hausdorff(&self) -> f32 {
+        self.a_to_b.max.max(self.b_to_a.max)
+    }
+
+    /// Distância média considerando as amostras dos dois sentidos
+    pub fn mean(&self) -> f32 {
+        let n = (self.a_to_b.samples + self.b_to_a.samples) as f32;
+        if n == 0.0 {
+            return 0.0;
+        }
+        (self.a_to_b.mean * self.a_to_b.samples as f32 + self.b_to_a.mean * self.b_to_a.samples as f32) / n
+    }
+
+    /// Erro RMS considerando as amostras dos dois sentidos
+    pub fn rms(&self) -> f32 {
+        let n = (self.a_to_b.samples + self.b_to_a.samples) as f32;
+        if n == 0.0 {
+            return 0.0;
+        }
+        let sum_sq = self.a_to_b.rms.powi(2) * self.a_to_b.samples as f32
+            + self.b_to_a.rms.powi(2) * self.b_to_a.samples as f32;
+        (sum_sq / n).sqrt()
+    }
+
+    /// Hausdorff como fração da diagonal (independe da escala do modelo)
+    pub fn relative_hausdorff(&self) -> f32 {
+        if self.diagonal > 0.0 {
+            self.hausdorff() / self.diagonal
+        } else {
+            0.0
+        }
+    }
+}
+
+// ============================================================================
+// COMPARAÇÃO
+// ============================================================================
+
+/// Compara a superfície de `a` (referência) com a de `b`
+pub fn compare_meshes(a: &Mesh, b: &Mesh, options: &CompareOptions) -> Result<MeshComparison> {
+    a.validate()?;
+    b.validate()?;
+    if a.triangle_count() == 0 || b.triangle_count() == 0 {
+        return Err(MeshError::InvalidMesh("Cannot compare a mesh without triangles".into()));
+    }
+
+    let (bvh_a, bvh_b) = (TriangleBvh::build(a), TriangleBvh::build(b));
+    let mut angles = Vec::new();
+    let a_to_b = directed(&bvh_a, &bvh_b, options, &mut angles);
+    let b_to_a = directed(&bvh_b, &bvh_a, options, &mut angles);
+
+    let mut bounds = Aabb::EMPTY;
+    for vertex in &a.vertices {
+        bounds.expand_point(vertex.position);
+    }
+
+    Ok(MeshComparison {
+        a_to_b,
+        b_to_a,
+        normal_mean_deg: if angles.is_empty() { 0.0 } else { angles.iter().sum::<f32>() / angles.len() as f32 },
+        normal_max_deg: angles.iter().copied().fold(0.0, f32::max),
+        diagonal: bounds.size().length(),
+    })
+}
+
+/// Amostra `from` e mede até a superfície de `to`; acumula desvios de normal em `angles`
+fn directed(from: &TriangleBvh, to: &TriangleBvh, options: &CompareOptions, angles: &mut Vec<f32>) -> DistanceStats {
+    let mut distances = Vec::new();
+
+    for sample in surface_samples(from, options.samples) {
+        let [p0, p1, p2] = from.corners(sample.triangle);
+        let point = p0 * (1.0 - sample.u - sample.v) + p1 * sample.u + p2 * sample.v;
+        let Some(hit) = to.closest(point) else { continue };
+
+        distances.push(hit.t);
+        let cos = from.normal_at(&sample).dot(&to.normal_at(&hit)).clamp(-1.0, 1.0);
+        angles.push(cos.acos().to_degrees());
+    }
+
+    // Vértices só contam para a distância: em arestas vivas a normal é ambígua
+    if options.include_vertices {
+        for vertex in &from.mesh.vertices {
+            if let Some(hit) = to.closest(vertex.position) {
+                distances.push(hit.t);
+            }
+        }
+    }
+
+    DistanceStats::from_distances(&distances)
+}
+
+/// Pontos distribuídos proporcionalmente à área de cada triângulo
+fn surface_samples(bvh: &TriangleBvh, samples: usize) -> Vec<Hit> {
+    let areas: Vec<f32> = (0..bvh.mesh.triangle_count())
+        .map(|t| {
+            let [a, b, c] = bvh.corners(t);
+            (b - a).cross(&(c - a)).length() * 0.5
+        })
+        .collect();
+    let total: f32 = areas.iter().sum();
+    if samples == 0 || total <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut result = Vec::with_capacity(samples + areas.len());
+    // Difusão do resto: a soma das cotas acompanha `samples`
+    let mut carry = 0.0;
+    for (triangle, &area) in areas.iter().enumerate() {
+        let quota = area / total * samples as f32 + carry;
+        let count = quota.floor().max(0.0) as u32;
+        carry = quota - count as f32;
+
+        for i in 0..count {
+            // Hammersley mapeado no triângulo (sqrt preserva a densidade
+            // uniforme); o deslocamento de meio passo evita pontos nas arestas
+            let r1 = ((i as f32 + 0.5) / count as f32).sqrt();
+            let r2 = radical_inverse(i) + 0.5 / count as f32;
+            result.push(Hit { t: 0.0, triangle, u: r1 * (1.0 - r2), v: r1 * r2 });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives;
+
+    #[test]
+    fn test_identical_meshes() {
+        let sphere = primitives::sphere(1.0, 16);
+        let result = compare_meshes(&sphere, &sphere, &CompareOptions::default()).unwrap();
+
+        assert!(result.hausdorff() < 1e-5, "hausdorff = {}", result.hausdorff());
+        assert!(result.normal_max_deg < 0.5, "normal_max = {}", result.normal_max_deg);
+        assert!(result.a_to_b.samples >= 10_000);
+        assert_eq!(result.a_to_b.samples, result.b_to_a.samples);
+    }
+
+    #[test]
+    fn test_offset_plane() {
+        let a = primitives::plane(2.0, 2.0);
+        let mut b = primitives::plane(2.0, 2.0);
+        b.transform(&Mat4::translation(Vec3::new(0.0, 0.25, 0.0)));
+
+        let options = CompareOptions { samples: 500, ..Default::default() };
+        let result = compare_meshes(&a, &b, &options).unwrap();
+        assert!((result.hausdorff() - 0.25).abs() < 1e-5);
+        assert!((result.rms() - 0.25).abs() < 1e-5);
+        assert!(result.normal_max_deg < 1e-3);
+        assert!((result.relative_hausdorff() - 0.25 / 8f32.sqrt()).abs() < 1e-4);
+
+        // Normal invertida: mesma superfície, desvio de 180°
+        let mut flipped = a.clone();
+        for tri in flipped.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        for vertex in &mut flipped.vertices {
+            vertex.normal = -vertex.normal;
+        }
+        let result = compare_meshes(&a, &flipped, &options).unwrap();
+        assert!(result.hausdorff() < 1e-6);
+        assert!(result.normal_mean_deg > 179.0);
+    }
+
+    #[test]
+    fn test_rejects_empty_mesh() {
+        let cube = primitives::cube(1.0);
+        assert!(compare_meshes(&cube, &Mesh::new(), &CompareOptions::default()).is_err());
+    }
+}
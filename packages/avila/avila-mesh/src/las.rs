@@ -0,0 +1,233 @@
+//! Leitor LAS (ASPRS LAS 1.0–1.4)
+//!
+//! Lê o cabeçalho público e os registros de pontos dos formatos 0 a 10:
+//! posição, intensidade e, quando o formato tem, cor RGB. VLRs, waveform e
+//! atributos extras são ignorados. Arquivos LAZ (comprimidos) são
+//! recusados com erro; descomprima antes com uma ferramenta externa.
+//!
+//! Coordenadas LAS costumam ser UTM ou similares (milhões de metros), sem
+//! precisão em `f32`: os pontos são devolvidos relativos a
+//! [`LasFile::origin`], o canto mínimo do cabeçalho.
+
+use crate::points::{Point, PointCloud};
+use crate::{MeshError, Result};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::Vec3;
+
+/// Cabeçalho público do arquivo
+#[derive(Debug, Clone, PartialEq)]
+pub struct LasHeader {
+    pub version: (u8, u8),
+    pub point_format: u8,
+    pub point_record_length: u16,
+    pub point_count: u64,
+    pub scale: [f64; 3],
+    pub offset: [f64; 3],
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+    offset_to_points: u32,
+}
+
+/// Nuvem lida e a origem das suas coordenadas
+#[derive(Debug, Clone)]
+pub struct LasFile {
+    pub header: LasHeader,
+    /// Coordenadas do arquivo do ponto (0, 0, 0) da nuvem
+    pub origin: [f64; 3],
+    pub cloud: PointCloud,
+}
+
+fn invalid(message: impl Into<String>) -> MeshError {
+    MeshError::InvalidMesh(format!("LAS: {}", message.into()))
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Result<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).ok_or_else(|| invalid("truncated header"))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Result<u32> {
+    let b = bytes.get(at..at + 4).ok_or_else(|| invalid("truncated header"))?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn f64_at(bytes: &[u8], at: usize) -> Result<f64> {
+    let b = bytes.get(at..at + 8).ok_or_else(|| invalid("truncated header"))?;
+    Ok(f64::from_le_bytes(b.try_into().expect("8 bytes")))
+}
+
+fn vec_at(bytes: &[u8], at: [usize; 3]) -> Result<[f64; 3]> {
+    Ok([f64_at(bytes, at[0])?, f64_at(bytes, at[1])?, f64_at(bytes, at[2])?])
+}
+
+/// Lê só o cabeçalho
+pub fn read_las_header(bytes: &[u8]) -> Result<LasHeader> {
+    if bytes.get(0..4) != Some(b"LASF") {
+        return Err(invalid("missing LASF signature"));
+    }
+    let header_size = u16_at(bytes, 94)?;
+    let format_byte = *bytes.get(104).ok_or_else(|| invalid("truncated header"))?;
+    // Bits 6-7 do formato marcam compressão LAZ
+    if format_byte & 0xC0 != 0 {
+        return Err(invalid("compressed LAZ files are not supported"));
+    }
+    let point_format = format_byte & 0x3F;
+    if point_format > 10 {
+        return Err(invalid(format!("unknown point format {}", point_format)));
+    }
+
+    let legacy_count = u32_at(bytes, 107)? as u64;
+    // LAS 1.4: contagem de 64 bits no cabeçalho estendido
+    let point_count = if legacy_count == 0 && header_size >= 375 {
+        let b = bytes.get(247..255).ok_or_else(|| invalid("truncated header"))?;
+        u64::from_le_bytes(b.try_into().expect("8 bytes"))
+    } else {
+        legacy_count
+    };
+
+    let header = LasHeader {
+        version: (bytes[24], bytes[25]),
+        point_format,
+        point_record_length: u16_at(bytes, 105)?,
+        point_count,
+        scale: vec_at(bytes, [131, 139, 147])?,
+        offset: vec_at(bytes, [155, 163, 171])?,
+        max: vec_at(bytes, [179, 195, 211])?,
+        min: vec_at(bytes, [187, 203, 219])?,
+        offset_to_points: u32_at(bytes, 96)?,
+    };
+    if (header.point_record_length as usize) < record_base_size(point_format) {
+        return Err(invalid(format!(
+            "record length {} too short for point format {}",
+            header.point_record_length, point_format
+        )));
+    }
+    Ok(header)
+}
+
+/// Bytes fixos do registro no formato
+fn record_base_size(format: u8) -> usize {
+    match format {
+        0 => 20,
+        1 => 28,
+        2 => 26,
+        3 => 34,
+        4 => 57,
+        5 => 63,
+        6 => 30,
+        7 => 36,
+        8 => 38,
+        9 => 59,
+        10 => 67,
+        _ => usize::MAX,
+    }
+}
+
+/// Posição do RGB no registro, nos formatos que têm cor
+fn rgb_offset(format: u8) -> Option<usize> {
+    match format {
+        2 => Some(20),
+        3 | 5 => Some(28),
+        7 | 8 | 10 => Some(30),
+        _ => None,
+    }
+}
+
+/// Lê o arquivo inteiro
+///
+/// Cores de 16 bits (como manda a especificação) são reduzidas a 8 bits;
+/// arquivos que gravam 0-255 direto são detectados e mantidos.
+pub fn read_las(bytes: &[u8]) -> Result<LasFile> {
+    let header = read_las_header(bytes)?;
+    let start = header.offset_to_points as usize;
+    let stride = header.point_record_length as usize;
+    let count = usize::try_from(header.point_count).map_err(|_| invalid("too many points"))?;
+    let end = count.checked_mul(stride).and_then(|len| len.checked_add(start)).ok_or_else(|| invalid("too many points"))?;
+    let records = bytes.get(start..end).ok_or_else(|| invalid(format!("truncated point data ({} points)", count)))?;
+
+    let rgb = rgb_offset(header.point_format);
+    let wide_colors = rgb.is_some_and(|at| {
+        records.chunks_exact(stride).any(|r| (0..3).any(|c| u16::from_le_bytes([r[at + 2 * c], r[at + 2 * c + 1]]) > 255))
+    });
+
+    let origin = header.min;
+    let mut cloud = PointCloud::with_capacity(count, rgb.is_some(), true);
+    for record in records.chunks_exact(stride) {
+        let coordinate = |axis: usize| {
+            let raw = i32::from_le_bytes(record[4 * axis..4 * axis + 4].try_into().expect("4 bytes"));
+            (raw as f64 * header.scale[axis] + header.offset[axis] - origin[axis]) as f32
+        };
+        let color = rgb.map(|at| {
+            [0, 1, 2].map(|c| {
+                let value = u16::from_le_bytes([record[at + 2 * c], record[at + 2 * c + 1]]);
+                if wide_colors { (value >> 8) as u8 } else { value as u8 }
+            })
+        });
+        cloud.push(Point {
+            position: Vec3::new(coordinate(0), coordinate(1), coordinate(2)),
+            color,
+            intensity: Some(u16::from_le_bytes([record[12], record[13]])),
+        });
+    }
+    Ok(LasFile { header, origin, cloud })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LAS 1.2, formato 2 (XYZ + intensidade + RGB)
+    fn sample(points: &[([i32; 3], u16, [u16; 3])]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 227];
+        bytes[0..4].copy_from_slice(b"LASF");
+        (bytes[24], bytes[25]) = (1, 2);
+        bytes[94..96].copy_from_slice(&227u16.to_le_bytes());
+        bytes[96..100].copy_from_slice(&227u32.to_le_bytes());
+        bytes[104] = 2;
+        bytes[105..107].copy_from_slice(&26u16.to_le_bytes());
+        bytes[107..111].copy_from_slice(&(points.len() as u32).to_le_bytes());
+        for (i, value) in [0.01, 0.01, 0.01, 500_000.0, 7_000_000.0, 0.0].iter().enumerate() {
+            bytes[131 + 8 * i..139 + 8 * i].copy_from_slice(&f64::to_le_bytes(*value));
+        }
+        // max/min por eixo
+        for (i, value) in [500_010.0, 500_000.0, 7_000_010.0, 7_000_000.0, 10.0, 0.0].iter().enumerate() {
+            bytes[179 + 8 * i..187 + 8 * i].copy_from_slice(&f64::to_le_bytes(*value));
+        }
+        for (xyz, intensity, rgb) in points {
+            let mut record = vec![0u8; 26];
+            for axis in 0..3 {
+                record[4 * axis..4 * axis + 4].copy_from_slice(&xyz[axis].to_le_bytes());
+                record[20 + 2 * axis..22 + 2 * axis].copy_from_slice(&rgb[axis].to_le_bytes());
+            }
+            record[12..14].copy_from_slice(&intensity.to_le_bytes());
+            bytes.extend_from_slice(&record);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_las() {
+        let bytes = sample(&[([0, 0, 0], 100, [65535, 0, 32768]), ([1000, 250, 500], 7, [0, 256, 0])]);
+        let las = read_las(&bytes).unwrap();
+
+        assert_eq!(las.header.version, (1, 2));
+        assert_eq!(las.header.point_count, 2);
+        assert_eq!(las.origin, [500_000.0, 7_000_000.0, 0.0]);
+        assert_eq!(las.cloud.len(), 2);
+        assert_eq!(las.cloud.positions[1], Vec3::new(10.0, 2.5, 5.0));
+        assert_eq!(las.cloud.intensities, vec![100, 7]);
+        assert_eq!(las.cloud.colors, vec![[255, 0, 128], [0, 1, 0]]);
+        assert_eq!(las.cloud.bounds.max, Vec3::new(10.0, 2.5, 5.0));
+        las.cloud.validate().unwrap();
+
+        // Cores de 8 bits gravadas direto
+        let narrow = read_las(&sample(&[([0, 0, 0], 0, [200, 10, 0])])).unwrap();
+        assert_eq!(narrow.cloud.colors, vec![[200, 10, 0]]);
+
+        let mut laz = bytes.clone();
+        laz[104] |= 0x80;
+        assert!(read_las(&laz).is_err());
+        assert!(read_las(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_las(b"PLY").is_err());
+    }
+}
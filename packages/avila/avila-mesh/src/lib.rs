@@ -7,14 +7,58 @@
 //! - Índices (triângulos)
 //! - Materiais (PBR)
 //! - Operações (merge, split, transform, simplify)
+//! - Baking de normal/occlusion maps entre LODs ([`bake`])
+//! - Métricas de erro entre meshes: Hausdorff, RMS e desvio de normais ([`compare`])
+//! - Ordem canônica de vértices, triângulos e meshes ([`canonical`])
+//! - Medições com snap a vértices/arestas/faces ([`measure`])
+//! - Renderização por software para miniaturas ([`render`])
+//! - Nuvens de pontos ([`points`]) e leitura de arquivos LAS ([`las`])
 //!
 //! Compatível com glTF, WebGL, e engines de renderização modernas.
+//!
+//! ## Features
+//! - `std` (padrão): sem ela o crate é `no_std` + `alloc`, para o
+//!   visualizador WASM e uso embarcado; os mapas passam a ser do
+//!   `hashbrown` e as funções de `f32` vêm da `libm` (via `avila-vec3d`)
+//! - `serde` (padrão): `Serialize`/`Deserialize` em cenas, meshes e materiais
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 use avila_vec3d::*;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
-pub type Result<T> = std::result::Result<T, MeshError>;
+pub mod bake;
+pub mod canonical;
+pub mod compare;
+pub mod las;
+pub mod measure;
+pub mod points;
+pub mod render;
+
+pub use bake::{bake_maps, BakeOptions, BakeResult, TextureBuffer, TextureFormat};
+pub use canonical::morton_code;
+pub use compare::{compare_meshes, CompareOptions, DistanceStats, MeshComparison};
+pub use las::{read_las, read_las_header, LasFile, LasHeader};
+pub use measure::{Measurement, MeshPicker, SnapKind, SnapOptions, SnapPoint};
+pub use points::{Point, PointCloud};
+pub use render::{Camera, Projection};
+
+pub type Result<T> = core::result::Result<T, MeshError>;
+
+/// O que o prelude da `std` traria, para os módulos compilarem sem ela
+#[cfg(not(feature = "std"))]
+mod prelude {
+    pub use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+    pub use hashbrown::{HashMap, HashSet};
+}
+
+#[cfg(not(feature = "std"))]
+use prelude::*;
 
 // ============================================================================
 // ERROS
@@ -43,7 +87,8 @@ pub enum MeshError {
 // ============================================================================
 
 /// Vértice completo com todos os atributos
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vertex {
     /// Posição 3D
     pub position: Vec3,
@@ -118,7 +163,8 @@ impl Vertex {
 // ============================================================================
 
 /// Mesh 3D - conjunto de triângulos
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Mesh {
     /// Vértices
     pub vertices: Vec<Vertex>,
@@ -129,8 +175,23 @@ pub struct Mesh {
     /// ID do material associado
     pub material_id: Option<String>,
 
+    /// Nome do elemento de origem (ex.: `IfcWall.Name`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+
+    /// GlobalId do elemento IFC de origem
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub element_guid: Option<String>,
+
     /// AABB (bounding box)
     pub bounds: Aabb,
+
+    /// Esfera envolvente para LOD e frustum culling
+    ///
+    /// Cresce de forma aproximada em `add_vertex`; `recalculate_bounds` a
+    /// refaz com a esfera mínima exata.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bounding_sphere: BoundingSphere,
 }
 
 impl Mesh {
@@ -139,7 +200,10 @@ impl Mesh {
             vertices: Vec::new(),
             indices: Vec::new(),
             material_id: None,
+            name: None,
+            element_guid: None,
             bounds: Aabb::EMPTY,
+            bounding_sphere: BoundingSphere::EMPTY,
         }
     }
 
@@ -148,13 +212,17 @@ impl Mesh {
             vertices: Vec::with_capacity(vertex_count),
             indices: Vec::with_capacity(index_count),
             material_id: None,
+            name: None,
+            element_guid: None,
             bounds: Aabb::EMPTY,
+            bounding_sphere: BoundingSphere::EMPTY,
         }
     }
 
     /// Adiciona vértice e retorna seu índice
     pub fn add_vertex(&mut self, vertex: Vertex) -> u32 {
         self.bounds.expand_point(vertex.position);
+        self.bounding_sphere.expand_point(vertex.position);
         let index = self.vertices.len() as u32;
         self.vertices.push(vertex);
         index
@@ -170,6 +238,13 @@ impl Mesh {
         Ok(())
     }
 
+    /// Recalcula AABB e esfera envolvente (mínima) a partir dos vértices
+    pub fn recalculate_bounds(&mut self) {
+        let positions: Vec<Vec3> = self.vertices.iter().map(|v| v.position).collect();
+        self.bounds = Aabb::from_points(&positions);
+        self.bounding_sphere = BoundingSphere::from_points(&positions);
+    }
+
     /// Número de triângulos
     pub fn triangle_count(&self) -> usize {
         self.indices.len() / 3
@@ -177,7 +252,7 @@ impl Mesh {
 
     /// Valida consistência da mesh
     pub fn validate(&self) -> Result<()> {
-        if self.indices.len() % 3 != 0 {
+        if !self.indices.len().is_multiple_of(3) {
             return Err(MeshError::InvalidMesh("Index count must be multiple of 3".into()));
         }
 
@@ -249,6 +324,7 @@ impl Mesh {
             *vertex = vertex.transform(matrix);
         }
         self.bounds = self.bounds.transform(matrix);
+        self.bounding_sphere = self.bounding_sphere.transform(matrix);
     }
 
     /// Merge com outra mesh (combina geometria)
@@ -265,6 +341,7 @@ impl Mesh {
 
         // Atualiza bounds
         self.bounds = self.bounds.merge(&other.bounds);
+        self.bounding_sphere = self.bounding_sphere.merge(&other.bounding_sphere);
     }
 
     /// Converte para buffers separados (para GPU/glTF)
@@ -329,7 +406,8 @@ impl Default for Mesh {
 // ============================================================================
 
 /// Buffers de mesh separados (mais eficiente para upload GPU)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MeshBuffers {
     /// Posições (x, y, z, x, y, z, ...)
     pub positions: Vec<f32>,
@@ -365,7 +443,8 @@ impl MeshBuffers {
 // ============================================================================
 
 /// Material PBR (Physically Based Rendering) - compatível com glTF
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PbrMaterial {
     pub id: String,
     pub name: String,
@@ -460,7 +539,8 @@ impl PbrMaterial {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AlphaMode {
     Opaque,
     Mask,
@@ -472,10 +552,17 @@ pub enum AlphaMode {
 // ============================================================================
 
 /// Cena 3D - coleção de meshes com materiais
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Scene {
     pub meshes: Vec<Mesh>,
     pub materials: HashMap<String, PbrMaterial>,
+    /// Texturas em memória, referenciadas pelos materiais via ID
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub textures: HashMap<String, TextureBuffer>,
+    /// Nuvens de pontos desenhadas junto com as meshes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub point_clouds: Vec<PointCloud>,
     pub bounds: Aabb,
 }
 
@@ -484,6 +571,8 @@ impl Scene {
         Self {
             meshes: Vec::new(),
             materials: HashMap::new(),
+            textures: HashMap::new(),
+            point_clouds: Vec::new(),
             bounds: Aabb::EMPTY,
         }
     }
@@ -493,10 +582,19 @@ impl Scene {
         self.meshes.push(mesh);
     }
 
+    pub fn add_point_cloud(&mut self, cloud: PointCloud) {
+        self.bounds = self.bounds.merge(&cloud.bounds);
+        self.point_clouds.push(cloud);
+    }
+
     pub fn add_material(&mut self, material: PbrMaterial) {
         self.materials.insert(material.id.clone(), material);
     }
 
+    pub fn add_texture(&mut self, id: impl Into<String>, texture: TextureBuffer) {
+        self.textures.insert(id.into(), texture);
+    }
+
     pub fn mesh_count(&self) -> usize {
         self.meshes.len()
     }
@@ -580,7 +678,7 @@ pub mod primitives {
     }
 
     /// Cria uma esfera (usando subdivisão de icosaedro)
-    pub fn sphere(radius: f32, subdivisions: u32) -> Mesh {
+    pub fn sphere(radius: f32, _subdivisions: u32) -> Mesh {
         // TODO: Implementar subdivisão de icosaedro
         // Por simplicidade, criar esfera UV (lat/lon)
         let mut mesh = Mesh::new();
@@ -589,12 +687,12 @@ pub mod primitives {
         let rings = 16;
 
         for ring in 0..=rings {
-            let phi = std::f32::consts::PI * ring as f32 / rings as f32;
+            let phi = core::f32::consts::PI * ring as f32 / rings as f32;
             let y = radius * phi.cos();
             let ring_radius = radius * phi.sin();
 
             for segment in 0..=segments {
-                let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
+                let theta = 2.0 * core::f32::consts::PI * segment as f32 / segments as f32;
                 let x = ring_radius * theta.cos();
                 let z = ring_radius * theta.sin();
 
@@ -674,6 +772,28 @@ mod tests {
         assert_eq!(mesh2.triangle_count(), mesh1.triangle_count() * 2);
     }
 
+    #[test]
+    fn test_bounding_sphere() {
+        let mut cube = primitives::cube(2.0);
+        for vertex in &cube.vertices {
+            assert!(cube.bounding_sphere.contains_point(vertex.position));
+        }
+
+        cube.recalculate_bounds();
+        assert!(cube.bounding_sphere.center.length() < 1e-5);
+        assert!((cube.bounding_sphere.radius - 3.0f32.sqrt()).abs() < 1e-5);
+
+        cube.transform(&Mat4::translation(Vec3::new(5.0, 0.0, 0.0)));
+        assert!((cube.bounding_sphere.center.x - 5.0).abs() < 1e-5);
+
+        let mut merged = primitives::cube(2.0);
+        merged.recalculate_bounds();
+        merged.merge(&cube);
+        for vertex in &merged.vertices {
+            assert!(merged.bounding_sphere.contains_point(vertex.position));
+        }
+    }
+
     #[test]
     fn test_primitives() {
         let cube = primitives::cube(2.0);
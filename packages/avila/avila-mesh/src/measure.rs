@@ -0,0 +1,260 @@
+//! Medições sobre a malha: snap por raio, distância, área e ângulo
+//!
+//! [`MeshPicker`] lança um raio (ex.: a partir do cursor) contra a malha e
+//! ajusta o ponto atingido para o vértice ou aresta mais próximos do
+//! triângulo, dentro dos raios de [`SnapOptions`]. Só arestas de borda ou
+//! de vinco contam: diagonais internas de faces planas não atraem o snap. Os resultados de
+//! [`Measurement`] serializam em JSON com uma tag `kind`, prontos para a
+//! interface.
+
+use crate::bake::{Hit, TriangleBvh};
+use crate::{Mesh, MeshError, Result};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Cosseno do ângulo abaixo do qual triângulos vizinhos são coplanares (~1°)
+const COPLANAR_COS: f32 = 0.9998;
+
+// ============================================================================
+// SNAP
+// ============================================================================
+
+/// Raios de snap, em unidades de mundo
+///
+/// Para um raio constante em pixels, o chamador escala pelo tamanho do
+/// pixel na distância do ponto.
+#[derive(Debug, Clone)]
+pub struct SnapOptions {
+    pub vertex_radius: f32,
+    pub edge_radius: f32,
+    /// Alcance máximo do raio
+    pub max_distance: f32,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            vertex_radius: 0.05,
+            edge_radius: 0.03,
+            max_distance: f32::INFINITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SnapKind {
+    Vertex,
+    Edge,
+    Face,
+}
+
+/// Ponto escolhido na malha
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SnapPoint {
+    pub kind: SnapKind,
+    pub point: Vec3,
+    /// Normal geométrica do triângulo atingido
+    pub normal: Vec3,
+    pub triangle: usize,
+    /// Distância ao longo do raio até a superfície
+    pub distance: f32,
+}
+
+type EdgeKey = ([u32; 3], [u32; 3]);
+
+/// Aresta por posição (vértices duplicados por face contam como um só)
+fn edge_key(a: Vec3, b: Vec3) -> EdgeKey {
+    let bits = |p: Vec3| p.to_array().map(f32::to_bits);
+    let (a, b) = (bits(a), bits(b));
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+fn face_normal([a, b, c]: [Vec3; 3]) -> Vec3 {
+    (b - a).cross(&(c - a)).normalize().unwrap_or(Vec3::Z)
+}
+
+/// Raycasting com snap; monta o BVH e as arestas de vinco uma vez por malha
+pub struct MeshPicker<'a> {
+    bvh: TriangleBvh<'a>,
+    feature_edges: HashSet<EdgeKey>,
+}
+
+impl<'a> MeshPicker<'a> {
+    pub fn new(mesh: &'a Mesh) -> Self {
+        let bvh = TriangleBvh::build(mesh);
+        let mut normals: HashMap<EdgeKey, Vec<Vec3>> = HashMap::new();
+        for triangle in 0..mesh.triangle_count() {
+            let corners @ [a, b, c] = bvh.corners(triangle);
+            let normal = face_normal(corners);
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                normals.entry(edge_key(start, end)).or_default().push(normal);
+            }
+        }
+        let feature_edges = normals
+            .into_iter()
+            .filter(|(_, n)| n.len() != 2 || n[0].dot(&n[1]) < COPLANAR_COS)
+            .map(|(edge, _)| edge)
+            .collect();
+        Self { bvh, feature_edges }
+    }
+
+    /// Primeiro ponto da malha ao longo do raio, com snap a vértice/aresta
+    pub fn pick(&self, ray: &Ray, options: &SnapOptions) -> Option<SnapPoint> {
+        let Hit { t, triangle, .. } = self.bvh.intersect(ray, options.max_distance)?;
+        let corners = self.bvh.corners(triangle);
+        let [a, b, c] = corners;
+        let point = ray.at(t);
+        let normal = face_normal(corners);
+        let snapped = |kind, point| SnapPoint { kind, point, normal, triangle, distance: t };
+
+        let vertex = corners
+            .iter()
+            .copied()
+            .min_by(|p, q| p.distance_squared(&point).total_cmp(&q.distance_squared(&point)))?;
+        if vertex.distance(&point) <= options.vertex_radius {
+            return Some(snapped(SnapKind::Vertex, vertex));
+        }
+
+        let edge = [(a, b), (b, c), (c, a)]
+            .iter()
+            .filter(|&&(start, end)| self.feature_edges.contains(&edge_key(start, end)))
+            .map(|&(start, end)| closest_on_segment(point, start, end))
+            .min_by(|p, q| p.distance_squared(&point).total_cmp(&q.distance_squared(&point)));
+        if let Some(edge) = edge.filter(|edge| edge.distance(&point) <= options.edge_radius) {
+            return Some(snapped(SnapKind::Edge, edge));
+        }
+
+        Some(snapped(SnapKind::Face, point))
+    }
+}
+
+fn closest_on_segment(point: Vec3, start: Vec3, end: Vec3) -> Vec3 {
+    let direction = end - start;
+    let length_sq = direction.length_squared();
+    if length_sq < f32::EPSILON {
+        return start;
+    }
+    let t = ((point - start).dot(&direction) / length_sq).clamp(0.0, 1.0);
+    start + direction * t
+}
+
+// ============================================================================
+// MEDIÇÕES
+// ============================================================================
+
+/// Resultado de uma medição
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Measurement {
+    Distance {
+        from: Vec3,
+        to: Vec3,
+        /// Componentes X/Y/Z da distância (útil para cotas alinhadas aos eixos)
+        delta: Vec3,
+        value: f32,
+    },
+    Area {
+        points: Vec<Vec3>,
+        value: f32,
+        perimeter: f32,
+    },
+    Angle {
+        /// Graus, em [0, 180]
+        degrees: f32,
+    },
+}
+
+impl Measurement {
+    /// Valor principal (metros, metros² ou graus)
+    pub fn value(&self) -> f32 {
+        match self {
+            Self::Distance { value, .. } | Self::Area { value, .. } => *value,
+            Self::Angle { degrees } => *degrees,
+        }
+    }
+}
+
+pub fn measure_distance(from: Vec3, to: Vec3) -> Measurement {
+    let delta = to - from;
+    Measurement::Distance { from, to, delta, value: delta.length() }
+}
+
+/// Área de um polígono planar (normal de Newell), fechado implicitamente
+pub fn measure_area(points: &[Vec3]) -> Result<Measurement> {
+    if points.len() < 3 {
+        return Err(MeshError::GeometryError("Area needs at least 3 points".into()));
+    }
+    let mut newell = Vec3::ZERO;
+    let mut perimeter = 0.0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        newell = newell + a.cross(&b);
+        perimeter += a.distance(&b);
+    }
+    Ok(Measurement::Area { points: points.to_vec(), value: newell.length() / 2.0, perimeter })
+}
+
+/// Ângulo entre duas faces, pelas suas normais (0° = paralelas, mesmo sentido)
+pub fn measure_face_angle(normal_a: Vec3, normal_b: Vec3) -> Result<Measurement> {
+    Ok(Measurement::Angle { degrees: normal_a.angle_to(&normal_b)?.to_degrees() })
+}
+
+/// Ângulo em `vertex` entre os segmentos até `a` e até `b`
+pub fn measure_angle(a: Vec3, vertex: Vec3, b: Vec3) -> Result<Measurement> {
+    measure_face_angle(a - vertex, b - vertex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_pick_snaps_to_features() {
+        let cube = primitives::cube(2.0);
+        let picker = MeshPicker::new(&cube);
+        let options = SnapOptions { vertex_radius: 0.1, edge_radius: 0.1, max_distance: 100.0 };
+        let down = |x: f32, y: f32| Ray::new(Vec3::new(x, y, 10.0), -Vec3::Z).unwrap();
+
+        let vertex = picker.pick(&down(0.95, 0.97), &options).unwrap();
+        assert_eq!(vertex.kind, SnapKind::Vertex);
+        assert_eq!(vertex.point, Vec3::new(1.0, 1.0, 1.0));
+        assert_relative_eq!(vertex.distance, 9.0, epsilon = 1e-4);
+
+        let edge = picker.pick(&down(0.3, -0.95), &options).unwrap();
+        assert_eq!(edge.kind, SnapKind::Edge);
+        assert_relative_eq!(edge.point.x, 0.3, epsilon = 1e-5);
+        assert_relative_eq!(edge.point.y, -1.0, epsilon = 1e-5);
+
+        let face = picker.pick(&down(0.3, 0.4), &options).unwrap();
+        assert_eq!(face.kind, SnapKind::Face);
+        assert_relative_eq!(face.normal.z, 1.0, epsilon = 1e-5);
+
+        assert!(picker.pick(&down(5.0, 5.0), &options).is_none());
+    }
+
+    #[test]
+    fn test_measurements() {
+        let distance = measure_distance(Vec3::ZERO, Vec3::new(3.0, 4.0, 0.0));
+        assert_relative_eq!(distance.value(), 5.0);
+
+        let square = [Vec3::ZERO, Vec3::new(2.0, 0.0, 1.0), Vec3::new(2.0, 3.0, 1.0), Vec3::new(0.0, 3.0, 0.0)];
+        let Measurement::Area { value, perimeter, .. } = measure_area(&square).unwrap() else { panic!() };
+        assert_relative_eq!(value, 3.0 * 5.0f32.sqrt(), epsilon = 1e-5);
+        assert_relative_eq!(perimeter, 2.0 * 5.0f32.sqrt() + 6.0, epsilon = 1e-5);
+        assert!(measure_area(&square[..2]).is_err());
+
+        assert_relative_eq!(measure_face_angle(Vec3::Z, Vec3::X).unwrap().value(), 90.0, epsilon = 1e-4);
+        assert_relative_eq!(measure_angle(Vec3::X, Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0)).unwrap().value(), 45.0, epsilon = 1e-4);
+    }
+}
@@ -0,0 +1,140 @@
+//! Nuvens de pontos (scan-to-BIM)
+//!
+//! [`PointCloud`] guarda os atributos em vetores paralelos (posição, cor
+//! RGB, intensidade), no formato que a GPU e o glTF consomem. Cor e
+//! intensidade são opcionais: vetor vazio significa atributo ausente.
+//!
+//! Nuvens entram na [`Scene`](crate::Scene) ao lado das meshes com
+//! [`Scene::add_point_cloud`](crate::Scene::add_point_cloud); arquivos LAS
+//! são lidos por [`crate::las`].
+
+use crate::{MeshError, Result};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Um ponto com todos os atributos
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Point {
+    pub position: Vec3,
+    pub color: Option<[u8; 3]>,
+    pub intensity: Option<u16>,
+}
+
+impl Point {
+    pub fn new(position: Vec3) -> Self {
+        Self { position, color: None, intensity: None }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PointCloud {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+    pub positions: Vec<Vec3>,
+    /// RGB por ponto (vazio = sem cor)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub colors: Vec<[u8; 3]>,
+    /// Intensidade do retorno do laser (vazio = sem intensidade)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub intensities: Vec<u16>,
+    pub bounds: Aabb,
+}
+
+impl Default for PointCloud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PointCloud {
+    pub fn new() -> Self {
+        Self::with_capacity(0, false, false)
+    }
+
+    pub fn with_capacity(points: usize, colors: bool, intensities: bool) -> Self {
+        Self {
+            name: None,
+            positions: Vec::with_capacity(points),
+            colors: Vec::with_capacity(if colors { points } else { 0 }),
+            intensities: Vec::with_capacity(if intensities { points } else { 0 }),
+            bounds: Aabb::EMPTY,
+        }
+    }
+
+    /// Adiciona o ponto; cor e intensidade devem vir em todos os pontos ou em nenhum
+    pub fn push(&mut self, point: Point) {
+        self.bounds.expand_point(point.position);
+        self.positions.push(point.position);
+        if let Some(color) = point.color {
+            self.colors.push(color);
+        }
+        if let Some(intensity) = point.intensity {
+            self.intensities.push(intensity);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn has_colors(&self) -> bool {
+        !self.colors.is_empty()
+    }
+
+    pub fn has_intensities(&self) -> bool {
+        !self.intensities.is_empty()
+    }
+
+    pub fn point(&self, index: usize) -> Point {
+        Point {
+            position: self.positions[index],
+            color: self.colors.get(index).copied(),
+            intensity: self.intensities.get(index).copied(),
+        }
+    }
+
+    /// Nova nuvem com os pontos `indices`, na ordem dada
+    pub fn subset(&self, indices: &[usize]) -> Self {
+        let mut cloud = Self::with_capacity(indices.len(), self.has_colors(), self.has_intensities());
+        cloud.name = self.name.clone();
+        indices.iter().for_each(|&i| cloud.push(self.point(i)));
+        cloud
+    }
+
+    /// Atributos opcionais com o mesmo comprimento das posições
+    pub fn validate(&self) -> Result<()> {
+        let check = |name: &str, len: usize| {
+            if len != 0 && len != self.len() {
+                return Err(MeshError::InvalidMesh(format!(
+                    "Point cloud has {} {} for {} points",
+                    len,
+                    name,
+                    self.len()
+                )));
+            }
+            Ok(())
+        };
+        check("colors", self.colors.len())?;
+        check("intensities", self.intensities.len())
+    }
+
+    pub fn recalculate_bounds(&mut self) {
+        self.bounds = Aabb::from_points(&self.positions);
+    }
+
+    pub fn transform(&mut self, matrix: &Mat4) {
+        for position in &mut self.positions {
+            *position = matrix.transform_point(*position);
+        }
+        self.recalculate_bounds();
+    }
+}
@@ -0,0 +1,280 @@
+//! Renderização por software (headless)
+//!
+//! Rasterizador com z-buffer para miniaturas no servidor e testes de
+//! regressão visual, sem GPU nem contexto gráfico. Sombreamento flat com
+//! luz na direção da câmera, na cor base do material; transparência e
+//! texturas são ignoradas. O resultado é um [`TextureBuffer`] RGB, ou PNG
+//! direto com [`Scene::render_to_png`].
+//!
+//! Convenções: clip space do OpenGL (z ∈ [-w, w]) e Z para cima ao
+//! enquadrar com [`Camera::fit`], como no IFC.
+
+use crate::{MeshError, Result, Scene, TextureBuffer, TextureFormat};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use avila_vec3d::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Cor de fundo das imagens
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+/// Cor das meshes sem material
+const DEFAULT_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+const AMBIENT: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "camelCase"))]
+pub enum Projection {
+    /// Campo de visão vertical em radianos
+    Perspective { fov_y: f32 },
+    /// Altura da área visível em unidades de mundo
+    Orthographic { height: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Camera {
+    pub eye: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub projection: Projection,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Camera {
+    /// Câmera perspectiva (45°) olhando de `eye` para `target`
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self {
+            eye,
+            target,
+            up,
+            projection: Projection::Perspective { fov_y: core::f32::consts::FRAC_PI_4 },
+            near: 0.1,
+            far: 1000.0,
+        }
+    }
+
+    /// Enquadra `bounds` olhando na `direction`, mantendo a projeção dada
+    pub fn fit(bounds: &Aabb, direction: Vec3, projection: Projection) -> Result<Self> {
+        if bounds.min.x > bounds.max.x {
+            return Err(MeshError::GeometryError("Cannot fit camera to empty bounds".into()));
+        }
+        let direction = direction.normalize()?;
+        let center = bounds.center();
+        let radius = (bounds.size().length() / 2.0).max(1e-3);
+        let (distance, projection) = match projection {
+            Projection::Perspective { fov_y } => (radius / (fov_y / 2.0).sin(), projection),
+            Projection::Orthographic { .. } => (2.0 * radius, Projection::Orthographic { height: 2.0 * radius }),
+        };
+        let up = if direction.cross(&Vec3::Z).length() < 1e-3 { Vec3::Y } else { Vec3::Z };
+        Ok(Self {
+            eye: center - direction * distance,
+            target: center,
+            up,
+            projection,
+            near: (distance - radius).max(distance * 1e-3),
+            far: distance + radius,
+        })
+    }
+
+    /// Matriz `projection * view` para a proporção largura/altura
+    pub fn view_projection(&self, aspect: f32) -> Result<Mat4> {
+        let forward = (self.target - self.eye).normalize()?;
+        let side = forward.cross(&self.up).normalize()?;
+        let up = side.cross(&forward);
+        let view = Mat4 {
+            m: [
+                [side.x, up.x, -forward.x, 0.0],
+                [side.y, up.y, -forward.y, 0.0],
+                [side.z, up.z, -forward.z, 0.0],
+                [-side.dot(&self.eye), -up.dot(&self.eye), forward.dot(&self.eye), 1.0],
+            ],
+        };
+
+        let (near, far) = (self.near, self.far);
+        let projection = match self.projection {
+            Projection::Perspective { fov_y } => {
+                let f = 1.0 / (fov_y / 2.0).tan();
+                Mat4 {
+                    m: [
+                        [f / aspect, 0.0, 0.0, 0.0],
+                        [0.0, f, 0.0, 0.0],
+                        [0.0, 0.0, (far + near) / (near - far), -1.0],
+                        [0.0, 0.0, 2.0 * far * near / (near - far), 0.0],
+                    ],
+                }
+            }
+            Projection::Orthographic { height } => {
+                let half = height / 2.0;
+                Mat4 {
+                    m: [
+                        [1.0 / (half * aspect), 0.0, 0.0, 0.0],
+                        [0.0, 1.0 / half, 0.0, 0.0],
+                        [0.0, 0.0, -2.0 / (far - near), 0.0],
+                        [0.0, 0.0, -(far + near) / (far - near), 1.0],
+                    ],
+                }
+            }
+        };
+        Ok(projection.mul_mat4(&view))
+    }
+}
+
+/// Vértice em clip space
+type Clip = [f32; 4];
+
+fn to_clip(matrix: &Mat4, p: Vec3) -> Clip {
+    let m = &matrix.m;
+    [0, 1, 2, 3].map(|row| m[0][row] * p.x + m[1][row] * p.y + m[2][row] * p.z + m[3][row])
+}
+
+/// Recorta o triângulo pelo plano near (`z + w >= 0`); devolve um polígono
+fn clip_near(triangle: [Clip; 3]) -> Vec<Clip> {
+    let mut polygon = Vec::with_capacity(4);
+    for i in 0..3 {
+        let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+        let (da, db) = (a[2] + a[3], b[2] + b[3]);
+        if da >= 0.0 {
+            polygon.push(a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            let t = da / (da - db);
+            polygon.push([0, 1, 2, 3].map(|k| a[k] + (b[k] - a[k]) * t));
+        }
+    }
+    polygon
+}
+
+impl Scene {
+    /// Renderiza a cena numa imagem RGB `width` × `height`
+    pub fn render(&self, width: u32, height: u32, camera: &Camera) -> Result<TextureBuffer> {
+        if width == 0 || height == 0 {
+            return Err(MeshError::GeometryError("Image size must be positive".into()));
+        }
+        let view_projection = camera.view_projection(width as f32 / height as f32)?;
+        let view_direction = (camera.target - camera.eye).normalize()?;
+
+        let mut image = TextureBuffer::filled(width, height, TextureFormat::Rgb8, &BACKGROUND);
+        let mut depth = vec![f32::INFINITY; width as usize * height as usize];
+        let (w, h) = (width as f32, height as f32);
+
+        for mesh in &self.meshes {
+            let color = mesh
+                .material_id
+                .as_ref()
+                .and_then(|id| self.materials.get(id))
+                .map_or(DEFAULT_COLOR, |m| [m.base_color_factor[0], m.base_color_factor[1], m.base_color_factor[2]]);
+
+            for triangle in mesh.indices.chunks_exact(3) {
+                let corners = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].position);
+                let normal = (corners[1] - corners[0]).cross(&(corners[2] - corners[0]));
+                let Ok(normal) = normal.normalize() else { continue };
+
+                // Duas faces: a luz vem da câmera
+                let shade = AMBIENT + (1.0 - AMBIENT) * normal.dot(&view_direction).abs();
+                let pixel = color.map(|c| (c * shade * 255.0).round().clamp(0.0, 255.0) as u8);
+
+                let polygon = clip_near(corners.map(|p| to_clip(&view_projection, p)));
+                let screen: Vec<[f32; 3]> = polygon
+                    .iter()
+                    .map(|&[x, y, z, cw]| {
+                        let cw = cw.max(f32::EPSILON);
+                        [(x / cw + 1.0) / 2.0 * w, (1.0 - y / cw) / 2.0 * h, z / cw]
+                    })
+                    .collect();
+                for i in 1..screen.len().saturating_sub(1) {
+                    let fan = [screen[0], screen[i], screen[i + 1]];
+                    rasterize(fan, &pixel, &mut image, &mut depth);
+                }
+            }
+        }
+        Ok(image)
+    }
+
+    /// Renderiza e codifica em PNG
+    pub fn render_to_png(&self, width: u32, height: u32, camera: &Camera) -> Result<Vec<u8>> {
+        Ok(self.render(width, height, camera)?.encode_png())
+    }
+}
+
+/// Preenche os pixels cujo centro está no triângulo (em pixels, z em NDC)
+fn rasterize([a, b, c]: [[f32; 3]; 3], color: &[u8; 3], image: &mut TextureBuffer, depth: &mut [f32]) {
+    let edge = |p: [f32; 3], q: [f32; 3], x: f32, y: f32| (q[0] - p[0]) * (y - p[1]) - (q[1] - p[1]) * (x - p[0]);
+    let area = edge(a, b, c[0], c[1]);
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+
+    let (width, height) = (image.width as f32, image.height as f32);
+    let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.0) as u32;
+    let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.0) as u32;
+    let max_x = a[0].max(b[0]).max(c[0]).ceil().min(width) as u32;
+    let max_y = a[1].max(b[1]).max(c[1]).ceil().min(height) as u32;
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let weights = [edge(b, c, px, py) / area, edge(c, a, px, py) / area, edge(a, b, px, py) / area];
+            if weights.iter().any(|&w| w < 0.0) {
+                continue;
+            }
+            let z = weights[0] * a[2] + weights[1] * b[2] + weights[2] * c[2];
+            let index = y as usize * image.width as usize + x as usize;
+            if z > 1.0 || z >= depth[index] {
+                continue;
+            }
+            depth[index] = z;
+            image.set_pixel(x, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{primitives, PbrMaterial};
+
+    fn red_cube() -> Scene {
+        let mut scene = Scene::new();
+        let mut cube = primitives::cube(2.0);
+        cube.material_id = Some("red".into());
+        scene.add_mesh(cube);
+        let mut red = PbrMaterial::default_material("red");
+        red.base_color_factor = [1.0, 0.0, 0.0, 1.0];
+        scene.add_material(red);
+        scene
+    }
+
+    #[test]
+    fn test_render_fitted_views() {
+        let scene = red_cube();
+        let perspective = Projection::Perspective { fov_y: 0.8 };
+        let camera = Camera::fit(&scene.bounds, Vec3::new(-1.0, 1.0, -1.0), perspective).unwrap();
+        let image = scene.render(32, 24, &camera).unwrap();
+
+        let center = image.pixel(16, 12);
+        assert!(center[0] > 0 && center[1] == 0 && center[2] == 0, "{:?}", center);
+        assert_eq!(image.pixel(0, 0), &BACKGROUND);
+
+        // Vista de topo ortográfica: face de cima iluminada de frente
+        let top = Camera::fit(&scene.bounds, -Vec3::Z, Projection::Orthographic { height: 1.0 }).unwrap();
+        let image = scene.render(16, 16, &top).unwrap();
+        assert_eq!(image.pixel(8, 8), &[255, 0, 0]);
+
+        let png = scene.render_to_png(8, 8, &top).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert!(scene.render(0, 8, &top).is_err());
+    }
+
+    #[test]
+    fn test_render_clips_near_plane() {
+        // Câmera dentro de uma caixa grande: as paredes cruzam o plano near
+        let mut scene = Scene::new();
+        scene.add_mesh(primitives::cube(10.0));
+        let camera = Camera::look_at(Vec3::ZERO, Vec3::new(1.0, 0.3, 0.2), Vec3::Z);
+        let image = scene.render(20, 20, &camera).unwrap();
+        assert!(image.data.chunks_exact(3).all(|p| p != BACKGROUND));
+    }
+}
@@ -0,0 +1,469 @@
+//! Edição de propriedades com change sets
+//!
+//! Edições feitas no visualizador (tag de patrimônio, data da última
+//! manutenção, ...) não alteram o IFC: ficam num [`ChangeSet`], uma lista
+//! ordenada de operações por GUID/Property Set/propriedade, serializável e
+//! aplicada sobre o [`BimMetadata`] extraído.
+//!
+//! ```json
+//! {
+//!   "author": "manutencao@empresa.com",
+//!   "edits": [
+//!     { "guid": "2O_RrAJHv7xv2dl5cNZYOF", "propertySet": "Pset_Asset",
+//!       "name": "AssetTag", "op": "add", "value": "PAT-0042" },
+//!     { "guid": "2O_RrAJHv7xv2dl5cNZYOF", "propertySet": "Pset_Asset",
+//!       "name": "LastMaintenance", "op": "update",
+//!       "previous": "2023-11-02", "value": "2024-05-17" }
+//!   ]
+//! }
+//! ```
+//!
+//! A aplicação é atômica: os tipos são conferidos contra um
+//! [`PropertySchema`] e, se uma edição falha (GUID inexistente, `add` de
+//! propriedade existente, `previous` diferente do valor atual, ...), nada é
+//! alterado. Change sets feitos em paralelo sobre a mesma revisão são
+//! combinados com [`ChangeSet::merge`], que aponta as propriedades editadas
+//! dos dois lados com resultados diferentes.
+
+use crate::{BimMetadata, MetadataError, PropertyValue, Result, ScheduleDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Tipo de valor aceito por uma propriedade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PropertyType {
+    Text,
+    Number,
+    Boolean,
+    /// Texto no formato `YYYY-MM-DD`
+    Date,
+}
+
+impl PropertyType {
+    /// Tipo natural do valor (textos são sempre [`PropertyType::Text`])
+    pub fn of(value: &PropertyValue) -> Self {
+        match value {
+            PropertyValue::String(_) => Self::Text,
+            PropertyValue::Number(_) => Self::Number,
+            PropertyValue::Boolean(_) => Self::Boolean,
+        }
+    }
+
+    pub fn accepts(&self, value: &PropertyValue) -> bool {
+        match (self, value) {
+            (Self::Text, PropertyValue::String(_)) => true,
+            (Self::Number, PropertyValue::Number(n)) => n.is_finite(),
+            (Self::Boolean, PropertyValue::Boolean(_)) => true,
+            (Self::Date, PropertyValue::String(s)) => s.parse::<ScheduleDate>().is_ok(),
+            _ => false,
+        }
+    }
+}
+
+/// Tipos esperados por Property Set e propriedade
+///
+/// Propriedades fora do schema aceitam qualquer valor.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PropertySchema {
+    pub types: BTreeMap<String, BTreeMap<String, PropertyType>>,
+}
+
+impl PropertySchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schema com os tipos já presentes no modelo
+    ///
+    /// Propriedades com valores de tipos diferentes entre elementos ficam
+    /// de fora.
+    pub fn infer(metadata: &BimMetadata) -> Self {
+        let mut seen: BTreeMap<(&str, &str), Option<PropertyType>> = BTreeMap::new();
+        for element in &metadata.elements {
+            for (pset, properties) in &element.properties {
+                for (name, value) in properties {
+                    let found = PropertyType::of(value);
+                    seen.entry((pset.as_str(), name.as_str()))
+                        .and_modify(|ty| {
+                            if *ty != Some(found) {
+                                *ty = None;
+                            }
+                        })
+                        .or_insert(Some(found));
+                }
+            }
+        }
+
+        let mut schema = Self::new();
+        for ((pset, name), ty) in seen {
+            if let Some(ty) = ty {
+                schema.define(pset, name, ty);
+            }
+        }
+        schema
+    }
+
+    pub fn define(&mut self, property_set: impl Into<String>, name: impl Into<String>, ty: PropertyType) {
+        self.types.entry(property_set.into()).or_default().insert(name.into(), ty);
+    }
+
+    pub fn get(&self, property_set: &str, name: &str) -> Option<PropertyType> {
+        self.types.get(property_set).and_then(|p| p.get(name)).copied()
+    }
+}
+
+/// Operação sobre uma propriedade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum PropertyOperation {
+    /// Cria a propriedade (erro se já existe)
+    Add { value: PropertyValue },
+    /// Troca o valor; com `previous`, só se o valor atual for esse
+    Update {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<PropertyValue>,
+        value: PropertyValue,
+    },
+    /// Remove a propriedade; com `previous`, só se o valor atual for esse
+    Delete {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<PropertyValue>,
+    },
+}
+
+impl PropertyOperation {
+    /// Valor depois da operação (`None` = removida)
+    pub fn result(&self) -> Option<&PropertyValue> {
+        match self {
+            Self::Add { value } | Self::Update { value, .. } => Some(value),
+            Self::Delete { .. } => None,
+        }
+    }
+}
+
+/// Uma edição: propriedade alvo e operação
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyEdit {
+    pub guid: String,
+    pub property_set: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub operation: PropertyOperation,
+}
+
+impl PropertyEdit {
+    fn key(&self) -> PropertyKey {
+        (self.guid.clone(), self.property_set.clone(), self.name.clone())
+    }
+}
+
+/// GUID, Property Set e nome
+type PropertyKey = (String, String, String);
+
+/// Property Sets de um elemento
+type Properties = HashMap<String, HashMap<String, PropertyValue>>;
+
+/// Lista ordenada de edições de propriedades
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeSet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub edits: Vec<PropertyEdit>,
+}
+
+/// Propriedade editada nos dois change sets com resultados diferentes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeConflict {
+    pub guid: String,
+    pub property_set: String,
+    pub name: String,
+    /// Valor final em cada lado (`None` = removida)
+    pub ours: Option<PropertyValue>,
+    pub theirs: Option<PropertyValue>,
+}
+
+/// Resultado de [`ChangeSet::merge`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeSetMerge {
+    /// Edições combinadas; nos conflitos, vale o primeiro change set
+    pub merged: ChangeSet,
+    pub conflicts: Vec<ChangeConflict>,
+}
+
+impl ChangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_author(author: impl Into<String>) -> Self {
+        Self { author: Some(author.into()), ..Self::default() }
+    }
+
+    pub fn push(&mut self, guid: &str, property_set: &str, name: &str, operation: PropertyOperation) -> &mut Self {
+        self.edits.push(PropertyEdit {
+            guid: guid.to_string(),
+            property_set: property_set.to_string(),
+            name: name.to_string(),
+            operation,
+        });
+        self
+    }
+
+    pub fn add(&mut self, guid: &str, property_set: &str, name: &str, value: PropertyValue) -> &mut Self {
+        self.push(guid, property_set, name, PropertyOperation::Add { value })
+    }
+
+    pub fn update(&mut self, guid: &str, property_set: &str, name: &str, value: PropertyValue) -> &mut Self {
+        self.push(guid, property_set, name, PropertyOperation::Update { previous: None, value })
+    }
+
+    pub fn delete(&mut self, guid: &str, property_set: &str, name: &str) -> &mut Self {
+        self.push(guid, property_set, name, PropertyOperation::Delete { previous: None })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Confere as edições sem alterar o modelo
+    pub fn validate(&self, metadata: &BimMetadata, schema: &PropertySchema) -> Result<()> {
+        self.simulate(metadata, schema).map(|_| ())
+    }
+
+    /// Aplica todas as edições, ou nenhuma se alguma for inválida
+    pub fn apply(&self, metadata: &mut BimMetadata, schema: &PropertySchema) -> Result<()> {
+        for (index, properties) in self.simulate(metadata, schema)? {
+            metadata.elements[index].properties = properties;
+        }
+        Ok(())
+    }
+
+    /// Executa as edições em cópias das propriedades dos elementos tocados
+    fn simulate(&self, metadata: &BimMetadata, schema: &PropertySchema) -> Result<HashMap<usize, Properties>> {
+        let mut index = HashMap::with_capacity(metadata.elements.len());
+        for (i, element) in metadata.elements.iter().enumerate() {
+            index.entry(element.guid.as_str()).or_insert(i);
+        }
+
+        let mut touched: HashMap<usize, Properties> = HashMap::new();
+        for (position, edit) in self.edits.iter().enumerate() {
+            let invalid = |message: String| {
+                MetadataError::InvalidChange(format!(
+                    "edit {} ({} {}.{}): {}",
+                    position, edit.guid, edit.property_set, edit.name, message
+                ))
+            };
+            let &element = index.get(edit.guid.as_str()).ok_or_else(|| invalid("unknown element".into()))?;
+
+            if let (Some(value), Some(ty)) = (edit.operation.result(), schema.get(&edit.property_set, &edit.name)) {
+                if !ty.accepts(value) {
+                    return Err(invalid(format!("expected {:?}, got {:?}", ty, value)));
+                }
+            }
+
+            let properties = touched.entry(element).or_insert_with(|| metadata.elements[element].properties.clone());
+            let current = properties.get(&edit.property_set).and_then(|p| p.get(&edit.name));
+            let previous = match &edit.operation {
+                PropertyOperation::Add { .. } => {
+                    if current.is_some() {
+                        return Err(invalid("property already exists".into()));
+                    }
+                    None
+                }
+                PropertyOperation::Update { previous, .. } | PropertyOperation::Delete { previous } => {
+                    if current.is_none() {
+                        return Err(invalid("property does not exist".into()));
+                    }
+                    previous.as_ref()
+                }
+            };
+            if let Some(previous) = previous.filter(|&p| Some(p) != current) {
+                return Err(invalid(format!("expected current value {:?}, found {:?}", previous, current)));
+            }
+
+            match edit.operation.result() {
+                Some(value) => {
+                    properties.entry(edit.property_set.clone()).or_default().insert(edit.name.clone(), value.clone());
+                }
+                None => {
+                    if let Some(pset) = properties.get_mut(&edit.property_set) {
+                        pset.remove(&edit.name);
+                        if pset.is_empty() {
+                            properties.remove(&edit.property_set);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(touched)
+    }
+
+    /// Valor final de cada propriedade tocada
+    fn results(&self) -> BTreeMap<PropertyKey, Option<&PropertyValue>> {
+        self.edits.iter().map(|edit| (edit.key(), edit.operation.result())).collect()
+    }
+
+    /// Combina dois change sets feitos sobre a mesma revisão
+    ///
+    /// Propriedades editadas só em um lado entram como estão; editadas nos
+    /// dois lados com o mesmo resultado entram uma vez. Resultados
+    /// diferentes viram [`ChangeConflict`] e ficam com as edições de `self`.
+    pub fn merge(&self, other: &ChangeSet) -> ChangeSetMerge {
+        let ours = self.results();
+        let theirs = other.results();
+
+        let conflicts = ours
+            .iter()
+            .filter_map(|(key, &our)| {
+                let &their = theirs.get(key)?;
+                (our != their).then(|| ChangeConflict {
+                    guid: key.0.clone(),
+                    property_set: key.1.clone(),
+                    name: key.2.clone(),
+                    ours: our.cloned(),
+                    theirs: their.cloned(),
+                })
+            })
+            .collect();
+
+        let mut merged = self.clone();
+        merged.edits.extend(other.edits.iter().filter(|edit| !ours.contains_key(&edit.key())).cloned());
+        ChangeSetMerge { merged, conflicts }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementMetadata, GeometryHealth, ModelStatistics, ProjectInfo, RelationGraph, SpatialStructure, UnitContext};
+
+    fn metadata() -> BimMetadata {
+        let element = |guid: &str| {
+            let mut asset = HashMap::new();
+            asset.insert("AssetTag".to_string(), PropertyValue::String(format!("PAT-{}", guid)));
+            asset.insert("LastMaintenance".to_string(), PropertyValue::String("2023-11-02".to_string()));
+            ElementMetadata {
+                guid: guid.to_string(),
+                ifc_type: "IfcPump".to_string(),
+                mesh_node: None,
+                name: format!("Bomba {}", guid),
+                description: None,
+                properties: HashMap::from([("Pset_Asset".to_string(), asset)]),
+                quantities: HashMap::new(),
+                original_quantities: HashMap::new(),
+                material: None,
+                bounding_box: None,
+                mesh_hash: None,
+                tags: vec![],
+            }
+        };
+        BimMetadata {
+            elements: vec![element("A"), element("B")],
+            structure: SpatialStructure {
+                project: ProjectInfo { name: "Teste".to_string(), description: None, author: None, organization: None },
+                site: None,
+                buildings: vec![],
+                storeys: vec![],
+            },
+            statistics: ModelStatistics {
+                total_elements: 2,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations: RelationGraph::default(),
+            units: UnitContext::default(),
+        }
+    }
+
+    fn text(value: &str) -> PropertyValue {
+        PropertyValue::String(value.to_string())
+    }
+
+    #[test]
+    fn test_change_set() {
+        let mut model = metadata();
+        let mut schema = PropertySchema::infer(&model);
+        assert_eq!(schema.get("Pset_Asset", "AssetTag"), Some(PropertyType::Text));
+        schema.define("Pset_Asset", "LastMaintenance", PropertyType::Date);
+        schema.define("Pset_Asset", "Critical", PropertyType::Boolean);
+
+        let mut changes = ChangeSet::with_author("fm");
+        changes
+            .update("A", "Pset_Asset", "LastMaintenance", text("2024-05-17"))
+            .add("A", "Pset_Asset", "Critical", PropertyValue::Boolean(true))
+            .delete("B", "Pset_Asset", "AssetTag")
+            .delete("B", "Pset_Asset", "LastMaintenance");
+        changes.apply(&mut model, &schema).unwrap();
+
+        assert_eq!(model.elements[0].properties["Pset_Asset"]["LastMaintenance"], text("2024-05-17"));
+        assert_eq!(model.elements[0].properties["Pset_Asset"]["Critical"], PropertyValue::Boolean(true));
+        // Property Set vazio é removido
+        assert!(model.elements[1].properties.is_empty());
+
+        let restored = ChangeSet::from_json(&changes.to_json().unwrap()).unwrap();
+        assert_eq!(restored, changes);
+        assert!(changes.to_json().unwrap().contains("\"op\": \"delete\""));
+
+        // Erros não alteram nada
+        let before = metadata();
+        let mut model = metadata();
+        for bad in [
+            ChangeSet::new().update("A", "Pset_Asset", "LastMaintenance", text("17/05/2024")).clone(),
+            ChangeSet::new().add("A", "Pset_Asset", "AssetTag", text("X")).clone(),
+            ChangeSet::new().delete("Z", "Pset_Asset", "AssetTag").clone(),
+            ChangeSet::new()
+                .update("A", "Pset_Asset", "AssetTag", text("PAT-9"))
+                .push("A", "Pset_Asset", "AssetTag", PropertyOperation::Delete { previous: Some(text("PAT-A")) })
+                .clone(),
+        ] {
+            assert!(bad.apply(&mut model, &schema).is_err());
+            assert_eq!(model.elements[0].properties, before.elements[0].properties);
+        }
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let mut ours = ChangeSet::new();
+        ours.update("A", "Pset_Asset", "AssetTag", text("PAT-1"))
+            .update("A", "Pset_Asset", "LastMaintenance", text("2024-05-17"));
+        let mut theirs = ChangeSet::new();
+        theirs
+            .update("A", "Pset_Asset", "AssetTag", text("PAT-2"))
+            .update("A", "Pset_Asset", "LastMaintenance", text("2024-05-17"))
+            .delete("B", "Pset_Asset", "AssetTag");
+
+        let merge = ours.merge(&theirs);
+        assert_eq!(merge.conflicts, vec![ChangeConflict {
+            guid: "A".to_string(),
+            property_set: "Pset_Asset".to_string(),
+            name: "AssetTag".to_string(),
+            ours: Some(text("PAT-1")),
+            theirs: Some(text("PAT-2")),
+        }]);
+        assert_eq!(merge.merged.edits.len(), 3);
+
+        let mut model = metadata();
+        merge.merged.apply(&mut model, &PropertySchema::new()).unwrap();
+        assert_eq!(model.elements[0].properties["Pset_Asset"]["AssetTag"], text("PAT-1"));
+        assert!(!model.elements[1].properties["Pset_Asset"].contains_key("AssetTag"));
+    }
+}
@@ -0,0 +1,433 @@
+//! Diff entre revisões de um modelo BIM
+//!
+//! Compara dois `BimMetadata` casando elementos pelo GUID e reporta
+//! elementos adicionados, removidos e modificados, com deltas por
+//! propriedade/quantidade e flags de alteração de geometria.
+
+use crate::{BimMetadata, ElementMetadata, PropertyValue, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Tolerância para comparar quantidades (relativa ao maior valor)
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+/// Tolerância para comparar bounding boxes (unidades do modelo)
+const BBOX_EPSILON: f32 = 1e-4;
+
+/// Estado de um elemento entre duas revisões
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// Resultado da comparação entre duas revisões
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataDiff {
+    /// Elementos presentes apenas na revisão nova
+    pub added: Vec<ElementRef>,
+
+    /// Elementos presentes apenas na revisão antiga
+    pub removed: Vec<ElementRef>,
+
+    /// Elementos presentes nas duas revisões com alterações
+    pub modified: Vec<ElementChange>,
+
+    /// Contagens agregadas
+    pub summary: DiffSummary,
+}
+
+/// Identificação resumida de um elemento
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementRef {
+    pub guid: String,
+    pub ifc_type: String,
+    pub name: String,
+}
+
+/// Alterações de um elemento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChange {
+    pub guid: String,
+    pub ifc_type: String,
+    pub name: String,
+
+    /// Atributos simples alterados (nome, tipo, material, ...)
+    pub attributes: Vec<AttributeDelta>,
+
+    /// Propriedades alteradas (Property Sets)
+    pub properties: Vec<PropertyDelta>,
+
+    /// Quantidades alteradas
+    pub quantities: Vec<QuantityDelta>,
+
+    /// Bounding box mudou
+    pub bounding_box_changed: bool,
+
+    /// Hash da mesh mudou
+    pub mesh_changed: bool,
+}
+
+impl ElementChange {
+    /// Geometria alterada (bounding box ou mesh)
+    pub fn geometry_changed(&self) -> bool {
+        self.bounding_box_changed || self.mesh_changed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+            && self.properties.is_empty()
+            && self.quantities.is_empty()
+            && !self.geometry_changed()
+    }
+}
+
+/// Alteração de atributo (`None` = ausente)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDelta {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Alteração de propriedade dentro de um Property Set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyDelta {
+    pub property_set: String,
+    pub name: String,
+    pub old: Option<PropertyValue>,
+    pub new: Option<PropertyValue>,
+}
+
+/// Alteração de quantidade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantityDelta {
+    pub name: String,
+    pub old: Option<f64>,
+    pub new: Option<f64>,
+}
+
+/// Contagens do diff
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub unchanged: usize,
+    pub geometry_changed: usize,
+}
+
+impl MetadataDiff {
+    /// Compara duas revisões casando elementos pelo GUID
+    pub fn compare(old: &BimMetadata, new: &BimMetadata) -> Self {
+        let old_index = index_by_guid(&old.elements);
+        let new_index = index_by_guid(&new.elements);
+
+        let mut diff = MetadataDiff::default();
+
+        for (guid, new_elem) in &new_index {
+            match old_index.get(guid) {
+                None => diff.added.push(ElementRef::from(*new_elem)),
+                Some(old_elem) => {
+                    let change = compare_elements(old_elem, new_elem);
+                    if change.is_empty() {
+                        diff.summary.unchanged += 1;
+                    } else {
+                        if change.geometry_changed() {
+                            diff.summary.geometry_changed += 1;
+                        }
+                        diff.modified.push(change);
+                    }
+                }
+            }
+        }
+
+        for (guid, old_elem) in &old_index {
+            if !new_index.contains_key(guid) {
+                diff.removed.push(ElementRef::from(*old_elem));
+            }
+        }
+
+        // Ordem estável para diffs reprodutíveis
+        diff.added.sort_by(|a, b| a.guid.cmp(&b.guid));
+        diff.removed.sort_by(|a, b| a.guid.cmp(&b.guid));
+        diff.modified.sort_by(|a, b| a.guid.cmp(&b.guid));
+
+        diff.summary.added = diff.added.len();
+        diff.summary.removed = diff.removed.len();
+        diff.summary.modified = diff.modified.len();
+        diff
+    }
+
+    /// Estado de um GUID no diff (para colorização por elemento)
+    pub fn status(&self, guid: &str) -> ChangeKind {
+        if self.added.iter().any(|e| e.guid == guid) {
+            ChangeKind::Added
+        } else if self.removed.iter().any(|e| e.guid == guid) {
+            ChangeKind::Removed
+        } else if self.modified.iter().any(|e| e.guid == guid) {
+            ChangeKind::Modified
+        } else {
+            ChangeKind::Unchanged
+        }
+    }
+
+    /// Mapa GUID → estado, apenas para elementos alterados
+    pub fn status_map(&self) -> HashMap<String, ChangeKind> {
+        let mut map = HashMap::new();
+        for e in &self.added {
+            map.insert(e.guid.clone(), ChangeKind::Added);
+        }
+        for e in &self.removed {
+            map.insert(e.guid.clone(), ChangeKind::Removed);
+        }
+        for e in &self.modified {
+            map.insert(e.guid.clone(), ChangeKind::Modified);
+        }
+        map
+    }
+
+    /// Nenhuma diferença entre as revisões
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Serializa o diff para JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Lê um diff serializado
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl From<&ElementMetadata> for ElementRef {
+    fn from(elem: &ElementMetadata) -> Self {
+        Self {
+            guid: elem.guid.clone(),
+            ifc_type: elem.ifc_type.clone(),
+            name: elem.name.clone(),
+        }
+    }
+}
+
+/// Índice GUID → elemento (em GUIDs duplicados, o primeiro vence)
+fn index_by_guid(elements: &[ElementMetadata]) -> HashMap<&str, &ElementMetadata> {
+    let mut index = HashMap::with_capacity(elements.len());
+    for elem in elements {
+        index.entry(elem.guid.as_str()).or_insert(elem);
+    }
+    index
+}
+
+fn compare_elements(old: &ElementMetadata, new: &ElementMetadata) -> ElementChange {
+    let mut attributes = Vec::new();
+    let mut push_attr = |field: &str, old: Option<String>, new: Option<String>| {
+        if old != new {
+            attributes.push(AttributeDelta { field: field.to_string(), old, new });
+        }
+    };
+
+    push_attr("ifcType", Some(old.ifc_type.clone()), Some(new.ifc_type.clone()));
+    push_attr("name", Some(old.name.clone()), Some(new.name.clone()));
+    push_attr("description", old.description.clone(), new.description.clone());
+    push_attr("material", old.material.clone(), new.material.clone());
+    if old.tags != new.tags {
+        push_attr("tags", Some(old.tags.join(",")), Some(new.tags.join(",")));
+    }
+
+    ElementChange {
+        guid: new.guid.clone(),
+        ifc_type: new.ifc_type.clone(),
+        name: new.name.clone(),
+        attributes,
+        properties: compare_properties(old, new),
+        quantities: compare_quantities(old, new),
+        bounding_box_changed: !bbox_equal(old.bounding_box, new.bounding_box),
+        mesh_changed: old.mesh_hash.is_some()
+            && new.mesh_hash.is_some()
+            && old.mesh_hash != new.mesh_hash,
+    }
+}
+
+fn compare_properties(old: &ElementMetadata, new: &ElementMetadata) -> Vec<PropertyDelta> {
+    let psets: BTreeSet<&String> = old.properties.keys().chain(new.properties.keys()).collect();
+    let mut deltas = Vec::new();
+
+    for pset in psets {
+        let old_props = old.properties.get(pset);
+        let new_props = new.properties.get(pset);
+        let names: BTreeSet<&String> = old_props.into_iter().flat_map(|p| p.keys())
+            .chain(new_props.into_iter().flat_map(|p| p.keys()))
+            .collect();
+
+        for name in names {
+            let old_value = old_props.and_then(|p| p.get(name));
+            let new_value = new_props.and_then(|p| p.get(name));
+            if old_value != new_value {
+                deltas.push(PropertyDelta {
+                    property_set: pset.clone(),
+                    name: name.clone(),
+                    old: old_value.cloned(),
+                    new: new_value.cloned(),
+                });
+            }
+        }
+    }
+
+    deltas
+}
+
+fn compare_quantities(old: &ElementMetadata, new: &ElementMetadata) -> Vec<QuantityDelta> {
+    let names: BTreeSet<&String> = old.quantities.keys().chain(new.quantities.keys()).collect();
+    let mut deltas = Vec::new();
+
+    for name in names {
+        let old_value = old.quantities.get(name).copied();
+        let new_value = new.quantities.get(name).copied();
+        let equal = match (old_value, new_value) {
+            (Some(a), Some(b)) => (a - b).abs() <= QUANTITY_EPSILON * a.abs().max(b.abs()).max(1.0),
+            (None, None) => true,
+            _ => false,
+        };
+        if !equal {
+            deltas.push(QuantityDelta { name: name.clone(), old: old_value, new: new_value });
+        }
+    }
+
+    deltas
+}
+
+fn bbox_equal(a: Option<[f32; 6]>, b: Option<[f32; 6]>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() <= BBOX_EPSILON),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeometryHealth, ModelStatistics, ProjectInfo, RelationGraph, SpatialStructure, UnitContext};
+
+    fn element(guid: &str, name: &str) -> ElementMetadata {
+        ElementMetadata {
+            guid: guid.to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node: None,
+            name: name.to_string(),
+            description: None,
+            properties: HashMap::new(),
+            quantities: HashMap::new(),
+            original_quantities: HashMap::new(),
+            material: None,
+            bounding_box: Some([0.0, 0.0, 0.0, 1.0, 1.0, 3.0]),
+            mesh_hash: Some(42),
+            tags: vec![],
+        }
+    }
+
+    fn metadata(elements: Vec<ElementMetadata>) -> BimMetadata {
+        BimMetadata {
+            elements,
+            structure: SpatialStructure {
+                project: ProjectInfo {
+                    name: "Teste".to_string(),
+                    description: None,
+                    author: None,
+                    organization: None,
+                },
+                site: None,
+                buildings: vec![],
+                storeys: vec![],
+            },
+            statistics: ModelStatistics {
+                total_elements: 0,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations: RelationGraph::default(),
+            units: UnitContext::default(),
+        }
+    }
+
+    #[test]
+    fn test_added_removed_modified() {
+        let mut changed = element("B", "Parede B");
+        changed.quantities.insert("Area".to_string(), 12.0);
+        changed.bounding_box = Some([0.0, 0.0, 0.0, 2.0, 1.0, 3.0]);
+        let mut pset = HashMap::new();
+        pset.insert("IsExternal".to_string(), PropertyValue::Boolean(true));
+        changed.properties.insert("Pset_WallCommon".to_string(), pset);
+
+        let old = metadata(vec![element("A", "Parede A"), element("B", "Parede B"), element("C", "Parede C")]);
+        let new = metadata(vec![changed, element("C", "Parede C"), element("D", "Parede D")]);
+
+        let diff = MetadataDiff::compare(&old, &new);
+        assert_eq!(diff.summary, DiffSummary {
+            added: 1,
+            removed: 1,
+            modified: 1,
+            unchanged: 1,
+            geometry_changed: 1,
+        });
+        assert_eq!(diff.status("A"), ChangeKind::Removed);
+        assert_eq!(diff.status("D"), ChangeKind::Added);
+        assert_eq!(diff.status("C"), ChangeKind::Unchanged);
+
+        let change = &diff.modified[0];
+        assert_eq!(change.guid, "B");
+        assert!(change.bounding_box_changed);
+        assert!(!change.mesh_changed);
+        assert_eq!(change.quantities[0], QuantityDelta { name: "Area".to_string(), old: None, new: Some(12.0) });
+        assert_eq!(change.properties[0].property_set, "Pset_WallCommon");
+        assert_eq!(change.properties[0].new, Some(PropertyValue::Boolean(true)));
+    }
+
+    #[test]
+    fn test_mesh_hash_and_attributes() {
+        let old = metadata(vec![element("A", "Parede A")]);
+        let mut new = metadata(vec![element("A", "Parede Norte")]);
+        new.elements[0].mesh_hash = Some(7);
+
+        let diff = MetadataDiff::compare(&old, &new);
+        let change = &diff.modified[0];
+        assert!(change.mesh_changed);
+        assert_eq!(change.attributes, vec![AttributeDelta {
+            field: "name".to_string(),
+            old: Some("Parede A".to_string()),
+            new: Some("Parede Norte".to_string()),
+        }]);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let old = metadata(vec![element("A", "Parede A")]);
+        let new = metadata(vec![]);
+
+        let diff = MetadataDiff::compare(&old, &new);
+        let json = diff.to_json().unwrap();
+        assert!(json.contains("\"removed\""));
+
+        let restored = MetadataDiff::from_json(&json).unwrap();
+        assert_eq!(restored.summary, diff.summary);
+        assert!(MetadataDiff::compare(&old, &old).is_empty());
+    }
+}
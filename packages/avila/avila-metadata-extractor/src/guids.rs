@@ -0,0 +1,292 @@
+//! Reparo de GUIDs repetidos
+//!
+//! Exportadores com defeito reutilizam o mesmo `GlobalId` em elementos
+//! diferentes, e o link elemento → node/relação passa a apontar para o
+//! elemento errado. [`repair_guids`] mantém o GUID na primeira ocorrência e
+//! gera um GUID comprimido novo para cada repetição (e para GUIDs vazios).
+//!
+//! Os GUIDs novos são determinísticos: derivados do GUID original e da
+//! ordem da repetição, então reexportar o mesmo arquivo gera os mesmos
+//! valores. A [`GuidRemapping`] devolvida registra cada troca e é a fonte
+//! para atualizar o que fica fora do `BimMetadata`, como o `element_guid`
+//! das meshes ([`GuidRemapping::guid_for_node`]).
+
+use crate::{BimMetadata, RelationKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Alfabeto do GUID comprimido do IFC (base 64, 22 caracteres)
+const GUID_ALPHABET: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+
+/// Troca de GUID de um elemento
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuidRemap {
+    /// Posição do elemento em `BimMetadata::elements`
+    pub index: usize,
+    pub original: String,
+    pub guid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh_node: Option<u32>,
+}
+
+/// Tabela de remapeamento produzida por [`repair_guids`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GuidRemapping {
+    entries: Vec<GuidRemap>,
+}
+
+impl GuidRemapping {
+    /// Trocas na ordem dos elementos
+    pub fn entries(&self) -> &[GuidRemap] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// GUIDs originais que estavam repetidos (ou vazios), sem repetição
+    pub fn duplicated_guids(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .map(|e| e.original.as_str())
+            .filter(|guid| seen.insert(*guid))
+            .collect()
+    }
+
+    /// GUID novo do elemento na posição `index`, se foi trocado
+    pub fn guid_for_index(&self, index: usize) -> Option<&str> {
+        self.entries.iter().find(|e| e.index == index).map(|e| e.guid.as_str())
+    }
+
+    /// GUID novo do elemento ligado ao node, para corrigir o `element_guid` da mesh
+    pub fn guid_for_node(&self, node: u32) -> Option<&str> {
+        self.entries.iter().find(|e| e.mesh_node == Some(node)).map(|e| e.guid.as_str())
+    }
+}
+
+/// Regenera GUIDs repetidos ou vazios dos elementos
+///
+/// A primeira ocorrência fica com o GUID e com as relações dele. As cópias
+/// herdam só a posição na hierarquia (`Contains`/`Aggregates` de entrada),
+/// para continuarem no mesmo pavimento; aberturas e conexões não têm como
+/// ser atribuídas a uma cópia específica e ficam com a primeira.
+pub fn repair_guids(metadata: &mut BimMetadata) -> GuidRemapping {
+    let structure = &metadata.structure;
+    let mut taken: HashSet<String> = structure
+        .buildings
+        .iter()
+        .map(|b| b.id.clone())
+        .chain(structure.storeys.iter().map(|s| s.id.clone()))
+        .chain(metadata.elements.iter().map(|e| e.guid.clone()))
+        .collect();
+
+    let mut occurrences: HashMap<String, u32> = HashMap::new();
+    let mut remapping = GuidRemapping::default();
+    for (index, element) in metadata.elements.iter_mut().enumerate() {
+        let occurrence = occurrences.entry(element.guid.clone()).or_insert(0);
+        *occurrence += 1;
+        if *occurrence == 1 && !element.guid.is_empty() {
+            continue;
+        }
+
+        let guid = regenerate_guid(&element.guid, *occurrence, &taken);
+        taken.insert(guid.clone());
+        remapping.entries.push(GuidRemap {
+            index,
+            original: std::mem::replace(&mut element.guid, guid.clone()),
+            guid,
+            mesh_node: element.mesh_node,
+        });
+    }
+
+    for entry in &remapping.entries {
+        let parents: Vec<(RelationKind, String)> = [RelationKind::Contains, RelationKind::Aggregates]
+            .into_iter()
+            .flat_map(|kind| {
+                let graph = &metadata.relations;
+                graph.sources(&entry.original, kind).into_iter().map(move |p| (kind, p.to_string()))
+            })
+            .collect();
+        for (kind, parent) in parents {
+            metadata.relations.add(kind, parent, entry.guid.clone());
+        }
+    }
+    remapping
+}
+
+/// GUID comprimido determinístico para a `occurrence`-ésima cópia de `original`
+fn regenerate_guid(original: &str, occurrence: u32, taken: &HashSet<String>) -> String {
+    (0u32..)
+        .map(|salt| {
+            let mut hash = Fnv128::new();
+            hash.update(original.as_bytes());
+            hash.update(&occurrence.to_le_bytes());
+            hash.update(&salt.to_le_bytes());
+            // UUID versão 8 (RFC 9562): bits de versão e variante fixos
+            let value = (hash.finish() & !(0xf << 76) & !(0b11 << 62)) | (0x8 << 76) | (0b10 << 62);
+            compress_guid(value)
+        })
+        .find(|guid| !taken.contains(guid))
+        .expect("salt space exhausted")
+}
+
+/// 128 bits → GUID comprimido do IFC (o primeiro caractere leva os 2 bits altos)
+pub fn compress_guid(value: u128) -> String {
+    (0..22)
+        .map(|i| GUID_ALPHABET[((value >> (6 * (21 - i))) & 0x3f) as usize] as char)
+        .collect()
+}
+
+/// GUID comprimido → 128 bits; `None` se não tiver 22 caracteres válidos
+pub fn decompress_guid(guid: &str) -> Option<u128> {
+    if guid.len() != 22 {
+        return None;
+    }
+    let mut value = 0u128;
+    for (i, byte) in guid.bytes().enumerate() {
+        let digit = GUID_ALPHABET.iter().position(|&c| c == byte)? as u128;
+        if i == 0 && digit > 3 {
+            return None;
+        }
+        value = value << 6 | digit;
+    }
+    Some(value)
+}
+
+/// FNV-1a de 128 bits
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new() -> Self {
+        Self(0x6c62_272e_07bb_0142_62b8_2175_6295_c58d)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = (self.0 ^ byte as u128).wrapping_mul(0x0000_0000_0100_0000_0000_0000_0000_013b);
+        }
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_relation_graph, ElementMetadata, GeometryHealth, ModelStatistics, ProjectInfo, RelationshipData,
+        SpatialStructure, UnitContext,
+    };
+
+    fn element(guid: &str, mesh_node: u32) -> ElementMetadata {
+        ElementMetadata {
+            guid: guid.to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node: Some(mesh_node),
+            name: format!("Parede {}", mesh_node),
+            description: None,
+            properties: HashMap::new(),
+            quantities: HashMap::new(),
+            original_quantities: HashMap::new(),
+            material: None,
+            bounding_box: None,
+            mesh_hash: None,
+            tags: vec![],
+        }
+    }
+
+    fn metadata(elements: Vec<ElementMetadata>) -> BimMetadata {
+        BimMetadata {
+            elements,
+            structure: SpatialStructure {
+                project: ProjectInfo {
+                    name: "Teste".to_string(),
+                    description: None,
+                    author: None,
+                    organization: None,
+                },
+                site: None,
+                buildings: vec![],
+                storeys: vec![],
+            },
+            statistics: ModelStatistics {
+                total_elements: 0,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations: build_relation_graph(&[
+                RelationshipData {
+                    ifc_type: "IfcRelContainedInSpatialStructure".to_string(),
+                    relating_guid: "storey1".to_string(),
+                    related_guids: vec!["2O_RrAJHv7xv2dl5cNZYOF".to_string()],
+                },
+                RelationshipData {
+                    ifc_type: "IfcRelVoidsElement".to_string(),
+                    relating_guid: "2O_RrAJHv7xv2dl5cNZYOF".to_string(),
+                    related_guids: vec!["opening1".to_string()],
+                },
+            ]),
+            units: UnitContext::default(),
+        }
+    }
+
+    #[test]
+    fn test_compress_guid_roundtrip() {
+        assert_eq!(compress_guid(0), "0000000000000000000000");
+        assert_eq!(compress_guid(u128::MAX), "3$$$$$$$$$$$$$$$$$$$$$");
+        let value = decompress_guid("2O_RrAJHv7xv2dl5cNZYOF").unwrap();
+        assert_eq!(compress_guid(value), "2O_RrAJHv7xv2dl5cNZYOF");
+        assert_eq!(decompress_guid("4O_RrAJHv7xv2dl5cNZYOF"), None);
+        assert_eq!(decompress_guid("wall_30"), None);
+    }
+
+    #[test]
+    fn test_repair_duplicates() {
+        let dup = "2O_RrAJHv7xv2dl5cNZYOF";
+        let mut model = metadata(vec![element(dup, 0), element("1xS3BCk291UvhgP2a6eflL", 1), element(dup, 2), element(dup, 3), element("", 4)]);
+        let remapping = repair_guids(&mut model);
+
+        assert_eq!(remapping.len(), 3);
+        assert_eq!(remapping.duplicated_guids(), [dup, ""]);
+        assert_eq!(model.elements[0].guid, dup);
+        let guids: HashSet<&str> = model.elements.iter().map(|e| e.guid.as_str()).collect();
+        assert_eq!(guids.len(), 5);
+        for entry in remapping.entries() {
+            assert_eq!(model.elements[entry.index].guid, entry.guid);
+            assert!(decompress_guid(&entry.guid).is_some());
+        }
+        let copy = remapping.guid_for_node(2).unwrap();
+        assert_eq!(remapping.guid_for_index(2), Some(copy));
+        assert_eq!(remapping.guid_for_node(0), None);
+
+        // Cópias continuam no pavimento; a abertura fica com a primeira
+        assert_eq!(model.relations.parent(copy), Some("storey1"));
+        assert!(model.relations.targets(copy, RelationKind::Voids).is_empty());
+        assert_eq!(model.relations.targets(dup, RelationKind::Voids), ["opening1"]);
+
+        // Determinístico e idempotente
+        let mut again = metadata(vec![element(dup, 0), element("1xS3BCk291UvhgP2a6eflL", 1), element(dup, 2), element(dup, 3), element("", 4)]);
+        assert_eq!(repair_guids(&mut again), remapping);
+        assert!(repair_guids(&mut again).is_empty());
+
+        let json = serde_json::to_value(&remapping).unwrap();
+        assert_eq!(json[0]["original"], dup);
+        assert_eq!(json[0]["meshNode"], 2);
+    }
+}
@@ -14,20 +14,81 @@
 //!     "meshNode": 17,
 //!     "name": "Parede 01",
 //!     "properties": { ... },
-//!     "quantities": { ... }
+//!     "quantities": { "NetSideArea": 15.6 },
+//!     "originalQuantities": { "NetSideArea": { "value": 15600000.0, "unit": "mm²" } }
 //!   }],
 //!   "structure": {
 //!     "project": { ... },
 //!     "buildings": [ ... ],
 //!     "storeys": [ ... ]
-//!   }
+//!   },
+//!   "units": { "length": { "symbol": "mm", "toSi": 0.001 }, ... },
+//!   "relations": [{
+//!     "kind": "contains",
+//!     "relating": "1xS3BCk291UvhgP2a6eflL",
+//!     "related": "2O_RrAJHv7xv2dl5cNZYOF"
+//!   }]
 //! }
 //! ```
+//!
+//! Para modelos grandes, [`TiledMetadata`] divide a saída em um manifesto
+//! leve e arquivos por pavimento/grade, carregados sob demanda com [`TileLoader`].
+//!
+//! Todas as quantidades são exportadas em SI (m, m², m³, kg); veja [`UnitContext`].
+//!
+//! O visualizador liga a seleção (node do glTF ou GUID) aos metadados com
+//! [`MetadataIndex`], que devolve propriedades, quantidades e pavimento.
+//!
+//! O sequenciamento 4D fica em [`Schedule`]: tarefas com datas ligadas a
+//! GUIDs, importadas de CSV/JSON, com o estado de cada elemento por data.
+//!
+//! Edições de propriedades feitas no visualizador ficam em um [`ChangeSet`],
+//! validado contra um [`PropertySchema`] e aplicado sobre os metadados.
+//!
+//! Consultas espaciais (raio, caixa, raio de visão, vizinhos mais próximos)
+//! sobre as caixas dos elementos ficam em [`SpatialIndex`]; com a feature
+//! `wasm`, o visualizador as usa via `SpatialQuery`.
+//!
+//! GUIDs repetidos por exportadores com defeito são corrigidos com
+//! [`repair_guids`], que devolve a tabela de remapeamento.
+//!
+//! Com a feature `sqlite`, `MetadataExtractor::export_sqlite` grava o mesmo
+//! conteúdo em tabelas relacionais para consultas SQL.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+mod changeset;
+mod diff;
+mod guids;
+mod relations;
+mod schedule;
+mod selection;
+mod spatial;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod statistics;
+mod tiles;
+mod units;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use changeset::*;
+pub use diff::*;
+pub use guids::*;
+pub use relations::*;
+pub use schedule::*;
+pub use selection::*;
+pub use spatial::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+pub use statistics::*;
+pub use tiles::*;
+pub use units::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+
 pub type Result<T> = std::result::Result<T, MetadataError>;
 
 // ============================================================================
@@ -42,8 +103,21 @@ pub enum MetadataError {
     #[error("Invalid element: {0}")]
     InvalidElement(String),
 
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    #[error("Invalid change: {0}")]
+    InvalidChange(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
 }
 
 // ============================================================================
@@ -61,6 +135,14 @@ pub struct BimMetadata {
 
     /// Estatísticas do modelo
     pub statistics: ModelStatistics,
+
+    /// Relacionamentos (contém, agrega, aberturas, conexões)
+    #[serde(default)]
+    pub relations: RelationGraph,
+
+    /// Unidades declaradas no arquivo (quantidades já estão em SI)
+    #[serde(default)]
+    pub units: UnitContext,
 }
 
 /// Metadados de um elemento BIM
@@ -85,21 +167,29 @@ pub struct ElementMetadata {
     /// Propriedades (Property Sets)
     pub properties: HashMap<String, HashMap<String, PropertyValue>>,
 
-    /// Quantidades (áreas, volumes, comprimentos)
+    /// Quantidades (áreas, volumes, comprimentos), sempre em SI
     pub quantities: HashMap<String, f64>,
 
+    /// Valores originais das quantidades convertidas para SI (para exibição)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub original_quantities: HashMap<String, OriginalQuantity>,
+
     /// Material
     pub material: Option<String>,
 
     /// Bounding box [minX, minY, minZ, maxX, maxY, maxZ]
     pub bounding_box: Option<[f32; 6]>,
 
+    /// Hash da mesh (detecção de alteração de geometria entre revisões)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh_hash: Option<u64>,
+
     /// Tags/classificações
     pub tags: Vec<String>,
 }
 
 /// Valor de propriedade (pode ser string, número, booleano)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PropertyValue {
     String(String),
@@ -176,6 +266,18 @@ pub struct ModelStatistics {
 
     /// Volume total (m³)
     pub total_volume: Option<f64>,
+
+    /// Detalhamento por pavimento (na ordem da estrutura espacial)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storeys: Vec<StoreyStatistics>,
+
+    /// Volume por material (ordenado pelo nome)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub materials: Vec<MaterialStatistics>,
+
+    /// Contadores de saúde da geometria
+    #[serde(default)]
+    pub health: GeometryHealth,
 }
 
 // ============================================================================
@@ -183,12 +285,24 @@ pub struct ModelStatistics {
 // ============================================================================
 
 pub struct MetadataExtractor {
-    // Configurações
+    /// Unidades do projeto (IfcUnitAssignment)
+    units: UnitContext,
 }
 
 impl MetadataExtractor {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            units: UnitContext::si(),
+        }
+    }
+
+    /// Extrator para um arquivo com unidades não-SI
+    pub fn with_units(units: UnitContext) -> Self {
+        Self { units }
+    }
+
+    pub fn units(&self) -> &UnitContext {
+        &self.units
     }
 
     /// Extrai metadados de elementos BIM
@@ -202,6 +316,7 @@ impl MetadataExtractor {
     fn extract_element(&self, element: &BimElement, mesh_node: Option<u32>) -> Result<ElementMetadata> {
         let mut properties = HashMap::new();
         let mut quantities = HashMap::new();
+        let mut original_quantities = HashMap::new();
 
         // Propriedades comuns
         let mut common_props = HashMap::new();
@@ -215,15 +330,20 @@ impl MetadataExtractor {
             properties.insert("Pset_Common".to_string(), common_props);
         }
 
-        // Quantidades (se disponíveis)
-        if let Some(length) = element.length {
-            quantities.insert("Length".to_string(), length);
-        }
-        if let Some(area) = element.area {
-            quantities.insert("Area".to_string(), area);
-        }
-        if let Some(volume) = element.volume {
-            quantities.insert("Volume".to_string(), volume);
+        // Quantidades (se disponíveis), normalizadas para SI
+        let raw_quantities = [
+            ("Length", element.length),
+            ("Area", element.area),
+            ("Volume", element.volume),
+        ];
+        for (name, value) in raw_quantities {
+            if let Some(value) = value {
+                let (si, original) = self.units.normalize_quantity(name, value);
+                quantities.insert(name.to_string(), si);
+                if let Some(original) = original {
+                    original_quantities.insert(name.to_string(), original);
+                }
+            }
         }
 
         // Bounding box (se disponível)
@@ -239,8 +359,10 @@ impl MetadataExtractor {
             description: element.description.clone(),
             properties,
             quantities,
+            original_quantities,
             material: element.material.clone(),
             bounding_box,
+            mesh_hash: None,
             tags: element.tags.clone(),
         })
     }
@@ -274,7 +396,15 @@ impl MetadataExtractor {
         }
     }
 
-    /// Calcula estatísticas
+    /// Extrai o grafo de relacionamentos
+    pub fn extract_relations(&self, relationships: &[RelationshipData]) -> RelationGraph {
+        build_relation_graph(relationships)
+    }
+
+    /// Calcula estatísticas globais
+    ///
+    /// Para detalhamento por pavimento/material, veja
+    /// [`calculate_detailed_statistics`](Self::calculate_detailed_statistics).
     pub fn calculate_statistics(&self, elements: &[ElementMetadata], scene_stats: &SceneStats) -> ModelStatistics {
         let mut elements_by_type = HashMap::new();
         let mut total_area = 0.0;
@@ -298,6 +428,9 @@ impl MetadataExtractor {
             total_vertices: scene_stats.vertex_count,
             total_area: if total_area > 0.0 { Some(total_area) } else { None },
             total_volume: if total_volume > 0.0 { Some(total_volume) } else { None },
+            storeys: Vec::new(),
+            materials: Vec::new(),
+            health: GeometryHealth::default(),
         }
     }
 
@@ -379,10 +512,12 @@ pub struct StoreyData {
     pub height: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SceneStats {
     pub triangle_count: usize,
     pub vertex_count: usize,
+    /// GUID → triângulos do elemento (opcional, usado no detalhamento por pavimento)
+    pub triangles_by_element: HashMap<String, usize>,
 }
 
 // ============================================================================
@@ -420,6 +555,40 @@ mod tests {
         assert!(metadata.properties.contains_key("Pset_Common"));
     }
 
+    #[test]
+    fn test_extract_element_normalizes_units() {
+        let units = UnitContext::from_assignment(&[UnitData {
+            unit_type: ".LENGTHUNIT.".to_string(),
+            prefix: Some(".MILLI.".to_string()),
+            name: ".METRE.".to_string(),
+            conversion_factor: None,
+        }])
+        .unwrap();
+
+        let element = BimElement {
+            guid: "2O_RrAJHv7xv2dl5cNZYOF".to_string(),
+            ifc_type: "IfcWall".to_string(),
+            name: "Parede 01".to_string(),
+            description: None,
+            material: None,
+            is_external: None,
+            is_load_bearing: None,
+            length: Some(5200.0),
+            area: Some(15.6),
+            volume: None,
+            bounding_box: None,
+            tags: vec![],
+        };
+
+        let extractor = MetadataExtractor::with_units(units);
+        let metadata = extractor.extract_element(&element, None).unwrap();
+
+        assert!((metadata.quantities["Length"] - 5.2).abs() < 1e-9);
+        assert_eq!(metadata.original_quantities["Length"].unit, "mm");
+        assert_eq!(metadata.quantities["Area"], 15.6);
+        assert!(!metadata.original_quantities.contains_key("Area"));
+    }
+
     #[test]
     fn test_export_json() {
         let metadata = BimMetadata {
@@ -442,7 +611,12 @@ mod tests {
                 total_vertices: 0,
                 total_area: None,
                 total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
             },
+            relations: RelationGraph::default(),
+            units: UnitContext::default(),
         };
 
         let extractor = MetadataExtractor::new();
@@ -451,5 +625,6 @@ mod tests {
         assert!(json.contains("elements"));
         assert!(json.contains("structure"));
         assert!(json.contains("statistics"));
+        assert!(json.contains("relations"));
     }
 }
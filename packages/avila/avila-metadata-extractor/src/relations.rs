@@ -0,0 +1,337 @@
+//! Grafo de relacionamentos entre elementos BIM
+//!
+//! Captura as relações IFC que tornam o modelo navegável:
+//! - `IfcRelAggregates` (decomposição: edifício → pavimentos, elemento → partes)
+//! - `IfcRelContainedInSpatialStructure` (pavimento/espaço → elementos)
+//! - `IfcRelVoidsElement` (parede → abertura)
+//! - `IfcRelFillsElement` (abertura → porta/janela)
+//! - `IfcRelConnects*` (conexões físicas entre elementos)
+//!
+//! Serializado como uma lista plana de arestas; os índices de adjacência são
+//! reconstruídos ao desserializar.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Tipo de relacionamento (direção: `relating` → `related`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelationKind {
+    /// Todo → parte (IfcRelAggregates)
+    Aggregates,
+    /// Estrutura espacial → elemento (IfcRelContainedInSpatialStructure)
+    Contains,
+    /// Elemento hospedeiro → abertura (IfcRelVoidsElement)
+    Voids,
+    /// Abertura → elemento de preenchimento (IfcRelFillsElement)
+    Fills,
+    /// Conexão física entre elementos (IfcRelConnects*)
+    Connects,
+}
+
+impl RelationKind {
+    /// Mapeia o nome da entidade IFC para o tipo de relacionamento
+    pub fn from_ifc_type(ifc_type: &str) -> Option<Self> {
+        let upper = ifc_type.to_ascii_uppercase();
+        match upper.as_str() {
+            "IFCRELAGGREGATES" | "IFCRELNESTS" => Some(Self::Aggregates),
+            "IFCRELCONTAINEDINSPATIALSTRUCTURE" => Some(Self::Contains),
+            "IFCRELVOIDSELEMENT" => Some(Self::Voids),
+            "IFCRELFILLSELEMENT" => Some(Self::Fills),
+            _ if upper.starts_with("IFCRELCONNECTS") => Some(Self::Connects),
+            _ => None,
+        }
+    }
+
+    /// Relações simétricas são navegáveis nos dois sentidos
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, Self::Connects)
+    }
+}
+
+/// Aresta do grafo: `relating` (origem) → `related` (destino)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relation {
+    pub kind: RelationKind,
+    pub relating: String,
+    pub related: String,
+}
+
+/// Grafo de adjacência tipado entre GUIDs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<Relation>", into = "Vec<Relation>")]
+pub struct RelationGraph {
+    relations: Vec<Relation>,
+    /// GUID → índices de relações onde é `relating`
+    outgoing: HashMap<String, Vec<usize>>,
+    /// GUID → índices de relações onde é `related`
+    incoming: HashMap<String, Vec<usize>>,
+}
+
+impl RelationGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adiciona uma relação (duplicatas são ignoradas)
+    pub fn add(&mut self, kind: RelationKind, relating: impl Into<String>, related: impl Into<String>) {
+        let relation = Relation {
+            kind,
+            relating: relating.into(),
+            related: related.into(),
+        };
+
+        let exists = self.outgoing.get(&relation.relating).is_some_and(|idx| {
+            idx.iter().any(|&i| self.relations[i] == relation)
+        });
+        if exists {
+            return;
+        }
+
+        let index = self.relations.len();
+        self.outgoing.entry(relation.relating.clone()).or_default().push(index);
+        self.incoming.entry(relation.related.clone()).or_default().push(index);
+        self.relations.push(relation);
+    }
+
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    pub fn len(&self) -> usize {
+        self.relations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.relations.is_empty()
+    }
+
+    /// Destinos diretos de `guid` pelo tipo de relação
+    pub fn targets(&self, guid: &str, kind: RelationKind) -> Vec<&str> {
+        let mut result: Vec<&str> = self.outgoing.get(guid)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.relations[i])
+            .filter(|r| r.kind == kind)
+            .map(|r| r.related.as_str())
+            .collect();
+
+        if kind.is_symmetric() {
+            result.extend(self.sources(guid, kind));
+        }
+        result
+    }
+
+    /// Origens diretas que apontam para `guid` pelo tipo de relação
+    pub fn sources(&self, guid: &str, kind: RelationKind) -> Vec<&str> {
+        self.incoming.get(guid)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.relations[i])
+            .filter(|r| r.kind == kind)
+            .map(|r| r.relating.as_str())
+            .collect()
+    }
+
+    /// Pai na hierarquia (estrutura espacial que contém ou todo que agrega)
+    pub fn parent(&self, guid: &str) -> Option<&str> {
+        self.sources(guid, RelationKind::Contains)
+            .into_iter()
+            .chain(self.sources(guid, RelationKind::Aggregates))
+            .next()
+    }
+
+    /// Filhos diretos na hierarquia (agregados + contidos)
+    pub fn children(&self, guid: &str) -> Vec<&str> {
+        let mut result = self.targets(guid, RelationKind::Aggregates);
+        result.extend(self.targets(guid, RelationKind::Contains));
+        result
+    }
+
+    /// Cadeia de ancestrais, do pai imediato até a raiz
+    pub fn ancestors(&self, guid: &str) -> Vec<&str> {
+        let mut result = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = guid;
+        seen.insert(guid);
+
+        while let Some(parent) = self.parent(current) {
+            if !seen.insert(parent) {
+                break; // ciclo em arquivo malformado
+            }
+            result.push(parent);
+            current = parent;
+        }
+        result
+    }
+
+    /// Todos os descendentes (busca em largura), ex.: "o que há na sala 203"
+    pub fn descendants(&self, guid: &str) -> Vec<&str> {
+        let mut result = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(guid);
+        queue.push_back(guid);
+
+        while let Some(current) = queue.pop_front() {
+            for child in self.children(current) {
+                if seen.insert(child) {
+                    result.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+        result
+    }
+
+    /// Elementos hospedeiros de uma porta/janela (elemento → abertura → parede)
+    pub fn hosts_of(&self, filler: &str) -> Vec<&str> {
+        let mut result = Vec::new();
+        for opening in self.sources(filler, RelationKind::Fills) {
+            for host in self.sources(opening, RelationKind::Voids) {
+                if !result.contains(&host) {
+                    result.push(host);
+                }
+            }
+        }
+        result
+    }
+
+    /// Portas/janelas hospedadas por um elemento (parede → abertura → porta)
+    pub fn hosted_by(&self, host: &str) -> Vec<&str> {
+        let mut result = Vec::new();
+        for opening in self.targets(host, RelationKind::Voids) {
+            for filler in self.targets(opening, RelationKind::Fills) {
+                if !result.contains(&filler) {
+                    result.push(filler);
+                }
+            }
+        }
+        result
+    }
+
+    /// Elementos conectados fisicamente
+    pub fn connected(&self, guid: &str) -> Vec<&str> {
+        self.targets(guid, RelationKind::Connects)
+    }
+
+    /// Remove todas as relações envolvendo `guid`
+    pub fn remove_element(&mut self, guid: &str) {
+        let relations = std::mem::take(&mut self.relations);
+        *self = relations
+            .into_iter()
+            .filter(|r| r.relating != guid && r.related != guid)
+            .collect::<Vec<_>>()
+            .into();
+    }
+
+    fn rebuild_index(&mut self) {
+        self.outgoing.clear();
+        self.incoming.clear();
+        for (index, relation) in self.relations.iter().enumerate() {
+            self.outgoing.entry(relation.relating.clone()).or_default().push(index);
+            self.incoming.entry(relation.related.clone()).or_default().push(index);
+        }
+    }
+}
+
+impl From<Vec<Relation>> for RelationGraph {
+    fn from(relations: Vec<Relation>) -> Self {
+        let mut graph = Self {
+            relations,
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+        };
+        graph.rebuild_index();
+        graph
+    }
+}
+
+impl From<RelationGraph> for Vec<Relation> {
+    fn from(graph: RelationGraph) -> Self {
+        graph.relations
+    }
+}
+
+/// Relacionamento IFC bruto (interface com parser IFC)
+#[derive(Debug, Clone)]
+pub struct RelationshipData {
+    /// Entidade IFC (IfcRelAggregates, IfcRelVoidsElement, ...)
+    pub ifc_type: String,
+    /// GUID do lado "relating" (RelatingObject, RelatingStructure, ...)
+    pub relating_guid: String,
+    /// GUIDs do lado "related" (RelatedObjects, RelatedElements, ...)
+    pub related_guids: Vec<String>,
+}
+
+/// Constrói o grafo a partir das relações IFC (tipos desconhecidos são ignorados)
+pub fn build_relation_graph(relationships: &[RelationshipData]) -> RelationGraph {
+    let mut graph = RelationGraph::new();
+    for rel in relationships {
+        if let Some(kind) = RelationKind::from_ifc_type(&rel.ifc_type) {
+            for related in &rel.related_guids {
+                graph.add(kind, rel.relating_guid.clone(), related.clone());
+            }
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rel(ifc_type: &str, relating: &str, related: &[&str]) -> RelationshipData {
+        RelationshipData {
+            ifc_type: ifc_type.to_string(),
+            relating_guid: relating.to_string(),
+            related_guids: related.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn sample_graph() -> RelationGraph {
+        build_relation_graph(&[
+            rel("IfcRelAggregates", "building", &["storey1"]),
+            rel("IfcRelAggregates", "storey1", &["room203"]),
+            rel("IfcRelContainedInSpatialStructure", "storey1", &["wall1", "wall2"]),
+            rel("IfcRelContainedInSpatialStructure", "room203", &["desk"]),
+            rel("IfcRelVoidsElement", "wall1", &["opening1"]),
+            rel("IfcRelFillsElement", "opening1", &["door1"]),
+            rel("IfcRelConnectsPathElements", "wall1", &["wall2"]),
+            rel("IfcRelAssociatesMaterial", "mat", &["wall1"]),
+        ])
+    }
+
+    #[test]
+    fn test_hosts_of_door() {
+        let graph = sample_graph();
+        assert_eq!(graph.hosts_of("door1"), vec!["wall1"]);
+        assert_eq!(graph.hosted_by("wall1"), vec!["door1"]);
+    }
+
+    #[test]
+    fn test_traversal() {
+        let graph = sample_graph();
+        assert_eq!(graph.parent("desk"), Some("room203"));
+        assert_eq!(graph.ancestors("desk"), vec!["room203", "storey1", "building"]);
+
+        let contents = graph.descendants("storey1");
+        assert!(contents.contains(&"room203"));
+        assert!(contents.contains(&"desk"));
+        assert!(contents.contains(&"wall2"));
+
+        assert_eq!(graph.connected("wall2"), vec!["wall1"]);
+        assert_eq!(graph.len(), 8); // IfcRelAssociatesMaterial ignorado
+    }
+
+    #[test]
+    fn test_serde_roundtrip_rebuilds_index() {
+        let graph = sample_graph();
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: RelationGraph = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.len(), graph.len());
+        assert_eq!(restored.hosts_of("door1"), vec!["wall1"]);
+    }
+}
@@ -0,0 +1,458 @@
+//! Sequenciamento 4D da obra
+//!
+//! Um [`Schedule`] liga GUIDs de elementos a tarefas com datas de início e
+//! término (inclusivas), importadas de CSV ou de um JSON simples. A partir
+//! dele se consulta o estado de cada elemento numa data — planejado, em
+//! execução, construído ou demolido — e a visibilidade por quadro para a
+//! linha do tempo do visualizador ou uma animação glTF.
+//!
+//! ```json
+//! {
+//!   "tasks": [{
+//!     "id": "T1",
+//!     "name": "Fundações",
+//!     "start": "2024-03-01",
+//!     "finish": "2024-03-15",
+//!     "kind": "construct",
+//!     "elements": ["2O_RrAJHv7xv2dl5cNZYOF"]
+//!   }]
+//! }
+//! ```
+//!
+//! Elementos fora do cronograma são tratados como existentes (sempre
+//! visíveis).
+
+use crate::{MetadataError, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Data do calendário, em dias desde 1970-01-01; texto `YYYY-MM-DD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScheduleDate(i32);
+
+impl ScheduleDate {
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        // Dias desde a época no calendário gregoriano proléptico
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (month as i32 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day as i32 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        Some(Self(era * 146_097 + doe - 719_468))
+    }
+
+    /// (ano, mês, dia)
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        let z = self.0 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i32::from(month <= 2);
+        (year, month, day)
+    }
+
+    pub fn days_since_epoch(&self) -> i32 {
+        self.0
+    }
+
+    pub fn add_days(&self, days: i32) -> Self {
+        Self(self.0 + days)
+    }
+
+    /// Dias de `earlier` até esta data (negativo se `earlier` for depois)
+    pub fn days_since(&self, earlier: ScheduleDate) -> i32 {
+        self.0 - earlier.0
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for ScheduleDate {
+    type Err = MetadataError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let invalid = || MetadataError::InvalidSchedule(format!("Invalid date '{}', expected YYYY-MM-DD", text));
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Self::from_ymd(year, month, day).ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for ScheduleDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl Serialize for ScheduleDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScheduleDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// O que a tarefa faz com os elementos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskKind {
+    /// Elemento aparece no início e permanece
+    #[default]
+    Construct,
+    /// Elemento existente some ao fim da tarefa
+    Demolish,
+    /// Elemento provisório (escoramento, andaime): só existe durante a tarefa
+    Temporary,
+}
+
+impl FromStr for TaskKind {
+    type Err = MetadataError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "" | "construct" | "construction" => Ok(Self::Construct),
+            "demolish" | "demolition" => Ok(Self::Demolish),
+            "temporary" => Ok(Self::Temporary),
+            other => Err(MetadataError::InvalidSchedule(format!("Unknown task kind '{}'", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleTask {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub start: ScheduleDate,
+    /// Último dia da tarefa (inclusivo)
+    pub finish: ScheduleDate,
+    #[serde(default)]
+    pub kind: TaskKind,
+    /// GUIDs dos elementos afetados
+    #[serde(default)]
+    pub elements: Vec<String>,
+}
+
+impl ScheduleTask {
+    fn state_at(&self, date: ScheduleDate) -> ElementState {
+        if date < self.start {
+            match self.kind {
+                TaskKind::Demolish => ElementState::Built,
+                TaskKind::Construct | TaskKind::Temporary => ElementState::Planned,
+            }
+        } else if date <= self.finish {
+            ElementState::InProgress
+        } else {
+            match self.kind {
+                TaskKind::Construct => ElementState::Built,
+                TaskKind::Demolish | TaskKind::Temporary => ElementState::Removed,
+            }
+        }
+    }
+}
+
+/// Estado de um elemento numa data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElementState {
+    /// Fora do cronograma
+    Unscheduled,
+    /// Ainda não iniciado
+    Planned,
+    InProgress,
+    Built,
+    /// Demolido ou desmontado
+    Removed,
+}
+
+impl ElementState {
+    pub fn is_visible(&self) -> bool {
+        !matches!(self, Self::Planned | Self::Removed)
+    }
+}
+
+/// Estados dos elementos do cronograma numa data (um quadro da linha do tempo)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleFrame {
+    pub date: ScheduleDate,
+    pub states: BTreeMap<String, ElementState>,
+}
+
+impl ScheduleFrame {
+    /// GUIDs visíveis no quadro
+    pub fn visible(&self) -> impl Iterator<Item = &str> {
+        self.states.iter().filter(|(_, s)| s.is_visible()).map(|(guid, _)| guid.as_str())
+    }
+}
+
+/// Cronograma 4D
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub tasks: Vec<ScheduleTask>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let schedule: Self = serde_json::from_str(json)?;
+        schedule.tasks.iter().try_for_each(validate)?;
+        Ok(schedule)
+    }
+
+    /// Lê CSV com cabeçalho `task_id,name,start,finish,guid[,kind]`
+    ///
+    /// As colunas podem vir em qualquer ordem. Cada linha liga um elemento a
+    /// uma tarefa; linhas com o mesmo `task_id` acumulam elementos, e a
+    /// coluna `guid` aceita vários GUIDs separados por `;`.
+    pub fn from_csv(csv: &str) -> Result<Self> {
+        let mut lines = csv.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let header = lines
+            .next()
+            .map(|(_, line)| split_csv_line(line))
+            .ok_or_else(|| MetadataError::InvalidSchedule("Empty CSV".into()))?;
+        let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let required = |name: &str| {
+            column(name).ok_or_else(|| MetadataError::InvalidSchedule(format!("Missing CSV column '{}'", name)))
+        };
+        let (id, start, finish, guid) = (required("task_id")?, required("start")?, required("finish")?, required("guid")?);
+        let (name, kind) = (column("name"), column("kind"));
+
+        let mut schedule = Self::new();
+        for (number, line) in lines {
+            let fields = split_csv_line(line);
+            let field = |index: usize| fields.get(index).map_or("", |f| f.trim());
+            let context = |e: MetadataError| MetadataError::InvalidSchedule(format!("Line {}: {}", number + 1, e));
+
+            let task = ScheduleTask {
+                id: field(id).to_string(),
+                name: name.map_or("", field).to_string(),
+                start: field(start).parse().map_err(context)?,
+                finish: field(finish).parse().map_err(context)?,
+                kind: kind.map_or("", field).parse().map_err(context)?,
+                elements: field(guid).split(';').map(str::trim).filter(|g| !g.is_empty()).map(String::from).collect(),
+            };
+            schedule.add_task(task).map_err(context)?;
+        }
+        Ok(schedule)
+    }
+
+    /// Adiciona a tarefa ou, se o `id` já existe com as mesmas datas, junta os elementos
+    pub fn add_task(&mut self, task: ScheduleTask) -> Result<()> {
+        validate(&task)?;
+        match self.tasks.iter_mut().find(|t| t.id == task.id) {
+            Some(existing) if (existing.start, existing.finish, existing.kind) != (task.start, task.finish, task.kind) => {
+                Err(MetadataError::InvalidSchedule(format!("Task '{}' redefined with different dates", task.id)))
+            }
+            Some(existing) => {
+                existing.elements.extend(task.elements);
+                Ok(())
+            }
+            None => {
+                self.tasks.push(task);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Primeiro início e último término
+    pub fn span(&self) -> Option<(ScheduleDate, ScheduleDate)> {
+        let start = self.tasks.iter().map(|t| t.start).min()?;
+        let finish = self.tasks.iter().map(|t| t.finish).max()?;
+        Some((start, finish))
+    }
+
+    /// GUIDs de todos os elementos do cronograma
+    pub fn elements(&self) -> BTreeSet<&str> {
+        self.tasks.iter().flat_map(|t| &t.elements).map(String::as_str).collect()
+    }
+
+    pub fn tasks_for<'a>(&'a self, guid: &'a str) -> impl Iterator<Item = &'a ScheduleTask> {
+        self.tasks.iter().filter(move |t| t.elements.iter().any(|e| e == guid))
+    }
+
+    /// Tarefas em execução na data
+    pub fn tasks_at(&self, date: ScheduleDate) -> impl Iterator<Item = &ScheduleTask> {
+        self.tasks.iter().filter(move |t| t.start <= date && date <= t.finish)
+    }
+
+    /// Estado do elemento na data
+    ///
+    /// Vale a tarefa iniciada mais recentemente; antes de qualquer tarefa,
+    /// o estado anterior à primeira.
+    pub fn state_at(&self, guid: &str, date: ScheduleDate) -> ElementState {
+        let mut first: Option<&ScheduleTask> = None;
+        let mut current: Option<&ScheduleTask> = None;
+        for task in self.tasks_for(guid) {
+            if first.is_none_or(|f| task.start < f.start) {
+                first = Some(task);
+            }
+            if task.start <= date && current.is_none_or(|c| task.start >= c.start) {
+                current = Some(task);
+            }
+        }
+        current.or(first).map_or(ElementState::Unscheduled, |task| task.state_at(date))
+    }
+
+    /// GUIDs dos elementos em execução na data
+    pub fn active_at(&self, date: ScheduleDate) -> BTreeSet<&str> {
+        self.tasks_at(date).flat_map(|t| &t.elements).map(String::as_str).collect()
+    }
+
+    /// Estados de todos os elementos do cronograma na data
+    pub fn frame_at(&self, date: ScheduleDate) -> ScheduleFrame {
+        let states = self.elements().into_iter().map(|guid| (guid.to_string(), self.state_at(guid, date))).collect();
+        ScheduleFrame { date, states }
+    }
+
+    /// Quadros a cada `step_days` dias, do início até o dia seguinte ao término
+    pub fn frames(&self, step_days: u32) -> Vec<ScheduleFrame> {
+        let Some((start, finish)) = self.span() else { return Vec::new() };
+        let step = step_days.max(1) as i32;
+        let count = finish.days_since(start) / step + 2;
+        (0..count).map(|i| self.frame_at(start.add_days(i * step))).collect()
+    }
+
+    /// Datas em que a visibilidade do elemento muda, a partir do início do cronograma
+    ///
+    /// O primeiro par é sempre o estado no início; os seguintes só aparecem
+    /// quando o elemento surge ou some. Serve de keyframes STEP de animação.
+    pub fn visibility_keys(&self, guid: &str) -> Vec<(ScheduleDate, bool)> {
+        let Some((start, _)) = self.span() else { return Vec::new() };
+        let mut dates: BTreeSet<ScheduleDate> = self.tasks_for(guid).flat_map(|t| [t.start, t.finish.add_days(1)]).collect();
+        dates.insert(start);
+
+        let mut keys: Vec<(ScheduleDate, bool)> = Vec::new();
+        for date in dates.into_iter().filter(|&d| d >= start) {
+            let visible = self.state_at(guid, date).is_visible();
+            if keys.last().is_none_or(|&(_, last)| last != visible) {
+                keys.push((date, visible));
+            }
+        }
+        keys
+    }
+}
+
+fn validate(task: &ScheduleTask) -> Result<()> {
+    if task.id.is_empty() {
+        return Err(MetadataError::InvalidSchedule("Task without id".into()));
+    }
+    if task.finish < task.start {
+        return Err(MetadataError::InvalidSchedule(format!(
+            "Task '{}' finishes ({}) before it starts ({})",
+            task.id, task.finish, task.start
+        )));
+    }
+    Ok(())
+}
+
+/// Divide uma linha CSV respeitando aspas (`""` é uma aspa literal)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().expect("always one field");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => field.push(c),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> ScheduleDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_schedule_states() {
+        assert_eq!(date("1970-01-01").days_since_epoch(), 0);
+        assert_eq!(date("2024-03-01").days_since(date("2024-02-28")), 2);
+        assert_eq!(date("2000-02-29").to_string(), "2000-02-29");
+        assert!("2023-02-29".parse::<ScheduleDate>().is_err());
+
+        let csv = "task_id,name,start,finish,guid,kind\n\
+                   T1,\"Fundações, bloco A\",2024-03-01,2024-03-10,found-1;found-2,\n\
+                   T2,Paredes,2024-03-11,2024-03-20,wall,construct\n\
+                   T3,Escoramento,2024-03-05,2024-03-12,shore,temporary\n\
+                   T4,Demolição,2024-03-01,2024-03-03,old,demolish\n\
+                   T2,Paredes,2024-03-11,2024-03-20,wall-2,construct\n";
+        let schedule = Schedule::from_csv(csv).unwrap();
+        assert_eq!(schedule.tasks.len(), 4);
+        assert_eq!(schedule.tasks[0].name, "Fundações, bloco A");
+        assert_eq!(schedule.tasks[1].elements, vec!["wall", "wall-2"]);
+        assert_eq!(schedule.span(), Some((date("2024-03-01"), date("2024-03-20"))));
+
+        let day = date("2024-03-06");
+        assert_eq!(schedule.active_at(day).into_iter().collect::<Vec<_>>(), vec!["found-1", "found-2", "shore"]);
+        assert_eq!(schedule.state_at("wall", day), ElementState::Planned);
+        assert_eq!(schedule.state_at("old", day), ElementState::Removed);
+        assert_eq!(schedule.state_at("old", date("2024-02-01")), ElementState::Built);
+        assert_eq!(schedule.state_at("shore", date("2024-03-13")), ElementState::Removed);
+        assert_eq!(schedule.state_at("other", day), ElementState::Unscheduled);
+
+        let frame = schedule.frame_at(day);
+        assert_eq!(frame.visible().collect::<Vec<_>>(), vec!["found-1", "found-2", "shore"]);
+        assert_eq!(schedule.frames(7).len(), 4);
+
+        assert_eq!(
+            schedule.visibility_keys("shore"),
+            vec![(date("2024-03-01"), false), (date("2024-03-05"), true), (date("2024-03-13"), false)]
+        );
+        assert_eq!(schedule.visibility_keys("old"), vec![(date("2024-03-01"), true), (date("2024-03-04"), false)]);
+
+        let json = schedule.to_json().unwrap();
+        assert!(json.contains("\"start\": \"2024-03-11\""));
+        assert_eq!(Schedule::from_json(&json).unwrap(), schedule);
+
+        let reversed = r#"{"tasks":[{"id":"X","start":"2024-03-02","finish":"2024-03-01"}]}"#;
+        assert!(Schedule::from_json(reversed).is_err());
+        assert!(Schedule::from_csv("task_id,start,finish\nT1,2024-01-01,2024-01-02").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Consulta de metadados a partir da seleção no visualizador
+//!
+//! O visualizador só conhece o node do glTF (ou o `element_guid` da mesh)
+//! do elemento clicado. [`MetadataIndex`] indexa um `BimMetadata` por GUID
+//! e por `meshNode` e devolve [`ElementDetails`]: propriedades, quantidades
+//! e a posição na estrutura espacial, prontos para um painel de
+//! propriedades sem um segundo serviço de consulta.
+//!
+//! GUIDs ou nodes repetidos apontam para a primeira ocorrência; rode
+//! [`repair_guids`](crate::repair_guids) antes para distinguir as cópias.
+
+use crate::tiles::storey_of;
+use crate::{BimMetadata, ElementMetadata, Result, StoreyInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Tudo o que o painel de propriedades mostra de um elemento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementDetails {
+    pub element: ElementMetadata,
+    /// Pavimento que contém o elemento (direta ou indiretamente)
+    pub storey: Option<StoreyInfo>,
+    /// GUIDs dos contêineres, do pai imediato até a raiz
+    pub containers: Vec<String>,
+}
+
+/// Metadados indexados por GUID e por node do glTF
+#[derive(Debug, Clone)]
+pub struct MetadataIndex {
+    metadata: BimMetadata,
+    by_guid: HashMap<String, usize>,
+    by_node: HashMap<u32, usize>,
+}
+
+impl MetadataIndex {
+    pub fn new(metadata: BimMetadata) -> Self {
+        let mut by_guid = HashMap::with_capacity(metadata.elements.len());
+        let mut by_node = HashMap::with_capacity(metadata.elements.len());
+        for (index, element) in metadata.elements.iter().enumerate() {
+            by_guid.entry(element.guid.clone()).or_insert(index);
+            if let Some(node) = element.mesh_node {
+                by_node.entry(node).or_insert(index);
+            }
+        }
+        Self { metadata, by_guid, by_node }
+    }
+
+    /// Lê a saída JSON de [`MetadataExtractor::export_json`](crate::MetadataExtractor::export_json)
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    pub fn metadata(&self) -> &BimMetadata {
+        &self.metadata
+    }
+
+    pub fn element(&self, guid: &str) -> Option<&ElementMetadata> {
+        self.by_guid.get(guid).map(|&i| &self.metadata.elements[i])
+    }
+
+    pub fn element_for_node(&self, node: u32) -> Option<&ElementMetadata> {
+        self.by_node.get(&node).map(|&i| &self.metadata.elements[i])
+    }
+
+    /// GUID do elemento desenhado pelo node
+    pub fn guid_for_node(&self, node: u32) -> Option<&str> {
+        self.element_for_node(node).map(|e| e.guid.as_str())
+    }
+
+    /// Detalhes do elemento para o painel de propriedades
+    pub fn details(&self, guid: &str) -> Option<ElementDetails> {
+        self.element(guid).map(|element| self.details_of(element))
+    }
+
+    pub fn details_for_node(&self, node: u32) -> Option<ElementDetails> {
+        self.element_for_node(node).map(|element| self.details_of(element))
+    }
+
+    /// Detalhes de uma seleção múltipla, na ordem dada; GUIDs desconhecidos são ignorados
+    pub fn selection<'a>(&self, guids: impl IntoIterator<Item = &'a str>) -> Vec<ElementDetails> {
+        guids.into_iter().filter_map(|guid| self.details(guid)).collect()
+    }
+
+    fn details_of(&self, element: &ElementMetadata) -> ElementDetails {
+        let relations = &self.metadata.relations;
+        let storeys = &self.metadata.structure.storeys;
+        let storey_ids: HashSet<&str> = storeys.iter().map(|s| s.id.as_str()).collect();
+        let storey = storey_of(relations, &storey_ids, &element.guid)
+            .and_then(|id| storeys.iter().find(|s| s.id == id))
+            .cloned();
+
+        ElementDetails {
+            element: element.clone(),
+            storey,
+            containers: relations.ancestors(&element.guid).into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GeometryHealth, ModelStatistics, ProjectInfo, PropertyValue, RelationGraph, RelationKind, SpatialStructure,
+        UnitContext,
+    };
+
+    fn element(guid: &str, mesh_node: Option<u32>) -> ElementMetadata {
+        let pset = HashMap::from([("IsExternal".to_string(), PropertyValue::Boolean(true))]);
+        ElementMetadata {
+            guid: guid.to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node,
+            name: guid.to_string(),
+            description: None,
+            properties: HashMap::from([("Pset_WallCommon".to_string(), pset)]),
+            quantities: HashMap::from([("NetSideArea".to_string(), 15.6)]),
+            original_quantities: HashMap::new(),
+            material: None,
+            bounding_box: None,
+            mesh_hash: None,
+            tags: vec![],
+        }
+    }
+
+    fn sample() -> BimMetadata {
+        let mut relations = RelationGraph::new();
+        relations.add(RelationKind::Aggregates, "building", "s1");
+        relations.add(RelationKind::Contains, "s1", "space");
+        relations.add(RelationKind::Contains, "space", "w1");
+
+        BimMetadata {
+            elements: vec![element("w1", Some(3)), element("w2", Some(7)), element("w1", Some(9))],
+            structure: SpatialStructure {
+                project: ProjectInfo {
+                    name: "Teste".to_string(),
+                    description: None,
+                    author: None,
+                    organization: None,
+                },
+                site: None,
+                buildings: vec![],
+                storeys: vec![StoreyInfo {
+                    id: "s1".to_string(),
+                    name: "Térreo".to_string(),
+                    elevation: 0.0,
+                    height: Some(3.0),
+                }],
+            },
+            statistics: ModelStatistics {
+                total_elements: 3,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations,
+            units: UnitContext::default(),
+        }
+    }
+
+    #[test]
+    fn test_selection_details() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        let index = MetadataIndex::from_json(&json).unwrap();
+
+        assert_eq!(index.guid_for_node(7), Some("w2"));
+        assert_eq!(index.guid_for_node(9), Some("w1"));
+        assert!(index.element_for_node(4).is_none());
+
+        let details = index.details_for_node(3).unwrap();
+        assert_eq!(details.element.quantities["NetSideArea"], 15.6);
+        assert_eq!(details.storey.as_ref().map(|s| s.name.as_str()), Some("Térreo"));
+        assert_eq!(details.containers, vec!["space", "s1", "building"]);
+
+        // GUID repetido: a primeira ocorrência
+        assert_eq!(index.element("w1").unwrap().mesh_node, Some(3));
+
+        let selection = index.selection(["w2", "missing", "w1"]);
+        assert_eq!(selection.len(), 2);
+        assert!(selection[0].storey.is_none());
+
+        let value = serde_json::to_value(&selection[1]).unwrap();
+        assert_eq!(value["element"]["properties"]["Pset_WallCommon"]["IsExternal"], true);
+        assert_eq!(value["storey"]["id"], "s1");
+    }
+}
@@ -0,0 +1,361 @@
+//! Consultas espaciais sobre as caixas dos elementos
+//!
+//! "Todos os elementos a até 2 m desta tubulação" sem carregar geometria:
+//! [`SpatialIndex`] monta uma BVH sobre o `boundingBox` de cada elemento
+//! do `BimMetadata` e responde por GUID:
+//!
+//! - raio em torno de um ponto ([`SpatialIndex::within_radius`]) ou de
+//!   outro elemento ([`SpatialIndex::near_element`]);
+//! - caixa ([`SpatialIndex::in_box`]);
+//! - raio de visão ([`SpatialIndex::raycast`]);
+//! - vizinhos mais próximos ([`SpatialIndex::nearest`]).
+//!
+//! As distâncias são entre caixas (zero quando se tocam), em unidades do
+//! modelo; é uma pré-seleção conservadora, não distância entre malhas.
+//! Elementos sem caixa ficam fora do índice.
+
+use crate::BimMetadata;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Elementos por folha da BVH
+const LEAF_SIZE: usize = 4;
+
+/// Elemento encontrado e sua distância ao alvo da consulta
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpatialHit {
+    pub guid: String,
+    pub distance: f32,
+}
+
+/// `[minX, minY, minZ, maxX, maxY, maxZ]`, como em `ElementMetadata::bounding_box`
+type Bounds = [f32; 6];
+
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: Bounds,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+    /// Faixa em `SpatialIndex::order`
+    Leaf { first: usize, count: usize },
+    Inner { left: usize, right: usize },
+}
+
+/// BVH sobre as caixas dos elementos
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    guids: Vec<String>,
+    boxes: Vec<Bounds>,
+    /// Índices dos elementos na ordem das folhas
+    order: Vec<usize>,
+    nodes: Vec<BvhNode>,
+}
+
+impl SpatialIndex {
+    /// Índice com os elementos que têm caixa (em GUIDs repetidos, todos entram)
+    pub fn new(metadata: &BimMetadata) -> Self {
+        Self::from_boxes(
+            metadata.elements.iter().filter_map(|e| e.bounding_box.map(|b| (e.guid.clone(), b))),
+        )
+    }
+
+    /// Índice a partir de pares GUID/caixa; caixas inválidas são ignoradas
+    pub fn from_boxes(boxes: impl IntoIterator<Item = (String, [f32; 6])>) -> Self {
+        let mut index = Self::default();
+        for (guid, b) in boxes {
+            if b.iter().all(|v| v.is_finite()) && (0..3).all(|i| b[i] <= b[i + 3]) {
+                index.guids.push(guid);
+                index.boxes.push(b);
+            }
+        }
+        index.order = (0..index.boxes.len()).collect();
+        if !index.order.is_empty() {
+            index.build(0, index.order.len());
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.guids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guids.is_empty()
+    }
+
+    /// Caixa de um elemento (a primeira, em GUIDs repetidos)
+    pub fn bounds(&self, guid: &str) -> Option<[f32; 6]> {
+        self.guids.iter().position(|g| g == guid).map(|i| self.boxes[i])
+    }
+
+    /// Divide `order[first..first + count]` na mediana do eixo mais longo dos centros
+    fn build(&mut self, first: usize, count: usize) -> usize {
+        let node = self.nodes.len();
+        let items = &mut self.order[first..first + count];
+        let bounds = items.iter().fold(EMPTY, |acc, &i| merge(&acc, &self.boxes[i]));
+        self.nodes.push(BvhNode { bounds, kind: NodeKind::Leaf { first, count } });
+        if count <= LEAF_SIZE {
+            return node;
+        }
+
+        let boxes = &self.boxes;
+        let centers = items.iter().fold(EMPTY, |acc, &i| {
+            let c = center(&boxes[i]);
+            merge(&acc, &[c[0], c[1], c[2], c[0], c[1], c[2]])
+        });
+        let axis = (0..3)
+            .max_by(|&a, &b| (centers[a + 3] - centers[a]).total_cmp(&(centers[b + 3] - centers[b])))
+            .unwrap_or(0);
+        let half = count / 2;
+        items.select_nth_unstable_by(half, |&a, &b| center(&boxes[a])[axis].total_cmp(&center(&boxes[b])[axis]));
+
+        let left = self.build(first, half);
+        let right = self.build(first + half, count - half);
+        self.nodes[node].kind = NodeKind::Inner { left, right };
+        node
+    }
+
+    /// Visita os elementos das folhas cujos nós passam em `accept`
+    fn visit(&self, accept: impl Fn(&Bounds) -> bool, mut found: impl FnMut(usize)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !accept(&node.bounds) {
+                continue;
+            }
+            match node.kind {
+                NodeKind::Leaf { first, count } => self.order[first..first + count].iter().for_each(|&i| found(i)),
+                NodeKind::Inner { left, right } => stack.extend([right, left]),
+            }
+        }
+    }
+
+    /// Hits ordenados por distância e GUID
+    fn hits(&self, mut hits: Vec<(usize, f32)>) -> Vec<SpatialHit> {
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| self.guids[a.0].cmp(&self.guids[b.0])));
+        hits.into_iter().map(|(i, distance)| SpatialHit { guid: self.guids[i].clone(), distance }).collect()
+    }
+
+    /// Elementos a até `radius` do ponto
+    pub fn within_radius(&self, center: [f32; 3], radius: f32) -> Vec<SpatialHit> {
+        let mut hits = Vec::new();
+        self.visit(
+            |b| point_distance(b, center) <= radius,
+            |i| {
+                let distance = point_distance(&self.boxes[i], center);
+                if distance <= radius {
+                    hits.push((i, distance));
+                }
+            },
+        );
+        self.hits(hits)
+    }
+
+    /// Outros elementos a até `radius` da caixa do elemento `guid`
+    ///
+    /// `None` se o GUID não está no índice.
+    pub fn near_element(&self, guid: &str, radius: f32) -> Option<Vec<SpatialHit>> {
+        let target = self.bounds(guid)?;
+        let mut hits = Vec::new();
+        self.visit(
+            |b| box_distance(b, &target) <= radius,
+            |i| {
+                let distance = box_distance(&self.boxes[i], &target);
+                if distance <= radius && self.guids[i] != guid {
+                    hits.push((i, distance));
+                }
+            },
+        );
+        Some(self.hits(hits))
+    }
+
+    /// GUIDs dos elementos cuja caixa intersecta `[min, max]`, em ordem alfabética
+    pub fn in_box(&self, min: [f32; 3], max: [f32; 3]) -> Vec<String> {
+        let query = [min[0], min[1], min[2], max[0], max[1], max[2]];
+        let mut guids = Vec::new();
+        self.visit(
+            |b| box_distance(b, &query) == 0.0,
+            |i| {
+                if box_distance(&self.boxes[i], &query) == 0.0 {
+                    guids.push(self.guids[i].clone());
+                }
+            },
+        );
+        guids.sort();
+        guids
+    }
+
+    /// Elementos atravessados pelo raio, do mais próximo ao mais distante
+    ///
+    /// A distância é o parâmetro de entrada na caixa ao longo da direção
+    /// normalizada (0 se a origem está dentro).
+    pub fn raycast(&self, origin: [f32; 3], direction: [f32; 3]) -> Vec<SpatialHit> {
+        let length = direction.iter().map(|d| d * d).sum::<f32>().sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return Vec::new();
+        }
+        let direction = direction.map(|d| d / length);
+        let mut hits = Vec::new();
+        self.visit(
+            |b| ray_entry(b, origin, direction).is_some(),
+            |i| {
+                if let Some(t) = ray_entry(&self.boxes[i], origin, direction) {
+                    hits.push((i, t));
+                }
+            },
+        );
+        self.hits(hits)
+    }
+
+    /// Os `k` elementos mais próximos do ponto
+    pub fn nearest(&self, point: [f32; 3], k: usize) -> Vec<SpatialHit> {
+        if self.nodes.is_empty() || k == 0 {
+            return Vec::new();
+        }
+        // Busca best-first: nós e elementos na mesma fila, pela distância mínima
+        let mut queue = BinaryHeap::new();
+        queue.push(Candidate { distance: point_distance(&self.nodes[0].bounds, point), node: Some(0), element: 0 });
+        let mut hits = Vec::with_capacity(k);
+        while let Some(candidate) = queue.pop() {
+            let Some(node) = candidate.node else {
+                hits.push((candidate.element, candidate.distance));
+                if hits.len() == k {
+                    break;
+                }
+                continue;
+            };
+            let node = &self.nodes[node];
+            match node.kind {
+                NodeKind::Leaf { first, count } => {
+                    for &i in &self.order[first..first + count] {
+                        queue.push(Candidate { distance: point_distance(&self.boxes[i], point), node: None, element: i });
+                    }
+                }
+                NodeKind::Inner { left, right } => {
+                    for child in [left, right] {
+                        let distance = point_distance(&self.nodes[child].bounds, point);
+                        queue.push(Candidate { distance, node: Some(child), element: 0 });
+                    }
+                }
+            }
+        }
+        self.hits(hits)
+    }
+}
+
+/// Entrada da fila de [`SpatialIndex::nearest`] (heap mínimo por distância)
+struct Candidate {
+    distance: f32,
+    node: Option<usize>,
+    element: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Invertido; em empate, nós antes de elementos
+        other.distance.total_cmp(&self.distance).then_with(|| self.node.is_some().cmp(&other.node.is_some()))
+    }
+}
+
+const EMPTY: Bounds = [f32::INFINITY, f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY];
+
+fn merge(a: &Bounds, b: &Bounds) -> Bounds {
+    [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2]), a[3].max(b[3]), a[4].max(b[4]), a[5].max(b[5])]
+}
+
+fn center(b: &Bounds) -> [f32; 3] {
+    [(b[0] + b[3]) * 0.5, (b[1] + b[4]) * 0.5, (b[2] + b[5]) * 0.5]
+}
+
+fn point_distance(b: &Bounds, p: [f32; 3]) -> f32 {
+    (0..3).map(|i| (b[i] - p[i]).max(p[i] - b[i + 3]).max(0.0).powi(2)).sum::<f32>().sqrt()
+}
+
+fn box_distance(a: &Bounds, b: &Bounds) -> f32 {
+    (0..3).map(|i| (b[i] - a[i + 3]).max(a[i] - b[i + 3]).max(0.0).powi(2)).sum::<f32>().sqrt()
+}
+
+/// Parâmetro de entrada do raio na caixa (slabs)
+fn ray_entry(b: &Bounds, origin: [f32; 3], direction: [f32; 3]) -> Option<f32> {
+    let (mut near, mut far) = (0.0f32, f32::INFINITY);
+    for i in 0..3 {
+        if direction[i] == 0.0 {
+            if origin[i] < b[i] || origin[i] > b[i + 3] {
+                return None;
+            }
+            continue;
+        }
+        let t0 = (b[i] - origin[i]) / direction[i];
+        let t1 = (b[i + 3] - origin[i]) / direction[i];
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+        if near > far {
+            return None;
+        }
+    }
+    Some(near)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spatial_queries() {
+        // Tubulação ao longo de X e uma fileira de elementos a cada 1 m em Y
+        let mut boxes = vec![("pipe".to_string(), [0.0, 0.0, 0.0, 10.0, 0.2, 0.2])];
+        for i in 1..=20 {
+            let y = i as f32;
+            boxes.push((format!("e{:02}", i), [4.0, y, 0.0, 5.0, y + 0.5, 1.0]));
+        }
+        boxes.push(("broken".to_string(), [1.0, 0.0, 0.0, 0.0, 1.0, 1.0]));
+        let index = SpatialIndex::from_boxes(boxes);
+        assert_eq!(index.len(), 21);
+
+        let near: Vec<_> = index.near_element("pipe", 2.0).unwrap().into_iter().map(|h| h.guid).collect();
+        assert_eq!(near, vec!["e01", "e02"]);
+        assert!(index.near_element("missing", 2.0).is_none());
+
+        let hits = index.within_radius([4.5, 0.0, 0.5], 3.0);
+        assert_eq!(hits[0].guid, "pipe");
+        assert!((hits[0].distance - 0.3).abs() < 1e-6);
+        assert_eq!(hits.len(), 4);
+
+        assert_eq!(index.in_box([0.0, 5.2, 0.0], [10.0, 6.0, 0.5]), vec!["e05", "e06"]);
+
+        let ray = index.raycast([4.5, 30.0, 0.5], [0.0, -2.0, 0.0]);
+        assert_eq!(ray.len(), 20);
+        assert_eq!((ray[0].guid.as_str(), ray[0].distance), ("e20", 9.5));
+        assert!(index.raycast([4.5, 30.0, 0.5], [1.0, 0.0, 0.0]).is_empty());
+
+        let nearest = index.nearest([4.5, 7.2, 0.5], 3);
+        let guids: Vec<_> = nearest.iter().map(|h| h.guid.as_str()).collect();
+        assert_eq!(guids, vec!["e07", "e06", "e08"]);
+        assert_eq!(nearest[0].distance, 0.0);
+
+        // A BVH concorda com a busca exaustiva
+        let brute = (0..index.len()).filter(|&i| point_distance(&index.boxes[i], [2.0, 9.0, 0.0]) <= 5.0).count();
+        assert_eq!(index.within_radius([2.0, 9.0, 0.0], 5.0).len(), brute);
+    }
+}
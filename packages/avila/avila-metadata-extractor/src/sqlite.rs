@@ -0,0 +1,338 @@
+//! Exportação relacional para SQLite (feature `sqlite`)
+//!
+//! Gera um snapshot consultável por SQL a partir de [`BimMetadata`]:
+//!
+//! | Tabela | Conteúdo |
+//! |--------|----------|
+//! | `elements` | um registro por elemento (tipo, nome, material, bbox, node glTF) |
+//! | `element_tags` | tags/classificações |
+//! | `properties` | `(element_guid, pset, name, value)`, com `value` tipado (TEXT/REAL/INTEGER) |
+//! | `quantities` | quantidades em SI e o valor original quando houve conversão |
+//! | `relations` | arestas do [`RelationGraph`](crate::RelationGraph) |
+//! | `storeys` | pavimentos da estrutura espacial |
+//! | `schema_version` | versões de esquema aplicadas |
+//!
+//! ```sql
+//! SELECT s.name, SUM(q.value)
+//! FROM quantities q
+//! JOIN relations r ON r.related = q.element_guid AND r.kind = 'contains'
+//! JOIN storeys s ON s.id = r.relating
+//! WHERE q.name = 'NetVolume'
+//! GROUP BY s.name;
+//! ```
+//!
+//! Reexportar para o mesmo arquivo substitui os dados; bancos criados por uma
+//! versão anterior são migrados antes.
+
+use crate::{BimMetadata, MetadataError, MetadataExtractor, PropertyValue, RelationKind, Result};
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+/// Versão do esquema gravada em `schema_version`
+pub const SQLITE_SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Migrações em ordem; a de índice `i` leva o esquema da versão `i` para `i + 1`
+const MIGRATIONS: &[&str] = &[
+    // v1
+    "CREATE TABLE elements (
+        guid TEXT PRIMARY KEY,
+        ifc_type TEXT NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT,
+        material TEXT,
+        mesh_node INTEGER,
+        mesh_hash INTEGER,
+        min_x REAL, min_y REAL, min_z REAL,
+        max_x REAL, max_y REAL, max_z REAL
+    );
+    CREATE TABLE element_tags (
+        element_guid TEXT NOT NULL REFERENCES elements(guid),
+        tag TEXT NOT NULL,
+        PRIMARY KEY (element_guid, tag)
+    );
+    CREATE TABLE properties (
+        element_guid TEXT NOT NULL REFERENCES elements(guid),
+        pset TEXT NOT NULL,
+        name TEXT NOT NULL,
+        value,
+        PRIMARY KEY (element_guid, pset, name)
+    );
+    CREATE TABLE quantities (
+        element_guid TEXT NOT NULL REFERENCES elements(guid),
+        name TEXT NOT NULL,
+        value REAL NOT NULL,
+        original_value REAL,
+        original_unit TEXT,
+        PRIMARY KEY (element_guid, name)
+    );
+    CREATE TABLE relations (
+        kind TEXT NOT NULL,
+        relating TEXT NOT NULL,
+        related TEXT NOT NULL
+    );
+    CREATE TABLE storeys (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        elevation REAL NOT NULL,
+        height REAL
+    );
+    CREATE INDEX idx_elements_type ON elements(ifc_type);
+    CREATE INDEX idx_properties_name ON properties(pset, name);
+    CREATE INDEX idx_quantities_name ON quantities(name);
+    CREATE INDEX idx_relations_relating ON relations(relating, kind);
+    CREATE INDEX idx_relations_related ON relations(related, kind);",
+];
+
+/// Tabelas de dados, limpas antes de cada exportação (dependentes primeiro)
+const DATA_TABLES: &[&str] = &["element_tags", "properties", "quantities", "relations", "storeys", "elements"];
+
+impl MetadataExtractor {
+    /// Exporta metadados para um banco SQLite em `path` (criado se não existir)
+    pub fn export_sqlite(&self, metadata: &BimMetadata, path: impl AsRef<Path>) -> Result<()> {
+        let mut conn = Connection::open(path)?;
+        self.write_sqlite(metadata, &mut conn)
+    }
+
+    /// Grava o snapshot numa conexão aberta, numa única transação
+    pub fn write_sqlite(&self, metadata: &BimMetadata, conn: &mut Connection) -> Result<()> {
+        let tx = conn.transaction()?;
+        migrate(&tx)?;
+        for table in DATA_TABLES {
+            tx.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        insert_elements(&tx, metadata)?;
+        insert_relations(&tx, metadata)?;
+        insert_storeys(&tx, metadata)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Aplica as migrações pendentes e devolve a versão resultante
+fn migrate(tx: &Transaction<'_>) -> Result<u32> {
+    tx.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, applied_at TEXT NOT NULL)")?;
+    let current: u32 = tx.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))?;
+    if current > SQLITE_SCHEMA_VERSION {
+        return Err(MetadataError::ExtractionError(format!(
+            "SQLite schema version {} is newer than supported version {}",
+            current, SQLITE_SCHEMA_VERSION
+        )));
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        tx.execute_batch(sql)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, applied_at) VALUES (?1, datetime('now'))",
+            params![index as u32 + 1],
+        )?;
+    }
+    Ok(SQLITE_SCHEMA_VERSION)
+}
+
+fn insert_elements(tx: &Transaction<'_>, metadata: &BimMetadata) -> Result<()> {
+    let mut element = tx.prepare(
+        "INSERT INTO elements (guid, ifc_type, name, description, material, mesh_node, mesh_hash,
+                               min_x, min_y, min_z, max_x, max_y, max_z)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+    )?;
+    let mut tag = tx.prepare("INSERT OR IGNORE INTO element_tags (element_guid, tag) VALUES (?1, ?2)")?;
+    let mut property = tx.prepare("INSERT INTO properties (element_guid, pset, name, value) VALUES (?1, ?2, ?3, ?4)")?;
+    let mut quantity = tx.prepare(
+        "INSERT INTO quantities (element_guid, name, value, original_value, original_unit) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+
+    for e in &metadata.elements {
+        let bbox = e.bounding_box.map(|b| b.map(f64::from));
+        let corner = |i: usize| bbox.map(|b| b[i]);
+        element.execute(params![
+            e.guid,
+            e.ifc_type,
+            e.name,
+            e.description,
+            e.material,
+            e.mesh_node,
+            // SQLite integers are signed; the hash keeps its bits
+            e.mesh_hash.map(|h| h as i64),
+            corner(0),
+            corner(1),
+            corner(2),
+            corner(3),
+            corner(4),
+            corner(5),
+        ])?;
+
+        for t in &e.tags {
+            tag.execute(params![e.guid, t])?;
+        }
+
+        for (pset, props) in &e.properties {
+            for (name, value) in props {
+                match value {
+                    PropertyValue::String(s) => property.execute(params![e.guid, pset, name, s])?,
+                    PropertyValue::Number(n) => property.execute(params![e.guid, pset, name, n])?,
+                    PropertyValue::Boolean(b) => property.execute(params![e.guid, pset, name, b])?,
+                };
+            }
+        }
+
+        for (name, value) in &e.quantities {
+            let original = e.original_quantities.get(name);
+            quantity.execute(params![
+                e.guid,
+                name,
+                value,
+                original.map(|o| o.value),
+                original.map(|o| o.unit.as_str()),
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_relations(tx: &Transaction<'_>, metadata: &BimMetadata) -> Result<()> {
+    let mut relation = tx.prepare("INSERT INTO relations (kind, relating, related) VALUES (?1, ?2, ?3)")?;
+    for r in metadata.relations.relations() {
+        relation.execute(params![relation_kind_name(r.kind), r.relating, r.related])?;
+    }
+    Ok(())
+}
+
+fn insert_storeys(tx: &Transaction<'_>, metadata: &BimMetadata) -> Result<()> {
+    let mut storey = tx.prepare("INSERT OR REPLACE INTO storeys (id, name, elevation, height) VALUES (?1, ?2, ?3, ?4)")?;
+    for s in &metadata.structure.storeys {
+        storey.execute(params![s.id, s.name, s.elevation, s.height])?;
+    }
+    Ok(())
+}
+
+/// Mesmo nome usado no JSON (`"kind": "contains"`)
+fn relation_kind_name(kind: RelationKind) -> &'static str {
+    match kind {
+        RelationKind::Aggregates => "aggregates",
+        RelationKind::Contains => "contains",
+        RelationKind::Voids => "voids",
+        RelationKind::Fills => "fills",
+        RelationKind::Connects => "connects",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ElementMetadata, GeometryHealth, ModelStatistics, OriginalQuantity, ProjectInfo, RelationGraph,
+        SpatialStructure, StoreyInfo, UnitContext,
+    };
+    use std::collections::HashMap;
+
+    fn sample() -> BimMetadata {
+        let mut pset = HashMap::new();
+        pset.insert("IsExternal".to_string(), PropertyValue::Boolean(true));
+        pset.insert("FireRating".to_string(), PropertyValue::String("REI 60".to_string()));
+        pset.insert("ThermalTransmittance".to_string(), PropertyValue::Number(0.35));
+
+        let wall = ElementMetadata {
+            guid: "2O_RrAJHv7xv2dl5cNZYOF".to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node: Some(3),
+            name: "Parede 01".to_string(),
+            description: None,
+            properties: HashMap::from([("Pset_WallCommon".to_string(), pset)]),
+            quantities: HashMap::from([("Length".to_string(), 5.2), ("NetVolume".to_string(), 3.12)]),
+            original_quantities: HashMap::from([(
+                "Length".to_string(),
+                OriginalQuantity { value: 5200.0, unit: "mm".to_string() },
+            )]),
+            material: Some("Concreto".to_string()),
+            bounding_box: Some([0.0, 0.0, 0.0, 5.2, 0.2, 3.0]),
+            mesh_hash: Some(u64::MAX),
+            tags: vec!["estrutural".to_string()],
+        };
+
+        let mut relations = RelationGraph::new();
+        relations.add(RelationKind::Contains, "storey-1", wall.guid.clone());
+
+        BimMetadata {
+            elements: vec![wall],
+            structure: SpatialStructure {
+                project: ProjectInfo { name: "Teste".to_string(), description: None, author: None, organization: None },
+                site: None,
+                buildings: vec![],
+                storeys: vec![StoreyInfo { id: "storey-1".to_string(), name: "Térreo".to_string(), elevation: 0.0, height: Some(3.0) }],
+            },
+            statistics: ModelStatistics {
+                total_elements: 1,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations,
+            units: UnitContext::default(),
+        }
+    }
+
+    #[test]
+    fn test_export_and_query() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let extractor = MetadataExtractor::new();
+        extractor.write_sqlite(&sample(), &mut conn).unwrap();
+        // Re-exporting replaces the snapshot instead of duplicating rows
+        extractor.write_sqlite(&sample(), &mut conn).unwrap();
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0)).unwrap()
+        };
+        assert_eq!((count("elements"), count("properties"), count("quantities"), count("relations")), (1, 3, 2, 1));
+        assert_eq!(count("schema_version"), 1);
+
+        let volume_by_storey: (String, f64) = conn
+            .query_row(
+                "SELECT s.name, SUM(q.value) FROM quantities q
+                 JOIN relations r ON r.related = q.element_guid AND r.kind = 'contains'
+                 JOIN storeys s ON s.id = r.relating
+                 WHERE q.name = 'NetVolume' GROUP BY s.name",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(volume_by_storey, ("Térreo".to_string(), 3.12));
+
+        // Property values keep their SQLite type
+        let types: Vec<String> = conn
+            .prepare("SELECT typeof(value) FROM properties ORDER BY name")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(types, ["text", "integer", "real"]);
+
+        let (unit, hash): (String, i64) = conn
+            .query_row(
+                "SELECT q.original_unit, e.mesh_hash FROM quantities q JOIN elements e ON e.guid = q.element_guid
+                 WHERE q.name = 'Length'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((unit.as_str(), hash as u64), ("mm", u64::MAX));
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY, applied_at TEXT NOT NULL);
+             INSERT INTO schema_version VALUES (99, '2030-01-01');",
+        )
+        .unwrap();
+
+        let err = MetadataExtractor::new().write_sqlite(&sample(), &mut conn).unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+    }
+}
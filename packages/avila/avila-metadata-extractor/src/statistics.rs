@@ -0,0 +1,224 @@
+//! Estatísticas detalhadas do modelo
+//!
+//! Complementa os totais de [`ModelStatistics`](crate::ModelStatistics) com a
+//! composição do modelo para dashboards: elementos/triângulos/área por
+//! pavimento, volume por material e contadores de saúde da geometria.
+
+use crate::tiles::storey_of;
+use crate::{ElementMetadata, MetadataExtractor, ModelStatistics, RelationGraph, SceneStats, SpatialStructure};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Estatísticas de um pavimento
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreyStatistics {
+    /// Id do pavimento (`StoreyInfo::id`)
+    pub storey_id: String,
+
+    pub name: String,
+
+    /// Elementos contidos (direta ou indiretamente)
+    pub element_count: usize,
+
+    /// Triângulos dos elementos do pavimento
+    pub triangle_count: usize,
+
+    /// Soma das áreas (m²)
+    pub area: f64,
+}
+
+/// Estatísticas de um material
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialStatistics {
+    pub material: String,
+
+    pub element_count: usize,
+
+    /// Soma dos volumes (m³)
+    pub volume: f64,
+}
+
+/// Contadores de saúde da geometria
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeometryHealth {
+    /// Elementos sem mesh nem bounding box (ou com 0 triângulos)
+    pub without_geometry: usize,
+
+    /// Elementos fora de qualquer pavimento
+    pub without_storey: usize,
+
+    /// Elementos sem material
+    pub without_material: usize,
+}
+
+impl GeometryHealth {
+    /// Nenhum problema encontrado
+    pub fn is_healthy(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl MetadataExtractor {
+    /// Calcula estatísticas com detalhamento por pavimento e por material
+    ///
+    /// Triângulos por pavimento vêm de [`SceneStats::triangles_by_element`];
+    /// elementos ausentes do mapa contam 0.
+    pub fn calculate_detailed_statistics(
+        &self,
+        elements: &[ElementMetadata],
+        structure: &SpatialStructure,
+        relations: &RelationGraph,
+        scene_stats: &SceneStats,
+    ) -> ModelStatistics {
+        let mut statistics = self.calculate_statistics(elements, scene_stats);
+
+        let storey_ids: HashSet<&str> = structure.storeys.iter().map(|s| s.id.as_str()).collect();
+        let mut storeys: Vec<StoreyStatistics> = structure
+            .storeys
+            .iter()
+            .map(|s| StoreyStatistics {
+                storey_id: s.id.clone(),
+                name: s.name.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut materials: BTreeMap<&str, MaterialStatistics> = BTreeMap::new();
+        let mut health = GeometryHealth::default();
+
+        for elem in elements {
+            let triangles = scene_stats.triangles_by_element.get(&elem.guid).copied();
+            let has_geometry = match triangles {
+                Some(count) => count > 0,
+                None => elem.mesh_node.is_some() || elem.bounding_box.is_some(),
+            };
+            if !has_geometry {
+                health.without_geometry += 1;
+            }
+
+            match storey_of(relations, &storey_ids, &elem.guid) {
+                Some(storey_id) => {
+                    // Ids duplicados na estrutura: vale o primeiro
+                    if let Some(storey) = storeys.iter_mut().find(|s| s.storey_id == storey_id) {
+                        storey.element_count += 1;
+                        storey.triangle_count += triangles.unwrap_or(0);
+                        storey.area += elem.quantities.get("Area").copied().unwrap_or(0.0);
+                    }
+                }
+                None => health.without_storey += 1,
+            }
+
+            match elem.material.as_deref() {
+                Some(material) if !material.is_empty() => {
+                    let entry = materials.entry(material).or_insert_with(|| MaterialStatistics {
+                        material: material.to_string(),
+                        ..Default::default()
+                    });
+                    entry.element_count += 1;
+                    entry.volume += elem.quantities.get("Volume").copied().unwrap_or(0.0);
+                }
+                _ => health.without_material += 1,
+            }
+        }
+
+        statistics.storeys = storeys;
+        statistics.materials = materials.into_values().collect();
+        statistics.health = health;
+        statistics
+    }
+}
+
+// ============================================================================
+// TESTES
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProjectInfo, RelationshipData, StoreyInfo};
+    use std::collections::HashMap;
+
+    fn element(guid: &str, material: Option<&str>, area: f64, volume: f64, mesh_node: Option<u32>) -> ElementMetadata {
+        ElementMetadata {
+            guid: guid.to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node,
+            name: guid.to_string(),
+            description: None,
+            properties: HashMap::new(),
+            quantities: HashMap::from([("Area".to_string(), area), ("Volume".to_string(), volume)]),
+            original_quantities: HashMap::new(),
+            material: material.map(str::to_string),
+            bounding_box: None,
+            mesh_hash: None,
+            tags: vec![],
+        }
+    }
+
+    fn contains(relating: &str, related: &str) -> RelationshipData {
+        RelationshipData {
+            ifc_type: "IfcRelContainedInSpatialStructure".to_string(),
+            relating_guid: relating.to_string(),
+            related_guids: vec![related.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_detailed_statistics() {
+        let structure = SpatialStructure {
+            project: ProjectInfo {
+                name: "Teste".to_string(),
+                description: None,
+                author: None,
+                organization: None,
+            },
+            site: None,
+            buildings: vec![],
+            storeys: ["s1", "s2"]
+                .iter()
+                .map(|id| StoreyInfo { id: id.to_string(), name: id.to_uppercase(), elevation: 0.0, height: None })
+                .collect(),
+        };
+        let elements = vec![
+            element("a", Some("Concreto"), 10.0, 2.0, Some(0)),
+            element("b", Some("Concreto"), 5.0, 1.5, Some(1)),
+            element("c", Some("Aço"), 1.0, 0.25, Some(2)),
+            element("d", None, 0.0, 0.0, None),
+        ];
+        let extractor = MetadataExtractor::new();
+        let relations = extractor.extract_relations(&[contains("s1", "a"), contains("s1", "b"), contains("s2", "c")]);
+        let scene_stats = SceneStats {
+            triangle_count: 30,
+            vertex_count: 60,
+            triangles_by_element: HashMap::from([
+                ("a".to_string(), 12),
+                ("b".to_string(), 12),
+                ("c".to_string(), 6),
+            ]),
+        };
+
+        let stats = extractor.calculate_detailed_statistics(&elements, &structure, &relations, &scene_stats);
+
+        assert_eq!(stats.total_elements, 4);
+        assert_eq!(stats.storeys.len(), 2);
+        assert_eq!(stats.storeys[0].storey_id, "s1");
+        assert_eq!(stats.storeys[0].element_count, 2);
+        assert_eq!(stats.storeys[0].triangle_count, 24);
+        assert!((stats.storeys[0].area - 15.0).abs() < 1e-9);
+        assert_eq!(stats.storeys[1].element_count, 1);
+        assert_eq!(stats.storeys[1].triangle_count, 6);
+
+        assert_eq!(stats.materials.len(), 2);
+        let concrete = stats.materials.iter().find(|m| m.material == "Concreto").unwrap();
+        assert_eq!(concrete.element_count, 2);
+        assert!((concrete.volume - 3.5).abs() < 1e-9);
+
+        assert_eq!(
+            stats.health,
+            GeometryHealth { without_geometry: 1, without_storey: 1, without_material: 1 }
+        );
+        assert!(!stats.health.is_healthy());
+    }
+}
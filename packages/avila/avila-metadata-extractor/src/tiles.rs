@@ -0,0 +1,534 @@
+//! Saída de metadados em tiles (carregamento sob demanda)
+//!
+//! Um `BimMetadata` de 100k elementos gera um JSON de centenas de MB. O modo
+//! em tiles divide os elementos por pavimento (ou por grade espacial) em
+//! arquivos independentes e gera um manifesto leve com estrutura espacial,
+//! estatísticas e o índice GUID → tile:
+//!
+//! ```text
+//! manifest.json
+//! tiles/tile-0000.json
+//! tiles/tile-0001.json
+//! ...
+//! ```
+//!
+//! O [`TileLoader`] lê o manifesto e carrega/descarta tiles conforme a
+//! necessidade, montando um `BimMetadata` apenas com o que está em memória.
+
+use crate::{
+    BimMetadata, ElementMetadata, MetadataError, ModelStatistics, Relation, RelationGraph, Result,
+    SpatialStructure, UnitContext,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Versão do formato do manifesto
+pub const TILE_FORMAT_VERSION: u32 = 1;
+
+/// Nome do arquivo de manifesto
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Critério de divisão dos elementos
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TilingStrategy {
+    /// Um tile por pavimento (via relações de contenção/agregação)
+    ByStorey,
+    /// Grade XY pelo centro da bounding box (tamanho da célula em metros)
+    Grid { cell_size: f64 },
+}
+
+/// Entrada de um tile no manifesto
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TileEntry {
+    pub id: String,
+    /// Caminho relativo ao manifesto
+    pub file: String,
+    /// Pavimento (estratégia `ByStorey`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storey: Option<String>,
+    /// Célula da grade (estratégia `Grid`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<[i64; 2]>,
+    pub element_count: usize,
+    /// União das bounding boxes dos elementos do tile
+    pub bounds: Option<[f32; 6]>,
+}
+
+/// Índice leve: tudo menos os elementos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataManifest {
+    pub format_version: u32,
+    pub strategy: TilingStrategy,
+    pub structure: SpatialStructure,
+    pub statistics: ModelStatistics,
+    #[serde(default)]
+    pub units: UnitContext,
+    pub tiles: Vec<TileEntry>,
+    /// GUID → id do tile
+    pub element_tiles: HashMap<String, String>,
+}
+
+impl MetadataManifest {
+    pub fn tile(&self, id: &str) -> Option<&TileEntry> {
+        self.tiles.iter().find(|t| t.id == id)
+    }
+
+    /// Tile de um pavimento
+    pub fn storey_tile(&self, storey_id: &str) -> Option<&TileEntry> {
+        self.tiles.iter().find(|t| t.storey.as_deref() == Some(storey_id))
+    }
+
+    /// Tile que contém o elemento
+    pub fn tile_of(&self, guid: &str) -> Option<&str> {
+        self.element_tiles.get(guid).map(String::as_str)
+    }
+
+    /// Tiles cuja bounding box intersecta a região
+    pub fn tiles_in(&self, region: &[f32; 6]) -> Vec<&TileEntry> {
+        self.tiles
+            .iter()
+            .filter(|t| {
+                t.bounds.is_some_and(|b| {
+                    (0..3).all(|axis| b[axis] <= region[axis + 3] && region[axis] <= b[axis + 3])
+                })
+            })
+            .collect()
+    }
+}
+
+/// Conteúdo de um arquivo de tile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataTile {
+    pub id: String,
+    pub elements: Vec<ElementMetadata>,
+    /// Relações cujo lado `related` está neste tile
+    pub relations: Vec<Relation>,
+}
+
+/// Metadados divididos em manifesto + tiles
+#[derive(Debug, Clone)]
+pub struct TiledMetadata {
+    pub manifest: MetadataManifest,
+    pub tiles: Vec<MetadataTile>,
+}
+
+impl TiledMetadata {
+    /// Divide os metadados segundo a estratégia
+    pub fn split(metadata: &BimMetadata, strategy: TilingStrategy) -> Result<Self> {
+        if let TilingStrategy::Grid { cell_size } = strategy {
+            if cell_size.is_nan() || cell_size <= 0.0 {
+                return Err(MetadataError::ExtractionError(format!(
+                    "Invalid grid cell size: {}",
+                    cell_size
+                )));
+            }
+        }
+
+        // Chave de agrupamento → elementos (BTreeMap: ordem estável dos tiles)
+        let mut groups: BTreeMap<TileKey, Vec<&ElementMetadata>> = BTreeMap::new();
+        let storeys: HashSet<&str> = metadata.structure.storeys.iter().map(|s| s.id.as_str()).collect();
+
+        for element in &metadata.elements {
+            let key = match strategy {
+                TilingStrategy::ByStorey => storey_of(&metadata.relations, &storeys, &element.guid)
+                    .map(|s| TileKey::Storey(storey_order(metadata, s), s.to_string())),
+                TilingStrategy::Grid { cell_size } => element.bounding_box.map(|bb| {
+                    let cx = (bb[0] + bb[3]) as f64 / 2.0;
+                    let cy = (bb[1] + bb[4]) as f64 / 2.0;
+                    TileKey::Cell([(cx / cell_size).floor() as i64, (cy / cell_size).floor() as i64])
+                }),
+            };
+            groups.entry(key.unwrap_or(TileKey::Unassigned)).or_default().push(element);
+        }
+
+        let mut tiles = Vec::with_capacity(groups.len());
+        let mut entries = Vec::with_capacity(groups.len());
+        let mut element_tiles = HashMap::with_capacity(metadata.elements.len());
+
+        for (index, (key, elements)) in groups.into_iter().enumerate() {
+            let id = format!("tile-{:04}", index);
+            for element in &elements {
+                element_tiles.insert(element.guid.clone(), id.clone());
+            }
+
+            let bounds = elements
+                .iter()
+                .filter_map(|e| e.bounding_box)
+                .reduce(|a, b| {
+                    [
+                        a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2]),
+                        a[3].max(b[3]), a[4].max(b[4]), a[5].max(b[5]),
+                    ]
+                });
+
+            let (storey, cell) = match key {
+                TileKey::Storey(_, storey) => (Some(storey), None),
+                TileKey::Cell(cell) => (None, Some(cell)),
+                TileKey::Unassigned => (None, None),
+            };
+
+            entries.push(TileEntry {
+                id: id.clone(),
+                file: format!("tiles/{}.json", id),
+                storey,
+                cell,
+                element_count: elements.len(),
+                bounds,
+            });
+            tiles.push(MetadataTile {
+                id,
+                elements: elements.into_iter().cloned().collect(),
+                relations: Vec::new(),
+            });
+        }
+
+        // Relações seguem o elemento `related`; relações entre elementos
+        // fora da lista (ex.: edifício → pavimento) ficam no primeiro tile
+        let element_index: HashMap<&str, usize> = tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(i, t)| t.elements.iter().map(move |e| (e.guid.as_str(), i)))
+            .collect();
+        let mut placed: Vec<Vec<Relation>> = vec![Vec::new(); tiles.len()];
+        for relation in metadata.relations.relations() {
+            let target = element_index
+                .get(relation.related.as_str())
+                .or_else(|| element_index.get(relation.relating.as_str()))
+                .copied()
+                .unwrap_or(0);
+            if let Some(bucket) = placed.get_mut(target) {
+                bucket.push(relation.clone());
+            }
+        }
+        for (tile, relations) in tiles.iter_mut().zip(placed) {
+            tile.relations = relations;
+        }
+
+        Ok(Self {
+            manifest: MetadataManifest {
+                format_version: TILE_FORMAT_VERSION,
+                strategy,
+                structure: metadata.structure.clone(),
+                statistics: metadata.statistics.clone(),
+                units: metadata.units.clone(),
+                tiles: entries,
+                element_tiles,
+            },
+            tiles,
+        })
+    }
+
+    /// Arquivos a gravar: (caminho relativo, conteúdo JSON)
+    pub fn files(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut files = Vec::with_capacity(self.tiles.len() + 1);
+        files.push((MANIFEST_FILE.to_string(), serde_json::to_vec(&self.manifest)?));
+        for (entry, tile) in self.manifest.tiles.iter().zip(&self.tiles) {
+            files.push((entry.file.clone(), serde_json::to_vec(tile)?));
+        }
+        Ok(files)
+    }
+
+    /// Grava manifesto e tiles em um diretório
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        for (file, bytes) in self.files()? {
+            let path = dir.join(file);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, bytes)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum TileKey {
+    /// (ordem na estrutura espacial, id do pavimento)
+    Storey(usize, String),
+    Cell([i64; 2]),
+    Unassigned,
+}
+
+/// Primeiro ancestral do elemento que é um pavimento
+pub(crate) fn storey_of<'a>(relations: &'a RelationGraph, storeys: &HashSet<&str>, guid: &'a str) -> Option<&'a str> {
+    if storeys.contains(guid) {
+        return Some(guid);
+    }
+    relations.ancestors(guid).into_iter().find(|a| storeys.contains(a))
+}
+
+fn storey_order(metadata: &BimMetadata, storey_id: &str) -> usize {
+    metadata.structure.storeys.iter().position(|s| s.id == storey_id).unwrap_or(usize::MAX)
+}
+
+// ============================================================================
+// CARREGAMENTO
+// ============================================================================
+
+/// Origem dos arquivos de tiles (disco, rede, bundle da aplicação)
+pub trait TileSource {
+    /// Lê um arquivo pelo caminho relativo ao manifesto
+    fn read(&self, file: &str) -> Result<Vec<u8>>;
+}
+
+/// Tiles gravados em um diretório local
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl TileSource for DirectorySource {
+    fn read(&self, file: &str) -> Result<Vec<u8>> {
+        Ok(std::fs::read(self.root.join(file))?)
+    }
+}
+
+/// Tiles em memória (caminho → conteúdo)
+impl TileSource for HashMap<String, Vec<u8>> {
+    fn read(&self, file: &str) -> Result<Vec<u8>> {
+        self.get(file)
+            .cloned()
+            .ok_or_else(|| MetadataError::ExtractionError(format!("Tile file not found: {}", file)))
+    }
+}
+
+/// Carrega tiles sob demanda a partir do manifesto
+pub struct TileLoader<S: TileSource> {
+    source: S,
+    manifest: MetadataManifest,
+    loaded: BTreeMap<String, MetadataTile>,
+}
+
+impl<S: TileSource> TileLoader<S> {
+    /// Lê apenas o manifesto
+    pub fn open(source: S) -> Result<Self> {
+        let manifest: MetadataManifest = serde_json::from_slice(&source.read(MANIFEST_FILE)?)?;
+        if manifest.format_version > TILE_FORMAT_VERSION {
+            return Err(MetadataError::ExtractionError(format!(
+                "Unsupported tile format version: {}",
+                manifest.format_version
+            )));
+        }
+
+        Ok(Self {
+            source,
+            manifest,
+            loaded: BTreeMap::new(),
+        })
+    }
+
+    pub fn manifest(&self) -> &MetadataManifest {
+        &self.manifest
+    }
+
+    pub fn is_loaded(&self, tile_id: &str) -> bool {
+        self.loaded.contains_key(tile_id)
+    }
+
+    /// Ids dos tiles em memória
+    pub fn loaded_tiles(&self) -> impl Iterator<Item = &str> {
+        self.loaded.keys().map(String::as_str)
+    }
+
+    /// Carrega um tile (no-op se já carregado)
+    pub fn load_tile(&mut self, tile_id: &str) -> Result<&MetadataTile> {
+        if !self.loaded.contains_key(tile_id) {
+            let entry = self.manifest.tile(tile_id).ok_or_else(|| {
+                MetadataError::ExtractionError(format!("Unknown tile: {}", tile_id))
+            })?;
+            let tile: MetadataTile = serde_json::from_slice(&self.source.read(&entry.file)?)?;
+            self.loaded.insert(tile_id.to_string(), tile);
+        }
+        Ok(&self.loaded[tile_id])
+    }
+
+    /// Carrega o tile de um pavimento
+    pub fn load_storey(&mut self, storey_id: &str) -> Result<&MetadataTile> {
+        let tile_id = self
+            .manifest
+            .storey_tile(storey_id)
+            .map(|t| t.id.clone())
+            .ok_or_else(|| MetadataError::ExtractionError(format!("No tile for storey: {}", storey_id)))?;
+        self.load_tile(&tile_id)
+    }
+
+    /// Busca um elemento, carregando seu tile se necessário
+    pub fn element(&mut self, guid: &str) -> Result<Option<&ElementMetadata>> {
+        let tile_id = match self.manifest.tile_of(guid) {
+            Some(id) => id.to_string(),
+            None => return Ok(None),
+        };
+        let tile = self.load_tile(&tile_id)?;
+        Ok(tile.elements.iter().find(|e| e.guid == guid))
+    }
+
+    /// Libera um tile da memória
+    pub fn unload(&mut self, tile_id: &str) {
+        self.loaded.remove(tile_id);
+    }
+
+    /// Monta um `BimMetadata` com os tiles carregados
+    pub fn merged(&self) -> BimMetadata {
+        let mut elements = Vec::new();
+        let mut relations = Vec::new();
+        for tile in self.loaded.values() {
+            elements.extend(tile.elements.iter().cloned());
+            relations.extend(tile.relations.iter().cloned());
+        }
+
+        BimMetadata {
+            elements,
+            structure: self.manifest.structure.clone(),
+            statistics: self.manifest.statistics.clone(),
+            relations: relations.into(),
+            units: self.manifest.units.clone(),
+        }
+    }
+
+    /// Carrega todos os tiles e monta o modelo completo
+    pub fn load_all(&mut self) -> Result<BimMetadata> {
+        let ids: Vec<String> = self.manifest.tiles.iter().map(|t| t.id.clone()).collect();
+        for id in ids {
+            self.load_tile(&id)?;
+        }
+        Ok(self.merged())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeometryHealth, ProjectInfo, RelationKind, StoreyInfo};
+
+    fn element(guid: &str, x: f32) -> ElementMetadata {
+        ElementMetadata {
+            guid: guid.to_string(),
+            ifc_type: "IfcWall".to_string(),
+            mesh_node: None,
+            name: guid.to_string(),
+            description: None,
+            properties: HashMap::new(),
+            quantities: HashMap::new(),
+            original_quantities: HashMap::new(),
+            material: None,
+            bounding_box: Some([x, 0.0, 0.0, x + 1.0, 1.0, 3.0]),
+            mesh_hash: None,
+            tags: vec![],
+        }
+    }
+
+    fn storey(id: &str, elevation: f64) -> StoreyInfo {
+        StoreyInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            elevation,
+            height: None,
+        }
+    }
+
+    fn sample() -> BimMetadata {
+        let mut relations = RelationGraph::new();
+        relations.add(RelationKind::Aggregates, "building", "s1");
+        relations.add(RelationKind::Aggregates, "building", "s2");
+        relations.add(RelationKind::Contains, "s1", "w1");
+        relations.add(RelationKind::Contains, "s1", "w2");
+        relations.add(RelationKind::Contains, "s2", "w3");
+
+        BimMetadata {
+            elements: vec![element("w1", 0.0), element("w2", 25.0), element("w3", 0.0), element("orphan", 50.0)],
+            structure: SpatialStructure {
+                project: ProjectInfo {
+                    name: "Teste".to_string(),
+                    description: None,
+                    author: None,
+                    organization: None,
+                },
+                site: None,
+                buildings: vec![],
+                storeys: vec![storey("s1", 0.0), storey("s2", 3.0)],
+            },
+            statistics: ModelStatistics {
+                total_elements: 4,
+                elements_by_type: HashMap::new(),
+                total_triangles: 0,
+                total_vertices: 0,
+                total_area: None,
+                total_volume: None,
+                storeys: vec![],
+                materials: vec![],
+                health: GeometryHealth::default(),
+            },
+            relations,
+            units: UnitContext::default(),
+        }
+    }
+
+    #[test]
+    fn test_split_by_storey() {
+        let tiled = TiledMetadata::split(&sample(), TilingStrategy::ByStorey).unwrap();
+        let manifest = &tiled.manifest;
+
+        assert_eq!(manifest.tiles.len(), 3);
+        assert_eq!(manifest.tiles[0].storey.as_deref(), Some("s1"));
+        assert_eq!(manifest.tiles[0].element_count, 2);
+        assert_eq!(manifest.tiles[1].storey.as_deref(), Some("s2"));
+        assert_eq!(manifest.tiles[2].storey, None); // "orphan"
+        assert_eq!(manifest.tile_of("w3"), Some("tile-0001"));
+
+        let relation_count: usize = tiled.tiles.iter().map(|t| t.relations.len()).sum();
+        assert_eq!(relation_count, 5);
+    }
+
+    #[test]
+    fn test_split_by_grid() {
+        let tiled = TiledMetadata::split(&sample(), TilingStrategy::Grid { cell_size: 20.0 }).unwrap();
+        let manifest = &tiled.manifest;
+
+        assert_eq!(manifest.tiles.len(), 3);
+        assert_eq!(manifest.tiles[0].cell, Some([0, 0]));
+        assert_eq!(manifest.tiles[0].element_count, 2); // w1, w3
+        assert_eq!(manifest.tiles_in(&[24.0, 0.0, 0.0, 30.0, 1.0, 1.0]).len(), 1);
+
+        assert!(TiledMetadata::split(&sample(), TilingStrategy::Grid { cell_size: 0.0 }).is_err());
+    }
+
+    #[test]
+    fn test_loader_on_demand() {
+        let files: HashMap<String, Vec<u8>> = TiledMetadata::split(&sample(), TilingStrategy::ByStorey)
+            .unwrap()
+            .files()
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        let mut loader = TileLoader::open(files).unwrap();
+        assert_eq!(loader.loaded_tiles().count(), 0);
+
+        assert_eq!(loader.load_storey("s2").unwrap().elements.len(), 1);
+        assert!(loader.element("w1").unwrap().is_some());
+        assert_eq!(loader.loaded_tiles().count(), 2);
+
+        let partial = loader.merged();
+        assert_eq!(partial.elements.len(), 3);
+        assert_eq!(partial.relations.parent("w3"), Some("s2"));
+
+        loader.unload("tile-0000");
+        assert!(!loader.is_loaded("tile-0000"));
+
+        let full = loader.load_all().unwrap();
+        assert_eq!(full.elements.len(), 4);
+        assert_eq!(full.relations.len(), 5);
+        assert!(loader.element("missing").unwrap().is_none());
+    }
+}
@@ -0,0 +1,363 @@
+//! Sistema de unidades do projeto (IfcUnitAssignment)
+//!
+//! Arquivos IFC declaram as unidades do projeto (mm, ft², ...) em
+//! `IfcUnitAssignment`. Todas as quantidades exportadas são normalizadas
+//! para SI (m, m², m³, kg); o valor e o símbolo originais ficam disponíveis
+//! para exibição.
+
+use crate::{MetadataError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Grandeza física de uma quantidade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnitKind {
+    Length,
+    Area,
+    Volume,
+    Mass,
+}
+
+impl UnitKind {
+    /// Mapeia o `UnitType` IFC (`.LENGTHUNIT.`, `AREAUNIT`, ...)
+    pub fn from_ifc_type(unit_type: &str) -> Option<Self> {
+        match unit_type.trim_matches('.').to_ascii_uppercase().as_str() {
+            "LENGTHUNIT" => Some(Self::Length),
+            "AREAUNIT" => Some(Self::Area),
+            "VOLUMEUNIT" => Some(Self::Volume),
+            "MASSUNIT" => Some(Self::Mass),
+            _ => None,
+        }
+    }
+
+    /// Infere a grandeza pelo nome da quantidade (`NetSideArea`, `GrossVolume`, ...)
+    pub fn for_quantity(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        if lower.ends_with("area") {
+            Some(Self::Area)
+        } else if lower.ends_with("volume") {
+            Some(Self::Volume)
+        } else if lower.ends_with("weight") || lower.ends_with("mass") {
+            Some(Self::Mass)
+        } else if ["length", "width", "height", "depth", "perimeter", "thickness"]
+            .iter()
+            .any(|suffix| lower.ends_with(suffix))
+        {
+            Some(Self::Length)
+        } else {
+            None
+        }
+    }
+
+    /// Símbolo da unidade SI correspondente
+    pub fn si_symbol(&self) -> &'static str {
+        match self {
+            Self::Length => "m",
+            Self::Area => "m²",
+            Self::Volume => "m³",
+            Self::Mass => "kg",
+        }
+    }
+}
+
+/// Unidade declarada no arquivo, com o fator de conversão para SI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unit {
+    /// Símbolo para exibição (`mm`, `ft²`, `kg`)
+    pub symbol: String,
+    /// Multiplicador: valor × `to_si` = valor em SI
+    pub to_si: f64,
+}
+
+impl Unit {
+    /// Unidade SI da grandeza
+    pub fn si(kind: UnitKind) -> Self {
+        Self {
+            symbol: kind.si_symbol().to_string(),
+            to_si: 1.0,
+        }
+    }
+
+    pub fn is_si(&self) -> bool {
+        self.to_si == 1.0
+    }
+}
+
+/// Quantidade como declarada no arquivo (para exibição)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginalQuantity {
+    pub value: f64,
+    pub unit: String,
+}
+
+/// Unidades do projeto por grandeza
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitContext {
+    pub length: Unit,
+    pub area: Unit,
+    pub volume: Unit,
+    pub mass: Unit,
+}
+
+impl UnitContext {
+    /// Contexto SI (padrão IFC quando não há IfcUnitAssignment)
+    pub fn si() -> Self {
+        Self {
+            length: Unit::si(UnitKind::Length),
+            area: Unit::si(UnitKind::Area),
+            volume: Unit::si(UnitKind::Volume),
+            mass: Unit::si(UnitKind::Mass),
+        }
+    }
+
+    /// Constrói o contexto a partir das unidades de IfcUnitAssignment
+    ///
+    /// Grandezas não declaradas permanecem em SI; tipos de unidade que não
+    /// afetam quantidades (ângulo, tempo, ...) são ignorados.
+    pub fn from_assignment(units: &[UnitData]) -> Result<Self> {
+        let mut context = Self::si();
+        for data in units {
+            if let Some(kind) = UnitKind::from_ifc_type(&data.unit_type) {
+                *context.unit_mut(kind) = data.resolve(kind)?;
+            }
+        }
+        Ok(context)
+    }
+
+    pub fn unit(&self, kind: UnitKind) -> &Unit {
+        match kind {
+            UnitKind::Length => &self.length,
+            UnitKind::Area => &self.area,
+            UnitKind::Volume => &self.volume,
+            UnitKind::Mass => &self.mass,
+        }
+    }
+
+    fn unit_mut(&mut self, kind: UnitKind) -> &mut Unit {
+        match kind {
+            UnitKind::Length => &mut self.length,
+            UnitKind::Area => &mut self.area,
+            UnitKind::Volume => &mut self.volume,
+            UnitKind::Mass => &mut self.mass,
+        }
+    }
+
+    /// Converte um valor da unidade do projeto para SI
+    pub fn to_si(&self, kind: UnitKind, value: f64) -> f64 {
+        value * self.unit(kind).to_si
+    }
+
+    /// Normaliza uma quantidade pelo nome
+    ///
+    /// Retorna o valor em SI e, quando houve conversão, o valor original.
+    /// Quantidades de grandeza desconhecida (contagens, ...) passam intactas.
+    pub fn normalize_quantity(&self, name: &str, value: f64) -> (f64, Option<OriginalQuantity>) {
+        match UnitKind::for_quantity(name) {
+            Some(kind) if !self.unit(kind).is_si() => {
+                let unit = self.unit(kind);
+                (
+                    value * unit.to_si,
+                    Some(OriginalQuantity {
+                        value,
+                        unit: unit.symbol.clone(),
+                    }),
+                )
+            }
+            _ => (value, None),
+        }
+    }
+}
+
+impl Default for UnitContext {
+    fn default() -> Self {
+        Self::si()
+    }
+}
+
+/// Unidade bruta de IfcUnitAssignment (interface com parser IFC)
+///
+/// `IfcSIUnit` preenche `prefix`/`name`; `IfcConversionBasedUnit` preenche
+/// `name` e, quando disponível, `conversion_factor` (valor SI de 1 unidade).
+#[derive(Debug, Clone)]
+pub struct UnitData {
+    /// UnitType IFC (`.LENGTHUNIT.`, `.AREAUNIT.`, ...)
+    pub unit_type: String,
+    /// Prefixo SI (`.MILLI.`, `.KILO.`)
+    pub prefix: Option<String>,
+    /// Nome (`.METRE.`, `.SQUARE_METRE.`, `FOOT`, `SQUARE FOOT`)
+    pub name: String,
+    /// Fator para SI de unidades convertidas
+    pub conversion_factor: Option<f64>,
+}
+
+impl UnitData {
+    fn resolve(&self, kind: UnitKind) -> Result<Unit> {
+        let name = normalize_name(&self.name);
+
+        if let Some((unit_kind, power, base, symbol)) = si_base(&name) {
+            if unit_kind != kind {
+                return Err(MetadataError::ExtractionError(format!(
+                    "Unit {} does not match unit type {}",
+                    self.name, self.unit_type
+                )));
+            }
+
+            let (scale, prefix_symbol) = match &self.prefix {
+                Some(prefix) => si_prefix(&normalize_name(prefix)).ok_or_else(|| {
+                    MetadataError::ExtractionError(format!("Unknown SI prefix: {}", prefix))
+                })?,
+                None => (1.0, ""),
+            };
+
+            return Ok(Unit {
+                symbol: format!("{}{}{}", prefix_symbol, symbol, power_suffix(power)),
+                to_si: scale.powi(power) * base,
+            });
+        }
+
+        let known = conversion_based(&name);
+        let to_si = self
+            .conversion_factor
+            .or(known.map(|(factor, _)| factor))
+            .ok_or_else(|| {
+                MetadataError::ExtractionError(format!("Unknown unit without conversion factor: {}", self.name))
+            })?;
+        let symbol = known
+            .map(|(_, symbol)| symbol.to_string())
+            .unwrap_or_else(|| self.name.trim_matches('.').to_lowercase());
+
+        Ok(Unit { symbol, to_si })
+    }
+}
+
+/// `.SQUARE_METRE.` / `square metre` → `SQUARE_METRE`
+fn normalize_name(name: &str) -> String {
+    name.trim_matches('.').trim().to_ascii_uppercase().replace(' ', "_")
+}
+
+/// Unidades SI base: (grandeza, potência, fator da unidade base, símbolo)
+fn si_base(name: &str) -> Option<(UnitKind, i32, f64, &'static str)> {
+    match name {
+        "METRE" => Some((UnitKind::Length, 1, 1.0, "m")),
+        "SQUARE_METRE" => Some((UnitKind::Area, 2, 1.0, "m")),
+        "CUBIC_METRE" => Some((UnitKind::Volume, 3, 1.0, "m")),
+        "GRAM" => Some((UnitKind::Mass, 1, 1e-3, "g")),
+        _ => None,
+    }
+}
+
+fn si_prefix(prefix: &str) -> Option<(f64, &'static str)> {
+    match prefix {
+        "EXA" => Some((1e18, "E")),
+        "PETA" => Some((1e15, "P")),
+        "TERA" => Some((1e12, "T")),
+        "GIGA" => Some((1e9, "G")),
+        "MEGA" => Some((1e6, "M")),
+        "KILO" => Some((1e3, "k")),
+        "HECTO" => Some((1e2, "h")),
+        "DECA" => Some((1e1, "da")),
+        "DECI" => Some((1e-1, "d")),
+        "CENTI" => Some((1e-2, "c")),
+        "MILLI" => Some((1e-3, "m")),
+        "MICRO" => Some((1e-6, "µ")),
+        "NANO" => Some((1e-9, "n")),
+        "PICO" => Some((1e-12, "p")),
+        "FEMTO" => Some((1e-15, "f")),
+        "ATTO" => Some((1e-18, "a")),
+        _ => None,
+    }
+}
+
+fn power_suffix(power: i32) -> &'static str {
+    match power {
+        2 => "²",
+        3 => "³",
+        _ => "",
+    }
+}
+
+/// Unidades imperiais usuais: (fator para SI, símbolo)
+fn conversion_based(name: &str) -> Option<(f64, &'static str)> {
+    match name {
+        "INCH" => Some((0.0254, "in")),
+        "FOOT" => Some((0.3048, "ft")),
+        "YARD" => Some((0.9144, "yd")),
+        "MILE" => Some((1609.344, "mi")),
+        "SQUARE_INCH" => Some((0.00064516, "in²")),
+        "SQUARE_FOOT" => Some((0.09290304, "ft²")),
+        "SQUARE_YARD" => Some((0.83612736, "yd²")),
+        "ACRE" => Some((4046.8564224, "ac")),
+        "CUBIC_INCH" => Some((0.000016387064, "in³")),
+        "CUBIC_FOOT" => Some((0.028316846592, "ft³")),
+        "CUBIC_YARD" => Some((0.764554857984, "yd³")),
+        "GALLON_US" => Some((0.003785411784, "gal")),
+        "POUND" => Some((0.45359237, "lb")),
+        "TON_US" => Some((907.18474, "ton")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(unit_type: &str, prefix: Option<&str>, name: &str) -> UnitData {
+        UnitData {
+            unit_type: unit_type.to_string(),
+            prefix: prefix.map(str::to_string),
+            name: name.to_string(),
+            conversion_factor: None,
+        }
+    }
+
+    #[test]
+    fn test_millimetre_project() {
+        let context = UnitContext::from_assignment(&[
+            unit(".LENGTHUNIT.", Some(".MILLI."), ".METRE."),
+            unit(".AREAUNIT.", Some(".MILLI."), ".SQUARE_METRE."),
+            unit(".VOLUMEUNIT.", None, ".CUBIC_METRE."),
+            unit(".MASSUNIT.", Some(".KILO."), ".GRAM."),
+            unit(".PLANEANGLEUNIT.", None, ".RADIAN."),
+        ])
+        .unwrap();
+
+        assert_eq!(context.length.symbol, "mm");
+        assert_eq!(context.area.symbol, "mm²");
+        assert!((context.to_si(UnitKind::Area, 2_000_000.0) - 2.0).abs() < 1e-9);
+        assert!(context.volume.is_si());
+        assert!((context.mass.to_si - 1.0).abs() < 1e-12);
+
+        let (si, original) = context.normalize_quantity("NetSideArea", 15_600_000.0);
+        assert!((si - 15.6).abs() < 1e-9);
+        assert_eq!(original.map(|q| q.unit).as_deref(), Some("mm²"));
+    }
+
+    #[test]
+    fn test_imperial_project() {
+        let mut feet = unit("LENGTHUNIT", None, "FOOT");
+        feet.conversion_factor = Some(0.3048);
+        let context = UnitContext::from_assignment(&[
+            feet,
+            unit("VOLUMEUNIT", None, "CUBIC FOOT"),
+            unit("MASSUNIT", None, "POUND"),
+        ])
+        .unwrap();
+
+        assert_eq!(context.volume.symbol, "ft³");
+        assert!((context.to_si(UnitKind::Length, 10.0) - 3.048).abs() < 1e-9);
+        assert!((context.to_si(UnitKind::Mass, 1.0) - 0.45359237).abs() < 1e-12);
+
+        // Contagens não têm unidade
+        assert_eq!(context.normalize_quantity("Count", 4.0), (4.0, None));
+    }
+
+    #[test]
+    fn test_invalid_units() {
+        assert!(UnitContext::from_assignment(&[unit("LENGTHUNIT", None, "FURLONG")]).is_err());
+        assert!(UnitContext::from_assignment(&[unit("AREAUNIT", None, "METRE")]).is_err());
+        assert!(UnitContext::from_assignment(&[unit("LENGTHUNIT", Some("HUGE"), "METRE")]).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Bindings WASM para as ferramentas do visualizador
+//!
+//! [`SpatialQuery`] é criado a partir do JSON de metadados já carregado
+//! pelo visualizador. Consultas com distância devolvem JSON
+//! (`[{ "guid": ..., "distance": ... }]`); a busca por caixa devolve um
+//! array de GUIDs.
+
+use crate::{BimMetadata, SpatialHit, SpatialIndex};
+use wasm_bindgen::prelude::*;
+
+fn to_json(hits: &[SpatialHit]) -> String {
+    serde_json::to_string(hits).expect("hits serialize")
+}
+
+#[wasm_bindgen]
+pub struct SpatialQuery {
+    index: SpatialIndex,
+}
+
+#[wasm_bindgen]
+impl SpatialQuery {
+    /// Índice sobre a saída de `MetadataExtractor::export_json`
+    #[wasm_bindgen(constructor)]
+    pub fn new(metadata_json: &str) -> Result<SpatialQuery, JsError> {
+        let metadata: BimMetadata = serde_json::from_str(metadata_json)?;
+        Ok(Self { index: SpatialIndex::new(&metadata) })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.index.len()
+    }
+
+    #[wasm_bindgen(js_name = withinRadius)]
+    pub fn within_radius(&self, x: f32, y: f32, z: f32, radius: f32) -> String {
+        to_json(&self.index.within_radius([x, y, z], radius))
+    }
+
+    /// `undefined` se o GUID não tem caixa
+    #[wasm_bindgen(js_name = nearElement)]
+    pub fn near_element(&self, guid: &str, radius: f32) -> Option<String> {
+        self.index.near_element(guid, radius).map(|hits| to_json(&hits))
+    }
+
+    #[wasm_bindgen(js_name = inBox)]
+    pub fn in_box(&self, min_x: f32, min_y: f32, min_z: f32, max_x: f32, max_y: f32, max_z: f32) -> Vec<String> {
+        self.index.in_box([min_x, min_y, min_z], [max_x, max_y, max_z])
+    }
+
+    pub fn raycast(&self, ox: f32, oy: f32, oz: f32, dx: f32, dy: f32, dz: f32) -> String {
+        to_json(&self.index.raycast([ox, oy, oz], [dx, dy, dz]))
+    }
+
+    pub fn nearest(&self, x: f32, y: f32, z: f32, k: usize) -> String {
+        to_json(&self.index.nearest([x, y, z], k))
+    }
+}
@@ -0,0 +1,43 @@
+use avila_modular::{Barrett, ModContext, Montgomery};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// NIST P-256
+const P256: [u64; 4] = [0xffff_ffff_ffff_ffff, 0x0000_0000_ffff_ffff, 0, 0xffff_ffff_0000_0001];
+
+const A: [u64; 4] = [0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 0x1111_2222_3333_4444, 0x0fff_0000_ffff_0000];
+const B: [u64; 4] = [0xdead_beef_cafe_babe, 0x0f0f_0f0f_f0f0_f0f0, 0x5555_aaaa_5555_aaaa, 0x7777_8888_9999_aaaa];
+
+fn bench_mul(c: &mut Criterion) {
+    let ctx = ModContext::new(P256);
+    let mont = Montgomery::new(P256);
+    let barrett = Barrett::new(P256);
+    let (a_mont, b_mont) = (mont.to_montgomery(A), mont.to_montgomery(B));
+
+    c.bench_function("P-256 mul (bitwise division)", |bencher| {
+        bencher.iter(|| ctx.mul(black_box(A), black_box(B)));
+    });
+    c.bench_function("P-256 mul (Montgomery)", |bencher| {
+        bencher.iter(|| mont.mul(black_box(a_mont), black_box(b_mont)));
+    });
+    c.bench_function("P-256 mul (Barrett)", |bencher| {
+        bencher.iter(|| barrett.mul(black_box(A), black_box(B)));
+    });
+}
+
+fn bench_pow(c: &mut Criterion) {
+    let ctx = ModContext::new(P256);
+    let mont = Montgomery::new(P256);
+
+    c.bench_function("P-256 pow 65537 (bitwise division)", |bencher| {
+        bencher.iter(|| ctx.pow(black_box(A), black_box(65537)));
+    });
+    c.bench_function("P-256 pow 65537 (Montgomery)", |bencher| {
+        bencher.iter(|| mont.pow(black_box(A), black_box(65537)));
+    });
+    c.bench_function("P-256 pow 256-bit constant time", |bencher| {
+        bencher.iter(|| ctx.pow_ct(black_box(A), black_box(B)));
+    });
+}
+
+criterion_group!(benches, bench_mul, bench_pow);
+criterion_main!(benches);
@@ -0,0 +1,619 @@
+//! Inteiros sem sinal de tamanho arbitrário
+//!
+//! [`BigUint`] guarda as palavras de 64 bits no heap, da menos para a mais
+//! significativa, sem zeros à esquerda. Serve para valores que não cabem nos
+//! arrays fixos de [`ModContext`](crate::ModContext) e para converter de e
+//! para hexadecimal, decimal e bytes.
+//!
+//! As operações não são de tempo constante: para segredos, converta com
+//! [`BigUint::to_limbs`] e use as variantes `_ct` do contexto modular.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+use core::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
+use core::str::FromStr;
+
+/// Maior potência de 10 que cabe numa palavra (10^19)
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+
+/// Inteiro sem sinal de tamanho arbitrário
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Sem palavras zero no fim; zero é o vetor vazio
+    limbs: Vec<u64>,
+}
+
+/// Erro ao converter texto em [`BigUint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    /// Texto vazio (ou só o prefixo `0x`)
+    Empty,
+    /// Caractere que não é dígito na base
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("cannot parse integer from empty string"),
+            Self::InvalidDigit(c) => write!(f, "invalid digit found in string: {:?}", c),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseBigUintError {}
+
+impl BigUint {
+    /// Zero
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Um
+    pub fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+
+    /// A partir de palavras da menos para a mais significativa
+    pub fn from_limbs(limbs: &[u64]) -> Self {
+        Self { limbs: limbs.to_vec() }.normalized()
+    }
+
+    /// Palavras da menos para a mais significativa, sem zeros no fim
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    /// Valor em `N` palavras, para [`ModContext`](crate::ModContext); `None` se não couber
+    pub fn to_limbs<const N: usize>(&self) -> Option<[u64; N]> {
+        if self.limbs.len() > N {
+            return None;
+        }
+        let mut result = [0u64; N];
+        result[..self.limbs.len()].copy_from_slice(&self.limbs);
+        Some(result)
+    }
+
+    /// Verifica se o valor é zero
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Número de bits significativos (0 para zero)
+    pub fn bits(&self) -> usize {
+        self.limbs
+            .last()
+            .map_or(0, |&top| self.limbs.len() * 64 - top.leading_zeros() as usize)
+    }
+
+    /// Bit `i`, contando do menos significativo
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs.get(i / 64).is_some_and(|&word| (word >> (i % 64)) & 1 == 1)
+    }
+
+    /// `self - other`, ou `None` se `other > self`
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        sub_in_place(&mut limbs, &other.limbs);
+        Some(Self { limbs }.normalized())
+    }
+
+    /// Quociente e resto de `self / divisor`, ou `None` se o divisor for zero
+    pub fn checked_div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        match divisor.limbs.as_slice() {
+            [] => None,
+            &[d] => {
+                let (quotient, rem) = self.div_rem_small(d);
+                Some((quotient, Self::from(rem)))
+            }
+            _ if self < divisor => Some((Self::zero(), self.clone())),
+            _ => Some(self.div_rem_long(divisor)),
+        }
+    }
+
+    /// Quociente e resto de `self / divisor`
+    ///
+    /// # Panics
+    ///
+    /// Se o divisor for zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        self.checked_div_rem(divisor).expect("attempt to divide by zero")
+    }
+
+    /// Bytes big-endian
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| chunk.iter().fold(0u64, |word, &byte| word << 8 | byte as u64))
+            .collect();
+        Self { limbs }.normalized()
+    }
+
+    /// Bytes little-endian
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .chunks(8)
+            .map(|chunk| chunk.iter().rev().fold(0u64, |word, &byte| word << 8 | byte as u64))
+            .collect();
+        Self { limbs }.normalized()
+    }
+
+    /// Bytes big-endian, sem zeros à esquerda (`[0]` para zero)
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Bytes little-endian, sem zeros no fim (`[0]` para zero)
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.limbs.iter().flat_map(|word| word.to_le_bytes()).collect();
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    /// Hexadecimal, com ou sem prefixo `0x`, maiúsculas ou minúsculas
+    pub fn from_hex(text: &str) -> Result<Self, ParseBigUintError> {
+        let digits = text
+            .strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"))
+            .unwrap_or(text);
+        if digits.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        let mut limbs = vec![0u64; digits.len().div_ceil(16)];
+        for (i, c) in digits.chars().rev().enumerate() {
+            let digit = c.to_digit(16).ok_or(ParseBigUintError::InvalidDigit(c))? as u64;
+            limbs[i / 16] |= digit << (4 * (i % 16));
+        }
+        Ok(Self { limbs }.normalized())
+    }
+
+    /// Decimal, só dígitos
+    pub fn from_decimal(text: &str) -> Result<Self, ParseBigUintError> {
+        if text.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(ParseBigUintError::InvalidDigit(c));
+        }
+        // Blocos de 19 dígitos: o primeiro fica com o resto
+        let first = match text.len() % DECIMAL_CHUNK_DIGITS {
+            0 => DECIMAL_CHUNK_DIGITS,
+            len => len,
+        };
+        let mut result = Self::zero();
+        let mut start = 0;
+        let mut end = first;
+        while start < text.len() {
+            let chunk: u64 = text[start..end].parse().expect("ascii digits");
+            let scale = 10u64.pow((end - start) as u32);
+            result.mul_add_small(scale, chunk);
+            start = end;
+            end += DECIMAL_CHUNK_DIGITS;
+        }
+        Ok(result)
+    }
+
+    /// Hexadecimal minúsculo sem prefixo (`"0"` para zero)
+    pub fn to_hex(&self) -> String {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return String::from("0");
+        };
+        let mut text = String::new();
+        let _ = write!(text, "{:x}", top);
+        for word in rest.iter().rev() {
+            let _ = write!(text, "{:016x}", word);
+        }
+        text
+    }
+
+    /// Decimal
+    pub fn to_decimal(&self) -> String {
+        let mut chunks = Vec::new();
+        let mut value = self.clone();
+        while !value.is_zero() {
+            let (quotient, chunk) = value.div_rem_small(DECIMAL_CHUNK);
+            chunks.push(chunk);
+            value = quotient;
+        }
+        let Some((top, rest)) = chunks.split_last() else {
+            return String::from("0");
+        };
+        let mut text = String::new();
+        let _ = write!(text, "{}", top);
+        for chunk in rest.iter().rev() {
+            let _ = write!(text, "{:019}", chunk);
+        }
+        text
+    }
+
+    fn normalized(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// `self = self * m + a`
+    fn mul_add_small(&mut self, m: u64, a: u64) {
+        let mut carry = a as u128;
+        for word in &mut self.limbs {
+            let product = *word as u128 * m as u128 + carry;
+            *word = product as u64;
+            carry = product >> 64;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u64);
+        }
+    }
+
+    /// Divisão por uma palavra (d != 0)
+    fn div_rem_small(&self, d: u64) -> (Self, u64) {
+        let mut quotient = vec![0u64; self.limbs.len()];
+        let mut rem = 0u128;
+        for (i, &word) in self.limbs.iter().enumerate().rev() {
+            let current = rem << 64 | word as u128;
+            quotient[i] = (current / d as u128) as u64;
+            rem = current % d as u128;
+        }
+        (Self { limbs: quotient }.normalized(), rem as u64)
+    }
+
+    /// Divisão longa bit a bit (divisor com mais de uma palavra)
+    fn div_rem_long(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = vec![0u64; self.limbs.len()];
+        let mut rem: Vec<u64> = Vec::with_capacity(divisor.limbs.len() + 1);
+        for i in (0..self.bits()).rev() {
+            // rem = 2 * rem + bit
+            let mut carry = self.bit(i) as u64;
+            for word in &mut rem {
+                let next = *word >> 63;
+                *word = *word << 1 | carry;
+                carry = next;
+            }
+            if carry != 0 {
+                rem.push(carry);
+            }
+            if cmp_limbs(&rem, &divisor.limbs) != Ordering::Less {
+                sub_in_place(&mut rem, &divisor.limbs);
+                while rem.last() == Some(&0) {
+                    rem.pop();
+                }
+                quotient[i / 64] |= 1 << (i % 64);
+            }
+        }
+        (Self { limbs: quotient }.normalized(), Self { limbs: rem })
+    }
+}
+
+/// Compara palavras normalizadas (sem zeros no fim)
+fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// `a -= b`, com `a >= b`
+fn sub_in_place(a: &mut [u64], b: &[u64]) {
+    let borrow = crate::sub_slice(a, b);
+    debug_assert_eq!(borrow, 0, "subtraction underflow");
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self { limbs: vec![value] }.normalized()
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        Self::from_limbs(&[value as u64, (value >> 64) as u64])
+    }
+}
+
+impl<const N: usize> From<[u64; N]> for BigUint {
+    fn from(limbs: [u64; N]) -> Self {
+        Self::from_limbs(&limbs)
+    }
+}
+
+/// Decimal, ou hexadecimal com prefixo `0x`
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.starts_with("0x") || text.starts_with("0X") {
+            Self::from_hex(text)
+        } else {
+            Self::from_decimal(text)
+        }
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal())
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_hex())
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_hex().to_uppercase())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BigUint({:#x})", self)
+    }
+}
+
+// ============================================================================
+// OPERADORES
+// ============================================================================
+
+fn add(a: &BigUint, b: &BigUint) -> BigUint {
+    let (long, short) = if a.limbs.len() >= b.limbs.len() { (a, b) } else { (b, a) };
+    let mut limbs = long.limbs.clone();
+    let mut carry = 0u64;
+    for (i, word) in limbs.iter_mut().enumerate() {
+        let sum = *word as u128 + short.limbs.get(i).copied().unwrap_or(0) as u128 + carry as u128;
+        *word = sum as u64;
+        carry = (sum >> 64) as u64;
+    }
+    if carry != 0 {
+        limbs.push(carry);
+    }
+    BigUint { limbs }
+}
+
+fn sub(a: &BigUint, b: &BigUint) -> BigUint {
+    a.checked_sub(b).expect("attempt to subtract with overflow")
+}
+
+fn mul(a: &BigUint, b: &BigUint) -> BigUint {
+    if a.is_zero() || b.is_zero() {
+        return BigUint::zero();
+    }
+    let mut limbs = vec![0u64; a.limbs.len() + b.limbs.len()];
+    crate::mul_slice(&a.limbs, &b.limbs, &mut limbs);
+    BigUint { limbs }.normalized()
+}
+
+fn div(a: &BigUint, b: &BigUint) -> BigUint {
+    a.div_rem(b).0
+}
+
+fn rem(a: &BigUint, b: &BigUint) -> BigUint {
+    a.div_rem(b).1
+}
+
+fn bitwise(a: &BigUint, b: &BigUint, op: impl Fn(u64, u64) -> u64) -> BigUint {
+    let len = a.limbs.len().max(b.limbs.len());
+    let word = |value: &BigUint, i: usize| value.limbs.get(i).copied().unwrap_or(0);
+    let limbs = (0..len).map(|i| op(word(a, i), word(b, i))).collect();
+    BigUint { limbs }.normalized()
+}
+
+fn bitand(a: &BigUint, b: &BigUint) -> BigUint {
+    bitwise(a, b, |x, y| x & y)
+}
+
+fn bitor(a: &BigUint, b: &BigUint) -> BigUint {
+    bitwise(a, b, |x, y| x | y)
+}
+
+fn bitxor(a: &BigUint, b: &BigUint) -> BigUint {
+    bitwise(a, b, |x, y| x ^ y)
+}
+
+/// Implementa o operador para as quatro combinações de valor e referência
+macro_rules! forward_binop {
+    ($($trait:ident, $method:ident;)*) => {$(
+        impl $trait<&BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: &BigUint) -> BigUint {
+                $method(self, rhs)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                $method(self, &rhs)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: &BigUint) -> BigUint {
+                $method(&self, rhs)
+            }
+        }
+
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                $method(&self, &rhs)
+            }
+        }
+    )*};
+}
+
+forward_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+    BitAnd, bitand;
+    BitOr, bitor;
+    BitXor, bitxor;
+}
+
+fn shl(value: &BigUint, n: usize) -> BigUint {
+    if value.is_zero() {
+        return BigUint::zero();
+    }
+    let (words, bits) = (n / 64, n % 64);
+    let mut limbs = vec![0u64; words];
+    let mut carry = 0u64;
+    for &word in &value.limbs {
+        limbs.push(word << bits | carry);
+        carry = if bits == 0 { 0 } else { word >> (64 - bits) };
+    }
+    limbs.push(carry);
+    BigUint { limbs }.normalized()
+}
+
+fn shr(value: &BigUint, n: usize) -> BigUint {
+    let mut limbs = vec![0u64; value.limbs.len().saturating_sub(n / 64)];
+    crate::shr_slice(&value.limbs, n, &mut limbs);
+    BigUint { limbs }.normalized()
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, n: usize) -> BigUint {
+        shl(self, n)
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, n: usize) -> BigUint {
+        shl(&self, n)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, n: usize) -> BigUint {
+        shr(self, n)
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, n: usize) -> BigUint {
+        shr(&self, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn big(text: &str) -> BigUint {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let value = big("340282366920938463463374607431768211457");
+        assert_eq!(value, BigUint::from(u128::MAX) + BigUint::from(2u64));
+        assert_eq!(value.to_string(), "340282366920938463463374607431768211457");
+        assert_eq!(value.to_hex(), "100000000000000000000000000000001");
+        assert_eq!(format!("{:#x}", value), "0x100000000000000000000000000000001");
+        assert_eq!(BigUint::from_hex("0x00FF").unwrap(), BigUint::from(255u64));
+        assert_eq!(big("0x100000000000000000000000000000001"), value);
+        assert_eq!(format!("{:>5}", BigUint::from(42u64)), "   42");
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(big("0000"), BigUint::zero());
+        assert_eq!(big("10000000000000000000").limbs(), [DECIMAL_CHUNK]);
+
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!(BigUint::from_hex("0x"), Err(ParseBigUintError::Empty));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError::InvalidDigit('a')));
+        assert_eq!(BigUint::from_hex("fg"), Err(ParseBigUintError::InvalidDigit('g')));
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let value = BigUint::from_bytes_be(&[0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(value.limbs(), [0x0203_0405_0607_0809, 0x01]);
+        assert_eq!(value.to_bytes_be(), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(value.to_bytes_le(), [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(BigUint::from_bytes_le(&value.to_bytes_le()), value);
+        assert_eq!(BigUint::from_bytes_be(&[]).to_bytes_be(), [0]);
+        assert_eq!(value.to_limbs::<4>(), Some([0x0203_0405_0607_0809, 1, 0, 0]));
+        assert_eq!(value.to_limbs::<1>(), None);
+        assert_eq!(BigUint::from([5u64, 0, 0]), BigUint::from(5u64));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("123456789012345678901234567890123456789012345678901234567890");
+        let b = big("987654321098765432109876543210");
+        let product = &a * &b;
+        assert_eq!(product.to_string(), "121932631137021795226185032733744855963374485596337448559633622923332237463801111263526900");
+        let (q, r) = product.div_rem(&b);
+        assert_eq!((q, r), (a.clone(), BigUint::zero()));
+
+        let c = &product + BigUint::from(12345u64);
+        assert_eq!(&c / &a, b);
+        assert_eq!(&c % &a, BigUint::from(12345u64));
+        assert_eq!(&c % BigUint::from(10u64), BigUint::from(5u64));
+        assert_eq!(c.clone() - product.clone(), BigUint::from(12345u64));
+        assert_eq!(product.checked_sub(&c), None);
+        assert_eq!(a.checked_div_rem(&BigUint::zero()), None);
+        assert_eq!(b.div_rem(&a), (BigUint::zero(), b.clone()));
+        assert!(a > b && BigUint::zero() < BigUint::one());
+    }
+
+    #[test]
+    fn test_bit_operations() {
+        let a = big("0xff00ff00ff00ff00ff00ff00ff00ff00ff");
+        let b = big("0x0ff0");
+        assert_eq!(&a & &b, big("0xf0"));
+        assert_eq!(&a | &b, big("0xff00ff00ff00ff00ff00ff00ff00ff0fff"));
+        assert_eq!(&a ^ &a, BigUint::zero());
+        assert_eq!(a.bits(), 136);
+        assert!(a.bit(135) && !a.bit(136) && !a.bit(1000));
+
+        assert_eq!(&b << 130, big("0x3fc000000000000000000000000000000000"));
+        assert_eq!((&b << 130) >> 130, b);
+        assert_eq!(&a >> 132, big("0xf"));
+        assert_eq!(&a >> 200, BigUint::zero());
+        assert_eq!(&a << 64, &a * (BigUint::one() << 64));
+    }
+}
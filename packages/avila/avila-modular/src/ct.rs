@@ -0,0 +1,244 @@
+//! Garantias de tempo constante
+//!
+//! "Tempo constante" aqui quer dizer: nenhum desvio e nenhum acesso à memória
+//! depende de valores secretos. O número de operações depende só do número de
+//! palavras e do tamanho em bits do módulo, que são públicos.
+//!
+//! Em tempo constante:
+//!
+//! - [`ModContext`](crate::ModContext): `add` e `sub` (entradas reduzidas),
+//!   `mul`, `reduce`, `reduce_wide`, `pow_ct`, `inv_ct` e `sqrt` com
+//!   m ≡ 3 (mod 4)
+//! - [`Montgomery`](crate::Montgomery): `to_montgomery`, `from_montgomery`,
+//!   `mul` e `pow_ct`
+//! - [`Barrett`](crate::Barrett): `reduce_ct` (x < 2^(2k)) e `mul_ct`
+//!   (entradas reduzidas)
+//! - as máscaras deste módulo: [`select`], [`swap`], [`is_zero`], [`eq`], [`lt`]
+//!
+//! Em tempo variável:
+//!
+//! - `pow`, `pow_u256`, `pow_limbs` e `Montgomery::pow`: dependem dos bits do
+//!   expoente
+//! - `inv`, `gcd`, `sqrt` nos demais primos e `Barrett::reduce`/`mul`:
+//!   dependem dos valores
+//! - `is_zero`, `is_one` e `cmp` do contexto: param na primeira palavra que
+//!   decide
+//! - [`BigUint`](crate::BigUint) e [`prime`](crate::prime): tudo
+//!
+//! As máscaras valem `0` ou `!0`. Nada impede o compilador de transformá-las
+//! em desvios; os testes de tempo medem isso no alvo em uso e ficam atrás da
+//! feature `ct-timing`, por serem lentos e sensíveis a ruído:
+//!
+//! ```text
+//! cargo test --release --features ct-timing ct::timing
+//! ```
+
+/// `a` com máscara zero, `b` com máscara `!0`, sem desvio
+pub fn select<const N: usize>(mask: u64, a: [u64; N], b: [u64; N]) -> [u64; N] {
+    let mut result = [0u64; N];
+    for i in 0..N {
+        result[i] = a[i] ^ (mask & (a[i] ^ b[i]));
+    }
+    result
+}
+
+/// Troca `a` e `b` se a máscara for `!0`
+pub fn swap<const N: usize>(mask: u64, a: &mut [u64; N], b: &mut [u64; N]) {
+    for i in 0..N {
+        let t = mask & (a[i] ^ b[i]);
+        a[i] ^= t;
+        b[i] ^= t;
+    }
+}
+
+/// `!0` se todas as palavras forem zero, senão `0`
+pub fn is_zero(value: &[u64]) -> u64 {
+    let acc = value.iter().fold(0, |acc, &word| acc | word);
+    // Bit alto de acc | -acc é 1 exatamente quando acc != 0
+    ((acc | acc.wrapping_neg()) >> 63).wrapping_sub(1)
+}
+
+/// `!0` se `a == b`, senão `0`
+///
+/// # Panics
+///
+/// Se os tamanhos forem diferentes (o tamanho é público).
+pub fn eq(a: &[u64], b: &[u64]) -> u64 {
+    assert_eq!(a.len(), b.len(), "ct::eq on slices of different lengths");
+    let acc = a.iter().zip(b).fold(0, |acc, (&a, &b)| acc | (a ^ b));
+    is_zero(&[acc])
+}
+
+/// `!0` se `a < b`, senão `0`
+///
+/// # Panics
+///
+/// Se os tamanhos forem diferentes (o tamanho é público).
+pub fn lt(a: &[u64], b: &[u64]) -> u64 {
+    assert_eq!(a.len(), b.len(), "ct::lt on slices of different lengths");
+    // Empréstimo final de a - b
+    let mut borrow = 0u64;
+    for (&a, &b) in a.iter().zip(b) {
+        let diff = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+        borrow = (diff >> 127) as u64;
+    }
+    borrow.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masks() {
+        assert_eq!(is_zero(&[0, 0, 0]), !0);
+        assert_eq!(is_zero(&[]), !0);
+        assert_eq!(is_zero(&[0, 1 << 63, 0]), 0);
+        assert_eq!(is_zero(&[0, 0, 1]), 0);
+
+        assert_eq!(eq(&[1, 2], &[1, 2]), !0);
+        assert_eq!(eq(&[1, 2], &[1, 3]), 0);
+        assert_eq!(eq(&[u64::MAX, 0], &[0, u64::MAX]), 0);
+
+        assert_eq!(lt(&[5, 1], &[0, 2]), !0);
+        assert_eq!(lt(&[0, 2], &[5, 1]), 0);
+        assert_eq!(lt(&[7, 7], &[7, 7]), 0);
+        assert_eq!(lt(&[u64::MAX - 1, u64::MAX], &[u64::MAX, u64::MAX]), !0);
+
+        let (mut a, mut b) = ([1, 2], [3, 4]);
+        assert_eq!(select(0, a, b), a);
+        assert_eq!(select(!0, a, b), b);
+        swap(0, &mut a, &mut b);
+        assert_eq!((a, b), ([1, 2], [3, 4]));
+        swap(!0, &mut a, &mut b);
+        assert_eq!((a, b), ([3, 4], [1, 2]));
+    }
+}
+
+/// Teste t de Welch entre entradas fixas e aleatórias, no estilo do dudect
+///
+/// Cada amostra mede um lote da operação sobre uma entrada da classe sorteada,
+/// com o lote ajustado para durar alguns microssegundos; |t| acima do limite
+/// indica que o tempo depende dos dados.
+#[cfg(all(test, feature = "ct-timing"))]
+mod timing {
+    use super::*;
+    use crate::{Barrett, ModContext, Montgomery};
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    const SAMPLES: usize = 4_000;
+    /// Duração mínima de uma amostra, bem acima da resolução do relógio
+    const SAMPLE_NANOS: u128 = 2_000;
+    /// O dudect considera vazamento a partir de 4.5; folga para máquinas ruidosas
+    const THRESHOLD: f64 = 10.0;
+
+    /// NIST P-256
+    const P256: [u64; 4] = [0xffff_ffff_ffff_ffff, 0x0000_0000_ffff_ffff, 0, 0xffff_ffff_0000_0001];
+
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state >> 12;
+        *state ^= *state << 25;
+        *state ^= *state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn random<const N: usize>(state: &mut u64) -> [u64; N] {
+        core::array::from_fn(|_| xorshift(state))
+    }
+
+    /// |t| entre a classe fixa e a aleatória; `random` gera entradas da segunda
+    fn welch_t<T: Copy, R>(fixed: T, mut random: impl FnMut(&mut u64) -> T, op: impl Fn(T) -> R) -> f64 {
+        let start = Instant::now();
+        black_box(op(black_box(fixed)));
+        let batch = (SAMPLE_NANOS / start.elapsed().as_nanos().max(1)).max(1);
+
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+        let inputs: Vec<(usize, T)> = (0..SAMPLES)
+            .map(|_| match xorshift(&mut state) & 1 {
+                0 => (0, fixed),
+                _ => (1, random(&mut state)),
+            })
+            .collect();
+
+        let mut times: Vec<(usize, f64)> = inputs
+            .into_iter()
+            .map(|(class, input)| {
+                let start = Instant::now();
+                for _ in 0..batch {
+                    black_box(op(black_box(input)));
+                }
+                (class, start.elapsed().as_nanos() as f64)
+            })
+            .collect();
+
+        // Descarta os 10% mais lentos (interrupções, trocas de contexto)
+        let mut sorted: Vec<f64> = times.iter().map(|&(_, t)| t).collect();
+        sorted.sort_by(f64::total_cmp);
+        let cutoff = sorted[sorted.len() * 9 / 10];
+        times.retain(|&(_, t)| t <= cutoff);
+
+        let stats = |class: usize| {
+            let values: Vec<f64> = times.iter().filter(|&&(c, _)| c == class).map(|&(_, t)| t).collect();
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (mean, variance, n)
+        };
+        let ((m0, v0, n0), (m1, v1, n1)) = (stats(0), stats(1));
+        ((m0 - m1) / (v0 / n0 + v1 / n1).sqrt()).abs()
+    }
+
+    fn assert_constant(name: &str, t: f64) {
+        assert!(t < THRESHOLD, "{} looks variable-time: |t| = {:.1}", name, t);
+    }
+
+    #[test]
+    fn test_harness_detects_leak() {
+        // Expoente 1 contra expoentes de 256 bits: diferença enorme
+        let ctx = ModContext::new(P256);
+        let t = welch_t([1, 0, 0, 0], random::<4>, |exp| ctx.pow_limbs([3, 0, 0, 0], &exp));
+        assert!(t > THRESHOLD, "timing harness missed a variable-time pow: |t| = {:.1}", t);
+    }
+
+    #[test]
+    fn test_mod_context_ops() {
+        let ctx = ModContext::new(P256);
+        let pair = |state: &mut u64| (ctx.reduce(random(state)), ctx.reduce(random(state)));
+        let zeros = ([0u64; 4], [0u64; 4]);
+
+        assert_constant("add", welch_t(zeros, pair, |(a, b)| ctx.add(a, b)));
+        assert_constant("sub", welch_t(zeros, pair, |(a, b)| ctx.sub(a, b)));
+        assert_constant("mul", welch_t(zeros, pair, |(a, b)| ctx.mul(a, b)));
+        assert_constant("reduce", welch_t([0u64; 4], random::<4>, |x| ctx.reduce(x)));
+        assert_constant("reduce_wide", welch_t([0u64; 8], random::<8>, |x| ctx.reduce_wide(x)));
+    }
+
+    #[test]
+    fn test_pow_ct() {
+        let ctx = ModContext::new(P256);
+        let mont = Montgomery::new(P256);
+        assert_constant("ModContext::pow_ct", welch_t([1, 0, 0, 0], random::<4>, |exp| ctx.pow_ct([3, 0, 0, 0], exp)));
+        assert_constant("Montgomery::pow_ct", welch_t([1, 0, 0, 0], random::<4>, |exp| mont.pow_ct([3, 0, 0, 0], exp)));
+    }
+
+    #[test]
+    fn test_montgomery_and_barrett() {
+        let ctx = ModContext::new(P256);
+        let mont = Montgomery::new(P256);
+        let barrett = Barrett::new(P256);
+        let pair = |state: &mut u64| (ctx.reduce(random(state)), ctx.reduce(random(state)));
+        let zeros = ([0u64; 4], [0u64; 4]);
+
+        assert_constant("Montgomery::mul", welch_t(zeros, pair, |(a, b)| mont.mul(a, b)));
+        assert_constant("Barrett::mul_ct", welch_t(zeros, pair, |(a, b)| barrett.mul_ct(a, b)));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let other = [0x1234, 0x5678, 0x9abc, 0xdef0];
+        assert_constant("eq", welch_t(other, random::<4>, |a| eq(&a, &other)));
+        assert_constant("lt", welch_t(other, random::<4>, |a| lt(&a, &other)));
+        assert_constant("is_zero", welch_t([0u64; 4], random::<4>, |a| is_zero(&a)));
+    }
+}
@@ -1,108 +1,256 @@
 //! # avila-modular - Modular Arithmetic
 //!
 //! Aritmética modular de alta performance para inteiros grandes.
+//!
+//! [`ModContext`] trabalha com arrays fixos de palavras (256 bits por
+//! padrão, qualquer múltiplo de 64 bits com `ModContext::<N>`); [`BigUint`]
+//! é o inteiro de tamanho arbitrário no heap, para parsing, conversões e
+//! aritmética sem módulo. O módulo [`prime`] testa primalidade e gera primos
+//! (inclusive primos seguros para Diffie–Hellman). O módulo [`ct`] lista o
+//! que roda em tempo constante e traz comparações por máscara.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
+mod biguint;
+pub mod ct;
+pub mod prime;
+
+pub use biguint::{BigUint, ParseBigUintError};
+
+use ct::{select, swap};
+
 /// Contexto modular
-pub struct ModContext {
+///
+/// Os valores são inteiros de `64 * N` bits em `N` palavras de 64 bits, da
+/// menos para a mais significativa; o padrão é `N = 4` (256 bits), e
+/// `ModContext::<6>` ou `ModContext::<64>` atendem módulos de 384 ou 4096
+/// bits. Para montar valores que não cabem num literal, use
+/// [`BigUint::to_limbs`]. O módulo não pode ser zero. `add` e `sub` esperam
+/// entradas já reduzidas; `mul` e `reduce` aceitam qualquer valor.
+///
+/// `add`, `sub`, `mul`, `reduce` e as variantes `_ct` executam a mesma
+/// sequência de operações para qualquer valor secreto (expoente, base), para
+/// uso com chaves privadas; ver [`ct`] para o que não tem essa garantia.
+pub struct ModContext<const N: usize = 4> {
     /// Modulus value
-    pub modulus: [u64; 4],
+    pub modulus: [u64; N],
 }
 
-impl ModContext {
+impl<const N: usize> ModContext<N> {
     /// Cria novo contexto modular
-    pub const fn new(modulus: [u64; 4]) -> Self {
+    pub const fn new(modulus: [u64; N]) -> Self {
         Self { modulus }
     }
 
     /// Reduces value modulo m
-    pub fn reduce(&self, mut value: [u64; 4]) -> [u64; 4] {
-        // Redução iterativa: subtrair modulus enquanto value >= modulus
-        while self.cmp(value, self.modulus) != core::cmp::Ordering::Less {
-            let mut borrow = 0u64;
-            for i in 0..4 {
-                let (diff, b1) = value[i].overflowing_sub(self.modulus[i]);
-                let (diff, b2) = diff.overflowing_sub(borrow);
-                value[i] = diff;
-                borrow = (b1 as u64) + (b2 as u64);
-            }
+    ///
+    /// Divisão bit a bit com subtração por máscara: tempo constante.
+    pub fn reduce(&self, value: [u64; N]) -> [u64; N] {
+        self.reduce_slice(&value)
+    }
+
+    /// Reduz um valor de `W` palavras, como o produto de dois valores (`2 * N`)
+    ///
+    /// Divisão bit a bit com subtração por máscara: tempo constante.
+    pub fn reduce_wide<const W: usize>(&self, value: [u64; W]) -> [u64; N] {
+        self.reduce_slice(&value)
+    }
+
+    fn reduce_slice(&self, value: &[u64]) -> [u64; N] {
+        let mut rem = [0u64; N];
+        for i in (0..value.len() * 64).rev() {
+            rem = div_step(rem, (value[i / 64] >> (i % 64)) & 1, self.modulus).0;
         }
-        value
+        rem
     }
 
     /// Modular addition
-    pub fn add(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
-        let mut result = [0u64; 4];
-        let mut carry = 0u64;
-        for i in 0..4 {
-            let (sum, c1) = a[i].overflowing_add(b[i]);
-            let (sum, c2) = sum.overflowing_add(carry);
-            result[i] = sum;
-            carry = (c1 as u64) + (c2 as u64);
-        }
-        self.reduce(result)
+    ///
+    /// Entradas reduzidas; uma subtração condicional por máscara.
+    pub fn add(&self, a: [u64; N], b: [u64; N]) -> [u64; N] {
+        let (sum, carry) = add_limbs(a, b);
+        let (diff, borrow) = sub_limbs(sum, self.modulus);
+        // Subtrai m se a soma passou de 2^(64N) ou é >= m
+        select((carry | (borrow ^ 1)).wrapping_neg(), sum, diff)
     }
 
     /// Modular subtraction
-    pub fn sub(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
-        let mut result = [0u64; 4];
-        let mut borrow = 0u64;
-        for i in 0..4 {
-            let (diff, b1) = a[i].overflowing_sub(b[i]);
-            let (diff, b2) = diff.overflowing_sub(borrow);
-            result[i] = diff;
-            borrow = (b1 as u64) + (b2 as u64);
+    ///
+    /// Entradas reduzidas; uma soma condicional por máscara.
+    pub fn sub(&self, a: [u64; N], b: [u64; N]) -> [u64; N] {
+        let (diff, borrow) = sub_limbs(a, b);
+        let (wrapped, _) = add_limbs(diff, self.modulus);
+        select(borrow.wrapping_neg(), diff, wrapped)
+    }
+
+    /// Modular multiplication (tempo constante)
+    pub fn mul(&self, a: [u64; N], b: [u64; N]) -> [u64; N] {
+        // Produto de 2N palavras: metade baixa e metade alta
+        let mut wide = [[0u64; N]; 2];
+        mul_slice(&a, &b, wide.as_flattened_mut());
+        self.reduce_slice(wide.as_flattened())
+    }
+
+    /// Modular exponentiation: base^exp mod m
+    pub fn pow(&self, base: [u64; N], exp: u64) -> [u64; N] {
+        self.pow_limbs(base, &[exp])
+    }
+
+    /// base^exp mod m com expoente de 256 bits
+    pub fn pow_u256(&self, base: [u64; N], exp: [u64; 4]) -> [u64; N] {
+        self.pow_limbs(base, &exp)
+    }
+
+    /// base^exp mod m com expoente de qualquer tamanho (square-and-multiply)
+    ///
+    /// O expoente vem em palavras da menos para a mais significativa, como em
+    /// [`BigUint::limbs`]. O tempo depende dos bits do expoente; para
+    /// expoentes secretos use [`pow_ct`](Self::pow_ct).
+    pub fn pow_limbs(&self, base: [u64; N], exp: &[u64]) -> [u64; N] {
+        let base = self.reduce(base);
+        let mut result = self.reduce(small(1));
+        for i in (0..bit_length(exp)).rev() {
+            result = self.mul(result, result);
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                result = self.mul(result, base);
+            }
         }
-        self.reduce(result)
+        result
     }
 
-    /// Modular multiplication (simplified)
-    pub fn mul(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
-        let mut result = [0u64; 4];
-        for i in 0..4 {
-            let mut carry = 0u64;
-            for j in 0..4 {
-                if i + j < 4 {
-                    let product = (a[i] as u128) * (b[j] as u128)
-                        + (result[i + j] as u128) + (carry as u128);
-                    result[i + j] = product as u64;
-                    carry = (product >> 64) as u64;
-                }
-            }
+    /// base^exp mod m em tempo constante (escada de Montgomery sobre os `64 * N` bits)
+    pub fn pow_ct(&self, base: [u64; N], exp: [u64; N]) -> [u64; N] {
+        let mut r0 = self.reduce(small(1));
+        let mut r1 = self.reduce(base);
+        for i in (0..64 * N).rev() {
+            let mask = bit(exp, i).wrapping_neg();
+            swap(mask, &mut r0, &mut r1);
+            r1 = self.mul(r0, r1);
+            r0 = self.mul(r0, r0);
+            swap(mask, &mut r0, &mut r1);
         }
-        self.reduce(result)
+        r0
     }
 
-    /// Modular exponentiation: base^exp mod m (simplified square-and-multiply)
-    pub fn pow(&self, base: [u64; 4], mut exp: u64) -> [u64; 4] {
-        let mut result = [1, 0, 0, 0];
-        let mut base = base;
+    /// Máximo divisor comum (Euclides)
+    pub fn gcd(&self, mut a: [u64; N], mut b: [u64; N]) -> [u64; N] {
+        while !self.is_zero(b) {
+            let (_, rem) = divrem(a, b);
+            a = b;
+            b = rem;
+        }
+        a
+    }
+
+    /// Inverso modular por Euclides estendido; `None` se mdc(a, m) != 1
+    ///
+    /// Vale para qualquer módulo, inclusive par (ex.: φ(n) no RSA). O tempo
+    /// depende dos valores; para `a` secreto e `m` primo use
+    /// [`inv_ct`](Self::inv_ct).
+    pub fn inv(&self, a: [u64; N]) -> Option<[u64; N]> {
+        let (mut r0, mut r1) = (self.modulus, self.reduce(a));
+        let (mut t0, mut t1) = ([0u64; N], self.reduce(small(1)));
+        while !self.is_zero(r1) {
+            let (quotient, rem) = divrem(r0, r1);
+            let t2 = self.sub(t0, self.mul(quotient, t1));
+            (r0, r1) = (r1, rem);
+            (t0, t1) = (t1, t2);
+        }
+        self.is_one(r0).then_some(t0)
+    }
+
+    /// Inverso por Fermat (a^(m-2)) em tempo constante; só para `m` primo
+    ///
+    /// `None` se a ≡ 0.
+    pub fn inv_ct(&self, a: [u64; N]) -> Option<[u64; N]> {
+        let (exp, _) = sub_limbs(self.modulus, small(2));
+        let result = self.pow_ct(a, exp);
+        (!self.is_zero(result)).then_some(result)
+    }
+
+    /// Símbolo de Legendre (a/m) para `m` primo ímpar: 0, 1 ou -1
+    pub fn legendre(&self, a: [u64; N]) -> i8 {
+        let (exp, _) = sub_limbs(self.modulus, small(1));
+        let result = self.pow_ct(a, shr(exp, 1));
+        if self.is_zero(result) {
+            0
+        } else if self.is_one(result) {
+            1
+        } else {
+            -1
+        }
+    }
 
-        while exp > 0 {
-            if exp & 1 == 1 {
-                result = self.mul(result, base);
+    /// Raiz quadrada modular para `m` primo; `None` se `a` não é resíduo quadrático
+    ///
+    /// Com m ≡ 3 (mod 4), a raiz é a^((m+1)/4) em tempo constante. Nos outros
+    /// primos usa Tonelli–Shanks, cujo número de passos depende de `a`.
+    pub fn sqrt(&self, a: [u64; N]) -> Option<[u64; N]> {
+        let a = self.reduce(a);
+        if self.is_zero(a) || self.modulus == small(2) {
+            return Some(a);
+        }
+        if self.legendre(a) != 1 {
+            return None;
+        }
+        if self.modulus[0] & 3 == 3 {
+            let (exp, _) = add_limbs(shr(self.modulus, 2), small(1));
+            return Some(self.pow_ct(a, exp));
+        }
+
+        // m - 1 = q * 2^s, q ímpar
+        let (m_minus_1, _) = sub_limbs(self.modulus, small(1));
+        let s = trailing_zeros(m_minus_1);
+        let q = shr(m_minus_1, s);
+        let mut z = small(2);
+        while self.legendre(z) != -1 {
+            z = add_limbs(z, small(1)).0;
+        }
+
+        let mut m = s;
+        let mut c = self.pow_limbs(z, &q);
+        let mut t = self.pow_limbs(a, &q);
+        let mut x = self.pow_limbs(a, &shr(add_limbs(q, small(1)).0, 1));
+        while !self.is_one(t) {
+            // Menor i com t^(2^i) = 1
+            let mut i = 0;
+            let mut t2i = t;
+            while !self.is_one(t2i) {
+                t2i = self.mul(t2i, t2i);
+                i += 1;
             }
-            base = self.mul(base, base);
-            exp >>= 1;
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = self.mul(b, b);
+            }
+            m = i;
+            c = self.mul(b, b);
+            t = self.mul(t, c);
+            x = self.mul(x, b);
         }
-        result
+        Some(x)
     }
 
     /// Verifica se o valor é zero
-    pub fn is_zero(&self, value: [u64; 4]) -> bool {
+    ///
+    /// Para no primeiro limb diferente de zero; para segredos use [`ct::is_zero`].
+    pub fn is_zero(&self, value: [u64; N]) -> bool {
         value.iter().all(|&x| x == 0)
     }
 
     /// Verifica se o valor é um
-    pub fn is_one(&self, value: [u64; 4]) -> bool {
+    pub fn is_one(&self, value: [u64; N]) -> bool {
         value[0] == 1 && value[1..].iter().all(|&x| x == 0)
     }
 
     /// Compara dois valores
-    pub fn cmp(&self, a: [u64; 4], b: [u64; 4]) -> core::cmp::Ordering {
-        for i in (0..4).rev() {
+    ///
+    /// Tempo variável; para segredos use [`ct::eq`] e [`ct::lt`].
+    pub fn cmp(&self, a: [u64; N], b: [u64; N]) -> core::cmp::Ordering {
+        for i in (0..N).rev() {
             match a[i].cmp(&b[i]) {
                 core::cmp::Ordering::Equal => continue,
                 other => return other,
@@ -112,7 +260,97 @@ impl ModContext {
     }
 }
 
-/// Montgomery form
+// ============================================================================
+// OPERAÇÕES EM PALAVRAS
+// ============================================================================
+
+/// Valor de uma palavra em `N` palavras
+fn small<const N: usize>(value: u64) -> [u64; N] {
+    let mut result = [0u64; N];
+    result[0] = value;
+    result
+}
+
+fn add_limbs<const N: usize>(a: [u64; N], b: [u64; N]) -> ([u64; N], u64) {
+    let mut result = [0u64; N];
+    let mut carry = 0u64;
+    for i in 0..N {
+        let sum = a[i] as u128 + b[i] as u128 + carry as u128;
+        result[i] = sum as u64;
+        carry = (sum >> 64) as u64;
+    }
+    (result, carry)
+}
+
+/// a - b e o empréstimo final (1 se b > a)
+fn sub_limbs<const N: usize>(a: [u64; N], b: [u64; N]) -> ([u64; N], u64) {
+    let mut result = [0u64; N];
+    let mut borrow = 0u64;
+    for i in 0..N {
+        let diff = (a[i] as u128).wrapping_sub(b[i] as u128 + borrow as u128);
+        result[i] = diff as u64;
+        borrow = (diff >> 127) as u64;
+    }
+    (result, borrow)
+}
+
+fn mul_wide(a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
+    let mut result = [0u64; 8];
+    mul_slice(&a, &b, &mut result);
+    result
+}
+
+fn bit<const N: usize>(value: [u64; N], i: usize) -> u64 {
+    (value[i / 64] >> (i % 64)) & 1
+}
+
+fn bit_length(value: &[u64]) -> usize {
+    (0..value.len())
+        .rev()
+        .find(|&i| value[i] != 0)
+        .map_or(0, |i| i * 64 + 64 - value[i].leading_zeros() as usize)
+}
+
+fn trailing_zeros<const N: usize>(value: [u64; N]) -> usize {
+    (0..N)
+        .find(|&i| value[i] != 0)
+        .map_or(64 * N, |i| i * 64 + value[i].trailing_zeros() as usize)
+}
+
+fn shr<const N: usize>(value: [u64; N], n: usize) -> [u64; N] {
+    let mut result = [0u64; N];
+    shr_slice(&value, n, &mut result);
+    result
+}
+
+/// Um passo da divisão longa: `2 * rem + bit`, menos `d` se couber
+///
+/// Com `rem < d` na entrada, a saída também fica abaixo de `d`; o segundo
+/// valor é o bit do quociente.
+fn div_step<const N: usize>(rem: [u64; N], bit: u64, d: [u64; N]) -> ([u64; N], u64) {
+    let mut shifted = [0u64; N];
+    for i in 0..N {
+        shifted[i] = rem[i] << 1 | if i == 0 { bit } else { rem[i - 1] >> 63 };
+    }
+    let top = rem[N - 1] >> 63;
+    let (diff, borrow) = sub_limbs(shifted, d);
+    let take = top | (borrow ^ 1);
+    (select(take.wrapping_neg(), shifted, diff), take)
+}
+
+/// Quociente e resto de a / d (d != 0)
+fn divrem<const N: usize>(a: [u64; N], d: [u64; N]) -> ([u64; N], [u64; N]) {
+    let mut quotient = [0u64; N];
+    let mut rem = [0u64; N];
+    for i in (0..64 * N).rev() {
+        let (next, take) = div_step(rem, bit(a, i), d);
+        rem = next;
+        quotient[i / 64] |= take << (i % 64);
+    }
+    (quotient, rem)
+}
+
+/// Montgomery form (módulo ímpar)
 pub struct Montgomery {
     modulus: [u64; 4],
     r: [u64; 4],
@@ -131,44 +369,27 @@ impl Montgomery {
         Self { modulus, r, r_inv, n_prime }
     }
 
-    fn compute_r(_modulus: [u64; 4]) -> [u64; 4] {
-        // R = 2^256 mod m - simplificado
-        let mut r = [0u64; 4];
-        r[0] = 1;
-        // Shiftar 256 bits é complexo, usar aproximação
-        r
+    fn compute_r(modulus: [u64; 4]) -> [u64; 4] {
+        ModContext::new(modulus).reduce_wide([0, 0, 0, 0, 1, 0, 0, 0])
     }
 
-    fn compute_r_inv(_modulus: [u64; 4]) -> [u64; 4] {
-        // R^(-1) mod m - simplificado usando Extended GCD
-        [1, 0, 0, 0]
+    fn compute_r_inv(modulus: [u64; 4]) -> [u64; 4] {
+        // Existe sempre que m é ímpar
+        ModContext::new(modulus).inv(Self::compute_r(modulus)).unwrap_or_default()
     }
 
     fn compute_n_prime(m0: u64) -> u64 {
-        // -m^(-1) mod 2^64 usando Extended Euclidean Algorithm
-        let mut t = 0u64;
-        let mut newt = 1u64;
-        let mut r = 1u64 << 63; // 2^63
-        let mut newr = m0;
-
-        while newr != 0 {
-            let quotient = r / newr;
-            let temp = t;
-            t = newt;
-            newt = temp.wrapping_sub(quotient.wrapping_mul(newt));
-            let temp = r;
-            r = newr;
-            newr = temp - quotient * newr;
+        // -m^(-1) mod 2^64 por Newton: cada passo dobra os bits corretos
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
         }
-
-        t.wrapping_neg()
+        inv.wrapping_neg()
     }
 
     /// To Montgomery form: x * R mod m
     pub fn to_montgomery(&self, x: [u64; 4]) -> [u64; 4] {
-        // x * R mod m = REDC(x * R^2 mod m)
-        // Simplificado: multiplicar e reduzir
-        self.redc(self.mul_wide(x, self.r))
+        ModContext::new(self.modulus).mul(x, self.r)
     }
 
     /// From Montgomery form: x * R^(-1) mod m
@@ -183,24 +404,11 @@ impl Montgomery {
         wide
     }
 
-    fn mul_wide(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 8] {
-        let mut result = [0u64; 8];
-        for i in 0..4 {
-            let mut carry = 0u128;
-            for j in 0..4 {
-                let product = (a[i] as u128) * (b[j] as u128)
-                    + (result[i + j] as u128) + carry;
-                result[i + j] = product as u64;
-                carry = product >> 64;
-            }
-            result[i + 4] = carry as u64;
-        }
-        result
-    }
-
     fn redc(&self, t: [u64; 8]) -> [u64; 4] {
         // Montgomery REDC algorithm
         let mut t = t;
+        // Bit 512: t + sum(m_i * modulus) pode passar de 8 palavras
+        let mut overflow = 0u64;
 
         for i in 0..4 {
             let m = t[i].wrapping_mul(self.n_prime);
@@ -218,35 +426,16 @@ impl Montgomery {
                 t[i + j] = sum as u64;
                 carry = sum >> 64;
             }
+            overflow += carry as u64;
         }
 
         let mut result = [0u64; 4];
         result.copy_from_slice(&t[4..8]);
 
-        // Redução final se necessário
-        if self.greater_or_equal(&result, &self.modulus) {
-            self.sub_mod(&mut result);
-        }
-
-        result
-    }
-
-    fn greater_or_equal(&self, a: &[u64; 4], b: &[u64; 4]) -> bool {
-        for i in (0..4).rev() {
-            if a[i] > b[i] { return true; }
-            if a[i] < b[i] { return false; }
-        }
-        true
-    }
-
-    fn sub_mod(&self, value: &mut [u64; 4]) {
-        let mut borrow = 0u64;
-        for i in 0..4 {
-            let (diff, b1) = value[i].overflowing_sub(self.modulus[i]);
-            let (diff, b2) = diff.overflowing_sub(borrow);
-            value[i] = diff;
-            borrow = (b1 as u64) + (b2 as u64);
-        }
+        // Redução final por máscara: subtrai m se passou de 2^256 (overflow
+        // é 0 ou 1, o resultado fica abaixo de 2m) ou se ficou >= m
+        let (diff, borrow) = sub_limbs(result, self.modulus);
+        select((overflow | (borrow ^ 1)).wrapping_neg(), result, diff)
     }
 
     /// Get modulus
@@ -262,12 +451,17 @@ impl Montgomery {
     pub fn n_prime(&self) -> u64 { self.n_prime }
 
     /// Montgomery multiplication: (a * b) * R^(-1) mod m
+    ///
+    /// Tempo constante; com `a` e `b` reduzidos o resultado também fica reduzido.
     pub fn mul(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
         // REDC(a * b)
-        self.redc(self.mul_wide(a, b))
+        self.redc(mul_wide(a, b))
     }
 
     /// Montgomery modular exponentiation
+    ///
+    /// O tempo depende dos bits do expoente; para expoentes secretos use
+    /// [`pow_ct`](Self::pow_ct).
     pub fn pow(&self, base: [u64; 4], mut exp: u64) -> [u64; 4] {
         let mut result = self.r; // R mod m (Montgomery representation of 1)
         let mut base_mont = self.to_montgomery(base);
@@ -281,9 +475,26 @@ impl Montgomery {
         }
         self.from_montgomery(result)
     }
+
+    /// base^exp mod m em tempo constante (escada de Montgomery sobre os 256 bits)
+    pub fn pow_ct(&self, base: [u64; 4], exp: [u64; 4]) -> [u64; 4] {
+        let mut r0 = self.r;
+        let mut r1 = self.to_montgomery(base);
+        for i in (0..256).rev() {
+            let mask = bit(exp, i).wrapping_neg();
+            swap(mask, &mut r0, &mut r1);
+            r1 = self.mul(r0, r1);
+            r0 = self.mul(r0, r0);
+            swap(mask, &mut r0, &mut r1);
+        }
+        self.from_montgomery(r0)
+    }
 }
 
 /// Barrett reduction
+///
+/// Troca a divisão por duas multiplicações com `mu = floor(2^(2k) / m)`,
+/// pré-calculado uma vez por módulo.
 pub struct Barrett {
     modulus: [u64; 4],
     mu: [u64; 5],
@@ -297,60 +508,73 @@ impl Barrett {
     }
 
     fn compute_mu(modulus: [u64; 4]) -> [u64; 5] {
-        // mu = floor(2^(2*256) / m) onde 256 é o bit length
-        // Simplificado: aproximação para o algoritmo
+        // mu = floor(2^(2k) / m), k = bits do módulo; cabe em k + 1 bits
+        let k = bit_length(&modulus);
         let mut mu = [0u64; 5];
-
-        // Encontrar bit mais significativo do modulus
-        let _bit_length = 256;
-        for i in (0..4).rev() {
-            if modulus[i] != 0 {
-                let _k = i * 64 + (64 - modulus[i].leading_zeros() as usize);
-                break;
+        let mut rem = [0u64; 4];
+        for i in (0..=2 * k).rev() {
+            let (next, take) = div_step(rem, (i == 2 * k) as u64, modulus);
+            rem = next;
+            if i < 320 {
+                mu[i / 64] |= take << (i % 64);
             }
         }
-
-        // mu = 2^(2k) / m (simplificado)
-        mu[4] = 1;
         mu
     }
 
     /// Barrett reduction: x mod m
+    ///
+    /// Para x < 2^(2k) (k = bits do módulo), como o produto de dois valores
+    /// reduzidos, usa [`reduce_ct`](Self::reduce_ct). Valores maiores usam a
+    /// divisão de [`ModContext`]; a escolha revela se `x` passou de 2^(2k).
     pub fn reduce(&self, x: [u64; 8]) -> [u64; 4] {
-        // Algoritmo Barrett:
-        // q = floor((x * mu) / 2^(k+256))
-        // r = x - q * m
-        // se r >= m: r = r - m
-
-        // Simplificado: fazer redução iterativa
-        let mut result = [x[0], x[1], x[2], x[3]];
-
-        // Subtrair modulus enquanto result >= modulus
-        loop {
-            let mut is_greater = false;
-            for i in (0..4).rev() {
-                if result[i] > self.modulus[i] {
-                    is_greater = true;
-                    break;
-                } else if result[i] < self.modulus[i] {
-                    break;
-                }
-            }
-
-            if !is_greater {
-                break;
-            }
-
-            let mut borrow = 0u64;
-            for i in 0..4 {
-                let (diff, b1) = result[i].overflowing_sub(self.modulus[i]);
-                let (diff, b2) = diff.overflowing_sub(borrow);
-                result[i] = diff;
-                borrow = (b1 as u64) + (b2 as u64);
+        if bit_length(&x) > 2 * bit_length(&self.modulus) {
+            return ModContext::new(self.modulus).reduce_wide(x);
+        }
+        self.reduce_ct(x)
+    }
+
+    /// Barrett reduction em tempo constante para x < 2^(2k)
+    ///
+    /// Duas multiplicações e duas subtrações por máscara, sem olhar o tamanho
+    /// de `x`. Fora da faixa o resultado é incorreto (verificado só em debug).
+    pub fn reduce_ct(&self, x: [u64; 8]) -> [u64; 4] {
+        let k = bit_length(&self.modulus);
+        debug_assert!(bit_length(&x) <= 2 * k, "Barrett::reduce_ct input exceeds 2^(2k)");
+
+        // q = floor(floor(x / 2^(k-1)) * mu / 2^(k+1)) fica no máximo 2 abaixo de floor(x / m)
+        let mut q1 = [0u64; 5];
+        shr_slice(&x, k - 1, &mut q1);
+        let mut q2 = [0u64; 10];
+        mul_slice(&q1, &self.mu, &mut q2);
+        let mut q3 = [0u64; 5];
+        shr_slice(&q2, k + 1, &mut q3);
+        let mut qm = [0u64; 9];
+        mul_slice(&q3, &self.modulus, &mut qm);
+
+        // r = x - q * m < 3m, que pode passar de 256 bits
+        let mut r = [x[0], x[1], x[2], x[3], x[4], x[5], x[6], x[7], 0];
+        sub_slice(&mut r, &qm);
+        let mut r = [r[0], r[1], r[2], r[3], r[4]];
+        let m = [self.modulus[0], self.modulus[1], self.modulus[2], self.modulus[3], 0];
+        for _ in 0..2 {
+            let mut diff = r;
+            let mask = (sub_slice(&mut diff, &m) ^ 1).wrapping_neg();
+            for (word, diff) in r.iter_mut().zip(diff) {
+                *word ^= mask & (*word ^ diff);
             }
         }
+        [r[0], r[1], r[2], r[3]]
+    }
 
-        result
+    /// Modular multiplication com redução de Barrett
+    pub fn mul(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+        self.reduce(mul_wide(a, b))
+    }
+
+    /// Multiplicação em tempo constante; `a` e `b` reduzidos
+    pub fn mul_ct(&self, a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+        self.reduce_ct(mul_wide(a, b))
     }
 
     /// Get modulus
@@ -360,9 +584,45 @@ impl Barrett {
     pub fn mu(&self) -> [u64; 5] { self.mu }
 }
 
+/// `out = value >> n`, truncado no tamanho de `out`
+fn shr_slice(value: &[u64], n: usize, out: &mut [u64]) {
+    let (words, bits) = (n / 64, n % 64);
+    let word = |i: usize| value.get(i).copied().unwrap_or(0);
+    for (i, out) in out.iter_mut().enumerate() {
+        let low = word(i + words) >> bits;
+        let high = if bits == 0 { 0 } else { word(i + words + 1) << (64 - bits) };
+        *out = low | high;
+    }
+}
+
+/// `out = a * b`; `out` precisa de `a.len() + b.len()` palavras
+fn mul_slice(a: &[u64], b: &[u64], out: &mut [u64]) {
+    out.fill(0);
+    for (i, &a) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &b) in b.iter().enumerate() {
+            let product = (a as u128) * (b as u128) + (out[i + j] as u128) + carry;
+            out[i + j] = product as u64;
+            carry = product >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+}
+
+/// `a -= b` (b pode ser mais curto) e o empréstimo final
+fn sub_slice(a: &mut [u64], b: &[u64]) -> u64 {
+    let mut borrow = 0u64;
+    for (i, word) in a.iter_mut().enumerate() {
+        let diff = (*word as u128).wrapping_sub(b.get(i).copied().unwrap_or(0) as u128 + borrow as u128);
+        *word = diff as u64;
+        borrow = (diff >> 127) as u64;
+    }
+    borrow
+}
+
 /// Prelude
 pub mod prelude {
-    pub use crate::{ModContext, Montgomery, Barrett};
+    pub use crate::{BigUint, ModContext, Montgomery, Barrett};
 }
 
 #[cfg(test)]
@@ -373,7 +633,8 @@ mod tests {
     fn test_mod_add() {
         let ctx = ModContext::new([13, 0, 0, 0]);
         let result = ctx.add([10, 0, 0, 0], [5, 0, 0, 0]);
-        assert_eq!(result[0], 15);
+        assert_eq!(result[0], 2);
+        assert_eq!(ctx.sub([5, 0, 0, 0], [10, 0, 0, 0])[0], 8);
     }
 
     #[test]
@@ -387,8 +648,8 @@ mod tests {
     fn test_mod_pow() {
         let ctx = ModContext::new([13, 0, 0, 0]);
         let result = ctx.pow([2, 0, 0, 0], 10); // 2^10 mod 13
-        // 2^10 = 1024, 1024 mod 13 = 11
-        assert_eq!(result[0], 1024);
+        // 2^10 = 1024, 1024 mod 13 = 10
+        assert_eq!(result, [10, 0, 0, 0]);
     }
 
     #[test]
@@ -411,12 +672,189 @@ mod tests {
         let a = mont.to_montgomery([3, 0, 0, 0]);
         let b = mont.from_montgomery(a);
         assert_eq!(b[0], 3);
+        assert_eq!(mont.pow([2, 0, 0, 0], 10), [10, 0, 0, 0]);
+
+        let mont = Montgomery::new(P256);
+        let ctx = ModContext::new(P256);
+        let x = [0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 0x1111_2222_3333_4444, 0x0fff_0000_ffff_0000];
+        assert_eq!(mont.from_montgomery(mont.to_montgomery(x)), x);
+        assert_eq!(mont.pow(x, 65537), ctx.pow(x, 65537));
+        assert_eq!(ctx.mul(mont.r(), mont.r_inv()), [1, 0, 0, 0]);
     }
 
     #[test]
     fn test_barrett() {
         let barrett = Barrett::new([13, 0, 0, 0]);
         let result = barrett.reduce([20, 0, 0, 0, 0, 0, 0, 0]);
-        assert_eq!(result[0], 20);
+        assert_eq!(result[0], 7);
+        // floor(2^8 / 13)
+        assert_eq!(barrett.mu(), [19, 0, 0, 0, 0]);
+        assert_eq!(barrett.mul([12, 0, 0, 0], [12, 0, 0, 0]), [1, 0, 0, 0]);
+    }
+
+    // ========================================================================
+    // PROPRIEDADES COM PRIMOS CONHECIDOS
+    // ========================================================================
+
+    /// NIST P-256: 2^256 - 2^224 + 2^192 + 2^96 - 1 (≡ 3 mod 4)
+    const P256: [u64; 4] = [0xffff_ffff_ffff_ffff, 0x0000_0000_ffff_ffff, 0, 0xffff_ffff_0000_0001];
+    /// secp256k1: 2^256 - 2^32 - 977 (≡ 3 mod 4)
+    const SECP256K1: [u64; 4] = [0xffff_fffe_ffff_fc2f, u64::MAX, u64::MAX, u64::MAX];
+    /// Curve25519: 2^255 - 19 (≡ 5 mod 8, Tonelli–Shanks com s = 2)
+    const P25519: [u64; 4] = [0xffff_ffff_ffff_ffed, u64::MAX, u64::MAX, 0x7fff_ffff_ffff_ffff];
+    /// Goldilocks: 2^64 - 2^32 + 1 (s = 32)
+    const GOLDILOCKS: [u64; 4] = [0xffff_ffff_0000_0001, 0, 0, 0];
+
+    /// NIST P-384: 2^384 - 2^128 - 2^96 + 2^32 - 1 (≡ 3 mod 4)
+    const P384: [u64; 6] = [
+        0x0000_0000_ffff_ffff,
+        0xffff_ffff_0000_0000,
+        0xffff_ffff_ffff_fffe,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+    ];
+
+    /// xorshift64*: valores pseudoaleatórios reproduzíveis
+    fn random_values<const N: usize>(seed: u64, count: usize) -> impl Iterator<Item = [u64; N]> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        };
+        (0..count).map(move |_| core::array::from_fn(|_| next()))
+    }
+
+    #[test]
+    fn test_inverse_and_pow_properties() {
+        for (seed, prime) in [(1, P256), (2, SECP256K1), (3, P25519), (4, GOLDILOCKS)] {
+            let ctx = ModContext::new(prime);
+            let (p_minus_1, _) = sub_limbs(prime, [1, 0, 0, 0]);
+            for a in random_values(seed, 4).map(|a| ctx.reduce(a)) {
+                let inv = ctx.inv(a).unwrap();
+                assert_eq!(ctx.mul(a, inv), [1, 0, 0, 0]);
+                assert_eq!(ctx.inv_ct(a), Some(inv));
+                // Fermat: a^(p-1) = 1
+                assert_eq!(ctx.pow_ct(a, p_minus_1), [1, 0, 0, 0]);
+                assert_eq!(ctx.pow_u256(a, p_minus_1), [1, 0, 0, 0]);
+                assert_eq!(ctx.gcd(prime, a), [1, 0, 0, 0]);
+            }
+            assert_eq!(ctx.inv([0, 0, 0, 0]), None);
+            assert_eq!(ctx.inv_ct(prime), None);
+        }
+    }
+
+    #[test]
+    fn test_sqrt_properties() {
+        for (seed, prime) in [(5, P256), (6, P25519), (7, GOLDILOCKS), (8, [97, 0, 0, 0])] {
+            let ctx = ModContext::new(prime);
+            for a in random_values(seed, 3).map(|a| ctx.reduce(a)) {
+                let square = ctx.mul(a, a);
+                assert_eq!(ctx.legendre(square), 1);
+                let root = ctx.sqrt(square).unwrap();
+                assert_eq!(ctx.mul(root, root), square);
+                assert!(root == a || root == ctx.sub(prime, a));
+            }
+            // -1 é resíduo só quando p ≡ 1 (mod 4)
+            let minus_one = ctx.sub([0, 0, 0, 0], [1, 0, 0, 0]);
+            assert_eq!(ctx.sqrt(minus_one).is_some(), prime[0] & 3 == 1);
+        }
+        assert_eq!(ModContext::new([97, 0, 0, 0]).sqrt([5, 0, 0, 0]), None);
+        assert_eq!(ModContext::new([2, 0, 0, 0]).sqrt([1, 0, 0, 0]), Some([1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_reductions_agree() {
+        let even = [0x1234_5678_9abc_def0, 0, 0, 0x8000_0000_0000_0000];
+        for (seed, modulus) in [(9, P256), (10, SECP256K1), (11, P25519), (12, GOLDILOCKS), (13, even), (14, [3, 0, 0, 0])] {
+            let ctx = ModContext::new(modulus);
+            let barrett = Barrett::new(modulus);
+            let mut values = random_values(seed, 16);
+            while let (Some(a), Some(b)) = (values.next(), values.next()) {
+                let wide = mul_wide(a, b);
+                assert_eq!(barrett.reduce(wide), ctx.reduce_wide(wide));
+                let (a, b) = (ctx.reduce(a), ctx.reduce(b));
+                assert_eq!(barrett.mul(a, b), ctx.mul(a, b));
+                assert_eq!(barrett.mul_ct(a, b), ctx.mul(a, b));
+
+                if modulus[0] & 1 == 1 {
+                    let mont = Montgomery::new(modulus);
+                    let product = mont.mul(mont.to_montgomery(a), mont.to_montgomery(b));
+                    assert_eq!(mont.from_montgomery(product), ctx.mul(a, b));
+                    assert_eq!(mont.pow(a, b[0]), ctx.pow(a, b[0]));
+                    assert_eq!(mont.pow_ct(a, b), ctx.pow_limbs(a, &b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_384_bit_modulus() {
+        let one = BigUint::one();
+        let p = BigUint::from(P384);
+        assert_eq!(p, (&one << 384) - (&one << 128) - (&one << 96) + (&one << 32) - &one);
+
+        let ctx = ModContext::new(P384);
+        let (p_minus_1, _) = sub_limbs(P384, small(1));
+        let mut values = random_values::<6>(15, 6);
+        while let (Some(a), Some(b)) = (values.next(), values.next()) {
+            let expected = (BigUint::from(a) * BigUint::from(b)) % &p;
+            assert_eq!(ctx.mul(a, b), expected.to_limbs().unwrap());
+            let (a, b) = (ctx.reduce(a), ctx.reduce(b));
+            let sum = (BigUint::from(a) + BigUint::from(b)) % &p;
+            assert_eq!(ctx.add(a, b), sum.to_limbs().unwrap());
+            assert_eq!(ctx.sub(ctx.add(a, b), b), a);
+
+            let inv = ctx.inv(a).unwrap();
+            assert_eq!(ctx.mul(a, inv), small(1));
+            assert_eq!(ctx.pow_ct(a, p_minus_1), small(1));
+            let square = ctx.mul(a, a);
+            let root = ctx.sqrt(square).unwrap();
+            assert!(root == a || root == ctx.sub(P384, a));
+        }
+    }
+
+    #[test]
+    fn test_4096_bit_modulus() {
+        let mut modulus = random_values::<64>(16, 1).next().unwrap();
+        modulus[0] |= 1;
+        modulus[63] |= 1 << 63;
+        let ctx = ModContext::new(modulus);
+        let m = BigUint::from(modulus);
+        assert_eq!(m.bits(), 4096);
+        assert_eq!(m.to_string().parse::<BigUint>().unwrap(), m);
+
+        let mut values = random_values::<64>(17, 2).map(|a| ctx.reduce(a));
+        let (a, b) = (values.next().unwrap(), values.next().unwrap());
+        let (big_a, big_b) = (BigUint::from(a), BigUint::from(b));
+        assert!(big_a < m && big_b < m);
+        assert_eq!(ctx.mul(a, b), ((&big_a * &big_b) % &m).to_limbs().unwrap());
+        assert_eq!(ctx.sub(a, b), ((&big_a + &m - &big_b) % &m).to_limbs().unwrap());
+
+        // a^65537 por quadrados sucessivos em BigUint
+        let mut expected = big_a.clone();
+        for _ in 0..16 {
+            expected = (&expected * &expected) % &m;
+        }
+        expected = (expected * &big_a) % &m;
+        assert_eq!(ctx.pow(a, 65537), expected.to_limbs().unwrap());
+    }
+
+    #[test]
+    fn test_rsa_with_even_modulus() {
+        // p = 61, q = 53: n = 3233, φ(n) = 3120, e = 17
+        let phi = ModContext::new([3120, 0, 0, 0]);
+        let d = phi.inv([17, 0, 0, 0]).unwrap();
+        assert_eq!(d, [2753, 0, 0, 0]);
+        assert_eq!(phi.inv([65, 0, 0, 0]), None);
+        assert_eq!(phi.gcd([3120, 0, 0, 0], [65, 0, 0, 0]), [65, 0, 0, 0]);
+
+        let n = ModContext::new([3233, 0, 0, 0]);
+        let cipher = n.pow([65, 0, 0, 0], 17);
+        assert_eq!(cipher, [2790, 0, 0, 0]);
+        assert_eq!(n.pow_ct(cipher, d), [65, 0, 0, 0]);
+        assert_eq!(n.pow_u256(cipher, d), [65, 0, 0, 0]);
     }
 }
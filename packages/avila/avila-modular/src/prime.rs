@@ -0,0 +1,668 @@
+//! Testes de primalidade e geração de primos
+//!
+//! - [`is_prime_u64`]: Miller–Rabin determinístico; as bases 2 a 37 não têm
+//!   pseudoprimo forte comum abaixo de 2^64
+//! - [`miller_rabin`] e [`miller_rabin_random`]: bases fixas ou sorteadas
+//! - [`baillie_psw`]: Miller–Rabin na base 2 mais Lucas forte (parâmetros de
+//!   Selfridge); sem contraexemplo conhecido
+//! - [`is_prime`]: divisão por primos pequenos e depois o teste adequado ao
+//!   tamanho
+//! - [`random_prime`] e [`random_safe_prime`]: primos com o número de bits
+//!   pedido; o seguro (p = 2q + 1, q primo) é o usado em Diffie–Hellman
+//!
+//! A geração recebe a fonte de bytes aleatórios do chamador (`rng` preenche
+//! o buffer), que precisa ser criptograficamente segura para chaves. Nenhum
+//! teste é de tempo constante.
+
+use crate::{sub_slice, BigUint, Montgomery};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Bases que tornam o Miller–Rabin determinístico para todo `u64`
+const U64_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Primos ímpares usados como peneira antes dos testes caros
+const SIEVE_PRIMES: [u64; 255] = odd_primes();
+
+const fn odd_primes<const COUNT: usize>() -> [u64; COUNT] {
+    let mut primes = [0u64; COUNT];
+    let (mut found, mut candidate) = (0, 3);
+    while found < COUNT {
+        let mut i = 0;
+        while i < found && primes[i] * primes[i] <= candidate && candidate % primes[i] != 0 {
+            i += 1;
+        }
+        if i == found || primes[i] * primes[i] > candidate {
+            primes[found] = candidate;
+            found += 1;
+        }
+        candidate += 2;
+    }
+    primes
+}
+
+// ============================================================================
+// TESTES
+// ============================================================================
+
+/// Primalidade exata de um `u64`
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in U64_WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    U64_WITNESSES.iter().all(|&base| miller_rabin_u64(n, base))
+}
+
+/// Miller–Rabin com as bases dadas; `false` se alguma prova que `n` é composto
+///
+/// Bases múltiplas de `n` são ignoradas. Para `n` abaixo de 2^64, as bases 2
+/// a 37 (ver [`is_prime_u64`]) tornam o teste exato.
+pub fn miller_rabin(n: &BigUint, bases: &[u64]) -> bool {
+    if let Some(n) = to_u64(n) {
+        return match n {
+            0 | 1 => false,
+            2 | 3 => true,
+            _ if n % 2 == 0 => false,
+            _ => bases.iter().all(|&base| miller_rabin_u64(n, base)),
+        };
+    }
+    if !n.bit(0) {
+        return false;
+    }
+    let test = MillerRabin::new(n);
+    bases.iter().all(|&base| test.passes(&BigUint::from(base)))
+}
+
+/// Miller–Rabin com `rounds` bases sorteadas em [2, n - 2]
+///
+/// Cada rodada deixa passar um composto com probabilidade de no máximo 1/4,
+/// mesmo que `n` tenha sido escolhido por um adversário.
+pub fn miller_rabin_random<R: FnMut(&mut [u8])>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    if let Some(n) = to_u64(n) {
+        return is_prime_u64(n);
+    }
+    if !n.bit(0) {
+        return false;
+    }
+    let test = MillerRabin::new(n);
+    let two = BigUint::from(2u64);
+    let upper = n - &two;
+    (0..rounds).all(|_| {
+        let base = loop {
+            let base = random_bits(n.bits(), rng);
+            if base >= two && base <= upper {
+                break base;
+            }
+        };
+        test.passes(&base)
+    })
+}
+
+/// Teste de Baillie–PSW: Miller–Rabin na base 2 e Lucas forte
+pub fn baillie_psw(n: &BigUint) -> bool {
+    if let Some(small) = to_u64(n) {
+        if small < 2 || small % 2 == 0 {
+            return small == 2;
+        }
+    } else if !n.bit(0) {
+        return false;
+    }
+    miller_rabin(n, &[2]) && strong_lucas(n)
+}
+
+/// Primalidade de `n`: exata até 2^64, Baillie–PSW acima
+pub fn is_prime(n: &BigUint) -> bool {
+    if let Some(n) = to_u64(n) {
+        return is_prime_u64(n);
+    }
+    n.bit(0) && !has_small_factor(n) && baillie_psw(n)
+}
+
+/// `p` e `(p - 1) / 2` primos
+pub fn is_safe_prime(p: &BigUint) -> bool {
+    let q = p >> 1;
+    is_prime(&q) && is_prime(p)
+}
+
+fn miller_rabin_u64(n: u64, base: u64) -> bool {
+    let base = base % n;
+    if base == 0 {
+        return true;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mul = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    let mut x = 1u64;
+    let (mut square, mut exp) = (base, d);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            x = mul(x, square);
+        }
+        square = mul(square, square);
+        exp >>= 1;
+    }
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul(x, x);
+        if x == n - 1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// Estado do Miller–Rabin para um `n` ímpar acima de 2^64
+struct MillerRabin {
+    monty: Monty,
+    /// `n - 1 = d * 2^s`, `d` ímpar
+    d: BigUint,
+    s: usize,
+    minus_one: Vec<u64>,
+}
+
+impl MillerRabin {
+    fn new(n: &BigUint) -> Self {
+        let monty = Monty::new(n);
+        let n_minus_1 = n - &BigUint::one();
+        let s = (0..).find(|&i| n_minus_1.bit(i)).unwrap_or(0);
+        let minus_one = monty.sub(&monty.zero(), &monty.one);
+        Self {
+            d: &n_minus_1 >> s,
+            s,
+            minus_one,
+            monty,
+        }
+    }
+
+    fn passes(&self, base: &BigUint) -> bool {
+        let base = self.monty.to_mont(base);
+        if self.monty.is_zero(&base) {
+            return true;
+        }
+        let mut x = self.monty.pow(&base, self.d.limbs());
+        if x == self.monty.one || x == self.minus_one {
+            return true;
+        }
+        for _ in 1..self.s {
+            x = self.monty.mul(&x, &x);
+            if x == self.minus_one {
+                return true;
+            }
+            if x == self.monty.one {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+/// Lucas forte com P = 1 e o primeiro D em 5, -7, 9, -11, ... com (D/n) = -1
+///
+/// `n` ímpar e maior que 2.
+fn strong_lucas(n: &BigUint) -> bool {
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(d, n) {
+            -1 => break,
+            // D tem fator comum com n
+            0 if BigUint::from(d.unsigned_abs()) != *n => return false,
+            0 => return true,
+            _ => {}
+        }
+        // Num quadrado perfeito (D/n) nunca é -1
+        if d == 21 && is_square(n) {
+            return false;
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+    let q = (1 - d) / 4;
+
+    let monty = Monty::new(n);
+    let signed = |value: i64| {
+        let magnitude = monty.to_mont(&BigUint::from(value.unsigned_abs()));
+        if value < 0 {
+            monty.sub(&monty.zero(), &magnitude)
+        } else {
+            magnitude
+        }
+    };
+    let (d_m, q_m) = (signed(d), signed(q));
+
+    // n + 1 = k * 2^s, k ímpar
+    let n_plus_1 = n + &BigUint::one();
+    let s = (0..).find(|&i| n_plus_1.bit(i)).unwrap_or(0);
+    let k = &n_plus_1 >> s;
+
+    // U_1 = 1, V_1 = P = 1; a cada bit, dobra o índice e soma um se o bit for 1
+    let (mut u, mut v, mut qk) = (monty.one.clone(), monty.one.clone(), q_m.clone());
+    for i in (0..k.bits() - 1).rev() {
+        u = monty.mul(&u, &v);
+        v = monty.sub(&monty.mul(&v, &v), &monty.add(&qk, &qk));
+        qk = monty.mul(&qk, &qk);
+        if k.bit(i) {
+            let next_u = monty.half(&monty.add(&u, &v));
+            v = monty.half(&monty.add(&monty.mul(&d_m, &u), &v));
+            u = next_u;
+            qk = monty.mul(&qk, &q_m);
+        }
+    }
+    if monty.is_zero(&u) || monty.is_zero(&v) {
+        return true;
+    }
+    for _ in 1..s {
+        v = monty.sub(&monty.mul(&v, &v), &monty.add(&qk, &qk));
+        if monty.is_zero(&v) {
+            return true;
+        }
+        qk = monty.mul(&qk, &qk);
+    }
+    false
+}
+
+/// Símbolo de Jacobi (a/n) para `n` ímpar
+fn jacobi(a: i64, n: &BigUint) -> i8 {
+    let n_mod_8 = n.limbs()[0] & 7;
+    // (-1/n) = 1 se n ≡ 1 (mod 4)
+    let mut sign = if a < 0 && n_mod_8 & 3 == 3 { -1 } else { 1 };
+    let mut a = a.unsigned_abs();
+    let twos = a.trailing_zeros();
+    a >>= twos;
+    // (2/n) = -1 se n ≡ 3, 5 (mod 8)
+    if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+        sign = -sign;
+    }
+    if a == 1 {
+        return sign;
+    }
+    // Reciprocidade quadrática: (a/n) = (n/a), trocando o sinal se ambos ≡ 3 (mod 4)
+    if a & 3 == 3 && n_mod_8 & 3 == 3 {
+        sign = -sign;
+    }
+    sign * jacobi_u64(rem_u64(n, a), a)
+}
+
+fn jacobi_u64(mut a: u64, mut n: u64) -> i8 {
+    let mut sign = 1;
+    a %= n;
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                sign = -sign;
+            }
+        }
+        core::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            sign = -sign;
+        }
+        a %= n;
+    }
+    if n == 1 {
+        sign
+    } else {
+        0
+    }
+}
+
+/// Raiz inteira por Newton
+fn is_square(n: &BigUint) -> bool {
+    let mut x = BigUint::one() << n.bits().div_ceil(2);
+    loop {
+        let y = (&x + &(n / &x)) >> 1;
+        if y >= x {
+            return &x * &x == *n;
+        }
+        x = y;
+    }
+}
+
+fn has_small_factor(n: &BigUint) -> bool {
+    SIEVE_PRIMES.iter().any(|&p| rem_u64(n, p) == 0)
+}
+
+fn rem_u64(n: &BigUint, d: u64) -> u64 {
+    n.limbs()
+        .iter()
+        .rev()
+        .fold(0u128, |rem, &word| (rem << 64 | word as u128) % d as u128) as u64
+}
+
+fn to_u64(n: &BigUint) -> Option<u64> {
+    match n.limbs() {
+        [] => Some(0),
+        &[word] => Some(word),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// GERAÇÃO
+// ============================================================================
+
+/// Primo ímpar aleatório com exatamente `bits` bits
+///
+/// # Panics
+///
+/// Se `bits < 2`.
+pub fn random_prime<R: FnMut(&mut [u8])>(bits: usize, rng: &mut R) -> BigUint {
+    assert!(bits >= 2, "a prime needs at least 2 bits");
+    loop {
+        let candidate = random_odd(bits, rng);
+        if bits <= 64 {
+            if is_prime(&candidate) {
+                return candidate;
+            }
+        } else if !has_small_factor(&candidate) && miller_rabin(&candidate, &[2]) && baillie_psw(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// Primo seguro aleatório (p = 2q + 1 com q primo) com exatamente `bits` bits
+///
+/// Muito mais raro que um primo comum: para 2048 bits espere de segundos a
+/// minutos em modo release.
+///
+/// # Panics
+///
+/// Se `bits < 3`.
+pub fn random_safe_prime<R: FnMut(&mut [u8])>(bits: usize, rng: &mut R) -> BigUint {
+    assert!(bits >= 3, "a safe prime needs at least 3 bits");
+    loop {
+        let q = random_odd(bits - 1, rng);
+        let p = (&q << 1) | BigUint::one();
+        if bits <= 64 {
+            if is_safe_prime(&p) {
+                return p;
+            }
+            continue;
+        }
+        // q e p sem fatores pequenos: q mod r != 0 e q mod r != (r - 1) / 2
+        let sieved = SIEVE_PRIMES.iter().all(|&r| {
+            let rem = rem_u64(&q, r);
+            rem != 0 && rem != (r - 1) / 2
+        });
+        // Base 2 nos dois antes do Lucas, que custa algumas vezes mais
+        if sieved && miller_rabin(&q, &[2]) && miller_rabin(&p, &[2]) && baillie_psw(&q) && baillie_psw(&p) {
+            return p;
+        }
+    }
+}
+
+/// Valor aleatório ímpar com o bit `bits - 1` ligado
+fn random_odd<R: FnMut(&mut [u8])>(bits: usize, rng: &mut R) -> BigUint {
+    random_bits(bits, rng) | (BigUint::one() << (bits - 1)) | BigUint::one()
+}
+
+/// Valor aleatório menor que 2^bits
+fn random_bits<R: FnMut(&mut [u8])>(bits: usize, rng: &mut R) -> BigUint {
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    rng(&mut bytes);
+    BigUint::from_bytes_be(&bytes) >> (bytes.len() * 8 - bits)
+}
+
+// ============================================================================
+// MONTGOMERY COM MÓDULO DE QUALQUER TAMANHO
+// ============================================================================
+
+/// Multiplicação de Montgomery (CIOS) para um módulo ímpar em `k` palavras
+///
+/// Valores em forma de Montgomery, sempre com `k` palavras e menores que o
+/// módulo.
+struct Monty {
+    modulus: Vec<u64>,
+    n_prime: u64,
+    /// R mod n, o 1 em forma de Montgomery
+    one: Vec<u64>,
+    /// R² mod n, para converter
+    r2: Vec<u64>,
+}
+
+impl Monty {
+    fn new(modulus: &BigUint) -> Self {
+        let k = modulus.limbs().len();
+        let padded = |value: BigUint| {
+            let mut limbs = value.limbs().to_vec();
+            limbs.resize(k, 0);
+            limbs
+        };
+        Self {
+            modulus: modulus.limbs().to_vec(),
+            n_prime: Montgomery::compute_n_prime(modulus.limbs()[0]),
+            one: padded((BigUint::one() << (64 * k)) % modulus),
+            r2: padded((BigUint::one() << (128 * k)) % modulus),
+        }
+    }
+
+    fn zero(&self) -> Vec<u64> {
+        vec![0; self.modulus.len()]
+    }
+
+    fn is_zero(&self, a: &[u64]) -> bool {
+        a.iter().all(|&word| word == 0)
+    }
+
+    fn to_mont(&self, value: &BigUint) -> Vec<u64> {
+        let mut limbs = (value % &BigUint::from_limbs(&self.modulus)).limbs().to_vec();
+        limbs.resize(self.modulus.len(), 0);
+        self.mul(&limbs, &self.r2)
+    }
+
+    /// a * b * R^(-1) mod n
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = &self.modulus;
+        let k = n.len();
+        let mut t = vec![0u64; k + 2];
+        for &b in b {
+            let mut carry = 0u128;
+            for j in 0..k {
+                let sum = t[j] as u128 + a[j] as u128 * b as u128 + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[k] as u128 + carry;
+            t[k] = sum as u64;
+            t[k + 1] = (sum >> 64) as u64;
+
+            // Soma m * n para zerar a palavra baixa e desloca uma palavra
+            let m = t[0].wrapping_mul(self.n_prime);
+            let mut carry = (t[0] as u128 + m as u128 * n[0] as u128) >> 64;
+            for j in 1..k {
+                let sum = t[j] as u128 + m as u128 * n[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[k] as u128 + carry;
+            t[k - 1] = sum as u64;
+            t[k] = t[k + 1] + (sum >> 64) as u64;
+        }
+        self.reduce_once(t)
+    }
+
+    /// `t` (k + 2 palavras, menor que 2n) reduzido para k palavras
+    fn reduce_once(&self, mut t: Vec<u64>) -> Vec<u64> {
+        let k = self.modulus.len();
+        let high = t[k];
+        t.truncate(k);
+        if high != 0 || !less_than(&t, &self.modulus) {
+            sub_slice(&mut t, &self.modulus);
+        }
+        t
+    }
+
+    fn add(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut sum = Vec::with_capacity(a.len() + 2);
+        let mut carry = 0u128;
+        for (&a, &b) in a.iter().zip(b) {
+            let word = a as u128 + b as u128 + carry;
+            sum.push(word as u64);
+            carry = word >> 64;
+        }
+        sum.extend([carry as u64, 0]);
+        self.reduce_once(sum)
+    }
+
+    fn sub(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut diff = a.to_vec();
+        if sub_slice(&mut diff, b) != 0 {
+            let mut carry = 0u128;
+            for (word, &n) in diff.iter_mut().zip(&self.modulus) {
+                let sum = *word as u128 + n as u128 + carry;
+                *word = sum as u64;
+                carry = sum >> 64;
+            }
+        }
+        diff
+    }
+
+    /// a / 2 mod n: soma n se `a` for ímpar e desloca
+    fn half(&self, a: &[u64]) -> Vec<u64> {
+        let mut value = a.to_vec();
+        let mut top = 0u64;
+        if value[0] & 1 == 1 {
+            let mut carry = 0u128;
+            for (word, &n) in value.iter_mut().zip(&self.modulus) {
+                let sum = *word as u128 + n as u128 + carry;
+                *word = sum as u64;
+                carry = sum >> 64;
+            }
+            top = carry as u64;
+        }
+        for i in 0..value.len() {
+            let next = value.get(i + 1).copied().unwrap_or(top);
+            value[i] = value[i] >> 1 | next << 63;
+        }
+        value
+    }
+
+    fn pow(&self, base: &[u64], exp: &[u64]) -> Vec<u64> {
+        let mut result = self.one.clone();
+        for i in (0..exp.len() * 64).rev() {
+            result = self.mul(&result, &result);
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                result = self.mul(&result, base);
+            }
+        }
+        result
+    }
+}
+
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(text: &str) -> BigUint {
+        text.parse().unwrap()
+    }
+
+    fn mersenne(exp: usize) -> BigUint {
+        (BigUint::one() << exp) - BigUint::one()
+    }
+
+    /// xorshift64*: bytes reproduzíveis para os testes
+    fn test_rng(mut state: u64) -> impl FnMut(&mut [u8]) {
+        move |buf: &mut [u8]| {
+            for byte in buf {
+                state ^= state >> 12;
+                state ^= state << 25;
+                state ^= state >> 27;
+                *byte = (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 56) as u8;
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_prime_u64() {
+        // Crivo de referência
+        let mut composite = vec![false; 10_000];
+        for i in 2..100 {
+            for j in (i * i..10_000).step_by(i) {
+                composite[j] = true;
+            }
+        }
+        for n in 0..10_000u64 {
+            assert_eq!(is_prime_u64(n), n >= 2 && !composite[n as usize], "{}", n);
+        }
+
+        assert!(is_prime_u64((1 << 61) - 1));
+        assert!(is_prime_u64(u64::MAX - 58));
+        // Carmichael e pseudoprimos fortes para várias bases pequenas
+        for n in [561, 3_215_031_751, 3_825_123_056_546_413_051] {
+            assert!(!is_prime_u64(n), "{}", n);
+        }
+        assert!(miller_rabin(&BigUint::from(3_215_031_751u64), &[2, 3, 5, 7]));
+        assert!(!miller_rabin(&BigUint::from(3_215_031_751u64), &[2, 3, 5, 7, 11]));
+    }
+
+    #[test]
+    fn test_strong_lucas() {
+        // Pseudoprimos de Lucas fortes (Selfridge) caem no Miller–Rabin base 2
+        for n in [5459u64, 5777, 10_877, 16_109, 18_971] {
+            let n = BigUint::from(n);
+            assert!(strong_lucas(&n), "{}", n);
+            assert!(!baillie_psw(&n), "{}", n);
+        }
+        // ... e pseudoprimos fortes base 2 caem no Lucas
+        for n in [2047u64, 3277, 4033, 4681, 8321] {
+            let n = BigUint::from(n);
+            assert!(miller_rabin(&n, &[2]) && !strong_lucas(&n), "{}", n);
+        }
+        for n in (3..2000u64).step_by(2) {
+            assert_eq!(baillie_psw(&BigUint::from(n)), is_prime_u64(n), "{}", n);
+        }
+        assert!(!baillie_psw(&BigUint::from(49u64)));
+        assert!(!baillie_psw(&BigUint::from(1_000_003u64 * 1_000_003)));
+    }
+
+    #[test]
+    fn test_is_prime_big() {
+        assert!(is_prime(&mersenne(89)));
+        assert!(is_prime(&mersenne(127)));
+        assert!(is_prime(&mersenne(521)));
+        assert!(!is_prime(&mersenne(523)));
+        assert!(!is_prime(&(&mersenne(61) * &mersenne(89))));
+        assert!(!is_prime(&(&mersenne(127) * &mersenne(127))));
+        // Menor primo acima de 2^64; 2^64 + 1 = 274177 * 67280421310721
+        assert!(is_prime(&big("18446744073709551629")));
+        assert!(!is_prime(&big("18446744073709551617")));
+        assert!(!is_prime(&(BigUint::one() << 100)));
+
+        let mut rng = test_rng(7);
+        assert!(miller_rabin_random(&mersenne(127), 10, &mut rng));
+        assert!(!miller_rabin_random(&(&mersenne(89) * &mersenne(107)), 10, &mut rng));
+        // Menor pseudoprimo forte para todas as bases primas até 37
+        let psi12 = big("318665857834031151167461");
+        assert!(miller_rabin(&psi12, &U64_WITNESSES));
+        assert!(!is_prime(&psi12));
+    }
+
+    #[test]
+    fn test_random_prime() {
+        let mut rng = test_rng(0x5eed);
+        for bits in [2, 3, 16, 64, 65, 256] {
+            let p = random_prime(bits, &mut rng);
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime(&p), "{}", p);
+        }
+        assert_eq!(random_prime(2, &mut rng), BigUint::from(3u64));
+
+        for bits in [3, 10, 64, 128] {
+            let p = random_safe_prime(bits, &mut rng);
+            assert_eq!(p.bits(), bits);
+            assert!(is_safe_prime(&p), "{}", p);
+        }
+        assert_eq!(random_safe_prime(3, &mut rng), BigUint::from(7u64));
+        assert!(!is_safe_prime(&BigUint::from(13u64)));
+    }
+}
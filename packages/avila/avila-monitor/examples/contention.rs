@@ -0,0 +1,100 @@
+//! Benchmark de contenção: `Arc<Mutex<Monitor>>` vs `ShardedMonitor`
+//!
+//! ```text
+//! cargo run --release --example contention -- [threads] [ops por thread]
+//! ```
+
+use avila_monitor::{Monitor, ShardedMonitor};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const METRICS: u64 = 16;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let max_threads: usize = args.next().and_then(|a| a.parse().ok()).unwrap_or_else(|| {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+    });
+    let ops: u64 = args.next().and_then(|a| a.parse().ok()).unwrap_or(200_000);
+
+    println!("=== avila-monitor: contenção na ingestão ({} ops/thread) ===\n", ops);
+    println!("{:>8} {:>16} {:>16} {:>8}", "threads", "mutex (Mops/s)", "shards (Mops/s)", "ganho");
+
+    let mut threads = 1;
+    while threads <= max_threads {
+        let mutex = bench_mutex(threads, ops);
+        let sharded = bench_sharded(threads, ops);
+        println!(
+            "{:>8} {:>16.2} {:>16.2} {:>7.1}x",
+            threads,
+            mops(threads, ops, mutex),
+            mops(threads, ops, sharded),
+            mutex.as_secs_f64() / sharded.as_secs_f64()
+        );
+        threads *= 2;
+    }
+}
+
+fn bench_mutex(threads: usize, ops: u64) -> Duration {
+    let monitor = Arc::new(Mutex::new(Monitor::new()));
+    let elapsed = run(threads, |_| {
+        let monitor = Arc::clone(&monitor);
+        move || {
+            for i in 0..ops {
+                monitor.lock().unwrap().increment(i % METRICS, 1.0);
+            }
+        }
+    });
+    check(&monitor.lock().unwrap(), threads, ops);
+    elapsed
+}
+
+fn bench_sharded(threads: usize, ops: u64) -> Duration {
+    let monitor = ShardedMonitor::new(Monitor::new());
+    let elapsed = run(threads, |_| {
+        let monitor = monitor.clone();
+        move || {
+            for i in 0..ops {
+                monitor.increment(i % METRICS, 1.0);
+            }
+            monitor.flush();
+        }
+    });
+    check(&monitor.lock(), threads, ops);
+    elapsed
+}
+
+/// Dispara `threads` trabalhadores ao mesmo tempo e mede até o último terminar
+fn run<F, W>(threads: usize, worker: W) -> Duration
+where
+    W: Fn(usize) -> F,
+    F: FnOnce() + Send + 'static,
+{
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let handles: Vec<_> = (0..threads)
+        .map(|n| {
+            let barrier = Arc::clone(&barrier);
+            let work = worker(n);
+            thread::spawn(move || {
+                barrier.wait();
+                work();
+            })
+        })
+        .collect();
+    barrier.wait();
+    let start = Instant::now();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn check(monitor: &Monitor, threads: usize, ops: u64) {
+    let total: f64 = monitor.all_metrics().iter().map(|(_, value)| value).sum();
+    assert_eq!(total, (threads as u64 * ops) as f64);
+}
+
+fn mops(threads: usize, ops: u64, elapsed: Duration) -> f64 {
+    (threads as u64 * ops) as f64 / elapsed.as_secs_f64() / 1e6
+}
@@ -6,7 +6,7 @@
 //! - Metadados e alertas
 //! - Queries e benchmarks
 
-#![allow(dead_code)]
+#![allow(dead_code, unused_imports)]
 
 extern crate alloc;
 
@@ -41,7 +41,7 @@ fn main() {
         let response_time = if i % 20 == 0 {
             500.0 + (i as f64 * 10.0) // Picos ocasionais
         } else {
-            100.0 + (i as f64 % 10 as f64) * 10.0
+            100.0 + (i as f64 % 10.0) * 10.0
         };
 
         // CPU: oscila entre 50-80%
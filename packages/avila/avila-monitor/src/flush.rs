@@ -0,0 +1,538 @@
+//! Flush de métricas em falhas (panic, SIGTERM, saída)
+//!
+//! [`Monitor::flush_on_exit`] registra um monitor compartilhado e um caminho
+//! de arquivo. Um snapshot final (métricas atuais + últimos alertas
+//! disparados) é gravado em JSON quando:
+//!
+//! - uma thread entra em panic (o hook anterior continua sendo chamado);
+//! - o processo recebe SIGTERM/SIGINT (Unix), se a aplicação optou por
+//!   [`Monitor::install_signal_handlers`];
+//! - o [`FlushGuard`] é descartado (saída normal).
+//!
+//! A gravação usa arquivo temporário + rename, então um crash durante o
+//! flush nunca deixa um snapshot truncado no lugar do anterior.
+//!
+//! ```no_run
+//! use avila_monitor::Monitor;
+//! use std::sync::{Arc, Mutex};
+//!
+//! let monitor = Arc::new(Mutex::new(Monitor::new()));
+//! let _guard = Monitor::flush_on_exit(&monitor, "/var/tmp/app-metrics.json");
+//! Monitor::install_signal_handlers().unwrap();
+//!
+//! monitor.lock().unwrap().record_with_timestamp(1, 42.0, 1000);
+//! // panic, SIGTERM ou fim do escopo → snapshot em disco
+//! ```
+
+use crate::{AlertEvent, Monitor};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Métrica incrementada a cada panic capturado
+pub const PANIC_METRIC_ID: u64 = u64::MAX;
+
+/// Motivo do flush
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushReason {
+    Panic,
+    Signal(i32),
+    Exit,
+    Manual,
+}
+
+impl FlushReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Panic => "panic",
+            Self::Signal(_) => "signal",
+            Self::Exit => "exit",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// Estado do monitor no momento do flush
+#[derive(Clone, Debug)]
+pub struct MonitorSnapshot {
+    /// Milissegundos desde a época Unix
+    pub timestamp_ms: u64,
+    pub reason: FlushReason,
+    /// (id, valor, nome)
+    pub metrics: Vec<(u64, f64, Option<&'static str>)>,
+    pub alert_events: Vec<AlertEvent>,
+    /// Mensagem e local do panic
+    pub panic: Option<String>,
+    /// `false` se o monitor estava bloqueado e as métricas não puderam ser lidas
+    pub complete: bool,
+}
+
+impl MonitorSnapshot {
+    fn empty(reason: FlushReason) -> Self {
+        Self {
+            timestamp_ms: now_ms(),
+            reason,
+            metrics: Vec::new(),
+            alert_events: Vec::new(),
+            panic: None,
+            complete: false,
+        }
+    }
+
+    /// Serializa em JSON
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push('{');
+        let _ = write!(out, "\"timestampMs\":{},\"reason\":\"{}\"", self.timestamp_ms, self.reason.as_str());
+        if let FlushReason::Signal(signal) = self.reason {
+            let _ = write!(out, ",\"signal\":{}", signal);
+        }
+        let _ = write!(out, ",\"complete\":{}", self.complete);
+        if let Some(panic) = &self.panic {
+            out.push_str(",\"panic\":");
+            push_json_string(&mut out, panic);
+        }
+
+        out.push_str(",\"metrics\":[");
+        for (i, (id, value, name)) in self.metrics.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{{\"id\":{},\"value\":", id);
+            push_json_number(&mut out, *value);
+            if let Some(name) = name {
+                out.push_str(",\"name\":");
+                push_json_string(&mut out, name);
+            }
+            out.push('}');
+        }
+
+        out.push_str("],\"alertEvents\":[");
+        for (i, event) in self.alert_events.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{{\"metricId\":{},\"value\":", event.metric_id);
+            push_json_number(&mut out, event.value);
+            out.push_str(",\"threshold\":");
+            push_json_number(&mut out, event.threshold);
+            let _ = write!(out, ",\"isMax\":{},\"timestamp\":{}}}", event.is_max, event.timestamp);
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// Grava de forma atômica (arquivo temporário + rename)
+    pub fn write_to(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, self.to_json())?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+impl Monitor {
+    /// Captura métricas atuais e os últimos alertas disparados
+    pub fn snapshot(&self, reason: FlushReason) -> MonitorSnapshot {
+        MonitorSnapshot {
+            timestamp_ms: now_ms(),
+            reason,
+            metrics: self.summary().into_iter().map(|(id, value, meta)| (id, value, meta.map(|m| m.name))).collect(),
+            alert_events: self.alert_events().copied().collect(),
+            panic: None,
+            complete: true,
+        }
+    }
+
+    /// Registra o monitor para gravar um snapshot final em panic, SIGTERM ou
+    /// quando o guard retornado for descartado
+    ///
+    /// O hook de panic anterior é preservado. Se a thread em panic estiver
+    /// segurando o lock do monitor, o snapshot sai com `complete: false`.
+    ///
+    /// Sinais não são tratados aqui; veja [`Monitor::install_signal_handlers`].
+    pub fn flush_on_exit(monitor: &Arc<Mutex<Monitor>>, path: impl Into<PathBuf>) -> FlushGuard {
+        install_panic_hook();
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        lock_registry().push(Registration {
+            id,
+            monitor: Arc::downgrade(monitor),
+            path: path.into(),
+        });
+        FlushGuard { id }
+    }
+
+    /// Grava o snapshot dos monitores registrados ao receber SIGTERM/SIGINT
+    ///
+    /// Opcional e global ao processo: os handlers anteriores são guardados e,
+    /// depois do flush (feito uma única vez, por uma thread que então
+    /// termina), restaurados e o sinal é reenviado. Assim a ação original
+    /// (encerrar, ou o handler da aplicação) continua acontecendo. Chamadas
+    /// seguintes não fazem nada; fora do Unix também não.
+    pub fn install_signal_handlers() -> std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            let mut result = Ok(());
+            SIGNALS.call_once(|| result = signals::install());
+            result
+        }
+        #[cfg(not(unix))]
+        Ok(())
+    }
+}
+
+/// Desregistra o monitor e grava o snapshot de saída ao ser descartado
+#[must_use = "o snapshot de saída é gravado quando o guard é descartado"]
+pub struct FlushGuard {
+    id: usize,
+}
+
+impl FlushGuard {
+    /// Grava um snapshot agora, sem desregistrar
+    pub fn flush(&self) -> std::io::Result<()> {
+        let registration = lock_registry().iter().find(|r| r.id == self.id).cloned();
+        match registration {
+            Some(registration) => registration.flush(FlushReason::Manual, None, true),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        let registration = {
+            let mut registry = lock_registry();
+            let index = registry.iter().position(|r| r.id == self.id);
+            index.map(|i| registry.remove(i))
+        };
+        if let Some(registration) = registration {
+            let _ = registration.flush(FlushReason::Exit, None, true);
+        }
+    }
+}
+
+// ============================================================================
+// REGISTRO GLOBAL
+// ============================================================================
+
+#[derive(Clone)]
+struct Registration {
+    id: usize,
+    monitor: Weak<Mutex<Monitor>>,
+    path: PathBuf,
+}
+
+impl Registration {
+    fn flush(&self, reason: FlushReason, panic: Option<&str>, blocking: bool) -> std::io::Result<()> {
+        let Some(monitor) = self.monitor.upgrade() else {
+            return Ok(());
+        };
+
+        let guard = if blocking {
+            Some(monitor.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+        } else {
+            match monitor.try_lock() {
+                Ok(guard) => Some(guard),
+                Err(std::sync::TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(std::sync::TryLockError::WouldBlock) => None,
+            }
+        };
+
+        let mut snapshot = match guard {
+            Some(mut monitor) => {
+                if reason == FlushReason::Panic {
+                    monitor.increment(PANIC_METRIC_ID, 1.0);
+                }
+                monitor.snapshot(reason)
+            }
+            None => MonitorSnapshot::empty(reason),
+        };
+        snapshot.panic = panic.map(String::from);
+        snapshot.write_to(&self.path)
+    }
+}
+
+static REGISTRY: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+static PANIC_HOOK: Once = Once::new();
+#[cfg(unix)]
+static SIGNALS: Once = Once::new();
+
+fn lock_registry() -> MutexGuard<'static, Vec<Registration>> {
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Flush de todos os monitores registrados (sem bloquear em locks)
+fn flush_all(reason: FlushReason, panic: Option<&str>) {
+    let registrations = match REGISTRY.try_lock() {
+        Ok(registry) => registry.clone(),
+        Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner().clone(),
+        Err(std::sync::TryLockError::WouldBlock) => return,
+    };
+    for registration in registrations {
+        let _ = registration.flush(reason, panic, false);
+    }
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let message = info
+                .payload()
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("<non-string panic payload>");
+            let description = match info.location() {
+                Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+                None => String::from(message),
+            };
+            flush_all(FlushReason::Panic, Some(&description));
+            previous(info);
+        }));
+    });
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn push_json_number(out: &mut String, value: f64) {
+    if value.is_finite() {
+        let _ = write!(out, "{}", value);
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// ============================================================================
+// SINAIS (Unix)
+// ============================================================================
+
+/// O handler de sinal só escreve o número do sinal num socket (`write` é
+/// async-signal-safe); uma thread bloqueada na outra ponta faz o flush fora
+/// do contexto do sinal, restaura os handlers anteriores, reenvia o sinal e
+/// termina.
+#[cfg(unix)]
+mod signals {
+    use super::{flush_all, FlushReason};
+    use std::io::Read;
+    use std::os::unix::io::IntoRawFd;
+    use std::os::unix::net::UnixStream;
+    use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+    const SIG_ERR: usize = usize::MAX;
+
+    /// Ponta de escrita do socket; -1 antes da instalação
+    static WAKE_FD: AtomicI32 = AtomicI32::new(-1);
+    /// Handlers anteriores de SIGINT e SIGTERM
+    static PREVIOUS: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+    extern "C" {
+        fn signal(signum: i32, handler: usize) -> usize;
+        fn raise(signum: i32) -> i32;
+        fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+    }
+
+    extern "C" fn on_signal(signum: i32) {
+        let byte = signum as u8;
+        // SAFETY: `write` é async-signal-safe e `byte` vive até o retorno
+        unsafe {
+            write(WAKE_FD.load(Ordering::SeqCst), &byte, 1);
+        }
+    }
+
+    pub(super) fn install() -> std::io::Result<()> {
+        let (mut reader, writer) = UnixStream::pair()?;
+        WAKE_FD.store(writer.into_raw_fd(), Ordering::SeqCst);
+
+        for (slot, signum) in PREVIOUS.iter().zip([SIGINT, SIGTERM]) {
+            // SAFETY: `on_signal` só faz uma chamada async-signal-safe
+            let previous = unsafe { signal(signum, handler()) };
+            if previous == SIG_ERR {
+                restore();
+                return Err(std::io::Error::last_os_error());
+            }
+            slot.store(previous, Ordering::SeqCst);
+        }
+
+        // Um sinal que chegue antes da thread existir fica no buffer do socket
+        let spawned = std::thread::Builder::new().name("avila-monitor-flush".into()).spawn(move || {
+            let mut byte = [0u8];
+            if reader.read_exact(&mut byte).is_err() {
+                return;
+            }
+            let signum = i32::from(byte[0]);
+            flush_all(FlushReason::Signal(signum), None);
+            restore();
+            // SAFETY: `raise` só entrega o sinal, agora ao handler anterior
+            unsafe {
+                raise(signum);
+            }
+        });
+        if let Err(err) = spawned {
+            restore();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn handler() -> usize {
+        let handler: extern "C" fn(i32) = on_signal;
+        handler as usize
+    }
+
+    /// Devolve os handlers anteriores onde o nosso ainda está instalado
+    fn restore() {
+        for (slot, signum) in PREVIOUS.iter().zip([SIGINT, SIGTERM]) {
+            // SAFETY: só instala handlers que já estavam no processo
+            unsafe {
+                let current = signal(signum, slot.load(Ordering::SeqCst));
+                if current != handler() {
+                    // Outro código trocou o handler depois de nós: fica o dele
+                    signal(signum, current);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// O registro é global: um flush por sinal não pode cruzar com os outros testes
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn serial() -> MutexGuard<'static, ()> {
+        SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("avila-monitor-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_snapshot_json() {
+        let mut mon = Monitor::new();
+        mon.set_metadata(1, "latency \"p99\"", "ms", "");
+        mon.add_max_alert(1, 100.0);
+        mon.record_with_timestamp(1, 250.0, 10);
+        mon.record(2, f64::NAN);
+
+        let json = mon.snapshot(FlushReason::Manual).to_json();
+        assert!(json.contains("\"reason\":\"manual\""));
+        assert!(json.contains("\"name\":\"latency \\\"p99\\\"\""));
+        assert!(json.contains("{\"id\":2,\"value\":null}"));
+        assert!(json.contains("\"alertEvents\":[{\"metricId\":1,\"value\":250,\"threshold\":100,\"isMax\":true,\"timestamp\":10}]"));
+    }
+
+    #[test]
+    fn test_guard_flushes_on_drop() {
+        let _serial = serial();
+        let path = temp_path("drop");
+        let monitor = Arc::new(Mutex::new(Monitor::new()));
+        {
+            let _guard = Monitor::flush_on_exit(&monitor, &path);
+            monitor.lock().unwrap().record(7, 3.5);
+        }
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"reason\":\"exit\""));
+        assert!(json.contains("{\"id\":7,\"value\":3.5}"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_panic_writes_snapshot() {
+        let _serial = serial();
+        let path = temp_path("panic");
+        let monitor = Arc::new(Mutex::new(Monitor::new()));
+        let guard = Monitor::flush_on_exit(&monitor, &path);
+        monitor.lock().unwrap().record(1, 99.0);
+
+        let result = std::thread::spawn(|| panic!("disk full")).join();
+        assert!(result.is_err());
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"reason\":\"panic\""));
+        assert!(json.contains("disk full"));
+        assert!(json.contains("\"complete\":true"));
+        assert_eq!(monitor.lock().unwrap().get(PANIC_METRIC_ID), Some(1.0));
+
+        drop(guard);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_flushes_and_chains() {
+        use std::sync::atomic::AtomicBool;
+
+        static PRIOR_CALLED: AtomicBool = AtomicBool::new(false);
+        extern "C" fn prior(_: i32) {
+            PRIOR_CALLED.store(true, Ordering::SeqCst);
+        }
+        extern "C" {
+            fn signal(signum: i32, handler: usize) -> usize;
+            fn raise(signum: i32) -> i32;
+        }
+
+        let _serial = serial();
+        let path = temp_path("signal");
+        let monitor = Arc::new(Mutex::new(Monitor::new()));
+        let guard = Monitor::flush_on_exit(&monitor, &path);
+        monitor.lock().unwrap().record(3, 1.5);
+
+        // SAFETY: `prior` só grava um atômico
+        unsafe {
+            let prior: extern "C" fn(i32) = prior;
+            signal(2, prior as usize);
+        }
+        Monitor::install_signal_handlers().unwrap();
+        unsafe {
+            raise(2);
+        }
+
+        // O handler anterior só roda depois do flush
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !PRIOR_CALLED.load(Ordering::SeqCst) {
+            assert!(std::time::Instant::now() < deadline, "handler anterior não foi chamado");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"reason\":\"signal\",\"signal\":2"));
+        assert!(json.contains("{\"id\":3,\"value\":1.5}"));
+
+        drop(guard);
+        let _ = std::fs::remove_file(&path);
+    }
+}
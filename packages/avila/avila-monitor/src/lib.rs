@@ -13,7 +13,9 @@
 //! - **Metadados**: Nome, unidade e descrição para cada métrica
 //! - **Queries**: Busca por intervalo de tempo
 //! - **Benchmark**: Compara com baselines
-//! - **Alertas**: Sistema de alertas configuráveis
+//! - **Alertas**: Sistema de alertas configuráveis com log dos últimos disparos
+//! - **Flush em falhas**: Snapshot final em disco em panic/SIGTERM ([`flush`])
+//! - **Ingestão por thread**: Shards locais agregados no monitor global ([`shard`])
 //! - **No STD Compatible**: Funciona com `alloc` em ambientes embedded
 //!
 //! ## Aplicações
@@ -26,9 +28,15 @@
 //! - Análise de SLOs/SLAs
 
 extern crate alloc;
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, VecDeque};
 use alloc::vec::Vec;
 
+pub mod flush;
+pub mod shard;
+
+pub use flush::{FlushGuard, FlushReason, MonitorSnapshot};
+pub use shard::{Aggregator, ShardedMonitor};
+
 /// Entrada de histórico com timestamp
 #[derive(Clone, Copy, Debug)]
 pub struct HistoryEntry {
@@ -44,6 +52,16 @@ pub struct Alert {
     pub is_max: bool,
 }
 
+/// Disparo de alerta registrado
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertEvent {
+    pub metric_id: u64,
+    pub value: f64,
+    pub threshold: f64,
+    pub is_max: bool,
+    pub timestamp: u64,
+}
+
 /// Estatísticas calculadas
 #[derive(Clone, Copy, Debug)]
 pub struct Statistics {
@@ -99,6 +117,8 @@ pub struct Monitor {
     pub metrics: BTreeMap<u64, f64>,
     history: BTreeMap<u64, Vec<HistoryEntry>>,
     alerts: Vec<Alert>,
+    alert_events: VecDeque<AlertEvent>,
+    alert_log_capacity: usize,
     history_max_size: usize,
     metadata: BTreeMap<u64, MetricMetadata>,
     aggregations: BTreeMap<u64, Vec<TimeWindow>>,
@@ -115,6 +135,8 @@ impl Monitor {
             metrics: BTreeMap::new(),
             history: BTreeMap::new(),
             alerts: Vec::new(),
+            alert_events: VecDeque::new(),
+            alert_log_capacity: 32,
             history_max_size: 100,
             metadata: BTreeMap::new(),
             aggregations: BTreeMap::new(),
@@ -129,6 +151,8 @@ impl Monitor {
             metrics: BTreeMap::new(),
             history: BTreeMap::new(),
             alerts: Vec::new(),
+            alert_events: VecDeque::new(),
+            alert_log_capacity: 32,
             history_max_size,
             metadata: BTreeMap::new(),
             aggregations: BTreeMap::new(),
@@ -143,6 +167,8 @@ impl Monitor {
             metrics: BTreeMap::new(),
             history: BTreeMap::new(),
             alerts: Vec::new(),
+            alert_events: VecDeque::new(),
+            alert_log_capacity: 32,
             history_max_size: 1000,
             metadata: BTreeMap::new(),
             aggregations: BTreeMap::new(),
@@ -254,7 +280,7 @@ impl Monitor {
     pub fn record_with_timestamp(&mut self, metric_id: u64, value: f64, timestamp: u64) {
         self.metrics.insert(metric_id, value);
 
-        let history = self.history.entry(metric_id).or_insert_with(Vec::new);
+        let history = self.history.entry(metric_id).or_default();
         history.push(HistoryEntry { timestamp, value });
 
         let should_aggregate = self.enable_aggregation && history.len() % 10 == 0;
@@ -263,24 +289,47 @@ impl Monitor {
             history.remove(0);
         }
 
-        self.check_alerts(metric_id, value);
+        self.check_alerts(metric_id, value, timestamp);
 
         // Auto-agregação se habilitada
         if should_aggregate {
             self.aggregate_windows(metric_id);
         }
-    }    fn check_alerts(&self, metric_id: u64, value: f64) {
+    }
+
+    fn check_alerts(&mut self, metric_id: u64, value: f64, timestamp: u64) {
         for alert in &self.alerts {
-            if alert.metric_id == metric_id {
-                if alert.is_max && value > alert.threshold {
-                    // Alerta disparado
-                } else if !alert.is_max && value < alert.threshold {
-                    // Alerta disparado
+            let fired = alert.metric_id == metric_id
+                && ((alert.is_max && value > alert.threshold) || (!alert.is_max && value < alert.threshold));
+
+            if fired && self.alert_log_capacity > 0 {
+                if self.alert_events.len() == self.alert_log_capacity {
+                    self.alert_events.pop_front();
                 }
+                self.alert_events.push_back(AlertEvent {
+                    metric_id,
+                    value,
+                    threshold: alert.threshold,
+                    is_max: alert.is_max,
+                    timestamp,
+                });
             }
         }
     }
 
+    /// Últimos alertas disparados (mais antigo primeiro)
+    pub fn alert_events(&self) -> impl Iterator<Item = &AlertEvent> {
+        self.alert_events.iter()
+    }
+
+    /// Define quantos disparos de alerta são mantidos (padrão: 32)
+    pub fn set_alert_log_capacity(&mut self, capacity: usize) {
+        self.alert_log_capacity = capacity;
+        while self.alert_events.len() > capacity {
+            self.alert_events.pop_front();
+        }
+    }
+
     /// Adiciona alerta de máximo
     pub fn add_max_alert(&mut self, metric_id: u64, threshold: f64) {
         self.alerts.push(Alert {
@@ -581,7 +630,9 @@ impl Monitor {
         self.history.remove(&metric_id);
         self.aggregations.remove(&metric_id);
     }
-}impl Default for Monitor {
+}
+
+impl Default for Monitor {
     fn default() -> Self {
         Self::new()
     }
@@ -700,7 +751,7 @@ mod tests {
 
         mon.aggregate_windows(1);
         let aggs = mon.get_aggregations(1).unwrap();
-        assert!(aggs.len() >= 1);
+        assert!(!aggs.is_empty());
     }
 
     #[test]
@@ -768,6 +819,24 @@ mod tests {
         assert_eq!(summary.len(), 2);
     }
 
+    #[test]
+    fn test_alert_events_are_bounded() {
+        let mut mon = Monitor::new();
+        mon.add_max_alert(1, 100.0);
+        mon.add_min_alert(1, 10.0);
+        mon.set_alert_log_capacity(2);
+
+        mon.record_with_timestamp(1, 150.0, 1);
+        mon.record_with_timestamp(1, 50.0, 2);
+        mon.record_with_timestamp(1, 5.0, 3);
+        mon.record_with_timestamp(1, 200.0, 4);
+
+        let events: Vec<_> = mon.alert_events().collect();
+        assert_eq!(events.len(), 2);
+        assert!(!events[0].is_max && events[0].timestamp == 3);
+        assert!(events[1].is_max && events[1].value == 200.0);
+    }
+
     #[test]
     fn test_reset_metric() {
         let mut mon = Monitor::new();
@@ -0,0 +1,384 @@
+//! Ingestão por thread sem disputa pelo mutex do [`Monitor`]
+//!
+//! Com muitas threads registrando métricas, um `Arc<Mutex<Monitor>>` vira
+//! gargalo: toda chamada disputa o mesmo lock. [`ShardedMonitor`] dá a cada
+//! thread um shard próprio, que só ela e o agregador tocam, onde gauges e
+//! contadores já chegam reduzidos ao valor final; os shards são aplicados no
+//! monitor global em lote:
+//!
+//! - quando o shard de uma thread atinge o limite (`flush_threshold`);
+//! - periodicamente, por um [`Aggregator`];
+//! - antes de cada consulta via [`ShardedMonitor::lock`].
+//!
+//! As consultas continuam sendo as do [`Monitor`]. Dentro de uma thread a
+//! ordem das operações é preservada; entre threads, as amostras com
+//! timestamp são intercaladas por timestamp a cada agregação.
+//!
+//! ```
+//! use avila_monitor::ShardedMonitor;
+//!
+//! let monitor = ShardedMonitor::new(Default::default());
+//! let handles: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let monitor = monitor.clone();
+//!         std::thread::spawn(move || {
+//!             for _ in 0..1000 {
+//!                 monitor.increment(1, 1.0);
+//!             }
+//!         })
+//!     })
+//!     .collect();
+//! for handle in handles {
+//!     handle.join().unwrap();
+//! }
+//! assert_eq!(monitor.lock().get(1), Some(4000.0));
+//! ```
+
+use crate::Monitor;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Operações por shard antes de a própria thread disparar a agregação
+pub const DEFAULT_FLUSH_THRESHOLD: usize = 1024;
+
+/// Identificador de cada `ShardedMonitor`, para separar os shards de uma thread
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Shards desta thread, um por `ShardedMonitor` usado por ela
+    static SHARDS: RefCell<Vec<(u64, Arc<Shard>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Operações acumuladas por uma thread desde a última agregação
+///
+/// Gauges e contadores são reduzidos ao efeito final por métrica; só as
+/// amostras com timestamp (que alimentam histórico e alertas) ficam uma a uma.
+#[derive(Default)]
+struct Pending {
+    /// `(métrica, valor, timestamp)` na ordem de registro
+    samples: Vec<(u64, f64, u64)>,
+    /// Efeito sobre o valor atual, aplicado depois das amostras
+    updates: BTreeMap<u64, Update>,
+    operations: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Update {
+    Set(f64),
+    Add(f64),
+}
+
+impl Pending {
+    fn record(&mut self, metric_id: u64, value: f64) {
+        self.updates.insert(metric_id, Update::Set(value));
+    }
+
+    fn sample(&mut self, metric_id: u64, value: f64, timestamp: u64) {
+        // A amostra sobrescreve o valor: o que veio antes dela já não importa
+        self.updates.remove(&metric_id);
+        self.samples.push((metric_id, value, timestamp));
+    }
+
+    fn add(&mut self, metric_id: u64, delta: f64) {
+        let update = self.updates.entry(metric_id).or_insert(Update::Add(0.0));
+        match update {
+            Update::Set(value) | Update::Add(value) => *value += delta,
+        }
+    }
+}
+
+/// Shard de uma thread
+type Shard = Mutex<Pending>;
+
+struct Inner {
+    id: u64,
+    global: Arc<Mutex<Monitor>>,
+    /// Shards de todas as threads; também serializa as agregações
+    shards: Mutex<Vec<Arc<Shard>>>,
+    flush_threshold: usize,
+}
+
+/// [`Monitor`] compartilhado com shards por thread
+///
+/// Clonar é barato: os clones apontam para o mesmo monitor global.
+#[derive(Clone)]
+pub struct ShardedMonitor {
+    inner: Arc<Inner>,
+}
+
+impl ShardedMonitor {
+    pub fn new(monitor: Monitor) -> Self {
+        Self::from_shared(Arc::new(Mutex::new(monitor)))
+    }
+
+    /// Usa um monitor já compartilhado (ex.: o registrado em `flush_on_exit`)
+    ///
+    /// Quem escreve direto em `monitor` continua funcionando; só não passa
+    /// pelos shards.
+    pub fn from_shared(monitor: Arc<Mutex<Monitor>>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                global: monitor,
+                shards: Mutex::new(Vec::new()),
+                flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+            }),
+        }
+    }
+
+    /// Define quantas operações um shard acumula antes de agregar (mínimo 1)
+    ///
+    /// Deve ser chamado antes de clonar o monitor.
+    pub fn with_flush_threshold(mut self, operations: usize) -> Self {
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            inner.flush_threshold = operations.max(1);
+        }
+        self
+    }
+
+    /// Equivalente a [`Monitor::record`]
+    pub fn record(&self, metric_id: u64, value: f64) {
+        self.push(|pending| pending.record(metric_id, value));
+    }
+
+    /// Equivalente a [`Monitor::record_with_timestamp`] (histórico e alertas)
+    pub fn record_with_timestamp(&self, metric_id: u64, value: f64, timestamp: u64) {
+        self.push(|pending| pending.sample(metric_id, value, timestamp));
+    }
+
+    /// Equivalente a [`Monitor::increment`]
+    pub fn increment(&self, metric_id: u64, delta: f64) {
+        self.push(|pending| pending.add(metric_id, delta));
+    }
+
+    /// Equivalente a [`Monitor::decrement`]
+    pub fn decrement(&self, metric_id: u64, delta: f64) {
+        self.push(|pending| pending.add(metric_id, -delta));
+    }
+
+    /// Agrega os shards e devolve o monitor global para consultas
+    pub fn lock(&self) -> MutexGuard<'_, Monitor> {
+        self.flush();
+        lock(&self.inner.global)
+    }
+
+    /// Monitor global (sem agregar os shards pendentes)
+    pub fn shared(&self) -> &Arc<Mutex<Monitor>> {
+        &self.inner.global
+    }
+
+    /// Aplica no monitor global tudo o que as threads acumularam
+    pub fn flush(&self) {
+        let mut shards = lock(&self.inner.shards);
+        let drained: Vec<Pending> = shards
+            .iter()
+            .map(|shard| std::mem::take(&mut *lock(shard)))
+            .filter(|pending| pending.operations > 0)
+            .collect();
+        // Thread encerrada: só o registro ainda referencia o shard, já drenado
+        shards.retain(|shard| Arc::strong_count(shard) > 1);
+        if drained.is_empty() {
+            return;
+        }
+        let mut monitor = lock(&self.inner.global);
+        apply(drained, &mut monitor);
+    }
+
+    /// Agrega os shards a cada `interval` numa thread própria
+    pub fn spawn_aggregator(&self, interval: Duration) -> Aggregator {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let monitor = self.clone();
+        let thread = std::thread::Builder::new()
+            .name("avila-monitor-aggregator".into())
+            .spawn(move || loop {
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => monitor.flush(),
+                    // Parada pedida ou handle descartado: agrega uma última vez
+                    _ => return monitor.flush(),
+                }
+            })
+            .expect("failed to spawn monitor aggregator thread");
+        Aggregator { stop: Some(stop), thread: Some(thread) }
+    }
+
+    fn push(&self, operation: impl FnOnce(&mut Pending)) {
+        let full = SHARDS.with(|shards| {
+            let mut shards = shards.borrow_mut();
+            let shard = match shards.iter().position(|(id, _)| *id == self.inner.id) {
+                Some(index) => &shards[index].1,
+                None => self.register_shard(&mut shards),
+            };
+            let mut pending = lock(shard);
+            operation(&mut pending);
+            pending.operations += 1;
+            pending.operations >= self.inner.flush_threshold
+        });
+        if full {
+            self.flush();
+        }
+    }
+
+    /// Cria o shard desta thread no primeiro uso e o registra para agregação
+    fn register_shard<'a>(&self, shards: &'a mut Vec<(u64, Arc<Shard>)>) -> &'a Arc<Shard> {
+        // Monitores já descartados deixam de referenciar seus shards
+        shards.retain(|(_, shard)| Arc::strong_count(shard) > 1);
+        let shard = Arc::new(Shard::default());
+        lock(&self.inner.shards).push(Arc::clone(&shard));
+        shards.push((self.inner.id, shard));
+        &shards[shards.len() - 1].1
+    }
+}
+
+/// Thread de agregação periódica; parar ou descartar faz uma última agregação
+pub struct Aggregator {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Aggregator {
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Aggregator {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Aplica as amostras intercaladas por timestamp e depois os valores finais
+///
+/// As amostras de cada thread nunca são reordenadas entre si.
+fn apply(drained: Vec<Pending>, monitor: &mut Monitor) {
+    let mut heads: BinaryHeap<Reverse<(u64, usize, usize)>> = drained
+        .iter()
+        .enumerate()
+        .filter_map(|(shard, pending)| pending.samples.first().map(|&(_, _, timestamp)| Reverse((timestamp, shard, 0))))
+        .collect();
+    while let Some(Reverse((_, shard, position))) = heads.pop() {
+        let samples = &drained[shard].samples;
+        let (metric_id, value, timestamp) = samples[position];
+        monitor.record_with_timestamp(metric_id, value, timestamp);
+        if let Some(&(_, _, timestamp)) = samples.get(position + 1) {
+            heads.push(Reverse((timestamp, shard, position + 1)));
+        }
+    }
+
+    for (metric_id, update) in drained.iter().flat_map(|pending| &pending.updates) {
+        match *update {
+            Update::Set(value) => monitor.record(*metric_id, value),
+            Update::Add(delta) => monitor.increment(*metric_id, delta),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations_keep_thread_order() {
+        let monitor = ShardedMonitor::new(Monitor::new());
+        monitor.record(1, 5.0);
+        monitor.increment(1, 2.0);
+        monitor.increment(2, 3.0);
+        monitor.record(2, 1.0);
+        monitor.decrement(3, 4.0);
+        monitor.increment(4, 1.0);
+        monitor.record_with_timestamp(4, 10.0, 1);
+        monitor.increment(4, 0.5);
+
+        // Nada chega ao global antes de agregar
+        assert_eq!(monitor.shared().lock().unwrap().count(), 0);
+        let global = monitor.lock();
+        assert_eq!(global.get(1), Some(7.0));
+        assert_eq!(global.get(2), Some(1.0));
+        assert_eq!(global.get(3), Some(-4.0));
+        assert_eq!(global.get(4), Some(10.5));
+        assert_eq!(global.get_history(4).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_threads_and_timestamp_merge() {
+        let monitor = ShardedMonitor::new(Monitor::with_history_size(1000)).with_flush_threshold(64);
+        let threads: Vec<_> = (0..4u64)
+            .map(|thread| {
+                let monitor = monitor.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100u64 {
+                        monitor.increment(1, 1.0);
+                        monitor.record_with_timestamp(2, thread as f64, i * 4 + thread);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let global = monitor.lock();
+        assert_eq!(global.get(1), Some(400.0));
+        let history = global.get_history(2).unwrap();
+        assert_eq!(history.len(), 400);
+        // Cada agregação intercala por timestamp; entre agregações a ordem é a de chegada
+        let last_batch = &history[history.len() - 4..];
+        assert!(last_batch.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        drop(global);
+        // Shards de threads encerradas são descartados após drenados
+        assert!(lock(&monitor.inner.shards).is_empty());
+    }
+
+    #[test]
+    fn test_merge_interleaves_samples() {
+        let mut global = Monitor::with_history_size(10);
+        let mut first = Pending::default();
+        first.sample(1, 1.0, 10);
+        first.add(9, 1.0);
+        first.sample(1, 3.0, 30);
+        let mut second = Pending::default();
+        second.sample(1, 2.0, 20);
+        second.sample(1, 4.0, 40);
+        second.add(9, 2.0);
+        apply(vec![first, second], &mut global);
+        let values: Vec<f64> = global.get_history(1).unwrap().iter().map(|e| e.value).collect();
+        assert_eq!(values, [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(global.get(9), Some(3.0));
+    }
+
+    #[test]
+    fn test_threshold_and_aggregator() {
+        let monitor = ShardedMonitor::new(Monitor::new()).with_flush_threshold(2);
+        monitor.increment(1, 1.0);
+        assert_eq!(monitor.shared().lock().unwrap().get(1), None);
+        monitor.increment(1, 1.0);
+        assert_eq!(monitor.shared().lock().unwrap().get(1), Some(2.0));
+
+        let monitor = ShardedMonitor::new(Monitor::new());
+        let aggregator = monitor.spawn_aggregator(Duration::from_millis(5));
+        monitor.record(7, 1.5);
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while monitor.shared().lock().unwrap().get(7).is_none() {
+            assert!(std::time::Instant::now() < deadline, "aggregator never flushed");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        aggregator.stop();
+    }
+}
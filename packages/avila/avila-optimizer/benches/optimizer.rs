@@ -0,0 +1,75 @@
+//! Throughput do pipeline de otimização em triângulos por segundo
+//!
+//! Os grupos terminam em `_tris` para o `avila-bench` reportar tris/s.
+
+use avila_mesh::primitives;
+use avila_mesh::{Mesh, Scene};
+use avila_optimizer::{LodGenerator, MeshMerger, Optimizer};
+use avila_vec3d::{Mat4, Vec3};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+/// Grade de esferas deslocadas, como elementos repetidos de um modelo BIM
+fn meshes() -> Vec<Mesh> {
+    (0..64)
+        .map(|i| {
+            let mut mesh = primitives::sphere(0.5, 24);
+            mesh.transform(&Mat4::translation(Vec3::new((i % 8) as f32 * 2.0, (i / 8) as f32 * 2.0, 0.0)));
+            mesh.material_id = Some(format!("material_{}", i % 4));
+            mesh
+        })
+        .collect()
+}
+
+fn triangles(meshes: &[Mesh]) -> u64 {
+    meshes.iter().map(|m| m.triangle_count() as u64).sum()
+}
+
+fn merge_benchmark(c: &mut Criterion) {
+    let meshes = meshes();
+    let refs: Vec<&Mesh> = meshes.iter().collect();
+    let merger = MeshMerger::new();
+    let mut group = c.benchmark_group("merge_tris");
+    group.throughput(Throughput::Elements(triangles(&meshes)));
+    // A deduplicação leva segundos nesta cena
+    group.sample_size(10);
+
+    group.bench_function("concat", |b| b.iter(|| merger.concat_meshes(black_box(&refs)).unwrap()));
+    let merged = merger.concat_meshes(&refs).unwrap();
+    group.bench_function("deduplicate", |b| {
+        b.iter_batched(
+            || merged.clone(),
+            |mut mesh| merger.deduplicate_vertices(&mut mesh).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn lod_benchmark(c: &mut Criterion) {
+    let meshes = meshes();
+    let merged = MeshMerger::new().concat_meshes(&meshes.iter().collect::<Vec<_>>()).unwrap();
+    let mut group = c.benchmark_group("lod_tris");
+    group.throughput(Throughput::Elements(merged.triangle_count() as u64));
+
+    let generator = LodGenerator::new();
+    group.bench_function("generate_lods", |b| b.iter(|| generator.generate_lods(black_box(&merged)).unwrap()));
+    group.finish();
+}
+
+fn scene_benchmark(c: &mut Criterion) {
+    let meshes = meshes();
+    let mut scene = Scene::new();
+    for mesh in &meshes {
+        scene.add_mesh(mesh.clone());
+    }
+    let mut group = c.benchmark_group("optimize_scene_tris");
+    group.throughput(Throughput::Elements(triangles(&meshes)));
+    group.sample_size(20);
+
+    let optimizer = Optimizer::new();
+    group.bench_function("default", |b| b.iter(|| optimizer.optimize_scene(black_box(&scene)).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, merge_benchmark, lod_benchmark, scene_benchmark);
+criterion_main!(benches);
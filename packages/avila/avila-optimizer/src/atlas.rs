@@ -0,0 +1,583 @@
+//! Atlas de texturas para os mapas gerados no baking
+//!
+//! Depois do [`bake_maps`](avila_mesh::bake_maps), cada material ganha suas
+//! próprias texturas pequenas (normal, occlusion...). [`build_atlas`] empacota
+//! essas texturas em poucas páginas compartilhadas:
+//!
+//! - Empacotamento *skyline bottom-left*, um retângulo por material (todas as
+//!   texturas do material ocupam a mesma posição em cada página)
+//! - Padding com extensão das bordas, evitando vazamento com mipmaps
+//! - Páginas opcionalmente em potência de dois
+//! - UVs das meshes remapeadas e materiais apontando para as texturas do atlas
+//!
+//! As páginas entram em [`Scene::textures`] com IDs `atlas{página}_{slot}`
+//! (ex.: `atlas0_normal`); o exporter glTF grava cada ID uma única vez, então
+//! todos os materiais de uma página compartilham a mesma imagem.
+
+use crate::{OptimizerError, Result};
+use avila_mesh::{Mesh, PbrMaterial, Scene, TextureBuffer, TextureFormat};
+use avila_vec3d::Vec2;
+use std::collections::{HashMap, HashSet};
+
+/// Tolerância para UVs fora de [0, 1] (erros de arredondamento do baking)
+const UV_EPSILON: f32 = 1e-4;
+
+// ============================================================================
+// OPÇÕES E RESULTADO
+// ============================================================================
+
+/// Configuração do atlas
+#[derive(Debug, Clone)]
+pub struct AtlasOptions {
+    /// Lado máximo de cada página, em texels
+    pub max_size: u32,
+    /// Texels de borda em volta de cada retângulo
+    pub padding: u32,
+    /// Arredonda as dimensões das páginas para potências de dois
+    pub power_of_two: bool,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 4096,
+            padding: 4,
+            power_of_two: true,
+        }
+    }
+}
+
+/// Slot de textura de um material PBR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TextureSlot {
+    BaseColor,
+    MetallicRoughness,
+    Normal,
+    Emissive,
+    Occlusion,
+}
+
+impl TextureSlot {
+    pub const ALL: [TextureSlot; 5] = [
+        Self::BaseColor,
+        Self::MetallicRoughness,
+        Self::Normal,
+        Self::Emissive,
+        Self::Occlusion,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BaseColor => "base_color",
+            Self::MetallicRoughness => "metallic_roughness",
+            Self::Normal => "normal",
+            Self::Emissive => "emissive",
+            Self::Occlusion => "occlusion",
+        }
+    }
+
+    /// ID de textura do material neste slot
+    pub fn texture<'a>(&self, material: &'a PbrMaterial) -> Option<&'a String> {
+        match self {
+            Self::BaseColor => material.base_color_texture.as_ref(),
+            Self::MetallicRoughness => material.metallic_roughness_texture.as_ref(),
+            Self::Normal => material.normal_texture.as_ref(),
+            Self::Emissive => material.emissive_texture.as_ref(),
+            Self::Occlusion => material.occlusion_texture.as_ref(),
+        }
+    }
+
+    fn texture_mut<'a>(&self, material: &'a mut PbrMaterial) -> &'a mut Option<String> {
+        match self {
+            Self::BaseColor => &mut material.base_color_texture,
+            Self::MetallicRoughness => &mut material.metallic_roughness_texture,
+            Self::Normal => &mut material.normal_texture,
+            Self::Emissive => &mut material.emissive_texture,
+            Self::Occlusion => &mut material.occlusion_texture,
+        }
+    }
+
+    /// Valor neutro para áreas sem textura (RGB)
+    fn neutral(&self) -> [u8; 3] {
+        match self {
+            Self::Normal => [128, 128, 255],
+            Self::Emissive => [0, 0, 0],
+            _ => [255, 255, 255],
+        }
+    }
+}
+
+/// Página do atlas
+#[derive(Debug, Clone)]
+pub struct AtlasPage {
+    pub width: u32,
+    pub height: u32,
+    /// Texturas geradas para esta página (ID em `Scene::textures`)
+    pub textures: Vec<(TextureSlot, String)>,
+    /// Materiais empacotados nesta página
+    pub materials: Vec<String>,
+    /// Texels ocupados pelos retângulos (sem padding)
+    pub used_texels: u64,
+}
+
+impl AtlasPage {
+    /// Fração da página ocupada por texturas
+    pub fn occupancy(&self) -> f32 {
+        self.used_texels as f32 / (self.width as u64 * self.height as u64).max(1) as f32
+    }
+}
+
+/// Posição de um material no atlas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasPlacement {
+    pub page: usize,
+    /// Retângulo útil em texels (sem padding)
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub uv_offset: Vec2,
+    pub uv_scale: Vec2,
+}
+
+impl AtlasPlacement {
+    /// Converte uma UV do material para a UV no atlas
+    pub fn remap_uv(&self, uv: Vec2) -> Vec2 {
+        Vec2::new(self.uv_offset.x + uv.x * self.uv_scale.x, self.uv_offset.y + uv.y * self.uv_scale.y)
+    }
+}
+
+/// Motivo para um material ficar fora do atlas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtlasSkipReason {
+    /// Alguma mesh usa UVs fora de [0, 1] (textura repetida)
+    TilingUvs,
+    /// As texturas não cabem numa página de `max_size`
+    TooLarge,
+}
+
+/// Resultado de [`build_atlas`]
+#[derive(Debug, Clone, Default)]
+pub struct TextureAtlas {
+    pub pages: Vec<AtlasPage>,
+    /// Posição por ID de material
+    pub placements: HashMap<String, AtlasPlacement>,
+    /// Materiais com texturas que continuaram separadas
+    pub skipped: Vec<(String, AtlasSkipReason)>,
+}
+
+impl TextureAtlas {
+    /// Remapeia as UVs de uma mesh cujo material foi empacotado
+    ///
+    /// [`build_atlas`] já faz isso nas meshes da cena; use para meshes
+    /// mantidas fora dela (ex.: LODs gerados antes do atlas).
+    pub fn remap_mesh(&self, mesh: &mut Mesh) -> bool {
+        let Some(placement) = mesh.material_id.as_ref().and_then(|id| self.placements.get(id)) else {
+            return false;
+        };
+        for vertex in &mut mesh.vertices {
+            vertex.uv = placement.remap_uv(vertex.uv);
+        }
+        true
+    }
+}
+
+// ============================================================================
+// CONSTRUÇÃO
+// ============================================================================
+
+/// Material candidato: texturas por slot e tamanho do retângulo
+struct Candidate {
+    material_id: String,
+    textures: Vec<(TextureSlot, String)>,
+    width: u32,
+    height: u32,
+}
+
+/// Empacota as texturas dos materiais da cena em páginas compartilhadas
+///
+/// Materiais, texturas e UVs da cena são atualizados; texturas originais que
+/// nenhum material referencia mais são removidas.
+pub fn build_atlas(scene: &mut Scene, options: &AtlasOptions) -> Result<TextureAtlas> {
+    if options.max_size == 0 || options.padding * 2 >= options.max_size {
+        return Err(OptimizerError::OptimizationError(
+            "Atlas max_size must exceed twice the padding".into(),
+        ));
+    }
+
+    let mut atlas = TextureAtlas::default();
+    let mut candidates = collect_candidates(scene, options, &mut atlas.skipped);
+    // Mais altos primeiro: o skyline desperdiça menos espaço
+    candidates.sort_by(|a, b| (b.height, b.width, &a.material_id).cmp(&(a.height, a.width, &b.material_id)));
+
+    // Distribui os retângulos nas páginas (first fit)
+    let mut packers: Vec<Skyline> = Vec::new();
+    let mut assigned: Vec<Vec<(usize, u32, u32)>> = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        let (w, h) = (candidate.width + 2 * options.padding, candidate.height + 2 * options.padding);
+        let mut placed = packers.iter_mut().enumerate().find_map(|(page, packer)| {
+            packer.insert(w, h).map(|(x, y)| (page, x, y))
+        });
+        if placed.is_none() {
+            let mut packer = Skyline::new(options.max_size, options.max_size);
+            placed = packer.insert(w, h).map(|(x, y)| (packers.len(), x, y));
+            packers.push(packer);
+            assigned.push(Vec::new());
+        }
+        let (page, x, y) = placed.expect("candidate fits an empty page");
+        assigned[page].push((index, x + options.padding, y + options.padding));
+    }
+
+    for (page_index, (packer, entries)) in packers.iter().zip(&assigned).enumerate() {
+        let (mut width, mut height) = packer.used_extent();
+        if options.power_of_two {
+            width = width.next_power_of_two().min(options.max_size);
+            height = height.next_power_of_two().min(options.max_size);
+        }
+
+        let slots: Vec<TextureSlot> = TextureSlot::ALL
+            .into_iter()
+            .filter(|slot| entries.iter().any(|&(i, _, _)| candidates[i].textures.iter().any(|(s, _)| s == slot)))
+            .collect();
+
+        let mut page = AtlasPage {
+            width,
+            height,
+            textures: Vec::new(),
+            materials: entries.iter().map(|&(i, _, _)| candidates[i].material_id.clone()).collect(),
+            used_texels: entries
+                .iter()
+                .map(|&(i, _, _)| candidates[i].width as u64 * candidates[i].height as u64)
+                .sum(),
+        };
+
+        for slot in slots {
+            let format = page_format(scene, &candidates, entries, slot);
+            let mut texture = TextureBuffer::filled(width, height, format, &slot.neutral()[..format.channels()]);
+            for &(i, x, y) in entries {
+                let candidate = &candidates[i];
+                let rect = Rect { x, y, width: candidate.width, height: candidate.height };
+                match candidate.textures.iter().find(|(s, _)| *s == slot) {
+                    Some((_, id)) => blit(&mut texture, &scene.textures[id], rect, options.padding),
+                    None => fill(&mut texture, rect, &slot.neutral(), options.padding),
+                }
+            }
+            page.textures.push((slot, format!("atlas{}_{}", page_index, slot.name())));
+            scene.add_texture(format!("atlas{}_{}", page_index, slot.name()), texture);
+        }
+
+        for &(i, x, y) in entries {
+            let candidate = &candidates[i];
+            atlas.placements.insert(
+                candidate.material_id.clone(),
+                AtlasPlacement {
+                    page: page_index,
+                    x,
+                    y,
+                    width: candidate.width,
+                    height: candidate.height,
+                    uv_offset: Vec2::new(x as f32 / width as f32, y as f32 / height as f32),
+                    uv_scale: Vec2::new(candidate.width as f32 / width as f32, candidate.height as f32 / height as f32),
+                },
+            );
+        }
+        atlas.pages.push(page);
+    }
+
+    // Materiais passam a apontar para as páginas
+    let mut replaced = HashSet::new();
+    for candidate in &candidates {
+        let page = atlas.placements[&candidate.material_id].page;
+        let material = scene.materials.get_mut(&candidate.material_id).expect("candidate material exists");
+        for (slot, id) in &candidate.textures {
+            replaced.insert(id.clone());
+            *slot.texture_mut(material) = Some(format!("atlas{}_{}", page, slot.name()));
+        }
+    }
+    let still_used: HashSet<String> = scene
+        .materials
+        .values()
+        .flat_map(|material| TextureSlot::ALL.into_iter().filter_map(|slot| slot.texture(material).cloned()))
+        .collect();
+    scene.textures.retain(|id, _| !replaced.contains(id) || still_used.contains(id));
+
+    for mesh in &mut scene.meshes {
+        atlas.remap_mesh(mesh);
+    }
+    Ok(atlas)
+}
+
+fn collect_candidates(
+    scene: &Scene,
+    options: &AtlasOptions,
+    skipped: &mut Vec<(String, AtlasSkipReason)>,
+) -> Vec<Candidate> {
+    let mut tiling: HashSet<&str> = HashSet::new();
+    for mesh in &scene.meshes {
+        let Some(material_id) = mesh.material_id.as_deref() else {
+            continue;
+        };
+        let outside = |v: f32| !(-UV_EPSILON..=1.0 + UV_EPSILON).contains(&v);
+        if mesh.vertices.iter().any(|vertex| outside(vertex.uv.x) || outside(vertex.uv.y)) {
+            tiling.insert(material_id);
+        }
+    }
+
+    let mut ids: Vec<&String> = scene.materials.keys().collect();
+    ids.sort();
+
+    let limit = options.max_size - 2 * options.padding;
+    let mut candidates = Vec::new();
+    for id in ids {
+        let material = &scene.materials[id];
+        let textures: Vec<(TextureSlot, String)> = TextureSlot::ALL
+            .into_iter()
+            .filter_map(|slot| slot.texture(material).filter(|t| scene.textures.contains_key(*t)).map(|t| (slot, t.clone())))
+            .collect();
+        if textures.is_empty() {
+            continue;
+        }
+
+        let width = textures.iter().map(|(_, t)| scene.textures[t].width).max().unwrap_or(0);
+        let height = textures.iter().map(|(_, t)| scene.textures[t].height).max().unwrap_or(0);
+        if tiling.contains(id.as_str()) {
+            skipped.push((id.clone(), AtlasSkipReason::TilingUvs));
+        } else if width > limit || height > limit || width == 0 || height == 0 {
+            skipped.push((id.clone(), AtlasSkipReason::TooLarge));
+        } else {
+            candidates.push(Candidate { material_id: id.clone(), textures, width, height });
+        }
+    }
+    candidates
+}
+
+/// Cinza só quando todas as texturas do slot na página são cinza
+fn page_format(scene: &Scene, candidates: &[Candidate], entries: &[(usize, u32, u32)], slot: TextureSlot) -> TextureFormat {
+    let any_rgb = entries.iter().any(|&(i, _, _)| {
+        candidates[i]
+            .textures
+            .iter()
+            .any(|(s, id)| *s == slot && scene.textures[id].format == TextureFormat::Rgb8)
+    });
+    if any_rgb {
+        TextureFormat::Rgb8
+    } else {
+        TextureFormat::Gray8
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Copia `source` para `rect` (nearest se o tamanho difere) e estende as
+/// bordas por `padding` texels
+fn blit(target: &mut TextureBuffer, source: &TextureBuffer, rect: Rect, padding: u32) {
+    write_rect(target, rect, padding, |u, v| {
+        let sx = (u as u64 * source.width as u64 / rect.width as u64) as u32;
+        let sy = (v as u64 * source.height as u64 / rect.height as u64) as u32;
+        let pixel = source.pixel(sx, sy);
+        match source.format {
+            TextureFormat::Gray8 => [pixel[0]; 3],
+            TextureFormat::Rgb8 => [pixel[0], pixel[1], pixel[2]],
+        }
+    });
+}
+
+fn fill(target: &mut TextureBuffer, rect: Rect, value: &[u8; 3], padding: u32) {
+    write_rect(target, rect, padding, |_, _| *value);
+}
+
+/// Escreve `rect` + padding; texels do padding repetem a borda mais próxima
+fn write_rect(target: &mut TextureBuffer, rect: Rect, padding: u32, texel: impl Fn(u32, u32) -> [u8; 3]) {
+    let channels = target.format.channels();
+    let x0 = rect.x.saturating_sub(padding);
+    let y0 = rect.y.saturating_sub(padding);
+    let x1 = (rect.x + rect.width + padding).min(target.width);
+    let y1 = (rect.y + rect.height + padding).min(target.height);
+
+    for y in y0..y1 {
+        let v = y.clamp(rect.y, rect.y + rect.height - 1) - rect.y;
+        for x in x0..x1 {
+            let u = x.clamp(rect.x, rect.x + rect.width - 1) - rect.x;
+            let rgb = texel(u, v);
+            target.set_pixel(x, y, &rgb[..channels]);
+        }
+    }
+}
+
+// ============================================================================
+// SKYLINE
+// ============================================================================
+
+/// Empacotador skyline bottom-left
+struct Skyline {
+    width: u32,
+    height: u32,
+    /// Segmentos (x, y, largura) do contorno superior, ordenados por x
+    segments: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    fn new(width: u32, height: u32) -> Self {
+        Self { width, height, segments: vec![(0, 0, width)] }
+    }
+
+    /// Insere um retângulo e devolve o canto superior esquerdo
+    fn insert(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        let mut best: Option<(u32, u32, usize)> = None;
+        for start in 0..self.segments.len() {
+            let x = self.segments[start].0;
+            if x + w > self.width {
+                break;
+            }
+            // Altura do contorno sob [x, x + w)
+            let mut y = 0;
+            let mut covered = 0;
+            for &(_, sy, sw) in &self.segments[start..] {
+                y = y.max(sy);
+                covered += sw;
+                if covered >= w {
+                    break;
+                }
+            }
+            if y + h > self.height {
+                continue;
+            }
+            if best.is_none_or(|(by, bx, _)| (y, x) < (by, bx)) {
+                best = Some((y, x, start));
+            }
+        }
+
+        let (y, x, start) = best?;
+        // Substitui os segmentos cobertos pelo topo do novo retângulo
+        let mut end = start;
+        while end < self.segments.len() && self.segments[end].0 < x + w {
+            end += 1;
+        }
+        let last = self.segments[end - 1];
+        let tail = (last.0 + last.2).saturating_sub(x + w);
+        let mut replacement = vec![(x, y + h, w)];
+        if tail > 0 {
+            replacement.push((x + w, last.1, tail));
+        }
+        self.segments.splice(start..end, replacement);
+
+        // Junta vizinhos na mesma altura
+        self.segments.dedup_by(|next, prev| {
+            if prev.1 == next.1 {
+                prev.2 += next.2;
+                true
+            } else {
+                false
+            }
+        });
+        Some((x, y))
+    }
+
+    /// Largura e altura efetivamente usadas
+    fn used_extent(&self) -> (u32, u32) {
+        let width = self.segments.iter().filter(|s| s.1 > 0).map(|s| s.0 + s.2).max().unwrap_or(0);
+        let height = self.segments.iter().map(|s| s.1).max().unwrap_or(0);
+        (width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::primitives;
+
+    fn textured_scene(sizes: &[u32]) -> Scene {
+        let mut scene = Scene::new();
+        for (i, &size) in sizes.iter().enumerate() {
+            let id = format!("mat{}", i);
+            let mut material = PbrMaterial::default_material(&id);
+            material.normal_texture = Some(format!("{}_normal", id));
+            material.occlusion_texture = Some(format!("{}_occlusion", id));
+            scene.add_material(material);
+
+            let value = [i as u8 * 40, 100, 200];
+            scene.add_texture(format!("{}_normal", id), TextureBuffer::filled(size, size, TextureFormat::Rgb8, &value));
+            scene.add_texture(format!("{}_occlusion", id), TextureBuffer::filled(size, size, TextureFormat::Gray8, &[i as u8]));
+
+            let mut mesh = primitives::plane(1.0, 1.0);
+            mesh.material_id = Some(id);
+            scene.add_mesh(mesh);
+        }
+        scene
+    }
+
+    #[test]
+    fn test_skyline_packs_without_overlap() {
+        let mut packer = Skyline::new(64, 64);
+        let mut rects = Vec::new();
+        for &(w, h) in &[(32, 32), (16, 16), (16, 16), (32, 16), (8, 24), (64, 8)] {
+            let (x, y) = packer.insert(w, h).unwrap();
+            assert!(x + w <= 64 && y + h <= 64);
+            for &(ox, oy, ow, oh) in &rects {
+                assert!(x >= ox + ow || ox >= x + w || y >= oy + oh || oy >= y + h, "overlap");
+            }
+            rects.push((x, y, w, h));
+        }
+        assert!(packer.insert(64, 64).is_none());
+    }
+
+    #[test]
+    fn test_build_atlas_remaps_materials_and_uvs() {
+        let mut scene = textured_scene(&[64, 32, 32, 16]);
+        let options = AtlasOptions { max_size: 256, padding: 2, power_of_two: true };
+        let atlas = build_atlas(&mut scene, &options).unwrap();
+
+        assert_eq!(atlas.pages.len(), 1);
+        let page = &atlas.pages[0];
+        assert!(page.width.is_power_of_two() && page.height.is_power_of_two());
+        assert_eq!(page.textures.len(), 2);
+        assert_eq!(scene.textures.len(), 2);
+        assert_eq!(scene.textures["atlas0_occlusion"].format, TextureFormat::Gray8);
+
+        let normal = &scene.textures["atlas0_normal"];
+        for i in 0..4 {
+            let id = format!("mat{}", i);
+            assert_eq!(scene.materials[&id].normal_texture.as_deref(), Some("atlas0_normal"));
+
+            // O centro da UV remapeada cai no texel original do material
+            let placement = atlas.placements[&id];
+            let uv = placement.remap_uv(Vec2::new(0.5, 0.5));
+            let pixel = normal.pixel((uv.x * page.width as f32) as u32, (uv.y * page.height as f32) as u32);
+            assert_eq!(pixel, [i as u8 * 40, 100, 200]);
+            // Padding repete a borda
+            assert_eq!(normal.pixel(placement.x - 1, placement.y), pixel);
+        }
+
+        let mesh = &scene.meshes[1];
+        let placement = atlas.placements["mat1"];
+        for vertex in &mesh.vertices {
+            assert!(vertex.uv.x >= placement.uv_offset.x - 1e-6);
+            assert!(vertex.uv.x <= placement.uv_offset.x + placement.uv_scale.x + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_overflow_pages_and_skips() {
+        let mut scene = textured_scene(&[100, 100, 100, 300]);
+        // Material com UV repetida fica de fora
+        scene.meshes[0].vertices[0].uv = Vec2::new(2.0, 0.0);
+
+        let options = AtlasOptions { max_size: 128, padding: 4, power_of_two: false };
+        let atlas = build_atlas(&mut scene, &options).unwrap();
+
+        assert_eq!(atlas.pages.len(), 2);
+        assert_eq!(atlas.pages[0].width, 108);
+        assert!(atlas.skipped.contains(&("mat0".to_string(), AtlasSkipReason::TilingUvs)));
+        assert!(atlas.skipped.contains(&("mat3".to_string(), AtlasSkipReason::TooLarge)));
+        // Texturas dos materiais ignorados continuam na cena
+        assert!(scene.textures.contains_key("mat0_normal"));
+        assert!(!scene.textures.contains_key("mat1_normal"));
+        assert_eq!(scene.materials["mat0"].normal_texture.as_deref(), Some("mat0_normal"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Remoção de objetos pequenos nos LODs
+//!
+//! Parafusos, clipes e conexões somem da tela a poucos metros, mas dominam a
+//! contagem de triângulos. Com [`Optimizer::small_object_culling`], cada LOD
+//! (nível ≥ 1) é montado a partir dos elementos de origem do grupo de
+//! material, sem os que ficariam abaixo de `min_pixels` na distância em que
+//! aquele LOD começa a ser usado. Eles são descartados ou trocados pela
+//! caixa envolvente ([`CullAction`]), e cada remoção fica registrada em
+//! [`OptimizedScene::culling`](crate::OptimizedScene::culling) com o GUID
+//! do elemento.
+//!
+//! O LOD0 nunca é alterado.
+
+use crate::{Optimizer, Result, LOD_SWITCH_DISTANCES};
+use avila_mesh::{primitives, Mesh};
+use avila_vec3d::*;
+
+// ============================================================================
+// CONFIGURAÇÃO
+// ============================================================================
+
+/// O que fazer com um elemento abaixo do limiar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CullAction {
+    /// Remove o elemento do LOD
+    Drop,
+    /// Troca o elemento pela sua AABB (12 triângulos)
+    BoundingBox,
+}
+
+/// Limiar de tamanho projetado por LOD
+#[derive(Debug, Clone)]
+pub struct SmallObjectCulling {
+    /// Diâmetro projetado mínimo, em pixels, para manter o elemento
+    pub min_pixels: f32,
+    /// Altura da viewport de referência (pixels)
+    pub viewport_height: f32,
+    /// Campo de visão vertical da câmera de referência (radianos)
+    pub vertical_fov: f32,
+    /// Distância a partir da qual cada LOD (1, 2, ...) é usado; níveis além
+    /// da lista usam a última distância
+    pub lod_distances: Vec<f32>,
+    pub action: CullAction,
+}
+
+impl Default for SmallObjectCulling {
+    fn default() -> Self {
+        Self {
+            min_pixels: 2.0,
+            viewport_height: 1080.0,
+            vertical_fov: 60f32.to_radians(),
+            lod_distances: LOD_SWITCH_DISTANCES.to_vec(),
+            action: CullAction::Drop,
+        }
+    }
+}
+
+impl SmallObjectCulling {
+    /// Diâmetro em pixels de uma esfera de raio `radius` a `distance` metros
+    pub fn projected_pixels(&self, radius: f32, distance: f32) -> f32 {
+        if distance <= radius {
+            return f32::INFINITY;
+        }
+        let angular_diameter = 2.0 * (radius / distance).asin();
+        angular_diameter / self.vertical_fov * self.viewport_height
+    }
+
+    /// Distância em que o LOD `level` (≥ 1) começa a ser usado
+    pub fn lod_distance(&self, level: usize) -> Option<f32> {
+        let index = level.checked_sub(1)?;
+        self.lod_distances
+            .get(index)
+            .or_else(|| self.lod_distances.last())
+            .copied()
+    }
+
+    /// O elemento fica abaixo do limiar já no início do LOD `level`
+    pub fn is_small(&self, radius: f32, level: usize) -> bool {
+        match self.lod_distance(level) {
+            Some(distance) => self.projected_pixels(radius, distance) < self.min_pixels,
+            None => false,
+        }
+    }
+}
+
+// ============================================================================
+// RELATÓRIO
+// ============================================================================
+
+/// Elemento removido (ou trocado pela caixa) num LOD
+#[derive(Debug, Clone, PartialEq)]
+pub struct CulledElement {
+    /// Índice da mesh otimizada (grupo de material)
+    pub mesh_index: usize,
+    pub level: usize,
+    /// GUID do elemento de origem, quando conhecido
+    pub guid: Option<String>,
+    /// Triângulos do elemento original
+    pub triangles: usize,
+    pub action: CullAction,
+}
+
+/// Elementos afetados pela remoção de objetos pequenos
+#[derive(Debug, Clone, Default)]
+pub struct CullingReport {
+    pub culled: Vec<CulledElement>,
+}
+
+impl CullingReport {
+    /// GUIDs dos elementos removidos no LOD `level`
+    pub fn removed_guids(&self, level: usize) -> impl Iterator<Item = &str> {
+        self.culled
+            .iter()
+            .filter(move |element| element.level == level)
+            .filter_map(|element| element.guid.as_deref())
+    }
+
+    /// Triângulos originais removidos no LOD `level`
+    pub fn triangles_removed(&self, level: usize) -> usize {
+        self.culled
+            .iter()
+            .filter(|element| element.level == level)
+            .map(|element| element.triangles)
+            .sum()
+    }
+}
+
+// ============================================================================
+// GERAÇÃO DOS LODS
+// ============================================================================
+
+/// LODs de um grupo, montados a partir de `sources` sem os objetos pequenos
+pub(crate) fn generate_lods(
+    optimizer: &Optimizer,
+    culling: &SmallObjectCulling,
+    mesh_index: usize,
+    merged: &Mesh,
+    sources: &[&Mesh],
+    report: &mut CullingReport,
+) -> Result<Vec<Mesh>> {
+    let generator = &optimizer.lod_generator;
+    let original_triangles = merged.triangle_count().max(1) as f32;
+    let radii: Vec<f32> = sources.iter().map(|mesh| radius(mesh)).collect();
+
+    let mut lods = Vec::with_capacity(generator.ratios.len() + 1);
+    lods.push(merged.clone());
+    for (i, &ratio) in generator.ratios.iter().enumerate() {
+        let level = i + 1;
+        let (small, kept): (Vec<usize>, Vec<usize>) =
+            (0..sources.len()).partition(|&source| culling.is_small(radii[source], level));
+        if small.is_empty() {
+            lods.push(generator.simplify_mesh(merged, ratio)?);
+            continue;
+        }
+
+        let proxies: Vec<Mesh> = match culling.action {
+            CullAction::Drop => Vec::new(),
+            CullAction::BoundingBox => small.iter().map(|&source| bounding_box(sources[source])).collect(),
+        };
+        for &source in &small {
+            report.culled.push(CulledElement {
+                mesh_index,
+                level,
+                guid: sources[source].element_guid.clone(),
+                triangles: sources[source].triangle_count(),
+                action: culling.action,
+            });
+        }
+
+        let parts: Vec<&Mesh> = kept.iter().map(|&source| sources[source]).chain(&proxies).collect();
+        if parts.is_empty() {
+            let mut empty = Mesh::new();
+            empty.material_id = merged.material_id.clone();
+            lods.push(empty);
+            continue;
+        }
+        let mut culled = optimizer.merger.concat_meshes(&parts)?;
+        culled.material_id = merged.material_id.clone();
+        if optimizer.canonical_order {
+            culled.canonicalize();
+        }
+        // Mantém a meta de triângulos relativa ao LOD0, não ao que sobrou
+        let ratio = (ratio * original_triangles / culled.triangle_count().max(1) as f32).min(1.0);
+        lods.push(generator.simplify_mesh(&culled, ratio)?);
+    }
+    Ok(lods)
+}
+
+/// Raio da esfera envolvente (a AABB serve quando a esfera não foi calculada)
+fn radius(mesh: &Mesh) -> f32 {
+    if !mesh.bounding_sphere.is_empty() {
+        return mesh.bounding_sphere.radius;
+    }
+    if mesh.vertices.is_empty() {
+        return 0.0;
+    }
+    mesh.bounds.size().length() / 2.0
+}
+
+/// Caixa com a AABB do elemento, com o mesmo material e GUID
+fn bounding_box(mesh: &Mesh) -> Mesh {
+    let size = mesh.bounds.size();
+    // Eixo achatado (placa) ainda precisa de uma escala invertível para as normais
+    let scale = Vec3::new(size.x.max(1e-4), size.y.max(1e-4), size.z.max(1e-4));
+    let mut proxy = primitives::cube(1.0);
+    proxy.transform(&Mat4::translation(mesh.bounds.center()).mul_mat4(&Mat4::scale(scale)));
+    proxy.material_id = mesh.material_id.clone();
+    proxy.name = mesh.name.clone();
+    proxy.element_guid = mesh.element_guid.clone();
+    proxy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projected_size_threshold() {
+        let culling = SmallObjectCulling::default();
+        // 1 cm a 10 m ≈ 1,03 px com 1080 px e 60°
+        let pixels = culling.projected_pixels(0.005, 10.0);
+        assert!((pixels - 1.03).abs() < 0.01, "{pixels}");
+        assert!(culling.is_small(0.005, 1));
+        assert!(!culling.is_small(0.5, 1));
+        assert!(culling.is_small(0.05, 3));
+        assert_eq!(culling.lod_distance(0), None);
+        assert_eq!(culling.lod_distance(7), Some(100.0));
+        assert_eq!(culling.projected_pixels(1.0, 0.5), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_bounding_box_proxy() {
+        let mut screw = primitives::sphere(0.01, 8);
+        screw.transform(&Mat4::translation(Vec3::new(5.0, 0.0, 0.0)));
+        screw.element_guid = Some("screw".into());
+        let proxy = bounding_box(&screw);
+        assert_eq!(proxy.triangle_count(), 12);
+        assert_eq!(proxy.element_guid.as_deref(), Some("screw"));
+        assert!((proxy.bounds.center() - screw.bounds.center()).length() < 1e-5);
+        assert!((proxy.bounds.size() - screw.bounds.size()).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_lods_drop_small_elements() {
+        let mut scene = avila_mesh::Scene::new();
+        let mut slab = primitives::cube(4.0);
+        slab.material_id = Some("steel".into());
+        slab.element_guid = Some("slab".into());
+        scene.add_mesh(slab);
+        for i in 0..5 {
+            let mut screw = primitives::sphere(0.004, 8);
+            screw.transform(&Mat4::translation(Vec3::new(i as f32, 2.0, 0.0)));
+            screw.material_id = Some("steel".into());
+            screw.element_guid = Some(format!("screw-{i}"));
+            scene.add_mesh(screw);
+        }
+
+        let mut optimizer = Optimizer::new();
+        optimizer.lod_generator.ratios = vec![1.0];
+        optimizer.small_object_culling = Some(SmallObjectCulling::default());
+        let optimized = optimizer.optimize_scene(&scene).unwrap();
+
+        let lods = &optimized.lods[0];
+        assert_eq!(lods[0].triangle_count(), scene.triangle_count());
+        assert_eq!(lods[1].triangle_count(), 12);
+        let removed: Vec<&str> = optimized.culling.removed_guids(1).collect();
+        assert_eq!(removed, ["screw-0", "screw-1", "screw-2", "screw-3", "screw-4"]);
+        assert_eq!(optimized.culling.triangles_removed(1), scene.triangle_count() - 12);
+
+        optimizer.small_object_culling = Some(SmallObjectCulling {
+            action: CullAction::BoundingBox,
+            ..Default::default()
+        });
+        let optimized = optimizer.optimize_scene(&scene).unwrap();
+        assert_eq!(optimized.lods[0][1].triangle_count(), 12 * 6);
+        assert!(optimized.culling.culled.iter().all(|c| c.action == CullAction::BoundingBox));
+    }
+}
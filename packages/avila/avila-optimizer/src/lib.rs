@@ -8,13 +8,33 @@
 //! - Spatial indexing (Octree)
 //! - Vertex deduplication
 //! - Triangle strip optimization
+//! - Otimização em estágios com progresso e cancelamento ([`staged`])
+//! - Relatório de qualidade dos LODs (Hausdorff, RMS, desvio de normais)
+//! - Atlas de texturas para os mapas gerados no baking ([`atlas`])
+//! - Remoção de objetos pequenos por LOD, pelo tamanho projetado ([`culling`])
+//! - LOD de nuvens de pontos em octree ([`point_lod`])
 
 use avila_vec3d::*;
 use avila_mesh::*;
 use std::collections::HashMap;
 
+pub mod atlas;
+pub mod culling;
+pub mod point_lod;
+pub mod staged;
+
+pub use atlas::{build_atlas, AtlasOptions, AtlasPage, AtlasPlacement, AtlasSkipReason, TextureAtlas, TextureSlot};
+pub use culling::{CullAction, CulledElement, CullingReport, SmallObjectCulling};
+pub use point_lod::{PointChunk, PointCloudLod, PointLodOptions};
+pub use staged::{
+    CancellationToken, OptimizationStage, OptimizationTask, ProgressEvent, ProgressSink, StagedOptimization,
+};
+
 pub type Result<T> = std::result::Result<T, OptimizerError>;
 
+/// Distância (m) a partir da qual cada LOD (1, 2, 3) é selecionado
+pub const LOD_SWITCH_DISTANCES: [f32; 3] = [10.0, 50.0, 100.0];
+
 #[derive(Debug, thiserror::Error)]
 pub enum OptimizerError {
     #[error("Optimization error: {0}")]
@@ -25,6 +45,9 @@ pub enum OptimizerError {
 
     #[error("Vec3d error: {0}")]
     Vec3dError(#[from] Vec3dError),
+
+    #[error("Optimization cancelled before stage {0:?}")]
+    Cancelled(OptimizationStage),
 }
 
 // ============================================================================
@@ -48,45 +71,61 @@ impl MeshMerger {
     pub fn merge_scene(&self, scene: &Scene) -> Result<Scene> {
         let mut merged_scene = Scene::new();
 
-        // Agrupar meshes por material
+        // Agrupar meshes por material, na ordem de primeira ocorrência
+        let mut order: Vec<Option<String>> = Vec::new();
         let mut meshes_by_material: HashMap<Option<String>, Vec<&Mesh>> = HashMap::new();
 
         for mesh in &scene.meshes {
             meshes_by_material
                 .entry(mesh.material_id.clone())
-                .or_insert_with(Vec::new)
+                .or_insert_with(|| {
+                    order.push(mesh.material_id.clone());
+                    Vec::new()
+                })
                 .push(mesh);
         }
 
         // Merge cada grupo
-        for (material_id, meshes) in meshes_by_material {
-            if meshes.is_empty() {
-                continue;
-            }
-
-            let merged = self.merge_meshes(&meshes)?;
-            let mut final_mesh = merged;
-            final_mesh.material_id = material_id.clone();
+        for material_id in order {
+            let meshes = &meshes_by_material[&material_id];
+            let mut final_mesh = self.merge_meshes(meshes)?;
+            final_mesh.material_id = material_id;
 
             merged_scene.add_mesh(final_mesh);
         }
 
-        // Copiar materiais
+        // Copiar materiais, texturas e nuvens de pontos
         merged_scene.materials = scene.materials.clone();
+        merged_scene.textures = scene.textures.clone();
+        for cloud in &scene.point_clouds {
+            merged_scene.add_point_cloud(cloud.clone());
+        }
 
         Ok(merged_scene)
     }
 
     /// Merge múltiplas meshes em uma única
     pub fn merge_meshes(&self, meshes: &[&Mesh]) -> Result<Mesh> {
-        if meshes.is_empty() {
-            return Err(OptimizerError::OptimizationError("No meshes to merge".into()));
-        }
-
         if meshes.len() == 1 {
             return Ok((*meshes[0]).clone());
         }
 
+        let mut merged = self.concat_meshes(meshes)?;
+
+        // Deduplicate vertices se habilitado
+        if self.vertex_tolerance > 0.0 {
+            self.deduplicate_vertices(&mut merged)?;
+        }
+
+        Ok(merged)
+    }
+
+    /// Concatena meshes sem deduplicar vértices
+    pub fn concat_meshes(&self, meshes: &[&Mesh]) -> Result<Mesh> {
+        if meshes.is_empty() {
+            return Err(OptimizerError::OptimizationError("No meshes to merge".into()));
+        }
+
         // Calcular capacidade total
         let total_vertices: usize = meshes.iter().map(|m| m.vertices.len()).sum();
         let total_indices: usize = meshes.iter().map(|m| m.indices.len()).sum();
@@ -100,7 +139,6 @@ impl MeshMerger {
             // Adicionar vértices
             for vertex in &mesh.vertices {
                 merged.vertices.push(*vertex);
-                merged.bounds.expand_point(vertex.position);
             }
 
             // Adicionar índices (com offset)
@@ -108,17 +146,13 @@ impl MeshMerger {
                 merged.indices.push(offset + index);
             }
         }
-
-        // Deduplicate vertices se habilitado
-        if self.vertex_tolerance > 0.0 {
-            self.deduplicate_vertices(&mut merged)?;
-        }
+        merged.recalculate_bounds();
 
         Ok(merged)
     }
 
     /// Remove vértices duplicados
-    fn deduplicate_vertices(&self, mesh: &mut Mesh) -> Result<()> {
+    pub fn deduplicate_vertices(&self, mesh: &mut Mesh) -> Result<()> {
         let vertex_count = mesh.vertices.len();
         if vertex_count == 0 {
             return Ok(());
@@ -226,7 +260,7 @@ impl LodGenerator {
     }
 
     /// Simplifica mesh para target ratio (edge collapse simplification)
-    fn simplify_mesh(&self, mesh: &Mesh, ratio: f32) -> Result<Mesh> {
+    pub(crate) fn simplify_mesh(&self, mesh: &Mesh, ratio: f32) -> Result<Mesh> {
         let target_triangles = ((mesh.indices.len() / 3) as f32 * ratio).max(1.0) as usize;
 
         // Implementação simples: decimação uniforme
@@ -255,11 +289,7 @@ impl LodGenerator {
             }
         }
 
-        // Recalcular bounds
-        simplified.bounds = Aabb::EMPTY;
-        for vertex in &simplified.vertices {
-            simplified.bounds.expand_point(vertex.position);
-        }
+        simplified.recalculate_bounds();
 
         Ok(simplified)
     }
@@ -396,6 +426,11 @@ impl Octree {
 pub struct Optimizer {
     pub merger: MeshMerger,
     pub lod_generator: LodGenerator,
+    /// Ordena as meshes mescladas por material e coloca vértices e
+    /// triângulos na ordem canônica antes dos LODs (saída reproduzível)
+    pub canonical_order: bool,
+    /// Remove objetos pequenos dos LODs ≥ 1 (desligado com `None`)
+    pub small_object_culling: Option<SmallObjectCulling>,
 }
 
 impl Optimizer {
@@ -403,34 +438,25 @@ impl Optimizer {
         Self {
             merger: MeshMerger::new(),
             lod_generator: LodGenerator::new(),
+            canonical_order: false,
+            small_object_culling: None,
         }
     }
 
-    /// Otimiza cena completa
+    /// Otimiza cena completa (bloqueante, sem eventos de progresso)
     pub fn optimize_scene(&self, scene: &Scene) -> Result<OptimizedScene> {
-        // 1. Merge meshes por material
-        let merged_scene = self.merger.merge_scene(scene)?;
-
-        // 2. Gerar LODs
-        let mut lods_by_mesh = Vec::new();
-        for mesh in &merged_scene.meshes {
-            let lods = self.lod_generator.generate_lods(mesh)?;
-            lods_by_mesh.push(lods);
-        }
-
-        // 3. Criar spatial index
-        let bounds = merged_scene.bounds;
-        let mut octree = Octree::new(bounds);
-
-        for (i, mesh) in merged_scene.meshes.iter().enumerate() {
-            octree.insert(i, &mesh.bounds);
-        }
+        let mut staged = self.staged(scene);
+        staged.run(&mut |_: &ProgressEvent| {}, &CancellationToken::new())?;
+        staged.finish()
+    }
 
-        Ok(OptimizedScene {
-            base_scene: merged_scene,
-            lods: lods_by_mesh,
-            spatial_index: octree,
-        })
+    /// Otimização em estágios (merge → dedup → LOD → spatial index)
+    ///
+    /// Cada estágio emite eventos de progresso e pode ser executado
+    /// isoladamente; o cancelamento é verificado entre estágios e o estado
+    /// já calculado é preservado para retomar depois.
+    pub fn staged(&self, scene: &Scene) -> StagedOptimization<'_> {
+        StagedOptimization::new(self, scene.clone())
     }
 }
 
@@ -445,6 +471,8 @@ pub struct OptimizedScene {
     pub base_scene: Scene,
     pub lods: Vec<Vec<Mesh>>, // LODs para cada mesh
     pub spatial_index: Octree,
+    /// Elementos removidos de cada LOD por [`Optimizer::small_object_culling`]
+    pub culling: CullingReport,
 }
 
 impl OptimizedScene {
@@ -459,19 +487,89 @@ impl OptimizedScene {
             return None;
         }
 
-        // Seleção simples baseada em distância
-        let lod_level = if distance < 10.0 {
-            0 // Full detail
-        } else if distance < 50.0 {
-            1.min(lods.len() - 1)
-        } else if distance < 100.0 {
-            2.min(lods.len() - 1)
-        } else {
-            3.min(lods.len() - 1)
-        };
+        // Seleção simples baseada em distância (LOD0 = detalhe completo)
+        let lod_level = LOD_SWITCH_DISTANCES
+            .iter()
+            .take_while(|&&switch| distance >= switch)
+            .count()
+            .min(lods.len() - 1);
 
         Some(&lods[lod_level])
     }
+
+    /// Mede o erro de cada LOD (nível ≥ 1) em relação ao LOD0 da mesma mesh
+    pub fn quality_report(&self, options: &CompareOptions) -> Result<QualityReport> {
+        let mut entries = Vec::new();
+        for (mesh_index, lods) in self.lods.iter().enumerate() {
+            let Some((original, simplified)) = lods.split_first() else {
+                continue;
+            };
+            let original_triangles = original.triangle_count().max(1) as f32;
+
+            for (i, lod) in simplified.iter().enumerate() {
+                let comparison = if lod.triangle_count() == 0 {
+                    None
+                } else {
+                    Some(compare_meshes(original, lod, options)?)
+                };
+                entries.push(LodQuality {
+                    mesh_index,
+                    level: i + 1,
+                    triangle_ratio: lod.triangle_count() as f32 / original_triangles,
+                    comparison,
+                });
+            }
+        }
+        Ok(QualityReport { entries })
+    }
+}
+
+// ============================================================================
+// RELATÓRIO DE QUALIDADE
+// ============================================================================
+
+/// Erro de um LOD em relação à mesh original
+#[derive(Debug, Clone)]
+pub struct LodQuality {
+    pub mesh_index: usize,
+    pub level: usize,
+    /// Triângulos do LOD / triângulos do LOD0
+    pub triangle_ratio: f32,
+    /// `None` quando a simplificação eliminou todos os triângulos
+    pub comparison: Option<MeshComparison>,
+}
+
+/// Resultado de [`OptimizedScene::quality_report`]
+#[derive(Debug, Clone, Default)]
+pub struct QualityReport {
+    pub entries: Vec<LodQuality>,
+}
+
+impl QualityReport {
+    /// LOD com maior Hausdorff relativo à diagonal da mesh
+    pub fn worst_hausdorff(&self) -> Option<&LodQuality> {
+        self.measured()
+            .max_by(|(_, a), (_, b)| a.relative_hausdorff().total_cmp(&b.relative_hausdorff()))
+            .map(|(entry, _)| entry)
+    }
+
+    /// LOD com maior desvio de normal
+    pub fn worst_normal_deviation(&self) -> Option<&LodQuality> {
+        self.measured()
+            .max_by(|(_, a), (_, b)| a.normal_max_deg.total_cmp(&b.normal_max_deg))
+            .map(|(entry, _)| entry)
+    }
+
+    /// LODs sem nenhum triângulo
+    pub fn empty_lods(&self) -> impl Iterator<Item = &LodQuality> {
+        self.entries.iter().filter(|entry| entry.comparison.is_none())
+    }
+
+    fn measured(&self) -> impl Iterator<Item = (&LodQuality, &MeshComparison)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.comparison.as_ref().map(|comparison| (entry, comparison)))
+    }
 }
 
 // ============================================================================
@@ -522,6 +620,9 @@ mod tests {
 
         // LOD0 = original
         assert_eq!(lods[0].indices.len(), mesh.indices.len());
+        let lod0 = compare_meshes(&mesh, &lods[0], &CompareOptions::default()).unwrap();
+        assert!(lod0.hausdorff() < 1e-5);
+        assert!(lod0.normal_max_deg < 1e-2);
 
         // LODs subsequentes devem ter menos triângulos
         for i in 1..lods.len() {
@@ -570,5 +671,18 @@ mod tests {
         // Deve ter LODs
         assert!(!optimized.lods.is_empty());
         assert_eq!(optimized.lods[0].len(), 4); // Original + 3 LODs
+
+        // Relatório cobre os 3 LODs simplificados
+        let report = optimized.quality_report(&CompareOptions { samples: 2_000, ..Default::default() }).unwrap();
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.empty_lods().count(), 0);
+        for entry in &report.entries {
+            assert!(entry.triangle_ratio <= 1.0);
+            let comparison = entry.comparison.as_ref().unwrap();
+            assert!(comparison.hausdorff().is_finite() && comparison.rms() <= comparison.hausdorff());
+            assert!((0.0..=180.0).contains(&comparison.normal_max_deg));
+        }
+        // Cubos decimados perdem faces inteiras: erro visível no pior LOD
+        assert!(report.worst_hausdorff().unwrap().comparison.unwrap().hausdorff() > 0.0);
     }
 }
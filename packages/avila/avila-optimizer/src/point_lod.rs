@@ -0,0 +1,230 @@
+//! LOD de nuvens de pontos por octree
+//!
+//! Scans de obra passam facilmente de centenas de milhões de pontos. A
+//! nuvem é dividida em uma octree em que cada nó guarda uma amostra
+//! espacialmente uniforme dos seus pontos (um ponto por célula de uma grade
+//! regular) e repassa o resto aos filhos. Os pontos não se repetem entre
+//! nós: desenhar a raiz dá uma visão geral, e cada filho carregado só
+//! acrescenta detalhe, como no Potree/Entwine.
+//!
+//! [`PointCloudLod::select`] escolhe os nós a desenhar a partir da posição
+//! da câmera.
+
+use crate::{OptimizerError, Result};
+use avila_mesh::PointCloud;
+use avila_vec3d::*;
+
+/// Parâmetros da subdivisão
+#[derive(Debug, Clone)]
+pub struct PointLodOptions {
+    /// Máximo de pontos em um nó (tamanho do chunk enviado à GPU)
+    pub max_points_per_node: usize,
+    /// Profundidade máxima; no último nível os nós guardam todos os pontos restantes
+    pub max_depth: usize,
+}
+
+impl Default for PointLodOptions {
+    fn default() -> Self {
+        Self {
+            max_points_per_node: 65_536,
+            max_depth: 10,
+        }
+    }
+}
+
+/// Nó da octree
+#[derive(Debug, Clone)]
+pub struct PointChunk {
+    /// Profundidade (0 = raiz)
+    pub level: usize,
+    /// Cubo do nó (não a AABB dos pontos)
+    pub bounds: Aabb,
+    /// Distância entre pontos da amostra (lado da célula da grade);
+    /// 0 em folhas que guardam todos os pontos
+    pub spacing: f32,
+    pub cloud: PointCloud,
+    /// Índices dos filhos em [`PointCloudLod::chunks`]
+    pub children: Vec<usize>,
+}
+
+/// Octree de LOD; `chunks[0]` é a raiz
+#[derive(Debug, Clone)]
+pub struct PointCloudLod {
+    pub chunks: Vec<PointChunk>,
+}
+
+impl PointCloudLod {
+    /// Constrói a octree (determinístico: mesma nuvem, mesmos chunks)
+    pub fn build(cloud: &PointCloud, options: &PointLodOptions) -> Result<Self> {
+        cloud.validate()?;
+        if options.max_points_per_node == 0 {
+            return Err(OptimizerError::OptimizationError(
+                "max_points_per_node must be positive".into(),
+            ));
+        }
+
+        let grid = (options.max_points_per_node as f64).cbrt().floor().max(1.0) as usize;
+        let mut chunks: Vec<PointChunk> = Vec::new();
+        let mut pending = vec![(None::<usize>, cube(&cloud.bounds), 0, (0..cloud.len()).collect::<Vec<_>>())];
+
+        while let Some((parent, bounds, level, indices)) = pending.pop() {
+            let index = chunks.len();
+            if let Some(parent) = parent {
+                chunks[parent].children.push(index);
+            }
+
+            let leaf = indices.len() <= options.max_points_per_node || level >= options.max_depth;
+            let (sample, rest, spacing) = if leaf {
+                (indices, Vec::new(), 0.0)
+            } else {
+                sample_grid(cloud, &bounds, grid, indices)
+            };
+
+            let mut chunk_cloud = cloud.subset(&sample);
+            if let Some(name) = &cloud.name {
+                chunk_cloud.name = Some(format!("{}_{}", name, index));
+            }
+            chunks.push(PointChunk { level, bounds, spacing, cloud: chunk_cloud, children: Vec::new() });
+
+            // Empilhados ao contrário para saírem na ordem dos octantes
+            let mut octants: [Vec<usize>; 8] = Default::default();
+            let center = bounds.center();
+            for i in rest {
+                octants[octant(cloud.positions[i], center)].push(i);
+            }
+            for (octant, points) in octants.into_iter().enumerate().rev() {
+                if !points.is_empty() {
+                    pending.push((Some(index), child_bounds(&bounds, octant), level + 1, points));
+                }
+            }
+        }
+
+        Ok(Self { chunks })
+    }
+
+    pub fn root(&self) -> &PointChunk {
+        &self.chunks[0]
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.chunks.iter().map(|c| c.cloud.len()).sum()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.chunks.iter().map(|c| c.level).max().unwrap_or(0)
+    }
+
+    /// Chunks a desenhar vistos de `eye`
+    ///
+    /// Um nó é refinado (filhos carregados) enquanto o espaçamento da sua
+    /// amostra, visto da câmera, for maior que `max_angle` radianos. A raiz
+    /// sempre entra no resultado.
+    pub fn select(&self, eye: Vec3, max_angle: f32) -> Vec<usize> {
+        let mut selected = Vec::new();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            selected.push(index);
+            let chunk = &self.chunks[index];
+            let distance = distance_to_box(eye, &chunk.bounds);
+            if chunk.spacing > max_angle * distance {
+                stack.extend(chunk.children.iter().rev());
+            }
+        }
+        selected
+    }
+}
+
+/// Cubo com o lado da maior dimensão da caixa, ancorado no mínimo
+fn cube(bounds: &Aabb) -> Aabb {
+    let size = bounds.size();
+    let side = size.x.max(size.y).max(size.z).max(f32::EPSILON);
+    Aabb::new(bounds.min, bounds.min + Vec3::new(side, side, side))
+}
+
+/// Primeiro ponto de cada célula da grade vai para a amostra; o resto desce
+fn sample_grid(cloud: &PointCloud, bounds: &Aabb, grid: usize, indices: Vec<usize>) -> (Vec<usize>, Vec<usize>, f32) {
+    let spacing = bounds.size().x / grid as f32;
+    let cell = |value: f32, min: f32| (((value - min) / spacing) as usize).min(grid - 1);
+
+    let mut occupied = vec![false; grid * grid * grid];
+    let mut sample = Vec::new();
+    let mut rest = Vec::new();
+    for i in indices {
+        let p = cloud.positions[i];
+        let key = (cell(p.x, bounds.min.x) * grid + cell(p.y, bounds.min.y)) * grid + cell(p.z, bounds.min.z);
+        if occupied[key] {
+            rest.push(i);
+        } else {
+            occupied[key] = true;
+            sample.push(i);
+        }
+    }
+    (sample, rest, spacing)
+}
+
+fn octant(point: Vec3, center: Vec3) -> usize {
+    (point.x >= center.x) as usize | ((point.y >= center.y) as usize) << 1 | ((point.z >= center.z) as usize) << 2
+}
+
+fn child_bounds(bounds: &Aabb, octant: usize) -> Aabb {
+    let half = bounds.size() * 0.5;
+    let min = Vec3::new(
+        bounds.min.x + if octant & 1 != 0 { half.x } else { 0.0 },
+        bounds.min.y + if octant & 2 != 0 { half.y } else { 0.0 },
+        bounds.min.z + if octant & 4 != 0 { half.z } else { 0.0 },
+    );
+    Aabb::new(min, min + half)
+}
+
+fn distance_to_box(point: Vec3, bounds: &Aabb) -> f32 {
+    let dx = (bounds.min.x - point.x).max(point.x - bounds.max.x).max(0.0);
+    let dy = (bounds.min.y - point.y).max(point.y - bounds.max.y).max(0.0);
+    let dz = (bounds.min.z - point.z).max(point.z - bounds.max.z).max(0.0);
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::Point;
+
+    #[test]
+    fn test_point_cloud_lod() {
+        let mut cloud = PointCloud::new();
+        for i in 0..20 {
+            for j in 0..20 {
+                for k in 0..20 {
+                    let mut point = Point::new(Vec3::new(i as f32, j as f32, k as f32) * 0.5);
+                    point.intensity = Some((i + j + k) as u16);
+                    cloud.push(point);
+                }
+            }
+        }
+
+        let options = PointLodOptions { max_points_per_node: 512, max_depth: 6 };
+        let lod = PointCloudLod::build(&cloud, &options).unwrap();
+
+        // Cada ponto em exatamente um chunk, nenhum chunk acima do limite
+        assert_eq!(lod.point_count(), cloud.len());
+        assert!(lod.chunks.iter().all(|c| c.cloud.len() <= 512 || c.level == options.max_depth));
+        assert!(lod.chunks.iter().all(|c| c.cloud.intensities.len() == c.cloud.len()));
+        assert!(lod.depth() >= 1);
+        // Amostra da raiz cobre a nuvem inteira
+        assert_eq!(lod.root().cloud.bounds.min, cloud.bounds.min);
+        assert!(lod.root().cloud.bounds.size().x > 8.0);
+        for chunk in &lod.chunks {
+            for &child in &chunk.children {
+                assert_eq!(lod.chunks[child].level, chunk.level + 1);
+            }
+            assert!(chunk.cloud.positions.iter().all(|&p| chunk.bounds.contains_point(p)));
+        }
+
+        // Longe: só a raiz; perto: desce na octree
+        assert_eq!(lod.select(Vec3::new(1000.0, 0.0, 0.0), 0.01), vec![0]);
+        let near = lod.select(Vec3::new(5.0, 5.0, 5.0), 0.01);
+        assert!(near.len() > 1 && near[0] == 0);
+
+        let again = PointCloudLod::build(&cloud, &options).unwrap();
+        assert_eq!(again.chunks[3].cloud.positions, lod.chunks[3].cloud.positions);
+    }
+}
@@ -0,0 +1,512 @@
+//! Otimização em estágios com eventos de progresso
+//!
+//! [`Optimizer::optimize_scene`] executa tudo de uma vez. Para barras de
+//! progresso e cancelamento, [`Optimizer::staged`] devolve uma
+//! [`StagedOptimization`] que executa um estágio por vez:
+//!
+//! 1. [`OptimizationStage::Merge`] - agrupa e concatena meshes por material
+//! 2. [`OptimizationStage::Dedup`] - remove vértices duplicados
+//! 3. [`OptimizationStage::Lod`] - gera os níveis de detalhe
+//! 4. [`OptimizationStage::SpatialIndex`] - monta o octree
+//!
+//! Os eventos vão para um [`ProgressSink`] (closure ou `mpsc::Sender`). O
+//! cancelamento é verificado entre estágios; o resultado dos estágios já
+//! concluídos é mantido e [`StagedOptimization::run`] pode ser chamado de
+//! novo para retomar.
+
+use crate::culling::{self, CullingReport};
+use crate::{OptimizedScene, Optimizer, OptimizerError, Octree, Result};
+use avila_mesh::{Mesh, Scene};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// ESTÁGIOS E EVENTOS
+// ============================================================================
+
+/// Estágio do pipeline de otimização
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizationStage {
+    Merge,
+    Dedup,
+    Lod,
+    SpatialIndex,
+}
+
+impl OptimizationStage {
+    /// Estágios na ordem de execução
+    pub const ALL: [OptimizationStage; 4] = [Self::Merge, Self::Dedup, Self::Lod, Self::SpatialIndex];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Dedup => "dedup",
+            Self::Lod => "lod",
+            Self::SpatialIndex => "spatial-index",
+        }
+    }
+
+    /// Posição no pipeline (0-based)
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+}
+
+/// Evento de progresso
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    StageStarted {
+        stage: OptimizationStage,
+        total: usize,
+    },
+    StageProgress {
+        stage: OptimizationStage,
+        completed: usize,
+        total: usize,
+    },
+    StageCompleted {
+        stage: OptimizationStage,
+        elapsed: Duration,
+    },
+}
+
+impl ProgressEvent {
+    pub fn stage(&self) -> OptimizationStage {
+        match self {
+            Self::StageStarted { stage, .. }
+            | Self::StageProgress { stage, .. }
+            | Self::StageCompleted { stage, .. } => *stage,
+        }
+    }
+
+    /// Progresso global em [0, 1] (estágios com peso igual)
+    pub fn overall_fraction(&self) -> f32 {
+        let stages = OptimizationStage::ALL.len() as f32;
+        let base = self.stage().index() as f32;
+        let within = match self {
+            Self::StageStarted { .. } => 0.0,
+            Self::StageProgress { completed, total, .. } if *total > 0 => *completed as f32 / *total as f32,
+            Self::StageProgress { .. } => 0.0,
+            Self::StageCompleted { .. } => 1.0,
+        };
+        (base + within) / stages
+    }
+}
+
+/// Destino dos eventos de progresso
+pub trait ProgressSink {
+    fn emit(&mut self, event: &ProgressEvent);
+}
+
+impl<F: FnMut(&ProgressEvent)> ProgressSink for F {
+    fn emit(&mut self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+impl ProgressSink for Sender<ProgressEvent> {
+    fn emit(&mut self, event: &ProgressEvent) {
+        // Receptor descartado: ninguém observa o progresso, a otimização segue
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Sinal de cancelamento compartilhável entre threads
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Rearma o token (para retomar após um cancelamento)
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+// ============================================================================
+// OTIMIZAÇÃO EM ESTÁGIOS
+// ============================================================================
+
+/// Mesh resultante do merge de um grupo de material
+struct MergedGroup {
+    mesh: Mesh,
+    /// Índices das meshes de origem em `scene.meshes`
+    sources: Vec<usize>,
+    /// Só grupos com mais de uma mesh passam pela deduplicação
+    /// (mesmo critério de [`crate::MeshMerger::merge_meshes`])
+    needs_dedup: bool,
+}
+
+/// Otimização retomável, um estágio por vez
+pub struct StagedOptimization<'a> {
+    optimizer: &'a Optimizer,
+    scene: Scene,
+    next: usize,
+    groups: Vec<MergedGroup>,
+    lods: Vec<Vec<Mesh>>,
+    culling: CullingReport,
+    spatial_index: Option<Octree>,
+}
+
+impl<'a> StagedOptimization<'a> {
+    pub(crate) fn new(optimizer: &'a Optimizer, scene: Scene) -> Self {
+        Self {
+            optimizer,
+            scene,
+            next: 0,
+            groups: Vec::new(),
+            lods: Vec::new(),
+            culling: CullingReport::default(),
+            spatial_index: None,
+        }
+    }
+
+    /// Próximo estágio a executar (`None` quando concluído)
+    pub fn next_stage(&self) -> Option<OptimizationStage> {
+        OptimizationStage::ALL.get(self.next).copied()
+    }
+
+    pub fn completed_stages(&self) -> &'static [OptimizationStage] {
+        &OptimizationStage::ALL[..self.next]
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_stage().is_none()
+    }
+
+    /// Executa os estágios restantes, verificando `cancel` antes de cada um
+    pub fn run(&mut self, sink: &mut dyn ProgressSink, cancel: &CancellationToken) -> Result<()> {
+        while let Some(stage) = self.next_stage() {
+            if cancel.is_cancelled() {
+                return Err(OptimizerError::Cancelled(stage));
+            }
+            self.run_stage(sink)?;
+        }
+        Ok(())
+    }
+
+    /// Executa apenas o próximo estágio
+    pub fn run_stage(&mut self, sink: &mut dyn ProgressSink) -> Result<Option<OptimizationStage>> {
+        let Some(stage) = self.next_stage() else {
+            return Ok(None);
+        };
+        let started = Instant::now();
+
+        match stage {
+            OptimizationStage::Merge => self.merge(sink)?,
+            OptimizationStage::Dedup => self.dedup(sink)?,
+            OptimizationStage::Lod => self.generate_lods(sink)?,
+            OptimizationStage::SpatialIndex => self.build_spatial_index(sink),
+        }
+
+        self.next += 1;
+        sink.emit(&ProgressEvent::StageCompleted {
+            stage,
+            elapsed: started.elapsed(),
+        });
+        Ok(Some(stage))
+    }
+
+    /// Monta a cena otimizada (todos os estágios precisam ter sido executados)
+    pub fn finish(self) -> Result<OptimizedScene> {
+        if let Some(stage) = self.next_stage() {
+            return Err(OptimizerError::OptimizationError(format!(
+                "Stage {} has not run yet",
+                stage.name()
+            )));
+        }
+
+        let mut base_scene = Scene::new();
+        for group in self.groups {
+            base_scene.add_mesh(group.mesh);
+        }
+        base_scene.materials = self.scene.materials;
+        base_scene.textures = self.scene.textures;
+        for cloud in self.scene.point_clouds {
+            base_scene.add_point_cloud(cloud);
+        }
+
+        Ok(OptimizedScene {
+            base_scene,
+            lods: self.lods,
+            culling: self.culling,
+            spatial_index: self.spatial_index.expect("spatial index stage completed"),
+        })
+    }
+
+    fn merge(&mut self, sink: &mut dyn ProgressSink) -> Result<()> {
+        // Agrupar por material, preservando a ordem de primeira ocorrência
+        let mut order: Vec<Option<String>> = Vec::new();
+        let mut by_material: HashMap<Option<String>, Vec<usize>> = HashMap::new();
+        for (index, mesh) in self.scene.meshes.iter().enumerate() {
+            by_material
+                .entry(mesh.material_id.clone())
+                .or_insert_with(|| {
+                    order.push(mesh.material_id.clone());
+                    Vec::new()
+                })
+                .push(index);
+        }
+
+        let total = order.len();
+        sink.emit(&ProgressEvent::StageStarted { stage: OptimizationStage::Merge, total });
+
+        let merger = &self.optimizer.merger;
+        let mut groups = Vec::with_capacity(total);
+        for (completed, material_id) in order.into_iter().enumerate() {
+            let sources = by_material.remove(&material_id).unwrap_or_default();
+            let meshes: Vec<&Mesh> = sources.iter().map(|&index| &self.scene.meshes[index]).collect();
+            let mut mesh = merger.concat_meshes(&meshes)?;
+            mesh.material_id = material_id;
+            groups.push(MergedGroup {
+                mesh,
+                sources,
+                needs_dedup: meshes.len() > 1 && merger.vertex_tolerance > 0.0,
+            });
+
+            sink.emit(&ProgressEvent::StageProgress {
+                stage: OptimizationStage::Merge,
+                completed: completed + 1,
+                total,
+            });
+        }
+
+        if self.optimizer.canonical_order {
+            groups.sort_by(|a, b| a.mesh.material_id.cmp(&b.mesh.material_id));
+        }
+        self.groups = groups;
+        Ok(())
+    }
+
+    fn dedup(&mut self, sink: &mut dyn ProgressSink) -> Result<()> {
+        let total = self.groups.len();
+        sink.emit(&ProgressEvent::StageStarted { stage: OptimizationStage::Dedup, total });
+
+        for (completed, group) in self.groups.iter_mut().enumerate() {
+            if group.needs_dedup {
+                self.optimizer.merger.deduplicate_vertices(&mut group.mesh)?;
+                group.needs_dedup = false;
+            }
+            if self.optimizer.canonical_order {
+                group.mesh.canonicalize();
+            }
+            sink.emit(&ProgressEvent::StageProgress {
+                stage: OptimizationStage::Dedup,
+                completed: completed + 1,
+                total,
+            });
+        }
+        Ok(())
+    }
+
+    fn generate_lods(&mut self, sink: &mut dyn ProgressSink) -> Result<()> {
+        let total = self.groups.len();
+        sink.emit(&ProgressEvent::StageStarted { stage: OptimizationStage::Lod, total });
+
+        let mut lods = Vec::with_capacity(total);
+        let mut report = CullingReport::default();
+        for (index, group) in self.groups.iter().enumerate() {
+            let levels = match &self.optimizer.small_object_culling {
+                Some(culling) => {
+                    let sources: Vec<&Mesh> = group.sources.iter().map(|&source| &self.scene.meshes[source]).collect();
+                    culling::generate_lods(self.optimizer, culling, index, &group.mesh, &sources, &mut report)?
+                }
+                None => self.optimizer.lod_generator.generate_lods(&group.mesh)?,
+            };
+            lods.push(levels);
+            sink.emit(&ProgressEvent::StageProgress {
+                stage: OptimizationStage::Lod,
+                completed: index + 1,
+                total,
+            });
+        }
+
+        self.culling = report;
+        self.lods = lods;
+        Ok(())
+    }
+
+    fn build_spatial_index(&mut self, sink: &mut dyn ProgressSink) {
+        let total = self.groups.len();
+        sink.emit(&ProgressEvent::StageStarted { stage: OptimizationStage::SpatialIndex, total });
+
+        let bounds = self
+            .groups
+            .iter()
+            .fold(avila_vec3d::Aabb::EMPTY, |bounds, group| bounds.merge(&group.mesh.bounds));
+        let mut octree = Octree::new(bounds);
+        for (i, group) in self.groups.iter().enumerate() {
+            octree.insert(i, &group.mesh.bounds);
+            sink.emit(&ProgressEvent::StageProgress {
+                stage: OptimizationStage::SpatialIndex,
+                completed: i + 1,
+                total,
+            });
+        }
+
+        self.spatial_index = Some(octree);
+    }
+}
+
+// ============================================================================
+// EXECUÇÃO EM BACKGROUND
+// ============================================================================
+
+/// Otimização em execução numa thread separada
+pub struct OptimizationTask {
+    /// Eventos de progresso (fecha quando a thread termina)
+    pub events: Receiver<ProgressEvent>,
+    cancel: CancellationToken,
+    handle: JoinHandle<Result<OptimizedScene>>,
+}
+
+impl OptimizationTask {
+    /// Solicita cancelamento (efetivo no próximo limite entre estágios)
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Aguarda o término
+    pub fn join(self) -> Result<OptimizedScene> {
+        self.handle
+            .join()
+            .map_err(|_| OptimizerError::OptimizationError("Optimizer thread panicked".into()))?
+    }
+}
+
+impl Optimizer {
+    /// Otimiza `scene` numa thread dedicada, com eventos em um canal
+    pub fn optimize_in_background(self, scene: Scene) -> OptimizationTask {
+        let (sender, events) = mpsc::channel();
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+
+        let handle = std::thread::spawn(move || {
+            let mut sender = sender;
+            let mut staged = self.staged(&scene);
+            staged.run(&mut sender, &token)?;
+            staged.finish()
+        });
+
+        OptimizationTask { events, cancel, handle }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::primitives;
+
+    fn scene() -> Scene {
+        let mut scene = Scene::new();
+        for material in ["concrete", "steel", "concrete"] {
+            let mut cube = primitives::cube(1.0);
+            cube.material_id = Some(material.into());
+            scene.add_mesh(cube);
+        }
+        scene
+    }
+
+    #[test]
+    fn test_stages_emit_progress_in_order() {
+        let optimizer = Optimizer::new();
+        let mut events = Vec::new();
+        let mut staged = optimizer.staged(&scene());
+        staged
+            .run(&mut |event: &ProgressEvent| events.push(event.clone()), &CancellationToken::new())
+            .unwrap();
+
+        let completed: Vec<OptimizationStage> = events
+            .iter()
+            .filter(|e| matches!(e, ProgressEvent::StageCompleted { .. }))
+            .map(ProgressEvent::stage)
+            .collect();
+        assert_eq!(completed, OptimizationStage::ALL);
+        assert_eq!(
+            events[1],
+            ProgressEvent::StageProgress { stage: OptimizationStage::Merge, completed: 1, total: 2 }
+        );
+        assert_eq!(events.last().unwrap().overall_fraction(), 1.0);
+
+        let optimized = staged.finish().unwrap();
+        assert_eq!(optimized.base_scene.meshes.len(), 2);
+        assert_eq!(optimized.lods.len(), 2);
+    }
+
+    #[test]
+    fn test_cancel_and_resume() {
+        let optimizer = Optimizer::new();
+        let cancel = CancellationToken::new();
+        let mut staged = optimizer.staged(&scene());
+
+        // Cancela logo após o merge
+        let mut sink = |event: &ProgressEvent| {
+            if matches!(event, ProgressEvent::StageCompleted { stage: OptimizationStage::Merge, .. }) {
+                cancel.cancel();
+            }
+        };
+        let err = staged.run(&mut sink, &cancel).unwrap_err();
+        assert!(matches!(err, OptimizerError::Cancelled(OptimizationStage::Dedup)));
+        assert_eq!(staged.completed_stages(), [OptimizationStage::Merge]);
+
+        cancel.reset();
+        staged.run(&mut |_: &ProgressEvent| {}, &cancel).unwrap();
+        let resumed = staged.finish().unwrap();
+
+        let direct = optimizer.optimize_scene(&scene()).unwrap();
+        assert_eq!(resumed.base_scene.vertex_count(), direct.base_scene.vertex_count());
+    }
+
+    #[test]
+    fn test_background_task_streams_events() {
+        let task = Optimizer::new().optimize_in_background(scene());
+        let events: Vec<ProgressEvent> = task.events.iter().collect();
+        let optimized = task.join().unwrap();
+
+        assert!(matches!(events[0], ProgressEvent::StageStarted { stage: OptimizationStage::Merge, .. }));
+        assert_eq!(optimized.base_scene.meshes.len(), 2);
+    }
+
+    #[test]
+    fn test_canonical_order_ignores_input_order() {
+        let mut optimizer = Optimizer::new();
+        optimizer.canonical_order = true;
+        optimizer.merger.vertex_tolerance = 0.0;
+
+        let mut forward = Scene::new();
+        for (i, material) in ["steel", "concrete", "steel"].into_iter().enumerate() {
+            let mut cube = primitives::cube(1.0);
+            cube.transform(&avila_vec3d::Mat4::translation(avila_vec3d::Vec3::new(i as f32 * 2.0, 0.0, 0.0)));
+            cube.material_id = Some(material.into());
+            forward.add_mesh(cube);
+        }
+        let mut reversed = Scene::new();
+        for mesh in forward.meshes.iter().rev() {
+            reversed.add_mesh(mesh.clone());
+        }
+
+        let a = optimizer.optimize_scene(&forward).unwrap().base_scene;
+        let b = optimizer.optimize_scene(&reversed).unwrap().base_scene;
+        let materials: Vec<_> = a.meshes.iter().map(|m| m.material_id.as_deref()).collect();
+        assert_eq!(materials, [Some("concrete"), Some("steel")]);
+        for (a, b) in a.meshes.iter().zip(&b.meshes) {
+            assert_eq!(a.vertices, b.vertices);
+            assert_eq!(a.indices, b.indices);
+        }
+    }
+}
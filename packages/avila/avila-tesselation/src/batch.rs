@@ -0,0 +1,237 @@
+//! Tesselação em lote, multi-thread
+//!
+//! [`Tesselator::tesselate_batch`] distribui os elementos entre threads,
+//! reporta o progresso (elementos concluídos / total, GUID atual) na thread
+//! chamadora e pode ser interrompido por um [`CancellationToken`].
+//!
+//! Falhas de um elemento não abortam o lote: cada posição do resultado tem o
+//! seu próprio `Result`.
+
+use crate::{IfcGeometry, Result, TesselationError, Tesselator};
+use avila_mesh::Mesh;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// Elemento de um lote: a geometria e, opcionalmente, o GUID do elemento IFC
+pub trait BatchItem: Sync {
+    fn geometry(&self) -> &IfcGeometry;
+
+    fn guid(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl BatchItem for IfcGeometry {
+    fn geometry(&self) -> &IfcGeometry {
+        self
+    }
+}
+
+impl BatchItem for (String, IfcGeometry) {
+    fn geometry(&self) -> &IfcGeometry {
+        &self.1
+    }
+
+    fn guid(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
+/// Sinal de cancelamento compartilhado entre threads
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Opções do lote
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Número de threads (0 = `available_parallelism`)
+    pub threads: usize,
+    pub cancellation: Option<CancellationToken>,
+    /// Coloca cada mesh na ordem canônica ([`Mesh::canonicalize`]), para que
+    /// a saída não dependa do escalonamento das threads
+    pub canonical_order: bool,
+}
+
+impl BatchOptions {
+    fn thread_count(&self, items: usize) -> usize {
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        };
+        threads.min(items).max(1)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|c| c.is_cancelled())
+    }
+}
+
+/// Progresso após a conclusão de um elemento
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress<'a> {
+    /// Elementos concluídos (inclusive os que falharam)
+    pub completed: usize,
+    pub total: usize,
+    /// Posição do elemento no lote
+    pub index: usize,
+    pub guid: Option<&'a str>,
+}
+
+impl BatchProgress<'_> {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f32 / self.total as f32
+        }
+    }
+}
+
+impl Tesselator {
+    /// Tessela um lote de geometrias em paralelo
+    ///
+    /// `progress` é chamado na thread chamadora, uma vez por elemento, na
+    /// ordem de conclusão. Devolve [`TesselationError::Cancelled`] se o token
+    /// for acionado antes do fim do lote.
+    pub fn tesselate_batch<'a, G, F>(
+        &self,
+        items: &'a [G],
+        options: &BatchOptions,
+        mut progress: F,
+    ) -> Result<Vec<Result<Mesh>>>
+    where
+        G: BatchItem,
+        F: FnMut(BatchProgress<'a>),
+    {
+        let total = items.len();
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<Option<Result<Mesh>>> = (0..total).map(|_| None).collect();
+        let mut completed = 0;
+
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel();
+            for _ in 0..options.thread_count(total) {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    if options.is_cancelled() {
+                        break;
+                    }
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else { break };
+                    let mut result = self.tesselate(item.geometry());
+                    if options.canonical_order {
+                        if let Ok(mesh) = &mut result {
+                            mesh.canonicalize();
+                        }
+                    }
+                    if tx.send((index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(tx);
+
+            for (index, result) in rx {
+                results[index] = Some(result);
+                completed += 1;
+                progress(BatchProgress { completed, total, index, guid: items[index].guid() });
+            }
+        });
+
+        if completed < total {
+            return Err(TesselationError::Cancelled);
+        }
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+// ============================================================================
+// TESTES
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_vec3d::Vec3;
+
+    fn boxes(count: usize) -> Vec<(String, IfcGeometry)> {
+        (0..count)
+            .map(|i| {
+                let geometry = IfcGeometry::Box { center: Vec3::new(i as f32, 0.0, 0.0), size: Vec3::ONE };
+                (format!("guid-{i}"), geometry)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_matches_sequential() {
+        let tesselator = Tesselator::new();
+        let items = boxes(40);
+        let options = BatchOptions { threads: 4, ..Default::default() };
+
+        let mut seen = Vec::new();
+        let meshes = tesselator
+            .tesselate_batch(&items, &options, |p| seen.push((p.completed, p.guid.unwrap().to_string())))
+            .unwrap();
+
+        assert_eq!(meshes.len(), 40);
+        for ((_, geometry), mesh) in items.iter().zip(&meshes) {
+            let expected = tesselator.tesselate(geometry).unwrap();
+            assert_eq!(mesh.as_ref().unwrap().bounds.center(), expected.bounds.center());
+        }
+        assert_eq!(seen.len(), 40);
+        assert_eq!(seen.last().unwrap().0, 40);
+        assert!(seen.iter().any(|(_, guid)| guid == "guid-39"));
+
+        let canonical = BatchOptions { threads: 3, canonical_order: true, ..Default::default() };
+        let first = tesselator.tesselate_batch(&items, &canonical, |_| {}).unwrap();
+        let second = tesselator.tesselate_batch(&items, &canonical, |_| {}).unwrap();
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.as_ref().unwrap().indices, b.as_ref().unwrap().indices);
+            assert_eq!(a.as_ref().unwrap().vertices, b.as_ref().unwrap().vertices);
+        }
+
+        // Falha isolada não derruba o lote
+        let geometries = [
+            IfcGeometry::Box { center: Vec3::ZERO, size: Vec3::ONE },
+            IfcGeometry::HalfSpace { base_point: Vec3::ZERO, normal: Vec3::Y, agreement_flag: true },
+        ];
+        let results = tesselator.tesselate_batch(&geometries, &BatchOptions::default(), |_| {}).unwrap();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_batch_cancellation() {
+        let tesselator = Tesselator::new();
+        let items = boxes(100);
+        let token = CancellationToken::new();
+        let options = BatchOptions { threads: 2, cancellation: Some(token.clone()), ..Default::default() };
+
+        token.cancel();
+        let mut calls = 0;
+        let result = tesselator.tesselate_batch(&items, &options, |_| calls += 1);
+        assert!(matches!(result, Err(TesselationError::Cancelled)));
+        assert_eq!(calls, 0);
+
+        // Lote vazio não é cancelamento
+        let empty: [IfcGeometry; 0] = [];
+        assert!(tesselator.tesselate_batch(&empty, &options, |_| {}).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+//! Cache de geometria para `IfcMappedItem`
+//!
+//! Representações reutilizadas (`IfcRepresentationMap`) são tesseladas uma
+//! única vez por [`Tesselator`](crate::Tesselator) e compartilhadas via
+//! `Arc`; cada ocorrência vira uma [`MeshInstance`] com sua transformação.
+
+use avila_mesh::Mesh;
+use avila_vec3d::Mat4;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Ocorrência de uma representação compartilhada
+#[derive(Debug, Clone)]
+pub struct MeshInstance {
+    pub representation_id: u64,
+    pub mesh: Arc<Mesh>,
+    pub transform: Mat4,
+}
+
+impl MeshInstance {
+    /// Mesh com a transformação aplicada (cópia)
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = (*self.mesh).clone();
+        mesh.transform(&self.transform);
+        mesh
+    }
+}
+
+/// Estatísticas do cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Representações em cache
+    pub entries: usize,
+    /// Triângulos armazenados (uma vez por representação)
+    pub cached_triangles: usize,
+}
+
+impl CacheStats {
+    /// Fração de acessos atendidos pelo cache
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Cache thread-safe de meshes por id de representação
+#[derive(Debug, Default)]
+pub(crate) struct GeometryCache {
+    meshes: Mutex<HashMap<u64, Arc<Mesh>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl GeometryCache {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Arc<Mesh>>> {
+        self.meshes.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn get(&self, id: u64) -> Option<Arc<Mesh>> {
+        let mesh = self.lock().get(&id).cloned();
+        let counter = if mesh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        mesh
+    }
+
+    /// Insere e devolve a mesh em cache (a primeira inserção vence)
+    pub(crate) fn insert(&self, id: u64, mesh: Mesh) -> Arc<Mesh> {
+        self.lock().entry(id).or_insert_with(|| Arc::new(mesh)).clone()
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let meshes = self.lock();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: meshes.len(),
+            cached_triangles: meshes.values().map(|m| m.triangle_count()).sum(),
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,489 @@
+//! Operações booleanas entre malhas (CSG por árvore BSP)
+//!
+//! Cobre `IfcBooleanResult` / `IfcBooleanClippingResult`: união, interseção
+//! e diferença entre sólidos fechados, além do recorte por semi-espaço
+//! (`IfcHalfSpaceSolid`), comum em telhados e lajes.
+//!
+//! Os sólidos de entrada devem ser fechados e ter faces orientadas para
+//! fora. O cálculo é feito em `f64`; o resultado sai com normais planas.
+
+use avila_mesh::{Mesh, Vertex};
+use avila_vec3d::{Aabb, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Tolerância para classificar pontos em relação a um plano
+const EPSILON: f64 = 1e-5;
+
+/// Operador booleano (`IfcBooleanOperator`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BooleanOperator {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// Aplica `op` entre os sólidos `a` e `b`
+pub fn boolean(a: &Mesh, b: &Mesh, op: BooleanOperator) -> Mesh {
+    let a = Solid::from_mesh(a);
+    let b = Solid::from_mesh(b);
+    a.apply(b, op).into_mesh()
+}
+
+/// Remove de `mesh` o material do semi-espaço definido pelo plano
+/// (`point`, `normal`)
+///
+/// Como no IFC, `agreement_flag = true` indica que a normal aponta para fora
+/// do material do semi-espaço (o material fica do lado oposto à normal).
+pub fn clip_half_space(mesh: &Mesh, point: Vec3, normal: Vec3, agreement_flag: bool) -> Mesh {
+    let half_space = Solid::half_space(point, normal, agreement_flag, &mesh.bounds);
+    Solid::from_mesh(mesh).apply(half_space, BooleanOperator::Difference).into_mesh()
+}
+
+// ============================================================================
+// GEOMETRIA (f64)
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DVec3 {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl DVec3 {
+    const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    fn from_vec3(v: Vec3) -> Self {
+        Self::new(v.x as f64, v.y as f64, v.z as f64)
+    }
+
+    fn to_vec3(self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+
+    fn add(self, o: Self) -> Self {
+        Self::new(self.x + o.x, self.y + o.y, self.z + o.z)
+    }
+
+    fn sub(self, o: Self) -> Self {
+        Self::new(self.x - o.x, self.y - o.y, self.z - o.z)
+    }
+
+    fn scale(self, s: f64) -> Self {
+        Self::new(self.x * s, self.y * s, self.z * s)
+    }
+
+    fn dot(self, o: Self) -> f64 {
+        self.x * o.x + self.y * o.y + self.z * o.z
+    }
+
+    fn cross(self, o: Self) -> Self {
+        Self::new(
+            self.y * o.z - self.z * o.y,
+            self.z * o.x - self.x * o.z,
+            self.x * o.y - self.y * o.x,
+        )
+    }
+
+    fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    fn lerp(self, o: Self, t: f64) -> Self {
+        self.add(o.sub(self).scale(t))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Plane {
+    normal: DVec3,
+    w: f64,
+}
+
+impl Plane {
+    fn from_points(a: DVec3, b: DVec3, c: DVec3) -> Option<Self> {
+        let n = b.sub(a).cross(c.sub(a));
+        let length = n.length();
+        if length < EPSILON * EPSILON {
+            return None;
+        }
+        let normal = n.scale(1.0 / length);
+        Some(Self { normal, w: normal.dot(a) })
+    }
+
+    fn flip(&mut self) {
+        self.normal = self.normal.scale(-1.0);
+        self.w = -self.w;
+    }
+
+    /// Divide `polygon` pelo plano, distribuindo os pedaços nas listas
+    fn split(
+        &self,
+        polygon: &Polygon,
+        coplanar_front: &mut Vec<Polygon>,
+        coplanar_back: &mut Vec<Polygon>,
+        front: &mut Vec<Polygon>,
+        back: &mut Vec<Polygon>,
+    ) {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let mut polygon_type = COPLANAR;
+        let types: Vec<u8> = polygon
+            .vertices
+            .iter()
+            .map(|v| {
+                let t = self.normal.dot(*v) - self.w;
+                let vertex_type = if t < -EPSILON {
+                    BACK
+                } else if t > EPSILON {
+                    FRONT
+                } else {
+                    COPLANAR
+                };
+                polygon_type |= vertex_type;
+                vertex_type
+            })
+            .collect();
+
+        match polygon_type {
+            COPLANAR => {
+                if self.normal.dot(polygon.plane.normal) > 0.0 {
+                    coplanar_front.push(polygon.clone());
+                } else {
+                    coplanar_back.push(polygon.clone());
+                }
+            }
+            FRONT => front.push(polygon.clone()),
+            BACK => back.push(polygon.clone()),
+            _ => {
+                let (mut f, mut b) = (Vec::new(), Vec::new());
+                let n = polygon.vertices.len();
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (polygon.vertices[i], polygon.vertices[j]);
+                    if ti != BACK {
+                        f.push(vi);
+                    }
+                    if ti != FRONT {
+                        b.push(vi);
+                    }
+                    if (ti | tj) == SPANNING {
+                        let t = (self.w - self.normal.dot(vi)) / self.normal.dot(vj.sub(vi));
+                        let v = vi.lerp(vj, t);
+                        f.push(v);
+                        b.push(v);
+                    }
+                }
+                if f.len() >= 3 {
+                    front.push(Polygon { vertices: f, plane: polygon.plane });
+                }
+                if b.len() >= 3 {
+                    back.push(Polygon { vertices: b, plane: polygon.plane });
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Polygon {
+    vertices: Vec<DVec3>,
+    plane: Plane,
+}
+
+impl Polygon {
+    fn new(vertices: Vec<DVec3>) -> Option<Self> {
+        let plane = Plane::from_points(vertices[0], vertices[1], vertices[2])?;
+        Some(Self { vertices, plane })
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane.flip();
+    }
+}
+
+// ============================================================================
+// ÁRVORE BSP
+// ============================================================================
+
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>) -> Self {
+        let mut node = Self::default();
+        node.build(polygons);
+        node
+    }
+
+    /// Converte sólido ↔ complemento
+    fn invert(&mut self) {
+        for polygon in &mut self.polygons {
+            polygon.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.flip();
+        }
+        if let Some(front) = &mut self.front {
+            front.invert();
+        }
+        if let Some(back) = &mut self.back {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Remove de `polygons` as partes dentro deste sólido
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let Some(plane) = &self.plane else {
+            return polygons;
+        };
+
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for polygon in &polygons {
+            let (mut coplanar_front, mut coplanar_back) = (Vec::new(), Vec::new());
+            plane.split(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+            front.append(&mut coplanar_front);
+            back.append(&mut coplanar_back);
+        }
+
+        let mut front = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        let back = match &self.back {
+            Some(node) => node.clip_polygons(back),
+            None => Vec::new(),
+        };
+        front.extend(back);
+        front
+    }
+
+    /// Remove deste nó as partes dentro de `other`
+    fn clip_to(&mut self, other: &Node) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = &mut self.front {
+            front.clip_to(other);
+        }
+        if let Some(back) = &mut self.back {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut polygons = self.polygons.clone();
+        if let Some(front) = &self.front {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            polygons.extend(back.all_polygons());
+        }
+        polygons
+    }
+
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        let plane = *self.plane.get_or_insert(polygons[0].plane);
+
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for polygon in &polygons {
+            let (mut coplanar_front, mut coplanar_back) = (Vec::new(), Vec::new());
+            plane.split(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+            self.polygons.append(&mut coplanar_front);
+            self.polygons.append(&mut coplanar_back);
+        }
+
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back);
+        }
+    }
+}
+
+// ============================================================================
+// SÓLIDOS
+// ============================================================================
+
+struct Solid {
+    polygons: Vec<Polygon>,
+}
+
+impl Solid {
+    fn from_mesh(mesh: &Mesh) -> Self {
+        let polygons = mesh
+            .indices
+            .chunks_exact(3)
+            .filter_map(|t| {
+                let v = |i: u32| DVec3::from_vec3(mesh.vertices[i as usize].position);
+                Polygon::new(vec![v(t[0]), v(t[1]), v(t[2])])
+            })
+            .collect();
+        Self { polygons }
+    }
+
+    /// Caixa finita que representa o semi-espaço na região de `bounds`
+    fn half_space(point: Vec3, normal: Vec3, agreement_flag: bool, bounds: &Aabb) -> Self {
+        let point = DVec3::from_vec3(point);
+        let mut normal = DVec3::from_vec3(normal);
+        normal = normal.scale(1.0 / normal.length().max(f64::MIN_POSITIVE));
+        let material = if agreement_flag { normal.scale(-1.0) } else { normal };
+
+        // Extensão suficiente para cobrir o operando inteiro
+        let (min, max) = (DVec3::from_vec3(bounds.min), DVec3::from_vec3(bounds.max));
+        let center = min.lerp(max, 0.5);
+        let extent = max.sub(min).length() + center.sub(point).dot(normal).abs() + 1.0;
+
+        // Base ortonormal (u, v, material)
+        let helper = if material.x.abs() < 0.9 { DVec3::new(1.0, 0.0, 0.0) } else { DVec3::new(0.0, 1.0, 0.0) };
+        let u = material.cross(helper);
+        let u = u.scale(1.0 / u.length());
+        let v = material.cross(u);
+
+        let origin = point.add(center.sub(point).sub(normal.scale(center.sub(point).dot(normal))));
+        let corner = |su: f64, sv: f64, depth: f64| {
+            origin.add(u.scale(su * extent)).add(v.scale(sv * extent)).add(material.scale(depth * 2.0 * extent))
+        };
+
+        // Faces com normais para fora (u × v = material)
+        let c = [
+            corner(-1.0, -1.0, 0.0),
+            corner(1.0, -1.0, 0.0),
+            corner(1.0, 1.0, 0.0),
+            corner(-1.0, 1.0, 0.0),
+            corner(-1.0, -1.0, 1.0),
+            corner(1.0, -1.0, 1.0),
+            corner(1.0, 1.0, 1.0),
+            corner(-1.0, 1.0, 1.0),
+        ];
+        let faces = [
+            [0, 3, 2, 1], // plano de corte (normal = -material)
+            [4, 5, 6, 7],
+            [0, 1, 5, 4],
+            [1, 2, 6, 5],
+            [2, 3, 7, 6],
+            [3, 0, 4, 7],
+        ];
+        let polygons = faces
+            .iter()
+            .filter_map(|face| Polygon::new(face.iter().map(|&i| c[i]).collect()))
+            .collect();
+        Self { polygons }
+    }
+
+    fn apply(self, other: Solid, op: BooleanOperator) -> Solid {
+        let mut a = Node::new(self.polygons);
+        let mut b = Node::new(other.polygons);
+
+        match op {
+            BooleanOperator::Union => {
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+            }
+            BooleanOperator::Difference => {
+                a.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+                a.invert();
+            }
+            BooleanOperator::Intersection => {
+                a.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                a.build(b.all_polygons());
+                a.invert();
+            }
+        }
+
+        Solid { polygons: a.all_polygons() }
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new();
+        for polygon in self.polygons {
+            let normal = polygon.plane.normal.to_vec3();
+            let base = mesh.vertices.len() as u32;
+            for v in &polygon.vertices {
+                mesh.add_vertex(Vertex::new(v.to_vec3()).with_normal(normal));
+            }
+            // Polígonos convexos: leque
+            for i in 1..polygon.vertices.len() as u32 - 1 {
+                mesh.indices.extend_from_slice(&[base, base + i, base + i + 1]);
+            }
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::primitives;
+    use avila_vec3d::Mat4;
+
+    fn signed_volume(mesh: &Mesh) -> f32 {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let a = mesh.vertices[t[0] as usize].position;
+                let b = mesh.vertices[t[1] as usize].position;
+                let c = mesh.vertices[t[2] as usize].position;
+                a.dot(&b.cross(&c)) / 6.0
+            })
+            .sum()
+    }
+
+    fn cube_at(size: f32, center: Vec3) -> Mesh {
+        let mut cube = primitives::cube(size);
+        cube.transform(&Mat4::translation(center));
+        cube
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let a = primitives::cube(2.0);
+        let b = cube_at(2.0, Vec3::new(1.0, 0.0, 0.0));
+
+        assert!((signed_volume(&boolean(&a, &b, BooleanOperator::Union)) - 12.0).abs() < 1e-3);
+        assert!((signed_volume(&boolean(&a, &b, BooleanOperator::Intersection)) - 4.0).abs() < 1e-3);
+        assert!((signed_volume(&boolean(&a, &b, BooleanOperator::Difference)) - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_half_space_clip() {
+        let cube = primitives::cube(2.0);
+
+        // Material abaixo de z = 0.5 (normal aponta para fora dele)
+        let kept_top = clip_half_space(&cube, Vec3::new(0.0, 0.0, 0.5), Vec3::Z, true);
+        assert!((signed_volume(&kept_top) - 2.0).abs() < 1e-3);
+        assert!(kept_top.vertices.iter().all(|v| v.position.z >= 0.5 - 1e-4));
+
+        let kept_bottom = clip_half_space(&cube, Vec3::new(0.0, 0.0, 0.5), Vec3::Z, false);
+        assert!((signed_volume(&kept_bottom) - 6.0).abs() < 1e-3);
+    }
+}
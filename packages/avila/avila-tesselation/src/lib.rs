@@ -3,17 +3,39 @@
 //! **Engine de Tesselação - Conversão de Sólidos IFC em Triângulos**
 //!
 //! Converte representações geométricas de alto nível (IFC) em meshes trianguladas:
-//! - Extruded Solids (perfis extrudados)
+//! - Extruded Solids (perfis extrudados, inclusive curvos: círculos, arcos, curvas compostas)
 //! - BRep (Boundary Representation)
-//! - CSG (Constructive Solid Geometry)
+//! - CSG (Constructive Solid Geometry): `IfcBooleanResult`, recorte por semi-espaço
 //! - Swept Solids
 //!
 //! Pipeline: IFC Geometry → Tesselação → Mesh 3D
+//!
+//! Cortes de seção com tampa triangulada ficam em [`section`]; plantas
+//! baixas 2D por pavimento, em [`plan`].
+//!
+//! Para modelos grandes, [`Tesselator::tesselate_batch`] tessela em paralelo
+//! com progresso e cancelamento.
 
 use avila_vec3d::*;
 use avila_mesh::*;
 use serde::{Deserialize, Serialize};
 
+pub mod batch;
+pub mod cache;
+pub mod csg;
+pub mod plan;
+pub mod profile;
+pub mod section;
+pub mod triangulation;
+
+pub use batch::{BatchItem, BatchOptions, BatchProgress, CancellationToken};
+pub use cache::{CacheStats, MeshInstance};
+pub use csg::BooleanOperator;
+pub use plan::{floor_plan, FloorPlan, PlanLabel, PlanOptions, PlanOutline};
+pub use profile::{CurveSegment, ProfileCurve, ProfileDef};
+pub use section::{cap_section, section_mesh, Section, SectionOptions};
+pub use triangulation::{triangulate_face, triangulate_polygon};
+
 pub type Result<T> = std::result::Result<T, TesselationError>;
 
 // ============================================================================
@@ -31,6 +53,9 @@ pub enum TesselationError {
     #[error("Tesselation failed: {0}")]
     TesselationFailed(String),
 
+    #[error("Tesselation cancelled")]
+    Cancelled,
+
     #[error("Vec3d error: {0}")]
     Vec3dError(#[from] Vec3dError),
 
@@ -48,6 +73,18 @@ pub enum IfcGeometry {
     /// Sólido extrudado (perfil 2D + direção/distância)
     ExtrudedAreaSolid {
         profile: Vec<Vec2>,
+        /// Furos do perfil (IfcArbitraryProfileDefWithVoids)
+        #[serde(default)]
+        inner_profiles: Vec<Vec<Vec2>>,
+        extrusion_direction: Vec3,
+        depth: f32,
+    },
+
+    /// Sólido extrudado de perfil curvo (círculos, arcos, curvas compostas)
+    ///
+    /// Os arcos são discretizados conforme a tolerância do [`Tesselator`].
+    ExtrudedProfileSolid {
+        profile: ProfileDef,
         extrusion_direction: Vec3,
         depth: f32,
     },
@@ -81,6 +118,33 @@ pub enum IfcGeometry {
     Brep {
         faces: Vec<BrepFace>,
     },
+
+    /// Semi-espaço (`IfcHalfSpaceSolid`) - ilimitado, só faz sentido como
+    /// operando de [`IfcGeometry::BooleanResult`]
+    HalfSpace {
+        base_point: Vec3,
+        normal: Vec3,
+        /// `true`: a normal aponta para fora do material do semi-espaço
+        agreement_flag: bool,
+    },
+
+    /// Resultado booleano (`IfcBooleanResult` / `IfcBooleanClippingResult`)
+    BooleanResult {
+        op: BooleanOperator,
+        first: Box<IfcGeometry>,
+        second: Box<IfcGeometry>,
+    },
+
+    /// Ocorrência de representação compartilhada (`IfcMappedItem`)
+    ///
+    /// `source` é tesselada uma vez por `representation_id` (id STEP do
+    /// `IfcRepresentationMap`); `transform` combina `MappingOrigin` e
+    /// `MappingTarget`.
+    MappedItem {
+        representation_id: u64,
+        source: Box<IfcGeometry>,
+        transform: Mat4,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,22 +159,46 @@ pub struct BrepFace {
 
 pub struct Tesselator {
     tolerance: f32, // Tolerância para curvas/aproximações
+    cache: cache::GeometryCache,
 }
 
 impl Tesselator {
     pub fn new() -> Self {
-        Self { tolerance: 0.01 }
+        Self::with_tolerance(0.01)
     }
 
     pub fn with_tolerance(tolerance: f32) -> Self {
-        Self { tolerance }
+        Self { tolerance, cache: Default::default() }
+    }
+
+    /// Tessela uma representação compartilhada (uma vez por id) e devolve a
+    /// ocorrência com sua transformação, sem copiar a mesh
+    pub fn tesselate_mapped(&self, representation_id: u64, source: &IfcGeometry, transform: Mat4) -> Result<MeshInstance> {
+        let mesh = match self.cache.get(representation_id) {
+            Some(mesh) => mesh,
+            None => self.cache.insert(representation_id, self.tesselate(source)?),
+        };
+        Ok(MeshInstance { representation_id, mesh, transform })
+    }
+
+    /// Estatísticas do cache de representações
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.clear();
     }
 
     /// Converte geometria IFC em mesh
     pub fn tesselate(&self, geometry: &IfcGeometry) -> Result<Mesh> {
         match geometry {
-            IfcGeometry::ExtrudedAreaSolid { profile, extrusion_direction, depth } => {
-                self.tesselate_extruded_solid(profile, *extrusion_direction, *depth)
+            IfcGeometry::ExtrudedAreaSolid { profile, inner_profiles, extrusion_direction, depth } => {
+                self.tesselate_extruded_solid(profile, inner_profiles, *extrusion_direction, *depth)
+            }
+            IfcGeometry::ExtrudedProfileSolid { profile, extrusion_direction, depth } => {
+                let (outer, inner) = profile.discretize(self.tolerance)?;
+                self.tesselate_extruded_solid(&outer, &inner, *extrusion_direction, *depth)
             }
             IfcGeometry::Box { center, size } => {
                 Ok(self.tesselate_box(*center, *size))
@@ -127,6 +215,15 @@ impl Tesselator {
             IfcGeometry::Brep { faces } => {
                 self.tesselate_brep(faces)
             }
+            IfcGeometry::HalfSpace { .. } => Err(TesselationError::UnsupportedGeometry(
+                "Half-space solid is unbounded; use it as a boolean operand".into()
+            )),
+            IfcGeometry::BooleanResult { op, first, second } => {
+                self.tesselate_boolean(*op, first, second)
+            }
+            IfcGeometry::MappedItem { representation_id, source, transform } => {
+                Ok(self.tesselate_mapped(*representation_id, source, *transform)?.to_mesh())
+            }
         }
     }
 
@@ -134,7 +231,13 @@ impl Tesselator {
     // EXTRUDED SOLID
     // ========================================================================
 
-    fn tesselate_extruded_solid(&self, profile: &[Vec2], direction: Vec3, depth: f32) -> Result<Mesh> {
+    fn tesselate_extruded_solid(
+        &self,
+        profile: &[Vec2],
+        inner_profiles: &[Vec<Vec2>],
+        direction: Vec3,
+        depth: f32,
+    ) -> Result<Mesh> {
         if profile.len() < 3 {
             return Err(TesselationError::InvalidGeometry(
                 "Profile must have at least 3 points".into()
@@ -146,63 +249,79 @@ impl Tesselator {
         // Normalizar direção
         let dir = direction.normalize()?;
 
-        // Criar perfil bottom e top
-        let bottom_verts: Vec<Vec3> = profile.iter()
-            .map(|p| Vec3::new(p.x, 0.0, p.y))
-            .collect();
+        // Triangulação das tampas (perfis côncavos e furos)
+        let cap_triangles = triangulate_polygon(profile, inner_profiles)?;
+
+        // Perfil (x, y) → plano XZ; triângulos CCW em 2D têm normal -Y em 3D.
+        // Se a extrusão aponta para o mesmo lado, a tampa inferior já está
+        // correta e a superior/paredes precisam ser invertidas.
+        let flip = Vec3::new(0.0, -1.0, 0.0).dot(&dir) < 0.0;
 
-        let top_verts: Vec<Vec3> = bottom_verts.iter()
-            .map(|v| *v + dir * depth)
+        let rings: Vec<&[Vec2]> = std::iter::once(profile)
+            .chain(inner_profiles.iter().map(Vec::as_slice))
+            .collect();
+        let cap_points: Vec<Vec3> = rings.iter()
+            .flat_map(|ring| ring.iter().map(|p| Vec3::new(p.x, 0.0, p.y)))
             .collect();
 
         // Adicionar vértices
-        let bottom_indices: Vec<u32> = bottom_verts.iter()
-            .map(|&v| {
-                let normal = -dir; // Normal para baixo
-                mesh.add_vertex(Vertex::new(v).with_normal(normal))
-            })
+        let bottom_indices: Vec<u32> = cap_points.iter()
+            .map(|&v| mesh.add_vertex(Vertex::new(v).with_normal(-dir))) // Normal para baixo
             .collect();
 
-        let top_indices: Vec<u32> = top_verts.iter()
-            .map(|&v| {
-                let normal = dir; // Normal para cima
-                mesh.add_vertex(Vertex::new(v).with_normal(normal))
-            })
+        let top_indices: Vec<u32> = cap_points.iter()
+            .map(|&v| mesh.add_vertex(Vertex::new(v + dir * depth).with_normal(dir))) // Normal para cima
             .collect();
 
-        // Triangular bottom face (fan triangulation)
-        for i in 1..bottom_indices.len() - 1 {
-            mesh.add_triangle(bottom_indices[0], bottom_indices[i + 1], bottom_indices[i])?;
-        }
-
-        // Triangular top face
-        for i in 1..top_indices.len() - 1 {
-            mesh.add_triangle(top_indices[0], top_indices[i], top_indices[i + 1])?;
+        for &[a, b, c] in &cap_triangles {
+            let (a, b, c) = (a as usize, b as usize, c as usize);
+            if flip {
+                mesh.add_triangle(bottom_indices[a], bottom_indices[b], bottom_indices[c])?;
+                mesh.add_triangle(top_indices[a], top_indices[c], top_indices[b])?;
+            } else {
+                mesh.add_triangle(bottom_indices[a], bottom_indices[c], bottom_indices[b])?;
+                mesh.add_triangle(top_indices[a], top_indices[b], top_indices[c])?;
+            }
         }
 
-        // Faces laterais
-        for i in 0..profile.len() {
-            let next = (i + 1) % profile.len();
-
-            let b0 = bottom_verts[i];
-            let b1 = bottom_verts[next];
-            let t0 = top_verts[i];
-            let t1 = top_verts[next];
-
-            // Calcular normal da face lateral
-            let edge1 = t0 - b0;
-            let edge2 = b1 - b0;
-            let normal = edge1.cross(&edge2).normalize().unwrap_or(Vec3::X);
-
-            // Adicionar 4 vértices da face lateral (quad)
-            let v0 = mesh.add_vertex(Vertex::new(b0).with_normal(normal));
-            let v1 = mesh.add_vertex(Vertex::new(b1).with_normal(normal));
-            let v2 = mesh.add_vertex(Vertex::new(t1).with_normal(normal));
-            let v3 = mesh.add_vertex(Vertex::new(t0).with_normal(normal));
-
-            // 2 triângulos
-            mesh.add_triangle(v0, v1, v2)?;
-            mesh.add_triangle(v0, v2, v3)?;
+        // Faces laterais: contorno externo CCW, furos CW (normais para fora do sólido)
+        for (ring_index, ring) in rings.iter().enumerate() {
+            let ccw = signed_area_2d(ring) > 0.0;
+            let reverse = (ring_index == 0) != ccw;
+            let n = ring.len();
+
+            for i in 0..n {
+                let (i0, i1) = if reverse { ((i + 1) % n, i) } else { (i, (i + 1) % n) };
+
+                let b0 = Vec3::new(ring[i0].x, 0.0, ring[i0].y);
+                let b1 = Vec3::new(ring[i1].x, 0.0, ring[i1].y);
+                if b0 == b1 {
+                    continue; // ponto de fechamento repetido
+                }
+                let t0 = b0 + dir * depth;
+                let t1 = b1 + dir * depth;
+
+                // Calcular normal da face lateral
+                let mut normal = (b1 - b0).cross(&(t1 - b0)).normalize().unwrap_or(Vec3::X);
+                if flip {
+                    normal = -normal;
+                }
+
+                // Adicionar 4 vértices da face lateral (quad)
+                let v0 = mesh.add_vertex(Vertex::new(b0).with_normal(normal));
+                let v1 = mesh.add_vertex(Vertex::new(b1).with_normal(normal));
+                let v2 = mesh.add_vertex(Vertex::new(t1).with_normal(normal));
+                let v3 = mesh.add_vertex(Vertex::new(t0).with_normal(normal));
+
+                // 2 triângulos
+                if flip {
+                    mesh.add_triangle(v0, v2, v1)?;
+                    mesh.add_triangle(v0, v3, v2)?;
+                } else {
+                    mesh.add_triangle(v0, v1, v2)?;
+                    mesh.add_triangle(v0, v2, v3)?;
+                }
+            }
         }
 
         Ok(mesh)
@@ -229,7 +348,8 @@ impl Tesselator {
     fn tesselate_cylinder(&self, base_center: Vec3, radius: f32, height: f32) -> Mesh {
         let mut mesh = Mesh::new();
 
-        let segments = 32;
+        let segments = profile::arc_segments(radius, std::f32::consts::TAU, self.tolerance)
+            .max(profile::MIN_CIRCLE_SEGMENTS) as u32;
 
         // Bottom circle
         let bottom_center_idx = mesh.add_vertex(
@@ -247,7 +367,7 @@ impl Tesselator {
         // Bottom triangles
         for i in 0..segments {
             let next = (i + 1) % segments;
-            mesh.add_triangle(bottom_center_idx, (i + 1) as u32, (next + 1) as u32).unwrap();
+            mesh.add_triangle(bottom_center_idx, i + 1, next + 1).unwrap();
         }
 
         // Top circle
@@ -268,15 +388,15 @@ impl Tesselator {
         // Top triangles
         for i in 0..segments {
             let next = (i + 1) % segments;
-            mesh.add_triangle(top_center_idx, (top_start + next), (top_start + i)).unwrap();
+            mesh.add_triangle(top_center_idx, top_start + next, top_start + i).unwrap();
         }
 
         // Side faces
         for i in 0..segments {
             let next = (i + 1) % segments;
 
-            let b0 = (i + 1) as u32;
-            let b1 = (next + 1) as u32;
+            let b0 = i + 1;
+            let b1 = next + 1;
             let t0 = top_start + i;
             let t1 = top_start + next;
 
@@ -325,21 +445,24 @@ impl Tesselator {
         let mut mesh = Mesh::new();
 
         for face in faces {
-            // Triangular outer bound (ear clipping simplificado)
-            let outer = &face.outer_bound;
-            if outer.len() < 3 {
+            if face.outer_bound.len() < 3 {
                 continue;
             }
 
-            // Fan triangulation (assume face convexa)
-            let base_idx = mesh.add_vertex(Vertex::new(outer[0]));
-            for i in 1..outer.len() - 1 {
-                let v1 = mesh.add_vertex(Vertex::new(outer[i]));
-                let v2 = mesh.add_vertex(Vertex::new(outer[i + 1]));
-                mesh.add_triangle(base_idx, v1, v2)?;
-            }
+            // Ear clipping com furos (inner bounds); faces degeneradas são ignoradas
+            let triangles = match triangulate_face(&face.outer_bound, &face.inner_bounds) {
+                Ok(triangles) => triangles,
+                Err(TesselationError::InvalidGeometry(_)) => continue,
+                Err(err) => return Err(err),
+            };
 
-            // TODO: inner bounds (furos)
+            let base = mesh.vertices.len() as u32;
+            for &p in face.outer_bound.iter().chain(face.inner_bounds.iter().flatten()) {
+                mesh.add_vertex(Vertex::new(p));
+            }
+            for [a, b, c] in triangles {
+                mesh.add_triangle(base + a, base + b, base + c)?;
+            }
         }
 
         mesh.recalculate_normals_smooth();
@@ -347,6 +470,48 @@ impl Tesselator {
     }
 }
 
+impl Tesselator {
+    // ========================================================================
+    // BOOLEAN RESULT (CSG)
+    // ========================================================================
+
+    fn tesselate_boolean(&self, op: BooleanOperator, first: &IfcGeometry, second: &IfcGeometry) -> Result<Mesh> {
+        let first = self.tesselate(first)?;
+        if first.triangle_count() == 0 {
+            return Ok(first);
+        }
+
+        match (op, second) {
+            // Clipping: remove o material do semi-espaço
+            (BooleanOperator::Difference, IfcGeometry::HalfSpace { base_point, normal, agreement_flag }) => {
+                Ok(csg::clip_half_space(&first, *base_point, *normal, *agreement_flag))
+            }
+            // Interseção: mantém o material do semi-espaço (remove o complemento)
+            (BooleanOperator::Intersection, IfcGeometry::HalfSpace { base_point, normal, agreement_flag }) => {
+                Ok(csg::clip_half_space(&first, *base_point, *normal, !*agreement_flag))
+            }
+            (BooleanOperator::Union, IfcGeometry::HalfSpace { .. }) => Err(TesselationError::UnsupportedGeometry(
+                "Union with a half-space solid is unbounded".into()
+            )),
+            (op, second) => {
+                let second = self.tesselate(second)?;
+                Ok(csg::boolean(&first, &second, op))
+            }
+        }
+    }
+}
+
+/// Área com sinal de um contorno 2D (positiva = CCW)
+fn signed_area_2d(ring: &[Vec2]) -> f32 {
+    (0..ring.len())
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
 impl Default for Tesselator {
     fn default() -> Self {
         Self::new()
@@ -402,6 +567,7 @@ mod tests {
 
         let geometry = IfcGeometry::ExtrudedAreaSolid {
             profile,
+            inner_profiles: vec![],
             extrusion_direction: Vec3::Y,
             depth: 3.0,
         };
@@ -410,4 +576,161 @@ mod tests {
         assert!(mesh.validate().is_ok());
         assert!(mesh.triangle_count() > 0);
     }
+
+    /// Volume pelo teorema da divergência (positivo = normais para fora)
+    fn signed_volume(mesh: &Mesh) -> f32 {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let a = mesh.vertices[t[0] as usize].position;
+                let b = mesh.vertices[t[1] as usize].position;
+                let c = mesh.vertices[t[2] as usize].position;
+                a.dot(&b.cross(&c)) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_extruded_concave_profile_with_hole() {
+        let tesselator = Tesselator::new();
+
+        // Parede em U (côncava) com um furo passante
+        let profile = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(6.0, 0.0),
+            Vec2::new(6.0, 4.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let hole = vec![
+            Vec2::new(0.5, 0.5),
+            Vec2::new(1.5, 0.5),
+            Vec2::new(1.5, 1.5),
+            Vec2::new(0.5, 1.5),
+        ];
+
+        for direction in [Vec3::Y, -Vec3::Y] {
+            let geometry = IfcGeometry::ExtrudedAreaSolid {
+                profile: profile.clone(),
+                inner_profiles: vec![hole.clone()],
+                extrusion_direction: direction,
+                depth: 2.0,
+            };
+
+            let mesh = tesselator.tesselate(&geometry).unwrap();
+            assert!(mesh.validate().is_ok());
+            // (24 - 4 - 1) * 2
+            assert!((signed_volume(&mesh) - 38.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_extruded_hollow_circle_follows_tolerance() {
+        let geometry = IfcGeometry::ExtrudedProfileSolid {
+            profile: ProfileDef::CircleHollow { center: Vec2::ZERO, radius: 1.0, wall_thickness: 0.25 },
+            extrusion_direction: Vec3::Y,
+            depth: 2.0,
+        };
+
+        let coarse = Tesselator::with_tolerance(0.05).tesselate(&geometry).unwrap();
+        let fine = Tesselator::with_tolerance(0.0005).tesselate(&geometry).unwrap();
+        assert!(fine.triangle_count() > coarse.triangle_count());
+
+        // π (1² - 0.75²) * 2
+        let expected = std::f32::consts::PI * (1.0 - 0.5625) * 2.0;
+        assert!((signed_volume(&fine) - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_boolean_clipping_result() {
+        // Laje 4x4x1 cortada por um plano inclinado (telhado)
+        let slab = IfcGeometry::Box { center: Vec3::ZERO, size: Vec3::new(4.0, 1.0, 4.0) };
+        let clipped = IfcGeometry::BooleanResult {
+            op: BooleanOperator::Difference,
+            first: Box::new(slab.clone()),
+            second: Box::new(IfcGeometry::HalfSpace {
+                base_point: Vec3::ZERO,
+                normal: Vec3::new(1.0, 1.0, 0.0),
+                agreement_flag: false,
+            }),
+        };
+
+        let tesselator = Tesselator::new();
+        let mesh = tesselator.tesselate(&clipped).unwrap();
+        assert!(mesh.validate().is_ok());
+        // Plano passa pelo centro da caixa: metade do volume
+        assert!((signed_volume(&mesh) - 8.0).abs() < 1e-3);
+
+        // Furo passante: diferença entre dois sólidos
+        let opening = IfcGeometry::BooleanResult {
+            op: BooleanOperator::Difference,
+            first: Box::new(slab),
+            second: Box::new(IfcGeometry::Box { center: Vec3::ZERO, size: Vec3::new(1.0, 3.0, 1.0) }),
+        };
+        let mesh = tesselator.tesselate(&opening).unwrap();
+        assert!((signed_volume(&mesh) - 15.0).abs() < 1e-3);
+
+        assert!(tesselator
+            .tesselate(&IfcGeometry::HalfSpace { base_point: Vec3::ZERO, normal: Vec3::Y, agreement_flag: true })
+            .is_err());
+    }
+
+    #[test]
+    fn test_mapped_items_tesselate_once() {
+        let tesselator = Tesselator::new();
+        let window = IfcGeometry::Box { center: Vec3::ZERO, size: Vec3::new(1.0, 1.5, 0.1) };
+
+        let meshes: Vec<Mesh> = (0..3)
+            .map(|i| {
+                let item = IfcGeometry::MappedItem {
+                    representation_id: 42,
+                    source: Box::new(window.clone()),
+                    transform: Mat4::translation(Vec3::new(i as f32 * 2.0, 0.0, 0.0)),
+                };
+                tesselator.tesselate(&item).unwrap()
+            })
+            .collect();
+
+        assert!((meshes[2].bounds.center().x - 4.0).abs() < 1e-5);
+
+        let stats = tesselator.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 1, 1));
+        assert_eq!(stats.cached_triangles, meshes[0].triangle_count());
+
+        // Instâncias compartilham a mesma mesh
+        let a = tesselator.tesselate_mapped(42, &window, Mat4::IDENTITY).unwrap();
+        let b = tesselator.tesselate_mapped(42, &window, Mat4::IDENTITY).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a.mesh, &b.mesh));
+
+        tesselator.clear_cache();
+        assert_eq!(tesselator.cache_stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_brep_face_with_inner_bound() {
+        let tesselator = Tesselator::new();
+        let geometry = IfcGeometry::Brep {
+            faces: vec![BrepFace {
+                outer_bound: vec![
+                    Vec3::new(0.0, 0.0, 0.0),
+                    Vec3::new(4.0, 0.0, 0.0),
+                    Vec3::new(4.0, 4.0, 0.0),
+                    Vec3::new(0.0, 4.0, 0.0),
+                ],
+                inner_bounds: vec![vec![
+                    Vec3::new(1.0, 1.0, 0.0),
+                    Vec3::new(1.0, 3.0, 0.0),
+                    Vec3::new(3.0, 3.0, 0.0),
+                    Vec3::new(3.0, 1.0, 0.0),
+                ]],
+            }],
+        };
+
+        let mesh = tesselator.tesselate(&geometry).unwrap();
+        assert!(mesh.validate().is_ok());
+        assert_eq!(mesh.triangle_count(), 8);
+    }
 }
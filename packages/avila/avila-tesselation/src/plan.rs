@@ -0,0 +1,207 @@
+//! Planta baixa 2D de um pavimento
+//!
+//! Corta cada mesh da cena num plano horizontal (elevação do pavimento +
+//! altura de corte, 1,20 m por padrão) com [`section_mesh`] e projeta os
+//! contornos em XY (Z para cima, como no IFC). Meshes cujo GUID está no
+//! mapa de ambientes (ex.: `IfcSpace` → nome, da estrutura espacial dos
+//! metadados) ganham um rótulo no centroide do maior contorno.
+//!
+//! O resultado serializa em JSON para uma camada vetorial do visualizador,
+//! ou vira SVG com [`FloorPlan::to_svg`].
+
+use crate::section::{section_mesh, SectionOptions};
+use avila_mesh::Scene;
+use avila_vec3d::{Plane, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Opções da planta
+#[derive(Debug, Clone)]
+pub struct PlanOptions {
+    /// Altura do corte acima da elevação do pavimento
+    pub cut_height: f32,
+    pub section: SectionOptions,
+}
+
+impl Default for PlanOptions {
+    fn default() -> Self {
+        Self {
+            cut_height: 1.2,
+            section: SectionOptions::default(),
+        }
+    }
+}
+
+/// Contorno cortado de uma mesh, em coordenadas XY do modelo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanOutline {
+    /// Índice em `Scene::meshes`
+    pub mesh: usize,
+    pub element_guid: Option<String>,
+    pub points: Vec<Vec2>,
+    /// Laço fechado (sem repetir o primeiro ponto) ou cadeia aberta
+    pub closed: bool,
+}
+
+/// Rótulo de ambiente
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanLabel {
+    pub element_guid: String,
+    pub text: String,
+    pub position: Vec2,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FloorPlan {
+    /// Z do plano de corte
+    pub cut_z: f32,
+    pub outlines: Vec<PlanOutline>,
+    pub labels: Vec<PlanLabel>,
+}
+
+/// Planta do pavimento na `elevation`; `rooms` mapeia GUID → rótulo
+pub fn floor_plan(scene: &Scene, elevation: f32, rooms: &HashMap<String, String>, options: &PlanOptions) -> FloorPlan {
+    let cut_z = elevation + options.cut_height;
+    // Normal para baixo: o lado visível (positivo) é o que fica abaixo do corte
+    let plane = Plane::new(-Vec3::Z, cut_z);
+    let mut plan = FloorPlan { cut_z, ..Default::default() };
+
+    for (index, mesh) in scene.meshes.iter().enumerate() {
+        if mesh.bounds.min.z > cut_z || mesh.bounds.max.z < cut_z {
+            continue;
+        }
+        let section = section_mesh(mesh, &plane, &options.section);
+        let flat = |chain: &Vec<Vec3>| chain.iter().map(|p| Vec2::new(p.x, p.y)).collect::<Vec<_>>();
+        let loops: Vec<Vec<Vec2>> = section.loops.iter().map(flat).collect();
+
+        let label = mesh.element_guid.as_ref().and_then(|guid| Some((guid, rooms.get(guid)?)));
+        if let Some((guid, text)) = label {
+            let largest = loops
+                .iter()
+                .filter_map(|ring| polygon_centroid(ring))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((position, _)) = largest {
+                plan.labels.push(PlanLabel { element_guid: guid.clone(), text: text.clone(), position });
+            }
+        }
+
+        let outline = |points, closed| PlanOutline { mesh: index, element_guid: mesh.element_guid.clone(), points, closed };
+        plan.outlines.extend(loops.into_iter().map(|points| outline(points, true)));
+        plan.outlines.extend(section.open_chains.iter().map(|chain| outline(flat(chain), false)));
+    }
+    plan
+}
+
+/// Centroide e área (absoluta) de um polígono simples
+fn polygon_centroid(ring: &[Vec2]) -> Option<(Vec2, f32)> {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        let cross = a.x * b.y - b.x * a.y;
+        area += cross;
+        cx += (a.x + b.x) * cross;
+        cy += (a.y + b.y) * cross;
+    }
+    if area.abs() < f32::EPSILON {
+        return None;
+    }
+    Some((Vec2::new(cx / (3.0 * area), cy / (3.0 * area)), area.abs() / 2.0))
+}
+
+impl FloorPlan {
+    /// Caixa 2D (mínimo, máximo) de todos os contornos
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        self.outlines.iter().flat_map(|o| &o.points).fold(None, |bounds, p| {
+            let (min, max) = bounds.unwrap_or((*p, *p));
+            Some((Vec2::new(min.x.min(p.x), min.y.min(p.y)), Vec2::new(max.x.max(p.x), max.y.max(p.y))))
+        })
+    }
+
+    /// SVG em unidades do modelo (Y invertido: norte para cima)
+    pub fn to_svg(&self) -> String {
+        let (min, max) = self.bounds().unwrap_or((Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0)));
+        let margin = 0.05 * (max.x - min.x).max(max.y - min.y).max(1.0);
+        let (width, height) = (max.x - min.x + 2.0 * margin, max.y - min.y + 2.0 * margin);
+        let point = |p: &Vec2| format!("{},{}", p.x, -p.y);
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min.x - margin,
+            -max.y - margin,
+            width,
+            height
+        );
+        let _ = writeln!(svg, r#"<g fill="none" stroke="black" stroke-width="{}">"#, margin / 10.0);
+        for outline in &self.outlines {
+            let tag = if outline.closed { "polygon" } else { "polyline" };
+            let points: Vec<String> = outline.points.iter().map(point).collect();
+            let _ = writeln!(svg, r#"<{} points="{}"/>"#, tag, points.join(" "));
+        }
+        let _ = writeln!(svg, "</g>");
+        for label in &self.labels {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle">{}</text>"#,
+                label.position.x,
+                -label.position.y,
+                margin,
+                escape_xml(&label.text)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::{primitives, Mesh};
+    use avila_vec3d::Mat4;
+
+    fn boxed(size: Vec3, center: Vec3, guid: &str) -> Mesh {
+        let mut mesh = primitives::cube(1.0);
+        mesh.transform(&Mat4::translation(center).mul_mat4(&Mat4::scale(size)));
+        mesh.element_guid = Some(guid.into());
+        mesh
+    }
+
+    #[test]
+    fn test_floor_plan() {
+        let mut scene = Scene::new();
+        scene.add_mesh(boxed(Vec3::new(4.0, 0.2, 3.0), Vec3::new(0.0, 2.0, 1.5), "wall"));
+        scene.add_mesh(boxed(Vec3::new(4.0, 4.0, 3.0), Vec3::new(0.0, 0.0, 1.5), "room"));
+        // Laje do pavimento de cima: não é cortada
+        scene.add_mesh(boxed(Vec3::new(4.0, 4.0, 0.2), Vec3::new(0.0, 0.0, 3.1), "slab"));
+
+        let rooms = HashMap::from([("room".to_string(), "Sala 101 <A&B>".to_string())]);
+        let plan = floor_plan(&scene, 0.0, &rooms, &PlanOptions::default());
+
+        assert_eq!(plan.cut_z, 1.2);
+        assert_eq!(plan.outlines.len(), 2);
+        assert!(plan.outlines.iter().all(|o| o.closed && o.points.len() >= 4));
+        assert_eq!(plan.outlines[0].element_guid.as_deref(), Some("wall"));
+
+        assert_eq!(plan.labels.len(), 1);
+        assert!(plan.labels[0].position.x.abs() < 1e-4 && plan.labels[0].position.y.abs() < 1e-4);
+
+        let (min, max) = plan.bounds().unwrap();
+        assert!((min.x + 2.0).abs() < 1e-4 && (max.y - 2.1).abs() < 1e-4);
+
+        let svg = plan.to_svg();
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        assert!(svg.contains("Sala 101 &lt;A&amp;B&gt;"));
+
+        assert!(floor_plan(&scene, 10.0, &rooms, &PlanOptions::default()).outlines.is_empty());
+    }
+}
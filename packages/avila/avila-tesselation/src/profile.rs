@@ -0,0 +1,292 @@
+//! Perfis curvos (círculos, arcos, curvas compostas)
+//!
+//! Cobre os perfis IFC mais comuns com trechos curvos:
+//! - `IfcCircleProfileDef` / `IfcCircleHollowProfileDef`
+//! - `IfcArbitraryClosedProfileDef` (e `...WithVoids`) com `IfcPolyline`,
+//!   `IfcCircle` ou `IfcCompositeCurve` de linhas e arcos
+//!
+//! A discretização é adaptativa: cada arco recebe o menor número de
+//! segmentos cuja flecha (distância corda–arco) não excede a tolerância.
+
+use crate::{Result, TesselationError};
+use avila_vec3d::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Limite de segmentos por arco (evita explosão com tolerâncias minúsculas)
+pub const MAX_ARC_SEGMENTS: usize = 256;
+
+/// Mínimo de segmentos para um círculo completo
+pub const MIN_CIRCLE_SEGMENTS: usize = 8;
+
+/// Trecho de uma curva composta (`IfcCompositeCurveSegment`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CurveSegment {
+    /// Segmento reto
+    Line { start: Vec2, end: Vec2 },
+
+    /// Arco por centro e ângulos em radianos (`IfcTrimmedCurve` sobre `IfcCircle`)
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        /// Sentido anti-horário (`SenseAgreement`)
+        ccw: bool,
+    },
+
+    /// Arco por três pontos (`IfcArcIndex` de `IfcIndexedPolyCurve`)
+    ThreePointArc { start: Vec2, mid: Vec2, end: Vec2 },
+}
+
+/// Curva fechada de um perfil
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProfileCurve {
+    Polyline(Vec<Vec2>),
+    Circle { center: Vec2, radius: f32 },
+    Composite(Vec<CurveSegment>),
+}
+
+/// Definição de perfil com trechos curvos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProfileDef {
+    /// `IfcCircleProfileDef`
+    Circle { center: Vec2, radius: f32 },
+
+    /// `IfcCircleHollowProfileDef` (tubo)
+    CircleHollow { center: Vec2, radius: f32, wall_thickness: f32 },
+
+    /// `IfcArbitraryClosedProfileDef` / `IfcArbitraryProfileDefWithVoids`
+    ArbitraryClosed {
+        outer: ProfileCurve,
+        #[serde(default)]
+        inner: Vec<ProfileCurve>,
+    },
+}
+
+impl ProfileDef {
+    /// Discretiza em contorno externo + furos
+    pub fn discretize(&self, tolerance: f32) -> Result<(Vec<Vec2>, Vec<Vec<Vec2>>)> {
+        match self {
+            Self::Circle { center, radius } => Ok((circle_points(*center, *radius, tolerance)?, Vec::new())),
+            Self::CircleHollow { center, radius, wall_thickness } => {
+                if *wall_thickness <= 0.0 || *wall_thickness >= *radius {
+                    return Err(TesselationError::InvalidGeometry(format!(
+                        "Wall thickness {} must be in (0, {})",
+                        wall_thickness, radius
+                    )));
+                }
+                let outer = circle_points(*center, *radius, tolerance)?;
+                let inner = circle_points(*center, radius - wall_thickness, tolerance)?;
+                Ok((outer, vec![inner]))
+            }
+            Self::ArbitraryClosed { outer, inner } => {
+                let outer = outer.discretize(tolerance)?;
+                let inner = inner
+                    .iter()
+                    .map(|curve| curve.discretize(tolerance))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((outer, inner))
+            }
+        }
+    }
+}
+
+impl ProfileCurve {
+    /// Discretiza em um contorno fechado (sem ponto de fechamento repetido)
+    pub fn discretize(&self, tolerance: f32) -> Result<Vec<Vec2>> {
+        let mut points = match self {
+            Self::Polyline(points) => points.clone(),
+            Self::Circle { center, radius } => return circle_points(*center, *radius, tolerance),
+            Self::Composite(segments) => {
+                let mut points = Vec::new();
+                for segment in segments {
+                    segment.append_points(tolerance, &mut points)?;
+                }
+                points
+            }
+        };
+
+        points.dedup_by(|a, b| a.distance(b) <= f32::EPSILON);
+        if points.len() > 1 && points[0].distance(points.last().unwrap()) <= f32::EPSILON {
+            points.pop();
+        }
+        if points.len() < 3 {
+            return Err(TesselationError::InvalidGeometry(
+                "Closed profile curve must have at least 3 distinct points".into()
+            ));
+        }
+        Ok(points)
+    }
+}
+
+impl CurveSegment {
+    /// Adiciona os pontos do trecho (incluindo início e fim)
+    fn append_points(&self, tolerance: f32, out: &mut Vec<Vec2>) -> Result<()> {
+        match *self {
+            Self::Line { start, end } => out.extend([start, end]),
+            Self::Arc { center, radius, start_angle, end_angle, ccw } => {
+                check_radius(radius)?;
+                let sweep = if ccw {
+                    positive_sweep(end_angle - start_angle)
+                } else {
+                    -positive_sweep(start_angle - end_angle)
+                };
+                append_arc(center, radius, start_angle, sweep, tolerance, out);
+            }
+            Self::ThreePointArc { start, mid, end } => match circumcenter(start, mid, end) {
+                Some(center) => {
+                    let radius = center.distance(&start);
+                    let angle = |p: Vec2| (p.y - center.y).atan2(p.x - center.x);
+                    let start_angle = angle(start);
+
+                    // O sentido é o que passa pelo ponto médio
+                    let ccw = cross(mid - start, end - start) > 0.0;
+                    let sweep = if ccw {
+                        positive_sweep(angle(end) - start_angle)
+                    } else {
+                        -positive_sweep(start_angle - angle(end))
+                    };
+                    append_arc(center, radius, start_angle, sweep, tolerance, out);
+                }
+                None => out.extend([start, end]), // pontos colineares
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Número de segmentos para um arco de raio `radius` e abertura `sweep`
+/// (radianos) com flecha máxima `tolerance`
+pub fn arc_segments(radius: f32, sweep: f32, tolerance: f32) -> usize {
+    let sweep = sweep.abs();
+    if tolerance <= 0.0 || radius <= 0.0 || sweep <= 0.0 {
+        return 1;
+    }
+
+    // Flecha de uma corda com ângulo θ: r (1 - cos(θ/2))
+    let max_angle = if tolerance >= radius {
+        std::f32::consts::PI
+    } else {
+        2.0 * (1.0 - tolerance / radius).acos()
+    };
+    ((sweep / max_angle).ceil() as usize).clamp(1, MAX_ARC_SEGMENTS)
+}
+
+fn circle_points(center: Vec2, radius: f32, tolerance: f32) -> Result<Vec<Vec2>> {
+    check_radius(radius)?;
+    let segments = arc_segments(radius, TAU, tolerance).max(MIN_CIRCLE_SEGMENTS);
+    Ok((0..segments)
+        .map(|i| {
+            let theta = TAU * i as f32 / segments as f32;
+            center + Vec2::new(theta.cos(), theta.sin()) * radius
+        })
+        .collect())
+}
+
+fn append_arc(center: Vec2, radius: f32, start_angle: f32, sweep: f32, tolerance: f32, out: &mut Vec<Vec2>) {
+    let segments = arc_segments(radius, sweep, tolerance);
+    out.extend((0..=segments).map(|i| {
+        let theta = start_angle + sweep * i as f32 / segments as f32;
+        center + Vec2::new(theta.cos(), theta.sin()) * radius
+    }));
+}
+
+fn check_radius(radius: f32) -> Result<()> {
+    if radius > 0.0 && radius.is_finite() {
+        Ok(())
+    } else {
+        Err(TesselationError::InvalidGeometry(format!("Invalid radius: {}", radius)))
+    }
+}
+
+/// Normaliza um ângulo para (0, 2π]
+fn positive_sweep(angle: f32) -> f32 {
+    let angle = angle.rem_euclid(TAU);
+    if angle <= f32::EPSILON {
+        TAU
+    } else {
+        angle
+    }
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn circumcenter(a: Vec2, b: Vec2, c: Vec2) -> Option<Vec2> {
+    let (ab, ac) = (b - a, c - a);
+    let d = 2.0 * cross(ab, ac);
+    if d.abs() <= f32::EPSILON * ab.length_squared().max(ac.length_squared()) {
+        return None;
+    }
+    let (ab2, ac2) = (ab.length_squared(), ac.length_squared());
+    Some(a + Vec2::new(ac.y * ab2 - ab.y * ac2, ab.x * ac2 - ac.x * ab2) / d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn area(ring: &[Vec2]) -> f32 {
+        (0..ring.len()).map(|i| cross(ring[i], ring[(i + 1) % ring.len()])).sum::<f32>() / 2.0
+    }
+
+    #[test]
+    fn test_adaptive_circle() {
+        let coarse = ProfileCurve::Circle { center: Vec2::ZERO, radius: 1.0 }.discretize(0.1).unwrap();
+        let fine = ProfileCurve::Circle { center: Vec2::ZERO, radius: 1.0 }.discretize(0.001).unwrap();
+        assert!(fine.len() > coarse.len());
+        assert!(coarse.len() >= MIN_CIRCLE_SEGMENTS);
+
+        // Flecha máxima respeitada
+        let half = std::f32::consts::PI / fine.len() as f32;
+        assert!(1.0 - half.cos() <= 0.001 + 1e-6);
+        assert_relative_eq!(area(&fine), std::f32::consts::PI, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_composite_curve_with_arcs() {
+        // Retângulo 4x2 com a lateral direita em semicírculo (raio 1)
+        let curve = ProfileCurve::Composite(vec![
+            CurveSegment::Line { start: Vec2::new(0.0, 0.0), end: Vec2::new(4.0, 0.0) },
+            CurveSegment::Arc {
+                center: Vec2::new(4.0, 1.0),
+                radius: 1.0,
+                start_angle: -std::f32::consts::FRAC_PI_2,
+                end_angle: std::f32::consts::FRAC_PI_2,
+                ccw: true,
+            },
+            CurveSegment::Line { start: Vec2::new(4.0, 2.0), end: Vec2::new(0.0, 2.0) },
+            CurveSegment::Line { start: Vec2::new(0.0, 2.0), end: Vec2::new(0.0, 0.0) },
+        ]);
+        let ring = curve.discretize(0.001).unwrap();
+        assert_relative_eq!(area(&ring), 8.0 + std::f32::consts::FRAC_PI_2, epsilon = 0.01);
+
+        // Mesmo semicírculo por três pontos, percorrido no sentido horário
+        let three_point = ProfileCurve::Composite(vec![
+            CurveSegment::ThreePointArc {
+                start: Vec2::new(4.0, 2.0),
+                mid: Vec2::new(5.0, 1.0),
+                end: Vec2::new(4.0, 0.0),
+            },
+            CurveSegment::Line { start: Vec2::new(4.0, 0.0), end: Vec2::new(0.0, 0.0) },
+            CurveSegment::Line { start: Vec2::new(0.0, 0.0), end: Vec2::new(0.0, 2.0) },
+        ]);
+        let ring = three_point.discretize(0.001).unwrap();
+        assert_relative_eq!(area(&ring), -(8.0 + std::f32::consts::FRAC_PI_2), epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_hollow_circle() {
+        let profile = ProfileDef::CircleHollow { center: Vec2::ZERO, radius: 1.0, wall_thickness: 0.2 };
+        let (outer, inner) = profile.discretize(0.01).unwrap();
+        assert_eq!(inner.len(), 1);
+        assert!(inner[0].iter().all(|p| (p.length() - 0.8).abs() < 1e-5));
+        assert!(outer.iter().all(|p| (p.length() - 1.0).abs() < 1e-5));
+
+        let invalid = ProfileDef::CircleHollow { center: Vec2::ZERO, radius: 1.0, wall_thickness: 1.0 };
+        assert!(invalid.discretize(0.01).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Cortes de seção e tampas (capping)
+//!
+//! Corta uma malha fechada por um plano e devolve os contornos da seção
+//! como laços fechados. [`cap_section`] triangula esses laços (contornos
+//! aninhados viram furos) para tapar o corte quando o visualizador descarta
+//! o lado negativo do plano.
+//!
+//! Convenção do plano: `normal · p + d = 0`, lado visível com distância
+//! positiva (a mesma do [`Frustum`](avila_vec3d::Frustum)). A tampa fica
+//! voltada para o lado removido (`-normal`).
+
+use crate::triangulation::triangulate_polygon;
+use crate::Result;
+use avila_mesh::{Mesh, Vertex};
+use avila_vec3d::{Plane, Vec2, Vec3};
+use std::collections::HashMap;
+
+/// Opções do corte
+#[derive(Debug, Clone)]
+pub struct SectionOptions {
+    /// Distância abaixo da qual um vértice é tratado como sobre o plano;
+    /// também é o passo da grade que solda as pontas dos segmentos
+    pub tolerance: f32,
+}
+
+impl Default for SectionOptions {
+    fn default() -> Self {
+        Self { tolerance: 1e-5 }
+    }
+}
+
+/// Contornos de um corte
+#[derive(Debug, Clone, Default)]
+pub struct Section {
+    /// Laços fechados (sem repetir o primeiro ponto)
+    pub loops: Vec<Vec<Vec3>>,
+    /// Cadeias que não fecham (malha aberta ou não-manifold); não são tampadas
+    pub open_chains: Vec<Vec<Vec3>>,
+}
+
+impl Section {
+    pub fn is_empty(&self) -> bool {
+        self.loops.is_empty() && self.open_chains.is_empty()
+    }
+}
+
+type PointKey = (i64, i64, i64);
+
+/// Corta `mesh` pelo plano
+pub fn section_mesh(mesh: &Mesh, plane: &Plane, options: &SectionOptions) -> Section {
+    let tolerance = options.tolerance.max(f32::EPSILON);
+    let key = |p: Vec3| {
+        let cell = |x: f32| (x / tolerance).round() as i64;
+        (cell(p.x), cell(p.y), cell(p.z))
+    };
+
+    let mut points: HashMap<PointKey, Vec3> = HashMap::new();
+    let mut segments: Vec<(PointKey, PointKey)> = Vec::new();
+    for triangle in mesh.indices.chunks_exact(3) {
+        let corners = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].position);
+        let Some([a, b]) = triangle_section(corners, plane, tolerance) else { continue };
+        let (ka, kb) = (key(a), key(b));
+        if ka == kb {
+            continue;
+        }
+        points.entry(ka).or_insert(a);
+        points.entry(kb).or_insert(b);
+        segments.push((ka, kb));
+    }
+
+    let mut section = Section::default();
+    for (chain, closed) in chain_segments(&segments) {
+        let chain: Vec<Vec3> = chain.iter().map(|k| points[k]).collect();
+        if closed {
+            section.loops.push(chain);
+        } else {
+            section.open_chains.push(chain);
+        }
+    }
+    section
+}
+
+/// Triangula os laços da seção numa malha voltada para `-normal`
+///
+/// Laços contidos num número ímpar de outros são furos do menor laço que os
+/// contém. A UV de cada vértice é a sua coordenada no plano.
+pub fn cap_section(section: &Section, plane: &Plane) -> Result<Mesh> {
+    // Base (u, v) com u × v = -normal: triângulos CCW em 2D ficam voltados para -normal
+    let facing = -plane.normal;
+    let helper = if facing.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+    let u = facing.cross(&helper).normalize()?;
+    let v = facing.cross(&u);
+    let project = |p: &Vec3| Vec2::new(p.dot(&u), p.dot(&v));
+
+    let loops: Vec<Vec<Vec2>> = section.loops.iter().map(|l| l.iter().map(project).collect()).collect();
+    let areas: Vec<f32> = loops.iter().map(|l| signed_area(l).abs()).collect();
+
+    // Pais: o menor laço que contém cada laço
+    let contains = |outer: usize, inner: usize| outer != inner && point_in_polygon(loops[inner][0], &loops[outer]);
+    let depth: Vec<usize> = (0..loops.len())
+        .map(|i| (0..loops.len()).filter(|&j| contains(j, i)).count())
+        .collect();
+    let parent = |i: usize| {
+        (0..loops.len())
+            .filter(|&j| contains(j, i) && depth[j] + 1 == depth[i])
+            .min_by(|&a, &b| areas[a].total_cmp(&areas[b]))
+    };
+
+    let mut cap = Mesh::new();
+    for outer in (0..loops.len()).filter(|&i| depth[i].is_multiple_of(2)) {
+        let holes: Vec<usize> = (0..loops.len())
+            .filter(|&i| !depth[i].is_multiple_of(2) && parent(i) == Some(outer))
+            .collect();
+        let hole_rings: Vec<Vec<Vec2>> = holes.iter().map(|&h| loops[h].clone()).collect();
+        let triangles = match triangulate_polygon(&loops[outer], &hole_rings) {
+            Ok(triangles) => triangles,
+            // Laço degenerado (área nula): nada a tapar
+            Err(_) => continue,
+        };
+
+        let base = cap.vertices.len() as u32;
+        for ring in std::iter::once(outer).chain(holes) {
+            for (point, uv) in section.loops[ring].iter().zip(&loops[ring]) {
+                cap.add_vertex(Vertex::new(*point).with_normal(facing).with_uv(*uv));
+            }
+        }
+        for [a, b, c] in triangles {
+            cap.add_triangle(base + a, base + b, base + c)?;
+        }
+    }
+    Ok(cap)
+}
+
+/// Segmento em que o triângulo cruza o plano
+///
+/// Arestas sobre o plano só contam pelo triângulo do lado positivo, para
+/// não aparecerem duas vezes numa malha fechada; faces coplanares são
+/// ignoradas.
+fn triangle_section(corners: [Vec3; 3], plane: &Plane, tolerance: f32) -> Option<[Vec3; 2]> {
+    let distances = corners.map(|p| {
+        let d = plane.signed_distance(p);
+        if d.abs() <= tolerance { 0.0 } else { d }
+    });
+    let positive = distances.iter().filter(|&&d| d > 0.0).count();
+    let negative = distances.iter().filter(|&&d| d < 0.0).count();
+
+    let mut hits: Vec<Vec3> = Vec::with_capacity(2);
+    if positive == 0 || negative == 0 {
+        // Só uma aresta sobre o plano, com o terceiro vértice acima
+        if positive != 1 {
+            return None;
+        }
+        hits.extend((0..3).filter(|&i| distances[i] == 0.0).map(|i| corners[i]));
+    } else {
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            if distances[i] == 0.0 {
+                hits.push(corners[i]);
+            } else if distances[i] * distances[j] < 0.0 {
+                hits.push(edge_crossing(corners[i], distances[i], corners[j], distances[j]));
+            }
+        }
+    }
+    match hits[..] {
+        [a, b] => Some([a, b]),
+        _ => None,
+    }
+}
+
+/// Ponto de cruzamento calculado sempre na mesma ordem dos extremos, para
+/// que triângulos vizinhos obtenham exatamente o mesmo ponto
+fn edge_crossing(a: Vec3, da: f32, b: Vec3, db: f32) -> Vec3 {
+    let (a, da, b, db) = if a.to_array() <= b.to_array() { (a, da, b, db) } else { (b, db, a, da) };
+    a.lerp(&b, da / (da - db))
+}
+
+/// Encadeia segmentos pelas pontas; `true` marca cadeias fechadas
+fn chain_segments(segments: &[(PointKey, PointKey)]) -> Vec<(Vec<PointKey>, bool)> {
+    let mut adjacency: HashMap<PointKey, Vec<usize>> = HashMap::new();
+    for (index, &(a, b)) in segments.iter().enumerate() {
+        adjacency.entry(a).or_default().push(index);
+        adjacency.entry(b).or_default().push(index);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let walk = |from: PointKey, used: &mut [bool], chain: &mut Vec<PointKey>| {
+        let mut current = from;
+        while let Some(&next) = adjacency[&current].iter().find(|&&s| !used[s]) {
+            used[next] = true;
+            let (a, b) = segments[next];
+            current = if a == current { b } else { a };
+            chain.push(current);
+        }
+    };
+
+    let mut chains = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let (a, b) = segments[start];
+        let mut chain = vec![a, b];
+        walk(b, &mut used, &mut chain);
+        if chain.last() == Some(&a) {
+            chain.pop();
+            chains.push((chain, true));
+            continue;
+        }
+
+        let mut back = Vec::new();
+        walk(a, &mut used, &mut back);
+        back.reverse();
+        back.extend(chain);
+        chains.push((back, false));
+    }
+    chains
+}
+
+fn signed_area(ring: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    area / 2.0
+}
+
+/// Par-ímpar por raio horizontal
+fn point_in_polygon(point: Vec2, ring: &[Vec2]) -> bool {
+    let mut inside = false;
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_mesh::primitives;
+
+    fn cap_area(cap: &Mesh) -> f32 {
+        cap.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| cap.vertices[t[i] as usize].position);
+                (b - a).cross(&(c - a)).length() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_cube_section_and_cap() {
+        let cube = primitives::cube(2.0);
+        let plane = Plane::new(Vec3::Z, -0.25);
+        let section = section_mesh(&cube, &plane, &SectionOptions::default());
+        assert_eq!(section.loops.len(), 1);
+        assert!(section.open_chains.is_empty());
+        for point in &section.loops[0] {
+            assert!((point.z - 0.25).abs() < 1e-5);
+        }
+
+        let cap = cap_section(&section, &plane).unwrap();
+        assert!((cap_area(&cap) - 4.0).abs() < 1e-4);
+        for t in cap.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| cap.vertices[t[i] as usize].position);
+            assert!((b - a).cross(&(c - a)).z < 0.0, "cap must face -normal");
+        }
+
+        assert!(section_mesh(&cube, &Plane::new(Vec3::Z, -5.0), &SectionOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_nested_loops_become_holes() {
+        // Tubo quadrado: cubo 4x4 menos cubo 2x2 (faces internas invertidas)
+        let mut tube = primitives::cube(4.0);
+        let mut inner = primitives::cube(2.0);
+        inner.transform(&avila_vec3d::Mat4::scale(Vec3::new(1.0, 1.0, 3.0)));
+        for t in inner.indices.chunks_exact_mut(3) {
+            t.swap(1, 2);
+        }
+        tube.merge(&inner);
+
+        let plane = Plane::new(Vec3::new(0.0, 0.0, -1.0), 0.5);
+        let section = section_mesh(&tube, &plane, &SectionOptions::default());
+        assert_eq!(section.loops.len(), 2);
+
+        let cap = cap_section(&section, &plane).unwrap();
+        assert!((cap_area(&cap) - 12.0).abs() < 1e-3);
+    }
+}
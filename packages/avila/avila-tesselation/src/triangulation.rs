@@ -0,0 +1,404 @@
+//! Triangulação de polígonos por ear clipping (com furos)
+//!
+//! Suporta polígonos côncavos e furos (IfcArbitraryProfileDefWithVoids,
+//! IfcFaceBound internos). Os furos são ligados ao contorno externo por
+//! pontes de largura zero, gerando um único polígono simples que é então
+//! triangulado por remoção de orelhas.
+//!
+//! Os índices retornados referem-se à concatenação `outer ++ holes[0] ++ ...`.
+
+use crate::{Result, TesselationError};
+use avila_vec3d::{Vec2, Vec3};
+
+/// Triangula um polígono 2D simples com furos
+///
+/// Os triângulos saem em sentido anti-horário (CCW), independente da
+/// orientação dos contornos de entrada. Um último ponto repetido (contorno
+/// fechado explicitamente, comum em IFC) é ignorado.
+pub fn triangulate_polygon(outer: &[Vec2], holes: &[Vec<Vec2>]) -> Result<Vec<[u32; 3]>> {
+    let mut points: Vec<[f64; 2]> = Vec::new();
+    let mut rings: Vec<Vec<usize>> = Vec::new();
+
+    for (ring_index, ring) in std::iter::once(outer).chain(holes.iter().map(Vec::as_slice)).enumerate() {
+        let offset = points.len();
+        points.extend(ring.iter().map(|p| [p.x as f64, p.y as f64]));
+
+        let mut indices: Vec<usize> = (offset..offset + ring.len()).collect();
+        if indices.len() > 1 && points[indices[0]] == points[*indices.last().unwrap()] {
+            indices.pop();
+        }
+        indices.dedup_by(|a, b| points[*a] == points[*b]);
+
+        if indices.len() < 3 {
+            if ring_index == 0 {
+                return Err(TesselationError::InvalidGeometry(
+                    "Polygon must have at least 3 distinct points".into()
+                ));
+            }
+            continue; // furo degenerado
+        }
+
+        // Externo CCW, furos CW
+        let ccw = signed_area(&points, &indices) > 0.0;
+        if (ring_index == 0) != ccw {
+            indices.reverse();
+        }
+        rings.push(indices);
+    }
+
+    let mut rings = rings.into_iter();
+    let mut polygon = rings.next().unwrap_or_default();
+    let holes: Vec<Vec<usize>> = rings.collect();
+    if signed_area(&points, &polygon).abs() < f64::EPSILON {
+        return Err(TesselationError::InvalidGeometry("Polygon has zero area".into()));
+    }
+
+    merge_holes(&points, &mut polygon, holes)?;
+    Ok(clip_ears(&points, polygon)
+        .into_iter()
+        .map(|[a, b, c]| [a as u32, b as u32, c as u32])
+        .collect())
+}
+
+/// Triangula uma face 3D planar com furos
+///
+/// Projeta no plano da face (normal de Newell do contorno externo); os
+/// triângulos seguem a orientação do contorno externo.
+pub fn triangulate_face(outer: &[Vec3], holes: &[Vec<Vec3>]) -> Result<Vec<[u32; 3]>> {
+    let normal = newell_normal(outer).normalize().map_err(|_| {
+        TesselationError::InvalidGeometry("Face has zero area".into())
+    })?;
+
+    // Base ortonormal (u, v) com u × v = normal
+    let helper = if normal.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+    let u = normal.cross(&helper).normalize()?;
+    let v = normal.cross(&u);
+    let project = |p: &Vec3| Vec2::new(p.dot(&u), p.dot(&v));
+
+    let outer_2d: Vec<Vec2> = outer.iter().map(project).collect();
+    let holes_2d: Vec<Vec<Vec2>> = holes.iter().map(|h| h.iter().map(project).collect()).collect();
+    triangulate_polygon(&outer_2d, &holes_2d)
+}
+
+/// Normal de Newell (robusta para polígonos côncavos/quase colineares)
+pub fn newell_normal(points: &[Vec3]) -> Vec3 {
+    let mut normal = Vec3::ZERO;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal.x += (a.y - b.y) * (a.z + b.z);
+        normal.y += (a.z - b.z) * (a.x + b.x);
+        normal.z += (a.x - b.x) * (a.y + b.y);
+    }
+    normal
+}
+
+// ============================================================================
+// FUROS (pontes)
+// ============================================================================
+
+/// Liga cada furo ao polígono externo por uma ponte visível
+fn merge_holes(points: &[[f64; 2]], polygon: &mut Vec<usize>, mut holes: Vec<Vec<usize>>) -> Result<()> {
+    // Furos mais à direita primeiro (ponte mais curta, menos cruzamentos)
+    holes.sort_by(|a, b| {
+        let max_x = |h: &Vec<usize>| h.iter().map(|&i| points[i][0]).fold(f64::MIN, f64::max);
+        max_x(b).total_cmp(&max_x(a))
+    });
+
+    let all_holes = holes.clone();
+
+    for (index, hole) in holes.iter().enumerate() {
+        // Vértice mais à direita do furo
+        let (hole_pos, &m) = hole
+            .iter()
+            .enumerate()
+            .max_by(|a, b| points[*a.1][0].total_cmp(&points[*b.1][0]))
+            .expect("hole has at least 3 points");
+
+        let remaining = &all_holes[index + 1..];
+        let bridge = find_bridge(points, polygon, remaining, &all_holes, m).ok_or_else(|| {
+            TesselationError::TesselationFailed("Could not connect hole to outer boundary".into())
+        })?;
+
+        // polygon: ... P, M, furo..., M, P, ...
+        let mut splice = Vec::with_capacity(hole.len() + 2);
+        splice.extend(hole[hole_pos..].iter().chain(&hole[..hole_pos]));
+        splice.push(m);
+        splice.push(polygon[bridge]);
+        polygon.splice(bridge + 1..bridge + 1, splice);
+    }
+    Ok(())
+}
+
+/// Posição no polígono do vértice mais próximo visível a partir de `m`
+fn find_bridge(
+    points: &[[f64; 2]],
+    polygon: &[usize],
+    remaining: &[Vec<usize>],
+    all_holes: &[Vec<usize>],
+    m: usize,
+) -> Option<usize> {
+    let pm = points[m];
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        let da = dist2(pm, points[polygon[a]]);
+        let db = dist2(pm, points[polygon[b]]);
+        // Preferir vértices à direita (ponte horizontal clássica)
+        (points[polygon[a]][0] < pm[0]).cmp(&(points[polygon[b]][0] < pm[0])).then(da.total_cmp(&db))
+    });
+
+    let edges = ring_edges(polygon).chain(remaining.iter().flat_map(|h| ring_edges(h)));
+    let edges: Vec<(usize, usize)> = edges.collect();
+
+    candidates.into_iter().find(|&pos| {
+        let v = points[polygon[pos]];
+        if v == pm {
+            return false;
+        }
+
+        // Não pode cruzar nenhuma aresta (exceto as que tocam os extremos)
+        let crosses = edges.iter().any(|&(a, b)| {
+            let (pa, pb) = (points[a], points[b]);
+            pa != v && pb != v && pa != pm && pb != pm && segments_intersect(pm, v, pa, pb)
+        });
+        if crosses {
+            return false;
+        }
+
+        // A ponte precisa estar no interior: fora dos furos e dentro do externo
+        let mid = [(pm[0] + v[0]) / 2.0, (pm[1] + v[1]) / 2.0];
+        point_in_ring(points, polygon, mid) && !all_holes.iter().any(|h| point_in_ring(points, h, mid))
+    })
+}
+
+fn ring_edges(ring: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()]))
+}
+
+// ============================================================================
+// EAR CLIPPING
+// ============================================================================
+
+fn clip_ears(points: &[[f64; 2]], mut polygon: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+    let eps = area_epsilon(points, &polygon);
+    let mut i = 0;
+    let mut stalled = 0;
+
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let (prev, curr, next) = (polygon[(i + n - 1) % n], polygon[i % n], polygon[(i + 1) % n]);
+        let (a, b, c) = (points[prev], points[curr], points[next]);
+        let area = cross(a, b, c);
+
+        if area.abs() <= eps {
+            // Vértice colinear/espigão de ponte: remove sem gerar triângulo
+            polygon.remove(i % n);
+            stalled = 0;
+            continue;
+        }
+
+        // Após uma volta completa sem orelhas, aceita qualquer convexo
+        let forced = stalled >= n;
+        if area > 0.0 && (forced || is_ear(points, &polygon, i % n, a, b, c)) {
+            triangles.push([prev, curr, next]);
+            polygon.remove(i % n);
+            stalled = 0;
+            continue;
+        }
+
+        if stalled >= 2 * n {
+            // Polígono auto-intersectante: fecha com o que restou
+            triangles.push([prev, curr, next]);
+            polygon.remove(i % n);
+            stalled = 0;
+            continue;
+        }
+
+        i = (i + 1) % n;
+        stalled += 1;
+    }
+
+    if polygon.len() == 3 {
+        let (a, b, c) = (points[polygon[0]], points[polygon[1]], points[polygon[2]]);
+        if cross(a, b, c).abs() > eps {
+            triangles.push([polygon[0], polygon[1], polygon[2]]);
+        }
+    }
+    triangles
+}
+
+/// Nenhum outro vértice (reflexo) dentro do triângulo candidato
+fn is_ear(points: &[[f64; 2]], polygon: &[usize], index: usize, a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    let n = polygon.len();
+    (0..n)
+        .filter(|&j| j != index && j != (index + n - 1) % n && j != (index + 1) % n)
+        .all(|j| {
+            let p = points[polygon[j]];
+            // Duplicatas das pontes coincidem com os vértices do triângulo
+            p == a || p == b || p == c || !point_in_triangle(p, a, b, c)
+        })
+}
+
+// ============================================================================
+// PREDICADOS GEOMÉTRICOS
+// ============================================================================
+
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn dist2(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+fn signed_area(points: &[[f64; 2]], ring: &[usize]) -> f64 {
+    ring_edges(ring)
+        .map(|(a, b)| points[a][0] * points[b][1] - points[b][0] * points[a][1])
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Tolerância de área relativa à escala do polígono
+fn area_epsilon(points: &[[f64; 2]], ring: &[usize]) -> f64 {
+    let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for &i in ring {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(points[i][axis]);
+            max[axis] = max[axis].max(points[i][axis]);
+        }
+    }
+    let extent = (max[0] - min[0]).max(max[1] - min[1]);
+    extent * extent * 1e-12
+}
+
+/// Ponto dentro (ou na borda) do triângulo CCW
+fn point_in_triangle(p: [f64; 2], a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+fn segments_intersect(p1: [f64; 2], p2: [f64; 2], q1: [f64; 2], q2: [f64; 2]) -> bool {
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+        return true;
+    }
+
+    let on_segment = |a: [f64; 2], b: [f64; 2], p: [f64; 2]| {
+        p[0] >= a[0].min(b[0]) && p[0] <= a[0].max(b[0]) && p[1] >= a[1].min(b[1]) && p[1] <= a[1].max(b[1])
+    };
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
+
+/// Par-ímpar (pontes de largura zero não alteram o resultado)
+fn point_in_ring(points: &[[f64; 2]], ring: &[usize], p: [f64; 2]) -> bool {
+    let mut inside = false;
+    for (a, b) in ring_edges(ring) {
+        let (pa, pb) = (points[a], points[b]);
+        if (pa[1] > p[1]) != (pb[1] > p[1]) {
+            let x = pa[0] + (p[1] - pa[1]) * (pb[0] - pa[0]) / (pb[1] - pa[1]);
+            if p[0] < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area_2d(points: &[Vec2], triangles: &[[u32; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| {
+                let (a, b, c) = (points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]);
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)) / 2.0
+            })
+            .sum()
+    }
+
+    fn square(min: f32, max: f32) -> Vec<Vec2> {
+        vec![Vec2::new(min, min), Vec2::new(max, min), Vec2::new(max, max), Vec2::new(min, max)]
+    }
+
+    #[test]
+    fn test_concave_l_profile() {
+        // Perfil em L (CW, como vem de alguns exportadores)
+        let profile = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 3.0),
+            Vec2::new(1.0, 3.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(3.0, 0.0),
+        ];
+
+        let triangles = triangulate_polygon(&profile, &[]).unwrap();
+        assert_eq!(triangles.len(), 4);
+        assert!((area_2d(&profile, &triangles) - 5.0).abs() < 1e-5);
+        // Todos CCW
+        for t in &triangles {
+            assert!(area_2d(&profile, &[*t]) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_polygon_with_holes() {
+        let outer = square(0.0, 10.0);
+        let holes = vec![square(2.0, 4.0), square(6.0, 8.0)];
+
+        let triangles = triangulate_polygon(&outer, &holes).unwrap();
+        let points: Vec<Vec2> = outer.iter().chain(holes.iter().flatten()).copied().collect();
+
+        assert!((area_2d(&points, &triangles) - 92.0).abs() < 1e-3);
+        // n + 2h - 2 triângulos
+        assert_eq!(triangles.len(), 12 + 2 * 2 - 2);
+    }
+
+    #[test]
+    fn test_closed_ring_and_degenerate_input() {
+        let mut outer = square(0.0, 1.0);
+        outer.push(outer[0]);
+        assert_eq!(triangulate_polygon(&outer, &[]).unwrap().len(), 2);
+
+        let line = vec![Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0)];
+        assert!(triangulate_polygon(&line, &[]).is_err());
+    }
+
+    #[test]
+    fn test_face_3d_with_hole() {
+        // Face vertical (plano XZ) com janela
+        let outer = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(4.0, 0.0, 3.0),
+            Vec3::new(0.0, 0.0, 3.0),
+        ];
+        let window = vec![
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, 2.0),
+            Vec3::new(3.0, 0.0, 2.0),
+            Vec3::new(3.0, 0.0, 1.0),
+        ];
+
+        let triangles = triangulate_face(&outer, std::slice::from_ref(&window)).unwrap();
+        let points: Vec<Vec3> = outer.iter().chain(&window).copied().collect();
+        let normal = newell_normal(&outer);
+
+        let mut area = 0.0;
+        for t in &triangles {
+            let (a, b, c) = (points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]);
+            let n = (b - a).cross(&(c - a));
+            assert!(n.dot(&normal) > 0.0);
+            area += n.length() / 2.0;
+        }
+        assert!((area - 10.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,174 @@
+//! Leitor DER mínimo (subconjunto usado por X.509 e PKCS#8)
+
+use crate::{Result, TlsConfigError};
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const SEQUENCE: u8 = 0x30;
+
+/// Tag de contexto construída (`[n] EXPLICIT`)
+pub(crate) const fn context(n: u8) -> u8 {
+    0xA0 | n
+}
+
+/// Elemento TLV
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    pub value: &'a [u8],
+    /// Elemento completo (tag + length + value)
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    pub fn reader(&self) -> Der<'a> {
+        Der::new(self.value)
+    }
+
+    /// Conteúdo de um BIT STRING sem bits não usados
+    pub fn bit_string(&self) -> Result<&'a [u8]> {
+        match self.value.split_first() {
+            Some((0, bits)) => Ok(bits),
+            _ => Err(der_error("unsupported BIT STRING padding")),
+        }
+    }
+
+    /// INTEGER sem zeros de sinal à esquerda
+    pub fn unsigned_integer(&self) -> &'a [u8] {
+        let leading = self.value.iter().take_while(|&&b| b == 0).count();
+        &self.value[leading.min(self.value.len().saturating_sub(1))..]
+    }
+
+    pub fn small_integer(&self) -> Result<u64> {
+        let bytes = self.unsigned_integer();
+        if bytes.len() > 8 || self.value.first().is_some_and(|b| b & 0x80 != 0) {
+            return Err(der_error("integer out of range"));
+        }
+        Ok(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    pub fn oid(&self) -> Result<String> {
+        decode_oid(self.value)
+    }
+}
+
+pub(crate) fn der_error(message: &str) -> TlsConfigError {
+    TlsConfigError::Der(message.to_string())
+}
+
+/// Cursor sobre uma sequência de elementos DER
+#[derive(Debug, Clone)]
+pub(crate) struct Der<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Der<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    pub fn read(&mut self) -> Result<Tlv<'a>> {
+        let start = self.pos;
+        let tag = *self.data.get(start).ok_or_else(|| der_error("unexpected end of data"))?;
+        if tag & 0x1F == 0x1F {
+            return Err(der_error("multi-byte tags are not supported"));
+        }
+
+        let first = *self.data.get(start + 1).ok_or_else(|| der_error("missing length"))?;
+        let (len, header) = if first < 0x80 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > 4 {
+                return Err(der_error("unsupported length encoding"));
+            }
+            let bytes = self
+                .data
+                .get(start + 2..start + 2 + count)
+                .ok_or_else(|| der_error("truncated length"))?;
+            (bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize), 2 + count)
+        };
+
+        let end = start
+            .checked_add(header)
+            .and_then(|v| v.checked_add(len))
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| der_error("element exceeds input"))?;
+        self.pos = end;
+        Ok(Tlv { tag, value: &self.data[start + header..end], raw: &self.data[start..end] })
+    }
+
+    /// Lê o próximo elemento exigindo a tag
+    pub fn expect(&mut self, tag: u8) -> Result<Tlv<'a>> {
+        let tlv = self.read()?;
+        if tlv.tag != tag {
+            return Err(TlsConfigError::Der(format!("expected tag 0x{:02x}, found 0x{:02x}", tag, tlv.tag)));
+        }
+        Ok(tlv)
+    }
+
+    /// Lê o próximo elemento apenas se tiver a tag
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Tlv<'a>>> {
+        if self.peek_tag() == Some(tag) {
+            self.read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn decode_oid(bytes: &[u8]) -> Result<String> {
+    let mut arcs = Vec::new();
+    let mut value: u64 = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        value = value.checked_mul(128).ok_or_else(|| der_error("OID arc overflow"))? | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        } else if i == bytes.len() - 1 {
+            return Err(der_error("truncated OID"));
+        }
+    }
+    if arcs.is_empty() {
+        return Err(der_error("empty OID"));
+    }
+    Ok(arcs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_der_reader() {
+        // SEQUENCE { OID 1.2.840.10045.2.1, INTEGER 300 }
+        let data = [0x30, 0x0D, 0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, 0x02, 0x02, 0x01, 0x2C];
+        let seq = Der::new(&data).expect(SEQUENCE).unwrap();
+        let mut inner = seq.reader();
+        assert_eq!(inner.expect(OID).unwrap().oid().unwrap(), "1.2.840.10045.2.1");
+        assert_eq!(inner.expect(INTEGER).unwrap().small_integer().unwrap(), 300);
+        assert!(inner.is_empty());
+
+        assert!(Der::new(&[0x30, 0x05, 0x00]).read().is_err());
+    }
+}
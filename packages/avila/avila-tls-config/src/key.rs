@@ -0,0 +1,186 @@
+//! Chaves privadas: PKCS#8, PKCS#1 (RSA) e SEC1 (EC)
+
+use crate::der::{self, Der};
+use crate::x509::Certificate;
+use crate::{pem, Result, TlsConfigError};
+
+const OID_RSA: &str = "1.2.840.113549.1.1.1";
+const OID_EC: &str = "1.2.840.10045.2.1";
+const OID_ED25519: &str = "1.3.101.112";
+
+/// Algoritmo da chave
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyKind {
+    Rsa,
+    Ec,
+    Ed25519,
+    /// OID de algoritmo não reconhecido (PKCS#8)
+    Unknown(String),
+}
+
+impl KeyKind {
+    fn from_oid(oid: &str) -> Self {
+        match oid {
+            OID_RSA => KeyKind::Rsa,
+            OID_EC => KeyKind::Ec,
+            OID_ED25519 => KeyKind::Ed25519,
+            other => KeyKind::Unknown(other.to_string()),
+        }
+    }
+}
+
+/// Formato de origem da chave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// `PRIVATE KEY`
+    Pkcs8,
+    /// `RSA PRIVATE KEY`
+    Pkcs1,
+    /// `EC PRIVATE KEY`
+    Sec1,
+}
+
+/// Chave privada (o DER original é mantido para o backend TLS)
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub kind: KeyKind,
+    pub format: KeyFormat,
+    pub der: Vec<u8>,
+}
+
+// Não expõe material da chave em logs
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("kind", &self.kind)
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PrivateKey {
+    pub fn from_der(format: KeyFormat, der: Vec<u8>) -> Result<Self> {
+        let kind = match format {
+            KeyFormat::Pkcs8 => {
+                let mut info = Der::new(&der).expect(der::SEQUENCE)?.reader();
+                info.expect(der::INTEGER)?;
+                let oid = info.expect(der::SEQUENCE)?.reader().expect(der::OID)?.oid()?;
+                info.expect(der::OCTET_STRING)?;
+                KeyKind::from_oid(&oid)
+            }
+            KeyFormat::Pkcs1 => {
+                Der::new(&der).expect(der::SEQUENCE)?;
+                KeyKind::Rsa
+            }
+            KeyFormat::Sec1 => {
+                Der::new(&der).expect(der::SEQUENCE)?;
+                KeyKind::Ec
+            }
+        };
+        Ok(Self { kind, format, der })
+    }
+
+    /// Primeira chave privada de um texto PEM
+    pub fn from_pem(text: &str) -> Result<Self> {
+        for block in pem::parse_pem(text)? {
+            let format = match block.label.as_str() {
+                "PRIVATE KEY" => KeyFormat::Pkcs8,
+                "RSA PRIVATE KEY" => KeyFormat::Pkcs1,
+                "EC PRIVATE KEY" => KeyFormat::Sec1,
+                "ENCRYPTED PRIVATE KEY" => {
+                    return Err(TlsConfigError::UnsupportedKey("encrypted PKCS#8 keys are not supported".into()))
+                }
+                _ => continue,
+            };
+            return Self::from_der(format, block.data);
+        }
+        Err(TlsConfigError::Pem("no private key block found".into()))
+    }
+
+    /// Verifica se a chave corresponde à chave pública do certificado
+    ///
+    /// `None` quando não é possível decidir sem criptografia (ex.: Ed25519
+    /// sem chave pública embutida, EC sem o campo `publicKey`).
+    pub fn matches(&self, cert: &Certificate) -> Option<bool> {
+        if KeyKind::from_oid(&cert.public_key_algorithm) != self.kind {
+            return Some(false);
+        }
+
+        match self.kind {
+            KeyKind::Rsa => {
+                let key_modulus = self.rsa_modulus()?;
+                let mut spki = Der::new(&cert.public_key).expect(der::SEQUENCE).ok()?.reader();
+                let cert_modulus = spki.expect(der::INTEGER).ok()?.unsigned_integer();
+                Some(key_modulus == cert_modulus)
+            }
+            KeyKind::Ec | KeyKind::Ed25519 => self.embedded_public_key().map(|key| key == cert.public_key),
+            KeyKind::Unknown(_) => None,
+        }
+    }
+
+    /// Conteúdo de `privateKey` dentro do PKCS#8 (ou o DER inteiro)
+    fn inner_key(&self) -> Option<&[u8]> {
+        match self.format {
+            KeyFormat::Pkcs8 => {
+                let mut info = Der::new(&self.der).expect(der::SEQUENCE).ok()?.reader();
+                info.expect(der::INTEGER).ok()?;
+                info.expect(der::SEQUENCE).ok()?;
+                Some(info.expect(der::OCTET_STRING).ok()?.value)
+            }
+            KeyFormat::Pkcs1 | KeyFormat::Sec1 => Some(&self.der),
+        }
+    }
+
+    fn rsa_modulus(&self) -> Option<&[u8]> {
+        let mut key = Der::new(self.inner_key()?).expect(der::SEQUENCE).ok()?.reader();
+        key.expect(der::INTEGER).ok()?;
+        Some(key.expect(der::INTEGER).ok()?.unsigned_integer())
+    }
+
+    fn embedded_public_key(&self) -> Option<Vec<u8>> {
+        match self.kind {
+            // ECPrivateKey ::= SEQUENCE { version, privateKey, [0] parameters, [1] publicKey }
+            KeyKind::Ec => {
+                let mut key = Der::new(self.inner_key()?).expect(der::SEQUENCE).ok()?.reader();
+                key.expect(der::INTEGER).ok()?;
+                key.expect(der::OCTET_STRING).ok()?;
+                key.optional(der::context(0)).ok()?;
+                let public = key.optional(der::context(1)).ok()??;
+                Some(public.reader().expect(der::BIT_STRING).ok()?.bit_string().ok()?.to_vec())
+            }
+            // OneAsymmetricKey (PKCS#8 v2): publicKey [1] IMPLICIT BIT STRING
+            KeyKind::Ed25519 => {
+                let mut info = Der::new(&self.der).expect(der::SEQUENCE).ok()?.reader();
+                info.expect(der::INTEGER).ok()?;
+                info.expect(der::SEQUENCE).ok()?;
+                info.expect(der::OCTET_STRING).ok()?;
+                info.optional(der::context(0)).ok()?;
+                let public = info.optional(0x81).ok()??;
+                public.bit_string().ok().map(<[u8]>::to_vec)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_private_key_matches_certificate() {
+        let key = PrivateKey::from_pem(LEAF_KEY).unwrap();
+        assert_eq!((key.kind.clone(), key.format), (KeyKind::Ec, KeyFormat::Pkcs8));
+        assert!(!format!("{:?}", key).contains("der"));
+
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+        let ca = Certificate::from_pem(CA_PEM).unwrap().remove(0);
+        assert_eq!(key.matches(&leaf), Some(true));
+        assert_eq!(key.matches(&ca), Some(false));
+
+        let encrypted = pem::encode_pem("ENCRYPTED PRIVATE KEY", &[0x30, 0x00]);
+        assert!(matches!(PrivateKey::from_pem(&encrypted), Err(TlsConfigError::UnsupportedKey(_))));
+        assert!(PrivateKey::from_pem(CA_PEM).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! PEM (RFC 7468): blocos `-----BEGIN <label>-----` com DER em Base64
+
+use crate::{Result, TlsConfigError};
+
+/// Bloco PEM decodificado
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemBlock {
+    /// Rótulo (`CERTIFICATE`, `PRIVATE KEY`, ...)
+    pub label: String,
+    /// Conteúdo DER
+    pub data: Vec<u8>,
+}
+
+/// Extrai todos os blocos PEM do texto (texto fora dos blocos é ignorado)
+pub fn parse_pem(text: &str) -> Result<Vec<PemBlock>> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(label) = line.strip_prefix("-----BEGIN ").and_then(|l| l.strip_suffix("-----")) {
+            if current.is_some() {
+                return Err(TlsConfigError::Pem(format!("nested BEGIN {}", label)));
+            }
+            current = Some((label.to_string(), String::new()));
+        } else if let Some(label) = line.strip_prefix("-----END ").and_then(|l| l.strip_suffix("-----")) {
+            let (begin, body) = current
+                .take()
+                .ok_or_else(|| TlsConfigError::Pem(format!("END {} without BEGIN", label)))?;
+            if begin != label {
+                return Err(TlsConfigError::Pem(format!("BEGIN {} closed by END {}", begin, label)));
+            }
+            blocks.push(PemBlock { label: begin, data: base64_decode(&body)? });
+        } else if let Some((_, body)) = current.as_mut() {
+            // Cabeçalhos RFC 1421 (Proc-Type, DEK-Info) não são suportados
+            if line.contains(':') {
+                return Err(TlsConfigError::Pem("encrypted PEM headers are not supported".into()));
+            }
+            body.push_str(line);
+        }
+    }
+
+    if let Some((label, _)) = current {
+        return Err(TlsConfigError::Pem(format!("BEGIN {} without END", label)));
+    }
+    Ok(blocks)
+}
+
+/// Codifica DER como bloco PEM (linhas de 64 caracteres)
+pub fn encode_pem(label: &str, data: &[u8]) -> String {
+    let encoded = base64_encode(data);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for chunk in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_value(c: u8) -> Option<u32> {
+    ALPHABET.iter().position(|&a| a == c).map(|p| p as u32)
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>> {
+    let symbols: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if !symbols.len().is_multiple_of(4) {
+        return Err(TlsConfigError::Pem("invalid Base64 length".into()));
+    }
+
+    let mut out = Vec::with_capacity(symbols.len() / 4 * 3);
+    for (i, quad) in symbols.chunks(4).enumerate() {
+        let last = i == symbols.len() / 4 - 1;
+        let padding = quad.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(TlsConfigError::Pem("invalid Base64 padding".into()));
+        }
+
+        let mut acc = 0u32;
+        for &c in &quad[..4 - padding] {
+            let value = base64_value(c).ok_or_else(|| TlsConfigError::Pem(format!("invalid Base64 character {:?}", c as char)))?;
+            acc = (acc << 6) | value;
+        }
+        acc <<= 6 * padding as u32;
+
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Ok(out)
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut buf = [0u8; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let acc = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((acc >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pem_roundtrip() {
+        for len in 0..8 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 5) as u8).collect();
+            let pem = encode_pem("TEST", &data);
+            let blocks = parse_pem(&format!("comentário\n{}", pem)).unwrap();
+            assert_eq!(blocks, vec![PemBlock { label: "TEST".into(), data }]);
+        }
+
+        assert!(parse_pem("-----BEGIN A-----\nAAAA\n-----END B-----\n").is_err());
+        assert!(parse_pem("-----BEGIN A-----\nAAAA\n").is_err());
+        assert!(parse_pem("-----BEGIN A-----\nA=AA\n-----END A-----\n").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Identidade TLS, raízes confiáveis e recarga a quente dos arquivos
+
+use crate::key::PrivateKey;
+use crate::x509::Certificate;
+use crate::{Result, TlsConfigError};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+// ============================================================================
+// IDENTIDADE E RAÍZES
+// ============================================================================
+
+/// Cadeia de certificados (folha primeiro) e chave privada da folha
+#[derive(Debug, Clone)]
+pub struct TlsIdentity {
+    pub chain: Vec<Certificate>,
+    pub key: PrivateKey,
+}
+
+impl TlsIdentity {
+    /// Monta a identidade e confere se a chave corresponde à folha
+    pub fn new(chain: Vec<Certificate>, key: PrivateKey) -> Result<Self> {
+        let leaf = chain.first().ok_or_else(|| TlsConfigError::Chain("empty certificate chain".into()))?;
+        if key.matches(leaf) == Some(false) {
+            return Err(TlsConfigError::KeyMismatch);
+        }
+        Ok(Self { chain, key })
+    }
+
+    pub fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self> {
+        Self::new(Certificate::from_pem(cert_pem)?, PrivateKey::from_pem(key_pem)?)
+    }
+
+    pub fn load(cert_path: impl AsRef<Path>, key_path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_pem(&fs::read_to_string(cert_path)?, &fs::read_to_string(key_path)?)
+    }
+
+    pub fn leaf(&self) -> &Certificate {
+        &self.chain[0]
+    }
+}
+
+/// Conjunto de certificados raiz confiáveis
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    pub roots: Vec<Certificate>,
+}
+
+impl TrustStore {
+    pub fn from_pem(text: &str) -> Result<Self> {
+        Ok(Self { roots: Certificate::from_pem(text)? })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_pem(&fs::read_to_string(path)?)
+    }
+
+    pub fn add(&mut self, root: Certificate) {
+        if !self.contains(&root) {
+            self.roots.push(root);
+        }
+    }
+
+    pub fn contains(&self, cert: &Certificate) -> bool {
+        self.roots.iter().any(|root| root.der == cert.der)
+    }
+
+    /// Raiz cujo sujeito é o emissor de `cert`
+    pub fn find_issuer(&self, cert: &Certificate) -> Option<&Certificate> {
+        self.roots.iter().find(|root| root.subject.raw == cert.issuer.raw)
+    }
+}
+
+// ============================================================================
+// RECARGA A QUENTE
+// ============================================================================
+
+/// Carimbo de um arquivo observado (mtime + tamanho)
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> FileStamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Identidade recarregada quando os arquivos de certificado/chave mudam
+///
+/// Conexões já abertas mantêm o `Arc` que obtiveram em [`current`](Self::current);
+/// novos handshakes usam a versão mais recente. Se a recarga falhar (arquivo
+/// pela metade, chave que não corresponde), a identidade anterior continua em uso.
+#[derive(Debug)]
+pub struct ReloadableIdentity {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    current: RwLock<Arc<TlsIdentity>>,
+    stamps: Mutex<(FileStamp, FileStamp)>,
+    generation: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ReloadableIdentity {
+    pub fn load(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Result<Self> {
+        let cert_path = cert_path.into();
+        let key_path = key_path.into();
+        let stamps = (stamp(&cert_path), stamp(&key_path));
+        let identity = TlsIdentity::load(&cert_path, &key_path)?;
+
+        Ok(Self {
+            cert_path,
+            key_path,
+            current: RwLock::new(Arc::new(identity)),
+            stamps: Mutex::new(stamps),
+            generation: AtomicU64::new(1),
+            last_error: Mutex::new(None),
+        })
+    }
+
+    pub fn current(&self) -> Arc<TlsIdentity> {
+        Arc::clone(&self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    /// Incrementado a cada recarga bem-sucedida (começa em 1)
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Erro da última tentativa de recarga, se falhou
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Recarrega se algum dos arquivos mudou; `Ok(true)` quando trocou a identidade
+    ///
+    /// Em caso de erro os carimbos não são atualizados, então a próxima
+    /// chamada tenta de novo (ex.: chave gravada depois do certificado).
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let mut stamps = self.stamps.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let observed = (stamp(&self.cert_path), stamp(&self.key_path));
+        if observed == *stamps {
+            return Ok(false);
+        }
+
+        let mut last_error = self.last_error.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match TlsIdentity::load(&self.cert_path, &self.key_path) {
+            Ok(identity) => {
+                *self.current.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(identity);
+                *stamps = observed;
+                *last_error = None;
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                Ok(true)
+            }
+            Err(err) => {
+                *last_error = Some(err.to_string());
+                Err(err)
+            }
+        }
+    }
+
+    /// Verifica os arquivos a cada `interval` numa thread dedicada
+    ///
+    /// A thread para quando o [`WatchHandle`] é descartado.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> WatchHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let identity = Arc::clone(self);
+            let stop = Arc::clone(&stop);
+            thread::Builder::new()
+                .name("tls-config-watch".into())
+                .spawn(move || {
+                    while !stop.load(Ordering::Acquire) {
+                        thread::park_timeout(interval);
+                        if stop.load(Ordering::Acquire) {
+                            break;
+                        }
+                        // O erro fica disponível em `last_error`
+                        let _ = identity.reload_if_changed();
+                    }
+                })
+                .ok()
+        };
+        WatchHandle { stop, thread }
+    }
+}
+
+/// Mantém ativa a thread de [`ReloadableIdentity::watch`]
+#[derive(Debug)]
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_identity_hot_reload() {
+        let dir = std::env::temp_dir().join(format!("avila-tls-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        fs::write(&cert_path, LEAF_PEM).unwrap();
+        fs::write(&key_path, LEAF_KEY).unwrap();
+
+        let identity = Arc::new(ReloadableIdentity::load(&cert_path, &key_path).unwrap());
+        assert_eq!(identity.current().leaf().subject.display(), "api.vizzio.local");
+        assert!(!identity.reload_if_changed().unwrap());
+
+        // Certificado que não corresponde à chave: mantém a identidade anterior
+        fs::write(&cert_path, CA_PEM).unwrap();
+        assert!(matches!(identity.reload_if_changed(), Err(TlsConfigError::KeyMismatch)));
+        assert!(identity.last_error().is_some());
+        assert_eq!((identity.generation(), identity.current().chain.len()), (1, 1));
+
+        // Cadeia completa, detectada pela thread de observação
+        let handle = identity.watch(Duration::from_millis(5));
+        fs::write(&cert_path, format!("{}{}", LEAF_PEM, CA_PEM)).unwrap();
+        while identity.generation() < 2 {
+            thread::sleep(Duration::from_millis(5));
+        }
+        drop(handle);
+
+        assert_eq!(identity.current().chain.len(), 2);
+        assert!(identity.last_error().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
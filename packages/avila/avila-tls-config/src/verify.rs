@@ -0,0 +1,250 @@
+//! Verificação de hostname (RFC 6125) e validação de cadeia
+
+use crate::reload::TrustStore;
+use crate::x509::{Certificate, SubjectAltName};
+use crate::{Result, TlsConfigError};
+use std::net::IpAddr;
+
+/// `id-kp-serverAuth` (RFC 5280, 4.2.1.12)
+const OID_SERVER_AUTH: &str = "1.3.6.1.5.5.7.3.1";
+
+/// Verificação criptográfica de assinaturas, fornecida pelo backend TLS
+pub trait SignatureVerifier {
+    /// `true` se `signature` sobre `message` é válida para `public_key`
+    /// (conteúdo do `subjectPublicKey` do emissor) no algoritmo `algorithm` (OID)
+    fn verify(&self, algorithm: &str, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Verifica se o certificado cobre o hostname ou IP
+///
+/// Apenas o SAN é consultado (sem fallback para o CN). Curingas valem só
+/// como rótulo inteiro mais à esquerda (`*.example.com`) e cobrem um único rótulo.
+pub fn verify_hostname(cert: &Certificate, host: &str) -> Result<()> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let matched = match host.parse::<IpAddr>() {
+        Ok(ip) => cert
+            .subject_alt_names
+            .iter()
+            .any(|san| matches!(san, SubjectAltName::Ip(addr) if *addr == ip)),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            cert.dns_names().any(|pattern| dns_name_matches(pattern.trim_end_matches('.'), &host))
+        }
+    };
+
+    if matched {
+        Ok(())
+    } else {
+        Err(TlsConfigError::HostnameMismatch(host.to_string()))
+    }
+}
+
+fn dns_name_matches(pattern: &str, host: &str) -> bool {
+    if host.is_empty() {
+        return false;
+    }
+    match pattern.strip_prefix("*.") {
+        // Exige ao menos dois rótulos após o curinga (rejeita `*.com`)
+        Some(suffix) if suffix.contains('.') && !suffix.contains('*') => host
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+        Some(_) => false,
+        None => !pattern.contains('*') && pattern == host,
+    }
+}
+
+/// Valida a cadeia de um servidor (folha primeiro) até uma raiz de `trust`
+///
+/// Além de [`validate_chain_structure`], confere com `verifier` a assinatura
+/// de cada certificado pela chave do seu emissor.
+pub fn validate_chain(
+    chain: &[Certificate],
+    trust: &TrustStore,
+    now: i64,
+    verifier: &dyn SignatureVerifier,
+) -> Result<()> {
+    check_chain(chain, trust, now, Some(verifier))
+}
+
+/// Valida a estrutura da cadeia de um servidor, sem verificar assinaturas
+///
+/// Confere validade em `now` (segundos Unix), ligação emissor/sujeito,
+/// `basicConstraints` (CA e `pathLenConstraint`), a âncora e o uso da folha:
+/// não pode ser CA e, com `extendedKeyUsage`, precisa de `serverAuth`.
+pub fn validate_chain_structure(chain: &[Certificate], trust: &TrustStore, now: i64) -> Result<()> {
+    check_chain(chain, trust, now, None)
+}
+
+fn check_chain(
+    chain: &[Certificate],
+    trust: &TrustStore,
+    now: i64,
+    verifier: Option<&dyn SignatureVerifier>,
+) -> Result<()> {
+    let leaf = chain.first().ok_or_else(|| TlsConfigError::Chain("empty certificate chain".into()))?;
+    check_validity(leaf, now)?;
+    check_leaf_usage(leaf)?;
+
+    for (depth, pair) in chain.windows(2).enumerate() {
+        let (cert, issuer) = (&pair[0], &pair[1]);
+        check_issuer(cert, issuer, depth, now, verifier)?;
+    }
+
+    let last = &chain[chain.len() - 1];
+    if trust.contains(last) {
+        return Ok(());
+    }
+    let root = trust.find_issuer(last).ok_or_else(|| {
+        TlsConfigError::Chain(format!("'{}' is not issued by a trusted root", last.subject.display()))
+    })?;
+    check_issuer(last, root, chain.len() - 1, now, verifier)
+}
+
+fn check_leaf_usage(leaf: &Certificate) -> Result<()> {
+    if leaf.is_ca {
+        return Err(TlsConfigError::Chain(format!(
+            "'{}' is a CA certificate, not a server certificate",
+            leaf.subject.display()
+        )));
+    }
+    if let Some(purposes) = &leaf.extended_key_usage {
+        if !purposes.iter().any(|oid| oid == OID_SERVER_AUTH) {
+            return Err(TlsConfigError::Chain(format!(
+                "'{}' is not valid for TLS server authentication",
+                leaf.subject.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn check_validity(cert: &Certificate, now: i64) -> Result<()> {
+    if cert.is_valid_at(now) {
+        Ok(())
+    } else {
+        Err(TlsConfigError::NotValid {
+            subject: cert.subject.display().to_string(),
+            not_before: cert.not_before,
+            not_after: cert.not_after,
+            now,
+        })
+    }
+}
+
+/// `depth` é a posição de `cert` na cadeia (0 = folha)
+fn check_issuer(
+    cert: &Certificate,
+    issuer: &Certificate,
+    depth: usize,
+    now: i64,
+    verifier: Option<&dyn SignatureVerifier>,
+) -> Result<()> {
+    check_validity(issuer, now)?;
+
+    if cert.issuer.raw != issuer.subject.raw {
+        return Err(TlsConfigError::Chain(format!(
+            "'{}' is not issued by '{}'",
+            cert.subject.display(),
+            issuer.subject.display()
+        )));
+    }
+    if !issuer.is_ca {
+        return Err(TlsConfigError::Chain(format!("'{}' is not a CA", issuer.subject.display())));
+    }
+    // CAs intermediárias abaixo do emissor (a folha não conta)
+    if let Some(path_len) = issuer.path_len {
+        if depth > path_len as usize {
+            return Err(TlsConfigError::Chain(format!(
+                "path length constraint of '{}' exceeded",
+                issuer.subject.display()
+            )));
+        }
+    }
+    if let Some(verifier) = verifier {
+        if !verifier.verify(&cert.signature_algorithm, &issuer.public_key, &cert.tbs, &cert.signature) {
+            return Err(TlsConfigError::Chain(format!("invalid signature on '{}'", cert.subject.display())));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    struct RejectAll;
+
+    impl SignatureVerifier for RejectAll {
+        fn verify(&self, _: &str, _: &[u8], _: &[u8], _: &[u8]) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_verify_hostname() {
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+
+        for host in ["api.vizzio.local", "API.Vizzio.Local.", "bim.models.vizzio.local", "10.0.0.7"] {
+            assert!(verify_hostname(&leaf, host).is_ok(), "{}", host);
+        }
+        for host in ["vizzio.local", "models.vizzio.local", "a.b.models.vizzio.local", "10.0.0.8", "::1"] {
+            assert!(verify_hostname(&leaf, host).is_err(), "{}", host);
+        }
+
+        assert!(!dns_name_matches("*.local", "vizzio.local"));
+        assert!(!dns_name_matches("a*.vizzio.local", "api.vizzio.local"));
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        let ca = Certificate::from_pem(CA_PEM).unwrap().remove(0);
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+        let trust = TrustStore::from_pem(CA_PEM).unwrap();
+
+        assert!(validate_chain_structure(std::slice::from_ref(&leaf), &trust, NOW).is_ok());
+        assert!(validate_chain_structure(&[leaf.clone(), ca.clone()], &trust, NOW).is_ok());
+
+        assert!(matches!(
+            validate_chain_structure(std::slice::from_ref(&leaf), &trust, leaf.not_after + 1),
+            Err(TlsConfigError::NotValid { .. })
+        ));
+        assert!(matches!(
+            validate_chain_structure(std::slice::from_ref(&leaf), &TrustStore::default(), NOW),
+            Err(TlsConfigError::Chain(_))
+        ));
+        assert!(validate_chain(std::slice::from_ref(&leaf), &trust, NOW, &RejectAll).is_err());
+        assert!(validate_chain(&[leaf.clone(), ca], &trust, NOW, &RejectAll).is_err());
+
+        // pathlen 0: a CA não pode emitir outra CA intermediária
+        let mut intermediate = leaf.clone();
+        intermediate.is_ca = true;
+        let mut below = leaf.clone();
+        below.issuer = intermediate.subject.clone();
+        assert!(validate_chain_structure(&[below, intermediate], &trust, NOW).is_err());
+    }
+
+    #[test]
+    fn test_validate_leaf_usage() {
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+        let trust = TrustStore::from_pem(CA_PEM).unwrap();
+        let check = |cert: Certificate| validate_chain_structure(&[cert], &trust, NOW);
+
+        let mut ca_leaf = leaf.clone();
+        ca_leaf.is_ca = true;
+        assert!(matches!(check(ca_leaf), Err(TlsConfigError::Chain(_))));
+
+        let mut client_only = leaf.clone();
+        client_only.extended_key_usage = Some(vec!["1.3.6.1.5.5.7.3.2".into()]);
+        assert!(matches!(check(client_only), Err(TlsConfigError::Chain(_))));
+
+        let mut no_purpose = leaf.clone();
+        no_purpose.extended_key_usage = Some(Vec::new());
+        assert!(check(no_purpose).is_err());
+
+        let mut server = leaf;
+        server.extended_key_usage = Some(vec!["1.3.6.1.5.5.7.3.2".into(), OID_SERVER_AUTH.into()]);
+        assert!(check(server).is_ok());
+    }
+}
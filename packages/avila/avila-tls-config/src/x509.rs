@@ -0,0 +1,314 @@
+//! Certificados X.509 (RFC 5280) - somente os campos usados na validação
+
+use crate::der::{self, der_error, Der, Tlv};
+use crate::{pem, Result, TlsConfigError};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const OID_COMMON_NAME: &str = "2.5.4.3";
+const OID_SUBJECT_ALT_NAME: &str = "2.5.29.17";
+const OID_BASIC_CONSTRAINTS: &str = "2.5.29.19";
+const OID_EXTENDED_KEY_USAGE: &str = "2.5.29.37";
+
+/// Nome distinto (DN)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Name {
+    /// DER do nome, usado para ligar emissor e sujeito na cadeia
+    pub raw: Vec<u8>,
+    pub common_name: Option<String>,
+}
+
+impl Name {
+    /// CN ou o marcador `<unnamed>` para mensagens de erro
+    pub fn display(&self) -> &str {
+        self.common_name.as_deref().unwrap_or("<unnamed>")
+    }
+}
+
+/// Entrada de Subject Alternative Name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubjectAltName {
+    Dns(String),
+    Ip(IpAddr),
+}
+
+/// Certificado X.509 decodificado
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// DER completo
+    pub der: Vec<u8>,
+    /// DER do `TBSCertificate` (mensagem assinada)
+    pub tbs: Vec<u8>,
+    pub serial: Vec<u8>,
+    pub issuer: Name,
+    pub subject: Name,
+    /// Início da validade (segundos Unix)
+    pub not_before: i64,
+    /// Fim da validade (segundos Unix)
+    pub not_after: i64,
+    pub subject_alt_names: Vec<SubjectAltName>,
+    pub is_ca: bool,
+    pub path_len: Option<u32>,
+    /// OIDs do `extendedKeyUsage`; `None` sem a extensão (uso irrestrito)
+    pub extended_key_usage: Option<Vec<String>>,
+    /// OID do algoritmo da chave pública (ex.: `1.2.840.10045.2.1`)
+    pub public_key_algorithm: String,
+    /// Conteúdo do BIT STRING `subjectPublicKey`
+    pub public_key: Vec<u8>,
+    /// OID do algoritmo de assinatura do emissor
+    pub signature_algorithm: String,
+    pub signature: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn from_der(data: &[u8]) -> Result<Self> {
+        let mut outer = Der::new(data);
+        let cert = outer.expect(der::SEQUENCE)?;
+        if !outer.is_empty() {
+            return Err(der_error("trailing data after certificate"));
+        }
+
+        let mut parts = cert.reader();
+        let tbs = parts.expect(der::SEQUENCE)?;
+        let signature_algorithm = parts.expect(der::SEQUENCE)?.reader().expect(der::OID)?.oid()?;
+        let signature = parts.expect(der::BIT_STRING)?.bit_string()?.to_vec();
+
+        let mut fields = tbs.reader();
+        fields.optional(der::context(0))?;
+        let serial = fields.expect(der::INTEGER)?.value.to_vec();
+        fields.expect(der::SEQUENCE)?;
+        let issuer = parse_name(fields.expect(der::SEQUENCE)?)?;
+
+        let mut validity = fields.expect(der::SEQUENCE)?.reader();
+        let not_before = parse_time(validity.read()?)?;
+        let not_after = parse_time(validity.read()?)?;
+
+        let subject = parse_name(fields.expect(der::SEQUENCE)?)?;
+
+        let mut spki = fields.expect(der::SEQUENCE)?.reader();
+        let public_key_algorithm = spki.expect(der::SEQUENCE)?.reader().expect(der::OID)?.oid()?;
+        let public_key = spki.expect(der::BIT_STRING)?.bit_string()?.to_vec();
+
+        let mut certificate = Self {
+            der: data.to_vec(),
+            tbs: tbs.raw.to_vec(),
+            serial,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            subject_alt_names: Vec::new(),
+            is_ca: false,
+            path_len: None,
+            extended_key_usage: None,
+            public_key_algorithm,
+            public_key,
+            signature_algorithm,
+            signature,
+        };
+
+        // issuerUniqueID [1] e subjectUniqueID [2] são ignorados
+        while let Some(tag) = fields.peek_tag() {
+            let field = fields.read()?;
+            if tag == der::context(3) {
+                certificate.parse_extensions(field)?;
+            }
+        }
+        Ok(certificate)
+    }
+
+    /// Todos os certificados de um texto PEM, na ordem em que aparecem
+    pub fn from_pem(text: &str) -> Result<Vec<Self>> {
+        pem::parse_pem(text)?
+            .into_iter()
+            .filter(|block| block.label == "CERTIFICATE")
+            .map(|block| Self::from_der(&block.data))
+            .collect()
+    }
+
+    pub fn is_valid_at(&self, now: i64) -> bool {
+        self.not_before <= now && now <= self.not_after
+    }
+
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer.raw == self.subject.raw
+    }
+
+    pub fn dns_names(&self) -> impl Iterator<Item = &str> {
+        self.subject_alt_names.iter().filter_map(|san| match san {
+            SubjectAltName::Dns(name) => Some(name.as_str()),
+            SubjectAltName::Ip(_) => None,
+        })
+    }
+
+    fn parse_extensions(&mut self, explicit: Tlv<'_>) -> Result<()> {
+        let mut extensions = explicit.reader().expect(der::SEQUENCE)?.reader();
+        while !extensions.is_empty() {
+            let mut extension = extensions.expect(der::SEQUENCE)?.reader();
+            let oid = extension.expect(der::OID)?.oid()?;
+            extension.optional(der::BOOLEAN)?;
+            let value = extension.expect(der::OCTET_STRING)?.value;
+
+            match oid.as_str() {
+                OID_SUBJECT_ALT_NAME => self.subject_alt_names = parse_subject_alt_names(value)?,
+                OID_BASIC_CONSTRAINTS => {
+                    let mut constraints = Der::new(value).expect(der::SEQUENCE)?.reader();
+                    if let Some(ca) = constraints.optional(der::BOOLEAN)? {
+                        self.is_ca = ca.value.first().is_some_and(|&b| b != 0);
+                    }
+                    if let Some(len) = constraints.optional(der::INTEGER)? {
+                        self.path_len = Some(u32::try_from(len.small_integer()?).unwrap_or(u32::MAX));
+                    }
+                }
+                OID_EXTENDED_KEY_USAGE => {
+                    let mut purposes = Der::new(value).expect(der::SEQUENCE)?.reader();
+                    let mut oids = Vec::new();
+                    while !purposes.is_empty() {
+                        oids.push(purposes.expect(der::OID)?.oid()?);
+                    }
+                    self.extended_key_usage = Some(oids);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_name(name: Tlv<'_>) -> Result<Name> {
+    let mut common_name = None;
+    let mut rdns = name.reader();
+    while !rdns.is_empty() {
+        let mut set = rdns.expect(0x31)?.reader();
+        while !set.is_empty() {
+            let mut attribute = set.expect(der::SEQUENCE)?.reader();
+            let oid = attribute.expect(der::OID)?.oid()?;
+            let value = attribute.read()?;
+            if oid == OID_COMMON_NAME {
+                common_name = Some(String::from_utf8_lossy(value.value).into_owned());
+            }
+        }
+    }
+    Ok(Name { raw: name.raw.to_vec(), common_name })
+}
+
+fn parse_subject_alt_names(value: &[u8]) -> Result<Vec<SubjectAltName>> {
+    let mut names = Vec::new();
+    let mut general_names = Der::new(value).expect(der::SEQUENCE)?.reader();
+    while !general_names.is_empty() {
+        let name = general_names.read()?;
+        match name.tag {
+            // dNSName [2] IA5String
+            0x82 => names.push(SubjectAltName::Dns(String::from_utf8_lossy(name.value).to_ascii_lowercase())),
+            // iPAddress [7] OCTET STRING
+            0x87 => {
+                let ip = match name.value.len() {
+                    4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(name.value).unwrap_or_default())),
+                    16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(name.value).unwrap_or_default())),
+                    _ => return Err(der_error("invalid iPAddress length")),
+                };
+                names.push(SubjectAltName::Ip(ip));
+            }
+            _ => {}
+        }
+    }
+    Ok(names)
+}
+
+/// UTCTime (`YYMMDDHHMMSSZ`) ou GeneralizedTime (`YYYYMMDDHHMMSSZ`) em segundos Unix
+fn parse_time(time: Tlv<'_>) -> Result<i64> {
+    let text = std::str::from_utf8(time.value).map_err(|_| der_error("invalid time encoding"))?;
+    let digits = text
+        .strip_suffix('Z')
+        .filter(|d| d.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| TlsConfigError::Der(format!("unsupported time format '{}'", text)))?;
+
+    let number = |range: std::ops::Range<usize>| -> Result<i64> {
+        digits
+            .get(range)
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| TlsConfigError::Der(format!("unsupported time format '{}'", text)))
+    };
+
+    let (year, rest) = match (time.tag, digits.len()) {
+        (der::UTC_TIME, 12) => {
+            let yy = number(0..2)?;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, 2)
+        }
+        (der::GENERALIZED_TIME, 14) => (number(0..4)?, 4),
+        _ => return Err(TlsConfigError::Der(format!("unsupported time format '{}'", text))),
+    };
+    let month = number(rest..rest + 2)?;
+    let day = number(rest + 2..rest + 4)?;
+    let hour = number(rest + 4..rest + 6)?;
+    let minute = number(rest + 6..rest + 8)?;
+    let second = number(rest + 8..rest + 10)?;
+
+    Ok(days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+/// Dias desde 1970-01-01 no calendário gregoriano proléptico
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_parse_certificates() {
+        let ca = Certificate::from_pem(CA_PEM).unwrap().remove(0);
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+
+        assert_eq!(ca.subject.common_name.as_deref(), Some("Vizzio Test CA"));
+        assert!(ca.is_self_issued());
+        assert!(ca.is_ca);
+        assert_eq!(ca.path_len, Some(0));
+
+        assert_eq!(leaf.subject.display(), "api.vizzio.local");
+        assert_eq!(leaf.issuer, ca.subject);
+        assert!(!leaf.is_ca);
+        assert_eq!(leaf.serial[0], 0x68);
+        assert_eq!(leaf.public_key_algorithm, "1.2.840.10045.2.1");
+        assert_eq!(leaf.signature_algorithm, "1.2.840.10045.4.3.2");
+        assert_eq!(
+            leaf.subject_alt_names,
+            vec![
+                SubjectAltName::Dns("api.vizzio.local".into()),
+                SubjectAltName::Dns("*.models.vizzio.local".into()),
+                SubjectAltName::Ip("10.0.0.7".parse().unwrap()),
+            ]
+        );
+
+        // 2026-10-17 04:10:50 UTC (UTCTime) até 2126-09-23 04:10:50 UTC (GeneralizedTime)
+        assert_eq!(leaf.not_before, 1_792_210_250);
+        assert_eq!(leaf.not_after, 4_945_810_250);
+        assert!(leaf.is_valid_at(NOW));
+        assert!(!leaf.is_valid_at(leaf.not_before - 1));
+        assert_eq!(leaf.extended_key_usage, None);
+    }
+
+    #[test]
+    fn test_parse_extended_key_usage() {
+        let mut leaf = Certificate::from_pem(LEAF_PEM).unwrap().remove(0);
+        // [3] { SEQUENCE { SEQUENCE { OID 2.5.29.37, OCTET STRING {
+        //     SEQUENCE { OID serverAuth, OID clientAuth } } } } }
+        let extensions = [
+            0xa3, 0x21, 0x30, 0x1f, 0x30, 0x1d, 0x06, 0x03, 0x55, 0x1d, 0x25, 0x04, 0x16, 0x30, 0x14, 0x06, 0x08,
+            0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03,
+            0x02,
+        ];
+        leaf.parse_extensions(Der::new(&extensions).read().unwrap()).unwrap();
+        assert_eq!(
+            leaf.extended_key_usage,
+            Some(vec!["1.3.6.1.5.5.7.3.1".to_string(), "1.3.6.1.5.5.7.3.2".to_string()])
+        );
+    }
+}
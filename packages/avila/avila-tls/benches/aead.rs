@@ -0,0 +1,56 @@
+//! Throughput das AEADs do TLS 1.3 em payloads de 1 MiB
+
+use avila_tls::cipher_suite::aes_gcm::AesGcm;
+use avila_tls::cipher_suite::chacha20_poly1305::ChaCha20Poly1305;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+const PAYLOAD: usize = 1 << 20;
+const NONCE: [u8; 12] = [7; 12];
+const AAD: &[u8] = &[0x17, 0x03, 0x03, 0x40, 0x11];
+
+fn aes_gcm_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aes_gcm_1mb");
+    group.throughput(Throughput::Bytes(PAYLOAD as u64));
+    let data = vec![0x5au8; PAYLOAD];
+
+    for (name, key_len) in [("aes128", 16), ("aes256", 32)] {
+        let cipher = AesGcm::new(&vec![0x42; key_len]);
+        group.bench_function(format!("{}_seal", name), |b| {
+            b.iter_batched(
+                || data.clone(),
+                |mut buffer| cipher.seal(&NONCE, black_box(AAD), &mut buffer),
+                BatchSize::LargeInput,
+            )
+        });
+
+        let mut sealed = data.clone();
+        cipher.seal(&NONCE, AAD, &mut sealed);
+        group.bench_function(format!("{}_open", name), |b| {
+            b.iter_batched(
+                || sealed.clone(),
+                |mut buffer| assert!(cipher.open(&NONCE, black_box(AAD), &mut buffer)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn chacha_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chacha20_poly1305_1mb");
+    group.throughput(Throughput::Bytes(PAYLOAD as u64));
+    let data = vec![0x5au8; PAYLOAD];
+    let cipher = ChaCha20Poly1305::new(&[0x42; 32]);
+
+    group.bench_function("seal", |b| {
+        b.iter_batched(
+            || data.clone(),
+            |mut buffer| cipher.seal(&NONCE, black_box(AAD), &mut buffer),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, aes_gcm_benchmark, chacha_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,253 @@
+//! Aritmética modular de Montgomery para verificação de assinaturas
+//!
+//! Só processa dados públicos (assinaturas, chaves públicas), então não busca
+//! tempo constante. Números são limbs `u32` little-endian de tamanho fixo.
+
+use std::cmp::Ordering;
+
+pub(crate) type Limbs = Vec<u32>;
+
+/// Big-endian para limbs, com `len` limbs (`None` se não couber)
+pub(crate) fn from_be_bytes(bytes: &[u8], len: usize) -> Option<Limbs> {
+    let bytes = &bytes[bytes.iter().take_while(|&&b| b == 0).count()..];
+    if bytes.len() > len * 4 {
+        return None;
+    }
+    let mut limbs = vec![0u32; len];
+    for (i, &byte) in bytes.iter().rev().enumerate() {
+        limbs[i / 4] |= (byte as u32) << (8 * (i % 4));
+    }
+    Some(limbs)
+}
+
+/// Limbs para big-endian com exatamente `len` bytes
+pub(crate) fn to_be_bytes(limbs: &[u32], len: usize) -> Vec<u8> {
+    (0..len)
+        .rev()
+        .map(|i| limbs.get(i / 4).map_or(0, |limb| (limb >> (8 * (i % 4))) as u8))
+        .collect()
+}
+
+pub(crate) fn compare(a: &[u32], b: &[u32]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+pub(crate) fn is_zero(a: &[u32]) -> bool {
+    a.iter().all(|&limb| limb == 0)
+}
+
+/// `a -= b`, devolvendo o borrow
+fn sub_assign(a: &mut [u32], b: &[u32]) -> bool {
+    let mut borrow = 0u64;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let diff = (*x as u64).wrapping_sub(y as u64).wrapping_sub(borrow);
+        *x = diff as u32;
+        borrow = (diff >> 63) & 1;
+    }
+    borrow != 0
+}
+
+/// `a += b`, devolvendo o carry
+fn add_assign(a: &mut [u32], b: &[u32]) -> bool {
+    let mut carry = 0u64;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let sum = *x as u64 + y as u64 + carry;
+        *x = sum as u32;
+        carry = sum >> 32;
+    }
+    carry != 0
+}
+
+/// Módulo ímpar com as constantes de Montgomery (`R = 2^(32·len)`)
+pub(crate) struct Modulus {
+    pub m: Limbs,
+    /// `-m⁻¹ mod 2^32`
+    m_inv: u32,
+    /// `R² mod m`
+    r2: Limbs,
+    /// `R mod m` (1 na forma de Montgomery)
+    one: Limbs,
+}
+
+impl Modulus {
+    pub(crate) fn new(m: Limbs) -> Self {
+        debug_assert!(m[0] & 1 == 1, "Montgomery modulus must be odd");
+        let mut inv = 1u32;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u32.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+
+        let len = m.len();
+        let mut modulus = Self {
+            m_inv: inv.wrapping_neg(),
+            r2: Vec::new(),
+            one: Vec::new(),
+            m,
+        };
+        // Dobra 1 até R² reduzindo a cada passo
+        let mut x = vec![0u32; len];
+        x[0] = 1;
+        for step in 0..64 * len {
+            modulus.double(&mut x);
+            if step == 32 * len - 1 {
+                modulus.one = x.clone();
+            }
+        }
+        modulus.r2 = x;
+        modulus
+    }
+
+    pub(crate) fn from_be_bytes(bytes: &[u8]) -> Self {
+        let len = bytes.len().div_ceil(4).max(1);
+        Self::new(from_be_bytes(bytes, len).expect("sized to fit"))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.m.len()
+    }
+
+    /// Bits significativos do módulo
+    pub(crate) fn bits(&self) -> usize {
+        let top = self.m.iter().rposition(|&limb| limb != 0).unwrap_or(0);
+        top * 32 + (32 - self.m[top].leading_zeros() as usize)
+    }
+
+    fn double(&self, x: &mut [u32]) {
+        let mut carry = 0u32;
+        for limb in x.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 || compare(x, &self.m) != Ordering::Less {
+            sub_assign(x, &self.m);
+        }
+    }
+
+    /// Reduz um valor menor que `2m` (ex.: hash truncado no tamanho de `m`)
+    pub(crate) fn reduce_once(&self, mut x: Limbs) -> Limbs {
+        if compare(&x, &self.m) != Ordering::Less {
+            sub_assign(&mut x, &self.m);
+        }
+        x
+    }
+
+    pub(crate) fn add(&self, a: &[u32], b: &[u32]) -> Limbs {
+        let mut sum = a.to_vec();
+        let carry = add_assign(&mut sum, b);
+        if carry || compare(&sum, &self.m) != Ordering::Less {
+            sub_assign(&mut sum, &self.m);
+        }
+        sum
+    }
+
+    pub(crate) fn sub(&self, a: &[u32], b: &[u32]) -> Limbs {
+        let mut diff = a.to_vec();
+        if sub_assign(&mut diff, b) {
+            add_assign(&mut diff, &self.m);
+        }
+        diff
+    }
+
+    /// Produto de Montgomery `a·b·R⁻¹ mod m` (CIOS)
+    pub(crate) fn mul(&self, a: &[u32], b: &[u32]) -> Limbs {
+        let n = self.len();
+        let mut t = vec![0u32; n + 2];
+        for &bi in b.iter().take(n) {
+            let mut carry = 0u64;
+            for j in 0..n {
+                let sum = t[j] as u64 + a[j] as u64 * bi as u64 + carry;
+                t[j] = sum as u32;
+                carry = sum >> 32;
+            }
+            let sum = t[n] as u64 + carry;
+            t[n] = sum as u32;
+            t[n + 1] = (sum >> 32) as u32;
+
+            let q = t[0].wrapping_mul(self.m_inv) as u64;
+            let mut carry = (t[0] as u64 + q * self.m[0] as u64) >> 32;
+            for j in 1..n {
+                let sum = t[j] as u64 + q * self.m[j] as u64 + carry;
+                t[j - 1] = sum as u32;
+                carry = sum >> 32;
+            }
+            let sum = t[n] as u64 + carry;
+            t[n - 1] = sum as u32;
+            t[n] = t[n + 1] + (sum >> 32) as u32;
+        }
+
+        let overflow = t[n] != 0;
+        t.truncate(n);
+        if overflow || compare(&t, &self.m) != Ordering::Less {
+            sub_assign(&mut t, &self.m);
+        }
+        t
+    }
+
+    pub(crate) fn square(&self, a: &[u32]) -> Limbs {
+        self.mul(a, a)
+    }
+
+    pub(crate) fn to_mont(&self, a: &[u32]) -> Limbs {
+        self.mul(a, &self.r2)
+    }
+
+    pub(crate) fn leave_mont(&self, a: &[u32]) -> Limbs {
+        let mut one = vec![0u32; self.len()];
+        one[0] = 1;
+        self.mul(a, &one)
+    }
+
+    /// `1` na forma de Montgomery
+    pub(crate) fn one(&self) -> Limbs {
+        self.one.clone()
+    }
+
+    /// `base^exponent` com `base` na forma de Montgomery; `exponent` big-endian
+    pub(crate) fn pow(&self, base: &[u32], exponent: &[u8]) -> Limbs {
+        let mut result = self.one();
+        for byte in exponent {
+            for bit in (0..8).rev() {
+                result = self.square(&result);
+                if (byte >> bit) & 1 == 1 {
+                    result = self.mul(&result, base);
+                }
+            }
+        }
+        result
+    }
+
+    /// Inverso por Fermat (`m` primo), na forma de Montgomery
+    pub(crate) fn invert(&self, a: &[u32]) -> Limbs {
+        let mut exponent = self.m.clone();
+        let two = {
+            let mut two = vec![0u32; self.len()];
+            two[0] = 2;
+            two
+        };
+        sub_assign(&mut exponent, &two);
+        self.pow(a, &to_be_bytes(&exponent, self.len() * 4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_montgomery_pow() {
+        // 2^127 - 1 é primo
+        let mut bytes = vec![0xFFu8; 16];
+        bytes[0] = 0x7F;
+        let modulus = Modulus::from_be_bytes(&bytes);
+        assert_eq!(modulus.bits(), 127);
+
+        let three = modulus.to_mont(&from_be_bytes(&[3], 4).unwrap());
+        let inverse = modulus.invert(&three);
+        assert_eq!(modulus.leave_mont(&modulus.mul(&three, &inverse)), from_be_bytes(&[1], 4).unwrap());
+
+        // 3^1000 mod (2^127 - 1)
+        let power = modulus.leave_mont(&modulus.pow(&three, &1000u16.to_be_bytes()));
+        assert_eq!(to_be_bytes(&power, 16), 0x741dd636fced9af3211f927e6e87a273u128.to_be_bytes());
+    }
+}
@@ -0,0 +1,311 @@
+//! Verificação ECDSA em P-256 e P-384 (FIPS 186-4)
+//!
+//! Necessária para falar com a web PKI: a maioria dos servidores e CAs usa
+//! essas curvas. Coordenadas jacobianas na forma de Montgomery.
+
+use super::bigint::{self, compare, from_be_bytes, is_zero, Limbs, Modulus};
+use std::cmp::Ordering;
+
+/// Curva NIST com `a = -3`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    P256,
+    P384,
+}
+
+struct Params {
+    p: &'static str,
+    b: &'static str,
+    gx: &'static str,
+    gy: &'static str,
+    n: &'static str,
+}
+
+impl Curve {
+    /// Tamanho de uma coordenada em bytes
+    pub fn field_len(self) -> usize {
+        match self {
+            Curve::P256 => 32,
+            Curve::P384 => 48,
+        }
+    }
+
+    /// Curva pelo tamanho do ponto não comprimido (`04 || x || y`)
+    pub fn from_point_len(len: usize) -> Option<Curve> {
+        match len {
+            65 => Some(Curve::P256),
+            97 => Some(Curve::P384),
+            _ => None,
+        }
+    }
+
+    fn params(self) -> Params {
+        match self {
+            Curve::P256 => Params {
+                p: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+                b: "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+                gx: "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                gy: "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+                n: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+            },
+            Curve::P384 => Params {
+                p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+                b: "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+                gx: "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+                gy: "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+                n: "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+            },
+        }
+    }
+}
+
+fn decode_hex(text: &str) -> Vec<u8> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).expect("valid curve constant"))
+        .collect()
+}
+
+/// Ponto jacobiano `(X/Z², Y/Z³)`; `Z = 0` é o ponto no infinito
+#[derive(Clone)]
+struct Point {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+}
+
+struct Group {
+    field: Modulus,
+    /// `b` na forma de Montgomery
+    b: Limbs,
+    generator: Point,
+    order: Modulus,
+}
+
+impl Group {
+    fn new(curve: Curve) -> Self {
+        let params = curve.params();
+        let field = Modulus::from_be_bytes(&decode_hex(params.p));
+        let len = field.len();
+        let mont = |hex: &str| field.to_mont(&from_be_bytes(&decode_hex(hex), len).expect("curve constant"));
+        let generator = Point {
+            x: mont(params.gx),
+            y: mont(params.gy),
+            z: field.one(),
+        };
+        Self {
+            b: mont(params.b),
+            generator,
+            order: Modulus::from_be_bytes(&decode_hex(params.n)),
+            field,
+        }
+    }
+
+    fn infinity(&self) -> Point {
+        let zero = vec![0; self.field.len()];
+        Point {
+            x: self.field.one(),
+            y: self.field.one(),
+            z: zero,
+        }
+    }
+
+    /// Ponto afim a partir de `04 || x || y`, conferindo a equação da curva
+    fn decode_point(&self, curve: Curve, encoded: &[u8]) -> Option<Point> {
+        let n = curve.field_len();
+        if encoded.len() != 1 + 2 * n || encoded[0] != 0x04 {
+            return None;
+        }
+        let len = self.field.len();
+        let x = from_be_bytes(&encoded[1..1 + n], len)?;
+        let y = from_be_bytes(&encoded[1 + n..], len)?;
+        if compare(&x, &self.field.m) != Ordering::Less || compare(&y, &self.field.m) != Ordering::Less {
+            return None;
+        }
+
+        let f = &self.field;
+        let (x, y) = (f.to_mont(&x), f.to_mont(&y));
+        // y² = x³ - 3x + b
+        let x3 = f.mul(&f.square(&x), &x);
+        let three_x = f.add(&f.add(&x, &x), &x);
+        let rhs = f.add(&f.sub(&x3, &three_x), &self.b);
+        (f.square(&y) == rhs).then(|| Point {
+            x,
+            y,
+            z: f.one(),
+        })
+    }
+
+    fn double(&self, p: &Point) -> Point {
+        let f = &self.field;
+        if is_zero(&p.z) || is_zero(&p.y) {
+            return self.infinity();
+        }
+        // dbl-2001-b (a = -3)
+        let delta = f.square(&p.z);
+        let gamma = f.square(&p.y);
+        let beta = f.mul(&p.x, &gamma);
+        let t = f.mul(&f.sub(&p.x, &delta), &f.add(&p.x, &delta));
+        let alpha = f.add(&f.add(&t, &t), &t);
+        let beta4 = f.add(&f.add(&beta, &beta), &f.add(&beta, &beta));
+        let x3 = f.sub(&f.square(&alpha), &f.add(&beta4, &beta4));
+        let yz = f.add(&p.y, &p.z);
+        let z3 = f.sub(&f.sub(&f.square(&yz), &gamma), &delta);
+        let gamma2 = f.square(&gamma);
+        let gamma8 = {
+            let g2 = f.add(&gamma2, &gamma2);
+            let g4 = f.add(&g2, &g2);
+            f.add(&g4, &g4)
+        };
+        let y3 = f.sub(&f.mul(&alpha, &f.sub(&beta4, &x3)), &gamma8);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    fn add(&self, p: &Point, q: &Point) -> Point {
+        let f = &self.field;
+        if is_zero(&p.z) {
+            return q.clone();
+        }
+        if is_zero(&q.z) {
+            return p.clone();
+        }
+        // add-2007-bl
+        let z1z1 = f.square(&p.z);
+        let z2z2 = f.square(&q.z);
+        let u1 = f.mul(&p.x, &z2z2);
+        let u2 = f.mul(&q.x, &z1z1);
+        let s1 = f.mul(&f.mul(&p.y, &q.z), &z2z2);
+        let s2 = f.mul(&f.mul(&q.y, &p.z), &z1z1);
+        let h = f.sub(&u2, &u1);
+        let r = {
+            let d = f.sub(&s2, &s1);
+            f.add(&d, &d)
+        };
+        if is_zero(&h) {
+            return if is_zero(&r) { self.double(p) } else { self.infinity() };
+        }
+        let i = {
+            let h2 = f.add(&h, &h);
+            f.square(&h2)
+        };
+        let j = f.mul(&h, &i);
+        let v = f.mul(&u1, &i);
+        let x3 = f.sub(&f.sub(&f.square(&r), &j), &f.add(&v, &v));
+        let s1j = f.mul(&s1, &j);
+        let y3 = f.sub(&f.mul(&r, &f.sub(&v, &x3)), &f.add(&s1j, &s1j));
+        let zz = f.add(&p.z, &q.z);
+        let z3 = f.mul(&f.sub(&f.sub(&f.square(&zz), &z1z1), &z2z2), &h);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    /// `a·P + b·Q` (truque de Shamir); escalares em limbs normais
+    fn double_scalar_mul(&self, a: &[u32], p: &Point, b: &[u32], q: &Point) -> Point {
+        let pq = self.add(p, q);
+        let mut acc = self.infinity();
+        for bit in (0..a.len() * 32).rev() {
+            acc = self.double(&acc);
+            let (ai, bi) = ((a[bit / 32] >> (bit % 32)) & 1, (b[bit / 32] >> (bit % 32)) & 1);
+            match (ai, bi) {
+                (1, 1) => acc = self.add(&acc, &pq),
+                (1, 0) => acc = self.add(&acc, p),
+                (0, 1) => acc = self.add(&acc, q),
+                _ => {}
+            }
+        }
+        acc
+    }
+
+    /// Coordenada x afim, fora da forma de Montgomery
+    fn affine_x(&self, p: &Point) -> Limbs {
+        let f = &self.field;
+        let z_inv = f.invert(&p.z);
+        f.leave_mont(&f.mul(&p.x, &f.square(&z_inv)))
+    }
+}
+
+/// Verifica `(r, s)` sobre o digest `digest` para a chave pública `04 || x || y`
+pub fn verify(curve: Curve, public_key: &[u8], digest: &[u8], r: &[u8], s: &[u8]) -> bool {
+    let group = Group::new(curve);
+    let Some(q) = group.decode_point(curve, public_key) else {
+        return false;
+    };
+    let order = &group.order;
+    let len = order.len();
+    let (Some(r), Some(s)) = (from_be_bytes(r, len), from_be_bytes(s, len)) else {
+        return false;
+    };
+    for value in [&r, &s] {
+        if is_zero(value) || compare(value, &order.m) != Ordering::Less {
+            return false;
+        }
+    }
+
+    // Digest truncado aos bits mais à esquerda do tamanho da ordem
+    let bits = order.bits();
+    let digest = &digest[..digest.len().min(bits.div_ceil(8))];
+    let mut e = from_be_bytes(digest, len).expect("digest truncated to order size");
+    let excess = (digest.len() * 8).saturating_sub(bits);
+    if excess > 0 {
+        let mut carry = 0;
+        for limb in e.iter_mut().rev() {
+            let next = *limb << (32 - excess);
+            *limb = (*limb >> excess) | carry;
+            carry = next;
+        }
+    }
+    let e = order.reduce_once(e);
+
+    let w = order.invert(&order.to_mont(&s));
+    let u1 = order.leave_mont(&order.mul(&order.to_mont(&e), &w));
+    let u2 = order.leave_mont(&order.mul(&order.to_mont(&r), &w));
+
+    let point = group.double_scalar_mul(&u1, &group.generator, &u2, &q);
+    if is_zero(&point.z) {
+        return false;
+    }
+    let x = group.affine_x(&point);
+    // x mod n (p > n, então basta uma subtração)
+    let x = order.reduce_once(bigint::from_be_bytes(&bigint::to_be_bytes(&x, len * 4), len).expect("same size"));
+    x == r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher_suite::sha2::HashAlgorithm;
+    use crate::test_util::hex;
+
+    fn check(curve: Curve, hash: HashAlgorithm, public_key: &str, r: &str, s: &str) {
+        let (public_key, r, s) = (hex(public_key), hex(r), hex(s));
+        let digest = hash.digest(b"avila-tls");
+        assert!(verify(curve, &public_key, &digest, &r, &s));
+        assert!(!verify(curve, &public_key, &hash.digest(b"avila-tlS"), &r, &s));
+        assert!(!verify(curve, &public_key, &digest, &s, &r));
+
+        // Ponto fora da curva
+        let mut off_curve = public_key.clone();
+        off_curve[10] ^= 1;
+        assert!(!verify(curve, &off_curve, &digest, &r, &s));
+    }
+
+    #[test]
+    fn test_verify_signatures() {
+        // Gerados com `cryptography` (Python) sobre "avila-tls"
+        check(
+            Curve::P256,
+            HashAlgorithm::Sha256,
+            "049fad84aeae08bbef7f010014d82cef6a09de2b0cf871b5ce0c4f1d13a59a5934\
+             07cb45769f1070e2c2470fe5b1bfe63133c0b0cdc64ea4bf3791a8ec2a07fd4f",
+            "23f5df4f1dcbdc2eb7bd63107baf2a27062f825a94548993aedd679e11b26d1b",
+            "e870d7d3752995fdb6370aaf430dcd41b8f4eccb48f23318689043432394f618",
+        );
+        check(
+            Curve::P384,
+            HashAlgorithm::Sha384,
+            "04fda859f82bfaaa0596e6bbe14024460e3c2e19c03abb26bd9a93a5a461ccd76b5f4f718b51658e3914225cd060531523\
+             015fd8ee541c0f14551a5629f9b5c17803cecf3be3d33e29d6167eb8bab4d7dcbae0876ac77cb467fd20edb046f6ae0d",
+            "98097b194e2dfb173efd5de237d00c20ca12a845d47c4b42f797f1f874ba148391424b8716e6dd5bb978ed6740fad2c5",
+            "51bab3e2db109c517d45765ee7cc4bcd058c601e2ff6269e780b106dcdf60ea17d2ef29b5c3f466506d63ae800dfc4ee",
+        );
+    }
+}
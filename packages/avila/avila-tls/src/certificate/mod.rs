@@ -0,0 +1,192 @@
+//! Verificação do certificado do servidor
+//!
+//! O parsing X.509, a cadeia e o hostname vêm de `avila-tls-config`; aqui
+//! ficam as assinaturas (RSA, ECDSA P-256/P-384) que ela delega via
+//! [`SignatureVerifier`].
+
+pub(crate) mod bigint;
+pub mod ecdsa;
+pub mod rsa;
+
+use crate::cipher_suite::sha2::HashAlgorithm;
+use avila_tls_config::{validate_chain, verify_hostname, Certificate, SignatureVerifier, TrustStore};
+use ecdsa::Curve;
+use rsa::RsaPublicKey;
+
+const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+
+/// Esquemas de assinatura aceitos no `CertificateVerify` (RFC 8446, 4.2.3)
+pub(crate) const CERTIFICATE_VERIFY_SCHEMES: [u16; 5] = [0x0403, 0x0503, 0x0804, 0x0805, 0x0806];
+/// Esquemas anunciados: os do `CertificateVerify` mais PKCS#1, ainda comum em cadeias
+pub(crate) const SIGNATURE_SCHEMES: [u16; 8] = [0x0403, 0x0503, 0x0804, 0x0805, 0x0806, 0x0401, 0x0501, 0x0601];
+
+/// Assinaturas de certificados da web PKI
+pub struct WebPkiVerifier;
+
+impl SignatureVerifier for WebPkiVerifier {
+    fn verify(&self, algorithm: &str, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        let (kind, hash) = match algorithm {
+            "1.2.840.113549.1.1.11" => (KeyType::Rsa, HashAlgorithm::Sha256),
+            "1.2.840.113549.1.1.12" => (KeyType::Rsa, HashAlgorithm::Sha384),
+            "1.2.840.113549.1.1.13" => (KeyType::Rsa, HashAlgorithm::Sha512),
+            "1.2.840.10045.4.3.2" => (KeyType::Ecdsa, HashAlgorithm::Sha256),
+            "1.2.840.10045.4.3.3" => (KeyType::Ecdsa, HashAlgorithm::Sha384),
+            "1.2.840.10045.4.3.4" => (KeyType::Ecdsa, HashAlgorithm::Sha512),
+            _ => return false,
+        };
+        let digest = hash.digest(message);
+        match kind {
+            KeyType::Rsa => RsaPublicKey::from_der(public_key).is_some_and(|key| key.verify_pkcs1(hash, &digest, signature)),
+            KeyType::Ecdsa => verify_ecdsa_der(None, public_key, &digest, signature),
+        }
+    }
+}
+
+enum KeyType {
+    Rsa,
+    Ecdsa,
+}
+
+/// Valida a cadeia até `roots` e o nome do servidor
+pub fn verify_server_certificate(
+    chain: &[Certificate],
+    roots: &TrustStore,
+    server_name: &str,
+    now: i64,
+) -> avila_tls_config::Result<()> {
+    validate_chain(chain, roots, now, &WebPkiVerifier)?;
+    verify_hostname(&chain[0], server_name)
+}
+
+/// Confere a assinatura do `CertificateVerify` com a chave da folha
+pub(crate) fn verify_signature_scheme(scheme: u16, leaf: &Certificate, message: &[u8], signature: &[u8]) -> bool {
+    let key = leaf.public_key.as_slice();
+    match (scheme, leaf.public_key_algorithm.as_str()) {
+        (0x0403, OID_EC_PUBLIC_KEY) => {
+            verify_ecdsa_der(Some(Curve::P256), key, &HashAlgorithm::Sha256.digest(message), signature)
+        }
+        (0x0503, OID_EC_PUBLIC_KEY) => {
+            verify_ecdsa_der(Some(Curve::P384), key, &HashAlgorithm::Sha384.digest(message), signature)
+        }
+        (0x0804..=0x0806, OID_RSA_ENCRYPTION) => {
+            let hash = match scheme {
+                0x0804 => HashAlgorithm::Sha256,
+                0x0805 => HashAlgorithm::Sha384,
+                _ => HashAlgorithm::Sha512,
+            };
+            RsaPublicKey::from_der(key).is_some_and(|rsa| rsa.verify_pss(hash, &hash.digest(message), signature))
+        }
+        _ => false,
+    }
+}
+
+/// ECDSA com assinatura DER (`Ecdsa-Sig-Value`); `curve` exige a curva da chave
+fn verify_ecdsa_der(curve: Option<Curve>, public_key: &[u8], digest: &[u8], signature: &[u8]) -> bool {
+    let Some(key_curve) = Curve::from_point_len(public_key.len()) else {
+        return false;
+    };
+    if curve.is_some_and(|expected| expected != key_curve) {
+        return false;
+    }
+    let Some([r, s]) = der_integers(signature).and_then(|ints| <[&[u8]; 2]>::try_from(ints).ok()) else {
+        return false;
+    };
+    ecdsa::verify(key_curve, public_key, digest, r, s)
+}
+
+/// INTEGERs (sem sinal) de um `SEQUENCE` DER
+pub(crate) fn der_integers(der: &[u8]) -> Option<Vec<&[u8]>> {
+    let (tag, body, rest) = der_element(der)?;
+    if tag != 0x30 || !rest.is_empty() {
+        return None;
+    }
+    let mut integers = Vec::new();
+    let mut input = body;
+    while !input.is_empty() {
+        let (tag, value, rest) = der_element(input)?;
+        // Inteiros negativos não aparecem em chaves nem assinaturas válidas
+        if tag != 0x02 || value.is_empty() || value[0] & 0x80 != 0 {
+            return None;
+        }
+        integers.push(value);
+        input = rest;
+    }
+    Some(integers)
+}
+
+/// `(tag, valor, restante)` de um elemento DER
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || input.len() < count {
+            return None;
+        }
+        let len = input[..count].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        input = &input[count..];
+        len
+    };
+    (input.len() >= len).then(|| (tag, &input[..len], &input[len..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CA P-256 e folha `api.vizzio.local` (mesmos fixtures de `avila-tls-config`)
+    const CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBnTCCAUKgAwIBAgIULFUP/wYsYi5fTMcKj1WFnZ3PhJIwCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOVml6emlvIFRlc3QgQ0EwIBcNMjYxMDE3MDQxMDUwWhgPMjEy
+NjA5MjMwNDEwNTBaMBkxFzAVBgNVBAMMDlZpenppbyBUZXN0IENBMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEG/ckXdYpqxdEma2qWcwlApLyNVGnBCDzZDLYXxcP
+mQor93/WtEMlie/2xctLN0ha3V5xzsXrLfM3vVTm0rLbS6NmMGQwHQYDVR0OBBYE
+FAjNTyTbsmkDrCevegfMQxxYnUO8MB8GA1UdIwQYMBaAFAjNTyTbsmkDrCevegfM
+QxxYnUO8MBIGA1UdEwEB/wQIMAYBAf8CAQAwDgYDVR0PAQH/BAQDAgIEMAoGCCqG
+SM49BAMCA0kAMEYCIQCDi5xy8v0PZh73BpYZV+Sje/2eUoYU9mzT7wPP/Vo7LwIh
+AO9UsMJrJNLZNIFwHI7nnym9Nv4hyhoakLkNYdPQrKBt
+-----END CERTIFICATE-----
+";
+
+    const LEAF_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBwTCCAWegAwIBAgIUaAAVBPn6VRt+LUwELUVBOrdKgQswCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOVml6emlvIFRlc3QgQ0EwIBcNMjYxMDE3MDQxMDUwWhgPMjEy
+NjA5MjMwNDEwNTBaMBsxGTAXBgNVBAMMEGFwaS52aXp6aW8ubG9jYWwwWTATBgcq
+hkjOPQIBBggqhkjOPQMBBwNCAAT1MOGkTtnc0zv/SF3CXuH3XT3gXvUD+J0FXff7
+7VHF40SKdI9VuHb0MWG/Zs6dgcmVd1vkDXALGNVXZkFxv41po4GIMIGFMDgGA1Ud
+EQQxMC+CEGFwaS52aXp6aW8ubG9jYWyCFSoubW9kZWxzLnZpenppby5sb2NhbIcE
+CgAABzAJBgNVHRMEAjAAMB0GA1UdDgQWBBSyNLD/2rEQsr0mF583JV4pPUsgvjAf
+BgNVHSMEGDAWgBQIzU8k27JpA6wnr3oHzEMcWJ1DvDAKBggqhkjOPQQDAgNIADBF
+AiB+ZAjBVunPvwU8z5+lDcshcLtKJrFh65YTVsj6uhqyigIhAMklfjoKjtweT4uS
+Z84T/AnmQ3pkP1dcgdoODkjhHRYP
+-----END CERTIFICATE-----
+";
+
+    const NOW: i64 = 1_800_000_000;
+
+    #[test]
+    fn test_verify_server_certificate() {
+        let roots = TrustStore::from_pem(CA_PEM).unwrap();
+        let leaf = Certificate::from_pem(LEAF_PEM).unwrap();
+
+        assert!(verify_server_certificate(&leaf, &roots, "api.vizzio.local", NOW).is_ok());
+        assert!(verify_server_certificate(&leaf, &roots, "ifc.models.vizzio.local", NOW).is_ok());
+        assert!(verify_server_certificate(&leaf, &roots, "evil.example", NOW).is_err());
+
+        // Assinatura adulterada
+        let mut forged = leaf.clone();
+        let last = forged[0].signature.len() - 3;
+        forged[0].signature[last] ^= 1;
+        assert!(verify_server_certificate(&forged, &roots, "api.vizzio.local", NOW).is_err());
+    }
+
+    #[test]
+    fn test_der_integers() {
+        assert_eq!(der_integers(&[0x30, 0x06, 0x02, 0x01, 0x05, 0x02, 0x01, 0x07]), Some(vec![&[5u8][..], &[7u8][..]]));
+        assert_eq!(der_integers(&[0x30, 0x03, 0x02, 0x01, 0x85]), None);
+        assert_eq!(der_integers(&[0x30, 0x05, 0x02, 0x01]), None);
+    }
+}
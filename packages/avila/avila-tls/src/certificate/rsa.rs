@@ -0,0 +1,195 @@
+//! Verificação RSA: PKCS#1 v1.5 e PSS (RFC 8017)
+//!
+//! RSA só aparece aqui para validar certificados e o `CertificateVerify` de
+//! servidores existentes; nada é assinado com RSA.
+
+use super::bigint::{compare, from_be_bytes, to_be_bytes, Modulus};
+use super::der_integers;
+use crate::cipher_suite::ct_eq;
+use crate::cipher_suite::sha2::HashAlgorithm;
+use std::cmp::Ordering;
+
+/// Menor módulo aceito
+const MIN_BITS: usize = 2048;
+const MAX_BITS: usize = 8192;
+
+/// Chave pública RSA (`RSAPublicKey`)
+pub struct RsaPublicKey {
+    modulus: Modulus,
+    exponent: Vec<u8>,
+    /// Tamanho do módulo em bytes
+    len: usize,
+}
+
+impl RsaPublicKey {
+    /// DER `SEQUENCE { modulus INTEGER, publicExponent INTEGER }`
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        let [n, e] = der_integers(der)?.try_into().ok()?;
+        let modulus = Modulus::from_be_bytes(n);
+        let bits = modulus.bits();
+        if !(MIN_BITS..=MAX_BITS).contains(&bits) || n.last()? & 1 == 0 || e.is_empty() {
+            return None;
+        }
+        Some(Self {
+            modulus,
+            exponent: e.to_vec(),
+            len: bits.div_ceil(8),
+        })
+    }
+
+    /// `signature^e mod n` como bloco codificado de `len` bytes
+    fn encoded_message(&self, signature: &[u8]) -> Option<Vec<u8>> {
+        if signature.len() != self.len {
+            return None;
+        }
+        let m = &self.modulus;
+        let s = from_be_bytes(signature, m.len())?;
+        if compare(&s, &m.m) != Ordering::Less {
+            return None;
+        }
+        let em = m.leave_mont(&m.pow(&m.to_mont(&s), &self.exponent));
+        Some(to_be_bytes(&em, self.len))
+    }
+
+    /// RSASSA-PKCS1-v1_5 sobre `digest`
+    pub fn verify_pkcs1(&self, hash: HashAlgorithm, digest: &[u8], signature: &[u8]) -> bool {
+        let Some(em) = self.encoded_message(signature) else {
+            return false;
+        };
+        let prefix = digest_info_prefix(hash);
+        let t_len = prefix.len() + digest.len();
+        if self.len < t_len + 11 {
+            return false;
+        }
+
+        let mut expected = vec![0xFFu8; self.len];
+        expected[0] = 0x00;
+        expected[1] = 0x01;
+        expected[self.len - t_len - 1] = 0x00;
+        expected[self.len - t_len..self.len - digest.len()].copy_from_slice(prefix);
+        expected[self.len - digest.len()..].copy_from_slice(digest);
+        ct_eq(&em, &expected)
+    }
+
+    /// RSASSA-PSS com MGF1 do mesmo hash e salt do tamanho do hash
+    pub fn verify_pss(&self, hash: HashAlgorithm, digest: &[u8], signature: &[u8]) -> bool {
+        let Some(em) = self.encoded_message(signature) else {
+            return false;
+        };
+        let em_bits = self.modulus.bits() - 1;
+        // Com emBits múltiplo de 8, EM tem um byte a menos que o módulo
+        let em = if em_bits.is_multiple_of(8) {
+            if em[0] != 0 {
+                return false;
+            }
+            &em[1..]
+        } else {
+            &em[..]
+        };
+
+        let h_len = hash.output_len();
+        let s_len = h_len;
+        if digest.len() != h_len || em.len() < h_len + s_len + 2 || em[em.len() - 1] != 0xBC {
+            return false;
+        }
+
+        let (masked_db, rest) = em.split_at(em.len() - h_len - 1);
+        let h = &rest[..h_len];
+        let top_bits = 8 * em.len() - em_bits;
+        if top_bits > 0 && masked_db[0] >> (8 - top_bits) != 0 {
+            return false;
+        }
+
+        let mask = mgf1(hash, h, masked_db.len());
+        let mut db: Vec<u8> = masked_db.iter().zip(&mask).map(|(a, b)| a ^ b).collect();
+        db[0] &= 0xFF >> top_bits;
+
+        let padding = db.len() - s_len - 1;
+        if db[..padding].iter().any(|&b| b != 0) || db[padding] != 0x01 {
+            return false;
+        }
+        let salt = &db[padding + 1..];
+
+        let mut hasher = hash.hasher();
+        hasher.update(&[0u8; 8]);
+        hasher.update(digest);
+        hasher.update(salt);
+        ct_eq(&hasher.finish(), h)
+    }
+}
+
+fn mgf1(hash: HashAlgorithm, seed: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + hash.output_len());
+    let mut counter = 0u32;
+    while out.len() < len {
+        let mut hasher = hash.hasher();
+        hasher.update(seed);
+        hasher.update(&counter.to_be_bytes());
+        out.extend_from_slice(&hasher.finish());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// `DigestInfo` DER sem o digest (RFC 8017, nota 1 da seção 9.2)
+fn digest_info_prefix(hash: HashAlgorithm) -> &'static [u8] {
+    match hash {
+        HashAlgorithm::Sha256 => &[
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04,
+            0x20,
+        ],
+        HashAlgorithm::Sha384 => &[
+            0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05, 0x00, 0x04,
+            0x30,
+        ],
+        HashAlgorithm::Sha512 => &[
+            0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04,
+            0x40,
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    // Chave RSA-2048 e assinaturas sobre "avila-tls" geradas com `cryptography` (Python)
+    const PUBLIC_KEY: &str = "3082010a0282010100885257cfe730d7d6508ce3778e63eb3054df41afb2e3318040e55f8928b7b2facd620e22662964\
+     6057fcf32644c9666b3a43a7dd391c4eda375880dff359b703beaaa230e4487a264bd5b57e87bda09e842e4443f37780\
+     de6910149f9b4c73f68e69ab54a11902074b031d78c381e10d98a4733d6db8a03ad17d71bad80ffb1e147885315c5c84\
+     0e1aab50c6b007291c9dbdf36d5d071842214f29e0270f92d1a84facac3a56531b7fe601cd67121ec3207cb1ccc58c21\
+     ec0ce5cc9c764947eb4bc345cf09b449e053d4e22f60dc886665d08ee77c0ff393eca3fd919743f341d531d5f2d0d0c6\
+     a5aa323e4bfce8b316650ff78c26f537127a2dfae61102670d0203010001";
+    const PKCS1_SHA256: &str = "69912d20b163ae748e44a7f6994c7574e93a20554e9079c2d69423669871dcb496d3e72a84d0748e484df408295cb77f\
+     33251e3d5a18c5ec7bc36bcaa7fa6272d259fbad6c9d5ae5f7cb37c3a81c36fd8b0643f5386d8d0fec5e5f7e6d205880\
+     ef964487ab55ac1e33792a4d9991c5c895fd9347126a74a361811936307dfd0b3d36cf0e44baea6ecdedd408f0bae910\
+     797b1e96945341fcdf254c5cd64aa36162cf533cdb3bd992652c40530243b2fba5b0bf91e13e6781c465e0b112541d51\
+     63cf66479c20530e0bfd131cee3fd7f645f4ab932e1dc6c26caae2cc9000d3b2213c2fc71f319a13b8c7ba242d4765fe\
+     cd0d1828d249c219efe4a2fc14421d5c";
+    const PSS_SHA256: &str = "5a05e3794cbe2f7e110749e656e20b18b932d4c6564710a4f7c94485216cc3c471ccfc4b4758992ea10fa440e45f5aa8\
+     83756d0887ae25a1544d98b239903756c730b25c042050bc1eb316b2e9ec41436485aab71d9d77e1e93f3d8be97a781d\
+     b0268592adb5a04719ce1e0b4b23a88ba12e5a81dd4aa66f5c40b34f00117d81409566327058f0a9e6708d0025507341\
+     8a81ab9b8446b3f9dfd63449c2a53333c3352d36c1f3012682bad636af3da4d2d1524738cd870c555d6e1e8aabf03c8c\
+     050554d368043246bc6ef85ff5e412da77ffd2f865ed3121ad1a14d7fa29e0a7c13c501de6e67e52e6340fa5d9485cc7\
+     bd91c00dbe7146885aa6b838ca36f157";
+
+    #[test]
+    fn test_verify_signatures() {
+        let key = RsaPublicKey::from_der(&hex(PUBLIC_KEY)).unwrap();
+        let digest = HashAlgorithm::Sha256.digest(b"avila-tls");
+        let other = HashAlgorithm::Sha256.digest(b"avila-tlS");
+
+        let pkcs1 = hex(PKCS1_SHA256);
+        assert!(key.verify_pkcs1(HashAlgorithm::Sha256, &digest, &pkcs1));
+        assert!(!key.verify_pkcs1(HashAlgorithm::Sha256, &other, &pkcs1));
+        assert!(!key.verify_pss(HashAlgorithm::Sha256, &digest, &pkcs1));
+
+        let pss = hex(PSS_SHA256);
+        assert!(key.verify_pss(HashAlgorithm::Sha256, &digest, &pss));
+        assert!(!key.verify_pss(HashAlgorithm::Sha256, &other, &pss));
+        assert!(!key.verify_pkcs1(HashAlgorithm::Sha256, &digest, &pss));
+        assert!(!key.verify_pss(HashAlgorithm::Sha256, &digest, &pss[1..]));
+    }
+}
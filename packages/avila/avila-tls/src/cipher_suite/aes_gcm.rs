@@ -0,0 +1,108 @@
+//! AES-128/256-GCM (FIPS 197, NIST SP 800-38D)
+//!
+//! Camada fina sobre o [`AesGcm`](avila_crypto::cipher::aes_gcm::AesGcm) do
+//! `avila-crypto`, que tem o AES e o GHASH com tabela de 4 bits. AES em
+//! software com S-box em tabela: não é constant-time em relação ao cache,
+//! por isso o ClientHello prefere ChaCha20-Poly1305.
+//!
+//! Além de `seal`/`open` (tag no fim do buffer), `seal_detached` e
+//! `open_detached` mantêm a tag separada e aceitam a AAD em pedaços, sem
+//! concatenar.
+
+use avila_crypto::cipher::aes_gcm::AesGcm as Cipher;
+
+/// Em `Box`: as chaves de rodada ocupam 240 bytes, que o [`Aead`](super::Aead)
+/// carregaria em todas as variantes
+pub struct AesGcm(Box<Cipher>);
+
+impl AesGcm {
+    /// Chave de 16 (AES-128) ou 32 bytes (AES-256)
+    pub fn new(key: &[u8]) -> Self {
+        match <&[u8; 16]>::try_from(key) {
+            Ok(key) => Self(Box::new(Cipher::new_128(key))),
+            Err(_) => Self(Box::new(Cipher::new(key.try_into().expect("AES key must be 16 or 32 bytes")))),
+        }
+    }
+
+    /// Cifra `data` no lugar e acrescenta a tag de 16 bytes
+    pub fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) {
+        let tag = self.seal_detached(nonce, &[aad], data);
+        data.extend_from_slice(&tag);
+    }
+
+    /// Confere a tag, decifra no lugar e remove a tag
+    pub fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool {
+        let Some(split) = data.len().checked_sub(16) else {
+            return false;
+        };
+        let tag: [u8; 16] = data[split..].try_into().expect("16 bytes");
+        if !self.open_detached(nonce, &[aad], &mut data[..split], &tag) {
+            return false;
+        }
+        data.truncate(split);
+        true
+    }
+
+    /// Cifra `data` no lugar e devolve a tag; a AAD é a concatenação de `aad`
+    pub fn seal_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16] {
+        self.0.encrypt_detached(nonce, aad, data)
+    }
+
+    /// Confere `tag` e só então decifra `data` no lugar
+    pub fn open_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8], tag: &[u8; 16]) -> bool {
+        self.0.decrypt_detached(nonce, aad, data, tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_gcm_round_trip() {
+        let cipher = AesGcm::new(&hex("feffe9928665731c6d6a8f9467308308"));
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        );
+
+        let mut data = plaintext.clone();
+        cipher.seal(&nonce, &aad, &mut data);
+        // NIST GCM, caso de teste 4
+        assert_eq!(&data[..16], &hex("42831ec2217774244b7221b784d0d49c")[..]);
+        assert_eq!(&data[data.len() - 16..], &hex("5bc94fbc3221a5db94fae95ae7121a47")[..]);
+
+        let mut tampered = data.clone();
+        tampered[0] ^= 0x80;
+        assert!(!cipher.open(&nonce, &aad, &mut tampered));
+        assert!(cipher.open(&nonce, &aad, &mut data));
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_gcm_detached() {
+        let cipher = AesGcm::new(&hex("feffe9928665731c6d6a8f9467308308"));
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = b"tag no cabecalho do pacote".to_vec();
+
+        let mut attached = plaintext.clone();
+        cipher.seal(&nonce, &aad, &mut attached);
+
+        // AAD em pedaços que não caem na fronteira de bloco
+        let parts: [&[u8]; 3] = [&aad[..3], &[], &aad[3..]];
+        let mut data = plaintext.clone();
+        let tag = cipher.seal_detached(&nonce, &parts, &mut data);
+        assert_eq!([&data[..], &tag[..]].concat(), attached);
+
+        let mut wrong = tag;
+        wrong[15] ^= 1;
+        let ciphertext = data.clone();
+        assert!(!cipher.open_detached(&nonce, &parts, &mut data, &wrong));
+        assert_eq!(data, ciphertext);
+        assert!(cipher.open_detached(&nonce, &[&aad], &mut data, &tag));
+        assert_eq!(data, plaintext);
+    }
+}
@@ -0,0 +1,82 @@
+//! ChaCha20-Poly1305 (RFC 8439)
+//!
+//! Camada fina sobre o
+//! [`ChaCha20Poly1305`](avila_crypto::cipher::chacha20::ChaCha20Poly1305) do
+//! `avila-crypto`, com a tag anexada (`seal`/`open`) ou separada
+//! (`seal_detached`/`open_detached`, AAD em pedaços).
+
+use avila_crypto::cipher::chacha20::ChaCha20Poly1305 as Cipher;
+
+pub struct ChaCha20Poly1305(Cipher);
+
+impl ChaCha20Poly1305 {
+    pub const KEY_LEN: usize = 32;
+
+    pub fn new(key: &[u8]) -> Self {
+        Self(Cipher::new(key.try_into().expect("ChaCha20 key must be 32 bytes")))
+    }
+
+    /// Cifra `data` no lugar e acrescenta a tag de 16 bytes
+    pub fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) {
+        let tag = self.seal_detached(nonce, &[aad], data);
+        data.extend_from_slice(&tag);
+    }
+
+    /// Confere a tag, decifra no lugar e remove a tag
+    pub fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool {
+        let Some(split) = data.len().checked_sub(16) else {
+            return false;
+        };
+        let tag: [u8; 16] = data[split..].try_into().expect("16 bytes");
+        if !self.open_detached(nonce, &[aad], &mut data[..split], &tag) {
+            return false;
+        }
+        data.truncate(split);
+        true
+    }
+
+    /// Cifra `data` no lugar e devolve a tag; a AAD é a concatenação de `aad`
+    pub fn seal_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16] {
+        self.0.encrypt_detached(nonce, aad, data)
+    }
+
+    /// Confere `tag` e só então decifra `data` no lugar
+    pub fn open_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8], tag: &[u8; 16]) -> bool {
+        self.0.decrypt_detached(nonce, aad, data, tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc8439_aead_vector() {
+        let key = hex("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce: [u8; 12] = hex("070000004041424344454647").try_into().unwrap();
+        let aad = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+        let cipher = ChaCha20Poly1305::new(&key);
+        let mut data = plaintext.to_vec();
+        cipher.seal(&nonce, &aad, &mut data);
+        assert_eq!(&data[..16], &hex("d31a8d34648e60db7b86afbc53ef7ec2")[..]);
+        assert_eq!(&data[data.len() - 16..], &hex("1ae10b594f09e26a7e902ecbd0600691")[..]);
+
+        let mut tampered = data.clone();
+        tampered[3] ^= 1;
+        assert!(!cipher.open(&nonce, &aad, &mut tampered));
+        assert!(cipher.open(&nonce, &aad, &mut data));
+        assert_eq!(data, plaintext);
+
+        // Tag separada e AAD em pedaços dão o mesmo resultado
+        let mut detached = plaintext.to_vec();
+        let parts: [&[u8]; 2] = [&aad[..5], &aad[5..]];
+        let tag = cipher.seal_detached(&nonce, &parts, &mut detached);
+        assert_eq!(tag[..], hex("1ae10b594f09e26a7e902ecbd0600691")[..]);
+        assert!(!cipher.open_detached(&nonce, &parts[..1], &mut detached, &tag));
+        assert!(cipher.open_detached(&nonce, &parts, &mut detached, &tag));
+        assert_eq!(detached, plaintext);
+    }
+}
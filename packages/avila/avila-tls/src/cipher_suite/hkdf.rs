@@ -0,0 +1,88 @@
+//! HMAC (RFC 2104) e HKDF (RFC 5869), com o `HKDF-Expand-Label` do TLS 1.3
+//!
+//! O HMAC é o `Hmac` do `avila-hash`; o HKDF fica aqui porque só o TLS o usa.
+
+use super::sha2::HashAlgorithm;
+use avila_hash::{Hmac, Sha256, Sha384, Sha512};
+
+/// HMAC de `data` com `key`
+pub fn hmac(hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac_parts(hash, key, &[data])
+}
+
+/// HMAC da concatenação de `parts`, sem copiá-las
+pub fn hmac_parts(hash: HashAlgorithm, key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    fn mac<H: avila_hash::Hasher>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac = Hmac::<H>::new(key);
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().as_ref().to_vec()
+    }
+
+    match hash {
+        HashAlgorithm::Sha256 => mac::<Sha256>(key, parts),
+        HashAlgorithm::Sha384 => mac::<Sha384>(key, parts),
+        HashAlgorithm::Sha512 => mac::<Sha512>(key, parts),
+    }
+}
+
+/// HKDF-Extract; `salt` vazio equivale a zeros do tamanho do hash
+pub fn extract(hash: HashAlgorithm, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    if salt.is_empty() {
+        hmac(hash, &vec![0; hash.output_len()], ikm)
+    } else {
+        hmac(hash, salt, ikm)
+    }
+}
+
+/// HKDF-Expand para `len` bytes (no máximo 255 blocos)
+pub fn expand(hash: HashAlgorithm, prk: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    assert!(len <= 255 * hash.output_len(), "HKDF output too long");
+    let mut okm = Vec::with_capacity(len);
+    let mut previous = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        previous = hmac_parts(hash, prk, &[&previous, info, &[counter]]);
+        okm.extend_from_slice(&previous);
+        counter += 1;
+    }
+    okm.truncate(len);
+    okm
+}
+
+/// `HKDF-Expand-Label` (RFC 8446, seção 7.1)
+pub fn expand_label(hash: HashAlgorithm, secret: &[u8], label: &str, context: &[u8], len: usize) -> Vec<u8> {
+    let full_label = [b"tls13 ", label.as_bytes()].concat();
+    let mut info = Vec::with_capacity(4 + full_label.len() + context.len());
+    info.extend_from_slice(&(len as u16).to_be_bytes());
+    info.push(full_label.len() as u8);
+    info.extend_from_slice(&full_label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+    expand(hash, secret, &info, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_rfc5869_case_1() {
+        let prk = extract(HashAlgorithm::Sha256, &hex("000102030405060708090a0b0c"), &[0x0b; 22]);
+        assert_eq!(prk, hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"));
+        assert_eq!(
+            expand(HashAlgorithm::Sha256, &prk, &hex("f0f1f2f3f4f5f6f7f8f9"), 42),
+            hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha384() {
+        assert_eq!(
+            hmac(HashAlgorithm::Sha384, b"key", b"The quick brown fox jumps over the lazy dog"),
+            hex("d7f4727e2c0b39ae0f1e40cc96f60242d5b7801841cea6fc592c5d3e1ae50700582a96cf35e1e554995fe4e03381c237")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Suítes de cifra do TLS 1.3 e as primitivas por trás delas
+
+pub mod aes_gcm;
+pub mod chacha20_poly1305;
+pub mod hkdf;
+pub mod sha2;
+
+use aes_gcm::AesGcm;
+use chacha20_poly1305::ChaCha20Poly1305;
+use sha2::HashAlgorithm;
+
+/// Suítes de cifra TLS 1.3 suportadas, em ordem de preferência
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CipherSuite {
+    /// `TLS_CHACHA20_POLY1305_SHA256` (0x1303)
+    ChaCha20Poly1305Sha256,
+    /// `TLS_AES_256_GCM_SHA384` (0x1302)
+    Aes256GcmSha384,
+    /// `TLS_AES_128_GCM_SHA256` (0x1301)
+    Aes128GcmSha256,
+}
+
+impl CipherSuite {
+    pub const ALL: [CipherSuite; 3] = [Self::ChaCha20Poly1305Sha256, Self::Aes256GcmSha384, Self::Aes128GcmSha256];
+
+    pub fn id(self) -> u16 {
+        match self {
+            Self::Aes128GcmSha256 => 0x1301,
+            Self::Aes256GcmSha384 => 0x1302,
+            Self::ChaCha20Poly1305Sha256 => 0x1303,
+        }
+    }
+
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|suite| suite.id() == id)
+    }
+
+    pub fn hash(self) -> HashAlgorithm {
+        match self {
+            Self::Aes256GcmSha384 => HashAlgorithm::Sha384,
+            Self::Aes128GcmSha256 | Self::ChaCha20Poly1305Sha256 => HashAlgorithm::Sha256,
+        }
+    }
+
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128GcmSha256 => 16,
+            Self::Aes256GcmSha384 | Self::ChaCha20Poly1305Sha256 => 32,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aes128GcmSha256 => "TLS_AES_128_GCM_SHA256",
+            Self::Aes256GcmSha384 => "TLS_AES_256_GCM_SHA384",
+            Self::ChaCha20Poly1305Sha256 => "TLS_CHACHA20_POLY1305_SHA256",
+        }
+    }
+
+    pub(crate) fn aead(self, key: &[u8]) -> Aead {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => Aead::ChaCha(ChaCha20Poly1305::new(key)),
+            Self::Aes128GcmSha256 | Self::Aes256GcmSha384 => Aead::Aes(AesGcm::new(key)),
+        }
+    }
+}
+
+/// AEAD da suíte negociada
+pub(crate) enum Aead {
+    ChaCha(ChaCha20Poly1305),
+    Aes(AesGcm),
+}
+
+impl Aead {
+    pub(crate) fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) {
+        match self {
+            Self::ChaCha(cipher) => cipher.seal(nonce, aad, data),
+            Self::Aes(cipher) => cipher.seal(nonce, aad, data),
+        }
+    }
+
+    pub(crate) fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool {
+        match self {
+            Self::ChaCha(cipher) => cipher.open(nonce, aad, data),
+            Self::Aes(cipher) => cipher.open(nonce, aad, data),
+        }
+    }
+}
+
+/// Comparação sem desvio dependente do conteúdo
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
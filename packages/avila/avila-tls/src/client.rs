@@ -0,0 +1,587 @@
+//! Cliente TLS 1.3 sem I/O
+//!
+//! [`ClientConnection`] recebe os bytes do servidor por [`read_tls`](ClientConnection::read_tls)
+//! e acumula os bytes a enviar, que o chamador obtém com
+//! [`take_tls_output`](ClientConnection::take_tls_output). Assim o mesmo estado
+//! serve para sockets bloqueantes ([`TlsStream`](crate::TlsStream)) e para runtimes async.
+
+use crate::alert::AlertDescription;
+use crate::certificate::{verify_server_certificate, verify_signature_scheme, CERTIFICATE_VERIFY_SCHEMES, SIGNATURE_SCHEMES};
+use crate::cipher_suite::sha2::Hasher;
+use crate::cipher_suite::{ct_eq, CipherSuite};
+use crate::handshake::key_schedule::{finished_verify_data, next_traffic_secret, KeySchedule, TrafficSecrets};
+use crate::handshake::{self, ClientHello, Joiner, Reader};
+use crate::record::{RecordKeys, RecordLayer, ALERT, APPLICATION_DATA, CHANGE_CIPHER_SPEC, HANDSHAKE};
+use crate::{fill_random, x25519, Result, TlsError};
+use avila_tls_config::{parse_pem, Certificate, TrustStore};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bundles de CA procurados em [`load_native_roots`], na ordem
+const NATIVE_ROOT_FILES: [&str; 4] = [
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+// ============================================================================
+// CONFIGURAÇÃO
+// ============================================================================
+
+/// Configuração compartilhada entre conexões
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Raízes confiáveis para validar o servidor
+    pub roots: TrustStore,
+    /// Protocolos ALPN oferecidos, em ordem de preferência
+    pub alpn_protocols: Vec<Vec<u8>>,
+    /// Suítes oferecidas, em ordem de preferência
+    pub cipher_suites: Vec<CipherSuite>,
+    /// Aceita qualquer certificado (apenas para testes; desativa a autenticação do servidor)
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl ClientConfig {
+    pub fn new(roots: TrustStore) -> Self {
+        Self {
+            roots,
+            alpn_protocols: Vec::new(),
+            cipher_suites: CipherSuite::ALL.to_vec(),
+            danger_accept_invalid_certs: false,
+        }
+    }
+
+    /// Configuração com as raízes do sistema
+    pub fn with_native_roots() -> Result<Self> {
+        Ok(Self::new(load_native_roots()?))
+    }
+
+    pub fn with_alpn(mut self, protocols: &[&str]) -> Self {
+        self.alpn_protocols = protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+        self
+    }
+}
+
+/// Carrega as raízes do sistema (`SSL_CERT_FILE` ou o bundle da distribuição)
+///
+/// Certificados que o parser X.509 não aceita são ignorados, para que uma
+/// raiz exótica não invalide o bundle inteiro.
+pub fn load_native_roots() -> Result<TrustStore> {
+    let candidates = std::env::var("SSL_CERT_FILE")
+        .ok()
+        .into_iter()
+        .chain(NATIVE_ROOT_FILES.iter().map(|path| path.to_string()));
+
+    for path in candidates {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut store = TrustStore::default();
+        for block in parse_pem(&text)? {
+            if block.label != "CERTIFICATE" {
+                continue;
+            }
+            if let Ok(cert) = Certificate::from_der(&block.data) {
+                store.add(cert);
+            }
+        }
+        if !store.roots.is_empty() {
+            return Ok(store);
+        }
+    }
+    Err(TlsError::Io(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "no system CA bundle found (set SSL_CERT_FILE)",
+    )))
+}
+
+// ============================================================================
+// CONEXÃO
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    ServerHello,
+    EncryptedExtensions,
+    /// Certificate (ou CertificateRequest antes dele)
+    Certificate,
+    CertificateVerify,
+    Finished,
+    Connected,
+    Closed,
+}
+
+/// Segredos e transcript enquanto o handshake está em andamento
+struct Handshake {
+    suite: CipherSuite,
+    transcript: Hasher,
+    schedule: KeySchedule,
+    secrets: TrafficSecrets,
+    certificate_requested: bool,
+}
+
+impl Handshake {
+    fn transcript_hash(&self) -> Vec<u8> {
+        self.transcript.clone().finish()
+    }
+}
+
+/// Conexão TLS 1.3 do lado cliente
+pub struct ClientConnection {
+    config: Arc<ClientConfig>,
+    server_name: String,
+    state: State,
+    record: RecordLayer,
+    joiner: Joiner,
+    /// Chave X25519 efêmera e ClientHello, até o ServerHello chegar
+    key_share_secret: [u8; 32],
+    session_id: [u8; 32],
+    client_hello: Vec<u8>,
+    handshake: Option<Handshake>,
+    suite: Option<CipherSuite>,
+    /// Segredos de aplicação (cliente, servidor), para KeyUpdate
+    application_secrets: Option<TrafficSecrets>,
+    alpn: Option<Vec<u8>>,
+    peer_certificates: Vec<Certificate>,
+    outgoing: Vec<u8>,
+    /// Dados da aplicação escritos antes do fim do handshake
+    early_plaintext: Vec<u8>,
+    received: Vec<u8>,
+    peer_closed: bool,
+}
+
+impl ClientConnection {
+    /// Inicia o handshake; o ClientHello fica disponível em [`take_tls_output`](Self::take_tls_output)
+    pub fn new(config: Arc<ClientConfig>, server_name: &str) -> Result<Self> {
+        let server_name = normalize_server_name(server_name)?;
+        let sni = server_name.parse::<IpAddr>().is_err().then_some(server_name.as_str());
+
+        let mut key_share_secret = [0u8; 32];
+        let mut random = [0u8; 32];
+        let mut session_id = [0u8; 32];
+        fill_random(&mut key_share_secret)?;
+        fill_random(&mut random)?;
+        fill_random(&mut session_id)?;
+
+        let suites: Vec<u16> = config.cipher_suites.iter().map(|suite| suite.id()).collect();
+        if suites.is_empty() {
+            return Err(TlsError::Handshake("no cipher suites configured".into()));
+        }
+        let client_hello = ClientHello {
+            random,
+            session_id,
+            cipher_suites: &suites,
+            server_name: sni,
+            key_share: x25519::public_key(&key_share_secret),
+            signature_schemes: &SIGNATURE_SCHEMES,
+            alpn: &config.alpn_protocols,
+        }
+        .encode();
+
+        let mut conn = Self {
+            config,
+            server_name,
+            state: State::ServerHello,
+            record: RecordLayer::default(),
+            joiner: Joiner::default(),
+            key_share_secret,
+            session_id,
+            client_hello,
+            handshake: None,
+            suite: None,
+            application_secrets: None,
+            alpn: None,
+            peer_certificates: Vec::new(),
+            outgoing: Vec::new(),
+            early_plaintext: Vec::new(),
+            received: Vec::new(),
+            peer_closed: false,
+        };
+        conn.record.write(HANDSHAKE, &conn.client_hello, &mut conn.outgoing);
+        Ok(conn)
+    }
+
+    /// Processa bytes recebidos do servidor
+    ///
+    /// Em erro a conexão é encerrada e o alerta correspondente fica na saída.
+    pub fn read_tls(&mut self, data: &[u8]) -> Result<()> {
+        if self.state == State::Closed {
+            return Err(TlsError::Closed);
+        }
+        self.record.push(data);
+        let result = self.process_records();
+        if let Err(err) = &result {
+            if let Some(alert) = err.alert() {
+                self.send_alert(alert);
+            }
+            self.state = State::Closed;
+        }
+        result
+    }
+
+    /// Bytes pendentes para o servidor
+    pub fn take_tls_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    pub fn wants_write(&self) -> bool {
+        !self.outgoing.is_empty()
+    }
+
+    pub fn is_handshaking(&self) -> bool {
+        !matches!(self.state, State::Connected | State::Closed)
+    }
+
+    /// Cifra dados da aplicação (guardados até o fim do handshake, se preciso)
+    pub fn write_plaintext(&mut self, data: &[u8]) -> Result<()> {
+        match self.state {
+            State::Closed => Err(TlsError::Closed),
+            State::Connected => {
+                self.record.write(APPLICATION_DATA, data, &mut self.outgoing);
+                Ok(())
+            }
+            _ => {
+                self.early_plaintext.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+
+    /// Copia dados da aplicação já decifrados para `buf`
+    pub fn read_plaintext(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.received.len());
+        buf[..len].copy_from_slice(&self.received[..len]);
+        self.received.drain(..len);
+        len
+    }
+
+    pub fn has_plaintext(&self) -> bool {
+        !self.received.is_empty()
+    }
+
+    /// `true` após o `close_notify` do servidor
+    pub fn peer_closed(&self) -> bool {
+        self.peer_closed
+    }
+
+    /// Encerra a escrita com `close_notify`
+    pub fn send_close_notify(&mut self) {
+        if self.state != State::Closed {
+            self.send_alert(AlertDescription::CloseNotify);
+            self.state = State::Closed;
+        }
+    }
+
+    pub fn negotiated_cipher_suite(&self) -> Option<CipherSuite> {
+        self.suite
+    }
+
+    /// Protocolo ALPN aceito pelo servidor
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn.as_deref()
+    }
+
+    /// Cadeia enviada pelo servidor (folha primeiro)
+    pub fn peer_certificates(&self) -> &[Certificate] {
+        &self.peer_certificates
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    fn send_alert(&mut self, alert: AlertDescription) {
+        let level = if alert == AlertDescription::CloseNotify { 1 } else { 2 };
+        self.record.write(ALERT, &[level, alert.code()], &mut self.outgoing);
+    }
+
+    fn process_records(&mut self) -> Result<()> {
+        while let Some((content_type, payload)) = self.record.next_record()? {
+            match content_type {
+                // CCS de compatibilidade (RFC 8446, D.4), só durante o handshake
+                CHANGE_CIPHER_SPEC if self.is_handshaking() && payload == [1] => {}
+                ALERT => self.handle_alert(&payload)?,
+                HANDSHAKE => {
+                    if payload.is_empty() {
+                        return Err(TlsError::UnexpectedMessage("empty handshake record".into()));
+                    }
+                    self.joiner.push(&payload);
+                    while let Some(message) = self.joiner.next_message()? {
+                        self.handle_message(&message)?;
+                    }
+                }
+                APPLICATION_DATA if self.state == State::Connected => {
+                    if self.peer_closed {
+                        return Err(TlsError::UnexpectedMessage("application data after close_notify".into()));
+                    }
+                    self.received.extend_from_slice(&payload);
+                }
+                other => {
+                    return Err(TlsError::UnexpectedMessage(format!("record of type {} in state {:?}", other, self.state)))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_alert(&mut self, payload: &[u8]) -> Result<()> {
+        let [_, code] = payload else {
+            return Err(TlsError::Decode("alert must be two bytes".into()));
+        };
+        match AlertDescription::from_code(*code) {
+            AlertDescription::CloseNotify => {
+                self.peer_closed = true;
+                Ok(())
+            }
+            AlertDescription::UserCanceled => Ok(()),
+            alert => {
+                self.state = State::Closed;
+                Err(TlsError::AlertReceived(alert))
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: &[u8]) -> Result<()> {
+        let kind = message[0];
+        let body = &message[4..];
+        match (self.state, kind) {
+            (State::ServerHello, handshake::SERVER_HELLO) => self.handle_server_hello(message),
+            (State::EncryptedExtensions, handshake::ENCRYPTED_EXTENSIONS) => {
+                let alpn = handshake::parse_encrypted_extensions(body)?;
+                if let Some(protocol) = &alpn {
+                    if !self.config.alpn_protocols.contains(protocol) {
+                        return Err(TlsError::Handshake("server selected an ALPN protocol that was not offered".into()));
+                    }
+                }
+                self.alpn = alpn;
+                self.update_transcript(message);
+                self.state = State::Certificate;
+                Ok(())
+            }
+            (State::Certificate, handshake::CERTIFICATE_REQUEST) => {
+                let hs = self.handshake_mut();
+                if hs.certificate_requested {
+                    return Err(TlsError::UnexpectedMessage("duplicate CertificateRequest".into()));
+                }
+                hs.certificate_requested = true;
+                hs.transcript.update(message);
+                Ok(())
+            }
+            (State::Certificate, handshake::CERTIFICATE) => {
+                let chain = handshake::parse_certificate(body)?
+                    .iter()
+                    .map(|der| Certificate::from_der(der))
+                    .collect::<avila_tls_config::Result<Vec<_>>>()?;
+                if chain.is_empty() {
+                    return Err(TlsError::Handshake("server sent an empty certificate chain".into()));
+                }
+                if !self.config.danger_accept_invalid_certs {
+                    verify_server_certificate(&chain, &self.config.roots, &self.server_name, unix_now())?;
+                }
+                self.peer_certificates = chain;
+                self.update_transcript(message);
+                self.state = State::CertificateVerify;
+                Ok(())
+            }
+            (State::CertificateVerify, handshake::CERTIFICATE_VERIFY) => {
+                let mut reader = Reader::new(body);
+                let scheme = reader.u16()?;
+                let signature = reader.vector(2)?.rest();
+                reader.finish()?;
+                if !CERTIFICATE_VERIFY_SCHEMES.contains(&scheme) {
+                    return Err(TlsError::Handshake(format!("unsupported signature scheme {:#06x}", scheme)));
+                }
+                if !self.config.danger_accept_invalid_certs {
+                    let mut signed = vec![0x20u8; 64];
+                    signed.extend_from_slice(b"TLS 1.3, server CertificateVerify\0");
+                    signed.extend_from_slice(&self.handshake_mut().transcript_hash());
+                    if !verify_signature_scheme(scheme, &self.peer_certificates[0], &signed, signature) {
+                        return Err(TlsError::Handshake("invalid CertificateVerify signature".into()));
+                    }
+                }
+                self.update_transcript(message);
+                self.state = State::Finished;
+                Ok(())
+            }
+            (State::Finished, handshake::FINISHED) => self.handle_finished(message),
+            // Retomada de sessão não é suportada; tickets são descartados
+            (State::Connected, handshake::NEW_SESSION_TICKET) => Ok(()),
+            (State::Connected, handshake::KEY_UPDATE) => self.handle_key_update(body),
+            (state, kind) => Err(TlsError::UnexpectedMessage(format!("handshake message {} in state {:?}", kind, state))),
+        }
+    }
+
+    fn handle_server_hello(&mut self, message: &[u8]) -> Result<()> {
+        let hello = handshake::parse_server_hello(&message[4..])?;
+        if hello.session_id != self.session_id {
+            return Err(TlsError::Handshake("server did not echo the session id".into()));
+        }
+        let suite = CipherSuite::from_id(hello.cipher_suite)
+            .filter(|suite| self.config.cipher_suites.contains(suite))
+            .ok_or_else(|| TlsError::Handshake("server selected a cipher suite that was not offered".into()))?;
+        // Mensagens seguintes usam chaves novas; nada pode ficar pela metade
+        if !self.joiner.is_empty() {
+            return Err(TlsError::UnexpectedMessage("handshake data after ServerHello in the same record".into()));
+        }
+
+        let shared = x25519::x25519(&self.key_share_secret, &hello.key_share);
+        if shared.iter().all(|&b| b == 0) {
+            return Err(TlsError::Handshake("server key share is a low-order point".into()));
+        }
+        self.key_share_secret = [0; 32];
+
+        let hash = suite.hash();
+        let mut transcript = hash.hasher();
+        transcript.update(&std::mem::take(&mut self.client_hello));
+        transcript.update(message);
+        let schedule = KeySchedule::handshake(hash, &shared);
+        let secrets = schedule.handshake_traffic(&transcript.clone().finish());
+        self.record.set_read_keys(RecordKeys::derive(suite, &secrets.server));
+
+        self.handshake = Some(Handshake { suite, transcript, schedule, secrets, certificate_requested: false });
+        self.suite = Some(suite);
+        self.state = State::EncryptedExtensions;
+        Ok(())
+    }
+
+    fn handle_finished(&mut self, message: &[u8]) -> Result<()> {
+        if !self.joiner.is_empty() {
+            return Err(TlsError::UnexpectedMessage("handshake data after server Finished in the same record".into()));
+        }
+        let mut hs = self.handshake.take().expect("handshake state before Finished");
+        let hash = hs.suite.hash();
+        let expected = finished_verify_data(hash, &hs.secrets.server, &hs.transcript_hash());
+        if !ct_eq(&expected, &message[4..]) {
+            return Err(TlsError::Handshake("server Finished verification failed".into()));
+        }
+        hs.transcript.update(message);
+
+        let server_finished_hash = hs.transcript_hash();
+        let application = hs.schedule.into_master().application_traffic(&server_finished_hash);
+
+        self.record.write(CHANGE_CIPHER_SPEC, &[1], &mut self.outgoing);
+        self.record.set_write_keys(RecordKeys::derive(hs.suite, &hs.secrets.client));
+        if hs.certificate_requested {
+            // Sem certificado de cliente: Certificate vazio (contexto e lista vazios)
+            let certificate = handshake::encode_message(handshake::CERTIFICATE, &[0, 0, 0, 0]);
+            hs.transcript.update(&certificate);
+            self.record.write(HANDSHAKE, &certificate, &mut self.outgoing);
+        }
+        let verify_data = finished_verify_data(hash, &hs.secrets.client, &hs.transcript.finish());
+        let finished = handshake::encode_message(handshake::FINISHED, &verify_data);
+        self.record.write(HANDSHAKE, &finished, &mut self.outgoing);
+
+        self.record.set_write_keys(RecordKeys::derive(hs.suite, &application.client));
+        self.record.set_read_keys(RecordKeys::derive(hs.suite, &application.server));
+        self.application_secrets = Some(application);
+        self.state = State::Connected;
+
+        let early = std::mem::take(&mut self.early_plaintext);
+        if !early.is_empty() {
+            self.record.write(APPLICATION_DATA, &early, &mut self.outgoing);
+        }
+        Ok(())
+    }
+
+    fn handle_key_update(&mut self, body: &[u8]) -> Result<()> {
+        let [request] = body else {
+            return Err(TlsError::Decode("KeyUpdate must be one byte".into()));
+        };
+        if *request > 1 {
+            return Err(TlsError::Decode("invalid KeyUpdate request".into()));
+        }
+        if !self.joiner.is_empty() {
+            return Err(TlsError::UnexpectedMessage("handshake data after KeyUpdate in the same record".into()));
+        }
+        let suite = self.suite.expect("suite negotiated");
+        let hash = suite.hash();
+        let secrets = self.application_secrets.as_mut().expect("application secrets");
+
+        secrets.server = next_traffic_secret(hash, &secrets.server);
+        self.record.set_read_keys(RecordKeys::derive(suite, &secrets.server));
+
+        if *request == 1 {
+            let update = handshake::encode_message(handshake::KEY_UPDATE, &[0]);
+            self.record.write(HANDSHAKE, &update, &mut self.outgoing);
+            secrets.client = next_traffic_secret(hash, &secrets.client);
+            self.record.set_write_keys(RecordKeys::derive(suite, &secrets.client));
+        }
+        Ok(())
+    }
+
+    fn handshake_mut(&mut self) -> &mut Handshake {
+        self.handshake.as_mut().expect("handshake state after ServerHello")
+    }
+
+    fn update_transcript(&mut self, message: &[u8]) {
+        self.handshake_mut().transcript.update(message);
+    }
+}
+
+/// Nome em minúsculas, sem ponto final e sem colchetes de IPv6
+fn normalize_server_name(name: &str) -> Result<String> {
+    let trimmed = name.trim_start_matches('[').trim_end_matches(']');
+    if trimmed.parse::<IpAddr>().is_ok() {
+        return Ok(trimmed.to_string());
+    }
+    let host = trimmed.trim_end_matches('.').to_ascii_lowercase();
+    let valid = !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        });
+    if valid {
+        Ok(host)
+    } else {
+        Err(TlsError::InvalidServerName(name.to_string()))
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_server_name() {
+        assert_eq!(normalize_server_name("API.Vizzio.local.").unwrap(), "api.vizzio.local");
+        assert_eq!(normalize_server_name("[::1]").unwrap(), "::1");
+        assert_eq!(normalize_server_name("10.0.0.7").unwrap(), "10.0.0.7");
+        assert!(normalize_server_name("").is_err());
+        assert!(normalize_server_name("bad host").is_err());
+        assert!(normalize_server_name("a..b").is_err());
+    }
+
+    #[test]
+    fn test_client_hello_and_rejects_tls12() {
+        let config = Arc::new(ClientConfig::new(TrustStore::default()));
+        let mut conn = ClientConnection::new(config, "api.vizzio.local").unwrap();
+        assert!(conn.is_handshaking());
+        let hello = conn.take_tls_output();
+        assert_eq!(&hello[..3], &[HANDSHAKE, 0x03, 0x01]);
+        assert_eq!(hello[5], handshake::CLIENT_HELLO);
+
+        // ServerHello de TLS 1.2 (sem supported_versions)
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[7; 32]);
+        body.push(32);
+        body.extend_from_slice(&conn.session_id);
+        body.extend_from_slice(&[0xc0, 0x2f, 0, 0, 0]);
+        let message = handshake::encode_message(handshake::SERVER_HELLO, &body);
+        let mut record = vec![HANDSHAKE, 0x03, 0x03];
+        record.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        record.extend_from_slice(&message);
+
+        assert!(matches!(conn.read_tls(&record), Err(TlsError::UnsupportedVersion)));
+        assert_eq!(conn.take_tls_output(), [ALERT, 0x03, 0x03, 0, 2, 2, AlertDescription::ProtocolVersion.code()]);
+        assert!(matches!(conn.read_tls(&[]), Err(TlsError::Closed)));
+        assert!(conn.write_plaintext(b"x").is_err());
+    }
+}
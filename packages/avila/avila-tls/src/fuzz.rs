@@ -0,0 +1,74 @@
+//! Pontos de entrada para fuzzing (feature `fuzzing`)
+//!
+//! A entrada escolhe a AEAD e fornece chave, nonce, AAD e texto; as
+//! invariantes valem para quaisquer valores deles.
+
+use crate::cipher_suite::aes_gcm::AesGcm;
+use crate::cipher_suite::chacha20_poly1305::ChaCha20Poly1305;
+
+/// Interface comum das AEADs, só para as verificações abaixo
+trait Aead {
+    fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>);
+    fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool;
+    fn seal_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16];
+}
+
+macro_rules! impl_aead {
+    ($($cipher:ty),*) => {$(
+        impl Aead for $cipher {
+            fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) {
+                <$cipher>::seal(self, nonce, aad, data)
+            }
+            fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool {
+                <$cipher>::open(self, nonce, aad, data)
+            }
+            fn seal_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16] {
+                <$cipher>::seal_detached(self, nonce, aad, data)
+            }
+        }
+    )*};
+}
+
+impl_aead!(AesGcm, ChaCha20Poly1305);
+
+/// AES-128-GCM, AES-256-GCM ou ChaCha20-Poly1305 com parâmetros da entrada:
+/// ida e volta, tag separada igual à anexada (AAD em pedaços), bit trocado
+/// rejeitado sem alterar o buffer e texto cifrado arbitrário sem pânico
+pub fn aead(data: &[u8]) {
+    let [selector, aad_len, split, flip, rest @ ..] = data else { return };
+    let key_len = if selector % 3 == 0 { 16 } else { 32 };
+    let Some((key, rest)) = rest.split_at_checked(key_len) else { return };
+    let Some((nonce, rest)) = rest.split_first_chunk::<12>() else { return };
+    let (aad, plaintext) = rest.split_at((*aad_len as usize).min(rest.len()));
+
+    let params = (nonce, aad, plaintext, *split, *flip);
+    match selector % 3 {
+        0 | 1 => check(&AesGcm::new(key), params),
+        _ => check(&ChaCha20Poly1305::new(key), params),
+    }
+}
+
+fn check(cipher: &impl Aead, (nonce, aad, plaintext, split, flip): (&[u8; 12], &[u8], &[u8], u8, u8)) {
+    let mut sealed = plaintext.to_vec();
+    cipher.seal(nonce, aad, &mut sealed);
+    assert_eq!(sealed.len(), plaintext.len() + 16);
+
+    let (head, tail) = aad.split_at(split as usize % (aad.len() + 1));
+    let mut detached = plaintext.to_vec();
+    let tag = cipher.seal_detached(nonce, &[head, &[], tail], &mut detached);
+    assert_eq!([&detached[..], &tag[..]].concat(), sealed);
+
+    // `flip` percorre todos os bits, da mensagem à tag
+    let mut tampered = sealed.clone();
+    let bit = flip as usize * tampered.len() * 8 / 256;
+    tampered[bit / 8] ^= 1 << (bit % 8);
+    let before = tampered.clone();
+    assert!(!cipher.open(nonce, aad, &mut tampered));
+    assert_eq!(tampered, before);
+
+    let mut garbage = plaintext.to_vec();
+    let _ = cipher.open(nonce, aad, &mut garbage);
+
+    assert!(cipher.open(nonce, aad, &mut sealed));
+    assert_eq!(sealed, plaintext);
+}
@@ -0,0 +1,94 @@
+//! Agenda de chaves do TLS 1.3 (RFC 8446, seção 7.1)
+
+use crate::cipher_suite::hkdf;
+use crate::cipher_suite::sha2::HashAlgorithm;
+
+/// Segredos de tráfego de uma etapa do handshake
+pub(crate) struct TrafficSecrets {
+    pub client: Vec<u8>,
+    pub server: Vec<u8>,
+}
+
+pub(crate) struct KeySchedule {
+    hash: HashAlgorithm,
+    /// Segredo da etapa atual (handshake ou master)
+    secret: Vec<u8>,
+}
+
+impl KeySchedule {
+    /// Handshake Secret a partir do segredo ECDHE (sem PSK)
+    pub(crate) fn handshake(hash: HashAlgorithm, shared_secret: &[u8]) -> Self {
+        let zeros = vec![0u8; hash.output_len()];
+        let early = hkdf::extract(hash, &[], &zeros);
+        let salt = derive_secret(hash, &early, "derived", &hash.digest(&[]));
+        Self {
+            hash,
+            secret: hkdf::extract(hash, &salt, shared_secret),
+        }
+    }
+
+    /// Avança para o Master Secret
+    pub(crate) fn into_master(self) -> Self {
+        let hash = self.hash;
+        let salt = derive_secret(hash, &self.secret, "derived", &hash.digest(&[]));
+        let zeros = vec![0u8; hash.output_len()];
+        Self {
+            hash,
+            secret: hkdf::extract(hash, &salt, &zeros),
+        }
+    }
+
+    /// Segredos `c/s hs traffic` com o transcript até o ServerHello
+    pub(crate) fn handshake_traffic(&self, transcript: &[u8]) -> TrafficSecrets {
+        TrafficSecrets {
+            client: derive_secret(self.hash, &self.secret, "c hs traffic", transcript),
+            server: derive_secret(self.hash, &self.secret, "s hs traffic", transcript),
+        }
+    }
+
+    /// Segredos `c/s ap traffic` com o transcript até o Finished do servidor
+    pub(crate) fn application_traffic(&self, transcript: &[u8]) -> TrafficSecrets {
+        TrafficSecrets {
+            client: derive_secret(self.hash, &self.secret, "c ap traffic", transcript),
+            server: derive_secret(self.hash, &self.secret, "s ap traffic", transcript),
+        }
+    }
+}
+
+fn derive_secret(hash: HashAlgorithm, secret: &[u8], label: &str, transcript: &[u8]) -> Vec<u8> {
+    hkdf::expand_label(hash, secret, label, transcript, hash.output_len())
+}
+
+/// `verify_data` do Finished para um traffic secret de handshake
+pub(crate) fn finished_verify_data(hash: HashAlgorithm, traffic_secret: &[u8], transcript: &[u8]) -> Vec<u8> {
+    let key = hkdf::expand_label(hash, traffic_secret, "finished", &[], hash.output_len());
+    hkdf::hmac(hash, &key, transcript)
+}
+
+/// Próximo segredo de tráfego após um KeyUpdate
+pub(crate) fn next_traffic_secret(hash: HashAlgorithm, secret: &[u8]) -> Vec<u8> {
+    hkdf::expand_label(hash, secret, "traffic upd", &[], hash.output_len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    // RFC 8448, seção 3 (Simple 1-RTT Handshake)
+    #[test]
+    fn test_rfc8448_secrets() {
+        let hash = HashAlgorithm::Sha256;
+        let shared = hex("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d");
+        let hello_hash = hex("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8");
+        let schedule = KeySchedule::handshake(hash, &shared);
+        assert_eq!(schedule.secret, hex("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac"));
+
+        let hs = schedule.handshake_traffic(&hello_hash);
+        assert_eq!(hs.client, hex("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21"));
+        assert_eq!(hs.server, hex("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38"));
+
+        let master = schedule.into_master();
+        assert_eq!(master.secret, hex("18df06843d13a08bf2a449844c5f8a478001bc4d4c627984d5a41da8d0402919"));
+    }
+}
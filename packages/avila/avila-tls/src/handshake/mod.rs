@@ -0,0 +1,388 @@
+//! Mensagens de handshake (RFC 8446, seção 4)
+
+pub(crate) mod key_schedule;
+
+use crate::{Result, TlsError};
+
+pub(crate) const CLIENT_HELLO: u8 = 1;
+pub(crate) const SERVER_HELLO: u8 = 2;
+pub(crate) const NEW_SESSION_TICKET: u8 = 4;
+pub(crate) const ENCRYPTED_EXTENSIONS: u8 = 8;
+pub(crate) const CERTIFICATE: u8 = 11;
+pub(crate) const CERTIFICATE_REQUEST: u8 = 13;
+pub(crate) const CERTIFICATE_VERIFY: u8 = 15;
+pub(crate) const FINISHED: u8 = 20;
+pub(crate) const KEY_UPDATE: u8 = 24;
+
+const EXT_SERVER_NAME: u16 = 0;
+const EXT_SUPPORTED_GROUPS: u16 = 10;
+const EXT_SIGNATURE_ALGORITHMS: u16 = 13;
+const EXT_ALPN: u16 = 16;
+const EXT_SUPPORTED_VERSIONS: u16 = 43;
+const EXT_KEY_SHARE: u16 = 51;
+
+pub(crate) const GROUP_X25519: u16 = 0x001d;
+const TLS13: u16 = 0x0304;
+
+/// `random` do ServerHello que indica HelloRetryRequest (SHA-256 de "HelloRetryRequest")
+const HELLO_RETRY_REQUEST: [u8; 32] = [
+    0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91, 0xc2, 0xa2, 0x11,
+    0x16, 0x7a, 0xbb, 0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+// ============================================================================
+// CODIFICAÇÃO
+// ============================================================================
+
+/// Leitor de campos com tamanho prefixado
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(TlsError::Decode("message truncated".into()));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn u24(&mut self) -> Result<usize> {
+        let bytes = self.take(3)?;
+        Ok((bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize)
+    }
+
+    /// Vetor com prefixo de `prefix` bytes (1, 2 ou 3)
+    pub(crate) fn vector(&mut self, prefix: usize) -> Result<Reader<'a>> {
+        let len = match prefix {
+            1 => self.u8()? as usize,
+            2 => self.u16()? as usize,
+            _ => self.u24()?,
+        };
+        Ok(Reader::new(self.take(len)?))
+    }
+
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+
+    pub(crate) fn finish(&self) -> Result<()> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(TlsError::Decode("trailing bytes in message".into()))
+        }
+    }
+}
+
+/// Grava `body()` precedido do tamanho em `prefix` bytes
+fn put_vector(out: &mut Vec<u8>, prefix: usize, body: impl FnOnce(&mut Vec<u8>)) {
+    let start = out.len();
+    out.extend(std::iter::repeat_n(0, prefix));
+    body(out);
+    let len = out.len() - start - prefix;
+    let bytes = (len as u32).to_be_bytes();
+    out[start..start + prefix].copy_from_slice(&bytes[4 - prefix..]);
+}
+
+fn put_extension(out: &mut Vec<u8>, kind: u16, body: impl FnOnce(&mut Vec<u8>)) {
+    out.extend_from_slice(&kind.to_be_bytes());
+    put_vector(out, 2, body);
+}
+
+/// Mensagem com cabeçalho `tipo || u24 tamanho`
+pub(crate) fn encode_message(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 4);
+    out.push(kind);
+    put_vector(&mut out, 3, |out| out.extend_from_slice(body));
+    out
+}
+
+/// Junta mensagens de handshake fragmentadas entre registros
+#[derive(Default)]
+pub(crate) struct Joiner {
+    buffer: Vec<u8>,
+}
+
+impl Joiner {
+    /// Maior mensagem aceita (cadeias de certificados grandes cabem com folga)
+    const MAX_MESSAGE: usize = 1 << 18;
+
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Próxima mensagem completa (com cabeçalho)
+    pub(crate) fn next_message(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.buffer.len() < 4 {
+            return Ok(None);
+        }
+        let len = Reader::new(&self.buffer[1..4]).u24()?;
+        if len > Self::MAX_MESSAGE {
+            return Err(TlsError::Decode("handshake message too large".into()));
+        }
+        if self.buffer.len() < 4 + len {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer.drain(..4 + len).collect()))
+    }
+}
+
+// ============================================================================
+// CLIENT HELLO / SERVER HELLO
+// ============================================================================
+
+pub(crate) struct ClientHello<'a> {
+    pub random: [u8; 32],
+    pub session_id: [u8; 32],
+    pub cipher_suites: &'a [u16],
+    /// SNI; `None` para endereços IP
+    pub server_name: Option<&'a str>,
+    pub key_share: [u8; 32],
+    pub signature_schemes: &'a [u16],
+    pub alpn: &'a [Vec<u8>],
+}
+
+impl ClientHello<'_> {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(512);
+        body.extend_from_slice(&0x0303u16.to_be_bytes());
+        body.extend_from_slice(&self.random);
+        put_vector(&mut body, 1, |out| out.extend_from_slice(&self.session_id));
+        put_vector(&mut body, 2, |out| {
+            for suite in self.cipher_suites {
+                out.extend_from_slice(&suite.to_be_bytes());
+            }
+        });
+        // legacy_compression_methods: apenas "null"
+        body.extend_from_slice(&[1, 0]);
+
+        put_vector(&mut body, 2, |ext| {
+            if let Some(name) = self.server_name {
+                put_extension(ext, EXT_SERVER_NAME, |out| {
+                    put_vector(out, 2, |list| {
+                        list.push(0);
+                        put_vector(list, 2, |host| host.extend_from_slice(name.as_bytes()));
+                    })
+                });
+            }
+            put_extension(ext, EXT_SUPPORTED_VERSIONS, |out| {
+                put_vector(out, 1, |versions| versions.extend_from_slice(&TLS13.to_be_bytes()))
+            });
+            put_extension(ext, EXT_SUPPORTED_GROUPS, |out| {
+                put_vector(out, 2, |groups| groups.extend_from_slice(&GROUP_X25519.to_be_bytes()))
+            });
+            put_extension(ext, EXT_SIGNATURE_ALGORITHMS, |out| {
+                put_vector(out, 2, |schemes| {
+                    for scheme in self.signature_schemes {
+                        schemes.extend_from_slice(&scheme.to_be_bytes());
+                    }
+                })
+            });
+            put_extension(ext, EXT_KEY_SHARE, |out| {
+                put_vector(out, 2, |shares| {
+                    shares.extend_from_slice(&GROUP_X25519.to_be_bytes());
+                    put_vector(shares, 2, |key| key.extend_from_slice(&self.key_share));
+                })
+            });
+            if !self.alpn.is_empty() {
+                put_extension(ext, EXT_ALPN, |out| {
+                    put_vector(out, 2, |list| {
+                        for protocol in self.alpn {
+                            put_vector(list, 1, |p| p.extend_from_slice(protocol));
+                        }
+                    })
+                });
+            }
+        });
+        encode_message(CLIENT_HELLO, &body)
+    }
+}
+
+pub(crate) struct ServerHello {
+    pub session_id: Vec<u8>,
+    pub cipher_suite: u16,
+    /// Chave X25519 do servidor
+    pub key_share: [u8; 32],
+}
+
+/// Decodifica o corpo de um ServerHello
+pub(crate) fn parse_server_hello(body: &[u8]) -> Result<ServerHello> {
+    let mut reader = Reader::new(body);
+    let legacy_version = reader.u16()?;
+    let random = reader.take(32)?;
+    let session_id = reader.vector(1)?.rest().to_vec();
+    let cipher_suite = reader.u16()?;
+    if reader.u8()? != 0 {
+        return Err(TlsError::Handshake("server selected a compression method".into()));
+    }
+
+    let mut version = None;
+    let mut key_share = None;
+    let mut extensions = reader.vector(2)?;
+    reader.finish()?;
+    while !extensions.is_empty() {
+        let kind = extensions.u16()?;
+        let mut data = extensions.vector(2)?;
+        match kind {
+            EXT_SUPPORTED_VERSIONS => version = Some(data.u16()?),
+            EXT_KEY_SHARE if random != HELLO_RETRY_REQUEST => {
+                let group = data.u16()?;
+                let key = data.vector(2)?.rest();
+                if group != GROUP_X25519 || key.len() != 32 {
+                    return Err(TlsError::Handshake(format!("server key share for unsupported group {:#06x}", group)));
+                }
+                key_share = Some(key.try_into().expect("32-byte key"));
+            }
+            _ => {}
+        }
+    }
+
+    // Sem supported_versions o servidor negociou TLS 1.2 ou anterior
+    if version != Some(TLS13) || legacy_version != 0x0303 {
+        return Err(TlsError::UnsupportedVersion);
+    }
+    if random == HELLO_RETRY_REQUEST {
+        // Só oferecemos X25519 e não usamos cookies; um HRR não tem como ser atendido
+        return Err(TlsError::Handshake("server requested a HelloRetryRequest".into()));
+    }
+    Ok(ServerHello {
+        session_id,
+        cipher_suite,
+        key_share: key_share.ok_or_else(|| TlsError::Handshake("server hello without key share".into()))?,
+    })
+}
+
+/// Protocolo escolhido no EncryptedExtensions, se houver ALPN
+pub(crate) fn parse_encrypted_extensions(body: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut reader = Reader::new(body);
+    let mut extensions = reader.vector(2)?;
+    reader.finish()?;
+    let mut alpn = None;
+    while !extensions.is_empty() {
+        let kind = extensions.u16()?;
+        let mut data = extensions.vector(2)?;
+        if kind == EXT_ALPN {
+            let mut list = data.vector(2)?;
+            let protocol = list.vector(1)?.rest().to_vec();
+            list.finish()?;
+            alpn = Some(protocol);
+        }
+    }
+    Ok(alpn)
+}
+
+/// Certificados DER de uma mensagem Certificate (folha primeiro)
+pub(crate) fn parse_certificate(body: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader::new(body);
+    if !reader.vector(1)?.is_empty() {
+        return Err(TlsError::Decode("unexpected certificate request context".into()));
+    }
+    let mut entries = reader.vector(3)?;
+    reader.finish()?;
+    let mut chain = Vec::new();
+    while !entries.is_empty() {
+        chain.push(entries.vector(3)?.rest().to_vec());
+        // Extensões por certificado (OCSP, SCT) são ignoradas
+        entries.vector(2)?;
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_hello_layout() {
+        let hello = ClientHello {
+            random: [1; 32],
+            session_id: [2; 32],
+            cipher_suites: &[0x1303, 0x1301],
+            server_name: Some("api.vizzio.local"),
+            key_share: [3; 32],
+            signature_schemes: &[0x0403],
+            alpn: &[b"http/1.1".to_vec()],
+        };
+        let encoded = hello.encode();
+        assert_eq!(encoded[0], CLIENT_HELLO);
+        assert_eq!(Reader::new(&encoded[1..4]).u24().unwrap(), encoded.len() - 4);
+
+        let mut reader = Reader::new(&encoded[4..]);
+        assert_eq!(reader.u16().unwrap(), 0x0303);
+        assert_eq!(reader.take(32).unwrap(), &[1; 32]);
+        assert_eq!(reader.vector(1).unwrap().rest(), &[2; 32]);
+        assert_eq!(reader.vector(2).unwrap().rest(), &[0x13, 0x03, 0x13, 0x01]);
+        assert_eq!(reader.vector(1).unwrap().rest(), &[0]);
+
+        let mut extensions = reader.vector(2).unwrap();
+        reader.finish().unwrap();
+        let mut kinds = Vec::new();
+        while !extensions.is_empty() {
+            kinds.push(extensions.u16().unwrap());
+            let data = extensions.vector(2).unwrap().rest();
+            if kinds.last() == Some(&EXT_SERVER_NAME) {
+                assert!(data.ends_with(b"api.vizzio.local"));
+            }
+        }
+        assert_eq!(kinds, [EXT_SERVER_NAME, EXT_SUPPORTED_VERSIONS, EXT_SUPPORTED_GROUPS, EXT_SIGNATURE_ALGORITHMS, EXT_KEY_SHARE, EXT_ALPN]);
+    }
+
+    #[test]
+    fn test_server_hello_versions() {
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[9; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x13, 0x01, 0]);
+        let mut tls12 = body.clone();
+        tls12.extend_from_slice(&[0, 0]);
+        assert!(matches!(parse_server_hello(&tls12), Err(TlsError::UnsupportedVersion)));
+
+        put_vector(&mut body, 2, |ext| {
+            put_extension(ext, EXT_SUPPORTED_VERSIONS, |out| out.extend_from_slice(&TLS13.to_be_bytes()));
+            put_extension(ext, EXT_KEY_SHARE, |out| {
+                out.extend_from_slice(&GROUP_X25519.to_be_bytes());
+                put_vector(out, 2, |key| key.extend_from_slice(&[5; 32]));
+            });
+        });
+        let hello = parse_server_hello(&body).unwrap();
+        assert_eq!(hello.cipher_suite, 0x1301);
+        assert_eq!(hello.key_share, [5; 32]);
+    }
+
+    #[test]
+    fn test_joiner_reassembles_fragments() {
+        let message = encode_message(FINISHED, &[7; 40]);
+        let mut joiner = Joiner::default();
+        joiner.push(&message[..10]);
+        assert!(joiner.next_message().unwrap().is_none());
+        joiner.push(&message[10..]);
+        joiner.push(&encode_message(KEY_UPDATE, &[0]));
+        assert_eq!(joiner.next_message().unwrap().unwrap(), message);
+        assert_eq!(joiner.next_message().unwrap().unwrap()[0], KEY_UPDATE);
+        assert!(joiner.is_empty());
+    }
+}
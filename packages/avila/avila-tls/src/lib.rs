@@ -1,27 +1,144 @@
-﻿//! # avila-tls - TLS/SSL Protocol
-extern crate alloc;
-use alloc::vec::Vec;
+//! # avila-tls - TLS/SSL Protocol
+//!
+//! Cliente TLS 1.3 (RFC 8446) em Rust puro, sem criptografia de terceiros:
+//! as AEADs vêm do `avila-crypto` e o SHA-2/HMAC do `avila-hash`.
+//!
+//! - Suítes `TLS_CHACHA20_POLY1305_SHA256`, `TLS_AES_256_GCM_SHA384` e
+//!   `TLS_AES_128_GCM_SHA256`
+//! - Troca de chaves X25519
+//! - Certificados validados até as raízes do sistema (ou as informadas), com
+//!   RSA e ECDSA P-256/P-384, hostname pelo SAN e SNI
+//! - ALPN, KeyUpdate e `close_notify`
+//! - [`ClientConnection`] sem I/O (alimentada com bytes) e [`TlsStream`]
+//!   bloqueante sobre qualquer `Read + Write`
+//!
+//! Fora do escopo: TLS 1.2, retomada de sessão/0-RTT e certificado de cliente.
+//!
+//! ```no_run
+//! use avila_tls::{ClientConfig, TlsStream};
+//! use std::io::{Read, Write};
+//! use std::net::TcpStream;
+//! use std::sync::Arc;
+//!
+//! let config = Arc::new(ClientConfig::with_native_roots()?);
+//! let tcp = TcpStream::connect("example.com:443")?;
+//! let mut tls = TlsStream::connect(config, "example.com", tcp)?;
+//! tls.write_all(b"GET / HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n")?;
+//! let mut response = Vec::new();
+//! tls.read_to_end(&mut response)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
+pub mod alert;
+pub mod certificate;
+pub mod cipher_suite;
+mod client;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+mod handshake;
+mod record;
+mod stream;
+pub mod x25519;
+
+pub use alert::AlertDescription;
+pub use avila_tls_config::{Certificate, TrustStore};
+pub use cipher_suite::CipherSuite;
+pub use client::{load_native_roots, ClientConfig, ClientConnection};
+pub use stream::TlsStream;
+
+pub type Result<T> = std::result::Result<T, TlsError>;
+
+/// Versão de protocolo negociada
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum TlsVersion { TLS12, TLS13 }
+pub enum TlsVersion {
+    TLS12,
+    TLS13,
+}
+
+// ============================================================================
+// ERROS
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid server certificate: {0}")]
+    Certificate(#[from] avila_tls_config::TlsConfigError),
+
+    #[error("Peer sent fatal alert: {0}")]
+    AlertReceived(AlertDescription),
+
+    #[error("Peer does not support TLS 1.3")]
+    UnsupportedVersion,
+
+    #[error("Handshake failure: {0}")]
+    Handshake(String),
+
+    #[error("Malformed TLS message: {0}")]
+    Decode(String),
 
-pub struct TlsConnection {
-    pub version: TlsVersion,
-    pub cipher_suite: u16,
+    #[error("Unexpected TLS message: {0}")]
+    UnexpectedMessage(String),
+
+    #[error("Record authentication failed")]
+    BadRecordMac,
+
+    #[error("Invalid server name '{0}'")]
+    InvalidServerName(String),
+
+    #[error("Connection closed")]
+    Closed,
+}
+
+impl TlsError {
+    /// Alerta enviado ao peer quando o erro é local
+    pub(crate) fn alert(&self) -> Option<AlertDescription> {
+        match self {
+            TlsError::Certificate(_) => Some(AlertDescription::BadCertificate),
+            TlsError::UnsupportedVersion => Some(AlertDescription::ProtocolVersion),
+            TlsError::Handshake(_) => Some(AlertDescription::HandshakeFailure),
+            TlsError::Decode(_) => Some(AlertDescription::DecodeError),
+            TlsError::UnexpectedMessage(_) => Some(AlertDescription::UnexpectedMessage),
+            TlsError::BadRecordMac => Some(AlertDescription::BadRecordMac),
+            TlsError::Io(_) | TlsError::AlertReceived(_) | TlsError::InvalidServerName(_) | TlsError::Closed => None,
+        }
+    }
 }
 
-impl TlsConnection {
-    pub fn new(version: TlsVersion) -> Self {
-        Self { version, cipher_suite: 0x1301 }
+impl From<TlsError> for std::io::Error {
+    fn from(err: TlsError) -> Self {
+        match err {
+            TlsError::Io(io) => io,
+            TlsError::Closed => std::io::Error::new(std::io::ErrorKind::BrokenPipe, err),
+            other => std::io::Error::new(std::io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+/// Bytes aleatórios do sistema operacional
+pub(crate) fn fill_random(buf: &mut [u8]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Read;
+        std::fs::File::open("/dev/urandom")?.read_exact(buf)?;
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = buf;
+        Err(TlsError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "no system random source on this platform",
+        )))
     }
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_tls() {
-        let conn = TlsConnection::new(TlsVersion::TLS13);
-        assert_eq!(conn.version, TlsVersion::TLS13);
+pub(crate) mod test_util {
+    pub fn hex(text: &str) -> Vec<u8> {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect()
     }
 }
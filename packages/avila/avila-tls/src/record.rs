@@ -0,0 +1,191 @@
+//! Camada de registros (RFC 8446, seção 5)
+
+use crate::cipher_suite::{hkdf, Aead, CipherSuite};
+use crate::{Result, TlsError};
+
+pub(crate) const CHANGE_CIPHER_SPEC: u8 = 20;
+pub(crate) const ALERT: u8 = 21;
+pub(crate) const HANDSHAKE: u8 = 22;
+pub(crate) const APPLICATION_DATA: u8 = 23;
+
+/// Maior fragmento de texto claro
+pub(crate) const MAX_FRAGMENT: usize = 1 << 14;
+/// Maior registro cifrado aceito (texto claro + tipo + tag + folga da RFC)
+const MAX_CIPHERTEXT: usize = MAX_FRAGMENT + 256;
+const HEADER_LEN: usize = 5;
+
+/// Chaves de uma direção, derivadas de um traffic secret
+pub(crate) struct RecordKeys {
+    aead: Aead,
+    iv: [u8; 12],
+    seq: u64,
+}
+
+impl RecordKeys {
+    pub(crate) fn derive(suite: CipherSuite, secret: &[u8]) -> Self {
+        let hash = suite.hash();
+        let key = hkdf::expand_label(hash, secret, "key", &[], suite.key_len());
+        let iv = hkdf::expand_label(hash, secret, "iv", &[], 12);
+        Self {
+            aead: suite.aead(&key),
+            iv: iv.try_into().expect("12-byte IV"),
+            seq: 0,
+        }
+    }
+
+    /// IV XOR número de sequência (big-endian, alinhado à direita)
+    fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = self.iv;
+        for (byte, seq) in nonce[4..].iter_mut().zip(self.seq.to_be_bytes()) {
+            *byte ^= seq;
+        }
+        self.seq += 1;
+        nonce
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct RecordLayer {
+    read: Option<RecordKeys>,
+    write: Option<RecordKeys>,
+    incoming: Vec<u8>,
+}
+
+impl RecordLayer {
+    pub(crate) fn set_read_keys(&mut self, keys: RecordKeys) {
+        self.read = Some(keys);
+    }
+
+    pub(crate) fn set_write_keys(&mut self, keys: RecordKeys) {
+        self.write = Some(keys);
+    }
+
+    /// Fragmenta `payload` e grava os registros em `out`, cifrando se houver chave
+    pub(crate) fn write(&mut self, content_type: u8, payload: &[u8], out: &mut Vec<u8>) {
+        // Um registro vazio ainda é necessário para payloads vazios
+        let mut chunks = payload.chunks(MAX_FRAGMENT).peekable();
+        if chunks.peek().is_none() {
+            self.write_fragment(content_type, &[], out);
+        }
+        for chunk in chunks {
+            self.write_fragment(content_type, chunk, out);
+        }
+    }
+
+    fn write_fragment(&mut self, content_type: u8, fragment: &[u8], out: &mut Vec<u8>) {
+        match &mut self.write {
+            None => {
+                // O primeiro ClientHello usa 0x0301 por compatibilidade
+                let version: u16 = if content_type == HANDSHAKE { 0x0301 } else { 0x0303 };
+                out.push(content_type);
+                out.extend_from_slice(&version.to_be_bytes());
+                out.extend_from_slice(&(fragment.len() as u16).to_be_bytes());
+                out.extend_from_slice(fragment);
+            }
+            Some(keys) => {
+                let mut inner = Vec::with_capacity(fragment.len() + 17);
+                inner.extend_from_slice(fragment);
+                inner.push(content_type);
+                let header = record_header(APPLICATION_DATA, inner.len() + 16);
+                let nonce = keys.next_nonce();
+                keys.aead.seal(&nonce, &header, &mut inner);
+                out.extend_from_slice(&header);
+                out.extend_from_slice(&inner);
+            }
+        }
+    }
+
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        self.incoming.extend_from_slice(data);
+    }
+
+    /// Próximo registro completo como `(tipo, texto claro)`
+    pub(crate) fn next_record(&mut self) -> Result<Option<(u8, Vec<u8>)>> {
+        if self.incoming.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let header: [u8; HEADER_LEN] = self.incoming[..HEADER_LEN].try_into().expect("header length");
+        let len = u16::from_be_bytes([header[3], header[4]]) as usize;
+        if header[1] != 0x03 {
+            return Err(TlsError::Decode(format!("record version {:02x}{:02x}", header[1], header[2])));
+        }
+        if len > MAX_CIPHERTEXT {
+            return Err(TlsError::Decode("record too large".into()));
+        }
+        if self.incoming.len() < HEADER_LEN + len {
+            return Ok(None);
+        }
+        let mut body: Vec<u8> = self.incoming[HEADER_LEN..HEADER_LEN + len].to_vec();
+        self.incoming.drain(..HEADER_LEN + len);
+
+        let content_type = header[0];
+        let Some(keys) = self.read.as_mut().filter(|_| content_type != CHANGE_CIPHER_SPEC) else {
+            return Ok(Some((content_type, body)));
+        };
+        if content_type != APPLICATION_DATA {
+            return Err(TlsError::UnexpectedMessage(format!("plaintext record of type {} after handshake keys", content_type)));
+        }
+
+        let nonce = keys.next_nonce();
+        if !keys.aead.open(&nonce, &header, &mut body) {
+            return Err(TlsError::BadRecordMac);
+        }
+        // TLSInnerPlaintext: conteúdo, tipo real e zeros de padding
+        let Some(end) = body.iter().rposition(|&b| b != 0) else {
+            return Err(TlsError::UnexpectedMessage("record without content type".into()));
+        };
+        let inner_type = body[end];
+        body.truncate(end);
+        if body.len() > MAX_FRAGMENT {
+            return Err(TlsError::Decode("record plaintext too large".into()));
+        }
+        Ok(Some((inner_type, body)))
+    }
+}
+
+fn record_header(content_type: u8, len: usize) -> [u8; HEADER_LEN] {
+    let len = (len as u16).to_be_bytes();
+    [content_type, 0x03, 0x03, len[0], len[1]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let suite = CipherSuite::Aes128GcmSha256;
+        let secret = [7u8; 32];
+        let mut writer = RecordLayer::default();
+        writer.set_write_keys(RecordKeys::derive(suite, &secret));
+        let mut reader = RecordLayer::default();
+        reader.set_read_keys(RecordKeys::derive(suite, &secret));
+
+        let payload: Vec<u8> = (0..40_000u32).map(|i| i as u8).collect();
+        let mut wire = Vec::new();
+        writer.write(APPLICATION_DATA, &payload, &mut wire);
+        writer.write(ALERT, &[1, 0], &mut wire);
+
+        // Entrega byte a byte parcial
+        reader.push(&wire[..100]);
+        assert!(reader.next_record().unwrap().is_none());
+        reader.push(&wire[100..]);
+
+        let mut received = Vec::new();
+        while let Some((content_type, data)) = reader.next_record().unwrap() {
+            match content_type {
+                APPLICATION_DATA => received.extend_from_slice(&data),
+                ALERT => assert_eq!(data, [1, 0]),
+                other => panic!("unexpected type {}", other),
+            }
+        }
+        assert_eq!(received, payload);
+
+        // Registro adulterado
+        let mut tampered = Vec::new();
+        writer.write(APPLICATION_DATA, b"x", &mut tampered);
+        tampered[7] ^= 1;
+        reader.push(&tampered);
+        assert!(matches!(reader.next_record(), Err(TlsError::BadRecordMac)));
+    }
+}
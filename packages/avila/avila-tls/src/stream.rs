@@ -0,0 +1,96 @@
+//! Stream TLS bloqueante sobre qualquer `Read + Write`
+
+use crate::client::{ClientConfig, ClientConnection};
+use crate::{Result, TlsError};
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+/// Conexão TLS sobre um transporte bloqueante (normalmente `TcpStream`)
+pub struct TlsStream<S> {
+    conn: ClientConnection,
+    sock: S,
+}
+
+impl<S: Read + Write> TlsStream<S> {
+    /// Executa o handshake completo antes de retornar
+    pub fn connect(config: Arc<ClientConfig>, server_name: &str, sock: S) -> Result<Self> {
+        let mut stream = Self {
+            conn: ClientConnection::new(config, server_name)?,
+            sock,
+        };
+        while stream.conn.is_handshaking() {
+            stream.flush_tls()?;
+            if stream.fill()? == 0 {
+                return Err(TlsError::Handshake("connection closed during handshake".into()));
+            }
+        }
+        stream.flush_tls()?;
+        Ok(stream)
+    }
+
+    pub fn connection(&self) -> &ClientConnection {
+        &self.conn
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.sock
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sock
+    }
+
+    /// Envia `close_notify`
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.conn.send_close_notify();
+        self.flush_tls()
+    }
+
+    fn flush_tls(&mut self) -> Result<()> {
+        if self.conn.wants_write() {
+            self.sock.write_all(&self.conn.take_tls_output())?;
+            self.sock.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Lê do transporte e processa os registros; retorna os bytes lidos
+    fn fill(&mut self) -> Result<usize> {
+        let mut buf = [0u8; 16 * 1024];
+        let n = self.sock.read(&mut buf)?;
+        let result = self.conn.read_tls(&buf[..n]);
+        // Respostas (KeyUpdate, alertas) saem mesmo quando houve erro
+        self.flush_tls()?;
+        result.map(|_| n)
+    }
+}
+
+impl<S: Read + Write> Read for TlsStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while !self.conn.has_plaintext() {
+            if self.conn.peer_closed() {
+                return Ok(0);
+            }
+            if self.fill()? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "peer closed connection without close_notify"));
+            }
+        }
+        Ok(self.conn.read_plaintext(buf))
+    }
+}
+
+impl<S: Read + Write> Write for TlsStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.conn.write_plaintext(buf)?;
+        self.flush_tls()?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_tls()?;
+        Ok(())
+    }
+}
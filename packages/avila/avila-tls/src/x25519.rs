@@ -0,0 +1,228 @@
+//! X25519 (RFC 7748) para o key share do handshake
+//!
+//! Corpo primo 2^255 - 19 com limbs de 51 bits e escada de Montgomery com
+//! trocas condicionais sem desvio.
+
+const MASK: u64 = (1 << 51) - 1;
+
+#[derive(Clone, Copy)]
+struct Fe([u64; 5]);
+
+impl Fe {
+    const ZERO: Fe = Fe([0; 5]);
+    const ONE: Fe = Fe([1, 0, 0, 0, 0]);
+
+    fn from_bytes(bytes: &[u8; 32]) -> Fe {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().expect("8 bytes"));
+        Fe([
+            load(0) & MASK,
+            (load(6) >> 3) & MASK,
+            (load(12) >> 6) & MASK,
+            (load(19) >> 1) & MASK,
+            // Bit mais alto ignorado (RFC 7748, seção 5)
+            (load(24) >> 12) & MASK,
+        ])
+    }
+
+    fn to_bytes(self) -> [u8; 32] {
+        // Limbs estritamente abaixo de 2^51 antes da redução final
+        let mut h = self.0;
+        for _ in 0..3 {
+            for i in 0..4 {
+                h[i + 1] += h[i] >> 51;
+                h[i] &= MASK;
+            }
+            h[0] += 19 * (h[4] >> 51);
+            h[4] &= MASK;
+        }
+        // Reduz para [0, p): soma 19 e observa o carry do bit 255
+        let mut q = (h[0] + 19) >> 51;
+        for limb in &h[1..] {
+            q = (limb + q) >> 51;
+        }
+        h[0] += 19 * q;
+        for i in 0..4 {
+            h[i + 1] += h[i] >> 51;
+            h[i] &= MASK;
+        }
+        h[4] &= MASK;
+
+        let mut out = [0u8; 32];
+        let words = [
+            h[0] | (h[1] << 51),
+            (h[1] >> 13) | (h[2] << 38),
+            (h[2] >> 26) | (h[3] << 25),
+            (h[3] >> 39) | (h[4] << 12),
+        ];
+        for (chunk, word) in out.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    fn carry(self) -> Fe {
+        let mut h = self.0;
+        for i in 0..4 {
+            h[i + 1] += h[i] >> 51;
+            h[i] &= MASK;
+        }
+        h[0] += 19 * (h[4] >> 51);
+        h[4] &= MASK;
+        h[1] += h[0] >> 51;
+        h[0] &= MASK;
+        Fe(h)
+    }
+
+    fn add(self, other: Fe) -> Fe {
+        let mut h = self.0;
+        for (a, b) in h.iter_mut().zip(other.0) {
+            *a += b;
+        }
+        Fe(h).carry()
+    }
+
+    /// `self - other` somando 2p para não sair do intervalo
+    fn sub(self, other: Fe) -> Fe {
+        const TWO_P: [u64; 5] = [0xf_ffff_ffff_ffda, 0xf_ffff_ffff_fffe, 0xf_ffff_ffff_fffe, 0xf_ffff_ffff_fffe, 0xf_ffff_ffff_fffe];
+        let mut h = [0u64; 5];
+        for i in 0..5 {
+            h[i] = self.0[i] + TWO_P[i] - other.0[i];
+        }
+        Fe(h).carry()
+    }
+
+    fn mul(self, other: Fe) -> Fe {
+        let a = self.0.map(u128::from);
+        let b = other.0.map(u128::from);
+        let b19 = [b[1] * 19, b[2] * 19, b[3] * 19, b[4] * 19];
+
+        let t = [
+            a[0] * b[0] + a[1] * b19[3] + a[2] * b19[2] + a[3] * b19[1] + a[4] * b19[0],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b19[3] + a[3] * b19[2] + a[4] * b19[1],
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b19[3] + a[4] * b19[2],
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b19[3],
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0],
+        ];
+        Self::reduce_wide(t)
+    }
+
+    fn square(self) -> Fe {
+        self.mul(self)
+    }
+
+    fn mul_small(self, k: u64) -> Fe {
+        let t = self.0.map(|limb| limb as u128 * k as u128);
+        Self::reduce_wide(t)
+    }
+
+    fn reduce_wide(mut t: [u128; 5]) -> Fe {
+        for i in 0..4 {
+            t[i + 1] += t[i] >> 51;
+            t[i] &= MASK as u128;
+        }
+        let carry = t[4] >> 51;
+        t[4] &= MASK as u128;
+        t[0] += carry * 19;
+        Fe(t.map(|limb| limb as u64)).carry()
+    }
+
+    /// `self^(p-2)`
+    fn invert(self) -> Fe {
+        let pow2k = |mut x: Fe, k: u32| {
+            for _ in 0..k {
+                x = x.square();
+            }
+            x
+        };
+        let z2 = self.square();
+        let z9 = pow2k(z2, 2).mul(self);
+        let z11 = z9.mul(z2);
+        let z2_5_0 = z11.square().mul(z9);
+        let z2_10_0 = pow2k(z2_5_0, 5).mul(z2_5_0);
+        let z2_20_0 = pow2k(z2_10_0, 10).mul(z2_10_0);
+        let z2_40_0 = pow2k(z2_20_0, 20).mul(z2_20_0);
+        let z2_50_0 = pow2k(z2_40_0, 10).mul(z2_10_0);
+        let z2_100_0 = pow2k(z2_50_0, 50).mul(z2_50_0);
+        let z2_200_0 = pow2k(z2_100_0, 100).mul(z2_100_0);
+        let z2_250_0 = pow2k(z2_200_0, 50).mul(z2_50_0);
+        pow2k(z2_250_0, 5).mul(z11)
+    }
+}
+
+fn cswap(a: &mut Fe, b: &mut Fe, swap: u64) {
+    let mask = swap.wrapping_neg();
+    for i in 0..5 {
+        let t = mask & (a.0[i] ^ b.0[i]);
+        a.0[i] ^= t;
+        b.0[i] ^= t;
+    }
+}
+
+/// Multiplicação escalar `scalar * u` (coordenada u)
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let x1 = Fe::from_bytes(u);
+    let (mut x2, mut z2, mut x3, mut z3) = (Fe::ONE, Fe::ZERO, x1, Fe::ONE);
+    let mut swap = 0u64;
+
+    for t in (0..255).rev() {
+        let bit = ((k[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= bit;
+        cswap(&mut x2, &mut x3, swap);
+        cswap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = x2.add(z2);
+        let aa = a.square();
+        let b = x2.sub(z2);
+        let bb = b.square();
+        let e = aa.sub(bb);
+        let c = x3.add(z3);
+        let d = x3.sub(z3);
+        let da = d.mul(a);
+        let cb = c.mul(b);
+        x3 = da.add(cb).square();
+        z3 = x1.mul(da.sub(cb).square());
+        x2 = aa.mul(bb);
+        z2 = e.mul(aa.add(e.mul_small(121_665)));
+    }
+    cswap(&mut x2, &mut x3, swap);
+    cswap(&mut z2, &mut z3, swap);
+
+    x2.mul(z2.invert()).to_bytes()
+}
+
+/// Chave pública `scalar * 9`
+pub fn public_key(scalar: &[u8; 32]) -> [u8; 32] {
+    let mut base = [0u8; 32];
+    base[0] = 9;
+    x25519(scalar, &base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    fn bytes(text: &str) -> [u8; 32] {
+        hex(text).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc7748_vectors() {
+        let scalar = bytes("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4");
+        let u = bytes("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+        assert_eq!(x25519(&scalar, &u), bytes("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"));
+
+        let alice = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        assert_eq!(public_key(&alice), bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        let shared = x25519(&alice, &public_key(&bob));
+        assert_eq!(shared, bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"));
+        assert_eq!(shared, x25519(&bob, &public_key(&alice)));
+    }
+}
@@ -0,0 +1,146 @@
+//! Propriedades das AEADs com chaves, nonces, AAD e textos aleatórios
+
+use avila_tls::cipher_suite::aes_gcm::AesGcm;
+use avila_tls::cipher_suite::chacha20_poly1305::ChaCha20Poly1305;
+use proptest::prelude::*;
+
+/// Chave gerada (as cifras não implementam `Debug`, para não vazar chaves)
+#[derive(Debug, Clone)]
+enum Key {
+    Aes128([u8; 16]),
+    Aes256([u8; 32]),
+    ChaCha([u8; 32]),
+}
+
+impl Key {
+    fn cipher(&self) -> Cipher {
+        match self {
+            Self::Aes128(key) => Cipher::Aes(AesGcm::new(key)),
+            Self::Aes256(key) => Cipher::Aes(AesGcm::new(key)),
+            Self::ChaCha(key) => Cipher::ChaCha(ChaCha20Poly1305::new(key)),
+        }
+    }
+}
+
+enum Cipher {
+    Aes(AesGcm),
+    ChaCha(ChaCha20Poly1305),
+}
+
+impl Cipher {
+    fn seal(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) {
+        match self {
+            Self::Aes(c) => c.seal(nonce, aad, data),
+            Self::ChaCha(c) => c.seal(nonce, aad, data),
+        }
+    }
+
+    fn open(&self, nonce: &[u8; 12], aad: &[u8], data: &mut Vec<u8>) -> bool {
+        match self {
+            Self::Aes(c) => c.open(nonce, aad, data),
+            Self::ChaCha(c) => c.open(nonce, aad, data),
+        }
+    }
+
+    fn seal_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8]) -> [u8; 16] {
+        match self {
+            Self::Aes(c) => c.seal_detached(nonce, aad, data),
+            Self::ChaCha(c) => c.seal_detached(nonce, aad, data),
+        }
+    }
+
+    fn open_detached(&self, nonce: &[u8; 12], aad: &[&[u8]], data: &mut [u8], tag: &[u8; 16]) -> bool {
+        match self {
+            Self::Aes(c) => c.open_detached(nonce, aad, data, tag),
+            Self::ChaCha(c) => c.open_detached(nonce, aad, data, tag),
+        }
+    }
+}
+
+/// AES-128-GCM, AES-256-GCM ou ChaCha20-Poly1305 com chave aleatória
+fn key() -> impl Strategy<Value = Key> {
+    prop_oneof![
+        any::<[u8; 16]>().prop_map(Key::Aes128),
+        any::<[u8; 32]>().prop_map(Key::Aes256),
+        any::<[u8; 32]>().prop_map(Key::ChaCha),
+    ]
+}
+
+fn bytes(max: usize) -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..max)
+}
+
+proptest! {
+    #[test]
+    fn round_trip(key in key(), nonce in any::<[u8; 12]>(), aad in bytes(64), plaintext in bytes(600)) {
+        let cipher = key.cipher();
+        let mut data = plaintext.clone();
+        cipher.seal(&nonce, &aad, &mut data);
+        prop_assert_eq!(data.len(), plaintext.len() + 16);
+        prop_assert!(cipher.open(&nonce, &aad, &mut data));
+        prop_assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn detached_tag_with_split_aad(
+        key in key(),
+        nonce in any::<[u8; 12]>(),
+        aad in bytes(80),
+        plaintext in bytes(300),
+        cuts in prop::collection::vec(any::<prop::sample::Index>(), 0..4),
+    ) {
+        let cipher = key.cipher();
+        let mut sealed = plaintext.clone();
+        cipher.seal(&nonce, &aad, &mut sealed);
+
+        // AAD cortada em pedaços arbitrários (inclusive vazios)
+        let mut cuts: Vec<usize> = cuts.iter().map(|cut| cut.index(aad.len() + 1)).collect();
+        cuts.sort_unstable();
+        let mut parts = Vec::new();
+        let mut start = 0;
+        for cut in cuts {
+            parts.push(&aad[start..cut]);
+            start = cut;
+        }
+        parts.push(&aad[start..]);
+
+        let mut data = plaintext.clone();
+        let tag = cipher.seal_detached(&nonce, &parts, &mut data);
+        prop_assert_eq!([&data[..], &tag[..]].concat(), sealed);
+        prop_assert!(cipher.open_detached(&nonce, &parts, &mut data, &tag));
+        prop_assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn tampering_is_rejected(
+        key in key(),
+        nonce in any::<[u8; 12]>(),
+        aad in bytes(32),
+        plaintext in bytes(200),
+        bit in any::<prop::sample::Index>(),
+    ) {
+        let cipher = key.cipher();
+        let mut sealed = plaintext;
+        cipher.seal(&nonce, &aad, &mut sealed);
+
+        let mut tampered = sealed.clone();
+        let bit = bit.index(tampered.len() * 8);
+        tampered[bit / 8] ^= 1 << (bit % 8);
+        let before = tampered.clone();
+        prop_assert!(!cipher.open(&nonce, &aad, &mut tampered));
+        prop_assert_eq!(tampered, before);
+
+        let mut wrong_aad = aad.clone();
+        wrong_aad.push(0);
+        prop_assert!(!cipher.open(&nonce, &wrong_aad, &mut sealed.clone()));
+        let mut wrong_nonce = nonce;
+        wrong_nonce[bit % 12] ^= 1;
+        prop_assert!(!cipher.open(&wrong_nonce, &aad, &mut sealed));
+    }
+
+    #[test]
+    fn arbitrary_ciphertext_never_panics(key in key(), nonce in any::<[u8; 12]>(), data in bytes(64)) {
+        let mut data = data;
+        let _ = key.cipher().open(&nonce, &[], &mut data);
+    }
+}
@@ -0,0 +1,386 @@
+//! W3C Trace Context (`traceparent` / `tracestate`) propagation
+//!
+//! A [`TraceContext`] identifies the current span inside a distributed trace.
+//! Clients inject it into outgoing requests with [`TraceContext::traceparent`],
+//! servers extract it with [`TraceContext::from_headers`] and continue the
+//! trace with [`TraceContext::child`].
+//!
+//! The "current" context is kept per thread: [`TraceContext::scope`] sets it
+//! for a closure and [`TraceContext::instrument`] sets it for every poll of a
+//! future, so code running inside a request handler can pick it up with
+//! [`TraceContext::current`] without threading it through every call.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+
+/// Header carrying version, trace id, parent span id and flags
+pub const TRACEPARENT: &str = "traceparent";
+
+/// Header carrying vendor-specific key/value pairs
+pub const TRACESTATE: &str = "tracestate";
+
+/// Maximum number of `tracestate` members kept
+const MAX_STATE_MEMBERS: usize = 32;
+
+/// 128-bit trace identifier; all zeros is invalid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceId(pub u128);
+
+impl TraceId {
+    pub fn random() -> Self {
+        Self(((random_u64() as u128) << 64) | random_u64() as u128)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// 64-bit span identifier; all zeros is invalid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpanId(pub u64);
+
+impl SpanId {
+    pub fn random() -> Self {
+        Self(random_u64())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// `trace-flags` byte of `traceparent`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TraceFlags(pub u8);
+
+impl TraceFlags {
+    pub const SAMPLED: TraceFlags = TraceFlags(0x01);
+
+    pub fn is_sampled(&self) -> bool {
+        self.0 & Self::SAMPLED.0 != 0
+    }
+}
+
+/// Ordered `tracestate` members, most recently updated first
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceState(Vec<(String, String)>);
+
+impl TraceState {
+    /// Parses a `tracestate` header, dropping malformed and duplicate members
+    pub fn parse(header: &str) -> Self {
+        let mut members: Vec<(String, String)> = Vec::new();
+        for member in header.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let Some((key, value)) = member.split_once('=') else {
+                continue;
+            };
+            if !valid_state_key(key) || !valid_state_value(value) || members.iter().any(|(k, _)| k == key) {
+                continue;
+            }
+            if members.len() == MAX_STATE_MEMBERS {
+                break;
+            }
+            members.push((key.to_string(), value.to_string()));
+        }
+        Self(members)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Sets `key`, moving it to the front as the spec requires for updates
+    ///
+    /// Invalid keys or values are ignored.
+    pub fn insert(&mut self, key: &str, value: &str) {
+        if !valid_state_key(key) || !valid_state_value(value) {
+            return;
+        }
+        self.0.retain(|(k, _)| k != key);
+        self.0.insert(0, (key.to_string(), value.to_string()));
+        self.0.truncate(MAX_STATE_MEMBERS);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Header value (`key1=value1,key2=value2`)
+    pub fn header(&self) -> String {
+        self.0
+            .iter()
+            .map(|(k, v)| alloc::format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn valid_state_key(key: &str) -> bool {
+    let (tenant, system) = match key.split_once('@') {
+        Some((tenant, system)) => (tenant, Some(system)),
+        None => (key, None),
+    };
+    let simple = |part: &str, max: usize| {
+        !part.is_empty()
+            && part.len() <= max
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'*' | b'/'))
+    };
+    match system {
+        None => simple(tenant, 256) && tenant.as_bytes()[0].is_ascii_lowercase(),
+        Some(system) => {
+            simple(tenant, 241) && simple(system, 14) && system.as_bytes()[0].is_ascii_lowercase()
+        }
+    }
+}
+
+fn valid_state_value(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 256
+        && !value.ends_with(' ')
+        && value.bytes().all(|b| (0x20..=0x7E).contains(&b) && b != b',' && b != b'=')
+}
+
+/// Position of the current span inside a distributed trace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: TraceId,
+    pub span_id: SpanId,
+    pub flags: TraceFlags,
+    pub state: TraceState,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<TraceContext>> = const { RefCell::new(None) };
+}
+
+impl TraceContext {
+    /// Starts a new, sampled trace
+    pub fn root() -> Self {
+        Self {
+            trace_id: TraceId::random(),
+            span_id: SpanId::random(),
+            flags: TraceFlags::SAMPLED,
+            state: TraceState::default(),
+        }
+    }
+
+    /// Context for a span whose parent is `self`: same trace, new span id
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: SpanId::random(),
+            flags: self.flags,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Extracts the remote parent from request headers
+    ///
+    /// Returns `None` for a missing or invalid `traceparent`; `tracestate` is
+    /// only honoured together with a valid `traceparent`.
+    pub fn from_headers(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let (trace_id, span_id, flags) = parse_traceparent(traceparent.trim())?;
+        Some(Self {
+            trace_id,
+            span_id,
+            flags,
+            state: tracestate.map(TraceState::parse).unwrap_or_default(),
+        })
+    }
+
+    /// `traceparent` header value (version `00`)
+    pub fn traceparent(&self) -> String {
+        alloc::format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, self.flags.0)
+    }
+
+    /// `tracestate` header value, if there is any state to propagate
+    pub fn tracestate(&self) -> Option<String> {
+        (!self.state.is_empty()).then(|| self.state.header())
+    }
+
+    /// Context set by the innermost enclosing [`scope`](Self::scope) or
+    /// [`instrument`](Self::instrument) on this thread
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` with `self` as the current context
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = CurrentGuard::set(self.clone());
+        f()
+    }
+
+    /// Makes `self` the current context whenever `future` is polled
+    pub fn instrument<F: Future>(self, future: F) -> Instrumented<F> {
+        Instrumented {
+            future: Box::pin(future),
+            context: self,
+        }
+    }
+}
+
+/// Restores the previous current context on drop, even when unwinding
+struct CurrentGuard(Option<TraceContext>);
+
+impl CurrentGuard {
+    fn set(context: TraceContext) -> Self {
+        Self(CURRENT.with(|current| current.replace(Some(context))))
+    }
+}
+
+impl Drop for CurrentGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Future returned by [`TraceContext::instrument`]
+pub struct Instrumented<F> {
+    future: Pin<Box<F>>,
+    context: TraceContext,
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _guard = CurrentGuard::set(self.context.clone());
+        self.future.as_mut().poll(cx)
+    }
+}
+
+fn parse_traceparent(header: &str) -> Option<(TraceId, SpanId, TraceFlags)> {
+    let bytes = header.as_bytes();
+    if !header.is_ascii() || bytes.len() < 55 || [2, 35, 52].iter().any(|&i| bytes[i] != b'-') {
+        return None;
+    }
+    let version = hex_field(&header[0..2])? as u8;
+    // Version 00 is exactly 55 characters; later versions may append fields
+    match version {
+        0xFF => return None,
+        0x00 if bytes.len() != 55 => return None,
+        _ if bytes.len() > 55 && bytes[55] != b'-' => return None,
+        _ => {}
+    }
+
+    let trace_id = TraceId(hex_field(&header[3..35])?);
+    let span_id = SpanId(hex_field(&header[36..52])? as u64);
+    let flags = TraceFlags(hex_field(&header[53..55])? as u8);
+    (trace_id.is_valid() && span_id.is_valid()).then_some((trace_id, span_id, flags))
+}
+
+/// Lowercase hex only, as the spec requires
+fn hex_field(field: &str) -> Option<u128> {
+    if !field.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return None;
+    }
+    u128::from_str_radix(field, 16).ok()
+}
+
+/// Non-zero identifier from a randomly keyed SipHash of a counter and the clock
+fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hasher.write_u128(now.as_nanos());
+        }
+        let id = hasher.finish();
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let context = TraceContext::from_headers(EXAMPLE, Some("rojo=00f067aa0ba902b7, congo=t61rcWkgMzE")).unwrap();
+        assert_eq!(context.trace_id, TraceId(0x4bf92f3577b34da6a3ce929d0e0e4736));
+        assert_eq!(context.span_id, SpanId(0x00f067aa0ba902b7));
+        assert!(context.flags.is_sampled());
+        assert_eq!(context.traceparent(), EXAMPLE);
+        assert_eq!(context.tracestate().unwrap(), "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE");
+
+        let child = context.child();
+        assert_eq!(child.trace_id, context.trace_id);
+        assert_ne!(child.span_id, context.span_id);
+        assert_eq!(child.state, context.state);
+    }
+
+    #[test]
+    fn test_rejects_invalid_traceparent() {
+        for header in [
+            "",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00_4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(TraceContext::from_headers(header, None).is_none(), "{}", header);
+        }
+        // Future versions may carry extra fields after the flags
+        let future = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra";
+        assert!(!TraceContext::from_headers(future, None).unwrap().flags.is_sampled());
+    }
+
+    #[test]
+    fn test_tracestate_members() {
+        let mut state = TraceState::parse("a=1,INVALID=2,b@vendor=3,a=dup,c=");
+        assert_eq!(state.header(), "a=1,b@vendor=3");
+        state.insert("b@vendor", "4");
+        state.insert("bad key", "x");
+        assert_eq!(state.header(), "b@vendor=4,a=1");
+        assert_eq!(state.get("a"), Some("1"));
+    }
+
+    #[test]
+    fn test_current_scope() {
+        assert!(TraceContext::current().is_none());
+        let outer = TraceContext::root();
+        let inner = outer.child();
+        outer.scope(|| {
+            assert_eq!(TraceContext::current().as_ref(), Some(&outer));
+            inner.scope(|| assert_eq!(TraceContext::current().as_ref(), Some(&inner)));
+            assert_eq!(TraceContext::current().as_ref(), Some(&outer));
+        });
+        assert!(TraceContext::current().is_none());
+
+        let mut future = core::pin::pin!(inner.clone().instrument(async { TraceContext::current() }));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(Some(inner)));
+        assert!(TraceContext::current().is_none());
+    }
+}
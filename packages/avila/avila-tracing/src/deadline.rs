@@ -0,0 +1,175 @@
+//! Request deadline propagation
+//!
+//! A [`Deadline`] is the instant by which the caller stops waiting for a
+//! response. Servers read it from the incoming [`REQUEST_TIMEOUT`] header (or
+//! from route configuration), make it current for the handler with
+//! [`Deadline::scope`] / [`Deadline::instrument`], and clients called from the
+//! handler cap their own timeout by [`Deadline::remaining`] and forward the
+//! remaining budget in the same header. A slow upstream then fails every hop
+//! at once instead of each hop waiting out its own, longer timeout.
+//!
+//! Like the current [`TraceContext`](crate::TraceContext), the current
+//! deadline is kept per thread.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Header carrying the remaining time budget, in whole milliseconds
+pub const REQUEST_TIMEOUT: &str = "x-request-timeout";
+
+/// Largest budget accepted from a header (one day); more is treated as garbage
+const MAX_HEADER_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Point in time after which the result of a request is no longer useful
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+thread_local! {
+    static CURRENT: Cell<Option<Deadline>> = const { Cell::new(None) };
+}
+
+impl Deadline {
+    pub fn at(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    /// Deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        let now = Instant::now();
+        Self(now.checked_add(timeout).unwrap_or(now + MAX_HEADER_TIMEOUT))
+    }
+
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Time left; zero once the deadline has passed
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// The earlier of the two deadlines
+    pub fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    /// Parses a [`REQUEST_TIMEOUT`] header value relative to now
+    ///
+    /// Returns `None` for anything but a plain number of milliseconds up to
+    /// one day.
+    pub fn from_header(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let timeout = Duration::from_millis(value.parse().ok()?);
+        (timeout <= MAX_HEADER_TIMEOUT).then(|| Self::after(timeout))
+    }
+
+    /// [`REQUEST_TIMEOUT`] header value with the remaining budget
+    pub fn header_value(&self) -> String {
+        self.remaining().as_millis().to_string()
+    }
+
+    /// Deadline set by the innermost enclosing [`scope`](Self::scope) or
+    /// [`instrument`](Self::instrument) on this thread
+    pub fn current() -> Option<Self> {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Runs `f` with `self` as the current deadline
+    ///
+    /// The deadline replaces any enclosing one; to only tighten it, scope
+    /// `deadline.min(current)` instead.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = CurrentGuard::set(*self);
+        f()
+    }
+
+    /// Makes `self` the current deadline whenever `future` is polled
+    pub fn instrument<F: Future>(self, future: F) -> WithDeadline<F> {
+        WithDeadline {
+            future: Box::pin(future),
+            deadline: self,
+        }
+    }
+}
+
+/// Restores the previous current deadline on drop, even when unwinding
+struct CurrentGuard(Option<Deadline>);
+
+impl CurrentGuard {
+    fn set(deadline: Deadline) -> Self {
+        Self(CURRENT.with(|current| current.replace(Some(deadline))))
+    }
+}
+
+impl Drop for CurrentGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+/// Future returned by [`Deadline::instrument`]
+pub struct WithDeadline<F> {
+    future: Pin<Box<F>>,
+    deadline: Deadline,
+}
+
+impl<F: Future> Future for WithDeadline<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _guard = CurrentGuard::set(self.deadline);
+        self.future.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header() {
+        let deadline = Deadline::from_header(" 1500 ").unwrap();
+        assert!(deadline.remaining() <= Duration::from_millis(1500));
+        assert!(deadline.remaining() > Duration::from_millis(1000));
+        assert!(deadline.header_value().parse::<u64>().unwrap() <= 1500);
+
+        for invalid in ["", "-5", "1.5", "1s", "99999999999999999999", "86400001"] {
+            assert_eq!(Deadline::from_header(invalid), None, "{:?}", invalid);
+        }
+        let expired = Deadline::at(Instant::now() - Duration::from_millis(10));
+        assert!(expired.is_expired());
+        assert_eq!(expired.header_value(), "0");
+    }
+
+    #[test]
+    fn test_current_scope() {
+        assert_eq!(Deadline::current(), None);
+
+        let outer = Deadline::after(Duration::from_secs(60));
+        let inner = Deadline::after(Duration::from_millis(100));
+        outer.scope(|| {
+            assert_eq!(Deadline::current(), Some(outer));
+            inner.scope(|| assert_eq!(Deadline::current(), Some(inner)));
+            assert_eq!(Deadline::current(), Some(outer));
+        });
+        assert_eq!(Deadline::current(), None);
+        assert_eq!(outer.min(inner), inner);
+
+        let mut future = core::pin::pin!(inner.instrument(async { Deadline::current() }));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(Some(inner)));
+        assert_eq!(Deadline::current(), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Span lifecycle hooks for instrumented clients and servers
+//!
+//! HTTP clients and servers create an [`ActiveSpan`] per request and report
+//! it to the registered [`SpanHook`]s, which can export or log it.
+
+use crate::context::{SpanId, TraceContext};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Role of a span in a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Outgoing request
+    Client,
+    /// Handling of an incoming request
+    Server,
+    Internal,
+}
+
+/// Snapshot of a span passed to hooks
+#[derive(Clone, Debug)]
+pub struct SpanData {
+    pub name: String,
+    pub kind: SpanKind,
+    pub context: TraceContext,
+    /// Span id of the (possibly remote) parent; `None` for a root span
+    pub parent: Option<SpanId>,
+    pub start: SystemTime,
+    /// Set once the span has ended
+    pub duration: Option<Duration>,
+    /// Protocol status (the HTTP status code for HTTP spans)
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Receives span start and end notifications
+///
+/// Hooks run inline on the request path and should return quickly.
+pub trait SpanHook: Send + Sync {
+    fn on_start(&self, _span: &SpanData) {}
+
+    fn on_end(&self, _span: &SpanData) {}
+}
+
+/// A span in progress; ends when [`end`](ActiveSpan::end) is called or on drop
+pub struct ActiveSpan {
+    data: SpanData,
+    started: Instant,
+    hooks: Vec<Arc<dyn SpanHook>>,
+    ended: bool,
+}
+
+impl ActiveSpan {
+    /// Starts a span as a child of `parent`, or as the root of a new trace
+    pub fn start(name: impl Into<String>, kind: SpanKind, parent: Option<&TraceContext>, hooks: &[Arc<dyn SpanHook>]) -> Self {
+        let context = match parent {
+            Some(parent) => parent.child(),
+            None => TraceContext::root(),
+        };
+        let data = SpanData {
+            name: name.into(),
+            kind,
+            context,
+            parent: parent.map(|p| p.span_id),
+            start: SystemTime::now(),
+            duration: None,
+            status: None,
+            error: None,
+            attributes: Vec::new(),
+        };
+        for hook in hooks {
+            hook.on_start(&data);
+        }
+        Self {
+            data,
+            started: Instant::now(),
+            hooks: hooks.to_vec(),
+            ended: false,
+        }
+    }
+
+    /// Context to propagate to downstream calls
+    pub fn context(&self) -> &TraceContext {
+        &self.data.context
+    }
+
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.data.attributes.push((key.into(), value.into()));
+    }
+
+    pub fn set_status(&mut self, status: u16) {
+        self.data.status = Some(status);
+    }
+
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        self.data.error = Some(error.into());
+    }
+
+    pub fn end(mut self) {
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        if self.ended {
+            return;
+        }
+        self.ended = true;
+        self.data.duration = Some(self.started.elapsed());
+        for hook in &self.hooks {
+            hook.on_end(&self.data);
+        }
+    }
+}
+
+impl Drop for ActiveSpan {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(&'static str, SpanData)>>);
+
+    impl SpanHook for Recorder {
+        fn on_start(&self, span: &SpanData) {
+            self.0.lock().unwrap().push(("start", span.clone()));
+        }
+
+        fn on_end(&self, span: &SpanData) {
+            self.0.lock().unwrap().push(("end", span.clone()));
+        }
+    }
+
+    #[test]
+    fn test_span_lifecycle() {
+        let recorder = Arc::new(Recorder::default());
+        let hooks: Vec<Arc<dyn SpanHook>> = vec![recorder.clone()];
+        let parent = TraceContext::root();
+
+        let mut span = ActiveSpan::start("GET /jobs", SpanKind::Server, Some(&parent), &hooks);
+        span.set_status(200);
+        let context = span.context().clone();
+        drop(span);
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        let (phase, data) = &events[1];
+        assert_eq!(*phase, "end");
+        assert_eq!(data.parent, Some(parent.span_id));
+        assert_eq!(data.context, context);
+        assert_eq!(data.context.trace_id, parent.trace_id);
+        assert_eq!(data.status, Some(200));
+        assert!(data.duration.is_some());
+
+        let root = ActiveSpan::start("job", SpanKind::Internal, None, &[]);
+        assert!(root.context().trace_id.is_valid());
+        root.end();
+    }
+}
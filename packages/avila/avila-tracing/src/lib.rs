@@ -1,5 +1,14 @@
 ﻿//! # avila-tracing - Distributed Tracing
 extern crate alloc;
+
+pub mod context;
+pub mod deadline;
+pub mod hook;
+
+pub use context::{SpanId, TraceContext, TraceFlags, TraceId, TraceState, TRACEPARENT, TRACESTATE};
+pub use deadline::{Deadline, WithDeadline, REQUEST_TIMEOUT};
+pub use hook::{ActiveSpan, SpanData, SpanHook, SpanKind};
+
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -0,0 +1,32 @@
+//! Pontos de entrada para fuzzing (feature `fuzzing`)
+//!
+//! Cada função recebe bytes arbitrários e não pode entrar em pânico; os alvos
+//! em `packages/avila/fuzz` só repassam a entrada.
+
+use crate::{Host, Url};
+
+/// Parser: uma URL aceita serializa numa forma estável, que lê de volta igual
+pub fn url(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+
+    let _ = Host::parse(input);
+    let Ok(url) = Url::parse(input) else { return };
+    let serialized = url.to_string();
+    let reparsed = Url::parse(&serialized).unwrap_or_else(|e| panic!("{:?} -> {:?}: {}", input, serialized, e));
+    assert_eq!(reparsed, url, "{:?} -> {:?}", input, serialized);
+    assert_eq!(reparsed.to_string(), serialized);
+
+    let _ = (url.query_pairs(), url.path_segments(), url.request_target(), url.authority());
+}
+
+/// Resolução de referências: a entrada é `base` e referência separadas pelo
+/// primeiro `\n`
+pub fn join(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else { return };
+    let Some((base, reference)) = input.split_once('\n') else { return };
+    let Ok(base) = Url::parse(base) else { return };
+
+    if let Ok(joined) = base.join(reference) {
+        assert_eq!(Url::parse(&joined.to_string()).as_ref(), Ok(&joined), "{:?} + {:?}", base.to_string(), reference);
+    }
+}
@@ -0,0 +1,201 @@
+//! Percent-encoding (RFC 3986, seção 2.1) e `application/x-www-form-urlencoded`
+//!
+//! Cada parte da URL aceita um conjunto diferente de caracteres literais
+//! ([`EncodeSet`]); o resto vira `%XX` com hexadecimal maiúsculo. A
+//! decodificação é tolerante: um `%` sem dois dígitos hexadecimais fica como
+//! está.
+
+/// Caracteres mantidos literais em cada parte da URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeSet {
+    /// Só os não reservados (`A-Z a-z 0-9 - . _ ~`): seguro em qualquer parte
+    Component,
+    /// Um segmento de caminho: `/` é codificado
+    PathSegment,
+    /// Caminho completo: segmentos e `/`
+    Path,
+    Query,
+    Fragment,
+    /// Usuário e senha: `:` e `@` são codificados
+    UserInfo,
+}
+
+impl EncodeSet {
+    fn keeps(self, byte: u8) -> bool {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        let sub_delim = b"!$&'()*+,;=".contains(&byte);
+        let pchar = unreserved || sub_delim || byte == b':' || byte == b'@';
+        match self {
+            EncodeSet::Component => unreserved,
+            EncodeSet::PathSegment => pchar,
+            EncodeSet::Path => pchar || byte == b'/',
+            EncodeSet::Query | EncodeSet::Fragment => pchar || byte == b'/' || byte == b'?',
+            EncodeSet::UserInfo => unreserved || sub_delim,
+        }
+    }
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+fn push_escaped(out: &mut String, byte: u8) {
+    out.push('%');
+    out.push(HEX[(byte >> 4) as usize] as char);
+    out.push(HEX[(byte & 0xf) as usize] as char);
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// `%XX` válido começando em `bytes[i]`
+fn escape_at(bytes: &[u8], i: usize) -> Option<u8> {
+    if bytes.get(i) != Some(&b'%') {
+        return None;
+    }
+    let high = hex_digit(*bytes.get(i + 1)?)?;
+    let low = hex_digit(*bytes.get(i + 2)?)?;
+    Some(high << 4 | low)
+}
+
+/// Codifica tudo o que `set` não mantém, inclusive `%`
+pub fn encode(input: &str, set: EncodeSet) -> String {
+    let mut out = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        if set.keeps(byte) {
+            out.push(byte as char);
+        } else {
+            push_escaped(&mut out, byte);
+        }
+    }
+    out
+}
+
+/// Como [`encode`], mas preserva escapes `%XX` já presentes
+///
+/// Usado ao ler URLs escritas à mão: `a b%20c` vira `a%20b%20c`.
+pub(crate) fn encode_invalid(input: &str, set: EncodeSet) -> String {
+    let bytes = input.as_bytes();
+    let mut out = String::with_capacity(input.len());
+    for (i, &byte) in bytes.iter().enumerate() {
+        if set.keeps(byte) || escape_at(bytes, i).is_some() {
+            out.push(byte as char);
+        } else {
+            push_escaped(&mut out, byte);
+        }
+    }
+    out
+}
+
+/// Decodifica `%XX` em bytes
+pub fn decode_bytes(input: &str) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match escape_at(bytes, i) {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decodifica `%XX`; sequências UTF-8 inválidas viram `U+FFFD`
+pub fn decode(input: &str) -> String {
+    String::from_utf8_lossy(&decode_bytes(input)).into_owned()
+}
+
+// ============================================================================
+// FORMULÁRIOS
+// ============================================================================
+
+/// Codifica um nome ou valor de formulário (espaço vira `+`)
+pub fn encode_form(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        match byte {
+            b' ' => out.push('+'),
+            b'*' | b'-' | b'.' | b'_' => out.push(byte as char),
+            byte if byte.is_ascii_alphanumeric() => out.push(byte as char),
+            byte => push_escaped(&mut out, byte),
+        }
+    }
+    out
+}
+
+/// Decodifica um nome ou valor de formulário (`+` vira espaço)
+pub fn decode_form(input: &str) -> String {
+    decode(&input.replace('+', " "))
+}
+
+/// Pares `chave=valor` de uma query string ou corpo de formulário, em ordem
+///
+/// Pares vazios (`a=1&&b=2`) são ignorados; uma chave sem `=` tem valor vazio.
+pub fn parse_form(input: &str) -> Vec<(String, String)> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (decode_form(key), decode_form(value)),
+            None => (decode_form(pair), String::new()),
+        })
+        .collect()
+}
+
+/// Serializa pares como `application/x-www-form-urlencoded`
+pub fn serialize_form<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> String
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", encode_form(key.as_ref()), encode_form(value.as_ref())))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(encode("a b/ç?", EncodeSet::Component), "a%20b%2F%C3%A7%3F");
+        assert_eq!(encode("a b/c", EncodeSet::Path), "a%20b/c");
+        assert_eq!(encode("a/b", EncodeSet::PathSegment), "a%2Fb");
+        assert_eq!(encode("user:p@ss", EncodeSet::UserInfo), "user%3Ap%40ss");
+        assert_eq!(encode("100%", EncodeSet::Query), "100%25");
+        assert_eq!(encode_invalid("a b%20c%zz", EncodeSet::Path), "a%20b%20c%25zz");
+
+        assert_eq!(decode("a%20b%2Fc%C3%A7"), "a b/cç");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+        assert_eq!(decode("a+b"), "a+b");
+        assert_eq!(decode_bytes("%FF"), [0xff]);
+        assert_eq!(decode("%FF"), "\u{fffd}");
+    }
+
+    #[test]
+    fn test_form() {
+        let pairs = parse_form("q=parede+externa&level=2&flag&&x=%26%3D");
+        assert_eq!(
+            pairs,
+            [
+                ("q".to_string(), "parede externa".to_string()),
+                ("level".to_string(), "2".to_string()),
+                ("flag".to_string(), String::new()),
+                ("x".to_string(), "&=".to_string()),
+            ]
+        );
+        let query = serialize_form([("q", "parede externa"), ("x", "&=~")]);
+        assert_eq!(query, "q=parede+externa&x=%26%3D%7E");
+        assert_eq!(parse_form(&query)[1].1, "&=~");
+    }
+}
@@ -0,0 +1,68 @@
+//! Throughput dos kernels de geometria usados no carregamento e no culling
+
+use avila_vec3d::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+const POINTS: usize = 100_000;
+const BOXES: usize = 10_000;
+
+/// Pontos pseudoaleatórios determinísticos em [-50, 50)³
+fn points(count: usize) -> Vec<Vec3> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 40) as f32 / (1u64 << 24) as f32 * 100.0 - 50.0
+    };
+    (0..count).map(|_| Vec3::new(next(), next(), next())).collect()
+}
+
+fn transform_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transform");
+    group.throughput(Throughput::Elements(POINTS as u64));
+    let points = points(POINTS);
+    let matrix = Mat4::translation(Vec3::new(1.0, 2.0, 3.0)).mul_mat4(&Mat4::rotation_y(0.7));
+    let quat = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 0.7).unwrap();
+
+    group.bench_function("mat4_transform_point", |b| {
+        b.iter(|| points.iter().map(|&p| matrix.transform_point(black_box(p))).fold(Vec3::ZERO, |a, p| a + p))
+    });
+    group.bench_function("quat_rotate_vec3", |b| {
+        b.iter(|| points.iter().map(|&p| quat.rotate_vec3(black_box(p))).fold(Vec3::ZERO, |a, p| a + p))
+    });
+    group.finish();
+}
+
+fn bounds_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bounds");
+    group.throughput(Throughput::Elements(POINTS as u64));
+    let points = points(POINTS);
+
+    group.bench_function("aabb_from_points", |b| b.iter(|| Aabb::from_points(black_box(&points))));
+    group.bench_function("sphere_ritter", |b| b.iter(|| BoundingSphere::from_points_ritter(black_box(&points))));
+    group.bench_function("sphere_welzl", |b| b.iter(|| BoundingSphere::from_points(black_box(&points))));
+    group.finish();
+}
+
+fn frustum_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frustum");
+    group.throughput(Throughput::Elements(BOXES as u64));
+    // Caixa de ±25 m: cerca de 1/8 dos objetos dentro
+    let frustum = Frustum::from_view_projection(&Mat4::scale(Vec3::new(0.04, 0.04, 0.04))).unwrap();
+    let boxes: Vec<Aabb> = points(BOXES).into_iter().map(|p| Aabb::new(p, p + Vec3::new(1.0, 1.0, 1.0))).collect();
+    let spheres: Vec<BoundingSphere> = boxes.iter().map(BoundingSphere::from_aabb).collect();
+
+    group.bench_function("classify_aabb", |b| {
+        b.iter(|| boxes.iter().filter(|aabb| frustum.classify_aabb(black_box(aabb)) != Containment::Outside).count())
+    });
+    group.bench_function("classify_sphere", |b| {
+        b.iter(|| {
+            spheres.iter().filter(|sphere| frustum.classify_sphere(black_box(sphere)) != Containment::Outside).count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, transform_benchmark, bounds_benchmark, frustum_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+//! Frustum de visão para culling
+//!
+//! Os seis planos são extraídos da matriz view-projection (Gribb-Hartmann),
+//! com clip space no intervalo z ∈ [-w, w] do OpenGL/WebGL. As normais
+//! apontam para dentro: distância positiva = lado visível.
+
+use crate::{Aabb, BoundingSphere, Mat4, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// PLANE
+// ============================================================================
+
+/// Plano `normal · p + d = 0` com normal unitária
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Plane {
+    pub normal: Vec3,
+    pub d: f32,
+}
+
+impl Plane {
+    #[inline]
+    pub const fn new(normal: Vec3, d: f32) -> Self {
+        Self { normal, d }
+    }
+
+    /// Normaliza `(a, b, c, d)`; `None` para normal nula
+    fn from_coefficients(a: f32, b: f32, c: f32, d: f32) -> Option<Self> {
+        let normal = Vec3::new(a, b, c);
+        let length = normal.length();
+        if length < f32::EPSILON {
+            return None;
+        }
+        Some(Self { normal: normal / length, d: d / length })
+    }
+
+    #[inline]
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(&point) + self.d
+    }
+}
+
+// ============================================================================
+// FRUSTUM
+// ============================================================================
+
+/// Resultado de um teste de volume contra o frustum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Containment {
+    Outside,
+    Intersecting,
+    Inside,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frustum {
+    /// Esquerda, direita, baixo, cima, near, far
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Frustum da matriz `projection * view` (column-major)
+    ///
+    /// Objetos em coordenadas de mundo são testados diretamente; para testar
+    /// em espaço de objeto, passe `projection * view * model`.
+    pub fn from_view_projection(matrix: &Mat4) -> crate::Result<Self> {
+        let m = &matrix.m;
+        let row = |i: usize| [m[0][i], m[1][i], m[2][i], m[3][i]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let plane = |sign: f32, r: [f32; 4]| {
+            Plane::from_coefficients(r3[0] + sign * r[0], r3[1] + sign * r[1], r3[2] + sign * r[2], r3[3] + sign * r[3])
+                .ok_or_else(|| crate::Vec3dError::InvalidMatrix("Degenerate view-projection matrix".into()))
+        };
+
+        Ok(Self {
+            planes: [
+                plane(1.0, r0)?,
+                plane(-1.0, r0)?,
+                plane(1.0, r1)?,
+                plane(-1.0, r1)?,
+                plane(1.0, r2)?,
+                plane(-1.0, r2)?,
+            ],
+        })
+    }
+
+    #[inline]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Classifica a AABB pelos vértices positivo/negativo de cada plano
+    ///
+    /// Conservador: uma caixa perto de um canto do frustum pode sair como
+    /// `Intersecting` mesmo estando fora; nunca descarta caixa visível.
+    pub fn classify_aabb(&self, aabb: &Aabb) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let n = plane.normal;
+            let (mut positive, mut negative) = (aabb.max, aabb.min);
+            if n.x < 0.0 {
+                core::mem::swap(&mut positive.x, &mut negative.x);
+            }
+            if n.y < 0.0 {
+                core::mem::swap(&mut positive.y, &mut negative.y);
+            }
+            if n.z < 0.0 {
+                core::mem::swap(&mut positive.z, &mut negative.z);
+            }
+            if plane.signed_distance(positive) < 0.0 {
+                return Containment::Outside;
+            }
+            if plane.signed_distance(negative) < 0.0 {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    #[inline]
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.classify_aabb(aabb) != Containment::Outside
+    }
+
+    pub fn classify_sphere(&self, sphere: &BoundingSphere) -> Containment {
+        if sphere.is_empty() {
+            return Containment::Outside;
+        }
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let distance = plane.signed_distance(sphere.center);
+            if distance < -sphere.radius {
+                return Containment::Outside;
+            }
+            if distance < sphere.radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    #[inline]
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.classify_sphere(sphere) != Containment::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Perspectiva OpenGL olhando para -Z a partir da origem
+    fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+        let f = 1.0 / (fov_y / 2.0).tan();
+        Mat4 {
+            m: [
+                [f / aspect, 0.0, 0.0, 0.0],
+                [0.0, f, 0.0, 0.0],
+                [0.0, 0.0, (far + near) / (near - far), -1.0],
+                [0.0, 0.0, 2.0 * far * near / (near - far), 0.0],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_planes_from_perspective() {
+        let frustum = Frustum::from_view_projection(&perspective(core::f32::consts::FRAC_PI_2, 1.0, 1.0, 100.0)).unwrap();
+        let [_, _, _, _, near, far] = frustum.planes;
+        assert_relative_eq!(near.signed_distance(Vec3::new(0.0, 0.0, -1.0)), 0.0, epsilon = 1e-4);
+        assert_relative_eq!(far.signed_distance(Vec3::new(0.0, 0.0, -100.0)), 0.0, epsilon = 1e-2);
+
+        assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(frustum.contains_point(Vec3::new(9.0, -9.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(11.0, 0.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 5.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -101.0)));
+    }
+
+    #[test]
+    fn test_classify_volumes() {
+        let view = Mat4::translation(Vec3::new(0.0, 0.0, -20.0));
+        let frustum = Frustum::from_view_projection(&perspective(1.0, 1.5, 0.1, 50.0).mul_mat4(&view)).unwrap();
+
+        let inside = Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::ONE);
+        let behind = Aabb::new(Vec3::new(-1.0, -1.0, 25.0), Vec3::new(1.0, 1.0, 30.0));
+        let crossing = Aabb::new(Vec3::new(-1.0, -1.0, 10.0), Vec3::new(1.0, 1.0, 30.0));
+        assert_eq!(frustum.classify_aabb(&inside), Containment::Inside);
+        assert_eq!(frustum.classify_aabb(&behind), Containment::Outside);
+        assert_eq!(frustum.classify_aabb(&crossing), Containment::Intersecting);
+
+        assert_eq!(frustum.classify_sphere(&BoundingSphere::new(Vec3::ZERO, 1.0)), Containment::Inside);
+        assert_eq!(frustum.classify_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 25.0), 1.0)), Containment::Outside);
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 20.5), 1.0)));
+        assert!(!frustum.intersects_sphere(&BoundingSphere::EMPTY));
+    }
+}
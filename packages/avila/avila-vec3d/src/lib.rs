@@ -6,15 +6,48 @@
 //! - Vetores 2D, 3D, 4D
 //! - Matrizes 4x4 (transformações)
 //! - Quaternions (rotações)
-//! - Bounding boxes (AABB, OBB)
+//! - Bounding boxes (AABB, OBB) e esferas envolventes (Ritter, Welzl)
+//! - Frustum de visão (planos, classificação de AABB e esferas)
 //! - Operações geométricas (interseções, projeções, etc.)
+//! - Snapping (grade, ângulo, incremento) para ferramentas de medição
 //!
 //! Tudo otimizado para performance (SIMD onde possível) e zero dependências externas pesadas.
+//!
+//! ## Features
+//! - `std` (padrão): sem ela o crate é `no_std` + `alloc` (WASM enxuto,
+//!   `thumbv7em`), e as funções de `f32`/`f64` vêm da `libm` ([`F32Ext`])
+//! - `serde` (padrão): `Serialize`/`Deserialize` em todos os tipos
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::ops::{Add, Sub, Mul, Div, Neg};
+use core::ops::{Add, Sub, Mul, Div, Neg};
+
+mod frustum;
+#[cfg(not(feature = "std"))]
+mod math;
+pub mod snap;
+mod sphere;
+
+pub use frustum::{Containment, Frustum, Plane};
+#[cfg(not(feature = "std"))]
+pub use math::{F32Ext, F64Ext};
+pub use sphere::BoundingSphere;
+
+/// O que o prelude da `std` traria, para os módulos compilarem sem ela
+#[cfg(not(feature = "std"))]
+mod prelude {
+    pub use crate::F32Ext;
+    pub use alloc::{format, string::String, vec::Vec};
+}
+
+#[cfg(not(feature = "std"))]
+use prelude::*;
 
-pub type Result<T> = std::result::Result<T, Vec3dError>;
+pub type Result<T> = core::result::Result<T, Vec3dError>;
 
 // ============================================================================
 // ERROS
@@ -39,7 +72,8 @@ pub enum Vec3dError {
 // VEC2 - Vetor 2D
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -130,7 +164,8 @@ impl Div<f32> for Vec2 {
 // VEC3 - Vetor 3D (o coração do sistema)
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -283,7 +318,8 @@ impl Neg for Vec3 {
 // VEC4 - Vetor 4D (para coordenadas homogêneas)
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vec4 {
     pub x: f32,
     pub y: f32,
@@ -320,7 +356,8 @@ impl Vec4 {
 // ============================================================================
 
 /// Matriz 4x4 em column-major order (compatível com OpenGL/glTF)
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Mat4 {
     // Armazenada como 4 colunas
     pub m: [[f32; 4]; 4],
@@ -538,7 +575,8 @@ impl Mat4 {
 // QUATERNION - Rotações eficientes
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Quat {
     pub x: f32,
     pub y: f32,
@@ -589,6 +627,21 @@ impl Quat {
         }
     }
 
+    /// Conjugado (rotação inversa para quaternions unitários)
+    #[inline]
+    pub fn conjugate(&self) -> Self {
+        Self { x: -self.x, y: -self.y, z: -self.z, w: self.w }
+    }
+
+    /// Rotaciona um vetor (quaternion unitário)
+    #[inline]
+    pub fn rotate_vec3(&self, v: Vec3) -> Vec3 {
+        // v' = v + 2w(q × v) + 2q × (q × v)
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(&v) * 2.0;
+        v + t * self.w + q.cross(&t)
+    }
+
     #[inline]
     pub fn normalize(&self) -> Result<Self> {
         let len = (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt();
@@ -608,7 +661,8 @@ impl Quat {
 // AABB - Axis-Aligned Bounding Box
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Aabb {
     pub min: Vec3,
     pub max: Vec3,
@@ -715,7 +769,8 @@ impl Aabb {
 // RAY - Raio para intersecções
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ray {
     pub origin: Vec3,
     pub direction: Vec3,
@@ -768,7 +823,7 @@ impl Ray {
                 let mut t0 = (min - origin) * inv_d;
                 let mut t1 = (max - origin) * inv_d;
                 if t0 > t1 {
-                    std::mem::swap(&mut t0, &mut t1);
+                    core::mem::swap(&mut t0, &mut t1);
                 }
                 tmin = tmin.max(t0);
                 tmax = tmax.min(t1);
@@ -789,6 +844,8 @@ impl Ray {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
     use approx::assert_relative_eq;
 
     #[test]
@@ -0,0 +1,90 @@
+//! Funções de `f32` que só existem com `std`
+//!
+//! Sem `std` (WASM enxuto, embarcados), `sqrt`, trigonometria e
+//! arredondamentos vêm da `libm`. Os métodos têm os mesmos nomes dos
+//! inerentes de `f32`, então o código chama `x.sqrt()` nos dois casos; com
+//! `std` este módulo não é compilado e os inerentes são usados.
+
+/// Métodos de `f32` implementados com `libm`
+pub trait F32Ext: Sized {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn tan(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+}
+
+impl F32Ext for f32 {
+    #[inline]
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    #[inline]
+    fn sin(self) -> f32 {
+        libm::sinf(self)
+    }
+
+    #[inline]
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (f32, f32) {
+        libm::sincosf(self)
+    }
+
+    #[inline]
+    fn tan(self) -> f32 {
+        libm::tanf(self)
+    }
+
+    #[inline]
+    fn acos(self) -> f32 {
+        libm::acosf(self)
+    }
+
+    #[inline]
+    fn atan2(self, other: f32) -> f32 {
+        libm::atan2f(self, other)
+    }
+
+    #[inline]
+    fn floor(self) -> f32 {
+        libm::floorf(self)
+    }
+
+    #[inline]
+    fn ceil(self) -> f32 {
+        libm::ceilf(self)
+    }
+
+    #[inline]
+    fn round(self) -> f32 {
+        libm::roundf(self)
+    }
+
+    #[inline]
+    fn powi(self, n: i32) -> f32 {
+        libm::powf(self, n as f32)
+    }
+}
+
+/// Métodos de `f64` implementados com `libm` (acumuladores de estatísticas)
+pub trait F64Ext {
+    fn sqrt(self) -> Self;
+}
+
+impl F64Ext for f64 {
+    #[inline]
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+}
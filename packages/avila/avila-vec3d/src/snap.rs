@@ -0,0 +1,268 @@
+//! Snapping para ferramentas de medição e posicionamento
+//!
+//! - Arredondamento para incrementos (`0.05` → múltiplos de 5 cm)
+//! - Snap angular em passos de N graus
+//! - Ponto mais próximo de uma grade com origem, rotação e espaçamento configuráveis
+
+use crate::{Quat, Result, Vec3, Vec3dError};
+// Só `F32Ext`; se outro crate do grafo liga a `std` (harness de teste,
+// dev-dependências) os métodos inerentes de `f32` vencem e o import sobra
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::prelude::*;
+
+// ============================================================================
+// INCREMENTOS
+// ============================================================================
+
+/// Arredonda para o múltiplo mais próximo de `increment`
+///
+/// Incrementos não positivos retornam o valor inalterado.
+#[inline]
+pub fn round_to_increment(value: f32, increment: f32) -> f32 {
+    if increment > 0.0 {
+        (value / increment).round() * increment
+    } else {
+        value
+    }
+}
+
+/// Arredonda para baixo (múltiplo de `increment` ≤ `value`)
+#[inline]
+pub fn floor_to_increment(value: f32, increment: f32) -> f32 {
+    if increment > 0.0 {
+        (value / increment).floor() * increment
+    } else {
+        value
+    }
+}
+
+/// Arredonda para cima (múltiplo de `increment` ≥ `value`)
+#[inline]
+pub fn ceil_to_increment(value: f32, increment: f32) -> f32 {
+    if increment > 0.0 {
+        (value / increment).ceil() * increment
+    } else {
+        value
+    }
+}
+
+/// Arredonda cada componente para o incremento
+#[inline]
+pub fn snap_vec3(v: Vec3, increment: f32) -> Vec3 {
+    Vec3::new(
+        round_to_increment(v.x, increment),
+        round_to_increment(v.y, increment),
+        round_to_increment(v.z, increment),
+    )
+}
+
+/// Ajusta o comprimento de um segmento para um incremento, mantendo a direção
+///
+/// Útil para medições: arrastar de `start` até `end` produz comprimentos
+/// múltiplos de `increment`.
+pub fn snap_length(start: Vec3, end: Vec3, increment: f32) -> Vec3 {
+    let delta = end - start;
+    let length = delta.length();
+    if length < f32::EPSILON {
+        return end;
+    }
+    start + delta * (round_to_increment(length, increment) / length)
+}
+
+// ============================================================================
+// ÂNGULOS
+// ============================================================================
+
+/// Arredonda um ângulo (radianos) para passos de `step_deg` graus
+#[inline]
+pub fn snap_angle(angle_rad: f32, step_deg: f32) -> f32 {
+    round_to_increment(angle_rad, step_deg.to_radians())
+}
+
+/// Gira `direction` no plano de normal `axis` até o passo angular mais próximo
+///
+/// Os ângulos são medidos a partir de `reference` (projetado no plano). A
+/// componente de `direction` fora do plano é descartada; o comprimento da
+/// projeção no plano é preservado.
+pub fn snap_direction(direction: Vec3, axis: Vec3, reference: Vec3, step_deg: f32) -> Result<Vec3> {
+    let axis = axis.normalize()?;
+    let u = (reference - axis * reference.dot(&axis))
+        .normalize()
+        .map_err(|_| Vec3dError::GeometryError("Reference direction is parallel to the snap axis".into()))?;
+    let v = axis.cross(&u);
+
+    let planar = direction - axis * direction.dot(&axis);
+    let length = planar.length();
+    if length < f32::EPSILON {
+        return Err(Vec3dError::InvalidVector("Direction is parallel to the snap axis".into()));
+    }
+
+    let angle = snap_angle(planar.dot(&v).atan2(planar.dot(&u)), step_deg);
+    let (sin, cos) = angle.sin_cos();
+    Ok((u * cos + v * sin) * length)
+}
+
+/// Snap de `end` em torno de `start` no plano XY (passos de `step_deg` a partir de +X)
+///
+/// Caso típico de desenho em planta: linhas a 0°, 45°, 90°...
+pub fn snap_segment_angle(start: Vec3, end: Vec3, step_deg: f32) -> Vec3 {
+    snap_direction(end - start, Vec3::Z, Vec3::X, step_deg)
+        .map(|d| start + d + Vec3::Z * (end.z - start.z))
+        .unwrap_or(end)
+}
+
+// ============================================================================
+// GRADE
+// ============================================================================
+
+/// Grade de referência com origem, rotação e espaçamento por eixo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    pub origin: Vec3,
+    /// Rotação da grade em relação ao mundo (quaternion unitário)
+    pub rotation: Quat,
+    /// Espaçamento por eixo local (≤ 0 desativa o snap no eixo)
+    pub spacing: Vec3,
+}
+
+impl Grid {
+    /// Grade alinhada aos eixos do mundo, com espaçamento uniforme
+    pub fn new(spacing: f32) -> Self {
+        Self {
+            origin: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            spacing: Vec3::new(spacing, spacing, spacing),
+        }
+    }
+
+    /// Grade de planta: snap apenas em X/Y locais
+    pub fn planar(spacing: f32) -> Self {
+        Self {
+            spacing: Vec3::new(spacing, spacing, 0.0),
+            ..Self::new(spacing)
+        }
+    }
+
+    pub fn with_origin(mut self, origin: Vec3) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Result<Self> {
+        self.rotation = rotation.normalize()?;
+        Ok(self)
+    }
+
+    /// Rotação em torno de Z (grade de planta girada)
+    pub fn with_angle_z(self, angle_rad: f32) -> Self {
+        Self {
+            rotation: Quat::from_axis_angle(Vec3::Z, angle_rad).unwrap_or(Quat::IDENTITY),
+            ..self
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: Vec3) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Mundo → coordenadas locais da grade
+    #[inline]
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        self.rotation.conjugate().rotate_vec3(point - self.origin)
+    }
+
+    /// Coordenadas locais da grade → mundo
+    #[inline]
+    pub fn to_world(&self, local: Vec3) -> Vec3 {
+        self.origin + self.rotation.rotate_vec3(local)
+    }
+
+    /// Ponto da grade mais próximo
+    pub fn snap(&self, point: Vec3) -> Vec3 {
+        let local = self.to_local(point);
+        self.to_world(Vec3::new(
+            round_to_increment(local.x, self.spacing.x),
+            round_to_increment(local.y, self.spacing.y),
+            round_to_increment(local.z, self.spacing.z),
+        ))
+    }
+
+    /// Snap apenas se o ponto da grade estiver a até `tolerance` (mundo)
+    pub fn snap_within(&self, point: Vec3, tolerance: f32) -> Option<Vec3> {
+        let snapped = self.snap(point);
+        (snapped.distance_squared(&point) <= tolerance * tolerance).then_some(snapped)
+    }
+
+    /// Índice inteiro da célula mais próxima (eixos desativados → 0)
+    pub fn cell(&self, point: Vec3) -> [i32; 3] {
+        let local = self.to_local(point);
+        let index = |value: f32, spacing: f32| {
+            if spacing > 0.0 {
+                (value / spacing).round() as i32
+            } else {
+                0
+            }
+        };
+        [
+            index(local.x, self.spacing.x),
+            index(local.y, self.spacing.y),
+            index(local.z, self.spacing.z),
+        ]
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_increments() {
+        assert_relative_eq!(round_to_increment(1.26, 0.05), 1.25);
+        assert_relative_eq!(floor_to_increment(1.29, 0.1), 1.2, epsilon = 1e-6);
+        assert_relative_eq!(ceil_to_increment(1.21, 0.1), 1.3, epsilon = 1e-6);
+        assert_eq!(round_to_increment(1.26, 0.0), 1.26);
+
+        let end = snap_length(Vec3::ZERO, Vec3::new(3.0, 4.1, 0.0), 0.5);
+        assert_relative_eq!(end.length(), 5.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_angle_snap() {
+        assert_relative_eq!(snap_angle(47f32.to_radians(), 15.0), 45f32.to_radians());
+
+        let end = snap_segment_angle(Vec3::new(1.0, 1.0, 2.0), Vec3::new(3.0, 1.1, 2.5), 45.0);
+        assert_relative_eq!(end.y, 1.0, epsilon = 1e-5);
+        assert_relative_eq!(end.z, 2.5);
+
+        let dir = snap_direction(Vec3::new(0.9, 1.0, 0.0), Vec3::Z, Vec3::X, 90.0).unwrap();
+        assert_relative_eq!(dir.x, 0.0, epsilon = 1e-5);
+        assert!(dir.y > 0.0);
+        assert!(snap_direction(Vec3::Z, Vec3::Z, Vec3::X, 15.0).is_err());
+    }
+
+    #[test]
+    fn test_rotated_grid() {
+        let grid = Grid::planar(1.0)
+            .with_origin(Vec3::new(10.0, 0.0, 0.0))
+            .with_angle_z(45f32.to_radians());
+
+        // Ponto local (1, 1) da grade
+        let target = grid.to_world(Vec3::new(1.0, 1.0, 0.0));
+        let snapped = grid.snap(target + Vec3::new(0.1, -0.05, 0.3));
+        assert_relative_eq!(snapped.x, target.x, epsilon = 1e-5);
+        assert_relative_eq!(snapped.y, target.y, epsilon = 1e-5);
+        assert_relative_eq!(snapped.z, 0.3, epsilon = 1e-5); // Z livre
+
+        assert_eq!(grid.cell(target), [1, 1, 0]);
+        assert!(grid.snap_within(target + Vec3::X * 0.4, 0.1).is_none());
+    }
+}
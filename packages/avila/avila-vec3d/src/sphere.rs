@@ -0,0 +1,344 @@
+//! Esferas envolventes para LOD e testes de frustum
+//!
+//! - [`BoundingSphere::from_points_ritter`]: aproximação de Ritter, duas
+//!   passadas, até ~5-20% maior que a ótima
+//! - [`BoundingSphere::from_points`]: esfera mínima exata (Welzl), tempo
+//!   linear esperado
+
+use crate::{Aabb, Mat4, Vec3};
+// Só `F32Ext`; se outro crate do grafo liga a `std` (harness de teste,
+// dev-dependências) os métodos inerentes de `f32` vencem e o import sobra
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Folga relativa nos testes de contenção (erros de arredondamento em f32)
+const TOLERANCE: f32 = 1e-5;
+
+// ============================================================================
+// BOUNDING SPHERE
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    /// Negativo para a esfera vazia
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub const EMPTY: Self = Self { center: Vec3::ZERO, radius: -1.0 };
+
+    #[inline]
+    pub const fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.radius < 0.0
+    }
+
+    /// Esfera aproximada de Ritter: rápida, nunca menor que a mínima
+    pub fn from_points_ritter(points: &[Vec3]) -> Self {
+        let Some(&first) = points.first() else {
+            return Self::EMPTY;
+        };
+        // Diâmetro inicial: ponto mais distante do primeiro e o mais distante deste
+        let farthest = |from: Vec3| {
+            points
+                .iter()
+                .copied()
+                .max_by(|a, b| a.distance_squared(&from).total_cmp(&b.distance_squared(&from)))
+                .unwrap_or(from)
+        };
+        let y = farthest(first);
+        let z = farthest(y);
+        let mut sphere = Self::from_diameter(y, z);
+        for &point in points {
+            sphere.expand_point(point);
+        }
+        sphere
+    }
+
+    /// Esfera mínima exata (algoritmo de Welzl, versão iterativa)
+    ///
+    /// A ordem dos pontos é embaralhada de forma determinística para manter o
+    /// tempo linear esperado mesmo com entradas ordenadas.
+    pub fn from_points(points: &[Vec3]) -> Self {
+        let mut points = points.to_vec();
+        shuffle(&mut points);
+
+        let mut sphere = Self::EMPTY;
+        for i in 0..points.len() {
+            if !sphere.contains_point(points[i]) {
+                sphere = Self::with_boundary_1(&points[..i], points[i]);
+            }
+        }
+        sphere
+    }
+
+    /// Menor esfera com `q1` na borda contendo `points`
+    fn with_boundary_1(points: &[Vec3], q1: Vec3) -> Self {
+        let mut sphere = Self::new(q1, 0.0);
+        for j in 0..points.len() {
+            if !sphere.contains_point(points[j]) {
+                sphere = Self::with_boundary_2(&points[..j], q1, points[j]);
+            }
+        }
+        sphere
+    }
+
+    fn with_boundary_2(points: &[Vec3], q1: Vec3, q2: Vec3) -> Self {
+        let mut sphere = Self::from_diameter(q1, q2);
+        for k in 0..points.len() {
+            if !sphere.contains_point(points[k]) {
+                sphere = Self::with_boundary_3(&points[..k], q1, q2, points[k]);
+            }
+        }
+        sphere
+    }
+
+    fn with_boundary_3(points: &[Vec3], q1: Vec3, q2: Vec3, q3: Vec3) -> Self {
+        let mut sphere = Self::circumscribed_3(q1, q2, q3);
+        for &point in points {
+            if !sphere.contains_point(point) {
+                sphere = Self::circumscribed_4(q1, q2, q3, point);
+            }
+        }
+        sphere
+    }
+
+    #[inline]
+    fn from_diameter(a: Vec3, b: Vec3) -> Self {
+        Self::new((a + b) * 0.5, a.distance(&b) * 0.5)
+    }
+
+    /// Esfera com `a`, `b` e `c` no círculo máximo
+    fn circumscribed_3(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        let ab = b - a;
+        let ac = c - a;
+        let normal = ab.cross(&ac);
+        let denominator = 2.0 * normal.length_squared();
+        if denominator <= f32::EPSILON * ab.length_squared().max(ac.length_squared()).powi(2) {
+            // Colineares: o segmento mais longo é o diâmetro
+            return [Self::from_diameter(a, b), Self::from_diameter(a, c), Self::from_diameter(b, c)]
+                .into_iter()
+                .max_by(|x, y| x.radius.total_cmp(&y.radius))
+                .unwrap_or(Self::EMPTY);
+        }
+        let offset = (ac * ab.length_squared() - ab * ac.length_squared()).cross(&normal) / denominator;
+        Self::new(a + offset, offset.length())
+    }
+
+    /// Esfera circunscrita ao tetraedro `abcd`
+    fn circumscribed_4(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Self {
+        let ab = b - a;
+        let ac = c - a;
+        let ad = d - a;
+        let denominator = 2.0 * ab.dot(&ac.cross(&ad));
+        let scale = ab.length() * ac.length() * ad.length();
+        if denominator.abs() <= f32::EPSILON * scale {
+            // Coplanares: a esfera dos três primeiros, crescida até `d`
+            let mut sphere = Self::circumscribed_3(a, b, c);
+            sphere.expand_point(d);
+            return sphere;
+        }
+        let offset = (ac.cross(&ad) * ab.length_squared()
+            + ad.cross(&ab) * ac.length_squared()
+            + ab.cross(&ac) * ad.length_squared())
+            / denominator;
+        Self::new(a + offset, offset.length())
+    }
+
+    /// Cresce o mínimo necessário para conter `point` (passo de Ritter)
+    pub fn expand_point(&mut self, point: Vec3) {
+        if self.is_empty() {
+            *self = Self::new(point, 0.0);
+            return;
+        }
+        let distance = self.center.distance(&point);
+        if distance <= self.radius {
+            return;
+        }
+        let radius = (self.radius + distance) * 0.5;
+        self.center = self.center + (point - self.center) * ((radius - self.radius) / distance);
+        self.radius = radius;
+    }
+
+    #[inline]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let limit = self.radius + TOLERANCE * self.radius.max(1.0);
+        self.center.distance_squared(&point) <= limit * limit
+    }
+
+    /// Se `other` está inteiramente dentro desta esfera
+    #[inline]
+    pub fn contains_sphere(&self, other: &Self) -> bool {
+        other.is_empty()
+            || (!self.is_empty()
+                && self.center.distance(&other.center) + other.radius
+                    <= self.radius + TOLERANCE * self.radius.max(1.0))
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+        let radii = self.radius + other.radius;
+        self.center.distance_squared(&other.center) <= radii * radii
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let closest = Vec3::new(
+            self.center.x.clamp(aabb.min.x, aabb.max.x),
+            self.center.y.clamp(aabb.min.y, aabb.max.y),
+            self.center.z.clamp(aabb.min.z, aabb.max.z),
+        );
+        self.center.distance_squared(&closest) <= self.radius * self.radius
+    }
+
+    /// Menor esfera contendo as duas esferas
+    pub fn merge(&self, other: &Self) -> Self {
+        if self.contains_sphere(other) {
+            return *self;
+        }
+        if other.contains_sphere(self) {
+            return *other;
+        }
+        let offset = other.center - self.center;
+        let distance = offset.length();
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        Self::new(self.center + offset * ((radius - self.radius) / distance), radius)
+    }
+
+    /// Transforma por uma matriz afim; o raio escala pelo maior fator dos eixos
+    pub fn transform(&self, matrix: &Mat4) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let axis_scale = |column: usize| Vec3::from_slice(&matrix.m[column]).map_or(0.0, |axis| axis.length());
+        let scale = axis_scale(0).max(axis_scale(1)).max(axis_scale(2));
+        Self::new(matrix.transform_point(self.center), self.radius * scale)
+    }
+
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        if aabb.min.x > aabb.max.x {
+            return Self::EMPTY;
+        }
+        Self::from_diameter(aabb.min, aabb.max)
+    }
+
+    pub fn to_aabb(&self) -> Aabb {
+        if self.is_empty() {
+            return Aabb::EMPTY;
+        }
+        let extent = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+}
+
+impl Default for BoundingSphere {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// Fisher-Yates com xorshift de semente fixa: mesma entrada, mesma esfera
+fn shuffle(points: &mut [Vec3]) {
+    let mut state: u32 = 0x9e37_79b9;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        points.swap(i, state as usize % (i + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Nuvem pseudoaleatória num cubo de lado 10
+    fn cloud(count: usize) -> Vec<Vec3> {
+        let mut state: u32 = 12345;
+        let mut next = move || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1u32 << 24) as f32 * 10.0
+        };
+        (0..count).map(|_| Vec3::new(next(), next(), next())).collect()
+    }
+
+    #[test]
+    fn test_exact_sphere() {
+        let tetrahedron = [
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::new(-1.0, -1.0, 1.0),
+        ];
+        let sphere = BoundingSphere::from_points(&tetrahedron);
+        assert_relative_eq!(sphere.center.length(), 0.0, epsilon = 1e-5);
+        assert_relative_eq!(sphere.radius, 3.0f32.sqrt(), epsilon = 1e-5);
+
+        // Pontos internos não mudam a esfera do diâmetro
+        let segment = [Vec3::new(-2.0, 0.0, 0.0), Vec3::ZERO, Vec3::new(0.5, 0.1, 0.0), Vec3::new(2.0, 0.0, 0.0)];
+        let sphere = BoundingSphere::from_points(&segment);
+        assert_relative_eq!(sphere.radius, 2.0, epsilon = 1e-5);
+
+        // Colineares e coplanares
+        let line = [Vec3::ZERO, Vec3::X, Vec3::X * 2.0, Vec3::X * 5.0];
+        assert_relative_eq!(BoundingSphere::from_points(&line).radius, 2.5, epsilon = 1e-5);
+        let square = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(1.0, 1.0, 0.0)];
+        assert_relative_eq!(BoundingSphere::from_points(&square).radius, 0.5f32.sqrt(), epsilon = 1e-5);
+
+        assert!(BoundingSphere::from_points(&[]).is_empty());
+        assert_eq!(BoundingSphere::from_points(&[Vec3::ONE]).radius, 0.0);
+    }
+
+    #[test]
+    fn test_ritter_encloses_and_welzl_is_tighter() {
+        let points = cloud(2000);
+        let ritter = BoundingSphere::from_points_ritter(&points);
+        let exact = BoundingSphere::from_points(&points);
+        for &point in &points {
+            assert!(ritter.contains_point(point));
+            assert!(exact.contains_point(point));
+        }
+        assert!(exact.radius <= ritter.radius);
+        // Num cubo de lado 10 a esfera mínima tem raio ≤ meia diagonal
+        assert!(exact.radius <= 5.0 * 3.0f32.sqrt() + 1e-3);
+    }
+
+    #[test]
+    fn test_merge_and_transform() {
+        let a = BoundingSphere::new(Vec3::ZERO, 1.0);
+        let b = BoundingSphere::new(Vec3::new(4.0, 0.0, 0.0), 1.0);
+        let merged = a.merge(&b);
+        assert_relative_eq!(merged.center.x, 2.0);
+        assert_relative_eq!(merged.radius, 3.0);
+        assert!(merged.contains_sphere(&a) && merged.contains_sphere(&b));
+        assert_eq!(merged.merge(&a), merged);
+        assert_eq!(BoundingSphere::EMPTY.merge(&a), a);
+
+        let matrix = Mat4::translation(Vec3::new(0.0, 10.0, 0.0)).mul_mat4(&Mat4::scale(Vec3::new(1.0, 3.0, 2.0)));
+        let moved = merged.transform(&matrix);
+        assert_relative_eq!(moved.center.x, 2.0);
+        assert_relative_eq!(moved.center.y, 10.0);
+        assert_relative_eq!(moved.radius, 9.0);
+
+        assert!(a.intersects_aabb(&Aabb::new(Vec3::new(0.5, 0.5, 0.0), Vec3::new(2.0, 2.0, 2.0))));
+        assert!(!a.intersects_aabb(&Aabb::new(Vec3::new(0.8, 0.8, 0.8), Vec3::new(2.0, 2.0, 2.0))));
+    }
+}
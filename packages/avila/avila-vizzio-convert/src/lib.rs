@@ -0,0 +1,759 @@
+//! # avila-vizzio-convert
+//!
+//! **Pipeline IFC → GLB + metadados**
+//!
+//! Fachada que liga os estágios da conversão, hoje montados à mão por cada
+//! chamador:
+//!
+//! 1. Parse - `avila-bim` (índice STEP + decodificação paralela)
+//! 2. Tesselação - `avila-tesselation` (em lote, multi-thread, cancelável)
+//! 3. Otimização - `avila-optimizer` (opcional: merge por material, LODs)
+//! 4. Exportação - `avila-gltf` (GLB binário)
+//! 5. Metadados - `avila-metadata-extractor` (JSON com link elemento → node)
+//!
+//! Com [`ConvertOptions::thumbnails`], a cena exportada também é renderizada
+//! por software em PNGs de miniatura (um por tamanho pedido).
+//!
+//! Com [`ConvertOptions::sequence`], um cronograma 4D ([`Schedule`]) vira
+//! uma animação de visibilidade no GLB ([`sequence_animation`]).
+//!
+//! Cada [`ConvertError`] tem um código estável ([`ConvertError::code`]) e
+//! vira um [`avila_error::Error`] com esse código para respostas HTTP.
+//!
+//! ```ignore
+//! let output = convert(&ifc_bytes, &ConvertOptions::default())?;
+//! std::fs::write("model.glb", &output.glb)?;
+//! std::fs::write("model.json", &output.metadata_json)?;
+//! println!("{}", output.report.summary());
+//! ```
+
+use avila_bim::file_parsers::{ElementGeometry, LoadedModel, ModelElement, ParseError, PropertyValue};
+use avila_bim::geo::Georeference;
+use avila_bim::ifc_parser::{DecodeOptions, IfcParser};
+use avila_bim::step_index::ParseDiagnostic;
+use avila_error::ErrorCode;
+use avila_gltf::{ExportOptions, ExportReport, GltfError, GltfExporter, PipelineStep, VisibilityAnimation, VisibilityKey};
+use avila_mesh::{Camera, Mesh, MeshError, PbrMaterial, Projection, Scene};
+use avila_metadata_extractor::{
+    BimElement, BimMetadata, BoundingBox, MetadataError, MetadataExtractor, ProjectData, Schedule, SceneStats,
+    SiteData, UnitContext,
+};
+use avila_optimizer::{Optimizer, OptimizerError};
+use avila_tesselation::{BatchOptions, CancellationToken, IfcGeometry, TesselationError, Tesselator};
+use avila_vec3d::{Mat4, Vec3, Vec4};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub type Result<T> = std::result::Result<T, ConvertError>;
+
+// ============================================================================
+// ERROS
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConvertError {
+    #[error("Parse error: {0}")]
+    Parse(#[from] ParseError),
+
+    #[error("Tesselation error: {0}")]
+    Tesselation(#[from] TesselationError),
+
+    #[error("Optimization error: {0}")]
+    Optimization(#[from] OptimizerError),
+
+    #[error("glTF export error: {0}")]
+    Gltf(#[from] GltfError),
+
+    #[error("Metadata error: {0}")]
+    Metadata(#[from] MetadataError),
+
+    #[error("Thumbnail render error: {0}")]
+    Render(#[from] MeshError),
+}
+
+impl ConvertError {
+    /// Estágio em que a conversão falhou
+    pub fn stage(&self) -> ConvertStage {
+        match self {
+            Self::Parse(_) => ConvertStage::Parse,
+            Self::Tesselation(_) => ConvertStage::Tesselation,
+            Self::Optimization(_) => ConvertStage::Optimization,
+            Self::Gltf(_) => ConvertStage::Export,
+            Self::Metadata(_) => ConvertStage::Metadata,
+            Self::Render(_) => ConvertStage::Thumbnails,
+        }
+    }
+
+    /// Código estável; cancelamento tem o seu, que pode ser repetido
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Parse(_) => ErrorCode::CONVERT_PARSE,
+            Self::Tesselation(TesselationError::Cancelled) => ErrorCode::CONVERT_CANCELLED,
+            Self::Tesselation(_) => ErrorCode::CONVERT_TESSELATION,
+            Self::Optimization(_) => ErrorCode::CONVERT_OPTIMIZATION,
+            Self::Gltf(_) => ErrorCode::CONVERT_EXPORT,
+            Self::Metadata(_) => ErrorCode::CONVERT_METADATA,
+            Self::Render(_) => ErrorCode::CONVERT_THUMBNAIL,
+        }
+    }
+}
+
+/// `"<estágio> failed: <erro>"`, com o código do erro e ele como causa
+impl From<ConvertError> for avila_error::Error {
+    fn from(err: ConvertError) -> Self {
+        let message = format!("{} failed", err.stage().name());
+        avila_error::Error::coded(err.code(), message).with_source(err)
+    }
+}
+
+// ============================================================================
+// OPÇÕES
+// ============================================================================
+
+/// Opções da conversão, por estágio
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Nome do arquivo de origem (repassado ao parser)
+    pub source_name: Option<String>,
+    pub parse: DecodeOptions,
+    pub tesselation: TesselationOptions,
+    pub optimization: OptimizationOptions,
+    pub gltf: ExportOptions,
+    pub metadata: MetadataOptions,
+    /// Miniaturas PNG da cena exportada (desligadas com `None`)
+    pub thumbnails: Option<ThumbnailOptions>,
+    /// Sequência 4D exportada como animação no GLB (desligada com `None`)
+    pub sequence: Option<SequenceOptions>,
+    /// Interrompe a conversão (verificado durante a tesselação)
+    pub cancellation: Option<CancellationToken>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TesselationOptions {
+    /// Tolerância de aproximação de curvas (m)
+    pub tolerance: f32,
+    /// Threads de tesselação (0 = `available_parallelism`)
+    pub threads: usize,
+    /// Vértices e triângulos de cada mesh na ordem canônica (GLB reproduzível)
+    pub canonical_order: bool,
+}
+
+impl Default for TesselationOptions {
+    fn default() -> Self {
+        Self { tolerance: 0.01, threads: 0, canonical_order: false }
+    }
+}
+
+/// Otimização da cena antes da exportação
+///
+/// O merge agrupa as meshes por material, então os nodes do GLB deixam de
+/// corresponder a elementos: com a otimização ligada, `meshNode` não é
+/// preenchido nos metadados.
+#[derive(Debug, Clone)]
+pub struct OptimizationOptions {
+    pub enabled: bool,
+    /// Tolerância de deduplicação de vértices (m)
+    pub vertex_tolerance: f32,
+    /// Meshes mescladas ordenadas por material e em ordem canônica
+    pub canonical_order: bool,
+}
+
+impl Default for OptimizationOptions {
+    fn default() -> Self {
+        Self { enabled: false, vertex_tolerance: 0.001, canonical_order: false }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetadataOptions {
+    /// Unidades do arquivo (quantidades são exportadas em SI)
+    pub units: UnitContext,
+    /// Inclui detalhamento por pavimento/material nas estatísticas
+    pub detailed_statistics: bool,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        Self { units: UnitContext::si(), detailed_statistics: true }
+    }
+}
+
+/// Cronograma 4D animado no GLB
+///
+/// A animação liga tarefas a nodes pelo GUID do elemento, então não
+/// sobrevive ao merge da otimização: com ela ligada, nenhum node é animado.
+#[derive(Debug, Clone)]
+pub struct SequenceOptions {
+    pub schedule: Schedule,
+    /// Duração de um dia do cronograma na animação
+    pub seconds_per_day: f32,
+}
+
+impl SequenceOptions {
+    pub fn new(schedule: Schedule) -> Self {
+        Self { schedule, seconds_per_day: 0.1 }
+    }
+}
+
+/// Miniaturas renderizadas por software, todas da mesma vista
+#[derive(Debug, Clone)]
+pub struct ThumbnailOptions {
+    /// Tamanhos (largura, altura) gerados
+    pub sizes: Vec<(u32, u32)>,
+    /// Direção de visão; o enquadramento cobre a cena inteira
+    pub direction: Vec3,
+    pub projection: Projection,
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        Self {
+            sizes: vec![(256, 256)],
+            // Isométrica, de cima, vinda de sudeste (Z para cima)
+            direction: Vec3::new(-1.0, 1.0, -1.0),
+            projection: Projection::Perspective { fov_y: std::f32::consts::FRAC_PI_4 },
+        }
+    }
+}
+
+// ============================================================================
+// RELATÓRIO
+// ============================================================================
+
+/// Estágio do pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConvertStage {
+    Parse,
+    Tesselation,
+    Optimization,
+    Export,
+    Thumbnails,
+    Metadata,
+}
+
+impl ConvertStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Tesselation => "tesselation",
+            Self::Optimization => "optimization",
+            Self::Export => "export",
+            Self::Thumbnails => "thumbnails",
+            Self::Metadata => "metadata",
+        }
+    }
+}
+
+/// Tempo gasto em um estágio
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageTiming {
+    pub stage: ConvertStage,
+    pub elapsed: Duration,
+}
+
+/// Elemento cuja geometria falhou na tesselação
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementFailure {
+    pub element_id: String,
+    pub message: String,
+}
+
+/// Tempos e estatísticas da conversão
+#[derive(Debug, Clone, Default)]
+pub struct ConvertReport {
+    /// Estágios executados, em ordem
+    pub stages: Vec<StageTiming>,
+    pub input_bytes: usize,
+    /// Problemas contornados pelo parser (somente com `parse.lenient`)
+    pub parse_diagnostics: Vec<ParseDiagnostic>,
+    pub elements: usize,
+    /// Elementos sem geometria de malha (vazios, pontos, linhas)
+    pub elements_without_geometry: usize,
+    pub failures: Vec<ElementFailure>,
+    pub meshes: usize,
+    pub triangles: usize,
+    pub vertices: usize,
+    pub glb_bytes: usize,
+    pub metadata_bytes: usize,
+    /// Decisões do exporter (também gravadas em `asset.extras` do GLB)
+    pub export: ExportReport,
+}
+
+impl ConvertReport {
+    pub fn total_time(&self) -> Duration {
+        self.stages.iter().map(|s| s.elapsed).sum()
+    }
+
+    pub fn stage_time(&self, stage: ConvertStage) -> Option<Duration> {
+        self.stages.iter().find(|s| s.stage == stage).map(|s| s.elapsed)
+    }
+
+    /// Resumo em uma linha por estágio (para CLI/logs)
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for timing in &self.stages {
+            out.push_str(&format!("{:<13} {:>9.1} ms\n", timing.stage.name(), timing.elapsed.as_secs_f64() * 1000.0));
+        }
+        out.push_str(&format!(
+            "total         {:>9.1} ms | {} elements ({} without geometry, {} failed) | {} meshes, {} triangles | GLB {} bytes, metadata {} bytes",
+            self.total_time().as_secs_f64() * 1000.0,
+            self.elements,
+            self.elements_without_geometry,
+            self.failures.len(),
+            self.meshes,
+            self.triangles,
+            self.glb_bytes,
+            self.metadata_bytes,
+        ));
+        out
+    }
+
+    fn time<T>(&mut self, stage: ConvertStage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        self.stages.push(StageTiming { stage, elapsed: start.elapsed() });
+        value
+    }
+}
+
+/// Miniatura PNG
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub png: Vec<u8>,
+}
+
+/// Resultado da conversão
+#[derive(Debug, Clone)]
+pub struct ConvertOutput {
+    pub glb: Vec<u8>,
+    pub metadata_json: String,
+    /// Na ordem de `ThumbnailOptions::sizes`; vazio sem miniaturas ou sem geometria
+    pub thumbnails: Vec<Thumbnail>,
+    /// Georreferenciamento do IFC, para converter pontos do modelo em WGS84
+    pub georeference: Option<Georeference>,
+    pub report: ConvertReport,
+}
+
+// ============================================================================
+// PIPELINE
+// ============================================================================
+
+/// Converte um arquivo IFC em GLB + metadados JSON
+pub fn convert(ifc_bytes: &[u8], options: &ConvertOptions) -> Result<ConvertOutput> {
+    let mut report = ConvertReport { input_bytes: ifc_bytes.len(), ..Default::default() };
+    let source_name = options.source_name.as_deref().unwrap_or("model.ifc");
+
+    // 1. Parse
+    let parsed = report.time(ConvertStage::Parse, || {
+        IfcParser.parse_with_diagnostics(ifc_bytes, source_name, &options.parse)
+    })?;
+    let model = parsed.model;
+    let georeference = parsed.georeference;
+    report.parse_diagnostics = parsed.diagnostics;
+    report.elements = model.elements.len();
+
+    // 2. Tesselação
+    let (scene, mut mesh_of_element) = report.time(ConvertStage::Tesselation, || tesselate_model(&model, options))?;
+    report.elements_without_geometry = model
+        .elements
+        .iter()
+        .filter(|e| !matches!(e.geometry, ElementGeometry::Mesh { .. }))
+        .count();
+    report.failures = std::mem::take(&mut mesh_of_element.failures);
+    let triangles_by_element = scene
+        .meshes
+        .iter()
+        .filter_map(|m| Some((m.element_guid.clone()?, m.triangle_count())))
+        .collect();
+
+    // 3. Otimização
+    let mut gltf_options = options.gltf.clone();
+    let scene = if options.optimization.enabled {
+        let optimized = report.time(ConvertStage::Optimization, || {
+            let mut optimizer = Optimizer::new();
+            optimizer.merger.vertex_tolerance = options.optimization.vertex_tolerance;
+            optimizer.canonical_order = options.optimization.canonical_order;
+            optimizer.optimize_scene(&scene)
+        })?;
+        mesh_of_element.nodes.clear();
+        gltf_options.pipeline.push(PipelineStep::new(
+            "optimization",
+            serde_json::json!({
+                "mergedByMaterial": true,
+                "vertexTolerance": options.optimization.vertex_tolerance,
+                "canonicalOrder": options.optimization.canonical_order,
+                "meshesBefore": scene.mesh_count(),
+                "meshesAfter": optimized.base_scene.mesh_count(),
+                "verticesBefore": scene.vertex_count(),
+                "verticesAfter": optimized.base_scene.vertex_count(),
+            }),
+        ));
+        optimized.base_scene
+    } else {
+        scene
+    };
+    report.meshes = scene.mesh_count();
+    report.triangles = scene.triangle_count();
+    report.vertices = scene.vertex_count();
+
+    if let Some(sequence) = &options.sequence {
+        gltf_options.animation = Some(sequence_animation(&sequence.schedule, sequence.seconds_per_day));
+    }
+
+    // 4. GLB
+    let (glb, export_report) =
+        report.time(ConvertStage::Export, || GltfExporter::new().export_glb_with_report(&scene, &gltf_options))?;
+    report.glb_bytes = glb.len();
+    report.export = export_report;
+
+    // Miniaturas da mesma cena do GLB
+    let thumbnails = match &options.thumbnails {
+        Some(thumbnail_options) => {
+            report.time(ConvertStage::Thumbnails, || render_thumbnails(&scene, thumbnail_options))?
+        }
+        None => Vec::new(),
+    };
+
+    // 5. Metadados
+    let scene_stats = SceneStats {
+        triangle_count: report.triangles,
+        vertex_count: report.vertices,
+        triangles_by_element,
+    };
+    let metadata_json = report.time(ConvertStage::Metadata, || {
+        let nodes = &mesh_of_element.nodes;
+        extract_metadata(&model, georeference.as_ref(), &scene, nodes, &scene_stats, &options.metadata)
+    })?;
+    report.metadata_bytes = metadata_json.len();
+
+    if !report.parse_diagnostics.is_empty() {
+        avila_log::warn!(
+            source = %source_name,
+            diagnostics = report.parse_diagnostics.len(),
+            "IFC parsed with recovered errors"
+        );
+    }
+    avila_log::info!(
+        source = %source_name,
+        elements = report.elements,
+        failed = report.failures.len(),
+        triangles = report.triangles,
+        glb_bytes = report.glb_bytes,
+        total_ms = report.total_time().as_millis(),
+        "IFC converted"
+    );
+
+    Ok(ConvertOutput { glb, metadata_json, thumbnails, georeference, report })
+}
+
+/// Trilhas de visibilidade do cronograma; o tempo 0 é o início da obra
+pub fn sequence_animation(schedule: &Schedule, seconds_per_day: f32) -> VisibilityAnimation {
+    let mut animation = VisibilityAnimation::new("Sequência 4D");
+    let Some((start, _)) = schedule.span() else { return animation };
+    for guid in schedule.elements() {
+        let keys = schedule
+            .visibility_keys(guid)
+            .into_iter()
+            .map(|(date, visible)| VisibilityKey { time: date.days_since(start) as f32 * seconds_per_day, visible })
+            .collect();
+        animation.add_track(guid, keys);
+    }
+    animation
+}
+
+/// Renderiza as miniaturas da cena; cena sem meshes não gera nenhuma
+pub fn render_thumbnails(scene: &Scene, options: &ThumbnailOptions) -> Result<Vec<Thumbnail>> {
+    if scene.meshes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let camera = Camera::fit(&scene.bounds, options.direction, options.projection)?;
+    options
+        .sizes
+        .iter()
+        .map(|&(width, height)| {
+            let png = scene.render_to_png(width, height, &camera)?;
+            Ok(Thumbnail { width, height, png })
+        })
+        .collect()
+}
+
+/// Elemento → índice do node no GLB, e falhas de tesselação
+struct ElementMeshes {
+    nodes: HashMap<String, u32>,
+    failures: Vec<ElementFailure>,
+}
+
+fn tesselate_model(model: &LoadedModel, options: &ConvertOptions) -> Result<(Scene, ElementMeshes)> {
+    let items: Vec<(String, IfcGeometry)> = model
+        .elements
+        .iter()
+        .filter_map(|element| Some((element.id.clone(), element_geometry(element)?)))
+        .collect();
+
+    let tesselator = Tesselator::with_tolerance(options.tesselation.tolerance);
+    let batch = BatchOptions {
+        threads: options.tesselation.threads,
+        cancellation: options.cancellation.clone(),
+        canonical_order: options.tesselation.canonical_order,
+    };
+    let results = tesselator.tesselate_batch(&items, &batch, |_| {})?;
+
+    let elements: HashMap<&str, &ModelElement> = model.elements.iter().map(|e| (e.id.as_str(), e)).collect();
+    let mut scene = Scene::new();
+    let mut meshes = ElementMeshes { nodes: HashMap::new(), failures: Vec::new() };
+
+    for ((element_id, _), result) in items.iter().zip(results) {
+        let mut mesh: Mesh = match result {
+            Ok(mesh) => mesh,
+            Err(e) => {
+                meshes.failures.push(ElementFailure { element_id: element_id.clone(), message: e.to_string() });
+                continue;
+            }
+        };
+        let element = elements[element_id.as_str()];
+        if let Some(transform) = &element.transform {
+            mesh.transform(&to_mat4(&transform.matrix));
+        }
+
+        // Um material por tipo de elemento
+        let material_id = element.element_type.to_lowercase();
+        if !scene.materials.contains_key(&material_id) {
+            scene.add_material(PbrMaterial::from_ifc_material(&material_id, &element.element_type));
+        }
+        mesh.material_id = Some(material_id);
+        mesh.name = element.name.clone();
+        mesh.element_guid = Some(element_id.clone());
+
+        meshes.nodes.insert(element_id.clone(), scene.meshes.len() as u32);
+        scene.add_mesh(mesh);
+    }
+
+    Ok((scene, meshes))
+}
+
+/// Geometria de malha do elemento (vazios, pontos e linhas não viram mesh)
+fn element_geometry(element: &ModelElement) -> Option<IfcGeometry> {
+    match &element.geometry {
+        ElementGeometry::Mesh { vertices, indices, .. } => Some(IfcGeometry::TriangulatedMesh {
+            vertices: vertices.iter().map(|v| Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32)).collect(),
+            indices: indices.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Matriz do parser (column-major, f64) → `Mat4`
+fn to_mat4(m: &[f64; 16]) -> Mat4 {
+    let col = |i: usize| Vec4::new(m[i] as f32, m[i + 1] as f32, m[i + 2] as f32, m[i + 3] as f32);
+    Mat4::from_cols(col(0), col(4), col(8), col(12))
+}
+
+fn extract_metadata(
+    model: &LoadedModel,
+    georeference: Option<&Georeference>,
+    scene: &Scene,
+    nodes: &HashMap<String, u32>,
+    scene_stats: &SceneStats,
+    options: &MetadataOptions,
+) -> Result<String> {
+    let extractor = MetadataExtractor::with_units(options.units.clone());
+    let bounds: HashMap<&str, &Mesh> = scene
+        .meshes
+        .iter()
+        .filter_map(|m| Some((m.element_guid.as_deref()?, m)))
+        .collect();
+
+    let bim_elements: Vec<BimElement> = model
+        .elements
+        .iter()
+        .map(|element| BimElement {
+            guid: element.id.clone(),
+            ifc_type: match element.properties.get("ifc_type") {
+                Some(PropertyValue::String(ifc_type)) => ifc_type.clone(),
+                _ => element.element_type.clone(),
+            },
+            name: element.name.clone().unwrap_or_default(),
+            description: None,
+            material: None,
+            is_external: None,
+            is_load_bearing: None,
+            length: None,
+            area: None,
+            volume: None,
+            bounding_box: bounds.get(element.id.as_str()).map(|mesh| BoundingBox {
+                min_x: mesh.bounds.min.x,
+                min_y: mesh.bounds.min.y,
+                min_z: mesh.bounds.min.z,
+                max_x: mesh.bounds.max.x,
+                max_y: mesh.bounds.max.y,
+                max_z: mesh.bounds.max.z,
+            }),
+            tags: Vec::new(),
+        })
+        .collect();
+
+    let mut elements = extractor.extract_elements(&bim_elements)?;
+    for element in &mut elements {
+        element.mesh_node = nodes.get(&element.guid).copied();
+    }
+
+    let project = ProjectData {
+        name: model.metadata.get("project_name").cloned().unwrap_or_default(),
+        description: None,
+        author: None,
+        organization: None,
+        site: georeference.and_then(|g| g.site).map(|site| SiteData {
+            name: String::new(),
+            address: None,
+            latitude: Some(site.latitude),
+            longitude: Some(site.longitude),
+        }),
+        buildings: Vec::new(),
+        storeys: Vec::new(),
+    };
+    let structure = extractor.extract_spatial_structure(&project);
+    let relations = extractor.extract_relations(&[]);
+    let statistics = if options.detailed_statistics {
+        extractor.calculate_detailed_statistics(&elements, &structure, &relations, scene_stats)
+    } else {
+        extractor.calculate_statistics(&elements, scene_stats)
+    };
+
+    let metadata = BimMetadata {
+        elements,
+        structure,
+        statistics,
+        relations,
+        units: options.units.clone(),
+    };
+    Ok(extractor.export_json(&metadata)?)
+}
+
+// ============================================================================
+// TESTES
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "ISO-10303-21;
+HEADER;
+FILE_SCHEMA(('IFC4'));
+ENDSEC;
+DATA;
+#1=IFCPROJECT('2O_RrAJHv7xv2dl5cNZYOF',$,'Projeto',$,$,$,$,$,$);
+#10=IFCRECTANGLEPROFILEDEF($,4.,0.2);
+#11=IFCEXTRUDEDAREASOLID(#10,$,$,3.);
+#12=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#11));
+#13=IFCPRODUCTDEFINITIONSHAPE($,$,(#12));
+#20=IFCCARTESIANPOINT((5.,0.,0.));
+#21=IFCAXIS2PLACEMENT3D(#20,$,$);
+#22=IFCLOCALPLACEMENT($,#21);
+#30=IFCWALL('3kd9F8QlX9wvJRPqN0Z6Yc',$,'Parede',$,$,#22,#13,$);
+#31=IFCWALL('0Gd9F8QlX9wvJRPqN0Z6Yd',$,'Sem geometria',$,$,$,$,$);
+ENDSEC;
+END-ISO-10303-21;";
+
+    #[test]
+    fn test_convert_pipeline() {
+        let output = convert(SAMPLE.as_bytes(), &ConvertOptions::default()).unwrap();
+
+        assert_eq!(&output.glb[0..4], b"glTF");
+        assert_eq!(output.report.elements, 1);
+        assert_eq!(output.report.meshes, 1);
+        assert!(output.report.triangles > 0);
+        assert!(output.report.failures.is_empty());
+        assert_eq!(output.report.glb_bytes, output.glb.len());
+        assert_eq!(output.report.export.meshes, 1);
+        assert!(output.report.export.pipeline.is_empty());
+
+        // Placement aplicado (translação em X)
+        let metadata: serde_json::Value = serde_json::from_str(&output.metadata_json).unwrap();
+        let element = &metadata["elements"][0];
+        assert_eq!(element["guid"], "wall_30");
+        assert_eq!(element["meshNode"], 0);
+        assert!(element["boundingBox"][0].as_f64().unwrap() >= 2.9);
+
+        let stages: Vec<ConvertStage> = output.report.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            [ConvertStage::Parse, ConvertStage::Tesselation, ConvertStage::Export, ConvertStage::Metadata]
+        );
+        assert!(output.report.summary().contains("tesselation"));
+        assert!(output.thumbnails.is_empty());
+    }
+
+    #[test]
+    fn test_convert_thumbnails() {
+        let options = ConvertOptions {
+            thumbnails: Some(ThumbnailOptions { sizes: vec![(64, 48), (16, 16)], ..Default::default() }),
+            ..Default::default()
+        };
+        let output = convert(SAMPLE.as_bytes(), &options).unwrap();
+
+        let sizes: Vec<(u32, u32)> = output.thumbnails.iter().map(|t| (t.width, t.height)).collect();
+        assert_eq!(sizes, [(64, 48), (16, 16)]);
+        assert!(output.thumbnails.iter().all(|t| t.png.starts_with(b"\x89PNG")));
+        assert!(output.report.stage_time(ConvertStage::Thumbnails).is_some());
+
+        assert!(render_thumbnails(&Scene::new(), &ThumbnailOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_convert_sequence() {
+        let csv = "task_id,name,start,finish,guid\nT1,Paredes,2024-03-01,2024-03-10,wall_30\n";
+        let schedule = Schedule::from_csv(csv).unwrap();
+        let options = ConvertOptions {
+            sequence: Some(SequenceOptions { schedule, seconds_per_day: 0.5 }),
+            ..Default::default()
+        };
+        let output = convert(SAMPLE.as_bytes(), &options).unwrap();
+
+        let len = u32::from_le_bytes(output.glb[12..16].try_into().unwrap()) as usize;
+        let json: serde_json::Value = serde_json::from_slice(&output.glb[20..20 + len]).unwrap();
+        let animation = &json["animations"][0];
+        assert_eq!(animation["channels"][0]["target"]["node"], 0);
+        assert_eq!(animation["samplers"][0]["interpolation"], "STEP");
+
+        let sequence = sequence_animation(&Schedule::from_csv(csv).unwrap(), 0.5);
+        // Obra começa na própria tarefa: visível desde o tempo 0
+        assert_eq!(sequence.tracks["wall_30"], vec![VisibilityKey { time: 0.0, visible: true }]);
+        assert!(sequence_animation(&Schedule::new(), 1.0).tracks.is_empty());
+    }
+
+    #[test]
+    fn test_convert_optimized_and_cancelled() {
+        let options = ConvertOptions {
+            optimization: OptimizationOptions { enabled: true, ..Default::default() },
+            ..Default::default()
+        };
+        let output = convert(SAMPLE.as_bytes(), &options).unwrap();
+        assert!(output.report.stage_time(ConvertStage::Optimization).is_some());
+        let metadata: serde_json::Value = serde_json::from_str(&output.metadata_json).unwrap();
+        assert!(metadata["elements"][0]["meshNode"].is_null());
+        assert_eq!(output.report.export.pipeline[0].stage, "optimization");
+        assert_eq!(avila_gltf::ExportReport::from_glb(&output.glb), Some(output.report.export.clone()));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ConvertOptions { cancellation: Some(token), ..Default::default() };
+        let err = convert(SAMPLE.as_bytes(), &options).unwrap_err();
+        assert!(matches!(err, ConvertError::Tesselation(TesselationError::Cancelled)));
+        assert_eq!((err.stage(), err.code()), (ConvertStage::Tesselation, ErrorCode::CONVERT_CANCELLED));
+
+        let err = avila_error::Error::from(err).context("converting model.ifc");
+        assert!(err.is_retryable());
+        assert_eq!(err.code().http_status(), 503);
+        assert!(err.to_string().starts_with("converting model.ifc: tesselation failed: Tesselation error: "));
+    }
+
+    #[test]
+    fn test_parse_error_code() {
+        let err = convert(b"not an IFC file", &ConvertOptions::default()).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::CONVERT_PARSE);
+        assert_eq!(avila_error::Error::from(err).code().http_status(), 422);
+    }
+}
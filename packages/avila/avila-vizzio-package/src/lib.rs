@@ -0,0 +1,480 @@
+//! # avila-vizzio-package
+//!
+//! **Pacote de modelo criptografado (`.vizz`)**
+//!
+//! Um único arquivo com o GLB, o JSON de metadados e as miniaturas de um
+//! modelo convertido, pronto para distribuição:
+//!
+//! - Conteúdo dividido em chunks, comprimidos com LZ4 (`avila-compress`)
+//!   quando compensa e cifrados com ChaCha20-Poly1305 (`avila-crypto`)
+//! - Manifesto cifrado com versões, tamanhos e hash BLAKE3 de cada entrada
+//! - Assinatura Ed25519 opcional do manifesto
+//! - Leitura de entradas inteiras ou em streaming, chunk a chunk
+//!
+//! ```ignore
+//! let key = PackageKey::generate()?;
+//! let mut writer = PackageWriter::new(File::create("model.vizz")?, &key, PackageOptions::default())?;
+//! writer.add_glb("model.glb", &output.glb)?;
+//! writer.add_metadata("model.json", output.metadata_json.as_bytes())?;
+//! writer.add_thumbnail("thumb.png", &png)?;
+//! writer.finish()?;
+//!
+//! let mut reader = PackageReader::open(File::open("model.vizz")?, &key)?;
+//! reader.extract("model.glb", &mut File::create("model.glb")?)?;
+//! ```
+//!
+//! ## Formato (v1, little-endian)
+//!
+//! ```text
+//! cabeçalho   magic "VIZZ\r\n\x1a\n" | versão u16 | reservado u16 | chunk_size u32 | salt [32]
+//! entradas    chunks: tamanho u32 | ciphertext | tag [16]
+//! manifesto   um chunk com o JSON do manifesto
+//! assinatura  [64], só se o manifesto tiver `signer`
+//! rodapé      offset do manifesto u64 | tamanho u64 | magic "VIZZEND\0"
+//! ```
+//!
+//! A chave de conteúdo vem de BLAKE3 `derive_key` sobre a chave do pacote e
+//! o salt aleatório do cabeçalho, então dois pacotes nunca repetem chave. O
+//! nonce de cada chunk é (entrada, índice); o AAD repete esses números e
+//! marca o último chunk, de modo que chunks trocados de lugar, movidos entre
+//! entradas ou cortados no fim não abrem.
+
+mod manifest;
+mod reader;
+mod writer;
+
+pub use manifest::{EntryInfo, EntryKind, Manifest, Signer};
+pub use reader::{EntryReader, PackageReader};
+pub use writer::{PackageOptions, PackageWriter};
+
+use avila_crypto::cipher::chacha20::{chacha20_poly1305_decrypt, chacha20_poly1305_encrypt, TAG_LEN};
+use avila_hash::Blake3;
+use std::io::{self, Read, Write};
+
+pub type Result<T> = std::result::Result<T, PackageError>;
+
+// ============================================================================
+// ERROS
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum PackageError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Not a .vizz package")]
+    BadMagic,
+
+    #[error("Unsupported package format version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("Decryption failed: wrong key or corrupted package")]
+    Decrypt,
+
+    #[error("Corrupted package: {0}")]
+    Corrupted(String),
+
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    #[error("Entry not found: {0}")]
+    NotFound(String),
+
+    #[error("Duplicate entry: {0}")]
+    DuplicateEntry(String),
+
+    #[error("Content hash mismatch for {0}")]
+    HashMismatch(String),
+
+    #[error("Invalid manifest signature")]
+    BadSignature,
+}
+
+// ============================================================================
+// FORMATO
+// ============================================================================
+
+/// Versão do formato escrita no cabeçalho
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Tamanho padrão dos chunks de conteúdo
+pub const DEFAULT_CHUNK_SIZE: u32 = 1 << 20;
+
+const MAGIC: [u8; 8] = *b"VIZZ\r\n\x1a\n";
+const FOOTER_MAGIC: [u8; 8] = *b"VIZZEND\0";
+const HEADER_LEN: u64 = 48;
+const FOOTER_LEN: u64 = 24;
+const SIGNATURE_LEN: u64 = 64;
+
+/// Limite do manifesto cifrado, para um rodapé corrompido não virar alocação enorme
+const MAX_MANIFEST_LEN: u64 = 16 << 20;
+
+/// Entrada usada no nonce e no AAD do manifesto
+const MANIFEST_ENTRY: u32 = u32::MAX;
+
+const KEY_CONTEXT: &str = "vizzio-package v1 content key";
+
+/// Primeiro byte do plaintext de cada chunk
+const CODEC_RAW: u8 = 0;
+const CODEC_LZ4: u8 = 1;
+
+/// Chave simétrica de um pacote (256 bits)
+#[derive(Clone)]
+pub struct PackageKey([u8; 32]);
+
+impl PackageKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Chave nova, do gerador do sistema operacional
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0u8; 32];
+        fill_random(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Chave de conteúdo de um pacote com este salt
+    fn content_key(&self, salt: &[u8; 32]) -> [u8; 32] {
+        let mut material = [0u8; 64];
+        material[..32].copy_from_slice(&self.0);
+        material[32..].copy_from_slice(salt);
+        Blake3::derive_key(KEY_CONTEXT, &material).0
+    }
+}
+
+impl std::fmt::Debug for PackageKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PackageKey(..)")
+    }
+}
+
+/// Bytes aleatórios do sistema operacional
+pub(crate) fn fill_random(buf: &mut [u8]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        std::fs::File::open("/dev/urandom")?.read_exact(buf)?;
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = buf;
+        Err(PackageError::Io(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no system random source on this platform",
+        )))
+    }
+}
+
+/// Cabeçalho fixo do início do arquivo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub chunk_size: u32,
+    pub salt: [u8; 32],
+}
+
+impl Header {
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(&MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        out.write_all(&[0; 2])?;
+        out.write_all(&self.chunk_size.to_le_bytes())?;
+        out.write_all(&self.salt)
+    }
+
+    pub fn read(input: &mut impl Read) -> Result<Self> {
+        let mut bytes = [0u8; HEADER_LEN as usize];
+        input.read_exact(&mut bytes).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => PackageError::BadMagic,
+            _ => PackageError::Io(e),
+        })?;
+        if bytes[..8] != MAGIC {
+            return Err(PackageError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != FORMAT_VERSION {
+            return Err(PackageError::UnsupportedVersion(version));
+        }
+        let chunk_size = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        if chunk_size == 0 {
+            return Err(PackageError::Corrupted("zero chunk size".to_string()));
+        }
+        Ok(Self { chunk_size, salt: bytes[16..48].try_into().unwrap() })
+    }
+}
+
+// ============================================================================
+// CHUNKS
+// ============================================================================
+
+/// Posição de um chunk, autenticada pelo nonce e pelo AAD
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkId {
+    pub entry: u32,
+    pub index: u64,
+    pub last: bool,
+}
+
+impl ChunkId {
+    fn nonce(&self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&self.entry.to_le_bytes());
+        nonce[4..].copy_from_slice(&self.index.to_le_bytes());
+        nonce
+    }
+
+    fn aad(&self) -> [u8; 19] {
+        let mut aad = [0u8; 19];
+        aad[..4].copy_from_slice(b"VIZZ");
+        aad[4..6].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        aad[6..10].copy_from_slice(&self.entry.to_le_bytes());
+        aad[10..18].copy_from_slice(&self.index.to_le_bytes());
+        aad[18] = self.last as u8;
+        aad
+    }
+}
+
+/// Comprime (se ajudar), cifra e grava um chunk; devolve os bytes gravados
+pub(crate) fn write_chunk(out: &mut impl Write, key: &[u8; 32], id: ChunkId, data: &[u8], compress: bool) -> Result<u64> {
+    let compressed = match compress {
+        true => avila_compress::lz4::compress(data).ok().filter(|c| c.len() < data.len()),
+        false => None,
+    };
+    let (codec, body) = match &compressed {
+        Some(compressed) => (CODEC_LZ4, compressed.as_slice()),
+        None => (CODEC_RAW, data),
+    };
+
+    let mut plaintext = Vec::with_capacity(body.len() + 1);
+    plaintext.push(codec);
+    plaintext.extend_from_slice(body);
+
+    let len = u32::try_from(plaintext.len()).map_err(|_| PackageError::Corrupted("chunk too large".to_string()))?;
+    let mut ciphertext = vec![0u8; plaintext.len()];
+    let mut tag = [0u8; TAG_LEN];
+    chacha20_poly1305_encrypt(key, &id.nonce(), &id.aad(), &plaintext, &mut ciphertext, &mut tag);
+
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(&ciphertext)?;
+    out.write_all(&tag)?;
+    Ok(4 + ciphertext.len() as u64 + TAG_LEN as u64)
+}
+
+/// Lê, autentica e descomprime um chunk de no máximo `max_len` bytes de plaintext
+pub(crate) fn read_chunk(input: &mut impl Read, key: &[u8; 32], id: ChunkId, max_len: u64) -> Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as u64;
+    // +1 do byte de codec
+    if len == 0 || len > max_len + 1 {
+        return Err(PackageError::Corrupted(format!("chunk {}/{} has invalid length {}", id.entry, id.index, len)));
+    }
+
+    let mut ciphertext = vec![0u8; len as usize];
+    input.read_exact(&mut ciphertext)?;
+    let mut tag = [0u8; TAG_LEN];
+    input.read_exact(&mut tag)?;
+
+    let mut plaintext = vec![0u8; ciphertext.len()];
+    if !chacha20_poly1305_decrypt(key, &id.nonce(), &id.aad(), &ciphertext, &tag, &mut plaintext) {
+        return Err(PackageError::Decrypt);
+    }
+
+    let body = plaintext.split_off(1);
+    let data = match plaintext[0] {
+        CODEC_RAW => body,
+        CODEC_LZ4 => avila_compress::lz4::decompress(&body).map_err(|e| PackageError::Corrupted(e.to_string()))?,
+        codec => return Err(PackageError::Corrupted(format!("unknown chunk codec {}", codec))),
+    };
+    if data.len() as u64 > max_len {
+        return Err(PackageError::Corrupted(format!("chunk {}/{} larger than the chunk size", id.entry, id.index)));
+    }
+    Ok(data)
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn unhex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    if text.len() != N * 2 || !text.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (byte, pair) in out.iter_mut().zip(text.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(out)
+}
+
+// ============================================================================
+// TESTES
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_crypto::signatures::eddsa::Ed25519PrivateKey;
+    use std::io::Cursor;
+
+    fn key() -> PackageKey {
+        PackageKey::from_bytes([7; 32])
+    }
+
+    fn options() -> PackageOptions {
+        PackageOptions { chunk_size: 1024, created_at: Some(1_700_000_000), ..PackageOptions::default() }
+    }
+
+    /// GLB fictício, compressível, com mais de um chunk
+    fn glb() -> Vec<u8> {
+        let mut glb = b"glTF".to_vec();
+        glb.extend((0..5000u32).flat_map(|i| (i % 97).to_le_bytes()));
+        glb
+    }
+
+    /// Bytes que o LZ4 não consegue reduzir
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn package(signer: Option<Ed25519PrivateKey>) -> Vec<u8> {
+        let mut writer = PackageWriter::with_salt(Vec::new(), &key(), options(), [1; 32]).unwrap();
+        if let Some(signer) = signer {
+            writer = writer.signed_by("Vizzio CI", signer);
+        }
+        writer.add_glb("model.glb", &glb()).unwrap();
+        writer.add_metadata("model.json", br#"{"elements":[]}"#).unwrap();
+        writer.add_thumbnail("thumb.png", &noise(3000)).unwrap();
+        writer.finish().unwrap().0
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = package(None);
+        let mut reader = PackageReader::open(Cursor::new(&bytes), &key()).unwrap();
+
+        let manifest = reader.manifest().clone();
+        assert_eq!(manifest.format_version, FORMAT_VERSION);
+        assert_eq!(manifest.created_at, 1_700_000_000);
+        assert_eq!(manifest.entries.len(), 3);
+        assert_eq!(manifest.entries_of(EntryKind::Thumbnail).count(), 1);
+
+        let glb_entry = manifest.entry("model.glb").unwrap();
+        assert_eq!((glb_entry.size, glb_entry.chunks), (20004, 20));
+        assert_eq!(glb_entry.blake3, hex(&Blake3::hash(&glb()).0));
+        // O GLB comprime; a miniatura fica como está (+ codec, tamanho e tag)
+        assert!(glb_entry.stored_size < glb_entry.size / 2);
+        let thumb = manifest.entry("thumb.png").unwrap();
+        assert_eq!(thumb.stored_size, 3000 + 3 * (1 + 4 + TAG_LEN as u64));
+
+        assert_eq!(reader.read("model.glb").unwrap(), glb());
+        assert_eq!(reader.read("model.json").unwrap(), br#"{"elements":[]}"#);
+        assert_eq!(reader.read("thumb.png").unwrap(), noise(3000));
+        assert!(matches!(reader.read("missing.bin"), Err(PackageError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_streaming() {
+        // Escrita a partir de um Read e extração chunk a chunk
+        let mut writer = PackageWriter::with_salt(Vec::new(), &key(), options(), [2; 32]).unwrap();
+        writer.add_entry("model.glb", EntryKind::Glb, Cursor::new(glb())).unwrap();
+        writer.add_entry("empty.bin", EntryKind::Other, io::empty()).unwrap();
+        let (bytes, manifest) = writer.finish().unwrap();
+        assert_eq!(manifest.entry("empty.bin").unwrap().chunks, 1);
+
+        let mut reader = PackageReader::open(Cursor::new(bytes), &key()).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(reader.extract("model.glb", &mut out).unwrap(), 20004);
+        assert_eq!(out, glb());
+
+        let mut entry = reader.entry_reader("model.glb").unwrap();
+        let mut first = [0u8; 10];
+        entry.read_exact(&mut first).unwrap();
+        assert_eq!(&first[..4], b"glTF");
+        let mut rest = Vec::new();
+        entry.read_to_end(&mut rest).unwrap();
+        assert_eq!([&first[..], &rest].concat(), glb());
+
+        assert!(reader.read("empty.bin").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_wrong_key_and_garbage() {
+        let bytes = package(None);
+        let wrong = PackageKey::from_bytes([8; 32]);
+        assert!(matches!(PackageReader::open(Cursor::new(&bytes), &wrong), Err(PackageError::Decrypt)));
+
+        assert!(matches!(PackageReader::open(Cursor::new(b"glTF"), &key()), Err(PackageError::BadMagic)));
+        let mut future = bytes.clone();
+        future[8] = 2;
+        assert!(matches!(PackageReader::open(Cursor::new(future), &key()), Err(PackageError::UnsupportedVersion(2))));
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(PackageReader::open(Cursor::new(truncated), &key()).is_err());
+    }
+
+    #[test]
+    fn test_detects_tampering() {
+        let bytes = package(None);
+        let reader = PackageReader::open(Cursor::new(&bytes), &key()).unwrap();
+        let glb_entry = reader.manifest().entry("model.glb").unwrap().clone();
+        let json_entry = reader.manifest().entry("model.json").unwrap().clone();
+
+        // Um bit trocado num chunk
+        let mut flipped = bytes.clone();
+        flipped[glb_entry.offset as usize + 10] ^= 1;
+        let mut reader = PackageReader::open(Cursor::new(flipped), &key()).unwrap();
+        assert!(matches!(reader.read("model.glb"), Err(PackageError::Decrypt)));
+        assert_eq!(reader.read("model.json").unwrap(), br#"{"elements":[]}"#);
+
+        // Chunk de outra entrada no lugar do primeiro chunk do GLB: o tamanho
+        // confere, mas nonce e AAD não
+        let mut swapped = bytes.clone();
+        let json_chunk = &bytes[json_entry.offset as usize..(json_entry.offset + json_entry.stored_size) as usize];
+        let at = glb_entry.offset as usize;
+        swapped[at..at + json_chunk.len()].copy_from_slice(json_chunk);
+        let mut reader = PackageReader::open(Cursor::new(swapped), &key()).unwrap();
+        assert!(matches!(reader.read("model.glb"), Err(PackageError::Decrypt)));
+    }
+
+    #[test]
+    fn test_signature() {
+        let signer = Ed25519PrivateKey { seed: [42; 32] };
+        let public = signer.public_key();
+        let bytes = package(Some(signer));
+
+        let reader = PackageReader::open(Cursor::new(&bytes), &key()).unwrap();
+        let recorded = reader.manifest().signer.as_ref().unwrap();
+        assert_eq!(recorded.name, "Vizzio CI");
+        assert!(reader.is_signed_by(&public));
+        assert!(!reader.is_signed_by(&Ed25519PrivateKey { seed: [43; 32] }.public_key()));
+
+        // A assinatura fica logo antes do rodapé
+        let mut forged = bytes.clone();
+        let at = forged.len() - FOOTER_LEN as usize - 1;
+        forged[at] ^= 1;
+        assert!(matches!(PackageReader::open(Cursor::new(forged), &key()), Err(PackageError::BadSignature)));
+
+        let unsigned = PackageReader::open(Cursor::new(package(None)), &key()).unwrap();
+        assert!(unsigned.manifest().signer.is_none());
+        assert!(!unsigned.is_signed_by(&public));
+    }
+
+    #[test]
+    fn test_duplicate_entry() {
+        let mut writer = PackageWriter::with_salt(Vec::new(), &key(), options(), [3; 32]).unwrap();
+        writer.add_glb("model.glb", b"glTF").unwrap();
+        assert!(matches!(writer.add_glb("model.glb", b"glTF"), Err(PackageError::DuplicateEntry(_))));
+    }
+}
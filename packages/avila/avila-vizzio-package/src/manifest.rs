@@ -0,0 +1,69 @@
+//! Manifesto do pacote: o que há dentro, onde está e quem assinou
+
+use serde::{Deserialize, Serialize};
+
+/// Índice do pacote, gravado cifrado depois das entradas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format_version: u16,
+    /// Versão do modelo empacotado, definida por quem gera o pacote
+    pub package_version: String,
+    pub generator: String,
+    /// Segundos desde a época Unix
+    pub created_at: u64,
+    pub chunk_size: u32,
+    pub entries: Vec<EntryInfo>,
+    /// Presente quando o manifesto vem seguido de uma assinatura Ed25519
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<Signer>,
+}
+
+impl Manifest {
+    pub fn entry(&self, name: &str) -> Option<&EntryInfo> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn entries_of(&self, kind: EntryKind) -> impl Iterator<Item = &EntryInfo> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+}
+
+/// Uma entrada do pacote
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryInfo {
+    pub name: String,
+    pub kind: EntryKind,
+    /// Tamanho original, em bytes
+    pub size: u64,
+    /// Bytes ocupados no arquivo (chunks comprimidos e cifrados)
+    pub stored_size: u64,
+    pub chunks: u64,
+    /// Posição do primeiro chunk no arquivo
+    pub offset: u64,
+    /// BLAKE3 do conteúdo original, em hexadecimal
+    pub blake3: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Glb,
+    Metadata,
+    Thumbnail,
+    Other,
+}
+
+/// Quem assinou o manifesto
+///
+/// A assinatura prova que o manifesto (e, pelos hashes, o conteúdo) saiu de
+/// quem tem a chave privada; se essa chave é confiável cabe ao leitor decidir,
+/// com [`PackageReader::is_signed_by`](crate::PackageReader::is_signed_by).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Signer {
+    pub name: String,
+    /// Chave pública Ed25519, em hexadecimal
+    pub public_key: String,
+}
@@ -0,0 +1,232 @@
+//! Leitura de pacotes: manifesto na abertura, entradas sob demanda
+
+use crate::{
+    hex, read_chunk, unhex, ChunkId, EntryInfo, Header, Manifest, PackageError, PackageKey, Result, FOOTER_LEN,
+    FOOTER_MAGIC, FORMAT_VERSION, HEADER_LEN, MANIFEST_ENTRY, MAX_MANIFEST_LEN, SIGNATURE_LEN,
+};
+use avila_crypto::signatures::eddsa::{Ed25519PublicKey, Ed25519Signature};
+use avila_hash::Blake3;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Lê um pacote `.vizz` de qualquer [`Read`] + [`Seek`]
+///
+/// [`open`](Self::open) já autentica o manifesto e, se houver, a assinatura;
+/// o conteúdo de cada entrada é autenticado chunk a chunk durante a leitura.
+pub struct PackageReader<R: Read + Seek> {
+    inner: R,
+    key: [u8; 32],
+    manifest: Manifest,
+}
+
+impl<R: Read + Seek> PackageReader<R> {
+    pub fn open(mut inner: R, key: &PackageKey) -> Result<Self> {
+        inner.seek(SeekFrom::Start(0))?;
+        let header = Header::read(&mut inner)?;
+        let key = key.content_key(&header.salt);
+
+        let file_len = inner.seek(SeekFrom::End(0))?;
+        if file_len < HEADER_LEN + FOOTER_LEN {
+            return Err(corrupted("missing footer"));
+        }
+        inner.seek(SeekFrom::Start(file_len - FOOTER_LEN))?;
+        let mut footer = [0u8; FOOTER_LEN as usize];
+        inner.read_exact(&mut footer)?;
+        if footer[16..] != FOOTER_MAGIC {
+            return Err(corrupted("missing footer"));
+        }
+        let manifest_offset = u64::from_le_bytes(footer[..8].try_into().unwrap());
+        let manifest_len = u64::from_le_bytes(footer[8..16].try_into().unwrap());
+
+        // Entre o manifesto e o rodapé só cabe nada ou uma assinatura
+        let manifest_end = manifest_offset
+            .checked_add(manifest_len)
+            .filter(|&end| manifest_offset >= HEADER_LEN && end <= file_len - FOOTER_LEN)
+            .ok_or_else(|| corrupted("manifest out of bounds"))?;
+        let trailing = file_len - FOOTER_LEN - manifest_end;
+        if trailing != 0 && trailing != SIGNATURE_LEN {
+            return Err(corrupted("unexpected bytes before the footer"));
+        }
+
+        inner.seek(SeekFrom::Start(manifest_offset))?;
+        let mut chunk = (&mut inner).take(manifest_len);
+        let id = ChunkId { entry: MANIFEST_ENTRY, index: 0, last: true };
+        let json = read_chunk(&mut chunk, &key, id, MAX_MANIFEST_LEN)?;
+        if chunk.limit() != 0 {
+            return Err(corrupted("manifest length mismatch"));
+        }
+
+        let manifest: Manifest = serde_json::from_slice(&json)?;
+        if manifest.format_version != FORMAT_VERSION {
+            return Err(PackageError::UnsupportedVersion(manifest.format_version));
+        }
+        if manifest.chunk_size != header.chunk_size {
+            return Err(corrupted("chunk size differs from the header"));
+        }
+        validate_entries(&manifest, manifest_offset)?;
+
+        match (&manifest.signer, trailing == SIGNATURE_LEN) {
+            (Some(signer), true) => {
+                let public = unhex::<32>(&signer.public_key).ok_or(PackageError::BadSignature)?;
+                let mut signature = Ed25519Signature { r: [0; 32], s: [0; 32] };
+                inner.read_exact(&mut signature.r)?;
+                inner.read_exact(&mut signature.s)?;
+                if !(Ed25519PublicKey { point: public }).verify(&json, &signature).is_valid() {
+                    return Err(PackageError::BadSignature);
+                }
+            }
+            (Some(_), false) => return Err(PackageError::BadSignature),
+            (None, true) => return Err(corrupted("signature without signer")),
+            (None, false) => {}
+        }
+
+        Ok(Self { inner, key, manifest })
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Se o manifesto foi assinado com esta chave (a assinatura já foi
+    /// verificada em [`open`](Self::open))
+    pub fn is_signed_by(&self, public_key: &Ed25519PublicKey) -> bool {
+        self.manifest.signer.as_ref().is_some_and(|signer| signer.public_key == hex(&public_key.point))
+    }
+
+    /// Conteúdo inteiro de uma entrada
+    pub fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.extract(name, &mut out)?;
+        Ok(out)
+    }
+
+    /// Copia uma entrada para `out`, chunk a chunk; devolve os bytes copiados
+    pub fn extract(&mut self, name: &str, out: &mut impl Write) -> Result<u64> {
+        let mut entry = self.entry_reader(name)?;
+        let mut written = 0;
+        while let Some(chunk) = entry.next_chunk()? {
+            out.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+        Ok(written)
+    }
+
+    /// Leitor em streaming de uma entrada
+    pub fn entry_reader(&mut self, name: &str) -> Result<EntryReader<'_, R>> {
+        let (entry, info) = self
+            .manifest
+            .entries
+            .iter()
+            .enumerate()
+            .find(|(_, info)| info.name == name)
+            .ok_or_else(|| PackageError::NotFound(name.to_string()))?;
+        let info = info.clone();
+        self.inner.seek(SeekFrom::Start(info.offset))?;
+        Ok(EntryReader {
+            inner: &mut self.inner,
+            key: self.key,
+            chunk_size: self.manifest.chunk_size as u64,
+            entry: entry as u32,
+            info,
+            index: 0,
+            read: 0,
+            hasher: Some(Blake3::new()),
+            buffer: Vec::new(),
+            pos: 0,
+        })
+    }
+}
+
+/// Uma entrada sendo lida do pacote
+///
+/// Os chunks são autenticados um a um; o tamanho e o hash BLAKE3 da entrada
+/// inteira são conferidos ao chegar no último.
+pub struct EntryReader<'a, R: Read + Seek> {
+    inner: &'a mut R,
+    key: [u8; 32],
+    chunk_size: u64,
+    entry: u32,
+    info: EntryInfo,
+    index: u64,
+    read: u64,
+    hasher: Option<Blake3>,
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read + Seek> EntryReader<'_, R> {
+    pub fn info(&self) -> &EntryInfo {
+        &self.info
+    }
+
+    /// Próximo chunk decifrado, ou `None` depois do último
+    pub fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        let Some(hasher) = self.hasher.as_mut() else {
+            return Ok(None);
+        };
+        let last = self.index + 1 == self.info.chunks;
+        let id = ChunkId { entry: self.entry, index: self.index, last };
+        let chunk = read_chunk(self.inner, &self.key, id, self.chunk_size)?;
+        if !last && chunk.len() as u64 != self.chunk_size {
+            return Err(corrupted(&format!("short chunk {} in {}", self.index, self.info.name)));
+        }
+        hasher.update(&chunk);
+        self.index += 1;
+        self.read += chunk.len() as u64;
+
+        if last {
+            let digest = self.hasher.take().unwrap().finalize();
+            if self.read != self.info.size || hex(&digest.0) != self.info.blake3 {
+                return Err(PackageError::HashMismatch(self.info.name.clone()));
+            }
+        }
+        Ok(Some(chunk))
+    }
+}
+
+impl<R: Read + Seek> Read for EntryReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.buffer.len() {
+            match self.next_chunk().map_err(into_io)? {
+                Some(chunk) => {
+                    self.buffer = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.buffer.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Entradas dentro da área de conteúdo, com o número de chunks que o tamanho pede
+fn validate_entries(manifest: &Manifest, content_end: u64) -> Result<()> {
+    let chunk_size = manifest.chunk_size as u64;
+    for info in &manifest.entries {
+        let in_bounds = info
+            .offset
+            .checked_add(info.stored_size)
+            .is_some_and(|end| info.offset >= HEADER_LEN && end <= content_end);
+        let expected_chunks = info.size.div_ceil(chunk_size).max(1);
+        if !in_bounds || info.chunks != expected_chunks {
+            return Err(corrupted(&format!("invalid manifest entry {}", info.name)));
+        }
+    }
+    if manifest.entries.len() >= MANIFEST_ENTRY as usize {
+        return Err(corrupted("too many entries"));
+    }
+    Ok(())
+}
+
+fn corrupted(message: &str) -> PackageError {
+    PackageError::Corrupted(message.to_string())
+}
+
+fn into_io(error: PackageError) -> io::Error {
+    match error {
+        PackageError::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
@@ -0,0 +1,186 @@
+//! Escrita de pacotes, entrada por entrada, sem segurar o conteúdo inteiro
+
+use crate::{
+    fill_random, hex, write_chunk, ChunkId, EntryInfo, EntryKind, Header, Manifest, PackageError, PackageKey,
+    Result, Signer, DEFAULT_CHUNK_SIZE, FOOTER_MAGIC, FORMAT_VERSION, HEADER_LEN, MANIFEST_ENTRY,
+};
+use avila_crypto::signatures::eddsa::Ed25519PrivateKey;
+use avila_hash::Blake3;
+use std::io::{self, Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Opções de escrita
+#[derive(Debug, Clone)]
+pub struct PackageOptions {
+    /// Bytes de conteúdo por chunk
+    pub chunk_size: u32,
+    /// Versão do modelo, gravada no manifesto
+    pub package_version: String,
+    pub generator: String,
+    /// Fixa a data do manifesto (builds reproduzíveis); `None` usa o relógio
+    pub created_at: Option<u64>,
+    /// Comprime com LZ4 os chunks em que isso reduz o tamanho
+    pub compress: bool,
+}
+
+impl Default for PackageOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            package_version: "1.0.0".to_string(),
+            generator: concat!("avila-vizzio-package ", env!("CARGO_PKG_VERSION")).to_string(),
+            created_at: None,
+            compress: true,
+        }
+    }
+}
+
+/// Escreve um pacote `.vizz` em qualquer [`Write`]
+///
+/// As entradas são gravadas à medida que chegam; o manifesto, a assinatura e
+/// o rodapé só em [`finish`](Self::finish).
+pub struct PackageWriter<W: Write> {
+    inner: W,
+    key: [u8; 32],
+    options: PackageOptions,
+    entries: Vec<EntryInfo>,
+    offset: u64,
+    signer: Option<(String, Ed25519PrivateKey)>,
+}
+
+impl<W: Write> PackageWriter<W> {
+    /// Começa um pacote com salt aleatório
+    pub fn new(inner: W, key: &PackageKey, options: PackageOptions) -> Result<Self> {
+        let mut salt = [0u8; 32];
+        fill_random(&mut salt)?;
+        Self::with_salt(inner, key, options, salt)
+    }
+
+    /// Como [`new`](Self::new), com o salt dado
+    ///
+    /// O salt precisa ser único por pacote com a mesma chave: repeti-lo repete
+    /// os nonces. Serve para testes e saídas reproduzíveis.
+    pub fn with_salt(mut inner: W, key: &PackageKey, options: PackageOptions, salt: [u8; 32]) -> Result<Self> {
+        if options.chunk_size == 0 {
+            return Err(PackageError::Io(io::Error::new(io::ErrorKind::InvalidInput, "chunk size must be positive")));
+        }
+        Header { chunk_size: options.chunk_size, salt }.write(&mut inner)?;
+        Ok(Self {
+            inner,
+            key: key.content_key(&salt),
+            options,
+            entries: Vec::new(),
+            offset: HEADER_LEN,
+            signer: None,
+        })
+    }
+
+    /// Assina o manifesto com Ed25519 ao finalizar
+    pub fn signed_by(mut self, name: impl Into<String>, key: Ed25519PrivateKey) -> Self {
+        self.signer = Some((name.into(), key));
+        self
+    }
+
+    pub fn add_glb(&mut self, name: &str, glb: &[u8]) -> Result<&EntryInfo> {
+        self.add_entry(name, EntryKind::Glb, glb)
+    }
+
+    pub fn add_metadata(&mut self, name: &str, json: &[u8]) -> Result<&EntryInfo> {
+        self.add_entry(name, EntryKind::Metadata, json)
+    }
+
+    pub fn add_thumbnail(&mut self, name: &str, image: &[u8]) -> Result<&EntryInfo> {
+        self.add_entry(name, EntryKind::Thumbnail, image)
+    }
+
+    /// Grava uma entrada lida de `source`, um chunk por vez
+    pub fn add_entry(&mut self, name: &str, kind: EntryKind, mut source: impl Read) -> Result<&EntryInfo> {
+        if self.entries.iter().any(|entry| entry.name == name) {
+            return Err(PackageError::DuplicateEntry(name.to_string()));
+        }
+        let entry = u32::try_from(self.entries.len())
+            .ok()
+            .filter(|&entry| entry != MANIFEST_ENTRY)
+            .ok_or_else(|| PackageError::Corrupted("too many entries".to_string()))?;
+
+        let chunk_size = self.options.chunk_size as usize;
+        let mut hasher = Blake3::new();
+        let (mut size, mut stored_size, mut index) = (0u64, 0u64, 0u64);
+
+        // Um chunk à frente, para saber qual é o último
+        let mut current = read_up_to(&mut source, chunk_size)?;
+        loop {
+            let next = match current.len() == chunk_size {
+                true => read_up_to(&mut source, chunk_size)?,
+                false => Vec::new(),
+            };
+            let last = next.is_empty();
+
+            hasher.update(&current);
+            size += current.len() as u64;
+            let id = ChunkId { entry, index, last };
+            stored_size += write_chunk(&mut self.inner, &self.key, id, &current, self.options.compress)?;
+            index += 1;
+
+            if last {
+                break;
+            }
+            current = next;
+        }
+
+        self.entries.push(EntryInfo {
+            name: name.to_string(),
+            kind,
+            size,
+            stored_size,
+            chunks: index,
+            offset: self.offset,
+            blake3: hex(&hasher.finalize().0),
+        });
+        self.offset += stored_size;
+        Ok(self.entries.last().unwrap())
+    }
+
+    /// Grava manifesto, assinatura e rodapé; devolve o destino e o manifesto
+    pub fn finish(mut self) -> Result<(W, Manifest)> {
+        let created_at = self.options.created_at.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+        });
+        let manifest = Manifest {
+            format_version: FORMAT_VERSION,
+            package_version: self.options.package_version,
+            generator: self.options.generator,
+            created_at,
+            chunk_size: self.options.chunk_size,
+            entries: self.entries,
+            signer: self.signer.as_ref().map(|(name, key)| Signer {
+                name: name.clone(),
+                public_key: hex(&key.public_key().point),
+            }),
+        };
+
+        let json = serde_json::to_vec(&manifest)?;
+        let id = ChunkId { entry: MANIFEST_ENTRY, index: 0, last: true };
+        // Sem compressão: o limite de leitura do manifesto vale para os bytes gravados
+        let manifest_len = write_chunk(&mut self.inner, &self.key, id, &json, false)?;
+
+        if let Some((_, key)) = &self.signer {
+            let signature = key.sign(&json);
+            self.inner.write_all(&signature.r)?;
+            self.inner.write_all(&signature.s)?;
+        }
+
+        self.inner.write_all(&self.offset.to_le_bytes())?;
+        self.inner.write_all(&manifest_len.to_le_bytes())?;
+        self.inner.write_all(&FOOTER_MAGIC)?;
+        self.inner.flush()?;
+        Ok((self.inner, manifest))
+    }
+}
+
+/// Lê até `len` bytes; menos só no fim da fonte
+fn read_up_to(source: &mut impl Read, len: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(len);
+    source.take(len as u64).read_to_end(&mut buf)?;
+    Ok(buf)
+}
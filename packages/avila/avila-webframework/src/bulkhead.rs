@@ -0,0 +1,238 @@
+//! Bulkheads - limite de concorrência por rota
+//!
+//! Endpoints pesados (ex.: disparo de conversão de modelo) não podem ocupar
+//! todas as threads de conexão. Cada rota com bulkhead admite até
+//! `max_concurrent` requisições; as excedentes esperam numa fila limitada e,
+//! com a fila cheia ou após `queue_timeout`, recebem 503 imediatamente.
+//!
+//! Cada conexão roda na sua própria thread, então a espera na fila bloqueia
+//! apenas a thread da requisição enfileirada.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Configuração de um bulkhead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkheadConfig {
+    /// Requisições executando ao mesmo tempo
+    pub max_concurrent: usize,
+    /// Requisições aguardando vaga (0 = rejeita assim que saturar)
+    pub max_queue: usize,
+    /// Espera máxima na fila
+    pub queue_timeout: Duration,
+}
+
+impl BulkheadConfig {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            max_queue: 0,
+            queue_timeout: Duration::from_secs(5),
+        }
+    }
+
+    pub fn with_queue(mut self, max_queue: usize, queue_timeout: Duration) -> Self {
+        self.max_queue = max_queue;
+        self.queue_timeout = queue_timeout;
+        self
+    }
+}
+
+/// Motivo da rejeição (ambos viram 503)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Todas as vagas e a fila ocupadas
+    QueueFull,
+    /// Esperou `queue_timeout` sem conseguir vaga
+    Timeout,
+}
+
+/// Métricas de saturação de um bulkhead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BulkheadMetrics {
+    pub max_concurrent: usize,
+    pub active: usize,
+    pub queued: usize,
+    /// Maior número de requisições simultâneas já observado
+    pub peak_active: usize,
+    pub admitted: u64,
+    /// Admitidas depois de esperar na fila
+    pub admitted_after_queue: u64,
+    pub rejected_queue_full: u64,
+    pub rejected_timeout: u64,
+}
+
+impl BulkheadMetrics {
+    /// Fração das vagas em uso (0.0 a 1.0)
+    pub fn saturation(&self) -> f64 {
+        if self.max_concurrent == 0 {
+            0.0
+        } else {
+            self.active as f64 / self.max_concurrent as f64
+        }
+    }
+
+    pub fn rejected(&self) -> u64 {
+        self.rejected_queue_full + self.rejected_timeout
+    }
+}
+
+#[derive(Debug, Default)]
+struct Slots {
+    active: usize,
+    queued: usize,
+    peak_active: usize,
+}
+
+/// Limitador de concorrência de uma rota
+#[derive(Debug)]
+pub struct Bulkhead {
+    config: BulkheadConfig,
+    slots: Mutex<Slots>,
+    released: Condvar,
+    admitted: AtomicU64,
+    admitted_after_queue: AtomicU64,
+    rejected_queue_full: AtomicU64,
+    rejected_timeout: AtomicU64,
+}
+
+impl Bulkhead {
+    pub fn new(config: BulkheadConfig) -> Self {
+        Self {
+            config,
+            slots: Mutex::new(Slots::default()),
+            released: Condvar::new(),
+            admitted: AtomicU64::new(0),
+            admitted_after_queue: AtomicU64::new(0),
+            rejected_queue_full: AtomicU64::new(0),
+            rejected_timeout: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &BulkheadConfig {
+        &self.config
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Ocupa uma vaga, esperando na fila se houver espaço
+    ///
+    /// A vaga é devolvida quando o [`BulkheadPermit`] sai de escopo.
+    pub fn acquire(&self) -> std::result::Result<BulkheadPermit<'_>, Rejection> {
+        let mut slots = self.lock();
+
+        if slots.active >= self.config.max_concurrent {
+            if slots.queued >= self.config.max_queue {
+                self.rejected_queue_full.fetch_add(1, Ordering::Relaxed);
+                return Err(Rejection::QueueFull);
+            }
+
+            slots.queued += 1;
+            let deadline = Instant::now() + self.config.queue_timeout;
+            while slots.active >= self.config.max_concurrent {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    slots.queued -= 1;
+                    self.rejected_timeout.fetch_add(1, Ordering::Relaxed);
+                    return Err(Rejection::Timeout);
+                }
+                slots = self
+                    .released
+                    .wait_timeout(slots, remaining)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .0;
+            }
+            slots.queued -= 1;
+            self.admitted_after_queue.fetch_add(1, Ordering::Relaxed);
+        }
+
+        slots.active += 1;
+        slots.peak_active = slots.peak_active.max(slots.active);
+        self.admitted.fetch_add(1, Ordering::Relaxed);
+        Ok(BulkheadPermit { bulkhead: self })
+    }
+
+    pub fn metrics(&self) -> BulkheadMetrics {
+        let slots = self.lock();
+        BulkheadMetrics {
+            max_concurrent: self.config.max_concurrent,
+            active: slots.active,
+            queued: slots.queued,
+            peak_active: slots.peak_active,
+            admitted: self.admitted.load(Ordering::Relaxed),
+            admitted_after_queue: self.admitted_after_queue.load(Ordering::Relaxed),
+            rejected_queue_full: self.rejected_queue_full.load(Ordering::Relaxed),
+            rejected_timeout: self.rejected_timeout.load(Ordering::Relaxed),
+        }
+    }
+
+    fn release(&self) {
+        let mut slots = self.lock();
+        slots.active = slots.active.saturating_sub(1);
+        drop(slots);
+        self.released.notify_one();
+    }
+}
+
+/// Vaga ocupada em um [`Bulkhead`]
+#[derive(Debug)]
+pub struct BulkheadPermit<'a> {
+    bulkhead: &'a Bulkhead,
+}
+
+impl Drop for BulkheadPermit<'_> {
+    fn drop(&mut self) {
+        self.bulkhead.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_bulkhead_sheds_when_queue_full() {
+        let bulkhead = Bulkhead::new(BulkheadConfig::new(1));
+
+        let permit = bulkhead.acquire().unwrap();
+        assert_eq!(bulkhead.acquire().unwrap_err(), Rejection::QueueFull);
+        assert_eq!(bulkhead.metrics().saturation(), 1.0);
+
+        drop(permit);
+        assert!(bulkhead.acquire().is_ok());
+
+        let metrics = bulkhead.metrics();
+        assert_eq!((metrics.active, metrics.admitted, metrics.rejected()), (0, 2, 1));
+    }
+
+    #[test]
+    fn test_bulkhead_queue_and_timeout() {
+        let config = BulkheadConfig::new(1).with_queue(1, Duration::from_secs(5));
+        let bulkhead = Arc::new(Bulkhead::new(config));
+
+        let permit = bulkhead.acquire().unwrap();
+        let waiter = {
+            let bulkhead = Arc::clone(&bulkhead);
+            thread::spawn(move || bulkhead.acquire().map(|_| ()))
+        };
+        while bulkhead.metrics().queued == 0 {
+            thread::yield_now();
+        }
+        // Fila cheia: a próxima é rejeitada sem esperar
+        assert_eq!(bulkhead.acquire().unwrap_err(), Rejection::QueueFull);
+
+        drop(permit);
+        assert!(waiter.join().unwrap().is_ok());
+        assert_eq!(bulkhead.metrics().admitted_after_queue, 1);
+
+        let short = Bulkhead::new(BulkheadConfig::new(1).with_queue(1, Duration::from_millis(10)));
+        let _permit = short.acquire().unwrap();
+        assert_eq!(short.acquire().unwrap_err(), Rejection::Timeout);
+        assert_eq!(short.metrics().rejected_timeout, 1);
+    }
+}
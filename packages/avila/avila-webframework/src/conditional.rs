@@ -0,0 +1,170 @@
+//! Requisições condicionais (RFC 9110, seção 13)
+//!
+//! [`Validators`] reúne o ETag e a data de modificação de uma representação
+//! e decide se a requisição pode ser respondida com `304 Not Modified`
+//! (`If-None-Match`, `If-Modified-Since`) ou se um `Range` ainda vale
+//! (`If-Range`). O ETag é forte: derivado do conteúdo, não da data.
+
+use crate::{Request, Response};
+use avila_headers::{http_date, parse_http_date};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Validadores de uma representação
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// ETag com aspas (`"..."`)
+    pub etag: Option<String>,
+    /// Truncada em segundos, a resolução de `Last-Modified`
+    pub last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    /// ETag forte calculado sobre `data`
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut hash = ContentHash::new();
+        hash.update(data);
+        Self {
+            etag: Some(hash.etag()),
+            last_modified: None,
+        }
+    }
+
+    pub fn last_modified(mut self, time: SystemTime) -> Self {
+        self.last_modified = Some(truncate_to_seconds(time));
+        self
+    }
+
+    /// Adiciona `ETag` e `Last-Modified` à resposta
+    pub fn apply(&self, mut response: Response) -> Response {
+        if let Some(etag) = &self.etag {
+            response = response.header("ETag", etag);
+        }
+        if let Some(time) = self.last_modified {
+            response = response.header("Last-Modified", &http_date(time));
+        }
+        response
+    }
+
+    /// O cliente já tem esta versão: `If-None-Match` tem precedência sobre
+    /// `If-Modified-Since` (RFC 9110, 13.2.2)
+    pub fn is_not_modified(&self, req: &Request) -> bool {
+        if let Some(candidates) = req.header("if-none-match") {
+            let Some(etag) = self.etag.as_deref().and_then(opaque_tag) else {
+                return false;
+            };
+            return candidates.trim() == "*"
+                || candidates
+                    .split(',')
+                    .any(|candidate| opaque_tag(candidate.trim()) == Some(etag));
+        }
+        match (req.header("if-modified-since").and_then(parse_http_date), self.last_modified) {
+            (Some(since), Some(modified)) => modified <= since,
+            _ => false,
+        }
+    }
+
+    /// `If-Range` ausente ou ainda válido: o `Range` pode ser atendido
+    ///
+    /// Só validadores fortes servem; um ETag fraco (`W/`) nunca confere.
+    pub fn range_allowed(&self, req: &Request) -> bool {
+        let Some(condition) = req.header("if-range").map(|h| h.trim()) else {
+            return true;
+        };
+        if condition.starts_with('"') || condition.starts_with("W/") {
+            return self.etag.as_deref() == Some(condition);
+        }
+        parse_http_date(condition).is_some_and(|date| Some(date) == self.last_modified)
+    }
+
+    /// `304 Not Modified` com os validadores
+    pub fn not_modified(&self) -> Response {
+        self.apply(Response::new(304))
+    }
+}
+
+/// Valor de um ETag sem o prefixo `W/` (comparação fraca)
+fn opaque_tag(tag: &str) -> Option<&str> {
+    let tag = tag.strip_prefix("W/").unwrap_or(tag);
+    (tag.len() >= 2 && tag.starts_with('"') && tag.ends_with('"')).then_some(tag)
+}
+
+/// Hash incremental do conteúdo (FNV-1a 64) para ETags fortes
+///
+/// O tamanho entra no ETag, então colisões exigiriam o mesmo tamanho e o
+/// mesmo hash.
+pub(crate) struct ContentHash {
+    hash: u64,
+    len: u64,
+}
+
+impl ContentHash {
+    pub(crate) fn new() -> Self {
+        Self {
+            hash: 0xcbf2_9ce4_8422_2325,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.hash = (self.hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+        self.len += data.len() as u64;
+    }
+
+    pub(crate) fn etag(&self) -> String {
+        format!("\"{:x}-{:016x}\"", self.len, self.hash)
+    }
+}
+
+fn truncate_to_seconds(time: SystemTime) -> SystemTime {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, State};
+    use std::collections::HashMap;
+
+    fn get(headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: Method::Get,
+            path: "/".to_string(),
+            query: HashMap::new(),
+            params: HashMap::new(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: Vec::new(),
+            state: State::default(),
+            trace: None,
+            deadline: None,
+        }
+    }
+
+    #[test]
+    fn test_conditions() {
+        let modified = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        let validators = Validators::from_bytes(b"glb").last_modified(modified);
+        let etag = validators.etag.clone().unwrap();
+        assert_eq!(etag, Validators::from_bytes(b"glb").etag.unwrap());
+        assert_ne!(etag, Validators::from_bytes(b"glc").etag.unwrap());
+
+        assert!(validators.is_not_modified(&get(&[("if-none-match", &etag)])));
+        assert!(validators.is_not_modified(&get(&[("if-none-match", &format!("\"x\", W/{}", etag))])));
+        assert!(validators.is_not_modified(&get(&[("if-none-match", "*")])));
+        assert!(!validators.is_not_modified(&get(&[("if-none-match", "\"other\"")])));
+        // If-None-Match decide sozinho, mesmo com uma data que conferiria
+        let both = [("if-none-match", "\"other\""), ("if-modified-since", "Sun, 06 Nov 1994 08:49:37 GMT")];
+        assert!(!validators.is_not_modified(&get(&both)));
+        assert!(validators.is_not_modified(&get(&both[1..])));
+        assert!(!validators.is_not_modified(&get(&[("if-modified-since", "Sat, 05 Nov 1994 08:49:37 GMT")])));
+        assert!(!validators.is_not_modified(&get(&[])));
+
+        assert!(validators.range_allowed(&get(&[])));
+        assert!(validators.range_allowed(&get(&[("if-range", &etag)])));
+        assert!(!validators.range_allowed(&get(&[("if-range", &format!("W/{}", etag))])));
+        assert!(validators.range_allowed(&get(&[("if-range", "Sun, 06 Nov 1994 08:49:37 GMT")])));
+        assert!(!validators.range_allowed(&get(&[("if-range", "Mon, 07 Nov 1994 08:49:37 GMT")])));
+    }
+}
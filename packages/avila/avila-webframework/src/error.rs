@@ -0,0 +1,111 @@
+//! Corpo uniforme das respostas de erro
+//!
+//! Erros do próprio framework (corpo grande demais, rota inexistente, prazo,
+//! bulkhead) e dos handlers viram o mesmo JSON, com o status HTTP tirado da
+//! categoria do [`ErrorCode`](avila_error::ErrorCode):
+//!
+//! ```json
+//! {"error": "Lease expired", "code": 2111, "name": "LEASE_EXPIRED",
+//!  "category": "gone", "retryable": false, "context": []}
+//! ```
+
+use crate::jobs::{fields, object, optional, required};
+use crate::Response;
+use avila_error::Error;
+use avila_serde::{Deserialize, Error as SerdeError, Serialize, Value};
+use std::error::Error as _;
+
+/// Corpo das respostas de erro
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorBody {
+    /// Mensagem seguida da causa, sem o contexto
+    pub error: String,
+    pub code: u32,
+    pub name: String,
+    pub category: String,
+    pub retryable: bool,
+    /// Do mais externo ao mais interno; ausente equivale a vazio
+    pub context: Vec<String>,
+}
+
+impl From<&Error> for ErrorBody {
+    fn from(err: &Error) -> Self {
+        let code = err.code();
+        Self {
+            error: match err.source() {
+                Some(source) => format!("{}: {}", err.message(), source),
+                None => err.message().to_string(),
+            },
+            code: code.code().into(),
+            name: code.name().to_string(),
+            category: code.category().name().to_string(),
+            retryable: code.is_retryable(),
+            context: err.contexts().map(str::to_string).collect(),
+        }
+    }
+}
+
+impl Serialize for ErrorBody {
+    fn to_value(&self) -> Value {
+        object([
+            ("error", self.error.to_value()),
+            ("code", self.code.to_value()),
+            ("name", self.name.to_value()),
+            ("category", self.category.to_value()),
+            ("retryable", self.retryable.to_value()),
+            ("context", self.context.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for ErrorBody {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            error: String::from_value(required(&mut fields, "error")?)?,
+            code: u32::from_value(required(&mut fields, "code")?)?,
+            name: String::from_value(required(&mut fields, "name")?)?,
+            category: String::from_value(required(&mut fields, "category")?)?,
+            retryable: bool::from_value(required(&mut fields, "retryable")?)?,
+            context: optional(&mut fields, "context").map(Vec::from_value).transpose()?.unwrap_or_default(),
+        })
+    }
+}
+
+impl Response {
+    /// Status da categoria do código e corpo [`ErrorBody`]
+    pub fn error(err: &Error) -> Self {
+        Response::new(err.code().http_status()).json(&ErrorBody::from(err))
+    }
+}
+
+impl From<Error> for Response {
+    fn from(err: Error) -> Self {
+        Response::error(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avila_error::ErrorCode;
+
+    #[test]
+    fn test_error_body() {
+        let err = Error::coded(ErrorCode::LEASE_EXPIRED, "Lease expired").context("acking lease 4");
+        let response = Response::from(err);
+        assert_eq!(response.status, 410);
+
+        let body = ErrorBody::from_json(std::str::from_utf8(&response.body).unwrap()).unwrap();
+        assert_eq!(body.error, "Lease expired");
+        assert_eq!((body.code, body.name.as_str(), body.category.as_str()), (2111, "LEASE_EXPIRED", "gone"));
+        assert!(!body.retryable);
+        assert_eq!(body.context, ["acking lease 4"]);
+
+        // Erro sem código registrado usa o do `ErrorKind`; a causa entra na mensagem
+        let io = std::io::Error::other("disk full");
+        let body = ErrorBody::from(&Error::io("Failed to write").with_source(io));
+        assert_eq!((body.code, body.retryable), (1001, true));
+        assert_eq!(body.error, "Failed to write: disk full");
+    }
+}
@@ -0,0 +1,380 @@
+//! Arquivos estáticos com suporte a `Range` (RFC 9110, seção 14) e
+//! requisições condicionais (ETag, `Last-Modified`, 304)
+
+use crate::conditional::{ContentHash, Validators};
+use crate::{Request, Response, READ_CHUNK_SIZE};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, SystemTime};
+
+/// Arquivo servido quando o caminho aponta para um diretório
+const INDEX_FILE: &str = "index.html";
+
+/// Arquivos com ETag em cache; acima disso o cache é esvaziado
+const ETAG_CACHE_LIMIT: usize = 4096;
+
+/// Diretório servido por `Router::serve_dir`
+///
+/// Segmentos `..`, `.` e arquivos ocultos são recusados, e links simbólicos
+/// que apontem para fora da raiz respondem 404.
+pub struct StaticFiles {
+    root: PathBuf,
+    max_age: Option<Duration>,
+}
+
+impl StaticFiles {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_age: None,
+        }
+    }
+
+    /// `Cache-Control: public, max-age=...`; sem ele, o navegador revalida
+    /// com o ETag a cada uso
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Responde com o arquivo em `req.params["path"]` (vazio = raiz)
+    pub fn respond(&self, req: &Request) -> Response {
+        let response = self.respond_uncached(req);
+        match self.max_age {
+            Some(max_age) if matches!(response.status, 200 | 206 | 304) => {
+                response.header("Cache-Control", &format!("public, max-age={}", max_age.as_secs()))
+            }
+            _ => response,
+        }
+    }
+
+    fn respond_uncached(&self, req: &Request) -> Response {
+        let relative = req.param("path").unwrap_or_default();
+        let Some(path) = self.resolve(relative) else {
+            return Response::not_found();
+        };
+        if path.is_dir() {
+            // Links relativos do index precisam da barra final
+            if !req.path.ends_with('/') {
+                return Response::new(301).header("Location", &format!("{}/", req.path));
+            }
+            return serve_file(req, &path.join(INDEX_FILE));
+        }
+        serve_file(req, &path)
+    }
+
+    fn resolve(&self, relative: &str) -> Option<PathBuf> {
+        let mut path = self.root.clone();
+        for segment in relative.split('/').filter(|segment| !segment.is_empty()) {
+            if segment.starts_with('.') || segment.contains(['\\', '\0']) {
+                return None;
+            }
+            path.push(segment);
+        }
+        let root = self.root.canonicalize().ok()?;
+        let path = path.canonicalize().ok()?;
+        path.starts_with(&root).then_some(path)
+    }
+}
+
+/// Responde com o conteúdo de `path`, ou o trecho pedido em `Range`
+///
+/// O arquivo é lido em partes enquanto é enviado, sem carregá-lo inteiro. O
+/// ETag vem do conteúdo e fica em cache enquanto tamanho e data de
+/// modificação não mudarem.
+pub fn serve_file(req: &Request, path: &Path) -> Response {
+    let Ok(mut file) = File::open(path) else {
+        return Response::not_found();
+    };
+    let Some(meta) = file.metadata().ok().filter(|meta| meta.is_file()) else {
+        return Response::not_found();
+    };
+    let len = meta.len();
+    let modified = meta.modified().ok();
+
+    let mut validators = Validators {
+        etag: file_etag(&mut file, path, len, modified),
+        last_modified: None,
+    };
+    if let Some(modified) = modified {
+        validators = validators.last_modified(modified);
+    }
+    if validators.is_not_modified(req) {
+        return validators.not_modified();
+    }
+
+    let response = validators
+        .apply(Response::ok())
+        .header("Content-Type", content_type(path))
+        .header("Accept-Ranges", "bytes");
+    match requested_range(req, &validators, len) {
+        ByteRange::Full => response.header("Content-Length", &len.to_string()).reader(file),
+        ByteRange::Partial(start, end) => {
+            if file.seek(SeekFrom::Start(start)).is_err() {
+                return Response::internal_error();
+            }
+            let mut response = partial(response, start, end, len).reader(file.take(end - start + 1));
+            response.status = 206;
+            response
+        }
+        ByteRange::Unsatisfiable => unsatisfiable(len),
+    }
+}
+
+/// Como [`serve_file`], para conteúdo já em memória (tiles, GLBs gerados)
+pub fn serve_bytes(req: &Request, content_type: &str, data: Vec<u8>) -> Response {
+    let validators = Validators::from_bytes(&data);
+    if validators.is_not_modified(req) {
+        return validators.not_modified();
+    }
+
+    let len = data.len() as u64;
+    let mut response = validators
+        .apply(Response::ok())
+        .header("Content-Type", content_type)
+        .header("Accept-Ranges", "bytes");
+    match requested_range(req, &validators, len) {
+        ByteRange::Full => response.body = data,
+        ByteRange::Partial(start, end) => {
+            response = partial(response, start, end, len);
+            response.status = 206;
+            response.body = data[start as usize..=end as usize].to_vec();
+        }
+        ByteRange::Unsatisfiable => return unsatisfiable(len),
+    }
+    response
+}
+
+fn partial(response: Response, start: u64, end: u64, len: u64) -> Response {
+    response
+        .header("Content-Length", &(end - start + 1).to_string())
+        .header("Content-Range", &format!("bytes {}-{}/{}", start, end, len))
+}
+
+fn unsatisfiable(len: u64) -> Response {
+    Response::new(416).header("Content-Range", &format!("bytes */{}", len))
+}
+
+/// `Range` pedido, ignorado quando o `If-Range` não confere mais
+fn requested_range(req: &Request, validators: &Validators, len: u64) -> ByteRange {
+    if !validators.range_allowed(req) {
+        return ByteRange::Full;
+    }
+    parse_range(req.header("range"), len)
+}
+
+/// ETag do conteúdo do arquivo, lido do início e devolvido ao início
+fn file_etag(file: &mut File, path: &Path, len: u64, modified: Option<SystemTime>) -> Option<String> {
+    type Cache = Mutex<HashMap<PathBuf, (u64, SystemTime, String)>>;
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+    let cache = CACHE.get_or_init(Cache::default);
+
+    if let Some(modified) = modified {
+        let cached = cache.lock().unwrap_or_else(PoisonError::into_inner).get(path).cloned();
+        if let Some((_, _, etag)) = cached.filter(|(l, m, _)| *l == len && *m == modified) {
+            return Some(etag);
+        }
+    }
+
+    let mut hash = ContentHash::new();
+    let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+    loop {
+        match file.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => hash.update(&chunk[..n]),
+            Err(_) => return None,
+        }
+    }
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let etag = hash.etag();
+
+    if let Some(modified) = modified {
+        let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() >= ETAG_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(path.to_path_buf(), (len, modified, etag.clone()));
+    }
+    Some(etag)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    /// Início e fim, inclusivos
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Só um intervalo é atendido; listas e cabeçalhos inválidos levam ao arquivo inteiro
+fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let Some((start, end)) = spec.split_once('-').filter(|_| !spec.contains(',')) else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        // Sufixo: os últimos `n` bytes
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(n) => ByteRange::Partial(len.saturating_sub(n), len - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = match end {
+        "" => u64::MAX,
+        end => match end.parse::<u64>() {
+            Ok(end) if end >= start => end,
+            _ => return ByteRange::Full,
+        },
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end.min(len - 1))
+}
+
+/// Tipo MIME pela extensão; desconhecidos vão como `application/octet-stream`
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "wasm" => "application/wasm",
+        "gltf" => "model/gltf+json",
+        "glb" => "model/gltf-binary",
+        "ifc" => "application/x-step",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, State};
+    use std::collections::HashMap;
+
+    fn get(path: &str, headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: Method::Get,
+            path: path.to_string(),
+            query: HashMap::new(),
+            params: HashMap::new(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: Vec::new(),
+            state: State::default(),
+            trace: None,
+            deadline: None,
+        }
+    }
+
+    fn body(response: Response) -> Vec<u8> {
+        let mut body = response.body;
+        body.extend(response.stream.into_iter().flatten().flatten());
+        body
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), ByteRange::Partial(0, 9));
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=90-500"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-10"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-500"), 100), ByteRange::Partial(0, 99));
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=9-1"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_static_files() {
+        let root = std::env::temp_dir().join(format!("avila-web-files-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("model.glb"), b"0123456789").unwrap();
+        std::fs::write(root.join("docs").join("index.html"), b"<h1>docs</h1>").unwrap();
+        std::fs::write(root.join(".env"), b"SECRET=1").unwrap();
+        let files = StaticFiles::new(&root);
+        let request = |path: &str, headers: &[(&str, &str)]| {
+            let mut req = get(&format!("/static/{}", path), headers);
+            req.params.insert("path".to_string(), path.to_string());
+            files.respond(&req)
+        };
+
+        let response = request("model.glb", &[]);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers["Content-Type"], "model/gltf-binary");
+        assert_eq!(response.headers["Content-Length"], "10");
+        assert_eq!(body(response), b"0123456789");
+
+        let response = request("model.glb", &[("range", "bytes=2-4")]);
+        assert_eq!(response.status, 206);
+        assert_eq!(response.headers["Content-Range"], "bytes 2-4/10");
+        assert_eq!(body(response), b"234");
+        assert_eq!(request("model.glb", &[("range", "bytes=20-")]).status, 416);
+
+        let etag = request("model.glb", &[]).headers["ETag"].clone();
+        assert_eq!(etag, Validators::from_bytes(b"0123456789").etag.unwrap());
+        let response = request("model.glb", &[("if-none-match", &etag)]);
+        assert_eq!(response.status, 304);
+        assert!(response.headers.contains_key("Last-Modified") && body(response).is_empty());
+        // If-Range desatualizado: o arquivo inteiro em vez do trecho
+        let response = request("model.glb", &[("range", "bytes=2-4"), ("if-range", "\"stale\"")]);
+        assert_eq!(response.status, 200);
+        assert_eq!(request("model.glb", &[("range", "bytes=2-4"), ("if-range", &etag)]).status, 206);
+        let cached = StaticFiles::new(&root).max_age(std::time::Duration::from_secs(3600));
+        let mut req = get("/static/model.glb", &[]);
+        req.params.insert("path".to_string(), "model.glb".to_string());
+        assert_eq!(cached.respond(&req).headers["Cache-Control"], "public, max-age=3600");
+        assert!(!request("model.glb", &[]).headers.contains_key("Cache-Control"));
+
+        assert_eq!(request("docs", &[]).status, 301);
+        assert_eq!(body(request("docs/", &[])), b"<h1>docs</h1>");
+        assert_eq!(request(".env", &[]).status, 404);
+        assert_eq!(request("../model.glb", &[]).status, 404);
+        assert_eq!(request("missing.txt", &[]).status, 404);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_serve_bytes() {
+        let data = b"glb-bytes".to_vec();
+        let response = serve_bytes(&get("/", &[]), "model/gltf-binary", data.clone());
+        assert_eq!(response.status, 200);
+        let etag = response.headers["ETag"].clone();
+        assert_eq!(body(response), data);
+
+        let response = serve_bytes(&get("/", &[("range", "bytes=-5")]), "model/gltf-binary", data.clone());
+        assert_eq!(response.status, 206);
+        assert_eq!(response.headers["Content-Range"], "bytes 4-8/9");
+        assert_eq!(body(response), b"bytes");
+        assert_eq!(serve_bytes(&get("/", &[("range", "bytes=9-")]), "model/gltf-binary", data.clone()).status, 416);
+        assert_eq!(serve_bytes(&get("/", &[("if-none-match", &etag)]), "model/gltf-binary", data).status, 304);
+    }
+}
@@ -0,0 +1,378 @@
+//! HTTP/2 no servidor (h2c com prior knowledge)
+//!
+//! A conexão é detectada pelo prefácio `PRI * HTTP/2.0`; qualquer outra
+//! linha de requisição segue pelo caminho HTTP/1.1. Uma thread lê o socket,
+//! cada stream roda seu handler numa thread própria e a thread da conexão
+//! concentra o estado HTTP/2 e as escritas.
+
+use crate::{block_on, parse_method, parse_query, HeaderMap, Request, Response, Router, State};
+use avila_error::{Error, Result};
+use avila_h2::{Connection, ErrorCode, Event, Settings, PREFACE};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+
+/// Primeira linha do prefácio do cliente, lida como linha de requisição
+pub(crate) const PREFACE_LINE: &str = "PRI * HTTP/2.0\r\n";
+
+/// Cabeçalhos de conexão que não existem em HTTP/2
+const CONNECTION_HEADERS: [&str; 5] = ["connection", "keep-alive", "proxy-connection", "transfer-encoding", "upgrade"];
+
+enum Input {
+    Bytes(Vec<u8>),
+    Closed,
+    /// Resposta do handler; `true` se ainda vierem partes do corpo
+    Response(u32, Response, bool),
+    Chunk(u32, Vec<u8>),
+    /// O handler do stream terminou (com ou sem corpo em partes)
+    Done(u32),
+}
+
+/// Requisição ainda recebendo cabeçalhos/corpo
+#[derive(Default)]
+struct Pending {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+/// Atende uma conexão cujo prefácio começou com [`PREFACE_LINE`]
+///
+/// `reader` já consumiu essa linha; o resto do prefácio vem dele.
+pub(crate) fn serve(stream: TcpStream, mut reader: BufReader<TcpStream>, router: Arc<Router>) -> Result<()> {
+    let mut input = PREFACE_LINE.as_bytes().to_vec();
+    let mut rest = vec![0u8; PREFACE.len() - input.len()];
+    reader
+        .read_exact(&mut rest)
+        .map_err(|e| Error::io(format!("Failed to read HTTP/2 preface: {}", e)))?;
+    input.extend_from_slice(&rest);
+    input.extend_from_slice(reader.buffer());
+    reader.consume(reader.buffer().len());
+
+    let (tx, rx) = mpsc::channel();
+    let mut socket = reader.into_inner();
+    let reader_tx = tx.clone();
+    std::thread::spawn(move || {
+        let mut buf = vec![0u8; 32 * 1024];
+        loop {
+            match socket.read(&mut buf) {
+                Ok(0) | Err(_) => {
+                    let _ = reader_tx.send(Input::Closed);
+                    return;
+                }
+                Ok(n) => {
+                    if reader_tx.send(Input::Bytes(buf[..n].to_vec())).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut server = Server {
+        connection: Connection::server(Settings::default()),
+        writer: stream,
+        router,
+        tx,
+        pending: HashMap::new(),
+        running: HashMap::new(),
+    };
+    let result = server.run(input, rx);
+    for cancelled in server.running.values() {
+        cancelled.store(true, Ordering::Release);
+    }
+    let _ = server.writer.shutdown(Shutdown::Both);
+    result
+}
+
+struct Server {
+    connection: Connection,
+    writer: TcpStream,
+    router: Arc<Router>,
+    tx: Sender<Input>,
+    pending: HashMap<u32, Pending>,
+    /// Handlers em execução e o sinal de cancelamento de cada um
+    running: HashMap<u32, Arc<AtomicBool>>,
+}
+
+impl Server {
+    fn run(&mut self, input: Vec<u8>, rx: mpsc::Receiver<Input>) -> Result<()> {
+        let mut next = Some(Input::Bytes(input));
+        loop {
+            match next.take() {
+                Some(Input::Bytes(bytes)) => {
+                    let received = self.connection.receive(&bytes);
+                    self.handle_events();
+                    if let Err(e) = received {
+                        self.flush()?;
+                        return Err(Error::network(format!("HTTP/2 connection error: {}", e)));
+                    }
+                }
+                Some(Input::Closed) => return Ok(()),
+                Some(Input::Response(stream, response, streaming)) => self.respond(stream, response, streaming),
+                Some(Input::Chunk(stream, chunk)) => {
+                    let _ = self.connection.send_data(stream, &chunk, false);
+                }
+                Some(Input::Done(stream)) => self.finish(stream),
+                None => {}
+            }
+            self.flush()?;
+            if self.connection.is_going_away() && self.running.is_empty() && self.connection.open_streams() == 0 {
+                return Ok(());
+            }
+            next = rx.recv().ok();
+            if next.is_none() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.connection.has_output() {
+            self.writer
+                .write_all(&self.connection.take_output())
+                .map_err(|e| Error::io(format!("Failed to write response: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn handle_events(&mut self) {
+        while let Some(event) = self.connection.poll_event() {
+            match event {
+                Event::Headers { stream, headers, end_stream } => {
+                    // Trailers da requisição são ignorados
+                    if let Some(pending) = self.pending.get_mut(&stream) {
+                        if pending.headers.is_empty() {
+                            pending.headers = headers;
+                        }
+                    } else if !self.running.contains_key(&stream) {
+                        self.pending.insert(stream, Pending { headers, body: Vec::new() });
+                    }
+                    if end_stream {
+                        self.dispatch(stream);
+                    }
+                }
+                Event::Data { stream, data, end_stream } => {
+                    let Some(pending) = self.pending.get_mut(&stream) else {
+                        continue;
+                    };
+                    if pending.body.len() + data.len() > self.router.body_limit {
+                        self.pending.remove(&stream);
+                        self.connection.reset_stream(stream, ErrorCode::EnhanceYourCalm);
+                        continue;
+                    }
+                    pending.body.extend_from_slice(&data);
+                    if end_stream {
+                        self.dispatch(stream);
+                    }
+                }
+                Event::Reset { stream, .. } => {
+                    self.pending.remove(&stream);
+                    if let Some(cancelled) = self.running.remove(&stream) {
+                        cancelled.store(true, Ordering::Release);
+                    }
+                }
+                Event::GoAway { .. } => {}
+            }
+        }
+    }
+
+    /// Requisição completa: roda o handler numa thread própria
+    fn dispatch(&mut self, stream: u32) {
+        let Some(pending) = self.pending.remove(&stream) else {
+            return;
+        };
+        let Some(request) = build_request(pending) else {
+            self.connection.reset_stream(stream, ErrorCode::ProtocolError);
+            return;
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.running.insert(stream, Arc::clone(&cancelled));
+
+        let router = Arc::clone(&self.router);
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let mut response = block_on(router.handle_request(request));
+            let chunks = response.stream.take();
+            if tx.send(Input::Response(stream, response, chunks.is_some())).is_ok() {
+                for chunk in chunks.into_iter().flatten() {
+                    if cancelled.load(Ordering::Acquire) || tx.send(Input::Chunk(stream, chunk)).is_err() {
+                        break;
+                    }
+                }
+            }
+            let _ = tx.send(Input::Done(stream));
+        });
+    }
+
+    fn respond(&mut self, stream: u32, response: Response, streaming: bool) {
+        let mut headers = vec![(":status".to_string(), response.status.to_string())];
+        for (name, value) in response.headers.iter() {
+            let name = name.to_ascii_lowercase();
+            // Num corpo em partes, o tamanho declarado pelo handler é mantido
+            if !CONNECTION_HEADERS.contains(&name.as_str()) && (streaming || name != "content-length") {
+                headers.push((name, value.to_string()));
+            }
+        }
+        if !streaming && !matches!(response.status, 204 | 304) {
+            headers.push(("content-length".to_string(), response.body.len().to_string()));
+        }
+        let end_stream = response.body.is_empty() && !streaming;
+        if self.connection.send_headers(stream, &headers, end_stream).is_err() {
+            return;
+        }
+        if !response.body.is_empty() {
+            let _ = self.connection.send_data(stream, &response.body, !streaming);
+        }
+        if !streaming {
+            self.running.remove(&stream);
+        }
+    }
+
+    /// Fim do handler; streams com corpo em partes ainda abertos são encerrados
+    fn finish(&mut self, stream: u32) {
+        if self.running.remove(&stream).is_some() {
+            let _ = self.connection.send_data(stream, &[], true);
+        }
+    }
+}
+
+/// Monta a [`Request`] a partir dos pseudo-cabeçalhos; `None` se faltar algum
+fn build_request(pending: Pending) -> Option<Request> {
+    let mut method = None;
+    let mut target = None;
+    let mut headers = HeaderMap::new();
+    for (name, value) in pending.headers {
+        match name.as_str() {
+            ":method" => method = Some(parse_method(&value)),
+            ":path" => target = Some(value),
+            ":authority" if !headers.contains_key("host") => {
+                headers.insert("host", value);
+            }
+            _ if name.starts_with(':') => {}
+            // Cookies podem vir divididos em vários campos
+            "cookie" => {
+                let cookie = match headers.remove("cookie") {
+                    Some(cookie) => format!("{}; {}", cookie, value),
+                    None => value,
+                };
+                headers.insert(name, cookie);
+            }
+            _ => headers.append(name, value),
+        }
+    }
+    let target = target.filter(|target| !target.is_empty())?;
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target, HashMap::new()),
+    };
+    Some(Request {
+        method: method?,
+        path,
+        query,
+        params: HashMap::new(),
+        headers,
+        body: pending.body,
+        state: State::default(),
+        trace: None,
+        deadline: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handle_connection_sync, SseEvent};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    /// Cliente HTTP/2 mínimo sobre um socket bloqueante
+    struct TestClient {
+        connection: Connection,
+        socket: TcpStream,
+    }
+
+    impl TestClient {
+        fn connect(router: Router) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let router = Arc::new(router);
+            std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                let _ = handle_connection_sync(stream, router);
+            });
+            let socket = TcpStream::connect(addr).unwrap();
+            socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            Self {
+                connection: Connection::client(Settings::default()),
+                socket,
+            }
+        }
+
+        fn request(&mut self, method: &str, path: &str, body: &[u8]) -> u32 {
+            let headers: Vec<(String, String)> = [(":method", method), (":scheme", "http"), (":authority", "localhost"), (":path", path)]
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect();
+            let stream = self.connection.send_request(&headers, body.is_empty()).unwrap();
+            if !body.is_empty() {
+                self.connection.send_data(stream, body, true).unwrap();
+            }
+            stream
+        }
+
+        /// Lê até todos os `streams` terminarem; devolve status e corpo de cada um
+        fn responses(&mut self, streams: &[u32]) -> HashMap<u32, (String, Vec<u8>)> {
+            let mut responses: HashMap<u32, (String, Vec<u8>)> = HashMap::new();
+            let mut finished = 0;
+            let mut buf = vec![0u8; 16 * 1024];
+            while finished < streams.len() {
+                self.socket.write_all(&self.connection.take_output()).unwrap();
+                let n = self.socket.read(&mut buf).unwrap();
+                assert!(n > 0, "server closed the connection");
+                self.connection.receive(&buf[..n]).unwrap();
+                while let Some(event) = self.connection.poll_event() {
+                    match event {
+                        Event::Headers { stream, headers, end_stream } => {
+                            let status = headers.iter().find(|(n, _)| n == ":status").unwrap().1.clone();
+                            responses.entry(stream).or_default().0 = status;
+                            finished += end_stream as usize;
+                        }
+                        Event::Data { stream, data, end_stream } => {
+                            responses.entry(stream).or_default().1.extend(data);
+                            finished += end_stream as usize;
+                        }
+                        other => panic!("unexpected event {other:?}"),
+                    }
+                }
+            }
+            responses
+        }
+    }
+
+    #[test]
+    fn test_multiplexed_requests() {
+        let router = Router::new()
+            .get("/hello/{name}", |req: Request| async move {
+                Response::ok().text(&format!("hello {}", req.param("name").unwrap()))
+            })
+            .post("/echo", |req: Request| async move { Response::ok().text(&String::from_utf8_lossy(&req.body)) })
+            .get("/events", |_req: Request| async move {
+                Response::ok().sse((0..3).map(|i| SseEvent::new(i.to_string())))
+            });
+        let mut client = TestClient::connect(router);
+
+        let events = client.request("GET", "/events", b"");
+        let hello = client.request("GET", "/hello/h2?x=1", b"");
+        let echo = client.request("POST", "/echo", &[b'x'; 100_000]);
+        let missing = client.request("GET", "/missing", b"");
+        let responses = client.responses(&[events, hello, echo, missing]);
+
+        assert_eq!(responses[&hello], ("200".to_string(), b"hello h2".to_vec()));
+        assert_eq!(responses[&echo].1.len(), 100_000);
+        assert_eq!(responses[&missing].0, "404");
+        assert_eq!(responses[&events].1, b"data: 0\n\ndata: 1\n\ndata: 2\n\n");
+    }
+}
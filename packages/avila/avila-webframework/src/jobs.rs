@@ -0,0 +1,1775 @@
+//! API REST de gerenciamento de jobs sobre o `avila-coordinator`
+//!
+//! Rotas montadas por [`routes`]:
+//!
+//! | Método | Caminho                 | Descrição                               |
+//! |--------|-------------------------|-----------------------------------------|
+//! | POST   | `/jobs`                 | Submete um job (`SubmitJob`)            |
+//! | GET    | `/jobs`                 | Lista com filtros `state`, `priority`, `limit`, `offset` |
+//! | GET    | `/jobs/{id}`            | Status de um job                        |
+//! | POST   | `/jobs/{id}/cancel`     | Cancela um job pendente ou em execução  |
+//! | GET    | `/jobs/{id}/events`     | Progresso do job via SSE até terminar   |
+//! | GET    | `/jobs/metrics`         | Contagem por estado e métricas de execução |
+//! | GET    | `/jobs/openapi.json`    | Documento OpenAPI 3.0 destas rotas      |
+//! | GET    | `/workflows`            | Nomes dos workflows registrados         |
+//! | GET    | `/workflows/{name}`     | Topologia (nós, dependências, ordem)    |
+//!
+//! Os workers continuam usando o `Coordinator` diretamente através de
+//! [`JobService::coordinator`]; a API só expõe o estado compartilhado.
+//! Jobs submetidos dentro de um handler herdam o span e o prazo da
+//! requisição ([`JobService::trace_context`], [`JobService::deadline`]).
+//!
+//! Os streams de progresso consultam o coordinator a cada
+//! `progress_interval`. Inscrevendo [`JobService::event_handler`] no
+//! `EventBus` do coordinator (ou chamando [`JobService::notify`] após cada
+//! transição), eles acordam assim que um evento é publicado:
+//!
+//! ```ignore
+//! let mut advanced = CoordinatorBuilder::new()
+//!     .with_event_handler(Box::new(service.event_handler()))
+//!     .build();
+//! ```
+//!
+//! Handlers próprios obtêm o serviço com [`Jobs::from_request`] quando ele foi
+//! registrado como estado (`routes` já faz isso):
+//!
+//! ```ignore
+//! let router = Router::new()
+//!     .post("/reports", |req| async move {
+//!         let jobs = match Jobs::from_request(&req) {
+//!             Ok(jobs) => jobs,
+//!             Err(response) => return response,
+//!         };
+//!         match jobs.enqueue(Priority::High) {
+//!             Ok(job) => jobs.events(job.id),
+//!             Err(_) => Response::internal_error(),
+//!         }
+//!     })
+//!     .merge(jobs::routes(service));
+//! ```
+
+use crate::{ErrorBody, Request, Response, Router, SseEvent};
+use avila_error::{Error, ErrorCode};
+use avila_coordinator::{
+    Coordinator, EventHandler, MetricsCollector, Priority, Task, TaskError, TaskEvent, TaskState, Workflow,
+};
+use avila_serde::{Deserialize, Error as SerdeError, Serialize, Value};
+use avila_tracing::{Deadline, TraceContext};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::ops::Deref;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Intervalo padrão entre consultas ao coordinator nos streams de progresso
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sem mudanças por este tempo, o stream envia um comentário para manter a conexão
+const HEARTBEAT: Duration = Duration::from_secs(15);
+
+// ============================================================================
+// Serviço
+// ============================================================================
+
+/// Estado compartilhado entre as rotas de jobs e os workers
+pub struct JobService {
+    coordinator: Arc<Mutex<Coordinator>>,
+    metrics: Arc<Mutex<MetricsCollector>>,
+    workflows: Mutex<HashMap<String, Workflow>>,
+    runs: Mutex<HashMap<u64, RunRecord>>,
+    /// Span de quem submeteu cada job, para os workers continuarem o trace
+    traces: Mutex<HashMap<u64, TraceContext>>,
+    /// Prazo da requisição que submeteu cada job
+    deadlines: Mutex<HashMap<u64, Deadline>>,
+    next_id: AtomicU64,
+    next_run: AtomicU64,
+    progress_interval: Duration,
+    changes: Arc<ChangeSignal>,
+}
+
+/// Jobs criados para uma execução de workflow, em ordem de execução
+#[derive(Clone)]
+struct RunRecord {
+    workflow: String,
+    /// (nó do workflow, nome do nó, job)
+    jobs: Vec<(u64, Option<String>, u64)>,
+}
+
+impl JobService {
+    pub fn new(coordinator: Arc<Mutex<Coordinator>>) -> Self {
+        Self {
+            coordinator,
+            metrics: Arc::new(Mutex::new(MetricsCollector::new())),
+            workflows: Mutex::new(HashMap::new()),
+            runs: Mutex::new(HashMap::new()),
+            traces: Mutex::new(HashMap::new()),
+            deadlines: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            next_run: AtomicU64::new(1),
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            changes: Arc::default(),
+        }
+    }
+
+    /// Intervalo máximo entre consultas ao coordinator nos streams SSE
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// Usa o coletor de métricas alimentado pelos workers
+    pub fn with_metrics(mut self, metrics: Arc<Mutex<MetricsCollector>>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn coordinator(&self) -> Arc<Mutex<Coordinator>> {
+        Arc::clone(&self.coordinator)
+    }
+
+    pub fn metrics_collector(&self) -> Arc<Mutex<MetricsCollector>> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Handler para o `EventBus` do coordinator: cada evento acorda os streams SSE
+    pub fn event_handler(&self) -> JobEventHandler {
+        JobEventHandler {
+            changes: Arc::clone(&self.changes),
+        }
+    }
+
+    /// Avisa os streams SSE (e os workers esperando na fila) de que algum job mudou de estado
+    pub fn notify(&self) {
+        self.changes.notify();
+    }
+
+    /// Sinal que [`notify`](Self::notify) aciona; a fila espera nele por jobs novos
+    pub(crate) fn changes(&self) -> &ChangeSignal {
+        &self.changes
+    }
+
+    /// Publica a topologia de um workflow em `/workflows/{name}`
+    pub fn register_workflow(&self, workflow: Workflow) {
+        lock(&self.workflows).insert(workflow.name().to_string(), workflow);
+    }
+
+    /// Submete um job; sem `id` explícito, o próximo livre é alocado
+    pub fn submit(&self, request: SubmitJob) -> Result<JobDto, TaskError> {
+        let mut coordinator = lock(&self.coordinator);
+        let id = match request.id {
+            Some(id) if coordinator.get_task(id).is_some() => return Err(TaskError::DuplicateId),
+            Some(id) => id,
+            None => self.free_id(&coordinator),
+        };
+        self.next_id.fetch_max(id + 1, Ordering::Relaxed);
+
+        coordinator.submit_with_priority(id, request.priority.unwrap_or_default());
+        self.record_context(id);
+        let job = coordinator.get_task(id).map(JobDto::from).expect("job was just submitted");
+        drop(coordinator);
+        self.notify();
+        Ok(job)
+    }
+
+    /// Contexto do span que submeteu o job (o handler HTTP, quando veio de uma rota)
+    ///
+    /// O worker que executa o job continua o trace com
+    /// `ActiveSpan::start(name, SpanKind::Internal, Some(&context), hooks)`.
+    pub fn trace_context(&self, id: u64) -> Option<TraceContext> {
+        lock(&self.traces).get(&id).cloned()
+    }
+
+    /// Prazo herdado da requisição que submeteu o job
+    ///
+    /// Depois dele ninguém espera mais pelo resultado: o worker deve desistir
+    /// de jobs vencidos em vez de executá-los (ver [`JobService::cancel_expired`]).
+    pub fn deadline(&self, id: u64) -> Option<Deadline> {
+        lock(&self.deadlines).get(&id).copied()
+    }
+
+    /// Cancela os jobs pendentes cujo prazo já passou; devolve os ids cancelados
+    ///
+    /// Jobs em execução ficam a cargo do worker, que consulta [`JobService::deadline`].
+    pub fn cancel_expired(&self) -> Vec<u64> {
+        let expired: Vec<u64> = lock(&self.deadlines)
+            .iter()
+            .filter(|(_, deadline)| deadline.is_expired())
+            .map(|(&id, _)| id)
+            .collect();
+        let mut cancelled = Vec::new();
+        {
+            let mut coordinator = lock(&self.coordinator);
+            for id in expired {
+                let pending = coordinator.get_task(id).is_some_and(|task| task.state == TaskState::Pending);
+                if pending && coordinator.cancel(id).is_ok() {
+                    cancelled.push(id);
+                }
+            }
+        }
+        cancelled.sort_unstable();
+        if !cancelled.is_empty() {
+            self.notify();
+        }
+        cancelled
+    }
+
+    /// Guarda o span e o prazo correntes (do handler HTTP) para o job
+    fn record_context(&self, id: u64) {
+        if let Some(context) = TraceContext::current() {
+            lock(&self.traces).insert(id, context);
+        }
+        if let Some(deadline) = Deadline::current() {
+            lock(&self.deadlines).insert(id, deadline);
+        }
+    }
+
+    fn free_id(&self, coordinator: &Coordinator) -> u64 {
+        let highest = coordinator.iter().map(|t| t.id.as_u64()).max().unwrap_or(0);
+        let mut id = self.next_id.load(Ordering::Relaxed).max(highest + 1);
+        while coordinator.get_task(id).is_some() {
+            id += 1;
+        }
+        id
+    }
+
+    /// Submete um job por nó do workflow registrado `name`, em ordem de execução
+    ///
+    /// Os ids dos jobs são novos; a correspondência nó → job fica em
+    /// [`RunStatus::jobs`]. Os workers seguem as dependências pela topologia.
+    pub fn start_workflow(&self, name: &str, priority: Priority) -> Result<RunStatus, TaskError> {
+        let (order, names) = {
+            let workflows = lock(&self.workflows);
+            let workflow = workflows.get(name).ok_or(TaskError::NotFound)?;
+            let names: HashMap<u64, Option<String>> = workflow
+                .nodes()
+                .iter()
+                .map(|node| (node.task_id.as_u64(), node.name.clone()))
+                .collect();
+            (workflow.execution_order()?, names)
+        };
+
+        let mut jobs = Vec::with_capacity(order.len());
+        {
+            let mut coordinator = lock(&self.coordinator);
+            for node in order {
+                let id = self.free_id(&coordinator);
+                self.next_id.fetch_max(id + 1, Ordering::Relaxed);
+                coordinator.submit_with_priority(id, priority);
+                self.record_context(id);
+                let node = node.as_u64();
+                jobs.push((node, names.get(&node).cloned().flatten(), id));
+            }
+        }
+
+        self.notify();
+
+        let run = self.next_run.fetch_add(1, Ordering::Relaxed);
+        lock(&self.runs).insert(
+            run,
+            RunRecord {
+                workflow: name.to_string(),
+                jobs,
+            },
+        );
+        Ok(self.run_status(run).expect("run was just started"))
+    }
+
+    /// Estado de uma execução iniciada com [`JobService::start_workflow`]
+    pub fn run_status(&self, run: u64) -> Option<RunStatus> {
+        let record = lock(&self.runs).get(&run).cloned()?;
+        let coordinator = lock(&self.coordinator);
+        let jobs: Vec<RunJob> = record
+            .jobs
+            .into_iter()
+            .filter_map(|(node, name, id)| {
+                let job = coordinator.get_task(id).map(JobDto::from)?;
+                Some(RunJob { node, name, job })
+            })
+            .collect();
+        let completed = jobs.iter().filter(|j| j.job.state == TaskState::Completed).count();
+        let progress = if jobs.is_empty() { 0.0 } else { completed as f64 / jobs.len() as f64 };
+        Some(RunStatus {
+            id: run,
+            workflow: record.workflow,
+            progress,
+            jobs,
+        })
+    }
+
+    /// Eventos SSE com o estado do job a cada mudança, até um estado final
+    pub fn events(self: &Arc<Self>, id: u64) -> Option<ProgressEvents> {
+        self.status(id)?;
+        let service = Arc::clone(self);
+        Some(ProgressEvents::new(self.progress_interval, Arc::clone(&self.changes), move || {
+            service.status(id).map(|job| (job.to_value(), job.state.is_terminal()))
+        }))
+    }
+
+    /// Eventos SSE com o [`RunStatus`] a cada mudança, até todos os jobs terminarem
+    pub fn run_events(self: &Arc<Self>, run: u64) -> Option<ProgressEvents> {
+        self.run_status(run)?;
+        let service = Arc::clone(self);
+        Some(ProgressEvents::new(self.progress_interval, Arc::clone(&self.changes), move || {
+            service.run_status(run).map(|status| {
+                let finished = status.jobs.iter().all(|j| j.job.state.is_terminal());
+                (status.to_value(), finished)
+            })
+        }))
+    }
+
+    pub fn status(&self, id: u64) -> Option<JobDto> {
+        lock(&self.coordinator).get_task(id).map(JobDto::from)
+    }
+
+    pub fn cancel(&self, id: u64) -> Result<JobDto, TaskError> {
+        let mut coordinator = lock(&self.coordinator);
+        coordinator.cancel(id)?;
+        Ok(coordinator.get_task(id).map(JobDto::from).expect("cancelled job exists"))
+    }
+
+    /// Jobs que passam no filtro, em ordem de submissão
+    pub fn list(&self, filter: &JobFilter) -> JobList {
+        let coordinator = lock(&self.coordinator);
+        let matching: Vec<JobDto> = coordinator
+            .iter()
+            .filter(|task| filter.matches(task))
+            .map(JobDto::from)
+            .collect();
+        let total = matching.len();
+        let jobs = matching
+            .into_iter()
+            .skip(filter.offset)
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect();
+        JobList { jobs, total }
+    }
+
+    pub fn metrics(&self) -> JobMetrics {
+        let by_state = {
+            let coordinator = lock(&self.coordinator);
+            ALL_STATES
+                .iter()
+                .map(|&state| (state_name(state).to_string(), coordinator.task_count_by_state(state)))
+                .collect::<HashMap<_, _>>()
+        };
+        let metrics = lock(&self.metrics);
+        JobMetrics {
+            total: by_state.values().sum(),
+            by_state,
+            attempts: metrics.total_attempts(),
+            successes: metrics.total_successes(),
+            failures: metrics.total_failures(),
+            success_rate: metrics.overall_success_rate() as f64,
+            average_duration_ms: metrics.overall_average_duration().map(|d| d.as_millis()),
+        }
+    }
+
+    pub fn workflow_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = lock(&self.workflows).keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn workflow(&self, name: &str) -> Option<WorkflowTopology> {
+        lock(&self.workflows).get(name).map(WorkflowTopology::from)
+    }
+}
+
+// ============================================================================
+// Handle para handlers
+// ============================================================================
+
+/// Acesso ao [`JobService`] registrado no router, para enfileirar e acompanhar jobs
+#[derive(Clone)]
+pub struct Jobs(Arc<JobService>);
+
+impl Jobs {
+    pub fn new(service: Arc<JobService>) -> Self {
+        Self(service)
+    }
+
+    /// Extrai o serviço registrado com `Router::state`; sem ele, responde 500
+    pub fn from_request(req: &Request) -> Result<Self, Response> {
+        req.state::<JobService>()
+            .map(Self)
+            .ok_or_else(|| error_response(ErrorCode::INTERNAL, "Job service is not registered on this router"))
+    }
+
+    /// Submete um job com o próximo id livre
+    pub fn enqueue(&self, priority: Priority) -> Result<JobDto, TaskError> {
+        self.0.submit(SubmitJob {
+            id: None,
+            priority: Some(priority),
+        })
+    }
+
+    /// Inicia uma execução do workflow registrado `name`
+    pub fn enqueue_workflow(&self, name: &str, priority: Priority) -> Result<RunStatus, TaskError> {
+        self.0.start_workflow(name, priority)
+    }
+
+    /// Resposta SSE com o progresso do job (404 se não existir)
+    pub fn events(&self, id: u64) -> Response {
+        match self.0.events(id) {
+            Some(events) => Response::ok().sse(events),
+            None => task_error_response(&TaskError::NotFound),
+        }
+    }
+
+    /// Resposta SSE com o progresso de uma execução de workflow (404 se não existir)
+    pub fn run_events(&self, run: u64) -> Response {
+        match self.0.run_events(run) {
+            Some(events) => Response::ok().sse(events),
+            None => error_response(ErrorCode::WORKFLOW_RUN_NOT_FOUND, &format!("Workflow run not found: {}", run)),
+        }
+    }
+}
+
+impl Deref for Jobs {
+    type Target = JobService;
+
+    fn deref(&self) -> &JobService {
+        &self.0
+    }
+}
+
+/// Stream de progresso: consulta o estado a cada intervalo e emite só as mudanças
+///
+/// Emite `progress` a cada mudança e `done` com o estado final; se o job some
+/// do coordinator (ex.: `clear_completed`), emite `error` e termina. O iterador
+/// bloqueia entre consultas, como o restante da conexão; um evento do
+/// coordinator ([`JobService::event_handler`]) antecipa a próxima consulta.
+/// Um cliente desconectado é percebido na próxima escrita (no máximo no
+/// heartbeat), e o stream é descartado.
+pub struct ProgressEvents {
+    poll: Box<dyn FnMut() -> Option<(Value, bool)> + Send>,
+    interval: Duration,
+    changes: Arc<ChangeSignal>,
+    /// Comparado como `Value`: a ordem das chaves no JSON não é estável
+    last: Option<Value>,
+    last_sent: Instant,
+    sequence: u64,
+    finished: bool,
+}
+
+impl ProgressEvents {
+    fn new(
+        interval: Duration,
+        changes: Arc<ChangeSignal>,
+        poll: impl FnMut() -> Option<(Value, bool)> + Send + 'static,
+    ) -> Self {
+        Self {
+            poll: Box::new(poll),
+            interval,
+            changes,
+            last: None,
+            last_sent: Instant::now(),
+            sequence: 0,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for ProgressEvents {
+    type Item = SseEvent;
+
+    fn next(&mut self) -> Option<SseEvent> {
+        loop {
+            if self.finished {
+                return None;
+            }
+            // Lida antes da consulta: um evento durante ela não se perde
+            let generation = self.changes.generation();
+            let Some((data, terminal)) = (self.poll)() else {
+                self.finished = true;
+                let error = ErrorBody::from(&Error::coded(ErrorCode::JOB_NOT_FOUND, "Job no longer exists"));
+                return Some(SseEvent::new(error.to_json()).event("error"));
+            };
+            self.finished = terminal;
+            if self.last.as_ref() != Some(&data) {
+                let payload = data.to_json();
+                self.last = Some(data);
+                self.last_sent = Instant::now();
+                self.sequence += 1;
+                let name = if terminal { "done" } else { "progress" };
+                return Some(SseEvent::new(payload).event(name).id(self.sequence.to_string()));
+            }
+            if terminal {
+                return None;
+            }
+            if self.last_sent.elapsed() >= HEARTBEAT {
+                self.last_sent = Instant::now();
+                return Some(SseEvent::comment("keep-alive"));
+            }
+            self.changes.wait(generation, self.interval.min(HEARTBEAT));
+        }
+    }
+}
+
+/// Contador de mudanças; os streams esperam nele em vez de dormir
+#[derive(Default)]
+pub(crate) struct ChangeSignal {
+    generation: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl ChangeSignal {
+    pub(crate) fn generation(&self) -> u64 {
+        *lock(&self.generation)
+    }
+
+    pub(crate) fn notify(&self) {
+        *lock(&self.generation) += 1;
+        self.changed.notify_all();
+    }
+
+    /// Espera uma mudança posterior a `seen`, no máximo por `timeout`
+    pub(crate) fn wait(&self, seen: u64, timeout: Duration) {
+        let generation = lock(&self.generation);
+        let _ = self
+            .changed
+            .wait_timeout_while(generation, timeout, |generation| *generation == seen);
+    }
+}
+
+/// Inscrito no `EventBus` do coordinator por [`JobService::event_handler`]
+pub struct JobEventHandler {
+    changes: Arc<ChangeSignal>,
+}
+
+impl EventHandler for JobEventHandler {
+    fn on_event(&mut self, _event: &TaskEvent) {
+        self.changes.notify();
+    }
+}
+
+/// Um worker que entrou em pânico não deve derrubar a API
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// ============================================================================
+// Rotas
+// ============================================================================
+
+/// Router com todas as rotas de jobs; combine com `Router::merge`
+///
+/// Também registra o serviço como estado, disponível via [`Jobs::from_request`].
+pub fn routes(service: Arc<JobService>) -> Router {
+    Router::new()
+        .state(Arc::clone(&service))
+        .post("/jobs", handler(&service, submit_job))
+        .get("/jobs", handler(&service, list_jobs))
+        .get("/jobs/metrics", handler(&service, |service, _| Response::ok().json(&service.metrics())))
+        .get("/jobs/openapi.json", |_| async { Response::ok().json(&openapi()) })
+        .get("/jobs/{id}", handler(&service, get_job))
+        .post("/jobs/{id}/cancel", handler(&service, cancel_job))
+        .get("/jobs/{id}/events", |req| std::future::ready(job_events(&req)))
+        .get("/workflows", handler(&service, |service, _| Response::ok().json(&service.workflow_names())))
+        .get("/workflows/{name}", handler(&service, get_workflow))
+}
+
+fn handler(
+    service: &Arc<JobService>,
+    f: fn(&JobService, &Request) -> Response,
+) -> impl Fn(Request) -> std::future::Ready<Response> + Send + Sync + 'static {
+    let service = Arc::clone(service);
+    move |req| std::future::ready(f(&service, &req))
+}
+
+fn submit_job(service: &JobService, req: &Request) -> Response {
+    let request = if req.body.iter().all(u8::is_ascii_whitespace) {
+        SubmitJob::default()
+    } else {
+        match req.json::<SubmitJob>() {
+            Ok(request) => request,
+            Err(err) => return Response::error(&err),
+        }
+    };
+    match service.submit(request) {
+        Ok(job) => Response::created()
+            .header("Location", &format!("/jobs/{}", job.id))
+            .json(&job),
+        Err(err) => task_error_response(&err),
+    }
+}
+
+fn list_jobs(service: &JobService, req: &Request) -> Response {
+    match JobFilter::from_query(&req.query) {
+        Ok(filter) => Response::ok().json(&service.list(&filter)),
+        Err(message) => error_response(ErrorCode::HTTP_INVALID_PARAMETER, &message),
+    }
+}
+
+fn get_job(service: &JobService, req: &Request) -> Response {
+    let id = match job_id(req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    match service.status(id) {
+        Some(job) => Response::ok().json(&job),
+        None => task_error_response(&TaskError::NotFound),
+    }
+}
+
+fn cancel_job(service: &JobService, req: &Request) -> Response {
+    let id = match job_id(req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    match service.cancel(id) {
+        Ok(job) => Response::ok().json(&job),
+        Err(err) => task_error_response(&err),
+    }
+}
+
+fn job_events(req: &Request) -> Response {
+    let id = match job_id(req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    match Jobs::from_request(req) {
+        Ok(jobs) => jobs.events(id),
+        Err(response) => response,
+    }
+}
+
+fn get_workflow(service: &JobService, req: &Request) -> Response {
+    let name = req.param("name").unwrap_or_default();
+    match service.workflow(name) {
+        Some(topology) => Response::ok().json(&topology),
+        None => error_response(ErrorCode::WORKFLOW_NOT_FOUND, &format!("Workflow not found: {}", name)),
+    }
+}
+
+fn job_id(req: &Request) -> Result<u64, Response> {
+    let raw = req.param("id").unwrap_or_default();
+    raw.parse()
+        .map_err(|_| error_response(ErrorCode::HTTP_INVALID_PARAMETER, &format!("Invalid job id: {}", raw)))
+}
+
+pub(crate) fn task_error_response(err: &TaskError) -> Response {
+    let code = match err {
+        TaskError::NotFound => ErrorCode::JOB_NOT_FOUND,
+        TaskError::DuplicateId | TaskError::InvalidState | TaskError::InvalidTransition { .. } => {
+            ErrorCode::JOB_CONFLICT
+        }
+        _ => ErrorCode::JOB_REJECTED,
+    };
+    let message = match err {
+        TaskError::InvalidTransition { from, to } => format!("Cannot move job from {} to {}", from, to),
+        other => other.message().to_string(),
+    };
+    error_response(code, &message)
+}
+
+pub(crate) fn error_response(code: ErrorCode, message: &str) -> Response {
+    Response::error(&Error::coded(code, message))
+}
+
+// ============================================================================
+// Filtros
+// ============================================================================
+
+/// Filtro de `GET /jobs`; estados e prioridades aceitam listas separadas por vírgula
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JobFilter {
+    pub states: Vec<TaskState>,
+    pub priorities: Vec<Priority>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl JobFilter {
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, String> {
+        let mut filter = Self::default();
+        if let Some(states) = query.get("state") {
+            filter.states = split_list(states)
+                .map(|s| parse_state(s).ok_or_else(|| format!("Unknown state: {}", s)))
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(priorities) = query.get("priority") {
+            filter.priorities = split_list(priorities)
+                .map(|p| parse_priority(p).ok_or_else(|| format!("Unknown priority: {}", p)))
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(limit) = query.get("limit") {
+            filter.limit = Some(limit.parse().map_err(|_| format!("Invalid limit: {}", limit))?);
+        }
+        if let Some(offset) = query.get("offset") {
+            filter.offset = offset.parse().map_err(|_| format!("Invalid offset: {}", offset))?;
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        (self.states.is_empty() || self.states.contains(&task.state))
+            && (self.priorities.is_empty() || self.priorities.contains(&task.priority))
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+const ALL_STATES: [TaskState; 5] = [
+    TaskState::Pending,
+    TaskState::Running,
+    TaskState::Completed,
+    TaskState::Failed,
+    TaskState::Cancelled,
+];
+
+const ALL_PRIORITIES: [Priority; 4] = [Priority::Low, Priority::Normal, Priority::High, Priority::Critical];
+
+fn state_name(state: TaskState) -> &'static str {
+    match state {
+        TaskState::Pending => "pending",
+        TaskState::Running => "running",
+        TaskState::Completed => "completed",
+        TaskState::Failed => "failed",
+        TaskState::Cancelled => "cancelled",
+    }
+}
+
+fn parse_state(name: &str) -> Option<TaskState> {
+    ALL_STATES.into_iter().find(|&s| state_name(s).eq_ignore_ascii_case(name))
+}
+
+fn priority_name(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Low => "low",
+        Priority::Normal => "normal",
+        Priority::High => "high",
+        Priority::Critical => "critical",
+    }
+}
+
+fn parse_priority(name: &str) -> Option<Priority> {
+    ALL_PRIORITIES
+        .into_iter()
+        .find(|&p| priority_name(p).eq_ignore_ascii_case(name))
+}
+
+// ============================================================================
+// DTOs
+// ============================================================================
+
+/// Estado público de um job
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobDto {
+    pub id: u64,
+    pub state: TaskState,
+    pub priority: Priority,
+}
+
+impl From<&Task> for JobDto {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id.as_u64(),
+            state: task.state,
+            priority: task.priority,
+        }
+    }
+}
+
+impl Serialize for JobDto {
+    fn to_value(&self) -> Value {
+        object([
+            ("id", self.id.to_value()),
+            ("state", Value::String(state_name(self.state).into())),
+            ("priority", Value::String(priority_name(self.priority).into())),
+        ])
+    }
+}
+
+impl Deserialize for JobDto {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            id: u64::from_value(required(&mut fields, "id")?)?,
+            state: enum_field(required(&mut fields, "state")?, parse_state, "job state")?,
+            priority: enum_field(required(&mut fields, "priority")?, parse_priority, "job priority")?,
+        })
+    }
+}
+
+/// Corpo de `POST /jobs`; todos os campos são opcionais
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubmitJob {
+    pub id: Option<u64>,
+    pub priority: Option<Priority>,
+}
+
+impl Serialize for SubmitJob {
+    fn to_value(&self) -> Value {
+        object([
+            ("id", self.id.to_value()),
+            ("priority", self.priority.map(|p| Value::String(priority_name(p).into())).unwrap_or(Value::Null)),
+        ])
+    }
+}
+
+impl Deserialize for SubmitJob {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        let priority = match optional(&mut fields, "priority") {
+            Some(value) => Some(enum_field(value, parse_priority, "job priority")?),
+            None => None,
+        };
+        Ok(Self {
+            id: optional(&mut fields, "id").map(u64::from_value).transpose()?,
+            priority,
+        })
+    }
+}
+
+/// Resposta de `GET /jobs`; `total` conta os jobs antes da paginação
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobList {
+    pub jobs: Vec<JobDto>,
+    pub total: usize,
+}
+
+impl Serialize for JobList {
+    fn to_value(&self) -> Value {
+        object([("jobs", self.jobs.to_value()), ("total", self.total.to_value())])
+    }
+}
+
+impl Deserialize for JobList {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            jobs: Vec::from_value(required(&mut fields, "jobs")?)?,
+            total: usize::from_value(required(&mut fields, "total")?)?,
+        })
+    }
+}
+
+/// Resposta de `GET /jobs/metrics`
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobMetrics {
+    pub total: usize,
+    /// Contagem por estado (`pending`, `running`, ...)
+    pub by_state: HashMap<String, usize>,
+    pub attempts: u32,
+    pub successes: u32,
+    pub failures: u32,
+    pub success_rate: f64,
+    pub average_duration_ms: Option<u64>,
+}
+
+impl Serialize for JobMetrics {
+    fn to_value(&self) -> Value {
+        object([
+            ("total", self.total.to_value()),
+            ("byState", self.by_state.to_value()),
+            ("attempts", self.attempts.to_value()),
+            ("successes", self.successes.to_value()),
+            ("failures", self.failures.to_value()),
+            ("successRate", self.success_rate.to_value()),
+            ("averageDurationMs", self.average_duration_ms.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for JobMetrics {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            total: usize::from_value(required(&mut fields, "total")?)?,
+            by_state: HashMap::from_value(required(&mut fields, "byState")?)?,
+            attempts: u32::from_value(required(&mut fields, "attempts")?)?,
+            successes: u32::from_value(required(&mut fields, "successes")?)?,
+            failures: u32::from_value(required(&mut fields, "failures")?)?,
+            success_rate: f64::from_value(required(&mut fields, "successRate")?)?,
+            average_duration_ms: optional(&mut fields, "averageDurationMs").map(u64::from_value).transpose()?,
+        })
+    }
+}
+
+/// Nó de um workflow com as dependências diretas
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkflowNodeDto {
+    pub id: u64,
+    pub name: Option<String>,
+    pub dependencies: Vec<u64>,
+}
+
+impl Serialize for WorkflowNodeDto {
+    fn to_value(&self) -> Value {
+        object([
+            ("id", self.id.to_value()),
+            ("name", self.name.to_value()),
+            ("dependencies", self.dependencies.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for WorkflowNodeDto {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            id: u64::from_value(required(&mut fields, "id")?)?,
+            name: optional(&mut fields, "name").map(String::from_value).transpose()?,
+            dependencies: Vec::from_value(required(&mut fields, "dependencies")?)?,
+        })
+    }
+}
+
+/// Resposta de `GET /workflows/{name}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkflowTopology {
+    pub name: String,
+    pub nodes: Vec<WorkflowNodeDto>,
+    /// Ordem topológica (dependências primeiro)
+    pub execution_order: Vec<u64>,
+}
+
+impl From<&Workflow> for WorkflowTopology {
+    fn from(workflow: &Workflow) -> Self {
+        let nodes = workflow
+            .nodes()
+            .iter()
+            .map(|node| WorkflowNodeDto {
+                id: node.task_id.as_u64(),
+                name: node.name.clone(),
+                dependencies: node.dependencies.iter().map(|d| d.as_u64()).collect(),
+            })
+            .collect();
+        // `add_edge` recusa ciclos, então a ordenação sempre existe
+        let execution_order = workflow
+            .execution_order()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| id.as_u64())
+            .collect();
+        Self {
+            name: workflow.name().to_string(),
+            nodes,
+            execution_order,
+        }
+    }
+}
+
+impl Serialize for WorkflowTopology {
+    fn to_value(&self) -> Value {
+        object([
+            ("name", self.name.to_value()),
+            ("nodes", self.nodes.to_value()),
+            ("executionOrder", self.execution_order.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for WorkflowTopology {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            name: String::from_value(required(&mut fields, "name")?)?,
+            nodes: Vec::from_value(required(&mut fields, "nodes")?)?,
+            execution_order: Vec::from_value(required(&mut fields, "executionOrder")?)?,
+        })
+    }
+}
+
+/// Job de uma execução de workflow e o nó que ele representa
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunJob {
+    pub node: u64,
+    pub name: Option<String>,
+    pub job: JobDto,
+}
+
+impl Serialize for RunJob {
+    fn to_value(&self) -> Value {
+        object([
+            ("node", self.node.to_value()),
+            ("name", self.name.to_value()),
+            ("job", self.job.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for RunJob {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            node: u64::from_value(required(&mut fields, "node")?)?,
+            name: optional(&mut fields, "name").map(String::from_value).transpose()?,
+            job: JobDto::from_value(required(&mut fields, "job")?)?,
+        })
+    }
+}
+
+/// Estado de uma execução de workflow; `progress` é a fração de jobs concluídos
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunStatus {
+    pub id: u64,
+    pub workflow: String,
+    pub progress: f64,
+    /// Em ordem de execução
+    pub jobs: Vec<RunJob>,
+}
+
+impl Serialize for RunStatus {
+    fn to_value(&self) -> Value {
+        object([
+            ("id", self.id.to_value()),
+            ("workflow", self.workflow.to_value()),
+            ("progress", self.progress.to_value()),
+            ("jobs", self.jobs.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for RunStatus {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            id: u64::from_value(required(&mut fields, "id")?)?,
+            workflow: String::from_value(required(&mut fields, "workflow")?)?,
+            progress: f64::from_value(required(&mut fields, "progress")?)?,
+            jobs: Vec::from_value(required(&mut fields, "jobs")?)?,
+        })
+    }
+}
+
+pub(crate) fn object<const N: usize>(pairs: [(&str, Value); N]) -> Value {
+    Value::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
+
+pub(crate) fn fields(value: Value) -> Result<HashMap<String, Value>, SerdeError> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(SerdeError::ExpectedObject),
+    }
+}
+
+pub(crate) fn required(fields: &mut HashMap<String, Value>, name: &str) -> Result<Value, SerdeError> {
+    fields
+        .remove(name)
+        .ok_or_else(|| SerdeError::MissingField(name.to_string()))
+}
+
+/// Campo ausente e `null` são equivalentes
+pub(crate) fn optional(fields: &mut HashMap<String, Value>, name: &str) -> Option<Value> {
+    fields.remove(name).filter(|v| *v != Value::Null)
+}
+
+pub(crate) fn enum_field<T>(value: Value, parse: fn(&str) -> Option<T>, what: &str) -> Result<T, SerdeError> {
+    let name = String::from_value(value)?;
+    parse(&name).ok_or_else(|| SerdeError::Parse(format!("unknown {}: {}", what, name)))
+}
+
+// ============================================================================
+// OpenAPI
+// ============================================================================
+
+/// Documento OpenAPI 3.0 servido em `/jobs/openapi.json`
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenApiDocument(pub Value);
+
+impl Serialize for OpenApiDocument {
+    fn to_value(&self) -> Value {
+        self.0.clone()
+    }
+}
+
+/// Descreve as rotas de [`routes`] e os schemas dos DTOs
+pub fn openapi() -> OpenApiDocument {
+    let id_param = path_param("id", "Job id", integer());
+    let name_param = path_param("name", "Workflow name", string());
+    let job_responses = |ok: &str| {
+        responses([
+            (200, ok, Some(schema_ref("Job"))),
+            (400, "Invalid job id", Some(schema_ref("Error"))),
+            (404, "Job not found", Some(schema_ref("Error"))),
+        ])
+    };
+
+    let paths = object([
+        (
+            "/jobs",
+            object([
+                (
+                    "get",
+                    operation(
+                        "listJobs",
+                        "List jobs",
+                        vec![
+                            query_param("state", "Comma-separated states", string_enum(ALL_STATES.map(state_name))),
+                            query_param(
+                                "priority",
+                                "Comma-separated priorities",
+                                string_enum(ALL_PRIORITIES.map(priority_name)),
+                            ),
+                            query_param("limit", "Maximum number of jobs returned", integer()),
+                            query_param("offset", "Number of matching jobs to skip", integer()),
+                        ],
+                        None,
+                        responses([
+                            (200, "Matching jobs", Some(schema_ref("JobList"))),
+                            (400, "Invalid filter", Some(schema_ref("Error"))),
+                        ]),
+                    ),
+                ),
+                (
+                    "post",
+                    operation(
+                        "submitJob",
+                        "Submit a job",
+                        vec![],
+                        Some(schema_ref("SubmitJob")),
+                        responses([
+                            (201, "Job submitted", Some(schema_ref("Job"))),
+                            (400, "Invalid request body", Some(schema_ref("Error"))),
+                            (409, "Job id already in use", Some(schema_ref("Error"))),
+                        ]),
+                    ),
+                ),
+            ]),
+        ),
+        (
+            "/jobs/{id}",
+            object([(
+                "get",
+                operation("getJob", "Get job status", vec![id_param.clone()], None, job_responses("Job status")),
+            )]),
+        ),
+        (
+            "/jobs/{id}/cancel",
+            object([(
+                "post",
+                operation("cancelJob", "Cancel a pending or running job", vec![id_param.clone()], None, {
+                    let mut responses = job_responses("Job cancelled");
+                    if let Value::Object(map) = &mut responses {
+                        map.insert("409".into(), response("Job already finished", Some(schema_ref("Error"))));
+                    }
+                    responses
+                }),
+            )]),
+        ),
+        (
+            "/jobs/{id}/events",
+            object([(
+                "get",
+                operation(
+                    "jobEvents",
+                    "Stream job state changes as server-sent events",
+                    vec![id_param],
+                    None,
+                    {
+                        let mut responses = job_responses("Job events");
+                        if let Value::Object(map) = &mut responses {
+                            let stream = object([("text/event-stream", object([("schema", string())]))]);
+                            let description = "`progress` events with a Job payload, then `done`";
+                            map.insert("200".into(), object([("description", description.into()), ("content", stream)]));
+                        }
+                        responses
+                    },
+                ),
+            )]),
+        ),
+        (
+            "/jobs/metrics",
+            object([(
+                "get",
+                operation(
+                    "getJobMetrics",
+                    "Job counts and execution metrics",
+                    vec![],
+                    None,
+                    responses([(200, "Job metrics", Some(schema_ref("JobMetrics")))]),
+                ),
+            )]),
+        ),
+        (
+            "/workflows",
+            object([(
+                "get",
+                operation(
+                    "listWorkflows",
+                    "Registered workflow names",
+                    vec![],
+                    None,
+                    responses([(200, "Workflow names", Some(array(string())))]),
+                ),
+            )]),
+        ),
+        (
+            "/workflows/{name}",
+            object([(
+                "get",
+                operation(
+                    "getWorkflow",
+                    "Workflow topology",
+                    vec![name_param],
+                    None,
+                    responses([
+                        (200, "Workflow topology", Some(schema_ref("WorkflowTopology"))),
+                        (404, "Workflow not found", Some(schema_ref("Error"))),
+                    ]),
+                ),
+            )]),
+        ),
+    ]);
+
+    let state = string_enum(ALL_STATES.map(state_name));
+    let priority = string_enum(ALL_PRIORITIES.map(priority_name));
+    let schemas = object([
+        (
+            "Job",
+            schema_object(
+                [("id", integer()), ("state", state.clone()), ("priority", priority.clone())],
+                &["id", "state", "priority"],
+            ),
+        ),
+        ("SubmitJob", schema_object([("id", integer()), ("priority", priority)], &[])),
+        (
+            "JobList",
+            schema_object([("jobs", array(schema_ref("Job"))), ("total", integer())], &["jobs", "total"]),
+        ),
+        (
+            "JobMetrics",
+            schema_object(
+                [
+                    ("total", integer()),
+                    (
+                        "byState",
+                        object([
+                            ("type", "object".into()),
+                            ("additionalProperties", integer()),
+                        ]),
+                    ),
+                    ("attempts", integer()),
+                    ("successes", integer()),
+                    ("failures", integer()),
+                    ("successRate", object([("type", "number".into())])),
+                    ("averageDurationMs", integer()),
+                ],
+                &["total", "byState", "attempts", "successes", "failures", "successRate"],
+            ),
+        ),
+        (
+            "WorkflowNode",
+            schema_object(
+                [("id", integer()), ("name", string()), ("dependencies", array(integer()))],
+                &["id", "dependencies"],
+            ),
+        ),
+        (
+            "WorkflowTopology",
+            schema_object(
+                [
+                    ("name", string()),
+                    ("nodes", array(schema_ref("WorkflowNode"))),
+                    ("executionOrder", array(integer())),
+                ],
+                &["name", "nodes", "executionOrder"],
+            ),
+        ),
+        (
+            "Error",
+            schema_object(
+                [
+                    ("error", string()),
+                    ("code", integer()),
+                    ("name", string()),
+                    ("category", string()),
+                    ("retryable", object([("type", "boolean".into())])),
+                    ("context", array(string())),
+                ],
+                &["error", "code", "name", "category", "retryable"],
+            ),
+        ),
+    ]);
+
+    OpenApiDocument(object([
+        ("openapi", "3.0.3".into()),
+        (
+            "info",
+            object([
+                ("title", "Avila job management API".into()),
+                ("version", env!("CARGO_PKG_VERSION").into()),
+            ]),
+        ),
+        ("paths", paths),
+        ("components", object([("schemas", schemas)])),
+    ]))
+}
+
+fn operation(id: &str, summary: &str, parameters: Vec<Value>, body: Option<Value>, responses: Value) -> Value {
+    let mut op = HashMap::new();
+    op.insert("operationId".to_string(), id.into());
+    op.insert("summary".to_string(), summary.into());
+    op.insert("tags".to_string(), Value::Array(vec!["jobs".into()]));
+    if !parameters.is_empty() {
+        op.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    if let Some(schema) = body {
+        op.insert(
+            "requestBody".to_string(),
+            object([("required", false.into()), ("content", json_content(schema))]),
+        );
+    }
+    op.insert("responses".to_string(), responses);
+    Value::Object(op)
+}
+
+fn responses<const N: usize>(entries: [(u16, &str, Option<Value>); N]) -> Value {
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(status, description, schema)| (status.to_string(), response(description, schema)))
+            .collect(),
+    )
+}
+
+fn response(description: &str, schema: Option<Value>) -> Value {
+    match schema {
+        Some(schema) => object([("description", description.into()), ("content", json_content(schema))]),
+        None => object([("description", description.into())]),
+    }
+}
+
+fn json_content(schema: Value) -> Value {
+    object([("application/json", object([("schema", schema)]))])
+}
+
+fn path_param(name: &str, description: &str, schema: Value) -> Value {
+    parameter(name, "path", description, true, schema)
+}
+
+fn query_param(name: &str, description: &str, schema: Value) -> Value {
+    parameter(name, "query", description, false, schema)
+}
+
+fn parameter(name: &str, location: &str, description: &str, required: bool, schema: Value) -> Value {
+    object([
+        ("name", name.into()),
+        ("in", location.into()),
+        ("description", description.into()),
+        ("required", required.into()),
+        ("schema", schema),
+    ])
+}
+
+fn schema_object<const N: usize>(properties: [(&str, Value); N], required: &[&str]) -> Value {
+    let mut schema = HashMap::new();
+    schema.insert("type".to_string(), "object".into());
+    schema.insert("properties".to_string(), object(properties));
+    if !required.is_empty() {
+        schema.insert(
+            "required".to_string(),
+            Value::Array(required.iter().map(|&r| r.into()).collect()),
+        );
+    }
+    Value::Object(schema)
+}
+
+fn schema_ref(name: &str) -> Value {
+    object([("$ref", format!("#/components/schemas/{}", name).into())])
+}
+
+fn integer() -> Value {
+    object([("type", "integer".into())])
+}
+
+fn string() -> Value {
+    object([("type", "string".into())])
+}
+
+fn string_enum<const N: usize>(values: [&str; N]) -> Value {
+    object([
+        ("type", "string".into()),
+        ("enum", Value::Array(values.iter().map(|&v| v.into()).collect())),
+    ])
+}
+
+fn array(items: Value) -> Value {
+    object([("type", "array".into()), ("items", items)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HeaderMap, Method};
+    use std::future::Future;
+    use avila_coordinator::{TaskId, WorkflowNode};
+
+    fn request(method: Method, target: &str, body: &str) -> Request {
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), crate::parse_query(query)),
+            None => (target.to_string(), HashMap::new()),
+        };
+        Request {
+            method,
+            path,
+            query,
+            params: HashMap::new(),
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+            state: crate::State::default(),
+            trace: None,
+            deadline: None,
+        }
+    }
+
+    fn respond(router: &Router, method: Method, target: &str, body: &str) -> Response {
+        // Os handlers de jobs são síncronos: o primeiro poll já resolve
+        let mut future = std::pin::pin!(router.handle_request(request(method, target, body)));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let std::task::Poll::Ready(response) = future.as_mut().poll(&mut cx) else {
+            panic!("job handler did not complete synchronously");
+        };
+        response
+    }
+
+    fn call(router: &Router, method: Method, target: &str, body: &str) -> (u16, Value) {
+        let response = respond(router, method, target, body);
+        let body = String::from_utf8(response.body).unwrap();
+        (response.status, Value::from_json(&body).unwrap())
+    }
+
+    fn service() -> Arc<JobService> {
+        Arc::new(JobService::new(Arc::new(Mutex::new(Coordinator::new()))))
+    }
+
+    #[test]
+    fn test_submit_status_cancel() {
+        let service = service();
+        let router = routes(Arc::clone(&service));
+
+        let (status, body) = call(&router, Method::Post, "/jobs", "");
+        assert_eq!(status, 201);
+        assert_eq!(JobDto::from_value(body).unwrap().state, TaskState::Pending);
+
+        let (status, body) = call(&router, Method::Post, "/jobs", r#"{"priority": "high"}"#);
+        assert_eq!(status, 201);
+        let job = JobDto::from_value(body).unwrap();
+        assert_eq!((job.id, job.priority), (2, Priority::High));
+
+        let (status, _) = call(&router, Method::Post, "/jobs", r#"{"id": 2}"#);
+        assert_eq!(status, 409);
+        let (status, _) = call(&router, Method::Post, "/jobs", r#"{"priority": "urgent"}"#);
+        assert_eq!(status, 400);
+
+        service.coordinator().lock().unwrap().start(1).unwrap();
+        let (status, body) = call(&router, Method::Get, "/jobs/1", "");
+        assert_eq!(status, 200);
+        assert_eq!(JobDto::from_value(body).unwrap().state, TaskState::Running);
+
+        let (status, body) = call(&router, Method::Post, "/jobs/1/cancel", "");
+        assert_eq!(status, 200);
+        assert_eq!(JobDto::from_value(body).unwrap().state, TaskState::Cancelled);
+
+        let (status, body) = call(&router, Method::Post, "/jobs/1/cancel", "");
+        assert_eq!(status, 409);
+        assert!(ErrorBody::from_value(body).unwrap().error.contains("Cancelled"));
+
+        assert_eq!(call(&router, Method::Get, "/jobs/99", "").0, 404);
+        assert_eq!(call(&router, Method::Get, "/jobs/abc", "").0, 400);
+
+        // Erros com o mesmo corpo, seja do handler ou do router
+        let (_, body) = call(&router, Method::Get, "/jobs/99", "");
+        let error = ErrorBody::from_value(body).unwrap();
+        assert_eq!((error.code, error.name.as_str()), (2100, "JOB_NOT_FOUND"));
+        let (status, body) = call(&router, Method::Delete, "/nothing/here", "");
+        assert_eq!(status, 404);
+        assert_eq!(ErrorBody::from_value(body).unwrap().name, "HTTP_ROUTE_NOT_FOUND");
+    }
+
+    #[test]
+    fn test_list_filters_and_metrics() {
+        let service = service();
+        for priority in [Priority::Low, Priority::High, Priority::High, Priority::Critical] {
+            service
+                .submit(SubmitJob {
+                    id: None,
+                    priority: Some(priority),
+                })
+                .unwrap();
+        }
+        service.cancel(3).unwrap();
+        let router = routes(Arc::clone(&service));
+
+        let (_, body) = call(&router, Method::Get, "/jobs?priority=high,critical&state=pending", "");
+        let list = JobList::from_value(body).unwrap();
+        assert_eq!(list.total, 2);
+        assert_eq!(list.jobs.iter().map(|j| j.id).collect::<Vec<_>>(), vec![2, 4]);
+
+        let (_, body) = call(&router, Method::Get, "/jobs?limit=2&offset=1", "");
+        let list = JobList::from_value(body).unwrap();
+        assert_eq!(list.total, 4);
+        assert_eq!(list.jobs.iter().map(|j| j.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        assert_eq!(call(&router, Method::Get, "/jobs?state=done", "").0, 400);
+
+        let (status, body) = call(&router, Method::Get, "/jobs/metrics", "");
+        assert_eq!(status, 200);
+        let metrics = JobMetrics::from_value(body).unwrap();
+        assert_eq!(metrics.total, 4);
+        assert_eq!(metrics.by_state["pending"], 3);
+        assert_eq!(metrics.by_state["cancelled"], 1);
+        assert_eq!(metrics.average_duration_ms, None);
+    }
+
+    #[test]
+    fn test_workflow_topology() {
+        let service = service();
+        let mut workflow = Workflow::new("ingest".to_string());
+        workflow
+            .add_node(WorkflowNode::new(TaskId::new(1)).with_name("download".to_string()))
+            .unwrap();
+        workflow.add_node(WorkflowNode::new(TaskId::new(2))).unwrap();
+        workflow.add_edge(TaskId::new(2), TaskId::new(1)).unwrap();
+        service.register_workflow(workflow);
+        let router = routes(Arc::clone(&service));
+
+        let (_, body) = call(&router, Method::Get, "/workflows", "");
+        assert_eq!(Vec::<String>::from_value(body).unwrap(), vec!["ingest"]);
+
+        let (status, body) = call(&router, Method::Get, "/workflows/ingest", "");
+        assert_eq!(status, 200);
+        let topology = WorkflowTopology::from_value(body).unwrap();
+        assert_eq!(topology.execution_order, vec![2, 1]);
+        assert_eq!(topology.nodes[0].name.as_deref(), Some("download"));
+        assert_eq!(topology.nodes[0].dependencies, vec![2]);
+
+        assert_eq!(call(&router, Method::Get, "/workflows/missing", "").0, 404);
+    }
+
+    #[test]
+    fn test_openapi_document() {
+        let router = routes(service());
+        let (status, doc) = call(&router, Method::Get, "/jobs/openapi.json", "");
+        assert_eq!(status, 200);
+
+        let paths = doc.as_object().unwrap()["paths"].as_object().unwrap();
+        for path in ["/jobs", "/jobs/{id}", "/jobs/{id}/cancel", "/jobs/metrics", "/workflows", "/workflows/{name}"] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+        let submit = paths["/jobs"].as_object().unwrap()["post"].as_object().unwrap();
+        assert_eq!(submit["operationId"].as_str(), Some("submitJob"));
+        let schemas = doc.as_object().unwrap()["components"].as_object().unwrap()["schemas"]
+            .as_object()
+            .unwrap();
+        assert!(schemas.contains_key("JobMetrics"));
+    }
+
+    #[test]
+    fn test_jobs_extractor() {
+        let service = service();
+        let router = Router::new()
+            .post("/reports", |req| async move {
+                let jobs = match Jobs::from_request(&req) {
+                    Ok(jobs) => jobs,
+                    Err(response) => return response,
+                };
+                match jobs.enqueue(Priority::High) {
+                    Ok(job) => Response::created().json(&job),
+                    Err(err) => task_error_response(&err),
+                }
+            })
+            .merge(routes(Arc::clone(&service)));
+
+        let (status, body) = call(&router, Method::Post, "/reports", "");
+        assert_eq!(status, 201);
+        let job = JobDto::from_value(body).unwrap();
+        assert_eq!(service.status(job.id).unwrap().priority, Priority::High);
+
+        let bare = Router::new().post("/reports", |req| async move {
+            Jobs::from_request(&req).err().unwrap_or_else(Response::ok)
+        });
+        assert_eq!(call(&bare, Method::Post, "/reports", "").0, 500);
+    }
+
+    #[test]
+    fn test_progress_events() {
+        let service = Arc::new(
+            JobService::new(Arc::new(Mutex::new(Coordinator::new()))).with_progress_interval(Duration::from_millis(1)),
+        );
+        let router = routes(Arc::clone(&service));
+        let job = service.submit(SubmitJob::default()).unwrap();
+
+        let response = respond(&router, Method::Get, &format!("/jobs/{}/events", job.id), "");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers["Content-Type"], "text/event-stream");
+        assert_eq!(respond(&router, Method::Get, "/jobs/99/events", "").status, 404);
+
+        let coordinator = service.coordinator();
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            coordinator.lock().unwrap().start(1).unwrap();
+            std::thread::sleep(Duration::from_millis(30));
+            coordinator.lock().unwrap().complete(1).unwrap();
+        });
+        let events: Vec<String> = response
+            .stream
+            .unwrap()
+            .map(|chunk| String::from_utf8(chunk).unwrap())
+            .collect();
+        worker.join().unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert!(events[0].starts_with("event: progress\nid: 1\n") && events[0].contains(r#""state":"pending""#));
+        assert!(events[1].contains(r#""state":"running""#));
+        assert!(events[2].starts_with("event: done\nid: 3\n") && events[2].contains(r#""state":"completed""#));
+    }
+
+    #[test]
+    fn test_event_bus_wakes_progress_stream() {
+        use avila_coordinator::EventBus;
+
+        // Sem o barramento, a próxima consulta só viria depois de 10 s
+        let service = Arc::new(
+            JobService::new(Arc::new(Mutex::new(Coordinator::new()))).with_progress_interval(Duration::from_secs(10)),
+        );
+        let job = service.submit(SubmitJob::default()).unwrap();
+        let events = service.events(job.id).unwrap();
+
+        let handler = service.event_handler();
+        let coordinator = service.coordinator();
+        let worker = std::thread::spawn(move || {
+            let mut bus = EventBus::new();
+            bus.subscribe(Box::new(handler));
+            std::thread::sleep(Duration::from_millis(20));
+            coordinator.lock().unwrap().start(job.id).unwrap();
+            bus.publish(&TaskEvent::Started(TaskId::new(job.id)));
+            std::thread::sleep(Duration::from_millis(20));
+            coordinator.lock().unwrap().complete(job.id).unwrap();
+            bus.publish(&TaskEvent::Completed(TaskId::new(job.id)));
+        });
+
+        let started = Instant::now();
+        let names: Vec<String> = events.filter_map(|event| event.event).collect();
+        worker.join().unwrap();
+        assert_eq!(names, ["progress", "progress", "done"]);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_workflow_run() {
+        let service = service();
+        service.submit(SubmitJob::default()).unwrap();
+        let mut workflow = Workflow::new("ingest".to_string());
+        workflow
+            .add_node(WorkflowNode::new(TaskId::new(1)).with_name("download".to_string()))
+            .unwrap();
+        workflow.add_node(WorkflowNode::new(TaskId::new(2))).unwrap();
+        workflow.add_edge(TaskId::new(2), TaskId::new(1)).unwrap();
+        service.register_workflow(workflow);
+        let jobs = Jobs::new(Arc::clone(&service));
+
+        let run = jobs.enqueue_workflow("ingest", Priority::Low).unwrap();
+        assert_eq!(run.jobs.iter().map(|j| (j.node, j.job.id)).collect::<Vec<_>>(), vec![(2, 2), (1, 3)]);
+        assert_eq!(run.jobs[1].name.as_deref(), Some("download"));
+        assert_eq!(run.progress, 0.0);
+        assert!(matches!(jobs.enqueue_workflow("missing", Priority::Low), Err(TaskError::NotFound)));
+
+        {
+            let coordinator = service.coordinator();
+            let mut coordinator = coordinator.lock().unwrap();
+            for id in [2, 3] {
+                coordinator.start(id).unwrap();
+            }
+            coordinator.complete(2).unwrap();
+            coordinator.fail(3).unwrap();
+        }
+        let status = jobs.run_status(run.id).unwrap();
+        assert_eq!(status.progress, 0.5);
+        assert_eq!(RunStatus::from_value(status.to_value()).unwrap(), status);
+
+        let events: Vec<SseEvent> = service.run_events(run.id).unwrap().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event.as_deref(), Some("done"));
+        assert_eq!(jobs.run_events(99).status, 404);
+    }
+
+    #[test]
+    fn test_trace_context_recorded() {
+        use avila_tracing::{SpanData, SpanHook};
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<SpanData>>);
+
+        impl SpanHook for Recorder {
+            fn on_end(&self, span: &SpanData) {
+                self.0.lock().unwrap().push(span.clone());
+            }
+        }
+
+        let service = service();
+        let recorder = Arc::new(Recorder::default());
+        let router = routes(Arc::clone(&service)).span_hook(recorder.clone());
+
+        let mut req = request(Method::Post, "/jobs", "");
+        req.headers.insert(
+            "traceparent".to_string(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+        );
+        let mut future = std::pin::pin!(router.handle_request(req));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let std::task::Poll::Ready(response) = future.as_mut().poll(&mut cx) else {
+            panic!("job handler did not complete synchronously");
+        };
+        assert_eq!(response.status, 201);
+
+        let span = {
+            let spans = recorder.0.lock().unwrap();
+            assert_eq!(spans.len(), 1);
+            spans[0].clone()
+        };
+        assert_eq!(span.name, "POST /jobs");
+        assert_eq!(span.status, Some(201));
+        assert_eq!(span.parent.map(|p| p.0), Some(0x00f067aa0ba902b7));
+
+        let context = service.trace_context(1).unwrap();
+        assert_eq!(context, span.context);
+        assert_eq!(context.trace_id.0, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+
+        // Sem traceparent, a requisição inicia um trace próprio
+        call(&router, Method::Post, "/jobs", "");
+        assert_ne!(service.trace_context(2).unwrap().trace_id, context.trace_id);
+    }
+
+    #[test]
+    fn test_deadline_recorded() {
+        let service = service();
+        let post = |router: &Router, timeout: Option<&str>| {
+            let mut req = request(Method::Post, "/jobs", "");
+            if let Some(timeout) = timeout {
+                req.headers.insert("x-request-timeout".to_string(), timeout.to_string());
+            }
+            let mut future = std::pin::pin!(router.handle_request(req));
+            let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+            let std::task::Poll::Ready(response) = future.as_mut().poll(&mut cx) else {
+                panic!("job handler did not complete synchronously");
+            };
+            response.status
+        };
+
+        // Sem prazo da rota, vale o do chamador
+        let router = routes(Arc::clone(&service));
+        assert_eq!(post(&router, Some("5000")), 201);
+        let remaining = service.deadline(1).unwrap().remaining();
+        assert!(remaining > Duration::from_secs(4) && remaining <= Duration::from_secs(5));
+        assert_eq!(post(&router, None), 201);
+        assert_eq!(service.deadline(2), None);
+        assert_eq!(post(&router, Some("0")), 504);
+        assert!(service.status(3).is_none());
+
+        // O menor prazo prevalece
+        let router = routes(Arc::clone(&service)).timeout(Method::Post, "/jobs", Duration::from_millis(20));
+        assert_eq!(post(&router, Some("5000")), 201);
+        assert!(service.deadline(3).unwrap().remaining() <= Duration::from_millis(20));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(service.cancel_expired(), [3]);
+        assert_eq!(service.status(3).unwrap().state, TaskState::Cancelled);
+        assert_eq!(service.status(1).unwrap().state, TaskState::Pending);
+        assert!(service.cancel_expired().is_empty());
+    }
+}
@@ -1,29 +1,147 @@
 //! Avila Web - Framework web nativo
 //! Substitui axum/tower
 
-use avila_error::{Error, Result};
+use avila_error::{Error, ErrorCode, Result};
 use avila_serde::{Deserialize, Serialize};
 use avila_async::net::{TcpListener, TcpStream};
+use avila_tracing::{ActiveSpan, Deadline, SpanHook, SpanKind, TraceContext, REQUEST_TIMEOUT, TRACEPARENT, TRACESTATE};
+use avila_url::percent;
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::future::Future;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+mod bulkhead;
+mod conditional;
+mod error;
+mod files;
+mod http2;
+pub mod jobs;
+mod multipart;
+pub mod queue;
+pub mod sse;
+
+pub use avila_headers::{http_date, parse_http_date, Authorization, ContentType, Cookie, HeaderMap, SameSite, SetCookie};
+pub use bulkhead::{Bulkhead, BulkheadConfig, BulkheadMetrics, BulkheadPermit, Rejection};
+pub use conditional::Validators;
+pub use error::ErrorBody;
+pub use files::{serve_bytes, serve_file, StaticFiles};
+pub use multipart::Part;
+pub use sse::{SseEvent, SseSender, SseStream};
+
+/// Certificado/chave do servidor com recarga a quente
+pub use avila_tls_config as tls;
+/// URLs e percent-encoding (RFC 3986)
+pub use avila_url as url;
+/// Headers tipados, cookies e datas HTTP
+pub use avila_headers as headers;
 
 pub type Handler = Arc<dyn Fn(Request) -> Pin<Box<dyn Future<Output = Response> + Send>> + Send + Sync>;
 
+/// Corpo entregue em partes, escritas e enviadas assim que produzidas
+pub type BodyStream = Box<dyn Iterator<Item = Vec<u8>> + Send>;
+
+/// Limite padrão do corpo de uma requisição
+pub const DEFAULT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Tamanho das partes lidas por `Response::reader`
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct Router {
     routes: HashMap<(Method, String), Handler>,
+    bulkheads: HashMap<(Method, String), Arc<Bulkhead>>,
+    timeouts: HashMap<(Method, String), Duration>,
+    state: State,
+    span_hooks: Vec<Arc<dyn SpanHook>>,
+    body_limit: usize,
 }
 
 impl Router {
     pub fn new() -> Self {
         Self {
             routes: HashMap::new(),
+            bulkheads: HashMap::new(),
+            timeouts: HashMap::new(),
+            state: State::default(),
+            span_hooks: Vec::new(),
+            body_limit: DEFAULT_BODY_LIMIT,
         }
     }
 
+    /// Maior corpo de requisição aceito, em bytes; acima dele a resposta é 413
+    pub fn body_limit(mut self, bytes: usize) -> Self {
+        self.body_limit = bytes;
+        self
+    }
+
+    /// Serve os arquivos de `dir` em `GET {prefix}/...`, com suporte a `Range`
+    pub fn serve_dir(self, prefix: &str, dir: impl Into<std::path::PathBuf>) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+        let files = Arc::new(StaticFiles::new(dir));
+        let index = Arc::clone(&files);
+        self.get(&format!("{}/{{*path}}", prefix), move |req| {
+            let files = Arc::clone(&files);
+            async move { files.respond(&req) }
+        })
+        .get(if prefix.is_empty() { "/" } else { prefix }, move |req| {
+            let files = Arc::clone(&index);
+            async move { files.respond(&req) }
+        })
+    }
+
+    /// Recebe início e fim do span de servidor de cada requisição roteada
+    pub fn span_hook(mut self, hook: Arc<dyn SpanHook>) -> Self {
+        self.span_hooks.push(hook);
+        self
+    }
+
+    /// Registra um valor compartilhado, acessível em todo handler via `Request::state`
+    ///
+    /// Há um valor por tipo; registrar o mesmo tipo de novo substitui o anterior.
+    pub fn state<T: Send + Sync + 'static>(mut self, value: Arc<T>) -> Self {
+        Arc::make_mut(&mut self.state.0).insert(TypeId::of::<T>(), value);
+        self
+    }
+
+    /// Limita a concorrência de uma rota (bulkhead)
+    ///
+    /// Excedentes esperam na fila configurada; com a fila cheia ou após o
+    /// timeout, a resposta é 503 com `Retry-After`.
+    pub fn bulkhead(mut self, method: Method, path: &str, config: BulkheadConfig) -> Self {
+        self.bulkheads
+            .insert((method, path.to_string()), Arc::new(Bulkhead::new(config)));
+        self
+    }
+
+    /// Prazo máximo de uma rota, contado da chegada da requisição
+    ///
+    /// Um `x-request-timeout` menor enviado pelo chamador prevalece. O prazo
+    /// fica em `Request::deadline` e limita as chamadas feitas pelo handler
+    /// (clientes `avila-http`, jobs submetidos); a resposta do handler não é
+    /// interrompida.
+    pub fn timeout(mut self, method: Method, path: &str, timeout: Duration) -> Self {
+        self.timeouts.insert((method, path.to_string()), timeout);
+        self
+    }
+
+    /// Handle para as métricas de saturação de uma rota
+    pub fn bulkhead_for(&self, method: Method, path: &str) -> Option<Arc<Bulkhead>> {
+        self.bulkheads.get(&(method, path.to_string())).cloned()
+    }
+
+    /// Métricas de todos os bulkheads
+    pub fn bulkhead_metrics(&self) -> Vec<(Method, String, BulkheadMetrics)> {
+        self.bulkheads
+            .iter()
+            .map(|((method, path), bulkhead)| (*method, path.clone(), bulkhead.metrics()))
+            .collect()
+    }
+
     pub fn get<F, Fut>(mut self, path: &str, handler: F) -> Self
     where
         F: Fn(Request) -> Fut + Send + Sync + 'static,
@@ -64,14 +182,98 @@ impl Router {
         self
     }
 
-    async fn handle_request(&self, req: Request) -> Response {
-        let key = (req.method, req.path.clone());
+    /// Incorpora as rotas (e bulkheads, prazos e estado) de outro router
+    pub fn merge(mut self, other: Router) -> Self {
+        self.routes.extend(other.routes);
+        self.bulkheads.extend(other.bulkheads);
+        self.timeouts.extend(other.timeouts);
+        Arc::make_mut(&mut self.state.0).extend(other.state.0.iter().map(|(k, v)| (*k, Arc::clone(v))));
+        self.span_hooks.extend(other.span_hooks);
+        self
+    }
+
+    /// Rota exata ou, na falta dela, o padrão com `{param}` mais específico
+    fn find_route(&self, method: Method, path: &str) -> Option<((Method, String), HashMap<String, String>)> {
+        let key = (method, path.to_string());
+        if self.routes.contains_key(&key) {
+            return Some((key, HashMap::new()));
+        }
 
-        if let Some(handler) = self.routes.get(&key) {
-            handler(req).await
-        } else {
-            Response::not_found()
+        self.routes
+            .keys()
+            .filter(|(m, _)| *m == method)
+            .filter_map(|(m, pattern)| {
+                match_pattern(pattern, path).map(|params| {
+                    let rank = (pattern.contains("{*"), params.len(), pattern.as_str());
+                    (rank, (*m, pattern.clone()), params)
+                })
+            })
+            // `{*resto}` só vence se nada mais casar; depois, menos parâmetros = mais
+            // literais; desempate determinístico pelo padrão
+            .min_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, key, params)| (key, params))
+    }
+
+    async fn handle_request(&self, mut req: Request) -> Response {
+        let Some((key, params)) = self.find_route(req.method, &req.path) else {
+            let message = format!("No route for {} {}", req.method.as_str(), req.path);
+            return Response::error(&Error::coded(ErrorCode::HTTP_ROUTE_NOT_FOUND, message));
+        };
+        let handler = &self.routes[&key];
+        req.params = params;
+        req.state = self.state.clone();
+
+        // Continua o trace do chamador (W3C traceparent) ou inicia um novo
+        let parent = req
+            .header(TRACEPARENT)
+            .and_then(|traceparent| TraceContext::from_headers(traceparent, req.header(TRACESTATE)));
+        let mut span = ActiveSpan::start(
+            format!("{} {}", key.0.as_str(), key.1),
+            SpanKind::Server,
+            parent.as_ref(),
+            &self.span_hooks,
+        );
+        span.set_attribute("http.method", key.0.as_str());
+        span.set_attribute("http.route", key.1.as_str());
+        req.trace = Some(span.context().clone());
+
+        // Prazo: o menor entre o `x-request-timeout` do chamador e o da rota
+        let deadline = req
+            .header(REQUEST_TIMEOUT)
+            .and_then(Deadline::from_header)
+            .into_iter()
+            .chain(self.timeouts.get(&key).map(|&timeout| Deadline::after(timeout)))
+            .min();
+        if deadline.is_some_and(|deadline| deadline.is_expired()) {
+            span.set_status(504);
+            return Response::error(&Error::coded(ErrorCode::HTTP_DEADLINE_EXCEEDED, "Request deadline exceeded"));
         }
+        req.deadline = deadline;
+
+        let _permit = match self.bulkheads.get(&key).map(|b| b.acquire()) {
+            Some(Err(_)) => {
+                span.set_status(503);
+                let err = Error::coded(ErrorCode::HTTP_OVERLOADED, format!("Route {} is at capacity", key.1));
+                return Response::error(&err).header("Retry-After", "1");
+            }
+            Some(Ok(permit)) => Some(permit),
+            None => None,
+        };
+        // Clientes chamados pelo handler herdam o span via `TraceContext::current()`
+        // e o prazo via `Deadline::current()`, tanto na parte síncrona do handler
+        // quanto em cada poll do future
+        let context = span.context().clone();
+        let future = context.scope(|| match deadline {
+            Some(deadline) => deadline.scope(|| handler(req)),
+            None => handler(req),
+        });
+        let future = context.instrument(future);
+        let response = match deadline {
+            Some(deadline) => deadline.instrument(future).await,
+            None => future.await,
+        };
+        span.set_status(response.status);
+        response
     }
 
     pub async fn serve(self, addr: SocketAddr) -> Result<()> {
@@ -79,7 +281,7 @@ impl Router {
             .await
             .map_err(|e| Error::network(format!("Failed to bind: {}", e)))?;
 
-        println!("🚀 Server running on http://{}", addr);
+        avila_log::info!(addr = %addr, "Server running");
 
         let router = Arc::new(self);
 
@@ -94,7 +296,7 @@ impl Router {
             std::thread::spawn(move || {
                 let stream = stream.into_std();
                 if let Err(e) = handle_connection_sync(stream, router) {
-                    eprintln!("Error handling connection: {}", e);
+                    avila_log::error!(error = %e, "Error handling connection");
                 }
             });
         }
@@ -107,51 +309,197 @@ impl Default for Router {
     }
 }
 
-fn handle_connection_sync(stream: std::net::TcpStream, router: Arc<Router>) -> Result<()> { let mut reader = BufReader::new(stream.try_clone().map_err(|e| Error::io(e.to_string()))?); let request = parse_request_sync(&mut reader)?; let runtime = avila_async::Runtime::new(); let response = runtime.block_on(async move { router.handle_request(request).await });
-
+/// Atende uma conexão: HTTP/2 se começar com o prefácio, HTTP/1.1 caso contrário
+fn handle_connection_sync(stream: std::net::TcpStream, router: Arc<Router>) -> Result<()> {
+    // O listener é não bloqueante; a conexão é atendida com I/O bloqueante nesta thread
+    stream
+        .set_nonblocking(false)
+        .map_err(|e| Error::io(e.to_string()))?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| Error::io(e.to_string()))?);
+    let line = read_request_line(&mut reader)?;
+    if line == http2::PREFACE_LINE {
+        return http2::serve(stream, reader, router);
+    }
     let mut stream = stream;
-    let response_str = format!(
-        "HTTP/1.1 {} {}\r\n{}\r\n\r\n{}",
+    let mut request = parse_request(&line, &mut reader)?;
+
+    let body = body_framing(&request.headers, router.body_limit).and_then(|framing| {
+        let expects_continue = request
+            .header("expect")
+            .is_some_and(|expect| expect.eq_ignore_ascii_case("100-continue"));
+        if expects_continue && framing != BodyFraming::Empty {
+            stream
+                .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+                .map_err(|e| Error::io(format!("Failed to write response: {}", e)))?;
+        }
+        read_body(framing, &mut reader, router.body_limit)
+    });
+    request.body = match body {
+        Ok(body) => body,
+        Err(e) => {
+            write_response(&mut stream, Response::error(&e))?;
+            return Err(e);
+        }
+    };
+
+    let response = block_on(router.handle_request(request));
+    write_response(&mut stream, response)
+}
+
+/// Escreve a resposta HTTP/1.1
+///
+/// Corpo em partes sem `Content-Length` vai com `Transfer-Encoding: chunked`;
+/// cada parte sai imediatamente e um cliente desconectado encerra o stream.
+/// Respostas 204 e 304 não têm corpo nem `Content-Length` calculado.
+fn write_response<W: Write>(stream: &mut W, mut response: Response) -> Result<()> {
+    response.headers = valid_headers(std::mem::take(&mut response.headers));
+    let has_length = response.headers.contains_key("content-length");
+    let chunked = response.stream.is_some() && !has_length;
+    response.headers.remove("transfer-encoding");
+    if !response.headers.contains_key("connection") {
+        response.headers.insert("Connection", "close");
+    }
+    if chunked {
+        response.headers.insert("Transfer-Encoding", "chunked");
+    } else if !has_length && !matches!(response.status, 204 | 304) {
+        response.headers.insert("Content-Length", response.body.len().to_string());
+    }
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\n{}\r\n\r\n",
         response.status,
         status_text(response.status),
-        format_headers(&response.headers),
-        String::from_utf8_lossy(&response.body)
+        format_headers(&response.headers)
     );
+    let write_err = |e: std::io::Error| Error::io(format!("Failed to write response: {}", e));
+    stream.write_all(head.as_bytes()).map_err(write_err)?;
 
-    stream
-        .write_all(response_str.as_bytes())
-        .map_err(|e| Error::io(format!("Failed to write response: {}", e)))?;
-
+    let Some(chunks) = response.stream else {
+        return stream.write_all(&response.body).and_then(|_| stream.flush()).map_err(write_err);
+    };
+    let body = (!response.body.is_empty()).then_some(response.body);
+    for chunk in body.into_iter().chain(chunks) {
+        if chunk.is_empty() {
+            continue;
+        }
+        let written = if chunked {
+            write!(stream, "{:x}\r\n", chunk.len())
+                .and_then(|_| stream.write_all(&chunk))
+                .and_then(|_| stream.write_all(b"\r\n"))
+        } else {
+            stream.write_all(&chunk)
+        };
+        if written.and_then(|_| stream.flush()).is_err() {
+            return Ok(());
+        }
+    }
+    if chunked {
+        let _ = stream.write_all(b"0\r\n\r\n").and_then(|_| stream.flush());
+    }
     Ok(())
 }
 
-fn parse_request_sync<R: BufRead>(reader: &mut R) -> Result<Request> {
+/// Executa um future na thread atual, dormindo até o waker acordá-la
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+/// Casa `/jobs/{id}` com `/jobs/42`, retornando `{"id": "42"}`
+///
+/// Um último segmento `{*nome}` captura o resto do caminho (`/static/{*path}`
+/// casa `/static/css/app.css` com `path = "css/app.css"`), inclusive vazio.
+fn match_pattern(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    if !pattern.contains('{') {
+        return None;
+    }
+
+    let mut pattern_segments = pattern.split('/');
+    let mut path_segments = path.split('/');
+    let mut params = HashMap::new();
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return Some(params),
+            (Some(expected), Some(actual)) => {
+                if let Some(name) = expected.strip_prefix("{*").and_then(|p| p.strip_suffix('}')) {
+                    let rest: Vec<String> = std::iter::once(actual).chain(path_segments).map(percent::decode).collect();
+                    params.insert(name.to_string(), rest.join("/"));
+                    return Some(params);
+                }
+                if let Some(name) = expected.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                    if actual.is_empty() {
+                        return None;
+                    }
+                    params.insert(name.to_string(), percent::decode(actual));
+                } else if expected != actual {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Separa a query string em pares chave/valor decodificados
+fn parse_query(query: &str) -> HashMap<String, String> {
+    avila_url::percent::parse_form(query).into_iter().collect()
+}
+
+fn read_request_line<R: BufRead>(reader: &mut R) -> Result<String> {
     let mut line = String::new();
     reader
         .read_line(&mut line)
-        .map_err(|e| Error::parse(format!("Failed to read request line: {}", e)))?;
-
-    let parts: Vec<&str> = line.split_whitespace().collect();
-    if parts.len() < 3 {
-        return Err(Error::parse("Invalid request line"));
-    }
+        .map_err(|e| malformed(format!("Failed to read request line: {}", e)))?;
+    Ok(line)
+}
 
-    let method = match parts[0] {
+fn parse_method(method: &str) -> Method {
+    match method {
         "GET" => Method::Get,
         "POST" => Method::Post,
         "PUT" => Method::Put,
         "DELETE" => Method::Delete,
         _ => Method::Get,
-    };
+    }
+}
+
+/// Lê os cabeçalhos de uma requisição HTTP/1.1 cuja linha inicial é `line`
+///
+/// O corpo fica vazio; ele é lido depois com [`read_body`].
+fn parse_request<R: BufRead>(line: &str, reader: &mut R) -> Result<Request> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 3 {
+        return Err(malformed("Invalid request line"));
+    }
+
+    let method = parse_method(parts[0]);
 
-    let path = parts[1].to_string();
+    let (path, query) = match parts[1].split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (parts[1].to_string(), HashMap::new()),
+    };
 
-    let mut headers = HashMap::new();
+    let mut headers = HeaderMap::new();
     loop {
         let mut line = String::new();
         reader
             .read_line(&mut line)
-            .map_err(|e| Error::parse(format!("Failed to read header: {}", e)))?;
+            .map_err(|e| malformed(format!("Failed to read header: {}", e)))?;
 
         let line = line.trim();
         if line.is_empty() {
@@ -161,21 +509,114 @@ fn parse_request_sync<R: BufRead>(reader: &mut R) -> Result<Request> {
         if let Some(idx) = line.find(':') {
             let key = line[..idx].trim().to_lowercase();
             let value = line[idx + 1..].trim().to_string();
-            headers.insert(key, value);
+            headers.append(key, value);
         }
     }
 
-    let body = Vec::new(); // TODO: Read body based on Content-Length
-
     Ok(Request {
         method,
         path,
+        query,
+        params: HashMap::new(),
         headers,
-        body,
+        body: Vec::new(),
+        state: State::default(),
+        trace: None,
+        deadline: None,
     })
 }
 
-fn format_headers(headers: &HashMap<String, String>) -> String {
+/// Como o corpo da requisição é delimitado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyFraming {
+    Empty,
+    Length(usize),
+    Chunked,
+}
+
+/// `Transfer-Encoding: chunked` tem precedência sobre `Content-Length`
+///
+/// Um `Content-Length` acima de `limit` é rejeitado antes de ler o corpo, e
+/// valores repetidos que divergem, rejeitados de vez.
+fn body_framing(headers: &HeaderMap, limit: usize) -> Result<BodyFraming> {
+    if headers.contains_key("transfer-encoding") {
+        // Vários campos equivalem a uma lista; vale a última codificação
+        let encoding = headers.get_all("transfer-encoding").collect::<Vec<_>>().join(", ");
+        let last = encoding.rsplit(',').next().unwrap_or_default().trim();
+        if !last.eq_ignore_ascii_case("chunked") {
+            return Err(malformed(format!("Unsupported Transfer-Encoding: {}", encoding)));
+        }
+        return Ok(BodyFraming::Chunked);
+    }
+    match headers.get("content-length") {
+        Some(value) => {
+            let length = headers
+                .content_length()
+                .and_then(|length| usize::try_from(length).ok())
+                .ok_or_else(|| malformed(format!("Invalid Content-Length: {}", value)))?;
+            if length > limit {
+                return Err(body_too_large(limit));
+            }
+            Ok(if length == 0 { BodyFraming::Empty } else { BodyFraming::Length(length) })
+        }
+        None => Ok(BodyFraming::Empty),
+    }
+}
+
+fn body_too_large(limit: usize) -> Error {
+    Error::coded(ErrorCode::HTTP_BODY_TOO_LARGE, format!("Request body exceeds {} bytes", limit))
+}
+
+/// Requisição HTTP/1.1 fora do protocolo (respondida com 400)
+fn malformed(message: impl Into<String>) -> Error {
+    Error::parse(message).with_code(ErrorCode::HTTP_MALFORMED_REQUEST)
+}
+
+fn read_body<R: BufRead>(framing: BodyFraming, reader: &mut R, limit: usize) -> Result<Vec<u8>> {
+    let read_err = |e: std::io::Error| malformed(format!("Failed to read body: {}", e));
+    match framing {
+        BodyFraming::Empty => Ok(Vec::new()),
+        BodyFraming::Length(length) => {
+            let mut body = vec![0u8; length];
+            reader.read_exact(&mut body).map_err(read_err)?;
+            Ok(body)
+        }
+        BodyFraming::Chunked => {
+            let mut body = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).map_err(read_err)?;
+                // Extensões (`;nome=valor`) são ignoradas
+                let size = line.split(';').next().unwrap_or_default().trim();
+                let size = usize::from_str_radix(size, 16)
+                    .map_err(|_| malformed(format!("Invalid chunk size: {}", line.trim())))?;
+                if size == 0 {
+                    break;
+                }
+                if body.len() + size > limit {
+                    return Err(body_too_large(limit));
+                }
+                let start = body.len();
+                body.resize(start + size, 0);
+                reader.read_exact(&mut body[start..]).map_err(read_err)?;
+                let mut crlf = [0u8; 2];
+                reader.read_exact(&mut crlf).map_err(read_err)?;
+                if &crlf != b"\r\n" {
+                    return Err(malformed("Missing CRLF after chunk"));
+                }
+            }
+            // Trailers são descartados
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_err(read_err)? == 0 || line.trim().is_empty() {
+                    return Ok(body);
+                }
+            }
+        }
+    }
+}
+
+fn format_headers(headers: &HeaderMap) -> String {
     headers
         .iter()
         .map(|(k, v)| format!("{}: {}", k, v))
@@ -183,14 +624,40 @@ fn format_headers(headers: &HashMap<String, String>) -> String {
         .join("\r\n")
 }
 
+/// Descarta cabeçalhos que permitiriam injetar outros (CR/LF no valor)
+fn valid_headers(headers: HeaderMap) -> HeaderMap {
+    headers
+        .into_iter()
+        .filter(|(name, value)| match avila_headers::validate_header(name, value) {
+            Ok(()) => true,
+            Err(e) => {
+                avila_log::warn!(error = %e, "Dropping invalid response header");
+                false
+            }
+        })
+        .collect()
+}
+
 fn status_text(status: u16) -> &'static str {
     match status {
         200 => "OK",
         201 => "Created",
         204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        304 => "Not Modified",
         400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
         404 => "Not Found",
+        409 => "Conflict",
+        410 => "Gone",
+        413 => "Payload Too Large",
+        416 => "Range Not Satisfiable",
+        422 => "Unprocessable Content",
         500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
         _ => "Unknown",
     }
 }
@@ -203,92 +670,232 @@ pub enum Method {
     Delete,
 }
 
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
 pub struct Request {
     pub method: Method,
+    /// Caminho sem a query string
     pub path: String,
-    pub headers: HashMap<String, String>,
+    /// Parâmetros da query string (`?state=pending`)
+    pub query: HashMap<String, String>,
+    /// Segmentos capturados por `{nome}` na rota
+    pub params: HashMap<String, String>,
+    pub headers: HeaderMap,
     pub body: Vec<u8>,
+    /// Valores registrados com `Router::state`
+    pub state: State,
+    /// Span de servidor desta requisição, definido pelo router
+    pub trace: Option<TraceContext>,
+    /// Prazo da requisição (`x-request-timeout` ou `Router::timeout`), definido pelo router
+    pub deadline: Option<Deadline>,
 }
 
 impl Request {
     pub fn json<T: Deserialize>(&self) -> Result<T> {
-        let text = String::from_utf8(self.body.clone())
-            .map_err(|e| Error::parse(format!("Invalid UTF-8: {}", e)))?;
-        T::from_json(&text).map_err(|e| Error::parse(format!("JSON error: {}", e)))
+        let invalid = |message: String| Error::parse(message).with_code(ErrorCode::HTTP_INVALID_JSON);
+        let text = String::from_utf8(self.body.clone()).map_err(|e| invalid(format!("Invalid UTF-8: {}", e)))?;
+        T::from_json(&text).map_err(|e| invalid(format!("JSON error: {}", e)))
+    }
+
+    /// Primeiro valor do cabeçalho, sem diferenciar maiúsculas
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.get(key)
+    }
+
+    pub fn content_type(&self) -> Option<ContentType> {
+        self.headers.content_type()
+    }
+
+    pub fn authorization(&self) -> Option<Authorization> {
+        self.headers.authorization()
+    }
+
+    /// Cookies enviados pelo cliente; pares inválidos são ignorados
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.headers.cookies()
+    }
+
+    /// Valor do cookie `name`
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.cookies()
+            .into_iter()
+            .find(|cookie| cookie.name() == name)
+            .map(|cookie| cookie.value().to_string())
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
     }
 
-    pub fn header(&self, key: &str) -> Option<&String> {
-        self.headers.get(&key.to_lowercase())
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+
+    /// Valor de tipo `T` registrado com `Router::state`
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.state.get()
+    }
+
+    /// Partes de um corpo `multipart/form-data`, na ordem em que chegaram
+    pub fn multipart(&self) -> Result<Vec<Part>> {
+        self.header("content-type")
+            .ok_or_else(|| Error::parse("Missing Content-Type"))
+            .and_then(|content_type| multipart::parse(content_type, &self.body))
+            .map_err(|e| e.with_code(ErrorCode::HTTP_INVALID_MULTIPART))
+    }
+}
+
+/// Valores compartilhados pelo router, um por tipo
+#[derive(Clone, Default)]
+pub struct State(Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>);
+
+impl State {
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|value| Arc::clone(value).downcast::<T>().ok())
     }
 }
 
 pub struct Response {
     pub status: u16,
-    pub headers: HashMap<String, String>,
+    pub headers: HeaderMap,
     pub body: Vec<u8>,
+    /// Partes enviadas depois de `body`, até o iterador terminar
+    pub stream: Option<BodyStream>,
 }
 
 impl Response {
     pub fn ok() -> Self {
         Self {
             status: 200,
-            headers: HashMap::new(),
+            headers: HeaderMap::new(),
             body: Vec::new(),
+            stream: None,
         }
     }
 
     pub fn created() -> Self {
         Self {
             status: 201,
-            headers: HashMap::new(),
+            headers: HeaderMap::new(),
             body: Vec::new(),
+            stream: None,
         }
     }
 
     pub fn not_found() -> Self {
-        Self::new(404).text("Not Found")
+        Self::error(&Error::not_found("Not Found"))
     }
 
     pub fn bad_request() -> Self {
-        Self::new(400).text("Bad Request")
+        Self::error(&Error::invalid_input("Bad Request"))
     }
 
     pub fn internal_error() -> Self {
-        Self::new(500).text("Internal Server Error")
+        Self::error(&Error::internal("Internal Server Error"))
+    }
+
+    pub fn service_unavailable() -> Self {
+        Self::error(&Error::network("Service Unavailable"))
     }
 
     pub fn new(status: u16) -> Self {
         Self {
             status,
-            headers: HashMap::new(),
+            headers: HeaderMap::new(),
             body: Vec::new(),
+            stream: None,
         }
     }
 
+    /// Define o cabeçalho, substituindo valores anteriores
+    ///
+    /// Nomes ou valores inválidos são descartados ao escrever a resposta.
     pub fn header(mut self, key: &str, value: &str) -> Self {
-        self.headers.insert(key.to_string(), value.to_string());
+        self.headers.insert(key, value);
+        self
+    }
+
+    /// Acrescenta um `Set-Cookie`; um cookie que o navegador recusaria é
+    /// descartado com um aviso no log
+    pub fn set_cookie(mut self, cookie: SetCookie) -> Self {
+        match cookie.to_header() {
+            Ok(value) => self.headers.append("Set-Cookie", value),
+            Err(e) => avila_log::warn!(error = %e, "Dropping invalid Set-Cookie"),
+        }
         self
     }
 
     pub fn text(mut self, text: &str) -> Self {
-        self.headers.insert("Content-Type".to_string(), "text/plain".to_string());
+        self.headers.insert("Content-Type", "text/plain");
         self.body = text.as_bytes().to_vec();
         self
     }
 
     pub fn json<T: Serialize>(mut self, data: &T) -> Self {
         let json = data.to_json();
-        self.headers
-            .insert("Content-Type".to_string(), "application/json".to_string());
+        self.headers.set_typed(ContentType::json());
         self.body = json.into_bytes();
         self
     }
 
     pub fn html(mut self, html: &str) -> Self {
-        self.headers.insert("Content-Type".to_string(), "text/html".to_string());
+        self.headers.insert("Content-Type", "text/html");
         self.body = html.as_bytes().to_vec();
         self
     }
+
+    /// Corpo produzido em partes por um iterador
+    ///
+    /// Sem `Content-Length` definido, o corpo vai com `Transfer-Encoding: chunked`.
+    pub fn stream<I>(mut self, chunks: I) -> Self
+    where
+        I: Iterator<Item = Vec<u8>> + Send + 'static,
+    {
+        self.body = Vec::new();
+        self.stream = Some(Box::new(chunks));
+        self
+    }
+
+    /// Corpo lido de `reader` em partes de 64 KiB; um erro de leitura encerra o corpo
+    pub fn reader<R: Read + Send + 'static>(self, mut reader: R) -> Self {
+        let chunks = std::iter::from_fn(move || {
+            let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+            match reader.read(&mut chunk) {
+                Ok(0) => None,
+                Ok(n) => {
+                    chunk.truncate(n);
+                    Some(chunk)
+                }
+                Err(e) => {
+                    avila_log::warn!(error = %e, "Failed to read response body");
+                    None
+                }
+            }
+        });
+        self.stream(chunks)
+    }
+
+    /// Stream `text/event-stream`; a conexão fica aberta até `events` terminar
+    pub fn sse<I>(mut self, events: I) -> Self
+    where
+        I: Iterator<Item = SseEvent> + Send + 'static,
+    {
+        self.headers.insert("Content-Type", "text/event-stream");
+        self.headers.insert("Cache-Control", "no-cache");
+        self.headers.insert("Connection", "close");
+        self.stream(events.map(|event| event.encode()))
+    }
 }
 
 // Helper functions
@@ -303,3 +910,102 @@ pub async fn created_json<T: Serialize>(data: &T) -> Response {
 pub async fn ok_text(text: &str) -> Response {
     Response::ok().text(text)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_chunked_body() {
+        let raw = "POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5;ext=1\r\nhello\r\n6\r\n world\r\n0\r\nX-Trailer: 1\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let line = read_request_line(&mut reader).unwrap();
+        let request = parse_request(&line, &mut reader).unwrap();
+        let framing = body_framing(&request.headers, DEFAULT_BODY_LIMIT).unwrap();
+        assert_eq!(framing, BodyFraming::Chunked);
+        assert_eq!(read_body(framing, &mut reader, DEFAULT_BODY_LIMIT).unwrap(), b"hello world");
+
+        let mut reader = BufReader::new(&raw.as_bytes()[line.len()..]);
+        let request = parse_request(&line, &mut reader).unwrap();
+        let err = read_body(BodyFraming::Chunked, &mut reader, 8).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::HTTP_BODY_TOO_LARGE);
+        assert_eq!(Response::error(&err).status, 413);
+
+        let headers = HeaderMap::from_iter([("content-length", "100")]);
+        assert_eq!(body_framing(&headers, 10).unwrap_err().code(), ErrorCode::HTTP_BODY_TOO_LARGE);
+        let headers = HeaderMap::from_iter([("content-length", "x")]);
+        assert_eq!(Response::error(&body_framing(&headers, 10).unwrap_err()).status, 400);
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn test_write_streaming_response() {
+        let mut out = Vec::new();
+        let response = Response::ok().stream(vec![b"abc".to_vec(), Vec::new(), b"de".to_vec()].into_iter());
+        write_response(&mut out, response).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Transfer-Encoding: chunked\r\n"));
+        assert!(out.ends_with("\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n"));
+
+        let mut out = Vec::new();
+        write_response(&mut out, Response::ok().reader(&b"\x00\xff"[..])).unwrap();
+        assert!(out.ends_with(b"2\r\n\x00\xff\r\n0\r\n\r\n"));
+
+        let mut out = Vec::new();
+        write_response(&mut out, Response::ok().text("hi")).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Content-Length: 2\r\n") && out.ends_with("\r\n\r\nhi"));
+    }
+
+    #[test]
+    fn test_cookies_and_header_validation() {
+        let raw = "GET / HTTP/1.1\r\nCookie: session=abc; bad value=1\r\nAuthorization: Bearer t0ken\r\n\
+                   Content-Length: 0\r\ncontent-length: 1\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let line = read_request_line(&mut reader).unwrap();
+        let request = parse_request(&line, &mut reader).unwrap();
+        assert_eq!(request.cookie("session").as_deref(), Some("abc"));
+        assert_eq!(request.cookies().len(), 1);
+        assert_eq!(request.authorization().unwrap().bearer_token(), Some("t0ken"));
+        // Content-Length repetido e divergente (request smuggling)
+        assert!(body_framing(&request.headers, DEFAULT_BODY_LIMIT).is_err());
+
+        let response = Response::ok()
+            .header("X-Injected", "a\r\nSet-Cookie: admin=1")
+            .set_cookie(SetCookie::new("session", "xyz").unwrap().http_only(true))
+            .set_cookie(SetCookie::new("theme", "dark").unwrap().path("/"))
+            .set_cookie(SetCookie::new("cross", "1").unwrap().same_site(SameSite::None))
+            .text("ok");
+        let mut out = Vec::new();
+        write_response(&mut out, response).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Set-Cookie: session=xyz; HttpOnly\r\nSet-Cookie: theme=dark; Path=/\r\n"));
+        assert!(!out.contains("X-Injected") && !out.contains("admin") && !out.contains("cross"));
+    }
+
+    #[test]
+    fn test_catch_all_route() {
+        let router = Router::new()
+            .get("/static/{*path}", |_| async { Response::ok() })
+            .get("/static/{name}", |_| async { Response::ok() });
+        let (key, params) = router.find_route(Method::Get, "/static/app.js").unwrap();
+        assert_eq!(key.1, "/static/{name}");
+        assert_eq!(params["name"], "app.js");
+        let (key, params) = router.find_route(Method::Get, "/static/css/a%20b.css").unwrap();
+        assert_eq!(key.1, "/static/{*path}");
+        assert_eq!(params["path"], "css/a b.css");
+        assert_eq!(match_pattern("/static/{*path}", "/static/").unwrap()["path"], "");
+        assert!(match_pattern("/static/{*path}", "/other/x").is_none());
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        // `+` só é espaço na query; no caminho é literal
+        let params = match_pattern("/models/{name}", "/models/c+d%20e%2F1").unwrap();
+        assert_eq!(params["name"], "c+d e/1");
+        let query = parse_query("q=parede+externa&tag=a%20b&empty=&flag");
+        assert_eq!(query["q"], "parede externa");
+        assert_eq!(query["tag"], "a b");
+        assert_eq!((query["empty"].as_str(), query["flag"].as_str()), ("", ""));
+    }
+}
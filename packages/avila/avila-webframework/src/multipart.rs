@@ -0,0 +1,169 @@
+//! Corpos `multipart/form-data` (RFC 7578)
+
+use avila_error::{Error, Result};
+use std::collections::HashMap;
+
+/// Uma parte do formulário: campo de texto ou arquivo enviado
+#[derive(Debug, Clone)]
+pub struct Part {
+    /// Nome do campo (`name` em `Content-Disposition`)
+    pub name: String,
+    /// Nome original do arquivo, se a parte for um upload
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    /// Cabeçalhos da parte, com nomes em minúsculas
+    pub headers: HashMap<String, String>,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    pub fn text(&self) -> Result<&str> {
+        std::str::from_utf8(&self.data).map_err(|e| Error::parse(format!("Invalid UTF-8: {}", e)))
+    }
+}
+
+/// Separa `body` nas partes delimitadas pelo `boundary` de `content_type`
+pub(crate) fn parse(content_type: &str, body: &[u8]) -> Result<Vec<Part>> {
+    let (mime, params) = split_params(content_type);
+    if !mime.eq_ignore_ascii_case("multipart/form-data") {
+        return Err(Error::parse(format!("Not a multipart/form-data body: {}", mime)));
+    }
+    let boundary = params
+        .get("boundary")
+        .filter(|boundary| !boundary.is_empty())
+        .ok_or_else(|| Error::parse("Missing multipart boundary"))?;
+    let delimiter = format!("--{}", boundary).into_bytes();
+
+    // O preâmbulo antes do primeiro delimitador é ignorado
+    let mut rest = match find(body, &delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return Err(Error::parse("Multipart boundary not found")),
+    };
+    let mut separator = b"\r\n".to_vec();
+    separator.extend_from_slice(&delimiter);
+
+    let mut parts = Vec::new();
+    loop {
+        if rest.starts_with(b"--") {
+            return Ok(parts);
+        }
+        rest = skip_line(rest).ok_or_else(|| Error::parse("Malformed multipart delimiter"))?;
+        let head_end = find(rest, b"\r\n\r\n").ok_or_else(|| Error::parse("Unterminated multipart headers"))?;
+        let headers = parse_headers(&rest[..head_end])?;
+        rest = &rest[head_end + 4..];
+        let data_end = find(rest, &separator).ok_or_else(|| Error::parse("Unterminated multipart body"))?;
+        parts.push(build_part(headers, rest[..data_end].to_vec())?);
+        rest = &rest[data_end + separator.len()..];
+    }
+}
+
+fn build_part(headers: HashMap<String, String>, data: Vec<u8>) -> Result<Part> {
+    let disposition = headers
+        .get("content-disposition")
+        .ok_or_else(|| Error::parse("Multipart part without Content-Disposition"))?;
+    let (_, params) = split_params(disposition);
+    let name = params
+        .get("name")
+        .cloned()
+        .ok_or_else(|| Error::parse("Multipart part without a name"))?;
+    Ok(Part {
+        name,
+        filename: params.get("filename").cloned(),
+        content_type: headers.get("content-type").cloned(),
+        headers,
+        data,
+    })
+}
+
+fn parse_headers(block: &[u8]) -> Result<HashMap<String, String>> {
+    let block = std::str::from_utf8(block).map_err(|e| Error::parse(format!("Invalid multipart header: {}", e)))?;
+    let mut headers = HashMap::new();
+    for line in block.split("\r\n").filter(|line| !line.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| Error::parse(format!("Invalid multipart header: {}", line)))?;
+        headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+    Ok(headers)
+}
+
+/// `form-data; name="file"; filename="a.ifc"` → (`form-data`, {name, filename})
+fn split_params(value: &str) -> (&str, HashMap<String, String>) {
+    let mut segments = split_unquoted(value).into_iter();
+    let head = segments.next().unwrap_or_default().trim();
+    let params = segments
+        .filter_map(|segment| {
+            let (key, value) = segment.split_once('=')?;
+            let value = value.trim();
+            let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+                None => value.to_string(),
+            };
+            Some((key.trim().to_lowercase(), value))
+        })
+        .collect();
+    (head, params)
+}
+
+/// Divide em `;` fora de aspas (nomes de arquivo podem conter `;`)
+fn split_unquoted(value: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                segments.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&value[start..]);
+    segments
+}
+
+fn skip_line(input: &[u8]) -> Option<&[u8]> {
+    // Espaços após o delimitador são permitidos (transport padding)
+    let end = find(input, b"\r\n")?;
+    input[..end]
+        .iter()
+        .all(|b| *b == b' ' || *b == b'\t')
+        .then(|| &input[end + 2..])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_form_and_file() {
+        let body = b"preamble\r\n--XyZ\r\n\
+            Content-Disposition: form-data; name=\"project\"\r\n\r\n\
+            Torre A\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"model\"; filename=\"a;b.ifc\"\r\n\
+            Content-Type: application/x-step\r\n\r\n\
+            ISO-10303-21;\r\n--X\r\nEND\r\n\
+            --XyZ--\r\n";
+        let parts = parse("multipart/form-data; boundary=\"XyZ\"", body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "project");
+        assert_eq!(parts[0].text().unwrap(), "Torre A");
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(parts[1].filename.as_deref(), Some("a;b.ifc"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("application/x-step"));
+        assert_eq!(parts[1].data, b"ISO-10303-21;\r\n--X\r\nEND");
+
+        assert!(parse("multipart/form-data", body).is_err());
+        assert!(parse("multipart/form-data; boundary=XyZ", b"--XyZ\r\nno headers").is_err());
+    }
+}
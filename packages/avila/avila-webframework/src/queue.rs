@@ -0,0 +1,609 @@
+//! Fila distribuída: workers remotos arrendam jobs do `avila-coordinator` via HTTP
+//!
+//! Rotas montadas por [`routes`]:
+//!
+//! | Método | Caminho                            | Descrição                                    |
+//! |--------|------------------------------------|----------------------------------------------|
+//! | POST   | `/queue/lease`                     | Arrenda até `max` jobs prontos (`LeaseRequest`) |
+//! | POST   | `/queue/leases/{lease}/heartbeat`  | Renova o arrendamento por mais um timeout    |
+//! | POST   | `/queue/leases/{lease}/ack`        | Conclui o job (`completed`, `failed`, `released`) |
+//! | GET    | `/queue/leases`                    | Arrendamentos ativos                         |
+//!
+//! Cada entrega é um arrendamento com timeout de visibilidade
+//! ([`LeaseQueue`]): sem heartbeat, o job volta para a fila e outro worker o
+//! pega. Só o arrendamento vigente conclui o job, então um worker que perdeu
+//! o seu recebe `410 Gone` e descarta o resultado; repetir um `ack` já
+//! aplicado devolve o mesmo job com `duplicate: true`. Com jobs idempotentes,
+//! isso garante que cada job é concluído exatamente uma vez.
+//!
+//! Sem jobs prontos, `POST /queue/lease` espera até `waitMs` (long polling)
+//! e acorda assim que um job é submetido ou concluído. Os jobs continuam
+//! sendo submetidos pelas rotas de [`jobs`](crate::jobs):
+//!
+//! ```ignore
+//! let jobs = Arc::new(JobService::new(coordinator));
+//! let queue = Arc::new(QueueService::new(Arc::clone(&jobs), LeaseQueue::new(Duration(30_000))));
+//! let router = jobs::routes(jobs).merge(queue::routes(queue));
+//! ```
+
+use crate::jobs::{
+    enum_field, error_response, fields, lock, object, optional, required, task_error_response, JobDto, JobService,
+};
+use crate::{Request, Response, Router};
+use avila_error::ErrorCode;
+use avila_coordinator::{AckStatus, Lease, LeaseError, LeaseId, LeaseOutcome, LeaseQueue, TaskError, TaskId, Timestamp};
+use avila_serde::{Deserialize, Error as SerdeError, Serialize, Value};
+use avila_tracing::Deadline;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Maior espera aceita em `POST /queue/lease`
+const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Maior lote entregue de uma vez
+const MAX_BATCH: usize = 100;
+
+/// Durante o long polling, arrendamentos vencidos são recolhidos pelo menos a esse intervalo
+const REAP_INTERVAL: Duration = Duration::from_secs(1);
+
+// ============================================================================
+// Serviço
+// ============================================================================
+
+/// Fila de arrendamentos sobre o coordinator de um [`JobService`]
+pub struct QueueService {
+    jobs: Arc<JobService>,
+    /// Sempre travada antes do coordinator
+    queue: Mutex<LeaseQueue>,
+    clock: Box<dyn Fn() -> Timestamp + Send + Sync>,
+    max_wait: Duration,
+}
+
+impl QueueService {
+    pub fn new(jobs: Arc<JobService>, queue: LeaseQueue) -> Self {
+        Self {
+            jobs,
+            queue: Mutex::new(queue),
+            clock: Box::new(system_time),
+            max_wait: DEFAULT_MAX_WAIT,
+        }
+    }
+
+    /// Relógio dos timeouts de visibilidade (milissegundos desde a época Unix)
+    pub fn with_clock(mut self, clock: impl Fn() -> Timestamp + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Limita o `waitMs` pedido pelos workers
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    pub fn jobs(&self) -> Arc<JobService> {
+        Arc::clone(&self.jobs)
+    }
+
+    /// Só entrega `job` depois de `depends_on` concluído
+    pub fn add_dependency(&self, job: u64, depends_on: u64) -> Result<(), TaskError> {
+        lock(&self.queue).add_dependency(TaskId::new(job), TaskId::new(depends_on))
+    }
+
+    /// Arrenda até `max` jobs prontos, esperando até `wait` se não houver nenhum
+    ///
+    /// A espera também termina no prazo da requisição (`Deadline::current()`).
+    pub fn lease(&self, worker: &str, max: usize, wait: Duration) -> Vec<LeaseDto> {
+        let wait = match Deadline::current() {
+            Some(deadline) => wait.min(deadline.remaining()),
+            None => wait,
+        };
+        let until = Instant::now() + wait.min(self.max_wait);
+        loop {
+            // Lida antes da tentativa: um job submetido durante ela não se perde
+            let generation = self.jobs.changes().generation();
+            let leases = self.try_lease(worker, max);
+            if !leases.is_empty() {
+                return leases;
+            }
+            let now = Instant::now();
+            if now >= until {
+                return leases;
+            }
+            self.jobs.changes().wait(generation, (until - now).min(REAP_INTERVAL));
+        }
+    }
+
+    fn try_lease(&self, worker: &str, max: usize) -> Vec<LeaseDto> {
+        let leases = {
+            let mut queue = lock(&self.queue);
+            let coordinator = self.jobs.coordinator();
+            let mut coordinator = lock(&coordinator);
+            let expired = queue.expire(&mut coordinator, (self.clock)());
+            let leases = queue.lease(&mut coordinator, worker, max.min(MAX_BATCH), (self.clock)());
+            if expired.is_empty() && leases.is_empty() {
+                return Vec::new();
+            }
+            leases
+        };
+        self.jobs.notify();
+        leases.iter().map(|lease| self.dto(lease)).collect()
+    }
+
+    /// Renova o arrendamento; `Expired` se o timeout já passou
+    pub fn heartbeat(&self, lease: u64) -> Result<LeaseDto, LeaseError> {
+        let mut queue = lock(&self.queue);
+        queue.heartbeat(LeaseId(lease), (self.clock)())?;
+        let lease = queue.get(LeaseId(lease)).cloned().ok_or(LeaseError::Unknown)?;
+        drop(queue);
+        Ok(self.dto(&lease))
+    }
+
+    /// Conclui um job arrendado; repetir o mesmo `ack` é inofensivo
+    pub fn ack(&self, lease: u64, outcome: LeaseOutcome) -> Result<AckResponse, LeaseError> {
+        let (status, job) = {
+            let mut queue = lock(&self.queue);
+            let coordinator = self.jobs.coordinator();
+            let mut coordinator = lock(&coordinator);
+            let status = queue.ack(&mut coordinator, LeaseId(lease), outcome, (self.clock)())?;
+            let job = queue
+                .acknowledged(LeaseId(lease))
+                .and_then(|(task, _)| coordinator.get_task(task.as_u64()).map(JobDto::from));
+            (status, job)
+        };
+        if status == AckStatus::Applied {
+            self.jobs.notify();
+        }
+        Ok(AckResponse {
+            lease,
+            job,
+            duplicate: status == AckStatus::Duplicate,
+        })
+    }
+
+    /// Arrendamentos ativos, em ordem de concessão
+    pub fn leases(&self) -> Vec<LeaseDto> {
+        let leases: Vec<Lease> = lock(&self.queue).leases().cloned().collect();
+        leases.iter().map(|lease| self.dto(lease)).collect()
+    }
+
+    /// Devolve à fila os jobs com timeout vencido; devolve os ids dos jobs
+    ///
+    /// Também acontece a cada `lease` e `ack`; chame periodicamente se os
+    /// workers podem ficar muito tempo sem pedir trabalho.
+    pub fn reap(&self) -> Vec<u64> {
+        let expired = {
+            let mut queue = lock(&self.queue);
+            let coordinator = self.jobs.coordinator();
+            let mut coordinator = lock(&coordinator);
+            queue.expire(&mut coordinator, (self.clock)())
+        };
+        if !expired.is_empty() {
+            self.jobs.notify();
+        }
+        expired.iter().map(|lease| lease.task.as_u64()).collect()
+    }
+
+    /// O worker continua o trace de quem submeteu o job
+    fn dto(&self, lease: &Lease) -> LeaseDto {
+        let trace = self.jobs.trace_context(lease.task.as_u64());
+        LeaseDto {
+            lease: lease.id.0,
+            job: lease.task.as_u64(),
+            worker: lease.worker.clone(),
+            attempt: lease.attempt,
+            expires_at: lease.expires_at.0,
+            traceparent: trace.as_ref().map(|context| context.traceparent()),
+            tracestate: trace.and_then(|context| context.tracestate()),
+        }
+    }
+}
+
+fn system_time() -> Timestamp {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    Timestamp(elapsed.as_millis() as u64)
+}
+
+// ============================================================================
+// Rotas
+// ============================================================================
+
+/// Router com as rotas da fila; combine com `jobs::routes` via `Router::merge`
+pub fn routes(service: Arc<QueueService>) -> Router {
+    Router::new()
+        .state(Arc::clone(&service))
+        .post("/queue/lease", handler(&service, lease_jobs))
+        .get("/queue/leases", handler(&service, |service, _| {
+            Response::ok().json(&LeaseBatch { leases: service.leases() })
+        }))
+        .post("/queue/leases/{lease}/heartbeat", handler(&service, heartbeat))
+        .post("/queue/leases/{lease}/ack", handler(&service, ack))
+}
+
+fn handler(
+    service: &Arc<QueueService>,
+    f: fn(&QueueService, &Request) -> Response,
+) -> impl Fn(Request) -> std::future::Ready<Response> + Send + Sync + 'static {
+    let service = Arc::clone(service);
+    move |req| std::future::ready(f(&service, &req))
+}
+
+fn lease_jobs(service: &QueueService, req: &Request) -> Response {
+    let request = match req.json::<LeaseRequest>() {
+        Ok(request) => request,
+        Err(err) => return Response::error(&err),
+    };
+    if request.worker.trim().is_empty() {
+        return error_response(ErrorCode::HTTP_INVALID_PARAMETER, "Worker name is required");
+    }
+    if request.max == Some(0) {
+        return error_response(ErrorCode::HTTP_INVALID_PARAMETER, "max must be at least 1");
+    }
+    let wait = Duration::from_millis(request.wait_ms.unwrap_or(0));
+    let leases = service.lease(&request.worker, request.max.unwrap_or(1), wait);
+    Response::ok().json(&LeaseBatch { leases })
+}
+
+fn heartbeat(service: &QueueService, req: &Request) -> Response {
+    let lease = match lease_id(req) {
+        Ok(lease) => lease,
+        Err(response) => return response,
+    };
+    match service.heartbeat(lease) {
+        Ok(lease) => Response::ok().json(&lease),
+        Err(err) => lease_error_response(&err),
+    }
+}
+
+fn ack(service: &QueueService, req: &Request) -> Response {
+    let lease = match lease_id(req) {
+        Ok(lease) => lease,
+        Err(response) => return response,
+    };
+    let request = match req.json::<AckRequest>() {
+        Ok(request) => request,
+        Err(err) => return Response::error(&err),
+    };
+    match service.ack(lease, request.outcome) {
+        Ok(ack) => Response::ok().json(&ack),
+        Err(err) => lease_error_response(&err),
+    }
+}
+
+fn lease_id(req: &Request) -> Result<u64, Response> {
+    let raw = req.param("lease").unwrap_or_default();
+    raw.parse()
+        .map_err(|_| error_response(ErrorCode::HTTP_INVALID_PARAMETER, &format!("Invalid lease id: {}", raw)))
+}
+
+/// `410 Gone` diz ao worker que o job pode estar com outro: descarte o resultado
+fn lease_error_response(err: &LeaseError) -> Response {
+    match err {
+        LeaseError::Unknown => error_response(ErrorCode::LEASE_NOT_FOUND, err.message()),
+        LeaseError::Expired => error_response(ErrorCode::LEASE_EXPIRED, err.message()),
+        LeaseError::Conflict => error_response(ErrorCode::LEASE_CONFLICT, err.message()),
+        LeaseError::Task(err) => task_error_response(err),
+    }
+}
+
+// ============================================================================
+// DTOs
+// ============================================================================
+
+/// Corpo de `POST /queue/lease`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LeaseRequest {
+    /// Identifica o worker nos arrendamentos (ex.: `hostname:pid`)
+    pub worker: String,
+    /// Tamanho do lote; 1 se ausente
+    pub max: Option<usize>,
+    /// Quanto esperar por jobs se a fila estiver vazia; 0 se ausente
+    pub wait_ms: Option<u64>,
+}
+
+impl Serialize for LeaseRequest {
+    fn to_value(&self) -> Value {
+        object([
+            ("worker", self.worker.to_value()),
+            ("max", self.max.to_value()),
+            ("waitMs", self.wait_ms.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for LeaseRequest {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            worker: String::from_value(required(&mut fields, "worker")?)?,
+            max: optional(&mut fields, "max").map(usize::from_value).transpose()?,
+            wait_ms: optional(&mut fields, "waitMs").map(u64::from_value).transpose()?,
+        })
+    }
+}
+
+/// Um job arrendado a um worker
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeaseDto {
+    /// Id do arrendamento, usado em heartbeat e ack
+    pub lease: u64,
+    pub job: u64,
+    pub worker: String,
+    /// 1 na primeira entrega do job
+    pub attempt: u32,
+    /// Milissegundos desde a época Unix, no relógio do servidor
+    pub expires_at: u64,
+    /// Span de quem submeteu o job
+    pub traceparent: Option<String>,
+    pub tracestate: Option<String>,
+}
+
+impl Serialize for LeaseDto {
+    fn to_value(&self) -> Value {
+        object([
+            ("lease", self.lease.to_value()),
+            ("job", self.job.to_value()),
+            ("worker", self.worker.to_value()),
+            ("attempt", self.attempt.to_value()),
+            ("expiresAt", self.expires_at.to_value()),
+            ("traceparent", self.traceparent.to_value()),
+            ("tracestate", self.tracestate.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for LeaseDto {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            lease: u64::from_value(required(&mut fields, "lease")?)?,
+            job: u64::from_value(required(&mut fields, "job")?)?,
+            worker: String::from_value(required(&mut fields, "worker")?)?,
+            attempt: u32::from_value(required(&mut fields, "attempt")?)?,
+            expires_at: u64::from_value(required(&mut fields, "expiresAt")?)?,
+            traceparent: optional(&mut fields, "traceparent").map(String::from_value).transpose()?,
+            tracestate: optional(&mut fields, "tracestate").map(String::from_value).transpose()?,
+        })
+    }
+}
+
+/// Resposta de `POST /queue/lease` e `GET /queue/leases`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeaseBatch {
+    pub leases: Vec<LeaseDto>,
+}
+
+impl Serialize for LeaseBatch {
+    fn to_value(&self) -> Value {
+        object([("leases", self.leases.to_value())])
+    }
+}
+
+impl Deserialize for LeaseBatch {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            leases: Vec::from_value(required(&mut fields, "leases")?)?,
+        })
+    }
+}
+
+/// Corpo de `POST /queue/leases/{lease}/ack`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AckRequest {
+    pub outcome: LeaseOutcome,
+}
+
+impl Serialize for AckRequest {
+    fn to_value(&self) -> Value {
+        object([("outcome", Value::String(outcome_name(self.outcome).into()))])
+    }
+}
+
+impl Deserialize for AckRequest {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            outcome: enum_field(required(&mut fields, "outcome")?, parse_outcome, "lease outcome")?,
+        })
+    }
+}
+
+/// Resposta de `POST /queue/leases/{lease}/ack`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AckResponse {
+    pub lease: u64,
+    /// Estado do job após o ack (ausente se ele já saiu do coordinator)
+    pub job: Option<JobDto>,
+    /// O ack já tinha sido aplicado antes
+    pub duplicate: bool,
+}
+
+impl Serialize for AckResponse {
+    fn to_value(&self) -> Value {
+        object([
+            ("lease", self.lease.to_value()),
+            ("job", self.job.as_ref().map(JobDto::to_value).unwrap_or(Value::Null)),
+            ("duplicate", self.duplicate.to_value()),
+        ])
+    }
+}
+
+impl Deserialize for AckResponse {
+    fn from_value(value: Value) -> Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            lease: u64::from_value(required(&mut fields, "lease")?)?,
+            job: optional(&mut fields, "job").map(JobDto::from_value).transpose()?,
+            duplicate: bool::from_value(required(&mut fields, "duplicate")?)?,
+        })
+    }
+}
+
+const ALL_OUTCOMES: [LeaseOutcome; 3] = [LeaseOutcome::Completed, LeaseOutcome::Failed, LeaseOutcome::Released];
+
+fn outcome_name(outcome: LeaseOutcome) -> &'static str {
+    match outcome {
+        LeaseOutcome::Completed => "completed",
+        LeaseOutcome::Failed => "failed",
+        LeaseOutcome::Released => "released",
+    }
+}
+
+fn parse_outcome(name: &str) -> Option<LeaseOutcome> {
+    ALL_OUTCOMES
+        .into_iter()
+        .find(|&o| outcome_name(o).eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::SubmitJob;
+    use crate::{HeaderMap, Method};
+    use avila_coordinator::{Coordinator, TaskState};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn call(router: &Router, method: Method, path: &str, body: &str) -> (u16, Value) {
+        let req = Request {
+            method,
+            path: path.to_string(),
+            query: HashMap::new(),
+            params: HashMap::new(),
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+            state: crate::State::default(),
+            trace: None,
+            deadline: None,
+        };
+        // Os handlers da fila são síncronos: o primeiro poll já resolve
+        let mut future = std::pin::pin!(router.handle_request(req));
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        let std::task::Poll::Ready(response) = future.as_mut().poll(&mut cx) else {
+            panic!("queue handler did not complete synchronously");
+        };
+        let body = String::from_utf8(response.body).unwrap();
+        (response.status, Value::from_json(&body).unwrap())
+    }
+
+    /// Serviço com relógio manual, em milissegundos
+    fn service(visibility: u64) -> (Arc<QueueService>, Arc<AtomicU64>) {
+        let jobs = Arc::new(JobService::new(Arc::new(Mutex::new(Coordinator::new()))));
+        let clock = Arc::new(AtomicU64::new(1_000));
+        let now = Arc::clone(&clock);
+        let queue = QueueService::new(jobs, LeaseQueue::new(avila_coordinator::Duration(visibility)))
+            .with_clock(move || Timestamp(now.load(Ordering::SeqCst)));
+        (Arc::new(queue), clock)
+    }
+
+    fn lease(router: &Router, worker: &str) -> Vec<LeaseDto> {
+        let (status, body) = call(router, Method::Post, "/queue/lease", &format!(r#"{{"worker": "{}"}}"#, worker));
+        assert_eq!(status, 200);
+        LeaseBatch::from_value(body).unwrap().leases
+    }
+
+    #[test]
+    fn test_lease_heartbeat_ack() {
+        let (service, clock) = service(10_000);
+        let jobs = service.jobs();
+        for _ in 0..3 {
+            jobs.submit(SubmitJob::default()).unwrap();
+        }
+        service.add_dependency(3, 1).unwrap();
+        let router = routes(Arc::clone(&service));
+
+        let (status, body) = call(&router, Method::Post, "/queue/lease", r#"{"worker": "gpu-1", "max": 5}"#);
+        assert_eq!(status, 200);
+        let leases = LeaseBatch::from_value(body).unwrap().leases;
+        assert_eq!(leases.iter().map(|l| l.job).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((leases[0].worker.as_str(), leases[0].attempt, leases[0].expires_at), ("gpu-1", 1, 11_000));
+        assert_eq!(jobs.status(1).unwrap().state, TaskState::Running);
+
+        clock.store(6_000, Ordering::SeqCst);
+        let (status, body) = call(&router, Method::Post, &format!("/queue/leases/{}/heartbeat", leases[0].lease), "");
+        assert_eq!(status, 200);
+        assert_eq!(LeaseDto::from_value(body).unwrap().expires_at, 16_000);
+
+        let path = format!("/queue/leases/{}/ack", leases[0].lease);
+        let (status, body) = call(&router, Method::Post, &path, r#"{"outcome": "completed"}"#);
+        assert_eq!(status, 200);
+        let ack = AckResponse::from_value(body).unwrap();
+        assert_eq!((ack.job.unwrap().state, ack.duplicate), (TaskState::Completed, false));
+
+        // O worker não recebeu a resposta e repete o ack
+        let (status, body) = call(&router, Method::Post, &path, r#"{"outcome": "completed"}"#);
+        assert_eq!(status, 200);
+        assert!(AckResponse::from_value(body).unwrap().duplicate);
+        assert_eq!(call(&router, Method::Post, &path, r#"{"outcome": "failed"}"#).0, 409);
+        assert_eq!(call(&router, Method::Post, &path, r#"{"outcome": "done"}"#).0, 400);
+
+        // Com o job 1 concluído, o 3 fica pronto
+        assert_eq!(lease(&router, "gpu-2").iter().map(|l| l.job).collect::<Vec<_>>(), vec![3]);
+        let (_, body) = call(&router, Method::Get, "/queue/leases", "");
+        assert_eq!(LeaseBatch::from_value(body).unwrap().leases.len(), 2);
+
+        assert_eq!(call(&router, Method::Post, "/queue/lease", r#"{"worker": " "}"#).0, 400);
+        assert_eq!(call(&router, Method::Post, "/queue/lease", r#"{"worker": "a", "max": 0}"#).0, 400);
+        assert_eq!(call(&router, Method::Post, "/queue/leases/99/heartbeat", "").0, 404);
+        assert_eq!(call(&router, Method::Post, "/queue/leases/x/heartbeat", "").0, 400);
+    }
+
+    #[test]
+    fn test_expired_lease_is_stolen() {
+        let (service, clock) = service(5_000);
+        let jobs = service.jobs();
+        jobs.submit(SubmitJob::default()).unwrap();
+        let router = routes(Arc::clone(&service));
+
+        let stalled = lease(&router, "worker-a").remove(0);
+        clock.store(7_000, Ordering::SeqCst);
+        let heartbeat = format!("/queue/leases/{}/heartbeat", stalled.lease);
+        let (status, body) = call(&router, Method::Post, &heartbeat, "");
+        assert_eq!(status, 410);
+        let error = crate::ErrorBody::from_value(body).unwrap();
+        assert_eq!((error.error.as_str(), error.name.as_str()), ("Lease expired", "LEASE_EXPIRED"));
+
+        let stolen = lease(&router, "worker-b").remove(0);
+        assert_eq!((stolen.job, stolen.attempt), (stalled.job, 2));
+
+        // O resultado do worker parado é descartado; só o novo dono conclui
+        let ack = |lease: u64| format!("/queue/leases/{}/ack", lease);
+        assert_eq!(call(&router, Method::Post, &ack(stalled.lease), r#"{"outcome": "completed"}"#).0, 410);
+        assert_eq!(jobs.status(1).unwrap().state, TaskState::Running);
+        assert_eq!(call(&router, Method::Post, &ack(stolen.lease), r#"{"outcome": "completed"}"#).0, 200);
+        assert_eq!(jobs.status(1).unwrap().state, TaskState::Completed);
+
+        // Arrendado e cancelado pela API de jobs: o ack informa o conflito
+        jobs.submit(SubmitJob::default()).unwrap();
+        let cancelled = lease(&router, "worker-b").remove(0);
+        jobs.cancel(cancelled.job).unwrap();
+        assert_eq!(call(&router, Method::Post, &ack(cancelled.lease), r#"{"outcome": "released"}"#).0, 409);
+
+        jobs.submit(SubmitJob::default()).unwrap();
+        lease(&router, "worker-c");
+        clock.store(20_000, Ordering::SeqCst);
+        assert_eq!(service.reap(), vec![3]);
+        assert_eq!(jobs.status(3).unwrap().state, TaskState::Pending);
+    }
+
+    #[test]
+    fn test_long_poll_wakes_on_submit() {
+        let (service, _) = service(5_000);
+        let jobs = service.jobs();
+        let submitter = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            jobs.submit(SubmitJob::default()).unwrap();
+        });
+
+        let started = Instant::now();
+        let leases = service.lease("worker-a", 1, Duration::from_secs(10));
+        submitter.join().unwrap();
+        assert_eq!(leases.len(), 1);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Fila vazia e sem espera: responde na hora
+        assert!(service.lease("worker-a", 1, Duration::ZERO).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+//! Server-Sent Events (`text/event-stream`)
+//!
+//! Para eventos produzidos fora do handler (um worker, um barramento de
+//! eventos), [`channel`] devolve um [`SseSender`] e o [`SseStream`] que vai
+//! na resposta:
+//!
+//! ```ignore
+//! let (events, stream) = sse::channel();
+//! std::thread::spawn(move || {
+//!     for step in 0..10 {
+//!         // Erro quando o cliente desconectou: para de produzir
+//!         if events.send(SseEvent::new(step.to_string())).is_err() {
+//!             return;
+//!         }
+//!     }
+//! });
+//! Response::ok().sse(stream)
+//! ```
+
+use avila_error::{Error, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sem eventos por este tempo, o stream envia um comentário para manter a conexão
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Um evento SSE; `data` com várias linhas vira várias linhas `data:`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub id: Option<String>,
+    pub data: String,
+    /// Linha de comentário (`: ...`), usada como heartbeat
+    pub comment: Option<String>,
+}
+
+impl SseEvent {
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Evento sem dados que só mantém a conexão viva
+    pub fn comment(text: impl Into<String>) -> Self {
+        Self {
+            comment: Some(text.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event = Some(name.into());
+        self
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Bloco no formato do fio, terminado pela linha em branco
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = String::new();
+        if let Some(comment) = &self.comment {
+            for line in comment.lines() {
+                out.push_str(": ");
+                out.push_str(line);
+                out.push('\n');
+            }
+            if self.data.is_empty() && self.event.is_none() && self.id.is_none() {
+                out.push('\n');
+                return out.into_bytes();
+            }
+        }
+        // Quebras de linha encerrariam o campo; o nome do evento e o id ficam numa linha só
+        if let Some(event) = &self.event {
+            out.push_str("event: ");
+            out.push_str(&event.replace(['\r', '\n'], " "));
+            out.push('\n');
+        }
+        if let Some(id) = &self.id {
+            out.push_str("id: ");
+            out.push_str(&id.replace(['\r', '\n'], " "));
+            out.push('\n');
+        }
+        for line in self.data.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)) {
+            out.push_str("data: ");
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push('\n');
+        out.into_bytes()
+    }
+}
+
+/// Canal de eventos com heartbeat de [`DEFAULT_HEARTBEAT`]
+pub fn channel() -> (SseSender, SseStream) {
+    channel_with_heartbeat(DEFAULT_HEARTBEAT)
+}
+
+/// Canal de eventos; sem eventos por `heartbeat`, o stream emite `: keep-alive`
+///
+/// O heartbeat também é o que revela a desconexão de um cliente ocioso: a
+/// escrita falha, a conexão descarta o stream e o próximo `send` falha.
+pub fn channel_with_heartbeat(heartbeat: Duration) -> (SseSender, SseStream) {
+    let (tx, rx) = mpsc::channel();
+    let closed = Arc::new(AtomicBool::new(false));
+    let sender = SseSender {
+        tx,
+        closed: Arc::clone(&closed),
+    };
+    (sender, SseStream { rx, heartbeat, closed })
+}
+
+/// Lado produtor de um canal SSE; pode ser clonado entre threads
+#[derive(Clone, Debug)]
+pub struct SseSender {
+    tx: Sender<SseEvent>,
+    closed: Arc<AtomicBool>,
+}
+
+impl SseSender {
+    /// Enfileira um evento; falha depois que o cliente desconectou
+    pub fn send(&self, event: SseEvent) -> Result<()> {
+        if self.is_closed() {
+            return Err(Error::network("SSE client disconnected"));
+        }
+        self.tx
+            .send(event)
+            .map_err(|_| Error::network("SSE client disconnected"))
+    }
+
+    /// O cliente desconectou (ou a resposta foi descartada)
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+}
+
+/// Lado da resposta: termina quando todos os [`SseSender`] são descartados
+#[derive(Debug)]
+pub struct SseStream {
+    rx: Receiver<SseEvent>,
+    heartbeat: Duration,
+    closed: Arc<AtomicBool>,
+}
+
+impl Iterator for SseStream {
+    type Item = SseEvent;
+
+    fn next(&mut self) -> Option<SseEvent> {
+        match self.rx.recv_timeout(self.heartbeat) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) => Some(SseEvent::comment("keep-alive")),
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl Drop for SseStream {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let event = SseEvent::new("{\"a\":1}\nsecond").event("progress").id("7");
+        assert_eq!(
+            String::from_utf8(event.encode()).unwrap(),
+            "event: progress\nid: 7\ndata: {\"a\":1}\ndata: second\n\n"
+        );
+        assert_eq!(SseEvent::comment("ping").encode(), b": ping\n\n");
+        assert_eq!(SseEvent::new("").encode(), b"data: \n\n");
+    }
+
+    #[test]
+    fn test_channel() {
+        let (sender, mut stream) = channel_with_heartbeat(Duration::from_millis(5));
+        sender.send(SseEvent::new("1").event("progress")).unwrap();
+        assert_eq!(stream.next().unwrap().data, "1");
+        assert_eq!(stream.next().unwrap(), SseEvent::comment("keep-alive"));
+
+        let producer = sender.clone();
+        drop(sender);
+        drop(stream);
+        assert!(producer.is_closed());
+        assert!(producer.send(SseEvent::new("2")).is_err());
+
+        let (sender, stream) = channel();
+        let worker = std::thread::spawn(move || {
+            for i in 0..3 {
+                sender.send(SseEvent::new(i.to_string())).unwrap();
+            }
+        });
+        let data: Vec<String> = stream.map(|event| event.data).collect();
+        worker.join().unwrap();
+        assert_eq!(data, ["0", "1", "2"]);
+    }
+}
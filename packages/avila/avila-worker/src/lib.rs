@@ -0,0 +1,504 @@
+//! # avila-worker - Workers da fila distribuída
+//!
+//! Cliente das rotas `/queue/*` do `avila-webframework` (ver
+//! `avila_webframework::queue`): o worker arrenda jobs, renova o
+//! arrendamento enquanto trabalha e confirma o resultado.
+//!
+//! - [`WorkerClient`]: as chamadas HTTP (`lease`, `heartbeat`, `ack`)
+//! - [`Worker`]: o laço completo, com `concurrency` jobs em paralelo,
+//!   heartbeats automáticos e desligamento gracioso
+//!
+//! Um arrendamento perdido (o worker travou além do timeout de
+//! visibilidade) aparece como [`WorkerError::LeaseLost`]: o job já pode
+//! estar com outro worker, então o [`Worker`] aborta o handler e descarta o
+//! resultado. O `ack` é idempotente no servidor e é repetido em falhas de
+//! rede, então cada job é concluído uma única vez; os handlers devem ser
+//! idempotentes, porque um job pode rodar mais de uma vez.
+//!
+//! ```ignore
+//! let client = WorkerClient::new("http://coordinator:8080", "converter-01");
+//! let worker = Worker::new(client).concurrency(4);
+//! let stats = worker
+//!     .run(|job| async move { convert(job.job).await.map_err(|e| e.to_string()) }, shutdown_signal())
+//!     .await?;
+//! ```
+
+use avila_http::Client;
+use avila_serde::{Deserialize, Error as SerdeError, Serialize, Value};
+use avila_tracing::TraceContext;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+pub type Result<T> = std::result::Result<T, WorkerError>;
+
+/// Espera padrão do long polling por jobs
+const DEFAULT_POLL_WAIT: Duration = Duration::from_secs(20);
+
+/// Mantenha abaixo de um terço do timeout de visibilidade do servidor
+const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(10);
+
+/// Tentativas extras de um `ack` que falhou por erro de rede
+const DEFAULT_ACK_RETRIES: u32 = 3;
+
+/// Pausa após uma falha ao pedir jobs, para não martelar o servidor
+const LEASE_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+// ============================================================================
+// ERROS
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] avila_error::Error),
+
+    /// Timeout de visibilidade vencido: o job pode estar com outro worker
+    #[error("Lease {0} expired")]
+    LeaseLost(u64),
+
+    #[error("Lease {0} not found")]
+    UnknownLease(u64),
+
+    /// O job mudou no servidor (ex.: foi cancelado) ou o ack contradiz um anterior
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Server responded {status}: {message}")]
+    Server { status: u16, message: String },
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl WorkerError {
+    /// Falhas transitórias, que valem uma nova tentativa
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            WorkerError::Http(_) => true,
+            WorkerError::Server { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+// ============================================================================
+// CLIENTE
+// ============================================================================
+
+/// Um job arrendado ao worker
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeasedJob {
+    /// Id do arrendamento, usado em heartbeat e ack
+    pub lease: u64,
+    pub job: u64,
+    /// 1 na primeira entrega do job
+    pub attempt: u32,
+    /// Milissegundos desde a época Unix, no relógio do servidor
+    pub expires_at: u64,
+    pub traceparent: Option<String>,
+    pub tracestate: Option<String>,
+}
+
+impl LeasedJob {
+    /// Span de quem submeteu o job; o [`Worker`] roda o handler dentro dele
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        TraceContext::from_headers(self.traceparent.as_deref()?, self.tracestate.as_deref())
+    }
+}
+
+impl Deserialize for LeasedJob {
+    fn from_value(value: Value) -> std::result::Result<Self, SerdeError> {
+        let mut fields = fields(value)?;
+        Ok(Self {
+            lease: u64::from_value(required(&mut fields, "lease")?)?,
+            job: u64::from_value(required(&mut fields, "job")?)?,
+            attempt: u32::from_value(required(&mut fields, "attempt")?)?,
+            expires_at: u64::from_value(required(&mut fields, "expiresAt")?)?,
+            traceparent: optional(&mut fields, "traceparent").map(String::from_value).transpose()?,
+            tracestate: optional(&mut fields, "tracestate").map(String::from_value).transpose()?,
+        })
+    }
+}
+
+/// Como o worker terminou um job
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Completed,
+    Failed,
+    /// Devolve o job à fila sem contar como tentativa
+    Released,
+}
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Completed => "completed",
+            Outcome::Failed => "failed",
+            Outcome::Released => "released",
+        }
+    }
+}
+
+/// Resposta do `ack`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Acked {
+    pub lease: u64,
+    /// O servidor já tinha aplicado este ack (ex.: a resposta anterior se perdeu)
+    pub duplicate: bool,
+}
+
+/// Chamadas HTTP da fila, em nome de um worker
+pub struct WorkerClient {
+    client: Client,
+    base_url: String,
+    worker: String,
+}
+
+impl WorkerClient {
+    /// `base_url` é a raiz do servidor (ex.: `http://coordinator:8080`)
+    pub fn new(base_url: &str, worker: &str) -> Self {
+        Self::with_client(Client::new(), base_url, worker)
+    }
+
+    /// Usa um cliente já configurado (TLS, timeouts, hooks de span)
+    pub fn with_client(client: Client, base_url: &str, worker: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            worker: worker.to_string(),
+        }
+    }
+
+    pub fn worker(&self) -> &str {
+        &self.worker
+    }
+
+    /// Arrenda até `max` jobs, esperando até `wait` se a fila estiver vazia
+    pub async fn lease(&self, max: usize, wait: Duration) -> Result<Vec<LeasedJob>> {
+        let body = Request(object([
+            ("worker", self.worker.to_value()),
+            ("max", max.to_value()),
+            ("waitMs", (wait.as_millis() as u64).to_value()),
+        ]));
+        let response = self.client.post(&self.url("/queue/lease")).await?.json(&body).send().await?;
+        let mut fields = fields(check(&response, 0)?).map_err(invalid)?;
+        let leases = required(&mut fields, "leases").map_err(invalid)?;
+        Vec::from_value(leases).map_err(invalid)
+    }
+
+    /// Renova o arrendamento por mais um timeout de visibilidade
+    pub async fn heartbeat(&self, lease: u64) -> Result<LeasedJob> {
+        let url = self.url(&format!("/queue/leases/{}/heartbeat", lease));
+        let response = self.client.post(&url).await?.send().await?;
+        LeasedJob::from_value(check(&response, lease)?).map_err(invalid)
+    }
+
+    /// Confirma o resultado; repetir o mesmo ack é seguro
+    pub async fn ack(&self, lease: u64, outcome: Outcome) -> Result<Acked> {
+        let url = self.url(&format!("/queue/leases/{}/ack", lease));
+        let body = Request(object([("outcome", Value::String(outcome.as_str().into()))]));
+        let response = self.client.post(&url).await?.json(&body).send().await?;
+        let mut fields = fields(check(&response, lease)?).map_err(invalid)?;
+        let duplicate = required(&mut fields, "duplicate").and_then(bool::from_value).map_err(invalid)?;
+        Ok(Acked { lease, duplicate })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+/// Corpo JSON das requisições
+struct Request(Value);
+
+impl Serialize for Request {
+    fn to_value(&self) -> Value {
+        self.0.clone()
+    }
+}
+
+/// Corpo de uma resposta 2xx; os erros da fila viram [`WorkerError`]
+fn check(response: &avila_http::Response, lease: u64) -> Result<Value> {
+    let text = response.text()?;
+    if response.is_success() {
+        return Value::from_json(&text).map_err(invalid);
+    }
+    // `{"error": "..."}`, ou o corpo cru se vier de um proxy
+    let message = Value::from_json(&text)
+        .ok()
+        .and_then(|body| fields(body).ok()?.remove("error"))
+        .and_then(|error| String::from_value(error).ok())
+        .unwrap_or(text);
+    Err(match response.status() {
+        410 => WorkerError::LeaseLost(lease),
+        404 if lease != 0 => WorkerError::UnknownLease(lease),
+        409 => WorkerError::Conflict(message),
+        status => WorkerError::Server { status, message },
+    })
+}
+
+fn invalid(error: SerdeError) -> WorkerError {
+    WorkerError::InvalidResponse(error.to_string())
+}
+
+fn object<const N: usize>(pairs: [(&str, Value); N]) -> Value {
+    Value::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
+
+fn fields(value: Value) -> std::result::Result<HashMap<String, Value>, SerdeError> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(SerdeError::ExpectedObject),
+    }
+}
+
+fn required(fields: &mut HashMap<String, Value>, name: &str) -> std::result::Result<Value, SerdeError> {
+    fields
+        .remove(name)
+        .ok_or_else(|| SerdeError::MissingField(name.to_string()))
+}
+
+/// Campo ausente e `null` são equivalentes
+fn optional(fields: &mut HashMap<String, Value>, name: &str) -> Option<Value> {
+    fields.remove(name).filter(|v| *v != Value::Null)
+}
+
+// ============================================================================
+// WORKER
+// ============================================================================
+
+/// Contagem de jobs processados por [`Worker::run`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunStats {
+    pub completed: u64,
+    pub failed: u64,
+    /// Arrendamentos perdidos ou acks recusados: o resultado foi descartado
+    pub lost: u64,
+}
+
+/// Laço de um worker: arrenda, executa, renova e confirma
+pub struct Worker {
+    client: Arc<WorkerClient>,
+    concurrency: usize,
+    poll_wait: Duration,
+    heartbeat: Duration,
+    ack_retries: u32,
+}
+
+impl Worker {
+    pub fn new(client: WorkerClient) -> Self {
+        Self {
+            client: Arc::new(client),
+            concurrency: 1,
+            poll_wait: DEFAULT_POLL_WAIT,
+            heartbeat: DEFAULT_HEARTBEAT,
+            ack_retries: DEFAULT_ACK_RETRIES,
+        }
+    }
+
+    /// Jobs executados ao mesmo tempo (mínimo 1)
+    pub fn concurrency(mut self, jobs: usize) -> Self {
+        self.concurrency = jobs.max(1);
+        self
+    }
+
+    /// Quanto cada pedido de jobs espera no servidor com a fila vazia
+    pub fn poll_wait(mut self, wait: Duration) -> Self {
+        self.poll_wait = wait;
+        self
+    }
+
+    /// Intervalo entre heartbeats de cada job em execução
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat = interval;
+        self
+    }
+
+    /// Tentativas extras de um ack que falhou por erro transitório
+    pub fn ack_retries(mut self, retries: u32) -> Self {
+        self.ack_retries = retries;
+        self
+    }
+
+    /// Processa jobs até `shutdown` terminar; depois espera os jobs em execução
+    ///
+    /// O handler roda numa task própria: `Err` ou pânico marcam o job como
+    /// falho, e um arrendamento perdido aborta a task. O pedido de jobs em
+    /// andamento nunca é abandonado, senão o servidor poderia arrendar jobs
+    /// que ninguém recebe; no desligamento, o que ele trouxer é devolvido.
+    pub async fn run<F, Fut, S>(&self, handler: F, shutdown: S) -> Result<RunStats>
+    where
+        F: Fn(LeasedJob) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), String>> + Send + 'static,
+        S: Future<Output = ()>,
+    {
+        let handler = Arc::new(handler);
+        let mut in_flight: JoinSet<JobResult> = JoinSet::new();
+        let mut polling: Option<LeasePoll> = None;
+        let mut stats = RunStats::default();
+        let mut stopping = false;
+        tokio::pin!(shutdown);
+
+        loop {
+            if stopping && in_flight.is_empty() && polling.is_none() {
+                return Ok(stats);
+            }
+            let free = self.concurrency.saturating_sub(in_flight.len());
+            if polling.is_none() && !stopping && free > 0 {
+                let (client, wait) = (Arc::clone(&self.client), self.poll_wait);
+                polling = Some(Box::pin(async move {
+                    let leased = client.lease(free, wait).await;
+                    if let Err(e) = &leased {
+                        avila_log::warn!(worker = %client.worker(), error = %e, "Failed to lease jobs");
+                        tokio::time::sleep(LEASE_ERROR_BACKOFF).await;
+                    }
+                    leased
+                }));
+            }
+
+            let poll = async {
+                match polling.as_mut() {
+                    Some(poll) => poll.await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                biased;
+                _ = &mut shutdown, if !stopping => stopping = true,
+                Some(done) = in_flight.join_next(), if !in_flight.is_empty() => {
+                    match done.unwrap_or(JobResult::Lost) {
+                        JobResult::Completed => stats.completed += 1,
+                        JobResult::Failed => stats.failed += 1,
+                        JobResult::Lost => stats.lost += 1,
+                    }
+                }
+                leased = poll => {
+                    polling = None;
+                    for job in leased.unwrap_or_default() {
+                        let client = Arc::clone(&self.client);
+                        if stopping {
+                            let _ = ack_with_retries(&client, job.lease, Outcome::Released, self.ack_retries).await;
+                            continue;
+                        }
+                        let handler = Arc::clone(&handler);
+                        let (heartbeat, retries) = (self.heartbeat, self.ack_retries);
+                        in_flight.spawn(async move {
+                            process(&client, job, handler.as_ref(), heartbeat, retries).await
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pedido de jobs em andamento em [`Worker::run`]
+type LeasePoll = Pin<Box<dyn Future<Output = Result<Vec<LeasedJob>>> + Send>>;
+
+enum JobResult {
+    Completed,
+    Failed,
+    Lost,
+}
+
+/// Executa um job renovando o arrendamento até o handler terminar
+async fn process<F, Fut>(client: &WorkerClient, job: LeasedJob, handler: &F, interval: Duration, retries: u32) -> JobResult
+where
+    F: Fn(LeasedJob) -> Fut,
+    Fut: Future<Output = std::result::Result<(), String>> + Send + 'static,
+{
+    let lease = job.lease;
+    let work = match job.trace_context() {
+        Some(context) => tokio::spawn(context.instrument(handler(job))),
+        None => tokio::spawn(handler(job)),
+    };
+    tokio::pin!(work);
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+
+    let outcome = loop {
+        tokio::select! {
+            result = &mut work => match result {
+                Ok(Ok(())) => break Outcome::Completed,
+                Ok(Err(message)) => {
+                    avila_log::warn!(lease = lease, error = %message, "Job failed");
+                    break Outcome::Failed;
+                }
+                Err(e) => {
+                    avila_log::error!(lease = lease, error = %e, "Job handler panicked");
+                    break Outcome::Failed;
+                }
+            },
+            _ = ticker.tick() => match client.heartbeat(lease).await {
+                Ok(_) => {}
+                Err(WorkerError::LeaseLost(_) | WorkerError::UnknownLease(_) | WorkerError::Conflict(_)) => {
+                    work.abort();
+                    avila_log::warn!(lease = lease, "Lease lost, abandoning job");
+                    return JobResult::Lost;
+                }
+                // Transitório: o próximo heartbeat tenta de novo antes do timeout
+                Err(e) => avila_log::warn!(lease = lease, error = %e, "Heartbeat failed"),
+            },
+        }
+    };
+
+    match ack_with_retries(client, lease, outcome, retries).await {
+        Ok(_) if outcome == Outcome::Completed => JobResult::Completed,
+        Ok(_) => JobResult::Failed,
+        Err(e) => {
+            avila_log::warn!(lease = lease, error = %e, "Ack rejected, result discarded");
+            JobResult::Lost
+        }
+    }
+}
+
+/// O servidor reconhece acks repetidos, então repetir após erro de rede é seguro
+async fn ack_with_retries(client: &WorkerClient, lease: u64, outcome: Outcome, retries: u32) -> Result<Acked> {
+    let mut attempt = 0;
+    loop {
+        match client.ack(lease, outcome).await {
+            Err(e) if e.is_retryable() && attempt < retries => {
+                attempt += 1;
+                tokio::time::sleep(Duration::from_millis(200 << attempt.min(6))).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leased_job_from_json() {
+        let json = r#"{"lease": 7, "job": 42, "worker": "w1", "attempt": 2, "expiresAt": 1700000030000,
+            "traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", "tracestate": null}"#;
+        let job = LeasedJob::from_value(Value::from_json(json).unwrap()).unwrap();
+        assert_eq!((job.lease, job.job, job.attempt, job.expires_at), (7, 42, 2, 1_700_000_030_000));
+        assert_eq!(job.tracestate, None);
+
+        let context = job.trace_context().unwrap();
+        assert_eq!(context.trace_id.0, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(context.span_id.0, 0x00f067aa0ba902b7);
+
+        let untraced = LeasedJob {
+            traceparent: Some("garbage".to_string()),
+            ..job
+        };
+        assert!(untraced.trace_context().is_none());
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(WorkerError::Http(avila_error::Error::network("connection reset")).is_retryable());
+        assert!(WorkerError::Server { status: 503, message: String::new() }.is_retryable());
+        assert!(!WorkerError::Server { status: 400, message: String::new() }.is_retryable());
+        assert!(!WorkerError::LeaseLost(1).is_retryable());
+        assert!(!WorkerError::Conflict("Cannot move job from Cancelled to Completed".to_string()).is_retryable());
+        assert_eq!(WorkerError::LeaseLost(3).to_string(), "Lease 3 expired");
+    }
+}
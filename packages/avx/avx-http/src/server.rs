@@ -55,7 +55,7 @@ impl Server {
                 message: format!("Failed to bind to {}: {}", self.addr, e),
             })?;
 
-        println!("🚀 Server listening on {}", self.addr);
+        avila_log::info!(addr = %self.addr, "Server listening");
 
         let router = Arc::new(self.router.unwrap_or_default());
 
@@ -69,7 +69,7 @@ impl Server {
 
             tokio::spawn(async move {
                 if let Err(e) = handle_connection(stream, router, telemetry).await {
-                    eprintln!("Error handling connection from {}: {}", peer_addr, e);
+                    avila_log::error!(peer = %peer_addr, error = %e, "Error handling connection");
                 }
             });
         }
@@ -90,7 +90,7 @@ async fn handle_connection(
     let request = parse_request(request_data)?;
 
     if telemetry {
-        println!("📥 {} {}", request.method, request.path);
+        avila_log::debug!(method = %request.method, path = %request.path, "Request received");
     }
 
     // Route request